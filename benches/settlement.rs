@@ -0,0 +1,189 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_decimal::Decimal;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+use settlement_engine::api::requests::CreateTransactionRequest;
+use settlement_engine::models::{TransactionRecord, TransactionType};
+use settlement_engine::services::{NettingConfig, NettingService};
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+const PARTICIPANTS: usize = 100;
+
+/// Netting never touches the database, but `NettingService` owns a pool. A lazy pool
+/// never connects; it only needs a runtime to exist while it is built.
+fn netting_service() -> NettingService {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to build runtime");
+    let _guard = runtime.enter();
+    let pool: PgPool = PgPoolOptions::new()
+        .connect_lazy("postgres://bench@localhost/bench")
+        .expect("Failed to build lazy pool");
+    NettingService::new(pool)
+}
+
+/// Deterministic transaction set with a skewed participant distribution so that some
+/// pairs net heavily and others barely at all.
+fn generate_transactions(count: usize) -> Vec<TransactionRecord> {
+    let participants: Vec<Uuid> = (0..PARTICIPANTS).map(|_| Uuid::new_v4()).collect();
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    (0..count)
+        .map(|i| {
+            let from = (next() % PARTICIPANTS as u64) as usize;
+            let to = (from + 1 + (next() % (PARTICIPANTS as u64 - 1)) as usize) % PARTICIPANTS;
+            TransactionRecord::new(
+                format!("BENCH-{}", i),
+                TransactionType::Payment,
+                participants[from],
+                participants[to],
+                Decimal::new((next() % 1_000_000) as i64 + 1, 2),
+                "USD".to_string(),
+                Decimal::ZERO,
+                format!("bench-{}", i),
+            )
+        })
+        .collect()
+}
+
+fn benchmark_multilateral_netting(c: &mut Criterion) {
+    let service = netting_service();
+    let mut group = c.benchmark_group("multilateral_netting");
+    group.sample_size(20);
+    group.measurement_time(Duration::from_secs(10));
+
+    for size in SIZES {
+        let transactions = generate_transactions(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &transactions, |b, txs| {
+            b.iter(|| {
+                black_box(service.calculate_multilateral_netting(Uuid::nil(), "USD", black_box(txs)))
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_parallel_netting(c: &mut Criterion) {
+    let sequential = netting_service().with_config(NettingConfig { parallel_threshold: usize::MAX, ..Default::default() });
+    let parallel = netting_service().with_config(NettingConfig { parallel_threshold: 0, ..Default::default() });
+    let mut group = c.benchmark_group("multilateral_netting_parallel");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(15));
+
+    for size in [100_000, 1_000_000] {
+        let transactions = generate_transactions(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("sequential", size), &transactions, |b, txs| {
+            b.iter(|| {
+                black_box(sequential.calculate_multilateral_netting(Uuid::nil(), "USD", black_box(txs)))
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", size), &transactions, |b, txs| {
+            b.iter(|| {
+                black_box(parallel.calculate_multilateral_netting(Uuid::nil(), "USD", black_box(txs)))
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_bilateral_netting(c: &mut Criterion) {
+    let service = netting_service();
+    let mut group = c.benchmark_group("bilateral_netting");
+    group.sample_size(20);
+    group.measurement_time(Duration::from_secs(10));
+
+    for size in SIZES {
+        let transactions = generate_transactions(size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &transactions, |b, txs| {
+            b.iter(|| {
+                black_box(service.calculate_bilateral_netting(Uuid::nil(), "USD", black_box(txs)))
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_instruction_generation(c: &mut Criterion) {
+    let service = netting_service();
+    let mut group = c.benchmark_group("instruction_generation");
+
+    for size in SIZES {
+        let positions = service
+            .calculate_multilateral_netting(Uuid::nil(), "USD", &generate_transactions(size))
+            .positions;
+        group.bench_with_input(BenchmarkId::new("greedy", size), &positions, |b, positions| {
+            b.iter(|| {
+                black_box(service.generate_multilateral_instructions(
+                    Uuid::nil(),
+                    "USD",
+                    black_box(positions),
+                ))
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("minimum_count", size), &positions, |b, positions| {
+            b.iter(|| {
+                black_box(service.generate_minimal_instructions(Uuid::nil(), "USD", black_box(positions)))
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_amount_validation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("amount_validation");
+
+    let valid = CreateTransactionRequest {
+        external_id: "BENCH-001".to_string(),
+        transaction_type: TransactionType::Payment,
+        source_account_id: Uuid::new_v4(),
+        destination_account_id: Uuid::new_v4(),
+        amount: Decimal::new(123_456, 2),
+        currency: "USD".to_string(),
+        fee_amount: None,
+        idempotency_key: "bench-001".to_string(),
+        metadata: None,
+        priority: None,
+        effective_date: None,
+        source_system: None,
+    };
+    let invalid = CreateTransactionRequest {
+        amount: Decimal::ZERO,
+        currency: "US".to_string(),
+        ..valid.clone()
+    };
+
+    group.bench_function("valid_request", |b| {
+        b.iter(|| black_box(black_box(&valid).validate().is_ok()))
+    });
+
+    group.bench_function("invalid_request", |b| {
+        b.iter(|| black_box(black_box(&invalid).validate().is_err()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_multilateral_netting,
+    benchmark_parallel_netting,
+    benchmark_bilateral_netting,
+    benchmark_instruction_generation,
+    benchmark_amount_validation,
+);
+
+criterion_main!(benches);
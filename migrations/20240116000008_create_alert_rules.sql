@@ -0,0 +1,20 @@
+-- Create Alert Rules table
+CREATE TYPE alert_rule_type AS ENUM ('LOW_BALANCE', 'HIGH_BALANCE', 'LARGE_TRANSACTION', 'FAILED_SETTLEMENT');
+
+CREATE TABLE alert_rules (
+    id UUID PRIMARY KEY,
+    account_id UUID REFERENCES accounts(id),
+    rule_type alert_rule_type NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    threshold DECIMAL(19, 4),
+    webhook_url VARCHAR(2048),
+    suppression_window_seconds INTEGER NOT NULL DEFAULT 0 CHECK (suppression_window_seconds >= 0),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_triggered_at TIMESTAMP WITH TIME ZONE,
+    metadata JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_alert_rules_account ON alert_rules(account_id);
+CREATE INDEX idx_alert_rules_type_enabled ON alert_rules(rule_type) WHERE enabled;
@@ -0,0 +1,7 @@
+-- Add sequence numbers to settlement batches
+-- Batches that share a settlement date and currency form one settlement window; caps can
+-- split a window into several batches, numbered from 1 in creation order.
+ALTER TABLE settlement_batches
+    ADD COLUMN sequence_number INTEGER NOT NULL DEFAULT 1 CHECK (sequence_number > 0);
+
+CREATE INDEX idx_batches_window ON settlement_batches(settlement_date, currency, sequence_number);
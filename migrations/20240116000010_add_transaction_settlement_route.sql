@@ -0,0 +1,9 @@
+-- Record how each transaction is settled
+-- Large-value transactions above the RTGS threshold settle gross in real time and never
+-- enter a settlement batch; everything else is netted.
+CREATE TYPE settlement_route AS ENUM ('NETTED', 'RTGS');
+
+ALTER TABLE transactions
+    ADD COLUMN settlement_route settlement_route NOT NULL DEFAULT 'NETTED';
+
+CREATE INDEX idx_transactions_route_settled ON transactions(settlement_route, settled_at);
@@ -0,0 +1,10 @@
+-- Add release priority to transactions
+-- Enum values are declared from most to least urgent so ORDER BY priority releases
+-- urgent payments first; ties fall back to creation order.
+CREATE TYPE transaction_priority AS ENUM ('URGENT', 'HIGH', 'NORMAL');
+
+ALTER TABLE transactions
+    ADD COLUMN priority transaction_priority NOT NULL DEFAULT 'NORMAL';
+
+CREATE INDEX idx_transactions_queue ON transactions(priority, created_at)
+    WHERE status = 'PENDING' AND settlement_batch_id IS NULL;
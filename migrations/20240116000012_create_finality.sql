@@ -0,0 +1,14 @@
+-- Settlement finality records
+-- One row per transaction at the moment it became final and irrevocable. The identity
+-- sequence gives a monotonic ordering that does not depend on clock resolution, and
+-- clock_timestamp() records the instant of the insert rather than the start of the
+-- surrounding database transaction.
+CREATE TABLE finality (
+    sequence BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    transaction_id UUID NOT NULL UNIQUE REFERENCES transactions(id),
+    batch_id UUID REFERENCES settlement_batches(id),
+    settlement_route settlement_route NOT NULL,
+    final_at TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_finality_batch ON finality(batch_id, sequence);
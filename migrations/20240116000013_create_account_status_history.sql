@@ -0,0 +1,25 @@
+-- Create Account Status History table
+-- Every freeze, activation and closure is recorded with a reason code so the current
+-- status of an account can always be explained.
+CREATE TYPE status_reason_code AS ENUM (
+    'CUSTOMER_REQUEST',
+    'COMPLIANCE_REVIEW',
+    'SUSPECTED_FRAUD',
+    'REGULATORY_ORDER',
+    'CREDIT_RISK',
+    'DORMANT',
+    'REVIEW_CLEARED',
+    'OTHER'
+);
+
+CREATE TABLE account_status_history (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    from_status account_status NOT NULL,
+    to_status account_status NOT NULL,
+    reason_code status_reason_code NOT NULL,
+    note TEXT,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_account_status_history_account ON account_status_history(account_id, changed_at);
@@ -0,0 +1,28 @@
+-- Create Counterparty Restrictions tables
+-- An account with any ALLOW entry may only transact with those counterparties; DENY
+-- entries always block. Every change is kept in the audit table.
+CREATE TYPE counterparty_list_mode AS ENUM ('ALLOW', 'DENY');
+CREATE TYPE counterparty_audit_action AS ENUM ('ADDED', 'REMOVED');
+
+CREATE TABLE counterparty_restrictions (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    counterparty_id UUID NOT NULL,
+    mode counterparty_list_mode NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (account_id, counterparty_id)
+);
+
+CREATE TABLE counterparty_restriction_audit (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    counterparty_id UUID NOT NULL,
+    mode counterparty_list_mode NOT NULL,
+    action counterparty_audit_action NOT NULL,
+    reason TEXT,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_counterparty_restriction_audit_account
+    ON counterparty_restriction_audit(account_id, changed_at);
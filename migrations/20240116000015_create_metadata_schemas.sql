@@ -0,0 +1,9 @@
+-- Create Metadata Schemas table
+-- Optional JSON Schema per transaction type; transactions of a type with a schema must
+-- carry metadata that validates against it.
+CREATE TABLE metadata_schemas (
+    transaction_type transaction_type PRIMARY KEY,
+    schema JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
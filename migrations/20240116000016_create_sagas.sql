@@ -0,0 +1,19 @@
+-- Create Sagas table
+-- Persisted state of multi-step settlement flows. `current_step` counts the steps that
+-- have completed (and, while compensating, those still to be undone), so a saga
+-- interrupted by a restart can be resumed from where it stopped.
+CREATE TYPE saga_status AS ENUM ('RUNNING', 'COMPLETED', 'COMPENSATING', 'COMPENSATED', 'FAILED');
+
+CREATE TABLE sagas (
+    id UUID PRIMARY KEY,
+    saga_type VARCHAR(100) NOT NULL,
+    status saga_status NOT NULL DEFAULT 'RUNNING',
+    current_step INTEGER NOT NULL DEFAULT 0,
+    context JSONB NOT NULL,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_sagas_unfinished ON sagas(saga_type, created_at)
+    WHERE status IN ('RUNNING', 'COMPENSATING');
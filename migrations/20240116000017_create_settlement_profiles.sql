@@ -0,0 +1,14 @@
+-- Create Settlement Profiles table
+-- Bank details used when a participant's net settlement leaves the engine as an
+-- external payment file (e.g. NACHA).
+CREATE TYPE bank_account_type AS ENUM ('CHECKING', 'SAVINGS');
+
+CREATE TABLE settlement_profiles (
+    account_id UUID PRIMARY KEY REFERENCES accounts(id),
+    account_name VARCHAR(255) NOT NULL,
+    routing_number CHAR(9) NOT NULL,
+    bank_account_number VARCHAR(17) NOT NULL,
+    bank_account_type bank_account_type NOT NULL DEFAULT 'CHECKING',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
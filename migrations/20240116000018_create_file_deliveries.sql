@@ -0,0 +1,25 @@
+-- Create File Deliveries table
+-- Generated files (NACHA, statements) queued for delivery to a configured SFTP or
+-- S3-compatible destination. The content is stored with its SHA-256 checksum so
+-- retries after a restart send exactly the bytes that were generated.
+CREATE TYPE delivery_status AS ENUM ('PENDING', 'DELIVERING', 'DELIVERED', 'FAILED');
+
+CREATE TABLE file_deliveries (
+    id UUID PRIMARY KEY,
+    destination VARCHAR(100) NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    content BYTEA NOT NULL,
+    checksum_sha256 CHAR(64) NOT NULL,
+    status delivery_status NOT NULL DEFAULT 'PENDING',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    last_error TEXT,
+    delivered_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_file_deliveries_due ON file_deliveries(next_attempt_at)
+    WHERE status = 'PENDING';
+CREATE INDEX idx_file_deliveries_created ON file_deliveries(created_at DESC);
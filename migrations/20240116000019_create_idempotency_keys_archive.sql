@@ -0,0 +1,20 @@
+-- Create idempotency_keys_archive table
+-- Expired idempotency records copied here by the cleanup job before deletion when
+-- archival is enabled, so duplicate-detection history remains auditable.
+CREATE TABLE IF NOT EXISTS idempotency_keys_archive (
+    id UUID PRIMARY KEY,
+    idempotency_key VARCHAR(255) NOT NULL,
+    client_id VARCHAR(255) NOT NULL,
+    operation_type VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    request_hash VARCHAR(64) NOT NULL,
+    response_data JSONB,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_archive_key ON idempotency_keys_archive(idempotency_key);
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_archive_archived_at ON idempotency_keys_archive(archived_at);
@@ -0,0 +1,16 @@
+-- Account anonymization
+-- Closed accounts can have their personal data scrubbed while ledger entries and
+-- transactions keep referencing the account by id. Each anonymization is recorded
+-- without any of the removed values.
+ALTER TABLE accounts ADD COLUMN anonymized_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE account_anonymizations (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    requested_by VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    scrubbed_fields TEXT[] NOT NULL,
+    anonymized_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_account_anonymizations_account ON account_anonymizations(account_id);
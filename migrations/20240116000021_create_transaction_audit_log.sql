@@ -0,0 +1,17 @@
+-- Create Transaction Audit Log table
+-- Lifecycle steps of a transaction that leave no other trace in the schema: passing
+-- validation, being assigned to a batch and having events published about it. With
+-- the ledger entries, netting positions, instruction sagas and finality record they
+-- make up the transaction timeline.
+CREATE TYPE transaction_audit_action AS ENUM ('VALIDATED', 'BATCH_ASSIGNED', 'EVENT_EMITTED');
+
+CREATE TABLE transaction_audit_log (
+    id UUID PRIMARY KEY,
+    transaction_id UUID NOT NULL REFERENCES transactions(id),
+    action transaction_audit_action NOT NULL,
+    detail JSONB NOT NULL DEFAULT '{}',
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_transaction_audit_log_transaction
+    ON transaction_audit_log(transaction_id, recorded_at);
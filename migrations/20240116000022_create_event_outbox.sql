@@ -0,0 +1,18 @@
+-- Create Event Outbox table
+-- Events are written here in the same database transaction as the state change they
+-- describe, and a relay publishes them to Kafka afterwards. An event is therefore
+-- published if and only if its change committed, even if Kafka is down at the time.
+CREATE TABLE event_outbox (
+    id UUID PRIMARY KEY,
+    topic VARCHAR(255) NOT NULL,
+    partition_key VARCHAR(255),
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp(),
+    published_at TIMESTAMPTZ,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+);
+
+CREATE INDEX idx_event_outbox_unpublished
+    ON event_outbox(created_at)
+    WHERE published_at IS NULL;
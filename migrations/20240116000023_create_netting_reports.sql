@@ -0,0 +1,18 @@
+-- Create Netting Reports table
+-- The full report is kept as JSONB; the aggregates used for efficiency analysis
+-- across batches are duplicated into columns so they can be queried directly.
+CREATE TABLE netting_reports (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL UNIQUE REFERENCES settlement_batches(id),
+    currency VARCHAR(3) NOT NULL,
+    total_transactions INTEGER NOT NULL,
+    participant_count INTEGER NOT NULL,
+    gross_volume DECIMAL(19, 4) NOT NULL,
+    net_volume DECIMAL(19, 4) NOT NULL,
+    reduction_amount DECIMAL(19, 4) NOT NULL,
+    reduction_percentage DECIMAL(9, 4) NOT NULL,
+    report JSONB NOT NULL,
+    generated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX idx_netting_reports_generated ON netting_reports(generated_at);
@@ -0,0 +1,13 @@
+-- Create Daily Netting Metrics table
+-- Running netting totals per currency and day, incremented each time a batch is
+-- netted, so they survive restarts and can be exported from any instance.
+CREATE TABLE netting_metrics_daily (
+    metric_date DATE NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    batches_processed BIGINT NOT NULL DEFAULT 0,
+    transactions_netted BIGINT NOT NULL DEFAULT 0,
+    gross_volume DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    net_volume DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (metric_date, currency)
+);
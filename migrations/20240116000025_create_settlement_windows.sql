@@ -0,0 +1,21 @@
+-- Create Settlement Windows table
+-- Named settlement windows per currency, each closing daily at a local cut-off time
+-- (e.g. EUR morning at 10:00 Europe/Berlin). Batches created for a window reference it.
+CREATE TABLE settlement_windows (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    cut_off_time TIME NOT NULL,
+    timezone VARCHAR(64) NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (currency, name)
+);
+
+CREATE INDEX idx_settlement_windows_currency ON settlement_windows(currency) WHERE active;
+
+ALTER TABLE settlement_batches ADD COLUMN window_id UUID REFERENCES settlement_windows(id);
+
+CREATE INDEX idx_settlement_batches_window ON settlement_batches(window_id, settlement_date)
+    WHERE window_id IS NOT NULL;
@@ -0,0 +1,23 @@
+-- Create Transaction Submissions table
+-- Durable queue for asynchronously submitted transactions. Requests are validated and
+-- stored here, then settled by the submission workers; clients poll for the outcome.
+CREATE TYPE submission_status AS ENUM ('QUEUED', 'PROCESSING', 'SETTLED', 'FAILED');
+
+CREATE TABLE transaction_submissions (
+    id UUID PRIMARY KEY,
+    idempotency_key VARCHAR(255) NOT NULL UNIQUE,
+    request JSONB NOT NULL,
+    status submission_status NOT NULL DEFAULT 'QUEUED',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    transaction_id UUID REFERENCES transactions(id),
+    error_code VARCHAR(50),
+    last_error TEXT,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_transaction_submissions_due ON transaction_submissions(next_attempt_at)
+    WHERE status = 'QUEUED';
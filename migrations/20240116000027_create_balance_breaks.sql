@@ -0,0 +1,22 @@
+-- Create Balance Breaks table
+-- Discrepancies found by the reconciliation job between an account's stored balance
+-- and the balance derived from its ledger entries. A break stays open, and is
+-- refreshed on every run, until the account reconciles again.
+CREATE TABLE balance_breaks (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    stored_balance DECIMAL(19, 4) NOT NULL,
+    derived_balance DECIMAL(19, 4) NOT NULL,
+    difference DECIMAL(19, 4) NOT NULL,
+    entry_count BIGINT NOT NULL,
+    detected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE UNIQUE INDEX idx_balance_breaks_open ON balance_breaks(account_id, currency)
+    WHERE resolved_at IS NULL;
+CREATE INDEX idx_balance_breaks_detected ON balance_breaks(detected_at);
+
+ALTER TYPE alert_rule_type ADD VALUE 'BALANCE_BREAK';
@@ -0,0 +1,22 @@
+-- Create Account Activity projection
+-- Per-account daily aggregates built from transaction-settled events in the outbox,
+-- so activity dashboards do not have to scan ledger_entries. Each event is applied
+-- once: the projector stamps projected_at in the same transaction as its upsert.
+CREATE TABLE account_activity_daily (
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    activity_date DATE NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    transaction_type transaction_type NOT NULL,
+    transaction_count BIGINT NOT NULL DEFAULT 0,
+    total_in DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    total_out DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    total_fees DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (account_id, activity_date, currency, transaction_type)
+);
+
+ALTER TABLE event_outbox ADD COLUMN projected_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_event_outbox_unprojected
+    ON event_outbox(created_at)
+    WHERE projected_at IS NULL AND topic = 'settlement.transactions';
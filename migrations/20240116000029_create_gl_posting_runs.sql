@@ -0,0 +1,25 @@
+-- Create General Ledger posting tables
+-- A posting run aggregates one day of ledger entries into journal lines per GL account
+-- code and currency for export to the ERP. There is at most one run per posting date,
+-- so re-running a day returns the journal already posted instead of posting it twice.
+CREATE TABLE gl_posting_runs (
+    id UUID PRIMARY KEY,
+    posting_date DATE NOT NULL UNIQUE,
+    line_count INTEGER NOT NULL DEFAULT 0,
+    entry_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE gl_journal_lines (
+    run_id UUID NOT NULL REFERENCES gl_posting_runs(id),
+    line_number INTEGER NOT NULL,
+    gl_code VARCHAR(50) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    debit_amount DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    credit_amount DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    entry_count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (run_id, line_number),
+    UNIQUE (run_id, gl_code, currency)
+);
+
+CREATE INDEX idx_ledger_effective_date ON ledger_entries(effective_date);
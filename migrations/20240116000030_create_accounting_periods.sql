@@ -0,0 +1,16 @@
+-- Create Accounting Periods table
+-- Entries can be backdated to any day of an open period. Periods are calendar months
+-- and are open unless a row here closes them.
+CREATE TYPE accounting_period_status AS ENUM ('OPEN', 'CLOSED');
+
+CREATE TABLE accounting_periods (
+    period_start DATE PRIMARY KEY,
+    period_end DATE NOT NULL,
+    status accounting_period_status NOT NULL DEFAULT 'OPEN',
+    closed_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (period_end >= period_start)
+);
+
+-- Value-dated balances sum an account's entries by effective date
+CREATE INDEX idx_ledger_account_effective_date ON ledger_entries(account_id, currency, effective_date);
@@ -0,0 +1,21 @@
+-- Add transaction expiry
+-- Pending transactions and queued submissions older than their type's TTL are expired
+-- by the expiry sweeper. Funds held for a pending transaction are released when it expires.
+ALTER TYPE transaction_status ADD VALUE IF NOT EXISTS 'EXPIRED';
+ALTER TYPE submission_status ADD VALUE IF NOT EXISTS 'EXPIRED';
+ALTER TYPE transaction_audit_action ADD VALUE IF NOT EXISTS 'EXPIRED';
+
+-- Funds reserved on an account for a pending transaction
+CREATE TABLE transaction_holds (
+    transaction_id UUID PRIMARY KEY REFERENCES transactions(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    amount DECIMAL(19, 4) NOT NULL CHECK (amount > 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    released_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_transactions_pending_created ON transactions(type, created_at)
+    WHERE status = 'PENDING';
+CREATE INDEX idx_transaction_submissions_queued_created ON transaction_submissions(created_at)
+    WHERE status = 'QUEUED';
@@ -0,0 +1,66 @@
+-- Create Balance Incidents
+-- A trigger on account_balances opens an incident whenever a balance falls below its
+-- allowed floor, however the write was made (application bug, race or manual SQL).
+-- The balance guard job freezes the account and raises alerts for open incidents.
+CREATE TYPE balance_incident_status AS ENUM ('OPEN', 'FROZEN', 'RESOLVED');
+
+-- Lowest available balance allowed per account and currency; zero when absent
+CREATE TABLE balance_floors (
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    floor DECIMAL(19, 4) NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (account_id, currency)
+);
+
+CREATE TABLE balance_incidents (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    floor DECIMAL(19, 4) NOT NULL,
+    available_balance DECIMAL(19, 4) NOT NULL,
+    pending_balance DECIMAL(19, 4) NOT NULL,
+    reserved_balance DECIMAL(19, 4) NOT NULL,
+    status balance_incident_status NOT NULL DEFAULT 'OPEN',
+    detected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    frozen_at TIMESTAMP WITH TIME ZONE,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    resolution_note TEXT
+);
+
+CREATE UNIQUE INDEX idx_balance_incidents_unresolved ON balance_incidents(account_id, currency)
+    WHERE status <> 'RESOLVED';
+CREATE INDEX idx_balance_incidents_open ON balance_incidents(detected_at)
+    WHERE status = 'OPEN';
+
+CREATE FUNCTION guard_balance_floor() RETURNS TRIGGER AS $$
+DECLARE
+    allowed DECIMAL(19, 4);
+BEGIN
+    SELECT floor INTO allowed
+    FROM balance_floors
+    WHERE account_id = NEW.account_id AND currency = NEW.currency;
+    allowed := COALESCE(allowed, 0);
+
+    IF NEW.available_balance < allowed OR NEW.pending_balance < 0 OR NEW.reserved_balance < 0 THEN
+        INSERT INTO balance_incidents (id, account_id, currency, floor, available_balance, pending_balance, reserved_balance)
+        VALUES (gen_random_uuid(), NEW.account_id, NEW.currency, allowed, NEW.available_balance, NEW.pending_balance, NEW.reserved_balance)
+        ON CONFLICT (account_id, currency) WHERE status <> 'RESOLVED'
+        DO UPDATE SET floor = EXCLUDED.floor,
+                      available_balance = EXCLUDED.available_balance,
+                      pending_balance = EXCLUDED.pending_balance,
+                      reserved_balance = EXCLUDED.reserved_balance,
+                      last_seen_at = NOW();
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER account_balances_floor_guard
+    AFTER INSERT OR UPDATE OF available_balance, pending_balance, reserved_balance ON account_balances
+    FOR EACH ROW EXECUTE FUNCTION guard_balance_floor();
+
+ALTER TYPE status_reason_code ADD VALUE 'BALANCE_FLOOR_BREACH';
+ALTER TYPE alert_rule_type ADD VALUE 'NEGATIVE_BALANCE';
@@ -0,0 +1,16 @@
+-- Scope external ID uniqueness
+-- External IDs are deduplicated on a key qualified by the configured scope (globally,
+-- per source system, or per source system and day) instead of a global unique index.
+-- Duplicates accepted as resubmissions carry no key and point at the transaction they resubmit.
+ALTER TABLE transactions
+    ADD COLUMN source_system VARCHAR(64),
+    ADD COLUMN resubmission_of UUID REFERENCES transactions(id),
+    ADD COLUMN dedupe_key VARCHAR(400);
+
+UPDATE transactions SET dedupe_key = external_id;
+
+DROP INDEX idx_transactions_external_id;
+CREATE INDEX idx_transactions_external_id ON transactions(external_id);
+CREATE UNIQUE INDEX idx_transactions_dedupe_key ON transactions(dedupe_key);
+CREATE INDEX idx_transactions_resubmission_of ON transactions(resubmission_of)
+    WHERE resubmission_of IS NOT NULL;
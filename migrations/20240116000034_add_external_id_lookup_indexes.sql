@@ -0,0 +1,6 @@
+-- Index transaction lookups by upstream reference
+-- Partners echo references back with their own casing, so lookups match external IDs
+-- case-insensitively. Reversals and fee refunds point at the transaction they return
+-- through metadata, so a lookup can also list them.
+CREATE INDEX idx_transactions_external_id_lower ON transactions (LOWER(external_id));
+CREATE INDEX idx_transactions_original_transaction_id ON transactions ((metadata->>'original_transaction_id'));
@@ -0,0 +1,22 @@
+-- Extend settlement profiles to one per participant and currency
+-- Besides ABA routing for NACHA, a profile can carry an IBAN and BIC for ISO 20022
+-- pacs.008 credit transfers, the rail the participant prefers to settle over, and a
+-- cut-off time overriding the settlement window's. Existing profiles are USD over ACH.
+CREATE TYPE payment_rail AS ENUM ('ACH', 'SEPA', 'SWIFT');
+
+ALTER TABLE settlement_profiles
+    ADD COLUMN currency VARCHAR(3),
+    ADD COLUMN bic VARCHAR(11),
+    ADD COLUMN iban VARCHAR(34),
+    ADD COLUMN rail payment_rail NOT NULL DEFAULT 'ACH',
+    ADD COLUMN cut_off_time TIME,
+    ALTER COLUMN routing_number DROP NOT NULL,
+    ALTER COLUMN bank_account_number DROP NOT NULL,
+    ALTER COLUMN bank_account_number TYPE VARCHAR(34);
+
+UPDATE settlement_profiles SET currency = 'USD';
+
+ALTER TABLE settlement_profiles
+    ALTER COLUMN currency SET NOT NULL,
+    DROP CONSTRAINT settlement_profiles_pkey,
+    ADD PRIMARY KEY (account_id, currency);
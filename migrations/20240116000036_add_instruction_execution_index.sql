@@ -0,0 +1,4 @@
+-- Index completed instruction executions by completion time
+-- Intraday liquidity reports take the time an instruction's execution saga completed as
+-- the moment its net obligation was paid.
+CREATE INDEX idx_sagas_completed ON sagas(saga_type, updated_at) WHERE status = 'COMPLETED';
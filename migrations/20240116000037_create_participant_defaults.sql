@@ -0,0 +1,22 @@
+-- Create Participant Defaults
+-- A participant that fails to fund its net obligation in a completed batch is declared
+-- in default. The batch's netting positions are rewritten without it and the default
+-- report, with the original and adjusted positions, is kept as JSONB.
+CREATE TYPE default_resolution AS ENUM ('EXCLUDE', 'LOSS_SHARING');
+
+CREATE TABLE participant_defaults (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    participant_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    unfunded_amount DECIMAL(19, 4) NOT NULL,
+    resolution default_resolution NOT NULL,
+    reason TEXT,
+    report JSONB NOT NULL,
+    declared_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (batch_id, participant_id)
+);
+
+CREATE INDEX idx_participant_defaults_participant ON participant_defaults(participant_id);
+
+ALTER TYPE status_reason_code ADD VALUE 'PARTICIPANT_DEFAULT';
@@ -0,0 +1,41 @@
+-- Create Risk Holds tables
+-- Payments and transfers tripping a risk rule (large amount, new counterparty,
+-- velocity) are held for review before anything is posted. The request is kept so a
+-- reviewer can release it for posting or reject it; every decision is audited.
+CREATE TYPE risk_hold_status AS ENUM ('HELD', 'RELEASED', 'REJECTED');
+CREATE TYPE risk_hold_action AS ENUM ('HELD', 'RELEASED', 'REJECTED', 'REOPENED');
+
+CREATE TABLE risk_holds (
+    id UUID PRIMARY KEY,
+    idempotency_key VARCHAR(255) NOT NULL UNIQUE,
+    external_id VARCHAR(255) NOT NULL,
+    type transaction_type NOT NULL,
+    source_account_id UUID NOT NULL REFERENCES accounts(id),
+    destination_account_id UUID NOT NULL REFERENCES accounts(id),
+    amount DECIMAL(19, 4) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    request JSONB NOT NULL,
+    triggers JSONB NOT NULL,
+    status risk_hold_status NOT NULL DEFAULT 'HELD',
+    transaction_id UUID REFERENCES transactions(id),
+    reviewed_by VARCHAR(255),
+    review_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    reviewed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_risk_holds_status ON risk_holds(status, created_at);
+
+CREATE TABLE risk_hold_audit (
+    id UUID PRIMARY KEY,
+    hold_id UUID NOT NULL REFERENCES risk_holds(id),
+    action risk_hold_action NOT NULL,
+    actor VARCHAR(255),
+    reason TEXT,
+    recorded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_risk_hold_audit_hold ON risk_hold_audit(hold_id, recorded_at);
+
+-- Velocity rule: a source account's recent transactions
+CREATE INDEX idx_transactions_source_created ON transactions(source_account_id, created_at);
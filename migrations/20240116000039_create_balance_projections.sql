@@ -0,0 +1,55 @@
+-- Event-sourced balance projection
+-- Every ledger entry is stamped with a per-account sequence taken from a counter on the
+-- account's balance row. Postings already hold that row's lock until they commit, so the
+-- sequence increases strictly in commit order without adding contention.
+-- balance_projections is a read model folded from the entries in sequence order by a
+-- background projector; it can be dropped and rebuilt from the ledger at any time.
+ALTER TABLE account_balances ADD COLUMN entry_sequence BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ledger_entries ADD COLUMN account_sequence BIGINT;
+
+UPDATE ledger_entries e
+SET account_sequence = s.sequence
+FROM (
+    SELECT id, created_at,
+           ROW_NUMBER() OVER (PARTITION BY account_id, currency ORDER BY created_at, id) AS sequence
+    FROM ledger_entries
+) s
+WHERE e.id = s.id AND e.created_at = s.created_at;
+
+UPDATE account_balances b
+SET entry_sequence = s.last_sequence
+FROM (
+    SELECT account_id, currency, MAX(account_sequence) AS last_sequence
+    FROM ledger_entries
+    GROUP BY account_id, currency
+) s
+WHERE b.account_id = s.account_id AND b.currency = s.currency;
+
+CREATE FUNCTION assign_ledger_entry_sequence() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO account_balances (account_id, currency, entry_sequence)
+    VALUES (NEW.account_id, NEW.currency, 1)
+    ON CONFLICT (account_id, currency) DO UPDATE
+        SET entry_sequence = account_balances.entry_sequence + 1
+    RETURNING entry_sequence INTO NEW.account_sequence;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER ledger_entries_account_sequence
+    BEFORE INSERT ON ledger_entries
+    FOR EACH ROW EXECUTE FUNCTION assign_ledger_entry_sequence();
+
+ALTER TABLE ledger_entries ALTER COLUMN account_sequence SET NOT NULL;
+
+CREATE INDEX idx_ledger_account_sequence ON ledger_entries(account_id, currency, account_sequence);
+
+CREATE TABLE balance_projections (
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    balance DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    last_sequence BIGINT NOT NULL DEFAULT 0,
+    last_entry_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (account_id, currency)
+);
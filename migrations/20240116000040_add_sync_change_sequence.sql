@@ -0,0 +1,34 @@
+-- Change sequence for downstream sync
+-- Every insert or update of an account or transaction stamps the row with the next value
+-- of a global change sequence and the ID of the database transaction that wrote it.
+-- Sync reads only rows written by transactions older than the oldest one still running
+-- (the snapshot xmin), so no change can commit behind a sync token already handed out.
+CREATE SEQUENCE sync_change_seq;
+
+ALTER TABLE accounts ADD COLUMN change_seq BIGINT, ADD COLUMN change_xid BIGINT;
+ALTER TABLE transactions ADD COLUMN change_seq BIGINT, ADD COLUMN change_xid BIGINT;
+
+UPDATE accounts SET change_seq = nextval('sync_change_seq'), change_xid = 0;
+UPDATE transactions SET change_seq = nextval('sync_change_seq'), change_xid = 0;
+
+ALTER TABLE accounts ALTER COLUMN change_seq SET NOT NULL, ALTER COLUMN change_xid SET NOT NULL;
+ALTER TABLE transactions ALTER COLUMN change_seq SET NOT NULL, ALTER COLUMN change_xid SET NOT NULL;
+
+CREATE FUNCTION stamp_sync_change() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.change_seq := nextval('sync_change_seq');
+    NEW.change_xid := pg_current_xact_id()::TEXT::BIGINT;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER accounts_sync_change
+    BEFORE INSERT OR UPDATE ON accounts
+    FOR EACH ROW EXECUTE FUNCTION stamp_sync_change();
+
+CREATE TRIGGER transactions_sync_change
+    BEFORE INSERT OR UPDATE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION stamp_sync_change();
+
+CREATE UNIQUE INDEX idx_accounts_change_seq ON accounts(change_seq);
+CREATE UNIQUE INDEX idx_transactions_change_seq ON transactions(change_seq);
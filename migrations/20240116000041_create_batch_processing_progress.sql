@@ -0,0 +1,13 @@
+-- Batch processing progress
+-- Processing checkpoints its counts here after every chunk of transactions, so any
+-- instance can report how far a batch has got, and at what rate, while another
+-- instance processes it. Reprocessing a batch (after a retry) starts its row over.
+CREATE TABLE batch_processing_progress (
+    batch_id UUID PRIMARY KEY REFERENCES settlement_batches(id),
+    total_transactions INTEGER NOT NULL,
+    processed_transactions INTEGER NOT NULL DEFAULT 0,
+    failed_transactions INTEGER NOT NULL DEFAULT 0,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMP WITH TIME ZONE
+);
@@ -0,0 +1,19 @@
+-- Resumable batch processing
+-- Each processing run holds the batch's progress row under its run_id and heartbeats it
+-- (updated_at) at every checkpoint. A run that stops heartbeating is considered dead,
+-- and another instance can claim the row and carry on.
+-- batch_transaction_checkpoints records every transaction a run has processed, written
+-- in the same database transaction as the processing itself, so a resumed run skips
+-- exactly the transactions whose effects were committed.
+ALTER TABLE batch_processing_progress
+    ADD COLUMN run_id UUID,
+    ADD COLUMN resumes INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE batch_transaction_checkpoints (
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    transaction_id UUID NOT NULL REFERENCES transactions(id),
+    outcome VARCHAR(16) NOT NULL CHECK (outcome IN ('PROCESSED', 'FAILED')),
+    error_message TEXT,
+    processed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (batch_id, transaction_id)
+);
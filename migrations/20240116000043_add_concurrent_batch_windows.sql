@@ -0,0 +1,18 @@
+-- Concurrent settlement windows per currency
+-- Several named windows can settle the same currency (e.g. two schemes both in USD),
+-- each with its own open batches per settlement date. Batches are unique per date,
+-- currency, window (none for batches outside any window) and sequence within it, so
+-- instances opening a window's batch at the same time cannot both create it.
+CREATE UNIQUE INDEX idx_batches_date_currency_window_sequence
+    ON settlement_batches(settlement_date, currency, window_id, sequence_number) NULLS NOT DISTINCT;
+
+-- Netting reports carry their batch's window so each window's netting can be reported
+ALTER TABLE netting_reports ADD COLUMN window_id UUID REFERENCES settlement_windows(id);
+
+UPDATE netting_reports r
+SET window_id = b.window_id
+FROM settlement_batches b
+WHERE b.id = r.batch_id AND b.window_id IS NOT NULL;
+
+CREATE INDEX idx_netting_reports_window ON netting_reports(window_id, generated_at)
+    WHERE window_id IS NOT NULL;
@@ -0,0 +1,25 @@
+-- Create Balance Reservations table
+-- Clients reserve part of an account's balance under a name and a reference unique to
+-- the account, optionally until an expiry. An active reservation is released back to
+-- the account, captured into a settled payment, or expired by the expiry sweeper.
+CREATE TYPE balance_reservation_status AS ENUM ('ACTIVE', 'RELEASED', 'CAPTURED', 'EXPIRED');
+
+CREATE TABLE balance_reservations (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    amount DECIMAL(19, 4) NOT NULL CHECK (amount > 0),
+    name VARCHAR(255) NOT NULL,
+    reference VARCHAR(255) NOT NULL,
+    status balance_reservation_status NOT NULL DEFAULT 'ACTIVE',
+    expires_at TIMESTAMP WITH TIME ZONE,
+    -- The payment a captured reservation was converted into
+    transaction_id UUID REFERENCES transactions(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    closed_at TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT uq_balance_reservations_account_reference UNIQUE (account_id, reference)
+);
+
+CREATE INDEX idx_balance_reservations_account ON balance_reservations(account_id, created_at);
+CREATE INDEX idx_balance_reservations_expiring ON balance_reservations(expires_at)
+    WHERE status = 'ACTIVE' AND expires_at IS NOT NULL;
@@ -0,0 +1,26 @@
+-- Bilateral netting mode
+-- Some schemes settle each pair of participants separately rather than against one
+-- multilateral position. A batch netted bilaterally keeps its net pair obligations,
+-- which are what its settlement instructions pay out.
+CREATE TYPE netting_mode AS ENUM ('MULTILATERAL', 'BILATERAL');
+
+ALTER TABLE settlement_batches ADD COLUMN netting_mode netting_mode NOT NULL DEFAULT 'MULTILATERAL';
+
+-- Pairs are stored once, with participant_a the lower account id
+CREATE TABLE bilateral_pairs (
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    participant_a UUID NOT NULL REFERENCES accounts(id),
+    participant_b UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    a_to_b_gross DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    b_to_a_gross DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    -- Positive when A pays B, negative when B pays A
+    net_amount DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    transaction_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (batch_id, participant_a, participant_b, currency),
+    CONSTRAINT chk_bilateral_pairs_order CHECK (participant_a < participant_b)
+);
+
+CREATE INDEX idx_bilateral_pairs_participant_a ON bilateral_pairs(participant_a);
+CREATE INDEX idx_bilateral_pairs_participant_b ON bilateral_pairs(participant_b);
@@ -0,0 +1,12 @@
+-- Net debit caps
+-- The most a participant may owe, net, in one batch. Accounts without a cap fall back
+-- to the configured default; assignment rejects or queues transactions that would breach it.
+CREATE TABLE net_debit_caps (
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    cap DECIMAL(19, 4) NOT NULL CHECK (cap >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (account_id, currency)
+);
+
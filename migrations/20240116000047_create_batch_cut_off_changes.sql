@@ -0,0 +1,19 @@
+-- Batch cut-off changes
+-- Operators can move a pending batch's cut-off or close it early. Each change needs a
+-- second person's approval and is kept here with who asked, who approved and why.
+CREATE TYPE cut_off_change_kind AS ENUM ('MOVED', 'CLOSED_EARLY');
+
+CREATE TABLE batch_cut_off_changes (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    kind cut_off_change_kind NOT NULL,
+    previous_cut_off TIMESTAMP WITH TIME ZONE NOT NULL,
+    new_cut_off TIMESTAMP WITH TIME ZONE NOT NULL,
+    requested_by VARCHAR(255) NOT NULL,
+    approved_by VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    recorded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (requested_by <> approved_by)
+);
+
+CREATE INDEX idx_batch_cut_off_changes_batch ON batch_cut_off_changes(batch_id, recorded_at);
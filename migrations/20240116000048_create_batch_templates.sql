@@ -0,0 +1,33 @@
+-- Batch templates
+-- A template holds the configuration of a recurring batch (currency, window or cut-off,
+-- metadata, netting mode and cut-off approval policy). A provisioning job opens each
+-- active template's batch for the next settlement date ahead of time.
+CREATE TYPE cut_off_approval_policy AS ENUM ('FOUR_EYES', 'SINGLE_OPERATOR');
+
+ALTER TABLE settlement_batches
+    ADD COLUMN cut_off_approval cut_off_approval_policy NOT NULL DEFAULT 'FOUR_EYES';
+
+-- The approval policy is now per batch, so requester and approver may be the same
+ALTER TABLE batch_cut_off_changes DROP CONSTRAINT batch_cut_off_changes_check;
+
+CREATE TABLE batch_templates (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    window_id UUID REFERENCES settlement_windows(id),
+    -- UTC cut-off for templates outside a named window
+    cut_off_time TIME,
+    metadata JSONB,
+    netting_mode netting_mode NOT NULL DEFAULT 'MULTILATERAL',
+    cut_off_approval cut_off_approval_policy NOT NULL DEFAULT 'FOUR_EYES',
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (currency, name),
+    CHECK ((window_id IS NULL) <> (cut_off_time IS NULL))
+);
+
+-- One active template per currency and window, since they share an open batch
+CREATE UNIQUE INDEX idx_batch_templates_slot
+    ON batch_templates(currency, COALESCE(window_id, '00000000-0000-0000-0000-000000000000'::uuid))
+    WHERE active;
@@ -0,0 +1,28 @@
+-- Fee settlements
+-- In the accrued fee booking mode, fees are withheld into a per-currency fees-receivable
+-- account and accrue there per payer and batch. When a batch completes, each payer's
+-- accrued fees, net of fees reversed or refunded since, move to the revenue account in
+-- one fee transaction, recorded here with the fee legs it settled.
+CREATE TABLE fee_settlements (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    accrued_amount DECIMAL(19, 4) NOT NULL,
+    reversed_amount DECIMAL(19, 4) NOT NULL,
+    net_amount DECIMAL(19, 4) NOT NULL,
+    fee_count INTEGER NOT NULL,
+    -- Fee transaction moving the net amount, none when it is zero
+    transaction_id UUID REFERENCES transactions(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_fee_settlements_batch ON fee_settlements(batch_id, account_id);
+
+-- ledger_entries is partitioned, so its entries cannot be referenced by ID alone
+CREATE TABLE fee_settlement_entries (
+    ledger_entry_id UUID PRIMARY KEY,
+    fee_settlement_id UUID NOT NULL REFERENCES fee_settlements(id)
+);
+
+CREATE INDEX idx_fee_settlement_entries_settlement ON fee_settlement_entries(fee_settlement_id);
@@ -0,0 +1,46 @@
+-- Participant invoicing
+-- A contract prices a participant's monthly transaction volume in a currency: a price
+-- per transaction and a rate on the amount, with free transactions and a monthly cap.
+-- Each ended month is invoiced once per participant and currency; the billed total is
+-- posted as a receivable against fee revenue.
+CREATE TABLE invoice_contracts (
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    free_transactions INTEGER NOT NULL DEFAULT 0 CHECK (free_transactions >= 0),
+    per_transaction_price DECIMAL(19, 4) NOT NULL DEFAULT 0 CHECK (per_transaction_price >= 0),
+    volume_rate DECIMAL(19, 8) NOT NULL DEFAULT 0 CHECK (volume_rate >= 0),
+    monthly_cap DECIMAL(19, 4) CHECK (monthly_cap >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (account_id, currency)
+);
+
+CREATE TABLE invoices (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    transaction_count BIGINT NOT NULL,
+    transaction_volume DECIMAL(19, 4) NOT NULL,
+    -- Fees already charged on the transactions, shown for reference and not billed
+    fees_charged DECIMAL(19, 4) NOT NULL,
+    total_amount DECIMAL(19, 4) NOT NULL,
+    -- Receivable posting, none when nothing was billed
+    transaction_id UUID REFERENCES transactions(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (account_id, currency, period_start)
+);
+
+CREATE INDEX idx_invoices_period ON invoices(period_start, currency);
+
+CREATE TABLE invoice_line_items (
+    invoice_id UUID NOT NULL REFERENCES invoices(id),
+    line_number INTEGER NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    description VARCHAR(255) NOT NULL,
+    quantity DECIMAL(19, 4) NOT NULL,
+    unit_price DECIMAL(19, 8) NOT NULL,
+    amount DECIMAL(19, 4) NOT NULL,
+    PRIMARY KEY (invoice_id, line_number)
+);
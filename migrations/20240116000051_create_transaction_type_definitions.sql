@@ -0,0 +1,19 @@
+-- Create Transaction Type Definitions table
+-- Scheme-specific transaction types (e.g. INTERCHANGE_FEE, ADJUSTMENT, SWEEP) post as
+-- one of the base transaction types and carry their own behavior: whether they can be
+-- reversed, whether they net with other transactions in a batch, whether they must
+-- reference an original transaction and what happens to their fees.
+CREATE TABLE transaction_type_definitions (
+    code VARCHAR(50) PRIMARY KEY,
+    base_type transaction_type NOT NULL,
+    reversible BOOLEAN NOT NULL DEFAULT FALSE,
+    nettable BOOLEAN NOT NULL DEFAULT TRUE,
+    requires_original BOOLEAN NOT NULL DEFAULT FALSE,
+    fee_policy VARCHAR(16) NOT NULL DEFAULT 'CONFIGURED',
+    description TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Custom type of the transaction, if it is not just its base type
+ALTER TABLE transactions ADD COLUMN type_code VARCHAR(50) REFERENCES transaction_type_definitions(code);
@@ -0,0 +1,5 @@
+-- Add per-transaction netting exclusion
+-- Excluded transactions stay in their batch but settle gross, each with its own
+-- instruction. Batch assignment also sets the flag for transactions whose type does not
+-- net, so a batch's exclusions do not change if the type is redefined afterwards.
+ALTER TABLE transactions ADD COLUMN exclude_from_netting BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+-- Index recent transactions by pair for duplicate payment detection
+-- The risk rule looks for a transaction between the same two accounts within a few
+-- minutes of a new one, so the lookup is by pair and creation time.
+CREATE INDEX idx_transactions_pair_created ON transactions (source_account_id, destination_account_id, created_at);
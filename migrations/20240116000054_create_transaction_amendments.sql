@@ -0,0 +1,17 @@
+-- Create Transaction Amendments
+-- Transactions still waiting to be posted, as queued submissions or risk holds, can have
+-- their amount, effective date and metadata amended. Each amendment is recorded with the
+-- values it replaced; posted transactions are immutable.
+CREATE TYPE amendment_target AS ENUM ('SUBMISSION', 'RISK_HOLD');
+
+CREATE TABLE transaction_amendments (
+    id UUID PRIMARY KEY,
+    target amendment_target NOT NULL,
+    target_id UUID NOT NULL,
+    changes JSONB NOT NULL,
+    amended_by VARCHAR(255) NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_transaction_amendments_target ON transaction_amendments(target_id, created_at);
@@ -0,0 +1,13 @@
+-- Add narratives and references to transactions and ledger entries
+-- The narrative is the human-readable description auditors and support read on
+-- statements and ledger listings. It comes from the request, or else from the narrative
+-- template of the transaction's type; the reference is the counterparty's own reference
+-- (an invoice or order number), passed through as given.
+ALTER TABLE transactions ADD COLUMN narrative TEXT;
+ALTER TABLE transactions ADD COLUMN reference VARCHAR(255);
+
+ALTER TABLE ledger_entries ADD COLUMN narrative TEXT;
+ALTER TABLE ledger_entries ADD COLUMN reference VARCHAR(255);
+
+-- Narrative template of each type, with {placeholders} filled from the transaction
+ALTER TABLE transaction_type_definitions ADD COLUMN narrative_template TEXT;
@@ -0,0 +1,16 @@
+-- Create Account Identifiers table
+-- Secondary identifiers upstream systems address accounts by instead of their UUID:
+-- IBANs, internal codes and free-form aliases. An identifier, stored normalized, names
+-- at most one account.
+CREATE TYPE account_identifier_type AS ENUM ('IBAN', 'INTERNAL_CODE', 'ALIAS');
+
+CREATE TABLE account_identifiers (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    identifier_type account_identifier_type NOT NULL,
+    identifier VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT uq_account_identifiers_identifier UNIQUE (identifier)
+);
+
+CREATE INDEX idx_account_identifiers_account ON account_identifiers(account_id);
@@ -0,0 +1,13 @@
+-- Create Batch Reconciliations table
+-- Totals checks run on a batch before it is marked completed: transaction gross against
+-- the batch's gross amount, ledger debits against credits for its transactions, and
+-- netting positions summing to zero. A batch with any break is failed instead.
+CREATE TABLE batch_reconciliations (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    passed BOOLEAN NOT NULL,
+    breaks JSONB NOT NULL DEFAULT '[]',
+    checked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_batch_reconciliations_batch ON batch_reconciliations(batch_id, checked_at);
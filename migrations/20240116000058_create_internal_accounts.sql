@@ -0,0 +1,32 @@
+-- Create Internal Accounts table
+-- The chart of the engine's own accounts (fee revenue, suspense, FX position, settlement
+-- control, collateral), provisioned from configuration and addressed by code and
+-- currency. Each entry is backed by an ordinary account; control accounts only move
+-- through system flows.
+CREATE TYPE internal_account_role AS ENUM (
+    'FEE_REVENUE',
+    'FEE_RECEIVABLE',
+    'INVOICE_RECEIVABLE',
+    'SUSPENSE',
+    'FX_POSITION',
+    'SETTLEMENT_CONTROL',
+    'COLLATERAL'
+);
+
+CREATE TABLE internal_accounts (
+    code VARCHAR(64) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    role internal_account_role,
+    parent_code VARCHAR(64),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    control BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (code, currency),
+    CONSTRAINT uq_internal_accounts_account UNIQUE (account_id),
+    CONSTRAINT fk_internal_accounts_parent FOREIGN KEY (parent_code, currency)
+        REFERENCES internal_accounts(code, currency)
+);
+
+CREATE UNIQUE INDEX uq_internal_accounts_role ON internal_accounts(role, currency) WHERE role IS NOT NULL;
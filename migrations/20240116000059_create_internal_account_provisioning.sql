@@ -0,0 +1,19 @@
+-- Create Internal Account Provisioning table
+-- Audit trail of entries of the chart of internal accounts provisioned in a currency,
+-- at startup or lazily the first time a transaction uses the currency. Entries that
+-- were already provisioned are not recorded again.
+CREATE TYPE provisioning_trigger AS ENUM ('STARTUP', 'FIRST_USE');
+
+CREATE TABLE internal_account_provisioning (
+    id UUID PRIMARY KEY,
+    code VARCHAR(64) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    trigger provisioning_trigger NOT NULL,
+    -- False when an existing account with the entry's external ID was adopted
+    created_account BOOLEAN NOT NULL,
+    provisioned_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_internal_account_provisioning_currency
+    ON internal_account_provisioning(currency, provisioned_at);
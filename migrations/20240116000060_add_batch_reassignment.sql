@@ -0,0 +1,4 @@
+-- Add batch reassignment
+-- A transaction moved from one open batch to another is recorded in its audit log with
+-- both batches and the reason for the move.
+ALTER TYPE transaction_audit_action ADD VALUE IF NOT EXISTS 'BATCH_REASSIGNED';
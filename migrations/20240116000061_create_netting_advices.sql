@@ -0,0 +1,21 @@
+-- Create Netting Advices table
+-- What each participant is told about its part in a netted batch: gross flows, net
+-- position, the instructions that settle it and when a net payer must fund. One advice
+-- per participant and batch; issuing again replaces it.
+CREATE TABLE netting_advices (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    participant_id UUID NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    settlement_date DATE NOT NULL,
+    gross_receivable DECIMAL(19, 4) NOT NULL,
+    gross_payable DECIMAL(19, 4) NOT NULL,
+    net_position DECIMAL(19, 4) NOT NULL,
+    transaction_count INTEGER NOT NULL,
+    instructions JSONB NOT NULL DEFAULT '[]',
+    funding_deadline TIMESTAMP WITH TIME ZONE,
+    generated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (batch_id, participant_id)
+);
+
+CREATE INDEX idx_netting_advices_participant ON netting_advices(participant_id, generated_at);
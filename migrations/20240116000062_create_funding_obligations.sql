@@ -0,0 +1,27 @@
+-- Create Funding Obligations table
+-- What each net payer of a completed batch must pay into the settlement control account
+-- by its funding deadline. Obligations still unfunded at the deadline are escalated.
+CREATE TYPE funding_status AS ENUM ('PENDING', 'FUNDED', 'OVERDUE', 'DEFAULTED');
+
+CREATE TABLE funding_obligations (
+    id UUID PRIMARY KEY,
+    batch_id UUID NOT NULL REFERENCES settlement_batches(id),
+    participant_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    amount DECIMAL(19, 4) NOT NULL CHECK (amount > 0),
+    funded_amount DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    -- Settlement control account credits are matched against, if the chart has one
+    control_account_id UUID REFERENCES accounts(id),
+    deadline TIMESTAMP WITH TIME ZONE NOT NULL,
+    status funding_status NOT NULL DEFAULT 'PENDING',
+    funded_at TIMESTAMP WITH TIME ZONE,
+    escalated_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (batch_id, participant_id)
+);
+
+CREATE INDEX idx_funding_obligations_open ON funding_obligations(deadline)
+    WHERE status IN ('PENDING', 'OVERDUE');
+
+ALTER TYPE status_reason_code ADD VALUE 'FUNDING_OVERDUE';
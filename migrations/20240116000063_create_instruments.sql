@@ -0,0 +1,35 @@
+-- Create Instruments table
+-- What a ledger code denotes: cash in an ISO 4217 currency, or units of a security held
+-- in custody. Balances and entries keep the code in their currency column; codes not
+-- registered here are cash currencies.
+CREATE TYPE instrument_kind AS ENUM ('CASH', 'SECURITY');
+
+CREATE TABLE instruments (
+    code VARCHAR(12) PRIMARY KEY,
+    kind instrument_kind NOT NULL,
+    -- Scheme of a security's code, e.g. ISIN or CUSIP
+    identifier_type VARCHAR(16),
+    -- Decimal places quantities can have; zero for whole units
+    scale SMALLINT NOT NULL DEFAULT 0 CHECK (scale BETWEEN 0 AND 4),
+    description TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Security identifiers are longer than currency codes
+ALTER TABLE accounts ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE transactions ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE account_balances ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE ledger_entries ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE balance_floors ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE balance_incidents ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE balance_breaks ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE balance_projections ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE balance_reservations ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE account_activity_daily ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE transaction_holds ALTER COLUMN currency TYPE VARCHAR(12);
+ALTER TABLE gl_journal_lines ALTER COLUMN currency TYPE VARCHAR(12);
+
+-- Transactions settled together, all or nothing, share a link
+ALTER TABLE transactions ADD COLUMN link_id UUID;
+
+CREATE INDEX idx_transactions_link ON transactions(link_id) WHERE link_id IS NOT NULL;
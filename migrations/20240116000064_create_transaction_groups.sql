@@ -0,0 +1,17 @@
+-- Create Transaction Groups table
+-- Transactions submitted together and settled all or nothing in one database
+-- transaction. The group's ID is the link_id its transactions share.
+CREATE TYPE transaction_group_status AS ENUM ('PENDING', 'SETTLED', 'FAILED');
+
+CREATE TABLE transaction_groups (
+    id UUID PRIMARY KEY,
+    idempotency_key VARCHAR(255) NOT NULL UNIQUE,
+    -- Idempotency keys of the group's transactions, in submission order
+    transaction_keys TEXT[] NOT NULL,
+    status transaction_group_status NOT NULL DEFAULT 'PENDING',
+    error_code VARCHAR(50),
+    last_error TEXT,
+    settled_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
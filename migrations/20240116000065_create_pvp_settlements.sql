@@ -0,0 +1,34 @@
+-- Create PvP Settlements table
+-- Payment-versus-payment trades: two payments in different currencies between the same
+-- counterparties, held until both payers are funded and then settled together, or
+-- cancelled at their deadline. The settlement's ID is the link_id both payments share.
+CREATE TYPE pvp_status AS ENUM ('AWAITING_FUNDING', 'SETTLED', 'CANCELLED');
+
+CREATE TABLE pvp_settlements (
+    id UUID PRIMARY KEY,
+    external_id VARCHAR(255) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL UNIQUE,
+    -- First payment: from one counterparty to the other in the first currency
+    first_source_account_id UUID NOT NULL REFERENCES accounts(id),
+    first_destination_account_id UUID NOT NULL REFERENCES accounts(id),
+    first_amount DECIMAL(19, 4) NOT NULL CHECK (first_amount > 0),
+    first_currency VARCHAR(12) NOT NULL,
+    first_funded BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Second payment: back the other way in the second currency
+    second_source_account_id UUID NOT NULL REFERENCES accounts(id),
+    second_destination_account_id UUID NOT NULL REFERENCES accounts(id),
+    second_amount DECIMAL(19, 4) NOT NULL CHECK (second_amount > 0),
+    second_currency VARCHAR(12) NOT NULL,
+    second_funded BOOLEAN NOT NULL DEFAULT FALSE,
+    status pvp_status NOT NULL DEFAULT 'AWAITING_FUNDING',
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    settled_at TIMESTAMP WITH TIME ZONE,
+    cancelled_at TIMESTAMP WITH TIME ZONE,
+    cancel_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (first_currency <> second_currency)
+);
+
+CREATE INDEX idx_pvp_settlements_awaiting ON pvp_settlements(expires_at)
+    WHERE status = 'AWAITING_FUNDING';
@@ -0,0 +1,69 @@
+-- Create FX Rates, Conversions and Rate Adjustments tables
+-- Every rate a conversion can use is stored with its source and spread, and each
+-- conversion keeps the ID of the rate it was made at, so conversions can be audited
+-- against the rates in effect at the time.
+CREATE TABLE fx_rates (
+    id UUID PRIMARY KEY,
+    -- Units of the quote currency one unit of the base currency buys
+    base_currency VARCHAR(3) NOT NULL,
+    quote_currency VARCHAR(3) NOT NULL,
+    rate DECIMAL(24, 10) NOT NULL CHECK (rate > 0),
+    -- Fraction of the rate kept by the engine on conversions, e.g. 0.002 for 20 bp
+    spread DECIMAL(12, 10) NOT NULL DEFAULT 0 CHECK (spread >= 0 AND spread < 1),
+    source VARCHAR(100) NOT NULL,
+    effective_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- A correction takes the effective time of the rate it corrects, which is then
+    -- superseded and no longer used for new conversions
+    corrects_rate_id UUID REFERENCES fx_rates(id),
+    correction_reason TEXT,
+    superseded_by UUID REFERENCES fx_rates(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (base_currency <> quote_currency)
+);
+
+CREATE INDEX idx_fx_rates_pair ON fx_rates(base_currency, quote_currency, effective_at DESC);
+CREATE UNIQUE INDEX idx_fx_rates_corrects ON fx_rates(corrects_rate_id) WHERE corrects_rate_id IS NOT NULL;
+
+-- A conversion debits the source account in the base currency into the FX position
+-- account of that currency, and credits the destination account in the quote currency
+-- from the FX position account of that currency.
+CREATE TABLE fx_conversions (
+    id UUID PRIMARY KEY,
+    external_id VARCHAR(255) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL UNIQUE,
+    source_account_id UUID NOT NULL REFERENCES accounts(id),
+    destination_account_id UUID NOT NULL REFERENCES accounts(id),
+    source_amount DECIMAL(19, 4) NOT NULL CHECK (source_amount > 0),
+    source_currency VARCHAR(3) NOT NULL,
+    destination_amount DECIMAL(19, 4) NOT NULL,
+    destination_currency VARCHAR(3) NOT NULL,
+    -- Rate the conversion was made at, and the rate it is now valued at after corrections
+    rate_id UUID NOT NULL REFERENCES fx_rates(id),
+    applied_rate DECIMAL(24, 10) NOT NULL,
+    current_rate_id UUID NOT NULL REFERENCES fx_rates(id),
+    debit_transaction_id UUID NOT NULL REFERENCES transactions(id),
+    credit_transaction_id UUID NOT NULL REFERENCES transactions(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_fx_conversions_rate ON fx_conversions(rate_id);
+CREATE INDEX idx_fx_conversions_current_rate ON fx_conversions(current_rate_id);
+
+-- Revaluation adjustments posted when a rate a conversion was valued at is corrected:
+-- the difference, in the destination currency, between the conversion at the corrected
+-- rate and at the rate it was valued at before.
+CREATE TABLE fx_rate_adjustments (
+    id UUID PRIMARY KEY,
+    conversion_id UUID NOT NULL REFERENCES fx_conversions(id),
+    rate_id UUID NOT NULL REFERENCES fx_rates(id),
+    previous_rate_id UUID NOT NULL REFERENCES fx_rates(id),
+    -- Positive when the destination account is credited, negative when it is debited
+    amount DECIMAL(19, 4) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    transaction_id UUID NOT NULL REFERENCES transactions(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (conversion_id, rate_id)
+);
+
+CREATE INDEX idx_fx_rate_adjustments_rate ON fx_rate_adjustments(rate_id);
@@ -0,0 +1,40 @@
+-- Create FX Revaluation Runs and Lines tables
+-- Period-end revaluation of the internal accounts' balances in currencies other than
+-- the functional currency. Each line marks one balance to the period-end rate; the
+-- change in its functional value since the balance was last revalued is posted as an
+-- unrealized gain or loss against the configured P&L account.
+CREATE TABLE fx_revaluation_runs (
+    id UUID PRIMARY KEY,
+    period_end DATE NOT NULL,
+    functional_currency VARCHAR(3) NOT NULL,
+    pnl_account_id UUID NOT NULL REFERENCES accounts(id),
+    -- Functional-currency FX position the gains and losses are posted against
+    position_account_id UUID NOT NULL REFERENCES accounts(id),
+    -- Net unrealized gain (positive) or loss (negative) in the functional currency
+    total_gain_loss DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    -- Currencies with a balance but no rate to the functional currency by period end
+    missing_rates TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (period_end, functional_currency)
+);
+
+CREATE TABLE fx_revaluation_lines (
+    id UUID PRIMARY KEY,
+    run_id UUID NOT NULL REFERENCES fx_revaluation_runs(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    currency VARCHAR(3) NOT NULL,
+    -- Balance at the end of the period, in its own currency
+    balance DECIMAL(19, 4) NOT NULL,
+    rate_id UUID NOT NULL REFERENCES fx_rates(id),
+    -- Units of the functional currency per unit of the balance's currency
+    rate DECIMAL(24, 10) NOT NULL,
+    -- Rate of the balance's previous revaluation; none the first time it is revalued
+    previous_rate DECIMAL(24, 10),
+    functional_amount DECIMAL(19, 4) NOT NULL,
+    gain_loss DECIMAL(19, 4) NOT NULL,
+    transaction_id UUID REFERENCES transactions(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_fx_revaluation_lines_run ON fx_revaluation_lines(run_id);
+CREATE INDEX idx_fx_revaluation_lines_balance ON fx_revaluation_lines(account_id, currency, created_at DESC);
@@ -0,0 +1,6 @@
+-- Per-batch instruction strategy
+-- A batch can choose how its multilateral positions are turned into settlement
+-- instructions. NULL keeps the engine's configured strategy.
+CREATE TYPE instruction_strategy AS ENUM ('GREEDY', 'MINIMUM_COUNT', 'CONTROL_ACCOUNT');
+
+ALTER TABLE settlement_batches ADD COLUMN instruction_strategy instruction_strategy;
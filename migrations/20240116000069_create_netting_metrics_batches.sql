@@ -0,0 +1,12 @@
+-- Create Netting Metrics Batches table
+-- Each netted batch's contribution to the daily netting totals, so netting a batch
+-- again replaces its contribution instead of counting it twice.
+CREATE TABLE netting_metrics_batches (
+    batch_id UUID PRIMARY KEY,
+    metric_date DATE NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    transactions_netted BIGINT NOT NULL,
+    gross_volume DECIMAL(19, 4) NOT NULL,
+    net_volume DECIMAL(19, 4) NOT NULL,
+    recorded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,9 @@
+-- Create Alert Rule Triggers table
+-- When each rule last fired for each account, so a rule covering every account is
+-- suppressed per account rather than for all of them at once.
+CREATE TABLE alert_rule_triggers (
+    rule_id UUID NOT NULL REFERENCES alert_rules(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    last_triggered_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (rule_id, account_id)
+);
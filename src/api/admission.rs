@@ -0,0 +1,189 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::responses::{ApiResponse, ErrorResponse};
+use crate::api::timeouts::is_segment_prefix;
+use crate::observability::{get_metrics, recent_query_latency};
+
+/// Writes in progress at once, unless configured.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 256;
+/// Average query latency above which writes are turned away, unless configured.
+pub const DEFAULT_MAX_DB_LATENCY_MS: u64 = 1_000;
+/// How long rejected clients are told to wait, unless configured.
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+
+/// Error code of writes turned away by admission control.
+pub const OVERLOADED_CODE: &str = "OVERLOADED";
+
+/// Write routes admitted regardless of load: the read-only GraphQL API, and job
+/// administration operators need to shed load.
+const EXEMPT_ROUTES: [&str; 2] = ["/graphql", "/admin"];
+
+/// Thresholds past which new writes are rejected.
+#[derive(Debug, Clone)]
+pub struct AdmissionConfig {
+    /// Writes in progress at once; None for no limit.
+    pub max_in_flight: Option<usize>,
+    /// Moving average of database query latency; None to disregard it.
+    pub max_db_latency: Option<Duration>,
+    /// Returned in `Retry-After`.
+    pub retry_after: Duration,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: Some(DEFAULT_MAX_IN_FLIGHT),
+            max_db_latency: Some(Duration::from_millis(DEFAULT_MAX_DB_LATENCY_MS)),
+            retry_after: Duration::from_secs(DEFAULT_RETRY_AFTER_SECS),
+        }
+    }
+}
+
+/// Why a write was turned away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    Concurrency,
+    DbLatency,
+}
+
+impl Rejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rejection::Concurrency => "concurrency",
+            Rejection::DbLatency => "db_latency",
+        }
+    }
+}
+
+/// Tracks writes in progress and turns new ones away early under overload, before
+/// they queue for connections and pile onto the serializable transaction path.
+#[derive(Debug, Default)]
+pub struct AdmissionControl {
+    config: AdmissionConfig,
+    in_flight: AtomicUsize,
+}
+
+impl AdmissionControl {
+    pub fn new(config: AdmissionConfig) -> Self {
+        Self {
+            config,
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &AdmissionConfig {
+        &self.config
+    }
+
+    /// Writes currently admitted and not yet answered.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Admits a write given the current query latency, holding its slot until the
+    /// permit is dropped.
+    pub fn try_admit(self: &Arc<Self>, db_latency: Option<Duration>) -> Result<AdmissionPermit, Rejection> {
+        if let (Some(max), Some(latency)) = (self.config.max_db_latency, db_latency) {
+            if latency > max {
+                return Err(Rejection::DbLatency);
+            }
+        }
+        let admitted = self.in_flight.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| match self.config.max_in_flight {
+            Some(max) if n >= max => None,
+            _ => Some(n + 1),
+        });
+        match admitted {
+            Ok(_) => Ok(AdmissionPermit { control: self.clone() }),
+            Err(_) => Err(Rejection::Concurrency),
+        }
+    }
+}
+
+/// A write's slot; released when dropped.
+#[derive(Debug)]
+pub struct AdmissionPermit {
+    control: Arc<AdmissionControl>,
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        self.control.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Answers writes with `429 OVERLOADED` and `Retry-After` while too many are in
+/// progress or database queries are slow. Reads are always let through.
+pub async fn enforce_admission(
+    State(control): State<Arc<AdmissionControl>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    if request.method().is_safe() || EXEMPT_ROUTES.iter().any(|exempt| is_segment_prefix(exempt, &route)) {
+        return next.run(request).await;
+    }
+
+    match control.try_admit(recent_query_latency()) {
+        Ok(permit) => {
+            get_metrics().set_admission_in_flight(control.in_flight());
+            let response = next.run(request).await;
+            drop(permit);
+            get_metrics().set_admission_in_flight(control.in_flight());
+            response
+        }
+        Err(rejection) => {
+            get_metrics().record_admission_rejected(&route, rejection.as_str());
+            let retry_after = control.config().retry_after.as_secs().max(1);
+            let error = ErrorResponse {
+                retryable: true,
+                ..ErrorResponse::new(OVERLOADED_CODE, "The engine is overloaded; retry the request later")
+            };
+            let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(ApiResponse::<()>::error(error))).into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admission_rejects_past_thresholds() {
+        let control = Arc::new(AdmissionControl::new(AdmissionConfig {
+            max_in_flight: Some(2),
+            max_db_latency: Some(Duration::from_millis(500)),
+            retry_after: Duration::from_secs(1),
+        }));
+
+        let first = control.try_admit(None).unwrap();
+        let _second = control.try_admit(Some(Duration::from_millis(100))).unwrap();
+        assert_eq!(control.try_admit(None).unwrap_err(), Rejection::Concurrency);
+        assert_eq!(control.in_flight(), 2);
+
+        drop(first);
+        assert_eq!(control.in_flight(), 1);
+        assert_eq!(
+            control.try_admit(Some(Duration::from_millis(800))).unwrap_err(),
+            Rejection::DbLatency
+        );
+        assert_eq!(control.in_flight(), 1);
+        assert!(control.try_admit(Some(Duration::from_millis(500))).is_ok());
+    }
+}
@@ -0,0 +1,666 @@
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, ErrorExtensions, Object, OutputType, Schema, SimpleObject,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models;
+use crate::repositories::{
+    AccountRepository, BalanceRepository, BatchRepository, LedgerRepository, NettingRepository, TransactionRepository,
+};
+
+/// Schema of the read-only GraphQL API served at `/graphql`.
+pub type SettlementSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Page size when a query does not give one.
+pub const DEFAULT_PAGE_SIZE: i32 = 50;
+/// Largest page a query can ask for, as in the REST list endpoints.
+pub const MAX_PAGE_SIZE: i32 = 100;
+/// Deepest nesting a query can select, e.g. batch, transactions, ledger entries.
+pub const MAX_QUERY_DEPTH: usize = 8;
+
+/// Builds the schema; resolvers read through the repositories on `pool`.
+pub fn build_schema(pool: PgPool) -> SettlementSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(pool)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+/// Maps a repository error to a GraphQL error carrying the REST error code. Server-side
+/// failures are logged and their details kept out of the response.
+fn gql_error(error: AppError) -> async_graphql::Error {
+    if error.status_code().is_server_error() {
+        tracing::error!("GraphQL query failed: {}", error);
+    }
+    async_graphql::Error::new(error.public_message()).extend_with(|_, extensions| {
+        extensions.set("code", error.code());
+        extensions.set("retryable", error.is_retryable());
+    })
+}
+
+fn pool<'a>(ctx: &Context<'a>) -> &'a PgPool {
+    ctx.data_unchecked::<PgPool>()
+}
+
+/// Limit and offset of a page, with the limit capped at `MAX_PAGE_SIZE`.
+fn page_bounds(limit: Option<i32>, offset: Option<i32>) -> async_graphql::Result<(i64, i64)> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    let offset = offset.unwrap_or(0);
+    if limit < 1 || offset < 0 {
+        return Err(gql_error(AppError::Validation(
+            "limit must be positive and offset must not be negative".to_string(),
+        )));
+    }
+    Ok((limit.min(MAX_PAGE_SIZE) as i64, offset as i64))
+}
+
+/// One page of a list. Lists are fetched one item past the page to tell whether more
+/// follow, so no count query is needed.
+#[derive(SimpleObject)]
+#[graphql(concrete(name = "AccountPage", params(Account)))]
+#[graphql(concrete(name = "TransactionPage", params(Transaction)))]
+#[graphql(concrete(name = "BatchPage", params(Batch)))]
+#[graphql(concrete(name = "LedgerEntryPage", params(LedgerEntry)))]
+pub struct Page<T: OutputType> {
+    pub items: Vec<T>,
+    pub limit: i32,
+    pub offset: i32,
+    pub has_more: bool,
+}
+
+impl<T: OutputType> Page<T> {
+    fn new<R>(mut rows: Vec<R>, limit: i64, offset: i64, item: impl Fn(R) -> T) -> Self {
+        let has_more = rows.len() as i64 > limit;
+        rows.truncate(limit as usize);
+        Self {
+            items: rows.into_iter().map(item).collect(),
+            limit: limit as i32,
+            offset: offset as i32,
+            has_more,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "models::AccountType")]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Revenue,
+    Expense,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "models::AccountStatus")]
+pub enum AccountStatus {
+    Active,
+    Frozen,
+    Closed,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "models::TransactionType")]
+pub enum TransactionType {
+    Payment,
+    Refund,
+    Chargeback,
+    Transfer,
+    Fee,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "models::TransactionStatus")]
+pub enum TransactionStatus {
+    Pending,
+    Settled,
+    Failed,
+    Reversed,
+    Expired,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "models::EntryType")]
+pub enum EntryType {
+    Debit,
+    Credit,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "models::BatchStatus")]
+pub enum BatchStatus {
+    Pending,
+    Processing,
+    Completed,
+    Failed,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "models::NettingMode")]
+pub enum NettingMode {
+    Multilateral,
+    Bilateral,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn account(&self, ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<Option<Account>> {
+        let account = AccountRepository::new(pool(ctx).clone()).find_by_id(id).await.map_err(gql_error)?;
+        Ok(account.map(Account))
+    }
+
+    /// Accounts, newest first.
+    async fn accounts(
+        &self,
+        ctx: &Context<'_>,
+        account_type: Option<AccountType>,
+        status: Option<AccountStatus>,
+        currency: Option<String>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> async_graphql::Result<Page<Account>> {
+        let (limit, offset) = page_bounds(limit, offset)?;
+        let rows = AccountRepository::new(pool(ctx).clone())
+            .list(
+                account_type.map(Into::into),
+                status.map(Into::into),
+                currency.as_deref(),
+                limit + 1,
+                offset,
+            )
+            .await
+            .map_err(gql_error)?;
+        Ok(Page::new(rows, limit, offset, Account))
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<Option<Transaction>> {
+        let transaction = TransactionRepository::new(pool(ctx).clone())
+            .find_by_id(id)
+            .await
+            .map_err(gql_error)?;
+        Ok(transaction.map(Transaction))
+    }
+
+    /// Transactions, newest first, optionally of one account (as payer or payee).
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        account_id: Option<Uuid>,
+        status: Option<TransactionStatus>,
+        currency: Option<String>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> async_graphql::Result<Page<Transaction>> {
+        let (limit, offset) = page_bounds(limit, offset)?;
+        let rows = TransactionRepository::new(pool(ctx).clone())
+            .list_with_filters(account_id, status.map(Into::into), currency.as_deref(), limit + 1, offset)
+            .await
+            .map_err(gql_error)?;
+        Ok(Page::new(rows, limit, offset, Transaction))
+    }
+
+    async fn batch(&self, ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<Option<Batch>> {
+        let batch = BatchRepository::new(pool(ctx).clone()).find_by_id(id).await.map_err(gql_error)?;
+        Ok(batch.map(Batch))
+    }
+
+    /// Settlement batches, newest first.
+    async fn batches(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<BatchStatus>,
+        currency: Option<String>,
+        window_id: Option<Uuid>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> async_graphql::Result<Page<Batch>> {
+        let (limit, offset) = page_bounds(limit, offset)?;
+        let rows = BatchRepository::new(pool(ctx).clone())
+            .list(status.map(Into::into), currency.as_deref(), window_id, limit + 1, offset)
+            .await
+            .map_err(gql_error)?;
+        Ok(Page::new(rows, limit, offset, Batch))
+    }
+
+    /// Netting positions of a batch.
+    async fn netting_positions(&self, ctx: &Context<'_>, batch_id: Uuid) -> async_graphql::Result<Vec<NettingPosition>> {
+        let positions = NettingRepository::new(pool(ctx).clone())
+            .find_by_batch(batch_id)
+            .await
+            .map_err(gql_error)?;
+        Ok(positions.into_iter().map(NettingPosition).collect())
+    }
+}
+
+pub struct Account(models::Account);
+
+#[Object]
+impl Account {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn external_id(&self) -> &str {
+        &self.0.external_id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn account_type(&self) -> AccountType {
+        self.0.account_type.into()
+    }
+
+    async fn status(&self) -> AccountStatus {
+        self.0.status.into()
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn metadata(&self) -> Option<async_graphql::Json<serde_json::Value>> {
+        self.0.metadata.clone().map(async_graphql::Json)
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn updated_at(&self) -> DateTime<Utc> {
+        self.0.updated_at
+    }
+
+    /// The account's balance in each currency it holds.
+    async fn balances(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Balance>> {
+        let balances = BalanceRepository::new(pool(ctx).clone())
+            .find_by_account(self.0.id)
+            .await
+            .map_err(gql_error)?;
+        Ok(balances.into_iter().map(Balance).collect())
+    }
+
+    /// Transactions the account paid or received, newest first.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> async_graphql::Result<Page<Transaction>> {
+        let (limit, offset) = page_bounds(limit, offset)?;
+        let rows = TransactionRepository::new(pool(ctx).clone())
+            .find_by_account(self.0.id, limit + 1, offset)
+            .await
+            .map_err(gql_error)?;
+        Ok(Page::new(rows, limit, offset, Transaction))
+    }
+
+    /// Ledger entries booked to the account, newest first.
+    async fn ledger_entries(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> async_graphql::Result<Page<LedgerEntry>> {
+        let (limit, offset) = page_bounds(limit, offset)?;
+        let rows = LedgerRepository::new(pool(ctx).clone())
+            .find_by_account(self.0.id, limit + 1, offset)
+            .await
+            .map_err(gql_error)?;
+        Ok(Page::new(rows, limit, offset, LedgerEntry))
+    }
+}
+
+pub struct Balance(models::AccountBalance);
+
+#[Object]
+impl Balance {
+    async fn account_id(&self) -> Uuid {
+        self.0.account_id
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn available_balance(&self) -> Decimal {
+        self.0.available_balance
+    }
+
+    async fn pending_balance(&self) -> Decimal {
+        self.0.pending_balance
+    }
+
+    async fn reserved_balance(&self) -> Decimal {
+        self.0.reserved_balance
+    }
+
+    async fn last_updated(&self) -> DateTime<Utc> {
+        self.0.last_updated
+    }
+}
+
+pub struct Transaction(models::TransactionRecord);
+
+#[Object]
+impl Transaction {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn external_id(&self) -> &str {
+        &self.0.external_id
+    }
+
+    async fn transaction_type(&self) -> TransactionType {
+        self.0.transaction_type.into()
+    }
+
+    /// The custom type the transaction was posted as, if any.
+    async fn type_code(&self) -> Option<&str> {
+        self.0.type_code.as_deref()
+    }
+
+    async fn status(&self) -> TransactionStatus {
+        self.0.status.into()
+    }
+
+    async fn source_account_id(&self) -> Uuid {
+        self.0.source_account_id
+    }
+
+    async fn destination_account_id(&self) -> Uuid {
+        self.0.destination_account_id
+    }
+
+    async fn amount(&self) -> Decimal {
+        self.0.amount
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn fee_amount(&self) -> Decimal {
+        self.0.fee_amount
+    }
+
+    async fn net_amount(&self) -> Decimal {
+        self.0.net_amount
+    }
+
+    async fn settlement_batch_id(&self) -> Option<Uuid> {
+        self.0.settlement_batch_id
+    }
+
+    async fn exclude_from_netting(&self) -> bool {
+        self.0.exclude_from_netting
+    }
+
+    async fn narrative(&self) -> Option<&str> {
+        self.0.narrative.as_deref()
+    }
+
+    async fn reference(&self) -> Option<&str> {
+        self.0.reference.as_deref()
+    }
+
+    async fn metadata(&self) -> Option<async_graphql::Json<serde_json::Value>> {
+        self.0.metadata.clone().map(async_graphql::Json)
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn settled_at(&self) -> Option<DateTime<Utc>> {
+        self.0.settled_at
+    }
+
+    async fn source_account(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Account>> {
+        let account = AccountRepository::new(pool(ctx).clone())
+            .find_by_id(self.0.source_account_id)
+            .await
+            .map_err(gql_error)?;
+        Ok(account.map(Account))
+    }
+
+    async fn destination_account(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Account>> {
+        let account = AccountRepository::new(pool(ctx).clone())
+            .find_by_id(self.0.destination_account_id)
+            .await
+            .map_err(gql_error)?;
+        Ok(account.map(Account))
+    }
+
+    /// The double-entry legs the transaction posted.
+    async fn ledger_entries(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<LedgerEntry>> {
+        let entries = LedgerRepository::new(pool(ctx).clone())
+            .find_by_transaction(self.0.id)
+            .await
+            .map_err(gql_error)?;
+        Ok(entries.into_iter().map(LedgerEntry).collect())
+    }
+
+    async fn batch(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Batch>> {
+        let Some(batch_id) = self.0.settlement_batch_id else {
+            return Ok(None);
+        };
+        let batch = BatchRepository::new(pool(ctx).clone())
+            .find_by_id(batch_id)
+            .await
+            .map_err(gql_error)?;
+        Ok(batch.map(Batch))
+    }
+}
+
+pub struct LedgerEntry(models::LedgerEntry);
+
+#[Object]
+impl LedgerEntry {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn transaction_id(&self) -> Uuid {
+        self.0.transaction_id
+    }
+
+    async fn account_id(&self) -> Uuid {
+        self.0.account_id
+    }
+
+    async fn entry_type(&self) -> EntryType {
+        self.0.entry_type.into()
+    }
+
+    async fn amount(&self) -> Decimal {
+        self.0.amount
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn balance_after(&self) -> Decimal {
+        self.0.balance_after
+    }
+
+    async fn effective_date(&self) -> NaiveDate {
+        self.0.effective_date
+    }
+
+    async fn narrative(&self) -> Option<&str> {
+        self.0.narrative.as_deref()
+    }
+
+    async fn reference(&self) -> Option<&str> {
+        self.0.reference.as_deref()
+    }
+
+    async fn metadata(&self) -> Option<async_graphql::Json<serde_json::Value>> {
+        self.0.metadata.clone().map(async_graphql::Json)
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+}
+
+pub struct Batch(models::SettlementBatch);
+
+#[Object]
+impl Batch {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn status(&self) -> BatchStatus {
+        self.0.status.into()
+    }
+
+    async fn settlement_date(&self) -> NaiveDate {
+        self.0.settlement_date
+    }
+
+    async fn cut_off_time(&self) -> DateTime<Utc> {
+        self.0.cut_off_time
+    }
+
+    async fn total_transactions(&self) -> i32 {
+        self.0.total_transactions
+    }
+
+    async fn gross_amount(&self) -> Decimal {
+        self.0.gross_amount
+    }
+
+    async fn net_amount(&self) -> Decimal {
+        self.0.net_amount
+    }
+
+    async fn fee_amount(&self) -> Decimal {
+        self.0.fee_amount
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn sequence_number(&self) -> i32 {
+        self.0.sequence_number
+    }
+
+    async fn window_id(&self) -> Option<Uuid> {
+        self.0.window_id
+    }
+
+    async fn netting_mode(&self) -> NettingMode {
+        self.0.netting_mode.into()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn completed_at(&self) -> Option<DateTime<Utc>> {
+        self.0.completed_at
+    }
+
+    /// The batch's transactions in release order.
+    async fn transactions(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Transaction>> {
+        let transactions = TransactionRepository::new(pool(ctx).clone())
+            .find_by_batch(self.0.id)
+            .await
+            .map_err(gql_error)?;
+        Ok(transactions.into_iter().map(Transaction).collect())
+    }
+
+    async fn netting_positions(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<NettingPosition>> {
+        let positions = NettingRepository::new(pool(ctx).clone())
+            .find_by_batch(self.0.id)
+            .await
+            .map_err(gql_error)?;
+        Ok(positions.into_iter().map(NettingPosition).collect())
+    }
+}
+
+pub struct NettingPosition(models::NettingPosition);
+
+#[Object]
+impl NettingPosition {
+    async fn batch_id(&self) -> Uuid {
+        self.0.batch_id
+    }
+
+    async fn participant_id(&self) -> Uuid {
+        self.0.participant_id
+    }
+
+    async fn currency(&self) -> &str {
+        &self.0.currency
+    }
+
+    async fn gross_receivable(&self) -> Decimal {
+        self.0.gross_receivable
+    }
+
+    async fn gross_payable(&self) -> Decimal {
+        self.0.gross_payable
+    }
+
+    /// Receivable less payable: positive for a net receiver.
+    async fn net_position(&self) -> Decimal {
+        self.0.net_position
+    }
+
+    async fn transaction_count(&self) -> i32 {
+        self.0.transaction_count
+    }
+
+    async fn participant(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Account>> {
+        let account = AccountRepository::new(pool(ctx).clone())
+            .find_by_id(self.0.participant_id)
+            .await
+            .map_err(gql_error)?;
+        Ok(account.map(Account))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_bounds() {
+        assert_eq!(page_bounds(None, None).unwrap(), (DEFAULT_PAGE_SIZE as i64, 0));
+        assert_eq!(page_bounds(Some(500), Some(20)).unwrap(), (MAX_PAGE_SIZE as i64, 20));
+        assert!(page_bounds(Some(0), None).is_err());
+        assert!(page_bounds(None, Some(-1)).is_err());
+    }
+
+    #[test]
+    fn test_page_detects_more_items() {
+        let page = Page::new(vec![1, 2, 3], 2, 0, |n: i32| n);
+        assert_eq!(page.items, vec![1, 2]);
+        assert!(page.has_more);
+
+        let page = Page::new(vec![1, 2], 2, 4, |n: i32| n);
+        assert!(!page.has_more);
+        assert_eq!(page.offset, 4);
+    }
+
+    #[test]
+    fn test_schema_exposes_queries() {
+        let sdl = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish().sdl();
+        assert!(sdl.contains("type TransactionPage"));
+        assert!(sdl.contains("ledgerEntries: [LedgerEntry!]!"));
+        assert!(sdl.contains("nettingPositions(batchId: UUID!): [NettingPosition!]!"));
+    }
+}
@@ -1,28 +1,62 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    response::{Html, IntoResponse},
     Json,
 };
+use futures::Stream;
 use rust_decimal::Decimal;
+use std::convert::Infallible;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::api::requests::{
-    CreateAccountRequest, CreateTransactionRequest, ListBatchesQuery, ListLedgerEntriesQuery,
-    ListTransactionsQuery, ProcessBatchRequest, ReverseTransactionRequest,
+    AccountActivityQuery, AmendTransactionRequest, BalanceAsOfQuery, FeeReportQuery, BalanceHistoryQuery, BalanceExplainQuery, ListAccountingPeriodsQuery, AddCounterpartyRestrictionRequest, CreateGlPostingRunRequest, CreateInvoiceRunRequest, ExportGlJournalQuery, ExportInvoiceQuery, ExportNettingAdviceQuery, AdviceFormat, ListFundingObligationsQuery, ListInvoicesQuery, SetInvoiceContractRequest,
+    JournalFormat, ListGlPostingRunsQuery, AnonymizeAccountRequest, AssignTransactionWindowRequest, PositionTransactionsQuery, ReassignTransactionBatchRequest, CreateAccountRequest,
+    CreateAlertRuleRequest, CreateDeliveryRequest, DeclareDefaultRequest, CreateSettlementWindowRequest, CreateTransactionRequest,
+    DeliverStatementRequest, DeliverySource, ExportFormat, FeeRefundRequest, ExportInstructionsQuery, ListAlertRulesQuery, ListBalanceBreaksQuery,
+    ListBalanceIncidentsQuery, ResolveBalanceIncidentRequest, SetBalanceFloorRequest, SetInstructionStrategyRequest, SetNetDebitCapRequest, SetNettingModeRequest,
+    CaptureReservationRequest, CloseBatchEarlyRequest, CreateBatchTemplateRequest, CreateReservationRequest, ListBatchTemplatesQuery, ProvisionBatchesRequest, ListReservationsQuery, MoveCutOffRequest,
+    BatchProgressStreamQuery, ListBatchesQuery, ListDeliveriesQuery, ListLedgerEntriesQuery, ListSettlementWindowsQuery,
+    ExternalIdLookupQuery, IntradayLiquidityQuery, ListTransactionsQuery, NettingReportQuery, ProcessBatchRequest, ReverseTransactionRequest,
+    ListRiskHoldsQuery, ProjectedBalanceQuery, ReportFormat, ReviewRiskHoldRequest, RoutingReportQuery, SetMetadataSchemaRequest, SetJobIntervalRequest, SetSettlementProfileRequest, SetTransactionTypeRequest, AddAccountIdentifierRequest, ListInstrumentsQuery, RegisterInstrumentRequest, SettleDvpRequest, CreateTransactionGroupRequest, CreatePvpRequest, ListPvpSettlementsQuery, CancelPvpRequest, RecordFxRateRequest, ListFxRatesQuery, CorrectFxRateRequest, CreateFxConversionRequest, RevalueFxRequest, ListFxRevaluationsQuery, AccountLookupQuery, InternalAccountProvisioningQuery, ListInternalAccountsQuery, StatementQuery, SyncQuery,
+    UpdateAlertRuleRequest, UpdateBatchTemplateRequest, UpdateSettlementWindowRequest, UpdateTransactionPriorityRequest,
 };
 use crate::api::responses::{
-    AccountResponse, ApiResponse, BalanceResponse, BatchResponse, ErrorResponse, HealthResponse,
-    LedgerEntryResponse, PaginatedResponse, ServiceHealth, TransactionResponse,
-    ValidationErrorDetail,
+    AccountingPeriodResponse, BalanceAsOfResponse, BalanceHistoryResponse, BalanceProjectionLagResponse, ProjectedBalanceResponse,
+    AccountActivityResponse, AccountAnonymizationResponse, AccountResponse, AccountStatusChangeResponse, AlertRuleResponse, ApiResponse,
+    BalanceBreakResponse, BalanceFloorResponse, BalanceIncidentResponse, NetDebitCapResponse, BalanceResponse, GlJournalResponse, GlPostingRunResponse,
+    AccountLedgerResponse, BatchProgressResponse, BatchReconciliationResponse, BatchResponse, CounterpartyAuditResponse, CounterpartyRestrictionResponse, ErrorResponse, ExternalIdLookupResponse,
+    FileDeliveryResponse, FinalityResponse, HealthResponse, IntradayLiquidityResponse,
+    MetadataSchemaResponse, NettingHistoryResponse, PaginatedResponse, ReservationResponse, RiskHoldResponse, RoutingReportResponse, ServiceHealth,
+    SettlementProfileResponse, SettlementWindowResponse, StatementDeliveryResponse, SubmissionResponse, SyncResponse,
+    DvpSettlementResponse, TransactionGroupResponse, TransactionResponse,
 };
+use crate::api::validation::ValidJson;
+use crate::core::job_control::{JobControl, JobStatus};
+use crate::core::leader::LeaderStatus;
 use crate::error::AppError;
-use crate::models::{BatchStatus, TransactionStatus};
+use crate::models::{AccountIdentifier, BalanceExplanation, BalanceReservation, InternalAccount, InternalAccountProvisioning, TransactionAmendment, BatchCutOffChange, BatchStatus, BatchTemplate, BilateralPairRecord, FeeSettlement, FundingObligation, FxConversion, FxRate, FxRateAdjustment, FxRevaluationReport, FxRevaluationRun, Instrument, Invoice, PvpSettlement, InvoiceContract, InvoiceDocument, NettingAdvice, ParticipantDefault, PositionContribution, ReplicationSlotStatus, SettlementProfile, TransactionStatus, TransactionType, TransactionTypeDefinition};
 use crate::services::{
-    AccountService, BalanceService, BatchService, LedgerService, LedgerTransactionRequest,
+    AccountService, AccountingPeriodService, ActivityService, AlertService, AmendmentService, BalanceGuardService, BalanceProjectionService, BalanceService, BatchService, BatchTemplateService, ChartOfAccountsService, CounterpartyService, CutOffApproval,
+    DefaultManagementService, DefaultReport, DeliveryService, DvpRequest, FeeSettlementService, FinalityService, FundingService, GlPostingService, InstructionExportService, InstrumentService, InvoiceContractTerms, InvoiceService, LedgerService, LedgerTransactionRequest, LiquidityReportService,
+    MetadataSchemaService, NettingAdviceService, NettingReport, NettingService, ProvisioningReport, PvpRequest, PvpService, FxConversionRequest, FxRateCorrection, FxRevaluationService, FxService, ReconciliationService, RiskService, RtgsService, SettlementWindowService, SignedAttestation,
+    StatementService, SubmissionService, SyncService, TransactionChanges, TransactionDryRun, TransactionGroupService, TransactionTimeline, TransactionTimelineService, TransactionTypeService,
 };
 
 use super::routes::AppState;
 
+/// Maps a service error to its HTTP status and error body. Server-side failures are
+/// logged with `context`; client errors are only returned.
+fn error_response(error: AppError, context: &str) -> (StatusCode, Json<ApiResponse<()>>) {
+    let status = error.status_code();
+    if status.is_server_error() {
+        tracing::error!("{}: {}", context, error);
+    }
+    (status, Json(ApiResponse::<()>::error(ErrorResponse::from(&error))))
+}
+
 /// Health check endpoint.
 pub async fn health_check(State(state): State<AppState>) -> Json<ApiResponse<HealthResponse>> {
     let db_healthy = sqlx::query("SELECT 1")
@@ -30,7 +64,7 @@ pub async fn health_check(State(state): State<AppState>) -> Json<ApiResponse<Hea
         .await
         .is_ok();
 
-    let redis_healthy = state.redis_client.get_multiplexed_async_connection().await.is_ok();
+    let redis_healthy = state.redis.ping().await.is_ok();
 
     let kafka_healthy = state.kafka_connected();
 
@@ -50,6 +84,14 @@ pub async fn health_check(State(state): State<AppState>) -> Json<ApiResponse<Hea
 
 /// Readiness check endpoint.
 pub async fn readiness_check(State(state): State<AppState>) -> StatusCode {
+    if let Some(health_checker) = &state.health_checker {
+        return if health_checker.is_ready().await {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+    }
+
     let db_healthy = sqlx::query("SELECT 1")
         .fetch_one(&state.pool)
         .await
@@ -84,6 +126,18 @@ pub async fn detailed_health_check(
     }
 }
 
+/// Dependency health endpoint with latency, rolling error rates, hard/soft classification
+/// and the readiness verdict.
+pub async fn dependency_health_check(
+    State(state): State<AppState>,
+) -> Json<ApiResponse<crate::observability::HealthDetails>> {
+    if let Some(health_checker) = &state.health_checker {
+        Json(ApiResponse::success(health_checker.details().await))
+    } else {
+        Json(ApiResponse::success(crate::observability::HealthDetails::unchecked()))
+    }
+}
+
 /// Prometheus metrics endpoint.
 pub async fn metrics_endpoint(State(state): State<AppState>) -> String {
     if let Some(handle) = &state.metrics_handle {
@@ -93,6 +147,25 @@ pub async fn metrics_endpoint(State(state): State<AppState>) -> String {
     }
 }
 
+// ============================================================================
+// GraphQL Handlers
+// ============================================================================
+
+/// Executes a GraphQL query against the read-only schema.
+pub async fn graphql(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(state.graphql.execute(request).await)
+}
+
+/// Serves the GraphQL playground for exploring the schema.
+pub async fn graphql_playground() -> Html<String> {
+    Html(async_graphql::http::playground_source(
+        async_graphql::http::GraphQLPlaygroundConfig::new("/graphql"),
+    ))
+}
+
 // ============================================================================
 // Account Handlers
 // ============================================================================
@@ -100,26 +173,8 @@ pub async fn metrics_endpoint(State(state): State<AppState>) -> String {
 /// Create a new account.
 pub async fn create_account(
     State(state): State<AppState>,
-    Json(request): Json<CreateAccountRequest>,
+    ValidJson(request): ValidJson<CreateAccountRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<AccountResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
-    if let Err(errors) = request.validate() {
-        let details: Vec<ValidationErrorDetail> = errors
-            .iter()
-            .map(|e| ValidationErrorDetail {
-                field: e.field.clone(),
-                message: e.message.clone(),
-            })
-            .collect();
-
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(
-                ErrorResponse::new("VALIDATION_ERROR", "Request validation failed")
-                    .with_details(details),
-            )),
-        ));
-    }
-
     let account_service = AccountService::new(state.pool.clone());
 
     let service_request = crate::services::account_service::CreateAccountRequest {
@@ -136,20 +191,7 @@ pub async fn create_account(
             StatusCode::CREATED,
             Json(ApiResponse::success(AccountResponse::from(account))),
         )),
-        Err(AppError::Validation(msg)) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("VALIDATION_ERROR", msg))),
-        )),
-        Err(e) => {
-            tracing::error!("Failed to create account: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+        Err(e) => Err(error_response(e, "Failed to create account")),
     }
 }
 
@@ -162,20 +204,7 @@ pub async fn get_account(
 
     match account_service.find_by_id(id).await {
         Ok(account) => Ok(Json(ApiResponse::success(AccountResponse::from(account)))),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
-        )),
-        Err(e) => {
-            tracing::error!("Failed to get account: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+        Err(e) => Err(error_response(e, "Failed to get account")),
     }
 }
 
@@ -189,446 +218,3090 @@ pub async fn get_account_balance(
 
     let account = match account_service.find_by_id(id).await {
         Ok(acc) => acc,
-        Err(AppError::NotFound(msg)) => return Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
-        )),
-        Err(e) => {
-            tracing::error!("Failed to get account for balance: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new("INTERNAL_ERROR", "An internal error occurred"))),
-            ));
-        }
+        Err(e) => return Err(error_response(e, "Failed to get account for balance")),
     };
 
     match balance_service.get_balance(id, &account.currency).await {
         Ok(balance) => Ok(Json(ApiResponse::success(BalanceResponse::from(balance)))),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
-        )),
-        Err(e) => {
-            tracing::error!("Failed to get balance: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+        Err(e) => Err(error_response(e, "Failed to get balance")),
     }
 }
 
-/// Get account ledger entries.
-pub async fn get_account_ledger(
+/// Get an account's balance at the end of a past day, by value or booking date.
+pub async fn get_account_balance_as_of(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-    Query(query): Query<ListLedgerEntriesQuery>,
-) -> Result<Json<ApiResponse<PaginatedResponse<LedgerEntryResponse>>>, (StatusCode, Json<ApiResponse<()>>)>
-{
-    let ledger_service = LedgerService::new(state.pool.clone());
-    let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.offset.unwrap_or(0);
+    Query(query): Query<BalanceAsOfQuery>,
+) -> Result<Json<ApiResponse<BalanceAsOfResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+    let account_service = AccountService::new(state.pool.clone());
 
-    let total = match ledger_service.count_account_ledger_entries(id).await {
-        Ok(count) => count,
-        Err(e) => {
-            tracing::error!("Failed to count ledger entries: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ));
-        }
+    let currency = match query.currency {
+        Some(currency) => currency,
+        None => match account_service.find_by_id(id).await {
+            Ok(account) => account.currency,
+            Err(e) => return Err(error_response(e, "Failed to get account for balance")),
+        },
     };
 
-    match ledger_service.get_account_ledger_entries(id, limit, offset).await {
-        Ok(entries) => {
-            let response_entries: Vec<LedgerEntryResponse> =
-                entries.iter().cloned().map(LedgerEntryResponse::from).collect();
-            Ok(Json(ApiResponse::success(PaginatedResponse::new(
-                response_entries,
-                total,
-                limit,
-                offset,
-            ))))
-        }
-        Err(e) => {
-            tracing::error!("Failed to get ledger entries: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+    match balance_service.balance_as_of(id, &currency, query.date, query.basis).await {
+        Ok(balance) => Ok(Json(ApiResponse::success(BalanceAsOfResponse {
+            account_id: id,
+            currency,
+            basis: query.basis,
+            date: balance.date,
+            balance: balance.balance,
+        }))),
+        Err(e) => Err(error_response(e, "Failed to get balance as of date")),
     }
 }
 
-// ============================================================================
-// Transaction Handlers
-// ============================================================================
+/// Get an account's balance from the event-sourced projection, with how far it is behind
+/// the ledger.
+pub async fn get_projected_balance(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ProjectedBalanceQuery>,
+) -> Result<Json<ApiResponse<ProjectedBalanceResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let projection_service = BalanceProjectionService::new(state.pool.clone());
+    let account_service = AccountService::new(state.pool.clone());
 
-/// Create a new transaction.
-pub async fn create_transaction(
+    let currency = match query.currency {
+        Some(currency) => currency,
+        None => match account_service.find_by_id(id).await {
+            Ok(account) => account.currency,
+            Err(e) => return Err(error_response(e, "Failed to get account for balance")),
+        },
+    };
+
+    let projection = match projection_service.get(id, &currency).await {
+        Ok(projection) => projection,
+        Err(e) => return Err(error_response(e, "Failed to get projected balance")),
+    };
+
+    match projection_service.lag(Some(id), Some(&currency)).await {
+        Ok(lag) => Ok(Json(ApiResponse::success(ProjectedBalanceResponse::new(
+            projection,
+            &lag,
+            chrono::Utc::now(),
+        )))),
+        Err(e) => Err(error_response(e, "Failed to get balance projection lag")),
+    }
+}
+
+/// Get how far the balance projection is behind the ledger.
+pub async fn get_balance_projection_lag(
     State(state): State<AppState>,
-    Json(request): Json<CreateTransactionRequest>,
-) -> Result<(StatusCode, Json<ApiResponse<TransactionResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
-    if let Err(errors) = request.validate() {
-        let details: Vec<ValidationErrorDetail> = errors
-            .iter()
-            .map(|e| ValidationErrorDetail {
-                field: e.field.clone(),
-                message: e.message.clone(),
-            })
-            .collect();
-
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(
-                ErrorResponse::new("VALIDATION_ERROR", "Request validation failed")
-                    .with_details(details),
-            )),
-        ));
+) -> Result<Json<ApiResponse<BalanceProjectionLagResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let projection_service = BalanceProjectionService::new(state.pool.clone());
+
+    match projection_service.lag(None, None).await {
+        Ok(lag) => Ok(Json(ApiResponse::success(BalanceProjectionLagResponse::new(
+            lag,
+            chrono::Utc::now(),
+        )))),
+        Err(e) => Err(error_response(e, "Failed to get balance projection lag")),
     }
+}
 
-    let ledger_service = LedgerService::new(state.pool.clone());
+/// Get a page of the account change feed: a snapshot without a token, then every
+/// account created or updated since the token was handed out.
+pub async fn sync_accounts(
+    State(state): State<AppState>,
+    Query(query): Query<SyncQuery>,
+) -> Result<Json<ApiResponse<SyncResponse<AccountResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let sync_service = SyncService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(500).clamp(1, 1000);
 
-    let ledger_request = LedgerTransactionRequest {
-        external_id: request.external_id,
-        transaction_type: request.transaction_type,
-        source_account_id: request.source_account_id,
-        destination_account_id: request.destination_account_id,
-        amount: request.amount,
-        currency: request.currency,
-        fee_amount: request.fee_amount.unwrap_or(Decimal::ZERO),
-        idempotency_key: request.idempotency_key,
-        effective_date: None,
-        metadata: request.metadata,
-        original_transaction_id: None,
+    match sync_service.accounts(query.token.as_deref(), limit).await {
+        Ok(page) => Ok(Json(ApiResponse::success(SyncResponse::new(page)))),
+        Err(e) => Err(error_response(e, "Failed to sync accounts")),
+    }
+}
+
+/// Get a page of the transaction change feed: a snapshot without a token, then every
+/// transaction created or updated since the token was handed out.
+pub async fn sync_transactions(
+    State(state): State<AppState>,
+    Query(query): Query<SyncQuery>,
+) -> Result<Json<ApiResponse<SyncResponse<TransactionResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let sync_service = SyncService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(500).clamp(1, 1000);
+
+    match sync_service.transactions(query.token.as_deref(), limit).await {
+        Ok(page) => Ok(Json(ApiResponse::success(SyncResponse::new(page)))),
+        Err(e) => Err(error_response(e, "Failed to sync transactions")),
+    }
+}
+
+/// Get the change data capture publisher's replication slot and how far it is behind.
+pub async fn get_cdc_status(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<ReplicationSlotStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let Some(cdc) = &state.cdc else {
+        return Err(error_response(
+            AppError::NotFound("Change data capture is not enabled".to_string()),
+            "Failed to get CDC status",
+        ));
     };
 
-    match ledger_service.process_transaction(ledger_request).await {
-        Ok(result) => Ok((
-            StatusCode::CREATED,
-            Json(ApiResponse::success(TransactionResponse::from(result.transaction))),
-        )),
-        Err(AppError::Validation(msg)) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("VALIDATION_ERROR", msg))),
-        )),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
+    match cdc.status().await {
+        Ok(status) => Ok(Json(ApiResponse::success(status))),
+        Err(e) => Err(error_response(e, "Failed to get CDC status")),
+    }
+}
+
+/// Get this instance's view of the leader election.
+pub async fn get_leader_status(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<LeaderStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match &state.leader {
+        Some(leader) => Ok(Json(ApiResponse::success(leader.status()))),
+        None => Err(error_response(
+            AppError::NotFound("Leader election is not enabled on this instance".to_string()),
+            "Failed to get leader status",
         )),
-        Err(e) => {
-            tracing::error!("Failed to create transaction: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
     }
 }
 
-/// Get transaction by ID.
-pub async fn get_transaction(
+fn job_control(state: &AppState, name: &str) -> Result<Arc<JobControl>, (StatusCode, Json<ApiResponse<()>>)> {
+    state.jobs.get(name).cloned().ok_or_else(|| {
+        error_response(
+            AppError::NotFound(format!("Job '{}' is not running on this instance", name)),
+            "Failed to get job",
+        )
+    })
+}
+
+/// List the background jobs running on this instance with their schedule and outcomes.
+pub async fn list_jobs(State(state): State<AppState>) -> Json<ApiResponse<Vec<JobStatus>>> {
+    Json(ApiResponse::success(state.jobs.values().map(|job| job.status()).collect()))
+}
+
+/// Get a background job's schedule and outcomes.
+pub async fn get_job(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
-    let ledger_service = LedgerService::new(state.pool.clone());
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let job = job_control(&state, &name)?;
+    Ok(Json(ApiResponse::success(job.status())))
+}
 
-    match ledger_service.get_transaction(id).await {
-        Ok(tx) => Ok(Json(ApiResponse::success(TransactionResponse::from(tx)))),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
-        )),
-        Err(e) => {
-            tracing::error!("Failed to get transaction: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
+/// Pause a background job's scheduled runs.
+pub async fn pause_job(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let job = job_control(&state, &name)?;
+    job.pause();
+    tracing::info!(job = %name, "Job paused");
+    Ok(Json(ApiResponse::success(job.status())))
+}
+
+/// Resume a paused background job.
+pub async fn resume_job(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let job = job_control(&state, &name)?;
+    job.resume();
+    tracing::info!(job = %name, "Job resumed");
+    Ok(Json(ApiResponse::success(job.status())))
+}
+
+/// Run a background job straight away, even while paused.
+pub async fn trigger_job(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let job = job_control(&state, &name)?;
+    match job.trigger() {
+        Ok(()) => {
+            tracing::info!(job = %name, "Job triggered");
+            Ok(Json(ApiResponse::success(job.status())))
         }
+        Err(e) => Err(error_response(e, "Failed to trigger job")),
     }
 }
 
-/// List transactions with filters.
-pub async fn list_transactions(
+/// Change how often a background job runs, until the next restart.
+pub async fn set_job_interval(
     State(state): State<AppState>,
-    Query(query): Query<ListTransactionsQuery>,
-) -> Result<Json<ApiResponse<PaginatedResponse<TransactionResponse>>>, (StatusCode, Json<ApiResponse<()>>)>
-{
-    let ledger_service = LedgerService::new(state.pool.clone());
-    let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.offset.unwrap_or(0);
+    Path(name): Path<String>,
+    ValidJson(request): ValidJson<SetJobIntervalRequest>,
+) -> Result<Json<ApiResponse<JobStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let job = job_control(&state, &name)?;
+    match job.set_interval(std::time::Duration::from_secs(request.interval_secs)) {
+        Ok(()) => {
+            tracing::info!(job = %name, interval_secs = request.interval_secs, "Job interval changed");
+            Ok(Json(ApiResponse::success(job.status())))
+        }
+        Err(e) => Err(error_response(e, "Failed to set job interval")),
+    }
+}
 
-    let status = match query.status.as_ref() {
-        Some(s) => match s.to_uppercase().as_str() {
-            "PENDING" => Some(TransactionStatus::Pending),
-            "SETTLED" => Some(TransactionStatus::Settled),
-            "FAILED" => Some(TransactionStatus::Failed),
-            "REVERSED" => Some(TransactionStatus::Reversed),
-            _ => return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "VALIDATION_ERROR",
-                    format!("Invalid status '{}'. Valid values: PENDING, SETTLED, FAILED, REVERSED", s),
-                ))),
-            )),
-        },
-        None => None,
-    };
+/// Get an account's closing balance for each day of a date range.
+pub async fn get_account_balance_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<BalanceHistoryQuery>,
+) -> Result<Json<ApiResponse<BalanceHistoryResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+    let account_service = AccountService::new(state.pool.clone());
 
-    let total = match ledger_service.count_transactions(query.account_id, status, query.currency.as_deref()).await {
-        Ok(count) => count,
-        Err(e) => {
-            tracing::error!("Failed to count transactions: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ));
-        }
+    let currency = match query.currency {
+        Some(currency) => currency,
+        None => match account_service.find_by_id(id).await {
+            Ok(account) => account.currency,
+            Err(e) => return Err(error_response(e, "Failed to get account for balance")),
+        },
     };
 
-    match ledger_service
-        .list_transactions(query.account_id, status, query.currency.as_deref(), limit, offset)
+    match balance_service
+        .balance_history(id, &currency, query.from, query.to, query.basis)
         .await
     {
-        Ok(transactions) => {
-            let response_txs: Vec<TransactionResponse> =
-                transactions.iter().cloned().map(TransactionResponse::from).collect();
-            Ok(Json(ApiResponse::success(PaginatedResponse::new(
-                response_txs,
-                total,
-                limit,
-                offset,
-            ))))
-        }
-        Err(e) => {
-            tracing::error!("Failed to list transactions: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+        Ok(balances) => Ok(Json(ApiResponse::success(BalanceHistoryResponse {
+            account_id: id,
+            currency,
+            basis: query.basis,
+            balances,
+        }))),
+        Err(e) => Err(error_response(e, "Failed to get balance history")),
     }
 }
 
-/// Reverse a transaction.
-pub async fn reverse_transaction(
+/// Explain an account's balance change over a period by category.
+pub async fn explain_account_balance(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-    Json(request): Json<ReverseTransactionRequest>,
-) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
-    if let Err(errors) = request.validate() {
-        let details: Vec<ValidationErrorDetail> = errors
-            .iter()
-            .map(|e| ValidationErrorDetail {
-                field: e.field.clone(),
-                message: e.message.clone(),
-            })
-            .collect();
-
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(
-                ErrorResponse::new("VALIDATION_ERROR", "Request validation failed")
-                    .with_details(details),
-            )),
-        ));
-    }
+    Query(query): Query<BalanceExplainQuery>,
+) -> Result<Json<ApiResponse<BalanceExplanation>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+    let account_service = AccountService::new(state.pool.clone());
 
-    let ledger_service = LedgerService::new(state.pool.clone());
+    let currency = match query.currency {
+        Some(currency) => currency,
+        None => match account_service.find_by_id(id).await {
+            Ok(account) => account.currency,
+            Err(e) => return Err(error_response(e, "Failed to get account for balance")),
+        },
+    };
 
-    match ledger_service
-        .reverse_transaction(id, &request.reason, &request.idempotency_key)
+    match balance_service
+        .explain_balance_change(id, &currency, query.from, query.to, query.basis)
         .await
     {
-        Ok(result) => Ok(Json(ApiResponse::success(TransactionResponse::from(
-            result.transaction,
-        )))),
-        Err(AppError::Validation(msg)) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("VALIDATION_ERROR", msg))),
-        )),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
-        )),
-        Err(e) => {
-            tracing::error!("Failed to reverse transaction: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+        Ok(explanation) => Ok(Json(ApiResponse::success(explanation))),
+        Err(e) => Err(error_response(e, "Failed to explain balance change")),
     }
 }
 
-// ============================================================================
-// Batch Handlers
-// ============================================================================
-
-/// List batches with filters.
-pub async fn list_batches(
+/// Get account ledger entries, newest first, with running balance checks.
+pub async fn get_account_ledger(
     State(state): State<AppState>,
-    Query(query): Query<ListBatchesQuery>,
-) -> Result<Json<ApiResponse<PaginatedResponse<BatchResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
-    let batch_service = BatchService::new(state.pool.clone());
+    Path(id): Path<Uuid>,
+    Query(query): Query<ListLedgerEntriesQuery>,
+) -> Result<Json<ApiResponse<AccountLedgerResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let ledger_service = LedgerService::new(state.pool.clone());
     let limit = query.limit.unwrap_or(50).min(100);
     let offset = query.offset.unwrap_or(0);
 
-    let status = query.status.as_ref().and_then(|s| match s.to_uppercase().as_str() {
-        "PENDING" => Some(BatchStatus::Pending),
-        "PROCESSING" => Some(BatchStatus::Processing),
-        "COMPLETED" => Some(BatchStatus::Completed),
-        "FAILED" => Some(BatchStatus::Failed),
-        _ => None,
-    });
-
-    match batch_service
-        .list_batches(status, query.currency.as_deref(), limit, offset)
+    match ledger_service
+        .get_account_history(id, query.from, query.to, limit, offset)
         .await
     {
-        Ok(batches) => {
-            let response_batches: Vec<BatchResponse> =
-                batches.iter().cloned().map(BatchResponse::from).collect();
-            let total = response_batches.len() as i64;
-            Ok(Json(ApiResponse::success(PaginatedResponse::new(
-                response_batches,
-                total,
-                limit,
-                offset,
-            ))))
-        }
-        Err(e) => {
-            tracing::error!("Failed to list batches: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+        Ok(history) => Ok(Json(ApiResponse::success(AccountLedgerResponse::new(history, limit, offset)))),
+        Err(e) => Err(error_response(e, "Failed to get ledger entries")),
     }
 }
 
-/// Get batch by ID.
-pub async fn get_batch(
+/// Get an account's status change history, oldest first.
+pub async fn get_account_status_history(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
-    let batch_service = BatchService::new(state.pool.clone());
+) -> Result<Json<ApiResponse<Vec<AccountStatusChangeResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
 
-    match batch_service.get_batch(id).await {
-        Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
-        )),
-        Err(e) => {
-            tracing::error!("Failed to get batch: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+    match account_service.get_status_history(id).await {
+        Ok(history) => Ok(Json(ApiResponse::success(
+            history.into_iter().map(AccountStatusChangeResponse::from).collect(),
+        ))),
+        Err(e) => Err(error_response(e, "Failed to get account status history")),
     }
 }
 
+/// Irreversibly scrub a closed account's personal data.
+pub async fn anonymize_account(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<AnonymizeAccountRequest>,
+) -> Result<Json<ApiResponse<AccountResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service
+        .anonymize(id, &request.requested_by, &request.reason)
+        .await
+    {
+        Ok(account) => Ok(Json(ApiResponse::success(AccountResponse::from(account)))),
+        Err(e) => Err(error_response(e, "Failed to anonymize account")),
+    }
+}
+
+/// Get the audit record of an account's anonymization.
+pub async fn get_account_anonymization(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<AccountAnonymizationResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service.get_anonymization(id).await {
+        Ok(anonymization) => Ok(Json(ApiResponse::success(AccountAnonymizationResponse::from(anonymization)))),
+        Err(e) => Err(error_response(e, "Failed to get account anonymization")),
+    }
+}
+
+/// Set the bank details an account settles to in a currency.
+pub async fn set_settlement_profile(
+    State(state): State<AppState>,
+    Path((id, currency)): Path<(Uuid, String)>,
+    ValidJson(request): ValidJson<SetSettlementProfileRequest>,
+) -> Result<Json<ApiResponse<SettlementProfileResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+    let identifier = |value: String| value.split_whitespace().collect::<String>().to_uppercase();
+    let now = chrono::Utc::now();
+    let profile = SettlementProfile {
+        account_id: id,
+        currency: currency.to_uppercase(),
+        account_name: request.account_name,
+        routing_number: request.routing_number,
+        bank_account_number: request.bank_account_number,
+        bank_account_type: request.bank_account_type,
+        bic: request.bic.map(identifier),
+        iban: request.iban.map(identifier),
+        rail: request.rail,
+        cut_off_time: request.cut_off_time,
+        created_at: now,
+        updated_at: now,
+    };
+
+    match account_service.set_settlement_profile(profile).await {
+        Ok(profile) => Ok(Json(ApiResponse::success(SettlementProfileResponse::from(profile)))),
+        Err(e) => Err(error_response(e, "Failed to set settlement profile")),
+    }
+}
+
+/// Get the bank details an account settles to in a currency.
+pub async fn get_settlement_profile(
+    State(state): State<AppState>,
+    Path((id, currency)): Path<(Uuid, String)>,
+) -> Result<Json<ApiResponse<SettlementProfileResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service.get_settlement_profile(id, &currency.to_uppercase()).await {
+        Ok(profile) => Ok(Json(ApiResponse::success(SettlementProfileResponse::from(profile)))),
+        Err(e) => Err(error_response(e, "Failed to get settlement profile")),
+    }
+}
+
+/// List an account's settlement profiles, one per currency.
+pub async fn list_settlement_profiles(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<SettlementProfileResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service.list_settlement_profiles(id).await {
+        Ok(profiles) => Ok(Json(ApiResponse::success(
+            profiles.into_iter().map(SettlementProfileResponse::from).collect(),
+        ))),
+        Err(e) => Err(error_response(e, "Failed to list settlement profiles")),
+    }
+}
+
+/// Remove an account's settlement profile for a currency.
+pub async fn delete_settlement_profile(
+    State(state): State<AppState>,
+    Path((id, currency)): Path<(Uuid, String)>,
+) -> Result<StatusCode, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service.delete_settlement_profile(id, &currency.to_uppercase()).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(error_response(e, "Failed to delete settlement profile")),
+    }
+}
+
+/// Assign a secondary identifier to an account.
+pub async fn add_account_identifier(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<AddAccountIdentifierRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<AccountIdentifier>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service
+        .add_identifier(id, request.identifier_type, &request.identifier)
+        .await
+    {
+        Ok(identifier) => Ok((StatusCode::CREATED, Json(ApiResponse::success(identifier)))),
+        Err(e) => Err(error_response(e, "Failed to add account identifier")),
+    }
+}
+
+/// List an account's secondary identifiers.
+pub async fn list_account_identifiers(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<AccountIdentifier>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service.list_identifiers(id).await {
+        Ok(identifiers) => Ok(Json(ApiResponse::success(identifiers))),
+        Err(e) => Err(error_response(e, "Failed to list account identifiers")),
+    }
+}
+
+/// Remove one of an account's secondary identifiers.
+pub async fn remove_account_identifier(
+    State(state): State<AppState>,
+    Path((id, identifier_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service.remove_identifier(id, identifier_id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(error_response(e, "Failed to remove account identifier")),
+    }
+}
+
+/// Find the account a secondary identifier is assigned to.
+pub async fn lookup_account(
+    State(state): State<AppState>,
+    Query(query): Query<AccountLookupQuery>,
+) -> Result<Json<ApiResponse<AccountResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let account_service = AccountService::new(state.pool.clone());
+
+    match account_service.lookup(&query.identifier).await {
+        Ok(account) => Ok(Json(ApiResponse::success(AccountResponse::from(account)))),
+        Err(e) => Err(error_response(e, "Failed to look up account")),
+    }
+}
+
+/// List the chart of internal accounts, optionally in one currency.
+pub async fn list_internal_accounts(
+    State(state): State<AppState>,
+    Query(query): Query<ListInternalAccountsQuery>,
+) -> Result<Json<ApiResponse<Vec<InternalAccount>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let chart_service = ChartOfAccountsService::new(state.pool.clone());
+
+    match chart_service.list(query.currency.as_deref()).await {
+        Ok(accounts) => Ok(Json(ApiResponse::success(accounts))),
+        Err(e) => Err(error_response(e, "Failed to list internal accounts")),
+    }
+}
+
+/// List the internal accounts provisioned at startup or on a currency's first use,
+/// newest first.
+pub async fn list_internal_account_provisioning(
+    State(state): State<AppState>,
+    Query(query): Query<InternalAccountProvisioningQuery>,
+) -> Result<Json<ApiResponse<Vec<InternalAccountProvisioning>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let chart_service = ChartOfAccountsService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+
+    match chart_service.provisioning_history(query.currency.as_deref(), limit).await {
+        Ok(records) => Ok(Json(ApiResponse::success(records))),
+        Err(e) => Err(error_response(e, "Failed to list internal account provisioning")),
+    }
+}
+
+/// Get an internal account by code and currency.
+pub async fn get_internal_account(
+    State(state): State<AppState>,
+    Path((code, currency)): Path<(String, String)>,
+) -> Result<Json<ApiResponse<InternalAccount>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let chart_service = ChartOfAccountsService::new(state.pool.clone());
+
+    match chart_service.get(&code, &currency).await {
+        Ok(account) => Ok(Json(ApiResponse::success(account))),
+        Err(e) => Err(error_response(e, "Failed to get internal account")),
+    }
+}
+
+/// Download an account statement as camt.053 (end of day) or camt.052 (intraday) XML.
+pub async fn get_account_statement(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<StatementQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    let statement_service = StatementService::new(state.pool.clone());
+    let date = query.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+
+    match statement_service
+        .generate(id, query.statement_type, date, query.currency.as_deref())
+        .await
+    {
+        Ok(statement) => Ok((
+            [
+                (header::CONTENT_TYPE, "application/xml".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}.xml\"", statement.message_id),
+                ),
+            ],
+            statement.to_xml(),
+        )),
+        Err(e) => Err(error_response(e, "Failed to generate account statement")),
+    }
+}
+
+/// Get an account's settled activity summarised by day, week or month.
+pub async fn get_account_activity(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AccountActivityQuery>,
+) -> Result<Json<ApiResponse<AccountActivityResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let activity_service = ActivityService::new(state.pool.clone());
+
+    match activity_service
+        .activity(id, query.granularity, query.currency.as_deref(), query.from, query.to)
+        .await
+    {
+        Ok(periods) => Ok(Json(ApiResponse::success(AccountActivityResponse {
+            account_id: id,
+            granularity: query.granularity,
+            periods: periods.into_iter().map(Into::into).collect(),
+        }))),
+        Err(e) => Err(error_response(e, "Failed to get account activity")),
+    }
+}
+
+/// Generate an account statement and push it to a webhook.
+pub async fn deliver_account_statement(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<DeliverStatementRequest>,
+) -> Result<Json<ApiResponse<StatementDeliveryResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let statement_service = StatementService::new(state.pool.clone());
+    let date = request.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+
+    let result: Result<_, AppError> = async {
+        let statement = statement_service
+            .generate(id, request.statement_type, date, request.currency.as_deref())
+            .await?;
+        statement_service
+            .deliver_webhook(&statement, &request.webhook_url)
+            .await?;
+        Ok(statement)
+    }
+    .await;
+
+    match result {
+        Ok(statement) => Ok(Json(ApiResponse::success(StatementDeliveryResponse::new(
+            &statement,
+            request.webhook_url,
+        )))),
+        Err(e) => Err(error_response(e, "Failed to deliver account statement")),
+    }
+}
+
+/// List an account's counterparty allow/deny restrictions.
+pub async fn list_counterparty_restrictions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<CounterpartyRestrictionResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let counterparty_service = CounterpartyService::new(state.pool.clone());
+
+    match counterparty_service.list_restrictions(id).await {
+        Ok(restrictions) => Ok(Json(ApiResponse::success(
+            restrictions.into_iter().map(CounterpartyRestrictionResponse::from).collect(),
+        ))),
+        Err(e) => Err(error_response(e, "Failed to list counterparty restrictions")),
+    }
+}
+
+/// Allow or deny a counterparty for an account, replacing any existing entry for it.
+pub async fn add_counterparty_restriction(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<AddCounterpartyRestrictionRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<CounterpartyRestrictionResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let counterparty_service = CounterpartyService::new(state.pool.clone());
+
+    match counterparty_service
+        .add_restriction(id, request.counterparty_id, request.mode, request.reason)
+        .await
+    {
+        Ok(restriction) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(CounterpartyRestrictionResponse::from(restriction))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to add counterparty restriction")),
+    }
+}
+
+/// Remove an account's restriction on a counterparty.
+pub async fn remove_counterparty_restriction(
+    State(state): State<AppState>,
+    Path((id, counterparty_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, (StatusCode, Json<ApiResponse<()>>)> {
+    let counterparty_service = CounterpartyService::new(state.pool.clone());
+
+    match counterparty_service.remove_restriction(id, counterparty_id).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(error_response(e, "Failed to remove counterparty restriction")),
+    }
+}
+
+/// Get the audit log of counterparty restriction changes for an account.
+pub async fn get_counterparty_audit(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<CounterpartyAuditResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let counterparty_service = CounterpartyService::new(state.pool.clone());
+
+    match counterparty_service.get_audit_log(id).await {
+        Ok(entries) => Ok(Json(ApiResponse::success(
+            entries.into_iter().map(CounterpartyAuditResponse::from).collect(),
+        ))),
+        Err(e) => Err(error_response(e, "Failed to get counterparty audit log")),
+    }
+}
+
+// ============================================================================
+// Transaction Handlers
+// ============================================================================
+
+/// Create a new transaction.
+pub async fn create_transaction(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateTransactionRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TransactionResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    match ledger_service(&state).process_transaction(ledger_request(request)).await {
+        Ok(result) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(TransactionResponse::from(result.transaction))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to create transaction")),
+    }
+}
+
+/// Previews a transaction: runs every check posting it would make and returns the
+/// projected ledger entries, fee and balances without writing anything.
+pub async fn dry_run_transaction(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateTransactionRequest>,
+) -> Result<Json<ApiResponse<TransactionDryRun>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match ledger_service(&state).dry_run(&ledger_request(request)).await {
+        Ok(dry_run) => Ok(Json(ApiResponse::success(dry_run))),
+        Err(e) => Err(error_response(e, "Failed to dry-run transaction")),
+    }
+}
+
+/// Settles a group of transactions in one database transaction: all of them post or
+/// none does. Resubmitting the group's idempotency key returns the settled group.
+pub async fn create_transaction_group(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateTransactionGroupRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TransactionGroupResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let group_service = TransactionGroupService::new(state.pool.clone(), Arc::new(ledger_service(&state)));
+    let requests = request.transactions.into_iter().map(ledger_request).collect();
+
+    match group_service.submit(&request.idempotency_key, requests).await {
+        Ok(detail) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(TransactionGroupResponse::from(detail))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to settle transaction group")),
+    }
+}
+
+/// Get a transaction group's status and the transactions it settled.
+pub async fn get_transaction_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TransactionGroupResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let group_service = TransactionGroupService::new(state.pool.clone(), Arc::new(ledger_service(&state)));
+
+    match group_service.get(id).await {
+        Ok(detail) => Ok(Json(ApiResponse::success(TransactionGroupResponse::from(detail)))),
+        Err(e) => Err(error_response(e, "Failed to get transaction group")),
+    }
+}
+
+/// Get a transaction group by its idempotency key, e.g. after a submission failed.
+pub async fn get_transaction_group_by_key(
+    State(state): State<AppState>,
+    Path(idempotency_key): Path<String>,
+) -> Result<Json<ApiResponse<TransactionGroupResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let group_service = TransactionGroupService::new(state.pool.clone(), Arc::new(ledger_service(&state)));
+
+    match group_service.get_by_idempotency_key(idempotency_key.trim()).await {
+        Ok(detail) => Ok(Json(ApiResponse::success(TransactionGroupResponse::from(detail)))),
+        Err(e) => Err(error_response(e, "Failed to get transaction group")),
+    }
+}
+
+fn fx_service(state: &AppState) -> FxService {
+    FxService::new(state.pool.clone()).with_rounding(state.rounding.clone())
+}
+
+/// Record an FX rate received from its source.
+pub async fn record_fx_rate(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<RecordFxRateRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<FxRate>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let rate = FxRate::new(
+        request.base_currency,
+        request.quote_currency,
+        request.rate,
+        request.spread.unwrap_or(Decimal::ZERO),
+        request.source,
+        request.effective_at.unwrap_or_else(chrono::Utc::now),
+    );
+
+    match fx_service(&state).record_rate(rate).await {
+        Ok(rate) => Ok((StatusCode::CREATED, Json(ApiResponse::success(rate)))),
+        Err(e) => Err(error_response(e, "Failed to record FX rate")),
+    }
+}
+
+/// List stored FX rates for audit, optionally of one pair and effective on one date.
+pub async fn list_fx_rates(
+    State(state): State<AppState>,
+    Query(query): Query<ListFxRatesQuery>,
+) -> Result<Json<ApiResponse<Vec<FxRate>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match fx_service(&state).list_rates(query.pair.as_deref(), query.date).await {
+        Ok(rates) => Ok(Json(ApiResponse::success(rates))),
+        Err(e) => Err(error_response(e, "Failed to list FX rates")),
+    }
+}
+
+/// Get a stored FX rate.
+pub async fn get_fx_rate(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<FxRate>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match fx_service(&state).get_rate(id).await {
+        Ok(rate) => Ok(Json(ApiResponse::success(rate))),
+        Err(e) => Err(error_response(e, "Failed to get FX rate")),
+    }
+}
+
+/// Correct a stored FX rate with effect from the same time, revaluing the conversions
+/// made at it.
+pub async fn correct_fx_rate(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<CorrectFxRateRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<FxRateCorrection>>), (StatusCode, Json<ApiResponse<()>>)> {
+    match fx_service(&state)
+        .correct_rate(id, request.rate, request.spread, request.source, &request.reason)
+        .await
+    {
+        Ok(correction) => Ok((StatusCode::CREATED, Json(ApiResponse::success(correction)))),
+        Err(e) => Err(error_response(e, "Failed to correct FX rate")),
+    }
+}
+
+/// Convert funds between accounts in two currencies at the current rate.
+pub async fn create_fx_conversion(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateFxConversionRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<FxConversion>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let request = FxConversionRequest {
+        external_id: request.external_id,
+        idempotency_key: request.idempotency_key,
+        source_account_id: request.source_account_id,
+        destination_account_id: request.destination_account_id,
+        amount: request.amount,
+        source_currency: request.source_currency,
+        destination_currency: request.destination_currency,
+    };
+
+    match fx_service(&state).convert(request).await {
+        Ok(conversion) => Ok((StatusCode::CREATED, Json(ApiResponse::success(conversion)))),
+        Err(e) => Err(error_response(e, "Failed to convert funds")),
+    }
+}
+
+/// Get an FX conversion with the ID of the rate it was made at.
+pub async fn get_fx_conversion(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<FxConversion>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match fx_service(&state).get_conversion(id).await {
+        Ok(conversion) => Ok(Json(ApiResponse::success(conversion))),
+        Err(e) => Err(error_response(e, "Failed to get FX conversion")),
+    }
+}
+
+/// List the revaluation adjustments posted to an FX conversion by rate corrections.
+pub async fn list_fx_conversion_adjustments(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<FxRateAdjustment>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let service = fx_service(&state);
+    let result = async {
+        service.get_conversion(id).await?;
+        service.conversion_adjustments(id).await
+    };
+
+    match result.await {
+        Ok(adjustments) => Ok(Json(ApiResponse::success(adjustments))),
+        Err(e) => Err(error_response(e, "Failed to list FX conversion adjustments")),
+    }
+}
+
+/// Revalue foreign-currency balances at the end of a period, or return the report of
+/// the period's run if it was already revalued.
+pub async fn revalue_fx(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<RevalueFxRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<FxRevaluationReport>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let service = FxRevaluationService::new(state.pool.clone())
+        .with_config(state.fx_revaluation.clone())
+        .with_rounding(state.rounding.clone());
+    let result = match request.period_end {
+        Some(period_end) => service.revalue(period_end).await,
+        None => service.revalue_due().await,
+    };
+
+    match result {
+        Ok(report) => Ok((StatusCode::CREATED, Json(ApiResponse::success(report)))),
+        Err(e) => Err(error_response(e, "Failed to revalue FX balances")),
+    }
+}
+
+/// List FX revaluation runs, latest period first.
+pub async fn list_fx_revaluations(
+    State(state): State<AppState>,
+    Query(query): Query<ListFxRevaluationsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<FxRevaluationRun>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    match FxRevaluationService::new(state.pool.clone()).list_runs(limit, offset).await {
+        Ok((runs, total)) => Ok(Json(ApiResponse::success(PaginatedResponse::new(runs, total, limit, offset)))),
+        Err(e) => Err(error_response(e, "Failed to list FX revaluations")),
+    }
+}
+
+/// Get the report of an FX revaluation run: each balance marked and the gain or loss
+/// posted for it.
+pub async fn get_fx_revaluation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<FxRevaluationReport>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match FxRevaluationService::new(state.pool.clone()).get_report(id).await {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => Err(error_response(e, "Failed to get FX revaluation")),
+    }
+}
+
+/// PvP service settling through the ledger configured in the state.
+fn pvp_service(state: &AppState) -> PvpService {
+    PvpService::new(state.pool.clone(), Arc::new(ledger_service(state))).with_timeout(state.pvp_timeout)
+}
+
+/// Accepts a payment-versus-payment settlement. It settles at once if both payers are
+/// funded, and otherwise waits for funding until its deadline.
+pub async fn create_pvp_settlement(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreatePvpRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<PvpSettlement>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let request = PvpRequest {
+        external_id: request.external_id,
+        idempotency_key: request.idempotency_key,
+        first_source_account_id: request.first_source_account_id,
+        first_destination_account_id: request.first_destination_account_id,
+        first_amount: request.first_amount,
+        first_currency: request.first_currency,
+        second_source_account_id: request.second_source_account_id,
+        second_destination_account_id: request.second_destination_account_id,
+        second_amount: request.second_amount,
+        second_currency: request.second_currency,
+        timeout_secs: request.timeout_secs,
+    };
+
+    match pvp_service(&state).create(request).await {
+        Ok(pvp) => Ok((StatusCode::CREATED, Json(ApiResponse::success(pvp)))),
+        Err(e) => Err(error_response(e, "Failed to create PvP settlement")),
+    }
+}
+
+/// List PvP settlements, newest first.
+pub async fn list_pvp_settlements(
+    State(state): State<AppState>,
+    Query(query): Query<ListPvpSettlementsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<PvpSettlement>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    match pvp_service(&state).list(query.status, limit, offset).await {
+        Ok((settlements, total)) => Ok(Json(ApiResponse::success(PaginatedResponse::new(settlements, total, limit, offset)))),
+        Err(e) => Err(error_response(e, "Failed to list PvP settlements")),
+    }
+}
+
+/// Get a PvP settlement.
+pub async fn get_pvp_settlement(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<PvpSettlement>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match pvp_service(&state).get(id).await {
+        Ok(pvp) => Ok(Json(ApiResponse::success(pvp))),
+        Err(e) => Err(error_response(e, "Failed to get PvP settlement")),
+    }
+}
+
+/// Check a PvP settlement's funding now rather than at the next sweep, settling it if
+/// both payers are funded.
+pub async fn attempt_pvp_settlement(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<PvpSettlement>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match pvp_service(&state).attempt(id).await {
+        Ok(pvp) => Ok(Json(ApiResponse::success(pvp))),
+        Err(e) => Err(error_response(e, "Failed to settle PvP settlement")),
+    }
+}
+
+/// Cancel a PvP settlement awaiting funding; neither payment is made.
+pub async fn cancel_pvp_settlement(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<CancelPvpRequest>,
+) -> Result<Json<ApiResponse<PvpSettlement>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match pvp_service(&state).cancel(id, &request.reason).await {
+        Ok(pvp) => Ok(Json(ApiResponse::success(pvp))),
+        Err(e) => Err(error_response(e, "Failed to cancel PvP settlement")),
+    }
+}
+
+/// Settles a securities trade delivery-versus-payment, posting the securities delivery
+/// and the cash payment together or not at all.
+pub async fn settle_dvp(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<SettleDvpRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<DvpSettlementResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let request = DvpRequest {
+        external_id: request.external_id,
+        instrument: request.instrument,
+        quantity: request.quantity,
+        seller_securities_account_id: request.seller_securities_account_id,
+        buyer_securities_account_id: request.buyer_securities_account_id,
+        amount: request.amount,
+        currency: request.currency,
+        buyer_cash_account_id: request.buyer_cash_account_id,
+        seller_cash_account_id: request.seller_cash_account_id,
+        idempotency_key: request.idempotency_key,
+        reference: request.reference,
+    };
+
+    match ledger_service(&state).settle_dvp(request).await {
+        Ok(settlement) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(DvpSettlementResponse {
+                link_id: settlement.link_id,
+                securities: TransactionResponse::from(settlement.securities.transaction),
+                cash: TransactionResponse::from(settlement.cash.transaction),
+            })),
+        )),
+        Err(e) => Err(error_response(e, "Failed to settle DvP trade")),
+    }
+}
+
+/// Ledger service posting transactions through the lanes and checks configured in the state.
+fn ledger_service(state: &AppState) -> LedgerService {
+    let mut ledger_service = LedgerService::new(state.pool.clone())
+        .with_fees(state.fees.clone())
+        .with_external_ids(state.external_ids)
+        .with_amount_limits(state.amount_limits.clone());
+    if let Some(engine) = &state.notification_engine {
+        ledger_service = ledger_service.with_notifications(engine.clone());
+    }
+    if let Some(rtgs) = &state.rtgs {
+        ledger_service = ledger_service.with_rtgs(rtgs.clone());
+    }
+    if let Some(batching) = &state.batching {
+        ledger_service = ledger_service.with_batching(batching.clone());
+    }
+    if let Some(risk) = &state.risk {
+        ledger_service = ledger_service.with_risk(risk.clone());
+    }
+    if let Some(combiner) = &state.write_combiner {
+        ledger_service = ledger_service.with_write_combining(combiner.clone());
+    }
+    if let Some(chart) = &state.chart {
+        ledger_service = ledger_service.with_chart_of_accounts(chart.clone());
+    }
+    ledger_service
+}
+
+fn ledger_request(request: CreateTransactionRequest) -> LedgerTransactionRequest {
+    LedgerTransactionRequest {
+        external_id: request.external_id,
+        transaction_type: request.transaction_type,
+        source_account_id: request.source_account_id,
+        destination_account_id: request.destination_account_id,
+        amount: request.amount,
+        currency: request.currency,
+        fee_amount: request.fee_amount.unwrap_or(Decimal::ZERO),
+        idempotency_key: request.idempotency_key,
+        effective_date: request.effective_date,
+        metadata: request.metadata,
+        original_transaction_id: None,
+        priority: request.priority.unwrap_or_default(),
+        source_system: request.source_system,
+        type_code: request.type_code,
+        exclude_from_netting: request.exclude_from_netting,
+        duplicate_override: request.duplicate_override,
+        narrative: request.narrative,
+        reference: request.reference,
+    }
+}
+
+/// Queue a transaction for asynchronous settlement and return a tracking id.
+pub async fn submit_transaction(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateTransactionRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<SubmissionResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let submissions = submission_service(&state).map_err(|e| error_response(e, "Failed to submit transaction"))?;
+
+    match submissions.submit(ledger_request(request)).await {
+        Ok(submission) => Ok((
+            StatusCode::ACCEPTED,
+            Json(ApiResponse::success(SubmissionResponse::from(submission))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to submit transaction")),
+    }
+}
+
+/// Get the status of an asynchronously submitted transaction.
+pub async fn get_submission(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<SubmissionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let submissions = submission_service(&state).map_err(|e| error_response(e, "Failed to get submission"))?;
+
+    match submissions.get_submission(id).await {
+        Ok(submission) => Ok(Json(ApiResponse::success(SubmissionResponse::from(submission)))),
+        Err(e) => Err(error_response(e, "Failed to get submission")),
+    }
+}
+
+fn submission_service(state: &AppState) -> Result<&std::sync::Arc<SubmissionService>, AppError> {
+    state
+        .submissions
+        .as_ref()
+        .ok_or_else(|| AppError::Unavailable("Asynchronous submission is not enabled".to_string()))
+}
+
+/// Get transaction by ID.
+pub async fn get_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let ledger_service = LedgerService::new(state.pool.clone());
+
+    match ledger_service.get_transaction(id).await {
+        Ok(tx) => Ok(Json(ApiResponse::success(TransactionResponse::from(tx)))),
+        Err(e) => Err(error_response(e, "Failed to get transaction")),
+    }
+}
+
+/// Find our transactions from an upstream reference.
+pub async fn get_transactions_by_external_id(
+    State(state): State<AppState>,
+    Path(external_id): Path<String>,
+    Query(query): Query<ExternalIdLookupQuery>,
+) -> Result<Json<ApiResponse<ExternalIdLookupResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let ledger_service = LedgerService::new(state.pool.clone());
+
+    let transactions = match ledger_service
+        .find_by_external_id(&external_id, query.source_system.as_deref())
+        .await
+    {
+        Ok(transactions) => transactions,
+        Err(e) => return Err(error_response(e, "Failed to look up transactions")),
+    };
+    let returns = if query.include_returns {
+        match ledger_service.find_returns(&transactions).await {
+            Ok(returns) => returns,
+            Err(e) => return Err(error_response(e, "Failed to look up returns")),
+        }
+    } else {
+        Vec::new()
+    };
+
+    Ok(Json(ApiResponse::success(ExternalIdLookupResponse {
+        external_id,
+        transactions: transactions.into_iter().map(TransactionResponse::from).collect(),
+        returns: returns.into_iter().map(TransactionResponse::from).collect(),
+    })))
+}
+
+/// List transactions with filters.
+pub async fn list_transactions(
+    State(state): State<AppState>,
+    Query(query): Query<ListTransactionsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<TransactionResponse>>>, (StatusCode, Json<ApiResponse<()>>)>
+{
+    let ledger_service = LedgerService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let status = match query.status.as_ref() {
+        Some(s) => match s.to_uppercase().as_str() {
+            "PENDING" => Some(TransactionStatus::Pending),
+            "SETTLED" => Some(TransactionStatus::Settled),
+            "FAILED" => Some(TransactionStatus::Failed),
+            "REVERSED" => Some(TransactionStatus::Reversed),
+            "EXPIRED" => Some(TransactionStatus::Expired),
+            _ => return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(ErrorResponse::new(
+                    "VALIDATION_ERROR",
+                    format!("Invalid status '{}'. Valid values: PENDING, SETTLED, FAILED, REVERSED, EXPIRED", s),
+                ))),
+            )),
+        },
+        None => None,
+    };
+
+    let total = match ledger_service.count_transactions(query.account_id, status, query.currency.as_deref()).await {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count transactions")),
+    };
+
+    match ledger_service
+        .list_transactions(query.account_id, status, query.currency.as_deref(), limit, offset)
+        .await
+    {
+        Ok(transactions) => {
+            let response_txs: Vec<TransactionResponse> =
+                transactions.iter().cloned().map(TransactionResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(
+                response_txs,
+                total,
+                limit,
+                offset,
+            ))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list transactions")),
+    }
+}
+
+/// Reverse a transaction.
+pub async fn reverse_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<ReverseTransactionRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let ledger_service = LedgerService::new(state.pool.clone())
+        .with_fees(state.fees.clone())
+        .with_external_ids(state.external_ids);
+
+    match ledger_service
+        .reverse_transaction_with(id, &request.reason, &request.idempotency_key, request.fee_policy)
+        .await
+    {
+        Ok(result) => Ok(Json(ApiResponse::success(TransactionResponse::from(
+            result.transaction,
+        )))),
+        Err(e) => Err(error_response(e, "Failed to reverse transaction")),
+    }
+}
+
+/// Refund all or part of a transaction's fee.
+pub async fn refund_transaction_fee(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<FeeRefundRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TransactionResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let ledger_service = LedgerService::new(state.pool.clone())
+        .with_fees(state.fees.clone())
+        .with_external_ids(state.external_ids);
+
+    match ledger_service
+        .refund_fee(id, request.amount, &request.reason, &request.idempotency_key)
+        .await
+    {
+        Ok(result) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(TransactionResponse::from(result.transaction))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to refund fee")),
+    }
+}
+
+/// Change the release priority of a queued transaction.
+pub async fn update_transaction_priority(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<UpdateTransactionPriorityRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let ledger_service = LedgerService::new(state.pool.clone());
+
+    match ledger_service.reprioritize_transaction(id, request.priority).await {
+        Ok(transaction) => Ok(Json(ApiResponse::success(TransactionResponse::from(transaction)))),
+        Err(e) => Err(error_response(e, "Failed to update transaction priority")),
+    }
+}
+
+/// Amend a transaction that is still queued for asynchronous settlement or held for
+/// risk review. Posted transactions are immutable.
+pub async fn amend_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<AmendTransactionRequest>,
+) -> Result<Json<ApiResponse<TransactionAmendment>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let amendment_service = AmendmentService::new(state.pool.clone(), Arc::new(ledger_service(&state)));
+    let changes = TransactionChanges {
+        amount: request.amount,
+        effective_date: request.effective_date,
+        metadata: request.metadata,
+    };
+
+    match amendment_service.amend(id, &changes, &request.amended_by, request.reason).await {
+        Ok(amendment) => Ok(Json(ApiResponse::success(amendment))),
+        Err(e) => Err(error_response(e, "Failed to amend transaction")),
+    }
+}
+
+/// List the amendments made to a queued or held transaction, oldest first.
+pub async fn get_transaction_amendments(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<TransactionAmendment>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let amendment_service = AmendmentService::new(state.pool.clone(), Arc::new(ledger_service(&state)));
+
+    match amendment_service.history(id).await {
+        Ok(amendments) => Ok(Json(ApiResponse::success(amendments))),
+        Err(e) => Err(error_response(e, "Failed to get transaction amendments")),
+    }
+}
+
+/// Get the moment a transaction reached settlement finality.
+pub async fn get_transaction_finality(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<FinalityResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let finality_service = FinalityService::new(state.pool.clone());
+
+    match finality_service.transaction_finality(id).await {
+        Ok(record) => Ok(Json(ApiResponse::success(FinalityResponse::from(record)))),
+        Err(e) => Err(error_response(e, "Failed to get transaction finality")),
+    }
+}
+
+/// Get the chronological history of a transaction.
+pub async fn get_transaction_timeline(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TransactionTimeline>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let timeline_service = TransactionTimelineService::new(state.pool.clone());
+
+    match timeline_service.get_timeline(id).await {
+        Ok(timeline) => Ok(Json(ApiResponse::success(timeline))),
+        Err(e) => Err(error_response(e, "Failed to get transaction timeline")),
+    }
+}
+
+/// Route a settled transaction to the open batch of a named settlement window.
+pub async fn assign_transaction_window(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<AssignTransactionWindowRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.assign_transaction_to_window(id, request.window_id).await {
+        Ok(transaction) => Ok(Json(ApiResponse::success(TransactionResponse::from(transaction)))),
+        Err(e) => Err(error_response(e, "Failed to assign transaction to settlement window")),
+    }
+}
+
+/// Move a batched transaction to another open batch, correcting both batches' totals.
+pub async fn reassign_transaction_batch(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<ReassignTransactionBatchRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.reassign_transaction(id, request.batch_id, &request.reason).await {
+        Ok(transaction) => Ok(Json(ApiResponse::success(TransactionResponse::from(transaction)))),
+        Err(e) => Err(error_response(e, "Failed to reassign transaction to batch")),
+    }
+}
+
+// ============================================================================
+// Batch Handlers
+// ============================================================================
+
+/// List batches with filters.
+pub async fn list_batches(
+    State(state): State<AppState>,
+    Query(query): Query<ListBatchesQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<BatchResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let status = query.status.as_ref().and_then(|s| match s.to_uppercase().as_str() {
+        "PENDING" => Some(BatchStatus::Pending),
+        "PROCESSING" => Some(BatchStatus::Processing),
+        "COMPLETED" => Some(BatchStatus::Completed),
+        "FAILED" => Some(BatchStatus::Failed),
+        _ => None,
+    });
+
+    match batch_service
+        .list_batches(status, query.currency.as_deref(), query.window_id, limit, offset)
+        .await
+    {
+        Ok(batches) => {
+            let response_batches: Vec<BatchResponse> =
+                batches.iter().cloned().map(BatchResponse::from).collect();
+            let total = response_batches.len() as i64;
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(
+                response_batches,
+                total,
+                limit,
+                offset,
+            ))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list batches")),
+    }
+}
+
+/// Get batch by ID.
+pub async fn get_batch(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.get_batch(id).await {
+        Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
+        Err(e) => Err(error_response(e, "Failed to get batch")),
+    }
+}
+
+/// Set how a pending batch is netted.
+pub async fn set_batch_netting_mode(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<SetNettingModeRequest>,
+) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.set_netting_mode(id, request.netting_mode).await {
+        Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
+        Err(e) => Err(error_response(e, "Failed to set batch netting mode")),
+    }
+}
+
+/// Set how a pending batch's positions become settlement instructions.
+pub async fn set_batch_instruction_strategy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<SetInstructionStrategyRequest>,
+) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.set_instruction_strategy(id, request.instruction_strategy).await {
+        Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
+        Err(e) => Err(error_response(e, "Failed to set batch instruction strategy")),
+    }
+}
+
+/// Move a pending batch's cut-off, e.g. to extend it.
+pub async fn move_batch_cut_off(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<MoveCutOffRequest>,
+) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let approval = CutOffApproval {
+        requested_by: request.requested_by,
+        approved_by: request.approved_by,
+        reason: request.reason,
+    };
+
+    match cut_off_batch_service(&state).move_cut_off(id, request.cut_off_time, &approval).await {
+        Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
+        Err(e) => Err(error_response(e, "Failed to move batch cut-off")),
+    }
+}
+
+/// Close a pending batch to new transactions now; the scheduler processes it next.
+pub async fn close_batch_early(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<CloseBatchEarlyRequest>,
+) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let approval = CutOffApproval {
+        requested_by: request.requested_by,
+        approved_by: request.approved_by,
+        reason: request.reason,
+    };
+
+    match cut_off_batch_service(&state).close_early(id, &approval).await {
+        Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
+        Err(e) => Err(error_response(e, "Failed to close batch early")),
+    }
+}
+
+/// List the approved cut-off changes made to a batch.
+pub async fn list_batch_cut_off_changes(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<BatchCutOffChange>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.get_cut_off_changes(id).await {
+        Ok(changes) => Ok(Json(ApiResponse::success(changes))),
+        Err(e) => Err(error_response(e, "Failed to list batch cut-off changes")),
+    }
+}
+
+/// Batch service that changes cut-offs under the same locks as closing and processing.
+fn cut_off_batch_service(state: &AppState) -> BatchService {
+    state
+        .batch_service()
+        .with_locks(state.locks.clone())
+        .with_max_cut_off_shift(state.max_cut_off_shift)
+}
+
 /// Process a batch.
 pub async fn process_batch(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-    Json(_request): Json<ProcessBatchRequest>,
-) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
-    let batch_service = BatchService::new(state.pool.clone());
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<ProcessBatchRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<BatchResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = processing_batch_service(&state);
+
+    if request.background {
+        return match Arc::new(batch_service).start_batch_processing(id).await {
+            Ok(batch) => Ok((StatusCode::ACCEPTED, Json(ApiResponse::success(BatchResponse::from(batch))))),
+            Err(e) => Err(error_response(e, "Failed to start batch processing")),
+        };
+    }
+
+    match batch_service.process_batch(id).await {
+        Ok(_result) => {
+            match batch_service.get_batch(id).await {
+                Ok(batch) => Ok((StatusCode::OK, Json(ApiResponse::success(BatchResponse::from(batch))))),
+                Err(e) => Err(error_response(e, "Failed to get batch after processing")),
+            }
+        }
+        Err(e) => Err(error_response(e, "Failed to process batch")),
+    }
+}
+
+/// Resume processing of a batch whose processing run stopped.
+pub async fn resume_batch_processing(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<BatchResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = processing_batch_service(&state);
+
+    match batch_service.resume_batch_processing(id).await {
+        Ok(_result) => match batch_service.get_batch(id).await {
+            Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
+            Err(e) => Err(error_response(e, "Failed to get batch after processing")),
+        },
+        Err(e) => Err(error_response(e, "Failed to resume batch processing")),
+    }
+}
+
+/// Batch service configured to process batches the way this instance does.
+fn processing_batch_service(state: &AppState) -> BatchService {
+    let mut batch_service = state
+        .batch_service()
+        .with_workers(state.batch_workers)
+        .with_stall_timeout(state.batch_stall_timeout)
+        .with_locks(state.locks.clone())
+        .with_fees(state.fees.clone())
+        .with_netting(state.netting.clone());
+    if let Some(producer) = &state.producer {
+        batch_service = batch_service.with_producer(producer.clone());
+    }
+    if let Some(rail) = &state.rail {
+        batch_service = batch_service.with_rail(rail.clone());
+    }
+    if let Some(advices) = &state.advices {
+        batch_service = batch_service.with_advices(advices.clone());
+    }
+    if let Some(funding) = &state.funding {
+        batch_service = batch_service.with_funding(funding.clone());
+    }
+    batch_service
+}
+
+/// Get batch processing progress.
+pub async fn get_batch_processing_progress(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<BatchProgressResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.get_processing_progress(id).await {
+        Ok(progress) => Ok(Json(ApiResponse::success(BatchProgressResponse::from(progress)))),
+        Err(e) => Err(error_response(e, "Failed to get batch processing progress")),
+    }
+}
+
+/// Get the totals checks run on a batch before completion.
+pub async fn get_batch_reconciliation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<BatchReconciliationResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.get_reconciliation(id).await {
+        Ok(reconciliation) => Ok(Json(ApiResponse::success(BatchReconciliationResponse::from(reconciliation)))),
+        Err(e) => Err(error_response(e, "Failed to get batch reconciliation")),
+    }
+}
+
+/// Stream batch processing progress as server-sent events. Each new checkpoint is sent
+/// as a `progress` event, waiting for processing to start if needed; the stream ends
+/// after the event for the finished batch.
+pub async fn stream_batch_processing_progress(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<BatchProgressStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+    if let Err(e) = batch_service.get_batch(id).await {
+        return Err(error_response(e, "Failed to get batch"));
+    }
+    let interval = std::time::Duration::from_millis(query.interval_ms.unwrap_or(1000).max(100));
+
+    let initial = (batch_service, None::<BatchProgressResponse>, false, false);
+    let stream = futures::stream::unfold(initial, move |(service, mut last, mut polled, done)| async move {
+        if done {
+            return None;
+        }
+        loop {
+            if polled {
+                tokio::time::sleep(interval).await;
+            }
+            polled = true;
+
+            match service.get_processing_progress(id).await {
+                Ok(progress) => {
+                    let progress = BatchProgressResponse::from(progress);
+                    if last.as_ref() == Some(&progress) {
+                        continue;
+                    }
+                    let finished = progress.finished_at.is_some();
+                    let event = Event::default()
+                        .event("progress")
+                        .data(serde_json::to_string(&progress).unwrap_or_default());
+                    last = Some(progress);
+                    return Some((Ok(event), (service, last, polled, finished)));
+                }
+                // Processing has not started yet
+                Err(AppError::NotFound(_)) => continue,
+                Err(e) => {
+                    tracing::warn!("Failed to get progress of batch {}: {}", id, e);
+                    let event = Event::default().event("error").data(e.to_string());
+                    return Some((Ok(event), (service, last, polled, true)));
+                }
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Get batch netting positions.
+pub async fn get_batch_positions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<crate::models::NettingPosition>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.get_batch_positions(id).await {
+        Ok(positions) => Ok(Json(ApiResponse::success(positions))),
+        Err(e) => Err(error_response(e, "Failed to get batch positions")),
+    }
+}
+
+/// List the transactions behind a participant's netting position in a batch.
+pub async fn get_position_transactions(
+    State(state): State<AppState>,
+    Path((id, participant_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<PositionTransactionsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<PositionContribution>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let netting_service = NettingService::new(state.pool.clone()).with_config(state.netting.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    match netting_service.get_position_transactions(id, participant_id, limit, offset).await {
+        Ok((items, total)) => Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset)))),
+        Err(e) => Err(error_response(e, "Failed to get position transactions")),
+    }
+}
+
+/// Issue the netting advices of a completed batch to its participants.
+pub async fn issue_netting_advices(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<NettingAdvice>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let result = match &state.advices {
+        Some(advices) => advices.issue(id).await,
+        None => {
+            NettingAdviceService::new(state.pool.clone())
+                .with_netting(state.netting.clone())
+                .issue(id)
+                .await
+        }
+    };
+
+    match result {
+        Ok(advices) => Ok(Json(ApiResponse::success(advices))),
+        Err(e) => Err(error_response(e, "Failed to issue netting advices")),
+    }
+}
+
+/// List the netting advices issued for a batch.
+pub async fn list_netting_advices(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<NettingAdvice>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let advice_service = NettingAdviceService::new(state.pool.clone());
+
+    match advice_service.list(id).await {
+        Ok(advices) => Ok(Json(ApiResponse::success(advices))),
+        Err(e) => Err(error_response(e, "Failed to list netting advices")),
+    }
+}
+
+/// Get the netting advice a participant was issued for a batch.
+pub async fn get_netting_advice(
+    State(state): State<AppState>,
+    Path((id, participant_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<NettingAdvice>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let advice_service = NettingAdviceService::new(state.pool.clone());
+
+    match advice_service.get(id, participant_id).await {
+        Ok(advice) => Ok(Json(ApiResponse::success(advice))),
+        Err(e) => Err(error_response(e, "Failed to get netting advice")),
+    }
+}
+
+/// Export a participant's netting advice as PDF, CSV or JSON.
+pub async fn export_netting_advice(
+    State(state): State<AppState>,
+    Path((id, participant_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<ExportNettingAdviceQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    let advice_service = NettingAdviceService::new(state.pool.clone());
+
+    let result = advice_service.get(id, participant_id).await.and_then(|advice| match query.format {
+        AdviceFormat::Pdf => Ok(("application/pdf", advice.to_pdf(), advice.file_name("pdf"))),
+        AdviceFormat::Csv => Ok(("text/csv", advice.to_csv().into_bytes(), advice.file_name("csv"))),
+        AdviceFormat::Json => serde_json::to_vec_pretty(&advice)
+            .map(|body| ("application/json", body, advice.file_name("json")))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize netting advice: {}", e))),
+    });
+
+    match result {
+        Ok((content_type, body, filename)) => Ok((
+            [
+                (header::CONTENT_TYPE, content_type.to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename),
+                ),
+            ],
+            body,
+        )),
+        Err(e) => Err(error_response(e, "Failed to export netting advice")),
+    }
+}
+
+fn funding_service(state: &AppState) -> FundingService {
+    FundingService::new(state.pool.clone())
+        .with_default_management(state.default_management)
+        .with_rounding(state.rounding.clone())
+        .with_netting(state.netting.clone())
+}
+
+/// Open funding obligations for the net payers of a completed batch.
+pub async fn open_funding_obligations(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<FundingObligation>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let result = match &state.funding {
+        Some(funding) => funding.open_obligations(id).await,
+        None => funding_service(&state).open_obligations(id).await,
+    };
+
+    match result {
+        Ok(obligations) => Ok(Json(ApiResponse::success(obligations))),
+        Err(e) => Err(error_response(e, "Failed to open funding obligations")),
+    }
+}
+
+/// List a batch's funding obligations.
+pub async fn list_batch_funding_obligations(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<FundingObligation>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match funding_service(&state).list_by_batch(id).await {
+        Ok(obligations) => Ok(Json(ApiResponse::success(obligations))),
+        Err(e) => Err(error_response(e, "Failed to list funding obligations")),
+    }
+}
+
+/// List funding obligations, optionally in one status.
+pub async fn list_funding_obligations(
+    State(state): State<AppState>,
+    Query(query): Query<ListFundingObligationsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<FundingObligation>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    match funding_service(&state).list(query.status, limit, offset).await {
+        Ok(obligations) => {
+            let total = obligations.len() as i64;
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(obligations, total, limit, offset))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list funding obligations")),
+    }
+}
+
+/// Get a funding obligation.
+pub async fn get_funding_obligation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<FundingObligation>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match funding_service(&state).get(id).await {
+        Ok(obligation) => Ok(Json(ApiResponse::success(obligation))),
+        Err(e) => Err(error_response(e, "Failed to get funding obligation")),
+    }
+}
+
+/// Get the stored bilateral pairs of a bilaterally netted batch.
+pub async fn get_batch_bilateral_pairs(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<BilateralPairRecord>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.get_bilateral_pairs(id).await {
+        Ok(pairs) => Ok(Json(ApiResponse::success(pairs))),
+        Err(e) => Err(error_response(e, "Failed to get bilateral pairs")),
+    }
+}
+
+/// Get the stored netting report of a batch.
+pub async fn get_batch_netting_report(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<NettingReport>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let netting_service = NettingService::new(state.pool.clone()).with_config(state.netting.clone());
+
+    match netting_service.get_stored_report(id).await {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => Err(error_response(e, "Failed to get netting report")),
+    }
+}
+
+/// Get the finality records for a batch in sequence order.
+pub async fn get_batch_finality(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<FinalityResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let finality_service = FinalityService::new(state.pool.clone());
+
+    match finality_service.batch_finality(id).await {
+        Ok(records) => Ok(Json(ApiResponse::success(
+            records.into_iter().map(FinalityResponse::from).collect(),
+        ))),
+        Err(e) => Err(error_response(e, "Failed to get batch finality")),
+    }
+}
+
+/// Get a batch's fee report: the fees settled per payer when fees accrue.
+pub async fn get_batch_fee_report(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<FeeReportQuery>,
+) -> Result<Json<ApiResponse<Vec<FeeSettlement>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let fee_service = FeeSettlementService::new(state.pool.clone()).with_fees(state.fees.clone());
+
+    match fee_service.get_fee_report(id, query.account_id).await {
+        Ok(settlements) => Ok(Json(ApiResponse::success(settlements))),
+        Err(e) => Err(error_response(e, "Failed to get batch fee report")),
+    }
+}
+
+/// Settle a completed batch's accrued fees not yet settled, such as fees reversed after
+/// the batch completed.
+pub async fn settle_batch_fees(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<FeeSettlement>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let fee_service = FeeSettlementService::new(state.pool.clone()).with_fees(state.fees.clone());
+
+    match fee_service.settle_batch(id).await {
+        Ok(settlements) => Ok(Json(ApiResponse::success(settlements))),
+        Err(e) => Err(error_response(e, "Failed to settle batch fees")),
+    }
+}
+
+/// Export the signed finality attestation for a completed batch.
+pub async fn get_batch_attestation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<SignedAttestation>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let mut finality_service = FinalityService::new(state.pool.clone());
+    if let Some(signer) = &state.attestation_signer {
+        finality_service = finality_service.with_signer(signer.clone());
+    }
+
+    match finality_service.attest_batch(id).await {
+        Ok(attestation) => Ok(Json(ApiResponse::success(attestation))),
+        Err(e) => Err(error_response(e, "Failed to build batch attestation")),
+    }
+}
+
+/// Download a completed batch's settlement instructions as a payment file.
+pub async fn export_batch_instructions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExportInstructionsQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    let mut export_service = InstructionExportService::new(state.pool.clone()).with_netting(state.netting.clone());
+    if let Some(config) = &state.nacha {
+        export_service = export_service.with_nacha(config.clone());
+    }
+
+    let result = match query.format {
+        ExportFormat::Nacha => export_service
+            .export_nacha(id)
+            .await
+            .map(|file| (file.to_string(), format!("batch-{}.ach", id), "text/plain; charset=us-ascii")),
+        ExportFormat::Pacs008 => export_service
+            .export_pacs008(id)
+            .await
+            .map(|message| (message.to_xml(), message.file_name(), "application/xml")),
+    };
+
+    match result {
+        Ok((body, filename, content_type)) => Ok((
+            [
+                (header::CONTENT_TYPE, content_type.to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename),
+                ),
+            ],
+            body,
+        )),
+        Err(e) => Err(error_response(e, "Failed to export batch instructions")),
+    }
+}
+
+/// Declare a participant in default on its net obligation in a completed batch.
+pub async fn declare_participant_default(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<DeclareDefaultRequest>,
+) -> Result<Json<ApiResponse<DefaultReport>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let default_service = DefaultManagementService::new(state.pool.clone())
+        .with_config(state.default_management)
+        .with_rounding(state.rounding.clone())
+        .with_netting(state.netting.clone());
+
+    match default_service
+        .declare_default(id, request.participant_id, request.resolution, request.reason)
+        .await
+    {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => Err(error_response(e, "Failed to declare participant default")),
+    }
+}
+
+/// List the participants declared in default in a batch.
+pub async fn list_batch_defaults(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<ParticipantDefault>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let default_service = DefaultManagementService::new(state.pool.clone());
+
+    match default_service.batch_defaults(id).await {
+        Ok(defaults) => Ok(Json(ApiResponse::success(defaults))),
+        Err(e) => Err(error_response(e, "Failed to list batch defaults")),
+    }
+}
+
+/// Get the default report of a participant in a batch.
+pub async fn get_default_report(
+    State(state): State<AppState>,
+    Path((id, participant_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<DefaultReport>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let default_service = DefaultManagementService::new(state.pool.clone());
+
+    match default_service.default_report(id, participant_id).await {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => Err(error_response(e, "Failed to get default report")),
+    }
+}
+
+// ============================================================================
+// Risk Review Handlers
+// ============================================================================
+
+fn risk_service(state: &AppState) -> Arc<RiskService> {
+    state
+        .risk
+        .clone()
+        .unwrap_or_else(|| Arc::new(RiskService::new(state.pool.clone())))
+}
+
+/// List transactions held for risk review, oldest first.
+pub async fn list_risk_holds(
+    State(state): State<AppState>,
+    Query(query): Query<ListRiskHoldsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<RiskHoldResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let risk_service = risk_service(&state);
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = match risk_service.count_holds(query.status).await {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count risk holds")),
+    };
+
+    match risk_service.list_holds(query.status, limit, offset).await {
+        Ok(holds) => {
+            let items: Vec<RiskHoldResponse> = holds.into_iter().map(RiskHoldResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list risk holds")),
+    }
+}
+
+/// Get a transaction held for risk review with its audit trail.
+pub async fn get_risk_hold(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RiskHoldResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let risk_service = risk_service(&state);
+
+    let hold = match risk_service.get_hold(id).await {
+        Ok(hold) => hold,
+        Err(e) => return Err(error_response(e, "Failed to get risk hold")),
+    };
+
+    match risk_service.hold_audit(id).await {
+        Ok(audit) => {
+            let mut response = RiskHoldResponse::from(hold);
+            response.audit = Some(audit);
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(e) => Err(error_response(e, "Failed to get risk hold audit")),
+    }
+}
+
+/// Release a held transaction and post it.
+pub async fn release_risk_hold(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<ReviewRiskHoldRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TransactionResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    // Holds queued while review was enabled can still be released
+    match ledger_service(&state)
+        .with_risk(risk_service(&state))
+        .release_held(id, &request.reviewed_by, &request.reason)
+        .await
+    {
+        Ok(result) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(TransactionResponse::from(result.transaction))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to release risk hold")),
+    }
+}
+
+/// Reject a held transaction; it will never be posted.
+pub async fn reject_risk_hold(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<ReviewRiskHoldRequest>,
+) -> Result<Json<ApiResponse<RiskHoldResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    match risk_service(&state)
+        .reject(id, &request.reviewed_by, &request.reason)
+        .await
+    {
+        Ok(hold) => Ok(Json(ApiResponse::success(RiskHoldResponse::from(hold)))),
+        Err(e) => Err(error_response(e, "Failed to reject risk hold")),
+    }
+}
+
+// ============================================================================
+// File Delivery Handlers
+// ============================================================================
+
+fn delivery_service(state: &AppState) -> DeliveryService {
+    let delivery_service = DeliveryService::new(state.pool.clone());
+    match &state.delivery {
+        Some(channels) => delivery_service.with_channels(channels.clone()),
+        None => delivery_service,
+    }
+}
+
+/// Generate a file and queue it for delivery to a configured destination.
+pub async fn create_delivery(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateDeliveryRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<FileDeliveryResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let delivery_service = delivery_service(&state);
+
+    let result: Result<_, AppError> = async {
+        let (file_name, content) = match request.source {
+            DeliverySource::Nacha { batch_id } => {
+                let mut export_service =
+                    InstructionExportService::new(state.pool.clone()).with_netting(state.netting.clone());
+                if let Some(config) = &state.nacha {
+                    export_service = export_service.with_nacha(config.clone());
+                }
+                let file = export_service.export_nacha(batch_id).await?;
+                (format!("batch-{}.ach", batch_id), file.to_string().into_bytes())
+            }
+            DeliverySource::Pacs008 { batch_id } => {
+                let message = InstructionExportService::new(state.pool.clone())
+                    .with_netting(state.netting.clone())
+                    .export_pacs008(batch_id)
+                    .await?;
+                (message.file_name(), message.to_xml().into_bytes())
+            }
+            DeliverySource::Statement { account_id, statement_type, date, currency } => {
+                let statement_service = StatementService::new(state.pool.clone());
+                let date = date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+                let statement = statement_service
+                    .generate(account_id, statement_type, date, currency.as_deref())
+                    .await?;
+                (format!("{}.xml", statement.message_id), statement.to_xml().into_bytes())
+            }
+        };
+        delivery_service
+            .enqueue(&request.destination, &file_name, content, request.scheduled_at)
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(delivery) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(FileDeliveryResponse::from(delivery))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to queue file delivery")),
+    }
+}
+
+/// List file deliveries, newest first.
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<FileDeliveryResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let delivery_service = delivery_service(&state);
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    match delivery_service.list_deliveries(query.status, limit, offset).await {
+        Ok(deliveries) => {
+            let items: Vec<FileDeliveryResponse> =
+                deliveries.into_iter().map(FileDeliveryResponse::from).collect();
+            let total = items.len() as i64;
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list file deliveries")),
+    }
+}
+
+/// Get a file delivery's status.
+pub async fn get_delivery(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<FileDeliveryResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let delivery_service = delivery_service(&state);
+
+    match delivery_service.get_delivery(id).await {
+        Ok(delivery) => Ok(Json(ApiResponse::success(FileDeliveryResponse::from(delivery)))),
+        Err(e) => Err(error_response(e, "Failed to get file delivery")),
+    }
+}
+
+/// Requeue a failed file delivery.
+pub async fn retry_delivery(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<FileDeliveryResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let delivery_service = delivery_service(&state);
+
+    match delivery_service.retry_delivery(id).await {
+        Ok(delivery) => Ok(Json(ApiResponse::success(FileDeliveryResponse::from(delivery)))),
+        Err(e) => Err(error_response(e, "Failed to retry file delivery")),
+    }
+}
+
+// ============================================================================
+// Report Handlers
+// ============================================================================
+
+/// Get settled volume per settlement route (netted vs RTGS) for a day.
+pub async fn get_routing_report(
+    State(state): State<AppState>,
+    Query(query): Query<RoutingReportQuery>,
+) -> Result<Json<ApiResponse<RoutingReportResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let rtgs_service = RtgsService::new(state.pool.clone());
+    let date = query.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+
+    match rtgs_service.daily_report(date).await {
+        Ok(report) => Ok(Json(ApiResponse::success(RoutingReportResponse::from(report)))),
+        Err(e) => Err(error_response(e, "Failed to build routing report")),
+    }
+}
+
+/// Get stored netting reports for a period, with the efficiency across them.
+pub async fn get_netting_history(
+    State(state): State<AppState>,
+    Query(query): Query<NettingReportQuery>,
+) -> Result<Json<ApiResponse<NettingHistoryResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let netting_service = NettingService::new(state.pool.clone()).with_config(state.netting.clone());
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(30));
+
+    match netting_service
+        .list_stored_reports(from, to, query.currency.as_deref(), query.window_id)
+        .await
+    {
+        Ok(records) => Ok(Json(ApiResponse::success(NettingHistoryResponse::new(from, to, records)))),
+        Err(e) => Err(error_response(e, "Failed to list netting reports")),
+    }
+}
+
+/// Get each participant's intraday liquidity usage in a currency for a day, as JSON or
+/// a CSV download.
+pub async fn get_intraday_liquidity_report(
+    State(state): State<AppState>,
+    Query(query): Query<IntradayLiquidityQuery>,
+) -> Result<axum::response::Response, (StatusCode, Json<ApiResponse<()>>)> {
+    let report_service = LiquidityReportService::new(state.pool.clone());
+    let date = query.date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+
+    let report = report_service
+        .intraday_report(date, &query.currency.to_uppercase(), query.account_id)
+        .await
+        .map_err(|e| error_response(e, "Failed to build intraday liquidity report"))?;
+
+    match query.format {
+        ReportFormat::Json => Ok(Json(ApiResponse::success(IntradayLiquidityResponse::from(report))).into_response()),
+        ReportFormat::Csv => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", report.file_name("csv")),
+                ),
+            ],
+            report.to_csv(),
+        )
+            .into_response()),
+    }
+}
+
+// ============================================================================
+// Settlement Window Handlers
+// ============================================================================
+
+/// Create a named settlement window.
+pub async fn create_settlement_window(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateSettlementWindowRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<SettlementWindowResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let window_service = SettlementWindowService::new(state.pool.clone());
+
+    let service_request = crate::services::CreateSettlementWindowRequest {
+        name: request.name,
+        currency: request.currency,
+        cut_off_time: request.cut_off_time,
+        timezone: request.timezone,
+    };
+
+    match window_service.create_window(service_request).await {
+        Ok(window) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(SettlementWindowResponse::from(window))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to create settlement window")),
+    }
+}
+
+/// List settlement windows, optionally for one currency.
+pub async fn list_settlement_windows(
+    State(state): State<AppState>,
+    Query(query): Query<ListSettlementWindowsQuery>,
+) -> Result<Json<ApiResponse<Vec<SettlementWindowResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let window_service = SettlementWindowService::new(state.pool.clone());
+
+    match window_service
+        .list_windows(query.currency.as_deref(), query.active_only)
+        .await
+    {
+        Ok(windows) => Ok(Json(ApiResponse::success(
+            windows.into_iter().map(SettlementWindowResponse::from).collect(),
+        ))),
+        Err(e) => Err(error_response(e, "Failed to list settlement windows")),
+    }
+}
+
+/// Get settlement window by ID.
+pub async fn get_settlement_window(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<SettlementWindowResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let window_service = SettlementWindowService::new(state.pool.clone());
+
+    match window_service.get_window(id).await {
+        Ok(window) => Ok(Json(ApiResponse::success(SettlementWindowResponse::from(window)))),
+        Err(e) => Err(error_response(e, "Failed to get settlement window")),
+    }
+}
+
+/// Update a settlement window.
+pub async fn update_settlement_window(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<UpdateSettlementWindowRequest>,
+) -> Result<Json<ApiResponse<SettlementWindowResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let window_service = SettlementWindowService::new(state.pool.clone());
+
+    let service_request = crate::services::UpdateSettlementWindowRequest {
+        name: request.name,
+        cut_off_time: request.cut_off_time,
+        timezone: request.timezone,
+        active: request.active,
+    };
+
+    match window_service.update_window(id, service_request).await {
+        Ok(window) => Ok(Json(ApiResponse::success(SettlementWindowResponse::from(window)))),
+        Err(e) => Err(error_response(e, "Failed to update settlement window")),
+    }
+}
+
+/// Deactivate a settlement window.
+pub async fn delete_settlement_window(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<ApiResponse<()>>)> {
+    let window_service = SettlementWindowService::new(state.pool.clone());
+
+    match window_service.deactivate_window(id).await {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(error_response(e, "Failed to deactivate settlement window")),
+    }
+}
+
+// ============================================================================
+// Batch Template Handlers
+// ============================================================================
+
+/// Create a batch template.
+pub async fn create_batch_template(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateBatchTemplateRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<BatchTemplate>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let template_service = BatchTemplateService::new(state.pool.clone());
+
+    let service_request = crate::services::CreateBatchTemplateRequest {
+        name: request.name,
+        currency: request.currency,
+        window_id: request.window_id,
+        cut_off_time: request.cut_off_time,
+        metadata: request.metadata,
+        netting_mode: request.netting_mode,
+        cut_off_approval: request.cut_off_approval,
+    };
+
+    match template_service.create_template(service_request).await {
+        Ok(template) => Ok((StatusCode::CREATED, Json(ApiResponse::success(template)))),
+        Err(e) => Err(error_response(e, "Failed to create batch template")),
+    }
+}
+
+/// List batch templates, optionally for one currency.
+pub async fn list_batch_templates(
+    State(state): State<AppState>,
+    Query(query): Query<ListBatchTemplatesQuery>,
+) -> Result<Json<ApiResponse<Vec<BatchTemplate>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let template_service = BatchTemplateService::new(state.pool.clone());
+
+    match template_service
+        .list_templates(query.currency.as_deref(), query.active_only)
+        .await
+    {
+        Ok(templates) => Ok(Json(ApiResponse::success(templates))),
+        Err(e) => Err(error_response(e, "Failed to list batch templates")),
+    }
+}
+
+/// Get batch template by ID.
+pub async fn get_batch_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<BatchTemplate>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let template_service = BatchTemplateService::new(state.pool.clone());
+
+    match template_service.get_template(id).await {
+        Ok(template) => Ok(Json(ApiResponse::success(template))),
+        Err(e) => Err(error_response(e, "Failed to get batch template")),
+    }
+}
+
+/// Update a batch template.
+pub async fn update_batch_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<UpdateBatchTemplateRequest>,
+) -> Result<Json<ApiResponse<BatchTemplate>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let template_service = BatchTemplateService::new(state.pool.clone());
+
+    let service_request = crate::services::UpdateBatchTemplateRequest {
+        name: request.name,
+        cut_off_time: request.cut_off_time,
+        metadata: request.metadata,
+        netting_mode: request.netting_mode,
+        cut_off_approval: request.cut_off_approval,
+        active: request.active,
+    };
+
+    match template_service.update_template(id, service_request).await {
+        Ok(template) => Ok(Json(ApiResponse::success(template))),
+        Err(e) => Err(error_response(e, "Failed to update batch template")),
+    }
+}
+
+/// Open a settlement date's batches from the active templates now, without waiting for
+/// the provisioning job.
+pub async fn provision_batches(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<ProvisionBatchesRequest>,
+) -> Result<Json<ApiResponse<ProvisioningReport>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let template_service = BatchTemplateService::new(state.pool.clone());
+    let settlement_date = request
+        .settlement_date
+        .unwrap_or_else(|| chrono::Utc::now().date_naive() + chrono::Duration::days(1));
+
+    match template_service.provision(settlement_date).await {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => Err(error_response(e, "Failed to provision batches")),
+    }
+}
+
+// ============================================================================
+// Alert Rule Handlers
+// ============================================================================
+
+/// Create an alert rule.
+pub async fn create_alert_rule(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateAlertRuleRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<AlertRuleResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let alert_service = AlertService::new(state.pool.clone());
+
+    let service_request = crate::services::CreateAlertRuleRequest {
+        account_id: request.account_id,
+        rule_type: request.rule_type,
+        currency: request.currency,
+        threshold: request.threshold,
+        webhook_url: request.webhook_url,
+        suppression_window_seconds: request.suppression_window_seconds,
+        metadata: request.metadata,
+    };
+
+    match alert_service.create_rule(service_request).await {
+        Ok(rule) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(AlertRuleResponse::from(rule))),
+        )),
+        Err(e) => Err(error_response(e, "Failed to create alert rule")),
+    }
+}
+
+/// List alert rules with filters.
+pub async fn list_alert_rules(
+    State(state): State<AppState>,
+    Query(query): Query<ListAlertRulesQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<AlertRuleResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let alert_service = AlertService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = match alert_service.count_rules(query.account_id, query.rule_type).await {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count alert rules")),
+    };
+
+    match alert_service
+        .list_rules(query.account_id, query.rule_type, limit, offset)
+        .await
+    {
+        Ok(rules) => {
+            let response_rules: Vec<AlertRuleResponse> =
+                rules.into_iter().map(AlertRuleResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(
+                response_rules,
+                total,
+                limit,
+                offset,
+            ))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list alert rules")),
+    }
+}
+
+/// List balance breaks found by reconciliation, newest first.
+pub async fn list_balance_breaks(
+    State(state): State<AppState>,
+    Query(query): Query<ListBalanceBreaksQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<BalanceBreakResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let reconciliation_service = ReconciliationService::new(state.pool.clone());
+    let open_only = !query.include_resolved;
+    let currency = query.currency.as_deref();
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = match reconciliation_service
+        .count_breaks(open_only, query.account_id, currency)
+        .await
+    {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count balance breaks")),
+    };
+
+    match reconciliation_service
+        .list_breaks(open_only, query.account_id, currency, limit, offset)
+        .await
+    {
+        Ok(breaks) => {
+            let items: Vec<BalanceBreakResponse> = breaks.into_iter().map(BalanceBreakResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list balance breaks")),
+    }
+}
+
+/// List balance incidents, newest first.
+pub async fn list_balance_incidents(
+    State(state): State<AppState>,
+    Query(query): Query<ListBalanceIncidentsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<BalanceIncidentResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let guard_service = BalanceGuardService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
 
-    match batch_service.process_batch(id).await {
-        Ok(_result) => {
-            match batch_service.get_batch(id).await {
-                Ok(batch) => Ok(Json(ApiResponse::success(BatchResponse::from(batch)))),
-                Err(e) => {
-                    tracing::error!("Failed to get batch after processing: {}", e);
-                    Err((
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse::<()>::error(ErrorResponse::new(
-                            "INTERNAL_ERROR",
-                            "An internal error occurred",
-                        ))),
-                    ))
-                }
-            }
+    let total = match guard_service.count(query.status, query.account_id).await {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count balance incidents")),
+    };
+
+    match guard_service.list(query.status, query.account_id, limit, offset).await {
+        Ok(incidents) => {
+            let items: Vec<BalanceIncidentResponse> =
+                incidents.into_iter().map(BalanceIncidentResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset))))
         }
-        Err(AppError::Validation(msg)) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("VALIDATION_ERROR", msg))),
+        Err(e) => Err(error_response(e, "Failed to list balance incidents")),
+    }
+}
+
+/// Resolve a balance incident once the balance is back within its floor.
+pub async fn resolve_balance_incident(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<ResolveBalanceIncidentRequest>,
+) -> Result<Json<ApiResponse<BalanceIncidentResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let guard_service = BalanceGuardService::new(state.pool.clone());
+
+    match guard_service.resolve(id, &request.note).await {
+        Ok(incident) => Ok(Json(ApiResponse::success(BalanceIncidentResponse::from(incident)))),
+        Err(e) => Err(error_response(e, "Failed to resolve balance incident")),
+    }
+}
+
+/// Set the lowest available balance an account may hold in a currency.
+pub async fn set_balance_floor(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<SetBalanceFloorRequest>,
+) -> Result<Json<ApiResponse<BalanceFloorResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let guard_service = BalanceGuardService::new(state.pool.clone());
+
+    match guard_service.set_floor(id, &request.currency, request.floor).await {
+        Ok(floor) => Ok(Json(ApiResponse::success(BalanceFloorResponse::from(floor)))),
+        Err(e) => Err(error_response(e, "Failed to set balance floor")),
+    }
+}
+
+/// Cap an account's net debit within a batch of a currency.
+pub async fn set_net_debit_cap(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<SetNetDebitCapRequest>,
+) -> Result<Json<ApiResponse<NetDebitCapResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let batch_service = state.batch_service();
+
+    match batch_service.set_net_debit_cap(id, &request.currency, request.cap).await {
+        Ok(cap) => Ok(Json(ApiResponse::success(NetDebitCapResponse::from(cap)))),
+        Err(e) => Err(error_response(e, "Failed to set net debit cap")),
+    }
+}
+
+// ============================================================================
+// Balance Reservation Handlers
+// ============================================================================
+
+/// Reserve funds on an account under a name and reference, optionally until an expiry.
+pub async fn create_reservation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<CreateReservationRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<ReservationResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+
+    let mut reservation = BalanceReservation::new(id, request.currency, request.amount, request.name, request.reference);
+    if let Some(expires_at) = request.expires_at {
+        reservation = reservation.with_expiry(expires_at);
+    }
+
+    match balance_service.create_reservation(reservation).await {
+        Ok(reservation) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(ReservationResponse::from(reservation))),
         )),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
+        Err(e) => Err(error_response(e, "Failed to create reservation")),
+    }
+}
+
+/// List an account's reservations, optionally filtered by status.
+pub async fn list_reservations(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ListReservationsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<ReservationResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = match balance_service.count_reservations(id, query.status).await {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count reservations")),
+    };
+
+    match balance_service.list_reservations(id, query.status, limit, offset).await {
+        Ok(reservations) => {
+            let items: Vec<ReservationResponse> = reservations.into_iter().map(ReservationResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list reservations")),
+    }
+}
+
+/// Get one of an account's reservations.
+pub async fn get_reservation(
+    State(state): State<AppState>,
+    Path((id, reservation_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<ReservationResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+
+    match balance_service.get_reservation(id, reservation_id).await {
+        Ok(reservation) => Ok(Json(ApiResponse::success(ReservationResponse::from(reservation)))),
+        Err(e) => Err(error_response(e, "Failed to get reservation")),
+    }
+}
+
+/// Release an active reservation's funds back to the account.
+pub async fn release_reservation(
+    State(state): State<AppState>,
+    Path((id, reservation_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<ReservationResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+
+    match balance_service.release_reservation_by_id(id, reservation_id).await {
+        Ok(reservation) => Ok(Json(ApiResponse::success(ReservationResponse::from(reservation)))),
+        Err(e) => Err(error_response(e, "Failed to release reservation")),
+    }
+}
+
+/// Capture an active reservation into a settled payment from the account.
+pub async fn capture_reservation(
+    State(state): State<AppState>,
+    Path((id, reservation_id)): Path<(Uuid, Uuid)>,
+    ValidJson(request): ValidJson<CaptureReservationRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<TransactionResponse>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let balance_service = BalanceService::new(state.pool.clone());
+
+    let reservation = balance_service
+        .get_reservation(id, reservation_id)
+        .await
+        .map_err(|e| error_response(e, "Failed to capture reservation"))?;
+    let mut payment = LedgerTransactionRequest::payment(
+        request.external_id,
+        reservation.account_id,
+        request.destination_account_id,
+        request.amount.unwrap_or(reservation.amount),
+        reservation.currency,
+        request.idempotency_key,
+    )
+    .with_fee(request.fee_amount.unwrap_or(Decimal::ZERO));
+    if let Some(metadata) = request.metadata {
+        payment = payment.with_metadata(metadata);
+    }
+
+    match ledger_service(&state).capture_reservation(reservation_id, payment).await {
+        Ok(result) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(TransactionResponse::from(result.transaction))),
         )),
-        Err(e) => {
-            tracing::error!("Failed to process batch: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
+        Err(e) => Err(error_response(e, "Failed to capture reservation")),
+    }
+}
+
+// ============================================================================
+// General Ledger Handlers
+// ============================================================================
+
+/// Post a closed day to the general ledger. Re-posting a day returns its existing run.
+pub async fn create_gl_posting_run(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateGlPostingRunRequest>,
+) -> Result<Json<ApiResponse<GlJournalResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let gl_service = GlPostingService::new(state.pool.clone()).with_mapping(state.gl_mapping.clone());
+
+    match gl_service.post_day(request.posting_date).await {
+        Ok(journal) => Ok(Json(ApiResponse::success(GlJournalResponse::from(journal)))),
+        Err(e) => Err(error_response(e, "Failed to post to the general ledger")),
+    }
+}
+
+/// List GL posting runs, latest posting date first.
+pub async fn list_gl_posting_runs(
+    State(state): State<AppState>,
+    Query(query): Query<ListGlPostingRunsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<GlPostingRunResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let gl_service = GlPostingService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = match gl_service.count_runs().await {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count GL posting runs")),
+    };
+
+    match gl_service.list_runs(limit, offset).await {
+        Ok(runs) => {
+            let items: Vec<GlPostingRunResponse> = runs.into_iter().map(GlPostingRunResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset))))
         }
+        Err(e) => Err(error_response(e, "Failed to list GL posting runs")),
     }
 }
 
-/// Get batch netting positions.
-pub async fn get_batch_positions(
+/// List accounting periods that were closed or reopened, latest first.
+pub async fn list_accounting_periods(
+    State(state): State<AppState>,
+    Query(query): Query<ListAccountingPeriodsQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<AccountingPeriodResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let period_service = AccountingPeriodService::new(state.pool.clone());
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = match period_service.count().await {
+        Ok(count) => count,
+        Err(e) => return Err(error_response(e, "Failed to count accounting periods")),
+    };
+
+    match period_service.list(limit, offset).await {
+        Ok(periods) => {
+            let items: Vec<AccountingPeriodResponse> =
+                periods.into_iter().map(AccountingPeriodResponse::from).collect();
+            Ok(Json(ApiResponse::success(PaginatedResponse::new(items, total, limit, offset))))
+        }
+        Err(e) => Err(error_response(e, "Failed to list accounting periods")),
+    }
+}
+
+/// Close the accounting period containing a date to backdated entries.
+pub async fn close_accounting_period(
+    State(state): State<AppState>,
+    Path(date): Path<chrono::NaiveDate>,
+) -> Result<Json<ApiResponse<AccountingPeriodResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let period_service = AccountingPeriodService::new(state.pool.clone());
+
+    match period_service.close(date).await {
+        Ok(period) => Ok(Json(ApiResponse::success(AccountingPeriodResponse::from(period)))),
+        Err(e) => Err(error_response(e, "Failed to close accounting period")),
+    }
+}
+
+/// Reopen the accounting period containing a date.
+pub async fn reopen_accounting_period(
+    State(state): State<AppState>,
+    Path(date): Path<chrono::NaiveDate>,
+) -> Result<Json<ApiResponse<AccountingPeriodResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let period_service = AccountingPeriodService::new(state.pool.clone());
+
+    match period_service.reopen(date).await {
+        Ok(period) => Ok(Json(ApiResponse::success(AccountingPeriodResponse::from(period)))),
+        Err(e) => Err(error_response(e, "Failed to reopen accounting period")),
+    }
+}
+
+/// Download a posting run's GL journal as CSV or JSON.
+pub async fn export_gl_journal(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Vec<crate::models::NettingPosition>>>, (StatusCode, Json<ApiResponse<()>>)> {
-    let batch_service = BatchService::new(state.pool.clone());
+    Query(query): Query<ExportGlJournalQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    let gl_service = GlPostingService::new(state.pool.clone());
 
-    match batch_service.get_batch_positions(id).await {
-        Ok(positions) => Ok(Json(ApiResponse::success(positions))),
-        Err(AppError::NotFound(msg)) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error(ErrorResponse::new("NOT_FOUND", msg))),
+    let result = gl_service.get_journal(id).await.and_then(|journal| match query.format {
+        JournalFormat::Csv => Ok(("text/csv", journal.to_csv(), journal.file_name("csv"))),
+        JournalFormat::Json => serde_json::to_string_pretty(&journal)
+            .map(|body| ("application/json", body, journal.file_name("json")))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize GL journal: {}", e))),
+    });
+
+    match result {
+        Ok((content_type, body, filename)) => Ok((
+            [
+                (header::CONTENT_TYPE, content_type.to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename),
+                ),
+            ],
+            body,
         )),
-        Err(e) => {
-            tracing::error!("Failed to get batch positions: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(ErrorResponse::new(
-                    "INTERNAL_ERROR",
-                    "An internal error occurred",
-                ))),
-            ))
-        }
+        Err(e) => Err(error_response(e, "Failed to export GL journal")),
+    }
+}
+
+// ============================================================================
+// Invoicing Handlers
+// ============================================================================
+
+/// Set how an account's monthly volume in a currency is invoiced.
+pub async fn set_invoice_contract(
+    State(state): State<AppState>,
+    Path((id, currency)): Path<(Uuid, String)>,
+    ValidJson(request): ValidJson<SetInvoiceContractRequest>,
+) -> Result<Json<ApiResponse<InvoiceContract>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let invoice_service = InvoiceService::new(state.pool.clone());
+    let terms = InvoiceContractTerms {
+        free_transactions: request.free_transactions,
+        per_transaction_price: request.per_transaction_price,
+        volume_rate: request.volume_rate,
+        monthly_cap: request.monthly_cap,
+    };
+
+    match invoice_service.set_contract(id, &currency.to_uppercase(), terms).await {
+        Ok(contract) => Ok(Json(ApiResponse::success(contract))),
+        Err(e) => Err(error_response(e, "Failed to set invoice contract")),
+    }
+}
+
+/// Get how an account's monthly volume in a currency is invoiced.
+pub async fn get_invoice_contract(
+    State(state): State<AppState>,
+    Path((id, currency)): Path<(Uuid, String)>,
+) -> Result<Json<ApiResponse<InvoiceContract>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let invoice_service = InvoiceService::new(state.pool.clone());
+
+    match invoice_service.get_contract(id, &currency.to_uppercase()).await {
+        Ok(contract) => Ok(Json(ApiResponse::success(contract))),
+        Err(e) => Err(error_response(e, "Failed to get invoice contract")),
+    }
+}
+
+/// Invoice an ended month. Participants already invoiced for it keep their invoices.
+pub async fn create_invoice_run(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<CreateInvoiceRunRequest>,
+) -> Result<Json<ApiResponse<Vec<Invoice>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let invoice_service = InvoiceService::new(state.pool.clone()).with_fees(state.fees.clone());
+    let currency = request.currency.map(|currency| currency.to_uppercase());
+
+    match invoice_service.invoice_month(request.month, currency.as_deref()).await {
+        Ok(invoices) => Ok(Json(ApiResponse::success(invoices))),
+        Err(e) => Err(error_response(e, "Failed to invoice month")),
+    }
+}
+
+/// List invoices, latest month first.
+pub async fn list_invoices(
+    State(state): State<AppState>,
+    Query(query): Query<ListInvoicesQuery>,
+) -> Result<Json<ApiResponse<Vec<Invoice>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let invoice_service = InvoiceService::new(state.pool.clone());
+
+    match invoice_service.list_invoices(query.account_id, query.month).await {
+        Ok(invoices) => Ok(Json(ApiResponse::success(invoices))),
+        Err(e) => Err(error_response(e, "Failed to list invoices")),
+    }
+}
+
+/// Get an invoice with its line items.
+pub async fn get_invoice(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<InvoiceDocument>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let invoice_service = InvoiceService::new(state.pool.clone());
+
+    match invoice_service.get_invoice(id).await {
+        Ok(document) => Ok(Json(ApiResponse::success(document))),
+        Err(e) => Err(error_response(e, "Failed to get invoice")),
+    }
+}
+
+/// Download an invoice's line items as CSV or JSON.
+pub async fn export_invoice(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExportInvoiceQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    let invoice_service = InvoiceService::new(state.pool.clone());
+
+    let result = invoice_service.get_invoice(id).await.and_then(|document| match query.format {
+        JournalFormat::Csv => Ok(("text/csv", document.to_csv(), document.file_name("csv"))),
+        JournalFormat::Json => serde_json::to_string_pretty(&document)
+            .map(|body| ("application/json", body, document.file_name("json")))
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize invoice: {}", e))),
+    });
+
+    match result {
+        Ok((content_type, body, filename)) => Ok((
+            [
+                (header::CONTENT_TYPE, content_type.to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename),
+                ),
+            ],
+            body,
+        )),
+        Err(e) => Err(error_response(e, "Failed to export invoice")),
+    }
+}
+
+/// Get alert rule by ID.
+pub async fn get_alert_rule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<AlertRuleResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let alert_service = AlertService::new(state.pool.clone());
+
+    match alert_service.get_rule(id).await {
+        Ok(rule) => Ok(Json(ApiResponse::success(AlertRuleResponse::from(rule)))),
+        Err(e) => Err(error_response(e, "Failed to get alert rule")),
+    }
+}
+
+/// Update an alert rule.
+pub async fn update_alert_rule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidJson(request): ValidJson<UpdateAlertRuleRequest>,
+) -> Result<Json<ApiResponse<AlertRuleResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let alert_service = AlertService::new(state.pool.clone());
+
+    let service_request = crate::services::UpdateAlertRuleRequest {
+        threshold: request.threshold,
+        webhook_url: request.webhook_url,
+        suppression_window_seconds: request.suppression_window_seconds,
+        enabled: request.enabled,
+        metadata: request.metadata,
+    };
+
+    match alert_service.update_rule(id, service_request).await {
+        Ok(rule) => Ok(Json(ApiResponse::success(AlertRuleResponse::from(rule)))),
+        Err(e) => Err(error_response(e, "Failed to update alert rule")),
+    }
+}
+
+/// Delete an alert rule.
+pub async fn delete_alert_rule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<ApiResponse<()>>)> {
+    let alert_service = AlertService::new(state.pool.clone());
+
+    match alert_service.delete_rule(id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(error_response(e, "Failed to delete alert rule")),
+    }
+}
+
+// ============================================================================
+// Metadata Schema Handlers
+// ============================================================================
+
+/// Set the metadata schema for a transaction type.
+pub async fn set_metadata_schema(
+    State(state): State<AppState>,
+    Path(transaction_type): Path<TransactionType>,
+    ValidJson(request): ValidJson<SetMetadataSchemaRequest>,
+) -> Result<Json<ApiResponse<MetadataSchemaResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let schema_service = MetadataSchemaService::new(state.pool.clone());
+
+    match schema_service.set_schema(transaction_type, request.schema).await {
+        Ok(schema) => Ok(Json(ApiResponse::success(MetadataSchemaResponse::from(schema)))),
+        Err(e) => Err(error_response(e, "Failed to set metadata schema")),
+    }
+}
+
+/// List metadata schemas.
+pub async fn list_metadata_schemas(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<MetadataSchemaResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let schema_service = MetadataSchemaService::new(state.pool.clone());
+
+    match schema_service.list_schemas().await {
+        Ok(schemas) => Ok(Json(ApiResponse::success(
+            schemas.into_iter().map(MetadataSchemaResponse::from).collect(),
+        ))),
+        Err(e) => Err(error_response(e, "Failed to list metadata schemas")),
+    }
+}
+
+/// Get the metadata schema for a transaction type.
+pub async fn get_metadata_schema(
+    State(state): State<AppState>,
+    Path(transaction_type): Path<TransactionType>,
+) -> Result<Json<ApiResponse<MetadataSchemaResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let schema_service = MetadataSchemaService::new(state.pool.clone());
+
+    match schema_service.get_schema(transaction_type).await {
+        Ok(schema) => Ok(Json(ApiResponse::success(MetadataSchemaResponse::from(schema)))),
+        Err(e) => Err(error_response(e, "Failed to get metadata schema")),
+    }
+}
+
+/// Remove the metadata schema for a transaction type.
+pub async fn delete_metadata_schema(
+    State(state): State<AppState>,
+    Path(transaction_type): Path<TransactionType>,
+) -> Result<StatusCode, (StatusCode, Json<ApiResponse<()>>)> {
+    let schema_service = MetadataSchemaService::new(state.pool.clone());
+
+    match schema_service.delete_schema(transaction_type).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(error_response(e, "Failed to delete metadata schema")),
+    }
+}
+
+// ============================================================================
+// Instrument Handlers
+// ============================================================================
+
+/// Register a security that accounts can hold.
+pub async fn register_instrument(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<RegisterInstrumentRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<Instrument>>), (StatusCode, Json<ApiResponse<()>>)> {
+    let instrument_service = InstrumentService::new(state.pool.clone());
+    let instrument = Instrument::security(request.code, request.identifier_type, request.scale)
+        .with_description(request.description);
+
+    match instrument_service.register(instrument).await {
+        Ok(instrument) => Ok((StatusCode::CREATED, Json(ApiResponse::success(instrument)))),
+        Err(e) => Err(error_response(e, "Failed to register instrument")),
+    }
+}
+
+/// List registered instruments.
+pub async fn list_instruments(
+    State(state): State<AppState>,
+    Query(query): Query<ListInstrumentsQuery>,
+) -> Result<Json<ApiResponse<Vec<Instrument>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let instrument_service = InstrumentService::new(state.pool.clone());
+
+    match instrument_service.list(query.kind).await {
+        Ok(instruments) => Ok(Json(ApiResponse::success(instruments))),
+        Err(e) => Err(error_response(e, "Failed to list instruments")),
+    }
+}
+
+/// Get a registered instrument by code.
+pub async fn get_instrument(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<Json<ApiResponse<Instrument>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let instrument_service = InstrumentService::new(state.pool.clone());
+
+    match instrument_service.get(&code.to_uppercase()).await {
+        Ok(instrument) => Ok(Json(ApiResponse::success(instrument))),
+        Err(e) => Err(error_response(e, "Failed to get instrument")),
+    }
+}
+
+// ============================================================================
+// Transaction Type Handlers
+// ============================================================================
+
+/// Register or update a custom transaction type.
+pub async fn set_transaction_type(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+    ValidJson(request): ValidJson<SetTransactionTypeRequest>,
+) -> Result<Json<ApiResponse<TransactionTypeDefinition>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let type_service = TransactionTypeService::new(state.pool.clone());
+    let mut definition = TransactionTypeDefinition::new(code, request.base_type);
+    definition.reversible = request.reversible.unwrap_or(definition.reversible);
+    definition.nettable = request.nettable.unwrap_or(definition.nettable);
+    definition.requires_original = request.requires_original.unwrap_or(definition.requires_original);
+    definition.fee_policy = request.fee_policy.unwrap_or(definition.fee_policy);
+    definition.description = request.description;
+    definition.narrative_template = request.narrative_template;
+
+    match type_service.register(definition).await {
+        Ok(definition) => Ok(Json(ApiResponse::success(definition))),
+        Err(e) => Err(error_response(e, "Failed to register transaction type")),
+    }
+}
+
+/// List the built-in and custom transaction types.
+pub async fn list_transaction_types(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<TransactionTypeDefinition>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let type_service = TransactionTypeService::new(state.pool.clone());
+
+    match type_service.list().await {
+        Ok(definitions) => Ok(Json(ApiResponse::success(definitions))),
+        Err(e) => Err(error_response(e, "Failed to list transaction types")),
+    }
+}
+
+/// Get a transaction type by code.
+pub async fn get_transaction_type(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<Json<ApiResponse<TransactionTypeDefinition>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let type_service = TransactionTypeService::new(state.pool.clone());
+
+    match type_service.get(&code).await {
+        Ok(definition) => Ok(Json(ApiResponse::success(definition))),
+        Err(e) => Err(error_response(e, "Failed to get transaction type")),
     }
 }
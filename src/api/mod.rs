@@ -1,6 +1,14 @@
+pub mod admission;
+pub mod graphql;
 pub mod handlers;
 pub mod requests;
 pub mod responses;
 pub mod routes;
+pub mod server;
+pub mod timeouts;
+pub mod validation;
 
+pub use admission::{AdmissionConfig, AdmissionControl};
 pub use routes::{create_router, AppState};
+pub use server::{bind_listeners, serve, ClientCertificate, ListenerConfig, TlsConfig};
+pub use timeouts::RequestTimeouts;
@@ -2,10 +2,17 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::models::{AccountType, TransactionType};
+use crate::api::validation::{canonicalize_code, canonicalize_identifier, FieldErrors, RequestBody, MAX_FX_SOURCE_LEN, MAX_IDENTIFIER_LEN, MAX_TEXT_LEN, MAX_URL_LEN};
+use crate::interop::camt::StatementType;
+use crate::services::MAX_GROUP_SIZE;
+use crate::models::{
+    AccountIdentifier, AccountIdentifierType, AccountType, ActivityGranularity, AlertRuleType, CutOffApprovalPolicy, BalanceBasis, BalanceIncidentStatus, BalanceReservationStatus, BankAccountType, CounterpartyListMode, DefaultResolution, DeliveryStatus, FundingStatus,
+    FeeReversalPolicy, InstructionStrategy, InstrumentKind, NettingMode, PvpStatus, PaymentRail, RiskHoldStatus, TransactionPriority, TransactionType, TypeFeePolicy,
+};
 
 /// Request to create a new account.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CreateAccountRequest {
     pub external_id: String,
     pub name: String,
@@ -15,114 +22,1586 @@ pub struct CreateAccountRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
-impl CreateAccountRequest {
-    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
-        let mut errors = Vec::new();
-        if self.external_id.trim().is_empty() {
-            errors.push(ValidationError { field: "external_id".to_string(), message: "external_id cannot be empty".to_string() });
+impl RequestBody for CreateAccountRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.external_id);
+        canonicalize_code(&mut self.currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("external_id", &self.external_id);
+        errors.identifier("name", &self.name);
+        errors.instrument("currency", &self.currency);
+        if let Some(balance) = self.initial_balance {
+            errors.amount("initial_balance", balance);
+        }
+        errors.finish()
+    }
+}
+
+/// Request to allow or deny a counterparty for an account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddCounterpartyRestrictionRequest {
+    pub counterparty_id: Uuid,
+    pub mode: CounterpartyListMode,
+    pub reason: Option<String>,
+}
+
+impl RequestBody for AddCounterpartyRestrictionRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(reason) = &self.reason {
+            errors.max_len("reason", reason, MAX_TEXT_LEN);
+        }
+        errors.finish()
+    }
+}
+
+/// Validation error.
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Request to create a new transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateTransactionRequest {
+    pub external_id: String,
+    pub transaction_type: TransactionType,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub fee_amount: Option<Decimal>,
+    pub idempotency_key: String,
+    pub metadata: Option<serde_json::Value>,
+    /// Release priority; defaults to NORMAL.
+    pub priority: Option<TransactionPriority>,
+    /// Value date of the entries; defaults to today. Past dates must fall in an open
+    /// accounting period.
+    #[serde(default)]
+    pub effective_date: Option<chrono::NaiveDate>,
+    /// Upstream system submitting the transaction. External IDs can be configured to be
+    /// unique per source system.
+    #[serde(default)]
+    pub source_system: Option<String>,
+    /// Registered custom type to post as, e.g. `INTERCHANGE_FEE`; its base type must be
+    /// `transaction_type`.
+    #[serde(default)]
+    pub type_code: Option<String>,
+    /// Settle the transaction gross instead of netting it with its batch.
+    #[serde(default)]
+    pub exclude_from_netting: bool,
+    /// ID of the earlier transaction this one intentionally repeats, as returned with
+    /// `POSSIBLE_DUPLICATE`.
+    #[serde(default)]
+    pub duplicate_override: Option<Uuid>,
+    /// Description shown on statements; defaults to the narrative template of the type.
+    #[serde(default)]
+    pub narrative: Option<String>,
+    /// Counterparty's reference, e.g. an invoice number.
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+impl RequestBody for CreateTransactionRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.external_id);
+        canonicalize_identifier(&mut self.idempotency_key);
+        canonicalize_code(&mut self.currency);
+        if let Some(source) = self.source_system.as_mut() {
+            canonicalize_identifier(source);
+        }
+        if let Some(code) = self.type_code.as_mut() {
+            canonicalize_code(code);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("external_id", &self.external_id);
+        errors.instrument("currency", &self.currency);
+        errors.positive_amount("amount", self.amount);
+        if let Some(fee) = self.fee_amount {
+            errors.non_negative_amount("fee_amount", fee);
+        }
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        if self.source_system.as_ref().is_some_and(|source| source.is_empty() || source.len() > 64) {
+            errors.push("source_system", "source_system must be 1 to 64 characters");
+        }
+        if let Some(code) = &self.type_code {
+            errors.identifier("type_code", code);
+        }
+        if let Some(narrative) = &self.narrative {
+            errors.max_len("narrative", narrative, MAX_TEXT_LEN);
+        }
+        if let Some(reference) = &self.reference {
+            errors.max_len("reference", reference, MAX_IDENTIFIER_LEN);
+        }
+        errors.finish()
+    }
+}
+
+/// Request to change the release priority of a queued transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateTransactionPriorityRequest {
+    pub priority: TransactionPriority,
+}
+
+impl RequestBody for UpdateTransactionPriorityRequest {}
+
+/// Request to reverse a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReverseTransactionRequest {
+    pub reason: String,
+    pub idempotency_key: String,
+    /// Overrides the configured fee reversal policy.
+    #[serde(default)]
+    pub fee_policy: Option<FeeReversalPolicy>,
+}
+
+impl RequestBody for ReverseTransactionRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.idempotency_key);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.text("reason", &self.reason);
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        errors.finish()
+    }
+}
+
+/// Request to refund all or part of a transaction's fee.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeRefundRequest {
+    /// Defaults to the part of the fee not yet refunded.
+    pub amount: Option<Decimal>,
+    pub reason: String,
+    pub idempotency_key: String,
+}
+
+impl RequestBody for FeeRefundRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.idempotency_key);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(amount) = self.amount {
+            errors.positive_amount("amount", amount);
+        }
+        errors.text("reason", &self.reason);
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        errors.finish()
+    }
+}
+
+/// Query parameters for listing transactions.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListTransactionsQuery {
+    pub account_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub currency: Option<String>,
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Query parameters for looking up transactions by external ID.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExternalIdLookupQuery {
+    pub source_system: Option<String>,
+    /// Also list the reversals and fee refunds of the matching transactions.
+    #[serde(default)]
+    pub include_returns: bool,
+}
+
+/// Query parameters for listing batches.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListBatchesQuery {
+    pub status: Option<String>,
+    pub currency: Option<String>,
+    /// Only batches of this named settlement window.
+    pub window_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Query parameters for listing ledger entries, optionally within an effective date
+/// range (both ends inclusive).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListLedgerEntriesQuery {
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request to process a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcessBatchRequest {
+    pub force: Option<bool>,
+    /// Returns once the batch is closed and processes it in the background; follow
+    /// it through the batch's processing progress.
+    #[serde(default)]
+    pub background: bool,
+}
+
+impl RequestBody for ProcessBatchRequest {}
+
+/// Query parameters for streaming batch processing progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgressStreamQuery {
+    /// Milliseconds between progress polls (default 1000, min 100).
+    pub interval_ms: Option<u64>,
+}
+
+/// Request to create an alert rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateAlertRuleRequest {
+    pub account_id: Option<Uuid>,
+    pub rule_type: AlertRuleType,
+    pub currency: String,
+    pub threshold: Option<Decimal>,
+    pub webhook_url: Option<String>,
+    pub suppression_window_seconds: Option<i32>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl RequestBody for CreateAlertRuleRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.currency("currency", &self.currency);
+        if self.rule_type.requires_threshold() && self.threshold.is_none() {
+            errors.push("threshold", "threshold is required for this rule type");
+        }
+        if let Some(threshold) = self.threshold {
+            errors.non_negative_amount("threshold", threshold);
+        }
+        if self.suppression_window_seconds.is_some_and(|s| s < 0) {
+            errors.push("suppression_window_seconds", "suppression_window_seconds cannot be negative");
+        }
+        if let Some(url) = &self.webhook_url {
+            check_webhook_url(&mut errors, url);
+        }
+        errors.finish()
+    }
+}
+
+/// Request to update an alert rule.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateAlertRuleRequest {
+    pub threshold: Option<Decimal>,
+    pub webhook_url: Option<String>,
+    pub suppression_window_seconds: Option<i32>,
+    pub enabled: Option<bool>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl RequestBody for UpdateAlertRuleRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(threshold) = self.threshold {
+            errors.non_negative_amount("threshold", threshold);
+        }
+        if self.suppression_window_seconds.is_some_and(|s| s < 0) {
+            errors.push("suppression_window_seconds", "suppression_window_seconds cannot be negative");
+        }
+        if let Some(url) = self.webhook_url.as_deref().filter(|u| !u.is_empty()) {
+            check_webhook_url(&mut errors, url);
+        }
+        errors.finish()
+    }
+}
+
+/// Alert webhooks must be http(s) URLs.
+fn check_webhook_url(errors: &mut FieldErrors, url: &str) {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        errors.push("webhook_url", "webhook_url must be an http(s) URL");
+    } else {
+        errors.max_len("webhook_url", url, MAX_URL_LEN);
+    }
+}
+
+/// Query parameters for listing alert rules.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListAlertRulesQuery {
+    pub account_id: Option<Uuid>,
+    pub rule_type: Option<AlertRuleType>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request to create a named settlement window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateSettlementWindowRequest {
+    pub name: String,
+    pub currency: String,
+    pub cut_off_time: chrono::NaiveTime,
+    /// IANA timezone the cut-off is expressed in, e.g. `Europe/Berlin`.
+    pub timezone: String,
+}
+
+impl RequestBody for CreateSettlementWindowRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("name", &self.name);
+        errors.currency("currency", &self.currency);
+        errors.identifier("timezone", &self.timezone);
+        errors.finish()
+    }
+}
+
+/// Request to update a settlement window.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateSettlementWindowRequest {
+    pub name: Option<String>,
+    pub cut_off_time: Option<chrono::NaiveTime>,
+    pub timezone: Option<String>,
+    pub active: Option<bool>,
+}
+
+impl RequestBody for UpdateSettlementWindowRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(name) = &self.name {
+            errors.identifier("name", name);
+        }
+        if let Some(timezone) = &self.timezone {
+            errors.identifier("timezone", timezone);
+        }
+        errors.finish()
+    }
+}
+
+/// Query parameters for listing settlement windows.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListSettlementWindowsQuery {
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub active_only: bool,
+}
+
+/// Request to create a batch template. Give either `window_id` or a UTC `cut_off_time`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateBatchTemplateRequest {
+    pub name: String,
+    pub currency: String,
+    pub window_id: Option<Uuid>,
+    pub cut_off_time: Option<chrono::NaiveTime>,
+    pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub netting_mode: NettingMode,
+    /// FOUR_EYES (default) or SINGLE_OPERATOR.
+    #[serde(default)]
+    pub cut_off_approval: CutOffApprovalPolicy,
+}
+
+impl RequestBody for CreateBatchTemplateRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("name", &self.name);
+        errors.currency("currency", &self.currency);
+        errors.finish()
+    }
+}
+
+/// Request to update a batch template.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateBatchTemplateRequest {
+    pub name: Option<String>,
+    pub cut_off_time: Option<chrono::NaiveTime>,
+    pub metadata: Option<serde_json::Value>,
+    pub netting_mode: Option<NettingMode>,
+    pub cut_off_approval: Option<CutOffApprovalPolicy>,
+    pub active: Option<bool>,
+}
+
+impl RequestBody for UpdateBatchTemplateRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(name) = &self.name {
+            errors.identifier("name", name);
+        }
+        errors.finish()
+    }
+}
+
+/// Query parameters for listing batch templates.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListBatchTemplatesQuery {
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub active_only: bool,
+}
+
+/// Request to provision batches from templates. Defaults to tomorrow (UTC).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProvisionBatchesRequest {
+    pub settlement_date: Option<chrono::NaiveDate>,
+}
+
+impl RequestBody for ProvisionBatchesRequest {}
+
+/// Request to route a settled transaction to a named settlement window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AssignTransactionWindowRequest {
+    pub window_id: Uuid,
+}
+
+impl RequestBody for AssignTransactionWindowRequest {}
+
+/// Query parameters for listing the transactions behind a netting position.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PositionTransactionsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// File format of an exported netting advice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdviceFormat {
+    #[default]
+    Pdf,
+    Csv,
+    Json,
+}
+
+/// Query parameters for exporting a netting advice.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExportNettingAdviceQuery {
+    #[serde(default)]
+    pub format: AdviceFormat,
+}
+
+/// Request to move a batched transaction to another open batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReassignTransactionBatchRequest {
+    pub batch_id: Uuid,
+    pub reason: String,
+}
+
+impl RequestBody for ReassignTransactionBatchRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.text("reason", &self.reason);
+        errors.finish()
+    }
+}
+
+/// Query parameters for the daily routing report. Defaults to today (UTC).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RoutingReportQuery {
+    pub date: Option<chrono::NaiveDate>,
+}
+
+/// Query parameters for historical netting reports. Defaults to the last 30 days.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NettingReportQuery {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub currency: Option<String>,
+    /// Only reports of batches in this named settlement window.
+    pub window_id: Option<Uuid>,
+}
+
+/// Format of a downloadable report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Query parameters for the intraday liquidity report. Defaults to today (UTC) and
+/// every participant with flows in the currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntradayLiquidityQuery {
+    pub currency: String,
+    pub date: Option<chrono::NaiveDate>,
+    pub account_id: Option<Uuid>,
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+/// Request to set the metadata JSON Schema for a transaction type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetMetadataSchemaRequest {
+    pub schema: serde_json::Value,
+}
+
+impl RequestBody for SetMetadataSchemaRequest {}
+
+/// Request to register a custom transaction type. Flags left out default to the base
+/// type's behavior.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetTransactionTypeRequest {
+    pub base_type: TransactionType,
+    pub reversible: Option<bool>,
+    pub nettable: Option<bool>,
+    pub requires_original: Option<bool>,
+    pub fee_policy: Option<TypeFeePolicy>,
+    pub description: Option<String>,
+    /// Narrative of the type's transactions when the request gives none, e.g.
+    /// `Interchange fee {reference}`.
+    #[serde(default)]
+    pub narrative_template: Option<String>,
+}
+
+impl RequestBody for SetTransactionTypeRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(description) = &self.description {
+            errors.max_len("description", description, MAX_TEXT_LEN);
+        }
+        if let Some(template) = &self.narrative_template {
+            errors.max_len("narrative_template", template, MAX_TEXT_LEN);
+        }
+        errors.finish()
+    }
+}
+
+/// Request to settle several transactions together, all or nothing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateTransactionGroupRequest {
+    /// Idempotency key of the group; each transaction also carries its own.
+    pub idempotency_key: String,
+    pub transactions: Vec<CreateTransactionRequest>,
+}
+
+impl RequestBody for CreateTransactionGroupRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.idempotency_key);
+        for transaction in &mut self.transactions {
+            transaction.canonicalize();
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        if self.transactions.is_empty() || self.transactions.len() > MAX_GROUP_SIZE {
+            errors.push(
+                "transactions",
+                format!("transactions must have between 1 and {} entries", MAX_GROUP_SIZE),
+            );
+        }
+        for (index, transaction) in self.transactions.iter().enumerate() {
+            errors.nested(&format!("transactions[{}]", index), transaction.validate());
+        }
+        errors.finish()
+    }
+}
+
+/// Request to settle two opposite payments in different currencies
+/// payment-versus-payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreatePvpRequest {
+    pub external_id: String,
+    pub idempotency_key: String,
+    pub first_source_account_id: Uuid,
+    pub first_destination_account_id: Uuid,
+    pub first_amount: Decimal,
+    pub first_currency: String,
+    pub second_source_account_id: Uuid,
+    pub second_destination_account_id: Uuid,
+    pub second_amount: Decimal,
+    pub second_currency: String,
+    /// Seconds to wait for both payers to be funded before cancelling.
+    pub timeout_secs: Option<u64>,
+}
+
+impl RequestBody for CreatePvpRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.external_id);
+        canonicalize_identifier(&mut self.idempotency_key);
+        canonicalize_code(&mut self.first_currency);
+        canonicalize_code(&mut self.second_currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("external_id", &self.external_id);
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        errors.positive_amount("first_amount", self.first_amount);
+        errors.currency("first_currency", &self.first_currency);
+        errors.positive_amount("second_amount", self.second_amount);
+        errors.currency("second_currency", &self.second_currency);
+        if self.first_currency == self.second_currency {
+            errors.push("second_currency", "second_currency must differ from first_currency");
+        }
+        if self.timeout_secs == Some(0) {
+            errors.push("timeout_secs", "timeout_secs must be positive");
+        }
+        errors.finish()
+    }
+}
+
+/// Request to record an FX rate received from its source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordFxRateRequest {
+    pub base_currency: String,
+    pub quote_currency: String,
+    /// Units of the quote currency one unit of the base currency buys.
+    pub rate: Decimal,
+    /// Fraction of the rate kept on conversions; none by default.
+    #[serde(default)]
+    pub spread: Option<Decimal>,
+    pub source: String,
+    /// When the rate takes effect; now by default.
+    #[serde(default)]
+    pub effective_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RequestBody for RecordFxRateRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.base_currency);
+        canonicalize_code(&mut self.quote_currency);
+        canonicalize_identifier(&mut self.source);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.currency("base_currency", &self.base_currency);
+        errors.currency("quote_currency", &self.quote_currency);
+        if self.base_currency == self.quote_currency {
+            errors.push("quote_currency", "quote_currency must differ from base_currency");
+        }
+        errors.fx_rate("rate", self.rate);
+        if let Some(spread) = self.spread {
+            errors.spread("spread", spread);
+        }
+        errors.identifier("source", &self.source);
+        errors.max_len("source", &self.source, MAX_FX_SOURCE_LEN);
+        errors.finish()
+    }
+}
+
+/// Query parameters for auditing stored FX rates.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListFxRatesQuery {
+    /// Currency pair, e.g. `EURUSD` or `EUR/USD`.
+    pub pair: Option<String>,
+    /// Only rates effective on this UTC date.
+    pub date: Option<chrono::NaiveDate>,
+}
+
+/// Request to correct a stored FX rate with effect from the same time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorrectFxRateRequest {
+    pub rate: Decimal,
+    /// The corrected rate's spread unless given.
+    #[serde(default)]
+    pub spread: Option<Decimal>,
+    /// The corrected rate's source unless given.
+    #[serde(default)]
+    pub source: Option<String>,
+    pub reason: String,
+}
+
+impl RequestBody for CorrectFxRateRequest {
+    fn canonicalize(&mut self) {
+        if let Some(source) = &mut self.source {
+            canonicalize_identifier(source);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.fx_rate("rate", self.rate);
+        if let Some(spread) = self.spread {
+            errors.spread("spread", spread);
+        }
+        if let Some(source) = &self.source {
+            errors.identifier("source", source);
+            errors.max_len("source", source, MAX_FX_SOURCE_LEN);
+        }
+        errors.text("reason", &self.reason);
+        errors.finish()
+    }
+}
+
+/// Request to convert funds between accounts in two currencies at the current rate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateFxConversionRequest {
+    pub external_id: String,
+    pub idempotency_key: String,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    /// Amount debited from the source account, in the source currency.
+    pub amount: Decimal,
+    pub source_currency: String,
+    pub destination_currency: String,
+}
+
+impl RequestBody for CreateFxConversionRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.external_id);
+        canonicalize_identifier(&mut self.idempotency_key);
+        canonicalize_code(&mut self.source_currency);
+        canonicalize_code(&mut self.destination_currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("external_id", &self.external_id);
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        errors.positive_amount("amount", self.amount);
+        errors.currency("source_currency", &self.source_currency);
+        errors.currency("destination_currency", &self.destination_currency);
+        if self.source_currency == self.destination_currency {
+            errors.push("destination_currency", "destination_currency must differ from source_currency");
+        }
+        if self.source_account_id == self.destination_account_id {
+            errors.push("destination_account_id", "destination_account_id must differ from source_account_id");
+        }
+        errors.finish()
+    }
+}
+
+/// Request to revalue foreign-currency balances at the end of a period.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RevalueFxRequest {
+    /// Last day of the period; the last month that has ended unless given.
+    #[serde(default)]
+    pub period_end: Option<chrono::NaiveDate>,
+}
+
+impl RequestBody for RevalueFxRequest {}
+
+/// Query parameters for listing FX revaluation runs.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListFxRevaluationsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Query parameters for listing PvP settlements.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListPvpSettlementsQuery {
+    pub status: Option<PvpStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request to cancel a PvP settlement awaiting funding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CancelPvpRequest {
+    pub reason: String,
+}
+
+impl RequestBody for CancelPvpRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.text("reason", &self.reason);
+        errors.finish()
+    }
+}
+
+/// Request to register a security that accounts can hold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegisterInstrumentRequest {
+    /// Code of the security, e.g. its ISIN.
+    pub code: String,
+    /// Scheme of the code, e.g. `ISIN` or `CUSIP`.
+    pub identifier_type: Option<String>,
+    /// Decimal places quantities can have; whole units by default.
+    #[serde(default)]
+    pub scale: i16,
+    pub description: Option<String>,
+}
+
+impl RequestBody for RegisterInstrumentRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.code);
+        if let Some(identifier_type) = self.identifier_type.as_mut() {
+            canonicalize_code(identifier_type);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.instrument("code", &self.code);
+        if let Some(identifier_type) = &self.identifier_type {
+            errors.max_len("identifier_type", identifier_type, 16);
+        }
+        if let Some(description) = &self.description {
+            errors.max_len("description", description, MAX_TEXT_LEN);
+        }
+        errors.finish()
+    }
+}
+
+/// Query parameters for listing instruments.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListInstrumentsQuery {
+    pub kind: Option<InstrumentKind>,
+}
+
+/// Request to settle a securities trade delivery-versus-payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettleDvpRequest {
+    pub external_id: String,
+    pub idempotency_key: String,
+    /// Registered security delivered and its quantity.
+    pub instrument: String,
+    pub quantity: Decimal,
+    pub seller_securities_account_id: Uuid,
+    pub buyer_securities_account_id: Uuid,
+    /// Cash the buyer pays for the delivery.
+    pub amount: Decimal,
+    pub currency: String,
+    pub buyer_cash_account_id: Uuid,
+    pub seller_cash_account_id: Uuid,
+    pub reference: Option<String>,
+}
+
+impl RequestBody for SettleDvpRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.external_id);
+        canonicalize_identifier(&mut self.idempotency_key);
+        canonicalize_code(&mut self.instrument);
+        canonicalize_code(&mut self.currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("external_id", &self.external_id);
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        errors.instrument("instrument", &self.instrument);
+        errors.positive_amount("quantity", self.quantity);
+        errors.currency("currency", &self.currency);
+        errors.positive_amount("amount", self.amount);
+        if let Some(reference) = &self.reference {
+            errors.max_len("reference", reference, MAX_IDENTIFIER_LEN);
+        }
+        errors.finish()
+    }
+}
+
+/// Request to set the bank details an account settles to in a currency. What is
+/// required depends on the rail: ACH needs a routing and account number, SEPA an IBAN,
+/// SWIFT a BIC and an IBAN or account number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetSettlementProfileRequest {
+    pub account_name: String,
+    pub routing_number: Option<String>,
+    pub bank_account_number: Option<String>,
+    #[serde(default)]
+    pub bank_account_type: BankAccountType,
+    pub bic: Option<String>,
+    pub iban: Option<String>,
+    #[serde(default)]
+    pub rail: PaymentRail,
+    /// Overrides the settlement window's cut-off for this participant.
+    pub cut_off_time: Option<chrono::NaiveTime>,
+}
+
+impl RequestBody for SetSettlementProfileRequest {
+    fn canonicalize(&mut self) {
+        for code in [&mut self.bic, &mut self.iban].into_iter().flatten() {
+            code.retain(|c| !c.is_whitespace());
+            code.make_ascii_uppercase();
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("account_name", &self.account_name);
+        for (field, value) in [
+            ("routing_number", &self.routing_number),
+            ("bank_account_number", &self.bank_account_number),
+            ("bic", &self.bic),
+            ("iban", &self.iban),
+        ] {
+            if let Some(value) = value {
+                errors.max_len(field, value, MAX_IDENTIFIER_LEN);
+            }
+        }
+        errors.finish()
+    }
+}
+
+/// Request to assign a secondary identifier (IBAN, internal code or alias) to an
+/// account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddAccountIdentifierRequest {
+    pub identifier_type: AccountIdentifierType,
+    pub identifier: String,
+}
+
+impl RequestBody for AddAccountIdentifierRequest {
+    fn canonicalize(&mut self) {
+        self.identifier = AccountIdentifier::normalize(&self.identifier);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("identifier", &self.identifier);
+        errors.finish()
+    }
+}
+
+/// Query parameters for finding an account by a secondary identifier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountLookupQuery {
+    pub identifier: String,
+}
+
+/// Query parameters for listing the chart of internal accounts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListInternalAccountsQuery {
+    pub currency: Option<String>,
+}
+
+/// Query parameters for the internal account provisioning audit trail.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InternalAccountProvisioningQuery {
+    pub currency: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Request to irreversibly anonymize a closed account's personal data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnonymizeAccountRequest {
+    pub requested_by: String,
+    pub reason: String,
+}
+
+impl RequestBody for AnonymizeAccountRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("requested_by", &self.requested_by);
+        errors.text("reason", &self.reason);
+        errors.finish()
+    }
+}
+
+/// Payment file format for exported settlement instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Nacha,
+    /// ISO 20022 pacs.008 credit transfers for SEPA and SWIFT participants.
+    Pacs008,
+}
+
+/// Query parameters for exporting a batch's settlement instructions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportInstructionsQuery {
+    pub format: ExportFormat,
+}
+
+/// Query parameters for downloading an account statement. Defaults to today (UTC)
+/// and the account's currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementQuery {
+    #[serde(rename = "type")]
+    pub statement_type: StatementType,
+    pub date: Option<chrono::NaiveDate>,
+    pub currency: Option<String>,
+}
+
+/// Request to push an account statement to a webhook.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeliverStatementRequest {
+    #[serde(rename = "type")]
+    pub statement_type: StatementType,
+    pub date: Option<chrono::NaiveDate>,
+    pub currency: Option<String>,
+    pub webhook_url: String,
+}
+
+impl RequestBody for DeliverStatementRequest {
+    fn canonicalize(&mut self) {
+        if let Some(currency) = self.currency.as_mut() {
+            canonicalize_code(currency);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(currency) = &self.currency {
+            errors.currency("currency", currency);
         }
-        if self.name.trim().is_empty() {
-            errors.push(ValidationError { field: "name".to_string(), message: "name cannot be empty".to_string() });
+        errors.identifier("webhook_url", &self.webhook_url);
+        errors.max_len("webhook_url", &self.webhook_url, MAX_URL_LEN);
+        errors.finish()
+    }
+}
+
+/// Generated file to queue for delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum DeliverySource {
+    /// NACHA file of a completed batch's settlement instructions.
+    Nacha { batch_id: Uuid },
+    /// pacs.008 message of a completed batch's settlement instructions.
+    Pacs008 { batch_id: Uuid },
+    /// camt.053/camt.052 account statement; defaults as for statement downloads.
+    Statement {
+        account_id: Uuid,
+        statement_type: StatementType,
+        date: Option<chrono::NaiveDate>,
+        currency: Option<String>,
+    },
+}
+
+/// Request to deliver a generated file to a configured destination.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateDeliveryRequest {
+    pub destination: String,
+    pub source: DeliverySource,
+    /// Deliver no earlier than this time; immediately when omitted.
+    pub scheduled_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RequestBody for CreateDeliveryRequest {
+    fn canonicalize(&mut self) {
+        if let DeliverySource::Statement { currency: Some(currency), .. } = &mut self.source {
+            canonicalize_code(currency);
         }
-        if self.currency.len() != 3 {
-            errors.push(ValidationError { field: "currency".to_string(), message: "currency must be a 3-letter ISO 4217 code".to_string() });
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("destination", &self.destination);
+        if let DeliverySource::Statement { currency: Some(currency), .. } = &self.source {
+            errors.currency("source.currency", currency);
         }
-        if errors.is_empty() { Ok(()) } else { Err(errors) }
+        errors.finish()
     }
 }
 
-/// Validation error.
-#[derive(Debug, Clone)]
-pub struct ValidationError {
-    pub field: String,
-    pub message: String,
+/// Query parameters for listing file deliveries.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListDeliveriesQuery {
+    pub status: Option<DeliveryStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
-/// Request to create a new transaction.
+/// Query parameters for listing funding obligations.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListFundingObligationsQuery {
+    pub status: Option<FundingStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Query parameters for listing balance breaks. Only open breaks are listed unless
+/// `include_resolved` is set.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListBalanceBreaksQuery {
+    pub account_id: Option<Uuid>,
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub include_resolved: bool,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Query parameters for listing balance incidents.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListBalanceIncidentsQuery {
+    pub status: Option<BalanceIncidentStatus>,
+    pub account_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request to resolve a balance incident.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CreateTransactionRequest {
-    pub external_id: String,
-    pub transaction_type: TransactionType,
-    pub source_account_id: Uuid,
-    pub destination_account_id: Uuid,
-    pub amount: Decimal,
+#[serde(deny_unknown_fields)]
+pub struct ResolveBalanceIncidentRequest {
+    pub note: String,
+}
+
+impl RequestBody for ResolveBalanceIncidentRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.text("note", &self.note);
+        errors.finish()
+    }
+}
+
+/// Request to set the lowest available balance an account may hold in a currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetBalanceFloorRequest {
+    pub currency: String,
+    pub floor: Decimal,
+}
+
+impl RequestBody for SetBalanceFloorRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.currency("currency", &self.currency);
+        errors.amount("floor", self.floor);
+        errors.finish()
+    }
+}
+
+/// Request to cap an account's net debit within a batch of a currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetNetDebitCapRequest {
+    pub currency: String,
+    pub cap: Decimal,
+}
+
+impl RequestBody for SetNetDebitCapRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.currency);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.currency("currency", &self.currency);
+        errors.non_negative_amount("cap", self.cap);
+        errors.finish()
+    }
+}
+
+/// Request to reserve funds on an account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateReservationRequest {
     pub currency: String,
+    pub amount: Decimal,
+    pub name: String,
+    /// Client reference, unique among the account's reservations.
+    pub reference: String,
+    /// When the reservation is released if still active; never if unset.
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RequestBody for CreateReservationRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_code(&mut self.currency);
+        canonicalize_identifier(&mut self.reference);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.currency("currency", &self.currency);
+        errors.positive_amount("amount", self.amount);
+        errors.identifier("name", &self.name);
+        errors.identifier("reference", &self.reference);
+        errors.finish()
+    }
+}
+
+/// Query parameters for listing an account's reservations.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListReservationsQuery {
+    pub status: Option<BalanceReservationStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request to capture a reservation into a payment from the reserved account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CaptureReservationRequest {
+    pub destination_account_id: Uuid,
+    /// Amount to pay, up to the reserved amount; defaults to all of it.
+    #[serde(default)]
+    pub amount: Option<Decimal>,
     pub fee_amount: Option<Decimal>,
+    pub external_id: String,
     pub idempotency_key: String,
     pub metadata: Option<serde_json::Value>,
 }
 
-impl CreateTransactionRequest {
-    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
-        let mut errors = Vec::new();
-        if self.external_id.trim().is_empty() {
-            errors.push(ValidationError { field: "external_id".to_string(), message: "external_id cannot be empty".to_string() });
+impl RequestBody for CaptureReservationRequest {
+    fn canonicalize(&mut self) {
+        canonicalize_identifier(&mut self.external_id);
+        canonicalize_identifier(&mut self.idempotency_key);
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(amount) = self.amount {
+            errors.positive_amount("amount", amount);
+        }
+        if let Some(fee) = self.fee_amount {
+            errors.non_negative_amount("fee_amount", fee);
         }
-        if self.currency.len() != 3 {
-            errors.push(ValidationError { field: "currency".to_string(), message: "currency must be a 3-letter ISO 4217 code".to_string() });
+        errors.identifier("external_id", &self.external_id);
+        errors.identifier("idempotency_key", &self.idempotency_key);
+        errors.finish()
+    }
+}
+
+/// Query parameters for listing transactions held for risk review.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListRiskHoldsQuery {
+    pub status: Option<RiskHoldStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request to amend a transaction that is still queued or held, before it is posted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AmendTransactionRequest {
+    #[serde(default)]
+    pub amount: Option<Decimal>,
+    #[serde(default)]
+    pub effective_date: Option<chrono::NaiveDate>,
+    /// Replaces the transaction's metadata.
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    pub amended_by: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl RequestBody for AmendTransactionRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if self.amount.is_none() && self.effective_date.is_none() && self.metadata.is_none() {
+            errors.push("body", "amount, effective_date or metadata must be given");
         }
-        if self.amount <= Decimal::ZERO {
-            errors.push(ValidationError { field: "amount".to_string(), message: "amount must be positive".to_string() });
+        if let Some(amount) = self.amount {
+            errors.positive_amount("amount", amount);
         }
-        if self.idempotency_key.trim().is_empty() {
-            errors.push(ValidationError { field: "idempotency_key".to_string(), message: "idempotency_key cannot be empty".to_string() });
+        errors.identifier("amended_by", &self.amended_by);
+        if let Some(reason) = &self.reason {
+            errors.max_len("reason", reason, MAX_TEXT_LEN);
         }
-        if errors.is_empty() { Ok(()) } else { Err(errors) }
+        errors.finish()
     }
 }
 
-/// Request to reverse a transaction.
+/// Request to release or reject a transaction held for risk review.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ReverseTransactionRequest {
+#[serde(deny_unknown_fields)]
+pub struct ReviewRiskHoldRequest {
+    pub reviewed_by: String,
     pub reason: String,
-    pub idempotency_key: String,
 }
 
-impl ReverseTransactionRequest {
-    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
-        let mut errors = Vec::new();
-        if self.reason.trim().is_empty() {
-            errors.push(ValidationError { field: "reason".to_string(), message: "reason cannot be empty".to_string() });
+impl RequestBody for ReviewRiskHoldRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("reviewed_by", &self.reviewed_by);
+        errors.text("reason", &self.reason);
+        errors.finish()
+    }
+}
+
+/// Request to change how a pending batch is netted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetNettingModeRequest {
+    /// MULTILATERAL or BILATERAL.
+    pub netting_mode: NettingMode,
+}
+
+impl RequestBody for SetNettingModeRequest {}
+
+/// Request to choose how a pending batch's positions become settlement instructions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetInstructionStrategyRequest {
+    /// GREEDY, MINIMUM_COUNT or CONTROL_ACCOUNT; null reverts to the configured strategy.
+    pub instruction_strategy: Option<InstructionStrategy>,
+}
+
+impl RequestBody for SetInstructionStrategyRequest {}
+
+/// Request to move a pending batch's cut-off. Needs a second operator's approval unless
+/// the batch allows single-operator changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MoveCutOffRequest {
+    pub cut_off_time: chrono::DateTime<chrono::Utc>,
+    pub requested_by: String,
+    pub approved_by: String,
+    pub reason: String,
+}
+
+impl RequestBody for MoveCutOffRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("requested_by", &self.requested_by);
+        errors.identifier("approved_by", &self.approved_by);
+        errors.text("reason", &self.reason);
+        errors.finish()
+    }
+}
+
+/// Request to close a pending batch before its cut-off. Needs a second operator's approval
+/// unless the batch allows single-operator changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CloseBatchEarlyRequest {
+    pub requested_by: String,
+    pub approved_by: String,
+    pub reason: String,
+}
+
+impl RequestBody for CloseBatchEarlyRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        errors.identifier("requested_by", &self.requested_by);
+        errors.identifier("approved_by", &self.approved_by);
+        errors.text("reason", &self.reason);
+        errors.finish()
+    }
+}
+
+/// Request to declare a participant in default in a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeclareDefaultRequest {
+    pub participant_id: Uuid,
+    /// EXCLUDE or LOSS_SHARING; the configured resolution if absent.
+    pub resolution: Option<DefaultResolution>,
+    pub reason: Option<String>,
+}
+
+impl RequestBody for DeclareDefaultRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(reason) = &self.reason {
+            errors.max_len("reason", reason, MAX_TEXT_LEN);
+        }
+        errors.finish()
+    }
+}
+
+/// Request to post a closed day to the general ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateGlPostingRunRequest {
+    pub posting_date: chrono::NaiveDate,
+}
+
+impl RequestBody for CreateGlPostingRunRequest {}
+
+/// File format of an exported GL journal or invoice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// Query parameters for exporting a GL journal.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExportGlJournalQuery {
+    #[serde(default)]
+    pub format: JournalFormat,
+}
+
+/// Request to set how an account's monthly volume in a currency is invoiced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetInvoiceContractRequest {
+    #[serde(default)]
+    pub free_transactions: i32,
+    #[serde(default)]
+    pub per_transaction_price: Decimal,
+    #[serde(default)]
+    pub volume_rate: Decimal,
+    pub monthly_cap: Option<Decimal>,
+}
+
+impl RequestBody for SetInvoiceContractRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if self.free_transactions < 0 {
+            errors.push("free_transactions", "free_transactions cannot be negative");
+        }
+        errors.non_negative_amount("per_transaction_price", self.per_transaction_price);
+        if self.volume_rate < Decimal::ZERO {
+            errors.push("volume_rate", "volume_rate cannot be negative");
         }
-        if self.idempotency_key.trim().is_empty() {
-            errors.push(ValidationError { field: "idempotency_key".to_string(), message: "idempotency_key cannot be empty".to_string() });
+        if let Some(cap) = self.monthly_cap {
+            errors.non_negative_amount("monthly_cap", cap);
         }
-        if errors.is_empty() { Ok(()) } else { Err(errors) }
+        errors.finish()
     }
 }
 
-/// Query parameters for listing transactions.
+/// Request to invoice the month containing a date, optionally in one currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateInvoiceRunRequest {
+    pub month: chrono::NaiveDate,
+    pub currency: Option<String>,
+}
+
+impl RequestBody for CreateInvoiceRunRequest {
+    fn canonicalize(&mut self) {
+        if let Some(currency) = self.currency.as_mut() {
+            canonicalize_code(currency);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = FieldErrors::new();
+        if let Some(currency) = &self.currency {
+            errors.currency("currency", currency);
+        }
+        errors.finish()
+    }
+}
+
+/// Query parameters for listing invoices. `month` is any date in the month.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ListTransactionsQuery {
+pub struct ListInvoicesQuery {
     pub account_id: Option<Uuid>,
-    pub status: Option<String>,
-    pub currency: Option<String>,
-    pub from_date: Option<String>,
-    pub to_date: Option<String>,
+    pub month: Option<chrono::NaiveDate>,
+}
+
+/// Query parameters for exporting an invoice.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExportInvoiceQuery {
+    #[serde(default)]
+    pub format: JournalFormat,
+}
+
+/// Query parameters for listing GL posting runs.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ListGlPostingRunsQuery {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
-/// Query parameters for listing batches.
+/// Query parameters for a batch's fee report, optionally narrowed to one payer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeeReportQuery {
+    pub account_id: Option<Uuid>,
+}
+
+/// Query parameters for an account's balance at the end of a day. Currency defaults to
+/// the account's currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceAsOfQuery {
+    pub date: chrono::NaiveDate,
+    #[serde(default)]
+    pub basis: BalanceBasis,
+    pub currency: Option<String>,
+}
+
+/// Query parameters for an account's projected balance.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ListBatchesQuery {
-    pub status: Option<String>,
+pub struct ProjectedBalanceQuery {
     pub currency: Option<String>,
+}
+
+/// Request to change how often a background job runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetJobIntervalRequest {
+    pub interval_secs: u64,
+}
+
+impl RequestBody for SetJobIntervalRequest {}
+
+/// Query parameters for the account and transaction change feeds. Without a token the
+/// feed starts with a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyncQuery {
+    pub token: Option<String>,
     pub limit: Option<i64>,
-    pub offset: Option<i64>,
 }
 
-/// Query parameters for listing ledger entries.
+/// Query parameters for an account's daily closing balances. Dates are inclusive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceHistoryQuery {
+    pub from: chrono::NaiveDate,
+    pub to: chrono::NaiveDate,
+    #[serde(default)]
+    pub basis: BalanceBasis,
+    pub currency: Option<String>,
+}
+
+/// Query parameters for explaining an account's balance change over a period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceExplainQuery {
+    pub from: chrono::NaiveDate,
+    pub to: chrono::NaiveDate,
+    #[serde(default)]
+    pub basis: BalanceBasis,
+    pub currency: Option<String>,
+}
+
+/// Query parameters for listing accounting periods.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ListLedgerEntriesQuery {
+pub struct ListAccountingPeriodsQuery {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
-/// Request to process a batch.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProcessBatchRequest {
-    pub force: Option<bool>,
+/// Query parameters for an account's activity summary. Dates are inclusive.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AccountActivityQuery {
+    #[serde(default)]
+    pub granularity: ActivityGranularity,
+    pub currency: Option<String>,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
 }
 
 #[cfg(test)]
@@ -165,6 +1644,14 @@ mod tests {
             fee_amount: Some(dec!(1.00)),
             idempotency_key: "key123".to_string(),
             metadata: None,
+            priority: None,
+            effective_date: None,
+            source_system: None,
+            type_code: None,
+            exclude_from_netting: false,
+            duplicate_override: None,
+            narrative: None,
+            reference: None,
         };
         assert!(valid_request.validate().is_ok());
 
@@ -178,7 +1665,96 @@ mod tests {
             fee_amount: None,
             idempotency_key: "key123".to_string(),
             metadata: None,
+            priority: None,
+            effective_date: None,
+            source_system: None,
+            type_code: None,
+            exclude_from_netting: false,
+            duplicate_override: None,
+            narrative: None,
+            reference: None,
         };
         assert!(invalid_currency.validate().is_err());
     }
+
+    #[test]
+    fn test_create_alert_rule_request_validation() {
+        let valid_request = CreateAlertRuleRequest {
+            account_id: None,
+            rule_type: AlertRuleType::LowBalance,
+            currency: "USD".to_string(),
+            threshold: Some(dec!(100.00)),
+            webhook_url: Some("https://hooks.example.com/alerts".to_string()),
+            suppression_window_seconds: Some(300),
+            metadata: None,
+        };
+        assert!(valid_request.validate().is_ok());
+
+        let missing_threshold = CreateAlertRuleRequest { threshold: None, ..valid_request.clone() };
+        assert!(missing_threshold.validate().is_err());
+
+        let failed_settlement = CreateAlertRuleRequest {
+            rule_type: AlertRuleType::FailedSettlement,
+            threshold: None,
+            ..valid_request.clone()
+        };
+        assert!(failed_settlement.validate().is_ok());
+
+        let bad_url = CreateAlertRuleRequest { webhook_url: Some("ftp://example.com".to_string()), ..valid_request };
+        assert!(bad_url.validate().is_err());
+    }
+
+    #[test]
+    fn test_create_transaction_request_canonicalization() {
+        let body = serde_json::json!({
+            "external_id": " TXN001 ",
+            "transaction_type": "PAYMENT",
+            "source_account_id": Uuid::new_v4(),
+            "destination_account_id": Uuid::new_v4(),
+            "amount": "100.00",
+            "currency": "usd",
+            "idempotency_key": "key123\n",
+            "type_code": "interchange_fee"
+        });
+        let mut request: CreateTransactionRequest = serde_json::from_value(body.clone()).unwrap();
+        request.canonicalize();
+        assert_eq!(request.external_id, "TXN001");
+        assert_eq!(request.currency, "USD");
+        assert_eq!(request.idempotency_key, "key123");
+        assert_eq!(request.type_code.as_deref(), Some("INTERCHANGE_FEE"));
+        assert!(request.validate().is_ok());
+
+        let mut misspelled = body;
+        misspelled["fee_amuont"] = serde_json::json!("1.00");
+        assert!(serde_json::from_value::<CreateTransactionRequest>(misspelled).is_err());
+
+        let too_precise = CreateTransactionRequest { amount: dec!(100.00001), ..request.clone() };
+        let errors = too_precise.validate().unwrap_err();
+        assert_eq!(errors[0].field, "amount");
+        let too_large = CreateTransactionRequest { amount: dec!(1000000000000000), ..request };
+        assert!(too_large.validate().is_err());
+    }
+
+    #[test]
+    fn test_record_fx_rate_request_validation() {
+        let body = serde_json::json!({
+            "base_currency": "eur",
+            "quote_currency": "usd",
+            "rate": "1.0850",
+            "spread": "0.002",
+            "source": " ECB "
+        });
+        let mut request: RecordFxRateRequest = serde_json::from_value(body).unwrap();
+        request.canonicalize();
+        assert_eq!(request.base_currency, "EUR");
+        assert_eq!(request.source, "ECB");
+        assert!(request.validate().is_ok());
+
+        let same_pair = RecordFxRateRequest { quote_currency: "EUR".to_string(), ..request.clone() };
+        assert_eq!(same_pair.validate().unwrap_err()[0].field, "quote_currency");
+        let whole_spread = RecordFxRateRequest { spread: Some(dec!(1)), ..request.clone() };
+        assert_eq!(whole_spread.validate().unwrap_err()[0].field, "spread");
+        let too_precise = RecordFxRateRequest { rate: dec!(1.00000000001), ..request };
+        assert_eq!(too_precise.validate().unwrap_err()[0].field, "rate");
+    }
 }
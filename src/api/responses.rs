@@ -3,10 +3,18 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::error::AppError;
 use crate::models::{
-    Account, AccountBalance, AccountStatus, AccountType, BatchStatus, LedgerEntry,
-    SettlementBatch, TransactionRecord, TransactionStatus, TransactionType,
+    Account, AccountAnonymization, AccountHistory, LedgerHistoryEntry, AccountingPeriod, BalanceBasis, DatedBalance, PeriodStatus, AccountBalance, ActivityGranularity, ActivityPeriod, ActivityTypeSummary, AccountStatus, BalanceBreak, BalanceFloor, BalanceProjection, BalanceProjectionLag, BalanceIncident, BalanceIncidentStatus, BalanceReservation, BalanceReservationStatus, NetDebitCap, AccountStatusChange, AccountType, AlertRule,
+    AlertRuleType, BatchProcessingProgress, BatchReconciliation, BatchReconciliationBreak, BatchStatus, CounterpartyAuditAction, CutOffApprovalPolicy, CounterpartyListMode,
+    CounterpartyRestriction, CounterpartyRestrictionAudit, DeliveryStatus, FileDelivery,
+    FinalityRecord, GlJournalLine, GlPostingRun, InstructionStrategy, IntradayLiquidityReport, LedgerEntry, LiquidityFlow, NettingMode, NettingReportRecord,
+    BankAccountType, MetadataSchema, PaymentRail, RiskHold, RiskHoldAudit, RiskHoldStatus, RiskTrigger, SettlementBatch, SettlementProfile, SettlementRoute, SettlementWindow, StatusReasonCode, TransactionPriority, TransactionRecord,
+    SubmissionStatus, TransactionGroupStatus, TransactionStatus, TransactionSubmission, TransactionType,
 };
+use crate::interop::camt::{Statement, StatementType};
+use crate::interop::gl::GlJournal;
+use crate::services::{RoutingReport, SyncPage, TransactionGroupDetail};
 
 /// Standard API response wrapper.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +48,9 @@ pub struct ErrorResponse {
     pub code: String,
     pub message: String,
     pub details: Option<Vec<ValidationErrorDetail>>,
+    /// Whether the same request may succeed if sent again.
+    #[serde(default)]
+    pub retryable: bool,
 }
 
 impl ErrorResponse {
@@ -48,6 +59,7 @@ impl ErrorResponse {
             code: code.into(),
             message: message.into(),
             details: None,
+            retryable: false,
         }
     }
 
@@ -57,6 +69,15 @@ impl ErrorResponse {
     }
 }
 
+impl From<&AppError> for ErrorResponse {
+    fn from(error: &AppError) -> Self {
+        Self {
+            retryable: error.is_retryable(),
+            ..Self::new(error.code(), error.public_message())
+        }
+    }
+}
+
 /// Validation error detail.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationErrorDetail {
@@ -91,6 +112,7 @@ pub struct AccountResponse {
     pub status: AccountStatus,
     pub currency: String,
     pub metadata: Option<serde_json::Value>,
+    pub anonymized_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -105,12 +127,109 @@ impl From<Account> for AccountResponse {
             status: account.status,
             currency: account.currency,
             metadata: account.metadata,
+            anonymized_at: account.anonymized_at,
             created_at: account.created_at,
             updated_at: account.updated_at,
         }
     }
 }
 
+/// Account anonymization audit response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountAnonymizationResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub requested_by: String,
+    pub reason: String,
+    pub scrubbed_fields: Vec<String>,
+    pub anonymized_at: DateTime<Utc>,
+}
+
+impl From<AccountAnonymization> for AccountAnonymizationResponse {
+    fn from(anonymization: AccountAnonymization) -> Self {
+        Self {
+            id: anonymization.id,
+            account_id: anonymization.account_id,
+            requested_by: anonymization.requested_by,
+            reason: anonymization.reason,
+            scrubbed_fields: anonymization.scrubbed_fields,
+            anonymized_at: anonymization.anonymized_at,
+        }
+    }
+}
+
+/// Account status change response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountStatusChangeResponse {
+    pub id: Uuid,
+    pub from_status: AccountStatus,
+    pub to_status: AccountStatus,
+    pub reason_code: StatusReasonCode,
+    pub note: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl From<AccountStatusChange> for AccountStatusChangeResponse {
+    fn from(change: AccountStatusChange) -> Self {
+        Self {
+            id: change.id,
+            from_status: change.from_status,
+            to_status: change.to_status,
+            reason_code: change.reason_code,
+            note: change.note,
+            changed_at: change.changed_at,
+        }
+    }
+}
+
+/// Counterparty restriction response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CounterpartyRestrictionResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub counterparty_id: Uuid,
+    pub mode: CounterpartyListMode,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<CounterpartyRestriction> for CounterpartyRestrictionResponse {
+    fn from(restriction: CounterpartyRestriction) -> Self {
+        Self {
+            id: restriction.id,
+            account_id: restriction.account_id,
+            counterparty_id: restriction.counterparty_id,
+            mode: restriction.mode,
+            reason: restriction.reason,
+            created_at: restriction.created_at,
+        }
+    }
+}
+
+/// Counterparty restriction audit entry response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CounterpartyAuditResponse {
+    pub id: Uuid,
+    pub counterparty_id: Uuid,
+    pub mode: CounterpartyListMode,
+    pub action: CounterpartyAuditAction,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl From<CounterpartyRestrictionAudit> for CounterpartyAuditResponse {
+    fn from(entry: CounterpartyRestrictionAudit) -> Self {
+        Self {
+            id: entry.id,
+            counterparty_id: entry.counterparty_id,
+            mode: entry.mode,
+            action: entry.action,
+            reason: entry.reason,
+            changed_at: entry.changed_at,
+        }
+    }
+}
+
 /// Balance response DTO.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BalanceResponse {
@@ -154,6 +273,15 @@ pub struct TransactionResponse {
     pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub settled_at: Option<DateTime<Utc>>,
+    pub settlement_route: SettlementRoute,
+    pub priority: TransactionPriority,
+    pub source_system: Option<String>,
+    /// Earlier transaction with the same external ID that this one resubmits.
+    pub resubmission_of: Option<Uuid>,
+    pub narrative: Option<String>,
+    pub reference: Option<String>,
+    /// Link shared with the transactions this one settled together with, all or nothing.
+    pub link_id: Option<Uuid>,
 }
 
 impl From<TransactionRecord> for TransactionResponse {
@@ -173,6 +301,254 @@ impl From<TransactionRecord> for TransactionResponse {
             metadata: tx.metadata,
             created_at: tx.created_at,
             settled_at: tx.settled_at,
+            settlement_route: tx.settlement_route,
+            priority: tx.priority,
+            source_system: tx.source_system,
+            resubmission_of: tx.resubmission_of,
+            narrative: tx.narrative,
+            reference: tx.reference,
+            link_id: tx.link_id,
+        }
+    }
+}
+
+/// A group of transactions settled all or nothing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionGroupResponse {
+    pub id: Uuid,
+    pub idempotency_key: String,
+    pub status: TransactionGroupStatus,
+    pub transaction_count: usize,
+    /// Error of the last failed attempt.
+    pub error_code: Option<String>,
+    pub last_error: Option<String>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    /// The settled transactions, in submission order.
+    pub transactions: Vec<TransactionResponse>,
+}
+
+impl From<TransactionGroupDetail> for TransactionGroupResponse {
+    fn from(detail: TransactionGroupDetail) -> Self {
+        let group = detail.group;
+        Self {
+            id: group.id,
+            idempotency_key: group.idempotency_key,
+            status: group.status,
+            transaction_count: group.transaction_keys.len(),
+            error_code: group.error_code,
+            last_error: group.last_error,
+            settled_at: group.settled_at,
+            created_at: group.created_at,
+            transactions: detail.transactions.into_iter().map(TransactionResponse::from).collect(),
+        }
+    }
+}
+
+/// A trade settled delivery-versus-payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DvpSettlementResponse {
+    pub link_id: Uuid,
+    /// Transfer delivering the securities to the buyer.
+    pub securities: TransactionResponse,
+    /// Transfer paying the seller.
+    pub cash: TransactionResponse,
+}
+
+/// Transactions found by an upstream reference.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalIdLookupResponse {
+    pub external_id: String,
+    /// Transactions carrying the reference, oldest first.
+    pub transactions: Vec<TransactionResponse>,
+    /// Reversals and fee refunds of those transactions, if requested.
+    pub returns: Vec<TransactionResponse>,
+}
+
+/// Settled volume for one route and currency in a routing report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteVolumeResponse {
+    pub settlement_route: SettlementRoute,
+    pub currency: String,
+    pub transaction_count: i64,
+    pub gross_amount: Decimal,
+}
+
+/// Daily routing report response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingReportResponse {
+    pub date: chrono::NaiveDate,
+    pub netted_count: i64,
+    pub rtgs_count: i64,
+    pub routes: Vec<RouteVolumeResponse>,
+}
+
+impl From<RoutingReport> for RoutingReportResponse {
+    fn from(report: RoutingReport) -> Self {
+        Self {
+            date: report.date,
+            netted_count: report.transaction_count(SettlementRoute::Netted),
+            rtgs_count: report.transaction_count(SettlementRoute::Rtgs),
+            routes: report
+                .routes
+                .into_iter()
+                .map(|v| RouteVolumeResponse {
+                    settlement_route: v.settlement_route,
+                    currency: v.currency,
+                    transaction_count: v.transaction_count,
+                    gross_amount: v.gross_amount,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A participant's liquidity usage in an intraday liquidity report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantLiquidityResponse {
+    pub participant_id: Uuid,
+    pub opening_balance: Decimal,
+    pub credit_line: Decimal,
+    pub available_liquidity: Decimal,
+    pub total_inflows: Decimal,
+    pub total_outflows: Decimal,
+    pub peak_net_outflow: Decimal,
+    pub peak_net_outflow_at: Option<DateTime<Utc>>,
+    /// Available liquidity left at the peak.
+    pub headroom: Decimal,
+    pub largest_obligations: Vec<LiquidityFlow>,
+    pub flow_count: usize,
+}
+
+/// Intraday liquidity report response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntradayLiquidityResponse {
+    pub date: chrono::NaiveDate,
+    pub currency: String,
+    pub participants: Vec<ParticipantLiquidityResponse>,
+}
+
+impl From<IntradayLiquidityReport> for IntradayLiquidityResponse {
+    fn from(report: IntradayLiquidityReport) -> Self {
+        Self {
+            date: report.date,
+            currency: report.currency,
+            participants: report
+                .participants
+                .into_iter()
+                .map(|p| ParticipantLiquidityResponse {
+                    participant_id: p.participant_id,
+                    opening_balance: p.opening_balance,
+                    credit_line: p.credit_line(),
+                    available_liquidity: p.available_liquidity,
+                    total_inflows: p.total_inflows,
+                    total_outflows: p.total_outflows,
+                    peak_net_outflow: p.peak_net_outflow,
+                    peak_net_outflow_at: p.peak_net_outflow_at,
+                    headroom: p.headroom(),
+                    largest_obligations: p.largest_obligations,
+                    flow_count: p.flow_count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Stored netting report summary DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NettingReportSummaryResponse {
+    pub batch_id: Uuid,
+    pub currency: String,
+    pub total_transactions: i32,
+    pub participant_count: i32,
+    pub gross_volume: Decimal,
+    pub net_volume: Decimal,
+    pub reduction_amount: Decimal,
+    pub reduction_percentage: Decimal,
+    pub generated_at: DateTime<Utc>,
+    pub window_id: Option<Uuid>,
+}
+
+impl From<NettingReportRecord> for NettingReportSummaryResponse {
+    fn from(record: NettingReportRecord) -> Self {
+        Self {
+            batch_id: record.batch_id,
+            currency: record.currency,
+            total_transactions: record.total_transactions,
+            participant_count: record.participant_count,
+            gross_volume: record.gross_volume,
+            net_volume: record.net_volume,
+            reduction_amount: record.reduction_amount,
+            reduction_percentage: record.reduction_percentage,
+            generated_at: record.generated_at,
+            window_id: record.window_id,
+        }
+    }
+}
+
+/// Netting efficiency across the batches reported in a period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NettingHistoryResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub batch_count: usize,
+    pub gross_volume: Decimal,
+    pub net_volume: Decimal,
+    /// Volume-weighted reduction across all batches, in percent.
+    pub reduction_percentage: Decimal,
+    pub reports: Vec<NettingReportSummaryResponse>,
+}
+
+impl NettingHistoryResponse {
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>, records: Vec<NettingReportRecord>) -> Self {
+        let gross_volume: Decimal = records.iter().map(|r| r.gross_volume).sum();
+        let net_volume: Decimal = records.iter().map(|r| r.net_volume).sum();
+        let reduction_percentage = if gross_volume.is_zero() {
+            Decimal::ZERO
+        } else {
+            ((gross_volume - net_volume) / gross_volume * Decimal::from(100)).round_dp(4)
+        };
+
+        Self {
+            from,
+            to,
+            batch_count: records.len(),
+            gross_volume,
+            net_volume,
+            reduction_percentage,
+            reports: records.into_iter().map(NettingReportSummaryResponse::from).collect(),
+        }
+    }
+}
+
+/// Asynchronous submission response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionResponse {
+    pub id: Uuid,
+    pub idempotency_key: String,
+    pub status: SubmissionStatus,
+    pub attempts: i32,
+    pub transaction_id: Option<Uuid>,
+    pub error_code: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<TransactionSubmission> for SubmissionResponse {
+    fn from(submission: TransactionSubmission) -> Self {
+        Self {
+            id: submission.id,
+            idempotency_key: submission.idempotency_key,
+            status: submission.status,
+            attempts: submission.attempts,
+            transaction_id: submission.transaction_id,
+            error_code: submission.error_code,
+            error: submission.last_error,
+            created_at: submission.created_at,
+            updated_at: submission.updated_at,
+            completed_at: submission.completed_at,
         }
     }
 }
@@ -184,6 +560,12 @@ pub struct BatchResponse {
     pub status: BatchStatus,
     pub currency: String,
     pub settlement_date: chrono::NaiveDate,
+    pub sequence_number: i32,
+    pub window_id: Option<Uuid>,
+    pub netting_mode: NettingMode,
+    pub instruction_strategy: Option<InstructionStrategy>,
+    pub cut_off_approval: CutOffApprovalPolicy,
+    pub cut_off_time: DateTime<Utc>,
     pub total_transactions: i32,
     pub gross_amount: Decimal,
     pub net_amount: Decimal,
@@ -199,6 +581,12 @@ impl From<SettlementBatch> for BatchResponse {
             status: batch.status,
             currency: batch.currency,
             settlement_date: batch.settlement_date,
+            sequence_number: batch.sequence_number,
+            window_id: batch.window_id,
+            netting_mode: batch.netting_mode,
+            instruction_strategy: batch.instruction_strategy,
+            cut_off_approval: batch.cut_off_approval,
+            cut_off_time: batch.cut_off_time,
             total_transactions: batch.total_transactions,
             gross_amount: batch.gross_amount,
             net_amount: batch.net_amount,
@@ -209,6 +597,122 @@ impl From<SettlementBatch> for BatchResponse {
     }
 }
 
+/// Batch reconciliation response DTO: the outcome of the totals checks run before the
+/// batch was completed, with any breaks that failed it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReconciliationResponse {
+    pub batch_id: Uuid,
+    pub passed: bool,
+    pub breaks: Vec<BatchReconciliationBreak>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl From<BatchReconciliation> for BatchReconciliationResponse {
+    fn from(reconciliation: BatchReconciliation) -> Self {
+        Self {
+            batch_id: reconciliation.batch_id,
+            passed: reconciliation.passed,
+            breaks: reconciliation.breaks(),
+            checked_at: reconciliation.checked_at,
+        }
+    }
+}
+
+/// Batch processing progress response DTO.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchProgressResponse {
+    pub batch_id: Uuid,
+    pub total_transactions: i32,
+    pub processed_transactions: i32,
+    pub failed_transactions: i32,
+    pub remaining_transactions: i32,
+    pub percent_complete: f64,
+    pub transactions_per_second: Option<f64>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// At the rate so far; the finish time once processing has finished.
+    pub estimated_completion_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Times processing was resumed after its run stopped.
+    pub resumes: i32,
+}
+
+impl From<BatchProcessingProgress> for BatchProgressResponse {
+    fn from(progress: BatchProcessingProgress) -> Self {
+        Self {
+            batch_id: progress.batch_id,
+            total_transactions: progress.total_transactions,
+            processed_transactions: progress.processed_transactions,
+            failed_transactions: progress.failed_transactions,
+            remaining_transactions: progress.remaining(),
+            percent_complete: progress.percent_complete(),
+            transactions_per_second: progress.transactions_per_second(),
+            started_at: progress.started_at,
+            updated_at: progress.updated_at,
+            estimated_completion_at: progress.estimated_completion_at(),
+            finished_at: progress.finished_at,
+            resumes: progress.resumes,
+        }
+    }
+}
+
+/// Settlement window response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementWindowResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub currency: String,
+    pub cut_off_time: chrono::NaiveTime,
+    pub timezone: String,
+    pub active: bool,
+    /// Next cut-off in UTC, if the window is active.
+    pub next_cut_off: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SettlementWindow> for SettlementWindowResponse {
+    fn from(window: SettlementWindow) -> Self {
+        let next_cut_off = window
+            .active
+            .then(|| window.next_cut_off(Utc::now()).map(|(_, cut_off)| cut_off))
+            .flatten();
+        Self {
+            id: window.id,
+            name: window.name,
+            currency: window.currency,
+            cut_off_time: window.cut_off_time,
+            timezone: window.timezone,
+            active: window.active,
+            next_cut_off,
+            created_at: window.created_at,
+            updated_at: window.updated_at,
+        }
+    }
+}
+
+/// Settlement finality response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalityResponse {
+    pub sequence: i64,
+    pub transaction_id: Uuid,
+    pub batch_id: Option<Uuid>,
+    pub settlement_route: SettlementRoute,
+    pub final_at: DateTime<Utc>,
+}
+
+impl From<FinalityRecord> for FinalityResponse {
+    fn from(record: FinalityRecord) -> Self {
+        Self {
+            sequence: record.sequence,
+            transaction_id: record.transaction_id,
+            batch_id: record.batch_id,
+            settlement_route: record.settlement_route,
+            final_at: record.final_at,
+        }
+    }
+}
+
 /// Ledger entry response DTO.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LedgerEntryResponse {
@@ -221,6 +725,8 @@ pub struct LedgerEntryResponse {
     pub currency: String,
     pub effective_date: chrono::NaiveDate,
     pub created_at: DateTime<Utc>,
+    pub narrative: Option<String>,
+    pub reference: Option<String>,
 }
 
 impl From<LedgerEntry> for LedgerEntryResponse {
@@ -235,6 +741,596 @@ impl From<LedgerEntry> for LedgerEntryResponse {
             currency: entry.currency,
             effective_date: entry.effective_date,
             created_at: entry.created_at,
+            narrative: entry.narrative,
+            reference: entry.reference,
+        }
+    }
+}
+
+/// Ledger history entry response DTO: the entry with the running balance check against
+/// the account's previous entry in the currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerHistoryEntryResponse {
+    #[serde(flatten)]
+    pub entry: LedgerEntryResponse,
+    pub previous_balance: Option<Decimal>,
+    pub expected_balance_after: Option<Decimal>,
+    /// False if `balance_after` does not follow from the previous balance.
+    pub continuous: bool,
+}
+
+impl From<LedgerHistoryEntry> for LedgerHistoryEntryResponse {
+    fn from(entry: LedgerHistoryEntry) -> Self {
+        Self {
+            previous_balance: entry.previous_balance,
+            expected_balance_after: entry.expected_balance_after(),
+            continuous: entry.is_continuous(),
+            entry: LedgerEntryResponse::from(entry.entry),
+        }
+    }
+}
+
+/// A page of an account's ledger history, flagged if its running balance has gaps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountLedgerResponse {
+    pub items: Vec<LedgerHistoryEntryResponse>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub gaps_detected: bool,
+}
+
+impl AccountLedgerResponse {
+    pub fn new(history: AccountHistory, limit: i64, offset: i64) -> Self {
+        Self {
+            items: history.entries.into_iter().map(LedgerHistoryEntryResponse::from).collect(),
+            total: history.total,
+            limit,
+            offset,
+            gaps_detected: history.gaps_detected,
+        }
+    }
+}
+
+/// Alert rule response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRuleResponse {
+    pub id: Uuid,
+    pub account_id: Option<Uuid>,
+    pub rule_type: AlertRuleType,
+    pub currency: String,
+    pub threshold: Option<Decimal>,
+    pub webhook_url: Option<String>,
+    pub suppression_window_seconds: i32,
+    pub enabled: bool,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AlertRule> for AlertRuleResponse {
+    fn from(rule: AlertRule) -> Self {
+        Self {
+            id: rule.id,
+            account_id: rule.account_id,
+            rule_type: rule.rule_type,
+            currency: rule.currency,
+            threshold: rule.threshold,
+            webhook_url: rule.webhook_url,
+            suppression_window_seconds: rule.suppression_window_seconds,
+            enabled: rule.enabled,
+            last_triggered_at: rule.last_triggered_at,
+            metadata: rule.metadata,
+            created_at: rule.created_at,
+            updated_at: rule.updated_at,
+        }
+    }
+}
+
+/// Metadata schema response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataSchemaResponse {
+    pub transaction_type: TransactionType,
+    pub schema: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<MetadataSchema> for MetadataSchemaResponse {
+    fn from(schema: MetadataSchema) -> Self {
+        Self {
+            transaction_type: schema.transaction_type,
+            schema: schema.schema,
+            created_at: schema.created_at,
+            updated_at: schema.updated_at,
+        }
+    }
+}
+
+/// Settlement profile response DTO. Only the last four characters of the bank account
+/// number and IBAN are returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementProfileResponse {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub account_name: String,
+    pub routing_number: Option<String>,
+    pub bank_account_number: Option<String>,
+    pub bank_account_type: BankAccountType,
+    pub bic: Option<String>,
+    pub iban: Option<String>,
+    pub rail: PaymentRail,
+    pub cut_off_time: Option<chrono::NaiveTime>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SettlementProfile> for SettlementProfileResponse {
+    fn from(profile: SettlementProfile) -> Self {
+        let mask = |number: String| {
+            let visible = number.len().saturating_sub(4);
+            format!("{}{}", "*".repeat(visible), &number[visible..])
+        };
+        Self {
+            account_id: profile.account_id,
+            currency: profile.currency,
+            account_name: profile.account_name,
+            routing_number: profile.routing_number,
+            bank_account_number: profile.bank_account_number.map(mask),
+            bank_account_type: profile.bank_account_type,
+            bic: profile.bic,
+            iban: profile.iban.map(mask),
+            rail: profile.rail,
+            cut_off_time: profile.cut_off_time,
+            created_at: profile.created_at,
+            updated_at: profile.updated_at,
+        }
+    }
+}
+
+/// Summary of a statement pushed to a webhook.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementDeliveryResponse {
+    pub message_id: String,
+    pub statement_type: StatementType,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub entry_count: usize,
+    pub delivered_to: String,
+}
+
+impl StatementDeliveryResponse {
+    pub fn new(statement: &Statement, delivered_to: String) -> Self {
+        Self {
+            message_id: statement.message_id.clone(),
+            statement_type: statement.statement_type,
+            account_id: statement.account.id,
+            currency: statement.currency.clone(),
+            from: statement.from,
+            to: statement.to,
+            entry_count: statement.entries.len(),
+            delivered_to,
+        }
+    }
+}
+
+/// Balance break response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceBreakResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub stored_balance: Decimal,
+    pub derived_balance: Decimal,
+    pub difference: Decimal,
+    pub entry_count: i64,
+    pub open: bool,
+    pub detected_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl From<BalanceBreak> for BalanceBreakResponse {
+    fn from(balance_break: BalanceBreak) -> Self {
+        Self {
+            open: balance_break.is_open(),
+            id: balance_break.id,
+            account_id: balance_break.account_id,
+            currency: balance_break.currency,
+            stored_balance: balance_break.stored_balance,
+            derived_balance: balance_break.derived_balance,
+            difference: balance_break.difference,
+            entry_count: balance_break.entry_count,
+            detected_at: balance_break.detected_at,
+            last_seen_at: balance_break.last_seen_at,
+            resolved_at: balance_break.resolved_at,
+        }
+    }
+}
+
+/// Balance incident response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceIncidentResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub floor: Decimal,
+    pub available_balance: Decimal,
+    pub pending_balance: Decimal,
+    pub reserved_balance: Decimal,
+    pub shortfall: Decimal,
+    pub status: BalanceIncidentStatus,
+    pub detected_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub frozen_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolution_note: Option<String>,
+}
+
+impl From<BalanceIncident> for BalanceIncidentResponse {
+    fn from(incident: BalanceIncident) -> Self {
+        Self {
+            shortfall: incident.shortfall(),
+            id: incident.id,
+            account_id: incident.account_id,
+            currency: incident.currency,
+            floor: incident.floor,
+            available_balance: incident.available_balance,
+            pending_balance: incident.pending_balance,
+            reserved_balance: incident.reserved_balance,
+            status: incident.status,
+            detected_at: incident.detected_at,
+            last_seen_at: incident.last_seen_at,
+            frozen_at: incident.frozen_at,
+            resolved_at: incident.resolved_at,
+            resolution_note: incident.resolution_note,
+        }
+    }
+}
+
+/// Risk hold response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskHoldResponse {
+    pub id: Uuid,
+    pub external_id: String,
+    pub transaction_type: TransactionType,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub triggers: Vec<RiskTrigger>,
+    pub status: RiskHoldStatus,
+    pub transaction_id: Option<Uuid>,
+    pub reviewed_by: Option<String>,
+    pub review_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// Audit trail, included when a single hold is fetched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit: Option<Vec<RiskHoldAudit>>,
+}
+
+impl From<RiskHold> for RiskHoldResponse {
+    fn from(hold: RiskHold) -> Self {
+        Self {
+            triggers: serde_json::from_value(hold.triggers).unwrap_or_default(),
+            id: hold.id,
+            external_id: hold.external_id,
+            transaction_type: hold.transaction_type,
+            source_account_id: hold.source_account_id,
+            destination_account_id: hold.destination_account_id,
+            amount: hold.amount,
+            currency: hold.currency,
+            status: hold.status,
+            transaction_id: hold.transaction_id,
+            reviewed_by: hold.reviewed_by,
+            review_reason: hold.review_reason,
+            created_at: hold.created_at,
+            reviewed_at: hold.reviewed_at,
+            audit: None,
+        }
+    }
+}
+
+/// Projected balance response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectedBalanceResponse {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub balance: Decimal,
+    /// Sequence of the last ledger entry folded in.
+    pub last_sequence: i64,
+    pub last_entry_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    /// Entries posted to the account but not yet folded in.
+    pub entries_behind: i64,
+    pub lag_secs: f64,
+}
+
+impl ProjectedBalanceResponse {
+    pub fn new(projection: BalanceProjection, lag: &BalanceProjectionLag, now: DateTime<Utc>) -> Self {
+        Self {
+            account_id: projection.account_id,
+            currency: projection.currency,
+            balance: projection.balance,
+            last_sequence: projection.last_sequence,
+            last_entry_at: projection.last_entry_at,
+            updated_at: projection.updated_at,
+            entries_behind: lag.entries_behind,
+            lag_secs: lag.lag_secs(now),
+        }
+    }
+}
+
+/// Balance projection lag response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceProjectionLagResponse {
+    pub accounts_behind: i64,
+    pub entries_behind: i64,
+    pub oldest_unprojected_at: Option<DateTime<Utc>>,
+    pub lag_secs: f64,
+}
+
+impl BalanceProjectionLagResponse {
+    pub fn new(lag: BalanceProjectionLag, now: DateTime<Utc>) -> Self {
+        Self {
+            lag_secs: lag.lag_secs(now),
+            accounts_behind: lag.accounts_behind,
+            entries_behind: lag.entries_behind,
+            oldest_unprojected_at: lag.oldest_unprojected_at,
+        }
+    }
+}
+
+/// Change feed page response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResponse<T> {
+    pub items: Vec<T>,
+    /// Opaque token to pass on the next call.
+    pub next_token: String,
+    /// True if more rows can be read straight away with `next_token`.
+    pub has_more: bool,
+    /// True while reading the initial snapshot.
+    pub snapshot: bool,
+}
+
+impl<T> SyncResponse<T> {
+    pub fn new<S: Into<T>>(page: SyncPage<S>) -> Self {
+        Self {
+            items: page.items.into_iter().map(Into::into).collect(),
+            next_token: page.next_token.encode(),
+            has_more: page.has_more,
+            snapshot: page.snapshot,
+        }
+    }
+}
+
+/// Balance reservation response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReservationResponse {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub amount: Decimal,
+    pub name: String,
+    pub reference: String,
+    pub status: BalanceReservationStatus,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl From<BalanceReservation> for ReservationResponse {
+    fn from(reservation: BalanceReservation) -> Self {
+        Self {
+            id: reservation.id,
+            account_id: reservation.account_id,
+            currency: reservation.currency,
+            amount: reservation.amount,
+            name: reservation.name,
+            reference: reservation.reference,
+            status: reservation.status,
+            expires_at: reservation.expires_at,
+            transaction_id: reservation.transaction_id,
+            created_at: reservation.created_at,
+            closed_at: reservation.closed_at,
+        }
+    }
+}
+
+/// Balance floor response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceFloorResponse {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub floor: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<BalanceFloor> for BalanceFloorResponse {
+    fn from(floor: BalanceFloor) -> Self {
+        Self {
+            account_id: floor.account_id,
+            currency: floor.currency,
+            floor: floor.floor,
+            updated_at: floor.updated_at,
+        }
+    }
+}
+
+/// Net debit cap response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetDebitCapResponse {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub cap: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<NetDebitCap> for NetDebitCapResponse {
+    fn from(cap: NetDebitCap) -> Self {
+        Self {
+            account_id: cap.account_id,
+            currency: cap.currency,
+            cap: cap.cap,
+            updated_at: cap.updated_at,
+        }
+    }
+}
+
+/// Account activity summary response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountActivityResponse {
+    pub account_id: Uuid,
+    pub granularity: ActivityGranularity,
+    pub periods: Vec<ActivityPeriodResponse>,
+}
+
+/// Activity of an account in one currency over one period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityPeriodResponse {
+    pub period_start: chrono::NaiveDate,
+    pub currency: String,
+    pub transaction_count: i64,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+    pub total_fees: Decimal,
+    pub by_type: Vec<ActivityTypeSummary>,
+}
+
+impl From<ActivityPeriod> for ActivityPeriodResponse {
+    fn from(period: ActivityPeriod) -> Self {
+        Self {
+            period_start: period.period_start,
+            currency: period.currency,
+            transaction_count: period.transaction_count,
+            total_in: period.total_in,
+            total_out: period.total_out,
+            total_fees: period.total_fees,
+            by_type: period.by_type,
+        }
+    }
+}
+
+/// Balance at the end of a day response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceAsOfResponse {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub basis: BalanceBasis,
+    pub date: chrono::NaiveDate,
+    pub balance: Decimal,
+}
+
+/// Daily closing balances response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceHistoryResponse {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub basis: BalanceBasis,
+    pub balances: Vec<DatedBalance>,
+}
+
+/// Accounting period response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountingPeriodResponse {
+    pub period_start: chrono::NaiveDate,
+    pub period_end: chrono::NaiveDate,
+    pub status: PeriodStatus,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AccountingPeriod> for AccountingPeriodResponse {
+    fn from(period: AccountingPeriod) -> Self {
+        Self {
+            period_start: period.period_start,
+            period_end: period.period_end,
+            status: period.status,
+            closed_at: period.closed_at,
+            updated_at: period.updated_at,
+        }
+    }
+}
+
+/// GL posting run response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlPostingRunResponse {
+    pub id: Uuid,
+    pub posting_date: chrono::NaiveDate,
+    pub line_count: i32,
+    pub entry_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<GlPostingRun> for GlPostingRunResponse {
+    fn from(run: GlPostingRun) -> Self {
+        Self {
+            id: run.id,
+            posting_date: run.posting_date,
+            line_count: run.line_count,
+            entry_count: run.entry_count,
+            created_at: run.created_at,
+        }
+    }
+}
+
+/// GL journal response DTO.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlJournalResponse {
+    pub run: GlPostingRunResponse,
+    pub balanced: bool,
+    pub lines: Vec<GlJournalLine>,
+}
+
+impl From<GlJournal> for GlJournalResponse {
+    fn from(journal: GlJournal) -> Self {
+        Self {
+            balanced: journal.is_balanced(),
+            run: GlPostingRunResponse::from(journal.run),
+            lines: journal.lines,
+        }
+    }
+}
+
+/// File delivery response DTO. The file content itself is not returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDeliveryResponse {
+    pub id: Uuid,
+    pub destination: String,
+    pub file_name: String,
+    pub size_bytes: usize,
+    pub checksum_sha256: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<FileDelivery> for FileDeliveryResponse {
+    fn from(delivery: FileDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            destination: delivery.destination,
+            file_name: delivery.file_name,
+            size_bytes: delivery.content.len(),
+            checksum_sha256: delivery.checksum_sha256,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            max_attempts: delivery.max_attempts,
+            next_attempt_at: delivery.next_attempt_at,
+            last_error: delivery.last_error,
+            delivered_at: delivery.delivered_at,
+            created_at: delivery.created_at,
+            updated_at: delivery.updated_at,
         }
     }
 }
@@ -1,33 +1,144 @@
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, post, put},
     Router,
 };
 use metrics_exporter_prometheus::PrometheusHandle;
 use rskafka::client::Client as KafkaClient;
 use sqlx::PgPool;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use super::admission::{enforce_admission, AdmissionConfig, AdmissionControl};
+use super::graphql::{self, SettlementSchema};
 use super::handlers;
+use super::timeouts::{enforce_request_timeout, RequestTimeouts};
+use super::validation::{DEFAULT_BODY_LIMIT, DOCUMENT_BODY_LIMIT};
+use crate::cache::RedisPool;
+use crate::core::job_control::JobControl;
+use crate::core::leader::LeaderElection;
+use crate::core::locks::DistributedLocks;
+use crate::core::rounding::RoundingPolicy;
+use crate::delivery::DeliveryChannels;
+use crate::events::{CdcPublisher, EventProducer};
+use crate::interop::gl::GlMapping;
+use crate::interop::nacha::NachaConfig;
+use crate::models::EngineMode;
+use crate::notifications::NotificationEngine;
 use crate::observability::HealthChecker;
+use crate::services::{
+    AmountLimits, AttestationSigner, BatchCaps, BatchService, ChartOfAccountsService, DefaultManagementConfig, ExternalIdPolicy, FeeConfig, FundingService, FxRevaluationConfig, NetDebitCapConfig, NettingAdviceService, NettingConfig, RiskService, RtgsService, SettlementRail, SubmissionService, WriteCombiner, DEFAULT_BATCH_WORKERS, DEFAULT_MAX_CUT_OFF_SHIFT_SECS, DEFAULT_PVP_TIMEOUT_SECS, DEFAULT_STALL_TIMEOUT_SECS,
+};
 
 /// Application state shared across handlers.
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
-    pub redis_client: redis::Client,
+    pub redis: Arc<RedisPool>,
     pub kafka_client: Option<Arc<KafkaClient>>,
     pub metrics_handle: Option<PrometheusHandle>,
     pub health_checker: Option<Arc<HealthChecker>>,
+    pub notification_engine: Option<Arc<NotificationEngine>>,
+    pub rtgs: Option<Arc<RtgsService>>,
+    /// Whether transactions settle gross, net or by threshold.
+    pub engine_mode: EngineMode,
+    pub risk: Option<Arc<RiskService>>,
+    pub batching: Option<Arc<BatchService>>,
+    /// Net debit caps enforced when transactions are assigned to batches.
+    pub net_debit_caps: NetDebitCapConfig,
+    /// Size and value caps past which assignment rolls over to a new sub-batch.
+    pub batch_caps: BatchCaps,
+    pub batch_workers: usize,
+    /// How long a batch processing run can go without checkpointing before it can be
+    /// resumed elsewhere.
+    pub batch_stall_timeout: std::time::Duration,
+    /// Furthest an operator can move a batch's cut-off in one change.
+    pub max_cut_off_shift: std::time::Duration,
+    pub submissions: Option<Arc<SubmissionService>>,
+    pub write_combiner: Option<Arc<WriteCombiner>>,
+    pub producer: Option<Arc<EventProducer>>,
+    pub cdc: Option<Arc<CdcPublisher>>,
+    /// Background jobs operators can control through `/admin/jobs`, by name.
+    pub jobs: BTreeMap<String, Arc<JobControl>>,
+    pub leader: Option<Arc<LeaderElection>>,
+    /// Locks serializing closing and processing of each batch.
+    pub locks: Arc<DistributedLocks>,
+    pub attestation_signer: Option<Arc<AttestationSigner>>,
+    pub rail: Option<Arc<dyn SettlementRail>>,
+    pub nacha: Option<Arc<NachaConfig>>,
+    pub delivery: Option<Arc<DeliveryChannels>>,
+    /// Issues netting advices to participants when batches complete.
+    pub advices: Option<Arc<NettingAdviceService>>,
+    /// Opens funding obligations for net payers when batches complete.
+    pub funding: Option<Arc<FundingService>>,
+    /// Parallelism and instruction strategy of netting calculations.
+    pub netting: NettingConfig,
+    /// How long PvP settlements wait for funding unless the request sets a deadline.
+    pub pvp_timeout: std::time::Duration,
+    /// Functional currency and P&L account FX revaluations are booked with.
+    pub fx_revaluation: FxRevaluationConfig,
+    pub gl_mapping: Arc<GlMapping>,
+    pub fees: Arc<FeeConfig>,
+    /// Chart of internal accounts, provisioning system accounts in new currencies.
+    pub chart: Option<Arc<ChartOfAccountsService>>,
+    pub external_ids: ExternalIdPolicy,
+    pub amount_limits: Arc<AmountLimits>,
+    pub default_management: DefaultManagementConfig,
+    /// How computed amounts are rounded and where split remainders go.
+    pub rounding: Arc<RoundingPolicy>,
+    /// How long each route may take before it is answered with a timeout.
+    pub request_timeouts: Arc<RequestTimeouts>,
+    /// Writes in progress and the thresholds past which new ones are turned away.
+    pub admission: Arc<AdmissionControl>,
+    /// Schema of the read-only GraphQL API.
+    pub graphql: SettlementSchema,
 }
 
 impl AppState {
-    pub fn new(pool: PgPool, redis_client: redis::Client, kafka_client: Option<Arc<KafkaClient>>) -> Self {
+    pub fn new(pool: PgPool, redis: Arc<RedisPool>, kafka_client: Option<Arc<KafkaClient>>) -> Self {
         Self {
+            locks: Arc::new(DistributedLocks::postgres(pool.clone())),
+            graphql: graphql::build_schema(pool.clone()),
             pool,
-            redis_client,
+            redis,
             kafka_client,
             metrics_handle: None,
             health_checker: None,
+            notification_engine: None,
+            rtgs: None,
+            engine_mode: EngineMode::default(),
+            risk: None,
+            batching: None,
+            net_debit_caps: NetDebitCapConfig::default(),
+            batch_caps: BatchCaps::default(),
+            batch_workers: DEFAULT_BATCH_WORKERS,
+            batch_stall_timeout: std::time::Duration::from_secs(DEFAULT_STALL_TIMEOUT_SECS),
+            max_cut_off_shift: std::time::Duration::from_secs(DEFAULT_MAX_CUT_OFF_SHIFT_SECS),
+            submissions: None,
+            write_combiner: None,
+            producer: None,
+            cdc: None,
+            jobs: BTreeMap::new(),
+            leader: None,
+            attestation_signer: None,
+            rail: None,
+            nacha: None,
+            delivery: None,
+            advices: None,
+            funding: None,
+            netting: NettingConfig::default(),
+            pvp_timeout: std::time::Duration::from_secs(DEFAULT_PVP_TIMEOUT_SECS),
+            fx_revaluation: FxRevaluationConfig::default(),
+            gl_mapping: Arc::new(GlMapping::default()),
+            fees: Arc::new(FeeConfig::default()),
+            chart: None,
+            external_ids: ExternalIdPolicy::default(),
+            amount_limits: Arc::new(AmountLimits::default()),
+            default_management: DefaultManagementConfig::default(),
+            rounding: Arc::new(RoundingPolicy::default()),
+            request_timeouts: Arc::new(RequestTimeouts::default()),
+            admission: Arc::new(AdmissionControl::default()),
         }
     }
 
@@ -43,6 +154,226 @@ impl AppState {
         self
     }
 
+    /// Adds the alert notification engine to the state.
+    pub fn with_notification_engine(mut self, engine: Arc<NotificationEngine>) -> Self {
+        self.notification_engine = Some(engine);
+        self
+    }
+
+    /// Adds the RTGS lane for large-value transactions to the state.
+    pub fn with_rtgs(mut self, rtgs: Arc<RtgsService>) -> Self {
+        self.rtgs = Some(rtgs);
+        self
+    }
+
+    /// Sets the engine mode; no batches are opened in `Gross` mode.
+    pub fn with_engine_mode(mut self, mode: EngineMode) -> Self {
+        self.engine_mode = mode;
+        self
+    }
+
+    /// Holds payments and transfers that trip a risk rule for review.
+    pub fn with_risk(mut self, risk: Arc<RiskService>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Assigns transactions to the current open batch as they settle.
+    pub fn with_batching(mut self, batching: Arc<BatchService>) -> Self {
+        self.batching = Some(batching);
+        self
+    }
+
+    /// Sets the net debit caps enforced when transactions are assigned to batches.
+    pub fn with_net_debit_caps(mut self, config: NetDebitCapConfig) -> Self {
+        self.net_debit_caps = config;
+        self
+    }
+
+    /// Sets the size and value caps past which assignment rolls over to a new sub-batch.
+    pub fn with_batch_caps(mut self, caps: BatchCaps) -> Self {
+        self.batch_caps = caps;
+        self
+    }
+
+    /// Combines postings arriving close together into one database transaction.
+    pub fn with_write_combiner(mut self, combiner: Arc<WriteCombiner>) -> Self {
+        self.write_combiner = Some(combiner);
+        self
+    }
+
+    /// Sets how many transactions are processed at once when a batch is processed.
+    pub fn with_batch_workers(mut self, workers: usize) -> Self {
+        self.batch_workers = workers;
+        self
+    }
+
+    /// Sets how long a batch processing run can go without checkpointing before its
+    /// batch can be resumed.
+    pub fn with_batch_stall_timeout(mut self, stall_timeout: std::time::Duration) -> Self {
+        self.batch_stall_timeout = stall_timeout;
+        self
+    }
+
+    /// Sets the furthest an operator can move a batch's cut-off in one change.
+    pub fn with_max_cut_off_shift(mut self, max_shift: std::time::Duration) -> Self {
+        self.max_cut_off_shift = max_shift;
+        self
+    }
+
+    /// Accepts transactions for asynchronous settlement.
+    pub fn with_submissions(mut self, submissions: Arc<SubmissionService>) -> Self {
+        self.submissions = Some(submissions);
+        self
+    }
+
+    /// Adds the event producer used for batch finality events.
+    pub fn with_producer(mut self, producer: Arc<EventProducer>) -> Self {
+        self.producer = Some(producer);
+        self
+    }
+
+    /// Adds the change data capture publisher, for reporting its slot's lag.
+    pub fn with_cdc(mut self, cdc: Arc<CdcPublisher>) -> Self {
+        self.cdc = Some(cdc);
+        self
+    }
+
+    /// Registers a background job for runtime control.
+    pub fn with_job(mut self, job: Arc<JobControl>) -> Self {
+        self.jobs.insert(job.name().to_string(), job);
+        self
+    }
+
+    /// Adds the leader election this instance campaigns in.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Sets where batch locks are kept, e.g. Redis instead of Postgres.
+    pub fn with_locks(mut self, locks: Arc<DistributedLocks>) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Adds the key used to sign batch finality attestations.
+    pub fn with_attestation_signer(mut self, signer: Arc<AttestationSigner>) -> Self {
+        self.attestation_signer = Some(signer);
+        self
+    }
+
+    /// Adds the settlement rail processed batches release their instructions to.
+    pub fn with_rail(mut self, rail: Arc<dyn SettlementRail>) -> Self {
+        self.rail = Some(rail);
+        self
+    }
+
+    /// Adds the originator details used to export batch instructions as NACHA files.
+    pub fn with_nacha(mut self, config: Arc<NachaConfig>) -> Self {
+        self.nacha = Some(config);
+        self
+    }
+
+    /// Sets the GL account codes used by general-ledger posting runs.
+    pub fn with_gl_mapping(mut self, mapping: Arc<GlMapping>) -> Self {
+        self.gl_mapping = mapping;
+        self
+    }
+
+    /// Sets the fee revenue accounts and the default fee reversal policy.
+    pub fn with_fees(mut self, fees: Arc<FeeConfig>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Provisions the chart's system accounts in a currency on its first use.
+    pub fn with_chart_of_accounts(mut self, chart: Arc<ChartOfAccountsService>) -> Self {
+        self.chart = Some(chart);
+        self
+    }
+
+    /// Sets the scope external IDs are unique in and what happens to duplicates.
+    pub fn with_external_ids(mut self, policy: ExternalIdPolicy) -> Self {
+        self.external_ids = policy;
+        self
+    }
+
+    /// Sets the maximum transaction amounts and decimal scale accepted for posting.
+    pub fn with_amount_limits(mut self, limits: Arc<AmountLimits>) -> Self {
+        self.amount_limits = limits;
+        self
+    }
+
+    /// Sets how participants declared in default are taken out of their batches.
+    pub fn with_default_management(mut self, config: DefaultManagementConfig) -> Self {
+        self.default_management = config;
+        self
+    }
+
+    /// Sets how computed amounts are rounded and where split remainders go.
+    pub fn with_rounding(mut self, rounding: Arc<RoundingPolicy>) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Adds the destinations generated files can be delivered to.
+    pub fn with_delivery(mut self, channels: Arc<DeliveryChannels>) -> Self {
+        self.delivery = Some(channels);
+        self
+    }
+
+    /// Issues netting advices to participants when batches complete.
+    pub fn with_advices(mut self, advices: Arc<NettingAdviceService>) -> Self {
+        self.advices = Some(advices);
+        self
+    }
+
+    /// Opens funding obligations for net payers when batches complete.
+    pub fn with_funding(mut self, funding: Arc<FundingService>) -> Self {
+        self.funding = Some(funding);
+        self
+    }
+
+    /// Sets how long PvP settlements wait for funding by default.
+    pub fn with_pvp_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.pvp_timeout = timeout;
+        self
+    }
+
+    /// Sets the functional currency and P&L account FX revaluations are booked with.
+    pub fn with_fx_revaluation(mut self, config: FxRevaluationConfig) -> Self {
+        self.fx_revaluation = config;
+        self
+    }
+
+    /// Sets the parallelism and instruction strategy of netting calculations.
+    pub fn with_netting(mut self, config: NettingConfig) -> Self {
+        self.netting = config;
+        self
+    }
+
+    /// Sets the default and per-route request timeouts.
+    pub fn with_request_timeouts(mut self, timeouts: RequestTimeouts) -> Self {
+        self.request_timeouts = Arc::new(timeouts);
+        self
+    }
+
+    /// Sets the thresholds past which writes are turned away.
+    pub fn with_admission(mut self, config: AdmissionConfig) -> Self {
+        self.admission = Arc::new(AdmissionControl::new(config));
+        self
+    }
+
+    /// Batch service assigning transactions under this instance's engine mode, batch
+    /// caps and net debit caps.
+    pub fn batch_service(&self) -> BatchService {
+        BatchService::new(self.pool.clone())
+            .with_caps(self.batch_caps.clone())
+            .with_net_debit_caps(self.net_debit_caps.clone())
+            .with_mode(self.engine_mode)
+    }
+
     /// Returns true if Kafka is connected.
     pub fn kafka_connected(&self) -> bool {
         self.kafka_client.is_some()
@@ -55,25 +386,228 @@ pub fn create_router(state: AppState) -> Router {
         // Health endpoints
         .route("/health", get(handlers::health_check))
         .route("/health/detailed", get(handlers::detailed_health_check))
+        .route("/health/dependencies", get(handlers::dependency_health_check))
         .route("/ready", get(handlers::readiness_check))
         .route("/live", get(handlers::liveness_check))
         // Metrics endpoint
         .route("/metrics", get(handlers::metrics_endpoint))
+        // GraphQL endpoint
+        .route("/graphql", post(handlers::graphql).get(handlers::graphql_playground))
         // Account endpoints
         .route("/accounts", post(handlers::create_account))
+        .route("/accounts/lookup", get(handlers::lookup_account))
         .route("/accounts/:id", get(handlers::get_account))
         .route("/accounts/:id/balance", get(handlers::get_account_balance))
+        .route("/accounts/:id/balance/as-of", get(handlers::get_account_balance_as_of))
+        .route("/accounts/:id/balance/history", get(handlers::get_account_balance_history))
+        .route("/accounts/:id/balance/explain", get(handlers::explain_account_balance))
+        .route("/accounts/:id/balance/projected", get(handlers::get_projected_balance))
+        .route("/accounts/:id/balance-floor", put(handlers::set_balance_floor))
+        .route("/accounts/:id/net-debit-cap", put(handlers::set_net_debit_cap))
+        .route("/accounts/:id/reservations", post(handlers::create_reservation))
+        .route("/accounts/:id/reservations", get(handlers::list_reservations))
+        .route("/accounts/:id/reservations/:reservation_id", get(handlers::get_reservation))
+        .route(
+            "/accounts/:id/reservations/:reservation_id/release",
+            post(handlers::release_reservation),
+        )
+        .route(
+            "/accounts/:id/reservations/:reservation_id/capture",
+            post(handlers::capture_reservation),
+        )
         .route("/accounts/:id/ledger", get(handlers::get_account_ledger))
+        .route("/accounts/:id/status-history", get(handlers::get_account_status_history))
+        .route("/accounts/:id/settlement-profiles", get(handlers::list_settlement_profiles))
+        .route(
+            "/accounts/:id/settlement-profiles/:currency",
+            get(handlers::get_settlement_profile)
+                .put(handlers::set_settlement_profile)
+                .delete(handlers::delete_settlement_profile),
+        )
+        .route(
+            "/accounts/:id/identifiers",
+            get(handlers::list_account_identifiers).post(handlers::add_account_identifier),
+        )
+        .route(
+            "/accounts/:id/identifiers/:identifier_id",
+            delete(handlers::remove_account_identifier),
+        )
+        .route(
+            "/accounts/:id/invoice-contracts/:currency",
+            get(handlers::get_invoice_contract).put(handlers::set_invoice_contract),
+        )
+        .route("/accounts/:id/anonymize", post(handlers::anonymize_account))
+        .route("/accounts/:id/anonymization", get(handlers::get_account_anonymization))
+        .route("/accounts/:id/statements", get(handlers::get_account_statement))
+        .route("/accounts/:id/statements/deliver", post(handlers::deliver_account_statement))
+        .route("/accounts/:id/activity", get(handlers::get_account_activity))
+        .route("/accounts/:id/counterparties", get(handlers::list_counterparty_restrictions))
+        .route("/accounts/:id/counterparties", post(handlers::add_counterparty_restriction))
+        .route("/accounts/:id/counterparties/audit", get(handlers::get_counterparty_audit))
+        .route(
+            "/accounts/:id/counterparties/:counterparty_id",
+            delete(handlers::remove_counterparty_restriction),
+        )
+        // Chart of internal accounts
+        .route("/internal-accounts", get(handlers::list_internal_accounts))
+        .route(
+            "/internal-accounts/provisioning",
+            get(handlers::list_internal_account_provisioning),
+        )
+        .route("/internal-accounts/:code/:currency", get(handlers::get_internal_account))
+        // Transaction group endpoints
+        .route("/transaction-groups", post(handlers::create_transaction_group))
+        .route("/transaction-groups/:id", get(handlers::get_transaction_group))
+        .route(
+            "/transaction-groups/by-idempotency-key/:idempotency_key",
+            get(handlers::get_transaction_group_by_key),
+        )
+        // Delivery-versus-payment settlement
+        .route("/settlements/dvp", post(handlers::settle_dvp))
+        // Payment-versus-payment settlement
+        .route(
+            "/settlements/pvp",
+            post(handlers::create_pvp_settlement).get(handlers::list_pvp_settlements),
+        )
+        .route("/settlements/pvp/:id", get(handlers::get_pvp_settlement))
+        .route("/settlements/pvp/:id/attempt", post(handlers::attempt_pvp_settlement))
+        .route("/settlements/pvp/:id/cancel", post(handlers::cancel_pvp_settlement))
+        // FX rates and conversions
+        .route("/fx/rates", post(handlers::record_fx_rate).get(handlers::list_fx_rates))
+        .route("/fx/rates/:id", get(handlers::get_fx_rate))
+        .route("/fx/rates/:id/corrections", post(handlers::correct_fx_rate))
+        .route("/fx/conversions", post(handlers::create_fx_conversion))
+        .route("/fx/conversions/:id", get(handlers::get_fx_conversion))
+        .route("/fx/conversions/:id/adjustments", get(handlers::list_fx_conversion_adjustments))
+        .route("/fx/revaluations", post(handlers::revalue_fx).get(handlers::list_fx_revaluations))
+        .route("/fx/revaluations/:id", get(handlers::get_fx_revaluation))
         // Transaction endpoints
         .route("/transactions", post(handlers::create_transaction))
         .route("/transactions", get(handlers::list_transactions))
-        .route("/transactions/:id", get(handlers::get_transaction))
+        .route("/transactions/dry-run", post(handlers::dry_run_transaction))
+        .route("/transactions/async", post(handlers::submit_transaction))
+        .route("/transactions/async/:id", get(handlers::get_submission))
+        .route("/transactions/by-external-id/:external_id", get(handlers::get_transactions_by_external_id))
+        .route("/transactions/:id", get(handlers::get_transaction).patch(handlers::amend_transaction))
+        .route("/transactions/:id/amendments", get(handlers::get_transaction_amendments))
         .route("/transactions/:id/reverse", post(handlers::reverse_transaction))
+        .route("/transactions/:id/fee-refunds", post(handlers::refund_transaction_fee))
+        .route("/transactions/:id/priority", put(handlers::update_transaction_priority))
+        .route("/transactions/:id/finality", get(handlers::get_transaction_finality))
+        .route("/transactions/:id/timeline", get(handlers::get_transaction_timeline))
+        .route("/transactions/:id/window", post(handlers::assign_transaction_window))
+        .route("/transactions/:id/reassign-batch", post(handlers::reassign_transaction_batch))
         // Batch endpoints
         .route("/batches", get(handlers::list_batches))
         .route("/batches/:id", get(handlers::get_batch))
+        .route("/batches/:id/netting-mode", put(handlers::set_batch_netting_mode))
+        .route("/batches/:id/instruction-strategy", put(handlers::set_batch_instruction_strategy))
+        .route("/batches/:id/cutoff", post(handlers::move_batch_cut_off))
+        .route("/batches/:id/close-early", post(handlers::close_batch_early))
+        .route("/batches/:id/cutoff-changes", get(handlers::list_batch_cut_off_changes))
         .route("/batches/:id/process", post(handlers::process_batch))
+        .route("/batches/:id/resume", post(handlers::resume_batch_processing))
+        .route("/batches/:id/processing-progress", get(handlers::get_batch_processing_progress))
+        .route("/batches/:id/processing-progress/stream", get(handlers::stream_batch_processing_progress))
+        .route("/batches/:id/reconciliation", get(handlers::get_batch_reconciliation))
         .route("/batches/:id/positions", get(handlers::get_batch_positions))
+        .route("/batches/:id/positions/:participant_id/transactions", get(handlers::get_position_transactions))
+        .route("/batches/:id/advices", post(handlers::issue_netting_advices).get(handlers::list_netting_advices))
+        .route("/batches/:id/advices/:participant_id", get(handlers::get_netting_advice))
+        .route("/batches/:id/advices/:participant_id/export", get(handlers::export_netting_advice))
+        .route("/batches/:id/funding", post(handlers::open_funding_obligations).get(handlers::list_batch_funding_obligations))
+        .route("/batches/:id/bilateral-pairs", get(handlers::get_batch_bilateral_pairs))
+        .route("/batches/:id/netting/report", get(handlers::get_batch_netting_report))
+        .route("/batches/:id/finality", get(handlers::get_batch_finality))
+        .route("/batches/:id/fee-report", get(handlers::get_batch_fee_report))
+        .route("/batches/:id/fee-settlement", post(handlers::settle_batch_fees))
+        .route("/batches/:id/attestation", get(handlers::get_batch_attestation))
+        .route("/batches/:id/instructions/export", get(handlers::export_batch_instructions))
+        .route("/batches/:id/defaults", post(handlers::declare_participant_default))
+        .route("/batches/:id/defaults", get(handlers::list_batch_defaults))
+        .route("/batches/:id/defaults/:participant_id", get(handlers::get_default_report))
+        // Risk review endpoints
+        .route("/risk-holds", get(handlers::list_risk_holds))
+        .route("/risk-holds/:id", get(handlers::get_risk_hold))
+        .route("/risk-holds/:id/release", post(handlers::release_risk_hold))
+        .route("/risk-holds/:id/reject", post(handlers::reject_risk_hold))
+        // Funding obligation endpoints
+        .route("/funding-obligations", get(handlers::list_funding_obligations))
+        .route("/funding-obligations/:id", get(handlers::get_funding_obligation))
+        // File delivery endpoints
+        .route("/deliveries", post(handlers::create_delivery).get(handlers::list_deliveries))
+        .route("/deliveries/:id", get(handlers::get_delivery))
+        .route("/deliveries/:id/retry", post(handlers::retry_delivery))
+        // Report endpoints
+        .route("/reports/routing", get(handlers::get_routing_report))
+        .route("/reports/netting", get(handlers::get_netting_history))
+        .route("/reports/intraday-liquidity", get(handlers::get_intraday_liquidity_report))
+        // Settlement window endpoints
+        .route("/settlement-windows", post(handlers::create_settlement_window))
+        .route("/settlement-windows", get(handlers::list_settlement_windows))
+        .route("/settlement-windows/:id", get(handlers::get_settlement_window))
+        .route("/settlement-windows/:id", put(handlers::update_settlement_window))
+        .route("/settlement-windows/:id", delete(handlers::delete_settlement_window))
+        // Batch template endpoints
+        .route("/batch-templates", post(handlers::create_batch_template))
+        .route("/batch-templates", get(handlers::list_batch_templates))
+        .route("/batch-templates/provision", post(handlers::provision_batches))
+        .route("/batch-templates/:id", get(handlers::get_batch_template))
+        .route("/batch-templates/:id", put(handlers::update_batch_template))
+        // Alert rule endpoints
+        .route("/alert-rules", post(handlers::create_alert_rule))
+        .route("/alert-rules", get(handlers::list_alert_rules))
+        .route("/alert-rules/:id", get(handlers::get_alert_rule))
+        .route("/alert-rules/:id", put(handlers::update_alert_rule))
+        .route("/alert-rules/:id", delete(handlers::delete_alert_rule))
+        // Reconciliation endpoints
+        .route("/reconciliation/breaks", get(handlers::list_balance_breaks))
+        .route("/balance-incidents", get(handlers::list_balance_incidents))
+        .route("/balance-projection/lag", get(handlers::get_balance_projection_lag))
+        .route("/balance-incidents/:id/resolve", post(handlers::resolve_balance_incident))
+        // Downstream sync endpoints
+        .route("/sync/accounts", get(handlers::sync_accounts))
+        .route("/sync/transactions", get(handlers::sync_transactions))
+        .route("/cdc/status", get(handlers::get_cdc_status))
+        // Background job administration
+        .route("/admin/leader", get(handlers::get_leader_status))
+        .route("/admin/jobs", get(handlers::list_jobs))
+        .route("/admin/jobs/:name", get(handlers::get_job))
+        .route("/admin/jobs/:name/pause", post(handlers::pause_job))
+        .route("/admin/jobs/:name/resume", post(handlers::resume_job))
+        .route("/admin/jobs/:name/trigger", post(handlers::trigger_job))
+        .route("/admin/jobs/:name/interval", put(handlers::set_job_interval))
+        // General ledger endpoints
+        .route("/gl/posting-runs", get(handlers::list_gl_posting_runs))
+        .route("/gl/posting-runs", post(handlers::create_gl_posting_run))
+        .route("/gl/posting-runs/:id/journal", get(handlers::export_gl_journal))
+        .route("/invoices", get(handlers::list_invoices))
+        .route("/invoices/runs", post(handlers::create_invoice_run))
+        .route("/invoices/:id", get(handlers::get_invoice))
+        .route("/invoices/:id/export", get(handlers::export_invoice))
+        .route("/accounting-periods", get(handlers::list_accounting_periods))
+        .route("/accounting-periods/:date/close", post(handlers::close_accounting_period))
+        .route("/accounting-periods/:date/reopen", post(handlers::reopen_accounting_period))
+        // Metadata schema endpoints
+        .route("/metadata-schemas", get(handlers::list_metadata_schemas))
+        .route("/metadata-schemas/:transaction_type", get(handlers::get_metadata_schema))
+        .route(
+            "/metadata-schemas/:transaction_type",
+            put(handlers::set_metadata_schema).layer(DefaultBodyLimit::max(DOCUMENT_BODY_LIMIT)),
+        )
+        .route("/metadata-schemas/:transaction_type", delete(handlers::delete_metadata_schema))
+        // Instrument endpoints
+        .route("/instruments", post(handlers::register_instrument).get(handlers::list_instruments))
+        .route("/instruments/:code", get(handlers::get_instrument))
+        // Transaction type endpoints
+        .route("/transaction-types", get(handlers::list_transaction_types))
+        .route(
+            "/transaction-types/:code",
+            get(handlers::get_transaction_type).put(handlers::set_transaction_type),
+        )
+        .layer(DefaultBodyLimit::max(DEFAULT_BODY_LIMIT))
+        .layer(middleware::from_fn_with_state(state.request_timeouts.clone(), enforce_request_timeout))
+        .layer(middleware::from_fn_with_state(state.admission.clone(), enforce_admission))
         .with_state(state)
 }
 
@@ -0,0 +1,391 @@
+use crate::error::{AppError, Result};
+use anyhow::anyhow;
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Extension, Router,
+};
+use hyper::server::conn::http1;
+use hyper_util::rt::TokioIo;
+use hyper_util::service::TowerToHyperService;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpSocket};
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio_rustls::rustls::server::{AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient};
+use tokio_rustls::rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, info, warn};
+
+/// Time allowed for a client to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header carrying the API key checked against certificate bindings.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// TLS termination settings.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf first.
+    pub cert_path: PathBuf,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1).
+    pub key_path: PathBuf,
+    /// PEM bundle of CAs trusted to issue client certificates. Enables mTLS.
+    pub client_ca_path: Option<PathBuf>,
+    /// Rejects handshakes without a client certificate. Without it, client
+    /// certificates are verified when presented but optional.
+    pub require_client_cert: bool,
+    /// API keys that are only accepted over a connection presenting a specific
+    /// client certificate, as SHA-256 hex of the key to SHA-256 hex of the
+    /// certificate's DER encoding.
+    pub api_key_bindings: HashMap<String, String>,
+}
+
+impl TlsConfig {
+    /// Builds the rustls configuration from the configured PEM files.
+    pub fn server_config(&self) -> Result<Arc<ServerConfig>> {
+        let certs = load_certificates(&self.cert_path)?;
+        let key = load_private_key(&self.key_path)?;
+        let builder = ServerConfig::builder().with_safe_defaults();
+
+        let builder = match &self.client_ca_path {
+            Some(path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certificates(path)? {
+                    roots.add(&cert).map_err(|e| {
+                        AppError::Internal(anyhow!("Invalid client CA in {}: {}", path.display(), e))
+                    })?;
+                }
+                let verifier = if self.require_client_cert {
+                    AllowAnyAuthenticatedClient::new(roots).boxed()
+                } else {
+                    AllowAnyAnonymousOrAuthenticatedClient::new(roots).boxed()
+                };
+                builder.with_client_cert_verifier(verifier)
+            }
+            None if self.require_client_cert => {
+                return Err(AppError::Internal(anyhow!(
+                    "Requiring client certificates needs a client CA"
+                )));
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder
+            .with_single_cert(certs, key)
+            .map_err(|e| AppError::Internal(anyhow!("Invalid TLS certificate or key: {}", e)))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
+
+/// Listening socket settings.
+#[derive(Debug, Clone)]
+pub struct ListenerConfig {
+    pub addr: SocketAddr,
+    /// Number of sockets accepting connections, each on its own task.
+    pub listeners: usize,
+    /// Sets SO_REUSEPORT, so several sockets (or a new process during a restart)
+    /// can bind the same port and the kernel balances connections between them.
+    pub reuse_port: bool,
+}
+
+/// Client certificate presented on the connection a request arrived on.
+#[derive(Debug, Clone)]
+pub struct ClientCertificate {
+    /// SHA-256 hex of the certificate's DER encoding.
+    pub fingerprint: String,
+}
+
+/// Binds the configured number of listening sockets.
+pub fn bind_listeners(config: &ListenerConfig) -> Result<Vec<TcpListener>> {
+    let count = config.listeners.max(1);
+    if count > 1 && !config.reuse_port {
+        return Err(AppError::Internal(anyhow!(
+            "Multiple listeners on one port require reuse_port"
+        )));
+    }
+
+    (0..count).map(|_| bind(config.addr, config.reuse_port)).collect()
+}
+
+fn bind(addr: SocketAddr, reuse_port: bool) -> Result<TcpListener> {
+    let bind_error = |e: std::io::Error| AppError::Internal(anyhow!("Failed to bind {}: {}", addr, e));
+    let socket = if addr.is_ipv4() { TcpSocket::new_v4() } else { TcpSocket::new_v6() }.map_err(bind_error)?;
+    socket.set_reuseaddr(true).map_err(bind_error)?;
+    if reuse_port {
+        socket.set_reuseport(true).map_err(bind_error)?;
+    }
+    socket.bind(addr).map_err(bind_error)?;
+    socket.listen(1024).map_err(bind_error)
+}
+
+/// Serves the router on every listener until `shutdown` resolves, then waits for
+/// in-flight requests to complete. With TLS, connections are terminated here and
+/// requests carry the verified [`ClientCertificate`], if any.
+pub async fn serve(
+    app: Router,
+    listeners: Vec<TcpListener>,
+    tls: Option<TlsConfig>,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown.await;
+        let _ = shutdown_tx.send(true);
+    });
+
+    let mut tasks = JoinSet::new();
+    match tls {
+        Some(tls) => {
+            let acceptor = TlsAcceptor::from(tls.server_config()?);
+            let bindings = Arc::new(tls.api_key_bindings);
+            let app = app.layer(middleware::from_fn_with_state(bindings, enforce_api_key_binding));
+            for listener in listeners {
+                tasks.spawn(accept_tls(listener, acceptor.clone(), app.clone(), shutdown_rx.clone()));
+            }
+        }
+        None => {
+            for listener in listeners {
+                let mut shutdown = shutdown_rx.clone();
+                let app = app.clone();
+                tasks.spawn(async move {
+                    axum::serve(listener, app)
+                        .with_graceful_shutdown(async move { stopped(&mut shutdown).await })
+                        .await
+                        .map_err(|e| AppError::Internal(anyhow!("HTTP server failed: {}", e)))
+                });
+            }
+        }
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        result.map_err(|e| AppError::Internal(anyhow!("Listener task failed: {}", e)))??;
+    }
+    Ok(())
+}
+
+async fn accept_tls(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<()> {
+    let mut connections = JoinSet::new();
+
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            },
+            _ = stopped(&mut shutdown) => break,
+        };
+
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        let mut shutdown = shutdown.clone();
+        connections.spawn(async move {
+            let tls = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(tls)) => tls,
+                Ok(Err(e)) => {
+                    debug!(%peer, "TLS handshake failed: {}", e);
+                    return;
+                }
+                Err(_) => {
+                    debug!(%peer, "TLS handshake timed out");
+                    return;
+                }
+            };
+
+            let client_certificate = tls
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|chain| chain.first())
+                .map(|cert| ClientCertificate {
+                    fingerprint: certificate_fingerprint(cert),
+                });
+            let service = TowerToHyperService::new(app.layer(Extension(client_certificate)));
+
+            let connection = http1::Builder::new().serve_connection(TokioIo::new(tls), service);
+            tokio::pin!(connection);
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = stopped(&mut shutdown) => {
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+            if let Err(e) = result {
+                debug!(%peer, "Connection closed with error: {}", e);
+            }
+        });
+
+        // Reap finished connections so the set does not grow without bound
+        while connections.try_join_next().is_some() {}
+    }
+
+    info!("Draining {} open TLS connections", connections.len());
+    while connections.join_next().await.is_some() {}
+    Ok(())
+}
+
+/// Resolves once shutdown has been signalled.
+async fn stopped(shutdown: &mut watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stop| *stop).await;
+}
+
+/// Rejects requests using an API key that is bound to a different client
+/// certificate than the one presented on the connection.
+async fn enforce_api_key_binding(
+    State(bindings): State<Arc<HashMap<String, String>>>,
+    Extension(client_certificate): Extension<Option<ClientCertificate>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let api_key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    if let Some(api_key) = api_key {
+        if !is_binding_satisfied(&bindings, api_key, client_certificate.as_ref()) {
+            warn!("API key used without the client certificate it is bound to");
+            return (
+                StatusCode::FORBIDDEN,
+                "API key is not valid for the presented client certificate",
+            )
+                .into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Unbound keys are accepted on any connection; bound keys only with their certificate.
+fn is_binding_satisfied(
+    bindings: &HashMap<String, String>,
+    api_key: &str,
+    client_certificate: Option<&ClientCertificate>,
+) -> bool {
+    let key_hash = hex::encode(Sha256::digest(api_key.as_bytes()));
+    match bindings.get(&key_hash) {
+        Some(expected) => client_certificate
+            .is_some_and(|cert| cert.fingerprint.eq_ignore_ascii_case(expected)),
+        None => true,
+    }
+}
+
+fn certificate_fingerprint(cert: &Certificate) -> String {
+    hex::encode(Sha256::digest(&cert.0))
+}
+
+fn open_pem(path: &Path) -> Result<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| AppError::Internal(anyhow!("Failed to open {}: {}", path.display(), e)))
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let certs = rustls_pemfile::certs(&mut open_pem(path)?)
+        .map_err(|e| AppError::Internal(anyhow!("Failed to read {}: {}", path.display(), e)))?;
+    if certs.is_empty() {
+        return Err(AppError::Internal(anyhow!("No certificates found in {}", path.display())));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey> {
+    let mut reader = open_pem(path)?;
+    loop {
+        match rustls_pemfile::read_one(&mut reader)
+            .map_err(|e| AppError::Internal(anyhow!("Failed to read {}: {}", path.display(), e)))?
+        {
+            Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(PrivateKey(key)),
+            Some(_) => continue,
+            None => {
+                return Err(AppError::Internal(anyhow!("No private key found in {}", path.display())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls").join(name)
+    }
+
+    fn tls_config() -> TlsConfig {
+        TlsConfig {
+            cert_path: fixture("server.crt"),
+            key_path: fixture("server.key"),
+            client_ca_path: Some(fixture("ca.crt")),
+            require_client_cert: true,
+            api_key_bindings: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_server_config_from_pem_files() {
+        assert!(tls_config().server_config().is_ok());
+
+        let mut config = tls_config();
+        config.client_ca_path = None;
+        assert!(config.server_config().is_err());
+        config.require_client_cert = false;
+        assert!(config.server_config().is_ok());
+
+        config.key_path = fixture("server.crt");
+        assert!(config.server_config().is_err());
+    }
+
+    #[test]
+    fn test_api_key_binding() {
+        let client = load_certificates(&fixture("client.crt")).unwrap().remove(0);
+        let server = load_certificates(&fixture("server.crt")).unwrap().remove(0);
+        let client = ClientCertificate { fingerprint: certificate_fingerprint(&client) };
+        let server = ClientCertificate { fingerprint: certificate_fingerprint(&server) };
+
+        let bindings = HashMap::from([(
+            hex::encode(Sha256::digest(b"participant-key")),
+            client.fingerprint.to_uppercase(),
+        )]);
+
+        assert!(is_binding_satisfied(&bindings, "participant-key", Some(&client)));
+        assert!(!is_binding_satisfied(&bindings, "participant-key", Some(&server)));
+        assert!(!is_binding_satisfied(&bindings, "participant-key", None));
+        assert!(is_binding_satisfied(&bindings, "unbound-key", None));
+    }
+
+    #[tokio::test]
+    async fn test_reuse_port_listeners_share_a_port() {
+        let probe = bind("127.0.0.1:0".parse().unwrap(), true).unwrap();
+        let config = ListenerConfig {
+            addr: probe.local_addr().unwrap(),
+            listeners: 2,
+            reuse_port: true,
+        };
+
+        let listeners = bind_listeners(&config).unwrap();
+        assert_eq!(listeners.len(), 2);
+        assert!(bind_listeners(&ListenerConfig { reuse_port: false, ..config }).is_err());
+    }
+}
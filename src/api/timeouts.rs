@@ -0,0 +1,152 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::responses::{ApiResponse, ErrorResponse};
+use crate::error::REQUEST_TIMEOUT_CODE;
+use crate::observability::get_metrics;
+
+/// Time a request may take unless its route has a timeout of its own.
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Routes that run for as long as their work takes unless configured otherwise: batch
+/// processing, which is checkpointed and resumable, and instruction exports.
+pub const UNLIMITED_ROUTES: &[&str] = &[
+    "/batches/:id/process",
+    "/batches/:id/resume",
+    "/batches/:id/instructions/export",
+];
+
+/// How long each route may take to produce its response.
+#[derive(Debug, Clone)]
+pub struct RequestTimeouts {
+    pub default: Duration,
+    /// Timeouts by route prefix, e.g. `/reports` or `/batches/:id/process`, matched
+    /// against the route pattern a whole segment at a time. The longest prefix wins;
+    /// a zero timeout leaves the route unlimited.
+    pub routes: Vec<(String, Duration)>,
+}
+
+impl Default for RequestTimeouts {
+    fn default() -> Self {
+        Self {
+            default: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
+            routes: UNLIMITED_ROUTES
+                .iter()
+                .map(|route| (route.to_string(), Duration::ZERO))
+                .collect(),
+        }
+    }
+}
+
+impl RequestTimeouts {
+    /// Sets a route prefix's timeout, replacing any it had.
+    pub fn with_route(mut self, prefix: impl Into<String>, timeout: Duration) -> Self {
+        let prefix = prefix.into();
+        self.routes.retain(|(existing, _)| *existing != prefix);
+        self.routes.push((prefix, timeout));
+        self
+    }
+
+    /// Timeout of a route, by its pattern or, for unmatched requests, its path. `None`
+    /// if the route is unlimited.
+    pub fn for_route(&self, route: &str) -> Option<Duration> {
+        let timeout = self
+            .routes
+            .iter()
+            .filter(|(prefix, _)| is_segment_prefix(prefix, route))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, timeout)| *timeout)
+            .unwrap_or(self.default);
+        (!timeout.is_zero()).then_some(timeout)
+    }
+}
+
+pub(crate) fn is_segment_prefix(prefix: &str, route: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    route
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Answers `504 REQUEST_TIMEOUT` when the handler does not respond within its route's
+/// timeout. The handler is dropped, which rolls back any open database transaction;
+/// a statement still running on the server is bounded by the database statement
+/// timeout, whose cancellations are reported the same way. Streamed bodies are not
+/// limited once the response has started.
+pub async fn enforce_request_timeout(
+    State(timeouts): State<Arc<RequestTimeouts>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let Some(timeout) = timeouts.for_route(&route) else {
+        return next.run(request).await;
+    };
+
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => {
+            if response.status() == StatusCode::GATEWAY_TIMEOUT {
+                get_metrics().record_request_timeout(&route, "statement");
+            }
+            response
+        }
+        Err(_) => {
+            tracing::warn!("{} timed out after {:?}", route, timeout);
+            get_metrics().record_request_timeout(&route, "request");
+            let error = ErrorResponse::new(
+                REQUEST_TIMEOUT_CODE,
+                format!("The request did not complete within {} seconds", timeout.as_secs_f64()),
+            );
+            (StatusCode::GATEWAY_TIMEOUT, Json(ApiResponse::<()>::error(error))).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_matching_route_prefix_wins() {
+        let timeouts = RequestTimeouts {
+            default: Duration::from_secs(30),
+            routes: vec![
+                ("/reports".to_string(), Duration::from_secs(120)),
+                ("/batches/".to_string(), Duration::from_secs(60)),
+                ("/batches/:id/process".to_string(), Duration::from_secs(300)),
+            ],
+        };
+
+        assert_eq!(timeouts.for_route("/reports/netting"), Some(Duration::from_secs(120)));
+        assert_eq!(timeouts.for_route("/reports"), Some(Duration::from_secs(120)));
+        assert_eq!(timeouts.for_route("/reports-archive"), Some(Duration::from_secs(30)));
+        assert_eq!(timeouts.for_route("/batches/:id"), Some(Duration::from_secs(60)));
+        assert_eq!(timeouts.for_route("/batches/:id/process"), Some(Duration::from_secs(300)));
+        assert_eq!(timeouts.for_route("/accounts/:id"), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_processing_and_export_routes_are_unlimited_by_default() {
+        let timeouts = RequestTimeouts::default();
+
+        assert_eq!(timeouts.for_route("/batches/:id/process"), None);
+        assert_eq!(timeouts.for_route("/batches/:id/resume"), None);
+        assert_eq!(timeouts.for_route("/batches/:id/instructions/export"), None);
+        assert_eq!(timeouts.for_route("/batches/:id"), Some(Duration::from_secs(30)));
+
+        let timeouts = timeouts.with_route("/batches/:id/process", Duration::from_secs(600));
+        assert_eq!(timeouts.for_route("/batches/:id/process"), Some(Duration::from_secs(600)));
+        assert_eq!(timeouts.for_route("/batches/:id/resume"), None);
+    }
+}
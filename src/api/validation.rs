@@ -0,0 +1,338 @@
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, StatusCode},
+    Json,
+};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+
+use crate::api::requests::ValidationError;
+use crate::api::responses::{ApiResponse, ErrorResponse, ValidationErrorDetail};
+use crate::models::{Instrument, MAX_INSTRUMENT_CODE_LEN};
+
+/// Largest request body accepted by routes without a limit of their own.
+pub const DEFAULT_BODY_LIMIT: usize = 64 * 1024;
+/// Body limit of routes taking documents, such as metadata JSON Schemas.
+pub const DOCUMENT_BODY_LIMIT: usize = 512 * 1024;
+/// Longest external ID, idempotency key, name or reference, as stored.
+pub const MAX_IDENTIFIER_LEN: usize = 255;
+/// Longest free-text field, such as a reason or note.
+pub const MAX_TEXT_LEN: usize = 2_000;
+/// Longest webhook URL.
+pub const MAX_URL_LEN: usize = 2_048;
+/// Decimal places amounts are stored with; more would be rounded silently.
+pub const AMOUNT_SCALE: u32 = 4;
+/// Integer digits amounts are stored with (DECIMAL(19, 4)).
+pub const AMOUNT_INTEGER_DIGITS: u32 = 15;
+/// Decimal places FX rates and spreads are stored with (DECIMAL(24, 10)).
+pub const FX_RATE_SCALE: u32 = 10;
+/// Longest FX rate source, as stored.
+pub const MAX_FX_SOURCE_LEN: usize = 100;
+
+/// A JSON request body validated before it reaches a handler by `ValidJson`.
+pub trait RequestBody: DeserializeOwned {
+    /// Brings identifiers into their canonical form before validation, e.g. trims
+    /// external IDs and upper-cases currency codes.
+    fn canonicalize(&mut self) {}
+
+    /// Checks the request, returning an error for each invalid field.
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        Ok(())
+    }
+}
+
+/// JSON body extractor that deserializes strictly, canonicalizes and validates the
+/// request. Malformed bodies, unknown fields and failed checks are all rejected with a
+/// `VALIDATION_ERROR` listing the offending fields; bodies over the route's limit with
+/// `PAYLOAD_TOO_LARGE`.
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ValidJson<T>
+where
+    S: Send + Sync,
+    T: RequestBody,
+{
+    type Rejection = (StatusCode, Json<ApiResponse<()>>);
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !is_json(&req) {
+            return Err((
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                Json(ApiResponse::<()>::error(ErrorResponse::new(
+                    "UNSUPPORTED_MEDIA_TYPE",
+                    "Expected request with `Content-Type: application/json`",
+                ))),
+            ));
+        }
+
+        let bytes = Bytes::from_request(req, state).await.map_err(|rejection| {
+            let status = rejection.status();
+            let code = if status == StatusCode::PAYLOAD_TOO_LARGE { "PAYLOAD_TOO_LARGE" } else { "INVALID_BODY" };
+            (status, Json(ApiResponse::<()>::error(ErrorResponse::new(code, rejection.body_text()))))
+        })?;
+
+        let mut request = parse_body::<T>(&bytes).map_err(|error| validation_failed(vec![error]))?;
+        request.canonicalize();
+        request.validate().map_err(validation_failed)?;
+        Ok(Self(request))
+    }
+}
+
+fn is_json(req: &Request) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|mime| {
+            let mime = mime.trim();
+            mime.eq_ignore_ascii_case("application/json") || mime.ends_with("+json")
+        })
+        .unwrap_or(false)
+}
+
+/// Deserializes a body, reporting the path of the field that failed. Unknown fields
+/// are rejected by `deny_unknown_fields` on the request types.
+pub fn parse_body<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ValidationError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|error| {
+        let path = error.path().to_string();
+        ValidationError {
+            field: if path == "." { "body".to_string() } else { path },
+            message: error.into_inner().to_string(),
+        }
+    })?;
+    deserializer.end().map_err(|error| ValidationError { field: "body".to_string(), message: error.to_string() })?;
+    Ok(value)
+}
+
+/// The 400 response listing each invalid field.
+pub fn validation_failed(errors: Vec<ValidationError>) -> (StatusCode, Json<ApiResponse<()>>) {
+    let details: Vec<ValidationErrorDetail> = errors
+        .into_iter()
+        .map(|e| ValidationErrorDetail { field: e.field, message: e.message })
+        .collect();
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::<()>::error(
+            ErrorResponse::new("VALIDATION_ERROR", "Request validation failed").with_details(details),
+        )),
+    )
+}
+
+/// Canonical form of an identifier: surrounding whitespace removed.
+pub fn canonicalize_identifier(value: &mut String) {
+    let trimmed = value.trim();
+    if trimmed.len() != value.len() {
+        *value = trimmed.to_string();
+    }
+}
+
+/// Canonical form of a code, such as a currency or transaction type: trimmed and
+/// upper-case.
+pub fn canonicalize_code(value: &mut String) {
+    canonicalize_identifier(value);
+    value.make_ascii_uppercase();
+}
+
+/// Collects the field errors of a request.
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<ValidationError>);
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.0.push(ValidationError { field: field.to_string(), message: message.into() });
+    }
+
+    /// A required identifier: not empty and at most `MAX_IDENTIFIER_LEN` characters.
+    pub fn identifier(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.push(field, format!("{} cannot be empty", field));
+        } else if value.chars().count() > MAX_IDENTIFIER_LEN {
+            self.push(field, format!("{} must be at most {} characters", field, MAX_IDENTIFIER_LEN));
+        }
+    }
+
+    /// A required free-text field: not empty and at most `MAX_TEXT_LEN` characters.
+    pub fn text(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.push(field, format!("{} cannot be empty", field));
+        } else {
+            self.max_len(field, value, MAX_TEXT_LEN);
+        }
+    }
+
+    /// An optional field of at most `max` characters.
+    pub fn max_len(&mut self, field: &str, value: &str, max: usize) {
+        if value.chars().count() > max {
+            self.push(field, format!("{} must be at most {} characters", field, max));
+        }
+    }
+
+    /// A 3-letter ISO 4217 currency code, after canonicalization.
+    pub fn currency(&mut self, field: &str, value: &str) {
+        if value.len() != 3 || !value.bytes().all(|b| b.is_ascii_uppercase()) {
+            self.push(field, format!("{} must be a 3-letter ISO 4217 code", field));
+        }
+    }
+
+    /// A currency code or the code of a registered instrument, after canonicalization.
+    /// Whether a longer code is registered is checked when the request is processed.
+    pub fn instrument(&mut self, field: &str, value: &str) {
+        if !Instrument::is_valid_code(value) {
+            self.push(
+                field,
+                format!(
+                    "{} must be a currency or instrument code of 3 to {} letters and digits",
+                    field, MAX_INSTRUMENT_CODE_LEN
+                ),
+            );
+        }
+    }
+
+    /// An amount that fits the ledger's columns: at most `AMOUNT_INTEGER_DIGITS` integer
+    /// digits and `AMOUNT_SCALE` decimal places.
+    pub fn amount(&mut self, field: &str, amount: Decimal) {
+        if amount.abs() >= Decimal::from(10_i64.pow(AMOUNT_INTEGER_DIGITS)) {
+            self.push(field, format!("{} must be less than 10^{}", field, AMOUNT_INTEGER_DIGITS));
+        } else if amount.normalize().scale() > AMOUNT_SCALE {
+            self.push(field, format!("{} must have at most {} decimal places", field, AMOUNT_SCALE));
+        }
+    }
+
+    /// A positive amount that fits the ledger's columns.
+    pub fn positive_amount(&mut self, field: &str, amount: Decimal) {
+        if amount <= Decimal::ZERO {
+            self.push(field, format!("{} must be positive", field));
+        } else {
+            self.amount(field, amount);
+        }
+    }
+
+    /// A non-negative amount that fits the ledger's columns.
+    pub fn non_negative_amount(&mut self, field: &str, amount: Decimal) {
+        if amount < Decimal::ZERO {
+            self.push(field, format!("{} cannot be negative", field));
+        } else {
+            self.amount(field, amount);
+        }
+    }
+
+    /// A positive FX rate of at most `FX_RATE_SCALE` decimal places.
+    pub fn fx_rate(&mut self, field: &str, rate: Decimal) {
+        if rate <= Decimal::ZERO {
+            self.push(field, format!("{} must be positive", field));
+        } else if rate.normalize().scale() > FX_RATE_SCALE {
+            self.push(field, format!("{} must have at most {} decimal places", field, FX_RATE_SCALE));
+        }
+    }
+
+    /// A spread: the fraction of a rate kept on conversions, at least 0 and less than 1.
+    pub fn spread(&mut self, field: &str, spread: Decimal) {
+        if spread < Decimal::ZERO || spread >= Decimal::ONE {
+            self.push(field, format!("{} must be at least 0 and less than 1", field));
+        } else if spread.normalize().scale() > FX_RATE_SCALE {
+            self.push(field, format!("{} must have at most {} decimal places", field, FX_RATE_SCALE));
+        }
+    }
+
+    /// Errors of a nested request, reported under `prefix`, e.g. `transactions[0].amount`.
+    pub fn nested(&mut self, prefix: &str, result: Result<(), Vec<ValidationError>>) {
+        if let Err(errors) = result {
+            self.0.extend(errors.into_iter().map(|error| ValidationError {
+                field: format!("{}.{}", prefix, error.field),
+                message: error.message,
+            }));
+        }
+    }
+
+    pub fn finish(self) -> Result<(), Vec<ValidationError>> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Body {
+        amount: Decimal,
+        nested: Option<Nested>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Nested {
+        count: i32,
+    }
+
+    #[test]
+    fn test_parse_body_reports_field_paths() {
+        assert!(parse_body::<Body>(br#"{"amount": "1.5"}"#).is_ok());
+
+        let unknown = parse_body::<Body>(br#"{"amount": "1.5", "amuont": "2"}"#).unwrap_err();
+        assert!(unknown.message.contains("unknown field `amuont`"));
+
+        let nested = parse_body::<Body>(br#"{"amount": "1", "nested": {"count": "x"}}"#).unwrap_err();
+        assert_eq!(nested.field, "nested.count");
+
+        let trailing = parse_body::<Body>(br#"{"amount": "1"} {}"#).unwrap_err();
+        assert_eq!(trailing.field, "body");
+    }
+
+    #[test]
+    fn test_canonicalization() {
+        let mut currency = " usd ".to_string();
+        canonicalize_code(&mut currency);
+        assert_eq!(currency, "USD");
+
+        let mut external_id = "\tTX-1 ".to_string();
+        canonicalize_identifier(&mut external_id);
+        assert_eq!(external_id, "TX-1");
+    }
+
+    #[test]
+    fn test_amount_caps() {
+        let mut errors = FieldErrors::new();
+        errors.positive_amount("amount", dec!(999999999999999.9999));
+        errors.amount("fee_amount", dec!(1.5000000));
+        assert!(errors.finish().is_ok());
+
+        let mut errors = FieldErrors::new();
+        errors.positive_amount("amount", dec!(1000000000000000));
+        errors.amount("fee_amount", dec!(0.00001));
+        errors.positive_amount("other", Decimal::ZERO);
+        assert_eq!(errors.finish().unwrap_err().len(), 3);
+    }
+
+    #[test]
+    fn test_identifier_and_currency_checks() {
+        let mut errors = FieldErrors::new();
+        errors.identifier("external_id", &"x".repeat(MAX_IDENTIFIER_LEN));
+        errors.currency("currency", "EUR");
+        assert!(errors.finish().is_ok());
+
+        let mut errors = FieldErrors::new();
+        errors.identifier("external_id", &"x".repeat(MAX_IDENTIFIER_LEN + 1));
+        errors.identifier("idempotency_key", "  ");
+        errors.currency("currency", "eu1");
+        let fields: Vec<_> = errors.finish().unwrap_err().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, vec!["external_id", "idempotency_key", "currency"]);
+    }
+}
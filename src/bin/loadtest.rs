@@ -0,0 +1,86 @@
+//! Load-tests a running server over HTTP and checks the results against latency SLOs.
+//!
+//! Seeds funded accounts, then keeps `LOADTEST_CONCURRENCY` clients sending a mix of
+//! payments, transfers, async submissions and balance reads for the run's duration.
+//! Prints a JSON report with throughput, latency percentiles and errors, and exits with
+//! status 1 if any SLO was missed, so it can gate a release pipeline.
+//!
+//! Tunables are read from the environment:
+//! - `LOADTEST_BASE_URL` (default `http://localhost:3000`)
+//! - `LOADTEST_API_KEY` (sent as `x-api-key` when set)
+//! - `LOADTEST_CONCURRENCY` (default 32)
+//! - `LOADTEST_DURATION_SECS` (default 30)
+//! - `LOADTEST_ACCOUNTS` (default 20)
+//! - `LOADTEST_MIX` (default `payment=6,transfer=1,async=1,balance=2`)
+//! - `LOADTEST_AMOUNT` (default 10.00)
+//! - `LOADTEST_CURRENCY` (default: a fresh code per run)
+//! - `LOADTEST_SLO_P99_MS` (default 100)
+//! - `LOADTEST_SLO_MIN_TPS` (default 0, no throughput floor)
+//! - `LOADTEST_SLO_MAX_ERROR_RATE` (default 0.01)
+//! - `LOADTEST_REPORT_PATH` (also write the report to this file)
+
+use rust_decimal::Decimal;
+use settlement_engine::tools::{parse_operation_mix, LoadTestConfig, LoadTester, SloThresholds};
+use std::time::Duration;
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+
+    let defaults = LoadTestConfig::default();
+    let mut config = LoadTestConfig::default()
+        .with_base_url(env_or("LOADTEST_BASE_URL", defaults.base_url.clone()))
+        .with_accounts(env_or("LOADTEST_ACCOUNTS", defaults.accounts))
+        .with_amount(env_or::<Decimal>("LOADTEST_AMOUNT", defaults.amount))
+        .with_load(
+            env_or("LOADTEST_CONCURRENCY", defaults.concurrency),
+            Duration::from_secs(env_or("LOADTEST_DURATION_SECS", defaults.duration.as_secs())),
+        )
+        .with_slo(SloThresholds {
+            p99_ms: env_or("LOADTEST_SLO_P99_MS", defaults.slo.p99_ms),
+            min_throughput: env_or("LOADTEST_SLO_MIN_TPS", defaults.slo.min_throughput),
+            max_error_rate: env_or("LOADTEST_SLO_MAX_ERROR_RATE", defaults.slo.max_error_rate),
+        });
+    if let Ok(mix) = std::env::var("LOADTEST_MIX") {
+        config = config.with_mix(parse_operation_mix(&mix)?);
+    }
+    if let Ok(currency) = std::env::var("LOADTEST_CURRENCY") {
+        config = config.with_currency(currency);
+    }
+    if let Ok(api_key) = std::env::var("LOADTEST_API_KEY") {
+        config = config.with_api_key(api_key);
+    }
+
+    let tester = LoadTester::new(config)?;
+    let config = tester.config();
+    eprintln!(
+        "loadtest: {} with {} clients for {:?}, mix {:?}",
+        config.base_url,
+        config.concurrency,
+        config.duration,
+        config.mix.iter().map(|(operation, weight)| format!("{}={}", operation.as_str(), weight)).collect::<Vec<_>>()
+    );
+    let report = tester.run().await?;
+
+    let json = serde_json::to_string_pretty(&report)?;
+    println!("{}", json);
+    if let Ok(path) = std::env::var("LOADTEST_REPORT_PATH") {
+        std::fs::write(&path, &json)?;
+        eprintln!("report written to {}", path);
+    }
+    for check in report.slo.iter().filter(|check| !check.passed) {
+        eprintln!("SLO missed: {} was {:.3}, threshold {:.3}", check.name, check.actual, check.threshold);
+    }
+    if !report.passed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
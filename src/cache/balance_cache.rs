@@ -1,8 +1,8 @@
+use crate::cache::redis_pool::RedisPool;
 use crate::config::CacheSettings;
 use crate::error::{AppError, Result};
 use crate::models::AccountBalance;
 use crate::observability::get_metrics;
-use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -76,15 +76,15 @@ struct CachedBalance {
 
 /// Redis-based cache for account balances.
 pub struct BalanceCache {
-    client: redis::Client,
+    redis: Arc<RedisPool>,
     settings: CacheSettings,
     stats: Arc<CacheStats>,
 }
 
 impl BalanceCache {
-    pub fn new(client: redis::Client, settings: CacheSettings) -> Self {
+    pub fn new(redis: Arc<RedisPool>, settings: CacheSettings) -> Self {
         Self {
-            client,
+            redis,
             settings,
             stats: Arc::new(CacheStats::new()),
         }
@@ -112,16 +112,7 @@ impl BalanceCache {
         let key = self.cache_key(account_id, currency);
         let start = std::time::Instant::now();
 
-        let mut conn = match self.client.get_multiplexed_async_connection().await {
-            Ok(c) => c,
-            Err(e) => {
-                self.stats.record_error();
-                tracing::warn!("Redis connection error in cache get: {}", e);
-                return Ok(None);
-            }
-        };
-
-        let result: Option<String> = match conn.get(&key).await {
+        let result: Option<String> = match self.redis.query(redis::cmd("GET").arg(&key)).await {
             Ok(v) => v,
             Err(e) => {
                 self.stats.record_error();
@@ -177,17 +168,12 @@ impl BalanceCache {
         let json = serde_json::to_string(&cached)
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize balance: {}", e)))?;
 
-        let mut conn = match self.client.get_multiplexed_async_connection().await {
-            Ok(c) => c,
-            Err(e) => {
-                self.stats.record_error();
-                tracing::warn!("Redis connection error in cache set: {}", e);
-                return Ok(());
-            }
-        };
-
         let ttl = self.settings.balance_ttl_secs as u64;
-        match conn.set_ex::<_, _, ()>(&key, json, ttl).await {
+        match self
+            .redis
+            .query::<()>(redis::cmd("SET").arg(&key).arg(json).arg("EX").arg(ttl))
+            .await
+        {
             Ok(_) => {
                 tracing::debug!(
                     account_id = %balance.account_id,
@@ -200,7 +186,7 @@ impl BalanceCache {
             Err(e) => {
                 self.stats.record_error();
                 tracing::warn!("Redis set error: {}", e);
-                Err(AppError::Redis(e))
+                Err(e)
             }
         }
     }
@@ -213,16 +199,7 @@ impl BalanceCache {
 
         let key = self.cache_key(account_id, currency);
 
-        let mut conn = match self.client.get_multiplexed_async_connection().await {
-            Ok(c) => c,
-            Err(e) => {
-                self.stats.record_error();
-                tracing::warn!("Redis connection error in cache invalidate: {}", e);
-                return Ok(());
-            }
-        };
-
-        if let Err(e) = conn.del::<_, ()>(&key).await {
+        if let Err(e) = self.redis.query::<()>(redis::cmd("DEL").arg(&key)).await {
             self.stats.record_error();
             tracing::warn!("Redis del error: {}", e);
         } else {
@@ -237,7 +214,8 @@ impl BalanceCache {
         Ok(())
     }
 
-    /// Invalidates all cached balances for an account.
+    /// Invalidates all cached balances for an account. Against Redis Cluster the
+    /// SCAN only covers the node it is routed to.
     pub async fn invalidate_account(&self, account_id: Uuid) -> Result<()> {
         if !self.settings.enabled {
             return Ok(());
@@ -245,24 +223,18 @@ impl BalanceCache {
 
         let pattern = format!("{}:balance:{}:*", self.settings.key_prefix, account_id);
 
-        let mut conn = match self.client.get_multiplexed_async_connection().await {
-            Ok(c) => c,
-            Err(e) => {
-                self.stats.record_error();
-                tracing::warn!("Redis connection error in cache invalidate_account: {}", e);
-                return Ok(());
-            }
-        };
-
         let mut cursor: u64 = 0;
         loop {
-            let scan_result: (u64, Vec<String>) = match redis::cmd("SCAN")
-                .arg(cursor)
-                .arg("MATCH")
-                .arg(&pattern)
-                .arg("COUNT")
-                .arg(100)
-                .query_async(&mut conn)
+            let scan_result: (u64, Vec<String>) = match self
+                .redis
+                .query(
+                    redis::cmd("SCAN")
+                        .arg(cursor)
+                        .arg("MATCH")
+                        .arg(&pattern)
+                        .arg("COUNT")
+                        .arg(100),
+                )
                 .await
             {
                 Ok(r) => r,
@@ -276,7 +248,7 @@ impl BalanceCache {
             let (new_cursor, keys) = scan_result;
 
             for key in keys {
-                if let Err(e) = conn.del::<_, ()>(&key).await {
+                if let Err(e) = self.redis.query::<()>(redis::cmd("DEL").arg(&key)).await {
                     self.stats.record_error();
                     tracing::warn!("Redis del error for key {}: {}", key, e);
                 } else {
@@ -322,6 +294,7 @@ impl BalanceCache {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::redis_pool::RedisPoolConfig;
 
     #[test]
     fn test_cache_stats() {
@@ -347,8 +320,8 @@ mod tests {
             balance_ttl_secs: 60,
             key_prefix: "test".to_string(),
         };
-        let client = redis::Client::open("redis://localhost:6379").unwrap();
-        let cache = BalanceCache::new(client, settings);
+        let redis = Arc::new(RedisPool::new(RedisPoolConfig::standalone("redis://localhost:6379")));
+        let cache = BalanceCache::new(redis, settings);
 
         let account_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
         let key = cache.cache_key(account_id, "USD");
@@ -1,3 +1,5 @@
 pub mod balance_cache;
+pub mod redis_pool;
 
 pub use balance_cache::{BalanceCache, CacheStats};
+pub use redis_pool::{RedisConnection, RedisPool, RedisPoolConfig, RedisTopology};
@@ -0,0 +1,252 @@
+use crate::core::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::error::{AppError, Result};
+use anyhow::anyhow;
+use redis::aio::{ConnectionLike, MultiplexedConnection};
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::sentinel::Sentinel;
+use redis::{Cmd, FromRedisValue, Pipeline, RedisError, RedisFuture, RedisResult, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex as AsyncMutex;
+
+/// Redis deployment to connect to.
+#[derive(Debug, Clone)]
+pub enum RedisTopology {
+    /// A single server.
+    Standalone { url: String },
+    /// Redis Cluster, discovered from any of the seed nodes.
+    Cluster { nodes: Vec<String> },
+    /// A master located through Sentinel. The master is looked up again on every
+    /// reconnect, so failovers are followed.
+    Sentinel { sentinels: Vec<String>, master_name: String },
+}
+
+/// Connection pool settings.
+#[derive(Debug, Clone)]
+pub struct RedisPoolConfig {
+    pub topology: RedisTopology,
+    /// Number of multiplexed connections requests are spread over.
+    pub pool_size: usize,
+    pub connect_timeout: Duration,
+    pub command_timeout: Duration,
+    /// Reconnect attempts after a failed connect, before giving up on a request.
+    pub reconnect_attempts: u32,
+    /// Delay before the first reconnect attempt; doubles up to `max_backoff`.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Breaker fed by connection failures and timeouts.
+    pub circuit: CircuitBreakerConfig,
+}
+
+impl RedisPoolConfig {
+    pub fn new(topology: RedisTopology) -> Self {
+        Self {
+            topology,
+            pool_size: 4,
+            connect_timeout: Duration::from_secs(2),
+            command_timeout: Duration::from_secs(1),
+            reconnect_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            circuit: CircuitBreakerConfig::default(),
+        }
+    }
+
+    pub fn standalone(url: impl Into<String>) -> Self {
+        Self::new(RedisTopology::Standalone { url: url.into() })
+    }
+}
+
+/// A connection to either a single node or a cluster.
+#[derive(Clone)]
+pub enum RedisConnection {
+    Single(MultiplexedConnection),
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_command(cmd),
+            RedisConnection::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Single(conn) => conn.get_db(),
+            RedisConnection::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+/// Pool of Redis connections with reconnect backoff and a circuit breaker.
+///
+/// Connections are opened lazily and replaced after connection-level errors. While
+/// the circuit is open, commands fail immediately so callers can fall back to the
+/// database instead of waiting on timeouts.
+pub struct RedisPool {
+    config: RedisPoolConfig,
+    slots: Vec<AsyncMutex<Option<RedisConnection>>>,
+    next_slot: AtomicUsize,
+    breaker: CircuitBreaker,
+}
+
+impl RedisPool {
+    pub fn new(config: RedisPoolConfig) -> Self {
+        let slots = (0..config.pool_size.max(1)).map(|_| AsyncMutex::new(None)).collect();
+        let breaker = CircuitBreaker::new("redis", config.circuit.clone());
+        Self {
+            config,
+            slots,
+            next_slot: AtomicUsize::new(0),
+            breaker,
+        }
+    }
+
+    /// Current circuit breaker state.
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.state()
+    }
+
+    /// Returns false while the circuit is open.
+    pub fn is_available(&self) -> bool {
+        self.breaker.is_available()
+    }
+
+    /// Runs a command, reconnecting if needed. Only connection failures and timeouts
+    /// count against the circuit; command errors mean Redis is answering.
+    pub async fn query<T: FromRedisValue>(&self, cmd: &Cmd) -> Result<T> {
+        self.breaker.try_acquire()?;
+        let (slot, mut conn) = self.checkout().await?;
+
+        match tokio::time::timeout(self.config.command_timeout, cmd.query_async::<_, T>(&mut conn)).await {
+            Ok(Ok(value)) => {
+                self.breaker.record_success();
+                Ok(value)
+            }
+            Ok(Err(e)) => {
+                if is_connection_error(&e) {
+                    self.breaker.record_failure();
+                    self.reset(slot).await;
+                } else {
+                    self.breaker.record_success();
+                }
+                Err(AppError::Redis(e))
+            }
+            Err(_) => {
+                self.breaker.record_failure();
+                self.reset(slot).await;
+                Err(AppError::Internal(anyhow!(
+                    "Redis command timed out after {:?}",
+                    self.config.command_timeout
+                )))
+            }
+        }
+    }
+
+    /// Checks that Redis answers a PING.
+    pub async fn ping(&self) -> Result<()> {
+        self.query::<()>(&redis::cmd("PING")).await
+    }
+
+    async fn checkout(&self) -> Result<(usize, RedisConnection)> {
+        let slot = self.next_slot.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+        let mut guard = self.slots[slot].lock().await;
+        if let Some(conn) = guard.as_ref() {
+            return Ok((slot, conn.clone()));
+        }
+
+        let conn = self.connect_with_backoff().await?;
+        *guard = Some(conn.clone());
+        Ok((slot, conn))
+    }
+
+    async fn reset(&self, slot: usize) {
+        *self.slots[slot].lock().await = None;
+    }
+
+    async fn connect_with_backoff(&self) -> Result<RedisConnection> {
+        let mut backoff = self.config.initial_backoff;
+        let mut last_error = String::new();
+
+        for attempt in 0..=self.config.reconnect_attempts {
+            if attempt > 0 {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(self.config.max_backoff);
+            }
+
+            match tokio::time::timeout(self.config.connect_timeout, self.connect()).await {
+                Ok(Ok(conn)) => return Ok(conn),
+                Ok(Err(e)) => last_error = e.to_string(),
+                Err(_) => last_error = format!("timed out after {:?}", self.config.connect_timeout),
+            }
+            tracing::debug!(attempt, "Redis connect failed: {}", last_error);
+        }
+
+        self.breaker.record_failure();
+        Err(AppError::Internal(anyhow!("Failed to connect to Redis: {}", last_error)))
+    }
+
+    async fn connect(&self) -> RedisResult<RedisConnection> {
+        match &self.config.topology {
+            RedisTopology::Standalone { url } => {
+                let client = redis::Client::open(url.as_str())?;
+                Ok(RedisConnection::Single(client.get_multiplexed_async_connection().await?))
+            }
+            RedisTopology::Cluster { nodes } => {
+                let client = ClusterClient::new(nodes.clone())?;
+                Ok(RedisConnection::Cluster(client.get_async_connection().await?))
+            }
+            RedisTopology::Sentinel { sentinels, master_name } => {
+                let mut sentinel = Sentinel::build(sentinels.clone())?;
+                let client = sentinel.async_master_for(master_name, None).await?;
+                Ok(RedisConnection::Single(client.get_multiplexed_async_connection().await?))
+            }
+        }
+    }
+}
+
+fn is_connection_error(error: &RedisError) -> bool {
+    error.is_io_error()
+        || error.is_connection_dropped()
+        || error.is_connection_refusal()
+        || error.is_timeout()
+        || error.is_cluster_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unreachable_redis_opens_circuit() {
+        let mut config = RedisPoolConfig::standalone("redis://127.0.0.1:1");
+        config.reconnect_attempts = 1;
+        config.initial_backoff = Duration::from_millis(1);
+        config.circuit.minimum_calls = 2;
+        let pool = RedisPool::new(config);
+
+        for _ in 0..2 {
+            let error = pool.ping().await.unwrap_err();
+            assert!(error.to_string().contains("Failed to connect"));
+        }
+        assert!(!pool.is_available());
+
+        let error = pool.ping().await.unwrap_err();
+        assert!(error.to_string().contains("circuit open"));
+    }
+}
@@ -1,4 +1,12 @@
+use crate::core::rounding::RoundingMode;
+use crate::models::{
+    AccountType, DefaultResolution, DuplicateExternalIdAction, DuplicatePaymentAction, EngineMode, ExternalIdScope, FeeBookingMode,
+    FeeReversalPolicy, InstructionStrategy, InternalAccountRole, LossAllocationBasis, NetDebitCapAction, TransactionType,
+};
+use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::collections::HashMap;
+use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
 pub struct Settings {
@@ -6,8 +14,88 @@ pub struct Settings {
     pub redis: RedisSettings,
     pub kafka: KafkaSettings,
     pub application: ApplicationSettings,
+    /// GROSS (pure RTGS, no batching), NET (deferred net, RTGS lane closed) or HYBRID
+    /// (routed by the `[rtgs]` thresholds, the default).
+    #[serde(default)]
+    pub engine_mode: EngineMode,
     #[serde(default)]
     pub cache: CacheSettings,
+    #[serde(default)]
+    pub health: HealthSettings,
+    #[serde(default)]
+    pub rtgs: RtgsSettings,
+    #[serde(default)]
+    pub finality: FinalitySettings,
+    #[serde(default)]
+    pub rail: RailSettings,
+    #[serde(default)]
+    pub nacha: Option<NachaSettings>,
+    #[serde(default)]
+    pub delivery: DeliverySettings,
+    #[serde(default)]
+    pub advices: AdviceSettings,
+    #[serde(default)]
+    pub funding: FundingSettings,
+    #[serde(default)]
+    pub pvp: PvpSettings,
+    #[serde(default)]
+    pub fx_revaluation: FxRevaluationSettings,
+    #[serde(default)]
+    pub rounding: RoundingSettings,
+    #[serde(default)]
+    pub timeouts: TimeoutSettings,
+    #[serde(default)]
+    pub admission: AdmissionSettings,
+    #[serde(default)]
+    pub idempotency: IdempotencySettings,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
+    #[serde(default)]
+    pub retention: RetentionSettings,
+    #[serde(default)]
+    pub outbox: OutboxSettings,
+    #[serde(default)]
+    pub batching: BatchingSettings,
+    #[serde(default)]
+    pub netting: NettingSettings,
+    #[serde(default)]
+    pub net_debit_caps: NetDebitCapSettings,
+    #[serde(default)]
+    pub submission: SubmissionSettings,
+    #[serde(default)]
+    pub write_combining: WriteCombiningSettings,
+    #[serde(default)]
+    pub reconciliation: ReconciliationSettings,
+    #[serde(default)]
+    pub expiry: ExpirySettings,
+    #[serde(default)]
+    pub balance_guard: BalanceGuardSettings,
+    #[serde(default)]
+    pub activity: ActivitySettings,
+    #[serde(default)]
+    pub balance_projection: BalanceProjectionSettings,
+    #[serde(default)]
+    pub cdc: CdcSettings,
+    #[serde(default)]
+    pub leader_election: LeaderElectionSettings,
+    #[serde(default)]
+    pub locks: LockSettings,
+    #[serde(default)]
+    pub gl: GlSettings,
+    #[serde(default)]
+    pub fees: FeeSettings,
+    #[serde(default)]
+    pub chart_of_accounts: ChartOfAccountsSettings,
+    #[serde(default)]
+    pub external_ids: ExternalIdSettings,
+    #[serde(default)]
+    pub amount_limits: AmountLimitSettings,
+    #[serde(default)]
+    pub default_management: DefaultManagementSettings,
+    #[serde(default)]
+    pub risk: RiskSettings,
+    #[serde(default)]
+    pub tls: Option<TlsSettings>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,21 +110,67 @@ pub struct DatabaseSettings {
     pub idle_timeout_secs: u64,
     #[serde(default = "default_max_lifetime")]
     pub max_lifetime_secs: u64,
+    /// Prepared statements each connection keeps, least recently used evicted first.
+    #[serde(default = "default_statement_cache_capacity")]
+    pub statement_cache_capacity: usize,
+    /// Queries taking at least this long are logged as slow.
+    #[serde(default = "default_slow_query_threshold_ms")]
+    pub slow_query_threshold_ms: u64,
+    /// Statements running longer are cancelled by the server; 0 leaves them unbounded.
+    #[serde(default = "default_statement_timeout_ms")]
+    pub statement_timeout_ms: u64,
 }
 
 fn default_min_connections() -> u32 { 5 }
 fn default_acquire_timeout() -> u64 { 5 }
 fn default_idle_timeout() -> u64 { 300 }
 fn default_max_lifetime() -> u64 { 1800 }
+fn default_statement_cache_capacity() -> usize { 100 }
+fn default_slow_query_threshold_ms() -> u64 { crate::observability::DEFAULT_SLOW_QUERY_THRESHOLD.as_millis() as u64 }
+fn default_statement_timeout_ms() -> u64 { 60_000 }
 
+/// Redis connection. `mode = "cluster"` uses `nodes` as seed nodes and
+/// `mode = "sentinel"` uses them as sentinel addresses together with `master_name`;
+/// `url` is only used in standalone mode.
 #[derive(Debug, Deserialize)]
 pub struct RedisSettings {
+    #[serde(default)]
     pub url: String,
     #[serde(default = "default_redis_pool_size")]
     pub pool_size: u32,
+    #[serde(default)]
+    pub mode: RedisMode,
+    #[serde(default)]
+    pub nodes: Vec<String>,
+    #[serde(default)]
+    pub master_name: Option<String>,
+    #[serde(default = "default_redis_connect_timeout")]
+    pub connect_timeout_ms: u64,
+    #[serde(default = "default_redis_command_timeout")]
+    pub command_timeout_ms: u64,
+    #[serde(default = "default_redis_reconnect_attempts")]
+    pub reconnect_attempts: u32,
+    #[serde(default = "default_redis_reconnect_backoff")]
+    pub reconnect_backoff_ms: u64,
+    #[serde(default = "default_redis_reconnect_max_backoff")]
+    pub reconnect_max_backoff_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedisMode {
+    #[default]
+    Standalone,
+    Cluster,
+    Sentinel,
 }
 
 fn default_redis_pool_size() -> u32 { 10 }
+fn default_redis_connect_timeout() -> u64 { 2000 }
+fn default_redis_command_timeout() -> u64 { 1000 }
+fn default_redis_reconnect_attempts() -> u32 { 3 }
+fn default_redis_reconnect_backoff() -> u64 { 100 }
+fn default_redis_reconnect_max_backoff() -> u64 { 2000 }
 
 #[derive(Debug, Deserialize)]
 pub struct CacheSettings {
@@ -62,6 +196,1108 @@ impl Default for CacheSettings {
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct HealthSettings {
+    #[serde(default = "default_hard_dependencies")]
+    pub hard_dependencies: Vec<String>,
+    /// Count slow-but-reachable hard dependencies as ready.
+    #[serde(default)]
+    pub allow_degraded_hard: bool,
+    #[serde(default = "default_max_error_rate")]
+    pub max_error_rate: f64,
+    #[serde(default = "default_min_samples")]
+    pub min_samples: usize,
+    #[serde(default = "default_health_window")]
+    pub window_secs: u64,
+}
+
+fn default_hard_dependencies() -> Vec<String> { vec!["database".to_string()] }
+fn default_max_error_rate() -> f64 { 0.5 }
+fn default_min_samples() -> usize { 5 }
+fn default_health_window() -> u64 { 300 }
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            hard_dependencies: default_hard_dependencies(),
+            allow_degraded_hard: false,
+            max_error_rate: default_max_error_rate(),
+            min_samples: default_min_samples(),
+            window_secs: default_health_window(),
+        }
+    }
+}
+
+/// Large-value transactions above these thresholds settle gross in real time instead of
+/// being netted. Amounts are best given as strings to avoid float rounding.
+#[derive(Debug, Default, Deserialize)]
+pub struct RtgsSettings {
+    #[serde(default)]
+    pub default_threshold: Option<Decimal>,
+    #[serde(default)]
+    pub currency_thresholds: HashMap<String, Decimal>,
+}
+
+/// Key used to sign per-batch finality attestations. Attestation export is disabled
+/// until a signing key is configured.
+#[derive(Debug, Deserialize)]
+pub struct FinalitySettings {
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    #[serde(default = "default_attestation_key_id")]
+    pub key_id: String,
+}
+
+fn default_attestation_key_id() -> String { "default".to_string() }
+
+impl Default for FinalitySettings {
+    fn default() -> Self {
+        Self {
+            signing_key: None,
+            key_id: default_attestation_key_id(),
+        }
+    }
+}
+
+/// External rail that completed batches release their net instructions to. Release is
+/// disabled unless a rail is enabled; the simulator is the only rail in this build.
+#[derive(Debug, Deserialize)]
+pub struct RailSettings {
+    #[serde(default)]
+    pub simulator: bool,
+    #[serde(default = "default_simulator_ack_polls")]
+    pub simulator_ack_polls: u32,
+    #[serde(default)]
+    pub simulator_rejection_limit: Option<Decimal>,
+}
+
+fn default_simulator_ack_polls() -> u32 { 1 }
+
+impl Default for RailSettings {
+    fn default() -> Self {
+        Self {
+            simulator: false,
+            simulator_ack_polls: default_simulator_ack_polls(),
+            simulator_rejection_limit: None,
+        }
+    }
+}
+
+/// Originator details for NACHA exports of batch instructions. NACHA export is
+/// disabled unless this section is present.
+#[derive(Debug, Deserialize)]
+pub struct NachaSettings {
+    pub immediate_destination: String,
+    pub immediate_destination_name: String,
+    pub immediate_origin: String,
+    pub immediate_origin_name: String,
+    pub company_name: String,
+    pub company_id: String,
+    pub originating_dfi: String,
+    #[serde(default = "default_nacha_entry_description")]
+    pub entry_description: String,
+}
+
+fn default_nacha_entry_description() -> String { "SETTLEMENT".to_string() }
+
+/// Named destinations that generated files can be delivered to. The delivery
+/// scheduler only runs when at least one destination is configured.
+#[derive(Debug, Deserialize)]
+pub struct DeliverySettings {
+    #[serde(default = "default_delivery_poll_interval")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_delivery_max_attempts")]
+    pub max_attempts: i32,
+    #[serde(default = "default_delivery_retry_backoff")]
+    pub retry_backoff_secs: i64,
+    #[serde(default)]
+    pub destinations: HashMap<String, DestinationSettings>,
+}
+
+fn default_delivery_poll_interval() -> u64 { 30 }
+fn default_delivery_max_attempts() -> i32 { 5 }
+fn default_delivery_retry_backoff() -> i64 { 60 }
+
+impl Default for DeliverySettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: default_delivery_poll_interval(),
+            max_attempts: default_delivery_max_attempts(),
+            retry_backoff_secs: default_delivery_retry_backoff(),
+            destinations: HashMap::new(),
+        }
+    }
+}
+
+/// Netting advices issued to each participant when a batch completes.
+#[derive(Debug, Deserialize)]
+pub struct AdviceSettings {
+    #[serde(default = "default_advices_enabled")]
+    pub enabled: bool,
+    /// How long after a batch's cut-off net payers have to fund their position.
+    #[serde(default = "default_advice_funding_window")]
+    pub funding_window_secs: i64,
+    /// Delivery destination advices are uploaded to as CSV and PDF.
+    #[serde(default)]
+    pub destination: Option<String>,
+    /// URL advices are posted to as JSON.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[serde(default = "default_advice_webhook_timeout")]
+    pub webhook_timeout_secs: u64,
+}
+
+fn default_advices_enabled() -> bool { true }
+fn default_advice_funding_window() -> i64 { 7200 }
+fn default_advice_webhook_timeout() -> u64 { 10 }
+
+impl Default for AdviceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_advices_enabled(),
+            funding_window_secs: default_advice_funding_window(),
+            destination: None,
+            webhook_url: None,
+            webhook_timeout_secs: default_advice_webhook_timeout(),
+        }
+    }
+}
+
+/// Funding obligations of net payers, due `advices.funding_window_secs` after their
+/// batch's cut-off, and what happens when one is missed.
+#[derive(Debug, Deserialize)]
+pub struct FundingSettings {
+    #[serde(default = "default_funding_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_funding_sweep_interval")]
+    pub sweep_interval_secs: u64,
+    #[serde(default = "default_funding_batch_size")]
+    pub batch_size: i64,
+    #[serde(default = "default_funding_freeze_participant")]
+    pub freeze_participant: bool,
+    #[serde(default)]
+    pub declare_default: bool,
+    /// Route released instructions through each currency's settlement control account:
+    /// net payers fund it and net receivers are paid from it. A batch that leaves it
+    /// off zero raises a control account break alert.
+    #[serde(default)]
+    pub control_account_flow: bool,
+}
+
+fn default_funding_enabled() -> bool { true }
+fn default_funding_sweep_interval() -> u64 { 60 }
+fn default_funding_batch_size() -> i64 { 500 }
+fn default_funding_freeze_participant() -> bool { true }
+
+impl Default for FundingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_funding_enabled(),
+            sweep_interval_secs: default_funding_sweep_interval(),
+            batch_size: default_funding_batch_size(),
+            freeze_participant: default_funding_freeze_participant(),
+            declare_default: false,
+            control_account_flow: false,
+        }
+    }
+}
+
+/// Payment-versus-payment settlements: how long they wait for both payers to be funded
+/// and how often those awaiting funding are checked.
+#[derive(Debug, Deserialize)]
+pub struct PvpSettings {
+    #[serde(default = "default_pvp_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_pvp_sweep_interval")]
+    pub sweep_interval_secs: u64,
+    #[serde(default = "default_pvp_timeout")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_pvp_batch_size")]
+    pub batch_size: i64,
+}
+
+fn default_pvp_enabled() -> bool { true }
+fn default_pvp_sweep_interval() -> u64 { 10 }
+fn default_pvp_timeout() -> u64 { crate::services::DEFAULT_PVP_TIMEOUT_SECS }
+fn default_pvp_batch_size() -> i64 { 100 }
+
+impl Default for PvpSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_pvp_enabled(),
+            sweep_interval_secs: default_pvp_sweep_interval(),
+            timeout_secs: default_pvp_timeout(),
+            batch_size: default_pvp_batch_size(),
+        }
+    }
+}
+
+/// Request timeouts: requests not answered in time get `504 REQUEST_TIMEOUT`. `routes`
+/// overrides the default by route prefix, e.g. `"/reports" = 120`; 0 leaves a route
+/// unlimited, as batch processing and instruction exports are unless configured.
+#[derive(Debug, Deserialize)]
+pub struct TimeoutSettings {
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout_secs: u64,
+    #[serde(default)]
+    pub routes: HashMap<String, u64>,
+}
+
+fn default_request_timeout() -> u64 { crate::api::timeouts::DEFAULT_REQUEST_TIMEOUT_SECS }
+
+impl Default for TimeoutSettings {
+    fn default() -> Self {
+        Self {
+            request_timeout_secs: default_request_timeout(),
+            routes: HashMap::new(),
+        }
+    }
+}
+
+/// Admission control for writes: new ones are rejected with `429 OVERLOADED` while
+/// `max_in_flight` are in progress or the average query latency is above
+/// `max_db_latency_ms`. Zero disables either check.
+#[derive(Debug, Deserialize)]
+pub struct AdmissionSettings {
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: usize,
+    #[serde(default = "default_max_db_latency")]
+    pub max_db_latency_ms: u64,
+    #[serde(default = "default_retry_after")]
+    pub retry_after_secs: u64,
+}
+
+fn default_max_in_flight() -> usize { crate::api::admission::DEFAULT_MAX_IN_FLIGHT }
+fn default_max_db_latency() -> u64 { crate::api::admission::DEFAULT_MAX_DB_LATENCY_MS }
+fn default_retry_after() -> u64 { crate::api::admission::DEFAULT_RETRY_AFTER_SECS }
+
+impl Default for AdmissionSettings {
+    fn default() -> Self {
+        Self {
+            max_in_flight: default_max_in_flight(),
+            max_db_latency_ms: default_max_db_latency(),
+            retry_after_secs: default_retry_after(),
+        }
+    }
+}
+
+/// Period-end FX revaluation: the functional currency, the P&L account unrealized gains
+/// and losses are booked to, and how often the job checks for an ended month.
+#[derive(Debug, Deserialize)]
+pub struct FxRevaluationSettings {
+    #[serde(default = "default_fx_revaluation_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_functional_currency")]
+    pub functional_currency: String,
+    #[serde(default)]
+    pub pnl_account_id: Option<Uuid>,
+    #[serde(default = "default_fx_revaluation_check_interval")]
+    pub check_interval_secs: u64,
+}
+
+fn default_fx_revaluation_enabled() -> bool { false }
+fn default_functional_currency() -> String { "USD".to_string() }
+fn default_fx_revaluation_check_interval() -> u64 { 3600 }
+
+impl Default for FxRevaluationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_fx_revaluation_enabled(),
+            functional_currency: default_functional_currency(),
+            pnl_account_id: None,
+            check_interval_secs: default_fx_revaluation_check_interval(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DestinationSettings {
+    Sftp {
+        host: String,
+        #[serde(default = "default_sftp_port")]
+        port: u16,
+        username: String,
+        #[serde(default)]
+        identity_file: Option<String>,
+        #[serde(default)]
+        remote_dir: String,
+    },
+    S3 {
+        endpoint: String,
+        bucket: String,
+        #[serde(default = "default_s3_region")]
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+        #[serde(default)]
+        prefix: String,
+    },
+    Directory {
+        path: String,
+    },
+}
+
+fn default_sftp_port() -> u16 { 22 }
+fn default_s3_region() -> String { "us-east-1".to_string() }
+
+/// Cleanup of expired idempotency records. Retention is measured from creation and
+/// never removes a record before it expires.
+#[derive(Debug, Deserialize)]
+pub struct IdempotencySettings {
+    #[serde(default = "default_idempotency_cleanup_enabled")]
+    pub cleanup_enabled: bool,
+    #[serde(default = "default_idempotency_cleanup_interval")]
+    pub cleanup_interval_secs: u64,
+    #[serde(default)]
+    pub default_retention_secs: i64,
+    /// Retention per key class (operation type).
+    #[serde(default)]
+    pub retention_secs: HashMap<String, i64>,
+    #[serde(default = "default_idempotency_cleanup_batch_size")]
+    pub cleanup_batch_size: i64,
+    #[serde(default = "default_idempotency_cleanup_batch_pause")]
+    pub cleanup_batch_pause_ms: u64,
+    #[serde(default = "default_idempotency_cleanup_max_batches")]
+    pub cleanup_max_batches: u32,
+    #[serde(default)]
+    pub archive: bool,
+}
+
+fn default_idempotency_cleanup_enabled() -> bool { true }
+fn default_idempotency_cleanup_interval() -> u64 { 300 }
+fn default_idempotency_cleanup_batch_size() -> i64 { 1000 }
+fn default_idempotency_cleanup_batch_pause() -> u64 { 100 }
+fn default_idempotency_cleanup_max_batches() -> u32 { 50 }
+
+/// Anonymization of closed accounts' personal data. Disabled by default, since
+/// the retention period is a legal decision for each deployment.
+#[derive(Debug, Deserialize)]
+pub struct RetentionSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_retention_interval")]
+    pub interval_secs: u64,
+    #[serde(default = "default_retention_anonymize_after")]
+    pub anonymize_closed_after_days: i64,
+    #[serde(default = "default_retention_batch_size")]
+    pub batch_size: i64,
+}
+
+fn default_retention_interval() -> u64 { 86400 }
+fn default_retention_anonymize_after() -> i64 { 365 }
+fn default_retention_batch_size() -> i64 { 100 }
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_retention_interval(),
+            anonymize_closed_after_days: default_retention_anonymize_after(),
+            batch_size: default_retention_batch_size(),
+        }
+    }
+}
+
+/// Relay publishing the event outbox to Kafka. It runs whenever Kafka is connected.
+#[derive(Debug, Deserialize)]
+pub struct OutboxSettings {
+    #[serde(default = "default_outbox_poll_interval")]
+    pub poll_interval_ms: u64,
+    #[serde(default = "default_outbox_batch_size")]
+    pub batch_size: i64,
+}
+
+fn default_outbox_poll_interval() -> u64 { 500 }
+fn default_outbox_batch_size() -> i64 { 100 }
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: default_outbox_poll_interval(),
+            batch_size: default_outbox_batch_size(),
+        }
+    }
+}
+
+impl Default for IdempotencySettings {
+    fn default() -> Self {
+        Self {
+            cleanup_enabled: default_idempotency_cleanup_enabled(),
+            cleanup_interval_secs: default_idempotency_cleanup_interval(),
+            default_retention_secs: 0,
+            retention_secs: HashMap::new(),
+            cleanup_batch_size: default_idempotency_cleanup_batch_size(),
+            cleanup_batch_pause_ms: default_idempotency_cleanup_batch_pause(),
+            cleanup_max_batches: default_idempotency_cleanup_max_batches(),
+            archive: false,
+        }
+    }
+}
+
+/// Automatic assignment of netted transactions to the current open batch for their
+/// currency as they settle. Off by default; callers then assign transactions explicitly.
+#[derive(Debug, Deserialize)]
+pub struct BatchingSettings {
+    #[serde(default)]
+    pub auto_assign: bool,
+    /// Transactions processed at once when a batch is processed. Transactions
+    /// sharing an account are still processed one at a time, in order.
+    #[serde(default = "default_batch_workers")]
+    pub workers: usize,
+    /// Runs `BatchScheduler`, which processes batches past their cut-off.
+    #[serde(default)]
+    pub scheduler_enabled: bool,
+    #[serde(default = "default_batch_scheduler_interval")]
+    pub scheduler_interval_secs: u64,
+    /// Seconds a processing run can go without checkpointing before the scheduler, or
+    /// `POST /batches/{id}/resume`, may take its batch over.
+    #[serde(default = "default_batch_stall_timeout")]
+    pub stall_timeout_secs: u64,
+    /// Furthest `POST /batches/{id}/cutoff` may move a cut-off in one change.
+    #[serde(default = "default_max_cut_off_shift")]
+    pub max_cut_off_shift_secs: u64,
+    /// Runs `BatchProvisioningJob`, which opens batches from the active batch templates.
+    #[serde(default)]
+    pub provisioning_enabled: bool,
+    #[serde(default = "default_batch_provisioning_interval")]
+    pub provisioning_interval_secs: u64,
+    /// UTC time of day from which the job opens tomorrow's batches.
+    #[serde(default = "default_provision_after")]
+    pub provision_after: chrono::NaiveTime,
+    /// Transactions a batch takes before assignment rolls over to a new sub-batch in
+    /// its settlement window; unlimited when unset.
+    #[serde(default)]
+    pub max_transactions: Option<i32>,
+    /// Gross amount a batch takes before assignment rolls over; unlimited when unset.
+    #[serde(default)]
+    pub max_gross_amount: Option<Decimal>,
+}
+
+fn default_batch_workers() -> usize { 8 }
+fn default_batch_scheduler_interval() -> u64 { 60 }
+fn default_batch_stall_timeout() -> u64 { 300 }
+fn default_max_cut_off_shift() -> u64 { 4 * 3600 }
+fn default_batch_provisioning_interval() -> u64 { 900 }
+fn default_provision_after() -> chrono::NaiveTime { chrono::NaiveTime::from_hms_opt(18, 0, 0).unwrap() }
+
+impl Default for BatchingSettings {
+    fn default() -> Self {
+        Self {
+            auto_assign: false,
+            workers: default_batch_workers(),
+            scheduler_enabled: false,
+            scheduler_interval_secs: default_batch_scheduler_interval(),
+            stall_timeout_secs: default_batch_stall_timeout(),
+            max_cut_off_shift_secs: default_max_cut_off_shift(),
+            provisioning_enabled: false,
+            provisioning_interval_secs: default_batch_provisioning_interval(),
+            provision_after: default_provision_after(),
+            max_transactions: None,
+            max_gross_amount: None,
+        }
+    }
+}
+
+/// How batches are netted: when positions are aggregated in parallel, and how
+/// instructions move funds unless a batch sets its own strategy.
+#[derive(Debug, Deserialize)]
+pub struct NettingSettings {
+    /// Transaction count from which a batch's positions are aggregated in parallel.
+    #[serde(default = "default_parallel_threshold")]
+    pub parallel_threshold: usize,
+    /// GREEDY, MINIMUM_COUNT or CONTROL_ACCOUNT. `funding.control_account_flow`
+    /// selects CONTROL_ACCOUNT regardless.
+    #[serde(default)]
+    pub instruction_strategy: InstructionStrategy,
+}
+
+fn default_parallel_threshold() -> usize { crate::services::DEFAULT_PARALLEL_THRESHOLD }
+
+impl Default for NettingSettings {
+    fn default() -> Self {
+        Self {
+            parallel_threshold: default_parallel_threshold(),
+            instruction_strategy: InstructionStrategy::default(),
+        }
+    }
+}
+
+/// Caps on each participant's net debit within a batch, enforced as transactions are
+/// assigned. Accounts without a cap of their own are held to `default_cap`, if set.
+#[derive(Debug, Deserialize)]
+pub struct NetDebitCapSettings {
+    #[serde(default)]
+    pub default_cap: Option<Decimal>,
+    /// Shares of a cap, in percent, at which a warning event is raised.
+    #[serde(default = "default_net_debit_warning_thresholds")]
+    pub warning_thresholds: Vec<u32>,
+    /// REJECT fails the transaction; QUEUE moves it to the next batch in its window.
+    #[serde(default)]
+    pub on_breach: NetDebitCapAction,
+}
+
+fn default_net_debit_warning_thresholds() -> Vec<u32> { vec![80, 95] }
+
+impl Default for NetDebitCapSettings {
+    fn default() -> Self {
+        Self {
+            default_cap: None,
+            warning_thresholds: default_net_debit_warning_thresholds(),
+            on_breach: NetDebitCapAction::default(),
+        }
+    }
+}
+
+/// Workers settling transactions submitted through `POST /transactions/async`.
+/// Submission is rejected with 503 while disabled.
+#[derive(Debug, Deserialize)]
+pub struct SubmissionSettings {
+    #[serde(default = "default_submission_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_submission_poll_interval")]
+    pub poll_interval_ms: u64,
+    /// Submissions claimed per pass.
+    #[serde(default = "default_submission_batch_size")]
+    pub batch_size: i64,
+    /// Submissions settled at once.
+    #[serde(default = "default_submission_concurrency")]
+    pub concurrency: usize,
+    /// Attempts for transient failures such as serialization conflicts.
+    #[serde(default = "default_submission_max_attempts")]
+    pub max_attempts: i32,
+    #[serde(default = "default_submission_retry_backoff")]
+    pub retry_backoff_ms: i64,
+}
+
+fn default_submission_enabled() -> bool { true }
+fn default_submission_poll_interval() -> u64 { 200 }
+fn default_submission_batch_size() -> i64 { 100 }
+fn default_submission_concurrency() -> usize { 8 }
+fn default_submission_max_attempts() -> i32 { 5 }
+fn default_submission_retry_backoff() -> i64 { 1000 }
+
+impl Default for SubmissionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_submission_enabled(),
+            poll_interval_ms: default_submission_poll_interval(),
+            batch_size: default_submission_batch_size(),
+            concurrency: default_submission_concurrency(),
+            max_attempts: default_submission_max_attempts(),
+            retry_backoff_ms: default_submission_retry_backoff(),
+        }
+    }
+}
+
+/// Combining of netted postings arriving close together into one database transaction,
+/// trading a little latency per posting for fewer round trips under load.
+#[derive(Debug, Deserialize)]
+pub struct WriteCombiningSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long the first posting of a group waits for others to join it.
+    #[serde(default = "default_write_combining_window")]
+    pub window_ms: u64,
+    #[serde(default = "default_write_combining_max_batch_size")]
+    pub max_batch_size: usize,
+}
+
+fn default_write_combining_window() -> u64 { crate::services::DEFAULT_COMBINE_WINDOW.as_millis() as u64 }
+fn default_write_combining_max_batch_size() -> usize { crate::services::DEFAULT_MAX_COMBINED }
+
+impl Default for WriteCombiningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_ms: default_write_combining_window(),
+            max_batch_size: default_write_combining_max_batch_size(),
+        }
+    }
+}
+
+/// Scheduled reconciliation of stored account balances against the ledger.
+#[derive(Debug, Deserialize)]
+pub struct ReconciliationSettings {
+    #[serde(default = "default_reconciliation_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_reconciliation_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_reconciliation_enabled() -> bool { true }
+fn default_reconciliation_interval() -> u64 { 3600 }
+
+impl Default for ReconciliationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_reconciliation_enabled(),
+            interval_secs: default_reconciliation_interval(),
+        }
+    }
+}
+
+/// Expiry of transactions left pending, and submissions left queued, past their type's
+/// TTL. A TTL of zero never expires; both default to zero, so nothing expires until a
+/// TTL is configured. The same sweep releases balance reservations past their expiry.
+#[derive(Debug, Deserialize)]
+pub struct ExpirySettings {
+    #[serde(default = "default_expiry_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_expiry_sweep_interval")]
+    pub sweep_interval_secs: u64,
+    #[serde(default = "default_expiry_batch_size")]
+    pub batch_size: i64,
+    #[serde(default)]
+    pub default_ttl_secs: i64,
+    /// TTL per transaction type, e.g. `PAYMENT`.
+    #[serde(default)]
+    pub ttl_secs: HashMap<String, i64>,
+}
+
+fn default_expiry_enabled() -> bool { true }
+fn default_expiry_sweep_interval() -> u64 { 60 }
+fn default_expiry_batch_size() -> i64 { 500 }
+
+impl Default for ExpirySettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_expiry_enabled(),
+            sweep_interval_secs: default_expiry_sweep_interval(),
+            batch_size: default_expiry_batch_size(),
+            default_ttl_secs: 0,
+            ttl_secs: HashMap::new(),
+        }
+    }
+}
+
+/// Freezing of accounts whose balance fell below its floor. Incidents are opened by a
+/// database trigger regardless; this job acts on them.
+#[derive(Debug, Deserialize)]
+pub struct BalanceGuardSettings {
+    #[serde(default = "default_balance_guard_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_balance_guard_interval")]
+    pub interval_secs: u64,
+    #[serde(default = "default_balance_guard_batch_size")]
+    pub batch_size: i64,
+}
+
+fn default_balance_guard_enabled() -> bool { true }
+fn default_balance_guard_interval() -> u64 { 5 }
+fn default_balance_guard_batch_size() -> i64 { 100 }
+
+impl Default for BalanceGuardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_balance_guard_enabled(),
+            interval_secs: default_balance_guard_interval(),
+            batch_size: default_balance_guard_batch_size(),
+        }
+    }
+}
+
+/// Projection of settled transactions into per-account daily activity aggregates.
+#[derive(Debug, Deserialize)]
+pub struct ActivitySettings {
+    #[serde(default = "default_activity_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_activity_poll_interval")]
+    pub poll_interval_ms: u64,
+    #[serde(default = "default_activity_batch_size")]
+    pub batch_size: i64,
+}
+
+fn default_activity_enabled() -> bool { true }
+fn default_activity_poll_interval() -> u64 { 1000 }
+fn default_activity_batch_size() -> i64 { 500 }
+
+impl Default for ActivitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_activity_enabled(),
+            poll_interval_ms: default_activity_poll_interval(),
+            batch_size: default_activity_batch_size(),
+        }
+    }
+}
+
+/// Event-sourced projection of account balances from the ledger. Off by default; when
+/// off the projection is only built by the rebuild command.
+#[derive(Debug, Deserialize)]
+pub struct BalanceProjectionSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_balance_projection_poll_interval")]
+    pub poll_interval_ms: u64,
+    /// Entries of one account folded per database transaction.
+    #[serde(default = "default_balance_projection_batch_size")]
+    pub batch_size: i64,
+    #[serde(default = "default_balance_projection_accounts_per_pass")]
+    pub accounts_per_pass: i64,
+}
+
+fn default_balance_projection_poll_interval() -> u64 { 500 }
+fn default_balance_projection_batch_size() -> i64 { 500 }
+fn default_balance_projection_accounts_per_pass() -> i64 { 100 }
+
+impl Default for BalanceProjectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval_ms: default_balance_projection_poll_interval(),
+            batch_size: default_balance_projection_batch_size(),
+            accounts_per_pass: default_balance_projection_accounts_per_pass(),
+        }
+    }
+}
+
+/// Change data capture publisher tailing logical replication into Kafka. Needs
+/// `wal_level = logical` on the server and runs only while Kafka is connected.
+#[derive(Debug, Deserialize)]
+pub struct CdcSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_cdc_slot_name")]
+    pub slot_name: String,
+    #[serde(default = "default_cdc_publication")]
+    pub publication: String,
+    #[serde(default = "default_cdc_tables")]
+    pub tables: Vec<String>,
+    /// Changes go to `{topic_prefix}.{table}`.
+    #[serde(default = "default_cdc_topic_prefix")]
+    pub topic_prefix: String,
+    #[serde(default = "default_cdc_poll_interval")]
+    pub poll_interval_ms: u64,
+    /// Changes decoded per pass; whole transactions are always read.
+    #[serde(default = "default_cdc_batch_size")]
+    pub batch_size: i64,
+}
+
+fn default_cdc_slot_name() -> String { "settlement_cdc".to_string() }
+fn default_cdc_publication() -> String { "settlement_cdc".to_string() }
+fn default_cdc_tables() -> Vec<String> {
+    ["accounts", "account_balances", "transactions", "ledger_entries", "settlement_batches"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+fn default_cdc_topic_prefix() -> String { "settlement.cdc".to_string() }
+fn default_cdc_poll_interval() -> u64 { 500 }
+fn default_cdc_batch_size() -> i64 { 1000 }
+
+impl Default for CdcSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            slot_name: default_cdc_slot_name(),
+            publication: default_cdc_publication(),
+            tables: default_cdc_tables(),
+            topic_prefix: default_cdc_topic_prefix(),
+            poll_interval_ms: default_cdc_poll_interval(),
+            batch_size: default_cdc_batch_size(),
+        }
+    }
+}
+
+/// Election of the one instance that runs the batch scheduler, idempotency cleanup,
+/// reconciliation, balance guard, expiry and account retention jobs. Needed when more
+/// than one instance shares the database.
+#[derive(Debug, Deserialize)]
+pub struct LeaderElectionSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Postgres advisory lock key instances campaign for; instances sharing a database
+    /// but not a deployment need different keys.
+    #[serde(default = "default_leader_lock_key")]
+    pub lock_key: i64,
+    /// Seconds between a follower's attempts to take over and the leader's checks that
+    /// it still holds the lock.
+    #[serde(default = "default_leader_heartbeat_interval")]
+    pub heartbeat_interval_secs: u64,
+}
+
+fn default_leader_lock_key() -> i64 { crate::core::leader::DEFAULT_LEADER_LOCK_KEY }
+fn default_leader_heartbeat_interval() -> u64 { 5 }
+
+impl Default for LeaderElectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lock_key: default_leader_lock_key(),
+            heartbeat_interval_secs: default_leader_heartbeat_interval(),
+        }
+    }
+}
+
+/// Locks serializing closing and processing of each batch across requests and
+/// instances. Postgres advisory locks need nothing else; Redis locks live under
+/// `cache.key_prefix` and expire `ttl_secs` after their holder stops renewing them.
+#[derive(Debug, Deserialize)]
+pub struct LockSettings {
+    #[serde(default)]
+    pub backend: LockBackendKind,
+    #[serde(default = "default_lock_ttl")]
+    pub ttl_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockBackendKind {
+    #[default]
+    Postgres,
+    Redis,
+}
+
+fn default_lock_ttl() -> u64 { crate::core::locks::DEFAULT_LOCK_TTL.as_secs() }
+
+impl Default for LockSettings {
+    fn default() -> Self {
+        Self {
+            backend: LockBackendKind::default(),
+            ttl_secs: default_lock_ttl(),
+        }
+    }
+}
+
+/// GL account codes for the general-ledger journal export. Anything left unset uses
+/// the built-in chart: 1000 assets, 2000 liabilities, 4000 revenue, 5000 expenses,
+/// 4100 fee income and 9999 suspense.
+#[derive(Debug, Default, Deserialize)]
+pub struct GlSettings {
+    /// Replaces the built-in account-type rules when non-empty.
+    #[serde(default)]
+    pub rules: Vec<GlRuleSettings>,
+    pub fee_income_code: Option<String>,
+    pub suspense_code: Option<String>,
+}
+
+/// Maps a transaction type and/or account type to a GL account code.
+#[derive(Debug, Deserialize)]
+pub struct GlRuleSettings {
+    pub transaction_type: Option<TransactionType>,
+    pub account_type: Option<AccountType>,
+    pub gl_code: String,
+}
+
+/// Fee revenue accounts and what reversals do with fees.
+#[derive(Debug, Default, Deserialize)]
+pub struct FeeSettings {
+    /// Account fees are booked to, keyed by currency. Fees in other currencies are not
+    /// booked to any account.
+    #[serde(default)]
+    pub revenue_accounts: HashMap<String, Uuid>,
+    /// Applied to reversals that do not choose a policy: RETAIN or REVERSE.
+    #[serde(default)]
+    pub reversal_policy: FeeReversalPolicy,
+    /// IMMEDIATE books fees to revenue as they are charged. ACCRUED books them to the
+    /// receivable account and settles each payer's net to revenue when its batch completes.
+    #[serde(default)]
+    pub booking: FeeBookingMode,
+    /// Account fees accrue to in the ACCRUED mode, keyed by currency.
+    #[serde(default)]
+    pub receivable_accounts: HashMap<String, Uuid>,
+    /// Account monthly invoices are posted to as receivables, keyed by currency.
+    #[serde(default)]
+    pub invoice_receivable_accounts: HashMap<String, Uuid>,
+}
+
+/// The chart of internal accounts. Fee accounts not configured under `fees` are taken
+/// from the chart's FEE_REVENUE, FEE_RECEIVABLE and INVOICE_RECEIVABLE entries.
+#[derive(Debug, Deserialize)]
+pub struct ChartOfAccountsSettings {
+    #[serde(default)]
+    pub accounts: Vec<InternalAccountSettings>,
+    /// Provisions every entry in a currency the first time a transaction uses it, in
+    /// addition to the currencies listed per entry.
+    #[serde(default = "default_auto_provision")]
+    pub auto_provision: bool,
+}
+
+fn default_auto_provision() -> bool { true }
+
+impl Default for ChartOfAccountsSettings {
+    fn default() -> Self {
+        Self {
+            accounts: Vec::new(),
+            auto_provision: default_auto_provision(),
+        }
+    }
+}
+
+/// An entry of the chart, provisioned once per currency.
+#[derive(Debug, Deserialize)]
+pub struct InternalAccountSettings {
+    pub code: String,
+    pub name: String,
+    /// FEE_REVENUE, FEE_RECEIVABLE, INVOICE_RECEIVABLE, SUSPENSE, FX_POSITION,
+    /// SETTLEMENT_CONTROL or COLLATERAL. Entries without a role group their children.
+    pub role: Option<InternalAccountRole>,
+    /// Code of the entry this one rolls up into.
+    pub parent: Option<String>,
+    /// Currencies the entry is provisioned in at startup.
+    #[serde(default)]
+    pub currencies: Vec<String>,
+    /// Defaults to the role's account type, or ASSET without a role.
+    pub account_type: Option<AccountType>,
+    /// Defaults to true for every role except COLLATERAL.
+    pub control: Option<bool>,
+}
+
+/// How transactions with an external ID already in use are handled.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExternalIdSettings {
+    /// GLOBAL, SOURCE_SYSTEM or SOURCE_SYSTEM_DAY.
+    #[serde(default)]
+    pub scope: ExternalIdScope,
+    /// REJECT, or LINK to accept duplicates as resubmissions of the first transaction.
+    #[serde(default)]
+    pub on_duplicate: DuplicateExternalIdAction,
+}
+
+/// Guard rails against fat-finger amounts. Amounts have no maximum unless one is set.
+#[derive(Debug, Deserialize)]
+pub struct AmountLimitSettings {
+    /// Maximum transaction amount in currencies without their own maximum.
+    #[serde(default)]
+    pub default_max: Option<Decimal>,
+    /// Maximum transaction amount, keyed by currency.
+    #[serde(default)]
+    pub currency_max: HashMap<String, Decimal>,
+    /// Most decimal places an amount or fee may have.
+    #[serde(default = "default_max_amount_scale")]
+    pub max_scale: u32,
+}
+
+fn default_max_amount_scale() -> u32 { crate::services::DEFAULT_MAX_AMOUNT_SCALE }
+
+impl Default for AmountLimitSettings {
+    fn default() -> Self {
+        Self {
+            default_max: None,
+            currency_max: HashMap::new(),
+            max_scale: default_max_amount_scale(),
+        }
+    }
+}
+
+/// How the obligations of a participant declared in default are resolved.
+#[derive(Debug, Default, Deserialize)]
+pub struct DefaultManagementSettings {
+    /// Applied to declarations that do not choose one: EXCLUDE or LOSS_SHARING.
+    #[serde(default)]
+    pub resolution: DefaultResolution,
+    /// How loss sharing splits the unfunded debit: NET_CREDIT or EXPOSURE.
+    #[serde(default)]
+    pub loss_allocation: LossAllocationBasis,
+}
+
+/// Rounding of computed amounts such as FX conversions, revaluations and loss shares.
+#[derive(Debug, Default, Deserialize)]
+pub struct RoundingSettings {
+    /// HALF_EVEN, HALF_UP, DOWN or UP.
+    #[serde(default)]
+    pub mode: RoundingMode,
+    /// Decimal places per currency, overriding the ISO 4217 minor unit.
+    #[serde(default)]
+    pub scales: HashMap<String, u32>,
+    /// Where the remainder of pro-rata splits goes: LARGEST_REMAINDER or DESIGNATED.
+    #[serde(default)]
+    pub remainder: RemainderStrategy,
+    /// Account taking the remainder with the DESIGNATED strategy.
+    #[serde(default)]
+    pub designated_account_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RemainderStrategy {
+    #[default]
+    LargestRemainder,
+    Designated,
+}
+
+/// Rules that hold payments and transfers for review before posting. Review is disabled
+/// unless enabled; each rule is also off until configured.
+#[derive(Debug, Deserialize)]
+pub struct RiskSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub default_amount_threshold: Option<Decimal>,
+    #[serde(default)]
+    pub currency_amount_thresholds: HashMap<String, Decimal>,
+    /// Holds the first transaction from an account to a counterparty.
+    #[serde(default)]
+    pub new_counterparty: bool,
+    #[serde(default = "default_velocity_window")]
+    pub velocity_window_secs: u64,
+    #[serde(default)]
+    pub velocity_max_count: Option<i64>,
+    #[serde(default)]
+    pub velocity_max_amount: Option<Decimal>,
+    /// Flags a payment matching an earlier one (source, destination, amount, currency)
+    /// with another idempotency key within this many seconds.
+    #[serde(default)]
+    pub duplicate_window_secs: Option<u64>,
+    /// WARN, HOLD or REJECT a likely duplicate.
+    #[serde(default)]
+    pub duplicate_action: DuplicatePaymentAction,
+}
+
+fn default_velocity_window() -> u64 { 3600 }
+
+impl Default for RiskSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_amount_threshold: None,
+            currency_amount_thresholds: HashMap::new(),
+            new_counterparty: false,
+            velocity_window_secs: default_velocity_window(),
+            velocity_max_count: None,
+            velocity_max_amount: None,
+            duplicate_window_secs: None,
+            duplicate_action: DuplicatePaymentAction::default(),
+        }
+    }
+}
+
+/// Circuit breakers around Kafka, Redis, alert webhooks and the settlement rail.
+/// Each dependency (and each webhook host) has its own breaker with these thresholds.
+#[derive(Debug, Deserialize)]
+pub struct CircuitBreakerSettings {
+    /// Share of failed calls in the window that opens a circuit.
+    #[serde(default = "default_circuit_failure_rate")]
+    pub failure_rate_threshold: f64,
+    #[serde(default = "default_circuit_minimum_calls")]
+    pub minimum_calls: u32,
+    #[serde(default = "default_circuit_window")]
+    pub window_secs: u64,
+    #[serde(default = "default_circuit_open")]
+    pub open_secs: u64,
+    #[serde(default = "default_circuit_half_open_probes")]
+    pub half_open_probes: u32,
+}
+
+fn default_circuit_failure_rate() -> f64 { 0.5 }
+fn default_circuit_minimum_calls() -> u32 { 10 }
+fn default_circuit_window() -> u64 { 60 }
+fn default_circuit_open() -> u64 { 30 }
+fn default_circuit_half_open_probes() -> u32 { 3 }
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold: default_circuit_failure_rate(),
+            minimum_calls: default_circuit_minimum_calls(),
+            window_secs: default_circuit_window(),
+            open_secs: default_circuit_open(),
+            half_open_probes: default_circuit_half_open_probes(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct KafkaSettings {
     pub brokers: String,
@@ -72,6 +1308,32 @@ pub struct KafkaSettings {
 pub struct ApplicationSettings {
     pub port: u16,
     pub log_level: String,
+    /// Sockets accepting connections on `port`; more than one requires `reuse_port`.
+    #[serde(default = "default_listeners")]
+    pub listeners: usize,
+    /// Binds with SO_REUSEPORT, so a new instance can start listening before the
+    /// old one has drained during a restart.
+    #[serde(default)]
+    pub reuse_port: bool,
+}
+
+fn default_listeners() -> usize { 1 }
+
+/// HTTPS termination. The server speaks plain HTTP unless this section is present.
+/// With `client_ca_path` set, client certificates issued by those CAs are verified
+/// (mTLS), and `api_key_bindings` restricts API keys to a single client certificate.
+#[derive(Debug, Deserialize)]
+pub struct TlsSettings {
+    pub cert_path: String,
+    pub key_path: String,
+    #[serde(default)]
+    pub client_ca_path: Option<String>,
+    #[serde(default)]
+    pub require_client_cert: bool,
+    /// SHA-256 hex of an API key to the SHA-256 hex fingerprint of the only client
+    /// certificate it is accepted with.
+    #[serde(default)]
+    pub api_key_bindings: HashMap<String, String>,
 }
 
 impl Settings {
@@ -0,0 +1,409 @@
+//! Circuit breaking for calls to external dependencies.
+//!
+//! A breaker tracks the outcome of recent calls over a sliding time window. Once the
+//! window holds at least `minimum_calls` outcomes and the share of failures reaches
+//! `failure_rate_threshold`, the circuit opens and calls fail immediately instead of
+//! waiting on a dependency that is down. After `open_duration` the circuit is half-open:
+//! a limited number of probe calls are let through, and it closes once they all succeed
+//! or reopens on the first failed probe.
+//!
+//! Every state change is logged, exported as metrics and passed to registered listeners.
+
+use crate::error::{AppError, Result};
+use crate::observability::metrics::get_metrics;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// State of a circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    /// Calls fail immediately without touching the dependency.
+    Open,
+    /// The open period has elapsed; a limited number of probe calls are let through.
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Thresholds for opening and closing a circuit.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Share of failed calls in the window (0.0 - 1.0) that opens the circuit.
+    pub failure_rate_threshold: f64,
+    /// Calls the window must hold before the failure rate is evaluated.
+    pub minimum_calls: u32,
+    /// How far back outcomes are counted.
+    pub window: Duration,
+    /// How long the circuit stays open before probes are let through.
+    pub open_duration: Duration,
+    /// Successful probes needed to close a half-open circuit.
+    pub half_open_probes: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold: 0.5,
+            minimum_calls: 10,
+            window: Duration::from_secs(60),
+            open_duration: Duration::from_secs(30),
+            half_open_probes: 3,
+        }
+    }
+}
+
+/// Called with the breaker name, the previous state and the new state.
+pub type TransitionListener = Arc<dyn Fn(&str, CircuitState, CircuitState) + Send + Sync>;
+
+struct BreakerState {
+    state: CircuitState,
+    /// Call outcomes in the window, oldest first; `true` is a failure.
+    outcomes: VecDeque<(Instant, bool)>,
+    opened_at: Option<Instant>,
+    probes_in_flight: u32,
+    probe_successes: u32,
+}
+
+/// Error-rate circuit breaker guarding one dependency.
+pub struct CircuitBreaker {
+    name: String,
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerState>,
+    listeners: Vec<TransitionListener>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+            inner: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: None,
+                probes_in_flight: 0,
+                probe_successes: 0,
+            }),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Registers a callback invoked on every state change.
+    pub fn with_listener(mut self, listener: TransitionListener) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Current state. An open circuit whose open period has elapsed reports half-open.
+    pub fn state(&self) -> CircuitState {
+        let mut inner = self.inner.lock().unwrap();
+        let transition = self.refresh(&mut inner);
+        let state = inner.state;
+        drop(inner);
+        self.notify(transition);
+        state
+    }
+
+    /// Returns false while the circuit is open.
+    pub fn is_available(&self) -> bool {
+        self.state() != CircuitState::Open
+    }
+
+    /// Asks permission for a call. Every granted call must be followed by
+    /// `record_success` or `record_failure`.
+    pub fn try_acquire(&self) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let transition = self.refresh(&mut inner);
+        let permitted = match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                if inner.probes_in_flight + inner.probe_successes < self.config.half_open_probes.max(1) {
+                    inner.probes_in_flight += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        drop(inner);
+        self.notify(transition);
+
+        if permitted {
+            Ok(())
+        } else {
+            Err(AppError::Unavailable(format!("{} unavailable: circuit open", self.name)))
+        }
+    }
+
+    /// Records a successful call.
+    pub fn record_success(&self) {
+        self.record(false);
+    }
+
+    /// Records a failed call.
+    pub fn record_failure(&self) {
+        self.record(true);
+    }
+
+    /// Runs `call` through the breaker. Only server-side errors count as failures;
+    /// client errors such as validation or not-found mean the dependency answered.
+    pub async fn call<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.try_acquire()?;
+        let result = call().await;
+        match &result {
+            Err(e) if e.status_code().is_server_error() => self.record_failure(),
+            _ => self.record_success(),
+        }
+        result
+    }
+
+    fn record(&self, failed: bool) {
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap();
+        let transition = match inner.state {
+            CircuitState::Closed => {
+                inner.outcomes.push_back((now, failed));
+                while let Some(&(at, _)) = inner.outcomes.front() {
+                    if now.duration_since(at) <= self.config.window {
+                        break;
+                    }
+                    inner.outcomes.pop_front();
+                }
+
+                let calls = inner.outcomes.len();
+                let failures = inner.outcomes.iter().filter(|(_, failed)| *failed).count();
+                if failed
+                    && calls >= self.config.minimum_calls.max(1) as usize
+                    && failures as f64 / calls as f64 >= self.config.failure_rate_threshold
+                {
+                    self.transition(&mut inner, CircuitState::Open)
+                } else {
+                    None
+                }
+            }
+            CircuitState::HalfOpen => {
+                inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+                if failed {
+                    // A failed probe reopens the circuit for another full period
+                    self.transition(&mut inner, CircuitState::Open)
+                } else {
+                    inner.probe_successes += 1;
+                    if inner.probe_successes >= self.config.half_open_probes.max(1) {
+                        self.transition(&mut inner, CircuitState::Closed)
+                    } else {
+                        None
+                    }
+                }
+            }
+            // Late results of calls started before the circuit opened
+            CircuitState::Open => None,
+        };
+        drop(inner);
+        self.notify(transition);
+    }
+
+    fn refresh(&self, inner: &mut BreakerState) -> Option<(CircuitState, CircuitState)> {
+        match (inner.state, inner.opened_at) {
+            (CircuitState::Open, Some(at)) if at.elapsed() >= self.config.open_duration => {
+                self.transition(inner, CircuitState::HalfOpen)
+            }
+            _ => None,
+        }
+    }
+
+    fn transition(&self, inner: &mut BreakerState, to: CircuitState) -> Option<(CircuitState, CircuitState)> {
+        let from = inner.state;
+        inner.state = to;
+        inner.probes_in_flight = 0;
+        inner.probe_successes = 0;
+        match to {
+            CircuitState::Open => inner.opened_at = Some(Instant::now()),
+            CircuitState::Closed => {
+                inner.opened_at = None;
+                inner.outcomes.clear();
+            }
+            CircuitState::HalfOpen => {}
+        }
+        Some((from, to))
+    }
+
+    /// Reports a state change outside the lock so listeners may use the breaker.
+    fn notify(&self, transition: Option<(CircuitState, CircuitState)>) {
+        let Some((from, to)) = transition else {
+            return;
+        };
+
+        match to {
+            CircuitState::Open => tracing::warn!(breaker = %self.name, from = from.as_str(), "Circuit opened"),
+            _ => tracing::info!(breaker = %self.name, from = from.as_str(), to = to.as_str(), "Circuit state changed"),
+        }
+        get_metrics().record_circuit_transition(&self.name, to);
+        for listener in &self.listeners {
+            listener(&self.name, from, to);
+        }
+    }
+}
+
+/// Breakers created on demand per key (e.g. per webhook host), sharing one config.
+pub struct CircuitBreakerRegistry {
+    prefix: String,
+    config: CircuitBreakerConfig,
+    breakers: Mutex<HashMap<String, Arc<CircuitBreaker>>>,
+}
+
+impl CircuitBreakerRegistry {
+    /// Breakers are named `<prefix>:<key>`.
+    pub fn new(prefix: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            prefix: prefix.into(),
+            config,
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the breaker for `key`, creating it on first use.
+    pub fn get(&self, key: &str) -> Arc<CircuitBreaker> {
+        self.breakers
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_insert_with(|| {
+                Arc::new(CircuitBreaker::new(
+                    format!("{}:{}", self.prefix, key),
+                    self.config.clone(),
+                ))
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_rate_threshold: 0.5,
+            minimum_calls: 4,
+            window: Duration::from_secs(60),
+            open_duration: Duration::from_millis(20),
+            half_open_probes: 2,
+        }
+    }
+
+    #[test]
+    fn test_opens_on_failure_rate() {
+        let breaker = CircuitBreaker::new("test", config());
+
+        // Below the minimum number of calls nothing opens
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        // 3 failures out of 4 calls
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let error = breaker.try_acquire().unwrap_err();
+        assert!(error.to_string().contains("test unavailable: circuit open"));
+    }
+
+    #[test]
+    fn test_stays_closed_below_threshold() {
+        let breaker = CircuitBreaker::new("test", config());
+        for _ in 0..10 {
+            breaker.record_success();
+            breaker.record_success();
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_probes() {
+        let transitions = Arc::new(AtomicUsize::new(0));
+        let counter = transitions.clone();
+        let breaker = CircuitBreaker::new("test", config()).with_listener(Arc::new(move |_, _, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        for _ in 0..4 {
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        // Only the configured number of probes are let through
+        breaker.try_acquire().unwrap();
+        breaker.try_acquire().unwrap();
+        assert!(breaker.try_acquire().is_err());
+
+        // A failed probe reopens the circuit
+        breaker.record_success();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(30));
+        for _ in 0..2 {
+            breaker.try_acquire().unwrap();
+            breaker.record_success();
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        // open, half-open, open, half-open, closed
+        assert_eq!(transitions.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_call_ignores_caller_errors() {
+        let breaker = CircuitBreaker::new("test", config());
+        for _ in 0..4 {
+            let result: Result<()> = breaker
+                .call(|| async { Err(AppError::Validation("bad input".to_string())) })
+                .await;
+            assert!(result.is_err());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        for _ in 0..4 {
+            let _: Result<()> = breaker
+                .call(|| async { Err(AppError::Internal(anyhow!("connection refused"))) })
+                .await;
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_registry_reuses_breakers() {
+        let registry = CircuitBreakerRegistry::new("webhook", config());
+        let first = registry.get("hooks.example.com");
+        assert_eq!(first.name(), "webhook:hooks.example.com");
+        assert!(Arc::ptr_eq(&first, &registry.get("hooks.example.com")));
+        assert!(!Arc::ptr_eq(&first, &registry.get("other.example.com")));
+    }
+}
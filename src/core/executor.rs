@@ -0,0 +1,157 @@
+//! Ordered concurrent execution of work keyed by account.
+//!
+//! Accounts are hash-partitioned into lanes. Work touching an account is queued on the
+//! account's lane when it is scheduled, and only starts once everything scheduled
+//! earlier on that lane has finished. Work on disjoint lanes runs concurrently, up to
+//! the executor's concurrency limit, so debits and credits for one account are applied
+//! in the order they were scheduled even while a batch is spread across workers.
+//!
+//! Work touching several accounts (a transfer's source and destination) waits on every
+//! lane involved. Since queueing happens synchronously at scheduling time, two pieces of
+//! work sharing a lane can never overtake each other.
+
+use futures::future::{FutureExt, Shared};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, Semaphore};
+use uuid::Uuid;
+
+/// Lanes accounts are partitioned into, unless configured.
+pub const DEFAULT_LANES: usize = 256;
+
+/// Resolves when the most recently scheduled work on a lane finishes or is dropped.
+type LaneTail = Shared<oneshot::Receiver<()>>;
+
+/// Runs work concurrently while keeping it ordered per account.
+pub struct AccountExecutor {
+    lanes: Mutex<Vec<Option<LaneTail>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl AccountExecutor {
+    /// Creates an executor running at most `concurrency` pieces of work at once.
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            lanes: Mutex::new(vec![None; DEFAULT_LANES]),
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+        }
+    }
+
+    /// Sets how many lanes accounts are partitioned into. Accounts sharing a lane are
+    /// ordered with respect to each other as well, so more lanes mean fewer false waits.
+    pub fn with_lanes(self, lanes: usize) -> Self {
+        *self.lanes.lock().unwrap() = vec![None; lanes.max(1)];
+        self
+    }
+
+    /// Schedules `task` for `accounts`. The returned future waits for earlier work on the
+    /// same lanes, then runs `task` once a worker is free. Work is ordered by when `run`
+    /// is called, not when the returned future is first polled.
+    pub fn run<F>(&self, accounts: &[Uuid], task: F) -> impl Future<Output = F::Output>
+    where
+        F: Future,
+    {
+        let (done, finished) = oneshot::channel::<()>();
+        let finished = finished.shared();
+
+        let predecessors: Vec<LaneTail> = {
+            let mut lanes = self.lanes.lock().unwrap();
+            let count = lanes.len() as u128;
+            let mut indices: Vec<usize> = accounts
+                .iter()
+                .map(|account| (account.as_u128() % count) as usize)
+                .collect();
+            indices.sort_unstable();
+            indices.dedup();
+            indices
+                .into_iter()
+                .filter_map(|index| lanes[index].replace(finished.clone()))
+                .collect()
+        };
+        let permits = self.permits.clone();
+
+        async move {
+            for predecessor in predecessors {
+                // A dropped predecessor also releases the lane
+                let _ = predecessor.await;
+            }
+            let _permit = permits.acquire_owned().await.expect("executor semaphore closed");
+            let output = task.await;
+            drop(done);
+            output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::join_all;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_account_runs_in_order() {
+        let executor = AccountExecutor::new(8);
+        let account = Uuid::new_v4();
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        // Earlier work sleeps longer, so it would finish last if it were not ordered
+        let work = (0..5u64).map(|i| {
+            let log = log.clone();
+            executor.run(&[account], async move {
+                tokio::time::sleep(Duration::from_millis(25 - i * 5)).await;
+                log.lock().unwrap().push(i);
+            })
+        });
+        join_all(work).await;
+
+        assert_eq!(*log.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_transfers_wait_on_both_accounts() {
+        let executor = AccountExecutor::new(8).with_lanes(16);
+        let (a, b, c) = (Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3));
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let entry = |name: &'static str, delay: u64| {
+            let log = log.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                log.lock().unwrap().push(name);
+            }
+        };
+        let first = executor.run(&[a], entry("a", 30));
+        let second = executor.run(&[c], entry("c", 0));
+        let transfer = executor.run(&[b, a], entry("b->a", 0));
+        futures::join!(first, second, transfer);
+
+        let log = log.lock().unwrap();
+        let position = |name| log.iter().position(|entry| *entry == name).unwrap();
+        assert!(position("a") < position("b->a"));
+        // Unrelated accounts do not wait
+        assert!(position("c") < position("a"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_is_bounded() {
+        let executor = AccountExecutor::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let work = (0..10).map(|_| {
+            let running = running.clone();
+            let peak = peak.clone();
+            executor.run(&[Uuid::new_v4()], async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
+        join_all(work).await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,351 @@
+//! Runtime control and visibility of background jobs.
+//!
+//! A job's loop asks its `JobControl` whether to run each time it wakes, reports how
+//! each run went, then waits on the control for the next one. Operators can pause and
+//! resume the job, trigger a run straight away and change its interval without a
+//! restart; the control records the last and next run, what the job processed and the
+//! errors it hit. A job gated on leader election stands by while another instance leads.
+
+use crate::core::leader::LeaderElection;
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Snapshot of a job's state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatus {
+    pub name: String,
+    /// True between `start` and `stop` of the job's background task.
+    pub running: bool,
+    /// A paused job skips its scheduled runs; triggered runs still happen.
+    pub paused: bool,
+    /// Gated on leader election and another instance leads, so the job does not run here.
+    pub standby: bool,
+    pub interval_secs: u64,
+    pub last_run_started_at: Option<DateTime<Utc>>,
+    pub last_run_finished_at: Option<DateTime<Utc>>,
+    /// When the next scheduled run is due; unset while paused or stopped.
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub runs: u64,
+    /// Items the job's runs processed, e.g. batches settled or records removed.
+    pub items_processed: u64,
+    pub errors: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+struct ControlState {
+    running: bool,
+    paused: bool,
+    triggered: bool,
+    interval: Duration,
+    last_run_started_at: Option<DateTime<Utc>>,
+    last_run_finished_at: Option<DateTime<Utc>>,
+    next_run_at: Option<DateTime<Utc>>,
+    runs: u64,
+    items_processed: u64,
+    errors: u64,
+    last_error: Option<String>,
+    last_error_at: Option<DateTime<Utc>>,
+}
+
+impl ControlState {
+    /// Next scheduled run after the last one finished, or now if it never ran.
+    fn schedule(&mut self, now: DateTime<Utc>) {
+        self.next_run_at = if self.paused || !self.running {
+            None
+        } else {
+            let interval = chrono::Duration::from_std(self.interval).unwrap_or_default();
+            let after_last = self.last_run_finished_at.map(|at| at + interval);
+            Some(after_last.map_or(now, |at| at.max(now)))
+        };
+    }
+}
+
+/// Shared control of one background job.
+pub struct JobControl {
+    name: String,
+    state: Mutex<ControlState>,
+    wake: Notify,
+    leader: OnceLock<Arc<LeaderElection>>,
+}
+
+impl JobControl {
+    pub fn new(name: impl Into<String>, interval: Duration) -> Self {
+        Self {
+            name: name.into(),
+            state: Mutex::new(ControlState {
+                running: false,
+                paused: false,
+                triggered: false,
+                interval,
+                last_run_started_at: None,
+                last_run_finished_at: None,
+                next_run_at: None,
+                runs: 0,
+                items_processed: 0,
+                errors: 0,
+                last_error: None,
+                last_error_at: None,
+            }),
+            wake: Notify::new(),
+            leader: OnceLock::new(),
+        }
+    }
+
+    /// Only runs the job while this instance is the elected leader. Scheduled runs
+    /// that fall due on standby wait, and run as soon as the instance takes over.
+    pub fn set_leader(&self, leader: Arc<LeaderElection>) {
+        if self.leader.set(leader).is_ok() {
+            self.wake.notify_one();
+        }
+    }
+
+    /// True while the job is gated on an election this instance has not won.
+    pub fn is_standby(&self) -> bool {
+        self.leader.get().is_some_and(|leader| !leader.is_leader())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.state.lock().unwrap().interval
+    }
+
+    /// Marks the job's task started or stopped. Stopping wakes the task so it exits
+    /// without waiting out its interval.
+    pub fn set_running(&self, running: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.running = running;
+        state.schedule(Utc::now());
+        drop(state);
+        self.wake.notify_one();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.lock().unwrap().running
+    }
+
+    /// Skips scheduled runs until resumed. A run in progress finishes.
+    pub fn pause(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = true;
+        state.schedule(Utc::now());
+    }
+
+    /// Resumes scheduled runs; a run overdue while paused starts straight away.
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.paused = false;
+        state.schedule(Utc::now());
+        drop(state);
+        self.wake.notify_one();
+    }
+
+    /// Runs the job as soon as possible, even while paused, without moving its
+    /// schedule otherwise.
+    pub fn trigger(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.running {
+            return Err(AppError::Validation(format!("Job '{}' is not running", self.name)));
+        }
+        if self.is_standby() {
+            return Err(AppError::Validation(format!(
+                "Job '{}' runs on the leader instance; this instance is on standby",
+                self.name
+            )));
+        }
+        state.triggered = true;
+        drop(state);
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    /// Changes the time between runs; the next run is rescheduled from the last one.
+    pub fn set_interval(&self, interval: Duration) -> Result<()> {
+        if interval.is_zero() {
+            return Err(AppError::Validation("Job interval must be positive".to_string()));
+        }
+        let mut state = self.state.lock().unwrap();
+        state.interval = interval;
+        state.schedule(Utc::now());
+        drop(state);
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    pub fn status(&self) -> JobStatus {
+        let state = self.state.lock().unwrap();
+        JobStatus {
+            name: self.name.clone(),
+            running: state.running,
+            paused: state.paused,
+            standby: self.is_standby(),
+            interval_secs: state.interval.as_secs(),
+            last_run_started_at: state.last_run_started_at,
+            last_run_finished_at: state.last_run_finished_at,
+            next_run_at: state.next_run_at,
+            runs: state.runs,
+            items_processed: state.items_processed,
+            errors: state.errors,
+            last_error: state.last_error.clone(),
+            last_error_at: state.last_error_at,
+        }
+    }
+
+    /// Called by the job when it wakes: returns true if it should run now, because
+    /// a run was triggered or one is due and the job is not paused.
+    pub fn begin_run(&self) -> bool {
+        if self.is_standby() {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        let now = Utc::now();
+        let due = !state.paused && state.next_run_at.is_some_and(|at| at <= now);
+        if !state.running || !(state.triggered || due) {
+            return false;
+        }
+        state.triggered = false;
+        state.last_run_started_at = Some(now);
+        state.next_run_at = None;
+        true
+    }
+
+    /// Called by the job after a run with how many items it processed, or why it
+    /// failed, and schedules the next run.
+    pub fn finish_run(&self, outcome: std::result::Result<u64, String>) {
+        let mut state = self.state.lock().unwrap();
+        let now = Utc::now();
+        state.runs += 1;
+        state.last_run_finished_at = Some(now);
+        match outcome {
+            Ok(items) => state.items_processed += items,
+            Err(error) => {
+                state.errors += 1;
+                state.last_error = Some(error);
+                state.last_error_at = Some(now);
+            }
+        }
+        state.schedule(now);
+    }
+
+    /// Waits until a run is due, a run is triggered or the job is stopped. May return
+    /// early; callers check `begin_run`.
+    pub async fn wait(&self) {
+        loop {
+            // Taken before checking for standby so a takeover in between is not missed
+            let leadership_changed = self.leader.get().map(|leader| leader.changed());
+            let sleep = {
+                let state = self.state.lock().unwrap();
+                if !state.running {
+                    return;
+                }
+                if self.is_standby() {
+                    // Only taking over leadership, or a stop, wakes the job
+                    Duration::MAX
+                } else if state.triggered {
+                    return;
+                } else {
+                    match state.next_run_at {
+                        Some(at) => (at - Utc::now()).to_std().unwrap_or(Duration::ZERO),
+                        // Paused: only a resume, trigger or stop wakes the job
+                        None => Duration::MAX,
+                    }
+                }
+            };
+            if sleep.is_zero() {
+                return;
+            }
+
+            let leadership_changed = async {
+                match leadership_changed {
+                    Some(changed) => changed.await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(sleep.min(Duration::from_secs(86_400))) => {}
+                _ = self.wake.notified() => {}
+                _ = leadership_changed => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_when_due_unless_paused() {
+        let control = JobControl::new("test", Duration::from_secs(60));
+        assert!(!control.begin_run(), "not started");
+
+        control.set_running(true);
+        assert!(control.begin_run(), "first run is due straight away");
+        control.finish_run(Ok(3));
+        assert!(!control.begin_run(), "next run is a minute away");
+
+        let status = control.status();
+        assert_eq!(status.runs, 1);
+        assert_eq!(status.items_processed, 3);
+        let next = status.next_run_at.expect("next run should be scheduled");
+        assert_eq!(next, status.last_run_finished_at.unwrap() + chrono::Duration::seconds(60));
+
+        control.pause();
+        assert_eq!(control.status().next_run_at, None);
+        control.trigger().unwrap();
+        assert!(control.begin_run(), "triggered runs happen while paused");
+        control.finish_run(Err("boom".to_string()));
+
+        let status = control.status();
+        assert!(status.paused);
+        assert_eq!(status.errors, 1);
+        assert_eq!(status.last_error.as_deref(), Some("boom"));
+        assert_eq!(status.next_run_at, None);
+    }
+
+    #[test]
+    fn test_interval_changes_reschedule() {
+        let control = JobControl::new("test", Duration::from_secs(3600));
+        control.set_running(true);
+        assert!(control.begin_run());
+        control.finish_run(Ok(0));
+
+        assert!(control.set_interval(Duration::ZERO).is_err());
+        control.set_interval(Duration::from_secs(5)).unwrap();
+        let status = control.status();
+        assert_eq!(status.interval_secs, 5);
+        assert_eq!(
+            status.next_run_at.unwrap(),
+            status.last_run_finished_at.unwrap() + chrono::Duration::seconds(5)
+        );
+
+        control.set_running(false);
+        assert!(control.trigger().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_wakes_on_trigger() {
+        let control = std::sync::Arc::new(JobControl::new("test", Duration::from_secs(3600)));
+        control.set_running(true);
+        assert!(control.begin_run());
+        control.finish_run(Ok(0));
+
+        let waiter = tokio::spawn({
+            let control = control.clone();
+            async move { control.wait().await }
+        });
+        control.trigger().unwrap();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("Trigger should wake the job")
+            .unwrap();
+        assert!(control.begin_run());
+    }
+}
@@ -0,0 +1,187 @@
+//! Leader election among instances sharing the database.
+//!
+//! Instances campaign for a session-level Postgres advisory lock, each on a connection
+//! taken out of the pool for the purpose. The instance holding the lock is the leader
+//! and runs the jobs that must not run on several instances at once. It stays leader
+//! until it stops or its connection fails a heartbeat; Postgres releases the lock when
+//! the session ends, including when the instance dies, and the next instance to try
+//! takes over.
+//!
+//! A leader cut off from the database notices at its next heartbeat, so for up to one
+//! heartbeat interval two instances can both consider themselves leader. Jobs gated on
+//! leadership should tolerate that; the batch scheduler does through its processing
+//! leases.
+
+use crate::observability::get_metrics;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, PgConnection, PgPool};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{futures::Notified, Notify};
+use uuid::Uuid;
+
+/// Advisory lock key instances campaign for, unless configured.
+pub const DEFAULT_LEADER_LOCK_KEY: i64 = 0x5345_5454_4c45;
+
+/// This instance's view of the election.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderStatus {
+    pub instance_id: String,
+    pub lock_key: i64,
+    pub leader: bool,
+    /// When this instance last became leader; unset while a follower.
+    pub leader_since: Option<DateTime<Utc>>,
+    /// Times this instance gained or lost leadership.
+    pub transitions: u64,
+}
+
+struct ElectionState {
+    leader_since: Option<DateTime<Utc>>,
+    transitions: u64,
+}
+
+/// Campaigns for leadership in the background and reports whether this instance leads.
+pub struct LeaderElection {
+    pool: PgPool,
+    lock_key: i64,
+    heartbeat_interval: Duration,
+    instance_id: String,
+    leader: AtomicBool,
+    running: AtomicBool,
+    state: Mutex<ElectionState>,
+    changed: Notify,
+    stopping: Notify,
+}
+
+impl LeaderElection {
+    /// Creates an election on `lock_key`. Followers retry, and the leader checks its
+    /// connection, every `heartbeat_interval`.
+    pub fn new(pool: PgPool, lock_key: i64, heartbeat_interval: Duration) -> Self {
+        Self {
+            pool,
+            lock_key,
+            heartbeat_interval,
+            instance_id: Uuid::new_v4().to_string(),
+            leader: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            state: Mutex::new(ElectionState { leader_since: None, transitions: 0 }),
+            changed: Notify::new(),
+            stopping: Notify::new(),
+        }
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::SeqCst)
+    }
+
+    pub fn status(&self) -> LeaderStatus {
+        let state = self.state.lock().unwrap();
+        LeaderStatus {
+            instance_id: self.instance_id.clone(),
+            lock_key: self.lock_key,
+            leader: self.is_leader(),
+            leader_since: state.leader_since,
+            transitions: state.transitions,
+        }
+    }
+
+    /// Resolves the next time this instance gains or loses leadership after the call.
+    pub fn changed(&self) -> Notified<'_> {
+        self.changed.notified()
+    }
+
+    /// Starts campaigning in a background task.
+    pub fn start(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let election = self.clone();
+        election.running.store(true, Ordering::SeqCst);
+        get_metrics().record_leadership(false, false);
+
+        tokio::spawn(async move {
+            while election.running.load(Ordering::SeqCst) {
+                // Session locks stay with the connection, so it must not go back to the pool
+                match election.pool.acquire().await {
+                    Ok(connection) => election.campaign(connection.detach()).await,
+                    Err(e) => {
+                        tracing::warn!("Leader election could not get a connection: {}", e);
+                        election.pause().await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Stops campaigning, releasing leadership if held.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.stopping.notify_one();
+    }
+
+    /// Tries for the lock on `connection`, then holds it while the connection stays
+    /// healthy. Returns when the connection fails or the election stops.
+    async fn campaign(&self, mut connection: PgConnection) {
+        while self.running.load(Ordering::SeqCst) {
+            let checked = if self.is_leader() {
+                sqlx::query("SELECT 1").execute(&mut connection).await.map(|_| true)
+            } else {
+                sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1)")
+                    .bind(self.lock_key)
+                    .fetch_one(&mut connection)
+                    .await
+            };
+
+            match checked {
+                Ok(leader) => self.set_leader(leader),
+                Err(e) => {
+                    tracing::warn!("Leader election connection failed: {}", e);
+                    self.set_leader(false);
+                    return;
+                }
+            }
+            self.pause().await;
+        }
+
+        if self.is_leader() {
+            let unlocked = sqlx::query("SELECT pg_advisory_unlock($1)")
+                .bind(self.lock_key)
+                .execute(&mut connection)
+                .await;
+            if let Err(e) = unlocked {
+                tracing::warn!("Failed to release leadership: {}", e);
+            }
+            self.set_leader(false);
+        }
+        let _ = connection.close().await;
+    }
+
+    fn set_leader(&self, leader: bool) {
+        if self.leader.swap(leader, Ordering::SeqCst) == leader {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.transitions += 1;
+        state.leader_since = leader.then(Utc::now);
+        drop(state);
+
+        if leader {
+            tracing::info!("Instance {} became leader", self.instance_id);
+        } else {
+            tracing::info!("Instance {} is no longer leader", self.instance_id);
+        }
+        get_metrics().record_leadership(leader, true);
+        self.changed.notify_waiters();
+    }
+
+    async fn pause(&self) {
+        tokio::select! {
+            _ = tokio::time::sleep(self.heartbeat_interval) => {}
+            _ = self.stopping.notified() => {}
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Named locks for critical sections that must not run twice at once, whether the
+//! callers are concurrent requests on one instance or different instances.
+//!
+//! Two backends are available. Postgres session advisory locks are held on a
+//! connection taken out of the pool while the lock is held; Postgres releases them
+//! when the session ends, so a holder that dies cannot leave one behind. Redis locks
+//! are keys set with `NX` and a TTL, renewed by the holder while it runs and deleted
+//! only by the token that set them. A Redis holder cut off for longer than the TTL
+//! loses its lock without noticing, so sections guarded through Redis should also be
+//! safe to repeat; the batch processing leases are.
+
+use crate::cache::RedisPool;
+use crate::error::{AppError, Result};
+use sqlx::{Connection, PgConnection, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// TTL of Redis locks, unless configured. Holders renew them every third of it.
+pub const DEFAULT_LOCK_TTL: Duration = Duration::from_secs(30);
+
+/// Deletes the key only if it still holds the caller's token.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// Extends the key's TTL only if it still holds the caller's token.
+const RENEW_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Where locks are kept.
+#[derive(Clone)]
+pub enum LockBackend {
+    Postgres(PgPool),
+    Redis { redis: Arc<RedisPool>, key_prefix: String },
+}
+
+/// Hands out named locks from one backend.
+pub struct DistributedLocks {
+    backend: LockBackend,
+    ttl: Duration,
+}
+
+impl DistributedLocks {
+    /// Locks kept as Postgres advisory locks.
+    pub fn postgres(pool: PgPool) -> Self {
+        Self {
+            backend: LockBackend::Postgres(pool),
+            ttl: DEFAULT_LOCK_TTL,
+        }
+    }
+
+    /// Locks kept as Redis keys under `key_prefix`.
+    pub fn redis(redis: Arc<RedisPool>, key_prefix: impl Into<String>) -> Self {
+        Self {
+            backend: LockBackend::Redis {
+                redis,
+                key_prefix: key_prefix.into(),
+            },
+            ttl: DEFAULT_LOCK_TTL,
+        }
+    }
+
+    /// Sets the TTL of Redis locks; Postgres locks last until released.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl.max(Duration::from_millis(3));
+        self
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        match self.backend {
+            LockBackend::Postgres(_) => "postgres",
+            LockBackend::Redis { .. } => "redis",
+        }
+    }
+
+    /// Takes the lock `name` if nobody holds it, or returns `None` straight away.
+    pub async fn try_acquire(&self, name: &str) -> Result<Option<LockGuard>> {
+        let held = match &self.backend {
+            LockBackend::Postgres(pool) => {
+                // Session locks stay with the connection, so it must not go back to the pool
+                let mut connection = pool.acquire().await.map_err(AppError::Database)?.detach();
+                let locked = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock(hashtextextended($1, 0))")
+                    .bind(name)
+                    .fetch_one(&mut connection)
+                    .await
+                    .map_err(AppError::Database)?;
+                if !locked {
+                    let _ = connection.close().await;
+                    return Ok(None);
+                }
+                Held::Postgres(Some(Box::new(connection)))
+            }
+            LockBackend::Redis { redis, key_prefix } => {
+                let key = format!("{}:lock:{}", key_prefix, name);
+                let token = Uuid::new_v4().to_string();
+                let set: Option<String> = redis
+                    .query(
+                        redis::cmd("SET")
+                            .arg(&key)
+                            .arg(&token)
+                            .arg("NX")
+                            .arg("PX")
+                            .arg(self.ttl.as_millis() as u64),
+                    )
+                    .await?;
+                if set.is_none() {
+                    return Ok(None);
+                }
+                let renewal = tokio::spawn(renew(redis.clone(), key.clone(), token.clone(), self.ttl));
+                Held::Redis {
+                    redis: redis.clone(),
+                    key,
+                    token,
+                    renewal,
+                }
+            }
+        };
+
+        tracing::debug!("Acquired lock {}", name);
+        Ok(Some(LockGuard {
+            name: name.to_string(),
+            held,
+        }))
+    }
+}
+
+/// Keeps a Redis lock alive until its guard goes, or until it is found lost.
+async fn renew(redis: Arc<RedisPool>, key: String, token: String, ttl: Duration) {
+    loop {
+        tokio::time::sleep(ttl / 3).await;
+        let renewed: Result<i64> = redis
+            .query(
+                redis::cmd("EVAL")
+                    .arg(RENEW_SCRIPT)
+                    .arg(1)
+                    .arg(&key)
+                    .arg(&token)
+                    .arg(ttl.as_millis() as u64),
+            )
+            .await;
+        match renewed {
+            Ok(1) => {}
+            Ok(_) => {
+                tracing::warn!("Lock {} expired before it could be renewed", key);
+                return;
+            }
+            Err(e) => tracing::warn!("Failed to renew lock {}: {}", key, e),
+        }
+    }
+}
+
+enum Held {
+    Postgres(Option<Box<PgConnection>>),
+    Redis {
+        redis: Arc<RedisPool>,
+        key: String,
+        token: String,
+        renewal: tokio::task::JoinHandle<()>,
+    },
+}
+
+/// A held lock. Release it with `release`; dropping the guard releases it too, by
+/// closing the Postgres session or deleting the Redis key in the background.
+pub struct LockGuard {
+    name: String,
+    held: Held,
+}
+
+impl LockGuard {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Releases the lock.
+    pub async fn release(mut self) -> Result<()> {
+        match &mut self.held {
+            Held::Postgres(connection) => {
+                if let Some(mut connection) = connection.take() {
+                    sqlx::query("SELECT pg_advisory_unlock(hashtextextended($1, 0))")
+                        .bind(&self.name)
+                        .execute(&mut *connection)
+                        .await
+                        .map_err(AppError::Database)?;
+                    let _ = connection.close().await;
+                }
+            }
+            Held::Redis {
+                redis,
+                key,
+                token,
+                renewal,
+            } => {
+                renewal.abort();
+                release_redis(redis, key, token).await?;
+                // Already released; keeps Drop from doing it again
+                *token = String::new();
+            }
+        }
+        tracing::debug!("Released lock {}", self.name);
+        Ok(())
+    }
+}
+
+async fn release_redis(redis: &RedisPool, key: &str, token: &str) -> Result<()> {
+    redis
+        .query::<i64>(redis::cmd("EVAL").arg(RELEASE_SCRIPT).arg(1).arg(key).arg(token))
+        .await
+        .map(|_| ())
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // A dropped Postgres connection ends its session, which releases the lock
+        if let Held::Redis {
+            redis,
+            key,
+            token,
+            renewal,
+        } = &mut self.held
+        {
+            renewal.abort();
+            if token.is_empty() {
+                return;
+            }
+            let (redis, key, token) = (redis.clone(), std::mem::take(key), std::mem::take(token));
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(async move {
+                    if let Err(e) = release_redis(&redis, &key, &token).await {
+                        tracing::warn!("Failed to release lock {}: {}", key, e);
+                    }
+                });
+            }
+        }
+    }
+}
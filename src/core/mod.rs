@@ -1,3 +1,11 @@
 pub mod batch;
+pub mod circuit_breaker;
 pub mod engine;
+pub mod executor;
+pub mod job_control;
+pub mod leader;
+pub mod locks;
 pub mod ledger;
+pub mod money;
+pub mod rounding;
+pub mod saga;
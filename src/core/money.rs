@@ -0,0 +1,232 @@
+//! Monetary amounts.
+//!
+//! A `Money` carries its amount together with the currency (or instrument code) it is
+//! denominated in, so the two cannot be separated and mixed up on the way through the
+//! services. Arithmetic is checked: amounts in different currencies cannot be added or
+//! compared, and no amount holds more decimal places than the ledger stores.
+
+use super::rounding::RoundingPolicy;
+use crate::error::AppError;
+use crate::models::Instrument;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Decimal places of the ledger's amount columns.
+pub const MAX_MONEY_SCALE: u32 = 4;
+
+/// Why an amount could not be made or combined.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MoneyError {
+    #[error("'{0}' is not a currency or instrument code")]
+    InvalidCurrency(String),
+
+    #[error("{amount} {currency} has more than {max} decimal places", max = MAX_MONEY_SCALE)]
+    ScaleExceeded { amount: Decimal, currency: String },
+
+    #[error("Cannot combine an amount in {0} with one in {1}")]
+    CurrencyMismatch(String, String),
+
+    #[error("Amount out of range")]
+    Overflow,
+}
+
+impl From<MoneyError> for AppError {
+    fn from(e: MoneyError) -> Self {
+        AppError::Validation(e.to_string())
+    }
+}
+
+/// An amount in a currency or instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "MoneyParts", into = "MoneyParts")]
+pub struct Money {
+    amount: Decimal,
+    currency: String,
+}
+
+/// Wire form of `Money`, validated on the way in.
+#[derive(Serialize, Deserialize)]
+struct MoneyParts {
+    amount: Decimal,
+    currency: String,
+}
+
+impl Money {
+    /// An amount in a currency, refused if the code is malformed or the amount is
+    /// finer than the ledger stores.
+    pub fn new(amount: Decimal, currency: impl Into<String>) -> Result<Self, MoneyError> {
+        let currency = currency.into();
+        if !Instrument::is_valid_code(&currency) {
+            return Err(MoneyError::InvalidCurrency(currency));
+        }
+        if amount.normalize().scale() > MAX_MONEY_SCALE {
+            return Err(MoneyError::ScaleExceeded { amount, currency });
+        }
+        Ok(Self { amount, currency })
+    }
+
+    pub fn zero(currency: impl Into<String>) -> Result<Self, MoneyError> {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    pub fn into_parts(self) -> (Decimal, String) {
+        (self.amount, self.currency)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.amount > Decimal::ZERO
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount < Decimal::ZERO
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            amount: self.amount.abs(),
+            currency: self.currency.clone(),
+        }
+    }
+
+    pub fn negated(&self) -> Self {
+        Self {
+            amount: -self.amount,
+            currency: self.currency.clone(),
+        }
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Self, MoneyError> {
+        self.ensure_same_currency(other)?;
+        let amount = self.amount.checked_add(other.amount).ok_or(MoneyError::Overflow)?;
+        Ok(Self {
+            amount,
+            currency: self.currency.clone(),
+        })
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Self, MoneyError> {
+        self.checked_add(&other.negated())
+    }
+
+    /// Compares two amounts in the same currency.
+    pub fn checked_cmp(&self, other: &Money) -> Result<Ordering, MoneyError> {
+        self.ensure_same_currency(other)?;
+        Ok(self.amount.cmp(&other.amount))
+    }
+
+    /// Multiplies the amount by a factor, such as a rate or a percentage, and rounds
+    /// the product to the currency's scale under the rounding policy.
+    pub fn scaled(&self, factor: Decimal, rounding: &RoundingPolicy) -> Result<Self, MoneyError> {
+        let product = self.amount.checked_mul(factor).ok_or(MoneyError::Overflow)?;
+        Self::new(rounding.round(product, &self.currency), self.currency.clone())
+    }
+
+    /// Splits the amount pro-rata to the weights under the rounding policy; the shares
+    /// add up to the amount exactly.
+    pub fn allocate(&self, weights: &[(Uuid, Decimal)], rounding: &RoundingPolicy) -> Vec<(Uuid, Money)> {
+        rounding
+            .allocate(self.amount, &self.currency, weights)
+            .into_iter()
+            .map(|(id, amount)| {
+                let share = Self {
+                    amount,
+                    currency: self.currency.clone(),
+                };
+                (id, share)
+            })
+            .collect()
+    }
+
+    fn ensure_same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency.clone(), other.currency.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+impl TryFrom<MoneyParts> for Money {
+    type Error = MoneyError;
+
+    fn try_from(parts: MoneyParts) -> Result<Self, Self::Error> {
+        Self::new(parts.amount, parts.currency)
+    }
+}
+
+impl From<Money> for MoneyParts {
+    fn from(money: Money) -> Self {
+        Self {
+            amount: money.amount,
+            currency: money.currency,
+        }
+    }
+}
+
+impl TryFrom<(Decimal, &str)> for Money {
+    type Error = MoneyError;
+
+    fn try_from((amount, currency): (Decimal, &str)) -> Result<Self, Self::Error> {
+        Self::new(amount, currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_money_refuses_cross_currency_arithmetic() {
+        let usd = Money::new(dec!(100.50), "USD").unwrap();
+        let more = Money::new(dec!(0.25), "USD").unwrap();
+        assert_eq!(usd.checked_add(&more).unwrap(), Money::new(dec!(100.75), "USD").unwrap());
+        assert_eq!(usd.checked_sub(&more).unwrap().amount(), dec!(100.25));
+        assert_eq!(more.checked_cmp(&usd), Ok(Ordering::Less));
+
+        let eur = Money::new(dec!(1), "EUR").unwrap();
+        assert_eq!(
+            usd.checked_add(&eur),
+            Err(MoneyError::CurrencyMismatch("USD".to_string(), "EUR".to_string()))
+        );
+        assert!(usd.checked_cmp(&eur).is_err());
+        assert_eq!(usd.to_string(), "100.50 USD");
+    }
+
+    #[test]
+    fn test_money_enforces_scale_and_code() {
+        assert!(Money::new(dec!(1.2345), "USD").is_ok());
+        assert!(Money::new(dec!(1.23450000), "USD").is_ok());
+        assert!(matches!(Money::new(dec!(1.23456), "USD"), Err(MoneyError::ScaleExceeded { .. })));
+        assert!(matches!(Money::new(dec!(1), "usd"), Err(MoneyError::InvalidCurrency(_))));
+        assert!(Money::new(dec!(10), "US0378331005").is_ok());
+
+        let policy = RoundingPolicy::default();
+        let fee = Money::new(dec!(1234.56), "USD").unwrap().scaled(dec!(0.0125), &policy).unwrap();
+        assert_eq!(fee.amount(), dec!(15.43));
+
+        let parsed: Result<Money, _> = serde_json::from_str(r#"{"amount": "1.000001", "currency": "USD"}"#);
+        assert!(parsed.is_err());
+    }
+}
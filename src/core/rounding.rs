@@ -0,0 +1,202 @@
+//! Rounding of computed amounts.
+//!
+//! FX conversions, revaluations and pro-rata splits compute amounts finer than a
+//! currency's minor unit. The policy rounds them to the currency's scale in one mode
+//! for the whole engine, and splits a total so the shares add up to it exactly: the
+//! rounding remainder goes to the shares with the largest remainders, or to a
+//! designated account, so every journal still balances to the minor unit.
+
+use crate::models::Currency;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Decimal places of currencies with neither a configured scale nor an ISO definition.
+pub const DEFAULT_SCALE: u32 = 2;
+
+/// How amounts are rounded to their currency's scale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RoundingMode {
+    /// Half to even, also known as banker's rounding.
+    #[default]
+    HalfEven,
+    /// Half away from zero.
+    HalfUp,
+    /// Toward zero.
+    Down,
+    /// Away from zero.
+    Up,
+}
+
+impl RoundingMode {
+    fn strategy(&self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+        }
+    }
+}
+
+/// Where the rounding remainder of a split goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemainderAllocation {
+    /// Shares are truncated to the minor unit and the units left over go one each to
+    /// the shares that lost the most, earlier shares first on ties.
+    #[default]
+    LargestRemainder,
+    /// Shares are rounded in the policy's mode and the difference is booked to this
+    /// account, added to its share or as a share of its own.
+    Designated(Uuid),
+}
+
+/// The engine's rounding policy.
+#[derive(Debug, Clone, Default)]
+pub struct RoundingPolicy {
+    pub mode: RoundingMode,
+    /// Decimal places per currency, overriding the ISO 4217 minor unit.
+    pub scales: HashMap<String, u32>,
+    pub remainder: RemainderAllocation,
+}
+
+impl RoundingPolicy {
+    /// Decimal places amounts in a currency are rounded to.
+    pub fn scale(&self, currency: &str) -> u32 {
+        self.scales.get(currency).copied().unwrap_or_else(|| {
+            Currency::from_str(currency)
+                .map(|c| c.decimal_places() as u32)
+                .unwrap_or(DEFAULT_SCALE)
+        })
+    }
+
+    /// Rounds an amount to its currency's scale.
+    pub fn round(&self, amount: Decimal, currency: &str) -> Decimal {
+        amount.round_dp_with_strategy(self.scale(currency), self.mode.strategy())
+    }
+
+    /// Splits `total` pro-rata to the positive weights, in their order. The shares add
+    /// up to `total` exactly; a total finer than the currency's scale leaves the excess
+    /// with the remainder. Returns no shares if no weight is positive.
+    pub fn allocate(&self, total: Decimal, currency: &str, weights: &[(Uuid, Decimal)]) -> Vec<(Uuid, Decimal)> {
+        let weights: Vec<(Uuid, Decimal)> = weights.iter().copied().filter(|(_, w)| *w > Decimal::ZERO).collect();
+        let weight_total: Decimal = weights.iter().map(|(_, w)| *w).sum();
+        if weight_total.is_zero() {
+            return Vec::new();
+        }
+        let scale = self.scale(currency);
+        let exact: Vec<Decimal> = weights.iter().map(|(_, w)| total * *w / weight_total).collect();
+
+        match self.remainder {
+            RemainderAllocation::LargestRemainder => {
+                let mut shares: Vec<(Uuid, Decimal)> = weights
+                    .iter()
+                    .zip(&exact)
+                    .map(|((id, _), e)| (*id, e.round_dp_with_strategy(scale, RoundingStrategy::ToZero)))
+                    .collect();
+                let mut remainder = total - shares.iter().map(|(_, s)| *s).sum::<Decimal>();
+                let mut order: Vec<usize> = (0..shares.len()).collect();
+                order.sort_by(|a, b| {
+                    let lost = |i: usize| (exact[i] - shares[i].1).abs();
+                    lost(*b).cmp(&lost(*a)).then(a.cmp(b))
+                });
+                // Each truncated share lost less than a unit, so one pass hands out every
+                // whole unit left over
+                let mut unit = Decimal::new(1, scale);
+                unit.set_sign_negative(total.is_sign_negative());
+                for &i in &order {
+                    if remainder.abs() < unit.abs() {
+                        break;
+                    }
+                    shares[i].1 += unit;
+                    remainder -= unit;
+                }
+                if !remainder.is_zero() {
+                    shares[order[0]].1 += remainder;
+                }
+                shares
+            }
+            RemainderAllocation::Designated(account_id) => {
+                let mut shares: Vec<(Uuid, Decimal)> = weights
+                    .iter()
+                    .zip(&exact)
+                    .map(|((id, _), e)| (*id, e.round_dp_with_strategy(scale, self.mode.strategy())))
+                    .collect();
+                let remainder = total - shares.iter().map(|(_, s)| *s).sum::<Decimal>();
+                if !remainder.is_zero() {
+                    match shares.iter_mut().find(|(id, _)| *id == account_id) {
+                        Some((_, share)) => *share += remainder,
+                        None => shares.push((account_id, remainder)),
+                    }
+                }
+                shares
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_rounds_to_currency_scale_half_even_by_default() {
+        let policy = RoundingPolicy::default();
+        assert_eq!(policy.round(dec!(1.005), "USD"), dec!(1.00));
+        assert_eq!(policy.round(dec!(1.015), "USD"), dec!(1.02));
+        assert_eq!(policy.round(dec!(1234.5), "JPY"), dec!(1234));
+        assert_eq!(policy.round(dec!(1.2345), "XYZ"), dec!(1.23));
+
+        let policy = RoundingPolicy {
+            mode: RoundingMode::HalfUp,
+            scales: HashMap::from([("BHD".to_string(), 3)]),
+            ..Default::default()
+        };
+        assert_eq!(policy.round(dec!(1.005), "USD"), dec!(1.01));
+        assert_eq!(policy.round(dec!(1.0005), "BHD"), dec!(1.001));
+    }
+
+    #[test]
+    fn test_largest_remainder_allocation_sums_to_total() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let policy = RoundingPolicy::default();
+
+        let shares = policy.allocate(dec!(100), "USD", &[(a, dec!(1)), (b, dec!(1)), (c, dec!(1))]);
+        assert_eq!(shares, vec![(a, dec!(33.34)), (b, dec!(33.33)), (c, dec!(33.33))]);
+
+        // The unit goes to the share that lost the most, not the first or last
+        let shares = policy.allocate(dec!(1), "USD", &[(a, dec!(1)), (b, dec!(2)), (c, dec!(3))]);
+        assert_eq!(shares, vec![(a, dec!(0.17)), (b, dec!(0.33)), (c, dec!(0.50))]);
+
+        let shares = policy.allocate(dec!(-10), "JPY", &[(a, dec!(1)), (b, dec!(2))]);
+        assert_eq!(shares, vec![(a, dec!(-3)), (b, dec!(-7))]);
+        assert_eq!(shares.iter().map(|(_, s)| *s).sum::<Decimal>(), dec!(-10));
+
+        assert!(policy.allocate(dec!(100), "USD", &[(a, dec!(0))]).is_empty());
+    }
+
+    #[test]
+    fn test_designated_account_takes_the_remainder() {
+        let (a, b, c, house) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let policy = RoundingPolicy {
+            remainder: RemainderAllocation::Designated(house),
+            ..Default::default()
+        };
+
+        let shares = policy.allocate(dec!(100), "USD", &[(a, dec!(1)), (b, dec!(1)), (c, dec!(1))]);
+        assert_eq!(
+            shares,
+            vec![(a, dec!(33.33)), (b, dec!(33.33)), (c, dec!(33.33)), (house, dec!(0.01))]
+        );
+
+        let shares = policy.allocate(dec!(100), "USD", &[(a, dec!(1)), (house, dec!(2))]);
+        assert_eq!(shares, vec![(a, dec!(33.33)), (house, dec!(66.67))]);
+
+        let exact = policy.allocate(dec!(90), "USD", &[(a, dec!(1)), (b, dec!(2))]);
+        assert_eq!(exact, vec![(a, dec!(30)), (b, dec!(60))]);
+    }
+}
@@ -0,0 +1,432 @@
+//! Saga orchestration for multi-step settlement flows.
+//!
+//! A saga is an ordered list of steps, each with a compensation that undoes it. Steps run
+//! one at a time under a timeout; if one fails or times out, the steps that completed are
+//! compensated in reverse order. Progress and the saga context are persisted after every
+//! step so an interrupted saga can be resumed, which means a step may run again after a
+//! restart and should be safe to repeat.
+
+use crate::error::{AppError, Result};
+use crate::models::{SagaState, SagaStatus};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Timeout applied to steps that do not set their own.
+pub const DEFAULT_STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Times a step failing with a retryable error is re-run before the saga compensates.
+pub const DEFAULT_TRANSIENT_RETRIES: u32 = 2;
+
+/// Persistence for saga state.
+#[async_trait]
+pub trait SagaStore: Send + Sync {
+    async fn save(&self, state: &SagaState) -> Result<()>;
+
+    async fn load(&self, id: Uuid) -> Result<Option<SagaState>>;
+}
+
+/// One step of a saga operating on a shared context.
+#[async_trait]
+pub trait SagaStep<C>: Send + Sync {
+    /// Name of the step, used in errors and logs.
+    fn name(&self) -> &str;
+
+    /// How long `execute` and `compensate` may each take.
+    fn timeout(&self) -> Duration {
+        DEFAULT_STEP_TIMEOUT
+    }
+
+    async fn execute(&self, context: &mut C) -> Result<()>;
+
+    /// Undoes a completed `execute`. Steps with nothing to undo keep the default.
+    async fn compensate(&self, _context: &mut C) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// An ordered list of steps identified by a saga type.
+pub struct Saga<C> {
+    saga_type: String,
+    steps: Vec<Box<dyn SagaStep<C>>>,
+}
+
+impl<C> Saga<C> {
+    pub fn new(saga_type: impl Into<String>) -> Self {
+        Self {
+            saga_type: saga_type.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn step(mut self, step: impl SagaStep<C> + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    pub fn saga_type(&self) -> &str {
+        &self.saga_type
+    }
+}
+
+/// Final state of a saga run together with its context.
+#[derive(Debug)]
+pub struct SagaOutcome<C> {
+    pub state: SagaState,
+    pub context: C,
+}
+
+/// Runs sagas, persisting their state through a `SagaStore`.
+pub struct SagaOrchestrator {
+    store: Arc<dyn SagaStore>,
+    transient_retries: u32,
+}
+
+impl SagaOrchestrator {
+    pub fn new(store: Arc<dyn SagaStore>) -> Self {
+        Self {
+            store,
+            transient_retries: DEFAULT_TRANSIENT_RETRIES,
+        }
+    }
+
+    /// Sets how often a step failing with a retryable error (see
+    /// `AppError::is_retryable`) is re-run before the saga compensates.
+    pub fn with_transient_retries(mut self, retries: u32) -> Self {
+        self.transient_retries = retries;
+        self
+    }
+
+    /// Starts a new saga and drives it to a terminal state.
+    ///
+    /// Step failures do not produce an error; they are reported through the status of
+    /// the returned state. Errors are returned only if the state cannot be persisted.
+    pub async fn start<C>(&self, saga: &Saga<C>, context: C) -> Result<SagaOutcome<C>>
+    where
+        C: Serialize + DeserializeOwned + Send,
+    {
+        let state = SagaState::new(saga.saga_type.clone(), to_value(&context)?);
+        self.store.save(&state).await?;
+        self.drive(saga, state, context).await
+    }
+
+    /// Resumes a persisted saga from its last recorded step.
+    pub async fn resume<C>(&self, saga: &Saga<C>, id: Uuid) -> Result<SagaOutcome<C>>
+    where
+        C: Serialize + DeserializeOwned + Send,
+    {
+        let state = self
+            .store
+            .load(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Saga {} not found", id)))?;
+
+        if state.saga_type != saga.saga_type {
+            return Err(AppError::Validation(format!(
+                "Saga {} is of type '{}', not '{}'",
+                id, state.saga_type, saga.saga_type
+            )));
+        }
+
+        let context = serde_json::from_value(state.context.clone())
+            .map_err(|e| AppError::Internal(e.into()))?;
+        self.drive(saga, state, context).await
+    }
+
+    async fn drive<C>(&self, saga: &Saga<C>, mut state: SagaState, mut context: C) -> Result<SagaOutcome<C>>
+    where
+        C: Serialize + DeserializeOwned + Send,
+    {
+        let mut retries = 0;
+        while state.status == SagaStatus::Running {
+            let Some(step) = saga.steps.get(state.current_step as usize) else {
+                state.status = SagaStatus::Completed;
+                self.persist(&mut state, &context).await?;
+                break;
+            };
+
+            match tokio::time::timeout(step.timeout(), step.execute(&mut context)).await {
+                Ok(Ok(())) => {
+                    state.current_step += 1;
+                    retries = 0;
+                }
+                Ok(Err(e)) if e.is_retryable() && retries < self.transient_retries => {
+                    retries += 1;
+                    tracing::warn!(
+                        saga_id = %state.id,
+                        attempt = retries,
+                        "Saga step '{}' failed with a retryable error, retrying: {}",
+                        step.name(),
+                        e
+                    );
+                    continue;
+                }
+                Ok(Err(e)) => {
+                    state.status = SagaStatus::Compensating;
+                    state.error = Some(format!("Step '{}' failed: {}", step.name(), e));
+                }
+                Err(_) => {
+                    state.status = SagaStatus::Compensating;
+                    state.error = Some(format!("Step '{}' timed out after {:?}", step.name(), step.timeout()));
+                }
+            }
+            self.persist(&mut state, &context).await?;
+        }
+
+        while state.status == SagaStatus::Compensating {
+            let Some(index) = (state.current_step as usize).checked_sub(1) else {
+                state.status = SagaStatus::Compensated;
+                self.persist(&mut state, &context).await?;
+                break;
+            };
+            let step = &saga.steps[index];
+
+            let failure = match tokio::time::timeout(step.timeout(), step.compensate(&mut context)).await {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some(format!("compensation of '{}' failed: {}", step.name(), e)),
+                Err(_) => Some(format!("compensation of '{}' timed out after {:?}", step.name(), step.timeout())),
+            };
+
+            match failure {
+                None => state.current_step -= 1,
+                Some(failure) => {
+                    tracing::error!(saga_id = %state.id, saga_type = %state.saga_type, "Saga {}", failure);
+                    state.status = SagaStatus::Failed;
+                    state.error = Some(match state.error.take() {
+                        Some(cause) => format!("{}; {}", cause, failure),
+                        None => failure,
+                    });
+                }
+            }
+            self.persist(&mut state, &context).await?;
+        }
+
+        Ok(SagaOutcome { state, context })
+    }
+
+    async fn persist<C: Serialize>(&self, state: &mut SagaState, context: &C) -> Result<()> {
+        state.context = to_value(context)?;
+        state.updated_at = Utc::now();
+        self.store.save(state).await
+    }
+}
+
+fn to_value<C: Serialize>(context: &C) -> Result<serde_json::Value> {
+    serde_json::to_value(context).map_err(|e| AppError::Internal(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        states: Mutex<HashMap<Uuid, SagaState>>,
+    }
+
+    #[async_trait]
+    impl SagaStore for MemoryStore {
+        async fn save(&self, state: &SagaState) -> Result<()> {
+            self.states.lock().unwrap().insert(state.id, state.clone());
+            Ok(())
+        }
+
+        async fn load(&self, id: Uuid) -> Result<Option<SagaState>> {
+            Ok(self.states.lock().unwrap().get(&id).cloned())
+        }
+    }
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct Trace {
+        log: Vec<String>,
+    }
+
+    enum Behaviour {
+        Succeed,
+        Fail,
+        Hang,
+    }
+
+    struct Recorded {
+        name: &'static str,
+        execute: Behaviour,
+        compensate: Behaviour,
+    }
+
+    impl Recorded {
+        fn ok(name: &'static str) -> Self {
+            Self { name, execute: Behaviour::Succeed, compensate: Behaviour::Succeed }
+        }
+    }
+
+    async fn act(behaviour: &Behaviour) -> Result<()> {
+        match behaviour {
+            Behaviour::Succeed => Ok(()),
+            Behaviour::Fail => Err(AppError::Validation("boom".to_string())),
+            Behaviour::Hang => {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SagaStep<Trace> for Recorded {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn timeout(&self) -> Duration {
+            Duration::from_millis(50)
+        }
+
+        async fn execute(&self, context: &mut Trace) -> Result<()> {
+            act(&self.execute).await?;
+            context.log.push(format!("execute {}", self.name));
+            Ok(())
+        }
+
+        async fn compensate(&self, context: &mut Trace) -> Result<()> {
+            act(&self.compensate).await?;
+            context.log.push(format!("compensate {}", self.name));
+            Ok(())
+        }
+    }
+
+    fn orchestrator() -> (Arc<MemoryStore>, SagaOrchestrator) {
+        let store = Arc::new(MemoryStore::default());
+        (store.clone(), SagaOrchestrator::new(store))
+    }
+
+    #[tokio::test]
+    async fn test_saga_completes() {
+        let (store, orchestrator) = orchestrator();
+        let saga = Saga::new("test").step(Recorded::ok("a")).step(Recorded::ok("b"));
+
+        let outcome = orchestrator.start(&saga, Trace::default()).await.unwrap();
+
+        assert_eq!(outcome.state.status, SagaStatus::Completed);
+        assert_eq!(outcome.state.current_step, 2);
+        assert_eq!(outcome.context.log, vec!["execute a", "execute b"]);
+        let persisted = store.load(outcome.state.id).await.unwrap().unwrap();
+        assert_eq!(persisted.status, SagaStatus::Completed);
+        assert_eq!(persisted.context["log"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failure_compensates_completed_steps_in_reverse() {
+        let (_, orchestrator) = orchestrator();
+        let saga = Saga::new("test")
+            .step(Recorded::ok("a"))
+            .step(Recorded::ok("b"))
+            .step(Recorded { name: "c", execute: Behaviour::Fail, compensate: Behaviour::Succeed });
+
+        let outcome = orchestrator.start(&saga, Trace::default()).await.unwrap();
+
+        assert_eq!(outcome.state.status, SagaStatus::Compensated);
+        assert_eq!(outcome.state.current_step, 0);
+        assert_eq!(outcome.state.error.as_deref(), Some("Step 'c' failed: Validation error: boom"));
+        assert_eq!(
+            outcome.context.log,
+            vec!["execute a", "execute b", "compensate b", "compensate a"]
+        );
+    }
+
+    /// Fails with a retryable conflict the first `failures` times it runs.
+    struct Flaky {
+        failures: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl SagaStep<Trace> for Flaky {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn execute(&self, context: &mut Trace) -> Result<()> {
+            let remaining = self.failures.load(std::sync::atomic::Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, std::sync::atomic::Ordering::SeqCst);
+                return Err(AppError::SerializationConflict("balance changed".to_string()));
+            }
+            context.log.push("execute flaky".to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retryable_step_failures_are_retried() {
+        let (_, orchestrator) = orchestrator();
+        let saga = Saga::new("test")
+            .step(Recorded::ok("a"))
+            .step(Flaky { failures: 2.into() });
+        let outcome = orchestrator.start(&saga, Trace::default()).await.unwrap();
+        assert_eq!(outcome.state.status, SagaStatus::Completed);
+        assert_eq!(outcome.context.log, vec!["execute a", "execute flaky"]);
+
+        // Retries are bounded
+        let saga = Saga::new("test")
+            .step(Recorded::ok("a"))
+            .step(Flaky { failures: 3.into() });
+        let outcome = orchestrator.start(&saga, Trace::default()).await.unwrap();
+        assert_eq!(outcome.state.status, SagaStatus::Compensated);
+        assert_eq!(outcome.context.log, vec!["execute a", "compensate a"]);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_triggers_compensation() {
+        let (_, orchestrator) = orchestrator();
+        let saga = Saga::new("test")
+            .step(Recorded::ok("a"))
+            .step(Recorded { name: "b", execute: Behaviour::Hang, compensate: Behaviour::Succeed });
+
+        let outcome = orchestrator.start(&saga, Trace::default()).await.unwrap();
+
+        assert_eq!(outcome.state.status, SagaStatus::Compensated);
+        assert!(outcome.state.error.unwrap().contains("timed out"));
+        assert_eq!(outcome.context.log, vec!["execute a", "compensate a"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_compensation_marks_saga_failed() {
+        let (_, orchestrator) = orchestrator();
+        let saga = Saga::new("test")
+            .step(Recorded::ok("a"))
+            .step(Recorded { name: "b", execute: Behaviour::Succeed, compensate: Behaviour::Fail })
+            .step(Recorded { name: "c", execute: Behaviour::Fail, compensate: Behaviour::Succeed });
+
+        let outcome = orchestrator.start(&saga, Trace::default()).await.unwrap();
+
+        assert_eq!(outcome.state.status, SagaStatus::Failed);
+        // Stops at the step that could not be undone, leaving it for manual intervention
+        assert_eq!(outcome.state.current_step, 2);
+        assert!(outcome.state.error.unwrap().contains("compensation of 'b' failed"));
+        assert_eq!(outcome.context.log, vec!["execute a", "execute b"]);
+    }
+
+    #[tokio::test]
+    async fn test_resume_continues_from_persisted_step() {
+        let (store, orchestrator) = orchestrator();
+        let saga = Saga::new("test").step(Recorded::ok("a")).step(Recorded::ok("b"));
+
+        // Simulate a crash after the first step completed
+        let mut state = SagaState::new("test", serde_json::json!({"log": ["execute a"]}));
+        state.current_step = 1;
+        store.save(&state).await.unwrap();
+
+        let outcome = orchestrator.resume::<Trace>(&saga, state.id).await.unwrap();
+        assert_eq!(outcome.state.status, SagaStatus::Completed);
+        assert_eq!(outcome.context.log, vec!["execute a", "execute b"]);
+
+        let other = Saga::<Trace>::new("other");
+        let result = orchestrator.resume(&other, state.id).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::delivery::transport::DeliveryTransport;
+use chrono::Duration;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Named delivery destinations and the retry policy applied to deliveries.
+#[derive(Clone)]
+pub struct DeliveryChannels {
+    destinations: HashMap<String, Arc<dyn DeliveryTransport>>,
+    pub max_attempts: i32,
+    /// Delay before the first retry, doubled for each further attempt.
+    pub retry_backoff: Duration,
+}
+
+impl DeliveryChannels {
+    pub fn new() -> Self {
+        Self {
+            destinations: HashMap::new(),
+            max_attempts: 5,
+            retry_backoff: Duration::seconds(60),
+        }
+    }
+
+    /// Registers a destination under a name.
+    pub fn with_destination(mut self, name: impl Into<String>, transport: Arc<dyn DeliveryTransport>) -> Self {
+        self.destinations.insert(name.into(), transport);
+        self
+    }
+
+    /// Sets how many attempts are made and the delay before the first retry.
+    pub fn with_retry_policy(mut self, max_attempts: i32, backoff: Duration) -> Self {
+        self.max_attempts = max_attempts;
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Gets a destination's transport.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn DeliveryTransport>> {
+        self.destinations.get(name).cloned()
+    }
+
+    /// Names of the configured destinations, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.destinations.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+impl Default for DeliveryChannels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,9 @@
+pub mod channels;
+pub mod s3;
+pub mod sftp;
+pub mod transport;
+
+pub use channels::DeliveryChannels;
+pub use s3::S3Transport;
+pub use sftp::SftpTransport;
+pub use transport::{DeliveryTransport, DirectoryTransport};
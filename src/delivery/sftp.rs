@@ -0,0 +1,186 @@
+use crate::delivery::transport::DeliveryTransport;
+use crate::error::{AppError, Result};
+use crate::models::file_delivery::sha256_hex;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// Uploads files over SFTP using the system `sftp` client in batch mode, so host keys
+/// and keys are managed the same way as for any other SSH connection from the host.
+///
+/// Files are uploaded as `<name>.part`, downloaded again to verify the checksum, and
+/// only then renamed to their final name.
+pub struct SftpTransport {
+    host: String,
+    port: u16,
+    username: String,
+    identity_file: Option<PathBuf>,
+    remote_dir: String,
+    program: String,
+}
+
+impl SftpTransport {
+    pub fn new(host: impl Into<String>, port: u16, username: impl Into<String>, remote_dir: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            username: username.into(),
+            identity_file: None,
+            remote_dir: remote_dir.into(),
+            program: "sftp".to_string(),
+        }
+    }
+
+    /// Authenticates with this private key instead of the SSH agent or defaults.
+    pub fn with_identity_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.identity_file = Some(path.into());
+        self
+    }
+
+    /// Uses a different `sftp` executable.
+    pub fn with_program(mut self, program: impl Into<String>) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    fn remote_path(&self, file_name: &str) -> String {
+        let dir = self.remote_dir.trim_end_matches('/');
+        if dir.is_empty() {
+            file_name.to_string()
+        } else {
+            format!("{}/{}", dir, file_name)
+        }
+    }
+
+    fn upload_script(&self, local: &Path, verify: &Path, file_name: &str) -> String {
+        let partial = self.remote_path(&format!("{}.part", file_name));
+        format!(
+            "put \"{}\" \"{}\"\nget \"{}\" \"{}\"\n",
+            local.display(),
+            partial,
+            partial,
+            verify.display()
+        )
+    }
+
+    fn publish_script(&self, file_name: &str) -> String {
+        let target = self.remote_path(file_name);
+        // A leading '-' lets the batch continue if there is no previous file to remove
+        format!(
+            "-rm \"{}\"\nrename \"{}\" \"{}\"\n",
+            target,
+            self.remote_path(&format!("{}.part", file_name)),
+            target
+        )
+    }
+
+    async fn run(&self, script: &str) -> Result<()> {
+        let mut command = Command::new(&self.program);
+        command
+            .arg("-b")
+            .arg("-")
+            .arg("-P")
+            .arg(self.port.to_string())
+            .arg("-o")
+            .arg("BatchMode=yes");
+        if let Some(identity) = &self.identity_file {
+            command.arg("-i").arg(identity);
+        }
+        command
+            .arg(format!("{}@{}", self.username, self.host))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| AppError::Internal(anyhow!("Failed to start {}: {}", self.program, e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(script.as_bytes())
+                .await
+                .map_err(|e| AppError::Internal(anyhow!("Failed to send SFTP commands: {}", e)))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("SFTP session failed: {}", e)))?;
+        if !output.status.success() {
+            return Err(AppError::Internal(anyhow!(
+                "SFTP to {} exited with {}: {}",
+                self.host,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeliveryTransport for SftpTransport {
+    fn name(&self) -> &str {
+        "sftp"
+    }
+
+    async fn deliver(&self, file_name: &str, content: &[u8], checksum: &str) -> Result<()> {
+        let io_error = |e: std::io::Error| AppError::Internal(anyhow!("SFTP staging failed: {}", e));
+        let staging = std::env::temp_dir().join(format!("delivery-{}", Uuid::new_v4().simple()));
+        let verify = staging.with_extension("verify");
+        tokio::fs::write(&staging, content).await.map_err(io_error)?;
+
+        let result = async {
+            self.run(&self.upload_script(&staging, &verify, file_name)).await?;
+            let downloaded = tokio::fs::read(&verify).await.map_err(io_error)?;
+            if sha256_hex(&downloaded) != checksum {
+                return Err(AppError::Internal(anyhow!(
+                    "Checksum mismatch for {} on {}",
+                    file_name,
+                    self.host
+                )));
+            }
+            self.run(&self.publish_script(file_name)).await
+        }
+        .await;
+
+        let _ = tokio::fs::remove_file(&staging).await;
+        let _ = tokio::fs::remove_file(&verify).await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_scripts() {
+        let transport = SftpTransport::new("sftp.bank.example", 22, "settlement", "/inbound/");
+        let upload = transport.upload_script(Path::new("/tmp/a"), Path::new("/tmp/a.verify"), "batch.ach");
+        assert_eq!(
+            upload,
+            "put \"/tmp/a\" \"/inbound/batch.ach.part\"\nget \"/inbound/batch.ach.part\" \"/tmp/a.verify\"\n"
+        );
+
+        let publish = transport.publish_script("batch.ach");
+        assert_eq!(
+            publish,
+            "-rm \"/inbound/batch.ach\"\nrename \"/inbound/batch.ach.part\" \"/inbound/batch.ach\"\n"
+        );
+
+        let home = SftpTransport::new("sftp.bank.example", 22, "settlement", "");
+        assert_eq!(home.remote_path("statement.xml"), "statement.xml");
+    }
+
+    #[tokio::test]
+    async fn test_failed_session_is_reported() {
+        let transport = SftpTransport::new("localhost", 22, "nobody", "").with_program("false");
+        let result = transport.deliver("file.txt", b"content", &sha256_hex(b"content")).await;
+        assert!(matches!(result, Err(AppError::Internal(_))));
+    }
+}
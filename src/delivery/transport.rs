@@ -0,0 +1,58 @@
+use crate::error::{AppError, Result};
+use crate::models::file_delivery::sha256_hex;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tracing::debug;
+
+/// A destination generated files are uploaded to.
+#[async_trait]
+pub trait DeliveryTransport: Send + Sync {
+    /// Kind of destination, used in logs.
+    fn name(&self) -> &str;
+
+    /// Uploads a file and verifies the destination received content matching the
+    /// hex SHA-256 `checksum`. Implementations should publish the file under its final
+    /// name only once it is complete.
+    async fn deliver(&self, file_name: &str, content: &[u8], checksum: &str) -> Result<()>;
+}
+
+/// Writes files to a local or mounted directory (e.g. a shared drop folder).
+pub struct DirectoryTransport {
+    root: PathBuf,
+}
+
+impl DirectoryTransport {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl DeliveryTransport for DirectoryTransport {
+    fn name(&self) -> &str {
+        "directory"
+    }
+
+    async fn deliver(&self, file_name: &str, content: &[u8], checksum: &str) -> Result<()> {
+        let io_error = |e: std::io::Error| AppError::Internal(anyhow!("Directory delivery failed: {}", e));
+        tokio::fs::create_dir_all(&self.root).await.map_err(io_error)?;
+
+        let target = self.root.join(file_name);
+        let partial = self.root.join(format!("{}.part", file_name));
+        tokio::fs::write(&partial, content).await.map_err(io_error)?;
+
+        let written = tokio::fs::read(&partial).await.map_err(io_error)?;
+        if sha256_hex(&written) != checksum {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(AppError::Internal(anyhow!(
+                "Checksum mismatch after writing {}",
+                partial.display()
+            )));
+        }
+
+        tokio::fs::rename(&partial, &target).await.map_err(io_error)?;
+        debug!("Delivered {} to {}", file_name, self.root.display());
+        Ok(())
+    }
+}
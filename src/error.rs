@@ -2,6 +2,21 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use thiserror::Error;
 
+/// PostgreSQL SQLSTATEs for transactions aborted by concurrency control, which
+/// succeed when retried.
+const RETRYABLE_SQLSTATES: [&str; 3] = [
+    "40001", // serialization_failure
+    "40P01", // deadlock_detected
+    "55P03", // lock_not_available
+];
+
+/// SQLSTATE of a statement cancelled by `statement_timeout`.
+const QUERY_CANCELED_SQLSTATE: &str = "57014";
+
+/// Error code of requests that ran out of time, whether the request timeout or the
+/// database statement timeout fired.
+pub const REQUEST_TIMEOUT_CODE: &str = "REQUEST_TIMEOUT";
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Configuration error: {0}")]
@@ -22,23 +37,211 @@ pub enum AppError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    // Domain errors carry a complete, client-facing message.
+
+    /// The account's available balance does not cover the debit or reservation.
+    #[error("{0}")]
+    InsufficientFunds(String),
+
+    /// The account is frozen or closed and cannot take part in postings.
+    #[error("{0}")]
+    AccountFrozen(String),
+
+    /// A configured amount or exposure limit would be breached.
+    #[error("{0}")]
+    LimitExceeded(String),
+
+    /// A transaction amount is above the configured maximum for its currency, or has
+    /// more decimal places than allowed.
+    #[error("{0}")]
+    AmountLimitExceeded(String),
+
+    /// An idempotency key was reused with a different request.
+    #[error("{0}")]
+    IdempotencyConflict(String),
+
+    /// A concurrent update won; the request can be retried as-is.
+    #[error("{0}")]
+    SerializationConflict(String),
+
+    /// The batch no longer accepts changes.
+    #[error("{0}")]
+    BatchClosed(String),
+
+    /// The transaction already belongs to another settlement batch.
+    #[error("{0}")]
+    AlreadyAssigned(String),
+
+    /// The transaction looks like a repeat of a recent one submitted under another
+    /// idempotency key; nothing was posted.
+    #[error("{0}")]
+    PossibleDuplicate(String),
+
+    /// The transaction tripped a risk rule and waits for review; nothing was posted.
+    #[error("{0}")]
+    HeldForReview(String),
+
+    /// A dependency is temporarily unavailable, e.g. its circuit breaker is open.
+    #[error("{0}")]
+    Unavailable(String),
+
     #[error("Internal server error: {0}")]
     Internal(#[from] anyhow::Error),
 }
 
+impl AppError {
+    /// Stable machine-readable code returned to API clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Validation(_) => "VALIDATION_ERROR",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::InsufficientFunds(_) => "INSUFFICIENT_FUNDS",
+            AppError::AccountFrozen(_) => "ACCOUNT_FROZEN",
+            AppError::LimitExceeded(_) => "LIMIT_EXCEEDED",
+            AppError::AmountLimitExceeded(_) => "AMOUNT_LIMIT_EXCEEDED",
+            AppError::IdempotencyConflict(_) => "IDEMPOTENCY_CONFLICT",
+            AppError::SerializationConflict(_) => "SERIALIZATION_CONFLICT",
+            AppError::BatchClosed(_) => "BATCH_CLOSED",
+            AppError::AlreadyAssigned(_) => "ALREADY_ASSIGNED",
+            AppError::HeldForReview(_) => "HELD_FOR_REVIEW",
+            AppError::PossibleDuplicate(_) => "POSSIBLE_DUPLICATE",
+            AppError::Database(e) if is_retryable_database_error(e) => "SERIALIZATION_CONFLICT",
+            AppError::Database(e) if is_statement_timeout(e) => REQUEST_TIMEOUT_CODE,
+            AppError::Unavailable(_) | AppError::Redis(_) | AppError::Kafka(_) => "SERVICE_UNAVAILABLE",
+            AppError::Config(_) | AppError::Database(_) | AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// HTTP status the error is reported with.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::Validation(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::InsufficientFunds(_)
+            | AppError::LimitExceeded(_)
+            | AppError::AmountLimitExceeded(_)
+            | AppError::IdempotencyConflict(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::AccountFrozen(_)
+            | AppError::SerializationConflict(_)
+            | AppError::BatchClosed(_)
+            | AppError::AlreadyAssigned(_)
+            | AppError::PossibleDuplicate(_) => StatusCode::CONFLICT,
+            AppError::HeldForReview(_) => StatusCode::ACCEPTED,
+            AppError::Database(e) if is_retryable_database_error(e) => StatusCode::CONFLICT,
+            AppError::Database(e) if is_statement_timeout(e) => StatusCode::GATEWAY_TIMEOUT,
+            AppError::Unavailable(_) | AppError::Redis(_) | AppError::Kafka(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Config(_) | AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Whether repeating the same request may succeed without any change on the
+    /// caller's side. Business rule rejections are permanent until the state they
+    /// depend on changes; conflicts and unavailable dependencies are transient.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::SerializationConflict(_) | AppError::Unavailable(_) => true,
+            AppError::Database(e) => {
+                is_retryable_database_error(e)
+                    || matches!(e, sqlx::Error::PoolTimedOut | sqlx::Error::Io(_))
+            }
+            AppError::Redis(e) => e.is_io_error() || e.is_timeout() || e.is_connection_dropped(),
+            AppError::Kafka(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Message that is safe to return to API clients. Server-side failures are not
+    /// described, since they may expose internals.
+    pub fn public_message(&self) -> String {
+        match self {
+            AppError::Validation(msg)
+            | AppError::NotFound(msg)
+            | AppError::InsufficientFunds(msg)
+            | AppError::AccountFrozen(msg)
+            | AppError::LimitExceeded(msg)
+            | AppError::AmountLimitExceeded(msg)
+            | AppError::IdempotencyConflict(msg)
+            | AppError::SerializationConflict(msg)
+            | AppError::BatchClosed(msg)
+            | AppError::AlreadyAssigned(msg)
+            | AppError::HeldForReview(msg)
+            | AppError::PossibleDuplicate(msg)
+            | AppError::Unavailable(msg) => msg.clone(),
+            AppError::Database(e) if is_retryable_database_error(e) => {
+                "The request conflicted with a concurrent update; retry it".to_string()
+            }
+            AppError::Database(e) if is_statement_timeout(e) => {
+                "The request took too long and was cancelled".to_string()
+            }
+            AppError::Redis(_) | AppError::Kafka(_) => "A dependency is temporarily unavailable".to_string(),
+            AppError::Config(_) | AppError::Database(_) | AppError::Internal(_) => {
+                "An internal error occurred".to_string()
+            }
+        }
+    }
+}
+
+fn is_retryable_database_error(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(db) => db
+            .code()
+            .is_some_and(|code| RETRYABLE_SQLSTATES.contains(&code.as_ref())),
+        _ => false,
+    }
+}
+
+fn is_statement_timeout(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(db) => db.code().is_some_and(|code| code == QUERY_CANCELED_SQLSTATE),
+        _ => false,
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            AppError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
-            AppError::Validation(_) => (StatusCode::BAD_REQUEST, self.to_string()),
-            _ => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal server error".to_string(),
-            ),
-        };
-
-        (status, error_message).into_response()
+        (self.status_code(), self.public_message()).into_response()
     }
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_error_classification() {
+        let funds = AppError::InsufficientFunds("Insufficient funds: requested 10, available 5".to_string());
+        assert_eq!(funds.code(), "INSUFFICIENT_FUNDS");
+        assert_eq!(funds.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(!funds.is_retryable());
+        assert_eq!(funds.public_message(), funds.to_string());
+
+        let conflict = AppError::SerializationConflict("balance changed".to_string());
+        assert_eq!(conflict.status_code(), StatusCode::CONFLICT);
+        assert!(conflict.is_retryable());
+
+        let unavailable = AppError::Unavailable("kafka unavailable: circuit open".to_string());
+        assert_eq!(unavailable.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(unavailable.is_retryable());
+
+        let assigned = AppError::AlreadyAssigned("Transaction already assigned to another batch".to_string());
+        assert_eq!(assigned.code(), "ALREADY_ASSIGNED");
+        assert_eq!(assigned.status_code(), StatusCode::CONFLICT);
+        assert!(!assigned.is_retryable());
+
+        let held = AppError::HeldForReview("Transaction held for risk review".to_string());
+        assert_eq!(held.status_code(), StatusCode::ACCEPTED);
+        assert!(!held.is_retryable());
+
+        let pool = AppError::Database(sqlx::Error::PoolTimedOut);
+        assert_eq!(pool.code(), "INTERNAL_ERROR");
+        assert!(pool.is_retryable());
+
+        let internal = AppError::Internal(anyhow!("connection string leaked"));
+        assert_eq!(internal.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!internal.is_retryable());
+        assert_eq!(internal.public_message(), "An internal error occurred");
+    }
+}
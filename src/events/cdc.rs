@@ -0,0 +1,779 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+
+use crate::error::{AppError, Result};
+use crate::events::{EventEnvelope, EventProducer, EventType};
+use crate::models::ReplicationSlotStatus;
+use crate::observability::get_metrics;
+use crate::repositories::ReplicationRepository;
+
+/// Position in the write-ahead log, written `X/Y` like Postgres does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Lsn(pub u64);
+
+impl fmt::Display for Lsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0xFFFF_FFFF)
+    }
+}
+
+impl FromStr for Lsn {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || AppError::Validation(format!("Invalid LSN '{}'", s));
+        let (high, low) = s.split_once('/').ok_or_else(invalid)?;
+        let high = u64::from_str_radix(high, 16).map_err(|_| invalid())?;
+        let low = u64::from_str_radix(low, 16).map_err(|_| invalid())?;
+        if high > u32::MAX as u64 || low > u32::MAX as u64 {
+            return Err(invalid());
+        }
+        Ok(Lsn((high << 32) | low))
+    }
+}
+
+/// Kind of row change captured from the WAL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeOperation {
+    Insert,
+    Update,
+    Delete,
+    Truncate,
+}
+
+/// Normalized change to one row of a captured table.
+///
+/// Column values are JSON: integers, booleans and JSON columns keep their type,
+/// timestamps are RFC 3339, and everything else, numerics included, is the column's
+/// text form so no precision is lost. Large values that an update left unchanged are
+/// omitted from `after`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub schema: String,
+    pub table: String,
+    pub operation: ChangeOperation,
+    /// Replica identity (primary key) columns of the row; empty for truncates.
+    pub key: Map<String, Value>,
+    /// Row before the change: the key only for deletes and key changes, unless the
+    /// table's replica identity is `FULL`.
+    pub before: Option<Map<String, Value>>,
+    /// Row after an insert or update.
+    pub after: Option<Map<String, Value>>,
+    pub xid: u32,
+    /// WAL position of the commit; changes of one database transaction share it.
+    pub commit_lsn: String,
+    pub committed_at: DateTime<Utc>,
+}
+
+impl ChangeEvent {
+    /// Topic the event is published to: `{prefix}.{table}`.
+    pub fn topic(&self, prefix: &str) -> String {
+        format!("{}.{}", prefix, self.table)
+    }
+
+    /// Partition key: the key column values joined with `:`, so every change to a
+    /// row lands on the same partition in order.
+    pub fn partition_key(&self) -> Option<String> {
+        if self.key.is_empty() {
+            return None;
+        }
+        let values: Vec<String> = self
+            .key
+            .values()
+            .map(|value| match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        Some(values.join(":"))
+    }
+}
+
+/// Changes of one committed database transaction, in the order they were made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdcTransaction {
+    pub xid: u32,
+    pub commit_lsn: Lsn,
+    /// WAL position just after the commit; acknowledging it consumes the transaction.
+    pub end_lsn: Lsn,
+    pub committed_at: DateTime<Utc>,
+    pub changes: Vec<ChangeEvent>,
+}
+
+#[derive(Debug, Clone)]
+struct RelationColumn {
+    name: String,
+    type_oid: u32,
+    key: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Relation {
+    schema: String,
+    name: String,
+    columns: Vec<RelationColumn>,
+}
+
+#[derive(Debug, Clone)]
+enum TupleValue {
+    Null,
+    /// A TOASTed value an update did not touch; pgoutput does not resend it.
+    Unchanged,
+    Text(String),
+}
+
+/// Decoder for version 1 of the `pgoutput` logical replication protocol.
+///
+/// Messages must be fed in WAL order. Relation messages describing a table's columns
+/// precede its first change in every decoding session and are cached; a transaction is
+/// returned once its commit message arrives.
+#[derive(Debug, Default)]
+pub struct PgOutputDecoder {
+    relations: HashMap<u32, Relation>,
+    current: Option<CdcTransaction>,
+}
+
+/// Microseconds between the Unix epoch and the Postgres epoch, 2000-01-01.
+const POSTGRES_EPOCH_MICROS: i64 = 946_684_800_000_000;
+
+impl PgOutputDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes one message, returning the transaction it completes, if any.
+    pub fn decode(&mut self, message: &[u8]) -> Result<Option<CdcTransaction>> {
+        let mut reader = Reader::new(message);
+        match reader.u8()? {
+            b'B' => {
+                let commit_lsn = Lsn(reader.u64()?);
+                let committed_at = reader.timestamp()?;
+                let xid = reader.u32()?;
+                self.current = Some(CdcTransaction {
+                    xid,
+                    commit_lsn,
+                    end_lsn: commit_lsn,
+                    committed_at,
+                    changes: Vec::new(),
+                });
+                Ok(None)
+            }
+            b'C' => {
+                let _flags = reader.u8()?;
+                let _commit_lsn = reader.u64()?;
+                let end_lsn = Lsn(reader.u64()?);
+                let mut transaction = self.current.take().ok_or_else(|| malformed("commit without begin"))?;
+                transaction.end_lsn = end_lsn;
+                Ok(Some(transaction))
+            }
+            b'R' => {
+                let oid = reader.u32()?;
+                let schema = reader.cstr()?;
+                let name = reader.cstr()?;
+                let _replica_identity = reader.u8()?;
+                let count = reader.u16()?;
+                let mut columns = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let flags = reader.u8()?;
+                    let column_name = reader.cstr()?;
+                    let type_oid = reader.u32()?;
+                    let _type_modifier = reader.u32()?;
+                    columns.push(RelationColumn {
+                        name: column_name,
+                        type_oid,
+                        key: flags & 1 == 1,
+                    });
+                }
+                let schema = if schema.is_empty() { "pg_catalog".to_string() } else { schema };
+                self.relations.insert(oid, Relation { schema, name, columns });
+                Ok(None)
+            }
+            b'I' => {
+                let relation = self.relation(reader.u32()?)?;
+                reader.expect(b'N')?;
+                let new = reader.tuple()?;
+                let change = ChangeDraft {
+                    operation: ChangeOperation::Insert,
+                    key: key_of(&relation, &new),
+                    before: None,
+                    after: Some(row_of(&relation, &new, false)),
+                };
+                self.push(&relation, change)
+            }
+            b'U' => {
+                let relation = self.relation(reader.u32()?)?;
+                let mut before = None;
+                let mut kind = reader.u8()?;
+                if kind == b'K' || kind == b'O' {
+                    before = Some(row_of(&relation, &reader.tuple()?, kind == b'K'));
+                    kind = reader.u8()?;
+                }
+                if kind != b'N' {
+                    return Err(malformed("update without new tuple"));
+                }
+                let new = reader.tuple()?;
+                let change = ChangeDraft {
+                    operation: ChangeOperation::Update,
+                    key: key_of(&relation, &new),
+                    before,
+                    after: Some(row_of(&relation, &new, false)),
+                };
+                self.push(&relation, change)
+            }
+            b'D' => {
+                let relation = self.relation(reader.u32()?)?;
+                let kind = reader.u8()?;
+                if kind != b'K' && kind != b'O' {
+                    return Err(malformed("delete without old tuple"));
+                }
+                let old = reader.tuple()?;
+                let change = ChangeDraft {
+                    operation: ChangeOperation::Delete,
+                    key: key_of(&relation, &old),
+                    before: Some(row_of(&relation, &old, kind == b'K')),
+                    after: None,
+                };
+                self.push(&relation, change)
+            }
+            b'T' => {
+                let count = reader.u32()?;
+                let _options = reader.u8()?;
+                for _ in 0..count {
+                    let relation = self.relation(reader.u32()?)?;
+                    let change = ChangeDraft {
+                        operation: ChangeOperation::Truncate,
+                        key: Map::new(),
+                        before: None,
+                        after: None,
+                    };
+                    self.push(&relation, change)?;
+                }
+                Ok(None)
+            }
+            // Type, origin and logical message announcements carry no row changes
+            b'Y' | b'O' | b'M' => Ok(None),
+            other => Err(malformed(&format!("unknown message type '{}'", other as char))),
+        }
+    }
+
+    fn relation(&self, oid: u32) -> Result<Relation> {
+        self.relations
+            .get(&oid)
+            .cloned()
+            .ok_or_else(|| malformed(&format!("change to unknown relation {}", oid)))
+    }
+
+    fn push(&mut self, relation: &Relation, change: ChangeDraft) -> Result<Option<CdcTransaction>> {
+        let transaction = self.current.as_mut().ok_or_else(|| malformed("change outside a transaction"))?;
+        transaction.changes.push(ChangeEvent {
+            schema: relation.schema.clone(),
+            table: relation.name.clone(),
+            operation: change.operation,
+            key: change.key,
+            before: change.before,
+            after: change.after,
+            xid: transaction.xid,
+            commit_lsn: transaction.commit_lsn.to_string(),
+            committed_at: transaction.committed_at,
+        });
+        Ok(None)
+    }
+}
+
+struct ChangeDraft {
+    operation: ChangeOperation,
+    key: Map<String, Value>,
+    before: Option<Map<String, Value>>,
+    after: Option<Map<String, Value>>,
+}
+
+fn malformed(reason: &str) -> AppError {
+    AppError::Internal(anyhow!("Malformed pgoutput message: {}", reason))
+}
+
+fn key_of(relation: &Relation, tuple: &[TupleValue]) -> Map<String, Value> {
+    relation
+        .columns
+        .iter()
+        .zip(tuple)
+        .filter(|(column, _)| column.key)
+        .filter_map(|(column, value)| normalize(column, value).map(|v| (column.name.clone(), v)))
+        .collect()
+}
+
+fn row_of(relation: &Relation, tuple: &[TupleValue], key_only: bool) -> Map<String, Value> {
+    relation
+        .columns
+        .iter()
+        .zip(tuple)
+        .filter(|(column, _)| !key_only || column.key)
+        .filter_map(|(column, value)| normalize(column, value).map(|v| (column.name.clone(), v)))
+        .collect()
+}
+
+/// Converts a column's text form to JSON by its type OID; `None` for unchanged values.
+fn normalize(column: &RelationColumn, value: &TupleValue) -> Option<Value> {
+    const BOOL: u32 = 16;
+    const INT8: u32 = 20;
+    const INT2: u32 = 21;
+    const INT4: u32 = 23;
+    const JSON: u32 = 114;
+    const TIMESTAMP: u32 = 1114;
+    const TIMESTAMPTZ: u32 = 1184;
+    const JSONB: u32 = 3802;
+
+    let text = match value {
+        TupleValue::Null => return Some(Value::Null),
+        TupleValue::Unchanged => return None,
+        TupleValue::Text(text) => text,
+    };
+    let normalized = match column.type_oid {
+        BOOL => Some(Value::Bool(text == "t")),
+        INT2 | INT4 | INT8 => text.parse::<i64>().ok().map(Value::from),
+        JSON | JSONB => serde_json::from_str(text).ok(),
+        TIMESTAMPTZ => DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%#z")
+            .ok()
+            .map(|at| Value::String(at.with_timezone(&Utc).to_rfc3339())),
+        TIMESTAMP => NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .map(|at| Value::String(at.and_utc().to_rfc3339())),
+        _ => None,
+    };
+    Some(normalized.unwrap_or_else(|| Value::String(text.clone())))
+}
+
+/// Big-endian cursor over a protocol message.
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.buf.len());
+        let end = end.ok_or_else(|| malformed("truncated message"))?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        if self.u8()? != byte {
+            return Err(malformed(&format!("expected '{}'", byte as char)));
+        }
+        Ok(())
+    }
+
+    fn cstr(&mut self) -> Result<String> {
+        let rest = &self.buf[self.pos..];
+        let len = rest.iter().position(|b| *b == 0).ok_or_else(|| malformed("unterminated string"))?;
+        let text = String::from_utf8(rest[..len].to_vec()).map_err(|_| malformed("invalid UTF-8"))?;
+        self.pos += len + 1;
+        Ok(text)
+    }
+
+    fn timestamp(&mut self) -> Result<DateTime<Utc>> {
+        let micros = self.u64()? as i64;
+        DateTime::from_timestamp_micros(POSTGRES_EPOCH_MICROS + micros).ok_or_else(|| malformed("invalid timestamp"))
+    }
+
+    fn tuple(&mut self) -> Result<Vec<TupleValue>> {
+        let count = self.u16()?;
+        let mut values = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            values.push(match self.u8()? {
+                b'n' => TupleValue::Null,
+                b'u' => TupleValue::Unchanged,
+                b't' => {
+                    let len = self.u32()? as usize;
+                    let bytes = self.take(len)?;
+                    TupleValue::Text(String::from_utf8(bytes.to_vec()).map_err(|_| malformed("invalid UTF-8"))?)
+                }
+                other => return Err(malformed(&format!("unknown tuple value kind '{}'", other as char))),
+            });
+        }
+        Ok(values)
+    }
+}
+
+/// Settings of the change data capture publisher.
+#[derive(Debug, Clone)]
+pub struct CdcConfig {
+    pub slot_name: String,
+    pub publication: String,
+    pub tables: Vec<String>,
+    pub topic_prefix: String,
+    /// Changes decoded per pass; whole transactions are always read.
+    pub batch_size: i64,
+}
+
+impl Default for CdcConfig {
+    fn default() -> Self {
+        Self {
+            slot_name: "settlement_cdc".to_string(),
+            publication: "settlement_cdc".to_string(),
+            tables: ["accounts", "account_balances", "transactions", "ledger_entries", "settlement_batches"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            topic_prefix: "settlement.cdc".to_string(),
+            batch_size: 1000,
+        }
+    }
+}
+
+impl CdcConfig {
+    fn validate(&self) -> Result<()> {
+        let identifier = |name: &str| {
+            let mut chars = name.chars();
+            chars.next().is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+                && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        };
+        for name in [&self.slot_name, &self.publication].into_iter().chain(&self.tables) {
+            if !identifier(name) {
+                return Err(AppError::Validation(format!(
+                    "'{}' is not a valid CDC identifier: use lowercase letters, digits and underscores",
+                    name
+                )));
+            }
+        }
+        if self.tables.is_empty() {
+            return Err(AppError::Validation("CDC needs at least one table".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Republishes row changes of the core tables to Kafka by tailing Postgres logical
+/// replication, for consumers that want every change without polling the API.
+///
+/// Changes are decoded from a `pgoutput` slot and published per database transaction,
+/// in commit order, to `{topic_prefix}.{table}`. The slot only moves past a transaction
+/// once all its changes are published, so delivery is at least once: a transaction
+/// interrupted part way is published again in full. The slot keeps WAL until then,
+/// so a publisher that stays down holds WAL back on the server; watch its lag.
+pub struct CdcPublisher {
+    repo: ReplicationRepository,
+    producer: Arc<EventProducer>,
+    config: CdcConfig,
+}
+
+impl CdcPublisher {
+    pub fn new(pool: PgPool, producer: Arc<EventProducer>) -> Self {
+        Self {
+            repo: ReplicationRepository::new(pool),
+            producer,
+            config: CdcConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: CdcConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Creates the publication and replication slot if they are missing. Returns true
+    /// if the slot was created, in which case capture starts from now.
+    pub async fn ensure_replication(&self) -> Result<bool> {
+        self.config.validate()?;
+        self.repo.ensure_publication(&self.config.publication, &self.config.tables).await?;
+        let created = self.repo.ensure_slot(&self.config.slot_name).await?;
+        if created {
+            tracing::info!(slot = %self.config.slot_name, "Created CDC replication slot");
+        }
+        Ok(created)
+    }
+
+    /// Decodes committed transactions not yet acknowledged, without consuming them.
+    pub async fn read_pending(&self) -> Result<Vec<CdcTransaction>> {
+        let messages = self
+            .repo
+            .peek_changes(&self.config.slot_name, &self.config.publication, self.config.batch_size)
+            .await?;
+
+        let mut decoder = PgOutputDecoder::new();
+        let mut transactions = Vec::new();
+        for (_, data) in messages {
+            if let Some(transaction) = decoder.decode(&data)? {
+                transactions.push(transaction);
+            }
+        }
+        Ok(transactions)
+    }
+
+    /// Marks everything up to `lsn` as published, so it is not read again.
+    pub async fn acknowledge(&self, lsn: Lsn) -> Result<()> {
+        self.repo.advance_slot(&self.config.slot_name, &lsn.to_string()).await
+    }
+
+    /// Publishes pending transactions, acknowledges those fully published, records
+    /// lag and returns how many changes were published.
+    ///
+    /// Stops at the first failed send so later changes are not published ahead of it.
+    /// Does nothing while another instance is reading the slot.
+    pub async fn publish_pending(&self) -> Result<usize> {
+        let transactions = match self.read_pending().await {
+            Ok(transactions) => transactions,
+            Err(AppError::Unavailable(reason)) => {
+                tracing::debug!("Skipping CDC pass: {}", reason);
+                return Ok(0);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut published = 0;
+        let mut acknowledged = None;
+        let mut oldest_pending = None;
+        'transactions: for transaction in &transactions {
+            for change in &transaction.changes {
+                let topic = change.topic(&self.config.topic_prefix);
+                let envelope = EventEnvelope::new(EventType::DataChanged, change);
+                let sent = self.producer.send(&topic, change.partition_key().as_deref(), &envelope).await;
+                get_metrics().record_kafka_message(&topic, sent.is_ok());
+                if let Err(e) = sent {
+                    tracing::warn!("Failed to publish CDC change of transaction {} to {}: {}", transaction.xid, topic, e);
+                    oldest_pending = Some(transaction.committed_at);
+                    break 'transactions;
+                }
+                published += 1;
+            }
+            acknowledged = Some(transaction.end_lsn);
+        }
+
+        if let Some(lsn) = acknowledged {
+            self.acknowledge(lsn).await?;
+        }
+        if let Some(status) = self.repo.slot_status(&self.config.slot_name).await? {
+            let lag_secs = oldest_pending
+                .map(|at| (Utc::now() - at).num_milliseconds().max(0) as f64 / 1000.0)
+                .unwrap_or(0.0);
+            get_metrics().record_cdc(published as u64, status.lag_bytes, status.retained_bytes, lag_secs);
+        }
+        Ok(published)
+    }
+
+    /// Gets the replication slot's state and lag.
+    pub async fn status(&self) -> Result<ReplicationSlotStatus> {
+        self.repo.slot_status(&self.config.slot_name).await?.ok_or_else(|| {
+            AppError::NotFound(format!("Replication slot '{}' does not exist", self.config.slot_name))
+        })
+    }
+
+    /// Drops the replication slot so the server stops retaining WAL for it. Capture
+    /// restarts from scratch, missing changes made meanwhile, the next time the
+    /// publisher starts.
+    pub async fn drop_slot(&self) -> Result<bool> {
+        self.repo.drop_slot(&self.config.slot_name).await
+    }
+}
+
+/// Background job publishing captured changes.
+pub struct CdcPublisherJob {
+    publisher: Arc<CdcPublisher>,
+    running: Arc<AtomicBool>,
+    interval_millis: u64,
+}
+
+impl CdcPublisherJob {
+    pub fn new(publisher: Arc<CdcPublisher>, interval_millis: u64) -> Self {
+        Self {
+            publisher,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_millis,
+        }
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let publisher = self.publisher.clone();
+        let running = self.running.clone();
+        let interval = self.interval_millis;
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if let Err(e) = publisher.publish_pending().await {
+                    tracing::error!("CDC publisher error: {}", e);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_millis(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cstr(buf: &mut Vec<u8>, s: &str) {
+        buf.extend_from_slice(s.as_bytes());
+        buf.push(0);
+    }
+
+    fn relation(oid: u32) -> Vec<u8> {
+        let mut buf = vec![b'R'];
+        buf.extend_from_slice(&oid.to_be_bytes());
+        cstr(&mut buf, "public");
+        cstr(&mut buf, "accounts");
+        buf.push(b'd');
+        buf.extend_from_slice(&4u16.to_be_bytes());
+        for (flags, name, type_oid) in [(1u8, "id", 2950u32), (0, "balance", 1700), (0, "active", 16), (0, "metadata", 3802)] {
+            buf.push(flags);
+            cstr(&mut buf, name);
+            buf.extend_from_slice(&type_oid.to_be_bytes());
+            buf.extend_from_slice(&(-1i32).to_be_bytes());
+        }
+        buf
+    }
+
+    fn tuple(buf: &mut Vec<u8>, values: &[Option<&str>]) {
+        buf.extend_from_slice(&(values.len() as u16).to_be_bytes());
+        for value in values {
+            match value {
+                None => buf.push(b'n'),
+                Some("<unchanged>") => buf.push(b'u'),
+                Some(text) => {
+                    buf.push(b't');
+                    buf.extend_from_slice(&(text.len() as u32).to_be_bytes());
+                    buf.extend_from_slice(text.as_bytes());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lsn_round_trip() {
+        let lsn: Lsn = "16/B374D848".parse().unwrap();
+        assert_eq!(lsn, Lsn(0x16_B374_D848));
+        assert_eq!(lsn.to_string(), "16/B374D848");
+        assert!("16B374D848".parse::<Lsn>().is_err());
+        assert!("0/G".parse::<Lsn>().is_err());
+    }
+
+    #[test]
+    fn test_decode_transaction() {
+        let mut decoder = PgOutputDecoder::new();
+
+        let mut begin = vec![b'B'];
+        begin.extend_from_slice(&0x0500_0000u64.to_be_bytes());
+        begin.extend_from_slice(&1_000_000i64.to_be_bytes());
+        begin.extend_from_slice(&77u32.to_be_bytes());
+        assert_eq!(decoder.decode(&begin).unwrap(), None);
+        assert_eq!(decoder.decode(&relation(9)).unwrap(), None);
+
+        let mut insert = vec![b'I'];
+        insert.extend_from_slice(&9u32.to_be_bytes());
+        insert.push(b'N');
+        tuple(&mut insert, &[Some("a1"), Some("10.50"), Some("t"), Some(r#"{"tier":"gold"}"#)]);
+        decoder.decode(&insert).unwrap();
+
+        let mut update = vec![b'U'];
+        update.extend_from_slice(&9u32.to_be_bytes());
+        update.push(b'N');
+        tuple(&mut update, &[Some("a1"), Some("7.00"), Some("f"), Some("<unchanged>")]);
+        decoder.decode(&update).unwrap();
+
+        let mut delete = vec![b'D'];
+        delete.extend_from_slice(&9u32.to_be_bytes());
+        delete.push(b'K');
+        tuple(&mut delete, &[Some("a1"), None, None, None]);
+        decoder.decode(&delete).unwrap();
+
+        let mut commit = vec![b'C', 0];
+        commit.extend_from_slice(&0x0500_0000u64.to_be_bytes());
+        commit.extend_from_slice(&0x0500_0030u64.to_be_bytes());
+        commit.extend_from_slice(&1_000_000i64.to_be_bytes());
+        let transaction = decoder.decode(&commit).unwrap().expect("Commit should complete the transaction");
+
+        assert_eq!(transaction.xid, 77);
+        assert_eq!(transaction.end_lsn, Lsn(0x0500_0030));
+        assert_eq!(transaction.committed_at.to_rfc3339(), "2000-01-01T00:00:01+00:00");
+        let [insert, update, delete] = transaction.changes.as_slice() else {
+            panic!("Expected three changes");
+        };
+
+        assert_eq!(insert.operation, ChangeOperation::Insert);
+        assert_eq!(insert.table, "accounts");
+        assert_eq!(insert.partition_key().as_deref(), Some("a1"));
+        assert_eq!(insert.commit_lsn, "0/5000000");
+        let after = insert.after.as_ref().unwrap();
+        assert_eq!(after["balance"], Value::String("10.50".to_string()));
+        assert_eq!(after["active"], Value::Bool(true));
+        assert_eq!(after["metadata"]["tier"], "gold");
+
+        // Unchanged TOAST values are left out rather than reported as null
+        let after = update.after.as_ref().unwrap();
+        assert_eq!(after["active"], Value::Bool(false));
+        assert!(!after.contains_key("metadata"));
+        assert_eq!(update.before, None);
+
+        assert_eq!(delete.operation, ChangeOperation::Delete);
+        assert_eq!(delete.after, None);
+        let before = delete.before.as_ref().unwrap();
+        assert_eq!(before.len(), 1);
+        assert_eq!(before["id"], "a1");
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_messages() {
+        let mut decoder = PgOutputDecoder::new();
+
+        let mut insert = vec![b'I'];
+        insert.extend_from_slice(&9u32.to_be_bytes());
+        assert!(decoder.decode(&insert).is_err(), "change to an unknown relation");
+        assert!(decoder.decode(&relation(9)[..12]).is_err(), "truncated relation");
+        assert!(decoder.decode(b"Z").is_err(), "unknown message type");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(CdcConfig::default().validate().is_ok());
+        let config = CdcConfig {
+            tables: vec!["accounts; DROP TABLE accounts".to_string()],
+            ..CdcConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(AppError::Validation(_))));
+    }
+}
@@ -1,10 +1,16 @@
+pub mod cdc;
 pub mod consumer;
+pub mod outbox;
 pub mod producer;
 pub mod types;
 
+pub use cdc::{
+    CdcConfig, CdcPublisher, CdcPublisherJob, CdcTransaction, ChangeEvent, ChangeOperation, Lsn, PgOutputDecoder,
+};
 pub use consumer::{EventConsumer, ConsumerConfig, MessageHandler};
+pub use outbox::{enqueue_event, enqueue_settled_event, OutboxRelay, OutboxRelayJob};
 pub use producer::{EventProducer, ProducerConfig};
 pub use types::{
-    BatchEvent, EventEnvelope, EventType, NettingEvent, PositionEvent,
-    SettlementEvent, TransactionEvent,
+    AlertEvent, AmountLimitBreachedEvent, BatchEvent, EventEnvelope, EventType, FinalityEvent, ControlAccountBreakEvent, FundingDeadlineMissedEvent, NetDebitCapWarningEvent, NettingEvent, PositionEvent, PossibleDuplicateEvent,
+    PvpEvent, RtgsSettlementEvent, SettlementEvent, TransactionEvent,
 };
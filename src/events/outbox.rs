@@ -0,0 +1,133 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::error::{AppError, Result};
+use crate::events::{EventEnvelope, EventProducer, EventType, TransactionEvent};
+use crate::models::{OutboxMessage, TransactionRecord};
+use crate::observability::get_metrics;
+use crate::repositories::OutboxRepository;
+
+/// Queues an event envelope in the outbox within an open database transaction.
+pub async fn enqueue_event<T: Serialize>(
+    tx: &mut Transaction<'_, Postgres>,
+    topic: &str,
+    key: Option<String>,
+    envelope: &EventEnvelope<T>,
+) -> Result<()> {
+    let payload = serde_json::to_value(envelope)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize event: {}", e)))?;
+    OutboxRepository::enqueue_in(tx, &OutboxMessage::new(topic, key, payload)).await
+}
+
+/// Queues a transaction-settled event within the transaction that settled it. Besides
+/// being published, these events feed the account activity projection.
+pub async fn enqueue_settled_event(tx: &mut Transaction<'_, Postgres>, transaction: &TransactionRecord) -> Result<()> {
+    let envelope = EventEnvelope::new(EventType::TransactionSettled, TransactionEvent::from_record(transaction));
+    enqueue_event(tx, TransactionEvent::topic(), Some(transaction.id.to_string()), &envelope).await
+}
+
+/// Publishes queued outbox events to Kafka in the order they were written.
+///
+/// Delivery is at least once: an event whose publish succeeded but whose
+/// `published_at` update failed is sent again on the next pass.
+pub struct OutboxRelay {
+    repo: OutboxRepository,
+    producer: Arc<EventProducer>,
+    batch_size: i64,
+}
+
+impl OutboxRelay {
+    pub fn new(pool: PgPool, producer: Arc<EventProducer>) -> Self {
+        Self {
+            repo: OutboxRepository::new(pool),
+            producer,
+            batch_size: 100,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Publishes up to one batch of queued events and returns how many were sent.
+    ///
+    /// Stops at the first failure so later events are not published ahead of it.
+    pub async fn relay_pending(&self) -> Result<usize> {
+        let messages = self.repo.find_unpublished(self.batch_size).await?;
+        let mut published = 0;
+
+        for message in messages {
+            let payload = serde_json::to_vec(&message.payload)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize event: {}", e)))?;
+            let sent = self
+                .producer
+                .send_raw(&message.topic, message.partition_key.as_deref(), payload)
+                .await;
+            get_metrics().record_kafka_message(&message.topic, sent.is_ok());
+
+            match sent {
+                Ok(_) => {
+                    self.repo.mark_published(message.id).await?;
+                    published += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to publish outbox event {} to {}: {}", message.id, message.topic, e);
+                    self.repo.record_failure(message.id, &e.to_string()).await?;
+                    break;
+                }
+            }
+        }
+
+        Ok(published)
+    }
+}
+
+/// Background job that drains the outbox.
+pub struct OutboxRelayJob {
+    relay: Arc<OutboxRelay>,
+    running: Arc<AtomicBool>,
+    interval_millis: u64,
+}
+
+impl OutboxRelayJob {
+    pub fn new(relay: Arc<OutboxRelay>, interval_millis: u64) -> Self {
+        Self {
+            relay,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_millis,
+        }
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let relay = self.relay.clone();
+        let running = self.running.clone();
+        let interval = self.interval_millis;
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if let Err(e) = relay.relay_pending().await {
+                    tracing::error!("Outbox relay error: {}", e);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_millis(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
 use crate::error::{AppError, Result};
 use anyhow::anyhow;
 use chrono::Utc;
@@ -58,10 +59,14 @@ impl Default for ProducerConfig {
 }
 
 /// Kafka event producer for settlement events.
+///
+/// Sends go through a circuit breaker, so while Kafka is failing callers get an error
+/// immediately instead of waiting out every retry.
 pub struct EventProducer {
     config: ProducerConfig,
     partition_clients: Arc<RwLock<BTreeMap<String, Arc<PartitionClient>>>>,
     client: Option<Arc<rskafka::client::Client>>,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl EventProducer {
@@ -71,9 +76,26 @@ impl EventProducer {
             config,
             partition_clients: Arc::new(RwLock::new(BTreeMap::new())),
             client: None,
+            breaker: Arc::new(CircuitBreaker::new("kafka", CircuitBreakerConfig::default())),
         }
     }
 
+    /// Creates a producer that reuses an already connected Kafka client.
+    pub fn from_client(config: ProducerConfig, client: Arc<rskafka::client::Client>) -> Self {
+        Self {
+            config,
+            partition_clients: Arc::new(RwLock::new(BTreeMap::new())),
+            client: Some(client),
+            breaker: Arc::new(CircuitBreaker::new("kafka", CircuitBreakerConfig::default())),
+        }
+    }
+
+    /// Replaces the default circuit breaker.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
     /// Connects to the Kafka cluster.
     pub async fn connect(&mut self) -> Result<()> {
         info!("Connecting to Kafka brokers: {:?}", self.config.brokers);
@@ -129,6 +151,12 @@ impl EventProducer {
 
     /// Sends a raw message to the specified topic.
     pub async fn send_raw(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> Result<i64> {
+        self.breaker
+            .call(|| self.produce_with_retries(topic, key, payload))
+            .await
+    }
+
+    async fn produce_with_retries(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> Result<i64> {
         let partition_client = self.get_partition_client(topic).await?;
 
         let record = Record {
@@ -173,6 +201,16 @@ impl EventProducer {
         &self,
         topic: &str,
         messages: &[(Option<String>, T)],
+    ) -> Result<Vec<i64>> {
+        self.breaker
+            .call(|| self.produce_batch(topic, messages))
+            .await
+    }
+
+    async fn produce_batch<T: Serialize>(
+        &self,
+        topic: &str,
+        messages: &[(Option<String>, T)],
     ) -> Result<Vec<i64>> {
         let partition_client = self.get_partition_client(topic).await?;
 
@@ -3,7 +3,10 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::models::{BatchStatus, TransactionStatus, TransactionType};
+use crate::models::{
+    AlertRuleType, BatchStatus, DuplicatePaymentAction, NettingSummary, PvpLeg, PvpSettlement, PvpStatus, SettlementBatch,
+    TransactionRecord, TransactionStatus, TransactionType,
+};
 
 /// Topics for settlement events.
 pub mod topics {
@@ -11,6 +14,10 @@ pub mod topics {
     pub const BATCHES: &str = "settlement.batches";
     pub const POSITIONS: &str = "settlement.positions";
     pub const COMPLETED: &str = "settlement.completed";
+    pub const ALERTS: &str = "settlement.alerts";
+    pub const RTGS: &str = "settlement.rtgs";
+    pub const FINALITY: &str = "settlement.finality";
+    pub const PVP: &str = "settlement.pvp";
 }
 
 /// Type of settlement event.
@@ -21,13 +28,44 @@ pub enum EventType {
     TransactionSettled,
     TransactionFailed,
     TransactionReversed,
+    /// A pending transaction outlived its time-to-live.
+    TransactionExpired,
     BatchCreated,
     BatchProcessing,
     BatchCompleted,
     BatchFailed,
+    /// A failed batch was reset to pending for another attempt.
+    BatchRetried,
+    /// An operator moved a pending batch's cut-off or closed it early.
+    BatchCutOffChanged,
     PositionCalculated,
     NettingCompleted,
     SettlementCompleted,
+    AlertTriggered,
+    /// A participant's net debit in an open batch reached a warning share of its cap.
+    NetDebitCapWarning,
+    /// A transaction was rejected for an amount above its currency's maximum or with
+    /// too many decimal places.
+    AmountLimitBreached,
+    /// A payment matched a recent one with another idempotency key.
+    PossibleDuplicate,
+    RtgsSettled,
+    SettlementFinal,
+    /// A net payer's funding obligation was still unfunded at its deadline.
+    FundingDeadlineMissed,
+    /// A settlement control account did not return to zero after a batch's funding and
+    /// payouts.
+    ControlAccountBreak,
+    /// A payment-versus-payment settlement was accepted and is awaiting funding.
+    PvpCreated,
+    /// One payer of a PvP settlement has the funds for its payment.
+    PvpLegFunded,
+    /// Both payments of a PvP settlement settled together.
+    PvpSettled,
+    /// A PvP settlement was cancelled before both payers were funded.
+    PvpCancelled,
+    /// A row of a table captured from the WAL was inserted, updated, deleted or truncated.
+    DataChanged,
 }
 
 /// Envelope wrapping all events with common metadata.
@@ -82,6 +120,26 @@ impl TransactionEvent {
     pub fn topic() -> &'static str {
         topics::TRANSACTIONS
     }
+
+    /// Snapshot of a transaction's state and amounts.
+    pub fn from_record(transaction: &TransactionRecord) -> Self {
+        Self {
+            transaction_id: transaction.id,
+            external_id: transaction.external_id.clone(),
+            transaction_type: transaction.transaction_type,
+            status: transaction.status,
+            source_account_id: transaction.source_account_id,
+            destination_account_id: transaction.destination_account_id,
+            amount: transaction.amount,
+            currency: transaction.currency.clone(),
+            fee_amount: transaction.fee_amount,
+            net_amount: transaction.net_amount,
+            batch_id: transaction.settlement_batch_id,
+            idempotency_key: transaction.idempotency_key.clone(),
+            created_at: transaction.created_at,
+            settled_at: transaction.settled_at,
+        }
+    }
 }
 
 /// Event payload for batch-related events.
@@ -97,12 +155,39 @@ pub struct BatchEvent {
     pub fee_amount: Decimal,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Position of the batch within its settlement window.
+    #[serde(default)]
+    pub sequence_number: i32,
+    #[serde(default)]
+    pub cut_off_time: Option<DateTime<Utc>>,
+    /// Netting results, once positions have been calculated for the batch.
+    #[serde(default)]
+    pub netting: Option<NettingSummary>,
 }
 
 impl BatchEvent {
     pub fn topic() -> &'static str {
         topics::BATCHES
     }
+
+    /// Snapshot of a batch's state and totals.
+    pub fn from_batch(batch: &SettlementBatch, netting: Option<NettingSummary>) -> Self {
+        Self {
+            batch_id: batch.id,
+            status: batch.status,
+            settlement_date: batch.settlement_date,
+            currency: batch.currency.clone(),
+            total_transactions: batch.total_transactions,
+            gross_amount: batch.gross_amount,
+            net_amount: batch.net_amount,
+            fee_amount: batch.fee_amount,
+            created_at: batch.created_at,
+            completed_at: batch.completed_at,
+            sequence_number: batch.sequence_number,
+            cut_off_time: Some(batch.cut_off_time),
+            netting,
+        }
+    }
 }
 
 /// Event payload for netting position events.
@@ -166,6 +251,221 @@ impl SettlementEvent {
     }
 }
 
+/// Event payload for triggered alert rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub alert_id: Uuid,
+    pub rule_id: Uuid,
+    pub rule_type: AlertRuleType,
+    pub account_id: Uuid,
+    pub transaction_id: Option<Uuid>,
+    pub currency: String,
+    pub threshold: Option<Decimal>,
+    pub observed_value: Option<Decimal>,
+    pub message: String,
+    pub triggered_at: DateTime<Utc>,
+}
+
+impl AlertEvent {
+    pub fn topic() -> &'static str {
+        topics::ALERTS
+    }
+}
+
+/// Event payload for a participant's net debit nearing its cap before the batch's cut-off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetDebitCapWarningEvent {
+    pub batch_id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub cap: Decimal,
+    pub net_debit: Decimal,
+    /// Warning threshold crossed, as a percentage of the cap.
+    pub threshold_percent: u32,
+    pub utilization_percent: Decimal,
+    pub cut_off_time: DateTime<Utc>,
+    pub transaction_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl NetDebitCapWarningEvent {
+    pub fn topic() -> &'static str {
+        topics::ALERTS
+    }
+}
+
+/// Event payload for a transaction rejected by the amount guard rails. Nothing was
+/// posted; the event flags a likely fat-finger entry for follow-up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmountLimitBreachedEvent {
+    pub external_id: String,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    /// Maximum the amount exceeded; absent when only the decimal scale was breached.
+    pub limit: Option<Decimal>,
+    pub max_scale: u32,
+    pub reason: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl AmountLimitBreachedEvent {
+    pub fn topic() -> &'static str {
+        topics::ALERTS
+    }
+}
+
+/// Event payload for a payment that looks like a repeat of a recent transaction
+/// submitted under another idempotency key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PossibleDuplicateEvent {
+    pub external_id: String,
+    pub idempotency_key: String,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    /// The earlier transaction it matched.
+    pub duplicate_of: Uuid,
+    pub window_secs: u64,
+    pub action: DuplicatePaymentAction,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl PossibleDuplicateEvent {
+    pub fn topic() -> &'static str {
+        topics::ALERTS
+    }
+}
+
+/// Event payload for a net payer that missed the funding deadline of its obligation in a
+/// completed batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingDeadlineMissedEvent {
+    pub obligation_id: Uuid,
+    pub batch_id: Uuid,
+    pub participant_id: Uuid,
+    pub currency: String,
+    pub amount: Decimal,
+    pub funded_amount: Decimal,
+    pub outstanding: Decimal,
+    pub deadline: DateTime<Utc>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl FundingDeadlineMissedEvent {
+    pub fn topic() -> &'static str {
+        topics::ALERTS
+    }
+}
+
+/// Event payload for a settlement control account left with a residual once a batch's
+/// funding and payout instructions were executed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlAccountBreakEvent {
+    pub batch_id: Uuid,
+    pub control_account_id: Uuid,
+    pub currency: String,
+    /// Executed funding from net payers.
+    pub funded: Decimal,
+    /// Executed payouts to net receivers.
+    pub paid_out: Decimal,
+    /// Funded less paid out, left on the control account.
+    pub residual: Decimal,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl ControlAccountBreakEvent {
+    pub fn topic() -> &'static str {
+        topics::ALERTS
+    }
+}
+
+/// Event payload for the lifecycle of a payment-versus-payment settlement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PvpEvent {
+    pub pvp_id: Uuid,
+    pub external_id: String,
+    pub status: PvpStatus,
+    /// Payment whose payer was funded, for `PVP_LEG_FUNDED`.
+    pub leg: Option<PvpLeg>,
+    pub first_amount: Decimal,
+    pub first_currency: String,
+    pub second_amount: Decimal,
+    pub second_currency: String,
+    pub expires_at: DateTime<Utc>,
+    /// Why the settlement was cancelled, for `PVP_CANCELLED`.
+    pub reason: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl PvpEvent {
+    pub fn from_settlement(pvp: &PvpSettlement, leg: Option<PvpLeg>) -> Self {
+        Self {
+            pvp_id: pvp.id,
+            external_id: pvp.external_id.clone(),
+            status: pvp.status,
+            leg,
+            first_amount: pvp.first_amount,
+            first_currency: pvp.first_currency.clone(),
+            second_amount: pvp.second_amount,
+            second_currency: pvp.second_currency.clone(),
+            expires_at: pvp.expires_at,
+            reason: pvp.cancel_reason.clone(),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn topic() -> &'static str {
+        topics::PVP
+    }
+}
+
+/// Event payload for a transaction settled gross through the RTGS lane.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RtgsSettlementEvent {
+    pub transaction_id: Uuid,
+    pub external_id: String,
+    pub transaction_type: TransactionType,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub fee_amount: Decimal,
+    pub net_amount: Decimal,
+    /// Threshold the amount exceeded when the transaction was routed.
+    pub threshold: Decimal,
+    pub settled_at: DateTime<Utc>,
+    pub finality_sequence: i64,
+    pub final_at: DateTime<Utc>,
+}
+
+impl RtgsSettlementEvent {
+    pub fn topic() -> &'static str {
+        topics::RTGS
+    }
+}
+
+/// Event payload for a batch whose transactions have reached settlement finality.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalityEvent {
+    pub batch_id: Uuid,
+    pub currency: String,
+    pub settlement_date: chrono::NaiveDate,
+    pub record_count: usize,
+    /// Finality sequence range covered by the batch, inclusive.
+    pub first_sequence: Option<i64>,
+    pub last_sequence: Option<i64>,
+    pub final_at: DateTime<Utc>,
+}
+
+impl FinalityEvent {
+    pub fn topic() -> &'static str {
+        topics::FINALITY
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,6 +510,9 @@ mod tests {
             fee_amount: dec!(10),
             created_at: Utc::now(),
             completed_at: Some(Utc::now()),
+            sequence_number: 1,
+            cut_off_time: None,
+            netting: None,
         };
 
         let envelope = EventEnvelope::new(EventType::BatchCompleted, payload)
@@ -247,5 +550,7 @@ mod tests {
         assert_eq!(topics::BATCHES, "settlement.batches");
         assert_eq!(topics::POSITIONS, "settlement.positions");
         assert_eq!(topics::COMPLETED, "settlement.completed");
+        assert_eq!(topics::ALERTS, "settlement.alerts");
+        assert_eq!(topics::RTGS, "settlement.rtgs");
     }
 }
@@ -1,3 +1,5 @@
+use crate::cache::RedisPool;
+use crate::core::job_control::JobControl;
 use crate::error::{AppError, Result};
 use crate::idempotency::key_generator::{IdempotencyAttributes, IdempotencyKeyGenerator, KeyGeneratorConfig};
 use crate::idempotency::storage::{
@@ -7,6 +9,8 @@ use crate::idempotency::storage::{
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use crate::observability::get_metrics;
+use std::collections::HashMap;
 use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -126,11 +130,11 @@ pub struct IdempotencyHandler {
 impl IdempotencyHandler {
     pub fn new(
         pool: PgPool,
-        redis_client: redis::Client,
+        redis: Arc<RedisPool>,
         config: IdempotencyHandlerConfig,
     ) -> Self {
         let postgres_store = PostgresIdempotencyStore::new(pool);
-        let redis_cache = RedisIdempotencyCache::new(redis_client, &config.key_prefix);
+        let redis_cache = RedisIdempotencyCache::new(redis, &config.key_prefix);
         let store = HybridIdempotencyStore::new(postgres_store, redis_cache, config.ttl_seconds);
 
         let key_config = KeyGeneratorConfig {
@@ -187,7 +191,7 @@ impl IdempotencyHandler {
 
             // Verify request hash matches (same request)
             if existing.request_hash != request_hash {
-                return Err(AppError::Validation(
+                return Err(AppError::IdempotencyConflict(
                     "Idempotency key reused with different request parameters".to_string(),
                 ));
             }
@@ -228,7 +232,7 @@ impl IdempotencyHandler {
                 self.metrics.record_duplicate();
 
                 if existing.request_hash != request_hash {
-                    return Err(AppError::Validation(
+                    return Err(AppError::IdempotencyConflict(
                         "Idempotency key reused with different request parameters".to_string(),
                     ));
                 }
@@ -304,7 +308,7 @@ impl IdempotencyHandler {
                 return Ok(response);
             }
             IdempotencyCheckResult::Processing => {
-                return Err(AppError::Validation(
+                return Err(AppError::SerializationConflict(
                     "Request is currently being processed. Please retry later.".to_string(),
                 ));
             }
@@ -338,48 +342,170 @@ impl IdempotencyHandler {
     }
 }
 
+/// Configuration for the idempotency cleanup job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyCleanupConfig {
+    pub interval_seconds: u64,
+    /// Minimum age, from creation, before an expired record is removed.
+    pub default_retention_seconds: i64,
+    /// Per key class (operation type) overrides of `default_retention_seconds`.
+    pub class_retention_seconds: HashMap<String, i64>,
+    /// Records removed per delete statement.
+    pub batch_size: i64,
+    /// Pause between delete statements, to limit load on the database.
+    pub batch_pause_ms: u64,
+    /// Upper bound on delete statements per run; the rest waits for the next run.
+    pub max_batches_per_run: u32,
+    /// Copy records to `idempotency_keys_archive` before deleting them.
+    pub archive: bool,
+}
+
+impl Default for IdempotencyCleanupConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 300,
+            default_retention_seconds: 0,
+            class_retention_seconds: HashMap::new(),
+            batch_size: 1000,
+            batch_pause_ms: 100,
+            max_batches_per_run: 50,
+            archive: false,
+        }
+    }
+}
+
+impl IdempotencyCleanupConfig {
+    /// Retention applied to records of an operation type.
+    pub fn retention_for(&self, operation_type: &str) -> i64 {
+        self.class_retention_seconds
+            .get(operation_type)
+            .copied()
+            .unwrap_or(self.default_retention_seconds)
+    }
+}
+
+/// Outcome of one cleanup run.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CleanupRunSummary {
+    pub removed: u64,
+    pub batches: u32,
+    pub archived: bool,
+    pub remaining: i64,
+}
+
 /// Background cleanup job for expired idempotency records.
+///
+/// Records are removed once they have expired and are older than their class's
+/// retention, in rate-limited batches so a large backlog does not lock the table.
 pub struct IdempotencyCleanupJob {
-    handler: Arc<IdempotencyHandler>,
-    interval_seconds: u64,
+    store: Arc<PostgresIdempotencyStore>,
+    config: IdempotencyCleanupConfig,
+    control: Arc<JobControl>,
 }
 
 impl IdempotencyCleanupJob {
-    pub fn new(handler: Arc<IdempotencyHandler>, interval_seconds: u64) -> Self {
+    /// Name the job is controlled by through the admin API.
+    pub const JOB_NAME: &'static str = "idempotency_cleanup";
+
+    pub fn new(pool: PgPool, config: IdempotencyCleanupConfig) -> Self {
         Self {
-            handler,
-            interval_seconds,
+            store: Arc::new(PostgresIdempotencyStore::new(pool)),
+            control: Arc::new(JobControl::new(
+                Self::JOB_NAME,
+                std::time::Duration::from_secs(config.interval_seconds),
+            )),
+            config,
         }
     }
 
+    /// Control for pausing, triggering and inspecting the job at runtime.
+    pub fn control(&self) -> Arc<JobControl> {
+        self.control.clone()
+    }
+
     /// Runs the cleanup job once.
-    pub async fn run_once(&self) -> Result<u64> {
-        self.handler.cleanup_expired().await
+    pub async fn run_once(&self) -> Result<CleanupRunSummary> {
+        Self::run(&self.store, &self.config).await
+    }
+
+    async fn run(
+        store: &PostgresIdempotencyStore,
+        config: &IdempotencyCleanupConfig,
+    ) -> Result<CleanupRunSummary> {
+        let mut summary = CleanupRunSummary {
+            archived: config.archive,
+            ..Default::default()
+        };
+
+        while summary.batches < config.max_batches_per_run {
+            let removed = store
+                .purge_expired(
+                    &config.class_retention_seconds,
+                    config.default_retention_seconds,
+                    config.batch_size,
+                    config.archive,
+                )
+                .await?;
+            summary.batches += 1;
+            summary.removed += removed;
+            get_metrics().record_idempotency_expired(removed, config.archive);
+
+            if removed < config.batch_size as u64 {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(config.batch_pause_ms)).await;
+        }
+
+        summary.remaining = store.count_all().await?;
+        get_metrics().set_idempotency_records(summary.remaining);
+
+        Ok(summary)
     }
 
     /// Starts the cleanup job in a background task.
-    pub fn start(self) -> tokio::task::JoinHandle<()> {
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                tokio::time::Duration::from_secs(self.interval_seconds)
-            );
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let store = self.store.clone();
+        let config = self.config.clone();
+        let control = self.control.clone();
 
-            loop {
-                interval.tick().await;
+        control.set_running(true);
 
-                match self.handler.cleanup_expired().await {
-                    Ok(count) => {
-                        if count > 0 {
-                            tracing::info!("Cleaned up {} expired idempotency records", count);
+        tokio::spawn(async move {
+            while control.is_running() {
+                if control.begin_run() {
+                    match Self::run(&store, &config).await {
+                        Ok(summary) => {
+                            if summary.removed > 0 {
+                                tracing::info!(
+                                    archived = summary.archived,
+                                    remaining = summary.remaining,
+                                    "Cleaned up {} expired idempotency records",
+                                    summary.removed
+                                );
+                            }
+                            control.finish_run(Ok(summary.removed));
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to cleanup expired idempotency records: {}", e);
+                            control.finish_run(Err(e.to_string()));
                         }
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to cleanup expired idempotency records: {}", e);
                     }
                 }
+
+                control.wait().await;
             }
         })
     }
+
+    /// Stops the cleanup job.
+    pub fn stop(&self) {
+        self.control.set_running(false);
+    }
+
+    /// Checks if the cleanup job is running.
+    pub fn is_running(&self) -> bool {
+        self.control.is_running()
+    }
 }
 
 #[cfg(test)]
@@ -410,4 +536,17 @@ mod tests {
         assert_eq!(config.key_prefix, "idem");
         assert!(!config.include_timestamp_in_key);
     }
+
+    #[test]
+    fn test_cleanup_retention_per_class() {
+        let mut config = IdempotencyCleanupConfig {
+            default_retention_seconds: 3600,
+            ..Default::default()
+        };
+        config.class_retention_seconds.insert("refund".to_string(), 7 * 86400);
+
+        assert_eq!(config.retention_for("payment"), 3600);
+        assert_eq!(config.retention_for("refund"), 7 * 86400);
+        assert!(!config.archive);
+    }
 }
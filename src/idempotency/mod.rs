@@ -3,8 +3,8 @@ pub mod key_generator;
 pub mod storage;
 
 pub use handler::{
-    IdempotencyCheckResult, IdempotencyCleanupJob, IdempotencyHandler, IdempotencyHandlerConfig,
-    IdempotencyMetrics, MetricsSnapshot,
+    CleanupRunSummary, IdempotencyCheckResult, IdempotencyCleanupConfig, IdempotencyCleanupJob,
+    IdempotencyHandler, IdempotencyHandlerConfig, IdempotencyMetrics, MetricsSnapshot,
 };
 pub use key_generator::{IdempotencyAttributes, IdempotencyKeyGenerator, KeyGeneratorConfig};
 pub use storage::{
@@ -1,8 +1,10 @@
+use crate::cache::RedisPool;
 use crate::error::{AppError, Result};
 use chrono::{DateTime, Duration, Utc};
-use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Status of an idempotency record.
@@ -192,6 +194,84 @@ impl PostgresIdempotencyStore {
         Ok(result.rows_affected())
     }
 
+    /// Deletes up to `limit` expired records that are also past their class's retention
+    /// period, oldest expiry first. `class_retention` pairs operation types with a
+    /// retention in seconds measured from creation; other classes use
+    /// `default_retention_seconds`. With `archive` the records are copied to
+    /// `idempotency_keys_archive` in the same statement. Returns the number removed.
+    pub async fn purge_expired(
+        &self,
+        class_retention: &HashMap<String, i64>,
+        default_retention_seconds: i64,
+        limit: i64,
+        archive: bool,
+    ) -> Result<u64> {
+        let (classes, retention): (Vec<String>, Vec<i64>) = class_retention
+            .iter()
+            .map(|(class, seconds)| (class.clone(), *seconds))
+            .unzip();
+
+        let doomed = r#"
+            WITH retention AS (
+                SELECT * FROM UNNEST($1::text[], $2::bigint[]) AS r(operation_type, seconds)
+            ),
+            doomed AS (
+                SELECT k.id
+                FROM idempotency_keys k
+                LEFT JOIN retention r ON r.operation_type = k.operation_type
+                WHERE k.expires_at < NOW()
+                  AND k.created_at < NOW() - make_interval(secs => COALESCE(r.seconds, $3)::double precision)
+                ORDER BY k.expires_at
+                LIMIT $4
+                FOR UPDATE OF k SKIP LOCKED
+            )
+        "#;
+        let query = if archive {
+            format!(
+                r#"{}, deleted AS (
+                    DELETE FROM idempotency_keys
+                    WHERE id IN (SELECT id FROM doomed)
+                    RETURNING id, idempotency_key, client_id, operation_type, status, request_hash, response_data, error_message, created_at, expires_at, completed_at
+                )
+                INSERT INTO idempotency_keys_archive (id, idempotency_key, client_id, operation_type, status, request_hash, response_data, error_message, created_at, expires_at, completed_at)
+                SELECT id, idempotency_key, client_id, operation_type, status, request_hash, response_data, error_message, created_at, expires_at, completed_at
+                FROM deleted
+                ON CONFLICT (id) DO NOTHING
+                "#,
+                doomed
+            )
+        } else {
+            format!(
+                r#"{}
+                DELETE FROM idempotency_keys
+                WHERE id IN (SELECT id FROM doomed)
+                "#,
+                doomed
+            )
+        };
+
+        let result = sqlx::query(&query)
+            .bind(classes)
+            .bind(retention)
+            .bind(default_retention_seconds)
+            .bind(limit)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Counts all stored records.
+    pub async fn count_all(&self) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM idempotency_keys")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+
     /// Deletes a specific record by key.
     pub async fn delete(&self, key: &str) -> Result<bool> {
         let result = sqlx::query(
@@ -226,14 +306,14 @@ impl PostgresIdempotencyStore {
 
 /// Redis-based idempotency cache for fast lookups.
 pub struct RedisIdempotencyCache {
-    client: redis::Client,
+    redis: Arc<RedisPool>,
     key_prefix: String,
 }
 
 impl RedisIdempotencyCache {
-    pub fn new(client: redis::Client, key_prefix: impl Into<String>) -> Self {
+    pub fn new(redis: Arc<RedisPool>, key_prefix: impl Into<String>) -> Self {
         Self {
-            client,
+            redis,
             key_prefix: key_prefix.into(),
         }
     }
@@ -245,39 +325,26 @@ impl RedisIdempotencyCache {
     /// Attempts to set a key with NX (only if not exists) and TTL.
     /// Returns true if the key was set (new request), false if it already exists.
     pub async fn try_set(&self, idempotency_key: &str, ttl_seconds: i64) -> Result<bool> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(AppError::Redis)?;
-
         let key = self.make_key(idempotency_key);
-        let result: Option<String> = conn
-            .set_options(
-                &key,
-                "processing",
-                redis::SetOptions::default()
-                    .conditional_set(redis::ExistenceCheck::NX)
-                    .with_expiration(redis::SetExpiry::EX(ttl_seconds as usize)),
+        let result: Option<String> = self
+            .redis
+            .query(
+                redis::cmd("SET")
+                    .arg(&key)
+                    .arg("processing")
+                    .arg("NX")
+                    .arg("EX")
+                    .arg(ttl_seconds),
             )
-            .await
-            .map_err(AppError::Redis)?;
+            .await?;
 
         Ok(result.is_some())
     }
 
     /// Gets the cached response for a key.
     pub async fn get_response(&self, idempotency_key: &str) -> Result<Option<String>> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(AppError::Redis)?;
-
         let key = self.make_key(idempotency_key);
-        let value: Option<String> = conn.get(&key).await.map_err(AppError::Redis)?;
-
-        Ok(value)
+        self.redis.query(redis::cmd("GET").arg(&key)).await
     }
 
     /// Sets the response for a completed request.
@@ -287,50 +354,32 @@ impl RedisIdempotencyCache {
         response: &str,
         ttl_seconds: i64,
     ) -> Result<()> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(AppError::Redis)?;
-
         let key = self.make_key(idempotency_key);
-        let _: () = conn.set_ex(&key, response, ttl_seconds as u64)
+        self.redis
+            .query(redis::cmd("SET").arg(&key).arg(response).arg("EX").arg(ttl_seconds))
             .await
-            .map_err(AppError::Redis)?;
-
-        Ok(())
     }
 
     /// Deletes a key from cache.
     pub async fn delete(&self, idempotency_key: &str) -> Result<bool> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(AppError::Redis)?;
-
         let key = self.make_key(idempotency_key);
-        let deleted: i64 = conn.del(&key).await.map_err(AppError::Redis)?;
+        let deleted: i64 = self.redis.query(redis::cmd("DEL").arg(&key)).await?;
 
         Ok(deleted > 0)
     }
 
     /// Checks if a key exists.
     pub async fn exists(&self, idempotency_key: &str) -> Result<bool> {
-        let mut conn = self
-            .client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(AppError::Redis)?;
-
         let key = self.make_key(idempotency_key);
-        let exists: bool = conn.exists(&key).await.map_err(AppError::Redis)?;
-
-        Ok(exists)
+        self.redis.query(redis::cmd("EXISTS").arg(&key)).await
     }
 }
 
 /// Combined storage that uses both PostgreSQL and Redis.
+///
+/// PostgreSQL is authoritative. Redis errors (including an open circuit) are logged
+/// and the store carries on with PostgreSQL alone, so a Redis outage does not fail
+/// requests.
 pub struct HybridIdempotencyStore {
     postgres: PostgresIdempotencyStore,
     redis: RedisIdempotencyCache,
@@ -353,9 +402,15 @@ impl HybridIdempotencyStore {
     /// Checks if a request is a duplicate using Redis first, then PostgreSQL.
     pub async fn check_duplicate(&self, idempotency_key: &str) -> Result<Option<IdempotencyRecord>> {
         // Check Redis first (fast path)
-        if self.redis.exists(idempotency_key).await? {
-            // Found in Redis, get full record from PostgreSQL
-            return self.postgres.find_by_key(idempotency_key).await;
+        match self.redis.exists(idempotency_key).await {
+            Ok(true) => {
+                // Found in Redis, get full record from PostgreSQL
+                return self.postgres.find_by_key(idempotency_key).await;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Idempotency cache unavailable, using PostgreSQL only: {}", e);
+            }
         }
 
         // Not in Redis, check PostgreSQL
@@ -365,11 +420,22 @@ impl HybridIdempotencyStore {
     /// Attempts to acquire an idempotency lock.
     pub async fn try_acquire(&self, record: &IdempotencyRecord) -> Result<Option<IdempotencyRecord>> {
         // Try Redis first
-        let is_new = self.redis.try_set(&record.idempotency_key, self.ttl_seconds).await?;
+        let is_new = match self.redis.try_set(&record.idempotency_key, self.ttl_seconds).await {
+            Ok(is_new) => is_new,
+            Err(e) => {
+                tracing::warn!("Idempotency cache unavailable, using PostgreSQL only: {}", e);
+                return self.postgres.try_acquire(record).await;
+            }
+        };
 
         if !is_new {
             // Key exists in Redis, get record from PostgreSQL
-            return self.postgres.find_by_key(&record.idempotency_key).await;
+            if let Some(existing) = self.postgres.find_by_key(&record.idempotency_key).await? {
+                return Ok(Some(existing));
+            }
+            // Redis entry without a database record (e.g. the insert failed); the
+            // database constraint decides
+            return self.postgres.try_acquire(record).await;
         }
 
         // New key, try to insert into PostgreSQL
@@ -378,9 +444,13 @@ impl HybridIdempotencyStore {
                 if existing.is_some() {
                     // Race condition: another process inserted first
                     // Update Redis with the existing key
-                    self.redis
+                    if let Err(e) = self
+                        .redis
                         .set_response(&record.idempotency_key, "processing", self.ttl_seconds)
-                        .await?;
+                        .await
+                    {
+                        tracing::warn!("Failed to update idempotency cache: {}", e);
+                    }
                 }
                 Ok(existing)
             }
@@ -404,9 +474,13 @@ impl HybridIdempotencyStore {
         // Update Redis cache with response
         if record.is_some() {
             let response_str = serde_json::to_string(&response_data).unwrap_or_default();
-            self.redis
+            if let Err(e) = self
+                .redis
                 .set_response(idempotency_key, &response_str, self.ttl_seconds)
-                .await?;
+                .await
+            {
+                tracing::warn!("Failed to cache idempotent response: {}", e);
+            }
         }
 
         Ok(record)
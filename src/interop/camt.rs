@@ -0,0 +1,331 @@
+//! ISO 20022 camt.053 (end-of-day statement) and camt.052 (intraday account report)
+//! generation for participant settlement accounts.
+
+use crate::models::{Account, EntryType, LedgerEntry};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// Statement message type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementType {
+    /// camt.053 end-of-day statement with opening and closing booked balances.
+    Camt053,
+    /// camt.052 intraday report with interim booked and available balances.
+    Camt052,
+}
+
+impl StatementType {
+    fn namespace(&self) -> &'static str {
+        match self {
+            StatementType::Camt053 => "urn:iso:std:iso:20022:tech:xsd:camt.053.001.08",
+            StatementType::Camt052 => "urn:iso:std:iso:20022:tech:xsd:camt.052.001.08",
+        }
+    }
+
+    /// Message and statement element names.
+    fn elements(&self) -> (&'static str, &'static str) {
+        match self {
+            StatementType::Camt053 => ("BkToCstmrStmt", "Stmt"),
+            StatementType::Camt052 => ("BkToCstmrAcctRpt", "Rpt"),
+        }
+    }
+}
+
+/// An account statement for one currency over a period.
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub statement_type: StatementType,
+    pub message_id: String,
+    pub account: Account,
+    pub currency: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Booked balance at the start of the period.
+    pub opening_balance: Decimal,
+    /// Booked balance at the end of the period.
+    pub closing_balance: Decimal,
+    /// Balance free to use (booked less reserved) at generation; intraday reports only.
+    pub available_balance: Option<Decimal>,
+    /// Ledger entries booked in the period, oldest first.
+    pub entries: Vec<LedgerEntry>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Statement {
+    pub fn new(
+        statement_type: StatementType,
+        account: Account,
+        currency: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            statement_type,
+            message_id: format!("STMT-{}", Uuid::new_v4().simple()),
+            account,
+            currency,
+            from,
+            to,
+            opening_balance: Decimal::ZERO,
+            closing_balance: Decimal::ZERO,
+            available_balance: None,
+            entries: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Sets the booked balances at the start and end of the period.
+    pub fn with_balances(mut self, opening: Decimal, closing: Decimal) -> Self {
+        self.opening_balance = opening;
+        self.closing_balance = closing;
+        self
+    }
+
+    /// Sets the entries booked in the period.
+    pub fn with_entries(mut self, entries: Vec<LedgerEntry>) -> Self {
+        self.entries = entries;
+        self
+    }
+
+    /// Sets the available balance reported on intraday reports.
+    pub fn with_available_balance(mut self, balance: Decimal) -> Self {
+        self.available_balance = Some(balance);
+        self
+    }
+
+    /// Number and sum of credit entries.
+    pub fn credit_totals(&self) -> (usize, Decimal) {
+        self.totals(EntryType::Credit)
+    }
+
+    /// Number and sum of debit entries.
+    pub fn debit_totals(&self) -> (usize, Decimal) {
+        self.totals(EntryType::Debit)
+    }
+
+    fn totals(&self, entry_type: EntryType) -> (usize, Decimal) {
+        self.entries
+            .iter()
+            .filter(|e| e.entry_type == entry_type)
+            .fold((0, Decimal::ZERO), |(count, sum), e| (count + 1, sum + e.amount))
+    }
+
+    /// Renders the statement as an ISO 20022 XML document.
+    pub fn to_xml(&self) -> String {
+        let (message, statement) = self.statement_type.elements();
+        let created_at = timestamp(self.created_at);
+        let mut xml = String::new();
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(xml, "<Document xmlns=\"{}\">", self.statement_type.namespace());
+        let _ = writeln!(xml, "  <{}>", message);
+        let _ = writeln!(
+            xml,
+            "    <GrpHdr><MsgId>{}</MsgId><CreDtTm>{}</CreDtTm></GrpHdr>",
+            self.message_id, created_at
+        );
+        let _ = writeln!(xml, "    <{}>", statement);
+        let _ = writeln!(xml, "      <Id>{}</Id>", self.message_id);
+        let _ = writeln!(xml, "      <CreDtTm>{}</CreDtTm>", created_at);
+        let _ = writeln!(
+            xml,
+            "      <FrToDt><FrDtTm>{}</FrDtTm><ToDtTm>{}</ToDtTm></FrToDt>",
+            timestamp(self.from),
+            timestamp(self.to)
+        );
+        let _ = writeln!(
+            xml,
+            "      <Acct><Id><Othr><Id>{}</Id></Othr></Id><Ccy>{}</Ccy><Nm>{}</Nm></Acct>",
+            escape(&self.account.external_id),
+            escape(&self.currency),
+            escape(&self.account.name)
+        );
+
+        self.write_balance(&mut xml, "OPBD", self.opening_balance, self.from);
+        match self.statement_type {
+            StatementType::Camt053 => self.write_balance(&mut xml, "CLBD", self.closing_balance, self.to),
+            StatementType::Camt052 => {
+                self.write_balance(&mut xml, "ITBD", self.closing_balance, self.to);
+                if let Some(available) = self.available_balance {
+                    self.write_balance(&mut xml, "ITAV", available, self.to);
+                }
+            }
+        }
+
+        let (credit_count, credit_sum) = self.credit_totals();
+        let (debit_count, debit_sum) = self.debit_totals();
+        let net = credit_sum - debit_sum;
+        let _ = writeln!(
+            xml,
+            "      <TxsSummry><TtlNtries><NbOfNtries>{}</NbOfNtries><Sum>{}</Sum><TtlNetNtry><Amt>{}</Amt><CdtDbtInd>{}</CdtDbtInd></TtlNetNtry></TtlNtries>\
+             <TtlCdtNtries><NbOfNtries>{}</NbOfNtries><Sum>{}</Sum></TtlCdtNtries>\
+             <TtlDbtNtries><NbOfNtries>{}</NbOfNtries><Sum>{}</Sum></TtlDbtNtries></TxsSummry>",
+            credit_count + debit_count,
+            amount(credit_sum + debit_sum),
+            amount(net),
+            indicator(net),
+            credit_count,
+            amount(credit_sum),
+            debit_count,
+            amount(debit_sum),
+        );
+
+        for entry in &self.entries {
+            let direction = match entry.entry_type {
+                EntryType::Credit => "CRDT",
+                EntryType::Debit => "DBIT",
+            };
+            // The reference goes out as unstructured remittance information and the
+            // narrative as the entry's additional information, capped at 500 characters
+            let remittance = entry
+                .reference
+                .as_deref()
+                .map(|reference| format!("<RmtInf><Ustrd>{}</Ustrd></RmtInf>", escape(&truncate(reference, 140))))
+                .unwrap_or_default();
+            let narrative = entry
+                .narrative
+                .as_deref()
+                .map(|narrative| format!("<AddtlNtryInf>{}</AddtlNtryInf>", escape(&truncate(narrative, 500))))
+                .unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                "      <Ntry><NtryRef>{}</NtryRef><Amt Ccy=\"{}\">{}</Amt><CdtDbtInd>{}</CdtDbtInd><Sts><Cd>BOOK</Cd></Sts>\
+                 <BookgDt><DtTm>{}</DtTm></BookgDt><ValDt><Dt>{}</Dt></ValDt><AcctSvcrRef>{}</AcctSvcrRef>\
+                 <BkTxCd><Prtry><Cd>SETTLEMENT</Cd></Prtry></BkTxCd>\
+                 <NtryDtls><TxDtls><Refs><EndToEndId>{}</EndToEndId></Refs>{}</TxDtls></NtryDtls>{}</Ntry>",
+                entry.id,
+                escape(&entry.currency),
+                amount(entry.amount),
+                direction,
+                timestamp(entry.created_at),
+                entry.effective_date,
+                entry.transaction_id,
+                entry.transaction_id,
+                remittance,
+                narrative,
+            );
+        }
+
+        let _ = writeln!(xml, "    </{}>", statement);
+        let _ = writeln!(xml, "  </{}>", message);
+        xml.push_str("</Document>\n");
+        xml
+    }
+
+    fn write_balance(&self, xml: &mut String, code: &str, balance: Decimal, at: DateTime<Utc>) {
+        let _ = writeln!(
+            xml,
+            "      <Bal><Tp><CdOrPrtry><Cd>{}</Cd></CdOrPrtry></Tp><Amt Ccy=\"{}\">{}</Amt><CdtDbtInd>{}</CdtDbtInd><Dt><DtTm>{}</DtTm></Dt></Bal>",
+            code,
+            escape(&self.currency),
+            amount(balance),
+            indicator(balance),
+            timestamp(at)
+        );
+    }
+}
+
+pub(super) fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Unsigned amount with at least two decimal places; the sign goes in `CdtDbtInd`.
+pub(super) fn amount(value: Decimal) -> String {
+    let value = value.abs().normalize();
+    if value.scale() < 2 {
+        format!("{:.2}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn indicator(value: Decimal) -> &'static str {
+    if value.is_sign_negative() && !value.is_zero() {
+        "DBIT"
+    } else {
+        "CRDT"
+    }
+}
+
+/// First `max` characters of a value, for fields of limited length.
+fn truncate(value: &str, max: usize) -> String {
+    value.chars().take(max).collect()
+}
+
+pub(super) fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AccountType;
+    use chrono::{Duration, NaiveDate};
+    use rust_decimal_macros::dec;
+
+    fn statement(statement_type: StatementType) -> Statement {
+        let account = Account::new(
+            "ACME & Sons".to_string(),
+            "Participant <A>".to_string(),
+            AccountType::Asset,
+            "USD".to_string(),
+        );
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let entries = vec![
+            LedgerEntry::credit(Uuid::new_v4(), account.id, dec!(250), "USD".to_string(), dec!(1250), date)
+                .with_narrative(Some("PAYMENT <PAY-1>".to_string()), Some("INV-7".to_string())),
+            LedgerEntry::debit(Uuid::new_v4(), account.id, dec!(400.5), "USD".to_string(), dec!(849.5), date),
+        ];
+        let from = Utc::now() - Duration::hours(1);
+        Statement::new(statement_type, account, "USD".to_string(), from, Utc::now())
+            .with_balances(dec!(1000), dec!(849.5))
+            .with_entries(entries)
+    }
+
+    #[test]
+    fn test_end_of_day_statement() {
+        let xml = statement(StatementType::Camt053).to_xml();
+
+        assert!(xml.contains("xmlns=\"urn:iso:std:iso:20022:tech:xsd:camt.053.001.08\""));
+        assert!(xml.contains("<BkToCstmrStmt>") && xml.contains("</Stmt>"));
+        assert!(xml.contains("<Cd>OPBD</Cd></CdOrPrtry></Tp><Amt Ccy=\"USD\">1000.00</Amt><CdtDbtInd>CRDT"));
+        assert!(xml.contains("<Cd>CLBD</Cd></CdOrPrtry></Tp><Amt Ccy=\"USD\">849.50</Amt>"));
+        assert!(xml.contains("<TtlNetNtry><Amt>150.50</Amt><CdtDbtInd>DBIT</CdtDbtInd>"));
+        assert!(xml.contains("<TtlCdtNtries><NbOfNtries>1</NbOfNtries><Sum>250.00</Sum>"));
+        assert_eq!(xml.matches("<Ntry>").count(), 2);
+        assert!(xml.contains("<Othr><Id>ACME &amp; Sons</Id>"));
+        assert!(xml.contains("<Nm>Participant &lt;A&gt;</Nm>"));
+        assert!(xml.contains("<RmtInf><Ustrd>INV-7</Ustrd></RmtInf></TxDtls></NtryDtls><AddtlNtryInf>PAYMENT &lt;PAY-1&gt;</AddtlNtryInf></Ntry>"));
+        assert_eq!(xml.matches("<AddtlNtryInf>").count(), 1);
+        assert!(!xml.contains("ITBD"));
+    }
+
+    #[test]
+    fn test_intraday_report() {
+        let xml = statement(StatementType::Camt052)
+            .with_available_balance(dec!(-20))
+            .to_xml();
+
+        assert!(xml.contains("<BkToCstmrAcctRpt>") && xml.contains("<Rpt>"));
+        assert!(xml.contains("<Cd>ITBD</Cd>"));
+        assert!(xml.contains("<Cd>ITAV</Cd></CdOrPrtry></Tp><Amt Ccy=\"USD\">20.00</Amt><CdtDbtInd>DBIT"));
+        assert!(!xml.contains("CLBD"));
+    }
+
+    #[test]
+    fn test_amount_formatting() {
+        assert_eq!(amount(dec!(10)), "10.00");
+        assert_eq!(amount(dec!(-3.5)), "3.50");
+        assert_eq!(amount(dec!(0.125)), "0.125");
+        assert_eq!(indicator(Decimal::ZERO), "CRDT");
+    }
+}
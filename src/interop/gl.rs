@@ -0,0 +1,313 @@
+//! General-ledger journal export for the ERP.
+//!
+//! Sub-ledger entries are mapped to GL account codes by transaction type and account
+//! type, summed per code and currency, and written out as a journal of debit and
+//! credit lines. Fee legs booked to a fee revenue account post to a fee income code.
+//! Fees in currencies without a revenue account are left by the sub-ledger as the gap
+//! between a source's gross debit and a destination's net credit, so they are credited
+//! to the fee income code as well, keeping each currency's journal balanced.
+
+use crate::models::{
+    AccountType, EntryType, GlFeeSummary, GlJournalLine, GlPostingRun, GlPostingSummary, TransactionType,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// Maps entries matching a transaction type and/or account type to a GL account code.
+/// A missing type matches any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlRule {
+    pub transaction_type: Option<TransactionType>,
+    pub account_type: Option<AccountType>,
+    pub gl_code: String,
+}
+
+impl GlRule {
+    pub fn new(transaction_type: Option<TransactionType>, account_type: Option<AccountType>, gl_code: impl Into<String>) -> Self {
+        Self {
+            transaction_type,
+            account_type,
+            gl_code: gl_code.into(),
+        }
+    }
+
+    /// How specifically the rule matches, or `None` if it does not. Account types
+    /// outrank transaction types.
+    fn specificity(&self, transaction_type: TransactionType, account_type: AccountType) -> Option<u8> {
+        let mut score = 0;
+        match self.account_type {
+            Some(rule_type) if rule_type != account_type => return None,
+            Some(_) => score += 2,
+            None => {}
+        }
+        match self.transaction_type {
+            Some(rule_type) if rule_type != transaction_type => return None,
+            Some(_) => score += 1,
+            None => {}
+        }
+        Some(score)
+    }
+}
+
+/// GL account codes entries are posted to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlMapping {
+    pub rules: Vec<GlRule>,
+    /// Credited with fees charged on transactions.
+    pub fee_income_code: String,
+    /// Receives entries no rule matches.
+    pub suspense_code: String,
+}
+
+impl Default for GlMapping {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                GlRule::new(None, Some(AccountType::Asset), "1000"),
+                GlRule::new(None, Some(AccountType::Liability), "2000"),
+                GlRule::new(None, Some(AccountType::Revenue), "4000"),
+                GlRule::new(None, Some(AccountType::Expense), "5000"),
+            ],
+            fee_income_code: "4100".to_string(),
+            suspense_code: "9999".to_string(),
+        }
+    }
+}
+
+impl GlMapping {
+    /// Returns the GL code of the most specific matching rule; the first one wins a tie.
+    pub fn resolve(&self, transaction_type: TransactionType, account_type: AccountType) -> &str {
+        let mut best: Option<(u8, &GlRule)> = None;
+        for rule in &self.rules {
+            if let Some(score) = rule.specificity(transaction_type, account_type) {
+                if best.is_none_or(|(best_score, _)| score > best_score) {
+                    best = Some((score, rule));
+                }
+            }
+        }
+        best.map_or(&self.suspense_code, |(_, rule)| &rule.gl_code)
+    }
+
+    /// Builds a run's journal lines, one per GL code and currency, ordered by currency
+    /// then code.
+    pub fn journal_lines(&self, run_id: Uuid, entries: &[GlPostingSummary], fees: &[GlFeeSummary]) -> Vec<GlJournalLine> {
+        let mut totals: BTreeMap<(String, String), (Decimal, Decimal, i64)> = BTreeMap::new();
+
+        for entry in entries {
+            let code = if entry.fee_leg {
+                self.fee_income_code.clone()
+            } else {
+                self.resolve(entry.transaction_type, entry.account_type).to_string()
+            };
+            let total = totals.entry((entry.currency.clone(), code)).or_default();
+            match entry.entry_type {
+                EntryType::Debit => total.0 += entry.amount,
+                EntryType::Credit => total.1 += entry.amount,
+            }
+            total.2 += entry.entry_count;
+        }
+        for fee in fees.iter().filter(|fee| !fee.amount.is_zero()) {
+            let total = totals
+                .entry((fee.currency.clone(), self.fee_income_code.clone()))
+                .or_default();
+            total.1 += fee.amount;
+            total.2 += fee.transaction_count;
+        }
+
+        totals
+            .into_iter()
+            .enumerate()
+            .map(|(index, ((currency, gl_code), (debit, credit, entry_count)))| GlJournalLine {
+                run_id,
+                line_number: index as i32 + 1,
+                gl_code,
+                currency,
+                debit_amount: debit,
+                credit_amount: credit,
+                entry_count,
+            })
+            .collect()
+    }
+}
+
+/// A posting run with its journal lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlJournal {
+    pub run: GlPostingRun,
+    pub lines: Vec<GlJournalLine>,
+}
+
+impl GlJournal {
+    /// Total debits and credits per currency.
+    pub fn totals(&self) -> BTreeMap<String, (Decimal, Decimal)> {
+        let mut totals: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
+        for line in &self.lines {
+            let total = totals.entry(line.currency.clone()).or_default();
+            total.0 += line.debit_amount;
+            total.1 += line.credit_amount;
+        }
+        totals
+    }
+
+    /// Returns true if debits equal credits in every currency.
+    pub fn is_balanced(&self) -> bool {
+        self.totals().values().all(|(debit, credit)| debit == credit)
+    }
+
+    /// Renders the journal as CSV with a header row. Amounts have four decimal places.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("posting_date,line_number,gl_code,currency,debit,credit,entry_count,run_id\n");
+        for line in &self.lines {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{:.4},{:.4},{},{}",
+                self.run.posting_date,
+                line.line_number,
+                csv_field(&line.gl_code),
+                csv_field(&line.currency),
+                line.debit_amount,
+                line.credit_amount,
+                line.entry_count,
+                self.run.id,
+            );
+        }
+        csv
+    }
+
+    /// File name the journal is exported under.
+    pub fn file_name(&self, extension: &str) -> String {
+        format!("gl-journal-{}.{}", self.run.posting_date, extension)
+    }
+}
+
+/// Quotes a field if it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn entry(
+        transaction_type: TransactionType,
+        account_type: AccountType,
+        entry_type: EntryType,
+        amount: Decimal,
+    ) -> GlPostingSummary {
+        GlPostingSummary {
+            transaction_type,
+            account_type,
+            entry_type,
+            fee_leg: false,
+            currency: "USD".to_string(),
+            amount,
+            entry_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_resolve_prefers_most_specific_rule() {
+        let mut mapping = GlMapping::default();
+        mapping.rules.push(GlRule::new(Some(TransactionType::Refund), None, "1900"));
+        mapping.rules.push(GlRule::new(Some(TransactionType::Fee), Some(AccountType::Asset), "1100"));
+
+        assert_eq!(mapping.resolve(TransactionType::Payment, AccountType::Asset), "1000");
+        assert_eq!(mapping.resolve(TransactionType::Fee, AccountType::Asset), "1100");
+        // Account type outranks transaction type
+        assert_eq!(mapping.resolve(TransactionType::Refund, AccountType::Liability), "2000");
+
+        let unmapped = GlMapping {
+            rules: vec![GlRule::new(Some(TransactionType::Refund), None, "1900")],
+            ..GlMapping::default()
+        };
+        assert_eq!(unmapped.resolve(TransactionType::Refund, AccountType::Asset), "1900");
+        assert_eq!(unmapped.resolve(TransactionType::Payment, AccountType::Asset), "9999");
+    }
+
+    #[test]
+    fn test_journal_lines_balance_with_fees() {
+        let mapping = GlMapping::default();
+        let run = GlPostingRun::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        let entries = vec![
+            entry(TransactionType::Payment, AccountType::Asset, EntryType::Debit, dec!(100)),
+            entry(TransactionType::Payment, AccountType::Liability, EntryType::Credit, dec!(98)),
+            entry(TransactionType::Transfer, AccountType::Asset, EntryType::Credit, dec!(40)),
+            entry(TransactionType::Transfer, AccountType::Asset, EntryType::Debit, dec!(40)),
+        ];
+        let fees = vec![GlFeeSummary {
+            transaction_type: TransactionType::Payment,
+            currency: "USD".to_string(),
+            amount: dec!(2),
+            transaction_count: 1,
+        }];
+
+        let lines = mapping.journal_lines(run.id, &entries, &fees);
+        let codes: Vec<&str> = lines.iter().map(|line| line.gl_code.as_str()).collect();
+        assert_eq!(codes, vec!["1000", "2000", "4100"]);
+        assert_eq!(lines[0].debit_amount, dec!(140));
+        assert_eq!(lines[0].credit_amount, dec!(40));
+        assert_eq!(lines[0].entry_count, 3);
+        assert_eq!(lines[2].credit_amount, dec!(2));
+        assert_eq!(lines.iter().map(|line| line.line_number).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let journal = GlJournal { run, lines };
+        assert!(journal.is_balanced());
+    }
+
+    #[test]
+    fn test_fee_legs_post_to_fee_income() {
+        let mapping = GlMapping::default();
+        let run = GlPostingRun::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        let fee_leg = GlPostingSummary {
+            fee_leg: true,
+            ..entry(TransactionType::Payment, AccountType::Revenue, EntryType::Credit, dec!(2))
+        };
+        let entries = vec![
+            entry(TransactionType::Payment, AccountType::Asset, EntryType::Debit, dec!(100)),
+            entry(TransactionType::Payment, AccountType::Liability, EntryType::Credit, dec!(98)),
+            fee_leg,
+        ];
+
+        let lines = mapping.journal_lines(run.id, &entries, &[]);
+        let codes: Vec<&str> = lines.iter().map(|line| line.gl_code.as_str()).collect();
+        assert_eq!(codes, vec!["1000", "2000", "4100"]);
+        assert_eq!(lines[2].credit_amount, dec!(2));
+        assert!(GlJournal { run, lines }.is_balanced());
+    }
+
+    #[test]
+    fn test_journal_csv() {
+        let run = GlPostingRun::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        let journal = GlJournal {
+            lines: vec![GlJournalLine {
+                run_id: run.id,
+                line_number: 1,
+                gl_code: "1000,A".to_string(),
+                currency: "USD".to_string(),
+                debit_amount: dec!(12.5),
+                credit_amount: dec!(0),
+                entry_count: 2,
+            }],
+            run,
+        };
+
+        let csv = journal.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[1],
+            format!("2024-03-15,1,\"1000,A\",USD,12.5000,0.0000,2,{}", journal.run.id)
+        );
+        assert_eq!(journal.file_name("csv"), "gl-journal-2024-03-15.csv");
+    }
+}
@@ -0,0 +1,5 @@
+pub mod camt;
+pub mod gl;
+pub mod nacha;
+pub mod pacs008;
+pub mod pdf;
@@ -0,0 +1,460 @@
+//! NACHA ACH file generation for net settlement instructions.
+//!
+//! Each instruction becomes a debit entry against the paying participant and a credit
+//! entry to the receiving participant, in a single CCD batch with one addenda record per
+//! entry. Records are 94 characters and the file is padded with `9` records to a
+//! multiple of ten (the blocking factor). Every participant needs a USD settlement
+//! profile on the ACH rail.
+
+use crate::error::{AppError, Result};
+use crate::models::{BankAccountType, PaymentRail, SettlementBatch, SettlementProfile};
+use crate::services::SettlementInstruction;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+const RECORD_LENGTH: usize = 94;
+const BLOCKING_FACTOR: usize = 10;
+/// Largest amount an entry can carry: ten digits of cents.
+const MAX_ENTRY_CENTS: i64 = 9_999_999_999;
+/// Mixed debits and credits.
+const SERVICE_CLASS_MIXED: &str = "200";
+/// Corporate Credit or Debit, used between businesses.
+const STANDARD_ENTRY_CLASS: &str = "CCD";
+
+/// Originator details written to the file and batch headers.
+#[derive(Debug, Clone)]
+pub struct NachaConfig {
+    /// Routing number of the ACH operator or bank the file is sent to.
+    pub immediate_destination: String,
+    pub immediate_destination_name: String,
+    /// Ten-character origin identifier, usually a routing number or `1` + EIN.
+    pub immediate_origin: String,
+    pub immediate_origin_name: String,
+    pub company_name: String,
+    /// Ten-character company identification.
+    pub company_id: String,
+    /// First eight digits of the originating bank's routing number.
+    pub originating_dfi: String,
+    pub entry_description: String,
+}
+
+impl NachaConfig {
+    fn validate(&self) -> Result<()> {
+        let digits = |value: &str, len: usize| value.len() == len && value.chars().all(|c| c.is_ascii_digit());
+        if !digits(&self.immediate_destination, 9) {
+            return Err(AppError::Validation(
+                "NACHA immediate destination must be a 9-digit routing number".to_string(),
+            ));
+        }
+        if !digits(&self.originating_dfi, 8) {
+            return Err(AppError::Validation(
+                "NACHA originating DFI must be the first 8 digits of a routing number".to_string(),
+            ));
+        }
+        if self.immediate_origin.is_empty() || self.immediate_origin.len() > 10 {
+            return Err(AppError::Validation(
+                "NACHA immediate origin must be 1 to 10 characters".to_string(),
+            ));
+        }
+        if self.company_id.is_empty() || self.company_id.len() > 10 {
+            return Err(AppError::Validation(
+                "NACHA company ID must be 1 to 10 characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A generated NACHA file.
+#[derive(Debug, Clone)]
+pub struct NachaFile {
+    records: Vec<String>,
+    pub entry_count: usize,
+    pub total_debit: Decimal,
+    pub total_credit: Decimal,
+}
+
+impl NachaFile {
+    /// Builds the file for a batch's settlement instructions. Every participant in the
+    /// instructions needs a settlement profile with ACH details; zero-amount instructions
+    /// are skipped.
+    pub fn build(
+        config: &NachaConfig,
+        batch: &SettlementBatch,
+        instructions: &[SettlementInstruction],
+        profiles: &HashMap<Uuid, SettlementProfile>,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self> {
+        config.validate()?;
+        if batch.currency != "USD" {
+            return Err(AppError::Validation(format!(
+                "NACHA files can only carry USD, batch {} is {}",
+                batch.id, batch.currency
+            )));
+        }
+
+        let mut missing: Vec<Uuid> = instructions
+            .iter()
+            .flat_map(|i| [i.from_participant, i.to_participant])
+            .filter(|id| !profiles.contains_key(id))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if !missing.is_empty() {
+            let ids: Vec<String> = missing.iter().map(Uuid::to_string).collect();
+            return Err(AppError::Validation(format!(
+                "Missing settlement profile for participants: {}",
+                ids.join(", ")
+            )));
+        }
+
+        let mut accounts: HashMap<Uuid, AchAccount<'_>> = HashMap::new();
+        let mut not_ach: Vec<String> = Vec::new();
+        for (participant, profile) in profiles {
+            match AchAccount::from_profile(profile) {
+                Some(account) => {
+                    accounts.insert(*participant, account);
+                }
+                None => not_ach.push(format!("{} ({})", participant, profile.rail.as_str())),
+            }
+        }
+        not_ach.sort();
+        if !not_ach.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Settlement profiles do not settle over ACH for participants: {}",
+                not_ach.join(", ")
+            )));
+        }
+
+        let effective_date = batch.settlement_date.format("%y%m%d").to_string();
+        let batch_number = 1;
+        let mut records = vec![Self::file_header(config, batch, created_at)];
+        records.push(format!(
+            "5{}{}{}{}{}{}{}{}{}1{}{}",
+            SERVICE_CLASS_MIXED,
+            alpha(&config.company_name, 16),
+            alpha(&format!("BATCH {}", batch.sequence_number), 20),
+            alpha(&config.company_id, 10),
+            STANDARD_ENTRY_CLASS,
+            alpha(&config.entry_description, 10),
+            effective_date,
+            effective_date,
+            alpha("", 3),
+            config.originating_dfi,
+            numeric(batch_number, 7),
+        ));
+
+        let mut entry_count = 0usize;
+        let mut entry_hash = 0u64;
+        let mut total_debit_cents = 0i64;
+        let mut total_credit_cents = 0i64;
+
+        for (index, instruction) in instructions.iter().enumerate() {
+            let cents = to_cents(instruction.amount)?;
+            if cents == 0 {
+                continue;
+            }
+
+            let info = format!(
+                "NET SETTLEMENT {} {} INSTRUCTION {}",
+                batch.id.simple(),
+                batch.settlement_date,
+                index + 1
+            );
+            for (participant, is_debit) in [
+                (instruction.from_participant, true),
+                (instruction.to_participant, false),
+            ] {
+                let account = &accounts[&participant];
+                entry_count += 1;
+                let trace = format!("{}{}", config.originating_dfi, numeric(entry_count as u64, 7));
+
+                records.push(format!(
+                    "6{}{}{}{}{}{}  1{}",
+                    transaction_code(account.account_type, is_debit),
+                    account.routing_number,
+                    alpha(account.account_number, 17),
+                    numeric(cents as u64, 10),
+                    alpha(&participant.simple().to_string(), 15),
+                    alpha(account.account_name, 22),
+                    trace,
+                ));
+                records.push(format!("705{}0001{}", alpha(&info, 80), &trace[8..]));
+
+                entry_hash += account.routing_number[..8].parse::<u64>().unwrap_or(0);
+                if is_debit {
+                    total_debit_cents += cents;
+                } else {
+                    total_credit_cents += cents;
+                }
+            }
+        }
+
+        // Each entry carries one addenda record
+        let entry_addenda_count = (entry_count * 2) as u64;
+        records.push(format!(
+            "8{}{}{}{}{}{}{}{}{}{}",
+            SERVICE_CLASS_MIXED,
+            numeric(entry_addenda_count, 6),
+            numeric(entry_hash, 10),
+            numeric(total_debit_cents as u64, 12),
+            numeric(total_credit_cents as u64, 12),
+            alpha(&config.company_id, 10),
+            alpha("", 19),
+            alpha("", 6),
+            config.originating_dfi,
+            numeric(batch_number, 7),
+        ));
+
+        let block_count = (records.len() + 1).div_ceil(BLOCKING_FACTOR);
+        records.push(format!(
+            "9{}{}{}{}{}{}{}",
+            numeric(1, 6),
+            numeric(block_count as u64, 6),
+            numeric(entry_addenda_count, 8),
+            numeric(entry_hash, 10),
+            numeric(total_debit_cents as u64, 12),
+            numeric(total_credit_cents as u64, 12),
+            alpha("", 39),
+        ));
+        while records.len() % BLOCKING_FACTOR != 0 {
+            records.push("9".repeat(RECORD_LENGTH));
+        }
+
+        debug_assert!(records.iter().all(|r| r.len() == RECORD_LENGTH));
+
+        Ok(Self {
+            records,
+            entry_count,
+            total_debit: Decimal::new(total_debit_cents, 2),
+            total_credit: Decimal::new(total_credit_cents, 2),
+        })
+    }
+
+    /// The 94-character records of the file, including block padding.
+    pub fn records(&self) -> &[String] {
+        &self.records
+    }
+
+    fn file_header(config: &NachaConfig, batch: &SettlementBatch, created_at: DateTime<Utc>) -> String {
+        format!(
+            "101 {}{}{}{}A094101{}{}{}",
+            config.immediate_destination,
+            right_justify(&config.immediate_origin, 10),
+            created_at.format("%y%m%d"),
+            created_at.format("%H%M"),
+            alpha(&config.immediate_destination_name, 23),
+            alpha(&config.immediate_origin_name, 23),
+            alpha(&format!("B{}", batch.sequence_number), 8),
+        )
+    }
+}
+
+impl fmt::Display for NachaFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(f, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+/// The ACH details of a settlement profile.
+struct AchAccount<'a> {
+    account_name: &'a str,
+    routing_number: &'a str,
+    account_number: &'a str,
+    account_type: BankAccountType,
+}
+
+impl<'a> AchAccount<'a> {
+    fn from_profile(profile: &'a SettlementProfile) -> Option<Self> {
+        if profile.rail != PaymentRail::Ach {
+            return None;
+        }
+        Some(Self {
+            account_name: &profile.account_name,
+            routing_number: profile.routing_number.as_deref()?,
+            account_number: profile.bank_account_number.as_deref()?,
+            account_type: profile.bank_account_type,
+        })
+    }
+}
+
+fn transaction_code(account_type: BankAccountType, is_debit: bool) -> &'static str {
+    match (account_type, is_debit) {
+        (BankAccountType::Checking, false) => "22",
+        (BankAccountType::Checking, true) => "27",
+        (BankAccountType::Savings, false) => "32",
+        (BankAccountType::Savings, true) => "37",
+    }
+}
+
+fn to_cents(amount: Decimal) -> Result<i64> {
+    let cents = amount * Decimal::from(100);
+    if !cents.fract().is_zero() || cents.is_sign_negative() {
+        return Err(AppError::Validation(format!(
+            "Amount {} cannot be expressed in whole cents",
+            amount
+        )));
+    }
+    match cents.to_i64() {
+        Some(cents) if cents <= MAX_ENTRY_CENTS => Ok(cents),
+        _ => Err(AppError::Validation(format!(
+            "Amount {} exceeds the largest NACHA entry amount",
+            amount
+        ))),
+    }
+}
+
+/// Uppercases, replaces characters outside the NACHA character set, and pads or
+/// truncates to `len`.
+fn alpha(value: &str, len: usize) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| c.to_ascii_uppercase())
+        .map(|c| if c.is_ascii_graphic() || c == ' ' { c } else { ' ' })
+        .take(len)
+        .collect();
+    format!("{:<len$}", cleaned, len = len)
+}
+
+fn right_justify(value: &str, len: usize) -> String {
+    let truncated: String = value.chars().take(len).collect();
+    format!("{:>len$}", truncated, len = len)
+}
+
+/// Zero-pads to `len`, keeping the rightmost digits of larger values (as required for
+/// the entry hash).
+fn numeric(value: u64, len: usize) -> String {
+    let digits = format!("{:0len$}", value, len = len);
+    digits[digits.len() - len..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::InstructionType;
+    use chrono::{NaiveDate, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn config() -> NachaConfig {
+        NachaConfig {
+            immediate_destination: "021000021".to_string(),
+            immediate_destination_name: "Federal Reserve Bank".to_string(),
+            immediate_origin: "1234567890".to_string(),
+            immediate_origin_name: "Settlement Engine".to_string(),
+            company_name: "Settlement Engine".to_string(),
+            company_id: "1234567890".to_string(),
+            originating_dfi: "01100001".to_string(),
+            entry_description: "SETTLEMENT".to_string(),
+        }
+    }
+
+    fn batch() -> SettlementBatch {
+        let mut batch = SettlementBatch::new(
+            NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            Utc::now(),
+            "USD".to_string(),
+        );
+        batch.sequence_number = 7;
+        batch
+    }
+
+    fn profile(account_id: Uuid, routing_number: &str, account_type: BankAccountType) -> SettlementProfile {
+        SettlementProfile::new(account_id, "Participant", routing_number, "000123456789", account_type)
+    }
+
+    fn fixture() -> (SettlementBatch, Vec<SettlementInstruction>, HashMap<Uuid, SettlementProfile>) {
+        let batch = batch();
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let instructions = vec![
+            SettlementInstruction::new(batch.id, a, b, dec!(1250.50), "USD".to_string(), InstructionType::MultilateralNet),
+            SettlementInstruction::new(batch.id, a, c, dec!(99.99), "USD".to_string(), InstructionType::MultilateralNet),
+        ];
+        let profiles = HashMap::from([
+            (a, profile(a, "021000021", BankAccountType::Checking)),
+            (b, profile(b, "011000015", BankAccountType::Savings)),
+            (c, profile(c, "021000021", BankAccountType::Checking)),
+        ]);
+        (batch, instructions, profiles)
+    }
+
+    #[test]
+    fn test_file_layout_and_control_totals() {
+        let (batch, instructions, profiles) = fixture();
+        let created_at = Utc.with_ymd_and_hms(2024, 3, 15, 17, 30, 0).unwrap();
+        let file = NachaFile::build(&config(), &batch, &instructions, &profiles, created_at).unwrap();
+        let records = file.records();
+
+        assert!(records.iter().all(|r| r.len() == RECORD_LENGTH));
+        assert_eq!(records.len() % BLOCKING_FACTOR, 0);
+
+        let types: String = records.iter().map(|r| &r[..1]).collect();
+        assert!(types.starts_with("15676767678"));
+        assert!(types[11..].chars().all(|c| c == '9'));
+
+        assert_eq!(&records[0][23..33], "2403151730");
+        assert_eq!(&records[1][50..53], "CCD");
+        assert_eq!(&records[1][69..75], "240315");
+
+        // Debit payer from checking, credit the savings receiver
+        assert_eq!(&records[2][1..3], "27");
+        assert_eq!(&records[4][1..3], "32");
+        assert_eq!(&records[2][29..39], "0000125050");
+        assert_eq!(&records[2][79..94], "011000010000001");
+        assert_eq!(&records[3][87..94], "0000001");
+
+        assert_eq!(file.entry_count, 4);
+        assert_eq!(file.total_debit, dec!(1350.49));
+        assert_eq!(file.total_credit, dec!(1350.49));
+
+        // Entry hash: 02100002 + 01100001 + 02100002 + 02100002
+        let batch_control = &records[10];
+        assert_eq!(&batch_control[4..10], "000008");
+        assert_eq!(&batch_control[10..20], "0007400007");
+        assert_eq!(&batch_control[20..32], "000000135049");
+        assert_eq!(&batch_control[32..44], "000000135049");
+
+        let file_control = &records[11];
+        assert_eq!(&file_control[7..13], "000002");
+        assert_eq!(&file_control[13..21], "00000008");
+        assert_eq!(file.to_string().lines().count(), records.len());
+    }
+
+    #[test]
+    fn test_missing_profile_and_currency_rejected() {
+        let (batch, instructions, mut profiles) = fixture();
+        profiles.remove(&instructions[0].to_participant);
+
+        let result = NachaFile::build(&config(), &batch, &instructions, &profiles, Utc::now());
+        assert!(matches!(result, Err(AppError::Validation(msg)) if msg.contains("Missing settlement profile")));
+
+        let (mut batch, instructions, profiles) = fixture();
+        batch.currency = "EUR".to_string();
+        let result = NachaFile::build(&config(), &batch, &instructions, &profiles, Utc::now());
+        assert!(matches!(result, Err(AppError::Validation(_))));
+
+        // Participants preferring another rail are paid through pacs.008 instead
+        let (batch, instructions, mut profiles) = fixture();
+        let payee = instructions[0].to_participant;
+        profiles.get_mut(&payee).unwrap().rail = PaymentRail::Swift;
+        let result = NachaFile::build(&config(), &batch, &instructions, &profiles, Utc::now());
+        assert!(matches!(result, Err(AppError::Validation(msg)) if msg.contains(&format!("{} (SWIFT)", payee))));
+    }
+
+    #[test]
+    fn test_field_formatting() {
+        assert_eq!(alpha("Acme é corp", 12), "ACME   CORP ");
+        assert_eq!(alpha("truncated value", 5), "TRUNC");
+        assert_eq!(numeric(42, 6), "000042");
+        assert_eq!(numeric(12_345_678_901, 10), "2345678901");
+        assert_eq!(to_cents(dec!(10.5)).unwrap(), 1050);
+        assert!(to_cents(dec!(0.001)).is_err());
+        assert!(to_cents(dec!(100000000)).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! ISO 20022 pacs.008 (FI-to-FI customer credit transfer) generation for net settlement
+//! instructions paid over SEPA or SWIFT.
+//!
+//! Each instruction becomes one credit transfer from the paying participant (debtor) to
+//! the receiving participant (creditor), addressed by the IBAN or account number and BIC
+//! of their settlement profiles. Banks without a BIC are sent as `NOTPROVIDED`, as SEPA
+//! allows.
+
+use super::camt::{amount, escape, timestamp};
+use crate::error::{AppError, Result};
+use crate::models::{PaymentRail, SettlementBatch, SettlementProfile};
+use crate::services::SettlementInstruction;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08";
+
+/// One credit transfer between two participants.
+#[derive(Debug, Clone)]
+pub struct CreditTransfer {
+    pub instruction_id: Uuid,
+    pub amount: Decimal,
+    pub debtor: SettlementProfile,
+    pub creditor: SettlementProfile,
+}
+
+/// A generated pacs.008 message.
+#[derive(Debug, Clone)]
+pub struct Pacs008Message {
+    pub message_id: String,
+    pub batch_id: Uuid,
+    pub currency: String,
+    pub settlement_date: NaiveDate,
+    pub transfers: Vec<CreditTransfer>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Pacs008Message {
+    /// Builds the message for a batch's settlement instructions. Every participant in
+    /// the instructions needs a settlement profile on the SEPA or SWIFT rail;
+    /// zero-amount instructions are skipped.
+    pub fn build(
+        batch: &SettlementBatch,
+        instructions: &[SettlementInstruction],
+        profiles: &HashMap<Uuid, SettlementProfile>,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self> {
+        let mut participants: Vec<Uuid> = instructions
+            .iter()
+            .flat_map(|i| [i.from_participant, i.to_participant])
+            .collect();
+        participants.sort();
+        participants.dedup();
+
+        let mut missing = Vec::new();
+        let mut unusable = Vec::new();
+        for participant in &participants {
+            match profiles.get(participant) {
+                None => missing.push(participant.to_string()),
+                Some(profile) if profile.rail == PaymentRail::Ach || profile.account_identifier().is_none() => {
+                    unusable.push(format!("{} ({})", participant, profile.rail.as_str()))
+                }
+                Some(_) => {}
+            }
+        }
+        if !missing.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Missing settlement profile for participants: {}",
+                missing.join(", ")
+            )));
+        }
+        if !unusable.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Settlement profiles do not settle over SEPA or SWIFT for participants: {}",
+                unusable.join(", ")
+            )));
+        }
+
+        let transfers = instructions
+            .iter()
+            .filter(|instruction| !instruction.amount.is_zero())
+            .map(|instruction| CreditTransfer {
+                instruction_id: instruction.id,
+                amount: instruction.amount,
+                debtor: profiles[&instruction.from_participant].clone(),
+                creditor: profiles[&instruction.to_participant].clone(),
+            })
+            .collect();
+
+        Ok(Self {
+            message_id: Uuid::new_v4().simple().to_string(),
+            batch_id: batch.id,
+            currency: batch.currency.clone(),
+            settlement_date: batch.settlement_date,
+            transfers,
+            created_at,
+        })
+    }
+
+    /// Sum of the transfers' interbank settlement amounts.
+    pub fn total(&self) -> Decimal {
+        self.transfers.iter().map(|transfer| transfer.amount).sum()
+    }
+
+    /// File name the message is exported under.
+    pub fn file_name(&self) -> String {
+        format!("batch-{}.pacs008.xml", self.batch_id)
+    }
+
+    /// Renders the message as an ISO 20022 XML document.
+    pub fn to_xml(&self) -> String {
+        let currency = escape(&self.currency);
+        let mut xml = String::new();
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(xml, "<Document xmlns=\"{}\">", NAMESPACE);
+        xml.push_str("  <FIToFICstmrCdtTrf>\n");
+        let _ = writeln!(
+            xml,
+            "    <GrpHdr><MsgId>{}</MsgId><CreDtTm>{}</CreDtTm><NbOfTxs>{}</NbOfTxs>\
+             <TtlIntrBkSttlmAmt Ccy=\"{}\">{}</TtlIntrBkSttlmAmt><IntrBkSttlmDt>{}</IntrBkSttlmDt>\
+             <SttlmInf><SttlmMtd>CLRG</SttlmMtd></SttlmInf></GrpHdr>",
+            self.message_id,
+            timestamp(self.created_at),
+            self.transfers.len(),
+            currency,
+            amount(self.total()),
+            self.settlement_date,
+        );
+
+        for transfer in &self.transfers {
+            let reference = transfer.instruction_id.simple();
+            let _ = writeln!(
+                xml,
+                "    <CdtTrfTxInf><PmtId><InstrId>{}</InstrId><EndToEndId>{}</EndToEndId><TxId>{}</TxId></PmtId>\
+                 <IntrBkSttlmAmt Ccy=\"{}\">{}</IntrBkSttlmAmt><ChrgBr>SLEV</ChrgBr>\
+                 <Dbtr><Nm>{}</Nm></Dbtr><DbtrAcct>{}</DbtrAcct><DbtrAgt>{}</DbtrAgt>\
+                 <CdtrAgt>{}</CdtrAgt><Cdtr><Nm>{}</Nm></Cdtr><CdtrAcct>{}</CdtrAcct>\
+                 <RmtInf><Ustrd>NET SETTLEMENT {} {}</Ustrd></RmtInf></CdtTrfTxInf>",
+                reference,
+                reference,
+                reference,
+                currency,
+                amount(transfer.amount),
+                escape(&transfer.debtor.account_name),
+                account(&transfer.debtor),
+                agent(&transfer.debtor),
+                agent(&transfer.creditor),
+                escape(&transfer.creditor.account_name),
+                account(&transfer.creditor),
+                self.batch_id.simple(),
+                self.settlement_date,
+            );
+        }
+
+        xml.push_str("  </FIToFICstmrCdtTrf>\n");
+        xml.push_str("</Document>\n");
+        xml
+    }
+}
+
+fn account(profile: &SettlementProfile) -> String {
+    match (&profile.iban, &profile.bank_account_number) {
+        (Some(iban), _) => format!("<Id><IBAN>{}</IBAN></Id>", escape(iban)),
+        (None, Some(number)) => format!("<Id><Othr><Id>{}</Id></Othr></Id>", escape(number)),
+        (None, None) => String::new(),
+    }
+}
+
+fn agent(profile: &SettlementProfile) -> String {
+    match &profile.bic {
+        Some(bic) => format!("<FinInstnId><BICFI>{}</BICFI></FinInstnId>", escape(bic)),
+        None => "<FinInstnId><Othr><Id>NOTPROVIDED</Id></Othr></FinInstnId>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::InstructionType;
+    use rust_decimal_macros::dec;
+
+    fn fixture() -> (SettlementBatch, Vec<SettlementInstruction>, HashMap<Uuid, SettlementProfile>) {
+        let batch = SettlementBatch::new(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(), Utc::now(), "EUR".to_string());
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let instructions = vec![
+            SettlementInstruction::new(batch.id, a, b, dec!(1250.5), "EUR".to_string(), InstructionType::MultilateralNet),
+            SettlementInstruction::new(batch.id, b, a, dec!(0), "EUR".to_string(), InstructionType::MultilateralNet),
+        ];
+        let profiles = HashMap::from([
+            (
+                a,
+                SettlementProfile::international(
+                    a,
+                    "EUR",
+                    "Müller & Co",
+                    "DE89370400440532013000",
+                    Some("DEUTDEFF".to_string()),
+                    PaymentRail::Sepa,
+                ),
+            ),
+            (
+                b,
+                SettlementProfile::international(b, "EUR", "Acme SA", "FR1420041010050500013M02606", None, PaymentRail::Sepa),
+            ),
+        ]);
+        (batch, instructions, profiles)
+    }
+
+    #[test]
+    fn test_credit_transfer_message() {
+        let (batch, instructions, profiles) = fixture();
+        let message = Pacs008Message::build(&batch, &instructions, &profiles, Utc::now()).unwrap();
+        assert_eq!(message.transfers.len(), 1);
+        assert_eq!(message.total(), dec!(1250.5));
+
+        let xml = message.to_xml();
+        assert!(xml.contains(NAMESPACE));
+        assert!(xml.contains("<NbOfTxs>1</NbOfTxs>"));
+        assert!(xml.contains("<TtlIntrBkSttlmAmt Ccy=\"EUR\">1250.50</TtlIntrBkSttlmAmt>"));
+        assert!(xml.contains("<IntrBkSttlmDt>2024-03-15</IntrBkSttlmDt>"));
+        assert!(xml.contains("<Dbtr><Nm>Müller &amp; Co</Nm></Dbtr><DbtrAcct><Id><IBAN>DE89370400440532013000</IBAN></Id></DbtrAcct>"));
+        assert!(xml.contains("<DbtrAgt><FinInstnId><BICFI>DEUTDEFF</BICFI></FinInstnId></DbtrAgt>"));
+        assert!(xml.contains("<CdtrAgt><FinInstnId><Othr><Id>NOTPROVIDED</Id></Othr></FinInstnId></CdtrAgt>"));
+        assert!(xml.contains(&format!("<EndToEndId>{}</EndToEndId>", instructions[0].id.simple())));
+    }
+
+    #[test]
+    fn test_participants_need_international_profiles() {
+        let (batch, instructions, mut profiles) = fixture();
+        let payee = instructions[0].to_participant;
+        profiles.insert(
+            payee,
+            SettlementProfile::new(payee, "Acme", "021000021", "123456789", Default::default()),
+        );
+        let result = Pacs008Message::build(&batch, &instructions, &profiles, Utc::now());
+        assert!(matches!(result, Err(AppError::Validation(msg)) if msg.contains(&format!("{} (ACH)", payee))));
+
+        profiles.remove(&payee);
+        let result = Pacs008Message::build(&batch, &instructions, &profiles, Utc::now());
+        assert!(matches!(result, Err(AppError::Validation(msg)) if msg.contains("Missing settlement profile")));
+    }
+}
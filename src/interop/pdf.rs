@@ -0,0 +1,160 @@
+//! Plain-text PDF documents.
+//!
+//! Writes lines of text as a PDF 1.4 file in 10pt Helvetica on A4 pages, starting a
+//! new page when one is full. Only printable ASCII is drawn; other characters are
+//! replaced with `?`. This is enough for advices and reports read by people, without a
+//! layout engine.
+
+use std::fmt::Write;
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 50;
+const FONT_SIZE: u32 = 10;
+const LEADING: u32 = 14;
+const LINES_PER_PAGE: usize = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+
+/// A document of text lines, rendered one line per row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextDocument {
+    lines: Vec<String>,
+}
+
+impl TextDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a line; an empty line leaves a blank row.
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push(line.into());
+        self
+    }
+
+    /// Number of pages the document renders to; an empty document has one blank page.
+    pub fn page_count(&self) -> usize {
+        self.lines.len().div_ceil(LINES_PER_PAGE).max(1)
+    }
+
+    /// Renders the document as a PDF file.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let pages: Vec<&[String]> = if self.lines.is_empty() {
+            vec![&[]]
+        } else {
+            self.lines.chunks(LINES_PER_PAGE).collect()
+        };
+
+        // Objects 1-3 are the catalog, page tree and font; each page adds a page
+        // object and its content stream.
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..pages.len())
+                    .map(|i| format!("{} 0 R", 4 + 2 * i))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+        ];
+        for (i, lines) in pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                5 + 2 * i
+            ));
+            let content = Self::content_stream(lines);
+            objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+        }
+        let xref = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        pdf.into_bytes()
+    }
+
+    fn content_stream(lines: &[String]) -> String {
+        let mut content = format!(
+            "BT\n/F1 {} Tf\n{} TL\n{} {} Td",
+            FONT_SIZE,
+            LEADING,
+            MARGIN,
+            PAGE_HEIGHT - MARGIN
+        );
+        for line in lines {
+            let _ = write!(content, "\n({}) '", Self::escape(line));
+        }
+        content.push_str("\nET");
+        content
+    }
+
+    /// Escapes a line for a PDF string literal.
+    fn escape(line: &str) -> String {
+        let mut escaped = String::with_capacity(line.len());
+        for c in line.chars() {
+            match c {
+                '(' | ')' | '\\' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                ' '..='~' => escaped.push(c),
+                _ => escaped.push('?'),
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_structure() {
+        let pdf = TextDocument::new().line("Netting advice (batch 1)").line("").line("Net: -25.00").to_pdf();
+        let text = String::from_utf8(pdf).unwrap();
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("(Netting advice \\(batch 1\\)) '"));
+        assert!(text.contains("/Count 1"));
+
+        // Every xref offset points at the start of its object
+        let xref = text.find("\nxref\n").unwrap() + 1;
+        let startxref: usize = text.split("startxref\n").nth(1).unwrap().lines().next().unwrap().parse().unwrap();
+        assert_eq!(startxref, xref);
+        for (i, line) in text[xref..].lines().skip(3).take(5).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_long_documents_span_pages() {
+        let mut document = TextDocument::new();
+        for i in 0..(LINES_PER_PAGE + 1) {
+            document = document.line(format!("Line {} \u{e9}", i));
+        }
+
+        assert_eq!(document.page_count(), 2);
+        let text = String::from_utf8(document.to_pdf()).unwrap();
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("(Line 0 ?) '"));
+    }
+}
@@ -3,12 +3,16 @@ pub mod api;
 pub mod cache;
 pub mod config;
 pub mod core;
+pub mod delivery;
 pub mod error;
 pub mod events;
 pub mod idempotency;
+pub mod interop;
 pub mod models;
 pub mod netting;
+pub mod notifications;
 pub mod observability;
 pub mod persistence;
 pub mod repositories;
 pub mod services;
+pub mod tools;
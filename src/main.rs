@@ -1,12 +1,39 @@
-use settlement_engine::api::{create_router, AppState};
-use settlement_engine::config::Settings;
+use settlement_engine::api::{
+    bind_listeners, create_router, serve, AdmissionConfig, AppState, ListenerConfig, RequestTimeouts, TlsConfig,
+};
+use settlement_engine::cache::{RedisPool, RedisPoolConfig, RedisTopology};
+use settlement_engine::config::{DestinationSettings, LockBackendKind, RedisMode, RemainderStrategy, Settings};
+use settlement_engine::core::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use settlement_engine::core::leader::LeaderElection;
+use settlement_engine::core::locks::DistributedLocks;
+use settlement_engine::core::rounding::{RemainderAllocation, RoundingPolicy};
+use settlement_engine::delivery::{
+    DeliveryChannels, DeliveryTransport, DirectoryTransport, S3Transport, SftpTransport,
+};
+use settlement_engine::events::{
+    CdcConfig, CdcPublisher, CdcPublisherJob, EventProducer, OutboxRelay, OutboxRelayJob, ProducerConfig,
+};
+use settlement_engine::idempotency::{IdempotencyCleanupConfig, IdempotencyCleanupJob};
+use settlement_engine::interop::gl::{GlMapping, GlRule};
+use settlement_engine::interop::nacha::NachaConfig;
+use settlement_engine::models::{InstructionStrategy, InternalAccountDefinition, ProvisioningTrigger};
+use settlement_engine::notifications::{
+    KafkaNotificationSink, NotificationEngine, WebhookNotificationSink,
+};
 use settlement_engine::observability::{
-    init_logging, init_metrics, LogConfig, LogFormat, HealthChecker,
+    init_logging, init_metrics, set_slow_query_threshold, LogConfig, LogFormat, HealthChecker, ReadinessPolicy,
+};
+use settlement_engine::services::{
+    AccountRetentionJob, AccountRetentionPolicy, AccountService, ActivityProjectionJob, ActivityService, AmountLimits, AttestationSigner,
+    BalanceGuardJob, BalanceGuardService, BalanceProjectionJob, BalanceProjectionService, BatchCaps, BatchProvisioningJob, BatchScheduler, BatchTemplateService, ChartOfAccountsService, CircuitBreakingRail, DefaultManagementConfig, DeliveryScheduler,
+    DeliveryService, ExpiryJob, ExpiryPolicy, ExpiryService, ExternalIdPolicy, FeeConfig, FundingConfig, FundingJob, FundingService, FxRevaluationConfig, FxRevaluationJob, FxRevaluationService, LedgerService, NetDebitCapConfig, NettingAdviceConfig, NettingAdviceService, NettingConfig, NettingService, PvpJob, PvpService, ReconciliationJob,
+    ReconciliationService, RiskConfig, RiskService, RtgsConfig, RtgsService, SimulatedRail, SubmissionService, SubmissionWorker,
+    WriteCombiner,
 };
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{ConnectOptions, Connection};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::net::TcpListener;
 use tracing::info;
 
 #[tokio::main]
@@ -32,25 +59,75 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Connect to PostgreSQL
     info!("Connecting to database at {}...", settings.database.url);
+    let slow_query_threshold = Duration::from_millis(settings.database.slow_query_threshold_ms);
+    set_slow_query_threshold(slow_query_threshold);
+    let connect_options = settings
+        .database
+        .url
+        .parse::<PgConnectOptions>()?
+        .statement_cache_capacity(settings.database.statement_cache_capacity)
+        .log_slow_statements(log::LevelFilter::Warn, slow_query_threshold);
+    // Migrations run on a connection of their own, without the statement timeout
+    let migration_options = connect_options.clone();
+    let connect_options = match settings.database.statement_timeout_ms {
+        0 => connect_options,
+        ms => connect_options.options([("statement_timeout", format!("{}ms", ms))]),
+    };
     let pool = PgPoolOptions::new()
         .max_connections(settings.database.pool_size)
-        .acquire_timeout(Duration::from_secs(5))
-        .connect(&settings.database.url)
+        .min_connections(settings.database.min_connections.min(settings.database.pool_size))
+        .acquire_timeout(Duration::from_secs(settings.database.acquire_timeout_secs))
+        .idle_timeout(Duration::from_secs(settings.database.idle_timeout_secs))
+        .max_lifetime(Duration::from_secs(settings.database.max_lifetime_secs))
+        .connect_with(connect_options)
         .await?;
 
     info!("Database connection established");
 
     // Run migrations
     info!("Running database migrations...");
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    let mut migration_conn = migration_options.connect().await?;
+    sqlx::migrate!("./migrations").run(&mut migration_conn).await?;
+    migration_conn.close().await?;
     info!("Migrations applied successfully");
 
+    // Circuit breaker thresholds shared by all external dependencies
+    let circuit = CircuitBreakerConfig {
+        failure_rate_threshold: settings.circuit_breaker.failure_rate_threshold,
+        minimum_calls: settings.circuit_breaker.minimum_calls,
+        window: Duration::from_secs(settings.circuit_breaker.window_secs),
+        open_duration: Duration::from_secs(settings.circuit_breaker.open_secs),
+        half_open_probes: settings.circuit_breaker.half_open_probes,
+    };
+
     // Connect to Redis
-    info!("Connecting to Redis at {}...", settings.redis.url);
-    let redis_client = redis::Client::open(settings.redis.url.clone())?;
-    let mut con = redis_client.get_multiplexed_async_connection().await?;
-    let _: () = redis::cmd("PING").query_async(&mut con).await?;
-    info!("Redis connection established");
+    let topology = match settings.redis.mode {
+        RedisMode::Standalone => RedisTopology::Standalone { url: settings.redis.url.clone() },
+        RedisMode::Cluster => RedisTopology::Cluster { nodes: settings.redis.nodes.clone() },
+        RedisMode::Sentinel => RedisTopology::Sentinel {
+            sentinels: settings.redis.nodes.clone(),
+            master_name: settings
+                .redis
+                .master_name
+                .clone()
+                .ok_or("redis.master_name is required in sentinel mode")?,
+        },
+    };
+    info!("Connecting to Redis ({:?} mode)...", settings.redis.mode);
+    let redis = Arc::new(RedisPool::new(RedisPoolConfig {
+        topology,
+        pool_size: settings.redis.pool_size as usize,
+        connect_timeout: Duration::from_millis(settings.redis.connect_timeout_ms),
+        command_timeout: Duration::from_millis(settings.redis.command_timeout_ms),
+        reconnect_attempts: settings.redis.reconnect_attempts,
+        initial_backoff: Duration::from_millis(settings.redis.reconnect_backoff_ms),
+        max_backoff: Duration::from_millis(settings.redis.reconnect_max_backoff_ms),
+        circuit: circuit.clone(),
+    }));
+    match redis.ping().await {
+        Ok(()) => info!("Redis connection established"),
+        Err(e) => tracing::warn!("Redis unavailable: {}. Continuing; caches fall back to PostgreSQL.", e),
+    }
 
     // Connect to Kafka (with timeout, preserve client)
     info!("Checking Kafka connection...");
@@ -84,26 +161,718 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("System startup verification complete.");
 
     // Create health checker
-    let health_checker = Arc::new(HealthChecker::new(
-        pool.clone(),
-        redis_client.clone(),
-        kafka_client.clone(),
-    ));
+    let readiness_policy = ReadinessPolicy {
+        hard_dependencies: settings.health.hard_dependencies.clone(),
+        allow_degraded_hard: settings.health.allow_degraded_hard,
+        max_error_rate: settings.health.max_error_rate,
+        min_samples: settings.health.min_samples,
+    };
+    let health_checker = Arc::new(
+        HealthChecker::new(pool.clone(), redis.clone(), kafka_client.clone())
+            .with_policy(readiness_policy)
+            .with_window(Duration::from_secs(settings.health.window_secs)),
+    );
+
+    // Shared event producer when Kafka is connected
+    let producer = kafka_client.as_ref().map(|client| {
+        Arc::new(EventProducer::from_client(
+            ProducerConfig {
+                brokers: vec![settings.kafka.brokers.clone()],
+                ..ProducerConfig::default()
+            },
+            client.clone(),
+        )
+        .with_circuit_breaker(Arc::new(CircuitBreaker::new("kafka", circuit.clone()))))
+    });
+
+    // Create alert notification engine (webhooks always, Kafka when connected)
+    let mut notification_engine = NotificationEngine::new(pool.clone())
+        .with_sink(Arc::new(
+            WebhookNotificationSink::new(Duration::from_secs(5))?.with_circuit_breaker(circuit.clone()),
+        ));
+    if let Some(producer) = &producer {
+        notification_engine =
+            notification_engine.with_sink(Arc::new(KafkaNotificationSink::new(producer.clone())));
+    }
+
+    // Create RTGS lane for large-value transactions, or all of them in gross mode
+    let engine_mode = settings.engine_mode;
+    info!("Engine mode: {:?}", engine_mode);
+    let mut rtgs = RtgsService::new(pool.clone())
+        .with_config(RtgsConfig {
+            default_threshold: settings.rtgs.default_threshold,
+            currency_thresholds: settings.rtgs.currency_thresholds.clone(),
+        })
+        .with_mode(engine_mode);
+    if let Some(producer) = &producer {
+        rtgs = rtgs.with_producer(producer.clone());
+    }
 
     // Create application state with metrics handle and health checker
-    let state = AppState::new(pool, redis_client, kafka_client)
+    let mut state = AppState::new(pool, redis, kafka_client)
         .with_metrics(metrics_handle)
-        .with_health_checker(health_checker);
+        .with_health_checker(health_checker)
+        .with_notification_engine(Arc::new(notification_engine))
+        .with_rtgs(Arc::new(rtgs))
+        .with_engine_mode(engine_mode)
+        .with_batch_workers(settings.batching.workers)
+        .with_batch_stall_timeout(Duration::from_secs(settings.batching.stall_timeout_secs))
+        .with_max_cut_off_shift(Duration::from_secs(settings.batching.max_cut_off_shift_secs));
+    let instruction_strategy = if settings.funding.control_account_flow {
+        InstructionStrategy::ControlAccount
+    } else {
+        settings.netting.instruction_strategy
+    };
+    state = state.with_netting(NettingConfig {
+        parallel_threshold: settings.netting.parallel_threshold,
+        instruction_strategy,
+    });
+    let locks = match settings.locks.backend {
+        LockBackendKind::Postgres => DistributedLocks::postgres(state.pool.clone()),
+        LockBackendKind::Redis => DistributedLocks::redis(state.redis.clone(), settings.cache.key_prefix.clone()),
+    };
+    info!("Batch locks kept in {}", locks.backend_name());
+    state = state.with_locks(Arc::new(locks.with_ttl(Duration::from_secs(settings.locks.ttl_secs))));
+    state = state.with_net_debit_caps(NetDebitCapConfig {
+        default_cap: settings.net_debit_caps.default_cap,
+        warning_thresholds: settings.net_debit_caps.warning_thresholds.clone(),
+        on_breach: settings.net_debit_caps.on_breach,
+    });
+    let batching_enabled = engine_mode.batching_enabled();
+    if !batching_enabled && (settings.batching.auto_assign || settings.batching.scheduler_enabled || settings.batching.provisioning_enabled) {
+        tracing::warn!("Batching is disabled in GROSS engine mode; ignoring [batching] auto-assignment and jobs");
+    }
+    state = state.with_batch_caps(BatchCaps {
+        max_transactions: settings.batching.max_transactions,
+        max_gross_amount: settings.batching.max_gross_amount,
+    });
+    if settings.batching.auto_assign && batching_enabled {
+        let batching = state.batch_service();
+        state = state.with_batching(Arc::new(batching));
+    }
+    let mut outbox_relay = None;
+    let mut cdc_publisher = None;
+    if let Some(producer) = producer {
+        let relay = OutboxRelay::new(state.pool.clone(), producer.clone()).with_batch_size(settings.outbox.batch_size);
+        let job = OutboxRelayJob::new(Arc::new(relay), settings.outbox.poll_interval_ms);
+        job.start();
+        outbox_relay = Some(job);
+
+        if settings.cdc.enabled {
+            let publisher = Arc::new(CdcPublisher::new(state.pool.clone(), producer.clone()).with_config(CdcConfig {
+                slot_name: settings.cdc.slot_name.clone(),
+                publication: settings.cdc.publication.clone(),
+                tables: settings.cdc.tables.clone(),
+                topic_prefix: settings.cdc.topic_prefix.clone(),
+                batch_size: settings.cdc.batch_size,
+            }));
+            match publisher.ensure_replication().await {
+                Ok(_) => {
+                    let job = CdcPublisherJob::new(publisher.clone(), settings.cdc.poll_interval_ms);
+                    job.start();
+                    cdc_publisher = Some(job);
+                    state = state.with_cdc(publisher);
+                }
+                Err(e) => tracing::error!("Change data capture disabled: {}", e),
+            }
+        }
+        state = state.with_producer(producer);
+    }
+    if let Some(key) = &settings.finality.signing_key {
+        state = state.with_attestation_signer(Arc::new(AttestationSigner::new(
+            settings.finality.key_id.clone(),
+            key,
+        )));
+    }
+
+    if settings.rail.simulator {
+        let mut rail = SimulatedRail::new().with_ack_after_polls(settings.rail.simulator_ack_polls);
+        if let Some(limit) = settings.rail.simulator_rejection_limit {
+            rail = rail.with_rejection_limit(limit);
+        }
+        state = state.with_rail(Arc::new(CircuitBreakingRail::new(Arc::new(rail), circuit.clone())));
+    }
+
+    if let Some(nacha) = &settings.nacha {
+        state = state.with_nacha(Arc::new(NachaConfig {
+            immediate_destination: nacha.immediate_destination.clone(),
+            immediate_destination_name: nacha.immediate_destination_name.clone(),
+            immediate_origin: nacha.immediate_origin.clone(),
+            immediate_origin_name: nacha.immediate_origin_name.clone(),
+            company_name: nacha.company_name.clone(),
+            company_id: nacha.company_id.clone(),
+            originating_dfi: nacha.originating_dfi.clone(),
+            entry_description: nacha.entry_description.clone(),
+        }));
+    }
+
+    let default_gl = GlMapping::default();
+    state = state.with_gl_mapping(Arc::new(GlMapping {
+        rules: if settings.gl.rules.is_empty() {
+            default_gl.rules
+        } else {
+            settings
+                .gl
+                .rules
+                .iter()
+                .map(|rule| GlRule::new(rule.transaction_type, rule.account_type, rule.gl_code.clone()))
+                .collect()
+        },
+        fee_income_code: settings.gl.fee_income_code.clone().unwrap_or(default_gl.fee_income_code),
+        suspense_code: settings.gl.suspense_code.clone().unwrap_or(default_gl.suspense_code),
+    }));
+
+    // Entries are templates provisioned at startup in their listed currencies and in
+    // any other currency on its first use
+    let templates: Vec<InternalAccountDefinition> = settings
+        .chart_of_accounts
+        .accounts
+        .iter()
+        .map(|account| {
+            let mut definition = InternalAccountDefinition::new(&account.code, &account.name, "", account.role);
+            if let Some(parent) = &account.parent {
+                definition = definition.with_parent(parent);
+            }
+            if let Some(account_type) = account.account_type {
+                definition = definition.with_account_type(account_type);
+            }
+            if let Some(control) = account.control {
+                definition = definition.with_control(control);
+            }
+            definition
+        })
+        .collect();
+    let startup_chart: Vec<InternalAccountDefinition> = settings
+        .chart_of_accounts
+        .accounts
+        .iter()
+        .zip(&templates)
+        .flat_map(|(account, template)| account.currencies.iter().map(|currency| template.in_currency(currency)))
+        .collect();
+    let mut chart = ChartOfAccountsService::new(state.pool.clone());
+    if settings.chart_of_accounts.auto_provision {
+        chart = chart.with_templates(templates);
+    }
+    chart.provision(&startup_chart, ProvisioningTrigger::Startup).await?;
+    let chart = Arc::new(chart);
+
+    state = state.with_fees(Arc::new(FeeConfig {
+        revenue_accounts: settings.fees.revenue_accounts.clone(),
+        reversal_policy: settings.fees.reversal_policy,
+        booking: settings.fees.booking,
+        receivable_accounts: settings.fees.receivable_accounts.clone(),
+        invoice_receivable_accounts: settings.fees.invoice_receivable_accounts.clone(),
+        chart: chart.registry(),
+    }));
+    state = state.with_chart_of_accounts(chart);
+    state = state.with_external_ids(ExternalIdPolicy {
+        scope: settings.external_ids.scope,
+        on_duplicate: settings.external_ids.on_duplicate,
+    });
+    state = state.with_amount_limits(Arc::new(AmountLimits {
+        default_max: settings.amount_limits.default_max,
+        currency_max: settings.amount_limits.currency_max.clone(),
+        max_scale: settings.amount_limits.max_scale,
+    }));
+    let remainder = match (settings.rounding.remainder, settings.rounding.designated_account_id) {
+        (RemainderStrategy::Designated, Some(account_id)) => RemainderAllocation::Designated(account_id),
+        (RemainderStrategy::Designated, None) => {
+            tracing::warn!("rounding.remainder is DESIGNATED without a designated_account_id; using LARGEST_REMAINDER");
+            RemainderAllocation::LargestRemainder
+        }
+        (RemainderStrategy::LargestRemainder, _) => RemainderAllocation::LargestRemainder,
+    };
+    state = state.with_rounding(Arc::new(RoundingPolicy {
+        mode: settings.rounding.mode,
+        scales: settings.rounding.scales.clone(),
+        remainder,
+    }));
+    let mut request_timeouts = RequestTimeouts {
+        default: Duration::from_secs(settings.timeouts.request_timeout_secs),
+        ..RequestTimeouts::default()
+    };
+    for (route, secs) in &settings.timeouts.routes {
+        request_timeouts = request_timeouts.with_route(route.clone(), Duration::from_secs(*secs));
+    }
+    state = state.with_request_timeouts(request_timeouts);
+    state = state.with_admission(AdmissionConfig {
+        max_in_flight: Some(settings.admission.max_in_flight).filter(|max| *max > 0),
+        max_db_latency: Some(settings.admission.max_db_latency_ms)
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis),
+        retry_after: Duration::from_secs(settings.admission.retry_after_secs),
+    });
+    state = state.with_default_management(DefaultManagementConfig {
+        resolution: settings.default_management.resolution,
+        loss_allocation: settings.default_management.loss_allocation,
+    });
+    if settings.risk.enabled {
+        let risk = RiskService::new(state.pool.clone()).with_config(RiskConfig {
+            default_amount_threshold: settings.risk.default_amount_threshold,
+            currency_amount_thresholds: settings.risk.currency_amount_thresholds.clone(),
+            new_counterparty: settings.risk.new_counterparty,
+            velocity_window_secs: settings.risk.velocity_window_secs,
+            velocity_max_count: settings.risk.velocity_max_count,
+            velocity_max_amount: settings.risk.velocity_max_amount,
+            duplicate_window_secs: settings.risk.duplicate_window_secs,
+            duplicate_action: settings.risk.duplicate_action,
+        });
+        state = state.with_risk(Arc::new(risk));
+    }
+
+    if settings.write_combining.enabled {
+        let mut ledger = LedgerService::new(state.pool.clone())
+            .with_fees(state.fees.clone())
+            .with_external_ids(state.external_ids)
+            .with_amount_limits(state.amount_limits.clone());
+        if let Some(batching) = &state.batching {
+            ledger = ledger.with_batching(batching.clone());
+        }
+        let combiner = WriteCombiner::start(
+            Arc::new(ledger),
+            Duration::from_millis(settings.write_combining.window_ms),
+            settings.write_combining.max_batch_size,
+        );
+        state = state.with_write_combiner(Arc::new(combiner));
+    }
+
+    let mut submission_worker = None;
+    if settings.submission.enabled {
+        let mut ledger = LedgerService::new(state.pool.clone())
+            .with_fees(state.fees.clone())
+            .with_external_ids(state.external_ids)
+            .with_amount_limits(state.amount_limits.clone());
+        if let Some(engine) = &state.notification_engine {
+            ledger = ledger.with_notifications(engine.clone());
+        }
+        if let Some(rtgs) = &state.rtgs {
+            ledger = ledger.with_rtgs(rtgs.clone());
+        }
+        if let Some(batching) = &state.batching {
+            ledger = ledger.with_batching(batching.clone());
+        }
+        if let Some(risk) = &state.risk {
+            ledger = ledger.with_risk(risk.clone());
+        }
+        if let Some(combiner) = &state.write_combiner {
+            ledger = ledger.with_write_combining(combiner.clone());
+        }
+        if let Some(chart) = &state.chart {
+            ledger = ledger.with_chart_of_accounts(chart.clone());
+        }
+        let service = Arc::new(
+            SubmissionService::new(state.pool.clone(), Arc::new(ledger))
+                .with_retry_policy(
+                    settings.submission.max_attempts,
+                    chrono::Duration::milliseconds(settings.submission.retry_backoff_ms),
+                )
+                .with_concurrency(settings.submission.batch_size, settings.submission.concurrency),
+        );
+        let worker = SubmissionWorker::new(service.clone(), settings.submission.poll_interval_ms);
+        worker.start();
+        submission_worker = Some(worker);
+        state = state.with_submissions(service);
+    }
+
+    // With several instances, only the elected leader runs the cron-style jobs below
+    let leader = if settings.leader_election.enabled {
+        let election = Arc::new(LeaderElection::new(
+            state.pool.clone(),
+            settings.leader_election.lock_key,
+            Duration::from_secs(settings.leader_election.heartbeat_interval_secs),
+        ));
+        election.start();
+        info!("Leader election enabled as instance {}", election.instance_id());
+        state = state.with_leader(election.clone());
+        Some(election)
+    } else {
+        None
+    };
+
+    let mut idempotency_cleanup = None;
+    if settings.idempotency.cleanup_enabled {
+        let job = IdempotencyCleanupJob::new(
+            state.pool.clone(),
+            IdempotencyCleanupConfig {
+                interval_seconds: settings.idempotency.cleanup_interval_secs,
+                default_retention_seconds: settings.idempotency.default_retention_secs,
+                class_retention_seconds: settings.idempotency.retention_secs.clone(),
+                batch_size: settings.idempotency.cleanup_batch_size,
+                batch_pause_ms: settings.idempotency.cleanup_batch_pause_ms,
+                max_batches_per_run: settings.idempotency.cleanup_max_batches,
+                archive: settings.idempotency.archive,
+            },
+        );
+        if let Some(leader) = &leader {
+            job.control().set_leader(leader.clone());
+        }
+        job.start();
+        state = state.with_job(job.control());
+        idempotency_cleanup = Some(job);
+    }
+
+    let mut delivery_scheduler = None;
+    if !settings.delivery.destinations.is_empty() {
+        let mut channels = DeliveryChannels::new().with_retry_policy(
+            settings.delivery.max_attempts,
+            chrono::Duration::seconds(settings.delivery.retry_backoff_secs),
+        );
+        for (name, destination) in &settings.delivery.destinations {
+            let transport: Arc<dyn DeliveryTransport> = match destination {
+                DestinationSettings::Sftp { host, port, username, identity_file, remote_dir } => {
+                    let mut sftp = SftpTransport::new(host.clone(), *port, username.clone(), remote_dir.clone());
+                    if let Some(identity) = identity_file {
+                        sftp = sftp.with_identity_file(identity);
+                    }
+                    Arc::new(sftp)
+                }
+                DestinationSettings::S3 { endpoint, bucket, region, access_key_id, secret_access_key, prefix } => {
+                    Arc::new(
+                        S3Transport::new(
+                            endpoint.clone(),
+                            bucket.clone(),
+                            region.clone(),
+                            access_key_id.clone(),
+                            secret_access_key.clone(),
+                            Duration::from_secs(30),
+                        )?
+                        .with_prefix(prefix.clone()),
+                    )
+                }
+                DestinationSettings::Directory { path } => Arc::new(DirectoryTransport::new(path)),
+            };
+            info!("Delivery destination '{}' uses {}", name, transport.name());
+            channels = channels.with_destination(name.clone(), transport);
+        }
+
+        let channels = Arc::new(channels);
+        state = state.with_delivery(channels.clone());
+
+        let service = Arc::new(DeliveryService::new(state.pool.clone()).with_channels(channels));
+        let scheduler = DeliveryScheduler::new(service, settings.delivery.poll_interval_secs);
+        scheduler.start();
+        delivery_scheduler = Some(scheduler);
+    }
+
+    if settings.advices.enabled {
+        let mut advices = NettingAdviceService::new(state.pool.clone()).with_config(NettingAdviceConfig {
+            funding_window: chrono::Duration::seconds(settings.advices.funding_window_secs),
+            destination: settings.advices.destination.clone(),
+            webhook_url: settings.advices.webhook_url.clone(),
+            webhook_timeout: Duration::from_secs(settings.advices.webhook_timeout_secs),
+        })
+        .with_netting(state.netting.clone());
+        if let Some(channels) = &state.delivery {
+            advices = advices.with_delivery(channels.clone());
+        }
+        state = state.with_advices(Arc::new(advices));
+    }
+
+    let mut funding_job = None;
+    if settings.funding.enabled {
+        let service = Arc::new(
+            FundingService::new(state.pool.clone())
+                .with_config(FundingConfig {
+                    funding_window: chrono::Duration::seconds(settings.advices.funding_window_secs),
+                    freeze_participant: settings.funding.freeze_participant,
+                    declare_default: settings.funding.declare_default,
+                })
+                .with_default_management(state.default_management)
+                .with_rounding(state.rounding.clone())
+                .with_netting(state.netting.clone())
+                .with_batch_size(settings.funding.batch_size),
+        );
+        state = state.with_funding(service.clone());
+
+        let mut job = FundingJob::new(service, settings.funding.sweep_interval_secs);
+        if let Some(leader) = &leader {
+            job = job.with_leader(leader.clone());
+        }
+        job.start();
+        funding_job = Some(job);
+    }
+
+    state = state.with_pvp_timeout(Duration::from_secs(settings.pvp.timeout_secs));
+    let mut pvp_job = None;
+    if settings.pvp.enabled {
+        let mut ledger = LedgerService::new(state.pool.clone())
+            .with_fees(state.fees.clone())
+            .with_external_ids(state.external_ids)
+            .with_amount_limits(state.amount_limits.clone());
+        if let Some(chart) = &state.chart {
+            ledger = ledger.with_chart_of_accounts(chart.clone());
+        }
+        let service = Arc::new(
+            PvpService::new(state.pool.clone(), Arc::new(ledger))
+                .with_timeout(state.pvp_timeout)
+                .with_batch_size(settings.pvp.batch_size),
+        );
+        let mut job = PvpJob::new(service, settings.pvp.sweep_interval_secs);
+        if let Some(leader) = &leader {
+            job = job.with_leader(leader.clone());
+        }
+        job.start();
+        pvp_job = Some(job);
+    }
+
+    let fx_revaluation = FxRevaluationConfig {
+        functional_currency: settings.fx_revaluation.functional_currency.clone(),
+        pnl_account_id: settings.fx_revaluation.pnl_account_id,
+    };
+    state = state.with_fx_revaluation(fx_revaluation.clone());
+    let mut fx_revaluation_job = None;
+    if settings.fx_revaluation.enabled {
+        let service = Arc::new(
+            FxRevaluationService::new(state.pool.clone())
+                .with_config(fx_revaluation)
+                .with_rounding(state.rounding.clone()),
+        );
+        let mut job = FxRevaluationJob::new(service, settings.fx_revaluation.check_interval_secs);
+        if let Some(leader) = &leader {
+            job = job.with_leader(leader.clone());
+        }
+        job.start();
+        fx_revaluation_job = Some(job);
+    }
+
+    let mut batch_scheduler = None;
+    if settings.batching.scheduler_enabled && batching_enabled {
+        let mut service = state
+            .batch_service()
+            .with_workers(state.batch_workers)
+            .with_stall_timeout(state.batch_stall_timeout)
+            .with_locks(state.locks.clone())
+            .with_fees(state.fees.clone())
+            .with_netting(state.netting.clone());
+        if let Some(producer) = &state.producer {
+            service = service.with_producer(producer.clone());
+        }
+        if let Some(rail) = &state.rail {
+            service = service.with_rail(rail.clone());
+        }
+        if let Some(advices) = &state.advices {
+            service = service.with_advices(advices.clone());
+        }
+        if let Some(funding) = &state.funding {
+            service = service.with_funding(funding.clone());
+        }
+        let scheduler = BatchScheduler::new(Arc::new(service), settings.batching.scheduler_interval_secs);
+        if let Some(leader) = &leader {
+            scheduler.control().set_leader(leader.clone());
+        }
+        scheduler.start();
+        state = state.with_job(scheduler.control());
+        batch_scheduler = Some(scheduler);
+    }
+
+    let mut batch_provisioning = None;
+    if settings.batching.provisioning_enabled && batching_enabled {
+        let job = BatchProvisioningJob::new(
+            Arc::new(BatchTemplateService::new(state.pool.clone())),
+            settings.batching.provisioning_interval_secs,
+            settings.batching.provision_after,
+        );
+        if let Some(leader) = &leader {
+            job.control().set_leader(leader.clone());
+        }
+        job.start();
+        state = state.with_job(job.control());
+        batch_provisioning = Some(job);
+    }
+
+    // Re-export today's persisted netting metrics so the gauges survive restarts
+    if let Err(e) = NettingService::new(state.pool.clone())
+        .export_daily_metrics(chrono::Utc::now().date_naive())
+        .await
+    {
+        tracing::warn!("Failed to export netting metrics: {}", e);
+    }
+
+    let mut account_retention = None;
+    if settings.retention.enabled {
+        let mut job = AccountRetentionJob::new(
+            Arc::new(AccountService::new(state.pool.clone())),
+            AccountRetentionPolicy {
+                anonymize_closed_after: chrono::Duration::days(settings.retention.anonymize_closed_after_days),
+                batch_size: settings.retention.batch_size,
+            },
+            settings.retention.interval_secs,
+        );
+        if let Some(leader) = &leader {
+            job = job.with_leader(leader.clone());
+        }
+        job.start();
+        account_retention = Some(job);
+    }
+
+    let mut reconciliation = None;
+    if settings.reconciliation.enabled {
+        let mut service = ReconciliationService::new(state.pool.clone());
+        if let Some(engine) = &state.notification_engine {
+            service = service.with_notifications(engine.clone());
+        }
+        let mut job = ReconciliationJob::new(Arc::new(service), settings.reconciliation.interval_secs);
+        if let Some(leader) = &leader {
+            job = job.with_leader(leader.clone());
+        }
+        job.start();
+        reconciliation = Some(job);
+    }
+
+    let mut balance_guard = None;
+    if settings.balance_guard.enabled {
+        let mut service =
+            BalanceGuardService::new(state.pool.clone()).with_batch_size(settings.balance_guard.batch_size);
+        if let Some(engine) = &state.notification_engine {
+            service = service.with_notifications(engine.clone());
+        }
+        let mut job = BalanceGuardJob::new(Arc::new(service), settings.balance_guard.interval_secs);
+        if let Some(leader) = &leader {
+            job = job.with_leader(leader.clone());
+        }
+        job.start();
+        balance_guard = Some(job);
+    }
+
+    let mut expiry = None;
+    if settings.expiry.enabled {
+        let policy = ExpiryPolicy {
+            default_ttl_secs: settings.expiry.default_ttl_secs,
+            ttl_secs: settings
+                .expiry
+                .ttl_secs
+                .iter()
+                .map(|(transaction_type, ttl)| (transaction_type.to_uppercase(), *ttl))
+                .collect(),
+        };
+        let service = ExpiryService::new(state.pool.clone())
+            .with_policy(policy)
+            .with_batch_size(settings.expiry.batch_size);
+        let mut job = ExpiryJob::new(Arc::new(service), settings.expiry.sweep_interval_secs);
+        if let Some(leader) = &leader {
+            job = job.with_leader(leader.clone());
+        }
+        job.start();
+        expiry = Some(job);
+    }
+
+    let mut activity_projection = None;
+    if settings.activity.enabled {
+        let service = ActivityService::new(state.pool.clone()).with_batch_size(settings.activity.batch_size);
+        let job = ActivityProjectionJob::new(Arc::new(service), settings.activity.poll_interval_ms);
+        job.start();
+        activity_projection = Some(job);
+    }
+
+    let mut balance_projection = None;
+    if settings.balance_projection.enabled {
+        let service = BalanceProjectionService::new(state.pool.clone())
+            .with_batch_size(settings.balance_projection.batch_size)
+            .with_accounts_per_pass(settings.balance_projection.accounts_per_pass);
+        let job = BalanceProjectionJob::new(Arc::new(service), settings.balance_projection.poll_interval_ms);
+        job.start();
+        balance_projection = Some(job);
+    }
 
     // Create API router
     let app = create_router(state);
 
     // Start HTTP server
-    let addr = format!("0.0.0.0:{}", settings.application.port);
-    info!("Starting HTTP server on {}", addr);
-    
-    let listener = TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    let listeners = bind_listeners(&ListenerConfig {
+        addr: ([0, 0, 0, 0], settings.application.port).into(),
+        listeners: settings.application.listeners,
+        reuse_port: settings.application.reuse_port,
+    })?;
+    let tls = settings.tls.as_ref().map(|tls| TlsConfig {
+        cert_path: tls.cert_path.clone().into(),
+        key_path: tls.key_path.clone().into(),
+        client_ca_path: tls.client_ca_path.clone().map(Into::into),
+        require_client_cert: tls.require_client_cert,
+        api_key_bindings: tls.api_key_bindings.clone(),
+    });
+    info!(
+        "Starting {} server on port {} ({} listener(s))",
+        if tls.is_some() { "HTTPS" } else { "HTTP" },
+        settings.application.port,
+        listeners.len()
+    );
+    serve(app, listeners, tls, shutdown_signal()).await?;
+
+    if let Some(scheduler) = delivery_scheduler {
+        scheduler.stop();
+    }
+    if let Some(job) = idempotency_cleanup {
+        job.stop();
+    }
+    if let Some(scheduler) = batch_scheduler {
+        scheduler.stop();
+    }
+    if let Some(job) = batch_provisioning {
+        job.stop();
+    }
+    if let Some(job) = account_retention {
+        job.stop();
+    }
+    if let Some(job) = reconciliation {
+        job.stop();
+    }
+    if let Some(job) = expiry {
+        job.stop();
+    }
+    if let Some(job) = funding_job {
+        job.stop();
+    }
+    if let Some(job) = pvp_job {
+        job.stop();
+    }
+    if let Some(job) = fx_revaluation_job {
+        job.stop();
+    }
+    if let Some(job) = balance_guard {
+        job.stop();
+    }
+    if let Some(job) = activity_projection {
+        job.stop();
+    }
+    if let Some(job) = balance_projection {
+        job.stop();
+    }
+    if let Some(worker) = submission_worker {
+        worker.stop();
+    }
+    if let Some(job) = cdc_publisher {
+        job.stop();
+    }
+    if let Some(election) = leader {
+        election.stop();
+    }
+    if let Some(job) = outbox_relay {
+        job.stop();
+    }
 
     Ok(())
 }
+
+/// Resolves on Ctrl+C or SIGTERM, so in-flight requests can drain before exit.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    info!("Shutdown signal received, draining connections");
+}
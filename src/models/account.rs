@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::AccountStatusChange;
+
 /// Account types following double-entry bookkeeping principles.
 /// Each type has a "normal balance" that determines how debits and credits affect it.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
@@ -66,10 +68,15 @@ pub struct Account {
     pub status: AccountStatus,
     pub currency: String,
     pub metadata: Option<serde_json::Value>,
+    /// When the account's personal data was scrubbed, if it has been.
+    pub anonymized_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Name given to accounts whose personal data has been scrubbed.
+pub const ANONYMIZED_ACCOUNT_NAME: &str = "Anonymized account";
+
 impl Account {
     /// Creates a new Account with the given parameters.
     pub fn new(
@@ -87,6 +94,7 @@ impl Account {
             status: AccountStatus::Active,
             currency,
             metadata: None,
+            anonymized_at: None,
             created_at: now,
             updated_at: now,
         }
@@ -127,6 +135,28 @@ impl Account {
             self.updated_at = Utc::now();
         }
     }
+
+    /// Returns true once the account's personal data has been scrubbed.
+    pub fn is_anonymized(&self) -> bool {
+        self.anonymized_at.is_some()
+    }
+
+    /// External ID that replaces the original one on anonymization. Derived from the
+    /// account ID so it stays unique without revealing the original value.
+    pub fn anonymized_external_id(id: Uuid) -> String {
+        format!("anonymized-{}", id.simple())
+    }
+
+    /// Describes the account's status for error messages, including the reason recorded
+    /// by the change that set it when one is known.
+    pub fn status_description(&self, change: Option<&AccountStatusChange>) -> String {
+        match change {
+            Some(change) if change.to_status == self.status => {
+                format!("status: {:?}, reason: {}", self.status, change.reason())
+            }
+            _ => format!("status: {:?}", self.status),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +209,31 @@ mod tests {
         assert_eq!(account.metadata, Some(metadata));
     }
 
+    #[test]
+    fn test_status_description_includes_reason() {
+        use crate::models::{StatusChangeReason, StatusReasonCode};
+
+        let mut account = Account::new(
+            "EXT-001".to_string(),
+            "Test Account".to_string(),
+            AccountType::Asset,
+            "USD".to_string(),
+        );
+        account.freeze();
+        let change = AccountStatusChange::new(
+            account.id,
+            AccountStatus::Active,
+            AccountStatus::Frozen,
+            StatusChangeReason::new(StatusReasonCode::ComplianceReview).with_note("KYC refresh"),
+        );
+
+        assert_eq!(
+            account.status_description(Some(&change)),
+            "status: Frozen, reason: COMPLIANCE_REVIEW: KYC refresh"
+        );
+        assert_eq!(account.status_description(None), "status: Frozen");
+    }
+
     #[test]
     fn test_account_freeze_and_activate() {
         let mut account = Account::new(
@@ -0,0 +1,179 @@
+use crate::models::TransactionType;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Period activity is summarised over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityGranularity {
+    #[default]
+    Day,
+    /// ISO weeks, starting on Monday.
+    Week,
+    Month,
+}
+
+impl ActivityGranularity {
+    /// First day of the period containing `date`.
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ActivityGranularity::Day => date,
+            ActivityGranularity::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            ActivityGranularity::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// One account's settled activity of one transaction type on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct AccountActivity {
+    pub account_id: Uuid,
+    pub activity_date: NaiveDate,
+    pub currency: String,
+    pub transaction_type: TransactionType,
+    pub transaction_count: i64,
+    /// Amounts credited to the account.
+    pub total_in: Decimal,
+    /// Amounts debited from the account, fees included.
+    pub total_out: Decimal,
+    /// Fees on transactions the account paid for.
+    pub total_fees: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Activity of one transaction type within a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityTypeSummary {
+    pub transaction_type: TransactionType,
+    pub transaction_count: i64,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+    pub total_fees: Decimal,
+}
+
+/// An account's activity in one currency over one period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityPeriod {
+    pub period_start: NaiveDate,
+    pub currency: String,
+    pub transaction_count: i64,
+    pub total_in: Decimal,
+    pub total_out: Decimal,
+    pub total_fees: Decimal,
+    pub by_type: Vec<ActivityTypeSummary>,
+}
+
+impl ActivityPeriod {
+    /// Rolls daily activity up into periods, ordered by period and currency.
+    pub fn rollup(days: &[AccountActivity], granularity: ActivityGranularity) -> Vec<ActivityPeriod> {
+        let mut periods: BTreeMap<(NaiveDate, String), ActivityPeriod> = BTreeMap::new();
+
+        for day in days {
+            let period_start = granularity.period_start(day.activity_date);
+            let period = periods
+                .entry((period_start, day.currency.clone()))
+                .or_insert_with(|| ActivityPeriod {
+                    period_start,
+                    currency: day.currency.clone(),
+                    transaction_count: 0,
+                    total_in: Decimal::ZERO,
+                    total_out: Decimal::ZERO,
+                    total_fees: Decimal::ZERO,
+                    by_type: Vec::new(),
+                });
+            period.transaction_count += day.transaction_count;
+            period.total_in += day.total_in;
+            period.total_out += day.total_out;
+            period.total_fees += day.total_fees;
+
+            match period
+                .by_type
+                .iter_mut()
+                .find(|summary| summary.transaction_type == day.transaction_type)
+            {
+                Some(summary) => {
+                    summary.transaction_count += day.transaction_count;
+                    summary.total_in += day.total_in;
+                    summary.total_out += day.total_out;
+                    summary.total_fees += day.total_fees;
+                }
+                None => period.by_type.push(ActivityTypeSummary {
+                    transaction_type: day.transaction_type,
+                    transaction_count: day.transaction_count,
+                    total_in: day.total_in,
+                    total_out: day.total_out,
+                    total_fees: day.total_fees,
+                }),
+            }
+        }
+
+        periods.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn day(date: NaiveDate, transaction_type: TransactionType, total_in: Decimal, total_out: Decimal) -> AccountActivity {
+        AccountActivity {
+            account_id: Uuid::nil(),
+            activity_date: date,
+            currency: "USD".to_string(),
+            transaction_type,
+            transaction_count: 1,
+            total_in,
+            total_out,
+            total_fees: Decimal::ZERO,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_period_start() {
+        // A Thursday
+        let date = NaiveDate::from_ymd_opt(2024, 2, 15).unwrap();
+
+        assert_eq!(ActivityGranularity::Day.period_start(date), date);
+        assert_eq!(
+            ActivityGranularity::Week.period_start(date),
+            NaiveDate::from_ymd_opt(2024, 2, 12).unwrap()
+        );
+        assert_eq!(
+            ActivityGranularity::Month.period_start(date),
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rollup_by_period_and_type() {
+        let monday = NaiveDate::from_ymd_opt(2024, 2, 12).unwrap();
+        let days = vec![
+            day(monday, TransactionType::Payment, dec!(100), dec!(0)),
+            day(monday, TransactionType::Refund, dec!(0), dec!(20)),
+            day(monday + Duration::days(2), TransactionType::Payment, dec!(50), dec!(10)),
+        ];
+
+        let daily = ActivityPeriod::rollup(&days, ActivityGranularity::Day);
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].transaction_count, 2);
+        assert_eq!(daily[0].by_type.len(), 2);
+
+        let weekly = ActivityPeriod::rollup(&days, ActivityGranularity::Week);
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].period_start, monday);
+        assert_eq!(weekly[0].transaction_count, 3);
+        assert_eq!(weekly[0].total_in, dec!(150));
+        assert_eq!(weekly[0].total_out, dec!(30));
+
+        let payments = &weekly[0].by_type[0];
+        assert_eq!(payments.transaction_type, TransactionType::Payment);
+        assert_eq!(payments.transaction_count, 2);
+        assert_eq!(payments.total_in, dec!(150));
+    }
+}
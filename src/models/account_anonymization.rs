@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Account fields holding personal data, in the order they are scrubbed.
+pub const ACCOUNT_PII_FIELDS: [&str; 6] = [
+    "name",
+    "external_id",
+    "metadata",
+    "settlement_profile",
+    "identifiers",
+    "status_history_notes",
+];
+
+/// Audit record of an account's personal data being scrubbed. It deliberately keeps
+/// none of the removed values.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AccountAnonymization {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    /// Operator or policy that requested the anonymization.
+    pub requested_by: String,
+    pub reason: String,
+    pub scrubbed_fields: Vec<String>,
+    pub anonymized_at: DateTime<Utc>,
+}
+
+impl AccountAnonymization {
+    pub fn new(account_id: Uuid, requested_by: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            account_id,
+            requested_by: requested_by.into(),
+            reason: reason.into(),
+            scrubbed_fields: ACCOUNT_PII_FIELDS.iter().map(|f| f.to_string()).collect(),
+            anonymized_at: Utc::now(),
+        }
+    }
+}
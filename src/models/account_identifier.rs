@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::settlement_profile::is_valid_iban;
+
+/// Kind of secondary identifier an account can be addressed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "account_identifier_type", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountIdentifierType {
+    /// International bank account number, checked with its mod-97 check digits.
+    Iban,
+    /// Code an upstream system uses for the account, e.g. a ledger or cost center code.
+    InternalCode,
+    /// Free-form nickname.
+    Alias,
+}
+
+/// A secondary identifier of an account. Identifiers are unique across all accounts
+/// and types, so a lookup by identifier alone finds at most one account.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AccountIdentifier {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub identifier_type: AccountIdentifierType,
+    /// The identifier in normalized form; see `normalize`.
+    pub identifier: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AccountIdentifier {
+    /// Creates an identifier, normalizing and checking the value.
+    pub fn new(account_id: Uuid, identifier_type: AccountIdentifierType, identifier: &str) -> Result<Self, String> {
+        let identifier = Self::normalize(identifier);
+        if identifier.is_empty() || identifier.len() > 255 {
+            return Err("Identifier must be 1 to 255 characters".to_string());
+        }
+        if identifier_type == AccountIdentifierType::Iban && !is_valid_iban(&identifier) {
+            return Err(format!("'{}' is not a valid IBAN", identifier));
+        }
+        Ok(Self {
+            id: Uuid::new_v4(),
+            account_id,
+            identifier_type,
+            identifier,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Normalized form identifiers are stored and looked up in: upper case, without
+    /// spaces, so `gb82 west 1234 5698 7654 32` and `GB82WEST12345698765432` match.
+    pub fn normalize(identifier: &str) -> String {
+        identifier
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers_are_normalized_and_checked() {
+        let account_id = Uuid::new_v4();
+        let iban = AccountIdentifier::new(account_id, AccountIdentifierType::Iban, "gb82 west 1234 5698 7654 32").unwrap();
+        assert_eq!(iban.identifier, "GB82WEST12345698765432");
+        assert!(AccountIdentifier::new(account_id, AccountIdentifierType::Iban, "GB00WEST12345698765432").is_err());
+
+        let alias = AccountIdentifier::new(account_id, AccountIdentifierType::Alias, " acme-ops ").unwrap();
+        assert_eq!(alias.identifier, "ACME-OPS");
+        assert!(AccountIdentifier::new(account_id, AccountIdentifierType::Alias, "   ").is_err());
+        assert_eq!(AccountIdentifier::normalize("Cc 104"), "CC104");
+    }
+}
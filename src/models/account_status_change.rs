@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::AccountStatus;
+
+/// Why an account's status was changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "status_reason_code", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatusReasonCode {
+    /// The account holder asked for the change.
+    CustomerRequest,
+    /// Held while compliance reviews the account.
+    ComplianceReview,
+    /// Activity on the account looks fraudulent.
+    SuspectedFraud,
+    /// Required by a regulator or court.
+    RegulatoryOrder,
+    /// Exposure or balance is outside credit limits.
+    CreditRisk,
+    /// No activity for an extended period.
+    Dormant,
+    /// A previous review found no issue.
+    ReviewCleared,
+    /// Frozen automatically after a balance fell below its allowed floor.
+    BalanceFloorBreach,
+    /// Frozen after failing to fund its net obligation in a settlement batch.
+    ParticipantDefault,
+    /// Frozen after missing the funding deadline of its net obligation in a batch.
+    FundingOverdue,
+    /// Anything else; the note should explain.
+    Other,
+}
+
+impl StatusReasonCode {
+    /// Returns the code as stored and returned by the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusReasonCode::CustomerRequest => "CUSTOMER_REQUEST",
+            StatusReasonCode::ComplianceReview => "COMPLIANCE_REVIEW",
+            StatusReasonCode::SuspectedFraud => "SUSPECTED_FRAUD",
+            StatusReasonCode::RegulatoryOrder => "REGULATORY_ORDER",
+            StatusReasonCode::CreditRisk => "CREDIT_RISK",
+            StatusReasonCode::Dormant => "DORMANT",
+            StatusReasonCode::ReviewCleared => "REVIEW_CLEARED",
+            StatusReasonCode::BalanceFloorBreach => "BALANCE_FLOOR_BREACH",
+            StatusReasonCode::ParticipantDefault => "PARTICIPANT_DEFAULT",
+            StatusReasonCode::FundingOverdue => "FUNDING_OVERDUE",
+            StatusReasonCode::Other => "OTHER",
+        }
+    }
+}
+
+/// Reason supplied when freezing, activating or closing an account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChangeReason {
+    pub code: StatusReasonCode,
+    pub note: Option<String>,
+}
+
+impl StatusChangeReason {
+    pub fn new(code: StatusReasonCode) -> Self {
+        Self { code, note: None }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// A recorded transition of an account's status.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AccountStatusChange {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub from_status: AccountStatus,
+    pub to_status: AccountStatus,
+    pub reason_code: StatusReasonCode,
+    pub note: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl AccountStatusChange {
+    /// Creates a status change record for the given transition.
+    pub fn new(
+        account_id: Uuid,
+        from_status: AccountStatus,
+        to_status: AccountStatus,
+        reason: StatusChangeReason,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            account_id,
+            from_status,
+            to_status,
+            reason_code: reason.code,
+            note: reason.note,
+            changed_at: Utc::now(),
+        }
+    }
+
+    /// Describes the reason for use in messages, e.g. `SUSPECTED_FRAUD: chargeback spike`.
+    pub fn reason(&self) -> String {
+        match &self.note {
+            Some(note) => format!("{}: {}", self.reason_code.as_str(), note),
+            None => self.reason_code.as_str().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_change_reason() {
+        let change = AccountStatusChange::new(
+            Uuid::new_v4(),
+            AccountStatus::Active,
+            AccountStatus::Frozen,
+            StatusChangeReason::new(StatusReasonCode::SuspectedFraud).with_note("chargeback spike"),
+        );
+
+        assert_eq!(change.reason_code, StatusReasonCode::SuspectedFraud);
+        assert_eq!(change.reason(), "SUSPECTED_FRAUD: chargeback spike");
+
+        let change = AccountStatusChange::new(
+            Uuid::new_v4(),
+            AccountStatus::Frozen,
+            AccountStatus::Active,
+            StatusChangeReason::new(StatusReasonCode::ReviewCleared),
+        );
+        assert_eq!(change.reason(), "REVIEW_CLEARED");
+    }
+
+    #[test]
+    fn test_reason_code_matches_serde() {
+        let json = serde_json::to_value(StatusReasonCode::ComplianceReview).unwrap();
+        assert_eq!(json, StatusReasonCode::ComplianceReview.as_str());
+    }
+}
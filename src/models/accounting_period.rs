@@ -0,0 +1,129 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Whether entries can still be posted to an accounting period.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "accounting_period_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PeriodStatus {
+    #[default]
+    Open,
+    Closed,
+}
+
+/// A calendar month of the ledger. Entries can be backdated into it while it is open.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct AccountingPeriod {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub status: PeriodStatus,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AccountingPeriod {
+    /// The open period containing a date, as it is before any status change is stored.
+    pub fn containing(date: NaiveDate) -> Self {
+        let period_start = date.with_day(1).expect("first of the month is always valid");
+        let period_end = period_start + Months::new(1) - chrono::Duration::days(1);
+        Self {
+            period_start,
+            period_end,
+            status: PeriodStatus::Open,
+            closed_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.period_start <= date && date <= self.period_end
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.status == PeriodStatus::Open
+    }
+}
+
+/// Which date of an entry places it in time when balances are rebuilt for past days.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BalanceBasis {
+    /// The entry's effective (value) date, which may be backdated.
+    #[default]
+    Value,
+    /// The day the entry was posted (UTC).
+    Booking,
+}
+
+/// An account's closing balance on a day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatedBalance {
+    pub date: NaiveDate,
+    pub balance: Decimal,
+}
+
+impl DatedBalance {
+    /// Rebuilds closing balances for each day from `from` to `to` by walking back from
+    /// the current balance. `movements` are net movements (credits less debits) per
+    /// day, and must include every day after `from` that has any, up to today.
+    pub fn series(current: Decimal, movements: &[(NaiveDate, Decimal)], from: NaiveDate, to: NaiveDate) -> Vec<Self> {
+        let mut balance = current
+            - movements
+                .iter()
+                .filter(|(date, _)| *date > to)
+                .map(|(_, amount)| *amount)
+                .sum::<Decimal>();
+        let mut series = Vec::new();
+        let mut date = to;
+        while date >= from {
+            series.push(Self { date, balance });
+            balance -= movements
+                .iter()
+                .filter(|(day, _)| *day == date)
+                .map(|(_, amount)| *amount)
+                .sum::<Decimal>();
+            date = match date.pred_opt() {
+                Some(previous) => previous,
+                None => break,
+            };
+        }
+        series.reverse();
+        series
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_period_containing() {
+        let period = AccountingPeriod::containing(NaiveDate::from_ymd_opt(2024, 2, 17).unwrap());
+        assert_eq!(period.period_start, NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        assert_eq!(period.period_end, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert!(period.contains(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()));
+        assert!(!period.contains(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()));
+
+        let december = AccountingPeriod::containing(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
+        assert_eq!(december.period_end, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
+    }
+
+    #[test]
+    fn test_balance_series_walks_back_from_current() {
+        // Balance is 150 now: +100 on the 2nd, -30 on the 4th, +80 on the 6th
+        let movements = vec![(day(2), dec!(100)), (day(4), dec!(-30)), (day(6), dec!(80))];
+        let series = DatedBalance::series(dec!(150), &movements, day(1), day(4));
+
+        let balances: Vec<Decimal> = series.iter().map(|b| b.balance).collect();
+        assert_eq!(balances, vec![dec!(0), dec!(100), dec!(100), dec!(70)]);
+        assert_eq!(series[0].date, day(1));
+        assert_eq!(series[3].date, day(4));
+    }
+}
@@ -0,0 +1,214 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Condition an alert rule watches for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "alert_rule_type", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlertRuleType {
+    /// Available balance dropped below the threshold.
+    LowBalance,
+    /// Available balance rose above the threshold.
+    HighBalance,
+    /// A single transaction amount met or exceeded the threshold.
+    LargeTransaction,
+    /// A transaction failed to settle.
+    FailedSettlement,
+    /// Reconciliation found the stored balance out of line with the ledger.
+    BalanceBreak,
+    /// A balance fell below its allowed floor and the account was frozen.
+    NegativeBalance,
+}
+
+impl AlertRuleType {
+    /// Returns true if the rule type needs a threshold to be evaluated.
+    pub fn requires_threshold(&self) -> bool {
+        !matches!(
+            self,
+            AlertRuleType::FailedSettlement | AlertRuleType::BalanceBreak | AlertRuleType::NegativeBalance
+        )
+    }
+}
+
+/// A notification rule evaluated against transactions and balances.
+///
+/// Rules without an `account_id` apply to every account in the rule's currency.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AlertRule {
+    pub id: Uuid,
+    pub account_id: Option<Uuid>,
+    pub rule_type: AlertRuleType,
+    pub currency: String,
+    pub threshold: Option<Decimal>,
+    pub webhook_url: Option<String>,
+    pub suppression_window_seconds: i32,
+    pub enabled: bool,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AlertRule {
+    /// Creates a new enabled AlertRule without suppression.
+    pub fn new(rule_type: AlertRuleType, currency: String, threshold: Option<Decimal>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            account_id: None,
+            rule_type,
+            currency,
+            threshold,
+            webhook_url: None,
+            suppression_window_seconds: 0,
+            enabled: true,
+            last_triggered_at: None,
+            metadata: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Scopes the rule to a single account.
+    pub fn for_account(mut self, account_id: Uuid) -> Self {
+        self.account_id = Some(account_id);
+        self
+    }
+
+    /// Sets the webhook URL alerts for this rule are delivered to.
+    pub fn with_webhook_url(mut self, url: String) -> Self {
+        self.webhook_url = Some(url);
+        self
+    }
+
+    /// Sets the suppression window in seconds.
+    pub fn with_suppression_window(mut self, seconds: i32) -> Self {
+        self.suppression_window_seconds = seconds;
+        self
+    }
+
+    /// Creates a new AlertRule with metadata.
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Returns true if the rule applies to the given account and currency.
+    pub fn applies_to(&self, account_id: Uuid, currency: &str) -> bool {
+        self.enabled
+            && self.currency == currency
+            && (self.account_id.is_none() || self.account_id == Some(account_id))
+    }
+
+    /// Returns true if the rule fired within its suppression window.
+    pub fn is_suppressed(&self, now: DateTime<Utc>) -> bool {
+        match self.last_triggered_at {
+            Some(last) if self.suppression_window_seconds > 0 => {
+                now < last + Duration::seconds(self.suppression_window_seconds as i64)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the available balance breaches a balance threshold rule.
+    pub fn is_breached_by_balance(&self, available_balance: Decimal) -> bool {
+        match (self.rule_type, self.threshold) {
+            (AlertRuleType::LowBalance, Some(threshold)) => available_balance < threshold,
+            (AlertRuleType::HighBalance, Some(threshold)) => available_balance > threshold,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the transaction amount breaches a large-transaction rule.
+    pub fn is_breached_by_amount(&self, amount: Decimal) -> bool {
+        match (self.rule_type, self.threshold) {
+            (AlertRuleType::LargeTransaction, Some(threshold)) => amount >= threshold,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_alert_rule_creation() {
+        let account_id = Uuid::new_v4();
+        let rule = AlertRule::new(AlertRuleType::LowBalance, "USD".to_string(), Some(dec!(100)))
+            .for_account(account_id)
+            .with_suppression_window(300);
+
+        assert_eq!(rule.account_id, Some(account_id));
+        assert_eq!(rule.suppression_window_seconds, 300);
+        assert!(rule.enabled);
+        assert!(rule.last_triggered_at.is_none());
+    }
+
+    #[test]
+    fn test_alert_rule_applies_to() {
+        let account_id = Uuid::new_v4();
+        let global = AlertRule::new(AlertRuleType::LargeTransaction, "USD".to_string(), Some(dec!(10000)));
+        let scoped = global.clone().for_account(account_id);
+
+        assert!(global.applies_to(Uuid::new_v4(), "USD"));
+        assert!(!global.applies_to(account_id, "EUR"));
+        assert!(scoped.applies_to(account_id, "USD"));
+        assert!(!scoped.applies_to(Uuid::new_v4(), "USD"));
+
+        let mut disabled = scoped;
+        disabled.enabled = false;
+        assert!(!disabled.applies_to(account_id, "USD"));
+    }
+
+    #[test]
+    fn test_balance_thresholds() {
+        let low = AlertRule::new(AlertRuleType::LowBalance, "USD".to_string(), Some(dec!(100)));
+        assert!(low.is_breached_by_balance(dec!(99.99)));
+        assert!(!low.is_breached_by_balance(dec!(100)));
+
+        let high = AlertRule::new(AlertRuleType::HighBalance, "USD".to_string(), Some(dec!(1000)));
+        assert!(high.is_breached_by_balance(dec!(1000.01)));
+        assert!(!high.is_breached_by_balance(dec!(1000)));
+
+        assert!(!high.is_breached_by_amount(dec!(5000)));
+    }
+
+    #[test]
+    fn test_large_transaction_threshold() {
+        let rule = AlertRule::new(AlertRuleType::LargeTransaction, "USD".to_string(), Some(dec!(10000)));
+        assert!(rule.is_breached_by_amount(dec!(10000)));
+        assert!(!rule.is_breached_by_amount(dec!(9999.99)));
+        assert!(!rule.is_breached_by_balance(dec!(0)));
+    }
+
+    #[test]
+    fn test_suppression_window() {
+        let now = Utc::now();
+        let mut rule = AlertRule::new(AlertRuleType::FailedSettlement, "USD".to_string(), None)
+            .with_suppression_window(60);
+        assert!(!rule.is_suppressed(now));
+
+        rule.last_triggered_at = Some(now - Duration::seconds(30));
+        assert!(rule.is_suppressed(now));
+
+        rule.last_triggered_at = Some(now - Duration::seconds(61));
+        assert!(!rule.is_suppressed(now));
+
+        rule.suppression_window_seconds = 0;
+        rule.last_triggered_at = Some(now);
+        assert!(!rule.is_suppressed(now));
+    }
+
+    #[test]
+    fn test_requires_threshold() {
+        assert!(AlertRuleType::LowBalance.requires_threshold());
+        assert!(AlertRuleType::LargeTransaction.requires_threshold());
+        assert!(!AlertRuleType::FailedSettlement.requires_threshold());
+        assert!(!AlertRuleType::BalanceBreak.requires_threshold());
+    }
+}
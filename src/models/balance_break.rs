@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An account's stored balance next to the balance derived from its ledger entries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct BalanceCheck {
+    pub account_id: Uuid,
+    pub currency: String,
+    /// Available, pending and reserved balance as stored in `account_balances`.
+    pub stored_balance: Decimal,
+    /// Opening balance plus every ledger movement since.
+    pub derived_balance: Decimal,
+    pub entry_count: i64,
+}
+
+impl BalanceCheck {
+    /// Stored less derived balance.
+    pub fn difference(&self) -> Decimal {
+        self.stored_balance - self.derived_balance
+    }
+
+    /// Returns true if the stored balance has drifted from the ledger.
+    pub fn is_break(&self) -> bool {
+        !self.difference().is_zero()
+    }
+}
+
+/// A discrepancy between an account's stored balance and its ledger.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BalanceBreak {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub stored_balance: Decimal,
+    pub derived_balance: Decimal,
+    /// Stored less derived balance, as last seen.
+    pub difference: Decimal,
+    pub entry_count: i64,
+    pub detected_at: DateTime<Utc>,
+    /// Last reconciliation run that still found the break.
+    pub last_seen_at: DateTime<Utc>,
+    /// Set once the account reconciles again.
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl BalanceBreak {
+    /// Creates an open break from a failed check.
+    pub fn new(check: &BalanceCheck) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            account_id: check.account_id,
+            currency: check.currency.clone(),
+            stored_balance: check.stored_balance,
+            derived_balance: check.derived_balance,
+            difference: check.difference(),
+            entry_count: check.entry_count,
+            detected_at: now,
+            last_seen_at: now,
+            resolved_at: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.resolved_at.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn check(stored: Decimal, derived: Decimal) -> BalanceCheck {
+        BalanceCheck {
+            account_id: Uuid::new_v4(),
+            currency: "USD".to_string(),
+            stored_balance: stored,
+            derived_balance: derived,
+            entry_count: 3,
+        }
+    }
+
+    #[test]
+    fn test_balance_check_difference() {
+        assert!(!check(dec!(100), dec!(100.0000)).is_break());
+
+        let drifted = check(dec!(100), dec!(75.5));
+        assert!(drifted.is_break());
+        assert_eq!(drifted.difference(), dec!(24.5));
+    }
+
+    #[test]
+    fn test_balance_break_from_check() {
+        let drifted = check(dec!(90), dec!(100));
+        let balance_break = BalanceBreak::new(&drifted);
+
+        assert_eq!(balance_break.account_id, drifted.account_id);
+        assert_eq!(balance_break.difference, dec!(-10));
+        assert_eq!(balance_break.detected_at, balance_break.last_seen_at);
+        assert!(balance_break.is_open());
+    }
+}
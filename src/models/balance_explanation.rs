@@ -0,0 +1,207 @@
+use crate::models::{BalanceBasis, EntryType, TransactionType};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What moved an account's balance, for explaining the change over a period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BalanceMovementCategory {
+    /// Payments and transfers received.
+    PaymentsIn,
+    /// Payments and transfers sent, fees they carried included.
+    PaymentsOut,
+    /// Fee legs, fee transactions, invoices and fee refunds.
+    Fees,
+    /// Refunds and chargebacks.
+    Refunds,
+    /// Reversals of earlier transactions.
+    Reversals,
+    /// Postings made when a settlement batch settled, such as fee settlements.
+    BatchSettlements,
+    /// Anything else.
+    Adjustments,
+}
+
+impl BalanceMovementCategory {
+    /// Every category, in the order explanations list them.
+    pub const ALL: [BalanceMovementCategory; 7] = [
+        BalanceMovementCategory::PaymentsIn,
+        BalanceMovementCategory::PaymentsOut,
+        BalanceMovementCategory::Fees,
+        BalanceMovementCategory::Refunds,
+        BalanceMovementCategory::Reversals,
+        BalanceMovementCategory::BatchSettlements,
+        BalanceMovementCategory::Adjustments,
+    ];
+
+    /// Categorizes a group of ledger entries. Reversals and batch settlements are
+    /// recognized first, since they reuse the payment, refund and fee types.
+    pub fn of(group: &LedgerMovementGroup) -> Self {
+        if group.reversal {
+            return BalanceMovementCategory::Reversals;
+        }
+        if group.batch_settlement {
+            return BalanceMovementCategory::BatchSettlements;
+        }
+        if group.fee_leg {
+            return BalanceMovementCategory::Fees;
+        }
+        match group.transaction_type {
+            Some(TransactionType::Payment | TransactionType::Transfer) => match group.entry_type {
+                EntryType::Credit => BalanceMovementCategory::PaymentsIn,
+                EntryType::Debit => BalanceMovementCategory::PaymentsOut,
+            },
+            Some(TransactionType::Fee) => BalanceMovementCategory::Fees,
+            Some(TransactionType::Refund | TransactionType::Chargeback) => BalanceMovementCategory::Refunds,
+            None => BalanceMovementCategory::Adjustments,
+        }
+    }
+}
+
+/// Ledger entries of one account that share an entry type and the traits of their
+/// transaction, with their count and total.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct LedgerMovementGroup {
+    pub entry_type: EntryType,
+    /// Type of the entries' transaction; `None` if it is missing.
+    pub transaction_type: Option<TransactionType>,
+    /// The entries book a fee, or refund one.
+    pub fee_leg: bool,
+    /// The entries' transaction reverses an earlier one.
+    pub reversal: bool,
+    /// The entries' transaction was posted when a batch settled.
+    pub batch_settlement: bool,
+    pub entry_count: i64,
+    pub total: Decimal,
+}
+
+/// One category's share of a balance change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceMovementSummary {
+    pub category: BalanceMovementCategory,
+    pub entry_count: i64,
+    pub credits: Decimal,
+    pub debits: Decimal,
+    /// Credits less debits.
+    pub net: Decimal,
+}
+
+/// An account's balance change over a period, decomposed by what moved it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceExplanation {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub basis: BalanceBasis,
+    /// Closing balance on the day before `from`.
+    pub opening_balance: Decimal,
+    /// Closing balance on `to`.
+    pub closing_balance: Decimal,
+    pub net_change: Decimal,
+    /// Every category, in a fixed order, including those with no entries.
+    pub categories: Vec<BalanceMovementSummary>,
+}
+
+impl BalanceExplanation {
+    /// Sums ledger entry groups into every category, in a fixed order. The categories'
+    /// net movements add up to the change the groups make to the balance.
+    pub fn summarize(groups: &[LedgerMovementGroup]) -> Vec<BalanceMovementSummary> {
+        let mut categories: Vec<BalanceMovementSummary> = BalanceMovementCategory::ALL
+            .iter()
+            .map(|category| BalanceMovementSummary {
+                category: *category,
+                entry_count: 0,
+                credits: Decimal::ZERO,
+                debits: Decimal::ZERO,
+                net: Decimal::ZERO,
+            })
+            .collect();
+
+        for group in groups {
+            let category = BalanceMovementCategory::of(group);
+            if let Some(summary) = categories.iter_mut().find(|summary| summary.category == category) {
+                summary.entry_count += group.entry_count;
+                match group.entry_type {
+                    EntryType::Credit => summary.credits += group.total,
+                    EntryType::Debit => summary.debits += group.total,
+                }
+                summary.net = summary.credits - summary.debits;
+            }
+        }
+        categories
+    }
+
+    /// The summary of one category.
+    pub fn category(&self, category: BalanceMovementCategory) -> Option<&BalanceMovementSummary> {
+        self.categories.iter().find(|summary| summary.category == category)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn group(entry_type: EntryType, transaction_type: TransactionType, total: Decimal) -> LedgerMovementGroup {
+        LedgerMovementGroup {
+            entry_type,
+            transaction_type: Some(transaction_type),
+            fee_leg: false,
+            reversal: false,
+            batch_settlement: false,
+            entry_count: 1,
+            total,
+        }
+    }
+
+    #[test]
+    fn test_categories_sum_to_net_change() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let groups = vec![
+            group(EntryType::Credit, TransactionType::Payment, dec!(500)),
+            group(EntryType::Debit, TransactionType::Transfer, dec!(120)),
+            LedgerMovementGroup {
+                fee_leg: true,
+                ..group(EntryType::Credit, TransactionType::Payment, dec!(5))
+            },
+            LedgerMovementGroup {
+                reversal: true,
+                ..group(EntryType::Debit, TransactionType::Refund, dec!(50))
+            },
+            LedgerMovementGroup {
+                batch_settlement: true,
+                ..group(EntryType::Debit, TransactionType::Fee, dec!(5))
+            },
+            group(EntryType::Credit, TransactionType::Chargeback, dec!(20)),
+        ];
+
+        let explanation = BalanceExplanation {
+            account_id: Uuid::new_v4(),
+            currency: "USD".to_string(),
+            from: day,
+            to: day,
+            basis: BalanceBasis::Value,
+            opening_balance: dec!(100),
+            closing_balance: dec!(450),
+            net_change: dec!(350),
+            categories: BalanceExplanation::summarize(&groups),
+        };
+
+        let net = |category| explanation.category(category).unwrap().net;
+        assert_eq!(net(BalanceMovementCategory::PaymentsIn), dec!(500));
+        assert_eq!(net(BalanceMovementCategory::PaymentsOut), dec!(-120));
+        assert_eq!(net(BalanceMovementCategory::Fees), dec!(5));
+        assert_eq!(net(BalanceMovementCategory::Reversals), dec!(-50));
+        assert_eq!(net(BalanceMovementCategory::BatchSettlements), dec!(-5));
+        assert_eq!(net(BalanceMovementCategory::Refunds), dec!(20));
+        assert_eq!(net(BalanceMovementCategory::Adjustments), dec!(0));
+        assert_eq!(explanation.categories.len(), BalanceMovementCategory::ALL.len());
+
+        let total: Decimal = explanation.categories.iter().map(|summary| summary.net).sum();
+        assert_eq!(total, explanation.net_change);
+    }
+}
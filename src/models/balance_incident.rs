@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Handling state of a balance incident.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "balance_incident_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BalanceIncidentStatus {
+    /// Detected; the balance guard has not acted on it yet.
+    Open,
+    /// The account was frozen and alerts were raised.
+    Frozen,
+    /// An operator confirmed the balance is back within its floor.
+    Resolved,
+}
+
+/// The lowest available balance an account may hold in a currency. Accounts without
+/// one may not go below zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct BalanceFloor {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub floor: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A balance found below its allowed floor, or with a negative pending or reserved
+/// part. Incidents are opened by a database trigger on `account_balances`, so writes
+/// that bypass the application are caught as well; while unresolved, later breaches
+/// refresh the same incident.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BalanceIncident {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub floor: Decimal,
+    /// Balance as last seen breaching.
+    pub available_balance: Decimal,
+    pub pending_balance: Decimal,
+    pub reserved_balance: Decimal,
+    pub status: BalanceIncidentStatus,
+    pub detected_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub frozen_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolution_note: Option<String>,
+}
+
+impl BalanceIncident {
+    /// How far the available balance is below the floor; zero if it is not.
+    pub fn shortfall(&self) -> Decimal {
+        (self.floor - self.available_balance).max(Decimal::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_shortfall() {
+        let mut incident = BalanceIncident {
+            id: Uuid::new_v4(),
+            account_id: Uuid::new_v4(),
+            currency: "USD".to_string(),
+            floor: dec!(-50),
+            available_balance: dec!(-80),
+            pending_balance: dec!(0),
+            reserved_balance: dec!(0),
+            status: BalanceIncidentStatus::Open,
+            detected_at: Utc::now(),
+            last_seen_at: Utc::now(),
+            frozen_at: None,
+            resolved_at: None,
+            resolution_note: None,
+        };
+        assert_eq!(incident.shortfall(), dec!(30));
+
+        // A negative reserved balance breaches without any shortfall
+        incident.available_balance = dec!(10);
+        incident.reserved_balance = dec!(-5);
+        assert_eq!(incident.shortfall(), dec!(0));
+    }
+}
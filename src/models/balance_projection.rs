@@ -0,0 +1,136 @@
+use super::LedgerEntry;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A ledger entry with its position in the account's entry sequence.
+#[derive(Debug, Clone, FromRow)]
+pub struct SequencedLedgerEntry {
+    #[sqlx(flatten)]
+    pub entry: LedgerEntry,
+    pub account_sequence: i64,
+}
+
+/// Account balance folded from its ledger entries in sequence order.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BalanceProjection {
+    pub account_id: Uuid,
+    pub currency: String,
+    /// Opening balance plus every credit less every debit folded so far.
+    pub balance: Decimal,
+    /// Sequence of the last entry folded in; zero before the first.
+    pub last_sequence: i64,
+    pub last_entry_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BalanceProjection {
+    pub fn new(account_id: Uuid, currency: String) -> Self {
+        Self {
+            account_id,
+            currency,
+            balance: Decimal::ZERO,
+            last_sequence: 0,
+            last_entry_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Folds the next entry in. Entries must arrive in sequence without gaps; returns
+    /// false and leaves the projection untouched otherwise.
+    ///
+    /// The first entry also sets the opening balance, the balance the account held
+    /// before it, from the entry's `balance_after`.
+    pub fn apply(&mut self, sequenced: &SequencedLedgerEntry) -> bool {
+        if sequenced.account_sequence != self.last_sequence + 1 {
+            return false;
+        }
+
+        let entry = &sequenced.entry;
+        // signed_amount is positive for debits; balances go up on credits
+        let movement = -entry.signed_amount();
+        self.balance = if self.last_sequence == 0 {
+            entry.balance_after
+        } else {
+            self.balance + movement
+        };
+        self.last_sequence = sequenced.account_sequence;
+        self.last_entry_at = Some(entry.created_at);
+        self.updated_at = Utc::now();
+        true
+    }
+}
+
+/// How far the balance projection is behind the ledger.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct BalanceProjectionLag {
+    /// Account balances with entries not yet projected.
+    pub accounts_behind: i64,
+    /// Entries not yet projected across all accounts.
+    pub entries_behind: i64,
+    /// When the oldest entry not yet projected was posted.
+    pub oldest_unprojected_at: Option<DateTime<Utc>>,
+}
+
+impl BalanceProjectionLag {
+    /// Age of the oldest entry not yet projected, in seconds; zero when caught up.
+    pub fn lag_secs(&self, now: DateTime<Utc>) -> f64 {
+        self.oldest_unprojected_at
+            .map(|at| (now - at).num_milliseconds().max(0) as f64 / 1000.0)
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDate};
+    use rust_decimal_macros::dec;
+
+    fn sequenced(sequence: i64, entry: LedgerEntry) -> SequencedLedgerEntry {
+        SequencedLedgerEntry {
+            entry,
+            account_sequence: sequence,
+        }
+    }
+
+    #[test]
+    fn test_apply_folds_entries_in_sequence() {
+        let account_id = Uuid::new_v4();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut projection = BalanceProjection::new(account_id, "USD".to_string());
+
+        // The account opened with 1000 before its first debit
+        let first = LedgerEntry::debit(Uuid::new_v4(), account_id, dec!(100), "USD".to_string(), dec!(900), date);
+        assert!(projection.apply(&sequenced(1, first)));
+        assert_eq!(projection.balance, dec!(900));
+
+        let credit = LedgerEntry::credit(Uuid::new_v4(), account_id, dec!(50), "USD".to_string(), dec!(0), date);
+        let gap = sequenced(3, credit.clone());
+        assert!(!projection.apply(&gap));
+        assert_eq!(projection.last_sequence, 1);
+
+        assert!(projection.apply(&sequenced(2, credit)));
+        assert_eq!(projection.balance, dec!(950));
+        assert_eq!(projection.last_sequence, 2);
+
+        // Replays are refused
+        let replay = LedgerEntry::debit(Uuid::new_v4(), account_id, dec!(100), "USD".to_string(), dec!(900), date);
+        assert!(!projection.apply(&sequenced(2, replay)));
+        assert_eq!(projection.balance, dec!(950));
+    }
+
+    #[test]
+    fn test_lag_secs() {
+        let now = Utc::now();
+        let lag = BalanceProjectionLag {
+            accounts_behind: 1,
+            entries_behind: 3,
+            oldest_unprojected_at: Some(now - Duration::milliseconds(2500)),
+        };
+        assert_eq!(lag.lag_secs(now), 2.5);
+        assert_eq!(BalanceProjectionLag::default().lag_secs(now), 0.0);
+    }
+}
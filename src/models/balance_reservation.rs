@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// State of a balance reservation. Only active reservations hold funds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "balance_reservation_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BalanceReservationStatus {
+    /// Holding its amount out of the account's available balance.
+    Active,
+    /// Released back to the account by the client.
+    Released,
+    /// Converted into a settled payment.
+    Captured,
+    /// Released back to the account by the expiry sweeper.
+    Expired,
+}
+
+/// Funds a client reserved on an account, under a name and a reference unique to the
+/// account, until they are released, captured or the reservation expires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct BalanceReservation {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub amount: Decimal,
+    pub name: String,
+    pub reference: String,
+    pub status: BalanceReservationStatus,
+    /// Never expires if unset.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The payment the reservation was captured into.
+    pub transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// When the reservation was released, captured or expired.
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl BalanceReservation {
+    pub fn new(
+        account_id: Uuid,
+        currency: impl Into<String>,
+        amount: Decimal,
+        name: impl Into<String>,
+        reference: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            account_id,
+            currency: currency.into(),
+            amount,
+            name: name.into(),
+            reference: reference.into(),
+            status: BalanceReservationStatus::Active,
+            expires_at: None,
+            transaction_id: None,
+            created_at: Utc::now(),
+            closed_at: None,
+        }
+    }
+
+    pub fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// True once an expiry was set and has passed, even before the sweeper gets to it.
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
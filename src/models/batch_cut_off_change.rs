@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// How an operator changed a batch's cut-off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "cut_off_change_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CutOffChangeKind {
+    /// The cut-off was moved to another time in the future.
+    Moved,
+    /// The cut-off was brought forward to now, closing the batch to new transactions.
+    ClosedEarly,
+}
+
+/// Audit record of an approved change to a pending batch's cut-off.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BatchCutOffChange {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub kind: CutOffChangeKind,
+    pub previous_cut_off: DateTime<Utc>,
+    pub new_cut_off: DateTime<Utc>,
+    pub requested_by: String,
+    /// A second operator, unless the batch allows single-operator changes.
+    pub approved_by: String,
+    pub reason: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl BatchCutOffChange {
+    pub fn new(
+        batch_id: Uuid,
+        kind: CutOffChangeKind,
+        previous_cut_off: DateTime<Utc>,
+        new_cut_off: DateTime<Utc>,
+        requested_by: impl Into<String>,
+        approved_by: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            batch_id,
+            kind,
+            previous_cut_off,
+            new_cut_off,
+            requested_by: requested_by.into(),
+            approved_by: approved_by.into(),
+            reason: reason.into(),
+            recorded_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// How far processing of a batch has got, as of its last checkpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct BatchProcessingProgress {
+    pub batch_id: Uuid,
+    pub total_transactions: i32,
+    /// Transactions processed so far, failed ones included.
+    pub processed_transactions: i32,
+    pub failed_transactions: i32,
+    pub started_at: DateTime<Utc>,
+    /// Time of the last checkpoint, which the processing run heartbeats.
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Processing run currently holding the batch.
+    pub run_id: Option<Uuid>,
+    /// Times processing was resumed after its run stopped.
+    pub resumes: i32,
+}
+
+impl BatchProcessingProgress {
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    pub fn remaining(&self) -> i32 {
+        (self.total_transactions - self.processed_transactions).max(0)
+    }
+
+    /// Share of the batch processed, from 0 to 100. An empty batch is complete.
+    pub fn percent_complete(&self) -> f64 {
+        if self.total_transactions == 0 {
+            return 100.0;
+        }
+        f64::from(self.processed_transactions) * 100.0 / f64::from(self.total_transactions)
+    }
+
+    /// Transactions processed per second between the start and the last checkpoint.
+    pub fn transactions_per_second(&self) -> Option<f64> {
+        let elapsed = (self.updated_at - self.started_at).num_milliseconds();
+        if self.processed_transactions == 0 || elapsed <= 0 {
+            return None;
+        }
+        Some(f64::from(self.processed_transactions) * 1000.0 / elapsed as f64)
+    }
+
+    /// Expected completion time at the rate so far; unknown until the first checkpoint.
+    pub fn estimated_completion_at(&self) -> Option<DateTime<Utc>> {
+        if let Some(finished_at) = self.finished_at {
+            return Some(finished_at);
+        }
+        let rate = self.transactions_per_second()?;
+        let remaining_ms = (f64::from(self.remaining()) * 1000.0 / rate).ceil() as i64;
+        Some(self.updated_at + chrono::Duration::milliseconds(remaining_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(total: i32, processed: i32, elapsed_secs: i64) -> BatchProcessingProgress {
+        let started_at = Utc::now();
+        BatchProcessingProgress {
+            batch_id: Uuid::new_v4(),
+            total_transactions: total,
+            processed_transactions: processed,
+            failed_transactions: 0,
+            started_at,
+            updated_at: started_at + chrono::Duration::seconds(elapsed_secs),
+            finished_at: None,
+            run_id: Some(Uuid::new_v4()),
+            resumes: 0,
+        }
+    }
+
+    #[test]
+    fn test_estimates_completion_from_rate() {
+        let progress = progress(1000, 250, 10);
+        assert_eq!(progress.remaining(), 750);
+        assert_eq!(progress.percent_complete(), 25.0);
+        assert_eq!(progress.transactions_per_second(), Some(25.0));
+        assert_eq!(
+            progress.estimated_completion_at(),
+            Some(progress.updated_at + chrono::Duration::seconds(30))
+        );
+    }
+
+    #[test]
+    fn test_no_estimate_before_first_checkpoint() {
+        let progress = progress(1000, 0, 0);
+        assert_eq!(progress.transactions_per_second(), None);
+        assert_eq!(progress.estimated_completion_at(), None);
+        assert_eq!(self::progress(0, 0, 0).percent_complete(), 100.0);
+    }
+}
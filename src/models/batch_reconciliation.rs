@@ -0,0 +1,236 @@
+use super::NettingPosition;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Totals check run on a batch before it is marked completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BatchCheck {
+    /// The batch's transactions add up to its gross amount.
+    GrossAmount,
+    /// Ledger debits equal credits, per currency, for the batch's transactions.
+    LedgerBalance,
+    /// The participants' net positions sum to zero.
+    NettingPositions,
+    /// The settlement control account returned to zero once the batch's funding and
+    /// payout instructions were executed.
+    ControlAccount,
+}
+
+/// Debit and credit totals of the ledger entries posted for a batch's transactions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct BatchLedgerTotals {
+    pub currency: String,
+    pub debits: Decimal,
+    pub credits: Decimal,
+    pub entry_count: i64,
+}
+
+/// A check that did not hold, with what was expected and what was found.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReconciliationBreak {
+    pub check: BatchCheck,
+    pub currency: String,
+    pub expected: Decimal,
+    pub actual: Decimal,
+    /// Expected less actual.
+    pub difference: Decimal,
+    pub detail: String,
+}
+
+impl BatchReconciliationBreak {
+    fn new(check: BatchCheck, currency: &str, expected: Decimal, actual: Decimal, detail: String) -> Self {
+        Self {
+            check,
+            currency: currency.to_string(),
+            expected,
+            actual,
+            difference: expected - actual,
+            detail,
+        }
+    }
+}
+
+/// Outcome of a batch's totals checks. A batch is only completed if it passed; otherwise
+/// it is failed and the breaks say why.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BatchReconciliation {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub passed: bool,
+    /// The `BatchReconciliationBreak`s found; empty if the batch passed.
+    pub breaks: serde_json::Value,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl BatchReconciliation {
+    /// Runs the checks on a batch's recorded gross amount against its transactions'
+    /// gross, its ledger totals and its netting positions.
+    pub fn check(
+        batch_id: Uuid,
+        currency: &str,
+        batch_gross: Decimal,
+        transaction_gross: Decimal,
+        ledger: &[BatchLedgerTotals],
+        positions: &[NettingPosition],
+    ) -> Self {
+        let mut breaks = Vec::new();
+
+        if batch_gross != transaction_gross {
+            breaks.push(BatchReconciliationBreak::new(
+                BatchCheck::GrossAmount,
+                currency,
+                batch_gross,
+                transaction_gross,
+                format!(
+                    "Batch gross amount is {} but its transactions add up to {}",
+                    batch_gross, transaction_gross
+                ),
+            ));
+        }
+
+        for totals in ledger.iter().filter(|t| t.debits != t.credits) {
+            breaks.push(BatchReconciliationBreak::new(
+                BatchCheck::LedgerBalance,
+                &totals.currency,
+                totals.debits,
+                totals.credits,
+                format!(
+                    "{} ledger entries debit {} but credit {}",
+                    totals.entry_count, totals.debits, totals.credits
+                ),
+            ));
+        }
+
+        let net: Decimal = positions.iter().map(|p| p.net_position).sum();
+        if !net.is_zero() {
+            breaks.push(BatchReconciliationBreak::new(
+                BatchCheck::NettingPositions,
+                currency,
+                Decimal::ZERO,
+                net,
+                format!("Net positions of {} participants sum to {}", positions.len(), net),
+            ));
+        }
+
+        Self {
+            id: Uuid::new_v4(),
+            batch_id,
+            passed: breaks.is_empty(),
+            breaks: serde_json::to_value(&breaks).unwrap_or_default(),
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// Checks that a batch's executed instructions left its settlement control account
+    /// where it started: what net payers paid in equals what net receivers were paid out.
+    pub fn check_control_account(
+        batch_id: Uuid,
+        currency: &str,
+        control_account_id: Uuid,
+        funded: Decimal,
+        paid_out: Decimal,
+    ) -> Self {
+        let mut breaks = Vec::new();
+        if funded != paid_out {
+            breaks.push(BatchReconciliationBreak::new(
+                BatchCheck::ControlAccount,
+                currency,
+                funded,
+                paid_out,
+                format!(
+                    "Settlement control account {} was funded {} but paid out {}, leaving {}",
+                    control_account_id,
+                    funded,
+                    paid_out,
+                    funded - paid_out
+                ),
+            ));
+        }
+
+        Self {
+            id: Uuid::new_v4(),
+            batch_id,
+            passed: breaks.is_empty(),
+            breaks: serde_json::to_value(&breaks).unwrap_or_default(),
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// The breaks found, decoded.
+    pub fn breaks(&self) -> Vec<BatchReconciliationBreak> {
+        serde_json::from_value(self.breaks.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn position(batch_id: Uuid, receivable: Decimal, payable: Decimal) -> NettingPosition {
+        let mut position = NettingPosition::new(batch_id, Uuid::new_v4(), "USD".to_string());
+        position.add_receivable(receivable);
+        position.add_payable(payable);
+        position
+    }
+
+    #[test]
+    fn test_check_passes_when_totals_agree() {
+        let batch_id = Uuid::new_v4();
+        let ledger = vec![BatchLedgerTotals {
+            currency: "USD".to_string(),
+            debits: dec!(150),
+            credits: dec!(150),
+            entry_count: 4,
+        }];
+        let positions = vec![position(batch_id, dec!(100), dec!(50)), position(batch_id, dec!(50), dec!(100))];
+
+        let reconciliation = BatchReconciliation::check(batch_id, "USD", dec!(150), dec!(150), &ledger, &positions);
+        assert!(reconciliation.passed);
+        assert!(reconciliation.breaks().is_empty());
+    }
+
+    #[test]
+    fn test_check_reports_each_break() {
+        let batch_id = Uuid::new_v4();
+        let ledger = vec![BatchLedgerTotals {
+            currency: "USD".to_string(),
+            debits: dec!(150),
+            credits: dec!(140),
+            entry_count: 4,
+        }];
+        let positions = vec![position(batch_id, dec!(100), dec!(0))];
+
+        let reconciliation = BatchReconciliation::check(batch_id, "USD", dec!(175), dec!(150), &ledger, &positions);
+        assert!(!reconciliation.passed);
+        let breaks = reconciliation.breaks();
+        let checks: Vec<_> = breaks.iter().map(|b| b.check).collect();
+        assert_eq!(
+            checks,
+            vec![BatchCheck::GrossAmount, BatchCheck::LedgerBalance, BatchCheck::NettingPositions]
+        );
+        assert_eq!(breaks[0].difference, dec!(25));
+        assert_eq!(breaks[1].difference, dec!(10));
+        assert_eq!(breaks[2].actual, dec!(100));
+    }
+
+    #[test]
+    fn test_control_account_check_flags_residual() {
+        let batch_id = Uuid::new_v4();
+        let control_account = Uuid::new_v4();
+
+        let balanced = BatchReconciliation::check_control_account(batch_id, "USD", control_account, dec!(60), dec!(60));
+        assert!(balanced.passed);
+
+        let residual = BatchReconciliation::check_control_account(batch_id, "USD", control_account, dec!(60), dec!(10));
+        assert!(!residual.passed);
+        let breaks = residual.breaks();
+        assert_eq!(breaks.len(), 1);
+        assert_eq!(breaks[0].check, BatchCheck::ControlAccount);
+        assert_eq!(breaks[0].difference, dec!(50));
+    }
+}
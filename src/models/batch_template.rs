@@ -0,0 +1,108 @@
+use super::{CutOffApprovalPolicy, NettingMode, SettlementBatch, SettlementWindow};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Configuration of a recurring batch, from which each settlement date's batch is
+/// provisioned ahead of time.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BatchTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub currency: String,
+    /// Named settlement window whose cut-off the batches use.
+    pub window_id: Option<Uuid>,
+    /// UTC cut-off for batches outside a named window.
+    pub cut_off_time: Option<NaiveTime>,
+    /// Copied onto every batch, alongside the template's ID.
+    pub metadata: Option<serde_json::Value>,
+    pub netting_mode: NettingMode,
+    pub cut_off_approval: CutOffApprovalPolicy,
+    /// Inactive templates are not provisioned.
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BatchTemplate {
+    pub fn new(name: impl Into<String>, currency: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            currency: currency.into(),
+            window_id: None,
+            cut_off_time: None,
+            metadata: None,
+            netting_mode: NettingMode::Multilateral,
+            cut_off_approval: CutOffApprovalPolicy::FourEyes,
+            active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Builds the template's batch for a settlement date, or None when its cut-off cannot
+    /// be resolved. `window` must be the template's window, if it has one.
+    pub fn batch_for(&self, settlement_date: NaiveDate, window: Option<&SettlementWindow>) -> Option<SettlementBatch> {
+        let cut_off_time = match window {
+            Some(window) => window.cut_off_on(settlement_date)?,
+            None => settlement_date.and_time(self.cut_off_time?).and_utc(),
+        };
+
+        let mut metadata = match &self.metadata {
+            Some(serde_json::Value::Object(fields)) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+        metadata.insert("template_id".to_string(), serde_json::json!(self.id));
+
+        let mut batch = SettlementBatch::new(settlement_date, cut_off_time, self.currency.clone())
+            .with_metadata(serde_json::Value::Object(metadata))
+            .with_netting_mode(self.netting_mode)
+            .with_cut_off_approval(self.cut_off_approval);
+        if let Some(window_id) = self.window_id {
+            batch = batch.with_window(window_id);
+        }
+        Some(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_batch_for_uses_template_settings() {
+        let mut template = BatchTemplate::new("eod", "EUR");
+        template.cut_off_time = NaiveTime::from_hms_opt(17, 30, 0);
+        template.metadata = Some(serde_json::json!({ "desk": "treasury" }));
+        template.netting_mode = NettingMode::Bilateral;
+        template.cut_off_approval = CutOffApprovalPolicy::SingleOperator;
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 16).unwrap();
+        let batch = template.batch_for(date, None).unwrap();
+        assert_eq!(batch.settlement_date, date);
+        assert_eq!(batch.cut_off_time, Utc.with_ymd_and_hms(2024, 1, 16, 17, 30, 0).unwrap());
+        assert_eq!(batch.netting_mode, NettingMode::Bilateral);
+        assert_eq!(batch.cut_off_approval, CutOffApprovalPolicy::SingleOperator);
+        let metadata = batch.metadata.unwrap();
+        assert_eq!(metadata["desk"], "treasury");
+        assert_eq!(metadata["template_id"], serde_json::json!(template.id));
+    }
+
+    #[test]
+    fn test_batch_for_window_uses_local_cut_off() {
+        let window = SettlementWindow::new("morning", "EUR", NaiveTime::from_hms_opt(10, 0, 0).unwrap(), "Europe/Berlin");
+        let mut template = BatchTemplate::new("morning", "EUR");
+        template.window_id = Some(window.id);
+
+        let batch = template.batch_for(NaiveDate::from_ymd_opt(2024, 7, 15).unwrap(), Some(&window)).unwrap();
+        assert_eq!(batch.cut_off_time, Utc.with_ymd_and_hms(2024, 7, 15, 8, 0, 0).unwrap());
+        assert_eq!(batch.window_id, Some(window.id));
+
+        let no_cut_off = BatchTemplate::new("broken", "EUR");
+        assert!(no_cut_off.batch_for(NaiveDate::from_ymd_opt(2024, 7, 15).unwrap(), None).is_none());
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Stored net obligation between two participants in a bilaterally netted batch.
+/// Each pair is stored once, with `participant_a` the lower account id.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BilateralPairRecord {
+    pub batch_id: Uuid,
+    pub participant_a: Uuid,
+    pub participant_b: Uuid,
+    pub currency: String,
+    /// Total paid from A to B.
+    pub a_to_b_gross: Decimal,
+    /// Total paid from B to A.
+    pub b_to_a_gross: Decimal,
+    /// Net obligation: positive = A pays B, negative = B pays A.
+    pub net_amount: Decimal,
+    pub transaction_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BilateralPairRecord {
+    /// Returns the paying and receiving participant, or `None` if the pair is balanced.
+    pub fn payer_and_payee(&self) -> Option<(Uuid, Uuid)> {
+        if self.net_amount > Decimal::ZERO {
+            Some((self.participant_a, self.participant_b))
+        } else if self.net_amount < Decimal::ZERO {
+            Some((self.participant_b, self.participant_a))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the gross volume exchanged in both directions.
+    pub fn gross_volume(&self) -> Decimal {
+        self.a_to_b_gross + self.b_to_a_gross
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_payer_and_payee_follow_net_sign() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut pair = BilateralPairRecord {
+            batch_id: Uuid::new_v4(),
+            participant_a: a,
+            participant_b: b,
+            currency: "USD".to_string(),
+            a_to_b_gross: dec!(100),
+            b_to_a_gross: dec!(40),
+            net_amount: dec!(60),
+            transaction_count: 3,
+            created_at: Utc::now(),
+        };
+        assert_eq!(pair.payer_and_payee(), Some((a, b)));
+        assert_eq!(pair.gross_volume(), dec!(140));
+
+        pair.net_amount = dec!(-60);
+        assert_eq!(pair.payer_and_payee(), Some((b, a)));
+
+        pair.net_amount = Decimal::ZERO;
+        assert_eq!(pair.payer_and_payee(), None);
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Whether a restriction permits or prohibits a counterparty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "counterparty_list_mode", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CounterpartyListMode {
+    /// The account may transact with this counterparty; once any entry is allowed,
+    /// every counterparty not listed is refused.
+    Allow,
+    /// The account must never transact with this counterparty.
+    Deny,
+}
+
+/// Change recorded in the counterparty restriction audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "counterparty_audit_action", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CounterpartyAuditAction {
+    Added,
+    Removed,
+}
+
+/// An allow- or denylist entry restricting who an account may transact with.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CounterpartyRestriction {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    /// Counterparty account or participant ID.
+    pub counterparty_id: Uuid,
+    pub mode: CounterpartyListMode,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CounterpartyRestriction {
+    pub fn new(account_id: Uuid, counterparty_id: Uuid, mode: CounterpartyListMode) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            account_id,
+            counterparty_id,
+            mode,
+            reason: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// Returns true if an account with these restrictions may transact with the
+    /// counterparty. Denials win over allows; an empty list permits everyone.
+    pub fn permits(restrictions: &[CounterpartyRestriction], counterparty_id: Uuid) -> bool {
+        let listed = restrictions.iter().find(|r| r.counterparty_id == counterparty_id);
+        match listed.map(|r| r.mode) {
+            Some(CounterpartyListMode::Deny) => false,
+            Some(CounterpartyListMode::Allow) => true,
+            None => !restrictions.iter().any(|r| r.mode == CounterpartyListMode::Allow),
+        }
+    }
+}
+
+/// Audit entry for a counterparty restriction being added or removed.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CounterpartyRestrictionAudit {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub counterparty_id: Uuid,
+    pub mode: CounterpartyListMode,
+    pub action: CounterpartyAuditAction,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl CounterpartyRestrictionAudit {
+    /// Creates an audit entry for a change to the given restriction.
+    pub fn new(restriction: &CounterpartyRestriction, action: CounterpartyAuditAction) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            account_id: restriction.account_id,
+            counterparty_id: restriction.counterparty_id,
+            mode: restriction.mode,
+            action,
+            reason: restriction.reason.clone(),
+            changed_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_restrictions_permit_everyone() {
+        assert!(CounterpartyRestriction::permits(&[], Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_denylist() {
+        let account = Uuid::new_v4();
+        let blocked = Uuid::new_v4();
+        let restrictions = [CounterpartyRestriction::new(account, blocked, CounterpartyListMode::Deny)];
+
+        assert!(!CounterpartyRestriction::permits(&restrictions, blocked));
+        assert!(CounterpartyRestriction::permits(&restrictions, Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_allowlist_refuses_unlisted() {
+        let account = Uuid::new_v4();
+        let allowed = Uuid::new_v4();
+        let blocked = Uuid::new_v4();
+        let restrictions = [
+            CounterpartyRestriction::new(account, allowed, CounterpartyListMode::Allow),
+            CounterpartyRestriction::new(account, blocked, CounterpartyListMode::Deny),
+        ];
+
+        assert!(CounterpartyRestriction::permits(&restrictions, allowed));
+        assert!(!CounterpartyRestriction::permits(&restrictions, blocked));
+        assert!(!CounterpartyRestriction::permits(&restrictions, Uuid::new_v4()));
+    }
+}
@@ -0,0 +1,97 @@
+use super::EntryType;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An unsettled fee leg on a fees-receivable account and the payer it accrued for.
+/// Credits accrue fees; debits take back fees reversed or refunded since.
+#[derive(Debug, Clone, FromRow)]
+pub struct FeeAccrual {
+    pub ledger_entry_id: Uuid,
+    pub account_id: Uuid,
+    pub entry_type: EntryType,
+    pub amount: Decimal,
+}
+
+/// One payer's accrued fees for a batch, settled from the fees-receivable account to
+/// the revenue account.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeeSettlement {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    /// The payer the fees were charged to.
+    pub account_id: Uuid,
+    pub currency: String,
+    pub accrued_amount: Decimal,
+    /// Fees reversed or refunded before settlement.
+    pub reversed_amount: Decimal,
+    /// Accrued less reversed; negative when more was given back than accrued.
+    pub net_amount: Decimal,
+    /// Fee legs settled.
+    pub fee_count: i32,
+    /// Fee transaction that moved the net amount, none when it was zero.
+    pub transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FeeSettlement {
+    /// Sums one payer's accruals in a batch.
+    pub fn from_accruals(batch_id: Uuid, account_id: Uuid, currency: impl Into<String>, accruals: &[FeeAccrual]) -> Self {
+        let sum = |entry_type: EntryType| {
+            accruals
+                .iter()
+                .filter(|accrual| accrual.entry_type == entry_type)
+                .map(|accrual| accrual.amount)
+                .sum::<Decimal>()
+        };
+        let accrued_amount = sum(EntryType::Credit);
+        let reversed_amount = sum(EntryType::Debit);
+
+        Self {
+            id: Uuid::new_v4(),
+            batch_id,
+            account_id,
+            currency: currency.into(),
+            accrued_amount,
+            reversed_amount,
+            net_amount: accrued_amount - reversed_amount,
+            fee_count: accruals.len() as i32,
+            transaction_id: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn accrual(entry_type: EntryType, amount: Decimal) -> FeeAccrual {
+        FeeAccrual {
+            ledger_entry_id: Uuid::new_v4(),
+            account_id: Uuid::nil(),
+            entry_type,
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_settlement_nets_reversed_fees() {
+        let accruals = [
+            accrual(EntryType::Credit, dec!(3)),
+            accrual(EntryType::Credit, dec!(2.5)),
+            accrual(EntryType::Debit, dec!(1)),
+        ];
+        let settlement = FeeSettlement::from_accruals(Uuid::new_v4(), Uuid::nil(), "EUR", &accruals);
+        assert_eq!(settlement.accrued_amount, dec!(5.5));
+        assert_eq!(settlement.reversed_amount, dec!(1));
+        assert_eq!(settlement.net_amount, dec!(4.5));
+        assert_eq!(settlement.fee_count, 3);
+
+        let refunded = FeeSettlement::from_accruals(Uuid::new_v4(), Uuid::nil(), "EUR", &[accrual(EntryType::Debit, dec!(2))]);
+        assert_eq!(refunded.net_amount, dec!(-2));
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Lifecycle of a file delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "delivery_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeliveryStatus {
+    /// Waiting for its next attempt.
+    Pending,
+    /// Claimed by the delivery worker.
+    Delivering,
+    /// Uploaded and verified at the destination.
+    Delivered,
+    /// Every attempt failed.
+    Failed,
+}
+
+/// A generated file queued for delivery to an external destination.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FileDelivery {
+    pub id: Uuid,
+    /// Name of the configured destination.
+    pub destination: String,
+    pub file_name: String,
+    #[serde(skip)]
+    pub content: Vec<u8>,
+    /// Hex SHA-256 of the content, verified before and after upload.
+    pub checksum_sha256: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FileDelivery {
+    pub fn new(destination: impl Into<String>, file_name: impl Into<String>, content: Vec<u8>, max_attempts: i32) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            destination: destination.into(),
+            file_name: file_name.into(),
+            checksum_sha256: sha256_hex(&content),
+            content,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            max_attempts,
+            next_attempt_at: now,
+            last_error: None,
+            delivered_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Holds the first attempt until the given time.
+    pub fn scheduled_at(mut self, at: DateTime<Utc>) -> Self {
+        self.next_attempt_at = at;
+        self
+    }
+
+    /// Returns true if the stored content still matches its checksum.
+    pub fn verify_content(&self) -> bool {
+        sha256_hex(&self.content) == self.checksum_sha256
+    }
+
+    /// Marks the current attempt as delivered.
+    pub fn mark_delivered(&mut self) {
+        let now = Utc::now();
+        self.status = DeliveryStatus::Delivered;
+        self.last_error = None;
+        self.delivered_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// Records a failed attempt. The delivery is retried after `backoff`, doubled for
+    /// each earlier attempt, until `max_attempts` is reached.
+    pub fn record_failure(&mut self, error: impl Into<String>, backoff: Duration) {
+        let now = Utc::now();
+        self.last_error = Some(error.into());
+        self.updated_at = now;
+        if self.attempts >= self.max_attempts {
+            self.status = DeliveryStatus::Failed;
+        } else {
+            let exponent = (self.attempts - 1).clamp(0, 16) as u32;
+            self.status = DeliveryStatus::Pending;
+            self.next_attempt_at = now + backoff * 2i32.pow(exponent);
+        }
+    }
+}
+
+/// Hex-encoded SHA-256 digest.
+pub fn sha256_hex(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff_until_failed() {
+        let mut delivery = FileDelivery::new("bank-sftp", "batch.ach", b"101 ...".to_vec(), 3);
+        assert!(delivery.verify_content());
+        assert_eq!(delivery.checksum_sha256.len(), 64);
+
+        let backoff = Duration::seconds(30);
+        delivery.attempts = 1;
+        delivery.record_failure("connection refused", backoff);
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        let first_delay = delivery.next_attempt_at - Utc::now();
+        assert!(first_delay > Duration::seconds(25) && first_delay <= backoff);
+
+        delivery.attempts = 2;
+        delivery.record_failure("connection refused", backoff);
+        assert!(delivery.next_attempt_at - Utc::now() > Duration::seconds(55));
+
+        delivery.attempts = 3;
+        delivery.record_failure("connection refused", backoff);
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert_eq!(delivery.last_error.as_deref(), Some("connection refused"));
+
+        delivery.content.push(b'X');
+        assert!(!delivery.verify_content());
+    }
+}
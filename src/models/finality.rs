@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::SettlementRoute;
+
+/// The moment a transaction became final and irrevocable.
+/// Sequences are assigned by the database and strictly increase in the order
+/// finality was reached, across all batches and the RTGS lane.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct FinalityRecord {
+    pub sequence: i64,
+    pub transaction_id: Uuid,
+    /// Batch the transaction settled in; `None` for RTGS settlements.
+    pub batch_id: Option<Uuid>,
+    pub settlement_route: SettlementRoute,
+    pub final_at: DateTime<Utc>,
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Funding state of a net payer's obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "funding_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FundingStatus {
+    /// Awaiting funds before the deadline.
+    Pending,
+    /// Matching credits cover the obligation.
+    Funded,
+    /// The deadline passed unfunded and the obligation was escalated. Funds arriving
+    /// later still fund it.
+    Overdue,
+    /// The deadline passed unfunded and the participant was declared in default.
+    Defaulted,
+}
+
+impl FundingStatus {
+    /// Whether funds are still being matched against the obligation.
+    pub fn is_open(&self) -> bool {
+        matches!(self, FundingStatus::Pending | FundingStatus::Overdue)
+    }
+}
+
+/// What a net payer of a completed batch must pay into the currency's settlement control
+/// account, and by when.
+///
+/// Credits are matched by reference: settled transactions from the participant to the
+/// control account whose reference is the batch ID count towards the obligation.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FundingObligation {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub participant_id: Uuid,
+    pub currency: String,
+    pub amount: Decimal,
+    pub funded_amount: Decimal,
+    /// Settlement control account funds are expected in; without one credits cannot be
+    /// matched.
+    pub control_account_id: Option<Uuid>,
+    pub deadline: DateTime<Utc>,
+    pub status: FundingStatus,
+    pub funded_at: Option<DateTime<Utc>>,
+    pub escalated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FundingObligation {
+    pub fn new(
+        batch_id: Uuid,
+        participant_id: Uuid,
+        currency: String,
+        amount: Decimal,
+        control_account_id: Option<Uuid>,
+        deadline: DateTime<Utc>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            batch_id,
+            participant_id,
+            currency,
+            amount,
+            funded_amount: Decimal::ZERO,
+            control_account_id,
+            deadline,
+            status: FundingStatus::Pending,
+            funded_at: None,
+            escalated_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Reference funding credits must carry to be matched to the obligation.
+    pub fn funding_reference(&self) -> String {
+        self.batch_id.to_string()
+    }
+
+    /// Amount still to be funded.
+    pub fn outstanding(&self) -> Decimal {
+        (self.amount - self.funded_amount).max(Decimal::ZERO)
+    }
+
+    /// Whether the deadline has passed with the obligation still awaiting funds.
+    pub fn is_past_deadline(&self, now: DateTime<Utc>) -> bool {
+        self.status == FundingStatus::Pending && now >= self.deadline
+    }
+
+    /// Records the total matched so far, marking the obligation funded once it is covered.
+    pub fn apply_funding(&mut self, funded_amount: Decimal) {
+        self.funded_amount = funded_amount;
+        if self.status.is_open() && funded_amount >= self.amount {
+            self.status = FundingStatus::Funded;
+            self.funded_at = Some(Utc::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    fn obligation(deadline: DateTime<Utc>) -> FundingObligation {
+        FundingObligation::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "USD".to_string(),
+            dec!(300),
+            Some(Uuid::new_v4()),
+            deadline,
+        )
+    }
+
+    #[test]
+    fn test_partial_funding_stays_open() {
+        let mut obligation = obligation(Utc::now() + Duration::hours(1));
+        obligation.apply_funding(dec!(120));
+
+        assert_eq!(obligation.status, FundingStatus::Pending);
+        assert_eq!(obligation.outstanding(), dec!(180));
+        assert!(obligation.funded_at.is_none());
+
+        obligation.apply_funding(dec!(350));
+        assert_eq!(obligation.status, FundingStatus::Funded);
+        assert_eq!(obligation.outstanding(), Decimal::ZERO);
+        assert!(obligation.funded_at.is_some());
+    }
+
+    #[test]
+    fn test_overdue_obligations_can_still_be_funded() {
+        let mut obligation = obligation(Utc::now() - Duration::minutes(5));
+        assert!(obligation.is_past_deadline(Utc::now()));
+
+        obligation.status = FundingStatus::Overdue;
+        assert!(!obligation.is_past_deadline(Utc::now()));
+        obligation.apply_funding(dec!(300));
+        assert_eq!(obligation.status, FundingStatus::Funded);
+
+        let mut defaulted = obligation.clone();
+        defaulted.status = FundingStatus::Defaulted;
+        defaulted.funded_at = None;
+        defaulted.apply_funding(dec!(300));
+        assert_eq!(defaulted.status, FundingStatus::Defaulted);
+    }
+}
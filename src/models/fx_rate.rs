@@ -0,0 +1,194 @@
+use crate::core::rounding::RoundingPolicy;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An FX rate as received from its source: units of the quote currency one unit of the
+/// base currency buys, e.g. EUR/USD 1.0850.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FxRate {
+    pub id: Uuid,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: Decimal,
+    /// Fraction of the rate kept on conversions, e.g. 0.002 for 20 bp.
+    pub spread: Decimal,
+    /// Where the rate came from, e.g. a market data feed or a treasury desk.
+    pub source: String,
+    pub effective_at: DateTime<Utc>,
+    /// Rate this one corrects, taking its effective time.
+    pub corrects_rate_id: Option<Uuid>,
+    pub correction_reason: Option<String>,
+    /// Correction that replaced this rate; superseded rates are kept for audit only.
+    pub superseded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FxRate {
+    pub fn new(
+        base_currency: impl Into<String>,
+        quote_currency: impl Into<String>,
+        rate: Decimal,
+        spread: Decimal,
+        source: impl Into<String>,
+        effective_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            base_currency: base_currency.into(),
+            quote_currency: quote_currency.into(),
+            rate,
+            spread,
+            source: source.into(),
+            effective_at,
+            corrects_rate_id: None,
+            correction_reason: None,
+            superseded_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// A correction of this rate, effective from the same time.
+    pub fn correction(&self, rate: Decimal, spread: Decimal, source: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            corrects_rate_id: Some(self.id),
+            correction_reason: Some(reason.into()),
+            ..Self::new(
+                self.base_currency.clone(),
+                self.quote_currency.clone(),
+                rate,
+                spread,
+                source,
+                self.effective_at,
+            )
+        }
+    }
+
+    /// The pair as `BASE/QUOTE`.
+    pub fn pair(&self) -> String {
+        format!("{}/{}", self.base_currency, self.quote_currency)
+    }
+
+    /// Rate conversions are made at: the rate less the spread.
+    pub fn applied_rate(&self) -> Decimal {
+        self.rate * (Decimal::ONE - self.spread)
+    }
+
+    /// Converts an amount of the base currency into the quote currency at the applied
+    /// rate, rounded to the quote currency's scale.
+    pub fn convert(&self, amount: Decimal, rounding: &RoundingPolicy) -> Decimal {
+        rounding.round(amount * self.applied_rate(), &self.quote_currency)
+    }
+
+    pub fn is_superseded(&self) -> bool {
+        self.superseded_by.is_some()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.base_currency == self.quote_currency {
+            return Err("The base and quote currencies must differ".to_string());
+        }
+        if self.rate <= Decimal::ZERO {
+            return Err("rate must be positive".to_string());
+        }
+        if self.spread < Decimal::ZERO || self.spread >= Decimal::ONE {
+            return Err("spread must be at least 0 and less than 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Parses a currency pair written `EURUSD`, `EUR/USD` or `EUR-USD` into its base and
+    /// quote currencies.
+    pub fn parse_pair(pair: &str) -> Option<(String, String)> {
+        let pair: String = pair
+            .trim()
+            .chars()
+            .filter(|c| *c != '/' && *c != '-')
+            .collect::<String>()
+            .to_uppercase();
+        if pair.len() != 6 || !pair.bytes().all(|b| b.is_ascii_uppercase()) {
+            return None;
+        }
+        let (base, quote) = pair.split_at(3);
+        Some((base.to_string(), quote.to_string()))
+    }
+}
+
+/// A conversion of funds between an account in one currency and an account in another,
+/// posted through the FX position accounts of both currencies.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FxConversion {
+    pub id: Uuid,
+    pub external_id: String,
+    pub idempotency_key: String,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub source_amount: Decimal,
+    pub source_currency: String,
+    pub destination_amount: Decimal,
+    pub destination_currency: String,
+    /// Stored rate the conversion was made at.
+    pub rate_id: Uuid,
+    pub applied_rate: Decimal,
+    /// Rate the conversion is valued at: the rate it was made at, or its latest correction.
+    pub current_rate_id: Uuid,
+    /// Transaction debiting the source account into the source currency's FX position.
+    pub debit_transaction_id: Uuid,
+    /// Transaction crediting the destination account from the destination currency's
+    /// FX position.
+    pub credit_transaction_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A revaluation adjustment posted to a conversion's destination account when the rate
+/// it was valued at is corrected.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FxRateAdjustment {
+    pub id: Uuid,
+    pub conversion_id: Uuid,
+    /// Correction the adjustment was posted for.
+    pub rate_id: Uuid,
+    pub previous_rate_id: Uuid,
+    /// Positive when the destination account was credited, negative when debited.
+    pub amount: Decimal,
+    pub currency: String,
+    pub transaction_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_fx_rate_converts_at_rate_less_spread() {
+        let rate = FxRate::new("EUR", "USD", dec!(1.0850), dec!(0.002), "ECB", Utc::now());
+        assert!(rate.validate().is_ok());
+        assert_eq!(rate.pair(), "EUR/USD");
+        assert_eq!(rate.applied_rate(), dec!(1.08283));
+        let rounding = RoundingPolicy::default();
+        assert_eq!(rate.convert(dec!(1000), &rounding), dec!(1082.83));
+
+        let corrected = rate.correction(dec!(1.0900), dec!(0.002), "ECB", "Stale feed");
+        assert_eq!(corrected.corrects_rate_id, Some(rate.id));
+        assert_eq!(corrected.effective_at, rate.effective_at);
+        assert_eq!(corrected.convert(dec!(1000), &rounding), dec!(1087.82));
+
+        assert!(FxRate::new("EUR", "EUR", dec!(1), dec!(0), "ECB", Utc::now()).validate().is_err());
+        assert!(FxRate::new("EUR", "USD", dec!(1.1), dec!(1), "ECB", Utc::now()).validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_pair() {
+        let eur_usd = Some(("EUR".to_string(), "USD".to_string()));
+        assert_eq!(FxRate::parse_pair("EURUSD"), eur_usd);
+        assert_eq!(FxRate::parse_pair("eur/usd"), eur_usd);
+        assert_eq!(FxRate::parse_pair("EUR-USD"), eur_usd);
+        assert_eq!(FxRate::parse_pair("EURUS"), None);
+        assert_eq!(FxRate::parse_pair("EUR/U5D"), None);
+    }
+}
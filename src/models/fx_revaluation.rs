@@ -0,0 +1,118 @@
+use crate::core::rounding::RoundingPolicy;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A period-end revaluation of the internal accounts' foreign-currency balances.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FxRevaluationRun {
+    pub id: Uuid,
+    pub period_end: NaiveDate,
+    pub functional_currency: String,
+    /// Account unrealized gains are credited to and losses debited from.
+    pub pnl_account_id: Uuid,
+    /// Functional-currency FX position the gains and losses are posted against.
+    pub position_account_id: Uuid,
+    /// Net unrealized gain (positive) or loss (negative) in the functional currency.
+    pub total_gain_loss: Decimal,
+    /// Currencies with a balance but no rate to the functional currency by period end;
+    /// their balances were not revalued.
+    pub missing_rates: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One balance marked to the period-end rate.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FxRevaluationLine {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    /// Balance at the end of the period, in its own currency.
+    pub balance: Decimal,
+    pub rate_id: Uuid,
+    /// Units of the functional currency per unit of the balance's currency.
+    pub rate: Decimal,
+    /// Rate the balance was last revalued at; none the first time.
+    pub previous_rate: Option<Decimal>,
+    pub functional_amount: Decimal,
+    pub gain_loss: Decimal,
+    /// Transaction posting the gain or loss, if there was one.
+    pub transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FxRevaluationLine {
+    /// A balance of a run, to be marked with `mark`.
+    pub fn new(run_id: Uuid, account_id: Uuid, currency: impl Into<String>, balance: Decimal) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            run_id,
+            account_id,
+            currency: currency.into(),
+            balance,
+            rate_id: Uuid::nil(),
+            rate: Decimal::ZERO,
+            previous_rate: None,
+            functional_amount: Decimal::ZERO,
+            gain_loss: Decimal::ZERO,
+            transaction_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Marks the balance to a rate, rounding functional amounts to the functional
+    /// currency's scale. The unrealized gain or loss is the balance's change in
+    /// functional value since it was last revalued; the first revaluation only sets the
+    /// baseline.
+    pub fn mark(
+        mut self,
+        rate_id: Uuid,
+        rate: Decimal,
+        previous_rate: Option<Decimal>,
+        functional_currency: &str,
+        rounding: &RoundingPolicy,
+    ) -> Self {
+        self.rate_id = rate_id;
+        self.rate = rate;
+        self.previous_rate = previous_rate;
+        self.functional_amount = rounding.round(self.balance * rate, functional_currency);
+        self.gain_loss = previous_rate
+            .map(|previous| rounding.round(self.balance * (rate - previous), functional_currency))
+            .unwrap_or(Decimal::ZERO);
+        self
+    }
+}
+
+/// A revaluation run with its lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxRevaluationReport {
+    pub run: FxRevaluationRun,
+    pub lines: Vec<FxRevaluationLine>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_revaluation_line_books_change_since_last_revaluation() {
+        let (run, account, rate) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let rounding = RoundingPolicy::default();
+
+        let first = FxRevaluationLine::new(run, account, "EUR", dec!(1000)).mark(rate, dec!(1.08), None, "USD", &rounding);
+        assert_eq!(first.functional_amount, dec!(1080.00));
+        assert_eq!(first.gain_loss, Decimal::ZERO);
+
+        let gain = FxRevaluationLine::new(run, account, "EUR", dec!(1000)).mark(rate, dec!(1.1), Some(dec!(1.08)), "USD", &rounding);
+        assert_eq!(gain.gain_loss, dec!(20.00));
+
+        // A short position loses when the currency strengthens
+        let loss = FxRevaluationLine::new(run, account, "EUR", dec!(-500)).mark(rate, dec!(1.1), Some(dec!(1.08)), "USD", &rounding);
+        assert_eq!(loss.gain_loss, dec!(-10.00));
+        assert_eq!(loss.functional_amount, dec!(-550.00));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::models::{AccountType, EntryType, TransactionType};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One day of ledger entries posted to the general ledger.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GlPostingRun {
+    pub id: Uuid,
+    pub posting_date: NaiveDate,
+    pub line_count: i32,
+    /// Ledger entries aggregated into the run's journal lines.
+    pub entry_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GlPostingRun {
+    pub fn new(posting_date: NaiveDate) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            posting_date,
+            line_count: 0,
+            entry_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Debits and credits to one GL account code in one currency within a posting run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct GlJournalLine {
+    pub run_id: Uuid,
+    pub line_number: i32,
+    pub gl_code: String,
+    pub currency: String,
+    pub debit_amount: Decimal,
+    pub credit_amount: Decimal,
+    pub entry_count: i64,
+}
+
+/// Ledger entries of a day grouped by what determines their GL account code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct GlPostingSummary {
+    pub transaction_type: TransactionType,
+    pub account_type: AccountType,
+    pub entry_type: EntryType,
+    /// Entries booking a fee to or from the fee revenue account, which post to the fee
+    /// income code whatever the account's type.
+    pub fee_leg: bool,
+    pub currency: String,
+    pub amount: Decimal,
+    pub entry_count: i64,
+}
+
+/// Fees charged on a day's transactions that were not booked to a fee revenue account.
+/// Their sources are debited the gross amount and destinations credited the net, so the
+/// fees are the credit side of the difference.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct GlFeeSummary {
+    pub transaction_type: TransactionType,
+    pub currency: String,
+    pub amount: Decimal,
+    pub transaction_count: i64,
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Whether a ledger code denotes money or securities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "instrument_kind", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InstrumentKind {
+    /// Money in an ISO 4217 currency.
+    #[default]
+    Cash,
+    /// Units of a security held in custody. Amounts are quantities, not money.
+    Security,
+}
+
+/// What balances and ledger entries in a code hold.
+///
+/// Balances, entries and transactions keep the code in their `currency` column, so an
+/// account holds either cash in one currency or one security. Codes not registered are
+/// cash currencies.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Instrument {
+    pub code: String,
+    pub kind: InstrumentKind,
+    /// Scheme of a security's code, e.g. ISIN or CUSIP.
+    pub identifier_type: Option<String>,
+    /// Decimal places a quantity can have; zero for whole units.
+    pub scale: i16,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Longest instrument code, e.g. a 12-character ISIN.
+pub const MAX_INSTRUMENT_CODE_LEN: usize = 12;
+
+impl Instrument {
+    /// A security whose quantities have `scale` decimal places.
+    pub fn security(code: impl Into<String>, identifier_type: Option<String>, scale: i16) -> Self {
+        Self {
+            code: code.into().trim().to_uppercase(),
+            kind: InstrumentKind::Security,
+            identifier_type,
+            scale,
+            description: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// An unregistered cash currency.
+    pub fn cash(currency: impl Into<String>) -> Self {
+        Self {
+            code: currency.into(),
+            kind: InstrumentKind::Cash,
+            identifier_type: None,
+            scale: 4,
+            description: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+
+    pub fn is_security(&self) -> bool {
+        self.kind == InstrumentKind::Security
+    }
+
+    /// Whether a code has the shape of an instrument code: 3 to 12 upper-case letters
+    /// and digits, as short as a currency code.
+    pub fn is_valid_code(code: &str) -> bool {
+        (3..=MAX_INSTRUMENT_CODE_LEN).contains(&code.len())
+            && code.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    }
+
+    /// Checks that an amount is a quantity the instrument can hold, if it is a security.
+    pub fn validate_quantity(&self, quantity: Decimal) -> Result<(), String> {
+        if self.is_security() && quantity.normalize().scale() > self.scale as u32 {
+            return Err(format!(
+                "Quantities of {} can have at most {} decimal places",
+                self.code, self.scale
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_security_quantities_follow_scale() {
+        let units = Instrument::security(" us0378331005 ", Some("ISIN".to_string()), 0);
+        assert_eq!(units.code, "US0378331005");
+        assert!(units.validate_quantity(dec!(150)).is_ok());
+        assert!(units.validate_quantity(dec!(150.00)).is_ok());
+        assert!(units.validate_quantity(dec!(150.5)).is_err());
+
+        // Cash amounts are checked by the amount rules instead
+        assert!(Instrument::cash("USD").validate_quantity(dec!(10.123)).is_ok());
+    }
+
+    #[test]
+    fn test_instrument_codes() {
+        assert!(Instrument::is_valid_code("USD"));
+        assert!(Instrument::is_valid_code("US0378331005"));
+        assert!(!Instrument::is_valid_code("us0378331005"));
+        assert!(!Instrument::is_valid_code("US03783310051"));
+        assert!(!Instrument::is_valid_code("US"));
+        assert!(!Instrument::is_valid_code(""));
+    }
+}
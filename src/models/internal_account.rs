@@ -0,0 +1,293 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::account::AccountType;
+
+/// What an internal account is for. Services find the account playing a role in a
+/// currency through the chart instead of holding its ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "internal_account_role", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InternalAccountRole {
+    /// Fees are recognized here.
+    FeeRevenue,
+    /// Fees accrue here until their batch settles, in the accrued booking mode.
+    FeeReceivable,
+    /// Monthly invoices are posted here.
+    InvoiceReceivable,
+    /// Holds amounts that cannot be applied yet, e.g. unmatched or returned funds.
+    Suspense,
+    /// The engine's open position from currency conversions.
+    FxPosition,
+    /// Offsets participants' net positions when a batch settles.
+    SettlementControl,
+    /// Collateral pledged by participants.
+    Collateral,
+}
+
+impl InternalAccountRole {
+    /// Account type an account in the role is created with unless configured otherwise.
+    pub fn default_account_type(&self) -> AccountType {
+        match self {
+            InternalAccountRole::FeeRevenue => AccountType::Revenue,
+            InternalAccountRole::FeeReceivable
+            | InternalAccountRole::InvoiceReceivable
+            | InternalAccountRole::FxPosition
+            | InternalAccountRole::SettlementControl => AccountType::Asset,
+            InternalAccountRole::Suspense | InternalAccountRole::Collateral => AccountType::Liability,
+        }
+    }
+
+    /// Whether an account in the role is a control account unless configured otherwise.
+    /// Collateral is moved by participants' own pledges and releases.
+    pub fn default_control(&self) -> bool {
+        *self != InternalAccountRole::Collateral
+    }
+}
+
+/// An entry of the configured chart, in one currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InternalAccountDefinition {
+    pub code: String,
+    pub name: String,
+    pub currency: String,
+    pub role: Option<InternalAccountRole>,
+    /// Code of the entry this one rolls up into, in the same currency.
+    pub parent_code: Option<String>,
+    pub account_type: AccountType,
+    /// Control accounts cannot be closed, or be the source or destination of a
+    /// transaction submitted through the API; only system flows post to them.
+    pub control: bool,
+}
+
+impl InternalAccountDefinition {
+    /// Defines an entry, taking the account type and control flag from the role.
+    /// Entries without a role are grouping accounts: assets and control accounts.
+    pub fn new(
+        code: impl Into<String>,
+        name: impl Into<String>,
+        currency: impl Into<String>,
+        role: Option<InternalAccountRole>,
+    ) -> Self {
+        Self {
+            code: code.into().trim().to_uppercase(),
+            name: name.into(),
+            currency: currency.into().trim().to_uppercase(),
+            account_type: role.map_or(AccountType::Asset, |r| r.default_account_type()),
+            control: match role {
+                Some(role) => role.default_control(),
+                None => true,
+            },
+            role,
+            parent_code: None,
+        }
+    }
+
+    pub fn with_parent(mut self, parent_code: impl Into<String>) -> Self {
+        self.parent_code = Some(parent_code.into().trim().to_uppercase());
+        self
+    }
+
+    pub fn with_account_type(mut self, account_type: AccountType) -> Self {
+        self.account_type = account_type;
+        self
+    }
+
+    pub fn with_control(mut self, control: bool) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// The same entry in another currency, e.g. to provision a chart template in a
+    /// currency seen for the first time.
+    pub fn in_currency(&self, currency: &str) -> Self {
+        Self {
+            currency: currency.trim().to_uppercase(),
+            ..self.clone()
+        }
+    }
+
+    /// External ID of the account backing the entry.
+    pub fn external_id(&self) -> String {
+        format!("INTERNAL-{}-{}", self.code, self.currency)
+    }
+
+    /// Checks a chart and orders it so every entry comes after its parent. Codes are
+    /// unique per currency, as are roles, and parents must be in the chart in the same
+    /// currency without forming a cycle.
+    pub fn order_chart(definitions: &[InternalAccountDefinition]) -> Result<Vec<InternalAccountDefinition>, String> {
+        let mut by_key: HashMap<(&str, &str), &InternalAccountDefinition> = HashMap::new();
+        let mut roles = HashSet::new();
+        for definition in definitions {
+            let code = definition.code.as_str();
+            if code.is_empty()
+                || code.len() > 64
+                || !code.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            {
+                return Err(format!(
+                    "Internal account code '{}' must be 1 to 64 letters, digits, '_', '-' or '.'",
+                    code
+                ));
+            }
+            if definition.currency.len() != 3 || !definition.currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!(
+                    "Internal account {} has invalid currency '{}'",
+                    code, definition.currency
+                ));
+            }
+            if by_key.insert((code, definition.currency.as_str()), definition).is_some() {
+                return Err(format!("Internal account {} is defined twice in {}", code, definition.currency));
+            }
+            if let Some(role) = definition.role {
+                if !roles.insert((role, definition.currency.as_str())) {
+                    return Err(format!(
+                        "More than one internal account has role {:?} in {}",
+                        role, definition.currency
+                    ));
+                }
+            }
+        }
+
+        let mut ordered = Vec::with_capacity(definitions.len());
+        let mut placed: HashSet<(&str, &str)> = HashSet::new();
+        for definition in definitions {
+            // Walk up to the first placed ancestor, then place the path top-down
+            let mut path = Vec::new();
+            let mut current = definition;
+            loop {
+                let key = (current.code.as_str(), current.currency.as_str());
+                if placed.contains(&key) {
+                    break;
+                }
+                if path.iter().any(|d: &&InternalAccountDefinition| d.code == current.code) {
+                    return Err(format!("Internal account {} is its own ancestor", current.code));
+                }
+                path.push(current);
+                let Some(parent_code) = &current.parent_code else {
+                    break;
+                };
+                current = by_key
+                    .get(&(parent_code.as_str(), current.currency.as_str()))
+                    .copied()
+                    .ok_or_else(|| {
+                        format!(
+                            "Internal account {} has parent {} which is not in the chart in {}",
+                            current.code, parent_code, current.currency
+                        )
+                    })?;
+            }
+            for definition in path.into_iter().rev() {
+                placed.insert((definition.code.as_str(), definition.currency.as_str()));
+                ordered.push(definition.clone());
+            }
+        }
+        Ok(ordered)
+    }
+}
+
+/// A provisioned entry of the chart of internal accounts.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InternalAccount {
+    pub code: String,
+    pub currency: String,
+    pub name: String,
+    pub role: Option<InternalAccountRole>,
+    pub parent_code: Option<String>,
+    /// The account the entry is backed by.
+    pub account_id: Uuid,
+    pub control: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What provisioned an entry of the chart in a currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "provisioning_trigger", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProvisioningTrigger {
+    /// The chart configured for the currency at startup.
+    Startup,
+    /// The first transaction in a currency the chart was not yet provisioned in.
+    FirstUse,
+}
+
+/// Audit record of an entry of the chart provisioned in a currency.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InternalAccountProvisioning {
+    pub id: Uuid,
+    pub code: String,
+    pub currency: String,
+    pub account_id: Uuid,
+    pub trigger: ProvisioningTrigger,
+    /// False when an existing account with the entry's external ID was adopted.
+    pub created_account: bool,
+    pub provisioned_at: DateTime<Utc>,
+}
+
+impl InternalAccountProvisioning {
+    pub fn new(entry: &InternalAccount, trigger: ProvisioningTrigger, created_account: bool) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            code: entry.code.clone(),
+            currency: entry.currency.clone(),
+            account_id: entry.account_id,
+            trigger,
+            created_account,
+            provisioned_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_is_ordered_parents_first() {
+        let chart = vec![
+            InternalAccountDefinition::new("fee-revenue", "Fee revenue", "usd", Some(InternalAccountRole::FeeRevenue))
+                .with_parent("FEES"),
+            InternalAccountDefinition::new("FEES", "Fees", "USD", None),
+            InternalAccountDefinition::new("COLLATERAL", "Collateral", "USD", Some(InternalAccountRole::Collateral)),
+        ];
+        let ordered = InternalAccountDefinition::order_chart(&chart).unwrap();
+        let codes: Vec<&str> = ordered.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, vec!["FEES", "FEE-REVENUE", "COLLATERAL"]);
+        assert_eq!(ordered[1].account_type, AccountType::Revenue);
+        assert_eq!(ordered[1].external_id(), "INTERNAL-FEE-REVENUE-USD");
+        assert!(ordered[1].control);
+        assert!(!ordered[2].control);
+
+        let eur = ordered[1].in_currency("eur");
+        assert_eq!(eur.currency, "EUR");
+        assert_eq!(eur.parent_code.as_deref(), Some("FEES"));
+        assert_eq!(eur.external_id(), "INTERNAL-FEE-REVENUE-EUR");
+    }
+
+    #[test]
+    fn test_invalid_charts_are_rejected() {
+        let missing_parent = vec![InternalAccountDefinition::new("SUSPENSE", "Suspense", "USD", None).with_parent("OPS")];
+        assert!(InternalAccountDefinition::order_chart(&missing_parent).is_err());
+
+        let other_currency = vec![
+            InternalAccountDefinition::new("OPS", "Operations", "EUR", None),
+            InternalAccountDefinition::new("SUSPENSE", "Suspense", "USD", None).with_parent("OPS"),
+        ];
+        assert!(InternalAccountDefinition::order_chart(&other_currency).is_err());
+
+        let cycle = vec![
+            InternalAccountDefinition::new("A", "A", "USD", None).with_parent("B"),
+            InternalAccountDefinition::new("B", "B", "USD", None).with_parent("A"),
+        ];
+        assert!(InternalAccountDefinition::order_chart(&cycle).is_err());
+
+        let shared_role = vec![
+            InternalAccountDefinition::new("SUSP-1", "Suspense", "USD", Some(InternalAccountRole::Suspense)),
+            InternalAccountDefinition::new("SUSP-2", "Suspense", "USD", Some(InternalAccountRole::Suspense)),
+        ];
+        assert!(InternalAccountDefinition::order_chart(&shared_role).is_err());
+    }
+}
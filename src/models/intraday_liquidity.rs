@@ -0,0 +1,239 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// Number of largest obligations kept per participant.
+const LARGEST_OBLIGATIONS: usize = 3;
+
+/// What moved a participant's intraday liquidity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "VARCHAR", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LiquidityFlowSource {
+    /// A transaction settled gross on the RTGS route, at its settlement time.
+    Rtgs,
+    /// A net settlement instruction, at the time its execution completed.
+    Instruction,
+}
+
+/// One settlement moving liquidity into or out of a participant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct LiquidityFlow {
+    pub participant_id: Uuid,
+    pub source: LiquidityFlowSource,
+    /// The transaction or settlement instruction.
+    pub reference_id: Uuid,
+    /// Positive for inflows, negative for outflows.
+    pub amount: Decimal,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A participant's intraday liquidity usage on one day, in the style of the BCBS 248
+/// monitoring tools.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantLiquidity {
+    pub participant_id: Uuid,
+    /// Booked balance at the start of the day.
+    pub opening_balance: Decimal,
+    /// Lowest balance the participant may hold; below zero it is an intraday credit line.
+    pub floor: Decimal,
+    /// Liquidity the participant could draw during the day: the opening balance down
+    /// to its floor.
+    pub available_liquidity: Decimal,
+    pub total_inflows: Decimal,
+    pub total_outflows: Decimal,
+    /// Largest amount by which cumulative outflows exceeded cumulative inflows during
+    /// the day; zero if the participant was never a net payer.
+    pub peak_net_outflow: Decimal,
+    pub peak_net_outflow_at: Option<DateTime<Utc>>,
+    /// Largest outflows of the day with their timing, largest first.
+    pub largest_obligations: Vec<LiquidityFlow>,
+    pub flow_count: usize,
+}
+
+impl ParticipantLiquidity {
+    /// Walks a participant's flows in time order, tracking the net cumulative position.
+    /// Flows at the same instant are netted before the position is measured.
+    pub fn from_flows(participant_id: Uuid, opening_balance: Decimal, floor: Decimal, flows: &[LiquidityFlow]) -> Self {
+        let mut ordered: Vec<&LiquidityFlow> = flows.iter().collect();
+        ordered.sort_by_key(|flow| flow.occurred_at);
+
+        let mut position = Decimal::ZERO;
+        let mut peak_net_outflow = Decimal::ZERO;
+        let mut peak_net_outflow_at = None;
+        for (i, flow) in ordered.iter().enumerate() {
+            position += flow.amount;
+            let instant_ends = ordered.get(i + 1).is_none_or(|next| next.occurred_at != flow.occurred_at);
+            if instant_ends && -position > peak_net_outflow {
+                peak_net_outflow = -position;
+                peak_net_outflow_at = Some(flow.occurred_at);
+            }
+        }
+
+        let mut outflows: Vec<LiquidityFlow> = flows.iter().filter(|flow| flow.amount.is_sign_negative()).cloned().collect();
+        let outflows_total: Decimal = outflows.iter().map(|flow| -flow.amount).sum();
+        outflows.sort_by(|a, b| a.amount.cmp(&b.amount).then(a.occurred_at.cmp(&b.occurred_at)));
+        outflows.truncate(LARGEST_OBLIGATIONS);
+
+        Self {
+            participant_id,
+            opening_balance,
+            floor,
+            available_liquidity: opening_balance - floor,
+            total_inflows: flows.iter().filter(|flow| flow.amount.is_sign_positive()).map(|flow| flow.amount).sum(),
+            total_outflows: outflows_total,
+            peak_net_outflow,
+            peak_net_outflow_at,
+            largest_obligations: outflows,
+            flow_count: flows.len(),
+        }
+    }
+
+    /// Intraday credit extended below a zero balance.
+    pub fn credit_line(&self) -> Decimal {
+        if self.floor.is_sign_negative() {
+            -self.floor
+        } else {
+            Decimal::ZERO
+        }
+    }
+
+    /// Available liquidity left at the peak of the day's usage.
+    pub fn headroom(&self) -> Decimal {
+        self.available_liquidity - self.peak_net_outflow
+    }
+}
+
+/// Intraday liquidity usage of every participant active in one currency on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntradayLiquidityReport {
+    pub date: NaiveDate,
+    pub currency: String,
+    pub participants: Vec<ParticipantLiquidity>,
+}
+
+impl IntradayLiquidityReport {
+    /// Renders the report as CSV with a header row and one row per participant. Amounts
+    /// have four decimal places; only each participant's largest obligation is included.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "date,currency,participant_id,opening_balance,credit_line,available_liquidity,total_inflows,\
+             total_outflows,peak_net_outflow,peak_net_outflow_at,largest_obligation,largest_obligation_at,headroom,flow_count\n",
+        );
+        for participant in &self.participants {
+            let largest = participant.largest_obligations.first();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{},{},{},{:.4},{}",
+                self.date,
+                self.currency,
+                participant.participant_id,
+                participant.opening_balance,
+                participant.credit_line(),
+                participant.available_liquidity,
+                participant.total_inflows,
+                participant.total_outflows,
+                participant.peak_net_outflow,
+                participant.peak_net_outflow_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+                largest.map(|flow| format!("{:.4}", -flow.amount)).unwrap_or_default(),
+                largest.map(|flow| flow.occurred_at.to_rfc3339()).unwrap_or_default(),
+                participant.headroom(),
+                participant.flow_count,
+            );
+        }
+        csv
+    }
+
+    /// File name the report is exported under.
+    pub fn file_name(&self, extension: &str) -> String {
+        format!("intraday-liquidity-{}-{}.{}", self.currency, self.date, extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn flow(participant_id: Uuid, amount: Decimal, hour: u32) -> LiquidityFlow {
+        LiquidityFlow {
+            participant_id,
+            source: LiquidityFlowSource::Rtgs,
+            reference_id: Uuid::new_v4(),
+            amount,
+            occurred_at: Utc.with_ymd_and_hms(2024, 3, 15, hour, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_peak_net_outflow() {
+        let participant = Uuid::new_v4();
+        let flows = vec![
+            flow(participant, dec!(-300), 11),
+            flow(participant, dec!(-100), 9),
+            flow(participant, dec!(250), 10),
+            flow(participant, dec!(400), 14),
+            // Netted with the inflow at the same instant, so never seen on its own
+            flow(participant, dec!(-500), 14),
+        ];
+
+        let liquidity = ParticipantLiquidity::from_flows(participant, dec!(1000), dec!(-200), &flows);
+
+        // Position: -100 at 9, +150 at 10, -150 at 11, -250 at 14
+        assert_eq!(liquidity.peak_net_outflow, dec!(250));
+        assert_eq!(liquidity.peak_net_outflow_at, Some(Utc.with_ymd_and_hms(2024, 3, 15, 14, 0, 0).unwrap()));
+        assert_eq!(liquidity.total_inflows, dec!(650));
+        assert_eq!(liquidity.total_outflows, dec!(900));
+        assert_eq!(liquidity.credit_line(), dec!(200));
+        assert_eq!(liquidity.available_liquidity, dec!(1200));
+        assert_eq!(liquidity.headroom(), dec!(950));
+        assert_eq!(liquidity.flow_count, 5);
+
+        let largest: Vec<Decimal> = liquidity.largest_obligations.iter().map(|flow| flow.amount).collect();
+        assert_eq!(largest, vec![dec!(-500), dec!(-300), dec!(-100)]);
+    }
+
+    #[test]
+    fn test_net_receiver_uses_no_liquidity() {
+        let participant = Uuid::new_v4();
+        let flows = vec![flow(participant, dec!(100), 9), flow(participant, dec!(-50), 10)];
+
+        let liquidity = ParticipantLiquidity::from_flows(participant, dec!(0), dec!(0), &flows);
+
+        assert_eq!(liquidity.peak_net_outflow, dec!(0));
+        assert_eq!(liquidity.peak_net_outflow_at, None);
+        assert_eq!(liquidity.credit_line(), dec!(0));
+    }
+
+    #[test]
+    fn test_report_csv() {
+        let participant = Uuid::new_v4();
+        let report = IntradayLiquidityReport {
+            date: NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            currency: "USD".to_string(),
+            participants: vec![ParticipantLiquidity::from_flows(
+                participant,
+                dec!(500),
+                dec!(0),
+                &[flow(participant, dec!(-120.50), 9)],
+            )],
+        };
+
+        let csv = report.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("date,currency,participant_id,opening_balance"));
+        assert_eq!(
+            rows[1],
+            format!(
+                "2024-03-15,USD,{},500.0000,0.0000,500.0000,0.0000,120.5000,120.5000,2024-03-15T09:00:00+00:00,120.5000,2024-03-15T09:00:00+00:00,379.5000,1",
+                participant
+            )
+        );
+        assert_eq!(report.file_name("csv"), "intraday-liquidity-USD-2024-03-15.csv");
+    }
+}
@@ -0,0 +1,325 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// How a participant's monthly volume in a currency is priced. Participants without a
+/// contract are invoiced for nothing, though their invoices still report their volume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct InvoiceContract {
+    pub account_id: Uuid,
+    pub currency: String,
+    /// Transactions a month that are not charged the per-transaction price.
+    pub free_transactions: i32,
+    pub per_transaction_price: Decimal,
+    /// Charged on the amount of the month's transactions, e.g. 0.001 for 10 basis points.
+    pub volume_rate: Decimal,
+    /// Most a month's invoice can bill, if capped.
+    pub monthly_cap: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A participant's payments and transfers in one currency over a month.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct ParticipantUsage {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub transaction_count: i64,
+    pub transaction_volume: Decimal,
+    pub fees_charged: Decimal,
+}
+
+/// What an invoice line bills or credits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "VARCHAR", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InvoiceLineKind {
+    /// The month's transactions at the per-transaction price.
+    Transactions,
+    /// Credit for the free transactions.
+    FreeTransactions,
+    /// The month's transaction amount at the volume rate.
+    Volume,
+    /// Credit bringing the invoice down to the monthly cap.
+    Cap,
+}
+
+impl InvoiceLineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InvoiceLineKind::Transactions => "TRANSACTIONS",
+            InvoiceLineKind::FreeTransactions => "FREE_TRANSACTIONS",
+            InvoiceLineKind::Volume => "VOLUME",
+            InvoiceLineKind::Cap => "CAP",
+        }
+    }
+}
+
+/// One month's invoice to a participant in a currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Invoice {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub transaction_count: i64,
+    pub transaction_volume: Decimal,
+    /// Fees already charged on the month's transactions. They were collected as the
+    /// transactions posted, so they are reported but not billed again.
+    pub fees_charged: Decimal,
+    /// Sum of the line items.
+    pub total_amount: Decimal,
+    /// Transaction posting the total as a receivable, none when nothing was billed.
+    pub transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct InvoiceLineItem {
+    pub invoice_id: Uuid,
+    pub line_number: i32,
+    pub kind: InvoiceLineKind,
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit_price: Decimal,
+    pub amount: Decimal,
+}
+
+/// An invoice with its line items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvoiceDocument {
+    pub invoice: Invoice,
+    pub lines: Vec<InvoiceLineItem>,
+}
+
+impl InvoiceDocument {
+    /// Prices a participant's usage over a period under its contract, if it has one.
+    /// Free transactions are credited at the per-transaction price, and the cap is
+    /// applied to what is left of the transaction and volume charges.
+    pub fn price(
+        usage: &ParticipantUsage,
+        contract: Option<&InvoiceContract>,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+    ) -> Self {
+        let invoice_id = Uuid::new_v4();
+        let mut charges: Vec<(InvoiceLineKind, String, Decimal, Decimal)> = Vec::new();
+
+        if let Some(contract) = contract {
+            let count = Decimal::from(usage.transaction_count);
+            if contract.per_transaction_price > Decimal::ZERO {
+                charges.push((
+                    InvoiceLineKind::Transactions,
+                    "Transactions".to_string(),
+                    count,
+                    contract.per_transaction_price,
+                ));
+                let free = count.min(Decimal::from(contract.free_transactions));
+                if free > Decimal::ZERO {
+                    charges.push((
+                        InvoiceLineKind::FreeTransactions,
+                        format!("Free transactions ({} a month)", contract.free_transactions),
+                        free,
+                        -contract.per_transaction_price,
+                    ));
+                }
+            }
+            if contract.volume_rate > Decimal::ZERO {
+                charges.push((
+                    InvoiceLineKind::Volume,
+                    "Transaction volume".to_string(),
+                    usage.transaction_volume,
+                    contract.volume_rate,
+                ));
+            }
+        }
+
+        let mut lines: Vec<InvoiceLineItem> = charges
+            .into_iter()
+            .enumerate()
+            .map(|(index, (kind, description, quantity, unit_price))| InvoiceLineItem {
+                invoice_id,
+                line_number: index as i32 + 1,
+                kind,
+                description,
+                quantity,
+                unit_price,
+                amount: (quantity * unit_price).round_dp(4),
+            })
+            .collect();
+
+        let subtotal: Decimal = lines.iter().map(|line| line.amount).sum();
+        if let Some(cap) = contract.and_then(|contract| contract.monthly_cap) {
+            if subtotal > cap {
+                lines.push(InvoiceLineItem {
+                    invoice_id,
+                    line_number: lines.len() as i32 + 1,
+                    kind: InvoiceLineKind::Cap,
+                    description: format!("Monthly cap of {:.4}", cap),
+                    quantity: Decimal::ONE,
+                    unit_price: cap - subtotal,
+                    amount: cap - subtotal,
+                });
+            }
+        }
+
+        let invoice = Invoice {
+            id: invoice_id,
+            account_id: usage.account_id,
+            currency: usage.currency.clone(),
+            period_start,
+            period_end,
+            transaction_count: usage.transaction_count,
+            transaction_volume: usage.transaction_volume,
+            fees_charged: usage.fees_charged,
+            total_amount: lines.iter().map(|line| line.amount).sum(),
+            transaction_id: None,
+            created_at: Utc::now(),
+        };
+        Self { invoice, lines }
+    }
+
+    /// Renders the invoice as CSV with a header row and one row per line item, each
+    /// repeating the invoice's participant, currency and period.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "invoice_id,account_id,currency,period_start,period_end,line_number,kind,description,quantity,unit_price,amount\n",
+        );
+        for line in &self.lines {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},\"{}\",{:.4},{},{:.4}",
+                self.invoice.id,
+                self.invoice.account_id,
+                self.invoice.currency,
+                self.invoice.period_start,
+                self.invoice.period_end,
+                line.line_number,
+                line.kind.as_str(),
+                line.description.replace('"', "\"\""),
+                line.quantity,
+                line.unit_price.normalize(),
+                line.amount,
+            );
+        }
+        csv
+    }
+
+    /// File name the invoice is exported under.
+    pub fn file_name(&self, extension: &str) -> String {
+        format!(
+            "invoice-{}-{}-{}.{}",
+            self.invoice.account_id,
+            self.invoice.currency,
+            self.invoice.period_start.format("%Y-%m"),
+            extension
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn usage(transaction_count: i64, transaction_volume: Decimal) -> ParticipantUsage {
+        ParticipantUsage {
+            account_id: Uuid::nil(),
+            currency: "USD".to_string(),
+            transaction_count,
+            transaction_volume,
+            fees_charged: dec!(12.5),
+        }
+    }
+
+    fn contract(free_transactions: i32, per_transaction_price: Decimal, volume_rate: Decimal, monthly_cap: Option<Decimal>) -> InvoiceContract {
+        InvoiceContract {
+            account_id: Uuid::nil(),
+            currency: "USD".to_string(),
+            free_transactions,
+            per_transaction_price,
+            volume_rate,
+            monthly_cap,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn march() -> (NaiveDate, NaiveDate) {
+        (NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 3, 31).unwrap())
+    }
+
+    #[test]
+    fn test_price_with_free_tier() {
+        let (start, end) = march();
+        let contract = contract(100, dec!(0.25), dec!(0.001), None);
+        let document = InvoiceDocument::price(&usage(150, dec!(20000)), Some(&contract), start, end);
+
+        let amounts: Vec<(InvoiceLineKind, Decimal)> = document.lines.iter().map(|line| (line.kind, line.amount)).collect();
+        assert_eq!(
+            amounts,
+            vec![
+                (InvoiceLineKind::Transactions, dec!(37.5)),
+                (InvoiceLineKind::FreeTransactions, dec!(-25)),
+                (InvoiceLineKind::Volume, dec!(20)),
+            ]
+        );
+        assert_eq!(document.invoice.total_amount, dec!(32.5));
+        assert_eq!(document.invoice.fees_charged, dec!(12.5));
+
+        // Fewer transactions than the free tier are all free
+        let quiet = InvoiceDocument::price(&usage(40, dec!(0)), Some(&contract), start, end);
+        assert_eq!(quiet.lines[1].quantity, dec!(40));
+        assert_eq!(quiet.invoice.total_amount, dec!(0));
+    }
+
+    #[test]
+    fn test_price_capped() {
+        let (start, end) = march();
+        let contract = contract(0, dec!(1), dec!(0), Some(dec!(50)));
+        let document = InvoiceDocument::price(&usage(80, dec!(1000)), Some(&contract), start, end);
+
+        let cap = document.lines.last().unwrap();
+        assert_eq!((cap.kind, cap.line_number, cap.amount), (InvoiceLineKind::Cap, 2, dec!(-30)));
+        assert_eq!(document.invoice.total_amount, dec!(50));
+
+        let under = InvoiceDocument::price(&usage(20, dec!(1000)), Some(&contract), start, end);
+        assert!(under.lines.iter().all(|line| line.kind != InvoiceLineKind::Cap));
+        assert_eq!(under.invoice.total_amount, dec!(20));
+    }
+
+    #[test]
+    fn test_price_without_contract_bills_nothing() {
+        let (start, end) = march();
+        let document = InvoiceDocument::price(&usage(10, dec!(500)), None, start, end);
+
+        assert!(document.lines.is_empty());
+        assert_eq!(document.invoice.total_amount, dec!(0));
+        assert_eq!(document.invoice.transaction_count, 10);
+    }
+
+    #[test]
+    fn test_invoice_csv() {
+        let (start, end) = march();
+        let contract = contract(1, dec!(0.5), dec!(0), None);
+        let document = InvoiceDocument::price(&usage(3, dec!(300)), Some(&contract), start, end);
+
+        let csv = document.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("invoice_id,account_id,currency,period_start"));
+        assert_eq!(
+            rows[2],
+            format!(
+                "{},{},USD,2024-03-01,2024-03-31,2,FREE_TRANSACTIONS,\"Free transactions (1 a month)\",1.0000,-0.5,-0.5000",
+                document.invoice.id,
+                Uuid::nil()
+            )
+        );
+        assert_eq!(document.file_name("csv"), format!("invoice-{}-USD-2024-03.csv", Uuid::nil()));
+    }
+}
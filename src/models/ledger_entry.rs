@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// `leg` metadata value of entries booking a fee to or from a fee revenue account.
+pub const FEE_LEG: &str = "FEE";
+
 /// Entry type for double-entry bookkeeping.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "entry_type", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -51,6 +54,12 @@ pub struct LedgerEntry {
     pub effective_date: NaiveDate,
     pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
+    /// Human-readable description, as shown on statements.
+    #[serde(default)]
+    pub narrative: Option<String>,
+    /// Counterparty's reference for the transaction, e.g. an invoice number.
+    #[serde(default)]
+    pub reference: Option<String>,
 }
 
 impl LedgerEntry {
@@ -74,6 +83,8 @@ impl LedgerEntry {
             effective_date,
             metadata: None,
             created_at: Utc::now(),
+            narrative: None,
+            reference: None,
         }
     }
 
@@ -97,15 +108,42 @@ impl LedgerEntry {
             effective_date,
             metadata: None,
             created_at: Utc::now(),
+            narrative: None,
+            reference: None,
         }
     }
 
+    /// Sets the entry's narrative and reference, usually those of its transaction.
+    pub fn with_narrative(mut self, narrative: Option<String>, reference: Option<String>) -> Self {
+        self.narrative = narrative;
+        self.reference = reference;
+        self
+    }
+
     /// Adds metadata to the entry.
     pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
         self.metadata = Some(metadata);
         self
     }
 
+    /// Marks the entry as a fee leg. `detail` is merged into the entry's metadata.
+    pub fn as_fee_leg(self, detail: serde_json::Value) -> Self {
+        let mut metadata = serde_json::json!({ "leg": FEE_LEG });
+        if let (Some(target), serde_json::Value::Object(detail)) = (metadata.as_object_mut(), detail) {
+            target.extend(detail);
+        }
+        self.with_metadata(metadata)
+    }
+
+    /// Returns true if the entry books a fee to or from a fee revenue account.
+    pub fn is_fee_leg(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("leg"))
+            .and_then(|leg| leg.as_str())
+            == Some(FEE_LEG)
+    }
+
     /// Returns the signed amount based on entry type.
     /// Positive for debit, negative for credit.
     pub fn signed_amount(&self) -> Decimal {
@@ -116,6 +154,54 @@ impl LedgerEntry {
     }
 }
 
+/// A ledger entry in an account's history, with the balance the account's previous
+/// entry in the currency left, so the running balance can be checked for gaps.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LedgerHistoryEntry {
+    #[sqlx(flatten)]
+    pub entry: LedgerEntry,
+    /// `balance_after` of the previous entry in sequence; `None` for the first entry.
+    pub previous_balance: Option<Decimal>,
+}
+
+impl LedgerHistoryEntry {
+    /// The balance the entry should have left: the previous balance plus a credit or
+    /// less a debit. `None` for the first entry, whose opening balance is not entered.
+    pub fn expected_balance_after(&self) -> Option<Decimal> {
+        self.previous_balance.map(|previous| previous - self.entry.signed_amount())
+    }
+
+    /// Returns false if the entry's balance does not follow from the previous one,
+    /// e.g. because a balance was changed without an entry.
+    pub fn is_continuous(&self) -> bool {
+        match self.expected_balance_after() {
+            Some(expected) => expected == self.entry.balance_after,
+            None => true,
+        }
+    }
+}
+
+/// A page of an account's ledger history, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountHistory {
+    pub entries: Vec<LedgerHistoryEntry>,
+    /// Entries matching the filters across all pages.
+    pub total: i64,
+    /// True if any entry on the page breaks the running balance.
+    pub gaps_detected: bool,
+}
+
+impl AccountHistory {
+    pub fn new(entries: Vec<LedgerHistoryEntry>, total: i64) -> Self {
+        let gaps_detected = entries.iter().any(|entry| !entry.is_continuous());
+        Self {
+            entries,
+            total,
+            gaps_detected,
+        }
+    }
+}
+
 /// A pair of ledger entries representing a complete double-entry transaction.
 #[derive(Debug, Clone)]
 pub struct LedgerEntryPair {
@@ -383,4 +469,27 @@ mod tests {
         assert_eq!(deserialized.amount, dec!(100.5000));
         assert_eq!(deserialized.entry_type, EntryType::Debit);
     }
+
+    #[test]
+    fn test_history_running_balance_gaps() {
+        let history_entry = |entry: LedgerEntry, previous_balance: Option<Decimal>| LedgerHistoryEntry {
+            entry,
+            previous_balance,
+        };
+        let today = Utc::now().date_naive();
+        let (tx, account) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let first = history_entry(LedgerEntry::credit(tx, account, dec!(100), "USD".into(), dec!(100), today), None);
+        let debit = history_entry(LedgerEntry::debit(tx, account, dec!(30), "USD".into(), dec!(70), today), Some(dec!(100)));
+        assert!(first.is_continuous());
+        assert_eq!(debit.expected_balance_after(), Some(dec!(70)));
+        assert!(debit.is_continuous());
+        assert!(!AccountHistory::new(vec![debit, first], 2).gaps_detected);
+
+        // A balance changed without an entry in between
+        let credit = history_entry(LedgerEntry::credit(tx, account, dec!(10), "USD".into(), dec!(95), today), Some(dec!(70)));
+        assert_eq!(credit.expected_balance_after(), Some(dec!(80)));
+        assert!(!credit.is_continuous());
+        assert!(AccountHistory::new(vec![credit], 3).gaps_detected);
+    }
 }
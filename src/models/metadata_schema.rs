@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::TransactionType;
+
+/// JSON Schema that transaction metadata of one type must satisfy.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MetadataSchema {
+    pub transaction_type: TransactionType,
+    pub schema: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MetadataSchema {
+    pub fn new(transaction_type: TransactionType, schema: serde_json::Value) -> Self {
+        let now = Utc::now();
+        Self {
+            transaction_type,
+            schema,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Validates metadata against the schema, returning one message per violation.
+    /// Missing metadata is checked as an empty object so required properties are reported.
+    pub fn validate(&self, metadata: Option<&serde_json::Value>) -> Result<(), Vec<String>> {
+        let validator = jsonschema::validator_for(&self.schema).map_err(|e| vec![e.to_string()])?;
+        let empty = serde_json::Value::Object(serde_json::Map::new());
+        let instance = metadata.unwrap_or(&empty);
+
+        let errors: Vec<String> = validator
+            .iter_errors(instance)
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{} (at {})", e, path)
+                }
+            })
+            .collect();
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Returns an error describing why the schema itself is invalid, if it is.
+    pub fn check_schema(schema: &serde_json::Value) -> Result<(), String> {
+        jsonschema::validator_for(schema).map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fee_schema() -> MetadataSchema {
+        MetadataSchema::new(
+            TransactionType::Fee,
+            json!({
+                "type": "object",
+                "required": ["invoice_number"],
+                "properties": {"invoice_number": {"type": "string", "minLength": 1}}
+            }),
+        )
+    }
+
+    #[test]
+    fn test_valid_metadata() {
+        assert!(fee_schema().validate(Some(&json!({"invoice_number": "INV-1"}))).is_ok());
+    }
+
+    #[test]
+    fn test_missing_metadata_reports_required_property() {
+        let errors = fee_schema().validate(None).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("invoice_number"));
+    }
+
+    #[test]
+    fn test_violation_includes_path() {
+        let errors = fee_schema().validate(Some(&json!({"invoice_number": 42}))).unwrap_err();
+        assert!(errors[0].ends_with("(at /invoice_number)"));
+    }
+
+    #[test]
+    fn test_check_schema() {
+        assert!(MetadataSchema::check_schema(&fee_schema().schema).is_ok());
+        assert!(MetadataSchema::check_schema(&json!({"type": 5})).is_err());
+    }
+}
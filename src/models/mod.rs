@@ -1,15 +1,121 @@
 pub mod account;
+pub mod accounting_period;
+pub mod account_activity;
+pub mod account_anonymization;
+pub mod account_identifier;
+pub mod account_status_change;
+pub mod alert_rule;
 pub mod account_balance;
+pub mod balance_break;
+pub mod balance_explanation;
+pub mod balance_incident;
+pub mod balance_projection;
+pub mod balance_reservation;
+pub mod batch_cut_off_change;
+pub mod batch_progress;
+pub mod batch_reconciliation;
+pub mod batch_template;
+pub mod bilateral_pair;
+pub mod counterparty_restriction;
 pub mod currency;
+pub mod fee_settlement;
+pub mod file_delivery;
+pub mod finality;
+pub mod funding_obligation;
+pub mod fx_rate;
+pub mod fx_revaluation;
+pub mod gl_posting;
+pub mod instrument;
+pub mod internal_account;
+pub mod intraday_liquidity;
+pub mod invoice;
 pub mod ledger_entry;
+pub mod metadata_schema;
+pub mod net_debit_cap;
+pub mod netting_advice;
+pub mod netting_metrics;
 pub mod netting_position;
+pub mod netting_report;
+pub mod outbox;
+pub mod participant_default;
+pub mod pvp_settlement;
+pub mod replication_slot;
+pub mod risk_hold;
+pub mod saga;
 pub mod settlement_batch;
+pub mod settlement_profile;
+pub mod settlement_window;
+pub mod sync;
 pub mod transaction;
+pub mod transaction_amendment;
+pub mod transaction_audit;
+pub mod transaction_group;
+pub mod transaction_type_definition;
+pub mod transaction_hold;
+pub mod transaction_submission;
 
-pub use account::{Account, AccountStatus, AccountType};
+pub use account::{Account, AccountStatus, AccountType, ANONYMIZED_ACCOUNT_NAME};
+pub use accounting_period::{AccountingPeriod, BalanceBasis, DatedBalance, PeriodStatus};
+pub use account_activity::{AccountActivity, ActivityGranularity, ActivityPeriod, ActivityTypeSummary};
+pub use account_anonymization::{AccountAnonymization, ACCOUNT_PII_FIELDS};
+pub use account_identifier::{AccountIdentifier, AccountIdentifierType};
+pub use account_status_change::{AccountStatusChange, StatusChangeReason, StatusReasonCode};
+pub use alert_rule::{AlertRule, AlertRuleType};
 pub use account_balance::AccountBalance;
+pub use balance_break::{BalanceBreak, BalanceCheck};
+pub use balance_explanation::{
+    BalanceExplanation, BalanceMovementCategory, BalanceMovementSummary, LedgerMovementGroup,
+};
+pub use balance_incident::{BalanceFloor, BalanceIncident, BalanceIncidentStatus};
+pub use balance_projection::{BalanceProjection, BalanceProjectionLag, SequencedLedgerEntry};
+pub use balance_reservation::{BalanceReservation, BalanceReservationStatus};
+pub use batch_cut_off_change::{BatchCutOffChange, CutOffChangeKind};
+pub use batch_progress::BatchProcessingProgress;
+pub use batch_reconciliation::{BatchCheck, BatchLedgerTotals, BatchReconciliation, BatchReconciliationBreak};
+pub use batch_template::BatchTemplate;
+pub use bilateral_pair::BilateralPairRecord;
+pub use counterparty_restriction::{
+    CounterpartyAuditAction, CounterpartyListMode, CounterpartyRestriction,
+    CounterpartyRestrictionAudit,
+};
 pub use currency::Currency;
-pub use ledger_entry::{EntryType, LedgerEntry};
-pub use netting_position::{NettingPosition, NettingSummary};
-pub use settlement_batch::{BatchStatus, SettlementBatch};
-pub use transaction::{TransactionRecord, TransactionStatus, TransactionType};
+pub use fee_settlement::{FeeAccrual, FeeSettlement};
+pub use file_delivery::{DeliveryStatus, FileDelivery};
+pub use finality::FinalityRecord;
+pub use funding_obligation::{FundingObligation, FundingStatus};
+pub use fx_rate::{FxConversion, FxRate, FxRateAdjustment};
+pub use fx_revaluation::{FxRevaluationLine, FxRevaluationReport, FxRevaluationRun};
+pub use gl_posting::{GlFeeSummary, GlJournalLine, GlPostingRun, GlPostingSummary};
+pub use instrument::{Instrument, InstrumentKind, MAX_INSTRUMENT_CODE_LEN};
+pub use internal_account::{
+    InternalAccount, InternalAccountDefinition, InternalAccountProvisioning, InternalAccountRole, ProvisioningTrigger,
+};
+pub use intraday_liquidity::{IntradayLiquidityReport, LiquidityFlow, LiquidityFlowSource, ParticipantLiquidity};
+pub use invoice::{Invoice, InvoiceContract, InvoiceDocument, InvoiceLineItem, InvoiceLineKind, ParticipantUsage};
+pub use ledger_entry::{AccountHistory, EntryType, LedgerEntry, LedgerHistoryEntry, FEE_LEG};
+pub use metadata_schema::MetadataSchema;
+pub use net_debit_cap::{NetDebitCap, NetDebitCapAction};
+pub use netting_advice::{AdviceInstruction, NettingAdvice};
+pub use netting_metrics::DailyNettingMetrics;
+pub use netting_position::{NettingPosition, NettingSummary, PositionContribution, PositionSide};
+pub use netting_report::NettingReportRecord;
+pub use outbox::OutboxMessage;
+pub use participant_default::{DefaultResolution, LossAllocation, LossAllocationBasis, ParticipantDefault};
+pub use pvp_settlement::{PvpLeg, PvpSettlement, PvpStatus};
+pub use replication_slot::ReplicationSlotStatus;
+pub use risk_hold::{DuplicatePaymentAction, RiskHold, RiskHoldAction, RiskHoldAudit, RiskHoldStatus, RiskTrigger};
+pub use saga::{SagaState, SagaStatus};
+pub use settlement_batch::{BatchStatus, CutOffApprovalPolicy, InstructionStrategy, NettingMode, SettlementBatch};
+pub use settlement_profile::{BankAccountType, PaymentRail, SettlementProfile};
+pub use settlement_window::SettlementWindow;
+pub use sync::{SyncToken, SyncedAccount, SyncedTransaction};
+pub use transaction::{
+    DuplicateExternalIdAction, EngineMode, ExternalIdClaim, ExternalIdScope, FeeBookingMode, FeeReversalPolicy, SettlementRoute,
+    TransactionPriority, TransactionRecord, TransactionStatus, TransactionType,
+};
+pub use transaction_amendment::{AmendedField, AmendmentTarget, TransactionAmendment};
+pub use transaction_audit::{TransactionAuditAction, TransactionAuditEntry};
+pub use transaction_group::{TransactionGroup, TransactionGroupStatus};
+pub use transaction_type_definition::{TransactionTypeDefinition, TypeFeePolicy};
+pub use transaction_hold::TransactionHold;
+pub use transaction_submission::{SubmissionStatus, TransactionSubmission};
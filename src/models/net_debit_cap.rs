@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// The most an account may owe, net of what it receives, within one settlement batch
+/// of a currency. Accounts without one are held to the configured default, if any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct NetDebitCap {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub cap: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What becomes of a transaction that would take its payer past its net debit cap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NetDebitCapAction {
+    /// Fail the assignment with `LimitExceeded`.
+    #[default]
+    Reject,
+    /// Move the transaction to the next batch in the same settlement window, rejecting
+    /// it only if the payer has no headroom there either.
+    Queue,
+}
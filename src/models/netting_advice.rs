@@ -0,0 +1,217 @@
+use super::{NettingPosition, PositionSide};
+use crate::interop::pdf::TextDocument;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// A settlement instruction as it concerns one participant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdviceInstruction {
+    pub instruction_id: Uuid,
+    /// Whether the participant pays or receives the instruction.
+    pub side: PositionSide,
+    pub counterparty_id: Uuid,
+    pub amount: Decimal,
+    pub instruction_type: String,
+    pub status: String,
+}
+
+/// What a participant is told about its part in a netted batch: its gross flows, net
+/// position, the instructions that settle it and, for net payers, when it must fund.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NettingAdvice {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub participant_id: Uuid,
+    pub currency: String,
+    pub settlement_date: NaiveDate,
+    pub gross_receivable: Decimal,
+    pub gross_payable: Decimal,
+    /// Positive to receive, negative to pay.
+    pub net_position: Decimal,
+    pub transaction_count: i32,
+    pub instructions: Json<Vec<AdviceInstruction>>,
+    /// When a net payer's funds must be in place; `None` for other participants.
+    pub funding_deadline: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl NettingAdvice {
+    /// Creates the advice for a participant's netting position.
+    pub fn new(
+        position: &NettingPosition,
+        settlement_date: NaiveDate,
+        instructions: Vec<AdviceInstruction>,
+        funding_deadline: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            batch_id: position.batch_id,
+            participant_id: position.participant_id,
+            currency: position.currency.clone(),
+            settlement_date,
+            gross_receivable: position.gross_receivable,
+            gross_payable: position.gross_payable,
+            net_position: position.net_position,
+            transaction_count: position.transaction_count,
+            instructions: Json(instructions),
+            funding_deadline: funding_deadline.filter(|_| position.is_net_payer()),
+            generated_at: Utc::now(),
+        }
+    }
+
+    /// Renders the advice as CSV with a header row and one row per instruction, each
+    /// repeating the participant's position. An advice without instructions has one row
+    /// with the instruction columns empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "batch_id,participant_id,currency,settlement_date,gross_receivable,gross_payable,net_position,transaction_count,funding_deadline,instruction_id,side,counterparty_id,amount,instruction_type,status\n",
+        );
+        let position = format!(
+            "{},{},{},{},{:.4},{:.4},{:.4},{},{}",
+            self.batch_id,
+            self.participant_id,
+            self.currency,
+            self.settlement_date,
+            self.gross_receivable,
+            self.gross_payable,
+            self.net_position,
+            self.transaction_count,
+            self.funding_deadline.map(|deadline| deadline.to_rfc3339()).unwrap_or_default(),
+        );
+        if self.instructions.is_empty() {
+            let _ = writeln!(csv, "{},,,,,,", position);
+        }
+        for instruction in self.instructions.iter() {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{:.4},{},{}",
+                position,
+                instruction.instruction_id,
+                instruction.side.as_str(),
+                instruction.counterparty_id,
+                instruction.amount,
+                instruction.instruction_type,
+                instruction.status,
+            );
+        }
+        csv
+    }
+
+    /// Lays the advice out as a text document for people to read.
+    pub fn to_document(&self) -> TextDocument {
+        let mut document = TextDocument::new()
+            .line("Netting Advice")
+            .line("")
+            .line(format!("Participant:      {}", self.participant_id))
+            .line(format!("Batch:            {}", self.batch_id))
+            .line(format!("Settlement date:  {}", self.settlement_date))
+            .line(format!("Currency:         {}", self.currency))
+            .line("")
+            .line(format!("Gross receivable: {:.4}", self.gross_receivable))
+            .line(format!("Gross payable:    {:.4}", self.gross_payable))
+            .line(format!("Net position:     {:.4}", self.net_position))
+            .line(format!("Transactions:     {}", self.transaction_count));
+        if let Some(deadline) = self.funding_deadline {
+            document = document.line(format!(
+                "Fund {:.4} by {}",
+                self.net_position.abs(),
+                deadline.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+
+        document = document.line("").line("Instructions");
+        if self.instructions.is_empty() {
+            document = document.line("  None");
+        }
+        for instruction in self.instructions.iter() {
+            let direction = match instruction.side {
+                PositionSide::Payable => "Pay to",
+                PositionSide::Receivable => "Receive from",
+            };
+            document = document.line(format!(
+                "  {} {} {:.4} ({}, {})",
+                direction, instruction.counterparty_id, instruction.amount, instruction.instruction_type, instruction.status
+            ));
+        }
+
+        document.line("").line(format!("Generated {}", self.generated_at.format("%Y-%m-%d %H:%M:%S UTC")))
+    }
+
+    /// Renders the advice as a PDF.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        self.to_document().to_pdf()
+    }
+
+    /// File name the advice is exported under.
+    pub fn file_name(&self, extension: &str) -> String {
+        format!(
+            "netting-advice-{}-{}-{}.{}",
+            self.batch_id, self.participant_id, self.currency, extension
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn advice(net_payer: bool) -> NettingAdvice {
+        let mut position = NettingPosition::new(Uuid::new_v4(), Uuid::new_v4(), "USD".to_string());
+        position.add_receivable(dec!(200));
+        position.add_payable(if net_payer { dec!(500) } else { dec!(100) });
+        let instructions = vec![AdviceInstruction {
+            instruction_id: Uuid::new_v4(),
+            side: if net_payer { PositionSide::Payable } else { PositionSide::Receivable },
+            counterparty_id: Uuid::new_v4(),
+            amount: position.absolute_net(),
+            instruction_type: "MultilateralNet".to_string(),
+            status: "Pending".to_string(),
+        }];
+        let deadline = Utc::now() + chrono::Duration::hours(2);
+        NettingAdvice::new(&position, Utc::now().date_naive(), instructions, Some(deadline))
+    }
+
+    #[test]
+    fn test_funding_deadline_only_for_net_payers() {
+        assert!(advice(true).funding_deadline.is_some());
+        assert!(advice(false).funding_deadline.is_none());
+    }
+
+    #[test]
+    fn test_advice_csv() {
+        let advice = advice(true);
+        let csv = advice.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+        assert!(lines[1].contains("-300.0000"));
+        assert!(lines[1].contains(",PAYABLE,"));
+
+        let empty = NettingAdvice {
+            instructions: Json(Vec::new()),
+            ..advice
+        };
+        let csv = empty.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+    }
+
+    #[test]
+    fn test_advice_pdf() {
+        let advice = advice(true);
+        let pdf = String::from_utf8(advice.to_pdf()).unwrap();
+
+        assert!(pdf.starts_with("%PDF-"));
+        assert!(pdf.contains("Net position:     -300.0000"));
+        assert!(pdf.contains("Fund 300.0000 by"));
+        assert!(advice.file_name("pdf").ends_with("-USD.pdf"));
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Netting totals for one currency on one day.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DailyNettingMetrics {
+    pub metric_date: NaiveDate,
+    pub currency: String,
+    pub batches_processed: i64,
+    pub transactions_netted: i64,
+    pub gross_volume: Decimal,
+    pub net_volume: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DailyNettingMetrics {
+    /// Share of gross volume removed by netting, in percent.
+    pub fn efficiency(&self) -> Decimal {
+        if self.gross_volume.is_zero() {
+            return Decimal::ZERO;
+        }
+        (self.gross_volume - self.net_volume) / self.gross_volume * Decimal::from(100)
+    }
+}
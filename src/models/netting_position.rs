@@ -1,3 +1,4 @@
+use crate::models::{TransactionRecord, TransactionType};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -95,6 +96,62 @@ impl NettingPosition {
     }
 }
 
+/// Side of a participant's position a transaction counts towards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PositionSide {
+    /// The participant pays the transaction.
+    Payable,
+    /// The participant receives the transaction.
+    Receivable,
+}
+
+impl PositionSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PositionSide::Payable => "PAYABLE",
+            PositionSide::Receivable => "RECEIVABLE",
+        }
+    }
+}
+
+/// A transaction that contributed to a participant's gross receivable or payable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionContribution {
+    pub transaction_id: Uuid,
+    pub external_id: String,
+    pub transaction_type: TransactionType,
+    pub type_code: Option<String>,
+    pub side: PositionSide,
+    /// The other participant of the transaction.
+    pub counterparty_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PositionContribution {
+    /// Describes a transaction from the point of view of one of its participants.
+    pub fn new(participant_id: Uuid, transaction: &TransactionRecord) -> Self {
+        let (side, counterparty_id) = if transaction.source_account_id == participant_id {
+            (PositionSide::Payable, transaction.destination_account_id)
+        } else {
+            (PositionSide::Receivable, transaction.source_account_id)
+        };
+        Self {
+            transaction_id: transaction.id,
+            external_id: transaction.external_id.clone(),
+            transaction_type: transaction.transaction_type,
+            type_code: transaction.type_code.clone(),
+            side,
+            counterparty_id,
+            amount: transaction.amount,
+            currency: transaction.currency.clone(),
+            created_at: transaction.created_at,
+        }
+    }
+}
+
 /// Summary of netting results for a batch.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NettingSummary {
@@ -294,6 +351,31 @@ mod tests {
         assert!(efficiency > dec!(85) && efficiency < dec!(86));
     }
 
+    #[test]
+    fn test_position_contribution_sides() {
+        let payer = Uuid::new_v4();
+        let payee = Uuid::new_v4();
+        let transaction = TransactionRecord::new(
+            "PAY-1".to_string(),
+            TransactionType::Payment,
+            payer,
+            payee,
+            dec!(100),
+            "USD".to_string(),
+            Decimal::ZERO,
+            "IDEM-1".to_string(),
+        );
+
+        let paid = PositionContribution::new(payer, &transaction);
+        assert_eq!(paid.side, PositionSide::Payable);
+        assert_eq!(paid.counterparty_id, payee);
+        assert_eq!(paid.amount, dec!(100));
+
+        let received = PositionContribution::new(payee, &transaction);
+        assert_eq!(received.side, PositionSide::Receivable);
+        assert_eq!(received.counterparty_id, payer);
+    }
+
     #[test]
     fn test_serialization() {
         let position = NettingPosition::new(Uuid::new_v4(), Uuid::new_v4(), "USD".to_string());
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Stored netting report of a batch: the key aggregates plus the full report.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NettingReportRecord {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub currency: String,
+    pub total_transactions: i32,
+    pub participant_count: i32,
+    pub gross_volume: Decimal,
+    pub net_volume: Decimal,
+    pub reduction_amount: Decimal,
+    /// Share of gross volume removed by netting, in percent.
+    pub reduction_percentage: Decimal,
+    /// The full `NettingReport` as generated.
+    pub report: serde_json::Value,
+    pub generated_at: DateTime<Utc>,
+    /// Named settlement window of the batch, taken from the batch when stored.
+    pub window_id: Option<Uuid>,
+}
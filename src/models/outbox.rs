@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Event waiting in the outbox to be published to Kafka.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OutboxMessage {
+    pub id: Uuid,
+    pub topic: String,
+    /// Kafka record key, so related events stay in order.
+    pub partition_key: Option<String>,
+    /// Serialized event envelope.
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+}
+
+impl OutboxMessage {
+    pub fn new(topic: impl Into<String>, partition_key: Option<String>, payload: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            topic: topic.into(),
+            partition_key,
+            payload,
+            created_at: Utc::now(),
+            published_at: None,
+            attempts: 0,
+            last_error: None,
+        }
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.published_at.is_some()
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// How the obligations of a participant in default are taken out of a batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "default_resolution", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DefaultResolution {
+    /// The batch is re-netted without the defaulter's transactions.
+    #[default]
+    Exclude,
+    /// Every transaction stands; the defaulter's unfunded net debit is shared among the
+    /// surviving participants.
+    LossSharing,
+}
+
+/// How an unfunded net debit is split among the surviving participants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LossAllocationBasis {
+    /// Pro-rata to each net receiver's credit in the batch.
+    #[default]
+    NetCredit,
+    /// Pro-rata to what the defaulter owed each participant gross; net credit if it
+    /// owed no one.
+    Exposure,
+}
+
+/// Share of a defaulter's unfunded obligation borne by one surviving participant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LossAllocation {
+    pub participant_id: Uuid,
+    /// Amount taken off the participant's net position.
+    pub amount: Decimal,
+}
+
+/// A participant declared in default in a settlement batch.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ParticipantDefault {
+    pub id: Uuid,
+    pub batch_id: Uuid,
+    pub participant_id: Uuid,
+    pub currency: String,
+    /// Net debit the participant failed to fund.
+    pub unfunded_amount: Decimal,
+    pub resolution: DefaultResolution,
+    pub reason: Option<String>,
+    /// The full `DefaultReport` as generated.
+    pub report: serde_json::Value,
+    pub declared_at: DateTime<Utc>,
+}
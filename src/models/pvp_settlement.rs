@@ -0,0 +1,164 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Lifecycle of a payment-versus-payment settlement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "pvp_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PvpStatus {
+    /// Held until both payers have the funds for their payment.
+    AwaitingFunding,
+    /// Both payments settled together.
+    Settled,
+    /// Cancelled before both payers were funded, by an operator or at its deadline.
+    /// Neither payment was made.
+    Cancelled,
+}
+
+/// Which of a PvP settlement's payments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PvpLeg {
+    First,
+    Second,
+}
+
+/// A payment-versus-payment trade: two payments in different currencies between the
+/// same counterparties, made simultaneously or not at all, so neither counterparty pays
+/// without being paid (Herstatt risk).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PvpSettlement {
+    /// Also the `link_id` of the two payments.
+    pub id: Uuid,
+    pub external_id: String,
+    pub idempotency_key: String,
+    pub first_source_account_id: Uuid,
+    pub first_destination_account_id: Uuid,
+    pub first_amount: Decimal,
+    pub first_currency: String,
+    /// Whether the first payer had the funds when last checked.
+    pub first_funded: bool,
+    pub second_source_account_id: Uuid,
+    pub second_destination_account_id: Uuid,
+    pub second_amount: Decimal,
+    pub second_currency: String,
+    pub second_funded: bool,
+    pub status: PvpStatus,
+    /// When the settlement is cancelled if both payers are not funded by then.
+    pub expires_at: DateTime<Utc>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancel_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PvpSettlement {
+    /// A settlement awaiting funding for `timeout`. Its payments are set with
+    /// `with_first_payment` and `with_second_payment`.
+    pub fn new(external_id: impl Into<String>, idempotency_key: impl Into<String>, timeout: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            external_id: external_id.into(),
+            idempotency_key: idempotency_key.into(),
+            first_source_account_id: Uuid::nil(),
+            first_destination_account_id: Uuid::nil(),
+            first_amount: Decimal::ZERO,
+            first_currency: String::new(),
+            first_funded: false,
+            second_source_account_id: Uuid::nil(),
+            second_destination_account_id: Uuid::nil(),
+            second_amount: Decimal::ZERO,
+            second_currency: String::new(),
+            second_funded: false,
+            status: PvpStatus::AwaitingFunding,
+            expires_at: now + timeout,
+            settled_at: None,
+            cancelled_at: None,
+            cancel_reason: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_first_payment(mut self, source: Uuid, destination: Uuid, amount: Decimal, currency: impl Into<String>) -> Self {
+        self.first_source_account_id = source;
+        self.first_destination_account_id = destination;
+        self.first_amount = amount;
+        self.first_currency = currency.into();
+        self
+    }
+
+    pub fn with_second_payment(mut self, source: Uuid, destination: Uuid, amount: Decimal, currency: impl Into<String>) -> Self {
+        self.second_source_account_id = source;
+        self.second_destination_account_id = destination;
+        self.second_amount = amount;
+        self.second_currency = currency.into();
+        self
+    }
+
+    /// Whether the settlement is past its deadline at `now` without having settled.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.status == PvpStatus::AwaitingFunding && now >= self.expires_at
+    }
+
+    /// Whether both payers are funded.
+    pub fn is_funded(&self) -> bool {
+        self.first_funded && self.second_funded
+    }
+
+    /// Checks the payments go between the same counterparties in opposite directions
+    /// and different currencies.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.first_currency == self.second_currency {
+            return Err("The two payments must be in different currencies".to_string());
+        }
+        if self.first_amount <= Decimal::ZERO || self.second_amount <= Decimal::ZERO {
+            return Err("Both payment amounts must be positive".to_string());
+        }
+        let accounts = [
+            self.first_source_account_id,
+            self.first_destination_account_id,
+            self.second_source_account_id,
+            self.second_destination_account_id,
+        ];
+        if accounts.iter().enumerate().any(|(i, a)| accounts[i + 1..].contains(a)) {
+            return Err("The four accounts of a PvP settlement must be distinct".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn settlement(second_currency: &str) -> PvpSettlement {
+        PvpSettlement::new("PVP-1", "IDEM-1", Duration::minutes(30))
+            .with_first_payment(Uuid::new_v4(), Uuid::new_v4(), dec!(1000), "USD")
+            .with_second_payment(Uuid::new_v4(), Uuid::new_v4(), dec!(920), second_currency)
+    }
+
+    #[test]
+    fn test_pvp_needs_two_currencies_and_funding_on_both_sides() {
+        assert!(settlement("USD").validate().is_err());
+
+        let mut pvp = settlement("EUR");
+        assert!(pvp.validate().is_ok());
+        assert!(!pvp.is_funded());
+        pvp.first_funded = true;
+        assert!(!pvp.is_funded());
+        pvp.second_funded = true;
+        assert!(pvp.is_funded());
+
+        assert!(!pvp.is_expired(Utc::now()));
+        assert!(pvp.is_expired(pvp.expires_at));
+        pvp.status = PvpStatus::Settled;
+        assert!(!pvp.is_expired(pvp.expires_at));
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// State of a logical replication slot and how much WAL it holds back.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReplicationSlotStatus {
+    pub slot_name: String,
+    pub plugin: Option<String>,
+    /// True while a session is decoding from the slot.
+    pub active: bool,
+    pub restart_lsn: Option<String>,
+    /// Position up to which changes have been published and acknowledged.
+    pub confirmed_flush_lsn: Option<String>,
+    /// WAL written since the confirmed position: changes not yet published.
+    pub lag_bytes: i64,
+    /// WAL the server keeps around for the slot.
+    pub retained_bytes: i64,
+}
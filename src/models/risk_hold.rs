@@ -0,0 +1,123 @@
+use super::TransactionType;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Review state of a held transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "risk_hold_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RiskHoldStatus {
+    /// Waiting for a reviewer; nothing has been posted.
+    Held,
+    /// Released by a reviewer and posted.
+    Released,
+    /// Rejected by a reviewer; it will never be posted.
+    Rejected,
+}
+
+impl RiskHoldStatus {
+    /// Returns true while the held request may still be amended, before a decision.
+    pub fn is_amendable(&self) -> bool {
+        *self == RiskHoldStatus::Held
+    }
+}
+
+/// Step recorded in a risk hold's audit trail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "risk_hold_action", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RiskHoldAction {
+    Held,
+    Released,
+    Rejected,
+    /// Released but failed to post, so returned to the queue.
+    Reopened,
+}
+
+/// What happens to a payment that looks like a duplicate of a recent one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplicatePaymentAction {
+    /// Post it, raising a `POSSIBLE_DUPLICATE` event on the alerts topic.
+    Warn,
+    /// Hold it for review like any other risk rule.
+    #[default]
+    Hold,
+    /// Refuse it with `POSSIBLE_DUPLICATE`.
+    Reject,
+}
+
+/// Risk rule a transaction tripped, with what it was measured against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RiskTrigger {
+    /// The amount is above the currency's review threshold.
+    LargeAmount { threshold: Decimal },
+    /// The source account has never settled a transaction to the destination.
+    NewCounterparty,
+    /// The source account's transactions in the window, including this one, exceed the
+    /// configured count or amount.
+    Velocity {
+        window_secs: u64,
+        count: i64,
+        amount: Decimal,
+    },
+    /// A transaction with the same source, destination, amount and currency but another
+    /// idempotency key was submitted within the window.
+    PossibleDuplicate {
+        transaction_id: Uuid,
+        window_secs: u64,
+    },
+}
+
+/// A transaction held for risk review before posting.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RiskHold {
+    pub id: Uuid,
+    pub idempotency_key: String,
+    pub external_id: String,
+    #[sqlx(rename = "type")]
+    pub transaction_type: TransactionType,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    /// The `LedgerTransactionRequest` posted on release.
+    pub request: serde_json::Value,
+    /// The `RiskTrigger`s that held it.
+    pub triggers: serde_json::Value,
+    pub status: RiskHoldStatus,
+    /// The posted transaction, once released.
+    pub transaction_id: Option<Uuid>,
+    pub reviewed_by: Option<String>,
+    pub review_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// Entry in a risk hold's audit trail.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RiskHoldAudit {
+    pub id: Uuid,
+    pub hold_id: Uuid,
+    pub action: RiskHoldAction,
+    pub actor: Option<String>,
+    pub reason: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl RiskHoldAudit {
+    pub fn new(hold_id: Uuid, action: RiskHoldAction, actor: Option<String>, reason: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            hold_id,
+            action,
+            actor,
+            reason,
+            recorded_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Lifecycle of a saga.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "saga_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SagaStatus {
+    /// Steps are being executed.
+    Running,
+    /// Every step succeeded.
+    Completed,
+    /// A step failed or timed out; completed steps are being undone.
+    Compensating,
+    /// Every completed step was undone.
+    Compensated,
+    /// A compensation failed; the saga needs manual intervention.
+    Failed,
+}
+
+impl SagaStatus {
+    /// Returns true if the saga will not make further progress on its own.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, SagaStatus::Completed | SagaStatus::Compensated | SagaStatus::Failed)
+    }
+}
+
+/// Persisted state of a saga.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SagaState {
+    pub id: Uuid,
+    pub saga_type: String,
+    pub status: SagaStatus,
+    /// Steps completed while running; steps left to compensate while compensating.
+    pub current_step: i32,
+    /// Serialized saga context after the last step.
+    pub context: serde_json::Value,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SagaState {
+    pub fn new(saga_type: impl Into<String>, context: serde_json::Value) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            saga_type: saga_type.into(),
+            status: SagaStatus::Running,
+            current_step: 0,
+            context,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_saga_is_running() {
+        let state = SagaState::new("test", serde_json::json!({}));
+        assert_eq!(state.status, SagaStatus::Running);
+        assert_eq!(state.current_step, 0);
+        assert!(!state.status.is_terminal());
+        assert!(!SagaStatus::Compensating.is_terminal());
+        assert!(SagaStatus::Failed.is_terminal());
+    }
+}
@@ -36,6 +36,46 @@ impl BatchStatus {
     }
 }
 
+/// How a batch's transactions are netted for settlement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "netting_mode", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NettingMode {
+    /// Each participant settles one net position against all the others.
+    #[default]
+    Multilateral,
+    /// Each pair of participants settles its own net obligation.
+    Bilateral,
+}
+
+/// How multilateral positions are turned into settlement instructions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "instruction_strategy", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InstructionStrategy {
+    /// Match the largest payers to the largest receivers.
+    #[default]
+    Greedy,
+    /// Partition participants into zero-sum groups to use the fewest instructions.
+    MinimumCount,
+    /// Net payers fund the currency's settlement control account and net receivers are
+    /// paid from it, as cash moves outside the engine. Falls back to `Greedy` when the
+    /// currency has no settlement control account.
+    ControlAccount,
+}
+
+/// Who has to sign off a change to a batch's cut-off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "cut_off_approval_policy", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CutOffApprovalPolicy {
+    /// A second person must approve the requester's change.
+    #[default]
+    FourEyes,
+    /// The requester may approve their own change.
+    SingleOperator,
+}
+
 /// Represents a settlement batch that groups transactions for batch processing.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct SettlementBatch {
@@ -57,6 +97,14 @@ pub struct SettlementBatch {
     pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Position of this batch within its settlement window (date, currency and named window), from 1.
+    pub sequence_number: i32,
+    /// Named settlement window the batch belongs to, if any.
+    pub window_id: Option<Uuid>,
+    pub netting_mode: NettingMode,
+    pub cut_off_approval: CutOffApprovalPolicy,
+    /// Instruction strategy of this batch; None for the engine's configured strategy.
+    pub instruction_strategy: Option<InstructionStrategy>,
 }
 
 impl SettlementBatch {
@@ -75,6 +123,11 @@ impl SettlementBatch {
             metadata: None,
             created_at: Utc::now(),
             completed_at: None,
+            sequence_number: 1,
+            window_id: None,
+            netting_mode: NettingMode::Multilateral,
+            cut_off_approval: CutOffApprovalPolicy::FourEyes,
+            instruction_strategy: None,
         }
     }
 
@@ -89,11 +142,50 @@ impl SettlementBatch {
         self
     }
 
+    /// Places the batch in a named settlement window.
+    pub fn with_window(mut self, window_id: Uuid) -> Self {
+        self.window_id = Some(window_id);
+        self
+    }
+
+    /// Sets how the batch is netted.
+    pub fn with_netting_mode(mut self, netting_mode: NettingMode) -> Self {
+        self.netting_mode = netting_mode;
+        self
+    }
+
+    /// Sets how the batch's instructions are generated, overriding the engine's strategy.
+    pub fn with_instruction_strategy(mut self, strategy: InstructionStrategy) -> Self {
+        self.instruction_strategy = Some(strategy);
+        self
+    }
+
+    /// Sets who has to approve changes to the batch's cut-off.
+    pub fn with_cut_off_approval(mut self, policy: CutOffApprovalPolicy) -> Self {
+        self.cut_off_approval = policy;
+        self
+    }
+
     /// Checks if the batch can accept a new transaction.
     pub fn can_accept_transaction(&self) -> bool {
         self.status.can_accept_transactions() && Utc::now() < self.cut_off_time
     }
 
+    /// Returns true if adding a transaction of `amount` would push the batch past either cap.
+    /// An empty batch never exceeds its caps, so a single oversized transaction still has a home.
+    pub fn would_exceed_caps(
+        &self,
+        amount: Decimal,
+        max_transactions: Option<i32>,
+        max_gross_amount: Option<Decimal>,
+    ) -> bool {
+        if self.total_transactions == 0 {
+            return false;
+        }
+        max_transactions.is_some_and(|max| self.total_transactions + 1 > max)
+            || max_gross_amount.is_some_and(|max| self.gross_amount + amount > max)
+    }
+
     /// Adds a transaction to the batch totals.
     pub fn add_transaction(&mut self, amount: Decimal, fee: Decimal) {
         self.total_transactions += 1;
@@ -371,4 +463,19 @@ mod tests {
         assert_eq!(deserialized.status, BatchStatus::Pending);
         assert_eq!(deserialized.currency, "USD");
     }
+
+    #[test]
+    fn test_batch_caps() {
+        let mut batch = SettlementBatch::for_today(Utc::now() + Duration::hours(2), "USD".to_string());
+        assert_eq!(batch.sequence_number, 1);
+
+        // Empty batches accept anything
+        assert!(!batch.would_exceed_caps(dec!(1000000), Some(1), Some(dec!(100))));
+
+        batch.add_transaction(dec!(60), Decimal::ZERO);
+        assert!(batch.would_exceed_caps(dec!(1), Some(1), None));
+        assert!(!batch.would_exceed_caps(dec!(40), Some(2), Some(dec!(100))));
+        assert!(batch.would_exceed_caps(dec!(40.01), Some(2), Some(dec!(100))));
+        assert!(!batch.would_exceed_caps(dec!(1000000), None, None));
+    }
 }
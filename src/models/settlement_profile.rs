@@ -0,0 +1,332 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Type of the bank account a participant settles to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "bank_account_type", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BankAccountType {
+    #[default]
+    Checking,
+    Savings,
+}
+
+/// Payment rail a participant prefers its net settlement to travel over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "payment_rail", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PaymentRail {
+    /// US ACH, exported as a NACHA file. Needs an ABA routing and account number.
+    #[default]
+    Ach,
+    /// SEPA credit transfer in euro, exported as pacs.008. Needs an IBAN.
+    Sepa,
+    /// Cross-border wire, exported as pacs.008. Needs a BIC and an IBAN or account number.
+    Swift,
+}
+
+impl PaymentRail {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentRail::Ach => "ACH",
+            PaymentRail::Sepa => "SEPA",
+            PaymentRail::Swift => "SWIFT",
+        }
+    }
+}
+
+/// Bank details a participant's net settlement in one currency is paid to or collected
+/// from.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SettlementProfile {
+    pub account_id: Uuid,
+    pub currency: String,
+    /// Name of the account holder as it should appear on payment files.
+    pub account_name: String,
+    /// 9-digit ABA routing number of the participant's bank.
+    pub routing_number: Option<String>,
+    pub bank_account_number: Option<String>,
+    pub bank_account_type: BankAccountType,
+    /// BIC of the participant's bank.
+    pub bic: Option<String>,
+    pub iban: Option<String>,
+    pub rail: PaymentRail,
+    /// Cut-off for the participant's settlement in this currency, overriding the
+    /// settlement window's.
+    pub cut_off_time: Option<NaiveTime>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SettlementProfile {
+    /// Creates a USD profile settling over ACH.
+    pub fn new(
+        account_id: Uuid,
+        account_name: impl Into<String>,
+        routing_number: impl Into<String>,
+        bank_account_number: impl Into<String>,
+        bank_account_type: BankAccountType,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            account_id,
+            currency: "USD".to_string(),
+            account_name: account_name.into(),
+            routing_number: Some(routing_number.into()),
+            bank_account_number: Some(bank_account_number.into()),
+            bank_account_type,
+            bic: None,
+            iban: None,
+            rail: PaymentRail::Ach,
+            cut_off_time: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Creates a profile settling to an IBAN over SEPA or SWIFT. The IBAN and BIC are
+    /// stored without spaces, in upper case.
+    pub fn international(
+        account_id: Uuid,
+        currency: impl Into<String>,
+        account_name: impl Into<String>,
+        iban: impl Into<String>,
+        bic: Option<String>,
+        rail: PaymentRail,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            account_id,
+            currency: currency.into(),
+            account_name: account_name.into(),
+            routing_number: None,
+            bank_account_number: None,
+            bank_account_type: BankAccountType::Checking,
+            bic: bic.map(|bic| normalize(&bic)),
+            iban: Some(normalize(&iban.into())),
+            rail,
+            cut_off_time: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_cut_off_time(mut self, cut_off_time: NaiveTime) -> Self {
+        self.cut_off_time = Some(cut_off_time);
+        self
+    }
+
+    /// Checks that the profile carries what its rail needs, along with the ABA check
+    /// digit, the IBAN checksum and the BIC format of any details given.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.currency.len() != 3 {
+            return Err("Currency must be a 3-letter ISO code".to_string());
+        }
+        if self.account_name.trim().is_empty() {
+            return Err("Account name is required".to_string());
+        }
+        if let Some(routing_number) = &self.routing_number {
+            if !is_valid_routing_number(routing_number) {
+                return Err(format!("'{}' is not a valid ABA routing number", routing_number));
+            }
+        }
+        if let Some(account_number) = &self.bank_account_number {
+            let max_len = if self.rail == PaymentRail::Ach { 17 } else { 34 };
+            let account_number = account_number.trim();
+            if account_number.is_empty() || account_number.len() > max_len {
+                return Err(format!("Bank account number must be 1 to {} characters", max_len));
+            }
+        }
+        if let Some(iban) = &self.iban {
+            if !is_valid_iban(iban) {
+                return Err(format!("'{}' is not a valid IBAN", iban));
+            }
+        }
+        if let Some(bic) = &self.bic {
+            if !is_valid_bic(bic) {
+                return Err(format!("'{}' is not a valid BIC", bic));
+            }
+        }
+
+        match self.rail {
+            PaymentRail::Ach => {
+                if self.currency != "USD" {
+                    return Err("ACH profiles must be in USD".to_string());
+                }
+                if self.routing_number.is_none() || self.bank_account_number.is_none() {
+                    return Err("ACH profiles need a routing number and bank account number".to_string());
+                }
+            }
+            PaymentRail::Sepa => {
+                if self.currency != "EUR" {
+                    return Err("SEPA profiles must be in EUR".to_string());
+                }
+                if self.iban.is_none() {
+                    return Err("SEPA profiles need an IBAN".to_string());
+                }
+            }
+            PaymentRail::Swift => {
+                if self.bic.is_none() {
+                    return Err("SWIFT profiles need a BIC".to_string());
+                }
+                if self.iban.is_none() && self.bank_account_number.is_none() {
+                    return Err("SWIFT profiles need an IBAN or bank account number".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Account identifier for ISO 20022 messages: the IBAN, or else the account number.
+    pub fn account_identifier(&self) -> Option<&str> {
+        self.iban.as_deref().or(self.bank_account_number.as_deref())
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase()
+}
+
+/// Returns true if the value is nine digits with a valid ABA check digit.
+pub fn is_valid_routing_number(routing_number: &str) -> bool {
+    let digits: Vec<u32> = routing_number.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() != 9 || routing_number.len() != 9 {
+        return false;
+    }
+
+    let checksum = 3 * (digits[0] + digits[3] + digits[6])
+        + 7 * (digits[1] + digits[4] + digits[7])
+        + (digits[2] + digits[5] + digits[8]);
+    checksum.is_multiple_of(10)
+}
+
+/// Returns true if the value is a country code, two check digits and up to 30
+/// alphanumerics whose ISO 13616 mod-97 checksum is 1.
+pub fn is_valid_iban(iban: &str) -> bool {
+    let bytes = iban.as_bytes();
+    if !(15..=34).contains(&bytes.len())
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..4].iter().all(u8::is_ascii_digit)
+        || !bytes.iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    {
+        return false;
+    }
+
+    // Move the country code and check digits to the end, then read letters as 10..35
+    let mut remainder = 0u32;
+    for &byte in bytes[4..].iter().chain(&bytes[..4]) {
+        let value = (byte as char).to_digit(36).unwrap_or(0);
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+/// Returns true if the value is a BIC: four letters for the bank, two for the country,
+/// two alphanumerics for the location and an optional three for the branch.
+pub fn is_valid_bic(bic: &str) -> bool {
+    let bytes = bic.as_bytes();
+    (bytes.len() == 8 || bytes.len() == 11)
+        && bytes[..6].iter().all(u8::is_ascii_uppercase)
+        && bytes[6..].iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routing_number_check_digit() {
+        assert!(is_valid_routing_number("021000021"));
+        assert!(is_valid_routing_number("011000015"));
+        assert!(!is_valid_routing_number("021000022"));
+        assert!(!is_valid_routing_number("02100002"));
+        assert!(!is_valid_routing_number("02100002A"));
+    }
+
+    #[test]
+    fn test_iban_checksum() {
+        assert!(is_valid_iban("DE89370400440532013000"));
+        assert!(is_valid_iban("GB82WEST12345698765432"));
+        assert!(!is_valid_iban("DE89370400440532013001"));
+        assert!(!is_valid_iban("de89370400440532013000"));
+        assert!(!is_valid_iban("DE8937040044"));
+    }
+
+    #[test]
+    fn test_bic_format() {
+        assert!(is_valid_bic("DEUTDEFF"));
+        assert!(is_valid_bic("DEUTDEFF500"));
+        assert!(!is_valid_bic("DEUTDEF"));
+        assert!(!is_valid_bic("DEU1DEFF"));
+    }
+
+    #[test]
+    fn test_profile_validation() {
+        let profile = SettlementProfile::new(
+            Uuid::new_v4(),
+            "Acme Corp",
+            "021000021",
+            "123456789",
+            BankAccountType::Checking,
+        );
+        assert!(profile.validate().is_ok());
+
+        let long_account = SettlementProfile {
+            bank_account_number: Some("1".repeat(18)),
+            ..profile.clone()
+        };
+        assert!(long_account.validate().is_err());
+
+        let bad_routing = SettlementProfile {
+            routing_number: Some("123456789".to_string()),
+            ..profile.clone()
+        };
+        assert!(bad_routing.validate().is_err());
+
+        let euro_ach = SettlementProfile {
+            currency: "EUR".to_string(),
+            ..profile
+        };
+        assert!(euro_ach.validate().is_err());
+    }
+
+    #[test]
+    fn test_international_profile_validation() {
+        let account_id = Uuid::new_v4();
+        let sepa = SettlementProfile::international(
+            account_id,
+            "EUR",
+            "Acme GmbH",
+            "de89 3704 0044 0532 0130 00",
+            None,
+            PaymentRail::Sepa,
+        );
+        assert_eq!(sepa.iban.as_deref(), Some("DE89370400440532013000"));
+        assert!(sepa.validate().is_ok());
+
+        // SWIFT needs the beneficiary bank's BIC
+        let swift = SettlementProfile {
+            currency: "GBP".to_string(),
+            rail: PaymentRail::Swift,
+            ..sepa.clone()
+        };
+        assert!(swift.validate().is_err());
+        let swift = SettlementProfile {
+            bic: Some("DEUTDEFF".to_string()),
+            ..swift
+        };
+        assert!(swift.validate().is_ok());
+
+        let bad_iban = SettlementProfile {
+            iban: Some("DE89370400440532013001".to_string()),
+            ..sepa
+        };
+        assert!(bad_iban.validate().is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A named settlement window for a currency, closing every day at a local cut-off time.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SettlementWindow {
+    pub id: Uuid,
+    pub name: String,
+    pub currency: String,
+    /// Cut-off in the window's timezone.
+    pub cut_off_time: NaiveTime,
+    /// IANA timezone name, e.g. `Europe/Berlin`.
+    pub timezone: String,
+    /// Inactive windows get no new batches.
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SettlementWindow {
+    pub fn new(
+        name: impl Into<String>,
+        currency: impl Into<String>,
+        cut_off_time: NaiveTime,
+        timezone: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            currency: currency.into(),
+            cut_off_time,
+            timezone: timezone.into(),
+            active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Parses the window's timezone.
+    pub fn tz(&self) -> Option<Tz> {
+        self.timezone.parse().ok()
+    }
+
+    /// Returns the window's cut-off on a local settlement date. A cut-off falling in a
+    /// DST gap moves forward an hour; one that occurs twice uses the first occurrence.
+    pub fn cut_off_on(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        let tz = self.tz()?;
+        let local = date.and_time(self.cut_off_time);
+        let resolved = match tz.from_local_datetime(&local) {
+            LocalResult::Single(dt) => dt,
+            LocalResult::Ambiguous(first, _) => first,
+            LocalResult::None => tz.from_local_datetime(&(local + Duration::hours(1))).earliest()?,
+        };
+        Some(resolved.with_timezone(&Utc))
+    }
+
+    /// Returns the next cut-off after `now` and the local settlement date it closes.
+    pub fn next_cut_off(&self, now: DateTime<Utc>) -> Option<(NaiveDate, DateTime<Utc>)> {
+        let today = now.with_timezone(&self.tz()?).date_naive();
+        [today, today.succ_opt()?]
+            .into_iter()
+            .filter_map(|date| self.cut_off_on(date).map(|cut_off| (date, cut_off)))
+            .find(|(_, cut_off)| *cut_off > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(cut_off: (u32, u32), timezone: &str) -> SettlementWindow {
+        SettlementWindow::new(
+            "morning",
+            "EUR",
+            NaiveTime::from_hms_opt(cut_off.0, cut_off.1, 0).unwrap(),
+            timezone,
+        )
+    }
+
+    #[test]
+    fn test_cut_off_follows_local_time() {
+        let window = window((10, 0), "Europe/Berlin");
+
+        // CET (UTC+1) in winter, CEST (UTC+2) in summer
+        let winter = window.cut_off_on(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()).unwrap();
+        assert_eq!(winter, Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap());
+        let summer = window.cut_off_on(NaiveDate::from_ymd_opt(2024, 7, 15).unwrap()).unwrap();
+        assert_eq!(summer, Utc.with_ymd_and_hms(2024, 7, 15, 8, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_next_cut_off_rolls_to_tomorrow() {
+        let window = window((16, 0), "Europe/Berlin");
+
+        let before = Utc.with_ymd_and_hms(2024, 1, 15, 14, 0, 0).unwrap();
+        let (date, cut_off) = window.next_cut_off(before).unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        assert_eq!(cut_off, Utc.with_ymd_and_hms(2024, 1, 15, 15, 0, 0).unwrap());
+
+        let after = Utc.with_ymd_and_hms(2024, 1, 15, 15, 0, 0).unwrap();
+        let (date, _) = window.next_cut_off(after).unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(2024, 1, 16).unwrap());
+    }
+
+    #[test]
+    fn test_cut_off_in_dst_gap_moves_forward() {
+        // Clocks in Berlin jump from 02:00 to 03:00 on 2024-03-31
+        let window = window((2, 30), "Europe/Berlin");
+        let cut_off = window.cut_off_on(NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()).unwrap();
+        assert_eq!(cut_off, Utc.with_ymd_and_hms(2024, 3, 31, 1, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_unknown_timezone() {
+        assert!(window((10, 0), "Mars/Olympus").next_cut_off(Utc::now()).is_none());
+    }
+}
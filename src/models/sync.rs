@@ -0,0 +1,127 @@
+use super::{Account, TransactionRecord};
+use sqlx::FromRow;
+
+/// An account with its position in the change sequence.
+#[derive(Debug, Clone, FromRow)]
+pub struct SyncedAccount {
+    #[sqlx(flatten)]
+    pub account: Account,
+    pub change_seq: i64,
+}
+
+/// A transaction with its position in the change sequence.
+#[derive(Debug, Clone, FromRow)]
+pub struct SyncedTransaction {
+    #[sqlx(flatten)]
+    pub transaction: TransactionRecord,
+    pub change_seq: i64,
+}
+
+/// Position of a downstream reader in the change feed, handed out as an opaque token.
+///
+/// A token covers the window of rows written by database transactions with IDs from
+/// `low` up to (not including) `high`, read in change sequence order after
+/// `after_seq`. An open window (`high` unset) is closed at the current watermark, the
+/// oldest transaction still running, when the token is next used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncToken {
+    pub low: i64,
+    pub high: Option<i64>,
+    pub after_seq: i64,
+}
+
+impl SyncToken {
+    const VERSION: &'static str = "v1";
+
+    /// Token for a full snapshot: every row written so far.
+    pub fn snapshot() -> Self {
+        Self {
+            low: 0,
+            high: None,
+            after_seq: 0,
+        }
+    }
+
+    /// Returns true if the token's window starts from the beginning.
+    pub fn is_snapshot(&self) -> bool {
+        self.low == 0
+    }
+
+    /// Token following a page read with this token's window closed at `high`. A full
+    /// page continues the window after its last row; a short one means the window is
+    /// exhausted, so the next token opens the window of later changes.
+    pub fn next(&self, high: i64, last_seq: Option<i64>, page_full: bool) -> Self {
+        if page_full {
+            Self {
+                low: self.low,
+                high: Some(high),
+                after_seq: last_seq.unwrap_or(self.after_seq),
+            }
+        } else {
+            Self {
+                low: high,
+                high: None,
+                after_seq: 0,
+            }
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let high = self.high.map(|h| h.to_string()).unwrap_or_default();
+        hex::encode(format!("{}:{}:{}:{}", Self::VERSION, self.low, high, self.after_seq))
+    }
+
+    /// Parses a token produced by `encode`.
+    pub fn decode(token: &str) -> Option<Self> {
+        let text = String::from_utf8(hex::decode(token).ok()?).ok()?;
+        let mut parts = text.split(':');
+        if parts.next()? != Self::VERSION {
+            return None;
+        }
+        let low = parts.next()?.parse().ok()?;
+        let high = match parts.next()? {
+            "" => None,
+            high => Some(high.parse().ok()?),
+        };
+        let after_seq = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || low < 0 || after_seq < 0 || high.is_some_and(|h| h < low) {
+            return None;
+        }
+        Some(Self { low, high, after_seq })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_round_trip() {
+        let snapshot = SyncToken::snapshot();
+        assert_eq!(SyncToken::decode(&snapshot.encode()), Some(snapshot));
+
+        let paging = SyncToken {
+            low: 10,
+            high: Some(25),
+            after_seq: 340,
+        };
+        assert_eq!(SyncToken::decode(&paging.encode()), Some(paging));
+
+        assert_eq!(SyncToken::decode("not-a-token"), None);
+        assert_eq!(SyncToken::decode(&hex::encode("v1:30:20:0")), None);
+        assert_eq!(SyncToken::decode(&hex::encode("v2:0::0")), None);
+    }
+
+    #[test]
+    fn test_next_token() {
+        let snapshot = SyncToken::snapshot();
+
+        let more = snapshot.next(100, Some(42), true);
+        assert_eq!(more, SyncToken { low: 0, high: Some(100), after_seq: 42 });
+        assert!(more.is_snapshot());
+
+        let done = more.next(100, Some(50), false);
+        assert_eq!(done, SyncToken { low: 100, high: None, after_seq: 0 });
+        assert!(!done.is_snapshot());
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -35,6 +35,98 @@ impl TransactionType {
             _ => None,
         }
     }
+
+    /// Returns the type's name as stored, e.g. `PAYMENT`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionType::Payment => "PAYMENT",
+            TransactionType::Refund => "REFUND",
+            TransactionType::Chargeback => "CHARGEBACK",
+            TransactionType::Transfer => "TRANSFER",
+            TransactionType::Fee => "FEE",
+        }
+    }
+}
+
+/// What happens to a payment's fee when the payment is reversed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FeeReversalPolicy {
+    /// The fee stays earned: the payer gets back the net amount the payee received.
+    #[default]
+    Retain,
+    /// The fee leg is reversed out of the fee revenue account as well, so the payer
+    /// gets back the gross amount.
+    Reverse,
+}
+
+impl FeeReversalPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeReversalPolicy::Retain => "RETAIN",
+            FeeReversalPolicy::Reverse => "REVERSE",
+        }
+    }
+}
+
+/// When a payment's fee becomes revenue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FeeBookingMode {
+    /// The fee is credited to the revenue account as the payment posts.
+    #[default]
+    Immediate,
+    /// The fee accrues in a fees-receivable account, per payer and batch, and moves to
+    /// the revenue account when the batch completes.
+    Accrued,
+}
+
+/// Scope within which a transaction's external ID must be unique.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExternalIdScope {
+    /// No two transactions share an external ID.
+    #[default]
+    Global,
+    /// External IDs are unique per source system.
+    SourceSystem,
+    /// External IDs are unique per source system and UTC day received, for upstreams
+    /// that recycle their IDs daily.
+    SourceSystemDay,
+}
+
+impl ExternalIdScope {
+    /// Qualifies an external ID with the parts of its scope. Transactions without a
+    /// source system share one unnamed system.
+    pub fn dedupe_key(&self, external_id: &str, source_system: Option<&str>, received_on: NaiveDate) -> String {
+        let source_system = source_system.unwrap_or("");
+        match self {
+            ExternalIdScope::Global => external_id.to_string(),
+            ExternalIdScope::SourceSystem => format!("{}|{}", source_system, external_id),
+            ExternalIdScope::SourceSystemDay => format!("{}|{}|{}", source_system, received_on, external_id),
+        }
+    }
+}
+
+/// What happens to a transaction whose external ID is already taken within its scope.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplicateExternalIdAction {
+    /// The transaction is rejected.
+    #[default]
+    Reject,
+    /// The transaction is posted and linked to the first one with the ID as a resubmission.
+    Link,
+}
+
+/// How a transaction claims its external ID: the scoped key it holds, or the earlier
+/// transaction it resubmits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExternalIdClaim {
+    pub source_system: Option<String>,
+    /// Unique key the transaction holds; resubmissions hold none.
+    pub dedupe_key: Option<String>,
+    pub resubmission_of: Option<Uuid>,
 }
 
 /// Status of a transaction in its lifecycle.
@@ -50,6 +142,8 @@ pub enum TransactionStatus {
     Failed,
     /// Transaction has been reversed.
     Reversed,
+    /// Transaction stayed pending past its type's time-to-live and was abandoned.
+    Expired,
 }
 
 impl TransactionStatus {
@@ -57,7 +151,10 @@ impl TransactionStatus {
     pub fn is_final(&self) -> bool {
         matches!(
             self,
-            TransactionStatus::Settled | TransactionStatus::Failed | TransactionStatus::Reversed
+            TransactionStatus::Settled
+                | TransactionStatus::Failed
+                | TransactionStatus::Reversed
+                | TransactionStatus::Expired
         )
     }
 
@@ -67,6 +164,63 @@ impl TransactionStatus {
     }
 }
 
+/// How a transaction is settled with its counterparty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "settlement_route", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SettlementRoute {
+    /// Assigned to a settlement batch and netted with other transactions.
+    #[default]
+    Netted,
+    /// Settled gross and individually in real time, outside any batch.
+    Rtgs,
+}
+
+impl SettlementRoute {
+    /// Returns true if transactions on this route can be assigned to a batch for netting.
+    pub fn is_nettable(&self) -> bool {
+        matches!(self, SettlementRoute::Netted)
+    }
+}
+
+/// How a deployment settles transactions, for schemes that settle gross, net or both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EngineMode {
+    /// Pure RTGS: every payment and transfer settles gross in real time, and no
+    /// settlement batches are opened.
+    Gross,
+    /// Deferred net settlement: transactions are batched and netted at cut-off, and the
+    /// RTGS lane is closed whatever its thresholds.
+    Net,
+    /// Payments and transfers above their currency's RTGS threshold settle gross and the
+    /// rest are netted. Without thresholds this settles everything net.
+    #[default]
+    Hybrid,
+}
+
+impl EngineMode {
+    /// Returns true if transactions can be assigned to settlement batches.
+    pub fn batching_enabled(&self) -> bool {
+        !matches!(self, EngineMode::Gross)
+    }
+}
+
+/// Release priority of a queued transaction. Variants are declared from most to least
+/// urgent, so sorting ascending (here and in Postgres) releases urgent payments first.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, sqlx::Type,
+)]
+#[sqlx(type_name = "transaction_priority", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionPriority {
+    /// Time-critical payments that must go out ahead of everything else.
+    Urgent,
+    High,
+    #[default]
+    Normal,
+}
+
 /// Represents a financial transaction in the settlement system.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct TransactionRecord {
@@ -90,6 +244,36 @@ pub struct TransactionRecord {
     pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub settled_at: Option<DateTime<Utc>>,
+    /// Routing decision made when the transaction was posted.
+    #[serde(default)]
+    pub settlement_route: SettlementRoute,
+    #[serde(default)]
+    pub priority: TransactionPriority,
+    /// Upstream system that submitted the transaction.
+    #[serde(default)]
+    pub source_system: Option<String>,
+    /// Earlier transaction with the same external ID that this one resubmits.
+    #[serde(default)]
+    pub resubmission_of: Option<Uuid>,
+    /// External ID qualified by its uniqueness scope. Unique among transactions;
+    /// resubmissions have none.
+    #[serde(default)]
+    pub dedupe_key: Option<String>,
+    /// Registered custom type the transaction posted as, if any.
+    #[serde(default)]
+    pub type_code: Option<String>,
+    /// Whether the transaction settles gross instead of netting with its batch.
+    #[serde(default)]
+    pub exclude_from_netting: bool,
+    /// Human-readable description, copied to the transaction's ledger entries.
+    #[serde(default)]
+    pub narrative: Option<String>,
+    /// Counterparty's reference for the transaction, e.g. an invoice number.
+    #[serde(default)]
+    pub reference: Option<String>,
+    /// Transactions settled together, all or nothing, share a link.
+    #[serde(default)]
+    pub link_id: Option<Uuid>,
 }
 
 impl TransactionRecord {
@@ -107,6 +291,7 @@ impl TransactionRecord {
         let net_amount = amount - fee_amount;
         Self {
             id: Uuid::new_v4(),
+            dedupe_key: Some(external_id.clone()),
             external_id,
             transaction_type,
             status: TransactionStatus::Pending,
@@ -121,6 +306,15 @@ impl TransactionRecord {
             metadata: None,
             created_at: Utc::now(),
             settled_at: None,
+            settlement_route: SettlementRoute::Netted,
+            priority: TransactionPriority::Normal,
+            source_system: None,
+            resubmission_of: None,
+            type_code: None,
+            exclude_from_netting: false,
+            narrative: None,
+            reference: None,
+            link_id: None,
         }
     }
 
@@ -173,6 +367,62 @@ impl TransactionRecord {
         self
     }
 
+    /// Sets the settlement route for the transaction.
+    pub fn with_route(mut self, route: SettlementRoute) -> Self {
+        self.settlement_route = route;
+        self
+    }
+
+    /// Sets the release priority for the transaction.
+    pub fn with_priority(mut self, priority: TransactionPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the custom type the transaction posts as.
+    pub fn with_type_code(mut self, type_code: Option<String>) -> Self {
+        self.type_code = type_code;
+        self
+    }
+
+    /// Keeps the transaction out of netting, to settle gross.
+    pub fn excluded_from_netting(mut self, excluded: bool) -> Self {
+        self.exclude_from_netting = excluded;
+        self
+    }
+
+    /// Sets the transaction's narrative and reference.
+    pub fn with_narrative(mut self, narrative: Option<String>, reference: Option<String>) -> Self {
+        self.narrative = narrative;
+        self.reference = reference;
+        self
+    }
+
+    /// Links the transaction to others it must settle together with.
+    pub fn with_link(mut self, link_id: Option<Uuid>) -> Self {
+        self.link_id = link_id;
+        self
+    }
+
+    /// Narrative of a transaction reversing or refunding this one: `label` followed by
+    /// this transaction's narrative, or its external ID if it has none.
+    pub fn derived_narrative(&self, label: &str) -> String {
+        format!("{} {}", label, self.narrative.as_deref().unwrap_or(&self.external_id))
+    }
+
+    /// Applies the transaction's claim on its external ID.
+    pub fn with_external_id_claim(mut self, claim: ExternalIdClaim) -> Self {
+        self.source_system = claim.source_system;
+        self.dedupe_key = claim.dedupe_key;
+        self.resubmission_of = claim.resubmission_of;
+        self
+    }
+
+    /// Key for releasing queued transactions: priority first, then FIFO by creation time.
+    pub fn release_key(&self) -> (TransactionPriority, DateTime<Utc>) {
+        (self.priority, self.created_at)
+    }
+
     /// Marks the transaction as settled.
     pub fn settle(&mut self) {
         self.status = TransactionStatus::Settled;
@@ -219,6 +469,18 @@ mod tests {
         assert!(!TransactionType::Fee.is_reversible());
     }
 
+    #[test]
+    fn test_external_id_dedupe_key() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        assert_eq!(ExternalIdScope::Global.dedupe_key("EXT-1", Some("CARDS"), day), "EXT-1");
+        assert_eq!(ExternalIdScope::SourceSystem.dedupe_key("EXT-1", Some("CARDS"), day), "CARDS|EXT-1");
+        assert_eq!(ExternalIdScope::SourceSystem.dedupe_key("EXT-1", None, day), "|EXT-1");
+        assert_eq!(
+            ExternalIdScope::SourceSystemDay.dedupe_key("EXT-1", Some("CARDS"), day),
+            "CARDS|2024-03-15|EXT-1"
+        );
+    }
+
     #[test]
     fn test_transaction_type_reversal_type() {
         assert_eq!(
@@ -412,4 +674,54 @@ mod tests {
         assert_eq!(deserialized.amount, dec!(100.50));
         assert_eq!(deserialized.transaction_type, TransactionType::Payment);
     }
+
+    #[test]
+    fn test_settlement_route() {
+        let tx = TransactionRecord::transfer(
+            "EXT-001".to_string(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            dec!(5000000),
+            "USD".to_string(),
+            "idem-key-001".to_string(),
+        );
+        assert_eq!(tx.settlement_route, SettlementRoute::Netted);
+        assert!(tx.settlement_route.is_nettable());
+
+        let tx = tx.with_route(SettlementRoute::Rtgs);
+        assert!(!tx.settlement_route.is_nettable());
+
+        let json = serde_json::to_string(&tx).unwrap();
+        assert!(json.contains("\"settlement_route\":\"RTGS\""));
+    }
+
+    #[test]
+    fn test_release_order_is_priority_then_fifo() {
+        let queued = |external_id: &str, priority: TransactionPriority, age_secs: i64| {
+            let mut tx = TransactionRecord::payment(
+                external_id.to_string(),
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                dec!(100),
+                "USD".to_string(),
+                Decimal::ZERO,
+                format!("idem-{}", external_id),
+            )
+            .with_priority(priority);
+            tx.created_at = Utc::now() - chrono::Duration::seconds(age_secs);
+            tx
+        };
+
+        let mut queue = [
+            queued("normal-old", TransactionPriority::Normal, 30),
+            queued("high", TransactionPriority::High, 10),
+            queued("urgent-new", TransactionPriority::Urgent, 1),
+            queued("normal-new", TransactionPriority::Normal, 5),
+            queued("urgent-old", TransactionPriority::Urgent, 20),
+        ];
+        queue.sort_by_key(|tx| tx.release_key());
+
+        let order: Vec<&str> = queue.iter().map(|tx| tx.external_id.as_str()).collect();
+        assert_eq!(order, vec!["urgent-old", "urgent-new", "high", "normal-old", "normal-new"]);
+    }
 }
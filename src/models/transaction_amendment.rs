@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Kind of queued item an amendment was made to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "amendment_target", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AmendmentTarget {
+    /// A transaction queued for asynchronous settlement.
+    Submission,
+    /// A transaction held for risk review.
+    RiskHold,
+}
+
+/// One field an amendment changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmendedField {
+    pub field: String,
+    pub from: serde_json::Value,
+    pub to: serde_json::Value,
+}
+
+/// A change made to a transaction before it was posted.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TransactionAmendment {
+    pub id: Uuid,
+    pub target: AmendmentTarget,
+    /// ID of the submission or risk hold amended.
+    pub target_id: Uuid,
+    /// The `AmendedField`s, with the values they replaced.
+    pub changes: serde_json::Value,
+    pub amended_by: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TransactionAmendment {
+    pub fn new(
+        target: AmendmentTarget,
+        target_id: Uuid,
+        changes: &[AmendedField],
+        amended_by: impl Into<String>,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            target,
+            target_id,
+            changes: serde_json::to_value(changes).unwrap_or_default(),
+            amended_by: amended_by.into(),
+            reason,
+            created_at: Utc::now(),
+        }
+    }
+}
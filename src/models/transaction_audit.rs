@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Lifecycle step recorded in the transaction audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "transaction_audit_action", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionAuditAction {
+    /// The request passed the validation pipeline and was accepted.
+    Validated,
+    /// The transaction was placed in a settlement batch.
+    BatchAssigned,
+    /// The transaction was moved from one settlement batch to another.
+    BatchReassigned,
+    /// An event about the transaction was published to Kafka.
+    EventEmitted,
+    /// The transaction stayed pending past its time-to-live and was expired.
+    Expired,
+}
+
+/// Entry in the transaction audit log.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TransactionAuditEntry {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub action: TransactionAuditAction,
+    /// Action-specific details, e.g. the batch or Kafka topic.
+    pub detail: serde_json::Value,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl TransactionAuditEntry {
+    pub fn new(transaction_id: Uuid, action: TransactionAuditAction, detail: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            transaction_id,
+            action,
+            detail,
+            recorded_at: Utc::now(),
+        }
+    }
+}
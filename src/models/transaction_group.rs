@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Outcome of a transaction group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "transaction_group_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionGroupStatus {
+    /// Submitted and not yet settled.
+    Pending,
+    /// Every transaction of the group settled.
+    Settled,
+    /// The last attempt failed and nothing was posted. Resubmitting the group tries again.
+    Failed,
+}
+
+/// Transactions submitted together and settled all or nothing, e.g. the legs of a
+/// payment-versus-payment trade or a split disbursement. The group's ID is the
+/// `link_id` of its transactions.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TransactionGroup {
+    pub id: Uuid,
+    /// Idempotency key of the group; resubmitting it returns this group.
+    pub idempotency_key: String,
+    /// Idempotency keys of the group's transactions, in submission order.
+    pub transaction_keys: Vec<String>,
+    pub status: TransactionGroupStatus,
+    /// Error code of the last failed attempt.
+    pub error_code: Option<String>,
+    pub last_error: Option<String>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TransactionGroup {
+    pub fn new(idempotency_key: impl Into<String>, transaction_keys: Vec<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            idempotency_key: idempotency_key.into(),
+            transaction_keys,
+            status: TransactionGroupStatus::Pending,
+            error_code: None,
+            last_error: None,
+            settled_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether a resubmission carries the same transactions as the group.
+    pub fn matches(&self, transaction_keys: &[String]) -> bool {
+        self.transaction_keys == transaction_keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resubmission_must_carry_the_same_transactions() {
+        let keys = vec!["LEG-1".to_string(), "LEG-2".to_string()];
+        let group = TransactionGroup::new("GROUP-1", keys.clone());
+        assert_eq!(group.status, TransactionGroupStatus::Pending);
+        assert!(group.matches(&keys));
+        assert!(!group.matches(&keys[..1]));
+        assert!(!group.matches(&["LEG-2".to_string(), "LEG-1".to_string()]));
+    }
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Funds reserved on the source account while a transaction is pending.
+/// The hold is released when the transaction expires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TransactionHold {
+    pub transaction_id: Uuid,
+    pub account_id: Uuid,
+    pub currency: String,
+    pub amount: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub released_at: Option<DateTime<Utc>>,
+}
+
+impl TransactionHold {
+    pub fn new(transaction_id: Uuid, account_id: Uuid, currency: impl Into<String>, amount: Decimal) -> Self {
+        Self {
+            transaction_id,
+            account_id,
+            currency: currency.into(),
+            amount,
+            created_at: Utc::now(),
+            released_at: None,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Lifecycle of an asynchronously submitted transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "submission_status", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SubmissionStatus {
+    /// Waiting for a worker.
+    Queued,
+    /// Claimed by a submission worker.
+    Processing,
+    /// The transaction settled.
+    Settled,
+    /// Rejected, or every attempt failed.
+    Failed,
+    /// Still queued when its type's time-to-live ran out.
+    Expired,
+}
+
+impl SubmissionStatus {
+    /// Returns true once the submission has an outcome.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            SubmissionStatus::Settled | SubmissionStatus::Failed | SubmissionStatus::Expired
+        )
+    }
+
+    /// Returns true while the queued request may still be amended: once a worker claims
+    /// it, it is on its way to being posted.
+    pub fn is_amendable(&self) -> bool {
+        *self == SubmissionStatus::Queued
+    }
+}
+
+/// A transaction request queued for asynchronous settlement.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TransactionSubmission {
+    pub id: Uuid,
+    /// Idempotency key of the queued request; resubmitting it returns this submission.
+    pub idempotency_key: String,
+    /// The queued ledger transaction request.
+    pub request: serde_json::Value,
+    pub status: SubmissionStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    /// The settled transaction.
+    pub transaction_id: Option<Uuid>,
+    /// Error code of the last failed attempt.
+    pub error_code: Option<String>,
+    pub last_error: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TransactionSubmission {
+    pub fn new(idempotency_key: impl Into<String>, request: serde_json::Value, max_attempts: i32) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            idempotency_key: idempotency_key.into(),
+            request,
+            status: SubmissionStatus::Queued,
+            attempts: 0,
+            max_attempts,
+            next_attempt_at: now,
+            transaction_id: None,
+            error_code: None,
+            last_error: None,
+            completed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Marks the current attempt as settled.
+    pub fn mark_settled(&mut self, transaction_id: Uuid) {
+        let now = Utc::now();
+        self.status = SubmissionStatus::Settled;
+        self.transaction_id = Some(transaction_id);
+        self.error_code = None;
+        self.last_error = None;
+        self.completed_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// Records a failed attempt. Retryable failures are attempted again after `backoff`,
+    /// doubled for each earlier attempt, until `max_attempts` is reached; anything else
+    /// fails the submission at once.
+    pub fn record_failure(
+        &mut self,
+        code: impl Into<String>,
+        error: impl Into<String>,
+        retryable: bool,
+        backoff: Duration,
+    ) {
+        let now = Utc::now();
+        self.error_code = Some(code.into());
+        self.last_error = Some(error.into());
+        self.updated_at = now;
+        if retryable && self.attempts < self.max_attempts {
+            let exponent = (self.attempts - 1).clamp(0, 16) as u32;
+            self.status = SubmissionStatus::Queued;
+            self.next_attempt_at = now + backoff * 2i32.pow(exponent);
+        } else {
+            self.status = SubmissionStatus::Failed;
+            self.completed_at = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_failures_back_off_until_failed() {
+        let mut submission = TransactionSubmission::new("IDEM-1", serde_json::json!({}), 2);
+        let backoff = Duration::seconds(10);
+
+        submission.attempts = 1;
+        submission.record_failure("SERIALIZATION_CONFLICT", "conflict", true, backoff);
+        assert_eq!(submission.status, SubmissionStatus::Queued);
+        assert!(submission.next_attempt_at - Utc::now() > Duration::seconds(5));
+
+        submission.attempts = 2;
+        submission.record_failure("SERIALIZATION_CONFLICT", "conflict", true, backoff);
+        assert_eq!(submission.status, SubmissionStatus::Failed);
+        assert!(submission.completed_at.is_some());
+    }
+
+    #[test]
+    fn test_permanent_failure_fails_at_once() {
+        let mut submission = TransactionSubmission::new("IDEM-2", serde_json::json!({}), 5);
+        submission.attempts = 1;
+        submission.record_failure("INSUFFICIENT_FUNDS", "Insufficient funds", false, Duration::seconds(10));
+        assert_eq!(submission.status, SubmissionStatus::Failed);
+        assert!(submission.status.is_final());
+        assert_eq!(submission.error_code.as_deref(), Some("INSUFFICIENT_FUNDS"));
+
+        submission.mark_settled(Uuid::new_v4());
+        assert_eq!(submission.status, SubmissionStatus::Settled);
+        assert!(submission.last_error.is_none());
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::{FeeReversalPolicy, TransactionType};
+
+/// Base types, whose definitions are built in rather than registered.
+const BASE_TYPES: [TransactionType; 5] = [
+    TransactionType::Payment,
+    TransactionType::Refund,
+    TransactionType::Chargeback,
+    TransactionType::Transfer,
+    TransactionType::Fee,
+];
+
+/// Narrative of transactions whose type has no template of its own.
+pub const DEFAULT_NARRATIVE_TEMPLATE: &str = "{type} {external_id}";
+
+/// What a transaction type allows for fees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "VARCHAR", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TypeFeePolicy {
+    /// Fees are allowed; reversals follow the requested or configured fee policy.
+    #[default]
+    Configured,
+    /// Fees are allowed and always kept when the transaction is reversed.
+    Retain,
+    /// Fees are allowed and always returned when the transaction is reversed.
+    Reverse,
+    /// The type cannot carry a fee.
+    Exempt,
+}
+
+/// How transactions of a type behave. Each base type has a built-in definition, which
+/// can be replaced by registering one under the base type's name; custom types are
+/// registered under their own code, post as a base type and carry the code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TransactionTypeDefinition {
+    /// The base type's name for built-in types, e.g. `PAYMENT`, or the custom type's code.
+    pub code: String,
+    /// How the transaction posts.
+    pub base_type: TransactionType,
+    pub reversible: bool,
+    /// Whether the transaction is offset against others in its batch. Transactions that
+    /// are not settle gross, with an instruction of their own.
+    pub nettable: bool,
+    /// Whether the transaction must reference the transaction it returns.
+    pub requires_original: bool,
+    pub fee_policy: TypeFeePolicy,
+    pub description: Option<String>,
+    /// Narrative given to the type's transactions and ledger entries when the request has
+    /// none, e.g. `Interchange fee {reference}`; see `narrative`.
+    #[serde(default)]
+    pub narrative_template: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TransactionTypeDefinition {
+    /// A custom type posting as `base_type`, with the base type's behavior until changed.
+    pub fn new(code: impl Into<String>, base_type: TransactionType) -> Self {
+        Self {
+            code: code.into(),
+            ..Self::builtin(base_type)
+        }
+    }
+
+    /// The built-in definition of a base type.
+    pub fn builtin(base_type: TransactionType) -> Self {
+        let now = Utc::now();
+        Self {
+            code: base_type.as_str().to_string(),
+            base_type,
+            reversible: matches!(base_type, TransactionType::Payment | TransactionType::Transfer),
+            nettable: true,
+            requires_original: matches!(base_type, TransactionType::Refund | TransactionType::Chargeback),
+            fee_policy: TypeFeePolicy::Configured,
+            description: None,
+            narrative_template: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Built-in definitions of every base type.
+    pub fn builtins() -> Vec<Self> {
+        BASE_TYPES.into_iter().map(Self::builtin).collect()
+    }
+
+    /// Returns true if the code names a base type.
+    pub fn is_builtin_code(code: &str) -> bool {
+        BASE_TYPES.iter().any(|base_type| base_type.as_str() == code)
+    }
+
+    /// Checks a custom type's code: upper-case letters, digits and underscores, starting
+    /// with a letter, at most 50 characters and not the name of a base type.
+    pub fn check_code(code: &str) -> Result<(), String> {
+        let well_formed = code.len() <= 50
+            && code.starts_with(|c: char| c.is_ascii_uppercase())
+            && code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if !well_formed {
+            return Err(format!(
+                "Type code '{}' must be upper-case letters, digits and underscores, starting with a letter",
+                code
+            ));
+        }
+        if Self::is_builtin_code(code) {
+            return Err(format!("'{}' is a base transaction type", code));
+        }
+        Ok(())
+    }
+
+    /// The type reversals of this type post as: the base type's reversal, or a transfer
+    /// for base types that have none.
+    pub fn reversal_type(&self) -> TransactionType {
+        self.base_type.reversal_type().unwrap_or(TransactionType::Transfer)
+    }
+
+    /// Fills the type's narrative template, or the default one, for a transaction.
+    /// Templates may use `{type}`, `{external_id}`, `{amount}`, `{currency}` and
+    /// `{reference}`, which is left empty when the transaction has none.
+    pub fn narrative(&self, external_id: &str, amount: Decimal, currency: &str, reference: Option<&str>) -> String {
+        let template = self.narrative_template.as_deref().unwrap_or(DEFAULT_NARRATIVE_TEMPLATE);
+        template
+            .replace("{type}", &self.code)
+            .replace("{external_id}", external_id)
+            .replace("{amount}", &amount.to_string())
+            .replace("{currency}", currency)
+            .replace("{reference}", reference.unwrap_or_default())
+            .trim()
+            .to_string()
+    }
+
+    pub fn allows_fee(&self) -> bool {
+        self.fee_policy != TypeFeePolicy::Exempt
+    }
+
+    /// The fee policy a reversal applies, given the one requested or configured.
+    pub fn fee_reversal_policy(&self, requested: FeeReversalPolicy) -> FeeReversalPolicy {
+        match self.fee_policy {
+            TypeFeePolicy::Retain => FeeReversalPolicy::Retain,
+            TypeFeePolicy::Reverse => FeeReversalPolicy::Reverse,
+            TypeFeePolicy::Configured | TypeFeePolicy::Exempt => requested,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtins_keep_base_behavior() {
+        let payment = TransactionTypeDefinition::builtin(TransactionType::Payment);
+        assert_eq!(payment.code, "PAYMENT");
+        assert!(payment.reversible && payment.nettable && !payment.requires_original);
+        assert_eq!(payment.reversal_type(), TransactionType::Refund);
+
+        let refund = TransactionTypeDefinition::builtin(TransactionType::Refund);
+        assert!(!refund.reversible && refund.requires_original);
+
+        let fee = TransactionTypeDefinition::builtin(TransactionType::Fee);
+        assert!(!fee.reversible && !fee.requires_original);
+        assert_eq!(fee.reversal_type(), TransactionType::Transfer);
+        assert_eq!(TransactionTypeDefinition::builtins().len(), 5);
+    }
+
+    #[test]
+    fn test_check_code() {
+        assert!(TransactionTypeDefinition::check_code("INTERCHANGE_FEE").is_ok());
+        assert!(TransactionTypeDefinition::check_code("SWEEP2").is_ok());
+        assert!(TransactionTypeDefinition::check_code("sweep").is_err());
+        assert!(TransactionTypeDefinition::check_code("2SWEEP").is_err());
+        assert!(TransactionTypeDefinition::check_code("").is_err());
+        assert!(TransactionTypeDefinition::check_code(&"A".repeat(51)).is_err());
+        assert!(TransactionTypeDefinition::check_code("PAYMENT").is_err());
+    }
+
+    #[test]
+    fn test_fee_policy_overrides_reversal_policy() {
+        let mut adjustment = TransactionTypeDefinition::new("ADJUSTMENT", TransactionType::Transfer);
+        assert_eq!(adjustment.fee_reversal_policy(FeeReversalPolicy::Reverse), FeeReversalPolicy::Reverse);
+
+        adjustment.fee_policy = TypeFeePolicy::Retain;
+        assert_eq!(adjustment.fee_reversal_policy(FeeReversalPolicy::Reverse), FeeReversalPolicy::Retain);
+
+        adjustment.fee_policy = TypeFeePolicy::Exempt;
+        assert!(!adjustment.allows_fee());
+    }
+
+    #[test]
+    fn test_narrative_fills_template() {
+        let payment = TransactionTypeDefinition::builtin(TransactionType::Payment);
+        assert_eq!(payment.narrative("PAY-1", Decimal::new(2500, 2), "USD", None), "PAYMENT PAY-1");
+
+        let mut interchange = TransactionTypeDefinition::new("INTERCHANGE_FEE", TransactionType::Fee);
+        interchange.narrative_template = Some("Interchange {amount} {currency} {reference}".to_string());
+        assert_eq!(
+            interchange.narrative("FEE-1", Decimal::new(2500, 2), "USD", Some("INV-7")),
+            "Interchange 25.00 USD INV-7"
+        );
+        assert_eq!(interchange.narrative("FEE-1", Decimal::new(2500, 2), "USD", None), "Interchange 25.00 USD");
+    }
+}
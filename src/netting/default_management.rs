@@ -0,0 +1,240 @@
+//! Default management for net settlement.
+//!
+//! When a participant cannot fund its net debit the batch still has to settle among the
+//! survivors. Its obligations are either excluded, as if they had never been submitted,
+//! or kept with the unfunded debit shared pro-rata among the surviving participants.
+//! Either way the defaulter ends flat and the adjusted positions still sum to zero.
+
+use super::optimizer::Transfer;
+use crate::core::rounding::RoundingPolicy;
+use crate::models::{LossAllocation, NettingPosition};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Removes the obligations between a defaulter and its counterparties from their
+/// positions. Obligations not involving the defaulter are ignored.
+pub fn exclude_obligations(positions: &mut [NettingPosition], defaulter: Uuid, obligations: &[Transfer]) {
+    let index: HashMap<Uuid, usize> = positions
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.participant_id, i))
+        .collect();
+
+    for obligation in obligations.iter().filter(|o| o.from != o.to && (o.from == defaulter || o.to == defaulter)) {
+        if let Some(&i) = index.get(&obligation.from) {
+            let payer = &mut positions[i];
+            payer.gross_payable -= obligation.amount;
+            payer.transaction_count -= 1;
+            reset_net(payer);
+        }
+        if let Some(&i) = index.get(&obligation.to) {
+            let receiver = &mut positions[i];
+            receiver.gross_receivable -= obligation.amount;
+            receiver.transaction_count -= 1;
+            reset_net(receiver);
+        }
+    }
+}
+
+/// Weights for sharing a loss by net credit: each surviving net receiver's position.
+pub fn net_credit_weights(positions: &[NettingPosition], defaulter: Uuid) -> Vec<(Uuid, Decimal)> {
+    positions
+        .iter()
+        .filter(|p| p.participant_id != defaulter && p.is_net_receiver())
+        .map(|p| (p.participant_id, p.net_position))
+        .collect()
+}
+
+/// Weights for sharing a loss by exposure: the gross amount the defaulter owed each
+/// counterparty, in order of first obligation.
+pub fn exposure_weights(obligations: &[Transfer], defaulter: Uuid) -> Vec<(Uuid, Decimal)> {
+    let mut weights: Vec<(Uuid, Decimal)> = Vec::new();
+    for obligation in obligations.iter().filter(|o| o.from == defaulter && o.to != defaulter) {
+        match weights.iter_mut().find(|(id, _)| *id == obligation.to) {
+            Some((_, weight)) => *weight += obligation.amount,
+            None => weights.push((obligation.to, obligation.amount)),
+        }
+    }
+    weights.retain(|(_, weight)| *weight > Decimal::ZERO);
+    weights
+}
+
+/// Splits `loss` pro-rata to the positive weights under the rounding policy, so the
+/// shares sum to the loss exactly. With a designated remainder account the account may
+/// bear a share of its own. Returns no shares if no weight is positive.
+pub fn allocate_loss(
+    loss: Decimal,
+    weights: &[(Uuid, Decimal)],
+    currency: &str,
+    rounding: &RoundingPolicy,
+) -> Vec<LossAllocation> {
+    rounding
+        .allocate(loss, currency, weights)
+        .into_iter()
+        .map(|(participant_id, amount)| LossAllocation { participant_id, amount })
+        .collect()
+}
+
+/// Moves each allocated share from the defaulter's position to the participant bearing
+/// it, as an extra payable for the participant and a receivable for the defaulter. A
+/// participant without a position, such as a designated rounding account, gets one.
+pub fn apply_allocations(positions: &mut Vec<NettingPosition>, defaulter: Uuid, allocations: &[LossAllocation]) {
+    for allocation in allocations {
+        if !positions.iter().any(|p| p.participant_id == allocation.participant_id) {
+            if let Some(template) = positions.iter().find(|p| p.participant_id == defaulter) {
+                let position =
+                    NettingPosition::new(template.batch_id, allocation.participant_id, template.currency.clone());
+                positions.push(position);
+            }
+        }
+        for position in positions.iter_mut() {
+            if position.participant_id == allocation.participant_id {
+                shift(position, -allocation.amount);
+            } else if position.participant_id == defaulter {
+                shift(position, allocation.amount);
+            }
+        }
+    }
+}
+
+/// Adds a signed amount to a position: positive as a receivable, negative as a payable.
+fn shift(position: &mut NettingPosition, amount: Decimal) {
+    if amount.is_sign_positive() {
+        position.gross_receivable += amount;
+    } else {
+        position.gross_payable -= amount;
+    }
+    reset_net(position);
+}
+
+fn reset_net(position: &mut NettingPosition) {
+    position.net_position = position.gross_receivable - position.gross_payable;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::rounding::RemainderAllocation;
+    use rust_decimal_macros::dec;
+
+    fn netted(batch_id: Uuid, obligations: &[Transfer]) -> Vec<NettingPosition> {
+        let mut positions: Vec<NettingPosition> = Vec::new();
+        for o in obligations {
+            for (participant, receivable) in [(o.from, false), (o.to, true)] {
+                let i = match positions.iter().position(|p| p.participant_id == participant) {
+                    Some(i) => i,
+                    None => {
+                        positions.push(NettingPosition::new(batch_id, participant, "USD".to_string()));
+                        positions.len() - 1
+                    }
+                };
+                if receivable {
+                    positions[i].add_receivable(o.amount);
+                } else {
+                    positions[i].add_payable(o.amount);
+                }
+            }
+        }
+        positions
+    }
+
+    fn net(positions: &[NettingPosition], participant: Uuid) -> Decimal {
+        positions.iter().find(|p| p.participant_id == participant).unwrap().net_position
+    }
+
+    fn transfer(from: Uuid, to: Uuid, amount: Decimal) -> Transfer {
+        Transfer { from, to, amount }
+    }
+
+    #[test]
+    fn test_exclude_obligations() {
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let obligations = vec![
+            transfer(d, a, dec!(500)),
+            transfer(d, b, dec!(300)),
+            transfer(a, d, dec!(100)),
+            transfer(b, c, dec!(200)),
+        ];
+        let mut positions = netted(Uuid::new_v4(), &obligations);
+        assert_eq!(net(&positions, d), dec!(-700));
+
+        exclude_obligations(&mut positions, d, &obligations);
+
+        // Only b -> c is left
+        assert_eq!(net(&positions, d), dec!(0));
+        assert_eq!(net(&positions, a), dec!(0));
+        assert_eq!(net(&positions, b), dec!(-200));
+        assert_eq!(net(&positions, c), dec!(200));
+        let defaulter = positions.iter().find(|p| p.participant_id == d).unwrap();
+        assert_eq!(defaulter.transaction_count, 0);
+        assert_eq!(positions.iter().map(|p| p.net_position).sum::<Decimal>(), dec!(0));
+    }
+
+    #[test]
+    fn test_loss_sharing_by_net_credit() {
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let obligations = vec![
+            transfer(d, a, dec!(600)),
+            transfer(c, b, dec!(300)),
+            transfer(d, b, dec!(100)),
+        ];
+        let mut positions = netted(Uuid::new_v4(), &obligations);
+
+        // a +600, b +400: d's 700 is shared 420 / 280
+        let rounding = RoundingPolicy::default();
+        let allocations = allocate_loss(dec!(700), &net_credit_weights(&positions, d), "USD", &rounding);
+        assert_eq!(allocations.iter().map(|a| a.amount).sum::<Decimal>(), dec!(700));
+        apply_allocations(&mut positions, d, &allocations);
+
+        assert_eq!(net(&positions, d), dec!(0));
+        assert_eq!(net(&positions, a), dec!(180));
+        assert_eq!(net(&positions, b), dec!(120));
+        assert_eq!(net(&positions, c), dec!(-300));
+        for p in &positions {
+            assert_eq!(p.net_position, p.gross_receivable - p.gross_payable);
+        }
+    }
+
+    #[test]
+    fn test_exposure_weights_and_rounding() {
+        let (a, b, c, d) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let obligations = vec![
+            transfer(d, a, dec!(100)),
+            transfer(b, d, dec!(50)),
+            transfer(d, c, dec!(100)),
+            transfer(d, a, dec!(100)),
+        ];
+
+        let weights = exposure_weights(&obligations, d);
+        assert_eq!(weights, vec![(a, dec!(200)), (c, dec!(100))]);
+
+        let rounding = RoundingPolicy::default();
+        let allocations = allocate_loss(dec!(100), &weights, "USD", &rounding);
+        assert_eq!(allocations[0].amount, dec!(66.67));
+        assert_eq!(allocations[1].amount, dec!(33.33));
+
+        assert!(allocate_loss(dec!(100), &[(a, dec!(0))], "USD", &rounding).is_empty());
+    }
+
+    #[test]
+    fn test_designated_account_bears_the_rounding_remainder() {
+        let (a, b, c, d, house) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let obligations = vec![transfer(d, a, dec!(100)), transfer(d, b, dec!(100)), transfer(d, c, dec!(100))];
+        let mut positions = netted(Uuid::new_v4(), &obligations);
+        let rounding = RoundingPolicy {
+            remainder: RemainderAllocation::Designated(house),
+            ..Default::default()
+        };
+
+        let allocations = allocate_loss(dec!(100), &exposure_weights(&obligations, d), "USD", &rounding);
+        assert_eq!(allocations.len(), 4);
+        assert_eq!(allocations[3].participant_id, house);
+        assert_eq!(allocations[3].amount, dec!(0.01));
+        apply_allocations(&mut positions, d, &allocations);
+
+        assert_eq!(net(&positions, house), dec!(-0.01));
+        assert_eq!(net(&positions, d), dec!(-200));
+        assert_eq!(positions.iter().map(|p| p.net_position).sum::<Decimal>(), dec!(0));
+    }
+}
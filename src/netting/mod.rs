@@ -1,3 +1,5 @@
 pub mod bilateral;
 pub mod calculator;
+pub mod default_management;
 pub mod multilateral;
+pub mod optimizer;
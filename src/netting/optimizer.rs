@@ -0,0 +1,381 @@
+//! Settlement instruction optimization.
+//!
+//! The greedy matcher in `NettingService` pairs the largest payers with the largest
+//! receivers. That always moves the minimum total amount but can use more instructions
+//! than necessary. The functions here find the instruction set with the fewest transfers
+//! and remove circular flows from arbitrary transfer sets.
+
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Participant counts up to this size are partitioned exactly; larger sets fall back to
+/// exact-amount pairing followed by greedy matching.
+pub const EXACT_PARTITION_LIMIT: usize = 18;
+
+/// A single transfer between two participants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub amount: Decimal,
+}
+
+/// Settles net positions (positive = receive, negative = pay) with the fewest transfers.
+///
+/// A group of `k` participants whose positions sum to zero needs at most `k - 1`
+/// transfers, so the instruction count is `n - g` where `g` is the number of zero-sum
+/// groups. Maximizing `g` is a subset-sum problem; it is solved exactly up to
+/// [`EXACT_PARTITION_LIMIT`] participants. The total amount moved is always the sum of
+/// the positive positions.
+pub fn minimize_transfers(positions: &[(Uuid, Decimal)]) -> Vec<Transfer> {
+    let active: Vec<(Uuid, Decimal)> = positions
+        .iter()
+        .filter(|(_, net)| !net.is_zero())
+        .copied()
+        .collect();
+
+    let groups = if active.len() <= EXACT_PARTITION_LIMIT {
+        partition_exact(&active)
+    } else {
+        partition_heuristic(&active)
+    };
+
+    groups.iter().flat_map(|group| settle_group(group)).collect()
+}
+
+/// Removes directed cycles from a set of transfers.
+///
+/// Transfers between the same pair are first netted against each other. Every remaining
+/// cycle is then reduced by its smallest edge, which drops at least one transfer per
+/// cycle. Each participant's net flow is unchanged and the total amount never grows.
+pub fn cancel_cycles(transfers: &[Transfer]) -> Vec<Transfer> {
+    let mut edges: HashMap<(Uuid, Uuid), Decimal> = HashMap::new();
+    for t in transfers.iter().filter(|t| t.from != t.to && !t.amount.is_zero()) {
+        let reverse = (t.to, t.from);
+        let mut amount = t.amount;
+        if let Some(existing) = edges.get_mut(&reverse) {
+            let offset = amount.min(*existing);
+            *existing -= offset;
+            amount -= offset;
+            if existing.is_zero() {
+                edges.remove(&reverse);
+            }
+        }
+        if !amount.is_zero() {
+            *edges.entry((t.from, t.to)).or_insert(Decimal::ZERO) += amount;
+        }
+    }
+
+    while let Some(cycle) = find_cycle(&edges) {
+        let min = cycle
+            .iter()
+            .map(|edge| edges[edge])
+            .min()
+            .unwrap_or(Decimal::ZERO);
+        for edge in &cycle {
+            let remaining = edges.get_mut(edge).expect("cycle edge exists");
+            *remaining -= min;
+            if remaining.is_zero() {
+                edges.remove(edge);
+            }
+        }
+    }
+
+    let mut result: Vec<Transfer> = edges
+        .into_iter()
+        .map(|((from, to), amount)| Transfer { from, to, amount })
+        .collect();
+    result.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.from.cmp(&b.from)).then(a.to.cmp(&b.to)));
+    result
+}
+
+/// Partitions positions into the maximum number of zero-sum groups.
+///
+/// `best[mask]` is the most zero-sum prefixes reachable by adding the members of `mask`
+/// one at a time. Walking back from the full mask gives an ordering whose zero-sum
+/// prefixes mark the group boundaries.
+fn partition_exact(positions: &[(Uuid, Decimal)]) -> Vec<Vec<(Uuid, Decimal)>> {
+    let n = positions.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let size = 1usize << n;
+    let mut sums = vec![Decimal::ZERO; size];
+    let mut best = vec![0u8; size];
+    for mask in 1..size {
+        let low = mask.trailing_zeros() as usize;
+        sums[mask] = sums[mask & (mask - 1)] + positions[low].1;
+
+        let closes = u8::from(sums[mask].is_zero());
+        best[mask] = (0..n)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| best[mask ^ (1 << i)])
+            .max()
+            .unwrap_or(0)
+            + closes;
+    }
+
+    let mut order = Vec::with_capacity(n);
+    let mut mask = size - 1;
+    while mask != 0 {
+        let target = best[mask] - u8::from(sums[mask].is_zero());
+        let i = (0..n)
+            .find(|i| mask & (1 << i) != 0 && best[mask ^ (1 << i)] == target)
+            .expect("a predecessor state always exists");
+        order.push(i);
+        mask ^= 1 << i;
+    }
+    order.reverse();
+
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    let mut running = Decimal::ZERO;
+    for i in order {
+        current.push(positions[i]);
+        running += positions[i].1;
+        if running.is_zero() {
+            groups.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// Pairs payers and receivers with exactly offsetting amounts, leaving everyone else in
+/// a single group.
+fn partition_heuristic(positions: &[(Uuid, Decimal)]) -> Vec<Vec<(Uuid, Decimal)>> {
+    let mut receivers_by_amount: HashMap<Decimal, Vec<usize>> = HashMap::new();
+    for (i, (_, net)) in positions.iter().enumerate() {
+        if *net > Decimal::ZERO {
+            receivers_by_amount.entry(*net).or_default().push(i);
+        }
+    }
+
+    let mut paired: HashSet<usize> = HashSet::new();
+    let mut groups = Vec::new();
+    for (i, (_, net)) in positions.iter().enumerate() {
+        if *net >= Decimal::ZERO {
+            continue;
+        }
+        if let Some(j) = receivers_by_amount.get_mut(&net.abs()).and_then(|c| c.pop()) {
+            paired.insert(i);
+            paired.insert(j);
+            groups.push(vec![positions[i], positions[j]]);
+        }
+    }
+
+    let rest: Vec<(Uuid, Decimal)> = positions
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !paired.contains(i))
+        .map(|(_, p)| *p)
+        .collect();
+    if !rest.is_empty() {
+        groups.push(rest);
+    }
+    groups
+}
+
+/// Settles a zero-sum group with at most `len - 1` transfers by repeatedly matching the
+/// largest remaining payer with the largest remaining receiver.
+fn settle_group(group: &[(Uuid, Decimal)]) -> Vec<Transfer> {
+    let mut payers: Vec<(Uuid, Decimal)> = group
+        .iter()
+        .filter(|(_, net)| *net < Decimal::ZERO)
+        .map(|(id, net)| (*id, net.abs()))
+        .collect();
+    let mut receivers: Vec<(Uuid, Decimal)> = group
+        .iter()
+        .filter(|(_, net)| *net > Decimal::ZERO)
+        .copied()
+        .collect();
+    payers.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    receivers.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut transfers = Vec::new();
+    let (mut p, mut r) = (0, 0);
+    while p < payers.len() && r < receivers.len() {
+        let amount = payers[p].1.min(receivers[r].1);
+        transfers.push(Transfer {
+            from: payers[p].0,
+            to: receivers[r].0,
+            amount,
+        });
+        payers[p].1 -= amount;
+        receivers[r].1 -= amount;
+        if payers[p].1.is_zero() {
+            p += 1;
+        }
+        if receivers[r].1.is_zero() {
+            r += 1;
+        }
+    }
+    transfers
+}
+
+/// Returns the edges of any directed cycle in the transfer graph.
+fn find_cycle(edges: &HashMap<(Uuid, Uuid), Decimal>) -> Option<Vec<(Uuid, Uuid)>> {
+    let mut adjacency: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (from, to) in edges.keys() {
+        adjacency.entry(*from).or_default().push(*to);
+    }
+    for targets in adjacency.values_mut() {
+        targets.sort();
+    }
+    let mut starts: Vec<Uuid> = adjacency.keys().copied().collect();
+    starts.sort();
+
+    let mut finished: HashSet<Uuid> = HashSet::new();
+    for start in starts {
+        if finished.contains(&start) {
+            continue;
+        }
+        // Iterative DFS; `path` holds the nodes on the current stack.
+        let mut path: Vec<Uuid> = vec![start];
+        let mut next_child: Vec<usize> = vec![0];
+        let mut on_path: HashSet<Uuid> = HashSet::from([start]);
+
+        while let Some(&node) = path.last() {
+            let depth = path.len() - 1;
+            let child = adjacency
+                .get(&node)
+                .and_then(|targets| targets.get(next_child[depth]))
+                .copied();
+            next_child[depth] += 1;
+
+            match child {
+                Some(target) if on_path.contains(&target) => {
+                    let begin = path.iter().position(|n| *n == target).expect("target on path");
+                    let mut cycle: Vec<(Uuid, Uuid)> =
+                        path[begin..].windows(2).map(|w| (w[0], w[1])).collect();
+                    cycle.push((node, target));
+                    return Some(cycle);
+                }
+                Some(target) if !finished.contains(&target) => {
+                    path.push(target);
+                    next_child.push(0);
+                    on_path.insert(target);
+                }
+                Some(_) => {}
+                None => {
+                    finished.insert(node);
+                    on_path.remove(&node);
+                    path.pop();
+                    next_child.pop();
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::new_v4()).collect()
+    }
+
+    fn net_flows(transfers: &[Transfer]) -> HashMap<Uuid, Decimal> {
+        let mut flows = HashMap::new();
+        for t in transfers {
+            *flows.entry(t.from).or_insert(Decimal::ZERO) -= t.amount;
+            *flows.entry(t.to).or_insert(Decimal::ZERO) += t.amount;
+        }
+        flows.retain(|_, v| !v.is_zero());
+        flows
+    }
+
+    #[test]
+    fn test_minimize_transfers_finds_zero_sum_groups() {
+        let p = ids(5);
+        // Greedy would need 4 transfers; {p1, p3} and {p0, p2, p4} need 1 + 2.
+        let positions = vec![
+            (p[0], dec!(-6)),
+            (p[1], dec!(-4)),
+            (p[2], dec!(5)),
+            (p[3], dec!(4)),
+            (p[4], dec!(1)),
+        ];
+
+        let transfers = minimize_transfers(&positions);
+
+        assert_eq!(transfers.len(), 3);
+        let total: Decimal = transfers.iter().map(|t| t.amount).sum();
+        assert_eq!(total, dec!(10));
+        let expected: HashMap<Uuid, Decimal> = positions.into_iter().collect();
+        assert_eq!(net_flows(&transfers), expected);
+    }
+
+    #[test]
+    fn test_minimize_transfers_ignores_balanced_participants() {
+        let p = ids(3);
+        let positions = vec![(p[0], dec!(-10)), (p[1], Decimal::ZERO), (p[2], dec!(10))];
+
+        let transfers = minimize_transfers(&positions);
+
+        assert_eq!(transfers, vec![Transfer { from: p[0], to: p[2], amount: dec!(10) }]);
+        assert!(minimize_transfers(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_minimize_transfers_heuristic_above_limit() {
+        let p = ids(EXACT_PARTITION_LIMIT + 2);
+        let half = p.len() / 2;
+        let positions: Vec<(Uuid, Decimal)> = p
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let amount = Decimal::from((i % half) as i64 + 1);
+                (*id, if i < half { -amount } else { amount })
+            })
+            .collect();
+
+        let transfers = minimize_transfers(&positions);
+
+        assert_eq!(transfers.len(), half);
+        let expected: HashMap<Uuid, Decimal> = positions.into_iter().collect();
+        assert_eq!(net_flows(&transfers), expected);
+    }
+
+    #[test]
+    fn test_cancel_cycles_removes_circular_flow() {
+        let p = ids(3);
+        let transfers = vec![
+            Transfer { from: p[0], to: p[1], amount: dec!(100) },
+            Transfer { from: p[1], to: p[2], amount: dec!(100) },
+            Transfer { from: p[2], to: p[0], amount: dec!(100) },
+        ];
+
+        assert!(cancel_cycles(&transfers).is_empty());
+    }
+
+    #[test]
+    fn test_cancel_cycles_preserves_net_flows() {
+        let p = ids(4);
+        let transfers = vec![
+            Transfer { from: p[0], to: p[1], amount: dec!(100) },
+            Transfer { from: p[1], to: p[2], amount: dec!(60) },
+            Transfer { from: p[2], to: p[0], amount: dec!(40) },
+            Transfer { from: p[2], to: p[3], amount: dec!(30) },
+            Transfer { from: p[1], to: p[0], amount: dec!(10) },
+        ];
+
+        let reduced = cancel_cycles(&transfers);
+
+        assert_eq!(net_flows(&reduced), net_flows(&transfers));
+        let before: Decimal = transfers.iter().map(|t| t.amount).sum();
+        let after: Decimal = reduced.iter().map(|t| t.amount).sum();
+        assert!(after < before);
+        assert!(find_cycle(
+            &reduced.iter().map(|t| ((t.from, t.to), t.amount)).collect()
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::core::circuit_breaker::{CircuitBreakerConfig, CircuitBreakerRegistry};
+use crate::error::{AppError, Result};
+use crate::events::{AlertEvent, EventEnvelope, EventProducer, EventType};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+use super::engine::AlertNotification;
+
+/// A destination triggered alerts are delivered to.
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    /// Name of the sink, used in logs.
+    fn name(&self) -> &str;
+
+    /// Delivers a single alert notification.
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()>;
+}
+
+/// Publishes alerts to the `settlement.alerts` Kafka topic.
+pub struct KafkaNotificationSink {
+    producer: Arc<EventProducer>,
+}
+
+impl KafkaNotificationSink {
+    pub fn new(producer: Arc<EventProducer>) -> Self {
+        Self { producer }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for KafkaNotificationSink {
+    fn name(&self) -> &str {
+        "kafka"
+    }
+
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()> {
+        let envelope = EventEnvelope::new(EventType::AlertTriggered, AlertEvent::from(notification));
+        let key = notification.account_id.to_string();
+        self.producer
+            .send(AlertEvent::topic(), Some(&key), &envelope)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Posts alerts as JSON to the webhook URL configured on the triggering rule.
+/// Rules without a webhook URL are skipped.
+///
+/// Each webhook host has its own circuit breaker, so one unreachable endpoint fails
+/// fast without holding up deliveries to the others.
+pub struct WebhookNotificationSink {
+    client: reqwest::Client,
+    breakers: CircuitBreakerRegistry,
+}
+
+impl WebhookNotificationSink {
+    pub fn new(timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| AppError::Internal(anyhow!("Failed to build webhook client: {}", e)))?;
+        Ok(Self {
+            client,
+            breakers: CircuitBreakerRegistry::new("webhook", CircuitBreakerConfig::default()),
+        })
+    }
+
+    /// Uses this configuration for the per-host circuit breakers.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.breakers = CircuitBreakerRegistry::new("webhook", config);
+        self
+    }
+
+    async fn post(&self, url: &str, body: Vec<u8>) -> Result<()> {
+        let response = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("Webhook request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Internal(anyhow!(
+                "Webhook returned status {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Host part of a URL, used to key webhook circuit breakers.
+fn webhook_host(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    authority.rsplit('@').next().unwrap_or(authority)
+}
+
+#[async_trait]
+impl NotificationSink for WebhookNotificationSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn deliver(&self, notification: &AlertNotification) -> Result<()> {
+        let Some(url) = notification.webhook_url.as_deref() else {
+            return Ok(());
+        };
+
+        let body = serde_json::to_vec(notification)
+            .map_err(|e| AppError::Internal(anyhow!("Failed to serialize alert: {}", e)))?;
+
+        self.breakers
+            .get(webhook_host(url))
+            .call(|| self.post(url, body))
+            .await?;
+
+        debug!("Delivered alert {} to webhook", notification.alert_id);
+        Ok(())
+    }
+}
+
+impl From<&AlertNotification> for AlertEvent {
+    fn from(n: &AlertNotification) -> Self {
+        Self {
+            alert_id: n.alert_id,
+            rule_id: n.rule_id,
+            rule_type: n.rule_type,
+            account_id: n.account_id,
+            transaction_id: n.transaction_id,
+            currency: n.currency.clone(),
+            threshold: n.threshold,
+            observed_value: n.observed_value,
+            message: n.message.clone(),
+            triggered_at: n.triggered_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AlertRuleType;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_webhook_host() {
+        assert_eq!(webhook_host("https://hooks.example.com/alerts?x=1"), "hooks.example.com");
+        assert_eq!(webhook_host("http://user:pw@10.0.0.5:8080/hook"), "10.0.0.5:8080");
+        assert_eq!(webhook_host("hooks.example.com"), "hooks.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_webhook_opens_circuit() {
+        let sink = WebhookNotificationSink::new(Duration::from_secs(1))
+            .unwrap()
+            .with_circuit_breaker(CircuitBreakerConfig {
+                minimum_calls: 2,
+                ..CircuitBreakerConfig::default()
+            });
+        let notification = AlertNotification {
+            alert_id: Uuid::new_v4(),
+            rule_id: Uuid::new_v4(),
+            rule_type: AlertRuleType::LowBalance,
+            account_id: Uuid::new_v4(),
+            transaction_id: None,
+            currency: "USD".to_string(),
+            threshold: None,
+            observed_value: None,
+            message: "Balance below threshold".to_string(),
+            webhook_url: Some("http://127.0.0.1:1/alerts".to_string()),
+            triggered_at: Utc::now(),
+        };
+
+        for _ in 0..2 {
+            let error = sink.deliver(&notification).await.unwrap_err();
+            assert!(error.to_string().contains("Webhook request failed"));
+        }
+        let error = sink.deliver(&notification).await.unwrap_err();
+        assert!(error.to_string().contains("webhook:127.0.0.1:1 unavailable: circuit open"));
+    }
+}
@@ -0,0 +1,513 @@
+use crate::error::Result;
+use crate::models::{AccountBalance, AlertRule, AlertRuleType, BalanceBreak, BalanceIncident, TransactionRecord};
+use crate::observability::get_metrics;
+use crate::repositories::AlertRuleRepository;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::delivery::NotificationSink;
+
+/// An alert produced by a rule that matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertNotification {
+    pub alert_id: Uuid,
+    pub rule_id: Uuid,
+    pub rule_type: AlertRuleType,
+    pub account_id: Uuid,
+    pub transaction_id: Option<Uuid>,
+    pub currency: String,
+    pub threshold: Option<Decimal>,
+    pub observed_value: Option<Decimal>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    pub triggered_at: DateTime<Utc>,
+}
+
+impl AlertNotification {
+    fn from_rule(
+        rule: &AlertRule,
+        account_id: Uuid,
+        transaction_id: Option<Uuid>,
+        observed_value: Option<Decimal>,
+        message: String,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            alert_id: Uuid::new_v4(),
+            rule_id: rule.id,
+            rule_type: rule.rule_type,
+            account_id,
+            transaction_id,
+            currency: rule.currency.clone(),
+            threshold: rule.threshold,
+            observed_value,
+            message,
+            webhook_url: rule.webhook_url.clone(),
+            triggered_at: now,
+        }
+    }
+}
+
+/// A transaction that could not be settled.
+#[derive(Debug, Clone)]
+pub struct SettlementFailure {
+    pub transaction_id: Option<Uuid>,
+    pub external_id: String,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub reason: String,
+}
+
+/// Whether a rule can fire now. A rule for one account is skipped while suppressed; a
+/// rule covering every account is suppressed per account when its trigger is claimed.
+fn may_fire(rule: &AlertRule, now: DateTime<Utc>) -> bool {
+    rule.account_id.is_none() || !rule.is_suppressed(now)
+}
+
+/// Matches rules against a settled transaction and the balances it produced.
+/// A large-transaction rule fires at most once per transaction even if it covers both accounts.
+pub fn match_settlement_rules(
+    rules: &[AlertRule],
+    transaction: &TransactionRecord,
+    balances: &[AccountBalance],
+    now: DateTime<Utc>,
+) -> Vec<AlertNotification> {
+    let mut alerts = Vec::new();
+
+    for rule in rules.iter().filter(|r| may_fire(r, now)) {
+        match rule.rule_type {
+            AlertRuleType::LowBalance | AlertRuleType::HighBalance => {
+                for balance in balances {
+                    if rule.applies_to(balance.account_id, &balance.currency)
+                        && rule.is_breached_by_balance(balance.available_balance)
+                    {
+                        let direction = if rule.rule_type == AlertRuleType::LowBalance { "below" } else { "above" };
+                        alerts.push(AlertNotification::from_rule(
+                            rule,
+                            balance.account_id,
+                            Some(transaction.id),
+                            Some(balance.available_balance),
+                            format!(
+                                "Available balance {} {} is {} threshold {}",
+                                balance.available_balance,
+                                balance.currency,
+                                direction,
+                                rule.threshold.unwrap_or_default()
+                            ),
+                            now,
+                        ));
+                    }
+                }
+            }
+            AlertRuleType::LargeTransaction => {
+                if !rule.is_breached_by_amount(transaction.amount) {
+                    continue;
+                }
+                let account_id = [transaction.source_account_id, transaction.destination_account_id]
+                    .into_iter()
+                    .find(|id| rule.applies_to(*id, &transaction.currency));
+                if let Some(account_id) = account_id {
+                    alerts.push(AlertNotification::from_rule(
+                        rule,
+                        account_id,
+                        Some(transaction.id),
+                        Some(transaction.amount),
+                        format!(
+                            "Transaction {} of {} {} meets large-transaction threshold {}",
+                            transaction.external_id,
+                            transaction.amount,
+                            transaction.currency,
+                            rule.threshold.unwrap_or_default()
+                        ),
+                        now,
+                    ));
+                }
+            }
+            AlertRuleType::FailedSettlement | AlertRuleType::BalanceBreak | AlertRuleType::NegativeBalance => {}
+        }
+    }
+
+    alerts
+}
+
+/// Matches failed-settlement rules against a transaction that could not be settled.
+pub fn match_failure_rules(
+    rules: &[AlertRule],
+    failure: &SettlementFailure,
+    now: DateTime<Utc>,
+) -> Vec<AlertNotification> {
+    rules
+        .iter()
+        .filter(|r| r.rule_type == AlertRuleType::FailedSettlement && may_fire(r, now))
+        .filter_map(|rule| {
+            [failure.source_account_id, failure.destination_account_id]
+                .into_iter()
+                .find(|id| rule.applies_to(*id, &failure.currency))
+                .map(|account_id| {
+                    AlertNotification::from_rule(
+                        rule,
+                        account_id,
+                        failure.transaction_id,
+                        Some(failure.amount),
+                        format!("Transaction {} failed to settle: {}", failure.external_id, failure.reason),
+                        now,
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Matches balance-break rules against a break found by reconciliation.
+pub fn match_break_rules(
+    rules: &[AlertRule],
+    balance_break: &BalanceBreak,
+    now: DateTime<Utc>,
+) -> Vec<AlertNotification> {
+    rules
+        .iter()
+        .filter(|r| r.rule_type == AlertRuleType::BalanceBreak && may_fire(r, now))
+        .filter(|r| r.applies_to(balance_break.account_id, &balance_break.currency))
+        .map(|rule| {
+            AlertNotification::from_rule(
+                rule,
+                balance_break.account_id,
+                None,
+                Some(balance_break.difference),
+                format!(
+                    "Stored balance {} {} differs from ledger-derived balance {} by {}",
+                    balance_break.stored_balance,
+                    balance_break.currency,
+                    balance_break.derived_balance,
+                    balance_break.difference
+                ),
+                now,
+            )
+        })
+        .collect()
+}
+
+/// Matches negative-balance rules against an incident the balance guard froze an
+/// account for.
+pub fn match_incident_rules(
+    rules: &[AlertRule],
+    incident: &BalanceIncident,
+    now: DateTime<Utc>,
+) -> Vec<AlertNotification> {
+    rules
+        .iter()
+        .filter(|r| r.rule_type == AlertRuleType::NegativeBalance && may_fire(r, now))
+        .filter(|r| r.applies_to(incident.account_id, &incident.currency))
+        .map(|rule| {
+            AlertNotification::from_rule(
+                rule,
+                incident.account_id,
+                None,
+                Some(incident.available_balance),
+                format!(
+                    "CRITICAL: balance {} {} (pending {}, reserved {}) breached floor {}; account frozen",
+                    incident.available_balance,
+                    incident.currency,
+                    incident.pending_balance,
+                    incident.reserved_balance,
+                    incident.floor
+                ),
+                now,
+            )
+        })
+        .collect()
+}
+
+/// Evaluates alert rules and fans triggered alerts out to the configured sinks.
+///
+/// Evaluation never fails the caller: rule lookups and deliveries that error are logged
+/// and dropped so that alerting cannot block settlement.
+#[derive(Clone)]
+pub struct NotificationEngine {
+    rule_repo: Arc<AlertRuleRepository>,
+    sinks: Vec<Arc<dyn NotificationSink>>,
+}
+
+impl NotificationEngine {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            rule_repo: Arc::new(AlertRuleRepository::new(pool)),
+            sinks: Vec::new(),
+        }
+    }
+
+    /// Adds a delivery sink.
+    pub fn with_sink(mut self, sink: Arc<dyn NotificationSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Evaluates balance and large-transaction rules for a settled transaction.
+    pub async fn evaluate_settlement(
+        &self,
+        transaction: &TransactionRecord,
+        balances: &[AccountBalance],
+    ) -> Vec<AlertNotification> {
+        let account_ids = [transaction.source_account_id, transaction.destination_account_id];
+        let rules = match self.load_rules(&account_ids, &transaction.currency).await {
+            Ok(rules) => rules,
+            Err(_) => return Vec::new(),
+        };
+
+        let candidates = match_settlement_rules(&rules, transaction, balances, Utc::now());
+        self.dispatch(candidates).await
+    }
+
+    /// Evaluates failed-settlement rules.
+    pub async fn evaluate_failure(&self, failure: &SettlementFailure) -> Vec<AlertNotification> {
+        let account_ids = [failure.source_account_id, failure.destination_account_id];
+        let rules = match self.load_rules(&account_ids, &failure.currency).await {
+            Ok(rules) => rules,
+            Err(_) => return Vec::new(),
+        };
+
+        let candidates = match_failure_rules(&rules, failure, Utc::now());
+        self.dispatch(candidates).await
+    }
+
+    /// Evaluates balance-break rules for a newly detected break.
+    pub async fn evaluate_break(&self, balance_break: &BalanceBreak) -> Vec<AlertNotification> {
+        let rules = match self.load_rules(&[balance_break.account_id], &balance_break.currency).await {
+            Ok(rules) => rules,
+            Err(_) => return Vec::new(),
+        };
+
+        let candidates = match_break_rules(&rules, balance_break, Utc::now());
+        self.dispatch(candidates).await
+    }
+
+    /// Evaluates negative-balance rules for a balance incident.
+    pub async fn evaluate_incident(&self, incident: &BalanceIncident) -> Vec<AlertNotification> {
+        let rules = match self.load_rules(&[incident.account_id], &incident.currency).await {
+            Ok(rules) => rules,
+            Err(_) => return Vec::new(),
+        };
+
+        let candidates = match_incident_rules(&rules, incident, Utc::now());
+        self.dispatch(candidates).await
+    }
+
+    async fn load_rules(&self, account_ids: &[Uuid], currency: &str) -> Result<Vec<AlertRule>> {
+        self.rule_repo
+            .find_enabled_for_accounts(account_ids, currency)
+            .await
+            .map_err(|e| {
+                warn!("Failed to load alert rules: {}", e);
+                e
+            })
+    }
+
+    /// Claims each candidate's suppression window and delivers the ones that won it.
+    async fn dispatch(&self, candidates: Vec<AlertNotification>) -> Vec<AlertNotification> {
+        let mut delivered = Vec::new();
+
+        for alert in candidates {
+            let rule_type = format!("{:?}", alert.rule_type);
+            match self.rule_repo.mark_triggered(alert.rule_id, alert.account_id, alert.triggered_at).await {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    get_metrics().record_alert_suppressed(&rule_type);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Failed to record alert trigger for rule {}: {}", alert.rule_id, e);
+                    continue;
+                }
+            }
+
+            info!(
+                rule_id = %alert.rule_id,
+                account_id = %alert.account_id,
+                "Alert triggered: {}",
+                alert.message
+            );
+            get_metrics().record_alert_triggered(&rule_type);
+
+            for sink in &self.sinks {
+                let result = sink.deliver(&alert).await;
+                if let Err(e) = &result {
+                    warn!("Failed to deliver alert {} via {}: {}", alert.alert_id, sink.name(), e);
+                }
+                get_metrics().record_alert_delivery(sink.name(), result.is_ok());
+            }
+
+            delivered.push(alert);
+        }
+
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TransactionType;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    fn transaction(source: Uuid, destination: Uuid, amount: Decimal) -> TransactionRecord {
+        TransactionRecord::new(
+            "TX-001".to_string(),
+            TransactionType::Payment,
+            source,
+            destination,
+            amount,
+            "USD".to_string(),
+            Decimal::ZERO,
+            "IDEM-001".to_string(),
+        )
+    }
+
+    fn balance(account_id: Uuid, available: Decimal) -> AccountBalance {
+        AccountBalance::with_available_balance(account_id, "USD".to_string(), available)
+    }
+
+    #[test]
+    fn test_low_balance_rule_matches_only_breaching_account() {
+        let source = Uuid::new_v4();
+        let destination = Uuid::new_v4();
+        let rule = AlertRule::new(AlertRuleType::LowBalance, "USD".to_string(), Some(dec!(100)));
+        let tx = transaction(source, destination, dec!(50));
+        let balances = vec![balance(source, dec!(20)), balance(destination, dec!(500))];
+
+        let alerts = match_settlement_rules(&[rule], &tx, &balances, Utc::now());
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].account_id, source);
+        assert_eq!(alerts[0].observed_value, Some(dec!(20)));
+    }
+
+    #[test]
+    fn test_large_transaction_rule_fires_once() {
+        let source = Uuid::new_v4();
+        let destination = Uuid::new_v4();
+        let rule = AlertRule::new(AlertRuleType::LargeTransaction, "USD".to_string(), Some(dec!(10000)));
+        let tx = transaction(source, destination, dec!(25000));
+
+        let alerts = match_settlement_rules(&[rule], &tx, &[], Utc::now());
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].transaction_id, Some(tx.id));
+    }
+
+    #[test]
+    fn test_scoped_rule_ignores_other_accounts() {
+        let rule = AlertRule::new(AlertRuleType::HighBalance, "USD".to_string(), Some(dec!(1000)))
+            .for_account(Uuid::new_v4());
+        let source = Uuid::new_v4();
+        let tx = transaction(source, Uuid::new_v4(), dec!(10));
+
+        let alerts = match_settlement_rules(&[rule], &tx, &[balance(source, dec!(5000))], Utc::now());
+
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_suppressed_rule_is_skipped() {
+        let now = Utc::now();
+        let source = Uuid::new_v4();
+        let mut rule = AlertRule::new(AlertRuleType::LargeTransaction, "USD".to_string(), Some(dec!(100)))
+            .for_account(source)
+            .with_suppression_window(600);
+        rule.last_triggered_at = Some(now - Duration::seconds(10));
+        let tx = transaction(source, Uuid::new_v4(), dec!(1000));
+
+        assert!(match_settlement_rules(&[rule], &tx, &[], now).is_empty());
+    }
+
+    #[test]
+    fn test_rule_for_every_account_is_suppressed_per_account() {
+        let now = Utc::now();
+        let mut rule = AlertRule::new(AlertRuleType::LargeTransaction, "USD".to_string(), Some(dec!(100)))
+            .with_suppression_window(600);
+        rule.last_triggered_at = Some(now - Duration::seconds(10));
+        let tx = transaction(Uuid::new_v4(), Uuid::new_v4(), dec!(1000));
+
+        // Whether this account is suppressed is decided when the trigger is claimed
+        assert_eq!(match_settlement_rules(&[rule], &tx, &[], now).len(), 1);
+    }
+
+    #[test]
+    fn test_failure_rules() {
+        let source = Uuid::new_v4();
+        let failure_rule = AlertRule::new(AlertRuleType::FailedSettlement, "USD".to_string(), None);
+        let balance_rule = AlertRule::new(AlertRuleType::LowBalance, "USD".to_string(), Some(dec!(1)));
+        let failure = SettlementFailure {
+            transaction_id: None,
+            external_id: "TX-001".to_string(),
+            source_account_id: source,
+            destination_account_id: Uuid::new_v4(),
+            amount: dec!(100),
+            currency: "USD".to_string(),
+            reason: "Insufficient funds".to_string(),
+        };
+
+        let alerts = match_failure_rules(&[failure_rule, balance_rule], &failure, Utc::now());
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].account_id, source);
+        assert!(alerts[0].message.contains("Insufficient funds"));
+    }
+
+    #[test]
+    fn test_break_rules() {
+        let account = Uuid::new_v4();
+        let break_rule = AlertRule::new(AlertRuleType::BalanceBreak, "USD".to_string(), None);
+        let other_currency = AlertRule::new(AlertRuleType::BalanceBreak, "EUR".to_string(), None);
+        let balance_break = BalanceBreak::new(&crate::models::BalanceCheck {
+            account_id: account,
+            currency: "USD".to_string(),
+            stored_balance: dec!(150),
+            derived_balance: dec!(100),
+            entry_count: 2,
+        });
+
+        let alerts = match_break_rules(&[break_rule, other_currency], &balance_break, Utc::now());
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].account_id, account);
+        assert_eq!(alerts[0].transaction_id, None);
+        assert_eq!(alerts[0].observed_value, Some(dec!(50)));
+    }
+
+    #[test]
+    fn test_incident_rules() {
+        let account = Uuid::new_v4();
+        let incident_rule = AlertRule::new(AlertRuleType::NegativeBalance, "USD".to_string(), None);
+        let break_rule = AlertRule::new(AlertRuleType::BalanceBreak, "USD".to_string(), None);
+        let incident = BalanceIncident {
+            id: Uuid::new_v4(),
+            account_id: account,
+            currency: "USD".to_string(),
+            floor: dec!(0),
+            available_balance: dec!(-12),
+            pending_balance: dec!(0),
+            reserved_balance: dec!(0),
+            status: crate::models::BalanceIncidentStatus::Frozen,
+            detected_at: Utc::now(),
+            last_seen_at: Utc::now(),
+            frozen_at: Some(Utc::now()),
+            resolved_at: None,
+            resolution_note: None,
+        };
+
+        let alerts = match_incident_rules(&[incident_rule, break_rule], &incident, Utc::now());
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule_type, AlertRuleType::NegativeBalance);
+        assert_eq!(alerts[0].observed_value, Some(dec!(-12)));
+        assert!(alerts[0].message.starts_with("CRITICAL"));
+    }
+}
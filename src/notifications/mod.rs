@@ -0,0 +1,9 @@
+pub mod delivery;
+pub mod engine;
+
+pub use delivery::{KafkaNotificationSink, NotificationSink, WebhookNotificationSink};
+pub use engine::{
+    match_break_rules, match_failure_rules, match_incident_rules, match_settlement_rules, AlertNotification,
+    NotificationEngine,
+    SettlementFailure,
+};
@@ -1,7 +1,9 @@
+use crate::cache::RedisPool;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Health status of a service or dependency.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -98,28 +100,237 @@ impl AggregatedHealth {
     }
 }
 
+/// Whether a dependency is required for the service to accept traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    /// The service cannot operate without it (e.g. Postgres).
+    Hard,
+    /// The service keeps running in a degraded mode without it (e.g. Kafka).
+    Soft,
+}
+
+/// Latency and error statistics for a dependency over the rolling window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DependencyStats {
+    pub samples: usize,
+    pub errors: usize,
+    pub error_rate: f64,
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: Instant,
+    latency_ms: Option<f64>,
+    success: bool,
+}
+
+/// Time-bounded window of health check samples for one dependency.
+#[derive(Debug)]
+struct RollingWindow {
+    window: Duration,
+    samples: VecDeque<Sample>,
+}
+
+impl RollingWindow {
+    const MAX_SAMPLES: usize = 1024;
+
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, sample: Sample) {
+        self.samples.push_back(sample);
+        if self.samples.len() > Self::MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.evict(sample.at);
+    }
+
+    fn evict(&mut self, now: Instant) {
+        while let Some(oldest) = self.samples.front() {
+            if now.duration_since(oldest.at) > self.window {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn stats(&self) -> DependencyStats {
+        let samples = self.samples.len();
+        let errors = self.samples.iter().filter(|s| !s.success).count();
+        let latencies: Vec<f64> = self.samples.iter().filter_map(|s| s.latency_ms).collect();
+
+        DependencyStats {
+            samples,
+            errors,
+            error_rate: if samples == 0 { 0.0 } else { errors as f64 / samples as f64 },
+            avg_latency_ms: if latencies.is_empty() {
+                None
+            } else {
+                Some(latencies.iter().sum::<f64>() / latencies.len() as f64)
+            },
+            max_latency_ms: latencies.iter().copied().reduce(f64::max),
+        }
+    }
+}
+
+/// Policy deciding whether the service is ready based on dependency health.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessPolicy {
+    /// Dependencies that must be available for readiness, the database by default. All
+    /// others are soft: without Redis the balance cache misses through to PostgreSQL and
+    /// idempotency checks run on PostgreSQL alone.
+    pub hard_dependencies: Vec<String>,
+    /// Whether a degraded (slow but reachable) hard dependency still counts as ready.
+    /// Off by default; relaxed mode has to be opted into.
+    pub allow_degraded_hard: bool,
+    /// Hard dependencies with a rolling error rate above this are treated as not ready.
+    pub max_error_rate: f64,
+    /// Minimum samples in the window before the error rate is enforced.
+    pub min_samples: usize,
+}
+
+impl Default for ReadinessPolicy {
+    fn default() -> Self {
+        Self {
+            hard_dependencies: vec!["database".to_string()],
+            allow_degraded_hard: false,
+            max_error_rate: 0.5,
+            min_samples: 5,
+        }
+    }
+}
+
+impl ReadinessPolicy {
+    /// Returns the kind of the named dependency under this policy.
+    pub fn kind_of(&self, name: &str) -> DependencyKind {
+        if self.hard_dependencies.iter().any(|d| d == name) {
+            DependencyKind::Hard
+        } else {
+            DependencyKind::Soft
+        }
+    }
+
+    /// Returns true if the given dependency details satisfy the policy.
+    pub fn is_ready(&self, dependencies: &[DependencyDetail]) -> bool {
+        dependencies
+            .iter()
+            .filter(|d| d.kind == DependencyKind::Hard)
+            .all(|d| {
+                let status_ok = match d.health.status {
+                    HealthStatus::Healthy => true,
+                    HealthStatus::Degraded => self.allow_degraded_hard,
+                    HealthStatus::Unhealthy => false,
+                };
+                let error_rate_ok = d.stats.samples < self.min_samples
+                    || d.stats.error_rate <= self.max_error_rate;
+                status_ok && error_rate_ok
+            })
+    }
+}
+
+/// Health of a dependency with its classification and rolling statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyDetail {
+    #[serde(flatten)]
+    pub health: DependencyHealth,
+    pub kind: DependencyKind,
+    pub stats: DependencyStats,
+}
+
+/// Detailed health report including readiness under the configured policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthDetails {
+    pub status: HealthStatus,
+    pub ready: bool,
+    pub version: String,
+    pub uptime_seconds: u64,
+    pub window_seconds: u64,
+    pub dependencies: Vec<DependencyDetail>,
+}
+
+impl HealthDetails {
+    /// Report of a service without a health checker: no dependencies checked.
+    pub fn unchecked() -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            ready: true,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_seconds: 0,
+            window_seconds: 0,
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Soft dependencies can only degrade the overall status, never make it unhealthy.
+    fn aggregate_status(dependencies: &[DependencyDetail]) -> HealthStatus {
+        let hard_unhealthy = dependencies
+            .iter()
+            .any(|d| d.kind == DependencyKind::Hard && d.health.status.is_unhealthy());
+        let any_impaired = dependencies.iter().any(|d| !d.health.status.is_healthy());
+
+        if hard_unhealthy {
+            HealthStatus::Unhealthy
+        } else if any_impaired {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
 /// Health checker for all dependencies.
 pub struct HealthChecker {
     pool: PgPool,
-    redis_client: redis::Client,
+    redis: Arc<RedisPool>,
     kafka_client: Option<Arc<rskafka::client::Client>>,
-    start_time: std::time::Instant,
+    start_time: Instant,
+    policy: ReadinessPolicy,
+    window: Duration,
+    history: Mutex<HashMap<String, RollingWindow>>,
 }
 
 impl HealthChecker {
     pub fn new(
         pool: PgPool,
-        redis_client: redis::Client,
+        redis: Arc<RedisPool>,
         kafka_client: Option<Arc<rskafka::client::Client>>,
     ) -> Self {
         Self {
             pool,
-            redis_client,
+            redis,
             kafka_client,
-            start_time: std::time::Instant::now(),
+            start_time: Instant::now(),
+            policy: ReadinessPolicy::default(),
+            window: Duration::from_secs(300),
+            history: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Sets the readiness policy.
+    pub fn with_policy(mut self, policy: ReadinessPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets the rolling window used for latency and error rate statistics.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Returns the readiness policy.
+    pub fn policy(&self) -> &ReadinessPolicy {
+        &self.policy
+    }
+
     /// Performs a full health check of all dependencies.
     pub async fn check_all(&self) -> AggregatedHealth {
         let mut dependencies = Vec::new();
@@ -135,11 +346,63 @@ impl HealthChecker {
         )
     }
 
+    /// Performs a full health check and reports rolling statistics and readiness.
+    pub async fn details(&self) -> HealthDetails {
+        let checks = vec![
+            self.check_database().await,
+            self.check_redis().await,
+            self.check_kafka().await,
+        ];
+
+        let dependencies: Vec<DependencyDetail> = checks
+            .into_iter()
+            .map(|health| DependencyDetail {
+                kind: self.policy.kind_of(&health.name),
+                stats: self.stats_for(&health.name),
+                health,
+            })
+            .collect();
+
+        HealthDetails {
+            status: HealthDetails::aggregate_status(&dependencies),
+            ready: self.policy.is_ready(&dependencies),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_seconds: self.start_time.elapsed().as_secs(),
+            window_seconds: self.window.as_secs(),
+            dependencies,
+        }
+    }
+
+    /// Returns rolling statistics for a dependency.
+    pub fn stats_for(&self, name: &str) -> DependencyStats {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        match history.get_mut(name) {
+            Some(window) => {
+                window.evict(Instant::now());
+                window.stats()
+            }
+            None => DependencyStats::default(),
+        }
+    }
+
+    fn record(&self, health: &DependencyHealth) {
+        let sample = Sample {
+            at: Instant::now(),
+            latency_ms: health.latency_ms,
+            success: !health.status.is_unhealthy(),
+        };
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        history
+            .entry(health.name.clone())
+            .or_insert_with(|| RollingWindow::new(self.window))
+            .record(sample);
+    }
+
     /// Checks database connectivity.
     pub async fn check_database(&self) -> DependencyHealth {
-        let start = std::time::Instant::now();
+        let start = Instant::now();
         
-        match tokio::time::timeout(
+        let health = match tokio::time::timeout(
             Duration::from_secs(5),
             sqlx::query("SELECT 1").fetch_one(&self.pool)
         ).await {
@@ -158,45 +421,51 @@ impl HealthChecker {
             }
             Ok(Err(e)) => DependencyHealth::unhealthy("database", format!("Query failed: {}", e)),
             Err(_) => DependencyHealth::unhealthy("database", "Connection timeout"),
-        }
+        };
+
+        self.record(&health);
+        health
     }
 
     /// Checks Redis connectivity.
     pub async fn check_redis(&self) -> DependencyHealth {
-        let start = std::time::Instant::now();
+        let start = Instant::now();
         
-        match self.redis_client.get_multiplexed_async_connection().await {
-            Ok(mut conn) => {
-                match tokio::time::timeout(
-                    Duration::from_secs(5),
-                    redis::cmd("PING").query_async::<_, ()>(&mut conn)
-                ).await {
-                    Ok(Ok(_)) => {
-                        let latency = start.elapsed().as_secs_f64() * 1000.0;
-                        if latency > 50.0 {
-                            DependencyHealth {
-                                name: "redis".to_string(),
-                                status: HealthStatus::Degraded,
-                                latency_ms: Some(latency),
-                                message: Some("High latency detected".to_string()),
-                            }
-                        } else {
-                            DependencyHealth::healthy("redis", latency)
+        let health = if !self.redis.is_available() {
+            DependencyHealth::unhealthy("redis", "Circuit open after a high rate of connection failures")
+        } else {
+            match self.redis.ping().await {
+                Ok(()) => {
+                    let latency = start.elapsed().as_secs_f64() * 1000.0;
+                    if latency > 50.0 {
+                        DependencyHealth {
+                            name: "redis".to_string(),
+                            status: HealthStatus::Degraded,
+                            latency_ms: Some(latency),
+                            message: Some("High latency detected".to_string()),
                         }
+                    } else {
+                        DependencyHealth::healthy("redis", latency)
                     }
-                    Ok(Err(e)) => DependencyHealth::unhealthy("redis", format!("PING failed: {}", e)),
-                    Err(_) => DependencyHealth::unhealthy("redis", "PING timeout"),
                 }
+                Err(e) => DependencyHealth::unhealthy("redis", format!("PING failed: {}", e)),
             }
-            Err(e) => DependencyHealth::unhealthy("redis", format!("Connection failed: {}", e)),
-        }
+        };
+
+        self.record(&health);
+        health
     }
 
-    /// Checks Kafka connectivity.
+    /// Checks Kafka connectivity by fetching cluster metadata.
     pub async fn check_kafka(&self) -> DependencyHealth {
-        match &self.kafka_client {
-            Some(_client) => {
-                DependencyHealth::healthy("kafka", 0.0)
+        let health = match &self.kafka_client {
+            Some(client) => {
+                let start = Instant::now();
+                match tokio::time::timeout(Duration::from_secs(5), client.list_topics()).await {
+                    Ok(Ok(_)) => DependencyHealth::healthy("kafka", start.elapsed().as_secs_f64() * 1000.0),
+                    Ok(Err(e)) => DependencyHealth::unhealthy("kafka", format!("Metadata request failed: {}", e)),
+                    Err(_) => DependencyHealth::unhealthy("kafka", "Metadata request timeout"),
+                }
             }
             None => DependencyHealth {
                 name: "kafka".to_string(),
@@ -204,7 +473,10 @@ impl HealthChecker {
                 latency_ms: None,
                 message: Some("Kafka client not connected".to_string()),
             },
-        }
+        };
+
+        self.record(&health);
+        health
     }
 
     /// Liveness check - returns true if the service is alive.
@@ -212,12 +484,24 @@ impl HealthChecker {
         true
     }
 
-    /// Readiness check - returns true if the service is ready to accept traffic.
+    /// Readiness check - evaluates the hard dependencies against the readiness policy.
     pub async fn is_ready(&self) -> bool {
-        let db_health = self.check_database().await;
-        let redis_health = self.check_redis().await;
+        let mut dependencies = Vec::new();
+        for name in &self.policy.hard_dependencies {
+            let health = match name.as_str() {
+                "database" => self.check_database().await,
+                "redis" => self.check_redis().await,
+                "kafka" => self.check_kafka().await,
+                _ => continue,
+            };
+            dependencies.push(DependencyDetail {
+                kind: DependencyKind::Hard,
+                stats: self.stats_for(&health.name),
+                health,
+            });
+        }
 
-        db_health.status.is_healthy() && (redis_health.status.is_healthy() || redis_health.status.is_degraded())
+        self.policy.is_ready(&dependencies)
     }
 
     /// Returns uptime in seconds.
@@ -283,4 +567,81 @@ mod tests {
         let health = AggregatedHealth::new("1.0.0".to_string(), 100, one_unhealthy);
         assert_eq!(health.status, HealthStatus::Unhealthy);
     }
+
+    fn detail(name: &str, status: HealthStatus, kind: DependencyKind, stats: DependencyStats) -> DependencyDetail {
+        DependencyDetail {
+            health: DependencyHealth {
+                name: name.to_string(),
+                status,
+                latency_ms: None,
+                message: None,
+            },
+            kind,
+            stats,
+        }
+    }
+
+    #[test]
+    fn test_rolling_window_stats() {
+        let mut window = RollingWindow::new(Duration::from_secs(60));
+        let now = Instant::now();
+        window.record(Sample { at: now, latency_ms: Some(10.0), success: true });
+        window.record(Sample { at: now, latency_ms: Some(30.0), success: true });
+        window.record(Sample { at: now, latency_ms: None, success: false });
+
+        let stats = window.stats();
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.errors, 1);
+        assert!((stats.error_rate - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(stats.avg_latency_ms, Some(20.0));
+        assert_eq!(stats.max_latency_ms, Some(30.0));
+    }
+
+    #[test]
+    fn test_rolling_window_eviction() {
+        let mut window = RollingWindow::new(Duration::from_secs(60));
+        let now = Instant::now();
+        window.record(Sample { at: now, latency_ms: Some(10.0), success: false });
+        window.evict(now + Duration::from_secs(61));
+
+        assert_eq!(window.stats(), DependencyStats::default());
+    }
+
+    #[test]
+    fn test_readiness_policy_ignores_soft_dependencies() {
+        let policy = ReadinessPolicy::default();
+        assert_eq!(policy.kind_of("database"), DependencyKind::Hard);
+        assert_eq!(policy.kind_of("redis"), DependencyKind::Soft);
+        assert_eq!(policy.kind_of("kafka"), DependencyKind::Soft);
+
+        let deps = vec![
+            detail("database", HealthStatus::Healthy, DependencyKind::Hard, DependencyStats::default()),
+            detail("kafka", HealthStatus::Unhealthy, DependencyKind::Soft, DependencyStats::default()),
+        ];
+        assert!(policy.is_ready(&deps));
+        assert_eq!(HealthDetails::aggregate_status(&deps), HealthStatus::Degraded);
+
+        let deps = vec![detail("database", HealthStatus::Unhealthy, DependencyKind::Hard, DependencyStats::default())];
+        assert!(!policy.is_ready(&deps));
+        assert_eq!(HealthDetails::aggregate_status(&deps), HealthStatus::Unhealthy);
+    }
+
+    #[test]
+    fn test_readiness_policy_degraded_and_error_rate() {
+        let relaxed = ReadinessPolicy {
+            allow_degraded_hard: true,
+            ..ReadinessPolicy::default()
+        };
+        let degraded = vec![detail("database", HealthStatus::Degraded, DependencyKind::Hard, DependencyStats::default())];
+        assert!(!ReadinessPolicy::default().is_ready(&degraded));
+        assert!(relaxed.is_ready(&degraded));
+
+        let flapping = DependencyStats { samples: 10, errors: 6, error_rate: 0.6, ..DependencyStats::default() };
+        let deps = vec![detail("database", HealthStatus::Healthy, DependencyKind::Hard, flapping.clone())];
+        assert!(!ReadinessPolicy::default().is_ready(&deps));
+
+        let too_few = DependencyStats { samples: 2, ..flapping };
+        let deps = vec![detail("database", HealthStatus::Healthy, DependencyKind::Hard, too_few)];
+        assert!(ReadinessPolicy::default().is_ready(&deps));
+    }
 }
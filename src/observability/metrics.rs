@@ -1,3 +1,4 @@
+use crate::core::circuit_breaker::CircuitState;
 use metrics::{counter, gauge, histogram, describe_counter, describe_gauge, describe_histogram, Unit};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use std::sync::OnceLock;
@@ -49,6 +50,10 @@ impl Metrics {
         histogram!("settlement_balance_query_duration_ms", "cache_hit" => cache_hit.to_string()).record(duration_ms);
     }
 
+    pub fn record_rtgs_settlement(&self, currency: &str) {
+        counter!("settlement_rtgs_settlements_total", "currency" => currency.to_string()).increment(1);
+    }
+
     pub fn record_batch_created(&self, currency: &str) {
         counter!("settlement_batches_created_total", "currency" => currency.to_string()).increment(1);
     }
@@ -82,6 +87,18 @@ impl Metrics {
         histogram!("settlement_netting_calculation_duration_ms").record(duration_ms);
     }
 
+    pub fn record_netting_batch(&self, currency: &str, transaction_count: u64) {
+        counter!("settlement_netting_batches_total", "currency" => currency.to_string()).increment(1);
+        counter!("settlement_netting_transactions_total", "currency" => currency.to_string()).increment(transaction_count);
+    }
+
+    /// Exports a day's persisted netting totals for a currency.
+    pub fn set_netting_daily(&self, currency: &str, date: &str, gross_volume: f64, net_volume: f64, efficiency: f64) {
+        gauge!("settlement_netting_daily_gross_volume", "currency" => currency.to_string(), "date" => date.to_string()).set(gross_volume);
+        gauge!("settlement_netting_daily_net_volume", "currency" => currency.to_string(), "date" => date.to_string()).set(net_volume);
+        gauge!("settlement_netting_daily_efficiency_percent", "currency" => currency.to_string(), "date" => date.to_string()).set(efficiency);
+    }
+
     pub fn set_active_batches(&self, count: i64) {
         gauge!("settlement_active_batches").set(count as f64);
     }
@@ -100,6 +117,30 @@ impl Metrics {
         histogram!("db_query_duration_ms", "type" => query_type.to_string()).record(duration_ms);
     }
 
+    /// Records a run of a named query, and counts it if it was slow.
+    pub fn record_named_query(&self, query: &str, duration_ms: f64, success: bool, slow: bool) {
+        counter!("settlement_db_queries_total", "query" => query.to_string(), "success" => success.to_string()).increment(1);
+        histogram!("settlement_db_query_duration_ms", "query" => query.to_string()).record(duration_ms);
+        if slow {
+            counter!("settlement_db_slow_queries_total", "query" => query.to_string()).increment(1);
+        }
+    }
+
+    /// Counts a request answered with a timeout, by route and by what timed out: the
+    /// request as a whole or a database statement.
+    pub fn record_request_timeout(&self, route: &str, kind: &str) {
+        counter!("settlement_request_timeouts_total", "route" => route.to_string(), "kind" => kind.to_string()).increment(1);
+    }
+
+    pub fn set_admission_in_flight(&self, in_flight: usize) {
+        gauge!("settlement_admission_in_flight").set(in_flight as f64);
+    }
+
+    /// Counts a write turned away by admission control, by route and reason.
+    pub fn record_admission_rejected(&self, route: &str, reason: &str) {
+        counter!("settlement_admission_rejected_total", "route" => route.to_string(), "reason" => reason.to_string()).increment(1);
+    }
+
     pub fn record_redis_operation(&self, operation: &str, duration_ms: f64, success: bool) {
         counter!("redis_operations_total", "operation" => operation.to_string(), "success" => success.to_string()).increment(1);
         histogram!("redis_operation_duration_ms", "operation" => operation.to_string()).record(duration_ms);
@@ -108,6 +149,99 @@ impl Metrics {
     pub fn record_kafka_message(&self, topic: &str, success: bool) {
         counter!("kafka_messages_total", "topic" => topic.to_string(), "success" => success.to_string()).increment(1);
     }
+
+    pub fn record_alert_triggered(&self, rule_type: &str) {
+        counter!("settlement_alerts_triggered_total", "rule_type" => rule_type.to_string()).increment(1);
+    }
+
+    pub fn record_alert_suppressed(&self, rule_type: &str) {
+        counter!("settlement_alerts_suppressed_total", "rule_type" => rule_type.to_string()).increment(1);
+    }
+
+    pub fn record_alert_delivery(&self, sink: &str, success: bool) {
+        counter!("settlement_alert_deliveries_total", "sink" => sink.to_string(), "success" => success.to_string()).increment(1);
+    }
+
+    pub fn record_idempotency_expired(&self, count: u64, archived: bool) {
+        counter!("settlement_idempotency_records_expired_total", "archived" => archived.to_string()).increment(count);
+    }
+
+    pub fn set_idempotency_records(&self, count: i64) {
+        gauge!("settlement_idempotency_records_remaining").set(count as f64);
+    }
+
+    pub fn record_reconciliation_run(&self, accounts_checked: u64, new_breaks: u64, open_breaks: i64) {
+        counter!("settlement_reconciliation_accounts_checked_total").increment(accounts_checked);
+        counter!("settlement_balance_breaks_detected_total").increment(new_breaks);
+        gauge!("settlement_balance_breaks_open").set(open_breaks as f64);
+    }
+
+    pub fn record_write_combined(&self, requests: usize, posted: usize) {
+        histogram!("settlement_write_combined_batch_size").record(requests as f64);
+        counter!("settlement_write_combined_transactions_total").increment(posted as u64);
+    }
+
+    pub fn record_expired(&self, kind: &str, transaction_type: &str, count: u64) {
+        counter!("settlement_expired_total", "kind" => kind.to_string(), "transaction_type" => transaction_type.to_string()).increment(count);
+    }
+
+    pub fn record_balance_floor_breach(&self, currency: &str) {
+        counter!("settlement_balance_floor_breaches_total", "currency" => currency.to_string()).increment(1);
+    }
+
+    /// Records a transaction that would have taken a participant past its net debit cap,
+    /// by what became of it: `rejected` or `queued`.
+    pub fn record_net_debit_cap_breach(&self, currency: &str, outcome: &str) {
+        counter!("settlement_net_debit_cap_breaches_total", "currency" => currency.to_string(), "outcome" => outcome.to_string()).increment(1);
+    }
+
+    pub fn record_net_debit_cap_warning(&self, currency: &str, threshold_percent: u32) {
+        counter!("settlement_net_debit_cap_warnings_total", "currency" => currency.to_string(), "threshold" => threshold_percent.to_string()).increment(1);
+    }
+
+    pub fn record_amount_limit_breach(&self, currency: &str, kind: &str) {
+        counter!("settlement_amount_limit_breaches_total", "currency" => currency.to_string(), "kind" => kind.to_string()).increment(1);
+    }
+
+    pub fn record_possible_duplicate(&self, currency: &str, action: &str) {
+        counter!("settlement_possible_duplicates_total", "currency" => currency.to_string(), "action" => action.to_string()).increment(1);
+    }
+
+    pub fn record_activity_projected(&self, events: u64) {
+        counter!("settlement_activity_events_projected_total").increment(events);
+    }
+
+    pub fn record_balance_projection(&self, entries: u64, accounts_behind: i64, entries_behind: i64, lag_secs: f64) {
+        counter!("settlement_balance_projection_entries_total").increment(entries);
+        gauge!("settlement_balance_projection_accounts_behind").set(accounts_behind as f64);
+        gauge!("settlement_balance_projection_entries_behind").set(entries_behind as f64);
+        gauge!("settlement_balance_projection_lag_seconds").set(lag_secs);
+    }
+
+    pub fn record_cdc(&self, changes: u64, lag_bytes: i64, retained_bytes: i64, lag_secs: f64) {
+        counter!("settlement_cdc_changes_published_total").increment(changes);
+        gauge!("settlement_cdc_lag_bytes").set(lag_bytes as f64);
+        gauge!("settlement_cdc_retained_wal_bytes").set(retained_bytes as f64);
+        gauge!("settlement_cdc_lag_seconds").set(lag_secs);
+    }
+
+    /// Records whether this instance leads; `changed` counts a leadership transition.
+    pub fn record_leadership(&self, leader: bool, changed: bool) {
+        gauge!("settlement_leader").set(if leader { 1.0 } else { 0.0 });
+        if changed {
+            counter!("settlement_leadership_transitions_total").increment(1);
+        }
+    }
+
+    pub fn record_circuit_transition(&self, breaker: &str, state: CircuitState) {
+        counter!("settlement_circuit_breaker_transitions_total", "breaker" => breaker.to_string(), "state" => state.as_str()).increment(1);
+        let value = match state {
+            CircuitState::Closed => 0.0,
+            CircuitState::HalfOpen => 1.0,
+            CircuitState::Open => 2.0,
+        };
+        gauge!("settlement_circuit_breaker_state", "breaker" => breaker.to_string()).set(value);
+    }
 }
 
 /// Timer for measuring operation latency.
@@ -156,9 +290,19 @@ fn describe_metrics() {
     describe_counter!("settlement_transactions_settled_total", Unit::Count, "Total number of transactions settled");
     describe_counter!("settlement_transactions_failed_total", Unit::Count, "Total number of failed transactions");
     describe_counter!("settlement_transactions_reversed_total", Unit::Count, "Total number of reversed transactions");
+    describe_counter!("settlement_balance_floor_breaches_total", Unit::Count, "Total balance incidents that froze an account after a balance fell below its floor");
+    describe_counter!("settlement_net_debit_cap_breaches_total", Unit::Count, "Total transactions rejected or queued to a later batch to keep a participant within its net debit cap");
+    describe_counter!("settlement_net_debit_cap_warnings_total", Unit::Count, "Total net debit cap warning thresholds crossed");
+    describe_counter!("settlement_amount_limit_breaches_total", Unit::Count, "Total transactions rejected for exceeding an amount maximum or decimal scale cap");
+    describe_counter!("settlement_possible_duplicates_total", Unit::Count, "Total payments matching a recent transaction submitted under another idempotency key");
+    describe_counter!("settlement_expired_total", Unit::Count, "Total pending transactions and queued submissions expired past their TTL");
+    
+    describe_counter!("settlement_rtgs_settlements_total", Unit::Count, "Total number of transactions settled gross through the RTGS lane");
     
     describe_histogram!("settlement_ledger_write_duration_ms", Unit::Milliseconds, "Ledger write latency in milliseconds");
     describe_histogram!("settlement_balance_query_duration_ms", Unit::Milliseconds, "Balance query latency in milliseconds");
+    describe_histogram!("settlement_write_combined_batch_size", Unit::Count, "Requests per combined ledger write");
+    describe_counter!("settlement_write_combined_transactions_total", Unit::Count, "Total transactions posted through combined ledger writes");
     
     describe_counter!("settlement_batches_created_total", Unit::Count, "Total number of batches created");
     describe_counter!("settlement_batches_processed_total", Unit::Count, "Total number of batches processed");
@@ -170,6 +314,11 @@ fn describe_metrics() {
     describe_histogram!("settlement_netting_position_count", Unit::Count, "Number of positions in netting calculation");
     describe_histogram!("settlement_netting_efficiency_ratio", Unit::Count, "Netting efficiency ratio (1 - net/gross)");
     describe_histogram!("settlement_netting_calculation_duration_ms", Unit::Milliseconds, "Netting calculation latency in milliseconds");
+    describe_counter!("settlement_netting_batches_total", Unit::Count, "Total number of batches netted per currency");
+    describe_counter!("settlement_netting_transactions_total", Unit::Count, "Total number of transactions netted per currency");
+    describe_gauge!("settlement_netting_daily_gross_volume", Unit::Count, "Gross volume netted per currency and day");
+    describe_gauge!("settlement_netting_daily_net_volume", Unit::Count, "Net settlement volume per currency and day");
+    describe_gauge!("settlement_netting_daily_efficiency_percent", Unit::Percent, "Share of gross volume removed by netting per currency and day");
     
     describe_gauge!("settlement_active_batches", Unit::Count, "Number of active batches");
     describe_gauge!("settlement_pending_transactions", Unit::Count, "Number of pending transactions");
@@ -179,11 +328,44 @@ fn describe_metrics() {
     
     describe_counter!("db_queries_total", Unit::Count, "Total database queries");
     describe_histogram!("db_query_duration_ms", Unit::Milliseconds, "Database query latency in milliseconds");
+    describe_counter!("settlement_db_queries_total", Unit::Count, "Total runs of named database queries");
+    describe_histogram!("settlement_db_query_duration_ms", Unit::Milliseconds, "Latency of named database queries in milliseconds");
+    describe_counter!("settlement_db_slow_queries_total", Unit::Count, "Total runs of named database queries at or above the slow-query threshold");
+    describe_counter!("settlement_request_timeouts_total", Unit::Count, "Total requests answered with a timeout, by route and kind");
+    describe_gauge!("settlement_admission_in_flight", Unit::Count, "Writes admitted and in progress");
+    describe_counter!("settlement_admission_rejected_total", Unit::Count, "Total writes turned away by admission control, by route and reason");
     
     describe_counter!("redis_operations_total", Unit::Count, "Total Redis operations");
     describe_histogram!("redis_operation_duration_ms", Unit::Milliseconds, "Redis operation latency in milliseconds");
     
     describe_counter!("kafka_messages_total", Unit::Count, "Total Kafka messages");
+
+    describe_counter!("settlement_alerts_triggered_total", Unit::Count, "Total number of alerts triggered");
+    describe_counter!("settlement_alerts_suppressed_total", Unit::Count, "Total number of alerts dropped by a suppression window");
+    describe_counter!("settlement_alert_deliveries_total", Unit::Count, "Total alert delivery attempts per sink");
+
+    describe_counter!("settlement_idempotency_records_expired_total", Unit::Count, "Total expired idempotency records removed by the cleanup job");
+    describe_gauge!("settlement_idempotency_records_remaining", Unit::Count, "Idempotency records stored after the last cleanup run");
+
+    describe_counter!("settlement_reconciliation_accounts_checked_total", Unit::Count, "Total account balances checked against the ledger");
+    describe_counter!("settlement_balance_breaks_detected_total", Unit::Count, "Total balance breaks newly detected by reconciliation");
+    describe_gauge!("settlement_balance_breaks_open", Unit::Count, "Balance breaks open after the last reconciliation run");
+
+    describe_counter!("settlement_activity_events_projected_total", Unit::Count, "Total transaction events applied to the account activity projection");
+    describe_counter!("settlement_balance_projection_entries_total", Unit::Count, "Total ledger entries folded into the balance projection");
+    describe_gauge!("settlement_balance_projection_accounts_behind", Unit::Count, "Account balances with ledger entries not yet projected");
+    describe_gauge!("settlement_balance_projection_entries_behind", Unit::Count, "Ledger entries not yet folded into the balance projection");
+    describe_gauge!("settlement_balance_projection_lag_seconds", Unit::Seconds, "Age of the oldest ledger entry not yet folded into the balance projection");
+
+    describe_counter!("settlement_cdc_changes_published_total", Unit::Count, "Total row changes captured from the WAL and published to Kafka");
+    describe_gauge!("settlement_cdc_lag_bytes", Unit::Bytes, "WAL written since the last change the CDC publisher acknowledged");
+    describe_gauge!("settlement_cdc_retained_wal_bytes", Unit::Bytes, "WAL the server retains for the CDC replication slot");
+    describe_gauge!("settlement_cdc_lag_seconds", Unit::Seconds, "Age of the oldest committed change the CDC publisher failed to publish");
+    describe_gauge!("settlement_leader", Unit::Count, "1 while this instance is the elected leader running leader-only jobs, 0 otherwise");
+    describe_counter!("settlement_leadership_transitions_total", Unit::Count, "Times this instance gained or lost leadership");
+
+    describe_counter!("settlement_circuit_breaker_transitions_total", Unit::Count, "Circuit breaker state changes per breaker and new state");
+    describe_gauge!("settlement_circuit_breaker_state", Unit::Count, "Circuit breaker state (0 closed, 1 half-open, 2 open)");
 }
 
 /// Returns the global metrics instance.
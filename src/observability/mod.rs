@@ -1,7 +1,12 @@
 pub mod logging;
 pub mod metrics;
 pub mod health;
+pub mod query_timing;
 
 pub use logging::{init_logging, LogConfig, LogFormat, RequestSpan, mask_sensitive, mask_uuid, mask_amount};
 pub use metrics::{init_metrics, get_metrics, Metrics, LatencyTimer, METRICS};
-pub use health::{HealthChecker, HealthStatus, DependencyHealth, AggregatedHealth};
+pub use query_timing::{recent_query_latency, set_slow_query_threshold, slow_query_threshold, timed, DEFAULT_SLOW_QUERY_THRESHOLD};
+pub use health::{
+    HealthChecker, HealthStatus, DependencyHealth, AggregatedHealth, DependencyDetail,
+    DependencyKind, DependencyStats, HealthDetails, ReadinessPolicy,
+};
@@ -0,0 +1,108 @@
+//! Timing of named database queries.
+//!
+//! Repositories wrap the queries on the settlement path in `timed` under a stable name
+//! such as `transactions.insert`. Each run is recorded in `settlement_db_query_duration_ms`
+//! by name, which the Prometheus exporter renders as a summary with p50, p95 and p99
+//! quantiles, and runs taking at least the slow-query threshold are logged and counted.
+//! A moving average of recent runs is kept for admission control.
+
+use super::metrics::get_metrics;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Queries taking at least this long are logged as slow, unless configured.
+pub const DEFAULT_SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(250);
+
+static SLOW_QUERY_THRESHOLD_MS: AtomicU64 = AtomicU64::new(DEFAULT_SLOW_QUERY_THRESHOLD.as_millis() as u64);
+
+/// Sets how long a query may take before it is logged as slow; zero logs every query.
+pub fn set_slow_query_threshold(threshold: Duration) {
+    SLOW_QUERY_THRESHOLD_MS.store(threshold.as_millis() as u64, Ordering::Relaxed);
+}
+
+pub fn slow_query_threshold() -> Duration {
+    Duration::from_millis(SLOW_QUERY_THRESHOLD_MS.load(Ordering::Relaxed))
+}
+
+/// Weight of each run in the moving average of query latency, out of 16.
+const LATENCY_SAMPLE_WEIGHT: u64 = 2;
+
+/// How long the moving average stands without new runs before it is disregarded.
+pub const LATENCY_SAMPLE_TTL: Duration = Duration::from_secs(5);
+
+static AVERAGE_LATENCY_US: AtomicU64 = AtomicU64::new(0);
+static LAST_SAMPLE_MS: AtomicU64 = AtomicU64::new(0);
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+fn since_epoch() -> Duration {
+    EPOCH.get_or_init(Instant::now).elapsed()
+}
+
+fn record_latency(elapsed: Duration) {
+    let sample = elapsed.as_micros() as u64;
+    let _ = AVERAGE_LATENCY_US.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |average| {
+        Some(if average == 0 {
+            sample
+        } else {
+            (average * (16 - LATENCY_SAMPLE_WEIGHT) + sample * LATENCY_SAMPLE_WEIGHT) / 16
+        })
+    });
+    // Offset by one so that zero means no run was recorded
+    LAST_SAMPLE_MS.store(since_epoch().as_millis() as u64 + 1, Ordering::Relaxed);
+}
+
+/// Moving average of named query latency, or None if no query ran in the last
+/// `LATENCY_SAMPLE_TTL`.
+pub fn recent_query_latency() -> Option<Duration> {
+    let last = LAST_SAMPLE_MS.load(Ordering::Relaxed);
+    if last == 0 || since_epoch().saturating_sub(Duration::from_millis(last - 1)) > LATENCY_SAMPLE_TTL {
+        return None;
+    }
+    Some(Duration::from_micros(AVERAGE_LATENCY_US.load(Ordering::Relaxed)))
+}
+
+/// Runs `query`, recording how long it took under `name`.
+pub async fn timed<T, E, F>(name: &'static str, query: F) -> std::result::Result<T, E>
+where
+    F: Future<Output = std::result::Result<T, E>>,
+{
+    let start = Instant::now();
+    let result = query.await;
+    let elapsed = start.elapsed();
+    record_latency(elapsed);
+
+    let slow = elapsed >= slow_query_threshold();
+    if slow {
+        tracing::warn!(
+            query = name,
+            duration_ms = elapsed.as_millis() as u64,
+            success = result.is_ok(),
+            "Slow query"
+        );
+    }
+    get_metrics().record_named_query(name, elapsed.as_secs_f64() * 1000.0, result.is_ok(), slow);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timed_passes_result_through() {
+        let ok: std::result::Result<u32, String> = timed("test.ok", async { Ok(7) }).await;
+        assert_eq!(ok, Ok(7));
+        let err: std::result::Result<u32, String> = timed("test.err", async { Err("failed".to_string()) }).await;
+        assert_eq!(err, Err("failed".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_recent_query_latency_follows_runs() {
+        let _: std::result::Result<(), ()> = timed("test.latency", async { Ok(()) }).await;
+        let latency = recent_query_latency().expect("a query just ran");
+        assert!(latency < LATENCY_SAMPLE_TTL);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::error::{AppError, Result};
+use crate::models::AccountIdentifier;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for accounts' secondary identifiers.
+pub struct AccountIdentifierRepository {
+    pool: PgPool,
+}
+
+impl AccountIdentifierRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_insert_error(error: sqlx::Error, identifier: &AccountIdentifier) -> AppError {
+        match &error {
+            sqlx::Error::Database(db) if db.constraint() == Some("uq_account_identifiers_identifier") => {
+                AppError::Validation(format!(
+                    "identifier: '{}' is already assigned to an account",
+                    identifier.identifier
+                ))
+            }
+            _ => AppError::Database(error),
+        }
+    }
+
+    /// Records an identifier. Returns a validation error if any account already has it.
+    pub async fn create(&self, identifier: &AccountIdentifier) -> Result<AccountIdentifier> {
+        let row = sqlx::query_as::<_, AccountIdentifier>(
+            r#"
+            INSERT INTO account_identifiers (id, account_id, identifier_type, identifier, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, account_id, identifier_type, identifier, created_at
+            "#,
+        )
+        .bind(identifier.id)
+        .bind(identifier.account_id)
+        .bind(identifier.identifier_type)
+        .bind(&identifier.identifier)
+        .bind(identifier.created_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Self::map_insert_error(e, identifier))?;
+
+        Ok(row)
+    }
+
+    /// Lists an account's identifiers, oldest first.
+    pub async fn find_by_account(&self, account_id: Uuid) -> Result<Vec<AccountIdentifier>> {
+        let rows = sqlx::query_as::<_, AccountIdentifier>(
+            r#"
+            SELECT id, account_id, identifier_type, identifier, created_at
+            FROM account_identifiers
+            WHERE account_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds the identifier with the normalized value.
+    pub async fn find_by_identifier(&self, identifier: &str) -> Result<Option<AccountIdentifier>> {
+        let row = sqlx::query_as::<_, AccountIdentifier>(
+            r#"
+            SELECT id, account_id, identifier_type, identifier, created_at
+            FROM account_identifiers
+            WHERE identifier = $1
+            "#,
+        )
+        .bind(identifier)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Deletes one of an account's identifiers. Returns false if the account has no
+    /// identifier with the ID.
+    pub async fn delete(&self, account_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM account_identifiers WHERE account_id = $1 AND id = $2")
+            .bind(account_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -1,5 +1,10 @@
 use crate::error::{AppError, Result};
-use crate::models::{Account, AccountStatus, AccountType};
+use crate::models::{
+    Account, AccountAnonymization, AccountStatus, AccountStatusChange, AccountType,
+    ANONYMIZED_ACCOUNT_NAME,
+};
+use crate::observability::timed;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -19,7 +24,7 @@ impl AccountRepository {
             r#"
             INSERT INTO accounts (id, external_id, name, type, status, currency, metadata, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            RETURNING id, external_id, name, type, status, currency, metadata, created_at, updated_at
+            RETURNING id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
             "#,
         )
         .bind(account.id)
@@ -40,15 +45,18 @@ impl AccountRepository {
 
     /// Finds an account by its UUID.
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Account>> {
-        let row = sqlx::query_as::<_, Account>(
-            r#"
-            SELECT id, external_id, name, type, status, currency, metadata, created_at, updated_at
-            FROM accounts
-            WHERE id = $1
-            "#,
+        let row = timed(
+            "accounts.find_by_id",
+            sqlx::query_as::<_, Account>(
+                r#"
+                SELECT id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
+                FROM accounts
+                WHERE id = $1
+                "#,
+            )
+            .bind(id)
+            .fetch_optional(&self.pool),
         )
-        .bind(id)
-        .fetch_optional(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
@@ -59,7 +67,7 @@ impl AccountRepository {
     pub async fn find_by_external_id(&self, external_id: &str) -> Result<Option<Account>> {
         let row = sqlx::query_as::<_, Account>(
             r#"
-            SELECT id, external_id, name, type, status, currency, metadata, created_at, updated_at
+            SELECT id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
             FROM accounts
             WHERE external_id = $1
             "#,
@@ -83,7 +91,7 @@ impl AccountRepository {
     ) -> Result<Vec<Account>> {
         let rows = sqlx::query_as::<_, Account>(
             r#"
-            SELECT id, external_id, name, type, status, currency, metadata, created_at, updated_at
+            SELECT id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
             FROM accounts
             WHERE ($1::account_type IS NULL OR type = $1)
               AND ($2::account_status IS NULL OR status = $2)
@@ -111,7 +119,7 @@ impl AccountRepository {
             UPDATE accounts
             SET status = $2, updated_at = NOW()
             WHERE id = $1
-            RETURNING id, external_id, name, type, status, currency, metadata, created_at, updated_at
+            RETURNING id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
             "#,
         )
         .bind(id)
@@ -123,6 +131,88 @@ impl AccountRepository {
         Ok(row)
     }
 
+    /// Applies a status transition and records it in the status history atomically.
+    /// Returns `None` if the account does not exist or is no longer in `from_status`.
+    pub async fn transition_status(&self, change: &AccountStatusChange) -> Result<Option<Account>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let row = sqlx::query_as::<_, Account>(
+            r#"
+            UPDATE accounts
+            SET status = $3, updated_at = NOW()
+            WHERE id = $1 AND status = $2
+            RETURNING id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
+            "#,
+        )
+        .bind(change.account_id)
+        .bind(change.from_status)
+        .bind(change.to_status)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        let Some(account) = row else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_status_history (id, account_id, from_status, to_status, reason_code, note, changed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(change.id)
+        .bind(change.account_id)
+        .bind(change.from_status)
+        .bind(change.to_status)
+        .bind(change.reason_code)
+        .bind(&change.note)
+        .bind(change.changed_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(Some(account))
+    }
+
+    /// Lists an account's status changes, oldest first.
+    pub async fn status_history(&self, account_id: Uuid) -> Result<Vec<AccountStatusChange>> {
+        let rows = sqlx::query_as::<_, AccountStatusChange>(
+            r#"
+            SELECT id, account_id, from_status, to_status, reason_code, note, changed_at
+            FROM account_status_history
+            WHERE account_id = $1
+            ORDER BY changed_at
+            "#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds the change that put the account in its current status.
+    pub async fn latest_status_change(&self, account_id: Uuid) -> Result<Option<AccountStatusChange>> {
+        let row = sqlx::query_as::<_, AccountStatusChange>(
+            r#"
+            SELECT id, account_id, from_status, to_status, reason_code, note, changed_at
+            FROM account_status_history
+            WHERE account_id = $1
+            ORDER BY changed_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(account_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
     /// Updates an account's metadata.
     pub async fn update_metadata(
         &self,
@@ -134,7 +224,7 @@ impl AccountRepository {
             UPDATE accounts
             SET metadata = $2, updated_at = NOW()
             WHERE id = $1
-            RETURNING id, external_id, name, type, status, currency, metadata, created_at, updated_at
+            RETURNING id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
             "#,
         )
         .bind(id)
@@ -146,6 +236,116 @@ impl AccountRepository {
         Ok(row)
     }
 
+    /// Scrubs a closed account's personal data and records the audit entry atomically:
+    /// name, external ID and metadata are replaced, the settlement profile and
+    /// secondary identifiers are removed and free-text notes on its status history are
+    /// cleared. Returns `None` if the
+    /// account does not exist, is not closed or was already anonymized.
+    pub async fn anonymize(&self, anonymization: &AccountAnonymization) -> Result<Option<Account>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let row = sqlx::query_as::<_, Account>(
+            r#"
+            UPDATE accounts
+            SET name = $2, external_id = $3, metadata = NULL, anonymized_at = $4, updated_at = $4
+            WHERE id = $1 AND status = 'CLOSED' AND anonymized_at IS NULL
+            RETURNING id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at
+            "#,
+        )
+        .bind(anonymization.account_id)
+        .bind(ANONYMIZED_ACCOUNT_NAME)
+        .bind(Account::anonymized_external_id(anonymization.account_id))
+        .bind(anonymization.anonymized_at)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        let Some(account) = row else {
+            return Ok(None);
+        };
+
+        sqlx::query("DELETE FROM settlement_profiles WHERE account_id = $1")
+            .bind(anonymization.account_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query("DELETE FROM account_identifiers WHERE account_id = $1")
+            .bind(anonymization.account_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query("UPDATE account_status_history SET note = NULL WHERE account_id = $1")
+            .bind(anonymization.account_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_anonymizations (id, account_id, requested_by, reason, scrubbed_fields, anonymized_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(anonymization.id)
+        .bind(anonymization.account_id)
+        .bind(&anonymization.requested_by)
+        .bind(&anonymization.reason)
+        .bind(&anonymization.scrubbed_fields)
+        .bind(anonymization.anonymized_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(Some(account))
+    }
+
+    /// Finds the anonymization record of an account.
+    pub async fn find_anonymization(&self, account_id: Uuid) -> Result<Option<AccountAnonymization>> {
+        let row = sqlx::query_as::<_, AccountAnonymization>(
+            r#"
+            SELECT id, account_id, requested_by, reason, scrubbed_fields, anonymized_at
+            FROM account_anonymizations
+            WHERE account_id = $1
+            "#,
+        )
+        .bind(account_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists closed, not yet anonymized accounts that were closed before `closed_before`.
+    /// Accounts without a recorded closure fall back to their last update time.
+    pub async fn find_closed_before(&self, closed_before: DateTime<Utc>, limit: i64) -> Result<Vec<Uuid>> {
+        let rows: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT a.id
+            FROM accounts a
+            WHERE a.status = 'CLOSED'
+              AND a.anonymized_at IS NULL
+              AND COALESCE(
+                    (SELECT MAX(h.changed_at) FROM account_status_history h
+                     WHERE h.account_id = a.id AND h.to_status = 'CLOSED'),
+                    a.updated_at
+                  ) < $1
+            ORDER BY a.updated_at
+            LIMIT $2
+            "#,
+        )
+        .bind(closed_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
     /// Deletes an account by ID (soft delete by setting status to Closed).
     pub async fn delete(&self, id: Uuid) -> Result<bool> {
         let result = sqlx::query(
@@ -0,0 +1,81 @@
+use crate::error::{AppError, Result};
+use crate::models::{AccountingPeriod, PeriodStatus};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+/// Repository for accounting period status.
+pub struct AccountingPeriodRepository {
+    pool: PgPool,
+}
+
+impl AccountingPeriodRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Gets the stored period containing a date, if its status was ever changed.
+    pub async fn find_containing(&self, date: NaiveDate) -> Result<Option<AccountingPeriod>> {
+        let row = sqlx::query_as::<_, AccountingPeriod>(
+            r#"
+            SELECT period_start, period_end, status, closed_at, updated_at
+            FROM accounting_periods
+            WHERE period_start <= $1 AND period_end >= $1
+            "#,
+        )
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Stores a period's status, creating the period if needed.
+    pub async fn set_status(&self, period: &AccountingPeriod, status: PeriodStatus) -> Result<AccountingPeriod> {
+        let row = sqlx::query_as::<_, AccountingPeriod>(
+            r#"
+            INSERT INTO accounting_periods (period_start, period_end, status, closed_at, updated_at)
+            VALUES ($1, $2, $3, CASE WHEN $3 = 'CLOSED'::accounting_period_status THEN NOW() END, NOW())
+            ON CONFLICT (period_start) DO UPDATE
+            SET status = EXCLUDED.status, closed_at = EXCLUDED.closed_at, updated_at = NOW()
+            RETURNING period_start, period_end, status, closed_at, updated_at
+            "#,
+        )
+        .bind(period.period_start)
+        .bind(period.period_end)
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists periods whose status was ever changed, latest first.
+    pub async fn list(&self, limit: i64, offset: i64) -> Result<Vec<AccountingPeriod>> {
+        let rows = sqlx::query_as::<_, AccountingPeriod>(
+            r#"
+            SELECT period_start, period_end, status, closed_at, updated_at
+            FROM accounting_periods
+            ORDER BY period_start DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    pub async fn count(&self) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM accounting_periods")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+}
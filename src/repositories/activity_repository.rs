@@ -0,0 +1,75 @@
+use crate::error::{AppError, Result};
+use crate::models::AccountActivity;
+use chrono::NaiveDate;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for the account activity projection.
+pub struct ActivityRepository {
+    pool: PgPool,
+}
+
+impl ActivityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Adds activity to the account's aggregate for its day and transaction type.
+    pub async fn apply_in(tx: &mut Transaction<'_, Postgres>, activity: &AccountActivity) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO account_activity_daily (account_id, activity_date, currency, transaction_type, transaction_count, total_in, total_out, total_fees, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+            ON CONFLICT (account_id, activity_date, currency, transaction_type) DO UPDATE SET
+                transaction_count = account_activity_daily.transaction_count + EXCLUDED.transaction_count,
+                total_in = account_activity_daily.total_in + EXCLUDED.total_in,
+                total_out = account_activity_daily.total_out + EXCLUDED.total_out,
+                total_fees = account_activity_daily.total_fees + EXCLUDED.total_fees,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(activity.account_id)
+        .bind(activity.activity_date)
+        .bind(&activity.currency)
+        .bind(activity.transaction_type)
+        .bind(activity.transaction_count)
+        .bind(activity.total_in)
+        .bind(activity.total_out)
+        .bind(activity.total_fees)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Gets an account's daily activity within an inclusive date range.
+    pub async fn find_by_account(
+        &self,
+        account_id: Uuid,
+        currency: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<AccountActivity>> {
+        let rows = sqlx::query_as::<_, AccountActivity>(
+            r#"
+            SELECT account_id, activity_date, currency, transaction_type, transaction_count, total_in, total_out, total_fees, updated_at
+            FROM account_activity_daily
+            WHERE account_id = $1
+              AND ($2::VARCHAR IS NULL OR currency = $2)
+              AND ($3::DATE IS NULL OR activity_date >= $3)
+              AND ($4::DATE IS NULL OR activity_date <= $4)
+            ORDER BY activity_date, currency, transaction_type
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::{AppError, Result};
+use crate::models::{AlertRule, AlertRuleType};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for AlertRule CRUD operations.
+pub struct AlertRuleRepository {
+    pool: PgPool,
+}
+
+impl AlertRuleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates a new alert rule in the database.
+    pub async fn create(&self, rule: &AlertRule) -> Result<AlertRule> {
+        let row = sqlx::query_as::<_, AlertRule>(
+            r#"
+            INSERT INTO alert_rules (id, account_id, rule_type, currency, threshold, webhook_url, suppression_window_seconds, enabled, last_triggered_at, metadata, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, account_id, rule_type, currency, threshold, webhook_url, suppression_window_seconds, enabled, last_triggered_at, metadata, created_at, updated_at
+            "#,
+        )
+        .bind(rule.id)
+        .bind(rule.account_id)
+        .bind(rule.rule_type)
+        .bind(&rule.currency)
+        .bind(rule.threshold)
+        .bind(&rule.webhook_url)
+        .bind(rule.suppression_window_seconds)
+        .bind(rule.enabled)
+        .bind(rule.last_triggered_at)
+        .bind(&rule.metadata)
+        .bind(rule.created_at)
+        .bind(rule.updated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds an alert rule by its UUID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<AlertRule>> {
+        let row = sqlx::query_as::<_, AlertRule>(
+            r#"
+            SELECT id, account_id, rule_type, currency, threshold, webhook_url, suppression_window_seconds, enabled, last_triggered_at, metadata, created_at, updated_at
+            FROM alert_rules
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists alert rules with optional filters.
+    pub async fn list(
+        &self,
+        account_id: Option<Uuid>,
+        rule_type: Option<AlertRuleType>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AlertRule>> {
+        let rows = sqlx::query_as::<_, AlertRule>(
+            r#"
+            SELECT id, account_id, rule_type, currency, threshold, webhook_url, suppression_window_seconds, enabled, last_triggered_at, metadata, created_at, updated_at
+            FROM alert_rules
+            WHERE ($1::uuid IS NULL OR account_id = $1)
+              AND ($2::alert_rule_type IS NULL OR rule_type = $2)
+            ORDER BY created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(account_id)
+        .bind(rule_type)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds enabled rules that apply to any of the given accounts, including global rules.
+    pub async fn find_enabled_for_accounts(
+        &self,
+        account_ids: &[Uuid],
+        currency: &str,
+    ) -> Result<Vec<AlertRule>> {
+        let rows = sqlx::query_as::<_, AlertRule>(
+            r#"
+            SELECT id, account_id, rule_type, currency, threshold, webhook_url, suppression_window_seconds, enabled, last_triggered_at, metadata, created_at, updated_at
+            FROM alert_rules
+            WHERE enabled
+              AND currency = $2
+              AND (account_id IS NULL OR account_id = ANY($1))
+            "#,
+        )
+        .bind(account_ids)
+        .bind(currency)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Updates the mutable fields of an alert rule.
+    pub async fn update(&self, rule: &AlertRule) -> Result<Option<AlertRule>> {
+        let row = sqlx::query_as::<_, AlertRule>(
+            r#"
+            UPDATE alert_rules
+            SET threshold = $2,
+                webhook_url = $3,
+                suppression_window_seconds = $4,
+                enabled = $5,
+                metadata = $6,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, account_id, rule_type, currency, threshold, webhook_url, suppression_window_seconds, enabled, last_triggered_at, metadata, created_at, updated_at
+            "#,
+        )
+        .bind(rule.id)
+        .bind(rule.threshold)
+        .bind(&rule.webhook_url)
+        .bind(rule.suppression_window_seconds)
+        .bind(rule.enabled)
+        .bind(&rule.metadata)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Records that a rule fired for an account, unless it is still inside its suppression
+    /// window for that account. Returns None when suppressed so concurrent evaluators fire
+    /// at most once per account. The rule's `last_triggered_at` is its latest firing for
+    /// any account.
+    pub async fn mark_triggered(
+        &self,
+        id: Uuid,
+        account_id: Uuid,
+        triggered_at: DateTime<Utc>,
+    ) -> Result<Option<AlertRule>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO alert_rule_triggers (rule_id, account_id, last_triggered_at)
+            SELECT id, $2, $3 FROM alert_rules WHERE id = $1
+            ON CONFLICT (rule_id, account_id) DO UPDATE SET last_triggered_at = EXCLUDED.last_triggered_at
+            WHERE (SELECT suppression_window_seconds FROM alert_rules WHERE id = $1) = 0
+               OR alert_rule_triggers.last_triggered_at
+                  + make_interval(secs => (SELECT suppression_window_seconds FROM alert_rules WHERE id = $1))
+                  <= EXCLUDED.last_triggered_at
+            "#,
+        )
+        .bind(id)
+        .bind(account_id)
+        .bind(triggered_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected()
+            > 0;
+        if !claimed {
+            return Ok(None);
+        }
+
+        let row = sqlx::query_as::<_, AlertRule>(
+            r#"
+            UPDATE alert_rules
+            SET last_triggered_at = GREATEST(last_triggered_at, $2)
+            WHERE id = $1
+            RETURNING id, account_id, rule_type, currency, threshold, webhook_url, suppression_window_seconds, enabled, last_triggered_at, metadata, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(triggered_at)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Deletes an alert rule by ID.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM alert_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Counts alert rules with optional filters.
+    pub async fn count(&self, account_id: Option<Uuid>, rule_type: Option<AlertRuleType>) -> Result<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM alert_rules
+            WHERE ($1::uuid IS NULL OR account_id = $1)
+              AND ($2::alert_rule_type IS NULL OR rule_type = $2)
+            "#,
+        )
+        .bind(account_id)
+        .bind(rule_type)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(count.0)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::error::{AppError, Result};
+use crate::models::{BalanceFloor, BalanceIncident, BalanceIncidentStatus};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for balance floors and the incidents opened when a balance breaches one.
+/// Incidents are inserted by the `guard_balance_floor` trigger, never by this repository.
+pub struct BalanceGuardRepository {
+    pool: PgPool,
+}
+
+impl BalanceGuardRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Sets the lowest available balance allowed for an account in a currency.
+    pub async fn set_floor(&self, account_id: Uuid, currency: &str, floor: Decimal) -> Result<BalanceFloor> {
+        let row = sqlx::query_as::<_, BalanceFloor>(
+            r#"
+            INSERT INTO balance_floors (account_id, currency, floor, updated_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (account_id, currency) DO UPDATE SET floor = EXCLUDED.floor, updated_at = NOW()
+            RETURNING account_id, currency, floor, updated_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(floor)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Gets the floor set for an account in a currency, if any.
+    pub async fn find_floor(&self, account_id: Uuid, currency: &str) -> Result<Option<BalanceFloor>> {
+        let row = sqlx::query_as::<_, BalanceFloor>(
+            r#"
+            SELECT account_id, currency, floor, updated_at
+            FROM balance_floors
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_incident(&self, id: Uuid) -> Result<Option<BalanceIncident>> {
+        let row = sqlx::query_as::<_, BalanceIncident>(
+            r#"
+            SELECT id, account_id, currency, floor, available_balance, pending_balance, reserved_balance, status, detected_at, last_seen_at, frozen_at, resolved_at, resolution_note
+            FROM balance_incidents
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Gets up to `limit` open incidents the guard has not acted on, oldest first.
+    pub async fn find_open(&self, limit: i64) -> Result<Vec<BalanceIncident>> {
+        let rows = sqlx::query_as::<_, BalanceIncident>(
+            r#"
+            SELECT id, account_id, currency, floor, available_balance, pending_balance, reserved_balance, status, detected_at, last_seen_at, frozen_at, resolved_at, resolution_note
+            FROM balance_incidents
+            WHERE status = 'OPEN'
+            ORDER BY detected_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Marks an open incident frozen. Returns `None` if another guard got to it first.
+    pub async fn mark_frozen(&self, id: Uuid) -> Result<Option<BalanceIncident>> {
+        let row = sqlx::query_as::<_, BalanceIncident>(
+            r#"
+            UPDATE balance_incidents
+            SET status = 'FROZEN', frozen_at = NOW()
+            WHERE id = $1 AND status = 'OPEN'
+            RETURNING id, account_id, currency, floor, available_balance, pending_balance, reserved_balance, status, detected_at, last_seen_at, frozen_at, resolved_at, resolution_note
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Resolves an incident if the balance is back within its floor. Returns `None` if
+    /// the incident is already resolved or the balance still breaches.
+    pub async fn resolve(&self, id: Uuid, note: &str) -> Result<Option<BalanceIncident>> {
+        let row = sqlx::query_as::<_, BalanceIncident>(
+            r#"
+            UPDATE balance_incidents i
+            SET status = 'RESOLVED', resolved_at = NOW(), resolution_note = $2
+            FROM account_balances b
+            LEFT JOIN balance_floors f ON f.account_id = b.account_id AND f.currency = b.currency
+            WHERE i.id = $1 AND i.status <> 'RESOLVED'
+              AND b.account_id = i.account_id AND b.currency = i.currency
+              AND b.available_balance >= COALESCE(f.floor, 0)
+              AND b.pending_balance >= 0 AND b.reserved_balance >= 0
+            RETURNING i.id, i.account_id, i.currency, i.floor, i.available_balance, i.pending_balance, i.reserved_balance, i.status, i.detected_at, i.last_seen_at, i.frozen_at, i.resolved_at, i.resolution_note
+            "#,
+        )
+        .bind(id)
+        .bind(note)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists incidents, newest first.
+    pub async fn list(
+        &self,
+        status: Option<BalanceIncidentStatus>,
+        account_id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BalanceIncident>> {
+        let rows = sqlx::query_as::<_, BalanceIncident>(
+            r#"
+            SELECT id, account_id, currency, floor, available_balance, pending_balance, reserved_balance, status, detected_at, last_seen_at, frozen_at, resolved_at, resolution_note
+            FROM balance_incidents
+            WHERE ($1::balance_incident_status IS NULL OR status = $1)
+              AND ($2::UUID IS NULL OR account_id = $2)
+            ORDER BY detected_at DESC, id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(status)
+        .bind(account_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Counts incidents matching the `list` filters.
+    pub async fn count(&self, status: Option<BalanceIncidentStatus>, account_id: Option<Uuid>) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM balance_incidents
+            WHERE ($1::balance_incident_status IS NULL OR status = $1)
+              AND ($2::UUID IS NULL OR account_id = $2)
+            "#,
+        )
+        .bind(status)
+        .bind(account_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+}
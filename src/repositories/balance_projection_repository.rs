@@ -0,0 +1,189 @@
+use crate::error::{AppError, Result};
+use crate::models::{BalanceProjection, BalanceProjectionLag, SequencedLedgerEntry};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for the balance projection folded from the ledger.
+pub struct BalanceProjectionRepository {
+    pool: PgPool,
+}
+
+impl BalanceProjectionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Finds the projected balance of an account in a currency.
+    pub async fn find(&self, account_id: Uuid, currency: &str) -> Result<Option<BalanceProjection>> {
+        let row = sqlx::query_as::<_, BalanceProjection>(
+            r#"
+            SELECT account_id, currency, balance, last_sequence, last_entry_at, updated_at
+            FROM balance_projections
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists account balances with entries not yet projected, least recently posted first.
+    pub async fn find_behind(&self, limit: i64) -> Result<Vec<(Uuid, String)>> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT b.account_id, b.currency
+            FROM account_balances b
+            LEFT JOIN balance_projections p ON p.account_id = b.account_id AND p.currency = b.currency
+            WHERE b.entry_sequence > COALESCE(p.last_sequence, 0)
+            ORDER BY b.last_updated
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Locks an account's projection for folding, creating it empty if it does not
+    /// exist. Returns `None` if another projector holds it.
+    pub async fn lock_in(
+        tx: &mut Transaction<'_, Postgres>,
+        account_id: Uuid,
+        currency: &str,
+    ) -> Result<Option<BalanceProjection>> {
+        let empty = BalanceProjection::new(account_id, currency.to_string());
+        sqlx::query(
+            r#"
+            INSERT INTO balance_projections (account_id, currency, balance, last_sequence, last_entry_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (account_id, currency) DO NOTHING
+            "#,
+        )
+        .bind(empty.account_id)
+        .bind(&empty.currency)
+        .bind(empty.balance)
+        .bind(empty.last_sequence)
+        .bind(empty.last_entry_at)
+        .bind(empty.updated_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        let row = sqlx::query_as::<_, BalanceProjection>(
+            r#"
+            SELECT account_id, currency, balance, last_sequence, last_entry_at, updated_at
+            FROM balance_projections
+            WHERE account_id = $1 AND currency = $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Gets the entries of an account after a sequence number, in sequence order.
+    pub async fn entries_after_in(
+        tx: &mut Transaction<'_, Postgres>,
+        account_id: Uuid,
+        currency: &str,
+        after_sequence: i64,
+        limit: i64,
+    ) -> Result<Vec<SequencedLedgerEntry>> {
+        let rows = sqlx::query_as::<_, SequencedLedgerEntry>(
+            r#"
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference, account_sequence
+            FROM ledger_entries
+            WHERE account_id = $1 AND currency = $2 AND account_sequence > $3
+            ORDER BY account_sequence
+            LIMIT $4
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(after_sequence)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Saves a projection locked with `lock_in`.
+    pub async fn save_in(tx: &mut Transaction<'_, Postgres>, projection: &BalanceProjection) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE balance_projections
+            SET balance = $3, last_sequence = $4, last_entry_at = $5, updated_at = $6
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(projection.account_id)
+        .bind(&projection.currency)
+        .bind(projection.balance)
+        .bind(projection.last_sequence)
+        .bind(projection.last_entry_at)
+        .bind(projection.updated_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Deletes projections so they are folded again from the first entry: one
+    /// account's, or every projection if no account is given.
+    pub async fn delete(&self, account_id: Option<Uuid>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM balance_projections
+            WHERE ($1::UUID IS NULL OR account_id = $1)
+            "#,
+        )
+        .bind(account_id)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Measures how far projections are behind the ledger, optionally for one account
+    /// or one account's currency.
+    pub async fn lag(&self, account_id: Option<Uuid>, currency: Option<&str>) -> Result<BalanceProjectionLag> {
+        let row = sqlx::query_as::<_, BalanceProjectionLag>(
+            r#"
+            SELECT COUNT(*) AS accounts_behind,
+                   COALESCE(SUM(b.entry_sequence - COALESCE(p.last_sequence, 0)), 0)::BIGINT AS entries_behind,
+                   MIN(e.created_at) AS oldest_unprojected_at
+            FROM account_balances b
+            LEFT JOIN balance_projections p ON p.account_id = b.account_id AND p.currency = b.currency
+            LEFT JOIN ledger_entries e
+                ON e.account_id = b.account_id AND e.currency = b.currency
+               AND e.account_sequence = COALESCE(p.last_sequence, 0) + 1
+            WHERE b.entry_sequence > COALESCE(p.last_sequence, 0)
+              AND ($1::UUID IS NULL OR b.account_id = $1)
+              AND ($2::VARCHAR IS NULL OR b.currency = $2)
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
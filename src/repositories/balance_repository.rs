@@ -1,7 +1,8 @@
 use crate::error::{AppError, Result};
 use crate::models::AccountBalance;
+use crate::observability::timed;
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 /// Repository for AccountBalance operations with optimistic locking support.
@@ -16,21 +17,24 @@ impl BalanceRepository {
 
     /// Creates a new balance record for an account.
     pub async fn create(&self, balance: &AccountBalance) -> Result<AccountBalance> {
-        let row = sqlx::query_as::<_, AccountBalance>(
-            r#"
-            INSERT INTO account_balances (account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
-            "#,
+        let row = timed(
+            "account_balances.insert",
+            sqlx::query_as::<_, AccountBalance>(
+                r#"
+                INSERT INTO account_balances (account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
+                "#,
+            )
+            .bind(balance.account_id)
+            .bind(&balance.currency)
+            .bind(balance.available_balance)
+            .bind(balance.pending_balance)
+            .bind(balance.reserved_balance)
+            .bind(balance.version)
+            .bind(balance.last_updated)
+            .fetch_one(&self.pool),
         )
-        .bind(balance.account_id)
-        .bind(&balance.currency)
-        .bind(balance.available_balance)
-        .bind(balance.pending_balance)
-        .bind(balance.reserved_balance)
-        .bind(balance.version)
-        .bind(balance.last_updated)
-        .fetch_one(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
@@ -43,16 +47,19 @@ impl BalanceRepository {
         account_id: Uuid,
         currency: &str,
     ) -> Result<Option<AccountBalance>> {
-        let row = sqlx::query_as::<_, AccountBalance>(
-            r#"
-            SELECT account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
-            FROM account_balances
-            WHERE account_id = $1 AND currency = $2
-            "#,
+        let row = timed(
+            "account_balances.find",
+            sqlx::query_as::<_, AccountBalance>(
+                r#"
+                SELECT account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
+                FROM account_balances
+                WHERE account_id = $1 AND currency = $2
+                "#,
+            )
+            .bind(account_id)
+            .bind(currency)
+            .fetch_optional(&self.pool),
         )
-        .bind(account_id)
-        .bind(currency)
-        .fetch_optional(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
@@ -135,6 +142,101 @@ impl BalanceRepository {
         Ok(row)
     }
 
+    /// Adds a signed amount to an account's available balance within an existing database
+    /// transaction. There is no funds check: callers use it for compensating entries that
+    /// must post regardless of the account's balance.
+    pub async fn adjust_in(
+        tx: &mut Transaction<'_, Postgres>,
+        account_id: Uuid,
+        currency: &str,
+        delta: Decimal,
+    ) -> Result<AccountBalance> {
+        let row = timed(
+            "account_balances.adjust",
+            sqlx::query_as::<_, AccountBalance>(
+                r#"
+                UPDATE account_balances
+                SET available_balance = available_balance + $3,
+                    version = version + 1,
+                    last_updated = NOW()
+                WHERE account_id = $1 AND currency = $2
+                RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
+                "#,
+            )
+            .bind(account_id)
+            .bind(currency)
+            .bind(delta)
+            .fetch_optional(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        row.ok_or_else(|| AppError::NotFound(format!("Balance for account '{}' in {} not found", account_id, currency)))
+    }
+
+    /// Locks the balances of the given account/currency pairs for the rest of the database
+    /// transaction. Rows are locked in key order, so transactions locking overlapping sets
+    /// this way cannot deadlock each other.
+    pub async fn lock_many_in(
+        tx: &mut Transaction<'_, Postgres>,
+        account_ids: &[Uuid],
+        currencies: &[String],
+    ) -> Result<Vec<AccountBalance>> {
+        let rows = timed(
+            "account_balances.lock_many",
+            sqlx::query_as::<_, AccountBalance>(
+                r#"
+                SELECT b.account_id, b.currency, b.available_balance, b.pending_balance, b.reserved_balance, b.version, b.last_updated
+                FROM account_balances b
+                JOIN UNNEST($1::uuid[], $2::varchar[]) AS k(account_id, currency)
+                  ON b.account_id = k.account_id AND b.currency = k.currency
+                ORDER BY b.account_id, b.currency
+                FOR UPDATE OF b
+                "#,
+            )
+            .bind(account_ids)
+            .bind(currencies)
+            .fetch_all(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Adds a signed amount to each listed balance's available balance in one statement
+    /// within an existing database transaction. Like `adjust_in` there is no funds check;
+    /// callers check funds against balances they hold locked.
+    pub async fn adjust_many_in(
+        tx: &mut Transaction<'_, Postgres>,
+        account_ids: &[Uuid],
+        currencies: &[String],
+        deltas: &[Decimal],
+    ) -> Result<Vec<AccountBalance>> {
+        let rows = timed(
+            "account_balances.adjust_many",
+            sqlx::query_as::<_, AccountBalance>(
+                r#"
+                UPDATE account_balances b
+                SET available_balance = b.available_balance + d.delta,
+                    version = b.version + 1,
+                    last_updated = NOW()
+                FROM UNNEST($1::uuid[], $2::varchar[], $3::numeric[]) AS d(account_id, currency, delta)
+                WHERE b.account_id = d.account_id AND b.currency = d.currency
+                RETURNING b.account_id, b.currency, b.available_balance, b.pending_balance, b.reserved_balance, b.version, b.last_updated
+                "#,
+            )
+            .bind(account_ids)
+            .bind(currencies)
+            .bind(deltas)
+            .fetch_all(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
     /// Debits an account balance atomically.
     /// Returns an error if insufficient funds.
     pub async fn debit(
@@ -161,7 +263,7 @@ impl BalanceRepository {
         .await
         .map_err(AppError::Database)?;
 
-        row.ok_or_else(|| AppError::Validation("Insufficient funds or balance not found".to_string()))
+        row.ok_or_else(|| AppError::InsufficientFunds("Insufficient funds or balance not found".to_string()))
     }
 
     /// Reserves an amount from available balance.
@@ -190,7 +292,7 @@ impl BalanceRepository {
         .await
         .map_err(AppError::Database)?;
 
-        row.ok_or_else(|| AppError::Validation("Insufficient funds for reservation".to_string()))
+        row.ok_or_else(|| AppError::InsufficientFunds("Insufficient funds for reservation".to_string()))
     }
 
     /// Releases a reserved amount back to available.
@@ -247,7 +349,7 @@ impl BalanceRepository {
         .await
         .map_err(AppError::Database)?;
 
-        row.ok_or_else(|| AppError::Validation("Insufficient funds to move to pending".to_string()))
+        row.ok_or_else(|| AppError::InsufficientFunds("Insufficient funds to move to pending".to_string()))
     }
 
     /// Settles pending balance to available.
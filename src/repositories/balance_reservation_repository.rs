@@ -0,0 +1,240 @@
+use crate::error::{AppError, Result};
+use crate::models::{BalanceReservation, BalanceReservationStatus};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for named reservations of account balances.
+pub struct BalanceReservationRepository {
+    pool: PgPool,
+}
+
+impl BalanceReservationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn map_insert_error(error: sqlx::Error) -> AppError {
+        match &error {
+            sqlx::Error::Database(db) if db.constraint() == Some("uq_balance_reservations_account_reference") => {
+                AppError::Validation("reference: Reference has already been used on this account".to_string())
+            }
+            _ => AppError::Database(error),
+        }
+    }
+
+    /// Records the reservation and reserves its amount on its account. Returns an error
+    /// if the reference is taken or the account's available balance does not cover it.
+    pub async fn create(&self, reservation: &BalanceReservation) -> Result<BalanceReservation> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let row = sqlx::query_as::<_, BalanceReservation>(
+            r#"
+            INSERT INTO balance_reservations (id, account_id, currency, amount, name, reference, status, expires_at, transaction_id, created_at, closed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, account_id, currency, amount, name, reference, status, expires_at, transaction_id, created_at, closed_at
+            "#,
+        )
+        .bind(reservation.id)
+        .bind(reservation.account_id)
+        .bind(&reservation.currency)
+        .bind(reservation.amount)
+        .bind(&reservation.name)
+        .bind(&reservation.reference)
+        .bind(reservation.status)
+        .bind(reservation.expires_at)
+        .bind(reservation.transaction_id)
+        .bind(reservation.created_at)
+        .bind(reservation.closed_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_insert_error)?;
+
+        let reserved = sqlx::query(
+            r#"
+            UPDATE account_balances
+            SET available_balance = available_balance - $3,
+                reserved_balance = reserved_balance + $3,
+                version = version + 1,
+                last_updated = NOW()
+            WHERE account_id = $1 AND currency = $2
+              AND available_balance >= $3
+            "#,
+        )
+        .bind(reservation.account_id)
+        .bind(&reservation.currency)
+        .bind(reservation.amount)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+        if reserved.rows_affected() == 0 {
+            return Err(AppError::InsufficientFunds("Insufficient funds for reservation".to_string()));
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<BalanceReservation>> {
+        let row = sqlx::query_as::<_, BalanceReservation>(
+            r#"
+            SELECT id, account_id, currency, amount, name, reference, status, expires_at, transaction_id, created_at, closed_at
+            FROM balance_reservations
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists an account's reservations, optionally in one status, oldest first.
+    pub async fn list_by_account(
+        &self,
+        account_id: Uuid,
+        status: Option<BalanceReservationStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BalanceReservation>> {
+        let rows = sqlx::query_as::<_, BalanceReservation>(
+            r#"
+            SELECT id, account_id, currency, amount, name, reference, status, expires_at, transaction_id, created_at, closed_at
+            FROM balance_reservations
+            WHERE account_id = $1
+              AND ($2::balance_reservation_status IS NULL OR status = $2)
+            ORDER BY created_at, id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(account_id)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Counts reservations matching the `list_by_account` filter.
+    pub async fn count_by_account(&self, account_id: Uuid, status: Option<BalanceReservationStatus>) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM balance_reservations
+            WHERE account_id = $1
+              AND ($2::balance_reservation_status IS NULL OR status = $2)
+            "#,
+        )
+        .bind(account_id)
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+
+    /// Locks a reservation for the rest of the database transaction.
+    pub async fn lock_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Option<BalanceReservation>> {
+        let row = sqlx::query_as::<_, BalanceReservation>(
+            r#"
+            SELECT id, account_id, currency, amount, name, reference, status, expires_at, transaction_id, created_at, closed_at
+            FROM balance_reservations
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Moves an active reservation to `status`, linking the payment it was captured
+    /// into if any, and returns its amount to the account's available balance within an
+    /// existing database transaction. Returns `None` if the reservation is not active.
+    pub async fn close_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        status: BalanceReservationStatus,
+        transaction_id: Option<Uuid>,
+    ) -> Result<Option<BalanceReservation>> {
+        let reservation = sqlx::query_as::<_, BalanceReservation>(
+            r#"
+            UPDATE balance_reservations
+            SET status = $2, transaction_id = $3, closed_at = NOW()
+            WHERE id = $1 AND status = 'ACTIVE'
+            RETURNING id, account_id, currency, amount, name, reference, status, expires_at, transaction_id, created_at, closed_at
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(transaction_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if let Some(reservation) = &reservation {
+            Self::restore_in(tx, reservation).await?;
+        }
+
+        Ok(reservation)
+    }
+
+    /// Expires up to `limit` active reservations whose expiry has passed, returning their
+    /// amounts to their accounts within an existing database transaction.
+    pub async fn expire_due_in(tx: &mut Transaction<'_, Postgres>, limit: i64) -> Result<Vec<BalanceReservation>> {
+        let expired = sqlx::query_as::<_, BalanceReservation>(
+            r#"
+            UPDATE balance_reservations
+            SET status = 'EXPIRED', closed_at = NOW()
+            WHERE id IN (
+                SELECT id
+                FROM balance_reservations
+                WHERE status = 'ACTIVE' AND expires_at <= NOW()
+                ORDER BY expires_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, account_id, currency, amount, name, reference, status, expires_at, transaction_id, created_at, closed_at
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        for reservation in &expired {
+            Self::restore_in(tx, reservation).await?;
+        }
+
+        Ok(expired)
+    }
+
+    async fn restore_in(tx: &mut Transaction<'_, Postgres>, reservation: &BalanceReservation) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE account_balances
+            SET available_balance = available_balance + LEAST($3, reserved_balance),
+                reserved_balance = reserved_balance - LEAST($3, reserved_balance),
+                version = version + 1,
+                last_updated = NOW()
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(reservation.account_id)
+        .bind(&reservation.currency)
+        .bind(reservation.amount)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use crate::error::{AppError, Result};
+use crate::models::BatchProcessingProgress;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashSet;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Repository for batch processing checkpoints and the runs holding them.
+pub struct BatchProgressRepository {
+    pool: PgPool,
+}
+
+impl BatchProgressRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Starts a processing run of a newly closed batch. An earlier run that finished or
+    /// stalled (before a retry) is started over: failed transactions are cleared to be
+    /// tried again, processed ones stay processed. Returns None if another run is still
+    /// processing the batch.
+    pub async fn start(
+        &self,
+        batch_id: Uuid,
+        total_transactions: i32,
+        run_id: Uuid,
+        stall_timeout: Duration,
+    ) -> Result<Option<BatchProcessingProgress>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let progress = sqlx::query_as::<_, BatchProcessingProgress>(
+            r#"
+            INSERT INTO batch_processing_progress (batch_id, total_transactions, run_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (batch_id) DO UPDATE
+                SET total_transactions = EXCLUDED.total_transactions,
+                    processed_transactions = 0,
+                    failed_transactions = 0,
+                    started_at = NOW(),
+                    updated_at = NOW(),
+                    finished_at = NULL,
+                    run_id = EXCLUDED.run_id,
+                    resumes = 0
+                WHERE batch_processing_progress.finished_at IS NOT NULL
+                   OR batch_processing_progress.updated_at < NOW() - make_interval(secs => $4)
+            RETURNING batch_id, total_transactions, processed_transactions, failed_transactions, started_at, updated_at, finished_at, run_id, resumes
+            "#,
+        )
+        .bind(batch_id)
+        .bind(total_transactions)
+        .bind(run_id)
+        .bind(stall_timeout.as_secs_f64())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if progress.is_some() {
+            sqlx::query("DELETE FROM batch_transaction_checkpoints WHERE batch_id = $1 AND outcome = 'FAILED'")
+                .bind(batch_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::Database)?;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(progress)
+    }
+
+    /// Takes over an unfinished batch for `run_id`: one whose run has not checkpointed
+    /// for `stall_timeout`, or that was closed without processing ever starting.
+    /// Returns None while the batch's run is still alive.
+    pub async fn claim(
+        &self,
+        batch_id: Uuid,
+        total_transactions: i32,
+        run_id: Uuid,
+        stall_timeout: Duration,
+    ) -> Result<Option<BatchProcessingProgress>> {
+        sqlx::query_as::<_, BatchProcessingProgress>(
+            r#"
+            INSERT INTO batch_processing_progress (batch_id, total_transactions, run_id, resumes)
+            VALUES ($1, $2, $3, 1)
+            ON CONFLICT (batch_id) DO UPDATE
+                SET total_transactions = EXCLUDED.total_transactions,
+                    updated_at = NOW(),
+                    run_id = EXCLUDED.run_id,
+                    resumes = batch_processing_progress.resumes + 1
+                WHERE batch_processing_progress.finished_at IS NULL
+                  AND batch_processing_progress.updated_at < NOW() - make_interval(secs => $4)
+            RETURNING batch_id, total_transactions, processed_transactions, failed_transactions, started_at, updated_at, finished_at, run_id, resumes
+            "#,
+        )
+        .bind(batch_id)
+        .bind(total_transactions)
+        .bind(run_id)
+        .bind(stall_timeout.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Batches still processing whose run has not checkpointed for `stall_timeout`.
+    pub async fn find_stalled(&self, stall_timeout: Duration) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT p.batch_id
+            FROM batch_processing_progress p
+            JOIN settlement_batches b ON b.id = p.batch_id
+            WHERE b.status = 'PROCESSING'
+              AND p.finished_at IS NULL
+              AND p.updated_at < NOW() - make_interval(secs => $1)
+            ORDER BY p.updated_at
+            "#,
+        )
+        .bind(stall_timeout.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Records a transaction as processed within `tx`, the database transaction its
+    /// processing is written in. Returns false if an earlier run already recorded it.
+    pub async fn claim_transaction_in(
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO batch_transaction_checkpoints (batch_id, transaction_id, outcome)
+            VALUES ($1, $2, 'PROCESSED')
+            ON CONFLICT (batch_id, transaction_id) DO NOTHING
+            "#,
+        )
+        .bind(batch_id)
+        .bind(transaction_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Records a transaction whose processing failed and was rolled back.
+    pub async fn record_failure(&self, batch_id: Uuid, transaction_id: Uuid, error_message: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO batch_transaction_checkpoints (batch_id, transaction_id, outcome, error_message)
+            VALUES ($1, $2, 'FAILED', $3)
+            ON CONFLICT (batch_id, transaction_id) DO NOTHING
+            "#,
+        )
+        .bind(batch_id)
+        .bind(transaction_id)
+        .bind(error_message)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Transactions of the batch processed so far, successfully or not.
+    pub async fn checkpointed(&self, batch_id: Uuid) -> Result<HashSet<Uuid>> {
+        let ids: Vec<Uuid> =
+            sqlx::query_scalar("SELECT transaction_id FROM batch_transaction_checkpoints WHERE batch_id = $1")
+                .bind(batch_id)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(AppError::Database)?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Transactions of the batch whose processing failed, with the error.
+    pub async fn failures(&self, batch_id: Uuid) -> Result<Vec<(Uuid, String)>> {
+        sqlx::query_as(
+            r#"
+            SELECT transaction_id, COALESCE(error_message, '')
+            FROM batch_transaction_checkpoints
+            WHERE batch_id = $1 AND outcome = 'FAILED'
+            ORDER BY processed_at, transaction_id
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Refreshes the batch's counts from its transaction checkpoints and heartbeats the
+    /// run. Returns false if `run_id` no longer holds the batch.
+    pub async fn checkpoint(&self, batch_id: Uuid, run_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE batch_processing_progress p
+            SET processed_transactions = c.processed,
+                failed_transactions = c.failed,
+                updated_at = NOW()
+            FROM (
+                SELECT COUNT(*)::INTEGER AS processed,
+                       (COUNT(*) FILTER (WHERE outcome = 'FAILED'))::INTEGER AS failed
+                FROM batch_transaction_checkpoints
+                WHERE batch_id = $1
+            ) c
+            WHERE p.batch_id = $1 AND p.run_id = $2
+            "#,
+        )
+        .bind(batch_id)
+        .bind(run_id)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn finish(&self, batch_id: Uuid, run_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE batch_processing_progress
+            SET updated_at = NOW(), finished_at = NOW()
+            WHERE batch_id = $1 AND run_id = $2
+            "#,
+        )
+        .bind(batch_id)
+        .bind(run_id)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    pub async fn find(&self, batch_id: Uuid) -> Result<Option<BatchProcessingProgress>> {
+        sqlx::query_as::<_, BatchProcessingProgress>(
+            r#"
+            SELECT batch_id, total_transactions, processed_transactions, failed_transactions, started_at, updated_at, finished_at, run_id, resumes
+            FROM batch_processing_progress
+            WHERE batch_id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::{AppError, Result};
+use crate::models::BatchReconciliation;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for the totals checks run on batches before completion.
+pub struct BatchReconciliationRepository {
+    pool: PgPool,
+}
+
+impl BatchReconciliationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records the outcome of a batch's checks.
+    pub async fn create(&self, reconciliation: &BatchReconciliation) -> Result<BatchReconciliation> {
+        let row = sqlx::query_as::<_, BatchReconciliation>(
+            r#"
+            INSERT INTO batch_reconciliations (id, batch_id, passed, breaks, checked_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, batch_id, passed, breaks, checked_at
+            "#,
+        )
+        .bind(reconciliation.id)
+        .bind(reconciliation.batch_id)
+        .bind(reconciliation.passed)
+        .bind(&reconciliation.breaks)
+        .bind(reconciliation.checked_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the most recent checks of a batch.
+    pub async fn find_latest(&self, batch_id: Uuid) -> Result<Option<BatchReconciliation>> {
+        let row = sqlx::query_as::<_, BatchReconciliation>(
+            r#"
+            SELECT id, batch_id, passed, breaks, checked_at
+            FROM batch_reconciliations
+            WHERE batch_id = $1
+            ORDER BY checked_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
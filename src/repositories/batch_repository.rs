@@ -1,8 +1,8 @@
 use crate::error::{AppError, Result};
-use crate::models::{BatchStatus, SettlementBatch};
+use crate::models::{BatchCutOffChange, BatchStatus, InstructionStrategy, NettingMode, SettlementBatch};
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 /// Repository for SettlementBatch lifecycle management.
@@ -15,13 +15,43 @@ impl BatchRepository {
         Self { pool }
     }
 
-    /// Creates a new settlement batch.
+    /// Creates a new settlement batch, numbering it after the last batch in its settlement window.
     pub async fn create(&self, batch: &SettlementBatch) -> Result<SettlementBatch> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let row = Self::create_in(&mut tx, batch).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Maps an insert that took a sequence number already in use to a conflict. Inserts
+    /// are numbered under the window lock, so this only guards against writers that
+    /// bypass it.
+    fn map_insert_error(error: sqlx::Error, batch: &SettlementBatch) -> AppError {
+        match &error {
+            sqlx::Error::Database(db)
+                if db.constraint() == Some("idx_batches_date_currency_window_sequence") =>
+            {
+                AppError::SerializationConflict(format!(
+                    "A batch for {} in {} was opened concurrently",
+                    batch.settlement_date, batch.currency
+                ))
+            }
+            _ => AppError::Database(error),
+        }
+    }
+
+    /// Creates a batch within an open database transaction, numbering it under the lock
+    /// on its settlement window so concurrent creations in the window, on any instance,
+    /// take consecutive sequence numbers. The lock is held until `tx` ends.
+    pub async fn create_in(tx: &mut Transaction<'_, Postgres>, batch: &SettlementBatch) -> Result<SettlementBatch> {
+        Self::lock_window_in(tx, batch.settlement_date, &batch.currency, batch.window_id).await?;
         let row = sqlx::query_as::<_, SettlementBatch>(
             r#"
-            INSERT INTO settlement_batches (id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            INSERT INTO settlement_batches (id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, window_id, netting_mode, cut_off_approval, instruction_strategy, sequence_number)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                    COALESCE((SELECT MAX(sequence_number) FROM settlement_batches
+                              WHERE settlement_date = $3 AND currency = $9 AND window_id IS NOT DISTINCT FROM $13), 0) + 1)
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             "#,
         )
         .bind(batch.id)
@@ -36,9 +66,13 @@ impl BatchRepository {
         .bind(&batch.metadata)
         .bind(batch.created_at)
         .bind(batch.completed_at)
-        .fetch_one(&self.pool)
+        .bind(batch.window_id)
+        .bind(batch.netting_mode)
+        .bind(batch.cut_off_approval)
+        .bind(batch.instruction_strategy)
+        .fetch_one(&mut **tx)
         .await
-        .map_err(AppError::Database)?;
+        .map_err(|e| Self::map_insert_error(e, batch))?;
 
         Ok(row)
     }
@@ -47,7 +81,7 @@ impl BatchRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<SettlementBatch>> {
         let row = sqlx::query_as::<_, SettlementBatch>(
             r#"
-            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             FROM settlement_batches
             WHERE id = $1
             "#,
@@ -64,7 +98,7 @@ impl BatchRepository {
     pub async fn find_by_status(&self, status: BatchStatus) -> Result<Vec<SettlementBatch>> {
         let rows = sqlx::query_as::<_, SettlementBatch>(
             r#"
-            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             FROM settlement_batches
             WHERE status = $1
             ORDER BY created_at DESC
@@ -78,7 +112,7 @@ impl BatchRepository {
         Ok(rows)
     }
 
-    /// Finds the current open batch for a settlement date and currency.
+    /// Finds the latest open batch for a settlement date and currency outside any named window.
     pub async fn find_open_batch(
         &self,
         settlement_date: NaiveDate,
@@ -86,10 +120,10 @@ impl BatchRepository {
     ) -> Result<Option<SettlementBatch>> {
         let row = sqlx::query_as::<_, SettlementBatch>(
             r#"
-            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             FROM settlement_batches
-            WHERE settlement_date = $1 AND currency = $2 AND status = 'PENDING'
-            ORDER BY created_at DESC
+            WHERE settlement_date = $1 AND currency = $2 AND status = 'PENDING' AND window_id IS NULL
+            ORDER BY sequence_number DESC, created_at DESC
             LIMIT 1
             "#,
         )
@@ -102,20 +136,118 @@ impl BatchRepository {
         Ok(row)
     }
 
-    /// Lists batches with pagination.
+    /// Finds the latest open batch of a named settlement window on a settlement date.
+    pub async fn find_open_batch_for_window(
+        &self,
+        window_id: Uuid,
+        settlement_date: NaiveDate,
+    ) -> Result<Option<SettlementBatch>> {
+        let row = sqlx::query_as::<_, SettlementBatch>(
+            r#"
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
+            FROM settlement_batches
+            WHERE window_id = $1 AND settlement_date = $2 AND status = 'PENDING'
+            ORDER BY sequence_number DESC, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(window_id)
+        .bind(settlement_date)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a batch by ID and locks its row until the database transaction ends.
+    pub async fn find_for_update_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Option<SettlementBatch>> {
+        let row = sqlx::query_as::<_, SettlementBatch>(
+            r#"
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
+            FROM settlement_batches
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the latest open batch for a settlement date, currency and named window (None
+    /// for batches outside any window) within an open database transaction.
+    pub async fn find_open_batch_in(
+        tx: &mut Transaction<'_, Postgres>,
+        settlement_date: NaiveDate,
+        currency: &str,
+        window_id: Option<Uuid>,
+    ) -> Result<Option<SettlementBatch>> {
+        let row = sqlx::query_as::<_, SettlementBatch>(
+            r#"
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
+            FROM settlement_batches
+            WHERE settlement_date = $1 AND currency = $2 AND status = 'PENDING'
+              AND window_id IS NOT DISTINCT FROM $3
+            ORDER BY sequence_number DESC, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(settlement_date)
+        .bind(currency)
+        .bind(window_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Serializes opening batches in a settlement date, currency and named window (None
+    /// for batches outside any window) until `tx` ends, so whoever opens one next sees
+    /// the batches opened before.
+    /// Advisory locks are re-entrant, so taking it again in the same transaction is
+    /// harmless.
+    pub async fn lock_window_in(
+        tx: &mut Transaction<'_, Postgres>,
+        settlement_date: NaiveDate,
+        currency: &str,
+        window_id: Option<Uuid>,
+    ) -> Result<()> {
+        let key = format!(
+            "batch-window:{}:{}:{}",
+            settlement_date,
+            currency,
+            window_id.map(|id| id.to_string()).unwrap_or_default()
+        );
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(key)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Lists batches with pagination, optionally of one status, currency or named window.
     pub async fn list(
         &self,
         status: Option<BatchStatus>,
         currency: Option<&str>,
+        window_id: Option<Uuid>,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<SettlementBatch>> {
         let rows = sqlx::query_as::<_, SettlementBatch>(
             r#"
-            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             FROM settlement_batches
             WHERE ($1::batch_status IS NULL OR status = $1)
               AND ($2::text IS NULL OR currency = $2)
+              AND ($5::uuid IS NULL OR window_id = $5)
             ORDER BY created_at DESC
             LIMIT $3 OFFSET $4
             "#,
@@ -124,6 +256,7 @@ impl BatchRepository {
         .bind(currency)
         .bind(limit)
         .bind(offset)
+        .bind(window_id)
         .fetch_all(&self.pool)
         .await
         .map_err(AppError::Database)?;
@@ -136,6 +269,18 @@ impl BatchRepository {
         &self,
         id: Uuid,
         status: BatchStatus,
+    ) -> Result<Option<SettlementBatch>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let row = Self::update_status_in(&mut tx, id, status).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Updates batch status within an open database transaction.
+    pub async fn update_status_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        status: BatchStatus,
     ) -> Result<Option<SettlementBatch>> {
         let completed_at = if status == BatchStatus::Completed || status == BatchStatus::Failed {
             Some(Utc::now())
@@ -148,13 +293,13 @@ impl BatchRepository {
             UPDATE settlement_batches
             SET status = $2, completed_at = COALESCE($3, completed_at)
             WHERE id = $1
-            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             "#,
         )
         .bind(id)
         .bind(status)
         .bind(completed_at)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut **tx)
         .await
         .map_err(AppError::Database)?;
 
@@ -175,7 +320,7 @@ impl BatchRepository {
             UPDATE settlement_batches
             SET total_transactions = $2, gross_amount = $3, net_amount = $4, fee_amount = $5
             WHERE id = $1
-            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             "#,
         )
         .bind(id)
@@ -190,12 +335,131 @@ impl BatchRepository {
         Ok(row)
     }
 
+    /// Sets how a batch is netted while it is still pending. Returns `None` if the batch
+    /// doesn't exist or has started processing.
+    pub async fn update_netting_mode(&self, id: Uuid, netting_mode: NettingMode) -> Result<Option<SettlementBatch>> {
+        let row = sqlx::query_as::<_, SettlementBatch>(
+            r#"
+            UPDATE settlement_batches
+            SET netting_mode = $2
+            WHERE id = $1 AND status = 'PENDING'
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
+            "#,
+        )
+        .bind(id)
+        .bind(netting_mode)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Sets a pending batch's instruction strategy, or clears it with `None`. Returns
+    /// `None` if the batch doesn't exist or has started processing.
+    pub async fn update_instruction_strategy(
+        &self,
+        id: Uuid,
+        strategy: Option<InstructionStrategy>,
+    ) -> Result<Option<SettlementBatch>> {
+        let row = sqlx::query_as::<_, SettlementBatch>(
+            r#"
+            UPDATE settlement_batches
+            SET instruction_strategy = $2
+            WHERE id = $1 AND status = 'PENDING'
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
+            "#,
+        )
+        .bind(id)
+        .bind(strategy)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Moves a pending batch's cut-off within an open database transaction and records
+    /// the approved change alongside it. Returns `None` if the batch is no longer pending.
+    pub async fn update_cut_off_in(
+        tx: &mut Transaction<'_, Postgres>,
+        change: &BatchCutOffChange,
+    ) -> Result<Option<SettlementBatch>> {
+        let row = sqlx::query_as::<_, SettlementBatch>(
+            r#"
+            UPDATE settlement_batches
+            SET cut_off_time = $2
+            WHERE id = $1 AND status = 'PENDING'
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
+            "#,
+        )
+        .bind(change.batch_id)
+        .bind(change.new_cut_off)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if row.is_some() {
+            sqlx::query(
+                r#"
+                INSERT INTO batch_cut_off_changes (id, batch_id, kind, previous_cut_off, new_cut_off, requested_by, approved_by, reason, recorded_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                "#,
+            )
+            .bind(change.id)
+            .bind(change.batch_id)
+            .bind(change.kind)
+            .bind(change.previous_cut_off)
+            .bind(change.new_cut_off)
+            .bind(&change.requested_by)
+            .bind(&change.approved_by)
+            .bind(&change.reason)
+            .bind(change.recorded_at)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        Ok(row)
+    }
+
+    /// Gets the cut-off changes made to a batch, oldest first.
+    pub async fn find_cut_off_changes(&self, batch_id: Uuid) -> Result<Vec<BatchCutOffChange>> {
+        let rows = sqlx::query_as::<_, BatchCutOffChange>(
+            r#"
+            SELECT id, batch_id, kind, previous_cut_off, new_cut_off, requested_by, approved_by, reason, recorded_at
+            FROM batch_cut_off_changes
+            WHERE batch_id = $1
+            ORDER BY recorded_at, id
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
     /// Increments batch totals atomically when adding a transaction.
     pub async fn increment_totals(
         &self,
         id: Uuid,
         amount: Decimal,
         fee: Decimal,
+    ) -> Result<Option<SettlementBatch>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let row = Self::increment_totals_in(&mut tx, id, amount, fee).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Increments batch totals within an open database transaction.
+    pub async fn increment_totals_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        amount: Decimal,
+        fee: Decimal,
     ) -> Result<Option<SettlementBatch>> {
         let row = sqlx::query_as::<_, SettlementBatch>(
             r#"
@@ -204,13 +468,13 @@ impl BatchRepository {
                 gross_amount = gross_amount + $2,
                 fee_amount = fee_amount + $3
             WHERE id = $1 AND status = 'PENDING'
-            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             "#,
         )
         .bind(id)
         .bind(amount)
         .bind(fee)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut **tx)
         .await
         .map_err(AppError::Database)?;
 
@@ -223,6 +487,19 @@ impl BatchRepository {
         id: Uuid,
         amount: Decimal,
         fee: Decimal,
+    ) -> Result<Option<SettlementBatch>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let row = Self::decrement_totals_in(&mut tx, id, amount, fee).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Decrements batch totals within an open database transaction.
+    pub async fn decrement_totals_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        amount: Decimal,
+        fee: Decimal,
     ) -> Result<Option<SettlementBatch>> {
         let row = sqlx::query_as::<_, SettlementBatch>(
             r#"
@@ -231,13 +508,13 @@ impl BatchRepository {
                 gross_amount = gross_amount - $2,
                 fee_amount = fee_amount - $3
             WHERE id = $1 AND status = 'PENDING'
-            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            RETURNING id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             "#,
         )
         .bind(id)
         .bind(amount)
         .bind(fee)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut **tx)
         .await
         .map_err(AppError::Database)?;
 
@@ -248,7 +525,7 @@ impl BatchRepository {
     pub async fn find_ready_for_processing(&self) -> Result<Vec<SettlementBatch>> {
         let rows = sqlx::query_as::<_, SettlementBatch>(
             r#"
-            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             FROM settlement_batches
             WHERE status = 'PENDING' AND cut_off_time <= NOW()
             ORDER BY cut_off_time
@@ -268,7 +545,7 @@ impl BatchRepository {
     ) -> Result<Vec<SettlementBatch>> {
         let rows = sqlx::query_as::<_, SettlementBatch>(
             r#"
-            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at
+            SELECT id, status, settlement_date, cut_off_time, total_transactions, gross_amount, net_amount, fee_amount, currency, metadata, created_at, completed_at, sequence_number, window_id, netting_mode, cut_off_approval, instruction_strategy
             FROM settlement_batches
             WHERE settlement_date = $1
             ORDER BY created_at
@@ -0,0 +1,145 @@
+use crate::error::{AppError, Result};
+use crate::models::BatchTemplate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for batch templates.
+pub struct BatchTemplateRepository {
+    pool: PgPool,
+}
+
+impl BatchTemplateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates a new batch template.
+    pub async fn create(&self, template: &BatchTemplate) -> Result<BatchTemplate> {
+        let row = sqlx::query_as::<_, BatchTemplate>(
+            r#"
+            INSERT INTO batch_templates (id, name, currency, window_id, cut_off_time, metadata, netting_mode, cut_off_approval, active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, name, currency, window_id, cut_off_time, metadata, netting_mode, cut_off_approval, active, created_at, updated_at
+            "#,
+        )
+        .bind(template.id)
+        .bind(&template.name)
+        .bind(&template.currency)
+        .bind(template.window_id)
+        .bind(template.cut_off_time)
+        .bind(&template.metadata)
+        .bind(template.netting_mode)
+        .bind(template.cut_off_approval)
+        .bind(template.active)
+        .bind(template.created_at)
+        .bind(template.updated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a batch template by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<BatchTemplate>> {
+        let row = sqlx::query_as::<_, BatchTemplate>(
+            r#"
+            SELECT id, name, currency, window_id, cut_off_time, metadata, netting_mode, cut_off_approval, active, created_at, updated_at
+            FROM batch_templates
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a currency's batch template by name.
+    pub async fn find_by_name(&self, currency: &str, name: &str) -> Result<Option<BatchTemplate>> {
+        let row = sqlx::query_as::<_, BatchTemplate>(
+            r#"
+            SELECT id, name, currency, window_id, cut_off_time, metadata, netting_mode, cut_off_approval, active, created_at, updated_at
+            FROM batch_templates
+            WHERE currency = $1 AND name = $2
+            "#,
+        )
+        .bind(currency)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the active template of a currency and window (None for no window).
+    pub async fn find_active_for_slot(&self, currency: &str, window_id: Option<Uuid>) -> Result<Option<BatchTemplate>> {
+        let row = sqlx::query_as::<_, BatchTemplate>(
+            r#"
+            SELECT id, name, currency, window_id, cut_off_time, metadata, netting_mode, cut_off_approval, active, created_at, updated_at
+            FROM batch_templates
+            WHERE currency = $1 AND window_id IS NOT DISTINCT FROM $2 AND active
+            "#,
+        )
+        .bind(currency)
+        .bind(window_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists batch templates, optionally for one currency and only active ones.
+    pub async fn list(&self, currency: Option<&str>, active_only: bool) -> Result<Vec<BatchTemplate>> {
+        let rows = sqlx::query_as::<_, BatchTemplate>(
+            r#"
+            SELECT id, name, currency, window_id, cut_off_time, metadata, netting_mode, cut_off_approval, active, created_at, updated_at
+            FROM batch_templates
+            WHERE ($1::VARCHAR IS NULL OR currency = $1)
+              AND (NOT $2 OR active)
+            ORDER BY currency, name
+            "#,
+        )
+        .bind(currency)
+        .bind(active_only)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Updates the mutable fields of a batch template.
+    pub async fn update(&self, template: &BatchTemplate) -> Result<Option<BatchTemplate>> {
+        let row = sqlx::query_as::<_, BatchTemplate>(
+            r#"
+            UPDATE batch_templates
+            SET name = $2,
+                cut_off_time = $3,
+                metadata = $4,
+                netting_mode = $5,
+                cut_off_approval = $6,
+                active = $7,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, currency, window_id, cut_off_time, metadata, netting_mode, cut_off_approval, active, created_at, updated_at
+            "#,
+        )
+        .bind(template.id)
+        .bind(&template.name)
+        .bind(template.cut_off_time)
+        .bind(&template.metadata)
+        .bind(template.netting_mode)
+        .bind(template.cut_off_approval)
+        .bind(template.active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::error::{AppError, Result};
+use crate::models::BilateralPairRecord;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for the net pair obligations of bilaterally netted batches.
+pub struct BilateralPairRepository {
+    pool: PgPool,
+}
+
+impl BilateralPairRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores a batch's pairs in a single transaction, replacing any stored by an
+    /// earlier netting run.
+    pub async fn upsert_batch(&self, pairs: &[BilateralPairRecord]) -> Result<Vec<BilateralPairRecord>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let mut stored = Vec::with_capacity(pairs.len());
+
+        for pair in pairs {
+            let row = sqlx::query_as::<_, BilateralPairRecord>(
+                r#"
+                INSERT INTO bilateral_pairs (batch_id, participant_a, participant_b, currency, a_to_b_gross, b_to_a_gross, net_amount, transaction_count, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (batch_id, participant_a, participant_b, currency)
+                DO UPDATE SET
+                    a_to_b_gross = EXCLUDED.a_to_b_gross,
+                    b_to_a_gross = EXCLUDED.b_to_a_gross,
+                    net_amount = EXCLUDED.net_amount,
+                    transaction_count = EXCLUDED.transaction_count
+                RETURNING batch_id, participant_a, participant_b, currency, a_to_b_gross, b_to_a_gross, net_amount, transaction_count, created_at
+                "#,
+            )
+            .bind(pair.batch_id)
+            .bind(pair.participant_a)
+            .bind(pair.participant_b)
+            .bind(&pair.currency)
+            .bind(pair.a_to_b_gross)
+            .bind(pair.b_to_a_gross)
+            .bind(pair.net_amount)
+            .bind(pair.transaction_count)
+            .bind(pair.created_at)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+            stored.push(row);
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(stored)
+    }
+
+    /// Finds all pairs of a batch, largest net obligation first.
+    pub async fn find_by_batch(&self, batch_id: Uuid) -> Result<Vec<BilateralPairRecord>> {
+        let rows = sqlx::query_as::<_, BilateralPairRecord>(
+            r#"
+            SELECT batch_id, participant_a, participant_b, currency, a_to_b_gross, b_to_a_gross, net_amount, transaction_count, created_at
+            FROM bilateral_pairs
+            WHERE batch_id = $1
+            ORDER BY ABS(net_amount) DESC, participant_a, participant_b
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds the pairs a participant is part of across batches, newest first.
+    pub async fn find_by_participant(&self, participant_id: Uuid) -> Result<Vec<BilateralPairRecord>> {
+        let rows = sqlx::query_as::<_, BilateralPairRecord>(
+            r#"
+            SELECT batch_id, participant_a, participant_b, currency, a_to_b_gross, b_to_a_gross, net_amount, transaction_count, created_at
+            FROM bilateral_pairs
+            WHERE participant_a = $1 OR participant_b = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(participant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Deletes all pairs of a batch.
+    pub async fn delete_by_batch(&self, batch_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM bilateral_pairs
+            WHERE batch_id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::error::{AppError, Result};
+use crate::models::{CounterpartyAuditAction, CounterpartyRestriction, CounterpartyRestrictionAudit};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for counterparty restrictions and their audit log.
+pub struct CounterpartyRepository {
+    pool: PgPool,
+}
+
+impl CounterpartyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Adds a restriction, replacing any existing entry for the same counterparty.
+    /// The replaced entry and the new one are both written to the audit log.
+    pub async fn upsert(&self, restriction: &CounterpartyRestriction) -> Result<CounterpartyRestriction> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let replaced = sqlx::query_as::<_, CounterpartyRestriction>(
+            r#"
+            DELETE FROM counterparty_restrictions
+            WHERE account_id = $1 AND counterparty_id = $2
+            RETURNING id, account_id, counterparty_id, mode, reason, created_at
+            "#,
+        )
+        .bind(restriction.account_id)
+        .bind(restriction.counterparty_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if let Some(replaced) = &replaced {
+            let entry = CounterpartyRestrictionAudit::new(replaced, CounterpartyAuditAction::Removed);
+            Self::audit(&mut tx, &entry).await?;
+        }
+
+        let row = sqlx::query_as::<_, CounterpartyRestriction>(
+            r#"
+            INSERT INTO counterparty_restrictions (id, account_id, counterparty_id, mode, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, account_id, counterparty_id, mode, reason, created_at
+            "#,
+        )
+        .bind(restriction.id)
+        .bind(restriction.account_id)
+        .bind(restriction.counterparty_id)
+        .bind(restriction.mode)
+        .bind(&restriction.reason)
+        .bind(restriction.created_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        let entry = CounterpartyRestrictionAudit::new(&row, CounterpartyAuditAction::Added);
+        Self::audit(&mut tx, &entry).await?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Removes the restriction for a counterparty and audits it. Returns the removed entry.
+    pub async fn delete(
+        &self,
+        account_id: Uuid,
+        counterparty_id: Uuid,
+    ) -> Result<Option<CounterpartyRestriction>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let removed = sqlx::query_as::<_, CounterpartyRestriction>(
+            r#"
+            DELETE FROM counterparty_restrictions
+            WHERE account_id = $1 AND counterparty_id = $2
+            RETURNING id, account_id, counterparty_id, mode, reason, created_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(counterparty_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if let Some(removed) = &removed {
+            let entry = CounterpartyRestrictionAudit::new(removed, CounterpartyAuditAction::Removed);
+            Self::audit(&mut tx, &entry).await?;
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(removed)
+    }
+
+    /// Lists an account's restrictions.
+    pub async fn find_by_account(&self, account_id: Uuid) -> Result<Vec<CounterpartyRestriction>> {
+        let rows = sqlx::query_as::<_, CounterpartyRestriction>(
+            r#"
+            SELECT id, account_id, counterparty_id, mode, reason, created_at
+            FROM counterparty_restrictions
+            WHERE account_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Lists an account's restriction changes, oldest first.
+    pub async fn find_audit_by_account(&self, account_id: Uuid) -> Result<Vec<CounterpartyRestrictionAudit>> {
+        let rows = sqlx::query_as::<_, CounterpartyRestrictionAudit>(
+            r#"
+            SELECT id, account_id, counterparty_id, mode, action, reason, changed_at
+            FROM counterparty_restriction_audit
+            WHERE account_id = $1
+            ORDER BY changed_at
+            "#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    async fn audit(
+        tx: &mut Transaction<'_, Postgres>,
+        entry: &CounterpartyRestrictionAudit,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO counterparty_restriction_audit (id, account_id, counterparty_id, mode, action, reason, changed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(entry.id)
+        .bind(entry.account_id)
+        .bind(entry.counterparty_id)
+        .bind(entry.mode)
+        .bind(entry.action)
+        .bind(&entry.reason)
+        .bind(entry.changed_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
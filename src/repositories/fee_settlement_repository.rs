@@ -0,0 +1,123 @@
+use crate::error::{AppError, Result};
+use crate::models::{FeeAccrual, FeeSettlement};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for fees accrued in fees-receivable accounts and their settlements.
+pub struct FeeSettlementRepository {
+    pool: PgPool,
+}
+
+impl FeeSettlementRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Serializes fee settlements of a batch until the database transaction ends.
+    pub async fn lock_batch_in(tx: &mut Transaction<'_, Postgres>, batch_id: Uuid) -> Result<()> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(format!("fee-settlement:{}", batch_id))
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Finds the unsettled fee legs on a fees-receivable account that belong to a batch.
+    /// A fee accrues with its payment, for the payer; reversals and refunds of the fee
+    /// reference the original fee leg and count against the batch of its payment.
+    pub async fn find_unsettled_in(
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: Uuid,
+        receivable_account_id: Uuid,
+        currency: &str,
+    ) -> Result<Vec<FeeAccrual>> {
+        let rows = sqlx::query_as::<_, FeeAccrual>(
+            r#"
+            SELECT e.id AS ledger_entry_id,
+                   CASE WHEN e.entry_type = 'CREDIT' THEN t.source_account_id ELSE t.destination_account_id END AS account_id,
+                   e.entry_type, e.amount
+            FROM ledger_entries e
+            JOIN transactions t ON t.id = e.transaction_id
+            LEFT JOIN ledger_entries original ON original.id = (e.metadata->>'original_fee_entry_id')::uuid
+            LEFT JOIN transactions original_tx ON original_tx.id = original.transaction_id
+            WHERE e.account_id = $2 AND e.currency = $3
+              AND e.metadata->>'leg' = 'FEE'
+              AND e.metadata->>'fee_settlement_id' IS NULL
+              AND COALESCE(original_tx.settlement_batch_id, t.settlement_batch_id) = $1
+              AND NOT EXISTS (SELECT 1 FROM fee_settlement_entries s WHERE s.ledger_entry_id = e.id)
+            ORDER BY e.created_at, e.id
+            "#,
+        )
+        .bind(batch_id)
+        .bind(receivable_account_id)
+        .bind(currency)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Records a settlement and the fee legs it settled.
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        settlement: &FeeSettlement,
+        ledger_entry_ids: &[Uuid],
+    ) -> Result<FeeSettlement> {
+        let row = sqlx::query_as::<_, FeeSettlement>(
+            r#"
+            INSERT INTO fee_settlements (id, batch_id, account_id, currency, accrued_amount, reversed_amount, net_amount, fee_count, transaction_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, batch_id, account_id, currency, accrued_amount, reversed_amount, net_amount, fee_count, transaction_id, created_at
+            "#,
+        )
+        .bind(settlement.id)
+        .bind(settlement.batch_id)
+        .bind(settlement.account_id)
+        .bind(&settlement.currency)
+        .bind(settlement.accrued_amount)
+        .bind(settlement.reversed_amount)
+        .bind(settlement.net_amount)
+        .bind(settlement.fee_count)
+        .bind(settlement.transaction_id)
+        .bind(settlement.created_at)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO fee_settlement_entries (ledger_entry_id, fee_settlement_id)
+            SELECT UNNEST($1::uuid[]), $2
+            "#,
+        )
+        .bind(ledger_entry_ids)
+        .bind(settlement.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a batch's fee settlements, optionally for one payer, oldest first.
+    pub async fn find_by_batch(&self, batch_id: Uuid, account_id: Option<Uuid>) -> Result<Vec<FeeSettlement>> {
+        let rows = sqlx::query_as::<_, FeeSettlement>(
+            r#"
+            SELECT id, batch_id, account_id, currency, accrued_amount, reversed_amount, net_amount, fee_count, transaction_id, created_at
+            FROM fee_settlements
+            WHERE batch_id = $1 AND ($2::UUID IS NULL OR account_id = $2)
+            ORDER BY created_at, account_id
+            "#,
+        )
+        .bind(batch_id)
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
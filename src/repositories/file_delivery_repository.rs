@@ -0,0 +1,172 @@
+use crate::error::{AppError, Result};
+use crate::models::{DeliveryStatus, FileDelivery};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for queued file deliveries.
+pub struct FileDeliveryRepository {
+    pool: PgPool,
+}
+
+impl FileDeliveryRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queues a new delivery.
+    pub async fn create(&self, delivery: &FileDelivery) -> Result<FileDelivery> {
+        let row = sqlx::query_as::<_, FileDelivery>(
+            r#"
+            INSERT INTO file_deliveries (id, destination, file_name, content, checksum_sha256, status, attempts, max_attempts, next_attempt_at, last_error, delivered_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING id, destination, file_name, content, checksum_sha256, status, attempts, max_attempts, next_attempt_at, last_error, delivered_at, created_at, updated_at
+            "#,
+        )
+        .bind(delivery.id)
+        .bind(&delivery.destination)
+        .bind(&delivery.file_name)
+        .bind(&delivery.content)
+        .bind(&delivery.checksum_sha256)
+        .bind(delivery.status)
+        .bind(delivery.attempts)
+        .bind(delivery.max_attempts)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.delivered_at)
+        .bind(delivery.created_at)
+        .bind(delivery.updated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a delivery by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<FileDelivery>> {
+        let row = sqlx::query_as::<_, FileDelivery>(
+            r#"
+            SELECT id, destination, file_name, content, checksum_sha256, status, attempts, max_attempts, next_attempt_at, last_error, delivered_at, created_at, updated_at
+            FROM file_deliveries
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists deliveries, newest first.
+    pub async fn list(
+        &self,
+        status: Option<DeliveryStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FileDelivery>> {
+        let rows = sqlx::query_as::<_, FileDelivery>(
+            r#"
+            SELECT id, destination, file_name, content, checksum_sha256, status, attempts, max_attempts, next_attempt_at, last_error, delivered_at, created_at, updated_at
+            FROM file_deliveries
+            WHERE ($1::delivery_status IS NULL OR status = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Claims up to `limit` pending deliveries whose next attempt is due, marking them
+    /// as delivering and counting the attempt. Concurrent workers claim disjoint rows.
+    pub async fn claim_due(&self, limit: i64) -> Result<Vec<FileDelivery>> {
+        let rows = sqlx::query_as::<_, FileDelivery>(
+            r#"
+            UPDATE file_deliveries
+            SET status = 'DELIVERING', attempts = attempts + 1, updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM file_deliveries
+                WHERE status = 'PENDING' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, destination, file_name, content, checksum_sha256, status, attempts, max_attempts, next_attempt_at, last_error, delivered_at, created_at, updated_at
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Saves the outcome of an attempt.
+    pub async fn update(&self, delivery: &FileDelivery) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE file_deliveries
+            SET status = $2, attempts = $3, next_attempt_at = $4, last_error = $5, delivered_at = $6, updated_at = $7
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery.id)
+        .bind(delivery.status)
+        .bind(delivery.attempts)
+        .bind(delivery.next_attempt_at)
+        .bind(&delivery.last_error)
+        .bind(delivery.delivered_at)
+        .bind(delivery.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Returns deliveries left delivering since before `cutoff` (e.g. by a worker that
+    /// crashed mid-upload) to the pending queue.
+    pub async fn release_stale(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE file_deliveries
+            SET status = 'PENDING', next_attempt_at = NOW(), updated_at = NOW()
+            WHERE status = 'DELIVERING' AND updated_at < $1
+            "#,
+        )
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Requeues a failed delivery with a fresh set of attempts.
+    pub async fn requeue(&self, id: Uuid) -> Result<Option<FileDelivery>> {
+        let row = sqlx::query_as::<_, FileDelivery>(
+            r#"
+            UPDATE file_deliveries
+            SET status = 'PENDING', attempts = 0, next_attempt_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'FAILED'
+            RETURNING id, destination, file_name, content, checksum_sha256, status, attempts, max_attempts, next_attempt_at, last_error, delivered_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
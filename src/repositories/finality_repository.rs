@@ -0,0 +1,102 @@
+use crate::error::{AppError, Result};
+use crate::models::{FinalityRecord, SettlementRoute};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for settlement finality records.
+pub struct FinalityRepository {
+    pool: PgPool,
+}
+
+impl FinalityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records finality for a single transaction. Finality is only reached once, so a
+    /// repeated call returns the existing record unchanged.
+    pub async fn record(
+        &self,
+        transaction_id: Uuid,
+        batch_id: Option<Uuid>,
+        route: SettlementRoute,
+    ) -> Result<FinalityRecord> {
+        sqlx::query(
+            r#"
+            INSERT INTO finality (transaction_id, batch_id, settlement_route)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (transaction_id) DO NOTHING
+            "#,
+        )
+        .bind(transaction_id)
+        .bind(batch_id)
+        .bind(route)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        self.find_by_transaction(transaction_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Finality record not found after insert".to_string()))
+    }
+
+    /// Records finality for a batch's transactions in the given order, so sequences follow
+    /// the batch's release order. Transactions that are already final are skipped.
+    pub async fn record_batch(
+        &self,
+        batch_id: Uuid,
+        transaction_ids: &[Uuid],
+    ) -> Result<Vec<FinalityRecord>> {
+        sqlx::query(
+            r#"
+            INSERT INTO finality (transaction_id, batch_id, settlement_route)
+            SELECT t.id, $1, 'NETTED'::settlement_route
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS t(id, ord)
+            ORDER BY t.ord
+            ON CONFLICT (transaction_id) DO NOTHING
+            "#,
+        )
+        .bind(batch_id)
+        .bind(transaction_ids)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        self.find_by_batch(batch_id).await
+    }
+
+    /// Finds the finality record for a transaction.
+    pub async fn find_by_transaction(&self, transaction_id: Uuid) -> Result<Option<FinalityRecord>> {
+        let row = sqlx::query_as::<_, FinalityRecord>(
+            r#"
+            SELECT sequence, transaction_id, batch_id, settlement_route, final_at
+            FROM finality
+            WHERE transaction_id = $1
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds all finality records for a batch in sequence order.
+    pub async fn find_by_batch(&self, batch_id: Uuid) -> Result<Vec<FinalityRecord>> {
+        let rows = sqlx::query_as::<_, FinalityRecord>(
+            r#"
+            SELECT sequence, transaction_id, batch_id, settlement_route, final_at
+            FROM finality
+            WHERE batch_id = $1
+            ORDER BY sequence
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,234 @@
+use crate::error::{AppError, Result};
+use crate::models::{FundingObligation, FundingStatus};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for net payers' funding obligations.
+pub struct FundingRepository {
+    pool: PgPool,
+}
+
+impl FundingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records an obligation unless the participant already has one in the batch, and
+    /// returns the obligation on record.
+    pub async fn create(&self, obligation: &FundingObligation) -> Result<FundingObligation> {
+        sqlx::query(
+            r#"
+            INSERT INTO funding_obligations (id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (batch_id, participant_id) DO NOTHING
+            "#,
+        )
+        .bind(obligation.id)
+        .bind(obligation.batch_id)
+        .bind(obligation.participant_id)
+        .bind(&obligation.currency)
+        .bind(obligation.amount)
+        .bind(obligation.funded_amount)
+        .bind(obligation.control_account_id)
+        .bind(obligation.deadline)
+        .bind(obligation.status)
+        .bind(obligation.created_at)
+        .bind(obligation.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        self.find_by_batch_and_participant(obligation.batch_id, obligation.participant_id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Funding obligation vanished after insert")))
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<FundingObligation>> {
+        let row = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            FROM funding_obligations
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_by_batch_and_participant(
+        &self,
+        batch_id: Uuid,
+        participant_id: Uuid,
+    ) -> Result<Option<FundingObligation>> {
+        let row = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            FROM funding_obligations
+            WHERE batch_id = $1 AND participant_id = $2
+            "#,
+        )
+        .bind(batch_id)
+        .bind(participant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a batch's obligations, earliest deadline first.
+    pub async fn find_by_batch(&self, batch_id: Uuid) -> Result<Vec<FundingObligation>> {
+        let rows = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            FROM funding_obligations
+            WHERE batch_id = $1
+            ORDER BY deadline, participant_id
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Lists obligations, optionally in one status, earliest deadline first.
+    pub async fn list(&self, status: Option<FundingStatus>, limit: i64, offset: i64) -> Result<Vec<FundingObligation>> {
+        let rows = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            FROM funding_obligations
+            WHERE ($1::funding_status IS NULL OR status = $1)
+            ORDER BY deadline, participant_id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds obligations still awaiting funds, earliest deadline first.
+    pub async fn find_open(&self, limit: i64) -> Result<Vec<FundingObligation>> {
+        let rows = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            FROM funding_obligations
+            WHERE status IN ('PENDING', 'OVERDUE')
+            ORDER BY deadline
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Sums the settled credits matched to an obligation: transactions from the
+    /// participant to its control account carrying the obligation's funding reference.
+    pub async fn matched_credits(&self, obligation: &FundingObligation) -> Result<Decimal> {
+        let Some(control_account_id) = obligation.control_account_id else {
+            return Ok(Decimal::ZERO);
+        };
+
+        let total: Option<Decimal> = sqlx::query_scalar(
+            r#"
+            SELECT SUM(amount)
+            FROM transactions
+            WHERE source_account_id = $1
+              AND destination_account_id = $2
+              AND currency = $3
+              AND reference = $4
+              AND status = 'SETTLED'
+            "#,
+        )
+        .bind(obligation.participant_id)
+        .bind(control_account_id)
+        .bind(&obligation.currency)
+        .bind(obligation.funding_reference())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(total.unwrap_or(Decimal::ZERO))
+    }
+
+    /// Records the funds matched to an open obligation and its resulting status.
+    pub async fn update_funding(&self, obligation: &FundingObligation) -> Result<Option<FundingObligation>> {
+        let row = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            UPDATE funding_obligations
+            SET funded_amount = $2, status = $3, funded_at = $4, updated_at = NOW()
+            WHERE id = $1 AND status IN ('PENDING', 'OVERDUE')
+            RETURNING id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            "#,
+        )
+        .bind(obligation.id)
+        .bind(obligation.funded_amount)
+        .bind(obligation.status)
+        .bind(obligation.funded_at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Marks a pending obligation overdue within an open database transaction. Returns
+    /// `None` if it is no longer pending, e.g. funded or escalated concurrently.
+    pub async fn mark_overdue_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        escalated_at: DateTime<Utc>,
+    ) -> Result<Option<FundingObligation>> {
+        let row = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            UPDATE funding_obligations
+            SET status = 'OVERDUE', escalated_at = $2, updated_at = NOW()
+            WHERE id = $1 AND status = 'PENDING'
+            RETURNING id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(escalated_at)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Marks an overdue obligation as defaulted.
+    pub async fn mark_defaulted(&self, id: Uuid) -> Result<Option<FundingObligation>> {
+        let row = sqlx::query_as::<_, FundingObligation>(
+            r#"
+            UPDATE funding_obligations
+            SET status = 'DEFAULTED', updated_at = NOW()
+            WHERE id = $1 AND status = 'OVERDUE'
+            RETURNING id, batch_id, participant_id, currency, amount, funded_amount, control_account_id, deadline, status, funded_at, escalated_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
@@ -0,0 +1,304 @@
+use crate::error::{AppError, Result};
+use crate::models::{FxConversion, FxRate, FxRateAdjustment};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for FX rates, the conversions made at them and the adjustments posted
+/// when they are corrected.
+pub struct FxRepository {
+    pool: PgPool,
+}
+
+impl FxRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_rate(&self, rate: &FxRate) -> Result<FxRate> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let created = Self::create_rate_in(&mut tx, rate).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(created)
+    }
+
+    pub async fn create_rate_in(tx: &mut Transaction<'_, Postgres>, rate: &FxRate) -> Result<FxRate> {
+        sqlx::query_as::<_, FxRate>(
+            r#"
+            INSERT INTO fx_rates (id, base_currency, quote_currency, rate, spread, source, effective_at, corrects_rate_id, correction_reason, superseded_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, base_currency, quote_currency, rate, spread, source, effective_at, corrects_rate_id, correction_reason, superseded_by, created_at
+            "#,
+        )
+        .bind(rate.id)
+        .bind(&rate.base_currency)
+        .bind(&rate.quote_currency)
+        .bind(rate.rate)
+        .bind(rate.spread)
+        .bind(&rate.source)
+        .bind(rate.effective_at)
+        .bind(rate.corrects_rate_id)
+        .bind(&rate.correction_reason)
+        .bind(rate.superseded_by)
+        .bind(rate.created_at)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn find_rate(&self, id: Uuid) -> Result<Option<FxRate>> {
+        let row = sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT id, base_currency, quote_currency, rate, spread, source, effective_at, corrects_rate_id, correction_reason, superseded_by, created_at
+            FROM fx_rates
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a rate and locks it until the transaction ends.
+    pub async fn find_rate_for_update_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Option<FxRate>> {
+        sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT id, base_currency, quote_currency, rate, spread, source, effective_at, corrects_rate_id, correction_reason, superseded_by, created_at
+            FROM fx_rates
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// The rate of a pair in effect at a time: the latest one effective by then that has
+    /// not been superseded by a correction.
+    pub async fn find_rate_at(&self, base_currency: &str, quote_currency: &str, at: DateTime<Utc>) -> Result<Option<FxRate>> {
+        let row = sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT id, base_currency, quote_currency, rate, spread, source, effective_at, corrects_rate_id, correction_reason, superseded_by, created_at
+            FROM fx_rates
+            WHERE base_currency = $1 AND quote_currency = $2 AND effective_at <= $3 AND superseded_by IS NULL
+            ORDER BY effective_at DESC, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(base_currency)
+        .bind(quote_currency)
+        .bind(at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists rates, superseded ones included, latest effective first, optionally for one
+    /// pair and effective within `[from, to)`.
+    pub async fn list_rates(
+        &self,
+        pair: Option<(&str, &str)>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<FxRate>> {
+        let (base_currency, quote_currency) = pair.unzip();
+        let rows = sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT id, base_currency, quote_currency, rate, spread, source, effective_at, corrects_rate_id, correction_reason, superseded_by, created_at
+            FROM fx_rates
+            WHERE ($1::VARCHAR IS NULL OR base_currency = $1)
+              AND ($2::VARCHAR IS NULL OR quote_currency = $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR effective_at >= $3)
+              AND ($4::TIMESTAMPTZ IS NULL OR effective_at < $4)
+            ORDER BY effective_at DESC, created_at DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(base_currency)
+        .bind(quote_currency)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    pub async fn supersede_rate_in(tx: &mut Transaction<'_, Postgres>, id: Uuid, superseded_by: Uuid) -> Result<()> {
+        sqlx::query("UPDATE fx_rates SET superseded_by = $2 WHERE id = $1")
+            .bind(id)
+            .bind(superseded_by)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Records a conversion. Returns None if one with the same idempotency key exists.
+    pub async fn create_conversion_in(
+        tx: &mut Transaction<'_, Postgres>,
+        conversion: &FxConversion,
+    ) -> Result<Option<FxConversion>> {
+        sqlx::query_as::<_, FxConversion>(
+            r#"
+            INSERT INTO fx_conversions (id, external_id, idempotency_key, source_account_id, destination_account_id, source_amount, source_currency, destination_amount, destination_currency, rate_id, applied_rate, current_rate_id, debit_transaction_id, credit_transaction_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            RETURNING id, external_id, idempotency_key, source_account_id, destination_account_id, source_amount, source_currency, destination_amount, destination_currency, rate_id, applied_rate, current_rate_id, debit_transaction_id, credit_transaction_id, created_at, updated_at
+            "#,
+        )
+        .bind(conversion.id)
+        .bind(&conversion.external_id)
+        .bind(&conversion.idempotency_key)
+        .bind(conversion.source_account_id)
+        .bind(conversion.destination_account_id)
+        .bind(conversion.source_amount)
+        .bind(&conversion.source_currency)
+        .bind(conversion.destination_amount)
+        .bind(&conversion.destination_currency)
+        .bind(conversion.rate_id)
+        .bind(conversion.applied_rate)
+        .bind(conversion.current_rate_id)
+        .bind(conversion.debit_transaction_id)
+        .bind(conversion.credit_transaction_id)
+        .bind(conversion.created_at)
+        .bind(conversion.updated_at)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn find_conversion(&self, id: Uuid) -> Result<Option<FxConversion>> {
+        let row = sqlx::query_as::<_, FxConversion>(
+            r#"
+            SELECT id, external_id, idempotency_key, source_account_id, destination_account_id, source_amount, source_currency, destination_amount, destination_currency, rate_id, applied_rate, current_rate_id, debit_transaction_id, credit_transaction_id, created_at, updated_at
+            FROM fx_conversions
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_conversion_by_idempotency_key(&self, idempotency_key: &str) -> Result<Option<FxConversion>> {
+        let row = sqlx::query_as::<_, FxConversion>(
+            r#"
+            SELECT id, external_id, idempotency_key, source_account_id, destination_account_id, source_amount, source_currency, destination_amount, destination_currency, rate_id, applied_rate, current_rate_id, debit_transaction_id, credit_transaction_id, created_at, updated_at
+            FROM fx_conversions
+            WHERE idempotency_key = $1
+            "#,
+        )
+        .bind(idempotency_key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Conversions currently valued at a rate, locked until the transaction ends.
+    pub async fn find_conversions_valued_at_in(
+        tx: &mut Transaction<'_, Postgres>,
+        rate_id: Uuid,
+    ) -> Result<Vec<FxConversion>> {
+        sqlx::query_as::<_, FxConversion>(
+            r#"
+            SELECT id, external_id, idempotency_key, source_account_id, destination_account_id, source_amount, source_currency, destination_amount, destination_currency, rate_id, applied_rate, current_rate_id, debit_transaction_id, credit_transaction_id, created_at, updated_at
+            FROM fx_conversions
+            WHERE current_rate_id = $1
+            ORDER BY created_at, id
+            FOR UPDATE
+            "#,
+        )
+        .bind(rate_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn set_current_rate_in(tx: &mut Transaction<'_, Postgres>, conversion_id: Uuid, rate_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE fx_conversions SET current_rate_id = $2, updated_at = NOW() WHERE id = $1")
+            .bind(conversion_id)
+            .bind(rate_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    pub async fn create_adjustment_in(
+        tx: &mut Transaction<'_, Postgres>,
+        adjustment: &FxRateAdjustment,
+    ) -> Result<FxRateAdjustment> {
+        sqlx::query_as::<_, FxRateAdjustment>(
+            r#"
+            INSERT INTO fx_rate_adjustments (id, conversion_id, rate_id, previous_rate_id, amount, currency, transaction_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, conversion_id, rate_id, previous_rate_id, amount, currency, transaction_id, created_at
+            "#,
+        )
+        .bind(adjustment.id)
+        .bind(adjustment.conversion_id)
+        .bind(adjustment.rate_id)
+        .bind(adjustment.previous_rate_id)
+        .bind(adjustment.amount)
+        .bind(&adjustment.currency)
+        .bind(adjustment.transaction_id)
+        .bind(adjustment.created_at)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Adjustments posted for a correction, in the order they were posted.
+    pub async fn find_adjustments_by_rate(&self, rate_id: Uuid) -> Result<Vec<FxRateAdjustment>> {
+        let rows = sqlx::query_as::<_, FxRateAdjustment>(
+            r#"
+            SELECT id, conversion_id, rate_id, previous_rate_id, amount, currency, transaction_id, created_at
+            FROM fx_rate_adjustments
+            WHERE rate_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(rate_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Adjustments posted to a conversion, oldest first.
+    pub async fn find_adjustments_by_conversion(&self, conversion_id: Uuid) -> Result<Vec<FxRateAdjustment>> {
+        let rows = sqlx::query_as::<_, FxRateAdjustment>(
+            r#"
+            SELECT id, conversion_id, rate_id, previous_rate_id, amount, currency, transaction_id, created_at
+            FROM fx_rate_adjustments
+            WHERE conversion_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(conversion_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,167 @@
+use crate::error::{AppError, Result};
+use crate::models::{FxRevaluationLine, FxRevaluationRun};
+use chrono::NaiveDate;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for FX revaluation runs and the balances they marked.
+pub struct FxRevaluationRepository {
+    pool: PgPool,
+}
+
+impl FxRevaluationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records a run. Returns None if the period was already revalued in the run's
+    /// functional currency.
+    pub async fn create_run_in(
+        tx: &mut Transaction<'_, Postgres>,
+        run: &FxRevaluationRun,
+    ) -> Result<Option<FxRevaluationRun>> {
+        sqlx::query_as::<_, FxRevaluationRun>(
+            r#"
+            INSERT INTO fx_revaluation_runs (id, period_end, functional_currency, pnl_account_id, position_account_id, total_gain_loss, missing_rates, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (period_end, functional_currency) DO NOTHING
+            RETURNING id, period_end, functional_currency, pnl_account_id, position_account_id, total_gain_loss, missing_rates, created_at
+            "#,
+        )
+        .bind(run.id)
+        .bind(run.period_end)
+        .bind(&run.functional_currency)
+        .bind(run.pnl_account_id)
+        .bind(run.position_account_id)
+        .bind(run.total_gain_loss)
+        .bind(&run.missing_rates)
+        .bind(run.created_at)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn create_line_in(tx: &mut Transaction<'_, Postgres>, line: &FxRevaluationLine) -> Result<FxRevaluationLine> {
+        sqlx::query_as::<_, FxRevaluationLine>(
+            r#"
+            INSERT INTO fx_revaluation_lines (id, run_id, account_id, currency, balance, rate_id, rate, previous_rate, functional_amount, gain_loss, transaction_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, run_id, account_id, currency, balance, rate_id, rate, previous_rate, functional_amount, gain_loss, transaction_id, created_at
+            "#,
+        )
+        .bind(line.id)
+        .bind(line.run_id)
+        .bind(line.account_id)
+        .bind(&line.currency)
+        .bind(line.balance)
+        .bind(line.rate_id)
+        .bind(line.rate)
+        .bind(line.previous_rate)
+        .bind(line.functional_amount)
+        .bind(line.gain_loss)
+        .bind(line.transaction_id)
+        .bind(line.created_at)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn find_run(&self, id: Uuid) -> Result<Option<FxRevaluationRun>> {
+        let row = sqlx::query_as::<_, FxRevaluationRun>(
+            r#"
+            SELECT id, period_end, functional_currency, pnl_account_id, position_account_id, total_gain_loss, missing_rates, created_at
+            FROM fx_revaluation_runs
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_run_by_period(&self, period_end: NaiveDate, functional_currency: &str) -> Result<Option<FxRevaluationRun>> {
+        let row = sqlx::query_as::<_, FxRevaluationRun>(
+            r#"
+            SELECT id, period_end, functional_currency, pnl_account_id, position_account_id, total_gain_loss, missing_rates, created_at
+            FROM fx_revaluation_runs
+            WHERE period_end = $1 AND functional_currency = $2
+            "#,
+        )
+        .bind(period_end)
+        .bind(functional_currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists runs, latest period first.
+    pub async fn list_runs(&self, limit: i64, offset: i64) -> Result<Vec<FxRevaluationRun>> {
+        let rows = sqlx::query_as::<_, FxRevaluationRun>(
+            r#"
+            SELECT id, period_end, functional_currency, pnl_account_id, position_account_id, total_gain_loss, missing_rates, created_at
+            FROM fx_revaluation_runs
+            ORDER BY period_end DESC, created_at DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    pub async fn count_runs(&self) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM fx_revaluation_runs")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+
+    pub async fn find_lines(&self, run_id: Uuid) -> Result<Vec<FxRevaluationLine>> {
+        let rows = sqlx::query_as::<_, FxRevaluationLine>(
+            r#"
+            SELECT id, run_id, account_id, currency, balance, rate_id, rate, previous_rate, functional_amount, gain_loss, transaction_id, created_at
+            FROM fx_revaluation_lines
+            WHERE run_id = $1
+            ORDER BY currency, account_id
+            "#,
+        )
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// The latest line a balance was revalued in, giving the rate it is carried at.
+    pub async fn find_last_line(&self, account_id: Uuid, currency: &str) -> Result<Option<FxRevaluationLine>> {
+        let row = sqlx::query_as::<_, FxRevaluationLine>(
+            r#"
+            SELECT l.id, l.run_id, l.account_id, l.currency, l.balance, l.rate_id, l.rate, l.previous_rate, l.functional_amount, l.gain_loss, l.transaction_id, l.created_at
+            FROM fx_revaluation_lines l
+            JOIN fx_revaluation_runs r ON r.id = l.run_id
+            WHERE l.account_id = $1 AND l.currency = $2
+            ORDER BY r.period_end DESC, l.created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::{AppError, Result};
+use crate::models::{GlFeeSummary, GlJournalLine, GlPostingRun, GlPostingSummary};
+use chrono::NaiveDate;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for general-ledger posting runs and their journal lines.
+pub struct GlRepository {
+    pool: PgPool,
+}
+
+impl GlRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Sums a day's ledger entries by transaction type, account type, side, currency and
+    /// whether they are fee legs.
+    pub async fn summarize_entries_in(
+        tx: &mut Transaction<'_, Postgres>,
+        posting_date: NaiveDate,
+    ) -> Result<Vec<GlPostingSummary>> {
+        let rows = sqlx::query_as::<_, GlPostingSummary>(
+            r#"
+            SELECT t.type AS transaction_type, a.type AS account_type, e.entry_type,
+                   COALESCE(e.metadata->>'leg', '') = 'FEE' AS fee_leg, e.currency,
+                   SUM(e.amount) AS amount, COUNT(*) AS entry_count
+            FROM ledger_entries e
+            JOIN transactions t ON t.id = e.transaction_id
+            JOIN accounts a ON a.id = e.account_id
+            WHERE e.effective_date = $1
+            GROUP BY t.type, a.type, e.entry_type, COALESCE(e.metadata->>'leg', '') = 'FEE', e.currency
+            "#,
+        )
+        .bind(posting_date)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Sums the fees of transactions whose debit was posted on a day, leaving out fees
+    /// booked to a revenue account since their fee legs are summed as entries.
+    pub async fn summarize_fees_in(
+        tx: &mut Transaction<'_, Postgres>,
+        posting_date: NaiveDate,
+    ) -> Result<Vec<GlFeeSummary>> {
+        let rows = sqlx::query_as::<_, GlFeeSummary>(
+            r#"
+            SELECT t.type AS transaction_type, t.currency,
+                   SUM(t.fee_amount) AS amount, COUNT(*) AS transaction_count
+            FROM ledger_entries e
+            JOIN transactions t ON t.id = e.transaction_id
+            WHERE e.effective_date = $1 AND e.entry_type = 'DEBIT' AND t.fee_amount <> 0
+              AND NOT EXISTS (
+                  SELECT 1 FROM ledger_entries f
+                  WHERE f.transaction_id = t.id AND f.metadata->>'leg' = 'FEE'
+              )
+            GROUP BY t.type, t.currency
+            "#,
+        )
+        .bind(posting_date)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Claims the posting date for a new run. Returns false if the date already has one.
+    /// Concurrent claims for one date wait on each other, so only one succeeds.
+    pub async fn create_run_in(tx: &mut Transaction<'_, Postgres>, run: &GlPostingRun) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO gl_posting_runs (id, posting_date, line_count, entry_count, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (posting_date) DO NOTHING
+            "#,
+        )
+        .bind(run.id)
+        .bind(run.posting_date)
+        .bind(run.line_count)
+        .bind(run.entry_count)
+        .bind(run.created_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Stores a run's journal lines and totals.
+    pub async fn complete_run_in(
+        tx: &mut Transaction<'_, Postgres>,
+        run_id: Uuid,
+        lines: &[GlJournalLine],
+    ) -> Result<GlPostingRun> {
+        for line in lines {
+            sqlx::query(
+                r#"
+                INSERT INTO gl_journal_lines (run_id, line_number, gl_code, currency, debit_amount, credit_amount, entry_count)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(line.run_id)
+            .bind(line.line_number)
+            .bind(&line.gl_code)
+            .bind(&line.currency)
+            .bind(line.debit_amount)
+            .bind(line.credit_amount)
+            .bind(line.entry_count)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        let entry_count: i64 = lines.iter().map(|line| line.entry_count).sum();
+        let run = sqlx::query_as::<_, GlPostingRun>(
+            r#"
+            UPDATE gl_posting_runs
+            SET line_count = $2, entry_count = $3
+            WHERE id = $1
+            RETURNING id, posting_date, line_count, entry_count, created_at
+            "#,
+        )
+        .bind(run_id)
+        .bind(lines.len() as i32)
+        .bind(entry_count)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(run)
+    }
+
+    pub async fn find_run(&self, id: Uuid) -> Result<Option<GlPostingRun>> {
+        let row = sqlx::query_as::<_, GlPostingRun>(
+            r#"
+            SELECT id, posting_date, line_count, entry_count, created_at
+            FROM gl_posting_runs
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_run_by_date(&self, posting_date: NaiveDate) -> Result<Option<GlPostingRun>> {
+        let row = sqlx::query_as::<_, GlPostingRun>(
+            r#"
+            SELECT id, posting_date, line_count, entry_count, created_at
+            FROM gl_posting_runs
+            WHERE posting_date = $1
+            "#,
+        )
+        .bind(posting_date)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Gets a run's journal lines in line order.
+    pub async fn find_lines(&self, run_id: Uuid) -> Result<Vec<GlJournalLine>> {
+        let rows = sqlx::query_as::<_, GlJournalLine>(
+            r#"
+            SELECT run_id, line_number, gl_code, currency, debit_amount, credit_amount, entry_count
+            FROM gl_journal_lines
+            WHERE run_id = $1
+            ORDER BY line_number
+            "#,
+        )
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Lists runs, latest posting date first.
+    pub async fn list_runs(&self, limit: i64, offset: i64) -> Result<Vec<GlPostingRun>> {
+        let rows = sqlx::query_as::<_, GlPostingRun>(
+            r#"
+            SELECT id, posting_date, line_count, entry_count, created_at
+            FROM gl_posting_runs
+            ORDER BY posting_date DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    pub async fn count_runs(&self) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM gl_posting_runs")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+}
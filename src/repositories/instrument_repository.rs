@@ -0,0 +1,69 @@
+use crate::error::{AppError, Result};
+use crate::models::{Instrument, InstrumentKind};
+use sqlx::PgPool;
+
+/// Repository for the registry of instruments ledger codes denote.
+pub struct InstrumentRepository {
+    pool: PgPool,
+}
+
+impl InstrumentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, instrument: &Instrument) -> Result<Instrument> {
+        let row = sqlx::query_as::<_, Instrument>(
+            r#"
+            INSERT INTO instruments (code, kind, identifier_type, scale, description, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING code, kind, identifier_type, scale, description, created_at
+            "#,
+        )
+        .bind(&instrument.code)
+        .bind(instrument.kind)
+        .bind(&instrument.identifier_type)
+        .bind(instrument.scale)
+        .bind(&instrument.description)
+        .bind(instrument.created_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_by_code(&self, code: &str) -> Result<Option<Instrument>> {
+        let row = sqlx::query_as::<_, Instrument>(
+            r#"
+            SELECT code, kind, identifier_type, scale, description, created_at
+            FROM instruments
+            WHERE code = $1
+            "#,
+        )
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists registered instruments, optionally of one kind, by code.
+    pub async fn list(&self, kind: Option<InstrumentKind>) -> Result<Vec<Instrument>> {
+        let rows = sqlx::query_as::<_, Instrument>(
+            r#"
+            SELECT code, kind, identifier_type, scale, description, created_at
+            FROM instruments
+            WHERE ($1::instrument_kind IS NULL OR kind = $1)
+            ORDER BY code
+            "#,
+        )
+        .bind(kind)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
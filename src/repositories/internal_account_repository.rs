@@ -0,0 +1,161 @@
+use crate::error::{AppError, Result};
+use crate::models::{InternalAccount, InternalAccountDefinition, InternalAccountProvisioning, InternalAccountRole};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for the chart of internal accounts.
+pub struct InternalAccountRepository {
+    pool: PgPool,
+}
+
+impl InternalAccountRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records a chart entry backed by the account, or updates the entry's name, role,
+    /// parent and control flag if it is already provisioned. The backing account of an
+    /// existing entry is kept.
+    pub async fn upsert(&self, definition: &InternalAccountDefinition, account_id: Uuid) -> Result<InternalAccount> {
+        let row = sqlx::query_as::<_, InternalAccount>(
+            r#"
+            INSERT INTO internal_accounts (code, currency, name, role, parent_code, account_id, control)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (code, currency) DO UPDATE
+            SET name = EXCLUDED.name,
+                role = EXCLUDED.role,
+                parent_code = EXCLUDED.parent_code,
+                control = EXCLUDED.control,
+                updated_at = NOW()
+            RETURNING code, currency, name, role, parent_code, account_id, control, created_at, updated_at
+            "#,
+        )
+        .bind(&definition.code)
+        .bind(&definition.currency)
+        .bind(&definition.name)
+        .bind(definition.role)
+        .bind(&definition.parent_code)
+        .bind(account_id)
+        .bind(definition.control)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the entry with the code in the currency.
+    pub async fn find(&self, code: &str, currency: &str) -> Result<Option<InternalAccount>> {
+        let row = sqlx::query_as::<_, InternalAccount>(
+            r#"
+            SELECT code, currency, name, role, parent_code, account_id, control, created_at, updated_at
+            FROM internal_accounts
+            WHERE code = $1 AND currency = $2
+            "#,
+        )
+        .bind(code)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the entry playing the role in the currency.
+    pub async fn find_by_role(&self, role: InternalAccountRole, currency: &str) -> Result<Option<InternalAccount>> {
+        let row = sqlx::query_as::<_, InternalAccount>(
+            r#"
+            SELECT code, currency, name, role, parent_code, account_id, control, created_at, updated_at
+            FROM internal_accounts
+            WHERE role = $1 AND currency = $2
+            "#,
+        )
+        .bind(role)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists the chart, optionally in one currency, ordered by currency and code.
+    pub async fn find_all(&self, currency: Option<&str>) -> Result<Vec<InternalAccount>> {
+        let rows = sqlx::query_as::<_, InternalAccount>(
+            r#"
+            SELECT code, currency, name, role, parent_code, account_id, control, created_at, updated_at
+            FROM internal_accounts
+            WHERE $1::VARCHAR IS NULL OR currency = $1
+            ORDER BY currency, code
+            "#,
+        )
+        .bind(currency)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds the entries backed by any of the accounts.
+    pub async fn find_by_accounts(&self, account_ids: &[Uuid]) -> Result<Vec<InternalAccount>> {
+        let rows = sqlx::query_as::<_, InternalAccount>(
+            r#"
+            SELECT code, currency, name, role, parent_code, account_id, control, created_at, updated_at
+            FROM internal_accounts
+            WHERE account_id = ANY($1)
+            "#,
+        )
+        .bind(account_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Records that an entry was provisioned.
+    pub async fn record_provisioning(&self, record: &InternalAccountProvisioning) -> Result<InternalAccountProvisioning> {
+        let row = sqlx::query_as::<_, InternalAccountProvisioning>(
+            r#"
+            INSERT INTO internal_account_provisioning
+                (id, code, currency, account_id, trigger, created_account, provisioned_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, code, currency, account_id, trigger, created_account, provisioned_at
+            "#,
+        )
+        .bind(record.id)
+        .bind(&record.code)
+        .bind(&record.currency)
+        .bind(record.account_id)
+        .bind(record.trigger)
+        .bind(record.created_account)
+        .bind(record.provisioned_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists provisioning records, optionally in one currency, newest first.
+    pub async fn find_provisioning(&self, currency: Option<&str>, limit: i64) -> Result<Vec<InternalAccountProvisioning>> {
+        let rows = sqlx::query_as::<_, InternalAccountProvisioning>(
+            r#"
+            SELECT id, code, currency, account_id, trigger, created_account, provisioned_at
+            FROM internal_account_provisioning
+            WHERE $1::VARCHAR IS NULL OR currency = $1
+            ORDER BY provisioned_at DESC, id
+            LIMIT $2
+            "#,
+        )
+        .bind(currency)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
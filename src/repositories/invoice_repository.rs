@@ -0,0 +1,236 @@
+use crate::error::{AppError, Result};
+use crate::models::{Invoice, InvoiceContract, InvoiceLineItem, ParticipantUsage};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for invoice contracts, invoices and their line items.
+pub struct InvoiceRepository {
+    pool: PgPool,
+}
+
+impl InvoiceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Sets an account's contract in a currency, replacing any it had.
+    pub async fn set_contract(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        free_transactions: i32,
+        per_transaction_price: Decimal,
+        volume_rate: Decimal,
+        monthly_cap: Option<Decimal>,
+    ) -> Result<InvoiceContract> {
+        let row = sqlx::query_as::<_, InvoiceContract>(
+            r#"
+            INSERT INTO invoice_contracts (account_id, currency, free_transactions, per_transaction_price, volume_rate, monthly_cap, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+            ON CONFLICT (account_id, currency) DO UPDATE
+            SET free_transactions = EXCLUDED.free_transactions,
+                per_transaction_price = EXCLUDED.per_transaction_price,
+                volume_rate = EXCLUDED.volume_rate,
+                monthly_cap = EXCLUDED.monthly_cap,
+                updated_at = NOW()
+            RETURNING account_id, currency, free_transactions, per_transaction_price, volume_rate, monthly_cap, created_at, updated_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(free_transactions)
+        .bind(per_transaction_price)
+        .bind(volume_rate)
+        .bind(monthly_cap)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_contract(&self, account_id: Uuid, currency: &str) -> Result<Option<InvoiceContract>> {
+        let row = sqlx::query_as::<_, InvoiceContract>(
+            r#"
+            SELECT account_id, currency, free_transactions, per_transaction_price, volume_rate, monthly_cap, created_at, updated_at
+            FROM invoice_contracts
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Sums each payer's payments and transfers whose debit took effect within a period,
+    /// per currency. Transactions reversed since still count, as they were processed.
+    pub async fn summarize_usage(
+        &self,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+        currency: Option<&str>,
+    ) -> Result<Vec<ParticipantUsage>> {
+        let rows = sqlx::query_as::<_, ParticipantUsage>(
+            r#"
+            SELECT t.source_account_id AS account_id, t.currency,
+                   COUNT(*) AS transaction_count, SUM(t.amount) AS transaction_volume,
+                   SUM(t.fee_amount) AS fees_charged
+            FROM transactions t
+            WHERE t.type IN ('PAYMENT', 'TRANSFER') AND t.status IN ('SETTLED', 'REVERSED')
+              AND ($3::VARCHAR IS NULL OR t.currency = $3)
+              AND EXISTS (
+                  SELECT 1 FROM ledger_entries e
+                  WHERE e.transaction_id = t.id AND e.account_id = t.source_account_id
+                    AND e.entry_type = 'DEBIT' AND e.effective_date BETWEEN $1 AND $2
+              )
+            GROUP BY t.source_account_id, t.currency
+            ORDER BY t.currency, t.source_account_id
+            "#,
+        )
+        .bind(period_start)
+        .bind(period_end)
+        .bind(currency)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Stores an invoice and its line items. Returns false if the account was already
+    /// invoiced for the period in the currency; concurrent attempts wait on each other,
+    /// so only one succeeds.
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        invoice: &Invoice,
+        lines: &[InvoiceLineItem],
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO invoices (id, account_id, currency, period_start, period_end, transaction_count, transaction_volume, fees_charged, total_amount, transaction_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (account_id, currency, period_start) DO NOTHING
+            "#,
+        )
+        .bind(invoice.id)
+        .bind(invoice.account_id)
+        .bind(&invoice.currency)
+        .bind(invoice.period_start)
+        .bind(invoice.period_end)
+        .bind(invoice.transaction_count)
+        .bind(invoice.transaction_volume)
+        .bind(invoice.fees_charged)
+        .bind(invoice.total_amount)
+        .bind(invoice.transaction_id)
+        .bind(invoice.created_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for line in lines {
+            sqlx::query(
+                r#"
+                INSERT INTO invoice_line_items (invoice_id, line_number, kind, description, quantity, unit_price, amount)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(line.invoice_id)
+            .bind(line.line_number)
+            .bind(line.kind)
+            .bind(&line.description)
+            .bind(line.quantity)
+            .bind(line.unit_price)
+            .bind(line.amount)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Records the transaction that posted an invoice's receivable.
+    pub async fn set_transaction_in(
+        tx: &mut Transaction<'_, Postgres>,
+        invoice_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Invoice> {
+        let row = sqlx::query_as::<_, Invoice>(
+            r#"
+            UPDATE invoices SET transaction_id = $2
+            WHERE id = $1
+            RETURNING id, account_id, currency, period_start, period_end, transaction_count, transaction_volume, fees_charged, total_amount, transaction_id, created_at
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(transaction_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Invoice>> {
+        let row = sqlx::query_as::<_, Invoice>(
+            r#"
+            SELECT id, account_id, currency, period_start, period_end, transaction_count, transaction_volume, fees_charged, total_amount, transaction_id, created_at
+            FROM invoices
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Gets an invoice's line items in line order.
+    pub async fn find_lines(&self, invoice_id: Uuid) -> Result<Vec<InvoiceLineItem>> {
+        let rows = sqlx::query_as::<_, InvoiceLineItem>(
+            r#"
+            SELECT invoice_id, line_number, kind, description, quantity, unit_price, amount
+            FROM invoice_line_items
+            WHERE invoice_id = $1
+            ORDER BY line_number
+            "#,
+        )
+        .bind(invoice_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Lists invoices, optionally for one account and/or the period starting on a date,
+    /// latest period first.
+    pub async fn list(&self, account_id: Option<Uuid>, period_start: Option<NaiveDate>) -> Result<Vec<Invoice>> {
+        let rows = sqlx::query_as::<_, Invoice>(
+            r#"
+            SELECT id, account_id, currency, period_start, period_end, transaction_count, transaction_volume, fees_charged, total_amount, transaction_id, created_at
+            FROM invoices
+            WHERE ($1::UUID IS NULL OR account_id = $1) AND ($2::DATE IS NULL OR period_start = $2)
+            ORDER BY period_start DESC, currency, account_id
+            "#,
+        )
+        .bind(account_id)
+        .bind(period_start)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
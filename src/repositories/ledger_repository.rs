@@ -1,8 +1,11 @@
 use crate::error::{AppError, Result};
-use crate::models::{EntryType, LedgerEntry};
+use crate::models::{
+    BalanceBasis, BatchLedgerTotals, EntryType, LedgerEntry, LedgerHistoryEntry, LedgerMovementGroup, FEE_LEG,
+};
+use crate::observability::timed;
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
 /// Repository for LedgerEntry operations.
@@ -17,30 +20,96 @@ impl LedgerRepository {
 
     /// Creates a new ledger entry.
     pub async fn create(&self, entry: &LedgerEntry) -> Result<LedgerEntry> {
-        let row = sqlx::query_as::<_, LedgerEntry>(
-            r#"
-            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
-            "#,
+        let row = timed(
+            "ledger_entries.insert",
+            sqlx::query_as::<_, LedgerEntry>(
+                r#"
+                INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
+                "#,
+            )
+            .bind(entry.id)
+            .bind(entry.transaction_id)
+            .bind(entry.account_id)
+            .bind(&entry.entry_type)
+            .bind(entry.amount)
+            .bind(&entry.currency)
+            .bind(entry.balance_after)
+            .bind(entry.effective_date)
+            .bind(&entry.metadata)
+            .bind(entry.created_at)
+            .bind(&entry.narrative)
+            .bind(&entry.reference)
+            .fetch_one(&self.pool),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Creates a ledger entry within an existing database transaction.
+    pub async fn create_in(tx: &mut Transaction<'_, Postgres>, entry: &LedgerEntry) -> Result<LedgerEntry> {
+        let row = timed(
+            "ledger_entries.insert",
+            sqlx::query_as::<_, LedgerEntry>(
+                r#"
+                INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
+                "#,
+            )
+            .bind(entry.id)
+            .bind(entry.transaction_id)
+            .bind(entry.account_id)
+            .bind(entry.entry_type)
+            .bind(entry.amount)
+            .bind(&entry.currency)
+            .bind(entry.balance_after)
+            .bind(entry.effective_date)
+            .bind(&entry.metadata)
+            .bind(entry.created_at)
+            .bind(&entry.narrative)
+            .bind(&entry.reference)
+            .fetch_one(&mut **tx),
         )
-        .bind(entry.id)
-        .bind(entry.transaction_id)
-        .bind(entry.account_id)
-        .bind(&entry.entry_type)
-        .bind(entry.amount)
-        .bind(&entry.currency)
-        .bind(entry.balance_after)
-        .bind(entry.effective_date)
-        .bind(&entry.metadata)
-        .bind(entry.created_at)
-        .fetch_one(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
         Ok(row)
     }
 
+    /// Creates ledger entries with one multi-row insert within an existing database
+    /// transaction. Entries are inserted, and sequenced per account, in slice order.
+    pub async fn create_many_in(tx: &mut Transaction<'_, Postgres>, entries: &[LedgerEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut insert = QueryBuilder::<Postgres>::new(
+            "INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference) ",
+        );
+        insert.push_values(entries, |mut row, entry| {
+            row.push_bind(entry.id)
+                .push_bind(entry.transaction_id)
+                .push_bind(entry.account_id)
+                .push_bind(entry.entry_type)
+                .push_bind(entry.amount)
+                .push_bind(&entry.currency)
+                .push_bind(entry.balance_after)
+                .push_bind(entry.effective_date)
+                .push_bind(&entry.metadata)
+                .push_bind(entry.created_at)
+                .push_bind(&entry.narrative)
+                .push_bind(&entry.reference);
+        });
+        timed("ledger_entries.insert_many", insert.build().execute(&mut **tx))
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
     /// Creates multiple ledger entries in a single transaction.
     pub async fn create_batch(&self, entries: &[LedgerEntry]) -> Result<Vec<LedgerEntry>> {
         let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
@@ -49,9 +118,9 @@ impl LedgerRepository {
         for entry in entries {
             let row = sqlx::query_as::<_, LedgerEntry>(
                 r#"
-                INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-                RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+                INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
                 "#,
             )
             .bind(entry.id)
@@ -64,6 +133,8 @@ impl LedgerRepository {
             .bind(entry.effective_date)
             .bind(&entry.metadata)
             .bind(entry.created_at)
+            .bind(&entry.narrative)
+            .bind(&entry.reference)
             .fetch_one(&mut *tx)
             .await
             .map_err(AppError::Database)?;
@@ -79,7 +150,7 @@ impl LedgerRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<LedgerEntry>> {
         let row = sqlx::query_as::<_, LedgerEntry>(
             r#"
-            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
             FROM ledger_entries
             WHERE id = $1
             "#,
@@ -94,16 +165,19 @@ impl LedgerRepository {
 
     /// Finds all entries for a transaction.
     pub async fn find_by_transaction(&self, transaction_id: Uuid) -> Result<Vec<LedgerEntry>> {
-        let rows = sqlx::query_as::<_, LedgerEntry>(
-            r#"
-            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
-            FROM ledger_entries
-            WHERE transaction_id = $1
-            ORDER BY created_at
-            "#,
+        let rows = timed(
+            "ledger_entries.find_by_transaction",
+            sqlx::query_as::<_, LedgerEntry>(
+                r#"
+                SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
+                FROM ledger_entries
+                WHERE transaction_id = $1
+                ORDER BY created_at
+                "#,
+            )
+            .bind(transaction_id)
+            .fetch_all(&self.pool),
         )
-        .bind(transaction_id)
-        .fetch_all(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
@@ -119,7 +193,7 @@ impl LedgerRepository {
     ) -> Result<Vec<LedgerEntry>> {
         let rows = sqlx::query_as::<_, LedgerEntry>(
             r#"
-            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
             FROM ledger_entries
             WHERE account_id = $1
             ORDER BY created_at DESC
@@ -153,6 +227,65 @@ impl LedgerRepository {
         Ok(row.0)
     }
 
+    /// Finds a page of an account's entries, newest first, optionally within an
+    /// effective date range. Each entry comes with the balance the account's previous
+    /// entry in its currency left, whether or not that entry is in the range.
+    pub async fn find_history(
+        &self,
+        account_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<LedgerHistoryEntry>> {
+        let rows = sqlx::query_as::<_, LedgerHistoryEntry>(
+            r#"
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference, previous_balance
+            FROM (
+                SELECT le.*,
+                       LAG(balance_after) OVER (PARTITION BY currency ORDER BY account_sequence) AS previous_balance
+                FROM ledger_entries le
+                WHERE account_id = $1
+            ) history
+            WHERE ($2::DATE IS NULL OR effective_date >= $2)
+              AND ($3::DATE IS NULL OR effective_date <= $3)
+            ORDER BY created_at DESC, account_sequence DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(account_id)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Counts an account's entries, optionally within an effective date range.
+    pub async fn count_history(&self, account_id: Uuid, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM ledger_entries
+            WHERE account_id = $1
+              AND ($2::DATE IS NULL OR effective_date >= $2)
+              AND ($3::DATE IS NULL OR effective_date <= $3)
+            "#,
+        )
+        .bind(account_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+
     /// Finds entries for an account within a date range.
     pub async fn find_by_account_and_date_range(
         &self,
@@ -162,7 +295,7 @@ impl LedgerRepository {
     ) -> Result<Vec<LedgerEntry>> {
         let rows = sqlx::query_as::<_, LedgerEntry>(
             r#"
-            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
             FROM ledger_entries
             WHERE account_id = $1
               AND effective_date >= $2
@@ -180,6 +313,136 @@ impl LedgerRepository {
         Ok(rows)
     }
 
+    /// Finds an account's entries in a currency created within `[start, end)`, oldest first.
+    pub async fn find_by_account_and_time_range(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<LedgerEntry>> {
+        let rows = sqlx::query_as::<_, LedgerEntry>(
+            r#"
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
+            FROM ledger_entries
+            WHERE account_id = $1 AND currency = $2
+              AND created_at >= $3 AND created_at < $4
+            ORDER BY created_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Net change (credits less debits) to an account's balance from entries created
+    /// at or after `since`.
+    pub async fn net_movement_since(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Decimal> {
+        let row: (Option<Decimal>,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(CASE WHEN entry_type = 'CREDIT' THEN amount ELSE -amount END), 0)
+            FROM ledger_entries
+            WHERE account_id = $1 AND currency = $2 AND created_at >= $3
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0.unwrap_or(Decimal::ZERO))
+    }
+
+    /// Net change (credits less debits) to an account's balance per day after `after`,
+    /// dating entries by effective date or booking date.
+    pub async fn daily_movements_after(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        after: NaiveDate,
+        basis: BalanceBasis,
+    ) -> Result<Vec<(NaiveDate, Decimal)>> {
+        let rows: Vec<(NaiveDate, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT day, SUM(CASE WHEN entry_type = 'CREDIT' THEN amount ELSE -amount END)
+            FROM (
+                SELECT entry_type, amount,
+                       CASE WHEN $4 THEN effective_date ELSE (created_at AT TIME ZONE 'UTC')::date END AS day
+                FROM ledger_entries
+                WHERE account_id = $1 AND currency = $2
+            ) dated
+            WHERE day > $3
+            GROUP BY day
+            ORDER BY day
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(after)
+        .bind(basis == BalanceBasis::Value)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Groups an account's entries dated from `from` to `to` by entry type and the traits
+    /// of their transaction, dating entries by effective date or booking date. Reversals
+    /// are recognized by the fee policy they record, batch settlement postings by their
+    /// batch, and fee refunds by their flag.
+    pub async fn movement_groups(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        basis: BalanceBasis,
+    ) -> Result<Vec<LedgerMovementGroup>> {
+        let rows = sqlx::query_as::<_, LedgerMovementGroup>(
+            r#"
+            SELECT e.entry_type,
+                   t.type AS transaction_type,
+                   COALESCE(e.metadata->>'leg' = $6 OR t.metadata->>'fee_refund' = 'true', FALSE) AS fee_leg,
+                   COALESCE(t.metadata->>'fee_policy' IS NOT NULL, FALSE) AS reversal,
+                   COALESCE(t.metadata->>'batch_id' IS NOT NULL, FALSE) AS batch_settlement,
+                   COUNT(*) AS entry_count,
+                   SUM(e.amount) AS total
+            FROM ledger_entries e
+            LEFT JOIN transactions t ON t.id = e.transaction_id
+            WHERE e.account_id = $1 AND e.currency = $2
+              AND CASE WHEN $5 THEN e.effective_date ELSE (e.created_at AT TIME ZONE 'UTC')::date END
+                  BETWEEN $3 AND $4
+            GROUP BY 1, 2, 3, 4, 5
+            ORDER BY 1, 2
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(from)
+        .bind(to)
+        .bind(basis == BalanceBasis::Value)
+        .bind(FEE_LEG)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
     /// Calculates the sum of entries for an account by type.
     pub async fn sum_by_account_and_type(
         &self,
@@ -212,7 +475,7 @@ impl LedgerRepository {
     ) -> Result<Option<LedgerEntry>> {
         let row = sqlx::query_as::<_, LedgerEntry>(
             r#"
-            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
             FROM ledger_entries
             WHERE account_id = $1 AND currency = $2
             ORDER BY created_at DESC
@@ -247,6 +510,68 @@ impl LedgerRepository {
         Ok(row.0 == row.1)
     }
 
+    /// Totals the debits and credits, per currency, of the ledger entries posted for a
+    /// batch's transactions.
+    pub async fn totals_by_batch(&self, batch_id: Uuid) -> Result<Vec<BatchLedgerTotals>> {
+        let rows = sqlx::query_as::<_, BatchLedgerTotals>(
+            r#"
+            SELECT le.currency,
+                   COALESCE(SUM(CASE WHEN le.entry_type = 'DEBIT' THEN le.amount ELSE 0 END), 0) AS debits,
+                   COALESCE(SUM(CASE WHEN le.entry_type = 'CREDIT' THEN le.amount ELSE 0 END), 0) AS credits,
+                   COUNT(*) AS entry_count
+            FROM ledger_entries le
+            JOIN transactions t ON t.id = le.transaction_id
+            WHERE t.settlement_batch_id = $1
+            GROUP BY le.currency
+            ORDER BY le.currency
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Gets the fee-leg entry crediting a transaction's fee to the fee revenue account.
+    pub async fn find_fee_leg_in(
+        tx: &mut Transaction<'_, Postgres>,
+        transaction_id: Uuid,
+    ) -> Result<Option<LedgerEntry>> {
+        let row = sqlx::query_as::<_, LedgerEntry>(
+            r#"
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
+            FROM ledger_entries
+            WHERE transaction_id = $1 AND entry_type = 'CREDIT' AND metadata->>'leg' = 'FEE'
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Sums what has already been refunded or reversed out of a fee-leg entry.
+    pub async fn sum_fee_refunds_in(tx: &mut Transaction<'_, Postgres>, fee_entry_id: Uuid) -> Result<Decimal> {
+        let row: (Decimal,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)
+            FROM ledger_entries
+            WHERE entry_type = 'DEBIT' AND metadata->>'leg' = 'FEE'
+              AND metadata->>'original_fee_entry_id' = $1
+            "#,
+        )
+        .bind(fee_entry_id.to_string())
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+
     /// Gets entries created within a time range (for batch processing).
     pub async fn find_by_time_range(
         &self,
@@ -256,7 +581,7 @@ impl LedgerRepository {
     ) -> Result<Vec<LedgerEntry>> {
         let rows = sqlx::query_as::<_, LedgerEntry>(
             r#"
-            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+            SELECT id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
             FROM ledger_entries
             WHERE created_at >= $1 AND created_at < $2
             ORDER BY created_at
@@ -0,0 +1,80 @@
+use crate::error::{AppError, Result};
+use crate::models::LiquidityFlow;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository reading the settlements that moved participants' intraday liquidity.
+pub struct LiquidityRepository {
+    pool: PgPool,
+}
+
+impl LiquidityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Finds the liquidity flows in a currency within `[start, end)`, optionally for one
+    /// participant, in time order.
+    ///
+    /// Flows are gross RTGS settlements (the source pays the amount, the destination
+    /// receives the net amount) and completed executions of net settlement
+    /// instructions, run as sagas of `saga_type`. Netted transactions are left out:
+    /// their liquidity moves with the batch's instructions.
+    pub async fn find_flows(
+        &self,
+        saga_type: &str,
+        currency: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        participant_id: Option<Uuid>,
+    ) -> Result<Vec<LiquidityFlow>> {
+        let rows = sqlx::query_as::<_, LiquidityFlow>(
+            r#"
+            WITH rtgs AS (
+                SELECT id, source_account_id, destination_account_id, amount, net_amount, settled_at
+                FROM transactions
+                WHERE settlement_route = 'RTGS' AND status = 'SETTLED' AND currency = $1
+                  AND settled_at >= $2 AND settled_at < $3
+            ),
+            executed AS (
+                SELECT context->'instruction' AS instruction, updated_at
+                FROM sagas
+                WHERE saga_type = $5 AND status = 'COMPLETED'
+                  AND updated_at >= $2 AND updated_at < $3
+                  AND context->'instruction'->>'currency' = $1
+            ),
+            flows AS (
+                SELECT source_account_id AS participant_id, 'RTGS'::VARCHAR AS source, id AS reference_id,
+                       -amount AS amount, settled_at AS occurred_at
+                FROM rtgs
+                UNION ALL
+                SELECT destination_account_id, 'RTGS'::VARCHAR, id, net_amount, settled_at
+                FROM rtgs
+                UNION ALL
+                SELECT (instruction->>'from_participant')::UUID, 'INSTRUCTION'::VARCHAR, (instruction->>'id')::UUID,
+                       -(instruction->>'amount')::NUMERIC, updated_at
+                FROM executed
+                UNION ALL
+                SELECT (instruction->>'to_participant')::UUID, 'INSTRUCTION'::VARCHAR, (instruction->>'id')::UUID,
+                       (instruction->>'amount')::NUMERIC, updated_at
+                FROM executed
+            )
+            SELECT participant_id, source, reference_id, amount, occurred_at
+            FROM flows
+            WHERE $4::UUID IS NULL OR participant_id = $4
+            ORDER BY occurred_at, participant_id
+            "#,
+        )
+        .bind(currency)
+        .bind(start)
+        .bind(end)
+        .bind(participant_id)
+        .bind(saga_type)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::{AppError, Result};
+use crate::models::{MetadataSchema, TransactionType};
+use sqlx::PgPool;
+
+/// Repository for per-transaction-type metadata schemas.
+pub struct MetadataSchemaRepository {
+    pool: PgPool,
+}
+
+impl MetadataSchemaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates or replaces the schema for a transaction type.
+    pub async fn upsert(&self, schema: &MetadataSchema) -> Result<MetadataSchema> {
+        let row = sqlx::query_as::<_, MetadataSchema>(
+            r#"
+            INSERT INTO metadata_schemas (transaction_type, schema, created_at, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (transaction_type) DO UPDATE
+            SET schema = EXCLUDED.schema, updated_at = EXCLUDED.updated_at
+            RETURNING transaction_type, schema, created_at, updated_at
+            "#,
+        )
+        .bind(schema.transaction_type)
+        .bind(&schema.schema)
+        .bind(schema.created_at)
+        .bind(schema.updated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the schema for a transaction type.
+    pub async fn find_by_type(&self, transaction_type: TransactionType) -> Result<Option<MetadataSchema>> {
+        let row = sqlx::query_as::<_, MetadataSchema>(
+            r#"
+            SELECT transaction_type, schema, created_at, updated_at
+            FROM metadata_schemas
+            WHERE transaction_type = $1
+            "#,
+        )
+        .bind(transaction_type)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists all configured schemas.
+    pub async fn list(&self) -> Result<Vec<MetadataSchema>> {
+        let rows = sqlx::query_as::<_, MetadataSchema>(
+            r#"
+            SELECT transaction_type, schema, created_at, updated_at
+            FROM metadata_schemas
+            ORDER BY transaction_type
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Deletes the schema for a transaction type.
+    pub async fn delete(&self, transaction_type: TransactionType) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM metadata_schemas
+            WHERE transaction_type = $1
+            "#,
+        )
+        .bind(transaction_type)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,16 +1,104 @@
+pub mod account_identifier_repository;
 pub mod account_repository;
+pub mod accounting_period_repository;
+pub mod activity_repository;
+pub mod alert_rule_repository;
+pub mod balance_guard_repository;
+pub mod balance_projection_repository;
 pub mod balance_repository;
+pub mod balance_reservation_repository;
+pub mod batch_progress_repository;
+pub mod batch_reconciliation_repository;
+pub mod bilateral_pair_repository;
 pub mod batch_repository;
+pub mod batch_template_repository;
+pub mod counterparty_repository;
+pub mod fee_settlement_repository;
+pub mod file_delivery_repository;
+pub mod finality_repository;
+pub mod funding_repository;
+pub mod fx_repository;
+pub mod fx_revaluation_repository;
+pub mod gl_repository;
+pub mod instrument_repository;
+pub mod internal_account_repository;
+pub mod invoice_repository;
 pub mod ledger_repository;
+pub mod liquidity_repository;
+pub mod metadata_schema_repository;
+pub mod net_debit_cap_repository;
+pub mod netting_advice_repository;
+pub mod netting_metrics_repository;
+pub mod netting_report_repository;
 pub mod netting_repository;
+pub mod outbox_repository;
+pub mod participant_default_repository;
+pub mod pvp_repository;
+pub mod reconciliation_repository;
+pub mod replication_repository;
+pub mod risk_hold_repository;
+pub mod saga_repository;
+pub mod settlement_profile_repository;
+pub mod settlement_window_repository;
+pub mod submission_repository;
+pub mod sync_repository;
+pub mod transaction_amendment_repository;
+pub mod transaction_audit_repository;
+pub mod transaction_hold_repository;
+pub mod transaction_group_repository;
 pub mod transaction_repository;
+pub mod transaction_type_repository;
 
+pub use account_identifier_repository::AccountIdentifierRepository;
 pub use account_repository::AccountRepository;
+pub use accounting_period_repository::AccountingPeriodRepository;
+pub use activity_repository::ActivityRepository;
+pub use alert_rule_repository::AlertRuleRepository;
+pub use balance_guard_repository::BalanceGuardRepository;
+pub use balance_projection_repository::BalanceProjectionRepository;
 pub use balance_repository::BalanceRepository;
+pub use balance_reservation_repository::BalanceReservationRepository;
+pub use batch_progress_repository::BatchProgressRepository;
+pub use batch_reconciliation_repository::BatchReconciliationRepository;
+pub use bilateral_pair_repository::BilateralPairRepository;
 pub use batch_repository::BatchRepository;
+pub use batch_template_repository::BatchTemplateRepository;
+pub use counterparty_repository::CounterpartyRepository;
+pub use fee_settlement_repository::FeeSettlementRepository;
+pub use file_delivery_repository::FileDeliveryRepository;
+pub use finality_repository::FinalityRepository;
+pub use funding_repository::FundingRepository;
+pub use fx_repository::FxRepository;
+pub use fx_revaluation_repository::FxRevaluationRepository;
+pub use gl_repository::GlRepository;
+pub use instrument_repository::InstrumentRepository;
+pub use internal_account_repository::InternalAccountRepository;
+pub use invoice_repository::InvoiceRepository;
 pub use ledger_repository::LedgerRepository;
+pub use liquidity_repository::LiquidityRepository;
+pub use metadata_schema_repository::MetadataSchemaRepository;
+pub use net_debit_cap_repository::NetDebitCapRepository;
+pub use netting_advice_repository::NettingAdviceRepository;
+pub use netting_metrics_repository::NettingMetricsRepository;
+pub use netting_report_repository::NettingReportRepository;
 pub use netting_repository::{BatchNettingSummary, NettingRepository};
-pub use transaction_repository::TransactionRepository;
+pub use outbox_repository::OutboxRepository;
+pub use participant_default_repository::ParticipantDefaultRepository;
+pub use pvp_repository::PvpRepository;
+pub use reconciliation_repository::ReconciliationRepository;
+pub use replication_repository::ReplicationRepository;
+pub use risk_hold_repository::RiskHoldRepository;
+pub use saga_repository::SagaRepository;
+pub use settlement_profile_repository::SettlementProfileRepository;
+pub use settlement_window_repository::SettlementWindowRepository;
+pub use submission_repository::SubmissionRepository;
+pub use sync_repository::SyncRepository;
+pub use transaction_amendment_repository::TransactionAmendmentRepository;
+pub use transaction_audit_repository::TransactionAuditRepository;
+pub use transaction_hold_repository::TransactionHoldRepository;
+pub use transaction_group_repository::TransactionGroupRepository;
+pub use transaction_repository::{RouteVolume, TransactionRepository};
+pub use transaction_type_repository::TransactionTypeRepository;
 
 use sqlx::PgPool;
 
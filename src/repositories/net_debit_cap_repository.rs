@@ -0,0 +1,116 @@
+use crate::error::{AppError, Result};
+use crate::models::NetDebitCap;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for per-account net debit caps and the net debit positions they limit.
+pub struct NetDebitCapRepository {
+    pool: PgPool,
+}
+
+impl NetDebitCapRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Sets the most an account may owe net in one batch of a currency.
+    pub async fn set_cap(&self, account_id: Uuid, currency: &str, cap: Decimal) -> Result<NetDebitCap> {
+        let row = sqlx::query_as::<_, NetDebitCap>(
+            r#"
+            INSERT INTO net_debit_caps (account_id, currency, cap, created_at, updated_at)
+            VALUES ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (account_id, currency) DO UPDATE SET cap = EXCLUDED.cap, updated_at = NOW()
+            RETURNING account_id, currency, cap, created_at, updated_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .bind(cap)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_cap(&self, account_id: Uuid, currency: &str) -> Result<Option<NetDebitCap>> {
+        let row = sqlx::query_as::<_, NetDebitCap>(
+            r#"
+            SELECT account_id, currency, cap, created_at, updated_at
+            FROM net_debit_caps
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Gets an account's cap within an open database transaction.
+    pub async fn find_cap_in(
+        tx: &mut Transaction<'_, Postgres>,
+        account_id: Uuid,
+        currency: &str,
+    ) -> Result<Option<NetDebitCap>> {
+        let row = sqlx::query_as::<_, NetDebitCap>(
+            r#"
+            SELECT account_id, currency, cap, created_at, updated_at
+            FROM net_debit_caps
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Sums what an account pays less what it receives across a batch's transactions.
+    /// Positive when the account owes, negative when it is owed.
+    pub async fn net_debit(&self, batch_id: Uuid, account_id: Uuid) -> Result<Decimal> {
+        let (position,): (Decimal,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(CASE WHEN source_account_id = $2 THEN amount ELSE -amount END), 0)
+            FROM transactions
+            WHERE settlement_batch_id = $1 AND (source_account_id = $2 OR destination_account_id = $2)
+            "#,
+        )
+        .bind(batch_id)
+        .bind(account_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(position)
+    }
+
+    /// Sums an account's net debit in a batch within an open database transaction.
+    pub async fn net_debit_in(
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Decimal> {
+        let (position,): (Decimal,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(CASE WHEN source_account_id = $2 THEN amount ELSE -amount END), 0)
+            FROM transactions
+            WHERE settlement_batch_id = $1 AND (source_account_id = $2 OR destination_account_id = $2)
+            "#,
+        )
+        .bind(batch_id)
+        .bind(account_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(position)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::error::{AppError, Result};
+use crate::models::NettingAdvice;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for the netting advices issued to participants.
+pub struct NettingAdviceRepository {
+    pool: PgPool,
+}
+
+impl NettingAdviceRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores an advice, replacing the one the participant was issued for the batch, if any.
+    pub async fn upsert(&self, advice: &NettingAdvice) -> Result<NettingAdvice> {
+        let row = sqlx::query_as::<_, NettingAdvice>(
+            r#"
+            INSERT INTO netting_advices (id, batch_id, participant_id, currency, settlement_date, gross_receivable, gross_payable, net_position, transaction_count, instructions, funding_deadline, generated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (batch_id, participant_id) DO UPDATE
+            SET currency = EXCLUDED.currency,
+                settlement_date = EXCLUDED.settlement_date,
+                gross_receivable = EXCLUDED.gross_receivable,
+                gross_payable = EXCLUDED.gross_payable,
+                net_position = EXCLUDED.net_position,
+                transaction_count = EXCLUDED.transaction_count,
+                instructions = EXCLUDED.instructions,
+                funding_deadline = EXCLUDED.funding_deadline,
+                generated_at = EXCLUDED.generated_at
+            RETURNING id, batch_id, participant_id, currency, settlement_date, gross_receivable, gross_payable, net_position, transaction_count, instructions, funding_deadline, generated_at
+            "#,
+        )
+        .bind(advice.id)
+        .bind(advice.batch_id)
+        .bind(advice.participant_id)
+        .bind(&advice.currency)
+        .bind(advice.settlement_date)
+        .bind(advice.gross_receivable)
+        .bind(advice.gross_payable)
+        .bind(advice.net_position)
+        .bind(advice.transaction_count)
+        .bind(&advice.instructions)
+        .bind(advice.funding_deadline)
+        .bind(advice.generated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the advices issued for a batch, net payers first.
+    pub async fn find_by_batch(&self, batch_id: Uuid) -> Result<Vec<NettingAdvice>> {
+        let rows = sqlx::query_as::<_, NettingAdvice>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, settlement_date, gross_receivable, gross_payable, net_position, transaction_count, instructions, funding_deadline, generated_at
+            FROM netting_advices
+            WHERE batch_id = $1
+            ORDER BY net_position, participant_id
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    pub async fn find_by_batch_and_participant(
+        &self,
+        batch_id: Uuid,
+        participant_id: Uuid,
+    ) -> Result<Option<NettingAdvice>> {
+        let row = sqlx::query_as::<_, NettingAdvice>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, settlement_date, gross_receivable, gross_payable, net_position, transaction_count, instructions, funding_deadline, generated_at
+            FROM netting_advices
+            WHERE batch_id = $1 AND participant_id = $2
+            "#,
+        )
+        .bind(batch_id)
+        .bind(participant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
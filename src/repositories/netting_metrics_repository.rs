@@ -0,0 +1,178 @@
+use crate::error::{AppError, Result};
+use crate::models::DailyNettingMetrics;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for daily netting metrics.
+pub struct NettingMetricsRepository {
+    pool: PgPool,
+}
+
+impl NettingMetricsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Adds a netted batch to the day's totals for its currency. A batch netted before
+    /// has its earlier contribution taken out first, so it is only ever counted once.
+    pub async fn record_batch(
+        &self,
+        batch_id: Uuid,
+        metric_date: NaiveDate,
+        currency: &str,
+        transactions: i64,
+        gross_volume: Decimal,
+        net_volume: Decimal,
+    ) -> Result<DailyNettingMetrics> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        // Waits for a concurrent first recording of the batch to commit
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO netting_metrics_batches (batch_id, metric_date, currency, transactions_netted, gross_volume, net_volume)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (batch_id) DO NOTHING
+            "#,
+        )
+        .bind(batch_id)
+        .bind(metric_date)
+        .bind(currency)
+        .bind(transactions)
+        .bind(gross_volume)
+        .bind(net_volume)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?
+        .rows_affected()
+            > 0;
+
+        if !inserted {
+            let (previous_date, previous_currency, previous_transactions, previous_gross, previous_net): (
+                NaiveDate,
+                String,
+                i64,
+                Decimal,
+                Decimal,
+            ) = sqlx::query_as(
+                r#"
+                SELECT metric_date, currency, transactions_netted, gross_volume, net_volume
+                FROM netting_metrics_batches
+                WHERE batch_id = $1
+                FOR UPDATE
+                "#,
+            )
+            .bind(batch_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+            sqlx::query(
+                r#"
+                UPDATE netting_metrics_daily SET
+                    batches_processed = batches_processed - 1,
+                    transactions_netted = transactions_netted - $3,
+                    gross_volume = gross_volume - $4,
+                    net_volume = net_volume - $5,
+                    updated_at = NOW()
+                WHERE metric_date = $1 AND currency = $2
+                "#,
+            )
+            .bind(previous_date)
+            .bind(&previous_currency)
+            .bind(previous_transactions)
+            .bind(previous_gross)
+            .bind(previous_net)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+            sqlx::query(
+                r#"
+                UPDATE netting_metrics_batches
+                SET metric_date = $2, currency = $3, transactions_netted = $4, gross_volume = $5, net_volume = $6, recorded_at = NOW()
+                WHERE batch_id = $1
+                "#,
+            )
+            .bind(batch_id)
+            .bind(metric_date)
+            .bind(currency)
+            .bind(transactions)
+            .bind(gross_volume)
+            .bind(net_volume)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        let row = sqlx::query_as::<_, DailyNettingMetrics>(
+            r#"
+            INSERT INTO netting_metrics_daily (metric_date, currency, batches_processed, transactions_netted, gross_volume, net_volume, updated_at)
+            VALUES ($1, $2, 1, $3, $4, $5, NOW())
+            ON CONFLICT (metric_date, currency) DO UPDATE SET
+                batches_processed = netting_metrics_daily.batches_processed + 1,
+                transactions_netted = netting_metrics_daily.transactions_netted + EXCLUDED.transactions_netted,
+                gross_volume = netting_metrics_daily.gross_volume + EXCLUDED.gross_volume,
+                net_volume = netting_metrics_daily.net_volume + EXCLUDED.net_volume,
+                updated_at = NOW()
+            RETURNING metric_date, currency, batches_processed, transactions_netted, gross_volume, net_volume, updated_at
+            "#,
+        )
+        .bind(metric_date)
+        .bind(currency)
+        .bind(transactions)
+        .bind(gross_volume)
+        .bind(net_volume)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Lists daily metrics in `[from, to]`, optionally for one currency.
+    pub async fn find_by_period(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        currency: Option<&str>,
+    ) -> Result<Vec<DailyNettingMetrics>> {
+        let rows = sqlx::query_as::<_, DailyNettingMetrics>(
+            r#"
+            SELECT metric_date, currency, batches_processed, transactions_netted, gross_volume, net_volume, updated_at
+            FROM netting_metrics_daily
+            WHERE metric_date BETWEEN $1 AND $2
+              AND ($3::VARCHAR IS NULL OR currency = $3)
+            ORDER BY metric_date, currency
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(currency)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Sums all recorded metrics: batches, transactions, gross and net volume.
+    pub async fn totals(&self) -> Result<(i64, i64, Decimal, Decimal)> {
+        let row: (i64, i64, Decimal, Decimal) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(batches_processed), 0)::BIGINT,
+                   COALESCE(SUM(transactions_netted), 0)::BIGINT,
+                   COALESCE(SUM(gross_volume), 0),
+                   COALESCE(SUM(net_volume), 0)
+            FROM netting_metrics_daily
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::{AppError, Result};
+use crate::models::NettingReportRecord;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for stored batch netting reports.
+pub struct NettingReportRepository {
+    pool: PgPool,
+}
+
+impl NettingReportRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores a batch's report, replacing the one from an earlier netting run.
+    pub async fn upsert(&self, record: &NettingReportRecord) -> Result<NettingReportRecord> {
+        let row = sqlx::query_as::<_, NettingReportRecord>(
+            r#"
+            INSERT INTO netting_reports (id, batch_id, currency, total_transactions, participant_count, gross_volume, net_volume, reduction_amount, reduction_percentage, report, generated_at, window_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, (SELECT window_id FROM settlement_batches WHERE id = $2))
+            ON CONFLICT (batch_id) DO UPDATE SET
+                currency = EXCLUDED.currency,
+                total_transactions = EXCLUDED.total_transactions,
+                participant_count = EXCLUDED.participant_count,
+                gross_volume = EXCLUDED.gross_volume,
+                net_volume = EXCLUDED.net_volume,
+                reduction_amount = EXCLUDED.reduction_amount,
+                reduction_percentage = EXCLUDED.reduction_percentage,
+                report = EXCLUDED.report,
+                generated_at = EXCLUDED.generated_at
+            RETURNING id, batch_id, currency, total_transactions, participant_count, gross_volume, net_volume, reduction_amount, reduction_percentage, report, generated_at, window_id
+            "#,
+        )
+        .bind(record.id)
+        .bind(record.batch_id)
+        .bind(&record.currency)
+        .bind(record.total_transactions)
+        .bind(record.participant_count)
+        .bind(record.gross_volume)
+        .bind(record.net_volume)
+        .bind(record.reduction_amount)
+        .bind(record.reduction_percentage)
+        .bind(&record.report)
+        .bind(record.generated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the report of a batch.
+    pub async fn find_by_batch(&self, batch_id: Uuid) -> Result<Option<NettingReportRecord>> {
+        let row = sqlx::query_as::<_, NettingReportRecord>(
+            r#"
+            SELECT id, batch_id, currency, total_transactions, participant_count, gross_volume, net_volume, reduction_amount, reduction_percentage, report, generated_at, window_id
+            FROM netting_reports
+            WHERE batch_id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists reports generated in `[from, to)`, oldest first, optionally of one currency
+    /// or named settlement window.
+    pub async fn find_by_period(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        currency: Option<&str>,
+        window_id: Option<Uuid>,
+    ) -> Result<Vec<NettingReportRecord>> {
+        let rows = sqlx::query_as::<_, NettingReportRecord>(
+            r#"
+            SELECT id, batch_id, currency, total_transactions, participant_count, gross_volume, net_volume, reduction_amount, reduction_percentage, report, generated_at, window_id
+            FROM netting_reports
+            WHERE generated_at >= $1 AND generated_at < $2
+              AND ($3::VARCHAR IS NULL OR currency = $3)
+              AND ($4::UUID IS NULL OR window_id = $4)
+            ORDER BY generated_at, batch_id
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(currency)
+        .bind(window_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
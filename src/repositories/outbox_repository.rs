@@ -0,0 +1,163 @@
+use crate::error::{AppError, Result};
+use crate::models::OutboxMessage;
+use crate::observability::timed;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for the transactional event outbox.
+pub struct OutboxRepository {
+    pool: PgPool,
+}
+
+impl OutboxRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queues an event on its own.
+    pub async fn enqueue(&self, message: &OutboxMessage) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        Self::enqueue_in(&mut tx, message).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(())
+    }
+
+    /// Queues an event within an open database transaction, so it is only published
+    /// if the change it describes commits.
+    pub async fn enqueue_in(tx: &mut Transaction<'_, Postgres>, message: &OutboxMessage) -> Result<()> {
+        timed(
+            "event_outbox.insert",
+            sqlx::query(
+                r#"
+                INSERT INTO event_outbox (id, topic, partition_key, payload)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(message.id)
+            .bind(&message.topic)
+            .bind(&message.partition_key)
+            .bind(&message.payload)
+            .execute(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Gets unpublished events, oldest first.
+    pub async fn find_unpublished(&self, limit: i64) -> Result<Vec<OutboxMessage>> {
+        let rows = sqlx::query_as::<_, OutboxMessage>(
+            r#"
+            SELECT id, topic, partition_key, payload, created_at, published_at, attempts, last_error
+            FROM event_outbox
+            WHERE published_at IS NULL
+            ORDER BY created_at, id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Gets events queued under a partition key, oldest first.
+    pub async fn find_by_partition_key(&self, topic: &str, partition_key: &str) -> Result<Vec<OutboxMessage>> {
+        let rows = sqlx::query_as::<_, OutboxMessage>(
+            r#"
+            SELECT id, topic, partition_key, payload, created_at, published_at, attempts, last_error
+            FROM event_outbox
+            WHERE topic = $1 AND partition_key = $2
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(topic)
+        .bind(partition_key)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Marks an event as published.
+    pub async fn mark_published(&self, id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE event_outbox
+            SET published_at = NOW(), attempts = attempts + 1, last_error = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Records a failed publish attempt; the event stays queued.
+    pub async fn record_failure(&self, id: Uuid, error: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE event_outbox
+            SET attempts = attempts + 1, last_error = $2
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Claims events of a topic not yet applied to read models, oldest first. Claimed
+    /// rows stay locked until `tx` ends, so concurrent projectors skip them.
+    pub async fn claim_unprojected_in(
+        tx: &mut Transaction<'_, Postgres>,
+        topic: &str,
+        limit: i64,
+    ) -> Result<Vec<OutboxMessage>> {
+        let rows = sqlx::query_as::<_, OutboxMessage>(
+            r#"
+            SELECT id, topic, partition_key, payload, created_at, published_at, attempts, last_error
+            FROM event_outbox
+            WHERE topic = $1 AND projected_at IS NULL
+            ORDER BY created_at, id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(topic)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Marks events as applied to read models.
+    pub async fn mark_projected_in(tx: &mut Transaction<'_, Postgres>, ids: &[Uuid]) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE event_outbox
+            SET projected_at = NOW()
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
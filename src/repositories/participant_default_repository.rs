@@ -0,0 +1,111 @@
+use crate::error::{AppError, Result};
+use crate::models::{NettingPosition, ParticipantDefault};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for participants declared in default and the batch positions rewritten
+/// without them.
+pub struct ParticipantDefaultRepository {
+    pool: PgPool,
+}
+
+impl ParticipantDefaultRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records a default and replaces the batch's netting positions with the adjusted
+    /// ones in a single transaction.
+    pub async fn create(
+        &self,
+        record: &ParticipantDefault,
+        positions: &[NettingPosition],
+    ) -> Result<ParticipantDefault> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let row = sqlx::query_as::<_, ParticipantDefault>(
+            r#"
+            INSERT INTO participant_defaults (id, batch_id, participant_id, currency, unfunded_amount, resolution, reason, report, declared_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, batch_id, participant_id, currency, unfunded_amount, resolution, reason, report, declared_at
+            "#,
+        )
+        .bind(record.id)
+        .bind(record.batch_id)
+        .bind(record.participant_id)
+        .bind(&record.currency)
+        .bind(record.unfunded_amount)
+        .bind(record.resolution)
+        .bind(&record.reason)
+        .bind(&record.report)
+        .bind(record.declared_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        sqlx::query("DELETE FROM netting_positions WHERE batch_id = $1")
+            .bind(record.batch_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        for position in positions {
+            sqlx::query(
+                r#"
+                INSERT INTO netting_positions (batch_id, participant_id, currency, gross_receivable, gross_payable, net_position, transaction_count, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+            )
+            .bind(position.batch_id)
+            .bind(position.participant_id)
+            .bind(&position.currency)
+            .bind(position.gross_receivable)
+            .bind(position.gross_payable)
+            .bind(position.net_position)
+            .bind(position.transaction_count)
+            .bind(position.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Finds the default of a participant in a batch.
+    pub async fn find(&self, batch_id: Uuid, participant_id: Uuid) -> Result<Option<ParticipantDefault>> {
+        let row = sqlx::query_as::<_, ParticipantDefault>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, unfunded_amount, resolution, reason, report, declared_at
+            FROM participant_defaults
+            WHERE batch_id = $1 AND participant_id = $2
+            "#,
+        )
+        .bind(batch_id)
+        .bind(participant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the defaults declared in a batch, oldest first.
+    pub async fn find_by_batch(&self, batch_id: Uuid) -> Result<Vec<ParticipantDefault>> {
+        let rows = sqlx::query_as::<_, ParticipantDefault>(
+            r#"
+            SELECT id, batch_id, participant_id, currency, unfunded_amount, resolution, reason, report, declared_at
+            FROM participant_defaults
+            WHERE batch_id = $1
+            ORDER BY declared_at, id
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,208 @@
+use crate::error::{AppError, Result};
+use crate::models::{PvpSettlement, PvpStatus};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for payment-versus-payment settlements.
+pub struct PvpRepository {
+    pool: PgPool,
+}
+
+impl PvpRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records a settlement. Returns None if one with the same idempotency key exists.
+    pub async fn create_in(tx: &mut Transaction<'_, Postgres>, pvp: &PvpSettlement) -> Result<Option<PvpSettlement>> {
+        sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            INSERT INTO pvp_settlements (id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            RETURNING id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            "#,
+        )
+        .bind(pvp.id)
+        .bind(&pvp.external_id)
+        .bind(&pvp.idempotency_key)
+        .bind(pvp.first_source_account_id)
+        .bind(pvp.first_destination_account_id)
+        .bind(pvp.first_amount)
+        .bind(&pvp.first_currency)
+        .bind(pvp.first_funded)
+        .bind(pvp.second_source_account_id)
+        .bind(pvp.second_destination_account_id)
+        .bind(pvp.second_amount)
+        .bind(&pvp.second_currency)
+        .bind(pvp.second_funded)
+        .bind(pvp.status)
+        .bind(pvp.expires_at)
+        .bind(pvp.settled_at)
+        .bind(pvp.cancelled_at)
+        .bind(&pvp.cancel_reason)
+        .bind(pvp.created_at)
+        .bind(pvp.updated_at)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<PvpSettlement>> {
+        let row = sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            SELECT id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            FROM pvp_settlements
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_by_idempotency_key(&self, idempotency_key: &str) -> Result<Option<PvpSettlement>> {
+        let row = sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            SELECT id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            FROM pvp_settlements
+            WHERE idempotency_key = $1
+            "#,
+        )
+        .bind(idempotency_key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a settlement and locks it until the transaction ends.
+    pub async fn find_for_update_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Option<PvpSettlement>> {
+        sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            SELECT id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            FROM pvp_settlements
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Lists settlements newest first, optionally in one status.
+    pub async fn list(&self, status: Option<PvpStatus>, limit: i64, offset: i64) -> Result<Vec<PvpSettlement>> {
+        let rows = sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            SELECT id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            FROM pvp_settlements
+            WHERE ($1::pvp_status IS NULL OR status = $1)
+            ORDER BY created_at DESC, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    pub async fn count(&self, status: Option<PvpStatus>) -> Result<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM pvp_settlements
+            WHERE ($1::pvp_status IS NULL OR status = $1)
+            "#,
+        )
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(count.0)
+    }
+
+    /// IDs of settlements awaiting funding, those closest to their deadline first.
+    pub async fn find_awaiting(&self, limit: i64) -> Result<Vec<Uuid>> {
+        let rows: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT id
+            FROM pvp_settlements
+            WHERE status = 'AWAITING_FUNDING'
+            ORDER BY expires_at, id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Records whether each payer is funded.
+    pub async fn update_funding_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        first_funded: bool,
+        second_funded: bool,
+    ) -> Result<PvpSettlement> {
+        sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            UPDATE pvp_settlements
+            SET first_funded = $2, second_funded = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(first_funded)
+        .bind(second_funded)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn mark_settled_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<PvpSettlement> {
+        sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            UPDATE pvp_settlements
+            SET status = 'SETTLED', first_funded = TRUE, second_funded = TRUE, settled_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    pub async fn cancel_in(tx: &mut Transaction<'_, Postgres>, id: Uuid, reason: &str) -> Result<PvpSettlement> {
+        sqlx::query_as::<_, PvpSettlement>(
+            r#"
+            UPDATE pvp_settlements
+            SET status = 'CANCELLED', cancelled_at = NOW(), cancel_reason = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, external_id, idempotency_key, first_source_account_id, first_destination_account_id, first_amount, first_currency, first_funded, second_source_account_id, second_destination_account_id, second_amount, second_currency, second_funded, status, expires_at, settled_at, cancelled_at, cancel_reason, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(reason)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+}
@@ -0,0 +1,184 @@
+use crate::error::{AppError, Result};
+use crate::models::{BalanceBreak, BalanceCheck};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository deriving balances from the ledger and storing balance breaks.
+pub struct ReconciliationRepository {
+    pool: PgPool,
+}
+
+impl ReconciliationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Compares every stored balance that has ledger entries with the balance derived
+    /// from them. The derivation starts from the balance before the account's first
+    /// entry and adds every credit and subtracts every debit since.
+    ///
+    /// Stored balances and entries are read from one snapshot, so postings made while
+    /// the check runs cannot show up as breaks.
+    pub async fn check_balances(&self) -> Result<Vec<BalanceCheck>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::Database)?;
+
+        let rows = sqlx::query_as::<_, BalanceCheck>(
+            r#"
+            WITH movement AS (
+                SELECT account_id, currency,
+                       COUNT(*) AS entry_count,
+                       SUM(CASE WHEN entry_type = 'CREDIT' THEN amount ELSE -amount END) AS net
+                FROM ledger_entries
+                GROUP BY account_id, currency
+            ), opening AS (
+                SELECT DISTINCT ON (account_id, currency) account_id, currency,
+                       balance_after - CASE WHEN entry_type = 'CREDIT' THEN amount ELSE -amount END AS balance
+                FROM ledger_entries
+                ORDER BY account_id, currency, created_at, id
+            )
+            SELECT b.account_id, b.currency,
+                   b.available_balance + b.pending_balance + b.reserved_balance AS stored_balance,
+                   o.balance + m.net AS derived_balance,
+                   m.entry_count
+            FROM account_balances b
+            JOIN movement m ON m.account_id = b.account_id AND m.currency = b.currency
+            JOIN opening o ON o.account_id = b.account_id AND o.currency = b.currency
+            ORDER BY b.account_id, b.currency
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(rows)
+    }
+
+    /// Finds the open break of an account, if any.
+    pub async fn find_open(&self, account_id: Uuid, currency: &str) -> Result<Option<BalanceBreak>> {
+        let row = sqlx::query_as::<_, BalanceBreak>(
+            r#"
+            SELECT id, account_id, currency, stored_balance, derived_balance, difference, entry_count, detected_at, last_seen_at, resolved_at
+            FROM balance_breaks
+            WHERE account_id = $1 AND currency = $2 AND resolved_at IS NULL
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Records a break, or refreshes the figures of the account's open break.
+    pub async fn upsert_open(&self, balance_break: &BalanceBreak) -> Result<BalanceBreak> {
+        let row = sqlx::query_as::<_, BalanceBreak>(
+            r#"
+            INSERT INTO balance_breaks (id, account_id, currency, stored_balance, derived_balance, difference, entry_count, detected_at, last_seen_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (account_id, currency) WHERE resolved_at IS NULL DO UPDATE SET
+                stored_balance = EXCLUDED.stored_balance,
+                derived_balance = EXCLUDED.derived_balance,
+                difference = EXCLUDED.difference,
+                entry_count = EXCLUDED.entry_count,
+                last_seen_at = EXCLUDED.last_seen_at
+            RETURNING id, account_id, currency, stored_balance, derived_balance, difference, entry_count, detected_at, last_seen_at, resolved_at
+            "#,
+        )
+        .bind(balance_break.id)
+        .bind(balance_break.account_id)
+        .bind(&balance_break.currency)
+        .bind(balance_break.stored_balance)
+        .bind(balance_break.derived_balance)
+        .bind(balance_break.difference)
+        .bind(balance_break.entry_count)
+        .bind(balance_break.detected_at)
+        .bind(balance_break.last_seen_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Resolves the open breaks of accounts that were checked and no longer break.
+    /// Returns how many were resolved.
+    pub async fn resolve_reconciled(&self, account_ids: &[Uuid], currencies: &[String]) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE balance_breaks b
+            SET resolved_at = NOW()
+            FROM UNNEST($1::UUID[], $2::VARCHAR[]) AS reconciled(account_id, currency)
+            WHERE b.account_id = reconciled.account_id
+              AND b.currency = reconciled.currency
+              AND b.resolved_at IS NULL
+            "#,
+        )
+        .bind(account_ids)
+        .bind(currencies)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Lists breaks, newest first.
+    pub async fn list(
+        &self,
+        open_only: bool,
+        account_id: Option<Uuid>,
+        currency: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BalanceBreak>> {
+        let rows = sqlx::query_as::<_, BalanceBreak>(
+            r#"
+            SELECT id, account_id, currency, stored_balance, derived_balance, difference, entry_count, detected_at, last_seen_at, resolved_at
+            FROM balance_breaks
+            WHERE (NOT $1 OR resolved_at IS NULL)
+              AND ($2::UUID IS NULL OR account_id = $2)
+              AND ($3::VARCHAR IS NULL OR currency = $3)
+            ORDER BY detected_at DESC, id
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(open_only)
+        .bind(account_id)
+        .bind(currency)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Counts breaks matching the `list` filters.
+    pub async fn count(&self, open_only: bool, account_id: Option<Uuid>, currency: Option<&str>) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM balance_breaks
+            WHERE (NOT $1 OR resolved_at IS NULL)
+              AND ($2::UUID IS NULL OR account_id = $2)
+              AND ($3::VARCHAR IS NULL OR currency = $3)
+            "#,
+        )
+        .bind(open_only)
+        .bind(account_id)
+        .bind(currency)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::{AppError, Result};
+use crate::models::ReplicationSlotStatus;
+use sqlx::PgPool;
+
+/// SQLSTATE raised when a replication slot is being read by another session.
+const OBJECT_IN_USE: &str = "55006";
+/// SQLSTATE raised when a replication slot already exists.
+const DUPLICATE_OBJECT: &str = "42710";
+
+/// Repository managing a logical replication slot and the publication it decodes.
+///
+/// Changes are read through the SQL interface to logical decoding with the built-in
+/// `pgoutput` plugin, so no extension or replication connection is needed. Publication
+/// and table names are identifiers, not bind parameters; callers must validate them.
+pub struct ReplicationRepository {
+    pool: PgPool,
+}
+
+impl ReplicationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates the publication for the tables, or sets its tables if it exists.
+    pub async fn ensure_publication(&self, publication: &str, tables: &[String]) -> Result<()> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_publication WHERE pubname = $1)")
+            .bind(publication)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        let statement = if exists {
+            format!("ALTER PUBLICATION {} SET TABLE {}", publication, tables.join(", "))
+        } else {
+            format!("CREATE PUBLICATION {} FOR TABLE {}", publication, tables.join(", "))
+        };
+        sqlx::query(&statement)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Creates the `pgoutput` slot if it does not exist. Returns true if it was created.
+    pub async fn ensure_slot(&self, slot: &str) -> Result<bool> {
+        let created = sqlx::query(
+            r#"
+            SELECT pg_create_logical_replication_slot($1, 'pgoutput')
+            WHERE NOT EXISTS (SELECT 1 FROM pg_replication_slots WHERE slot_name = $1)
+            "#,
+        )
+        .bind(slot)
+        .fetch_optional(&self.pool)
+        .await;
+
+        match created {
+            Ok(row) => Ok(row.is_some()),
+            // Another instance created it first
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(DUPLICATE_OBJECT) => Ok(false),
+            Err(e) => Err(AppError::Database(e)),
+        }
+    }
+
+    /// Reads decoded `pgoutput` messages after the slot's confirmed position without
+    /// consuming them, returning each message's LSN and bytes. Whole transactions are
+    /// returned, so more than `max_changes` messages may come back.
+    ///
+    /// Fails with `Unavailable` while another session is reading the slot.
+    pub async fn peek_changes(&self, slot: &str, publication: &str, max_changes: i64) -> Result<Vec<(String, Vec<u8>)>> {
+        let rows = sqlx::query_as::<_, (String, Vec<u8>)>(
+            r#"
+            SELECT lsn::TEXT, data
+            FROM pg_logical_slot_peek_binary_changes($1, NULL, $2::INT, 'proto_version', '1', 'publication_names', $3)
+            "#,
+        )
+        .bind(slot)
+        .bind(max_changes)
+        .bind(publication)
+        .fetch_all(&self.pool)
+        .await;
+
+        match rows {
+            Ok(rows) => Ok(rows),
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(OBJECT_IN_USE) => Err(AppError::Unavailable(
+                format!("Replication slot '{}' is in use by another session", slot),
+            )),
+            Err(e) => Err(AppError::Database(e)),
+        }
+    }
+
+    /// Moves the slot's confirmed position to `lsn`, releasing the WAL before it.
+    pub async fn advance_slot(&self, slot: &str, lsn: &str) -> Result<()> {
+        sqlx::query("SELECT pg_replication_slot_advance($1, $2::PG_LSN)")
+            .bind(slot)
+            .bind(lsn)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Gets the slot's state and how far it is behind the current WAL position.
+    pub async fn slot_status(&self, slot: &str) -> Result<Option<ReplicationSlotStatus>> {
+        let row = sqlx::query_as::<_, ReplicationSlotStatus>(
+            r#"
+            SELECT slot_name::TEXT AS slot_name,
+                   plugin::TEXT AS plugin,
+                   active,
+                   restart_lsn::TEXT AS restart_lsn,
+                   confirmed_flush_lsn::TEXT AS confirmed_flush_lsn,
+                   COALESCE(pg_wal_lsn_diff(pg_current_wal_lsn(), confirmed_flush_lsn), 0)::BIGINT AS lag_bytes,
+                   COALESCE(pg_wal_lsn_diff(pg_current_wal_lsn(), restart_lsn), 0)::BIGINT AS retained_bytes
+            FROM pg_replication_slots
+            WHERE slot_name = $1
+            "#,
+        )
+        .bind(slot)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Drops the slot, if it exists, so the server stops retaining WAL for it.
+    pub async fn drop_slot(&self, slot: &str) -> Result<bool> {
+        let dropped = sqlx::query(
+            r#"
+            SELECT pg_drop_replication_slot(slot_name)
+            FROM pg_replication_slots
+            WHERE slot_name = $1
+            "#,
+        )
+        .bind(slot)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(dropped.is_some())
+    }
+}
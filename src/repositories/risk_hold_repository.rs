@@ -0,0 +1,297 @@
+use crate::error::{AppError, Result};
+use crate::models::{RiskHold, RiskHoldAction, RiskHoldAudit, RiskHoldStatus};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for transactions held for risk review and their audit trail.
+pub struct RiskHoldRepository {
+    pool: PgPool,
+}
+
+impl RiskHoldRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records a hold with its `Held` audit entry. If a hold already exists for the
+    /// idempotency key, that hold is returned instead.
+    pub async fn create(&self, hold: &RiskHold) -> Result<RiskHold> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let row = sqlx::query_as::<_, RiskHold>(
+            r#"
+            INSERT INTO risk_holds (id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            RETURNING id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            "#,
+        )
+        .bind(hold.id)
+        .bind(&hold.idempotency_key)
+        .bind(&hold.external_id)
+        .bind(hold.transaction_type)
+        .bind(hold.source_account_id)
+        .bind(hold.destination_account_id)
+        .bind(hold.amount)
+        .bind(&hold.currency)
+        .bind(&hold.request)
+        .bind(&hold.triggers)
+        .bind(hold.status)
+        .bind(hold.transaction_id)
+        .bind(&hold.reviewed_by)
+        .bind(&hold.review_reason)
+        .bind(hold.created_at)
+        .bind(hold.reviewed_at)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        let Some(row) = row else {
+            tx.rollback().await.map_err(AppError::Database)?;
+            return self
+                .find_by_idempotency_key(&hold.idempotency_key)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Risk hold for '{}' not found", hold.idempotency_key)));
+        };
+
+        Self::audit_in(&mut tx, &RiskHoldAudit::new(row.id, RiskHoldAction::Held, None, None)).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Finds a hold by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<RiskHold>> {
+        let row = sqlx::query_as::<_, RiskHold>(
+            r#"
+            SELECT id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            FROM risk_holds
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the hold of a request by its idempotency key.
+    pub async fn find_by_idempotency_key(&self, idempotency_key: &str) -> Result<Option<RiskHold>> {
+        let row = sqlx::query_as::<_, RiskHold>(
+            r#"
+            SELECT id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            FROM risk_holds
+            WHERE idempotency_key = $1
+            "#,
+        )
+        .bind(idempotency_key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists holds, oldest first so the review queue is worked in arrival order.
+    pub async fn list(&self, status: Option<RiskHoldStatus>, limit: i64, offset: i64) -> Result<Vec<RiskHold>> {
+        let rows = sqlx::query_as::<_, RiskHold>(
+            r#"
+            SELECT id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            FROM risk_holds
+            WHERE ($1::risk_hold_status IS NULL OR status = $1)
+            ORDER BY created_at, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Counts holds matching the `list` filter.
+    pub async fn count(&self, status: Option<RiskHoldStatus>) -> Result<i64> {
+        let row: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM risk_holds
+            WHERE ($1::risk_hold_status IS NULL OR status = $1)
+            "#,
+        )
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+
+    /// Records a reviewer's decision on a held item with its audit entry. Returns `None`
+    /// if the hold was already decided.
+    pub async fn decide(
+        &self,
+        id: Uuid,
+        status: RiskHoldStatus,
+        reviewed_by: &str,
+        reason: &str,
+    ) -> Result<Option<RiskHold>> {
+        let action = match status {
+            RiskHoldStatus::Released => RiskHoldAction::Released,
+            RiskHoldStatus::Rejected => RiskHoldAction::Rejected,
+            RiskHoldStatus::Held => {
+                return Err(AppError::Validation("A hold cannot be decided back to HELD".to_string()))
+            }
+        };
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let row = sqlx::query_as::<_, RiskHold>(
+            r#"
+            UPDATE risk_holds
+            SET status = $2, reviewed_by = $3, review_reason = $4, reviewed_at = NOW()
+            WHERE id = $1 AND status = 'HELD'
+            RETURNING id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(reviewed_by)
+        .bind(reason)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if let Some(hold) = &row {
+            let entry = RiskHoldAudit::new(hold.id, action, Some(reviewed_by.to_string()), Some(reason.to_string()));
+            Self::audit_in(&mut tx, &entry).await?;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Finds a hold by ID and locks it until the transaction ends.
+    pub async fn find_for_update_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Option<RiskHold>> {
+        sqlx::query_as::<_, RiskHold>(
+            r#"
+            SELECT id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            FROM risk_holds
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Replaces the request a hold posts on release, and its amount.
+    pub async fn update_request_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        amount: Decimal,
+        request: &serde_json::Value,
+    ) -> Result<RiskHold> {
+        sqlx::query_as::<_, RiskHold>(
+            r#"
+            UPDATE risk_holds
+            SET amount = $2, request = $3
+            WHERE id = $1
+            RETURNING id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            "#,
+        )
+        .bind(id)
+        .bind(amount)
+        .bind(request)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Links a released hold to the transaction it posted.
+    pub async fn set_transaction(&self, id: Uuid, transaction_id: Uuid) -> Result<RiskHold> {
+        let row = sqlx::query_as::<_, RiskHold>(
+            r#"
+            UPDATE risk_holds
+            SET transaction_id = $2
+            WHERE id = $1
+            RETURNING id, idempotency_key, external_id, type, source_account_id, destination_account_id, amount, currency, request, triggers, status, transaction_id, reviewed_by, review_reason, created_at, reviewed_at
+            "#,
+        )
+        .bind(id)
+        .bind(transaction_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Puts a released hold back in the queue after it failed to post, with a
+    /// `Reopened` audit entry giving the failure.
+    pub async fn reopen(&self, id: Uuid, reason: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let reopened = sqlx::query(
+            r#"
+            UPDATE risk_holds
+            SET status = 'HELD', reviewed_by = NULL, review_reason = NULL, reviewed_at = NULL
+            WHERE id = $1 AND status = 'RELEASED' AND transaction_id IS NULL
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if reopened.rows_affected() > 0 {
+            let entry = RiskHoldAudit::new(id, RiskHoldAction::Reopened, None, Some(reason.to_string()));
+            Self::audit_in(&mut tx, &entry).await?;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(())
+    }
+
+    /// Gets a hold's audit trail in order.
+    pub async fn find_audit(&self, hold_id: Uuid) -> Result<Vec<RiskHoldAudit>> {
+        let rows = sqlx::query_as::<_, RiskHoldAudit>(
+            r#"
+            SELECT id, hold_id, action, actor, reason, recorded_at
+            FROM risk_hold_audit
+            WHERE hold_id = $1
+            ORDER BY recorded_at, id
+            "#,
+        )
+        .bind(hold_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    async fn audit_in(tx: &mut Transaction<'_, Postgres>, entry: &RiskHoldAudit) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO risk_hold_audit (id, hold_id, action, actor, reason, recorded_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(entry.id)
+        .bind(entry.hold_id)
+        .bind(entry.action)
+        .bind(&entry.actor)
+        .bind(&entry.reason)
+        .bind(entry.recorded_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::core::saga::SagaStore;
+use crate::error::{AppError, Result};
+use crate::models::SagaState;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for persisted saga state.
+pub struct SagaRepository {
+    pool: PgPool,
+}
+
+impl SagaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts a saga or overwrites its progress.
+    pub async fn save(&self, state: &SagaState) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO sagas (id, saga_type, status, current_step, context, error, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE
+            SET status = EXCLUDED.status,
+                current_step = EXCLUDED.current_step,
+                context = EXCLUDED.context,
+                error = EXCLUDED.error,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(state.id)
+        .bind(&state.saga_type)
+        .bind(state.status)
+        .bind(state.current_step)
+        .bind(&state.context)
+        .bind(&state.error)
+        .bind(state.created_at)
+        .bind(state.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Finds a saga by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<SagaState>> {
+        let row = sqlx::query_as::<_, SagaState>(
+            r#"
+            SELECT id, saga_type, status, current_step, context, error, created_at, updated_at
+            FROM sagas
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds sagas of a type whose context carries an `instruction` of the batch
+    /// between two participants, in either direction, oldest first.
+    pub async fn find_by_instruction_parties(
+        &self,
+        saga_type: &str,
+        batch_id: Uuid,
+        participant_a: Uuid,
+        participant_b: Uuid,
+    ) -> Result<Vec<SagaState>> {
+        let rows = sqlx::query_as::<_, SagaState>(
+            r#"
+            SELECT id, saga_type, status, current_step, context, error, created_at, updated_at
+            FROM sagas
+            WHERE saga_type = $1
+              AND context->'instruction'->>'batch_id' = $2::text
+              AND context->'instruction'->>'from_participant' IN ($3::text, $4::text)
+              AND context->'instruction'->>'to_participant' IN ($3::text, $4::text)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(saga_type)
+        .bind(batch_id)
+        .bind(participant_a)
+        .bind(participant_b)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds sagas of a type that are still running or compensating, oldest first.
+    pub async fn find_unfinished(&self, saga_type: &str) -> Result<Vec<SagaState>> {
+        let rows = sqlx::query_as::<_, SagaState>(
+            r#"
+            SELECT id, saga_type, status, current_step, context, error, created_at, updated_at
+            FROM sagas
+            WHERE saga_type = $1 AND status IN ('RUNNING', 'COMPENSATING')
+            ORDER BY created_at
+            "#,
+        )
+        .bind(saga_type)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl SagaStore for SagaRepository {
+    async fn save(&self, state: &SagaState) -> Result<()> {
+        SagaRepository::save(self, state).await
+    }
+
+    async fn load(&self, id: Uuid) -> Result<Option<SagaState>> {
+        self.find_by_id(id).await
+    }
+}
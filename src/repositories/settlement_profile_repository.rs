@@ -0,0 +1,126 @@
+use crate::error::{AppError, Result};
+use crate::models::SettlementProfile;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for participant settlement profiles.
+pub struct SettlementProfileRepository {
+    pool: PgPool,
+}
+
+impl SettlementProfileRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates or replaces an account's settlement profile for the profile's currency.
+    pub async fn upsert(&self, profile: &SettlementProfile) -> Result<SettlementProfile> {
+        let row = sqlx::query_as::<_, SettlementProfile>(
+            r#"
+            INSERT INTO settlement_profiles (account_id, currency, account_name, routing_number, bank_account_number,
+                                             bank_account_type, bic, iban, rail, cut_off_time, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (account_id, currency) DO UPDATE
+            SET account_name = EXCLUDED.account_name,
+                routing_number = EXCLUDED.routing_number,
+                bank_account_number = EXCLUDED.bank_account_number,
+                bank_account_type = EXCLUDED.bank_account_type,
+                bic = EXCLUDED.bic,
+                iban = EXCLUDED.iban,
+                rail = EXCLUDED.rail,
+                cut_off_time = EXCLUDED.cut_off_time,
+                updated_at = EXCLUDED.updated_at
+            RETURNING account_id, currency, account_name, routing_number, bank_account_number, bank_account_type,
+                      bic, iban, rail, cut_off_time, created_at, updated_at
+            "#,
+        )
+        .bind(profile.account_id)
+        .bind(&profile.currency)
+        .bind(&profile.account_name)
+        .bind(&profile.routing_number)
+        .bind(&profile.bank_account_number)
+        .bind(profile.bank_account_type)
+        .bind(&profile.bic)
+        .bind(&profile.iban)
+        .bind(profile.rail)
+        .bind(profile.cut_off_time)
+        .bind(profile.created_at)
+        .bind(profile.updated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds an account's settlement profile for a currency.
+    pub async fn find(&self, account_id: Uuid, currency: &str) -> Result<Option<SettlementProfile>> {
+        let row = sqlx::query_as::<_, SettlementProfile>(
+            r#"
+            SELECT account_id, currency, account_name, routing_number, bank_account_number, bank_account_type,
+                   bic, iban, rail, cut_off_time, created_at, updated_at
+            FROM settlement_profiles
+            WHERE account_id = $1 AND currency = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(currency)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists an account's settlement profiles by currency.
+    pub async fn find_by_account(&self, account_id: Uuid) -> Result<Vec<SettlementProfile>> {
+        let rows = sqlx::query_as::<_, SettlementProfile>(
+            r#"
+            SELECT account_id, currency, account_name, routing_number, bank_account_number, bank_account_type,
+                   bic, iban, rail, cut_off_time, created_at, updated_at
+            FROM settlement_profiles
+            WHERE account_id = $1
+            ORDER BY currency
+            "#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds the settlement profiles of several accounts in one currency. Accounts
+    /// without a profile in the currency are omitted.
+    pub async fn find_by_accounts(&self, account_ids: &[Uuid], currency: &str) -> Result<Vec<SettlementProfile>> {
+        let rows = sqlx::query_as::<_, SettlementProfile>(
+            r#"
+            SELECT account_id, currency, account_name, routing_number, bank_account_number, bank_account_type,
+                   bic, iban, rail, cut_off_time, created_at, updated_at
+            FROM settlement_profiles
+            WHERE account_id = ANY($1) AND currency = $2
+            "#,
+        )
+        .bind(account_ids)
+        .bind(currency)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Deletes an account's settlement profile for a currency. Returns false if there
+    /// was none.
+    pub async fn delete(&self, account_id: Uuid, currency: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM settlement_profiles WHERE account_id = $1 AND currency = $2")
+            .bind(account_id)
+            .bind(currency)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::{AppError, Result};
+use crate::models::SettlementWindow;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for named settlement windows.
+pub struct SettlementWindowRepository {
+    pool: PgPool,
+}
+
+impl SettlementWindowRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates a new settlement window.
+    pub async fn create(&self, window: &SettlementWindow) -> Result<SettlementWindow> {
+        let row = sqlx::query_as::<_, SettlementWindow>(
+            r#"
+            INSERT INTO settlement_windows (id, name, currency, cut_off_time, timezone, active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, name, currency, cut_off_time, timezone, active, created_at, updated_at
+            "#,
+        )
+        .bind(window.id)
+        .bind(&window.name)
+        .bind(&window.currency)
+        .bind(window.cut_off_time)
+        .bind(&window.timezone)
+        .bind(window.active)
+        .bind(window.created_at)
+        .bind(window.updated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a settlement window by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<SettlementWindow>> {
+        let row = sqlx::query_as::<_, SettlementWindow>(
+            r#"
+            SELECT id, name, currency, cut_off_time, timezone, active, created_at, updated_at
+            FROM settlement_windows
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a currency's settlement window by name.
+    pub async fn find_by_name(&self, currency: &str, name: &str) -> Result<Option<SettlementWindow>> {
+        let row = sqlx::query_as::<_, SettlementWindow>(
+            r#"
+            SELECT id, name, currency, cut_off_time, timezone, active, created_at, updated_at
+            FROM settlement_windows
+            WHERE currency = $1 AND name = $2
+            "#,
+        )
+        .bind(currency)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists settlement windows, optionally for one currency and only active ones.
+    pub async fn list(&self, currency: Option<&str>, active_only: bool) -> Result<Vec<SettlementWindow>> {
+        let rows = sqlx::query_as::<_, SettlementWindow>(
+            r#"
+            SELECT id, name, currency, cut_off_time, timezone, active, created_at, updated_at
+            FROM settlement_windows
+            WHERE ($1::VARCHAR IS NULL OR currency = $1)
+              AND (NOT $2 OR active)
+            ORDER BY currency, cut_off_time, name
+            "#,
+        )
+        .bind(currency)
+        .bind(active_only)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Updates the mutable fields of a settlement window.
+    pub async fn update(&self, window: &SettlementWindow) -> Result<Option<SettlementWindow>> {
+        let row = sqlx::query_as::<_, SettlementWindow>(
+            r#"
+            UPDATE settlement_windows
+            SET name = $2,
+                cut_off_time = $3,
+                timezone = $4,
+                active = $5,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, currency, cut_off_time, timezone, active, created_at, updated_at
+            "#,
+        )
+        .bind(window.id)
+        .bind(&window.name)
+        .bind(window.cut_off_time)
+        .bind(&window.timezone)
+        .bind(window.active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Deactivates a settlement window. Batches keep referring to it, so windows are never
+    /// deleted outright.
+    pub async fn deactivate(&self, id: Uuid) -> Result<Option<SettlementWindow>> {
+        let row = sqlx::query_as::<_, SettlementWindow>(
+            r#"
+            UPDATE settlement_windows
+            SET active = FALSE, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, currency, cut_off_time, timezone, active, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
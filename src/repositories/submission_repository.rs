@@ -0,0 +1,231 @@
+use crate::error::{AppError, Result};
+use crate::models::TransactionSubmission;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for the asynchronous transaction submission queue.
+pub struct SubmissionRepository {
+    pool: PgPool,
+}
+
+impl SubmissionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queues a submission. Returns None if one with the same idempotency key exists.
+    pub async fn create(&self, submission: &TransactionSubmission) -> Result<Option<TransactionSubmission>> {
+        let row = sqlx::query_as::<_, TransactionSubmission>(
+            r#"
+            INSERT INTO transaction_submissions (id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            RETURNING id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at
+            "#,
+        )
+        .bind(submission.id)
+        .bind(&submission.idempotency_key)
+        .bind(&submission.request)
+        .bind(submission.status)
+        .bind(submission.attempts)
+        .bind(submission.max_attempts)
+        .bind(submission.next_attempt_at)
+        .bind(submission.transaction_id)
+        .bind(&submission.error_code)
+        .bind(&submission.last_error)
+        .bind(submission.completed_at)
+        .bind(submission.created_at)
+        .bind(submission.updated_at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a submission by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<TransactionSubmission>> {
+        let row = sqlx::query_as::<_, TransactionSubmission>(
+            r#"
+            SELECT id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at
+            FROM transaction_submissions
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a submission by ID and locks it until the transaction ends.
+    pub async fn find_for_update_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<TransactionSubmission>> {
+        sqlx::query_as::<_, TransactionSubmission>(
+            r#"
+            SELECT id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at
+            FROM transaction_submissions
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Replaces the queued request of a submission.
+    pub async fn update_request_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        request: &serde_json::Value,
+    ) -> Result<TransactionSubmission> {
+        sqlx::query_as::<_, TransactionSubmission>(
+            r#"
+            UPDATE transaction_submissions
+            SET request = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(request)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Finds a submission by idempotency key.
+    pub async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<TransactionSubmission>> {
+        let row = sqlx::query_as::<_, TransactionSubmission>(
+            r#"
+            SELECT id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at
+            FROM transaction_submissions
+            WHERE idempotency_key = $1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Claims up to `limit` queued submissions whose next attempt is due, oldest first,
+    /// marking them processing so concurrent workers skip them.
+    pub async fn claim_due(&self, limit: i64) -> Result<Vec<TransactionSubmission>> {
+        let rows = sqlx::query_as::<_, TransactionSubmission>(
+            r#"
+            UPDATE transaction_submissions
+            SET status = 'PROCESSING', attempts = attempts + 1, updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM transaction_submissions
+                WHERE status = 'QUEUED' AND next_attempt_at <= NOW()
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Saves the outcome of an attempt.
+    pub async fn update(&self, submission: &TransactionSubmission) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE transaction_submissions
+            SET status = $2, attempts = $3, next_attempt_at = $4, transaction_id = $5, error_code = $6,
+                last_error = $7, completed_at = $8, updated_at = $9
+            WHERE id = $1
+            "#,
+        )
+        .bind(submission.id)
+        .bind(submission.status)
+        .bind(submission.attempts)
+        .bind(submission.next_attempt_at)
+        .bind(submission.transaction_id)
+        .bind(&submission.error_code)
+        .bind(&submission.last_error)
+        .bind(submission.completed_at)
+        .bind(submission.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Returns submissions left processing since before `cutoff` (e.g. by a worker that
+    /// crashed mid-settlement) to the queue. Settlement is idempotent on the request's
+    /// key, so a transaction that did settle is not applied twice.
+    pub async fn release_stale(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE transaction_submissions
+            SET status = 'QUEUED', next_attempt_at = NOW(), updated_at = NOW()
+            WHERE status = 'PROCESSING' AND updated_at < $1
+            "#,
+        )
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Expires up to `limit` queued submissions that have waited longer than their
+    /// transaction type's TTL, oldest first. TTLs are given as in
+    /// [`TransactionRepository::find_expirable_in`](crate::repositories::TransactionRepository::find_expirable_in).
+    /// Submissions a worker has already claimed are left alone.
+    pub async fn expire_queued(
+        &self,
+        types: &[String],
+        ttl_secs: &[i64],
+        default_ttl_secs: i64,
+        limit: i64,
+    ) -> Result<Vec<TransactionSubmission>> {
+        let rows = sqlx::query_as::<_, TransactionSubmission>(
+            r#"
+            UPDATE transaction_submissions
+            SET status = 'EXPIRED', error_code = 'EXPIRED', last_error = 'Expired while queued',
+                completed_at = NOW(), updated_at = NOW()
+            WHERE id IN (
+                SELECT s.id FROM transaction_submissions s
+                LEFT JOIN UNNEST($1::text[], $2::bigint[]) AS p(type, ttl_secs)
+                    ON p.type = s.request->>'transaction_type'
+                WHERE s.status = 'QUEUED'
+                  AND COALESCE(p.ttl_secs, $3) > 0
+                  AND s.created_at < NOW() - COALESCE(p.ttl_secs, $3) * INTERVAL '1 second'
+                ORDER BY s.created_at
+                LIMIT $4
+                FOR UPDATE OF s SKIP LOCKED
+            )
+            RETURNING id, idempotency_key, request, status, attempts, max_attempts, next_attempt_at, transaction_id, error_code, last_error, completed_at, created_at, updated_at
+            "#,
+        )
+        .bind(types)
+        .bind(ttl_secs)
+        .bind(default_ttl_secs)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
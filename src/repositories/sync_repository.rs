@@ -0,0 +1,81 @@
+use crate::error::{AppError, Result};
+use crate::models::{SyncedAccount, SyncedTransaction};
+use sqlx::PgPool;
+
+/// Repository reading the account and transaction change feed.
+///
+/// Rows carry the change sequence and the ID of the database transaction that last
+/// wrote them, both stamped by trigger. Reads are bounded by database transaction ID so
+/// a window is only read once every write that falls in it has committed.
+pub struct SyncRepository {
+    pool: PgPool,
+}
+
+impl SyncRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Gets the sync watermark: the ID of the oldest database transaction still
+    /// running. Every write by an older transaction has committed or rolled back.
+    pub async fn watermark(&self) -> Result<i64> {
+        let watermark: i64 = sqlx::query_scalar("SELECT pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(watermark)
+    }
+
+    /// Lists accounts last written by database transactions in `[low, high)`, in change
+    /// sequence order after `after_seq`.
+    pub async fn account_changes(&self, low: i64, high: i64, after_seq: i64, limit: i64) -> Result<Vec<SyncedAccount>> {
+        let rows = sqlx::query_as::<_, SyncedAccount>(
+            r#"
+            SELECT id, external_id, name, type, status, currency, metadata, anonymized_at, created_at, updated_at, change_seq
+            FROM accounts
+            WHERE change_xid >= $1 AND change_xid < $2 AND change_seq > $3
+            ORDER BY change_seq
+            LIMIT $4
+            "#,
+        )
+        .bind(low)
+        .bind(high)
+        .bind(after_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Lists transactions last written by database transactions in `[low, high)`, in
+    /// change sequence order after `after_seq`.
+    pub async fn transaction_changes(
+        &self,
+        low: i64,
+        high: i64,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<SyncedTransaction>> {
+        let rows = sqlx::query_as::<_, SyncedTransaction>(
+            r#"
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id, change_seq
+            FROM transactions
+            WHERE change_xid >= $1 AND change_xid < $2 AND change_seq > $3
+            ORDER BY change_seq
+            LIMIT $4
+            "#,
+        )
+        .bind(low)
+        .bind(high)
+        .bind(after_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::{AppError, Result};
+use crate::models::TransactionAmendment;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for the amendment history of queued transactions.
+pub struct TransactionAmendmentRepository {
+    pool: PgPool,
+}
+
+impl TransactionAmendmentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records an amendment within the transaction that applied it.
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        amendment: &TransactionAmendment,
+    ) -> Result<TransactionAmendment> {
+        sqlx::query_as::<_, TransactionAmendment>(
+            r#"
+            INSERT INTO transaction_amendments (id, target, target_id, changes, amended_by, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, target, target_id, changes, amended_by, reason, created_at
+            "#,
+        )
+        .bind(amendment.id)
+        .bind(amendment.target)
+        .bind(amendment.target_id)
+        .bind(&amendment.changes)
+        .bind(&amendment.amended_by)
+        .bind(&amendment.reason)
+        .bind(amendment.created_at)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)
+    }
+
+    /// Lists the amendments made to a submission or risk hold, oldest first.
+    pub async fn find_by_target(&self, target_id: Uuid) -> Result<Vec<TransactionAmendment>> {
+        sqlx::query_as::<_, TransactionAmendment>(
+            r#"
+            SELECT id, target, target_id, changes, amended_by, reason, created_at
+            FROM transaction_amendments
+            WHERE target_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(target_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::{AppError, Result};
+use crate::models::TransactionAuditEntry;
+use crate::observability::timed;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+/// Repository for the transaction audit log.
+pub struct TransactionAuditRepository {
+    pool: PgPool,
+}
+
+impl TransactionAuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Appends an entry to the audit log.
+    pub async fn record(&self, entry: &TransactionAuditEntry) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        Self::record_in(&mut tx, entry).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(())
+    }
+
+    /// Appends an entry within an open database transaction, so it is only kept if
+    /// the change it describes commits.
+    pub async fn record_in(tx: &mut Transaction<'_, Postgres>, entry: &TransactionAuditEntry) -> Result<()> {
+        timed(
+            "transaction_audit_log.insert",
+            sqlx::query(
+                r#"
+                INSERT INTO transaction_audit_log (id, transaction_id, action, detail, recorded_at)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(entry.id)
+            .bind(entry.transaction_id)
+            .bind(entry.action)
+            .bind(&entry.detail)
+            .bind(entry.recorded_at)
+            .execute(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Records audit entries with one multi-row insert within an existing database transaction.
+    pub async fn record_many_in(tx: &mut Transaction<'_, Postgres>, entries: &[TransactionAuditEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut insert =
+            QueryBuilder::<Postgres>::new("INSERT INTO transaction_audit_log (id, transaction_id, action, detail, recorded_at) ");
+        insert.push_values(entries, |mut row, entry| {
+            row.push_bind(entry.id)
+                .push_bind(entry.transaction_id)
+                .push_bind(entry.action)
+                .push_bind(&entry.detail)
+                .push_bind(entry.recorded_at);
+        });
+        timed("transaction_audit_log.insert_many", insert.build().execute(&mut **tx))
+            .await
+            .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// Gets a transaction's audit entries, oldest first.
+    pub async fn find_by_transaction(&self, transaction_id: Uuid) -> Result<Vec<TransactionAuditEntry>> {
+        let rows = sqlx::query_as::<_, TransactionAuditEntry>(
+            r#"
+            SELECT id, transaction_id, action, detail, recorded_at
+            FROM transaction_audit_log
+            WHERE transaction_id = $1
+            ORDER BY recorded_at, id
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::error::{AppError, Result};
+use crate::models::{TransactionGroup, TransactionGroupStatus};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for groups of transactions settled all or nothing.
+pub struct TransactionGroupRepository {
+    pool: PgPool,
+}
+
+impl TransactionGroupRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records a group. Returns None if one with the same idempotency key exists.
+    pub async fn create(&self, group: &TransactionGroup) -> Result<Option<TransactionGroup>> {
+        let row = sqlx::query_as::<_, TransactionGroup>(
+            r#"
+            INSERT INTO transaction_groups (id, idempotency_key, transaction_keys, status, error_code, last_error, settled_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            RETURNING id, idempotency_key, transaction_keys, status, error_code, last_error, settled_at, created_at, updated_at
+            "#,
+        )
+        .bind(group.id)
+        .bind(&group.idempotency_key)
+        .bind(&group.transaction_keys)
+        .bind(group.status)
+        .bind(&group.error_code)
+        .bind(&group.last_error)
+        .bind(group.settled_at)
+        .bind(group.created_at)
+        .bind(group.updated_at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<TransactionGroup>> {
+        let row = sqlx::query_as::<_, TransactionGroup>(
+            r#"
+            SELECT id, idempotency_key, transaction_keys, status, error_code, last_error, settled_at, created_at, updated_at
+            FROM transaction_groups
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_by_idempotency_key(&self, idempotency_key: &str) -> Result<Option<TransactionGroup>> {
+        let row = sqlx::query_as::<_, TransactionGroup>(
+            r#"
+            SELECT id, idempotency_key, transaction_keys, status, error_code, last_error, settled_at, created_at, updated_at
+            FROM transaction_groups
+            WHERE idempotency_key = $1
+            "#,
+        )
+        .bind(idempotency_key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Records the outcome of an attempt to settle a group: `SETTLED`, or `FAILED` with
+    /// the attempt's error. Settled groups keep their status; returns None for them.
+    pub async fn update_status(
+        &self,
+        id: Uuid,
+        status: TransactionGroupStatus,
+        error_code: Option<&str>,
+        last_error: Option<&str>,
+    ) -> Result<Option<TransactionGroup>> {
+        sqlx::query_as::<_, TransactionGroup>(
+            r#"
+            UPDATE transaction_groups
+            SET status = $2,
+                error_code = $3,
+                last_error = $4,
+                settled_at = CASE WHEN $2 = 'SETTLED'::transaction_group_status THEN NOW() ELSE settled_at END,
+                updated_at = NOW()
+            WHERE id = $1 AND status <> 'SETTLED'
+            RETURNING id, idempotency_key, transaction_keys, status, error_code, last_error, settled_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(error_code)
+        .bind(last_error)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)
+    }
+}
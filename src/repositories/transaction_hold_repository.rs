@@ -0,0 +1,119 @@
+use crate::error::{AppError, Result};
+use crate::models::TransactionHold;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Repository for funds held against pending transactions.
+pub struct TransactionHoldRepository {
+    pool: PgPool,
+}
+
+impl TransactionHoldRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Reserves the hold's amount on its account and records the hold.
+    /// Returns an error if the account's available balance does not cover it.
+    pub async fn place(&self, hold: &TransactionHold) -> Result<TransactionHold> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let reserved = sqlx::query(
+            r#"
+            UPDATE account_balances
+            SET available_balance = available_balance - $3,
+                reserved_balance = reserved_balance + $3,
+                version = version + 1,
+                last_updated = NOW()
+            WHERE account_id = $1 AND currency = $2
+              AND available_balance >= $3
+            "#,
+        )
+        .bind(hold.account_id)
+        .bind(&hold.currency)
+        .bind(hold.amount)
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+        if reserved.rows_affected() == 0 {
+            return Err(AppError::InsufficientFunds("Insufficient funds for hold".to_string()));
+        }
+
+        let row = sqlx::query_as::<_, TransactionHold>(
+            r#"
+            INSERT INTO transaction_holds (transaction_id, account_id, currency, amount, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING transaction_id, account_id, currency, amount, created_at, released_at
+            "#,
+        )
+        .bind(hold.transaction_id)
+        .bind(hold.account_id)
+        .bind(&hold.currency)
+        .bind(hold.amount)
+        .bind(hold.created_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Releases a transaction's hold back to its account's available balance within an
+    /// existing database transaction. Returns `None` if the transaction has no
+    /// unreleased hold.
+    pub async fn release_in(
+        tx: &mut Transaction<'_, Postgres>,
+        transaction_id: Uuid,
+    ) -> Result<Option<TransactionHold>> {
+        let hold = sqlx::query_as::<_, TransactionHold>(
+            r#"
+            UPDATE transaction_holds
+            SET released_at = NOW()
+            WHERE transaction_id = $1 AND released_at IS NULL
+            RETURNING transaction_id, account_id, currency, amount, created_at, released_at
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        if let Some(hold) = &hold {
+            sqlx::query(
+                r#"
+                UPDATE account_balances
+                SET available_balance = available_balance + LEAST($3, reserved_balance),
+                    reserved_balance = reserved_balance - LEAST($3, reserved_balance),
+                    version = version + 1,
+                    last_updated = NOW()
+                WHERE account_id = $1 AND currency = $2
+                "#,
+            )
+            .bind(hold.account_id)
+            .bind(&hold.currency)
+            .bind(hold.amount)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::Database)?;
+        }
+
+        Ok(hold)
+    }
+
+    pub async fn find_by_transaction(&self, transaction_id: Uuid) -> Result<Option<TransactionHold>> {
+        let row = sqlx::query_as::<_, TransactionHold>(
+            r#"
+            SELECT transaction_id, account_id, currency, amount, created_at, released_at
+            FROM transaction_holds
+            WHERE transaction_id = $1
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+}
@@ -1,7 +1,15 @@
 use crate::error::{AppError, Result};
-use crate::models::{TransactionRecord, TransactionStatus, TransactionType};
+use crate::models::{
+    SettlementRoute, TransactionAuditAction, TransactionAuditEntry, TransactionPriority, TransactionRecord,
+    TransactionStatus, TransactionType,
+};
+use crate::observability::timed;
+use crate::repositories::TransactionAuditRepository;
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
 /// Repository for TransactionRecord operations.
@@ -14,69 +22,329 @@ impl TransactionRepository {
         Self { pool }
     }
 
+    /// Maps an insert that lost the race for an external ID to the error a duplicate
+    /// caught before posting gets.
+    pub(crate) fn map_insert_error(error: sqlx::Error) -> AppError {
+        match &error {
+            sqlx::Error::Database(db) if db.constraint() == Some("idx_transactions_dedupe_key") => {
+                AppError::Validation("external_id: External ID has already been used".to_string())
+            }
+            _ => AppError::Database(error),
+        }
+    }
+
     /// Creates a new transaction record.
     pub async fn create(&self, transaction: &TransactionRecord) -> Result<TransactionRecord> {
-        let row = sqlx::query_as::<_, TransactionRecord>(
+        let row = timed(
+            "transactions.insert",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
+                RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                "#,
+            )
+            .bind(transaction.id)
+            .bind(&transaction.external_id)
+            .bind(&transaction.transaction_type)
+            .bind(&transaction.status)
+            .bind(transaction.source_account_id)
+            .bind(transaction.destination_account_id)
+            .bind(transaction.amount)
+            .bind(&transaction.currency)
+            .bind(transaction.fee_amount)
+            .bind(transaction.net_amount)
+            .bind(transaction.settlement_batch_id)
+            .bind(&transaction.idempotency_key)
+            .bind(&transaction.metadata)
+            .bind(transaction.created_at)
+            .bind(transaction.settled_at)
+            .bind(transaction.settlement_route)
+            .bind(transaction.priority)
+            .bind(&transaction.source_system)
+            .bind(transaction.resubmission_of)
+            .bind(&transaction.dedupe_key)
+            .bind(&transaction.type_code)
+            .bind(transaction.exclude_from_netting)
+            .bind(&transaction.narrative)
+            .bind(&transaction.reference)
+            .bind(transaction.link_id)
+            .fetch_one(&self.pool),
+        )
+        .await
+        .map_err(Self::map_insert_error)?;
+
+        Ok(row)
+    }
+
+    /// Creates a transaction record within an existing database transaction.
+    pub async fn create_in(
+        tx: &mut Transaction<'_, Postgres>,
+        transaction: &TransactionRecord,
+    ) -> Result<TransactionRecord> {
+        let row = timed(
+            "transactions.insert",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
+                RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                "#,
+            )
+            .bind(transaction.id)
+            .bind(&transaction.external_id)
+            .bind(transaction.transaction_type)
+            .bind(transaction.status)
+            .bind(transaction.source_account_id)
+            .bind(transaction.destination_account_id)
+            .bind(transaction.amount)
+            .bind(&transaction.currency)
+            .bind(transaction.fee_amount)
+            .bind(transaction.net_amount)
+            .bind(transaction.settlement_batch_id)
+            .bind(&transaction.idempotency_key)
+            .bind(&transaction.metadata)
+            .bind(transaction.created_at)
+            .bind(transaction.settled_at)
+            .bind(transaction.settlement_route)
+            .bind(transaction.priority)
+            .bind(&transaction.source_system)
+            .bind(transaction.resubmission_of)
+            .bind(&transaction.dedupe_key)
+            .bind(&transaction.type_code)
+            .bind(transaction.exclude_from_netting)
+            .bind(&transaction.narrative)
+            .bind(&transaction.reference)
+            .bind(transaction.link_id)
+            .fetch_one(&mut **tx),
+        )
+        .await
+        .map_err(Self::map_insert_error)?;
+
+        Ok(row)
+    }
+
+    /// Finds a transaction by ID and locks its row until the database transaction ends.
+    pub async fn find_for_update_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<TransactionRecord>> {
+        let row = timed(
+            "transactions.lock",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                FROM transactions
+                WHERE id = $1
+                FOR UPDATE
+                "#,
+            )
+            .bind(id)
+            .fetch_optional(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Marks a transaction settled within an existing database transaction.
+    pub async fn mark_settled_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<TransactionRecord> {
+        let row = timed(
+            "transactions.mark_settled",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                UPDATE transactions
+                SET status = 'SETTLED', settled_at = NOW()
+                WHERE id = $1
+                RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                "#,
+            )
+            .bind(id)
+            .fetch_one(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Locks up to `limit` pending transactions that have outlived their type's TTL,
+    /// oldest first, skipping rows other sessions hold. `types` and `ttl_secs` pair each
+    /// type name with its TTL; other types use `default_ttl_secs`. A TTL of zero or less
+    /// never expires.
+    pub async fn find_expirable_in(
+        tx: &mut Transaction<'_, Postgres>,
+        types: &[String],
+        ttl_secs: &[i64],
+        default_ttl_secs: i64,
+        limit: i64,
+    ) -> Result<Vec<TransactionRecord>> {
+        let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT t.id, t.external_id, t.type, t.status, t.source_account_id, t.destination_account_id, t.amount, t.currency, t.fee_amount, t.net_amount, t.settlement_batch_id, t.idempotency_key, t.metadata, t.created_at, t.settled_at, t.settlement_route, t.priority, t.source_system, t.resubmission_of, t.dedupe_key, t.type_code, t.exclude_from_netting, t.narrative, t.reference, t.link_id
+            FROM transactions t
+            LEFT JOIN UNNEST($1::text[], $2::bigint[]) AS p(type, ttl_secs) ON p.type = t.type::text
+            WHERE t.status = 'PENDING'
+              AND COALESCE(p.ttl_secs, $3) > 0
+              AND t.created_at < NOW() - COALESCE(p.ttl_secs, $3) * INTERVAL '1 second'
+            ORDER BY t.created_at
+            LIMIT $4
+            FOR UPDATE OF t SKIP LOCKED
             "#,
         )
-        .bind(transaction.id)
-        .bind(&transaction.external_id)
-        .bind(&transaction.transaction_type)
-        .bind(&transaction.status)
-        .bind(transaction.source_account_id)
-        .bind(transaction.destination_account_id)
-        .bind(transaction.amount)
-        .bind(&transaction.currency)
-        .bind(transaction.fee_amount)
-        .bind(transaction.net_amount)
-        .bind(transaction.settlement_batch_id)
-        .bind(&transaction.idempotency_key)
-        .bind(&transaction.metadata)
-        .bind(transaction.created_at)
-        .bind(transaction.settled_at)
-        .fetch_one(&self.pool)
+        .bind(types)
+        .bind(ttl_secs)
+        .bind(default_ttl_secs)
+        .bind(limit)
+        .fetch_all(&mut **tx)
         .await
         .map_err(AppError::Database)?;
 
-        Ok(row)
+        Ok(rows)
     }
 
-    /// Finds a transaction by ID.
-    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<TransactionRecord>> {
+    /// Marks a transaction expired within an existing database transaction.
+    pub async fn mark_expired_in(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<TransactionRecord> {
         let row = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
-            FROM transactions
+            UPDATE transactions
+            SET status = 'EXPIRED'
             WHERE id = $1
+            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             "#,
         )
         .bind(id)
-        .fetch_optional(&self.pool)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds a transaction by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<TransactionRecord>> {
+        let row = timed(
+            "transactions.find_by_id",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                FROM transactions
+                WHERE id = $1
+                "#,
+            )
+            .bind(id)
+            .fetch_optional(&self.pool),
+        )
         .await
         .map_err(AppError::Database)?;
 
         Ok(row)
     }
 
-    /// Finds a transaction by external ID.
+    /// Finds the first transaction with an external ID.
     pub async fn find_by_external_id(&self, external_id: &str) -> Result<Option<TransactionRecord>> {
-        let row = sqlx::query_as::<_, TransactionRecord>(
+        let row = timed(
+            "transactions.find_by_external_id",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                FROM transactions
+                WHERE external_id = $1
+                ORDER BY created_at, id
+                LIMIT 1
+                "#,
+            )
+            .bind(external_id)
+            .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the transaction holding an external ID's scoped key.
+    pub async fn find_by_dedupe_key(&self, dedupe_key: &str) -> Result<Option<TransactionRecord>> {
+        let row = timed(
+            "transactions.find_by_dedupe_key",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                FROM transactions
+                WHERE dedupe_key = $1
+                "#,
+            )
+            .bind(dedupe_key)
+            .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds every transaction with an external ID, ignoring case and surrounding
+    /// whitespace, optionally from one source system. Oldest first, so an original
+    /// precedes its resubmissions.
+    pub async fn find_all_by_external_id(
+        &self,
+        external_id: &str,
+        source_system: Option<&str>,
+    ) -> Result<Vec<TransactionRecord>> {
+        let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             FROM transactions
-            WHERE external_id = $1
+            WHERE LOWER(external_id) = LOWER(BTRIM($1))
+              AND ($2::text IS NULL OR source_system = $2)
+            ORDER BY created_at, id
             "#,
         )
         .bind(external_id)
-        .fetch_optional(&self.pool)
+        .bind(source_system)
+        .fetch_all(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
-        Ok(row)
+        Ok(rows)
+    }
+
+    /// Finds the reversals and fee refunds of the given transactions, oldest first.
+    pub async fn find_returns_of(&self, transaction_ids: &[Uuid]) -> Result<Vec<TransactionRecord>> {
+        let ids: Vec<String> = transaction_ids.iter().map(Uuid::to_string).collect();
+        let rows = sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+            FROM transactions
+            WHERE metadata->>'original_transaction_id' = ANY($1::text[])
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds the transactions settled together under a link, in creation order.
+    pub async fn find_by_link(&self, link_id: Uuid) -> Result<Vec<TransactionRecord>> {
+        let rows = sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+            FROM transactions
+            WHERE link_id = $1
+            ORDER BY created_at, idempotency_key
+            "#,
+        )
+        .bind(link_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
     }
 
     /// Finds a transaction by idempotency key.
@@ -84,15 +352,18 @@ impl TransactionRepository {
         &self,
         idempotency_key: &str,
     ) -> Result<Option<TransactionRecord>> {
-        let row = sqlx::query_as::<_, TransactionRecord>(
-            r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
-            FROM transactions
-            WHERE idempotency_key = $1
-            "#,
+        let row = timed(
+            "transactions.find_by_idempotency_key",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                FROM transactions
+                WHERE idempotency_key = $1
+                "#,
+            )
+            .bind(idempotency_key)
+            .fetch_optional(&self.pool),
         )
-        .bind(idempotency_key)
-        .fetch_optional(&self.pool)
         .await
         .map_err(AppError::Database)?;
 
@@ -111,7 +382,7 @@ impl TransactionRepository {
     ) -> Result<Vec<TransactionRecord>> {
         let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             FROM transactions
             WHERE ($1::transaction_type IS NULL OR type = $1)
               AND ($2::transaction_status IS NULL OR status = $2)
@@ -134,17 +405,52 @@ impl TransactionRepository {
         Ok(rows)
     }
 
-    /// Finds transactions by settlement batch.
+    /// Finds transactions by settlement batch in release order (priority, then FIFO).
     pub async fn find_by_batch(&self, batch_id: Uuid) -> Result<Vec<TransactionRecord>> {
         let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             FROM transactions
             WHERE settlement_batch_id = $1
-            ORDER BY created_at
+            ORDER BY priority, created_at
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Finds the transactions of a batch a participant pays or receives that were netted:
+    /// neither flagged to settle gross nor of a type in `gross_types`, by custom type code
+    /// or else base type. Ordered by creation, oldest first.
+    pub async fn find_netted_by_participant(
+        &self,
+        batch_id: Uuid,
+        participant_id: Uuid,
+        gross_types: &[String],
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<TransactionRecord>> {
+        let rows = sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+            FROM transactions
+            WHERE settlement_batch_id = $1
+              AND (source_account_id = $2 OR destination_account_id = $2)
+              AND NOT exclude_from_netting
+              AND NOT (COALESCE(type_code, type::text) = ANY($3))
+            ORDER BY created_at, id
+            LIMIT $4 OFFSET $5
             "#,
         )
         .bind(batch_id)
+        .bind(participant_id)
+        .bind(gross_types)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&self.pool)
         .await
         .map_err(AppError::Database)?;
@@ -152,6 +458,49 @@ impl TransactionRepository {
         Ok(rows)
     }
 
+    /// Counts the netted transactions of a batch a participant pays or receives.
+    pub async fn count_netted_by_participant(
+        &self,
+        batch_id: Uuid,
+        participant_id: Uuid,
+        gross_types: &[String],
+    ) -> Result<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM transactions
+            WHERE settlement_batch_id = $1
+              AND (source_account_id = $2 OR destination_account_id = $2)
+              AND NOT exclude_from_netting
+              AND NOT (COALESCE(type_code, type::text) = ANY($3))
+            "#,
+        )
+        .bind(batch_id)
+        .bind(participant_id)
+        .bind(gross_types)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(count.0)
+    }
+
+    /// Streams transactions in a settlement batch row by row instead of collecting them.
+    pub fn stream_by_batch(&self, batch_id: Uuid) -> BoxStream<'_, Result<TransactionRecord>> {
+        sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+            FROM transactions
+            WHERE settlement_batch_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(batch_id)
+        .fetch(&self.pool)
+        .map_err(AppError::Database)
+        .boxed()
+    }
+
     /// Updates transaction status.
     pub async fn update_status(
         &self,
@@ -169,7 +518,7 @@ impl TransactionRepository {
             UPDATE transactions
             SET status = $2, settled_at = COALESCE($3, settled_at)
             WHERE id = $1
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             "#,
         )
         .bind(id)
@@ -182,22 +531,187 @@ impl TransactionRepository {
         Ok(row)
     }
 
-    /// Assigns a transaction to a settlement batch.
+    /// Assigns a transaction to a settlement batch and records the assignment in the
+    /// transaction audit log.
     pub async fn assign_to_batch(
         &self,
         id: Uuid,
         batch_id: Uuid,
+    ) -> Result<Option<TransactionRecord>> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let row = Self::assign_to_batch_in(&mut tx, id, batch_id, false).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(row)
+    }
+
+    /// Creates transaction records with one multi-row insert within an existing database
+    /// transaction. A duplicate idempotency key or external ID fails the whole insert.
+    pub async fn create_many_in(tx: &mut Transaction<'_, Postgres>, transactions: &[TransactionRecord]) -> Result<()> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        let mut insert = QueryBuilder::<Postgres>::new(
+            "INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id) ",
+        );
+        insert.push_values(transactions, |mut row, transaction| {
+            row.push_bind(transaction.id)
+                .push_bind(&transaction.external_id)
+                .push_bind(transaction.transaction_type)
+                .push_bind(transaction.status)
+                .push_bind(transaction.source_account_id)
+                .push_bind(transaction.destination_account_id)
+                .push_bind(transaction.amount)
+                .push_bind(&transaction.currency)
+                .push_bind(transaction.fee_amount)
+                .push_bind(transaction.net_amount)
+                .push_bind(transaction.settlement_batch_id)
+                .push_bind(&transaction.idempotency_key)
+                .push_bind(&transaction.metadata)
+                .push_bind(transaction.created_at)
+                .push_bind(transaction.settled_at)
+                .push_bind(transaction.settlement_route)
+                .push_bind(transaction.priority)
+                .push_bind(&transaction.source_system)
+                .push_bind(transaction.resubmission_of)
+                .push_bind(&transaction.dedupe_key)
+                .push_bind(&transaction.type_code)
+                .push_bind(transaction.exclude_from_netting)
+                .push_bind(&transaction.narrative)
+                .push_bind(&transaction.reference)
+                .push_bind(transaction.link_id);
+        });
+        timed("transactions.insert_many", insert.build().execute(&mut **tx))
+            .await
+            .map_err(Self::map_insert_error)?;
+
+        Ok(())
+    }
+
+    /// Assigns a transaction to a batch within an open database transaction. With
+    /// `exclude_from_netting` the transaction is also flagged to settle gross; a flag it
+    /// already carries is never cleared. Only a transaction in no batch is assigned, so
+    /// `None` means it was not found or already belongs to a batch.
+    pub async fn assign_to_batch_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        batch_id: Uuid,
+        exclude_from_netting: bool,
+    ) -> Result<Option<TransactionRecord>> {
+        let row = timed(
+            "transactions.assign_to_batch",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                UPDATE transactions
+                SET settlement_batch_id = $2, exclude_from_netting = exclude_from_netting OR $3
+                WHERE id = $1 AND settlement_batch_id IS NULL
+                RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                "#,
+            )
+            .bind(id)
+            .bind(batch_id)
+            .bind(exclude_from_netting)
+            .fetch_optional(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        if let Some(row) = &row {
+            let entry = TransactionAuditEntry::new(
+                id,
+                TransactionAuditAction::BatchAssigned,
+                serde_json::json!({ "batch_id": batch_id, "exclude_from_netting": row.exclude_from_netting }),
+            );
+            TransactionAuditRepository::record_in(tx, &entry).await?;
+        }
+
+        Ok(row)
+    }
+
+    /// Moves a transaction from one batch to another within an open database transaction
+    /// and records the move in the transaction audit log. Only a transaction still in
+    /// `from_batch_id` is moved, so `None` means it was not found or is in another batch.
+    pub async fn reassign_batch_in(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        from_batch_id: Uuid,
+        to_batch_id: Uuid,
+        reason: &str,
+    ) -> Result<Option<TransactionRecord>> {
+        let row = timed(
+            "transactions.reassign_batch",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                UPDATE transactions
+                SET settlement_batch_id = $3
+                WHERE id = $1 AND settlement_batch_id = $2
+                RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                "#,
+            )
+            .bind(id)
+            .bind(from_batch_id)
+            .bind(to_batch_id)
+            .fetch_optional(&mut **tx),
+        )
+        .await
+        .map_err(AppError::Database)?;
+
+        if row.is_some() {
+            let entry = TransactionAuditEntry::new(
+                id,
+                TransactionAuditAction::BatchReassigned,
+                serde_json::json!({ "from_batch_id": from_batch_id, "batch_id": to_batch_id, "reason": reason }),
+            );
+            TransactionAuditRepository::record_in(tx, &entry).await?;
+        }
+
+        Ok(row)
+    }
+
+    /// Finds transactions that refer back to this one: reversals, and refunds or
+    /// chargebacks recorded against it, oldest first.
+    pub async fn find_related(&self, original_id: Uuid) -> Result<Vec<TransactionRecord>> {
+        let rows = sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+            FROM transactions t
+            WHERE t.metadata->>'original_transaction_id' = $1::text
+               OR EXISTS (
+                   SELECT 1 FROM transaction_audit_log a
+                   WHERE a.transaction_id = t.id
+                     AND a.action = 'VALIDATED'
+                     AND a.detail->>'original_transaction_id' = $1::text
+               )
+            ORDER BY created_at
+            "#,
+        )
+        .bind(original_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Changes the priority of a queued transaction: one still pending, or waiting in a
+    /// batch that has not started processing. Returns None if the transaction is not queued.
+    pub async fn update_priority(
+        &self,
+        id: Uuid,
+        priority: TransactionPriority,
     ) -> Result<Option<TransactionRecord>> {
         let row = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            UPDATE transactions
-            SET settlement_batch_id = $2
-            WHERE id = $1
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            UPDATE transactions t
+            SET priority = $2
+            WHERE t.id = $1
+              AND (t.status = 'PENDING'
+                   OR EXISTS (SELECT 1 FROM settlement_batches b
+                              WHERE b.id = t.settlement_batch_id AND b.status = 'PENDING'))
+            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             "#,
         )
         .bind(id)
-        .bind(batch_id)
+        .bind(priority)
         .fetch_optional(&self.pool)
         .await
         .map_err(AppError::Database)?;
@@ -205,14 +719,14 @@ impl TransactionRepository {
         Ok(row)
     }
 
-    /// Finds pending transactions not yet assigned to a batch.
+    /// Finds pending transactions not yet assigned to a batch in release order (priority, then FIFO).
     pub async fn find_pending_unassigned(&self, limit: i64) -> Result<Vec<TransactionRecord>> {
         let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             FROM transactions
             WHERE status = 'PENDING' AND settlement_batch_id IS NULL
-            ORDER BY created_at
+            ORDER BY priority, created_at
             LIMIT $1
             "#,
         )
@@ -233,7 +747,7 @@ impl TransactionRepository {
     ) -> Result<Vec<TransactionRecord>> {
         let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             FROM transactions
             WHERE source_account_id = $1 OR destination_account_id = $1
             ORDER BY created_at DESC
@@ -267,6 +781,84 @@ impl TransactionRepository {
         Ok(row.0)
     }
 
+    /// Checks whether an account has ever settled a transaction to a counterparty.
+    pub async fn has_settled_between(&self, source_account_id: Uuid, destination_account_id: Uuid) -> Result<bool> {
+        let row: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM transactions
+                WHERE source_account_id = $1 AND destination_account_id = $2 AND status IN ('SETTLED', 'REVERSED')
+            )
+            "#,
+        )
+        .bind(source_account_id)
+        .bind(destination_account_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.0)
+    }
+
+    /// Counts and totals the transactions an account sent in a currency since `since`,
+    /// leaving out failed ones.
+    pub async fn outgoing_since(
+        &self,
+        source_account_id: Uuid,
+        currency: &str,
+        since: DateTime<Utc>,
+    ) -> Result<(i64, Decimal)> {
+        let row: (i64, Decimal) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COALESCE(SUM(amount), 0)
+            FROM transactions
+            WHERE source_account_id = $1 AND currency = $2 AND created_at >= $3 AND status <> 'FAILED'
+            "#,
+        )
+        .bind(source_account_id)
+        .bind(currency)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Finds the latest transaction since `since` with the same source, destination,
+    /// amount and currency as a request but another idempotency key, leaving out failed
+    /// ones.
+    pub async fn find_recent_duplicate(
+        &self,
+        source_account_id: Uuid,
+        destination_account_id: Uuid,
+        amount: Decimal,
+        currency: &str,
+        idempotency_key: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Option<Uuid>> {
+        let row: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM transactions
+            WHERE source_account_id = $1 AND destination_account_id = $2 AND amount = $3 AND currency = $4
+              AND idempotency_key <> $5 AND created_at >= $6 AND status <> 'FAILED'
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(source_account_id)
+        .bind(destination_account_id)
+        .bind(amount)
+        .bind(currency)
+        .bind(idempotency_key)
+        .bind(since)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row.map(|(id,)| id))
+    }
+
     /// Checks if an idempotency key exists.
     pub async fn exists_by_idempotency_key(&self, idempotency_key: &str) -> Result<bool> {
         let row: (bool,) = sqlx::query_as(
@@ -293,7 +885,7 @@ impl TransactionRepository {
     ) -> Result<Vec<TransactionRecord>> {
         let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             FROM transactions
             WHERE ($1::uuid IS NULL OR source_account_id = $1 OR destination_account_id = $1)
               AND ($2::transaction_status IS NULL OR status = $2)
@@ -349,7 +941,7 @@ impl TransactionRepository {
     ) -> Result<Vec<TransactionRecord>> {
         let rows = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            SELECT id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             FROM transactions
             WHERE created_at >= $1 AND created_at < $2
             ORDER BY created_at
@@ -365,4 +957,37 @@ impl TransactionRepository {
 
         Ok(rows)
     }
+
+    /// Sums settled volume per settlement route and currency within a time range.
+    pub async fn route_volumes(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<RouteVolume>> {
+        let rows = sqlx::query_as::<_, RouteVolume>(
+            r#"
+            SELECT settlement_route, currency, COUNT(*) AS transaction_count, SUM(amount) AS gross_amount
+            FROM transactions
+            WHERE settled_at >= $1 AND settled_at < $2
+            GROUP BY settlement_route, currency
+            ORDER BY currency, settlement_route
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+}
+
+/// Settled volume for one settlement route and currency.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RouteVolume {
+    pub settlement_route: SettlementRoute,
+    pub currency: String,
+    pub transaction_count: i64,
+    pub gross_amount: Decimal,
 }
@@ -0,0 +1,97 @@
+use crate::error::{AppError, Result};
+use crate::models::TransactionTypeDefinition;
+use sqlx::PgPool;
+
+/// Repository for custom transaction types and redefined base types.
+pub struct TransactionTypeRepository {
+    pool: PgPool,
+}
+
+impl TransactionTypeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates or replaces the definition of a type.
+    pub async fn upsert(&self, definition: &TransactionTypeDefinition) -> Result<TransactionTypeDefinition> {
+        let row = sqlx::query_as::<_, TransactionTypeDefinition>(
+            r#"
+            INSERT INTO transaction_type_definitions (code, base_type, reversible, nettable, requires_original, fee_policy, description, narrative_template, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (code) DO UPDATE
+            SET base_type = EXCLUDED.base_type,
+                reversible = EXCLUDED.reversible,
+                nettable = EXCLUDED.nettable,
+                requires_original = EXCLUDED.requires_original,
+                fee_policy = EXCLUDED.fee_policy,
+                description = EXCLUDED.description,
+                narrative_template = EXCLUDED.narrative_template,
+                updated_at = EXCLUDED.updated_at
+            RETURNING code, base_type, reversible, nettable, requires_original, fee_policy, description, narrative_template, created_at, updated_at
+            "#,
+        )
+        .bind(&definition.code)
+        .bind(definition.base_type)
+        .bind(definition.reversible)
+        .bind(definition.nettable)
+        .bind(definition.requires_original)
+        .bind(definition.fee_policy)
+        .bind(&definition.description)
+        .bind(&definition.narrative_template)
+        .bind(definition.created_at)
+        .bind(definition.updated_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    pub async fn find_by_code(&self, code: &str) -> Result<Option<TransactionTypeDefinition>> {
+        let row = sqlx::query_as::<_, TransactionTypeDefinition>(
+            r#"
+            SELECT code, base_type, reversible, nettable, requires_original, fee_policy, description, narrative_template, created_at, updated_at
+            FROM transaction_type_definitions
+            WHERE code = $1
+            "#,
+        )
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(row)
+    }
+
+    /// Lists all registered definitions.
+    pub async fn list(&self) -> Result<Vec<TransactionTypeDefinition>> {
+        let rows = sqlx::query_as::<_, TransactionTypeDefinition>(
+            r#"
+            SELECT code, base_type, reversible, nettable, requires_original, fee_policy, description, narrative_template, created_at, updated_at
+            FROM transaction_type_definitions
+            ORDER BY code
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows)
+    }
+
+    /// Codes of the registered types that do not net.
+    pub async fn list_gross_codes(&self) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT code
+            FROM transaction_type_definitions
+            WHERE NOT nettable
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::Database)?;
+
+        Ok(rows.into_iter().map(|(code,)| code).collect())
+    }
+}
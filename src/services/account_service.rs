@@ -1,8 +1,18 @@
+use crate::core::leader::LeaderElection;
 use crate::error::{AppError, Result};
-use crate::models::{Account, AccountBalance, AccountStatus, AccountType};
-use crate::repositories::{AccountRepository, BalanceRepository};
+use crate::models::{
+    Account, AccountAnonymization, AccountBalance, AccountIdentifier, AccountIdentifierType, AccountStatus,
+    AccountStatusChange, AccountType, SettlementProfile, StatusChangeReason,
+};
+use crate::repositories::{
+    AccountIdentifierRepository, AccountRepository, BalanceRepository, InternalAccountRepository,
+    SettlementProfileRepository,
+};
+use crate::services::InstrumentService;
 use rust_decimal::Decimal;
 use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Request to create a new account.
@@ -20,13 +30,21 @@ pub struct CreateAccountRequest {
 pub struct AccountService {
     account_repo: AccountRepository,
     balance_repo: BalanceRepository,
+    profile_repo: SettlementProfileRepository,
+    identifier_repo: AccountIdentifierRepository,
+    internal_account_repo: InternalAccountRepository,
+    instruments: InstrumentService,
 }
 
 impl AccountService {
     pub fn new(pool: PgPool) -> Self {
         Self {
             account_repo: AccountRepository::new(pool.clone()),
-            balance_repo: BalanceRepository::new(pool),
+            balance_repo: BalanceRepository::new(pool.clone()),
+            profile_repo: SettlementProfileRepository::new(pool.clone()),
+            identifier_repo: AccountIdentifierRepository::new(pool.clone()),
+            internal_account_repo: InternalAccountRepository::new(pool.clone()),
+            instruments: InstrumentService::new(pool),
         }
     }
 
@@ -42,12 +60,8 @@ impl AccountService {
             return Err(AppError::Validation("Account name cannot be empty".to_string()));
         }
 
-        // Validate currency code (basic validation)
-        if request.currency.len() != 3 {
-            return Err(AppError::Validation(
-                "Currency must be a 3-letter ISO 4217 code".to_string(),
-            ));
-        }
+        // Validate currency code, or the registered security the account holds
+        self.instruments.resolve(&request.currency).await?;
 
         // Check if external_id already exists
         if self.account_repo.exists_by_external_id(&request.external_id).await? {
@@ -119,7 +133,7 @@ impl AccountService {
     }
 
     /// Freezes an account, preventing new transactions.
-    pub async fn freeze_account(&self, id: Uuid) -> Result<Account> {
+    pub async fn freeze_account(&self, id: Uuid, reason: StatusChangeReason) -> Result<Account> {
         let account = self.find_by_id(id).await?;
 
         if account.status == AccountStatus::Closed {
@@ -130,14 +144,11 @@ impl AccountService {
             return Err(AppError::Validation("Account is already frozen".to_string()));
         }
 
-        self.account_repo
-            .update_status(id, AccountStatus::Frozen)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("Account with id '{}' not found", id)))
+        self.transition(&account, AccountStatus::Frozen, reason).await
     }
 
     /// Activates a frozen account.
-    pub async fn activate_account(&self, id: Uuid) -> Result<Account> {
+    pub async fn activate_account(&self, id: Uuid, reason: StatusChangeReason) -> Result<Account> {
         let account = self.find_by_id(id).await?;
 
         if account.status == AccountStatus::Closed {
@@ -148,20 +159,30 @@ impl AccountService {
             return Err(AppError::Validation("Account is already active".to_string()));
         }
 
-        self.account_repo
-            .update_status(id, AccountStatus::Active)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("Account with id '{}' not found", id)))
+        self.transition(&account, AccountStatus::Active, reason).await
     }
 
     /// Closes an account permanently.
-    pub async fn close_account(&self, id: Uuid) -> Result<Account> {
+    pub async fn close_account(&self, id: Uuid, reason: StatusChangeReason) -> Result<Account> {
         let account = self.find_by_id(id).await?;
 
         if account.status == AccountStatus::Closed {
             return Err(AppError::Validation("Account is already closed".to_string()));
         }
 
+        if let Some(entry) = self
+            .internal_account_repo
+            .find_by_accounts(&[id])
+            .await?
+            .into_iter()
+            .find(|entry| entry.control)
+        {
+            return Err(AppError::Validation(format!(
+                "Account is internal control account {} and cannot be closed",
+                entry.code
+            )));
+        }
+
         // Check if account has non-zero balance
         let balances = self.balance_repo.find_by_account(id).await?;
         for balance in &balances {
@@ -172,10 +193,117 @@ impl AccountService {
             }
         }
 
+        self.transition(&account, AccountStatus::Closed, reason).await
+    }
+
+    /// Gets an account's status changes, oldest first.
+    pub async fn get_status_history(&self, id: Uuid) -> Result<Vec<AccountStatusChange>> {
+        // Verify account exists
+        self.find_by_id(id).await?;
+
+        self.account_repo.status_history(id).await
+    }
+
+    /// Moves an account to a new status and records why.
+    async fn transition(
+        &self,
+        account: &Account,
+        to: AccountStatus,
+        reason: StatusChangeReason,
+    ) -> Result<Account> {
+        let change = AccountStatusChange::new(account.id, account.status, to, reason);
+
         self.account_repo
-            .update_status(id, AccountStatus::Closed)
+            .transition_status(&change)
             .await?
-            .ok_or_else(|| AppError::NotFound(format!("Account with id '{}' not found", id)))
+            .ok_or_else(|| {
+                AppError::SerializationConflict(format!(
+                    "Account '{}' changed status concurrently; retry the request",
+                    account.id
+                ))
+            })
+    }
+
+    /// Irreversibly scrubs the personal data of a closed account: its name, external ID,
+    /// metadata, settlement profile and status-change notes. Balances, ledger entries
+    /// and transactions reference the account by ID and are left untouched, so the
+    /// ledger still balances. The action is recorded in the anonymization audit trail.
+    pub async fn anonymize(&self, account_id: Uuid, requested_by: &str, reason: &str) -> Result<Account> {
+        if requested_by.trim().is_empty() || reason.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Anonymization requires who requested it and why".to_string(),
+            ));
+        }
+
+        let account = self.find_by_id(account_id).await?;
+        if account.is_anonymized() {
+            return Err(AppError::Validation(format!(
+                "Account '{}' is already anonymized",
+                account_id
+            )));
+        }
+        if account.status != AccountStatus::Closed {
+            return Err(AppError::Validation(format!(
+                "Account '{}' must be closed before it can be anonymized (status: {:?})",
+                account_id, account.status
+            )));
+        }
+
+        let anonymization = AccountAnonymization::new(account_id, requested_by, reason);
+        self.account_repo
+            .anonymize(&anonymization)
+            .await?
+            .ok_or_else(|| {
+                AppError::SerializationConflict(format!(
+                    "Account '{}' changed concurrently; retry the request",
+                    account_id
+                ))
+            })
+    }
+
+    /// Gets the audit record of an account's anonymization.
+    pub async fn get_anonymization(&self, account_id: Uuid) -> Result<AccountAnonymization> {
+        self.account_repo
+            .find_anonymization(account_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Account '{}' has not been anonymized", account_id))
+            })
+    }
+
+    /// Anonymizes up to `limit` accounts that have been closed for longer than
+    /// `closed_for`, returning their IDs.
+    pub async fn anonymize_closed_before(
+        &self,
+        closed_for: chrono::Duration,
+        limit: i64,
+    ) -> Result<Vec<Uuid>> {
+        let reason = format!("Retention policy: closed for more than {} days", closed_for.num_days());
+        let candidates = self
+            .account_repo
+            .find_closed_before(chrono::Utc::now() - closed_for, limit)
+            .await?;
+
+        let mut anonymized = Vec::with_capacity(candidates.len());
+        for account_id in candidates {
+            let anonymization = AccountAnonymization::new(account_id, "retention-policy", reason.as_str());
+            // Skipped if the account was reopened or anonymized in the meantime
+            if self.account_repo.anonymize(&anonymization).await?.is_some() {
+                anonymized.push(account_id);
+            }
+        }
+        Ok(anonymized)
+    }
+
+    /// Rejects changes that would store personal data on an anonymized account.
+    fn ensure_not_anonymized(account: &Account) -> Result<()> {
+        if account.is_anonymized() {
+            return Err(AppError::Validation(format!(
+                "Account '{}' is anonymized and cannot hold personal data",
+                account.id
+            )));
+        }
+        Ok(())
     }
 
     /// Updates account metadata.
@@ -184,8 +312,8 @@ impl AccountService {
         id: Uuid,
         metadata: serde_json::Value,
     ) -> Result<Account> {
-        // Verify account exists
-        self.find_by_id(id).await?;
+        let account = self.find_by_id(id).await?;
+        Self::ensure_not_anonymized(&account)?;
 
         self.account_repo
             .update_metadata(id, metadata)
@@ -217,14 +345,101 @@ impl AccountService {
             })
     }
 
+    /// Sets the bank details an account settles to in the profile's currency, replacing
+    /// any existing profile for that currency.
+    pub async fn set_settlement_profile(&self, profile: SettlementProfile) -> Result<SettlementProfile> {
+        let account = self.find_by_id(profile.account_id).await?;
+        Self::ensure_not_anonymized(&account)?;
+
+        profile.validate().map_err(AppError::Validation)?;
+        self.profile_repo.upsert(&profile).await
+    }
+
+    /// Gets the bank details an account settles to in a currency.
+    pub async fn get_settlement_profile(&self, account_id: Uuid, currency: &str) -> Result<SettlementProfile> {
+        self.profile_repo
+            .find(account_id, currency)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Settlement profile for account '{}' in {} not found",
+                    account_id, currency
+                ))
+            })
+    }
+
+    /// Lists an account's settlement profiles, one per currency.
+    pub async fn list_settlement_profiles(&self, account_id: Uuid) -> Result<Vec<SettlementProfile>> {
+        self.find_by_id(account_id).await?;
+        self.profile_repo.find_by_account(account_id).await
+    }
+
+    /// Removes an account's settlement profile for a currency.
+    pub async fn delete_settlement_profile(&self, account_id: Uuid, currency: &str) -> Result<()> {
+        if !self.profile_repo.delete(account_id, currency).await? {
+            return Err(AppError::NotFound(format!(
+                "Settlement profile for account '{}' in {} not found",
+                account_id, currency
+            )));
+        }
+        Ok(())
+    }
+
+    /// Assigns a secondary identifier to an account. The identifier is normalized and
+    /// must not be assigned to any account yet.
+    pub async fn add_identifier(
+        &self,
+        account_id: Uuid,
+        identifier_type: AccountIdentifierType,
+        identifier: &str,
+    ) -> Result<AccountIdentifier> {
+        let account = self.find_by_id(account_id).await?;
+        Self::ensure_not_anonymized(&account)?;
+
+        let identifier =
+            AccountIdentifier::new(account_id, identifier_type, identifier).map_err(AppError::Validation)?;
+        self.identifier_repo.create(&identifier).await
+    }
+
+    /// Lists an account's secondary identifiers.
+    pub async fn list_identifiers(&self, account_id: Uuid) -> Result<Vec<AccountIdentifier>> {
+        self.find_by_id(account_id).await?;
+        self.identifier_repo.find_by_account(account_id).await
+    }
+
+    /// Removes one of an account's secondary identifiers.
+    pub async fn remove_identifier(&self, account_id: Uuid, identifier_id: Uuid) -> Result<()> {
+        if !self.identifier_repo.delete(account_id, identifier_id).await? {
+            return Err(AppError::NotFound(format!(
+                "Identifier '{}' of account '{}' not found",
+                identifier_id, account_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Finds the account a secondary identifier is assigned to. The identifier is
+    /// normalized first, so spacing and case do not matter.
+    pub async fn lookup(&self, identifier: &str) -> Result<Account> {
+        let normalized = AccountIdentifier::normalize(identifier);
+        let assigned = self
+            .identifier_repo
+            .find_by_identifier(&normalized)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("No account with identifier '{}' found", normalized)))?;
+        self.find_by_id(assigned.account_id).await
+    }
+
     /// Validates that an account can participate in transactions.
     pub async fn validate_for_transaction(&self, account_id: Uuid) -> Result<Account> {
         let account = self.find_by_id(account_id).await?;
 
         if !account.status.is_operational() {
-            return Err(AppError::Validation(format!(
-                "Account '{}' is not operational (status: {:?})",
-                account_id, account.status
+            let change = self.account_repo.latest_status_change(account_id).await?;
+            return Err(AppError::AccountFrozen(format!(
+                "Account '{}' is not operational ({})",
+                account_id,
+                account.status_description(change.as_ref())
             )));
         }
 
@@ -260,3 +475,79 @@ mod tests {
         assert_eq!(request.account_type, AccountType::Asset);
     }
 }
+
+/// How long closed accounts keep their personal data.
+#[derive(Debug, Clone)]
+pub struct AccountRetentionPolicy {
+    /// Closed accounts are anonymized once they have been closed this long.
+    pub anonymize_closed_after: chrono::Duration,
+    /// Accounts anonymized per run.
+    pub batch_size: i64,
+}
+
+/// Periodically anonymizes closed accounts that are past their retention period.
+pub struct AccountRetentionJob {
+    service: Arc<AccountService>,
+    policy: AccountRetentionPolicy,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl AccountRetentionJob {
+    pub fn new(service: Arc<AccountService>, policy: AccountRetentionPolicy, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            policy,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+            leader: None,
+        }
+    }
+
+    /// Only runs while this instance is the elected leader.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let policy = self.policy.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+        let leader = self.leader.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if leader.as_ref().is_none_or(|leader| leader.is_leader()) {
+                    match service
+                        .anonymize_closed_before(policy.anonymize_closed_after, policy.batch_size)
+                        .await
+                    {
+                        Ok(anonymized) if !anonymized.is_empty() => {
+                            tracing::info!("Anonymized {} closed accounts past retention", anonymized.len());
+                        }
+                        Ok(_) => {}
+                        Err(e) => tracing::error!("Account retention job error: {}", e),
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::{AppError, Result};
+use crate::models::{AccountingPeriod, PeriodStatus};
+use crate::repositories::{AccountingPeriodRepository, GlRepository};
+use chrono::{NaiveDate, Utc};
+use sqlx::PgPool;
+use tracing::info;
+
+/// Controls which past days entries can be backdated to.
+///
+/// Periods are calendar months and are open until closed. A backdated entry is accepted
+/// if its period is open and its day has not yet been posted to the general ledger,
+/// since a posted day's journal would never pick it up.
+pub struct AccountingPeriodService {
+    repo: AccountingPeriodRepository,
+    gl_repo: GlRepository,
+}
+
+impl AccountingPeriodService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: AccountingPeriodRepository::new(pool.clone()),
+            gl_repo: GlRepository::new(pool),
+        }
+    }
+
+    /// Gets the period containing a date.
+    pub async fn period_containing(&self, date: NaiveDate) -> Result<AccountingPeriod> {
+        Ok(self
+            .repo
+            .find_containing(date)
+            .await?
+            .unwrap_or_else(|| AccountingPeriod::containing(date)))
+    }
+
+    /// Closes the period containing a date. Only periods that have ended can be closed.
+    pub async fn close(&self, date: NaiveDate) -> Result<AccountingPeriod> {
+        let period = self.period_containing(date).await?;
+        if period.period_end >= Utc::now().date_naive() {
+            return Err(AppError::Validation(format!(
+                "Cannot close the period {} to {} before it has ended",
+                period.period_start, period.period_end
+            )));
+        }
+        let period = self.repo.set_status(&period, PeriodStatus::Closed).await?;
+        info!("Closed accounting period {} to {}", period.period_start, period.period_end);
+        Ok(period)
+    }
+
+    /// Reopens the period containing a date for backdated entries.
+    pub async fn reopen(&self, date: NaiveDate) -> Result<AccountingPeriod> {
+        let period = self.period_containing(date).await?;
+        let period = self.repo.set_status(&period, PeriodStatus::Open).await?;
+        info!("Reopened accounting period {} to {}", period.period_start, period.period_end);
+        Ok(period)
+    }
+
+    /// Returns why entries cannot be dated on a past day, or `None` if they can.
+    /// Today is always open.
+    pub async fn check_postable(&self, effective_date: NaiveDate) -> Result<Option<String>> {
+        if effective_date >= Utc::now().date_naive() {
+            return Ok(None);
+        }
+        let period = self.period_containing(effective_date).await?;
+        if !period.is_open() {
+            return Ok(Some(format!(
+                "Accounting period {} to {} is closed",
+                period.period_start, period.period_end
+            )));
+        }
+        if self.gl_repo.find_run_by_date(effective_date).await?.is_some() {
+            return Ok(Some(format!(
+                "{} has already been posted to the general ledger",
+                effective_date
+            )));
+        }
+        Ok(None)
+    }
+
+    /// Lists periods that were closed or reopened, latest first.
+    pub async fn list(&self, limit: i64, offset: i64) -> Result<Vec<AccountingPeriod>> {
+        self.repo.list(limit, offset).await
+    }
+
+    pub async fn count(&self) -> Result<i64> {
+        self.repo.count().await
+    }
+}
@@ -0,0 +1,207 @@
+use crate::error::{AppError, Result};
+use crate::events::{EventEnvelope, EventType, TransactionEvent};
+use crate::models::{AccountActivity, ActivityGranularity, ActivityPeriod};
+use crate::observability::get_metrics;
+use crate::repositories::{AccountRepository, ActivityRepository, OutboxRepository};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Maintains and serves the account activity projection.
+///
+/// Settled transactions queue a transaction-settled event in the outbox. The projector
+/// folds those events into per-account daily aggregates and stamps them as projected in
+/// the same database transaction, so every event is counted exactly once no matter how
+/// many projectors run.
+pub struct ActivityService {
+    pool: PgPool,
+    repo: ActivityRepository,
+    account_repo: AccountRepository,
+    batch_size: i64,
+}
+
+impl ActivityService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: ActivityRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool.clone()),
+            pool,
+            batch_size: 500,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Applies up to one batch of unprojected transaction events and returns how many
+    /// were applied.
+    pub async fn project_pending(&self) -> Result<usize> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let messages =
+            OutboxRepository::claim_unprojected_in(&mut tx, TransactionEvent::topic(), self.batch_size).await?;
+        if messages.is_empty() {
+            return Ok(0);
+        }
+
+        let mut applied = 0;
+        for message in &messages {
+            let envelope = match serde_json::from_value::<EventEnvelope<TransactionEvent>>(message.payload.clone()) {
+                Ok(envelope) => envelope,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable transaction event {}: {}", message.id, e);
+                    continue;
+                }
+            };
+            if envelope.event_type != EventType::TransactionSettled {
+                continue;
+            }
+            for activity in activity_from_event(&envelope.payload) {
+                ActivityRepository::apply_in(&mut tx, &activity).await?;
+            }
+            applied += 1;
+        }
+
+        let ids: Vec<Uuid> = messages.iter().map(|message| message.id).collect();
+        OutboxRepository::mark_projected_in(&mut tx, &ids).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        get_metrics().record_activity_projected(applied as u64);
+        Ok(applied)
+    }
+
+    /// Gets an account's activity rolled up by period, within an inclusive date range.
+    pub async fn activity(
+        &self,
+        account_id: Uuid,
+        granularity: ActivityGranularity,
+        currency: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<ActivityPeriod>> {
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(AppError::Validation("'from' must not be after 'to'".to_string()));
+            }
+        }
+        self.account_repo
+            .find_by_id(account_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account '{}' not found", account_id)))?;
+
+        let days = self.repo.find_by_account(account_id, currency, from, to).await?;
+        Ok(ActivityPeriod::rollup(&days, granularity))
+    }
+}
+
+/// Splits a settled transaction into the activity of its two accounts. The source pays
+/// the gross amount, fee included; the destination receives the net amount.
+fn activity_from_event(event: &TransactionEvent) -> [AccountActivity; 2] {
+    let settled_at = event.settled_at.unwrap_or(event.created_at);
+    let activity = |account_id, total_in, total_out, total_fees| AccountActivity {
+        account_id,
+        activity_date: settled_at.date_naive(),
+        currency: event.currency.clone(),
+        transaction_type: event.transaction_type,
+        transaction_count: 1,
+        total_in,
+        total_out,
+        total_fees,
+        updated_at: settled_at,
+    };
+
+    [
+        activity(event.source_account_id, Decimal::ZERO, event.amount, event.fee_amount),
+        activity(event.destination_account_id, event.net_amount, Decimal::ZERO, Decimal::ZERO),
+    ]
+}
+
+/// Background job keeping the activity projection up to date.
+pub struct ActivityProjectionJob {
+    service: Arc<ActivityService>,
+    running: Arc<AtomicBool>,
+    interval_millis: u64,
+}
+
+impl ActivityProjectionJob {
+    pub fn new(service: Arc<ActivityService>, interval_millis: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_millis,
+        }
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_millis;
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if let Err(e) = service.project_pending().await {
+                    tracing::error!("Activity projection error: {}", e);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_millis(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TransactionStatus, TransactionType};
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_activity_from_event() {
+        let settled_at = Utc.with_ymd_and_hms(2024, 3, 1, 23, 30, 0).unwrap();
+        let event = TransactionEvent {
+            transaction_id: Uuid::new_v4(),
+            external_id: "TX-001".to_string(),
+            transaction_type: TransactionType::Payment,
+            status: TransactionStatus::Settled,
+            source_account_id: Uuid::new_v4(),
+            destination_account_id: Uuid::new_v4(),
+            amount: dec!(100),
+            currency: "USD".to_string(),
+            fee_amount: dec!(2),
+            net_amount: dec!(98),
+            batch_id: None,
+            idempotency_key: "IDEM-001".to_string(),
+            created_at: settled_at,
+            settled_at: Some(settled_at),
+        };
+
+        let [source, destination] = activity_from_event(&event);
+
+        assert_eq!(source.account_id, event.source_account_id);
+        assert_eq!(source.total_out, dec!(100));
+        assert_eq!(source.total_fees, dec!(2));
+        assert_eq!(destination.account_id, event.destination_account_id);
+        assert_eq!(destination.total_in, dec!(98));
+        assert_eq!(destination.total_out, dec!(0));
+        assert_eq!(source.activity_date, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!(source.transaction_count, 1);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::{AppError, Result};
+use crate::models::{AlertRule, AlertRuleType};
+use crate::repositories::{AccountRepository, AlertRuleRepository};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Request to create a new alert rule.
+#[derive(Debug, Clone)]
+pub struct CreateAlertRuleRequest {
+    pub account_id: Option<Uuid>,
+    pub rule_type: AlertRuleType,
+    pub currency: String,
+    pub threshold: Option<Decimal>,
+    pub webhook_url: Option<String>,
+    pub suppression_window_seconds: Option<i32>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Partial update of an alert rule. Fields left as None are unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateAlertRuleRequest {
+    pub threshold: Option<Decimal>,
+    pub webhook_url: Option<String>,
+    pub suppression_window_seconds: Option<i32>,
+    pub enabled: Option<bool>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Service for managing alert rules.
+pub struct AlertService {
+    rule_repo: AlertRuleRepository,
+    account_repo: AccountRepository,
+}
+
+impl AlertService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            rule_repo: AlertRuleRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool),
+        }
+    }
+
+    /// Creates a new alert rule with validation.
+    pub async fn create_rule(&self, request: CreateAlertRuleRequest) -> Result<AlertRule> {
+        if request.currency.len() != 3 {
+            return Err(AppError::Validation(
+                "Currency must be a 3-letter ISO 4217 code".to_string(),
+            ));
+        }
+
+        Self::validate_threshold(request.rule_type, request.threshold)?;
+        Self::validate_suppression_window(request.suppression_window_seconds)?;
+
+        if let Some(account_id) = request.account_id {
+            if self.account_repo.find_by_id(account_id).await?.is_none() {
+                return Err(AppError::NotFound(format!("Account with id '{}' not found", account_id)));
+            }
+        }
+
+        let mut rule = AlertRule::new(request.rule_type, request.currency, request.threshold)
+            .with_suppression_window(request.suppression_window_seconds.unwrap_or(0));
+
+        if let Some(account_id) = request.account_id {
+            rule = rule.for_account(account_id);
+        }
+        if let Some(url) = request.webhook_url {
+            rule = rule.with_webhook_url(url);
+        }
+        if let Some(metadata) = request.metadata {
+            rule = rule.with_metadata(metadata);
+        }
+
+        self.rule_repo.create(&rule).await
+    }
+
+    /// Gets an alert rule by ID.
+    pub async fn get_rule(&self, id: Uuid) -> Result<AlertRule> {
+        self.rule_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Alert rule with id '{}' not found", id)))
+    }
+
+    /// Lists alert rules with optional filters.
+    pub async fn list_rules(
+        &self,
+        account_id: Option<Uuid>,
+        rule_type: Option<AlertRuleType>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AlertRule>> {
+        self.rule_repo.list(account_id, rule_type, limit, offset).await
+    }
+
+    /// Counts alert rules with optional filters.
+    pub async fn count_rules(&self, account_id: Option<Uuid>, rule_type: Option<AlertRuleType>) -> Result<i64> {
+        self.rule_repo.count(account_id, rule_type).await
+    }
+
+    /// Applies a partial update to an alert rule.
+    pub async fn update_rule(&self, id: Uuid, request: UpdateAlertRuleRequest) -> Result<AlertRule> {
+        let mut rule = self.get_rule(id).await?;
+
+        if let Some(threshold) = request.threshold {
+            rule.threshold = Some(threshold);
+        }
+        Self::validate_threshold(rule.rule_type, rule.threshold)?;
+        Self::validate_suppression_window(request.suppression_window_seconds)?;
+
+        if let Some(url) = request.webhook_url {
+            rule.webhook_url = if url.trim().is_empty() { None } else { Some(url) };
+        }
+        if let Some(seconds) = request.suppression_window_seconds {
+            rule.suppression_window_seconds = seconds;
+        }
+        if let Some(enabled) = request.enabled {
+            rule.enabled = enabled;
+        }
+        if let Some(metadata) = request.metadata {
+            rule.metadata = Some(metadata);
+        }
+
+        self.rule_repo
+            .update(&rule)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Alert rule with id '{}' not found", id)))
+    }
+
+    /// Deletes an alert rule.
+    pub async fn delete_rule(&self, id: Uuid) -> Result<()> {
+        if self.rule_repo.delete(id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Alert rule with id '{}' not found", id)))
+        }
+    }
+
+    fn validate_threshold(rule_type: AlertRuleType, threshold: Option<Decimal>) -> Result<()> {
+        match threshold {
+            None if rule_type.requires_threshold() => Err(AppError::Validation(format!(
+                "Threshold is required for {:?} rules",
+                rule_type
+            ))),
+            Some(t) if t < Decimal::ZERO => {
+                Err(AppError::Validation("Threshold cannot be negative".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_suppression_window(seconds: Option<i32>) -> Result<()> {
+        match seconds {
+            Some(s) if s < 0 => Err(AppError::Validation(
+                "Suppression window cannot be negative".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::{AppError, Result};
+use crate::models::{AmendedField, AmendmentTarget, TransactionAmendment};
+use crate::repositories::{
+    RiskHoldRepository, SubmissionRepository, TransactionAmendmentRepository, TransactionRepository,
+};
+use crate::services::{LedgerService, LedgerTransactionRequest};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Fields of a transaction that can be amended until it is posted. Fields left out are
+/// kept.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionChanges {
+    pub amount: Option<Decimal>,
+    pub effective_date: Option<NaiveDate>,
+    /// Replaces the metadata as a whole.
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl TransactionChanges {
+    pub fn is_empty(&self) -> bool {
+        self.amount.is_none() && self.effective_date.is_none() && self.metadata.is_none()
+    }
+
+    /// Applies the changes to a request, returning each field whose value changed with
+    /// the value it replaced.
+    pub fn apply(&self, request: &mut LedgerTransactionRequest) -> Vec<AmendedField> {
+        let mut changed = Vec::new();
+        if let Some(amount) = self.amount {
+            if amount != request.amount {
+                changed.push(field("amount", &request.amount, &amount));
+                request.amount = amount;
+            }
+        }
+        if let Some(date) = self.effective_date {
+            if Some(date) != request.effective_date {
+                changed.push(field("effective_date", &request.effective_date, &date));
+                request.effective_date = Some(date);
+            }
+        }
+        if let Some(metadata) = &self.metadata {
+            if Some(metadata) != request.metadata.as_ref() {
+                changed.push(field("metadata", &request.metadata, metadata));
+                request.metadata = Some(metadata.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn field<A: Serialize, B: Serialize>(name: &str, from: &A, to: &B) -> AmendedField {
+    AmendedField {
+        field: name.to_string(),
+        from: serde_json::to_value(from).unwrap_or_default(),
+        to: serde_json::to_value(to).unwrap_or_default(),
+    }
+}
+
+/// Amends transactions that are still waiting to be posted: queued asynchronous
+/// submissions and transactions held for risk review. Amended requests go through the
+/// ledger's validation again and every amendment is kept with the values it replaced.
+/// Posted transactions are immutable; they are corrected by reversal.
+pub struct AmendmentService {
+    pool: PgPool,
+    ledger: Arc<LedgerService>,
+    amendment_repo: TransactionAmendmentRepository,
+    transaction_repo: TransactionRepository,
+}
+
+impl AmendmentService {
+    pub fn new(pool: PgPool, ledger: Arc<LedgerService>) -> Self {
+        Self {
+            amendment_repo: TransactionAmendmentRepository::new(pool.clone()),
+            transaction_repo: TransactionRepository::new(pool.clone()),
+            pool,
+            ledger,
+        }
+    }
+
+    /// Amends the submission or risk hold with the ID. The item stays locked while the
+    /// amended request is validated, so a worker or reviewer cannot pick it up halfway.
+    pub async fn amend(
+        &self,
+        id: Uuid,
+        changes: &TransactionChanges,
+        amended_by: &str,
+        reason: Option<String>,
+    ) -> Result<TransactionAmendment> {
+        if changes.is_empty() {
+            return Err(AppError::Validation(
+                "An amendment must change the amount, effective date or metadata".to_string(),
+            ));
+        }
+        if amended_by.trim().is_empty() {
+            return Err(AppError::Validation("amended_by is required".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let (target, fields) = if let Some(submission) = SubmissionRepository::find_for_update_in(&mut tx, id).await? {
+            if !submission.status.is_amendable() {
+                return Err(AppError::Validation(format!(
+                    "Submission {} is {:?} and can no longer be amended",
+                    id, submission.status
+                )));
+            }
+            let (request, fields) = self.amended_request(&submission.request, changes).await?;
+            SubmissionRepository::update_request_in(&mut tx, id, &to_json(&request)?).await?;
+            (AmendmentTarget::Submission, fields)
+        } else if let Some(hold) = RiskHoldRepository::find_for_update_in(&mut tx, id).await? {
+            if !hold.status.is_amendable() {
+                return Err(AppError::Validation(format!(
+                    "Risk hold {} was already {:?} and can no longer be amended",
+                    id, hold.status
+                )));
+            }
+            let (request, fields) = self.amended_request(&hold.request, changes).await?;
+            RiskHoldRepository::update_request_in(&mut tx, id, request.amount, &to_json(&request)?).await?;
+            (AmendmentTarget::RiskHold, fields)
+        } else {
+            return Err(match self.transaction_repo.find_by_id(id).await? {
+                Some(transaction) => AppError::Validation(format!(
+                    "Transaction {} is {:?}; posted transactions cannot be amended, only reversed",
+                    id, transaction.status
+                )),
+                None => AppError::NotFound(format!("No queued or held transaction {} found", id)),
+            });
+        };
+
+        let amendment = TransactionAmendment::new(target, id, &fields, amended_by.trim(), reason);
+        let amendment = TransactionAmendmentRepository::create_in(&mut tx, &amendment).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        info!(
+            target_id = %id,
+            target = ?target,
+            fields = fields.len(),
+            amended_by = %amendment.amended_by,
+            "Transaction amended before posting"
+        );
+        Ok(amendment)
+    }
+
+    /// Lists the amendments made to a submission or risk hold, oldest first.
+    pub async fn history(&self, id: Uuid) -> Result<Vec<TransactionAmendment>> {
+        self.amendment_repo.find_by_target(id).await
+    }
+
+    async fn amended_request(
+        &self,
+        stored: &serde_json::Value,
+        changes: &TransactionChanges,
+    ) -> Result<(LedgerTransactionRequest, Vec<AmendedField>)> {
+        let mut request = serde_json::from_value::<LedgerTransactionRequest>(stored.clone())
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Stored request is invalid: {}", e)))?;
+        let fields = changes.apply(&mut request);
+        if fields.is_empty() {
+            return Err(AppError::Validation("The amendment does not change anything".to_string()));
+        }
+        self.ledger.ensure_valid(&request).await?;
+        Ok((request, fields))
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value> {
+    serde_json::to_value(value)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize amended request: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_apply_records_replaced_values() {
+        let mut request = LedgerTransactionRequest::payment("EXT-1", Uuid::new_v4(), Uuid::new_v4(), dec!(100), "USD", "IDEM-1");
+        let changes = TransactionChanges {
+            amount: Some(dec!(120)),
+            effective_date: None,
+            metadata: Some(serde_json::json!({"invoice": "INV-7"})),
+        };
+
+        let fields = changes.apply(&mut request);
+        assert_eq!(request.amount, dec!(120));
+        assert_eq!(request.metadata, Some(serde_json::json!({"invoice": "INV-7"})));
+        let names: Vec<_> = fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(names, vec!["amount", "metadata"]);
+        assert_eq!(fields[0].from, serde_json::to_value(dec!(100)).unwrap());
+        assert_eq!(fields[1].from, serde_json::Value::Null);
+
+        // Applying the same changes again changes nothing
+        assert!(changes.apply(&mut request).is_empty());
+        assert!(TransactionChanges::default().is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::core::leader::LeaderElection;
+use crate::error::{AppError, Result};
+use crate::models::{BalanceFloor, BalanceIncident, BalanceIncidentStatus, StatusChangeReason, StatusReasonCode};
+use crate::notifications::NotificationEngine;
+use crate::observability::get_metrics;
+use crate::repositories::BalanceGuardRepository;
+use crate::services::AccountService;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Last line of defence against balances that should never exist.
+///
+/// A trigger on `account_balances` opens an incident whenever a balance falls below
+/// its floor, whatever made the write. The guard acts on open incidents: it freezes the
+/// account so nothing more moves through it, marks the incident frozen and raises
+/// negative-balance alerts. Unfreezing is left to an operator once the incident has
+/// been resolved.
+pub struct BalanceGuardService {
+    repo: BalanceGuardRepository,
+    account_service: AccountService,
+    notifications: Option<Arc<NotificationEngine>>,
+    batch_size: i64,
+}
+
+impl BalanceGuardService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: BalanceGuardRepository::new(pool.clone()),
+            account_service: AccountService::new(pool),
+            notifications: None,
+            batch_size: 100,
+        }
+    }
+
+    /// Raises negative-balance alerts through the notification engine.
+    pub fn with_notifications(mut self, engine: Arc<NotificationEngine>) -> Self {
+        self.notifications = Some(engine);
+        self
+    }
+
+    /// Sets how many open incidents one pass acts on at most.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Sets the lowest available balance an account may hold in a currency. A negative
+    /// floor lets the account overdraw down to it.
+    pub async fn set_floor(&self, account_id: Uuid, currency: &str, floor: Decimal) -> Result<BalanceFloor> {
+        self.account_service.find_by_id(account_id).await?;
+        self.repo.set_floor(account_id, currency, floor).await
+    }
+
+    /// Freezes the accounts of open incidents and raises alerts for them. Returns the
+    /// incidents acted on.
+    pub async fn enforce(&self) -> Result<Vec<BalanceIncident>> {
+        let mut frozen = Vec::new();
+
+        for incident in self.repo.find_open(self.batch_size).await? {
+            let reason = StatusChangeReason::new(StatusReasonCode::BalanceFloorBreach).with_note(format!(
+                "Balance {} {} breached floor {}",
+                incident.available_balance, incident.currency, incident.floor
+            ));
+            match self.account_service.freeze_account(incident.account_id, reason).await {
+                Ok(_) => {}
+                // Already frozen or closed: nothing left to stop
+                Err(AppError::Validation(message)) => {
+                    warn!(account_id = %incident.account_id, "Balance guard did not freeze account: {}", message)
+                }
+                Err(e) => return Err(e),
+            }
+
+            let Some(incident) = self.repo.mark_frozen(incident.id).await? else {
+                continue;
+            };
+            error!(
+                incident_id = %incident.id,
+                account_id = %incident.account_id,
+                currency = %incident.currency,
+                available = %incident.available_balance,
+                pending = %incident.pending_balance,
+                reserved = %incident.reserved_balance,
+                floor = %incident.floor,
+                "Balance breached its floor; account frozen"
+            );
+            get_metrics().record_balance_floor_breach(&incident.currency);
+            if let Some(engine) = &self.notifications {
+                engine.evaluate_incident(&incident).await;
+            }
+            frozen.push(incident);
+        }
+
+        Ok(frozen)
+    }
+
+    /// Resolves an incident once the balance is back within its floor. The account
+    /// stays frozen until it is activated.
+    pub async fn resolve(&self, id: Uuid, note: &str) -> Result<BalanceIncident> {
+        let incident = self
+            .repo
+            .find_incident(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Balance incident '{}' not found", id)))?;
+        if incident.status == BalanceIncidentStatus::Resolved {
+            return Err(AppError::Validation("Balance incident is already resolved".to_string()));
+        }
+
+        self.repo.resolve(id, note).await?.ok_or_else(|| {
+            AppError::Validation(format!(
+                "Balance of account '{}' in {} is still below its floor",
+                incident.account_id, incident.currency
+            ))
+        })
+    }
+
+    /// Lists incidents, newest first.
+    pub async fn list(
+        &self,
+        status: Option<BalanceIncidentStatus>,
+        account_id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BalanceIncident>> {
+        self.repo.list(status, account_id, limit, offset).await
+    }
+
+    pub async fn count(&self, status: Option<BalanceIncidentStatus>, account_id: Option<Uuid>) -> Result<i64> {
+        self.repo.count(status, account_id).await
+    }
+}
+
+/// Periodically acts on open balance incidents.
+pub struct BalanceGuardJob {
+    service: Arc<BalanceGuardService>,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl BalanceGuardJob {
+    pub fn new(service: Arc<BalanceGuardService>, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+            leader: None,
+        }
+    }
+
+    /// Only runs while this instance is the elected leader.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+        let leader = self.leader.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if leader.as_ref().is_none_or(|leader| leader.is_leader()) {
+                    if let Err(e) = service.enforce().await {
+                        tracing::error!("Balance guard job error: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
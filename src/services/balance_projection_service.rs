@@ -0,0 +1,199 @@
+use crate::error::{AppError, Result};
+use crate::models::{BalanceProjection, BalanceProjectionLag};
+use crate::observability::get_metrics;
+use crate::repositories::{BalanceProjectionRepository, BalanceRepository};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Maintains and serves the event-sourced balance projection.
+///
+/// Ledger entries are the append-only event log: each carries a per-account sequence
+/// assigned when it is posted. The projector folds every account's entries into
+/// `balance_projections` in sequence order, holding the projection row's lock while it
+/// does, so any number of projectors can run and reads never touch the balance rows
+/// postings lock.
+pub struct BalanceProjectionService {
+    pool: PgPool,
+    repo: BalanceProjectionRepository,
+    balance_repo: BalanceRepository,
+    batch_size: i64,
+    accounts_per_pass: i64,
+}
+
+impl BalanceProjectionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: BalanceProjectionRepository::new(pool.clone()),
+            balance_repo: BalanceRepository::new(pool.clone()),
+            pool,
+            batch_size: 500,
+            accounts_per_pass: 100,
+        }
+    }
+
+    /// Sets how many entries of one account are folded per database transaction.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Sets how many lagging accounts one pass works through.
+    pub fn with_accounts_per_pass(mut self, accounts_per_pass: i64) -> Self {
+        self.accounts_per_pass = accounts_per_pass;
+        self
+    }
+
+    /// Folds pending entries of up to `accounts_per_pass` lagging accounts, records the
+    /// remaining lag and returns how many entries were folded.
+    pub async fn project_pending(&self) -> Result<usize> {
+        let mut folded = 0;
+        for (account_id, currency) in self.repo.find_behind(self.accounts_per_pass).await? {
+            folded += self.project_account(account_id, &currency).await?;
+        }
+
+        let lag = self.repo.lag(None, None).await?;
+        get_metrics().record_balance_projection(
+            folded as u64,
+            lag.accounts_behind,
+            lag.entries_behind,
+            lag.lag_secs(Utc::now()),
+        );
+        Ok(folded)
+    }
+
+    /// Folds up to one batch of an account's pending entries and returns how many were
+    /// folded. Accounts another projector is working on are skipped.
+    pub async fn project_account(&self, account_id: Uuid, currency: &str) -> Result<usize> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let Some(mut projection) = BalanceProjectionRepository::lock_in(&mut tx, account_id, currency).await? else {
+            return Ok(0);
+        };
+
+        let entries = BalanceProjectionRepository::entries_after_in(
+            &mut tx,
+            account_id,
+            currency,
+            projection.last_sequence,
+            self.batch_size,
+        )
+        .await?;
+
+        let mut folded = 0;
+        for entry in &entries {
+            if !projection.apply(entry) {
+                tracing::error!(
+                    account_id = %account_id,
+                    currency = %currency,
+                    expected = projection.last_sequence + 1,
+                    found = entry.account_sequence,
+                    "Gap in ledger entry sequence; balance projection stopped for the account"
+                );
+                break;
+            }
+            folded += 1;
+        }
+
+        if folded > 0 {
+            BalanceProjectionRepository::save_in(&mut tx, &projection).await?;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(folded)
+    }
+
+    /// Discards projections and folds them again from the first ledger entry: one
+    /// account's, or every projection if no account is given. Returns how many entries
+    /// were folded.
+    pub async fn rebuild(&self, account_id: Option<Uuid>) -> Result<usize> {
+        self.repo.delete(account_id).await?;
+
+        let mut folded = 0;
+        match account_id {
+            Some(account_id) => {
+                for balance in self.balance_repo.find_by_account(account_id).await? {
+                    loop {
+                        let batch = self.project_account(account_id, &balance.currency).await?;
+                        if batch == 0 {
+                            break;
+                        }
+                        folded += batch;
+                    }
+                }
+            }
+            None => loop {
+                let batch = self.project_pending().await?;
+                if batch == 0 {
+                    break;
+                }
+                folded += batch;
+            },
+        }
+
+        tracing::info!(account_id = ?account_id, entries = folded, "Balance projection rebuilt");
+        Ok(folded)
+    }
+
+    /// Gets the projected balance of an account in a currency.
+    pub async fn get(&self, account_id: Uuid, currency: &str) -> Result<BalanceProjection> {
+        self.repo.find(account_id, currency).await?.ok_or_else(|| {
+            AppError::NotFound(format!(
+                "No projected {} balance for account '{}'",
+                currency, account_id
+            ))
+        })
+    }
+
+    /// Measures how far projections are behind the ledger, optionally for one account
+    /// or one account's currency.
+    pub async fn lag(&self, account_id: Option<Uuid>, currency: Option<&str>) -> Result<BalanceProjectionLag> {
+        self.repo.lag(account_id, currency).await
+    }
+}
+
+/// Background job keeping the balance projection up to date.
+pub struct BalanceProjectionJob {
+    service: Arc<BalanceProjectionService>,
+    running: Arc<AtomicBool>,
+    interval_millis: u64,
+}
+
+impl BalanceProjectionJob {
+    pub fn new(service: Arc<BalanceProjectionService>, interval_millis: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_millis,
+        }
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_millis;
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if let Err(e) = service.project_pending().await {
+                    tracing::error!("Balance projection error: {}", e);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_millis(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
@@ -1,7 +1,10 @@
+use crate::core::money::Money;
 use crate::error::{AppError, Result};
-use crate::models::AccountBalance;
-use crate::repositories::BalanceRepository;
-use chrono::{DateTime, Utc};
+use crate::models::{
+    AccountBalance, BalanceBasis, BalanceExplanation, BalanceReservation, BalanceReservationStatus, DatedBalance,
+};
+use crate::repositories::{BalanceRepository, BalanceReservationRepository, LedgerRepository};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
@@ -39,13 +42,19 @@ impl From<AccountBalance> for BalanceSnapshot {
 
 /// Service for balance management operations.
 pub struct BalanceService {
+    pool: PgPool,
     balance_repo: BalanceRepository,
+    ledger_repo: LedgerRepository,
+    reservation_repo: BalanceReservationRepository,
 }
 
 impl BalanceService {
     pub fn new(pool: PgPool) -> Self {
         Self {
-            balance_repo: BalanceRepository::new(pool),
+            balance_repo: BalanceRepository::new(pool.clone()),
+            ledger_repo: LedgerRepository::new(pool.clone()),
+            reservation_repo: BalanceReservationRepository::new(pool.clone()),
+            pool,
         }
     }
 
@@ -94,73 +103,127 @@ impl BalanceService {
     pub async fn credit(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Credit amount must be positive".to_string()));
         }
 
-        self.balance_repo.credit(account_id, currency, amount).await
+        self.balance_repo.credit(account_id, amount.currency(), amount.amount()).await
     }
 
     /// Debits an account balance.
     pub async fn debit(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Debit amount must be positive".to_string()));
         }
 
-        self.balance_repo.debit(account_id, currency, amount).await
+        self.balance_repo.debit(account_id, amount.currency(), amount.amount()).await
     }
 
     /// Reserves an amount from available balance.
     pub async fn reserve(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Reserve amount must be positive".to_string()));
         }
 
-        self.balance_repo.reserve(account_id, currency, amount).await
+        self.balance_repo.reserve(account_id, amount.currency(), amount.amount()).await
     }
 
     /// Releases a reserved amount back to available.
     pub async fn release_reservation(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Release amount must be positive".to_string()));
         }
 
         self.balance_repo
-            .release_reservation(account_id, currency, amount)
+            .release_reservation(account_id, amount.currency(), amount.amount())
             .await
     }
 
+    /// Reserves funds on an account under a name and a reference unique to the account,
+    /// optionally until an expiry, after which the expiry sweeper releases them.
+    pub async fn create_reservation(&self, reservation: BalanceReservation) -> Result<BalanceReservation> {
+        if reservation.amount <= Decimal::ZERO {
+            return Err(AppError::Validation("Reserve amount must be positive".to_string()));
+        }
+        if reservation.name.trim().is_empty() || reservation.reference.trim().is_empty() {
+            return Err(AppError::Validation("Reservation name and reference are required".to_string()));
+        }
+        if reservation.is_expired_at(Utc::now()) {
+            return Err(AppError::Validation("Reservation expiry must be in the future".to_string()));
+        }
+        self.get_balance(reservation.account_id, &reservation.currency).await?;
+
+        self.reservation_repo.create(&reservation).await
+    }
+
+    /// Gets one of an account's reservations.
+    pub async fn get_reservation(&self, account_id: Uuid, id: Uuid) -> Result<BalanceReservation> {
+        self.reservation_repo
+            .find_by_id(id)
+            .await?
+            .filter(|reservation| reservation.account_id == account_id)
+            .ok_or_else(|| AppError::NotFound(format!("Reservation '{}' not found", id)))
+    }
+
+    /// Lists an account's reservations, optionally in one status.
+    pub async fn list_reservations(
+        &self,
+        account_id: Uuid,
+        status: Option<BalanceReservationStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BalanceReservation>> {
+        self.reservation_repo.list_by_account(account_id, status, limit, offset).await
+    }
+
+    pub async fn count_reservations(&self, account_id: Uuid, status: Option<BalanceReservationStatus>) -> Result<i64> {
+        self.reservation_repo.count_by_account(account_id, status).await
+    }
+
+    /// Releases an active reservation's funds back to its account.
+    pub async fn release_reservation_by_id(&self, account_id: Uuid, id: Uuid) -> Result<BalanceReservation> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let reservation = BalanceReservationRepository::lock_in(&mut tx, id)
+            .await?
+            .filter(|reservation| reservation.account_id == account_id)
+            .ok_or_else(|| AppError::NotFound(format!("Reservation '{}' not found", id)))?;
+        let released =
+            BalanceReservationRepository::close_in(&mut tx, id, BalanceReservationStatus::Released, None)
+                .await?
+                .ok_or_else(|| {
+                    AppError::Validation(format!("Reservation '{}' is {:?}, not active", id, reservation.status))
+                })?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(released)
+    }
+
     /// Moves amount from available to pending.
     pub async fn move_to_pending(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Amount must be positive".to_string()));
         }
 
         self.balance_repo
-            .move_to_pending(account_id, currency, amount)
+            .move_to_pending(account_id, amount.currency(), amount.amount())
             .await
     }
 
@@ -168,15 +231,14 @@ impl BalanceService {
     pub async fn settle_pending(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Amount must be positive".to_string()));
         }
 
         self.balance_repo
-            .settle_pending(account_id, currency, amount)
+            .settle_pending(account_id, amount.currency(), amount.amount())
             .await
     }
 
@@ -190,7 +252,7 @@ impl BalanceService {
             .update_with_version(balance)
             .await?
             .ok_or_else(|| {
-                AppError::Validation(
+                AppError::SerializationConflict(
                     "Concurrent modification detected. Please retry the operation.".to_string(),
                 )
             })
@@ -200,11 +262,10 @@ impl BalanceService {
     pub async fn has_sufficient_funds(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<bool> {
-        let balance = self.get_balance(account_id, currency).await?;
-        Ok(balance.has_sufficient_funds(amount))
+        let balance = self.get_balance(account_id, amount.currency()).await?;
+        Ok(balance.has_sufficient_funds(amount.amount()))
     }
 
     /// Gets the usable balance (available - reserved).
@@ -217,16 +278,99 @@ impl BalanceService {
         Ok(balance.usable_balance())
     }
 
+    /// Gets an account's closing balance on a past day or today.
+    pub async fn balance_as_of(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        date: NaiveDate,
+        basis: BalanceBasis,
+    ) -> Result<DatedBalance> {
+        let mut series = self.balance_history(account_id, currency, date, date, basis).await?;
+        series
+            .pop()
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("No balance rebuilt for {}", date)))
+    }
+
+    /// Gets an account's closing balance for each day from `from` to `to`.
+    ///
+    /// Balances include reserved funds, which are held but still booked to the account.
+    /// On the value basis backdated entries count from their effective date, which is
+    /// what interest and limit calculations should use; on the booking basis they count
+    /// from the day they were posted.
+    pub async fn balance_history(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        basis: BalanceBasis,
+    ) -> Result<Vec<DatedBalance>> {
+        if from > to {
+            return Err(AppError::Validation("from must not be after to".to_string()));
+        }
+        if to > Utc::now().date_naive() {
+            return Err(AppError::Validation(format!("Cannot rebuild balances for future date {}", to)));
+        }
+
+        let balance = self.get_balance(account_id, currency).await?;
+        let movements = self
+            .ledger_repo
+            .daily_movements_after(account_id, currency, from, basis)
+            .await?;
+        let booked = balance.available_balance + balance.reserved_balance;
+        Ok(DatedBalance::series(booked, &movements, from, to))
+    }
+
+    /// Explains the change in an account's balance from the start of `from` to the end
+    /// of `to`: the opening and closing balances, and the entries in between summed by
+    /// what moved the balance (payments, fees, refunds, reversals, batch settlements and
+    /// adjustments).
+    pub async fn explain_balance_change(
+        &self,
+        account_id: Uuid,
+        currency: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        basis: BalanceBasis,
+    ) -> Result<BalanceExplanation> {
+        if from > to {
+            return Err(AppError::Validation("from must not be after to".to_string()));
+        }
+        let day_before = from
+            .pred_opt()
+            .ok_or_else(|| AppError::Validation(format!("Cannot explain balances from {}", from)))?;
+        let series = self.balance_history(account_id, currency, day_before, to, basis).await?;
+        let (Some(opening), Some(closing)) = (series.first(), series.last()) else {
+            return Err(AppError::Internal(anyhow::anyhow!("No balances rebuilt for {} to {}", from, to)));
+        };
+        let groups = self
+            .ledger_repo
+            .movement_groups(account_id, currency, from, to, basis)
+            .await?;
+
+        Ok(BalanceExplanation {
+            account_id,
+            currency: currency.to_string(),
+            from,
+            to,
+            basis,
+            opening_balance: opening.balance,
+            closing_balance: closing.balance,
+            net_change: closing.balance - opening.balance,
+            categories: BalanceExplanation::summarize(&groups),
+        })
+    }
+
     /// Validates that an account has sufficient funds for a debit operation.
     pub async fn validate_sufficient_funds(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<()> {
-        if !self.has_sufficient_funds(account_id, currency, amount).await? {
-            let balance = self.get_balance(account_id, currency).await?;
-            return Err(AppError::Validation(format!(
+        if !self.has_sufficient_funds(account_id, amount).await? {
+            let balance = self.get_balance(account_id, amount.currency()).await?;
+            return Err(AppError::InsufficientFunds(format!(
                 "Insufficient funds: requested {}, available {}",
                 amount,
                 balance.usable_balance()
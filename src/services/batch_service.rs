@@ -1,11 +1,30 @@
+use crate::core::executor::AccountExecutor;
+use crate::core::job_control::JobControl;
+use crate::core::locks::{DistributedLocks, LockGuard};
 use crate::error::{AppError, Result};
-use crate::models::{BatchStatus, SettlementBatch, TransactionRecord, TransactionStatus};
-use crate::repositories::{BatchRepository, TransactionRepository};
+use crate::events::{
+    enqueue_event, BatchEvent, ControlAccountBreakEvent, EventEnvelope, EventProducer, EventType, FinalityEvent, NetDebitCapWarningEvent,
+};
+use crate::models::{
+    BatchCutOffChange, BatchProcessingProgress, BatchReconciliation, BatchStatus, BilateralPairRecord, CutOffApprovalPolicy, CutOffChangeKind,
+    EngineMode, FinalityRecord, InstructionStrategy,
+    NetDebitCap, NetDebitCapAction, NettingMode, NettingSummary, SettlementBatch, SettlementWindow, TransactionRecord,
+    TransactionStatus,
+};
+use crate::observability::get_metrics;
+use crate::repositories::{
+    AccountRepository, BatchProgressRepository, BatchReconciliationRepository, BatchRepository,
+    BilateralPairRepository, FinalityRepository, LedgerRepository, NetDebitCapRepository, NettingRepository, SettlementWindowRepository, TransactionRepository,
+};
+use crate::services::{
+    FeeConfig, FeeSettlementService, FundingService, InstructionStatus, InstructionType, NettingAdviceService,
+    NettingConfig, NettingService, SettlementInstruction, SettlementRail, TransactionTypeService,
+};
 use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use futures::future::join_all;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
@@ -66,6 +85,122 @@ impl Default for SettlementWindowConfig {
     }
 }
 
+/// Size and value caps for a single batch. When a transaction would push a batch past a
+/// cap, assignment rolls over to the next batch in the same settlement window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchCaps {
+    pub max_transactions: Option<i32>,
+    pub max_gross_amount: Option<Decimal>,
+}
+
+impl BatchCaps {
+    /// Returns true if the batch cannot take a transaction of `amount` without breaching a cap.
+    pub fn is_exceeded_by(&self, batch: &SettlementBatch, amount: Decimal) -> bool {
+        batch.would_exceed_caps(amount, self.max_transactions, self.max_gross_amount)
+    }
+}
+
+/// Limits on the multilateral net debit a participant may run up within one batch.
+/// Caps set per account and currency take precedence over `default_cap`; participants
+/// with neither are unlimited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetDebitCapConfig {
+    pub default_cap: Option<Decimal>,
+    /// Shares of the cap, in percent, at which a warning event is raised the first
+    /// time a transaction takes the net debit past them.
+    pub warning_thresholds: Vec<u32>,
+    pub on_breach: NetDebitCapAction,
+}
+
+impl Default for NetDebitCapConfig {
+    fn default() -> Self {
+        Self {
+            default_cap: None,
+            warning_thresholds: vec![80, 95],
+            on_breach: NetDebitCapAction::Reject,
+        }
+    }
+}
+
+impl NetDebitCapConfig {
+    /// Returns the warning thresholds a net debit moving from `before` to `after`
+    /// crosses, lowest first.
+    pub fn crossed_thresholds(&self, cap: Decimal, before: Decimal, after: Decimal) -> Vec<u32> {
+        if cap <= Decimal::ZERO {
+            return Vec::new();
+        }
+        let mut crossed: Vec<u32> = self
+            .warning_thresholds
+            .iter()
+            .copied()
+            .filter(|percent| {
+                let level = cap * Decimal::from(*percent) / Decimal::ONE_HUNDRED;
+                before < level && after >= level
+            })
+            .collect();
+        crossed.sort_unstable();
+        crossed.dedup();
+        crossed
+    }
+}
+
+/// A payer's net debit in a batch before and after taking a transaction, against its cap.
+#[derive(Debug, Clone, Copy)]
+struct NetDebitExposure {
+    cap: Decimal,
+    before: Decimal,
+    after: Decimal,
+}
+
+impl NetDebitExposure {
+    fn is_breached(&self) -> bool {
+        self.after > self.cap
+    }
+}
+
+/// Transactions processed at once when a batch is processed, unless configured.
+pub const DEFAULT_BATCH_WORKERS: usize = 8;
+
+/// Transactions processed between progress checkpoints, unless configured.
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 500;
+
+/// Seconds a processing run can go without checkpointing before its batch is
+/// considered stalled, unless configured.
+pub const DEFAULT_STALL_TIMEOUT_SECS: u64 = 300;
+
+/// Furthest a cut-off can be moved in one change, unless configured.
+pub const DEFAULT_MAX_CUT_OFF_SHIFT_SECS: u64 = 4 * 3600;
+
+/// Who asked for a cut-off change, who approved it and why. Under the four-eyes policy
+/// the approver must be a different person from the requester.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CutOffApproval {
+    pub requested_by: String,
+    pub approved_by: String,
+    pub reason: String,
+}
+
+impl CutOffApproval {
+    pub fn validate(&self, policy: CutOffApprovalPolicy) -> Result<()> {
+        if self.requested_by.trim().is_empty() || self.approved_by.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Cut-off changes need a requester and an approver".to_string(),
+            ));
+        }
+        if policy == CutOffApprovalPolicy::FourEyes
+            && self.requested_by.trim().eq_ignore_ascii_case(self.approved_by.trim())
+        {
+            return Err(AppError::Validation(
+                "Cut-off changes must be approved by someone other than the requester".to_string(),
+            ));
+        }
+        if self.reason.trim().is_empty() {
+            return Err(AppError::Validation("Cut-off changes need a reason".to_string()));
+        }
+        Ok(())
+    }
+}
+
 /// Batch state machine for managing status transitions.
 #[derive(Debug, Clone)]
 pub struct BatchStateMachine;
@@ -112,6 +247,11 @@ pub struct BatchProcessingResult {
     pub fee_amount: Decimal,
     pub processing_time_ms: u64,
     pub errors: Vec<BatchProcessingError>,
+    /// Net instructions released to the settlement rail, with their final status.
+    pub instructions: Vec<SettlementInstruction>,
+    /// Totals checks run before completion; a batch with breaks is failed. `None` if
+    /// every transaction failed, so the checks were not run.
+    pub reconciliation: Option<BatchReconciliation>,
 }
 
 /// Error during batch processing.
@@ -122,6 +262,31 @@ pub struct BatchProcessingError {
     pub error_message: String,
 }
 
+/// Batch a transaction was assigned to when it settled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchAssignment {
+    pub batch_id: Uuid,
+    pub settlement_date: NaiveDate,
+    pub sequence_number: i32,
+    pub window_id: Option<Uuid>,
+    pub cut_off_time: DateTime<Utc>,
+    /// True if the batch was opened for this transaction.
+    pub opened_batch: bool,
+}
+
+impl BatchAssignment {
+    pub fn new(batch: &SettlementBatch, opened_batch: bool) -> Self {
+        Self {
+            batch_id: batch.id,
+            settlement_date: batch.settlement_date,
+            sequence_number: batch.sequence_number,
+            window_id: batch.window_id,
+            cut_off_time: batch.cut_off_time,
+            opened_batch,
+        }
+    }
+}
+
 /// Notification for batch completion.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchCompletionNotification {
@@ -141,6 +306,11 @@ pub struct CreateBatchRequest {
     pub cut_off_time: DateTime<Utc>,
     pub currency: String,
     pub metadata: Option<serde_json::Value>,
+    /// Named settlement window the batch belongs to.
+    pub window_id: Option<Uuid>,
+    pub netting_mode: NettingMode,
+    /// Overrides the configured instruction strategy for this batch.
+    pub instruction_strategy: Option<InstructionStrategy>,
 }
 
 impl CreateBatchRequest {
@@ -150,6 +320,9 @@ impl CreateBatchRequest {
             cut_off_time,
             currency: currency.into(),
             metadata: None,
+            window_id: None,
+            netting_mode: NettingMode::Multilateral,
+            instruction_strategy: None,
         }
     }
 
@@ -162,25 +335,78 @@ impl CreateBatchRequest {
         self.metadata = Some(metadata);
         self
     }
+
+    pub fn with_window(mut self, window_id: Uuid) -> Self {
+        self.window_id = Some(window_id);
+        self
+    }
+
+    pub fn with_netting_mode(mut self, netting_mode: NettingMode) -> Self {
+        self.netting_mode = netting_mode;
+        self
+    }
+
+    pub fn with_instruction_strategy(mut self, strategy: InstructionStrategy) -> Self {
+        self.instruction_strategy = Some(strategy);
+        self
+    }
 }
 
 /// The batch settlement service handles all batch-related operations.
 pub struct BatchService {
     pool: PgPool,
     batch_repo: BatchRepository,
+    progress_repo: BatchProgressRepository,
+    reconciliation_repo: BatchReconciliationRepository,
     transaction_repo: TransactionRepository,
+    finality_repo: FinalityRepository,
+    window_repo: SettlementWindowRepository,
+    transaction_types: TransactionTypeService,
     config: SettlementWindowConfig,
+    caps: BatchCaps,
+    net_debit_caps: NetDebitCapConfig,
+    executor: Arc<AccountExecutor>,
+    checkpoint_interval: usize,
+    stall_timeout: std::time::Duration,
+    max_cut_off_shift: Duration,
+    locks: Arc<DistributedLocks>,
     notifications: Arc<RwLock<Vec<BatchCompletionNotification>>>,
+    producer: Option<Arc<EventProducer>>,
+    rail: Option<Arc<dyn SettlementRail>>,
+    fees: Option<Arc<FeeConfig>>,
+    advices: Option<Arc<NettingAdviceService>>,
+    funding: Option<Arc<FundingService>>,
+    netting: NettingConfig,
+    mode: EngineMode,
 }
 
 impl BatchService {
     pub fn new(pool: PgPool) -> Self {
         Self {
             batch_repo: BatchRepository::new(pool.clone()),
+            progress_repo: BatchProgressRepository::new(pool.clone()),
+            reconciliation_repo: BatchReconciliationRepository::new(pool.clone()),
             transaction_repo: TransactionRepository::new(pool.clone()),
+            finality_repo: FinalityRepository::new(pool.clone()),
+            window_repo: SettlementWindowRepository::new(pool.clone()),
+            transaction_types: TransactionTypeService::new(pool.clone()),
+            locks: Arc::new(DistributedLocks::postgres(pool.clone())),
             pool,
             config: SettlementWindowConfig::default(),
+            caps: BatchCaps::default(),
+            net_debit_caps: NetDebitCapConfig::default(),
+            executor: Arc::new(AccountExecutor::new(DEFAULT_BATCH_WORKERS)),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            stall_timeout: std::time::Duration::from_secs(DEFAULT_STALL_TIMEOUT_SECS),
+            max_cut_off_shift: Duration::seconds(DEFAULT_MAX_CUT_OFF_SHIFT_SECS as i64),
             notifications: Arc::new(RwLock::new(Vec::new())),
+            producer: None,
+            rail: None,
+            fees: None,
+            advices: None,
+            funding: None,
+            netting: NettingConfig::default(),
+            mode: EngineMode::default(),
         }
     }
 
@@ -189,52 +415,330 @@ impl BatchService {
         self
     }
 
-    /// Creates a new settlement batch.
+    pub fn with_caps(mut self, caps: BatchCaps) -> Self {
+        self.caps = caps;
+        self
+    }
+
+    /// Limits each participant's net debit within a batch as transactions are assigned.
+    pub fn with_net_debit_caps(mut self, net_debit_caps: NetDebitCapConfig) -> Self {
+        self.net_debit_caps = net_debit_caps;
+        self
+    }
+
+    /// Sets how many transactions are processed at once when a batch is processed.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.executor = Arc::new(AccountExecutor::new(workers));
+        self
+    }
+
+    /// Sets how many transactions are processed between progress checkpoints.
+    pub fn with_checkpoint_interval(mut self, transactions: usize) -> Self {
+        self.checkpoint_interval = transactions.max(1);
+        self
+    }
+
+    /// Sets how long a processing run can go without checkpointing before another run
+    /// may take its batch over. Must comfortably exceed the time a checkpoint interval's
+    /// worth of transactions takes to process.
+    pub fn with_stall_timeout(mut self, stall_timeout: std::time::Duration) -> Self {
+        self.stall_timeout = stall_timeout;
+        self
+    }
+
+    /// Sets the furthest an operator can move a batch's cut-off, either way, in one change.
+    pub fn with_max_cut_off_shift(mut self, max_shift: std::time::Duration) -> Self {
+        self.max_cut_off_shift = Duration::from_std(max_shift).unwrap_or(Duration::MAX);
+        self
+    }
+
+    /// Sets where the locks serializing closing and processing of a batch are kept.
+    /// Defaults to Postgres advisory locks on the service's pool.
+    pub fn with_locks(mut self, locks: Arc<DistributedLocks>) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// Publishes a finality event when a batch's transactions become final.
+    pub fn with_producer(mut self, producer: Arc<EventProducer>) -> Self {
+        self.producer = Some(producer);
+        self
+    }
+
+    /// Settles the fees accrued in a batch when it completes, for currencies whose fees
+    /// are booked in the accrued mode.
+    pub fn with_fees(mut self, fees: Arc<FeeConfig>) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+
+    /// Issues netting advices to the participants of completed batches.
+    pub fn with_advices(mut self, advices: Arc<NettingAdviceService>) -> Self {
+        self.advices = Some(advices);
+        self
+    }
+
+    /// Opens funding obligations for the net payers of completed batches.
+    pub fn with_funding(mut self, funding: Arc<FundingService>) -> Self {
+        self.funding = Some(funding);
+        self
+    }
+
+    /// Sets how released instructions move funds. With `ControlAccount`, the settlement
+    /// control account is checked to return to zero once they are executed.
+    pub fn with_instruction_strategy(mut self, strategy: InstructionStrategy) -> Self {
+        self.netting.instruction_strategy = strategy;
+        self
+    }
+
+    /// Sets how completed batches are netted for reconciliation and release.
+    pub fn with_netting(mut self, config: NettingConfig) -> Self {
+        self.netting = config;
+        self
+    }
+
+    /// Nets completed batches and releases their instructions to a settlement rail.
+    pub fn with_rail(mut self, rail: Arc<dyn SettlementRail>) -> Self {
+        self.rail = Some(rail);
+        self
+    }
+
+    /// Sets the engine mode. No batches are opened in `Gross` mode.
+    pub fn with_mode(mut self, mode: EngineMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn ensure_batching(&self) -> Result<()> {
+        if !self.mode.batching_enabled() {
+            return Err(AppError::Validation(
+                "Settlement batches are disabled in GROSS engine mode".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates a new settlement batch. Each named settlement window of a currency has
+    /// its own open batch; batches outside any window share one per currency.
     pub async fn create_batch(&self, request: CreateBatchRequest) -> Result<SettlementBatch> {
         // Validate cut-off time is in the future
         if request.cut_off_time <= Utc::now() {
             return Err(AppError::Validation("Cut-off time must be in the future".to_string()));
         }
 
-        // Check if there's already an open batch for this date/currency
-        if let Some(existing) = self
-            .batch_repo
-            .find_open_batch(request.settlement_date, &request.currency)
-            .await?
-        {
-            return Err(AppError::Validation(format!(
-                "Open batch already exists for {} in {}: {}",
-                request.settlement_date, request.currency, existing.id
-            )));
+        if let Some(window_id) = request.window_id {
+            let window = self.find_window(window_id).await?;
+            if window.currency != request.currency {
+                return Err(AppError::Validation(format!(
+                    "Settlement window '{}' is for {}, not {}",
+                    window.name, window.currency, request.currency
+                )));
+            }
         }
 
         let mut batch = SettlementBatch::new(
             request.settlement_date,
             request.cut_off_time,
             request.currency,
-        );
+        )
+        .with_netting_mode(request.netting_mode);
 
+        if let Some(strategy) = request.instruction_strategy {
+            batch = batch.with_instruction_strategy(strategy);
+        }
         if let Some(metadata) = request.metadata {
             batch = batch.with_metadata(metadata);
         }
+        if let Some(window_id) = request.window_id {
+            batch = batch.with_window(window_id);
+        }
+
+        // Fails if there's already an open batch for this date/currency (and window);
+        // one past its cut-off, e.g. closed early, is waiting to be processed
+        let (existing, opened) = self.open_batch(&batch, |latest| latest.can_accept_transaction()).await?;
+        if !opened {
+            return Err(AppError::Validation(format!(
+                "Open batch already exists for {} in {}: {}",
+                existing.settlement_date, existing.currency, existing.id
+            )));
+        }
+        Ok(existing)
+    }
 
-        self.batch_repo.create(&batch).await
+    /// Opens a batch prepared ahead of time, e.g. from a template, unless its settlement
+    /// date, currency and window already have an open batch taking transactions. Returns
+    /// the open batch and whether it was opened.
+    pub async fn provision_batch(&self, batch: &SettlementBatch) -> Result<(SettlementBatch, bool)> {
+        self.open_batch(batch, |latest| latest.can_accept_transaction()).await
     }
 
     /// Gets or creates a batch for the current settlement window.
+    ///
+    /// When the currency has active named windows, the batch belongs to the one with the
+    /// nearest cut-off; otherwise the configured window type decides the cut-off.
     pub async fn get_or_create_current_batch(&self, currency: &str) -> Result<SettlementBatch> {
+        if let Some(window) = self.next_window(currency).await? {
+            return self.open_window_batch(&window).await;
+        }
+
         let today = Utc::now().date_naive();
 
         // Try to find existing open batch
         if let Some(batch) = self.batch_repo.find_open_batch(today, currency).await? {
-            return Ok(batch);
+            if batch.can_accept_transaction() {
+                return Ok(batch);
+            }
         }
 
         // Calculate cut-off time based on config
         let cut_off_time = self.calculate_cut_off_time();
 
-        let request = CreateBatchRequest::new(today, cut_off_time, currency);
-        self.create_batch(request).await
+        let batch = SettlementBatch::new(today, cut_off_time, currency.to_string());
+        self.open_batch(&batch, |latest| latest.can_accept_transaction()).await.map(|(batch, _)| batch)
+    }
+
+    /// Gets or creates the open batch of a named settlement window for its next cut-off.
+    pub async fn get_or_create_window_batch(&self, window_id: Uuid) -> Result<SettlementBatch> {
+        let window = self.find_window(window_id).await?;
+        if !window.active {
+            return Err(AppError::Validation(format!(
+                "Settlement window '{}' is inactive",
+                window.name
+            )));
+        }
+        self.open_window_batch(&window).await
+    }
+
+    /// Assigns a transaction to the open batch of a named settlement window.
+    pub async fn assign_transaction_to_window(
+        &self,
+        transaction_id: Uuid,
+        window_id: Uuid,
+    ) -> Result<TransactionRecord> {
+        let transaction = self
+            .transaction_repo
+            .find_by_id(transaction_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction_id)))?;
+        let window = self.find_window(window_id).await?;
+        if transaction.currency != window.currency {
+            return Err(AppError::Validation(format!(
+                "Transaction '{}' is in {} but settlement window '{}' settles {}",
+                transaction_id, transaction.currency, window.name, window.currency
+            )));
+        }
+
+        let batch = self.get_or_create_window_batch(window_id).await?;
+        self.assign_transaction_to_batch(transaction_id, batch.id).await
+    }
+
+    /// Assigns a newly settled transaction to the current open batch for its currency
+    /// within the caller's database transaction, so the assignment commits or rolls back
+    /// with the settlement. A batch is opened when none can take the transaction. A
+    /// transaction already in a batch is returned with that batch, leaving its totals as
+    /// they are.
+    pub async fn assign_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        transaction: &TransactionRecord,
+    ) -> Result<(TransactionRecord, BatchAssignment)> {
+        self.ensure_batching()?;
+        let locked = TransactionRepository::find_for_update_in(tx, transaction.id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction.id)))?;
+        if let Some(batch_id) = locked.settlement_batch_id {
+            let batch = self
+                .batch_repo
+                .find_by_id(batch_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Batch '{}' not found", batch_id)))?;
+            return Ok((locked, BatchAssignment::new(&batch, false)));
+        }
+        let (settlement_date, cut_off_time, window_id) = match self.next_window(&transaction.currency).await? {
+            Some(window) => {
+                let (date, cut_off) = window.next_cut_off(Utc::now()).ok_or_else(|| {
+                    AppError::Internal(anyhow::anyhow!("Settlement window '{}' has no next cut-off", window.name))
+                })?;
+                (date, cut_off, Some(window.id))
+            }
+            None => (Utc::now().date_naive(), self.calculate_cut_off_time(), None),
+        };
+
+        let open = BatchRepository::find_open_batch_in(tx, settlement_date, &transaction.currency, window_id)
+            .await?
+            .filter(|batch| batch.can_accept_transaction() && !self.caps.is_exceeded_by(batch, transaction.amount));
+
+        let (batch, opened) = match open {
+            Some(batch) => (batch, false),
+            None => {
+                let mut batch = SettlementBatch::new(settlement_date, cut_off_time, transaction.currency.clone());
+                if let Some(window_id) = window_id {
+                    batch = batch.with_window(window_id);
+                }
+                Self::open_batch_in(tx, &batch, |open| {
+                    open.can_accept_transaction() && !self.caps.is_exceeded_by(open, transaction.amount)
+                })
+                .await?
+            }
+        };
+
+        let (batch, exposure, opened_for_cap) = self.enforce_net_debit_cap_in(tx, batch, transaction).await?;
+
+        let gross = !self.transaction_types.resolve_record(transaction).await?.nettable;
+        let assigned = TransactionRepository::assign_to_batch_in(tx, transaction.id, batch.id, gross)
+            .await?
+            .ok_or_else(|| Self::assigned_elsewhere(transaction.id, None))?;
+        let batch = BatchRepository::increment_totals_in(tx, batch.id, transaction.amount, transaction.fee_amount)
+            .await?
+            .ok_or_else(|| AppError::BatchClosed(format!("Batch '{}' closed during assignment", batch.id)))?;
+        if let Some(exposure) = exposure {
+            self.enqueue_net_debit_warnings(tx, &batch, transaction, exposure).await?;
+        }
+
+        Ok((assigned, BatchAssignment::new(&batch, opened || opened_for_cap)))
+    }
+
+    /// Returns the active named window of a currency with the nearest cut-off.
+    async fn next_window(&self, currency: &str) -> Result<Option<SettlementWindow>> {
+        let now = Utc::now();
+        Ok(self
+            .window_repo
+            .list(Some(currency), true)
+            .await?
+            .into_iter()
+            .filter_map(|window| window.next_cut_off(now).map(|(_, cut_off)| (cut_off, window)))
+            .min_by_key(|(cut_off, _)| *cut_off)
+            .map(|(_, window)| window))
+    }
+
+    async fn find_window(&self, window_id: Uuid) -> Result<SettlementWindow> {
+        self.window_repo
+            .find_by_id(window_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Settlement window with id '{}' not found", window_id)))
+    }
+
+    async fn open_window_batch(&self, window: &SettlementWindow) -> Result<SettlementBatch> {
+        let (settlement_date, cut_off_time) = window.next_cut_off(Utc::now()).ok_or_else(|| {
+            AppError::Validation(format!(
+                "Settlement window '{}' has an unknown timezone '{}'",
+                window.name, window.timezone
+            ))
+        })?;
+
+        if let Some(batch) = self
+            .batch_repo
+            .find_open_batch_for_window(window.id, settlement_date)
+            .await?
+            .filter(|batch| batch.can_accept_transaction())
+        {
+            return Ok(batch);
+        }
+
+        let batch = SettlementBatch::new(settlement_date, cut_off_time, window.currency.clone()).with_window(window.id);
+        self.open_batch(&batch, |latest| latest.can_accept_transaction())
+            .await
+            .map(|(batch, _)| batch)
     }
 
     /// Calculates the cut-off time based on configuration.
@@ -269,53 +773,367 @@ impl BatchService {
     }
 
     /// Assigns a transaction to a batch.
+    ///
+    /// If the transaction would breach the batch caps it goes to the newest open batch in
+    /// the same settlement window instead, creating a new sub-batch when that one is full
+    /// too. The returned record's `settlement_batch_id` shows where it landed. Caps are
+    /// checked before the totals are incremented, so concurrent assignments can overshoot
+    /// a cap slightly.
+    ///
+    /// A transaction that would take its payer past its net debit cap is rejected with
+    /// `LimitExceeded`, or queued to the next batch when configured to.
+    ///
+    /// Assignment is idempotent: a transaction already in the batch is returned as it is
+    /// without counting it again, and one in another batch is rejected with
+    /// `AlreadyAssigned`. The transaction row is locked while it is assigned and the
+    /// totals incremented, so concurrent retries count it once.
     pub async fn assign_transaction_to_batch(
         &self,
         transaction_id: Uuid,
         batch_id: Uuid,
     ) -> Result<TransactionRecord> {
-        // Verify batch exists and can accept transactions
+        // Verify batch exists
         let batch = self
             .batch_repo
             .find_by_id(batch_id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Batch '{}' not found", batch_id)))?;
 
+        // Verify transaction exists and is not yet in a batch
+        let transaction = self
+            .transaction_repo
+            .find_by_id(transaction_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction_id)))?;
+        if Self::is_assigned(&transaction, batch_id)? {
+            return Ok(transaction);
+        }
+
         if !batch.can_accept_transaction() {
-            return Err(AppError::Validation(format!(
+            return Err(AppError::BatchClosed(format!(
                 "Batch '{}' cannot accept transactions (status: {:?}, cut-off: {})",
                 batch_id, batch.status, batch.cut_off_time
             )));
         }
 
-        // Verify transaction exists
+        // Verify transaction is settled
+        if transaction.status != TransactionStatus::Settled {
+            return Err(AppError::Validation(format!(
+                "Transaction '{}' must be settled before batch assignment (status: {:?})",
+                transaction_id, transaction.status
+            )));
+        }
+
+        if !transaction.settlement_route.is_nettable() {
+            return Err(AppError::Validation(format!(
+                "Transaction '{}' settled gross via RTGS and cannot be netted in a batch",
+                transaction_id
+            )));
+        }
+
+        let batch = if self.caps.is_exceeded_by(&batch, transaction.amount) {
+            self.roll_over(&batch, transaction.amount).await?
+        } else {
+            batch
+        };
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        // Re-check under the row lock, since a concurrent call may have assigned it
+        let locked = TransactionRepository::find_for_update_in(&mut tx, transaction_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction_id)))?;
+        if Self::is_assigned(&locked, batch_id)? {
+            return Ok(locked);
+        }
+
+        let (batch, exposure, _) = self.enforce_net_debit_cap_in(&mut tx, batch, &transaction).await?;
+
+        // Assign transaction to batch, flagging it to settle gross if its type does not net
+        let gross = !self.transaction_types.resolve_record(&transaction).await?.nettable;
+        let updated = TransactionRepository::assign_to_batch_in(&mut tx, transaction_id, batch.id, gross)
+            .await?
+            .ok_or_else(|| Self::assigned_elsewhere(transaction_id, None))?;
+
+        // Update batch totals
+        BatchRepository::increment_totals_in(&mut tx, batch.id, transaction.amount, transaction.fee_amount).await?;
+        if let Some(exposure) = exposure {
+            self.enqueue_net_debit_warnings(&mut tx, &batch, &transaction, exposure).await?;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(updated)
+    }
+
+    /// Moves a transaction from its batch to another, e.g. when it was routed to the
+    /// wrong settlement window. Both batches must still be pending and before their
+    /// cut-off, and the new one must settle the transaction's currency and have room
+    /// under its caps and the payer's net debit cap, checked with the new batch locked;
+    /// unlike assignment, a breach is rejected rather than queued. The old batch's totals
+    /// are decremented, the new one's incremented and the move audited in one database
+    /// transaction. Moving a transaction to the batch it is in changes nothing.
+    pub async fn reassign_transaction(
+        &self,
+        transaction_id: Uuid,
+        batch_id: Uuid,
+        reason: &str,
+    ) -> Result<TransactionRecord> {
+        self.ensure_batching()?;
         let transaction = self
             .transaction_repo
             .find_by_id(transaction_id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction_id)))?;
+        let from_batch_id = transaction.settlement_batch_id.ok_or_else(|| {
+            AppError::Validation(format!(
+                "Transaction '{}' is not in a batch; assign it instead",
+                transaction_id
+            ))
+        })?;
+        if from_batch_id == batch_id {
+            return Ok(transaction);
+        }
 
-        // Verify transaction is settled
-        if transaction.status != TransactionStatus::Settled {
+        let from = self.get_batch(from_batch_id).await?;
+        let to = self.get_batch(batch_id).await?;
+        for batch in [&from, &to] {
+            if !batch.can_accept_transaction() {
+                return Err(AppError::BatchClosed(format!(
+                    "Batch '{}' cannot change its transactions (status: {:?}, cut-off: {})",
+                    batch.id, batch.status, batch.cut_off_time
+                )));
+            }
+        }
+        if to.currency != transaction.currency {
             return Err(AppError::Validation(format!(
-                "Transaction '{}' must be settled before batch assignment (status: {:?})",
-                transaction_id, transaction.status
+                "Transaction '{}' is in {} but batch '{}' settles {}",
+                transaction_id, transaction.currency, batch_id, to.currency
             )));
         }
 
-        // Assign transaction to batch
-        let updated = self
-            .transaction_repo
-            .assign_to_batch(transaction_id, batch_id)
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let to = BatchRepository::find_for_update_in(&mut tx, batch_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Transaction not found after update".to_string()))?;
+            .ok_or_else(|| AppError::NotFound(format!("Batch '{}' not found", batch_id)))?;
+        if self.caps.is_exceeded_by(&to, transaction.amount) {
+            return Err(AppError::LimitExceeded(format!(
+                "Batch '{}' has no room for transaction '{}' under its caps",
+                batch_id, transaction_id
+            )));
+        }
+        let exposure = self.net_debit_exposure_in(&mut tx, batch_id, &transaction).await?;
+        if let Some(exposure) = exposure.as_ref().filter(|exposure| exposure.is_breached()) {
+            get_metrics().record_net_debit_cap_breach(&transaction.currency, "rejected");
+            return Err(AppError::LimitExceeded(format!(
+                "Moving transaction '{}' would take account '{}' to a net debit of {} {} in batch '{}', over its cap of {}",
+                transaction_id, transaction.source_account_id, exposure.after, transaction.currency, batch_id, exposure.cap
+            )));
+        }
+        let moved =
+            TransactionRepository::reassign_batch_in(&mut tx, transaction_id, from_batch_id, batch_id, reason)
+                .await?
+                .ok_or_else(|| {
+                    AppError::SerializationConflict(format!(
+                        "Transaction '{}' left batch '{}' while it was being moved",
+                        transaction_id, from_batch_id
+                    ))
+                })?;
+        BatchRepository::decrement_totals_in(&mut tx, from_batch_id, transaction.amount, transaction.fee_amount)
+            .await?
+            .ok_or_else(|| AppError::BatchClosed(format!("Batch '{}' closed during reassignment", from_batch_id)))?;
+        BatchRepository::increment_totals_in(&mut tx, batch_id, transaction.amount, transaction.fee_amount)
+            .await?
+            .ok_or_else(|| AppError::BatchClosed(format!("Batch '{}' closed during reassignment", batch_id)))?;
+        if let Some(exposure) = exposure {
+            self.enqueue_net_debit_warnings(&mut tx, &to, &transaction, exposure).await?;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
 
-        // Update batch totals
-        self.batch_repo
-            .increment_totals(batch_id, transaction.amount, transaction.fee_amount)
+        tracing::info!(
+            transaction_id = %transaction_id,
+            from_batch_id = %from_batch_id,
+            batch_id = %batch_id,
+            "Transaction moved to another batch"
+        );
+        Ok(moved)
+    }
+
+    /// Whether a transaction is already in `batch_id`, so assigning it again changes
+    /// nothing. A transaction in another batch cannot be assigned.
+    fn is_assigned(transaction: &TransactionRecord, batch_id: Uuid) -> Result<bool> {
+        match transaction.settlement_batch_id {
+            None => Ok(false),
+            Some(current) if current == batch_id => Ok(true),
+            Some(current) => Err(Self::assigned_elsewhere(transaction.id, Some(current))),
+        }
+    }
+
+    fn assigned_elsewhere(transaction_id: Uuid, batch_id: Option<Uuid>) -> AppError {
+        match batch_id {
+            Some(batch_id) => AppError::AlreadyAssigned(format!(
+                "Transaction '{}' is already assigned to batch '{}'",
+                transaction_id, batch_id
+            )),
+            None => AppError::AlreadyAssigned(format!(
+                "Transaction '{}' is already assigned to a batch",
+                transaction_id
+            )),
+        }
+    }
+
+    /// Finds or creates the batch that takes over from a full one.
+    async fn roll_over(&self, full: &SettlementBatch, amount: Decimal) -> Result<SettlementBatch> {
+        let mut sub_batch = SettlementBatch::new(full.settlement_date, full.cut_off_time, full.currency.clone())
+            .with_metadata(serde_json::json!({ "rolled_over_from": full.id }));
+        if let Some(window_id) = full.window_id {
+            sub_batch = sub_batch.with_window(window_id);
+        }
+        // The latest open batch takes over if it has room, otherwise a new one is opened
+        let (next, opened) = self
+            .open_batch(&sub_batch, |latest| {
+                latest.id != full.id && latest.can_accept_transaction() && !self.caps.is_exceeded_by(latest, amount)
+            })
             .await?;
 
-        Ok(updated)
+        if opened {
+            tracing::info!(
+                "Batch {} reached its caps; rolled over to batch {} (sequence {})",
+                full.id, next.id, next.sequence_number
+            );
+        }
+        Ok(next)
+    }
+
+    /// Sets the most an account may owe net in one batch of a currency, overriding the
+    /// configured default.
+    pub async fn set_net_debit_cap(&self, account_id: Uuid, currency: &str, cap: Decimal) -> Result<NetDebitCap> {
+        if cap < Decimal::ZERO {
+            return Err(AppError::Validation("Net debit cap cannot be negative".to_string()));
+        }
+        AccountRepository::new(self.pool.clone())
+            .find_by_id(account_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account with id '{}' not found", account_id)))?;
+        NetDebitCapRepository::new(self.pool.clone()).set_cap(account_id, currency, cap).await
+    }
+
+    /// Checks a transaction against its payer's net debit cap in `batch`, within the
+    /// caller's database transaction. Returns the batch the transaction should go to, the
+    /// payer's exposure there if it is capped, and whether a batch was opened to queue
+    /// the transaction in.
+    async fn enforce_net_debit_cap_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        batch: SettlementBatch,
+        transaction: &TransactionRecord,
+    ) -> Result<(SettlementBatch, Option<NetDebitExposure>, bool)> {
+        let exposure = match self.net_debit_exposure_in(tx, batch.id, transaction).await? {
+            Some(exposure) if exposure.is_breached() => exposure,
+            exposure => return Ok((batch, exposure, false)),
+        };
+
+        if self.net_debit_caps.on_breach == NetDebitCapAction::Queue {
+            let mut next = SettlementBatch::new(batch.settlement_date, batch.cut_off_time, batch.currency.clone())
+                .with_metadata(serde_json::json!({ "rolled_over_from": batch.id }));
+            if let Some(window_id) = batch.window_id {
+                next = next.with_window(window_id);
+            }
+            let (next, opened) = Self::open_batch_in(tx, &next, |latest| {
+                latest.id != batch.id
+                    && latest.can_accept_transaction()
+                    && !self.caps.is_exceeded_by(latest, transaction.amount)
+            })
+            .await?;
+            if let Some(queued) = self.net_debit_exposure_in(tx, next.id, transaction).await? {
+                if !queued.is_breached() {
+                    tracing::info!(
+                        "Transaction {} would take account {} past its net debit cap in batch {}; queued to batch {}",
+                        transaction.id, transaction.source_account_id, batch.id, next.id
+                    );
+                    get_metrics().record_net_debit_cap_breach(&transaction.currency, "queued");
+                    return Ok((next, Some(queued), opened));
+                }
+            }
+        }
+
+        get_metrics().record_net_debit_cap_breach(&transaction.currency, "rejected");
+        Err(AppError::LimitExceeded(format!(
+            "Transaction '{}' would take account '{}' to a net debit of {} {} in batch '{}', over its cap of {}",
+            transaction.id, transaction.source_account_id, exposure.after, transaction.currency, batch.id, exposure.cap
+        )))
+    }
+
+    /// Looks up the payer's net debit in a batch and what it would be with the
+    /// transaction. `None` if the payer has no cap.
+    async fn net_debit_exposure_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        batch_id: Uuid,
+        transaction: &TransactionRecord,
+    ) -> Result<Option<NetDebitExposure>> {
+        let cap = NetDebitCapRepository::find_cap_in(tx, transaction.source_account_id, &transaction.currency)
+            .await?
+            .map(|cap| cap.cap)
+            .or(self.net_debit_caps.default_cap);
+        let Some(cap) = cap else {
+            return Ok(None);
+        };
+
+        let before = NetDebitCapRepository::net_debit_in(tx, batch_id, transaction.source_account_id).await?;
+        Ok(Some(NetDebitExposure {
+            cap,
+            before,
+            after: before + transaction.amount,
+        }))
+    }
+
+    /// Queues a warning event for each threshold of the payer's cap the transaction
+    /// took its net debit past, so the participant can fund or slow down before cut-off.
+    async fn enqueue_net_debit_warnings(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        batch: &SettlementBatch,
+        transaction: &TransactionRecord,
+        exposure: NetDebitExposure,
+    ) -> Result<()> {
+        let crossed = self
+            .net_debit_caps
+            .crossed_thresholds(exposure.cap, exposure.before, exposure.after);
+        for threshold_percent in crossed {
+            let utilization_percent = (exposure.after * Decimal::ONE_HUNDRED / exposure.cap).round_dp(2);
+            tracing::warn!(
+                "Account {} reached {}% of its net debit cap in batch {} ({} of {} {}), cut-off {}",
+                transaction.source_account_id,
+                threshold_percent,
+                batch.id,
+                exposure.after,
+                exposure.cap,
+                transaction.currency,
+                batch.cut_off_time
+            );
+            let event = NetDebitCapWarningEvent {
+                batch_id: batch.id,
+                account_id: transaction.source_account_id,
+                currency: transaction.currency.clone(),
+                cap: exposure.cap,
+                net_debit: exposure.after,
+                threshold_percent,
+                utilization_percent,
+                cut_off_time: batch.cut_off_time,
+                transaction_id: transaction.id,
+                occurred_at: Utc::now(),
+            };
+            let envelope = EventEnvelope::new(EventType::NetDebitCapWarning, event);
+            enqueue_event(
+                tx,
+                NetDebitCapWarningEvent::topic(),
+                Some(transaction.source_account_id.to_string()),
+                &envelope,
+            )
+            .await?;
+            get_metrics().record_net_debit_cap_warning(&transaction.currency, threshold_percent);
+        }
+        Ok(())
     }
 
     /// Calculates and updates batch totals from assigned transactions.
@@ -345,6 +1163,147 @@ impl BatchService {
 
     /// Closes a batch for processing (no more transactions accepted).
     pub async fn close_batch(&self, batch_id: Uuid) -> Result<SettlementBatch> {
+        let lock = self.lock_batch(batch_id).await?;
+        let closed = self.close_locked(batch_id).await;
+        self.unlock_batch(lock).await;
+        closed
+    }
+
+    /// Moves a pending batch's cut-off, e.g. to give participants more time. The new
+    /// cut-off must be in the future and within the configured shift of the current
+    /// one. Transactions are accepted, and the scheduler waits, until the new cut-off.
+    pub async fn move_cut_off(
+        &self,
+        batch_id: Uuid,
+        cut_off_time: DateTime<Utc>,
+        approval: &CutOffApproval,
+    ) -> Result<SettlementBatch> {
+        if cut_off_time <= Utc::now() {
+            return Err(AppError::Validation(
+                "New cut-off must be in the future; use close-early to close the batch now".to_string(),
+            ));
+        }
+
+        let lock = self.lock_batch(batch_id).await?;
+        let moved = async {
+            let batch = self.pending_batch(batch_id).await?;
+            approval.validate(batch.cut_off_approval)?;
+            let shift = cut_off_time - batch.cut_off_time;
+            if shift.abs() > self.max_cut_off_shift {
+                return Err(AppError::Validation(format!(
+                    "Cut-off can move by at most {} minutes at a time",
+                    self.max_cut_off_shift.num_minutes()
+                )));
+            }
+            self.change_cut_off(&batch, CutOffChangeKind::Moved, cut_off_time, approval).await
+        }
+        .await;
+        self.unlock_batch(lock).await;
+        moved
+    }
+
+    /// Closes a pending batch to new transactions now by bringing its cut-off forward.
+    /// Transactions arriving afterwards go to a new batch, and the scheduler processes
+    /// this one on its next run.
+    pub async fn close_early(&self, batch_id: Uuid, approval: &CutOffApproval) -> Result<SettlementBatch> {
+        let lock = self.lock_batch(batch_id).await?;
+        let closed = async {
+            let batch = self.pending_batch(batch_id).await?;
+            approval.validate(batch.cut_off_approval)?;
+            let now = Utc::now();
+            if batch.cut_off_time <= now {
+                return Err(AppError::Validation(format!(
+                    "Batch '{}' is already past its cut-off of {}",
+                    batch_id, batch.cut_off_time
+                )));
+            }
+            self.change_cut_off(&batch, CutOffChangeKind::ClosedEarly, now, approval).await
+        }
+        .await;
+        self.unlock_batch(lock).await;
+        closed
+    }
+
+    /// Gets the cut-off changes made to a batch, oldest first.
+    pub async fn get_cut_off_changes(&self, batch_id: Uuid) -> Result<Vec<BatchCutOffChange>> {
+        self.get_batch(batch_id).await?;
+        self.batch_repo.find_cut_off_changes(batch_id).await
+    }
+
+    async fn pending_batch(&self, batch_id: Uuid) -> Result<SettlementBatch> {
+        let batch = self.get_batch(batch_id).await?;
+        if batch.status != BatchStatus::Pending {
+            return Err(AppError::BatchClosed(format!(
+                "Batch '{}' is {:?}; only pending batches can change cut-off",
+                batch_id, batch.status
+            )));
+        }
+        Ok(batch)
+    }
+
+    /// Stores a new cut-off with its audit record and queues a batch event, together.
+    async fn change_cut_off(
+        &self,
+        batch: &SettlementBatch,
+        kind: CutOffChangeKind,
+        cut_off_time: DateTime<Utc>,
+        approval: &CutOffApproval,
+    ) -> Result<SettlementBatch> {
+        let change = BatchCutOffChange::new(
+            batch.id,
+            kind,
+            batch.cut_off_time,
+            cut_off_time,
+            approval.requested_by.trim(),
+            approval.approved_by.trim(),
+            approval.reason.trim(),
+        );
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let updated = BatchRepository::update_cut_off_in(&mut tx, &change).await?.ok_or_else(|| {
+            AppError::BatchClosed(format!("Batch '{}' closed before its cut-off could change", batch.id))
+        })?;
+        Self::enqueue_batch_event(&mut tx, &updated, EventType::BatchCutOffChanged, None).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        tracing::info!(
+            "Batch {} cut-off {:?} from {} to {} by {}, approved by {}: {}",
+            batch.id,
+            kind,
+            change.previous_cut_off,
+            change.new_cut_off,
+            change.requested_by,
+            change.approved_by,
+            change.reason
+        );
+        Ok(updated)
+    }
+
+    /// Takes the batch's lock, held while it is closed or processed so concurrent
+    /// requests and other instances cannot close or process it at the same time.
+    async fn lock_batch(&self, batch_id: Uuid) -> Result<LockGuard> {
+        self.locks
+            .try_acquire(&format!("batch:{}", batch_id))
+            .await?
+            .ok_or_else(|| {
+                AppError::BatchClosed(format!(
+                    "Batch '{}' is being closed or processed by another request",
+                    batch_id
+                ))
+            })
+    }
+
+    /// Releases a batch lock. The work it guarded is done, so a failure is logged;
+    /// the lock still goes with its connection or expires.
+    async fn unlock_batch(&self, lock: LockGuard) {
+        let name = lock.name().to_string();
+        if let Err(e) = lock.release().await {
+            tracing::warn!("Failed to release lock {}: {}", name, e);
+        }
+    }
+
+    /// Closes a batch; the caller holds its lock.
+    async fn close_locked(&self, batch_id: Uuid) -> Result<SettlementBatch> {
         let batch = self
             .batch_repo
             .find_by_id(batch_id)
@@ -353,54 +1312,169 @@ impl BatchService {
 
         BatchStateMachine::transition(batch.status, BatchStatus::Processing)?;
 
-        self.batch_repo
-            .update_status(batch_id, BatchStatus::Processing)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Batch not found after update".to_string()))
+        self.transition(batch_id, BatchStatus::Processing, EventType::BatchProcessing)
+            .await
     }
 
     /// Manually triggers batch processing.
     pub async fn trigger_batch_processing(&self, batch_id: Uuid) -> Result<BatchProcessingResult> {
         let start_time = std::time::Instant::now();
+        let lock = self.lock_batch(batch_id).await?;
 
-        // Close the batch first
-        let batch = self.close_batch(batch_id).await?;
+        // Close the batch first, then process it
+        let result = match self.close_locked(batch_id).await {
+            Ok(batch) => self.process_batch_internal(batch, false, start_time).await,
+            Err(e) => Err(e),
+        };
 
-        // Process the batch
-        self.process_batch_internal(batch, start_time).await
+        self.unlock_batch(lock).await;
+        result
     }
 
-    /// Processes a batch internally.
+    /// Closes a batch and processes it in the background, returning the closed batch.
+    /// Progress can be followed with `get_processing_progress`.
+    pub async fn start_batch_processing(self: &Arc<Self>, batch_id: Uuid) -> Result<SettlementBatch> {
+        let start_time = std::time::Instant::now();
+        let lock = self.lock_batch(batch_id).await?;
+        let batch = match self.close_locked(batch_id).await {
+            Ok(batch) => batch,
+            Err(e) => {
+                self.unlock_batch(lock).await;
+                return Err(e);
+            }
+        };
+
+        // The lock stays held until the background run finishes
+        let service = self.clone();
+        let closed = batch.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.process_batch_internal(closed, false, start_time).await {
+                tracing::error!("Failed to process batch {}: {}", batch_id, e);
+            }
+            service.unlock_batch(lock).await;
+        });
+
+        Ok(batch)
+    }
+
+    /// Resumes processing of a batch whose run stopped mid-way, e.g. because its
+    /// instance died, skipping the transactions that run already processed.
+    pub async fn resume_batch_processing(&self, batch_id: Uuid) -> Result<BatchProcessingResult> {
+        let start_time = std::time::Instant::now();
+        let batch = self.get_batch(batch_id).await?;
+        if batch.status != BatchStatus::Processing {
+            return Err(AppError::Validation(format!(
+                "Only batches being processed can be resumed (current status: {:?})",
+                batch.status
+            )));
+        }
+
+        let lock = self.lock_batch(batch_id).await?;
+        let result = self.process_batch_internal(batch, true, start_time).await;
+        self.unlock_batch(lock).await;
+        result
+    }
+
+    /// Resumes every batch whose processing run has not checkpointed within the stall
+    /// timeout. Batches another instance claims first are skipped.
+    pub async fn resume_stalled_batches(&self) -> Result<Vec<BatchProcessingResult>> {
+        let stalled = self.progress_repo.find_stalled(self.stall_timeout).await?;
+        let mut results = Vec::new();
+
+        for batch_id in stalled {
+            match self.resume_batch_processing(batch_id).await {
+                Ok(result) => results.push(result),
+                Err(AppError::BatchClosed(_)) => {}
+                Err(e) => {
+                    tracing::error!("Failed to resume batch {}: {}", batch_id, e);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Processes a closed batch, or resumes one a stopped run left unfinished.
+    ///
+    /// The run holds the batch's progress row, heartbeating it at every checkpoint, and
+    /// stops if another run has taken the batch over. Each transaction is recorded in
+    /// `batch_transaction_checkpoints` in the database transaction it is processed in,
+    /// so a resumed run skips exactly the transactions already processed.
     async fn process_batch_internal(
         &self,
         batch: SettlementBatch,
+        resume: bool,
         start_time: std::time::Instant,
     ) -> Result<BatchProcessingResult> {
         let batch_id = batch.id;
-        let mut errors = Vec::new();
-        let mut successful = 0;
-        let mut failed = 0;
+        let run_id = Uuid::new_v4();
 
         // Get all transactions in the batch
         let transactions = self.transaction_repo.find_by_batch(batch_id).await?;
+        let total = transactions.len() as i32;
+        let claimed = if resume {
+            self.progress_repo.claim(batch_id, total, run_id, self.stall_timeout).await?
+        } else {
+            self.progress_repo.start(batch_id, total, run_id, self.stall_timeout).await?
+        };
+        if claimed.is_none() {
+            return Err(AppError::BatchClosed(format!(
+                "Batch '{}' is being processed by another run",
+                batch_id
+            )));
+        }
 
-        // Process transactions (in a real system, this would do actual settlement)
-        for transaction in &transactions {
-            match self.process_transaction_in_batch(transaction).await {
-                Ok(_) => successful += 1,
-                Err(e) => {
-                    failed += 1;
-                    errors.push(BatchProcessingError {
-                        transaction_id: transaction.id,
-                        error_code: "PROCESSING_ERROR".to_string(),
-                        error_message: e.to_string(),
-                    });
-                }
+        // Counts what earlier runs processed, which this run skips
+        self.progress_repo.checkpoint(batch_id, run_id).await?;
+        let done = self.progress_repo.checkpointed(batch_id).await?;
+        let pending: Vec<&TransactionRecord> = transactions.iter().filter(|t| !done.contains(&t.id)).collect();
+        if !done.is_empty() {
+            tracing::info!(
+                "Continuing batch {}: {} of {} transactions left",
+                batch_id,
+                pending.len(),
+                transactions.len()
+            );
+        }
+
+        // Process transactions (in a real system, this would do actual settlement).
+        // Transactions run concurrently, but those sharing an account run in batch order.
+        for chunk in pending.chunks(self.checkpoint_interval) {
+            join_all(chunk.iter().map(|transaction| {
+                self.executor.run(
+                    &[transaction.source_account_id, transaction.destination_account_id],
+                    self.process_checkpointed(batch_id, transaction),
+                )
+            }))
+            .await
+            .into_iter()
+            .collect::<Result<Vec<()>>>()?;
+
+            if !self.progress_repo.checkpoint(batch_id, run_id).await? {
+                return Err(AppError::BatchClosed(format!(
+                    "Batch '{}' was taken over by another run",
+                    batch_id
+                )));
             }
         }
 
+        // Failures of earlier runs count too
+        let errors: Vec<BatchProcessingError> = self
+            .progress_repo
+            .failures(batch_id)
+            .await?
+            .into_iter()
+            .map(|(transaction_id, error_message)| BatchProcessingError {
+                transaction_id,
+                error_code: "PROCESSING_ERROR".to_string(),
+                error_message,
+            })
+            .collect();
+        let failed = errors.len() as i32;
+        let successful = transactions.len() as i32 - failed;
+
         // Determine final status
-        let final_status = if failed == 0 {
+        let mut final_status = if failed == 0 {
             BatchStatus::Completed
         } else if successful == 0 {
             BatchStatus::Failed
@@ -409,13 +1483,55 @@ impl BatchService {
             BatchStatus::Completed
         };
 
+        // A batch whose totals do not reconcile is failed rather than completed
+        let reconciliation = if final_status == BatchStatus::Completed {
+            let reconciliation = self.reconcile(&batch, &transactions).await?;
+            if !reconciliation.passed {
+                tracing::warn!(
+                    "Batch {} failed reconciliation: {}",
+                    batch_id,
+                    reconciliation.breaks
+                );
+                final_status = BatchStatus::Failed;
+            }
+            Some(reconciliation)
+        } else {
+            None
+        };
+
         // Update batch status
-        let updated_batch = self
-            .batch_repo
-            .update_status(batch_id, final_status)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Batch not found after processing".to_string()))?;
+        let event_type = if final_status == BatchStatus::Completed {
+            EventType::BatchCompleted
+        } else {
+            EventType::BatchFailed
+        };
+        let updated_batch = self.transition(batch_id, final_status, event_type).await?;
+
+        if final_status == BatchStatus::Completed {
+            let settled: Vec<Uuid> = transactions
+                .iter()
+                .map(|t| t.id)
+                .filter(|id| !errors.iter().any(|e| e.transaction_id == *id))
+                .collect();
+            let records = self.finality_repo.record_batch(batch_id, &settled).await?;
+            self.publish_finality(&updated_batch, &records).await;
+            self.settle_fees(&updated_batch).await;
+        }
 
+        let instructions = match &self.rail {
+            Some(rail) if final_status == BatchStatus::Completed => {
+                self.release_instructions(&updated_batch, rail.clone()).await
+            }
+            _ => Vec::new(),
+        };
+        if final_status == BatchStatus::Completed {
+            self.issue_advices(batch_id).await;
+            self.open_funding(batch_id).await;
+        }
+
+        if let Err(e) = self.progress_repo.finish(batch_id, run_id).await {
+            tracing::warn!("Failed to record end of processing for batch {}: {}", batch_id, e);
+        }
         let processing_time_ms = start_time.elapsed().as_millis() as u64;
 
         // Send completion notification
@@ -442,11 +1558,237 @@ impl BatchService {
             fee_amount: updated_batch.fee_amount,
             processing_time_ms,
             errors,
+            instructions,
+            reconciliation,
         })
     }
 
-    /// Processes a single transaction within a batch.
-    async fn process_transaction_in_batch(&self, _transaction: &TransactionRecord) -> Result<()> {
+    /// Checks a processed batch's totals before it is completed: its transactions add
+    /// up to its gross amount, the ledger entries posted for them balance, and the
+    /// participants' net positions sum to zero. Positions persisted by an earlier
+    /// netting run are checked; otherwise they are calculated from the transactions.
+    /// The outcome is recorded either way.
+    async fn reconcile(
+        &self,
+        batch: &SettlementBatch,
+        transactions: &[TransactionRecord],
+    ) -> Result<BatchReconciliation> {
+        let transaction_gross: Decimal = transactions.iter().map(|t| t.amount).sum();
+        let ledger = LedgerRepository::new(self.pool.clone()).totals_by_batch(batch.id).await?;
+        let mut positions = NettingRepository::new(self.pool.clone()).find_by_batch(batch.id).await?;
+        if positions.is_empty() {
+            positions = NettingService::new(self.pool.clone())
+                .with_config(self.netting.clone())
+                .calculate_multilateral_netting(batch.id, &batch.currency, transactions)
+                .positions;
+        }
+
+        let reconciliation = BatchReconciliation::check(
+            batch.id,
+            &batch.currency,
+            batch.gross_amount,
+            transaction_gross,
+            &ledger,
+            &positions,
+        );
+        self.reconciliation_repo.create(&reconciliation).await
+    }
+
+    /// Gets the most recent totals checks of a batch.
+    pub async fn get_reconciliation(&self, batch_id: Uuid) -> Result<BatchReconciliation> {
+        self.reconciliation_repo
+            .find_latest(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("No reconciliation of batch '{}' found", batch_id)))
+    }
+
+    /// Nets a completed batch and releases its instructions to the settlement rail. The
+    /// batch is already final, so failures are logged and left in the instruction status
+    /// rather than returned.
+    async fn release_instructions(
+        &self,
+        batch: &SettlementBatch,
+        rail: Arc<dyn SettlementRail>,
+    ) -> Vec<SettlementInstruction> {
+        let netting = NettingService::new(self.pool.clone())
+            .with_config(self.netting.clone())
+            .with_rail(rail);
+
+        let report = match netting
+            .process_batch_netting_streaming(batch.id, &batch.currency)
+            .await
+        {
+            Ok(report) => report,
+            Err(e) => {
+                tracing::warn!("Failed to net batch {} for release: {}", batch.id, e);
+                return Vec::new();
+            }
+        };
+
+        let mut instructions = report.instructions();
+        if let Err(e) = netting.release_instructions(&mut instructions).await {
+            tracing::warn!("Failed to release instructions for batch {}: {}", batch.id, e);
+        }
+
+        if let Some(control_account_id) = netting.control_account(&batch.currency) {
+            if instructions.iter().any(|i| i.to_participant == control_account_id) {
+                if let Err(e) = self.check_control_account(batch, control_account_id, &instructions).await {
+                    tracing::warn!("Failed to check the control account of batch {}: {}", batch.id, e);
+                }
+            }
+        }
+
+        instructions
+    }
+
+    /// Checks that a batch's executed funding and payouts left the settlement control
+    /// account at zero, recording the outcome. A residual raises a control account break
+    /// alert; the batch stays completed.
+    async fn check_control_account(
+        &self,
+        batch: &SettlementBatch,
+        control_account_id: Uuid,
+        instructions: &[SettlementInstruction],
+    ) -> Result<BatchReconciliation> {
+        let executed = |instruction_type: InstructionType| -> Decimal {
+            instructions
+                .iter()
+                .filter(|i| i.instruction_type == instruction_type && i.status == InstructionStatus::Executed)
+                .map(|i| i.amount)
+                .sum()
+        };
+        let funded = executed(InstructionType::Funding);
+        let paid_out = executed(InstructionType::Payout);
+
+        let reconciliation = self
+            .reconciliation_repo
+            .create(&BatchReconciliation::check_control_account(
+                batch.id,
+                &batch.currency,
+                control_account_id,
+                funded,
+                paid_out,
+            ))
+            .await?;
+        if reconciliation.passed {
+            return Ok(reconciliation);
+        }
+
+        tracing::error!(
+            batch_id = %batch.id,
+            control_account_id = %control_account_id,
+            funded = %funded,
+            paid_out = %paid_out,
+            "Settlement control account did not return to zero"
+        );
+        let event = ControlAccountBreakEvent {
+            batch_id: batch.id,
+            control_account_id,
+            currency: batch.currency.clone(),
+            funded,
+            paid_out,
+            residual: funded - paid_out,
+            occurred_at: Utc::now(),
+        };
+        let envelope = EventEnvelope::new(EventType::ControlAccountBreak, event);
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        enqueue_event(&mut tx, ControlAccountBreakEvent::topic(), Some(batch.id.to_string()), &envelope).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(reconciliation)
+    }
+
+    /// Settles the batch's accrued fees. The batch is complete either way, so a failure is
+    /// logged and the settlement can be run again for the batch.
+    async fn settle_fees(&self, batch: &SettlementBatch) {
+        let Some(fees) = &self.fees else {
+            return;
+        };
+        if fees.accrual_account(&batch.currency).is_none() {
+            return;
+        }
+        let service = FeeSettlementService::new(self.pool.clone()).with_fees(fees.clone());
+        if let Err(e) = service.settle_batch(batch.id).await {
+            tracing::warn!("Failed to settle accrued fees of batch {}: {}", batch.id, e);
+        }
+    }
+
+    /// Issues the batch's netting advices. The batch is complete either way, so a failure
+    /// is logged and the advices can be issued again for the batch.
+    async fn issue_advices(&self, batch_id: Uuid) {
+        let Some(advices) = &self.advices else {
+            return;
+        };
+        if let Err(e) = advices.issue(batch_id).await {
+            tracing::warn!("Failed to issue netting advices for batch {}: {}", batch_id, e);
+        }
+    }
+
+    /// Opens the batch's funding obligations. The batch is complete either way, so a
+    /// failure is logged and the obligations can be opened again for the batch.
+    async fn open_funding(&self, batch_id: Uuid) {
+        let Some(funding) = &self.funding else {
+            return;
+        };
+        if let Err(e) = funding.open_obligations(batch_id).await {
+            tracing::warn!("Failed to open funding obligations for batch {}: {}", batch_id, e);
+        }
+    }
+
+    /// Publishes the batch finality event. Finality is already recorded, so a publish
+    /// failure is logged rather than returned.
+    async fn publish_finality(&self, batch: &SettlementBatch, records: &[FinalityRecord]) {
+        let Some(producer) = &self.producer else {
+            return;
+        };
+
+        let event = FinalityEvent {
+            batch_id: batch.id,
+            currency: batch.currency.clone(),
+            settlement_date: batch.settlement_date,
+            record_count: records.len(),
+            first_sequence: records.first().map(|r| r.sequence),
+            last_sequence: records.last().map(|r| r.sequence),
+            final_at: records.last().map(|r| r.final_at).unwrap_or_else(Utc::now),
+        };
+        let envelope = EventEnvelope::new(EventType::SettlementFinal, event);
+        let key = batch.id.to_string();
+
+        let sent = producer
+            .send(FinalityEvent::topic(), Some(&key), &envelope)
+            .await;
+        get_metrics().record_kafka_message(FinalityEvent::topic(), sent.is_ok());
+        if let Err(e) = sent {
+            tracing::warn!("Failed to publish finality event for batch {}: {}", batch.id, e);
+        }
+    }
+
+    /// Processes a transaction unless an earlier run already did, recording it in the
+    /// same database transaction. A failure rolls the processing back and is recorded
+    /// on its own; only errors recording the checkpoint are returned.
+    async fn process_checkpointed(&self, batch_id: Uuid, transaction: &TransactionRecord) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        if !BatchProgressRepository::claim_transaction_in(&mut tx, batch_id, transaction.id).await? {
+            return Ok(());
+        }
+
+        match self.process_transaction_in_batch(&mut tx, transaction).await {
+            Ok(()) => tx.commit().await.map_err(AppError::Database),
+            Err(e) => {
+                tx.rollback().await.map_err(AppError::Database)?;
+                self.progress_repo
+                    .record_failure(batch_id, transaction.id, &e.to_string())
+                    .await
+            }
+        }
+    }
+
+    /// Processes a single transaction within a batch, writing any effects in `tx`.
+    async fn process_transaction_in_batch(
+        &self,
+        _tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        _transaction: &TransactionRecord,
+    ) -> Result<()> {
         // In a real system, this would:
         // 1. Verify the transaction is still valid
         // 2. Execute any pending settlements
@@ -507,6 +1849,16 @@ impl BatchService {
             .ok_or_else(|| AppError::NotFound(format!("Batch '{}' not found", batch_id)))
     }
 
+    /// Gets how far processing of a batch has got, as of its last checkpoint.
+    pub async fn get_processing_progress(&self, batch_id: Uuid) -> Result<BatchProcessingProgress> {
+        let _batch = self.get_batch(batch_id).await?;
+
+        self.progress_repo
+            .find(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch '{}' has not started processing", batch_id)))
+    }
+
     /// Processes a batch (alias for trigger_batch_processing for API).
     pub async fn process_batch(&self, batch_id: Uuid) -> Result<BatchProcessingResult> {
         self.trigger_batch_processing(batch_id).await
@@ -514,23 +1866,63 @@ impl BatchService {
 
     /// Gets netting positions for a batch.
     pub async fn get_batch_positions(&self, batch_id: Uuid) -> Result<Vec<crate::models::NettingPosition>> {
-        use crate::repositories::NettingRepository;
-        
         let _batch = self.get_batch(batch_id).await?;
         
         let netting_repo = NettingRepository::new(self.pool.clone());
         netting_repo.find_by_batch(batch_id).await
     }
 
+    /// Gets the stored bilateral pairs of a bilaterally netted batch.
+    /// Sets how a batch's multilateral positions become instructions, or reverts it to
+    /// the configured strategy with `None`. Only pending batches can change strategy.
+    pub async fn set_instruction_strategy(
+        &self,
+        batch_id: Uuid,
+        strategy: Option<InstructionStrategy>,
+    ) -> Result<SettlementBatch> {
+        match self.batch_repo.update_instruction_strategy(batch_id, strategy).await? {
+            Some(batch) => Ok(batch),
+            None => {
+                let batch = self.get_batch(batch_id).await?;
+                Err(AppError::BatchClosed(format!(
+                    "Batch '{}' is {:?}; only pending batches can change instruction strategy",
+                    batch_id, batch.status
+                )))
+            }
+        }
+    }
+
+    pub async fn get_bilateral_pairs(&self, batch_id: Uuid) -> Result<Vec<BilateralPairRecord>> {
+        let _batch = self.get_batch(batch_id).await?;
+
+        BilateralPairRepository::new(self.pool.clone()).find_by_batch(batch_id).await
+    }
+
+    /// Sets how a batch is netted. Only batches that haven't started processing can
+    /// change mode.
+    pub async fn set_netting_mode(&self, batch_id: Uuid, netting_mode: NettingMode) -> Result<SettlementBatch> {
+        match self.batch_repo.update_netting_mode(batch_id, netting_mode).await? {
+            Some(batch) => Ok(batch),
+            None => {
+                let batch = self.get_batch(batch_id).await?;
+                Err(AppError::BatchClosed(format!(
+                    "Batch '{}' is {:?}; only pending batches can change netting mode",
+                    batch_id, batch.status
+                )))
+            }
+        }
+    }
+
     /// Lists batches with optional filters.
     pub async fn list_batches(
         &self,
         status: Option<BatchStatus>,
         currency: Option<&str>,
+        window_id: Option<Uuid>,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<SettlementBatch>> {
-        self.batch_repo.list(status, currency, limit, offset).await
+        self.batch_repo.list(status, currency, window_id, limit, offset).await
     }
 
     /// Gets transactions in a batch.
@@ -553,14 +1945,8 @@ impl BatchService {
             obj.insert("failure_reason".to_string(), serde_json::json!(reason));
         }
 
-        // Update status
-        let updated = self
-            .batch_repo
-            .update_status(batch_id, BatchStatus::Failed)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Batch not found after update".to_string()))?;
-
-        Ok(updated)
+        self.transition(batch_id, BatchStatus::Failed, EventType::BatchFailed)
+            .await
     }
 
     /// Retries a failed batch.
@@ -579,57 +1965,147 @@ impl BatchService {
         }
 
         // Reset to pending
-        self.batch_repo
-            .update_status(batch_id, BatchStatus::Pending)
+        self.transition(batch_id, BatchStatus::Pending, EventType::BatchRetried)
+            .await
+    }
+
+    /// Opens `batch` and queues its `BatchCreated` event, unless the latest open batch
+    /// of its settlement date, currency and window is one `reuse` accepts; returns the
+    /// batch and whether it was opened.
+    async fn open_batch(
+        &self,
+        batch: &SettlementBatch,
+        reuse: impl Fn(&SettlementBatch) -> bool,
+    ) -> Result<(SettlementBatch, bool)> {
+        self.ensure_batching()?;
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let opened = Self::open_batch_in(&mut tx, batch, reuse).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(opened)
+    }
+
+    /// Like `open_batch`, within the caller's database transaction. Openings in a
+    /// settlement date, currency and window are serialized until the transaction ends,
+    /// so concurrent callers, on any instance, settle on the same batch.
+    async fn open_batch_in(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        batch: &SettlementBatch,
+        reuse: impl Fn(&SettlementBatch) -> bool,
+    ) -> Result<(SettlementBatch, bool)> {
+        BatchRepository::lock_window_in(tx, batch.settlement_date, &batch.currency, batch.window_id).await?;
+        let latest =
+            BatchRepository::find_open_batch_in(tx, batch.settlement_date, &batch.currency, batch.window_id).await?;
+        if let Some(latest) = latest.filter(|latest| reuse(latest)) {
+            return Ok((latest, false));
+        }
+
+        let created = BatchRepository::create_in(tx, batch).await?;
+        Self::enqueue_batch_event(tx, &created, EventType::BatchCreated, None).await?;
+        Ok((created, true))
+    }
+
+    /// Moves a batch to a new status and queues the matching batch event in the same
+    /// database transaction. Completion events carry the netting summary when positions
+    /// have been calculated.
+    async fn transition(
+        &self,
+        batch_id: Uuid,
+        status: BatchStatus,
+        event_type: EventType,
+    ) -> Result<SettlementBatch> {
+        let netting = if status == BatchStatus::Completed {
+            let positions = NettingRepository::new(self.pool.clone()).find_by_batch(batch_id).await?;
+            positions
+                .first()
+                .map(|first| NettingSummary::from_positions(batch_id, first.currency.clone(), &positions))
+        } else {
+            None
+        };
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let updated = BatchRepository::update_status_in(&mut tx, batch_id, status)
             .await?
-            .ok_or_else(|| AppError::NotFound("Batch not found after update".to_string()))
+            .ok_or_else(|| AppError::NotFound("Batch not found after update".to_string()))?;
+        Self::enqueue_batch_event(&mut tx, &updated, event_type, netting).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(updated)
+    }
+
+    async fn enqueue_batch_event(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        batch: &SettlementBatch,
+        event_type: EventType,
+        netting: Option<NettingSummary>,
+    ) -> Result<()> {
+        let envelope = EventEnvelope::new(event_type, BatchEvent::from_batch(batch, netting));
+        enqueue_event(tx, BatchEvent::topic(), Some(batch.id.to_string()), &envelope).await
     }
 }
 
 /// Background scheduler for automatic batch processing.
 pub struct BatchScheduler {
     service: Arc<BatchService>,
-    running: Arc<AtomicBool>,
-    interval_seconds: u64,
+    control: Arc<JobControl>,
 }
 
 impl BatchScheduler {
+    /// Name the scheduler is controlled by through the admin API.
+    pub const JOB_NAME: &'static str = "batch_scheduler";
+
     pub fn new(service: Arc<BatchService>, interval_seconds: u64) -> Self {
         Self {
             service,
-            running: Arc::new(AtomicBool::new(false)),
-            interval_seconds,
+            control: Arc::new(JobControl::new(Self::JOB_NAME, std::time::Duration::from_secs(interval_seconds))),
         }
     }
 
+    /// Control for pausing, triggering and inspecting the scheduler at runtime.
+    pub fn control(&self) -> Arc<JobControl> {
+        self.control.clone()
+    }
+
     /// Starts the scheduler in a background task.
     pub fn start(&self) -> tokio::task::JoinHandle<()> {
         let service = self.service.clone();
-        let running = self.running.clone();
-        let interval = self.interval_seconds;
+        let control = self.control.clone();
 
-        running.store(true, Ordering::SeqCst);
+        control.set_running(true);
 
         tokio::spawn(async move {
-            while running.load(Ordering::SeqCst) {
-                // Process expired batches
-                if let Err(e) = service.auto_close_expired_batches().await {
-                    tracing::error!("Batch scheduler error: {}", e);
+            while control.is_running() {
+                if control.begin_run() {
+                    // Resume batches whose processing stopped, then process expired ones
+                    let outcome = match service.resume_stalled_batches().await {
+                        Ok(resumed) => service
+                            .auto_close_expired_batches()
+                            .await
+                            .map(|results| (resumed.len() + results.len()) as u64),
+                        Err(e) => Err(e),
+                    };
+                    match outcome {
+                        Ok(processed) => control.finish_run(Ok(processed)),
+                        Err(e) => {
+                            tracing::error!("Batch scheduler error: {}", e);
+                            control.finish_run(Err(e.to_string()));
+                        }
+                    }
                 }
 
-                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+                control.wait().await;
             }
         })
     }
 
     /// Stops the scheduler.
     pub fn stop(&self) {
-        self.running.store(false, Ordering::SeqCst);
+        self.control.set_running(false);
     }
 
     /// Checks if the scheduler is running.
     pub fn is_running(&self) -> bool {
-        self.running.load(Ordering::SeqCst)
+        self.control.is_running()
     }
 }
 
@@ -700,4 +2176,37 @@ mod tests {
         assert!(config.auto_close);
         assert_eq!(config.timezone, "UTC");
     }
+
+    #[test]
+    fn test_net_debit_thresholds_crossed_once() {
+        let config = NetDebitCapConfig::default();
+        let cap = Decimal::from(1000);
+
+        assert!(config.crossed_thresholds(cap, Decimal::ZERO, Decimal::from(799)).is_empty());
+        assert_eq!(config.crossed_thresholds(cap, Decimal::from(700), Decimal::from(800)), vec![80]);
+        assert_eq!(config.crossed_thresholds(cap, Decimal::from(-200), Decimal::from(960)), vec![80, 95]);
+        // Already past 80%: only the next threshold fires
+        assert_eq!(config.crossed_thresholds(cap, Decimal::from(850), Decimal::from(990)), vec![95]);
+        assert!(config.crossed_thresholds(cap, Decimal::from(960), Decimal::from(1000)).is_empty());
+        assert!(config.crossed_thresholds(Decimal::ZERO, Decimal::ZERO, Decimal::from(10)).is_empty());
+    }
+
+    #[test]
+    fn test_cut_off_approval_needs_second_person() {
+        let approval = |requested_by: &str, approved_by: &str, reason: &str| CutOffApproval {
+            requested_by: requested_by.to_string(),
+            approved_by: approved_by.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let four_eyes = CutOffApprovalPolicy::FourEyes;
+        assert!(approval("alice", "bob", "rail outage").validate(four_eyes).is_ok());
+        assert!(approval("alice", " Alice ", "rail outage").validate(four_eyes).is_err());
+        assert!(approval("alice", "", "rail outage").validate(four_eyes).is_err());
+        assert!(approval("alice", "bob", "  ").validate(four_eyes).is_err());
+
+        let single = CutOffApprovalPolicy::SingleOperator;
+        assert!(approval("alice", "alice", "rail outage").validate(single).is_ok());
+        assert!(approval("alice", "alice", "").validate(single).is_err());
+    }
 }
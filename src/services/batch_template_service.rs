@@ -0,0 +1,372 @@
+use crate::core::job_control::JobControl;
+use crate::error::{AppError, Result};
+use crate::models::{BatchTemplate, CutOffApprovalPolicy, NettingMode, SettlementBatch, SettlementWindow};
+use crate::repositories::{BatchTemplateRepository, SettlementWindowRepository};
+use crate::services::BatchService;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Request to create a batch template. Templates in a named window take its cut-off;
+/// others need a UTC `cut_off_time`.
+#[derive(Debug, Clone)]
+pub struct CreateBatchTemplateRequest {
+    pub name: String,
+    pub currency: String,
+    pub window_id: Option<Uuid>,
+    pub cut_off_time: Option<NaiveTime>,
+    pub metadata: Option<serde_json::Value>,
+    pub netting_mode: NettingMode,
+    pub cut_off_approval: CutOffApprovalPolicy,
+}
+
+/// Partial update of a batch template. Fields left as None are unchanged. Batches
+/// already provisioned keep the configuration they were opened with.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateBatchTemplateRequest {
+    pub name: Option<String>,
+    pub cut_off_time: Option<NaiveTime>,
+    pub metadata: Option<serde_json::Value>,
+    pub netting_mode: Option<NettingMode>,
+    pub cut_off_approval: Option<CutOffApprovalPolicy>,
+    pub active: Option<bool>,
+}
+
+/// A template that was not provisioned, and why.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedTemplate {
+    pub template_id: Uuid,
+    pub reason: String,
+}
+
+/// Outcome of provisioning a settlement date's batches from the active templates.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvisioningReport {
+    pub settlement_date: NaiveDate,
+    /// Batches opened by this run.
+    pub opened: Vec<SettlementBatch>,
+    /// Batches that were already open, from an earlier run or the first transaction.
+    pub existing: Vec<Uuid>,
+    pub skipped: Vec<SkippedTemplate>,
+}
+
+/// Service for batch templates and provisioning batches from them.
+pub struct BatchTemplateService {
+    template_repo: BatchTemplateRepository,
+    window_repo: SettlementWindowRepository,
+    batch_service: BatchService,
+}
+
+impl BatchTemplateService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            template_repo: BatchTemplateRepository::new(pool.clone()),
+            window_repo: SettlementWindowRepository::new(pool.clone()),
+            batch_service: BatchService::new(pool),
+        }
+    }
+
+    /// Creates a batch template. Names are unique per currency, and a currency's window
+    /// (or its batches outside any window) has at most one active template.
+    pub async fn create_template(&self, request: CreateBatchTemplateRequest) -> Result<BatchTemplate> {
+        if request.currency.len() != 3 {
+            return Err(AppError::Validation(
+                "Currency must be a 3-letter ISO 4217 code".to_string(),
+            ));
+        }
+        Self::validate_name(&request.name)?;
+        Self::validate_metadata(request.metadata.as_ref())?;
+        match (request.window_id, request.cut_off_time) {
+            (Some(window_id), None) => {
+                let window = self.find_window(window_id).await?;
+                if window.currency != request.currency {
+                    return Err(AppError::Validation(format!(
+                        "Settlement window '{}' is for {}, not {}",
+                        window.name, window.currency, request.currency
+                    )));
+                }
+            }
+            (None, Some(_)) => {}
+            _ => {
+                return Err(AppError::Validation(
+                    "A batch template needs either a settlement window or a cut-off time, not both".to_string(),
+                ))
+            }
+        }
+
+        if let Some(existing) = self.template_repo.find_by_name(&request.currency, &request.name).await? {
+            return Err(AppError::Validation(format!(
+                "Batch template '{}' already exists for {}: {}",
+                request.name, request.currency, existing.id
+            )));
+        }
+        self.ensure_slot_free(&request.currency, request.window_id, None).await?;
+
+        let mut template = BatchTemplate::new(request.name, request.currency);
+        template.window_id = request.window_id;
+        template.cut_off_time = request.cut_off_time;
+        template.metadata = request.metadata;
+        template.netting_mode = request.netting_mode;
+        template.cut_off_approval = request.cut_off_approval;
+        self.template_repo.create(&template).await
+    }
+
+    /// Gets a batch template by ID.
+    pub async fn get_template(&self, id: Uuid) -> Result<BatchTemplate> {
+        self.template_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch template with id '{}' not found", id)))
+    }
+
+    /// Lists batch templates, optionally for one currency.
+    pub async fn list_templates(&self, currency: Option<&str>, active_only: bool) -> Result<Vec<BatchTemplate>> {
+        self.template_repo.list(currency, active_only).await
+    }
+
+    /// Applies a partial update to a batch template.
+    pub async fn update_template(&self, id: Uuid, request: UpdateBatchTemplateRequest) -> Result<BatchTemplate> {
+        let mut template = self.get_template(id).await?;
+
+        if let Some(name) = request.name {
+            Self::validate_name(&name)?;
+            if name != template.name {
+                if let Some(existing) = self.template_repo.find_by_name(&template.currency, &name).await? {
+                    return Err(AppError::Validation(format!(
+                        "Batch template '{}' already exists for {}: {}",
+                        name, template.currency, existing.id
+                    )));
+                }
+            }
+            template.name = name;
+        }
+        if let Some(cut_off_time) = request.cut_off_time {
+            if template.window_id.is_some() {
+                return Err(AppError::Validation(
+                    "Templates in a settlement window take the window's cut-off".to_string(),
+                ));
+            }
+            template.cut_off_time = Some(cut_off_time);
+        }
+        if let Some(metadata) = request.metadata {
+            Self::validate_metadata(Some(&metadata))?;
+            template.metadata = Some(metadata);
+        }
+        if let Some(netting_mode) = request.netting_mode {
+            template.netting_mode = netting_mode;
+        }
+        if let Some(cut_off_approval) = request.cut_off_approval {
+            template.cut_off_approval = cut_off_approval;
+        }
+        if let Some(active) = request.active {
+            if active && !template.active {
+                self.ensure_slot_free(&template.currency, template.window_id, Some(template.id)).await?;
+            }
+            template.active = active;
+        }
+
+        self.template_repo
+            .update(&template)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch template with id '{}' not found", id)))
+    }
+
+    /// Opens each active template's batch for a settlement date. Safe to repeat: a batch
+    /// already open for the template's date, currency and window is left as it is.
+    /// Templates whose cut-off on the date has passed, or whose window is inactive,
+    /// are skipped.
+    pub async fn provision(&self, settlement_date: NaiveDate) -> Result<ProvisioningReport> {
+        let mut report = ProvisioningReport {
+            settlement_date,
+            opened: Vec::new(),
+            existing: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let now = Utc::now();
+
+        for template in self.template_repo.list(None, true).await? {
+            let mut skip = |reason: String| {
+                report.skipped.push(SkippedTemplate { template_id: template.id, reason });
+            };
+
+            let window = match template.window_id {
+                Some(window_id) => match self.window_repo.find_by_id(window_id).await? {
+                    Some(window) if window.active => Some(window),
+                    _ => {
+                        skip(format!("Settlement window '{}' is inactive", window_id));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            let Some(batch) = template.batch_for(settlement_date, window.as_ref()) else {
+                skip("Cut-off could not be resolved".to_string());
+                continue;
+            };
+            if batch.cut_off_time <= now {
+                skip(format!("Cut-off {} has passed", batch.cut_off_time));
+                continue;
+            }
+
+            match self.batch_service.provision_batch(&batch).await {
+                Ok((batch, true)) => {
+                    tracing::info!(
+                        "Provisioned batch {} for {} {} from template '{}'",
+                        batch.id,
+                        batch.currency,
+                        settlement_date,
+                        template.name
+                    );
+                    report.opened.push(batch);
+                }
+                Ok((batch, false)) => report.existing.push(batch.id),
+                Err(e) => {
+                    tracing::warn!("Failed to provision batch from template {}: {}", template.id, e);
+                    skip(e.to_string());
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Provisions the batches due at `now`: today's, in case a run was missed, and from
+    /// `provision_after` (UTC) onwards, tomorrow's.
+    pub async fn provision_due(&self, now: DateTime<Utc>, provision_after: NaiveTime) -> Result<Vec<ProvisioningReport>> {
+        let mut reports = Vec::new();
+        for date in provisioning_dates(now, provision_after) {
+            reports.push(self.provision(date).await?);
+        }
+        Ok(reports)
+    }
+
+    async fn ensure_slot_free(&self, currency: &str, window_id: Option<Uuid>, except: Option<Uuid>) -> Result<()> {
+        match self.template_repo.find_active_for_slot(currency, window_id).await? {
+            Some(existing) if Some(existing.id) != except => Err(AppError::Validation(format!(
+                "Batch template '{}' is already active for {} {}",
+                existing.name,
+                currency,
+                if window_id.is_some() { "in this window" } else { "outside any window" }
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    async fn find_window(&self, window_id: Uuid) -> Result<SettlementWindow> {
+        self.window_repo
+            .find_by_id(window_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Settlement window with id '{}' not found", window_id)))
+    }
+
+    fn validate_name(name: &str) -> Result<()> {
+        if name.trim().is_empty() || name.len() > 100 {
+            return Err(AppError::Validation(
+                "Batch template name must be 1-100 characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_metadata(metadata: Option<&serde_json::Value>) -> Result<()> {
+        match metadata {
+            Some(value) if !value.is_object() => Err(AppError::Validation(
+                "Batch template metadata must be a JSON object".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Settlement dates to provision at `now`: today, plus tomorrow once the UTC time of day
+/// reaches `provision_after`.
+pub fn provisioning_dates(now: DateTime<Utc>, provision_after: NaiveTime) -> Vec<NaiveDate> {
+    let today = now.date_naive();
+    let mut dates = vec![today];
+    if now.time() >= provision_after {
+        dates.extend(today.succ_opt());
+    }
+    dates
+}
+
+/// Background job opening the next settlement date's batches from templates, so the
+/// first transaction of the day finds its batch already open.
+pub struct BatchProvisioningJob {
+    service: Arc<BatchTemplateService>,
+    provision_after: NaiveTime,
+    control: Arc<JobControl>,
+}
+
+impl BatchProvisioningJob {
+    /// Name the job is controlled by through the admin API.
+    pub const JOB_NAME: &'static str = "batch_provisioning";
+
+    pub fn new(service: Arc<BatchTemplateService>, interval_seconds: u64, provision_after: NaiveTime) -> Self {
+        Self {
+            service,
+            provision_after,
+            control: Arc::new(JobControl::new(Self::JOB_NAME, std::time::Duration::from_secs(interval_seconds))),
+        }
+    }
+
+    /// Control for pausing, triggering and inspecting the job at runtime.
+    pub fn control(&self) -> Arc<JobControl> {
+        self.control.clone()
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let control = self.control.clone();
+        let provision_after = self.provision_after;
+
+        control.set_running(true);
+
+        tokio::spawn(async move {
+            while control.is_running() {
+                if control.begin_run() {
+                    match service.provision_due(Utc::now(), provision_after).await {
+                        Ok(reports) => {
+                            let opened = reports.iter().map(|report| report.opened.len() as u64).sum();
+                            control.finish_run(Ok(opened));
+                        }
+                        Err(e) => {
+                            tracing::error!("Batch provisioning error: {}", e);
+                            control.finish_run(Err(e.to_string()));
+                        }
+                    }
+                }
+
+                control.wait().await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.control.set_running(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_tomorrow_is_provisioned_after_end_of_day() {
+        let provision_after = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+
+        let afternoon = Utc.with_ymd_and_hms(2024, 1, 15, 17, 59, 59).unwrap();
+        assert_eq!(provisioning_dates(afternoon, provision_after), vec![today]);
+
+        let evening = Utc.with_ymd_and_hms(2024, 1, 15, 18, 0, 0).unwrap();
+        assert_eq!(
+            provisioning_dates(evening, provision_after),
+            vec![today, NaiveDate::from_ymd_opt(2024, 1, 16).unwrap()]
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use crate::cache::BalanceCache;
+use crate::cache::{BalanceCache, RedisPool};
 use crate::config::CacheSettings;
+use crate::core::money::Money;
 use crate::error::{AppError, Result};
 use crate::models::AccountBalance;
 use crate::observability::{get_metrics, LatencyTimer};
@@ -16,10 +17,10 @@ pub struct CachedBalanceService {
 }
 
 impl CachedBalanceService {
-    pub fn new(pool: PgPool, redis_client: redis::Client, cache_settings: CacheSettings) -> Self {
+    pub fn new(pool: PgPool, redis: Arc<RedisPool>, cache_settings: CacheSettings) -> Self {
         Self {
             balance_repo: BalanceRepository::new(pool),
-            cache: Arc::new(BalanceCache::new(redis_client, cache_settings)),
+            cache: Arc::new(BalanceCache::new(redis, cache_settings)),
         }
     }
 
@@ -92,17 +93,16 @@ impl CachedBalanceService {
     pub async fn credit(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Credit amount must be positive".to_string()));
         }
 
         let timer = LatencyTimer::new();
-        let balance = self.balance_repo.credit(account_id, currency, amount).await?;
+        let balance = self.balance_repo.credit(account_id, amount.currency(), amount.amount()).await?;
 
-        if let Err(e) = self.cache.invalidate(account_id, currency).await {
+        if let Err(e) = self.cache.invalidate(account_id, amount.currency()).await {
             tracing::warn!("Cache invalidation failed after credit: {}", e);
         }
 
@@ -115,17 +115,16 @@ impl CachedBalanceService {
     pub async fn debit(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Debit amount must be positive".to_string()));
         }
 
         let timer = LatencyTimer::new();
-        let balance = self.balance_repo.debit(account_id, currency, amount).await?;
+        let balance = self.balance_repo.debit(account_id, amount.currency(), amount.amount()).await?;
 
-        if let Err(e) = self.cache.invalidate(account_id, currency).await {
+        if let Err(e) = self.cache.invalidate(account_id, amount.currency()).await {
             tracing::warn!("Cache invalidation failed after debit: {}", e);
         }
 
@@ -138,16 +137,15 @@ impl CachedBalanceService {
     pub async fn reserve(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Reserve amount must be positive".to_string()));
         }
 
-        let balance = self.balance_repo.reserve(account_id, currency, amount).await?;
+        let balance = self.balance_repo.reserve(account_id, amount.currency(), amount.amount()).await?;
 
-        if let Err(e) = self.cache.invalidate(account_id, currency).await {
+        if let Err(e) = self.cache.invalidate(account_id, amount.currency()).await {
             tracing::warn!("Cache invalidation failed after reserve: {}", e);
         }
 
@@ -158,18 +156,17 @@ impl CachedBalanceService {
     pub async fn release_reservation(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Release amount must be positive".to_string()));
         }
 
         let balance = self.balance_repo
-            .release_reservation(account_id, currency, amount)
+            .release_reservation(account_id, amount.currency(), amount.amount())
             .await?;
 
-        if let Err(e) = self.cache.invalidate(account_id, currency).await {
+        if let Err(e) = self.cache.invalidate(account_id, amount.currency()).await {
             tracing::warn!("Cache invalidation failed after release_reservation: {}", e);
         }
 
@@ -180,18 +177,17 @@ impl CachedBalanceService {
     pub async fn move_to_pending(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Amount must be positive".to_string()));
         }
 
         let balance = self.balance_repo
-            .move_to_pending(account_id, currency, amount)
+            .move_to_pending(account_id, amount.currency(), amount.amount())
             .await?;
 
-        if let Err(e) = self.cache.invalidate(account_id, currency).await {
+        if let Err(e) = self.cache.invalidate(account_id, amount.currency()).await {
             tracing::warn!("Cache invalidation failed after move_to_pending: {}", e);
         }
 
@@ -202,18 +198,17 @@ impl CachedBalanceService {
     pub async fn settle_pending(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
-        if amount <= Decimal::ZERO {
+        if !amount.is_positive() {
             return Err(AppError::Validation("Amount must be positive".to_string()));
         }
 
         let balance = self.balance_repo
-            .settle_pending(account_id, currency, amount)
+            .settle_pending(account_id, amount.currency(), amount.amount())
             .await?;
 
-        if let Err(e) = self.cache.invalidate(account_id, currency).await {
+        if let Err(e) = self.cache.invalidate(account_id, amount.currency()).await {
             tracing::warn!("Cache invalidation failed after settle_pending: {}", e);
         }
 
@@ -229,7 +224,7 @@ impl CachedBalanceService {
             .update_with_version(balance)
             .await?
             .ok_or_else(|| {
-                AppError::Validation(
+                AppError::SerializationConflict(
                     "Concurrent modification detected. Please retry the operation.".to_string(),
                 )
             })?;
@@ -245,11 +240,10 @@ impl CachedBalanceService {
     pub async fn has_sufficient_funds(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<bool> {
-        let balance = self.get_balance(account_id, currency).await?;
-        Ok(balance.has_sufficient_funds(amount))
+        let balance = self.get_balance(account_id, amount.currency()).await?;
+        Ok(balance.has_sufficient_funds(amount.amount()))
     }
 
     /// Gets the usable balance (available - reserved).
@@ -266,12 +260,11 @@ impl CachedBalanceService {
     pub async fn validate_sufficient_funds(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<()> {
-        let balance = self.get_balance(account_id, currency).await?;
-        if balance.usable_balance() < amount {
-            return Err(AppError::Validation(format!(
+        let balance = self.get_balance(account_id, amount.currency()).await?;
+        if balance.usable_balance() < amount.amount() {
+            return Err(AppError::InsufficientFunds(format!(
                 "Insufficient funds: requested {}, available {}",
                 amount,
                 balance.usable_balance()
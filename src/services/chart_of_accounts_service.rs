@@ -0,0 +1,258 @@
+use crate::error::{AppError, Result};
+use crate::models::{
+    InternalAccount, InternalAccountDefinition, InternalAccountProvisioning, InternalAccountRole, ProvisioningTrigger,
+};
+use crate::repositories::{AccountRepository, InternalAccountRepository};
+use crate::services::account_service::{AccountService, CreateAccountRequest};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use tokio::sync::Mutex as AsyncMutex;
+use uuid::Uuid;
+
+/// The chart's accounts by role and currency, for lookups that cannot wait on the
+/// database. Clones share the same entries, so accounts provisioned after startup are
+/// seen by every holder.
+#[derive(Debug, Clone, Default)]
+pub struct ChartRegistry {
+    entries: Arc<RwLock<HashMap<(InternalAccountRole, String), Uuid>>>,
+}
+
+impl ChartRegistry {
+    /// Adds the entries that play a role.
+    pub fn register(&self, entries: &[InternalAccount]) {
+        if let Ok(mut registered) = self.entries.write() {
+            for entry in entries {
+                if let Some(role) = entry.role {
+                    registered.insert((role, entry.currency.clone()), entry.account_id);
+                }
+            }
+        }
+    }
+
+    /// Returns the account playing the role in the currency, if one is registered.
+    pub fn role_account(&self, role: InternalAccountRole, currency: &str) -> Option<Uuid> {
+        self.entries
+            .read()
+            .ok()
+            .and_then(|entries| entries.get(&(role, currency.to_string())).copied())
+    }
+}
+
+/// Manages the chart of internal accounts: the engine's own fee, suspense, FX position,
+/// settlement control and collateral accounts, addressed by code and currency.
+pub struct ChartOfAccountsService {
+    repo: InternalAccountRepository,
+    account_repo: AccountRepository,
+    accounts: AccountService,
+    registry: ChartRegistry,
+    templates: Vec<InternalAccountDefinition>,
+    /// Currencies the templates are known to be provisioned in.
+    provisioned: RwLock<HashSet<String>>,
+    /// Serializes first-use provisioning within this instance.
+    provisioning: AsyncMutex<()>,
+}
+
+impl ChartOfAccountsService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: InternalAccountRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool.clone()),
+            accounts: AccountService::new(pool),
+            registry: ChartRegistry::default(),
+            templates: Vec::new(),
+            provisioned: RwLock::new(HashSet::new()),
+            provisioning: AsyncMutex::new(()),
+        }
+    }
+
+    /// Provisions these entries in a currency the first time `ensure_currency` sees it.
+    /// The templates' own currency is ignored.
+    pub fn with_templates(mut self, templates: Vec<InternalAccountDefinition>) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Registry kept up to date with the roles of provisioned entries.
+    pub fn registry(&self) -> ChartRegistry {
+        self.registry.clone()
+    }
+
+    /// Provisions a chart, parents first. Entries already provisioned keep their backing
+    /// account and pick up changes to their name, role, parent and control flag; new
+    /// entries get an account with external ID `INTERNAL-{code}-{currency}`, or adopt
+    /// the account with that ID if one exists, and are recorded in the provisioning
+    /// audit trail. Entries missing from the chart are left in place.
+    pub async fn provision(
+        &self,
+        definitions: &[InternalAccountDefinition],
+        trigger: ProvisioningTrigger,
+    ) -> Result<Vec<InternalAccount>> {
+        let ordered = InternalAccountDefinition::order_chart(definitions).map_err(AppError::Validation)?;
+
+        let mut provisioned = Vec::with_capacity(ordered.len());
+        let mut added = 0;
+        for definition in &ordered {
+            let entry = match self.repo.find(&definition.code, &definition.currency).await? {
+                Some(existing) => self.repo.upsert(definition, existing.account_id).await?,
+                None => {
+                    let (account_id, created_account) = self.backing_account(definition).await?;
+                    let entry = self.repo.upsert(definition, account_id).await?;
+                    self.repo
+                        .record_provisioning(&InternalAccountProvisioning::new(&entry, trigger, created_account))
+                        .await?;
+                    added += 1;
+                    entry
+                }
+            };
+            provisioned.push(entry);
+        }
+        self.registry.register(&provisioned);
+
+        tracing::info!(
+            "Provisioned {} internal account(s), {} new ({:?})",
+            provisioned.len(),
+            added,
+            trigger
+        );
+        Ok(provisioned)
+    }
+
+    /// Makes sure every template is provisioned in the currency, provisioning the
+    /// missing ones the first time the currency is used. Cheap once a currency is known.
+    pub async fn ensure_currency(&self, currency: &str) -> Result<()> {
+        if self.templates.is_empty() {
+            return Ok(());
+        }
+        let currency = currency.trim().to_uppercase();
+        if self.is_provisioned(&currency) {
+            return Ok(());
+        }
+
+        let _guard = self.provisioning.lock().await;
+        if self.is_provisioned(&currency) {
+            return Ok(());
+        }
+        let existing: HashSet<String> = self
+            .repo
+            .find_all(Some(&currency))
+            .await?
+            .into_iter()
+            .map(|entry| entry.code)
+            .collect();
+        let definitions: Vec<InternalAccountDefinition> =
+            self.templates.iter().map(|template| template.in_currency(&currency)).collect();
+        if definitions.iter().all(|definition| existing.contains(&definition.code)) {
+            // Provisioned by another instance, or at startup under a different list
+            self.registry.register(&self.repo.find_all(Some(&currency)).await?);
+        } else {
+            tracing::info!("Provisioning system accounts for first use of {}", currency);
+            self.provision(&definitions, ProvisioningTrigger::FirstUse).await?;
+        }
+
+        if let Ok(mut provisioned) = self.provisioned.write() {
+            provisioned.insert(currency);
+        }
+        Ok(())
+    }
+
+    fn is_provisioned(&self, currency: &str) -> bool {
+        self.provisioned
+            .read()
+            .map(|provisioned| provisioned.contains(currency))
+            .unwrap_or(false)
+    }
+
+    /// Returns the account backing a new entry and whether it was created for it.
+    async fn backing_account(&self, definition: &InternalAccountDefinition) -> Result<(Uuid, bool)> {
+        let external_id = definition.external_id();
+        if let Some(account) = self.account_repo.find_by_external_id(&external_id).await? {
+            return Ok((account.id, false));
+        }
+
+        let created = self
+            .accounts
+            .create_account(CreateAccountRequest {
+                external_id: external_id.clone(),
+                name: definition.name.clone(),
+                account_type: definition.account_type,
+                currency: definition.currency.clone(),
+                initial_balance: None,
+                metadata: Some(serde_json::json!({
+                    "internal_account": definition.code,
+                    "role": definition.role,
+                })),
+            })
+            .await;
+        match created {
+            Ok(account) => Ok((account.id, true)),
+            // Another instance created it first
+            Err(e) => match self.account_repo.find_by_external_id(&external_id).await? {
+                Some(account) => Ok((account.id, false)),
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Lists the chart, optionally in one currency.
+    pub async fn list(&self, currency: Option<&str>) -> Result<Vec<InternalAccount>> {
+        self.repo.find_all(currency.map(str::to_uppercase).as_deref()).await
+    }
+
+    /// Gets the entry with the code in the currency.
+    pub async fn get(&self, code: &str, currency: &str) -> Result<InternalAccount> {
+        let code = code.to_uppercase();
+        let currency = currency.to_uppercase();
+        self.repo.find(&code, &currency).await?.ok_or_else(|| {
+            AppError::NotFound(format!("Internal account '{}' not found in {}", code, currency))
+        })
+    }
+
+    /// Returns the account playing the role in the currency, if the chart has one.
+    pub async fn role_account(&self, role: InternalAccountRole, currency: &str) -> Result<Option<Uuid>> {
+        Ok(self
+            .repo
+            .find_by_role(role, &currency.to_uppercase())
+            .await?
+            .map(|entry| entry.account_id))
+    }
+
+    /// Lists the provisioning audit trail, optionally in one currency, newest first.
+    pub async fn provisioning_history(
+        &self,
+        currency: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<InternalAccountProvisioning>> {
+        self.repo
+            .find_provisioning(currency.map(str::to_uppercase).as_deref(), limit)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_registry_is_shared_between_clones() {
+        let registry = ChartRegistry::default();
+        let holder = registry.clone();
+        let account_id = Uuid::new_v4();
+        registry.register(&[InternalAccount {
+            code: "SUSPENSE".to_string(),
+            currency: "JPY".to_string(),
+            name: "Suspense".to_string(),
+            role: Some(InternalAccountRole::Suspense),
+            parent_code: None,
+            account_id,
+            control: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }]);
+
+        assert_eq!(holder.role_account(InternalAccountRole::Suspense, "JPY"), Some(account_id));
+        assert_eq!(holder.role_account(InternalAccountRole::Suspense, "USD"), None);
+        assert_eq!(holder.role_account(InternalAccountRole::FeeRevenue, "JPY"), None);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::error::{AppError, Result};
+use crate::models::{CounterpartyListMode, CounterpartyRestriction, CounterpartyRestrictionAudit};
+use crate::repositories::{AccountRepository, CounterpartyRepository};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Manages per-account counterparty allow- and denylists and checks transactions
+/// against them. Restrictions apply in both directions: an account that may not deal
+/// with a counterparty can neither pay it nor be paid by it.
+pub struct CounterpartyService {
+    restriction_repo: CounterpartyRepository,
+    account_repo: AccountRepository,
+}
+
+impl CounterpartyService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            restriction_repo: CounterpartyRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool),
+        }
+    }
+
+    /// Adds or replaces the restriction on a counterparty for an account.
+    pub async fn add_restriction(
+        &self,
+        account_id: Uuid,
+        counterparty_id: Uuid,
+        mode: CounterpartyListMode,
+        reason: Option<String>,
+    ) -> Result<CounterpartyRestriction> {
+        if account_id == counterparty_id {
+            return Err(AppError::Validation(
+                "An account cannot restrict itself as a counterparty".to_string(),
+            ));
+        }
+        self.ensure_account(account_id).await?;
+
+        let mut restriction = CounterpartyRestriction::new(account_id, counterparty_id, mode);
+        if let Some(reason) = reason {
+            restriction = restriction.with_reason(reason);
+        }
+
+        self.restriction_repo.upsert(&restriction).await
+    }
+
+    /// Removes the restriction on a counterparty for an account.
+    pub async fn remove_restriction(
+        &self,
+        account_id: Uuid,
+        counterparty_id: Uuid,
+    ) -> Result<CounterpartyRestriction> {
+        self.restriction_repo
+            .delete(account_id, counterparty_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "No restriction on counterparty '{}' for account '{}'",
+                    counterparty_id, account_id
+                ))
+            })
+    }
+
+    /// Lists an account's restrictions.
+    pub async fn list_restrictions(&self, account_id: Uuid) -> Result<Vec<CounterpartyRestriction>> {
+        self.ensure_account(account_id).await?;
+        self.restriction_repo.find_by_account(account_id).await
+    }
+
+    /// Gets the audit log of restriction changes for an account, oldest first.
+    pub async fn get_audit_log(&self, account_id: Uuid) -> Result<Vec<CounterpartyRestrictionAudit>> {
+        self.ensure_account(account_id).await?;
+        self.restriction_repo.find_audit_by_account(account_id).await
+    }
+
+    /// Checks whether two accounts may transact. Returns a description of the violated
+    /// restriction, or `None` if the pair is permitted.
+    pub async fn check_pair(&self, source_account_id: Uuid, destination_account_id: Uuid) -> Result<Option<String>> {
+        let source_restrictions = self.restriction_repo.find_by_account(source_account_id).await?;
+        if !CounterpartyRestriction::permits(&source_restrictions, destination_account_id) {
+            return Ok(Some(format!(
+                "Account '{}' is not permitted to transact with counterparty '{}'",
+                source_account_id, destination_account_id
+            )));
+        }
+
+        let destination_restrictions = self.restriction_repo.find_by_account(destination_account_id).await?;
+        if !CounterpartyRestriction::permits(&destination_restrictions, source_account_id) {
+            return Ok(Some(format!(
+                "Account '{}' is not permitted to transact with counterparty '{}'",
+                destination_account_id, source_account_id
+            )));
+        }
+
+        Ok(None)
+    }
+
+    async fn ensure_account(&self, account_id: Uuid) -> Result<()> {
+        self.account_repo
+            .find_by_id(account_id)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound(format!("Account with id '{}' not found", account_id)))
+    }
+}
@@ -0,0 +1,317 @@
+use crate::core::rounding::RoundingPolicy;
+use crate::error::{AppError, Result};
+use crate::models::{
+    BatchStatus, DefaultResolution, LossAllocation, LossAllocationBasis, NettingPosition, ParticipantDefault,
+    StatusChangeReason, StatusReasonCode,
+};
+use crate::netting::default_management::{
+    allocate_loss, apply_allocations, exclude_obligations, exposure_weights, net_credit_weights,
+};
+use crate::netting::optimizer::Transfer;
+use crate::repositories::{BatchRepository, NettingRepository, ParticipantDefaultRepository, TransactionRepository};
+use crate::services::{AccountService, InstructionType, NettingConfig, NettingService, SettlementInstruction};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Default management rules applied when a participant fails to fund its net obligation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultManagementConfig {
+    /// Resolution used when the declaration does not choose one.
+    pub resolution: DefaultResolution,
+    /// How loss sharing splits the unfunded debit.
+    pub loss_allocation: LossAllocationBasis,
+}
+
+/// What a participant's default did to its batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultReport {
+    pub batch_id: Uuid,
+    pub participant_id: Uuid,
+    pub currency: String,
+    pub resolution: DefaultResolution,
+    /// Basis the unfunded debit was shared on, if any was shared.
+    pub loss_allocation: Option<LossAllocationBasis>,
+    /// Net debit the participant failed to fund.
+    pub unfunded_amount: Decimal,
+    /// Transactions with the defaulter taken out of the netting.
+    pub excluded_transactions: Vec<Uuid>,
+    /// Shares of the unfunded debit borne by the surviving participants.
+    pub allocations: Vec<LossAllocation>,
+    pub original_positions: Vec<NettingPosition>,
+    pub adjusted_positions: Vec<NettingPosition>,
+    /// Instructions settling the adjusted positions, replacing the batch's originals.
+    pub replacement_instructions: Vec<SettlementInstruction>,
+    pub reason: Option<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Takes participants that fail to fund their net obligations out of completed batches,
+/// CCP style: the participant is declared in default and frozen, the batch's positions
+/// are rewritten without it and replacement instructions are generated from them.
+pub struct DefaultManagementService {
+    pool: PgPool,
+    repo: ParticipantDefaultRepository,
+    batch_repo: BatchRepository,
+    netting_repo: NettingRepository,
+    transaction_repo: TransactionRepository,
+    account_service: AccountService,
+    config: DefaultManagementConfig,
+    rounding: Arc<RoundingPolicy>,
+    netting: NettingConfig,
+}
+
+impl DefaultManagementService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: ParticipantDefaultRepository::new(pool.clone()),
+            batch_repo: BatchRepository::new(pool.clone()),
+            netting_repo: NettingRepository::new(pool.clone()),
+            transaction_repo: TransactionRepository::new(pool.clone()),
+            account_service: AccountService::new(pool.clone()),
+            pool,
+            config: DefaultManagementConfig::default(),
+            rounding: Arc::new(RoundingPolicy::default()),
+            netting: NettingConfig::default(),
+        }
+    }
+
+    /// Sets the default resolution and loss allocation rules.
+    pub fn with_config(mut self, config: DefaultManagementConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets how loss shares are rounded and where their remainder goes.
+    pub fn with_rounding(mut self, rounding: Arc<RoundingPolicy>) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Sets the netting configuration replacement instructions are generated under.
+    pub fn with_netting(mut self, config: NettingConfig) -> Self {
+        self.netting = config;
+        self
+    }
+
+    /// Declares a net payer of a completed batch in default.
+    ///
+    /// With `Exclude` the defaulter's transactions are taken out of the netting; anything
+    /// left on its position by an earlier loss sharing is shared by net credit. With
+    /// `LossSharing` every transaction stands and the unfunded debit is shared on the
+    /// configured basis. The adjusted positions replace the batch's stored ones, so
+    /// instruction exports pay out the replacement instructions. The participant's
+    /// account is frozen.
+    pub async fn declare_default(
+        &self,
+        batch_id: Uuid,
+        participant_id: Uuid,
+        resolution: Option<DefaultResolution>,
+        reason: Option<String>,
+    ) -> Result<DefaultReport> {
+        let resolution = resolution.unwrap_or(self.config.resolution);
+        let batch = self
+            .batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch {} not found", batch_id)))?;
+
+        if batch.status != BatchStatus::Completed {
+            return Err(AppError::Validation(format!(
+                "Batch {} is {:?}; only completed batches have obligations to default on",
+                batch_id, batch.status
+            )));
+        }
+        if self.repo.find(batch_id, participant_id).await?.is_some() {
+            return Err(AppError::Validation(format!(
+                "Participant {} is already in default in batch {}",
+                participant_id, batch_id
+            )));
+        }
+
+        let netting = NettingService::new(self.pool.clone()).with_config(self.netting.clone());
+        let netting_report = netting.batch_report(batch_id, &batch.currency).await?;
+        // Gross settled transactions never entered the positions
+        let gross: HashSet<Uuid> = netting_report
+            .excluded_transactions
+            .iter()
+            .map(|tx| tx.transaction_id)
+            .collect();
+        let transactions: Vec<_> = self
+            .transaction_repo
+            .find_by_batch(batch_id)
+            .await?
+            .into_iter()
+            .filter(|tx| !gross.contains(&tx.id))
+            .collect();
+        let mut positions = self.netting_repo.find_by_batch(batch_id).await?;
+        if positions.is_empty() {
+            positions = netting_report
+                .multilateral_result
+                .as_ref()
+                .map(|result| result.positions.clone())
+                .unwrap_or_default();
+        }
+
+        let unfunded_amount = match positions.iter().find(|p| p.participant_id == participant_id) {
+            Some(position) if position.is_net_payer() => position.absolute_net(),
+            _ => {
+                return Err(AppError::Validation(format!(
+                    "Participant {} has no net obligation in batch {}",
+                    participant_id, batch_id
+                )))
+            }
+        };
+
+        // Obligations with earlier defaulters are already out of the positions
+        let defaulted: HashSet<Uuid> = self
+            .repo
+            .find_by_batch(batch_id)
+            .await?
+            .into_iter()
+            .map(|d| d.participant_id)
+            .collect();
+        let (excluded_transactions, obligations): (Vec<Uuid>, Vec<Transfer>) = transactions
+            .iter()
+            .filter(|tx| {
+                (tx.source_account_id == participant_id && !defaulted.contains(&tx.destination_account_id))
+                    || (tx.destination_account_id == participant_id && !defaulted.contains(&tx.source_account_id))
+            })
+            .map(|tx| {
+                (
+                    tx.id,
+                    Transfer {
+                        from: tx.source_account_id,
+                        to: tx.destination_account_id,
+                        amount: tx.amount,
+                    },
+                )
+            })
+            .unzip();
+
+        let original_positions = positions.clone();
+
+        let (loss_allocation, excluded_transactions, weights, loss) = match resolution {
+            DefaultResolution::Exclude => {
+                exclude_obligations(&mut positions, participant_id, &obligations);
+                let residual = positions
+                    .iter()
+                    .find(|p| p.participant_id == participant_id)
+                    .map(|p| -p.net_position)
+                    .unwrap_or(Decimal::ZERO);
+                let basis = (!residual.is_zero()).then_some(LossAllocationBasis::NetCredit);
+                (basis, excluded_transactions, net_credit_weights(&positions, participant_id), residual)
+            }
+            DefaultResolution::LossSharing => {
+                let mut basis = self.config.loss_allocation;
+                let mut weights = match basis {
+                    LossAllocationBasis::NetCredit => Vec::new(),
+                    LossAllocationBasis::Exposure => exposure_weights(&obligations, participant_id),
+                };
+                if weights.is_empty() {
+                    basis = LossAllocationBasis::NetCredit;
+                    weights = net_credit_weights(&positions, participant_id);
+                }
+                (Some(basis), Vec::new(), weights, unfunded_amount)
+            }
+        };
+
+        let allocations = if loss.is_zero() {
+            Vec::new()
+        } else {
+            let allocations = allocate_loss(loss, &weights, &batch.currency, &self.rounding);
+            if allocations.is_empty() {
+                return Err(AppError::Validation(format!(
+                    "No surviving participant in batch {} can share the loss of {}",
+                    batch_id, participant_id
+                )));
+            }
+            allocations
+        };
+        apply_allocations(&mut positions, participant_id, &allocations);
+
+        let mut replacement_instructions = netting.batch_instructions(&batch, &positions).await?;
+        // Gross items still settle on their own, except those of defaulters
+        replacement_instructions.extend(netting_report.instructions().into_iter().filter(|i| {
+            i.instruction_type == InstructionType::Gross
+                && [i.from_participant, i.to_participant]
+                    .iter()
+                    .all(|p| *p != participant_id && !defaulted.contains(p))
+        }));
+        let report = DefaultReport {
+            batch_id,
+            participant_id,
+            currency: batch.currency.clone(),
+            resolution,
+            loss_allocation,
+            unfunded_amount,
+            excluded_transactions,
+            allocations,
+            original_positions,
+            adjusted_positions: positions,
+            replacement_instructions,
+            reason: reason.clone(),
+            generated_at: Utc::now(),
+        };
+
+        let record = ParticipantDefault {
+            id: Uuid::new_v4(),
+            batch_id,
+            participant_id,
+            currency: batch.currency.clone(),
+            unfunded_amount,
+            resolution,
+            reason,
+            report: serde_json::to_value(&report)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize default report: {}", e)))?,
+            declared_at: report.generated_at,
+        };
+        self.repo.create(&record, &report.adjusted_positions).await?;
+
+        let freeze_reason = StatusChangeReason::new(StatusReasonCode::ParticipantDefault).with_note(format!(
+            "Failed to fund net obligation of {} {} in batch {}",
+            unfunded_amount, batch.currency, batch_id
+        ));
+        match self.account_service.freeze_account(participant_id, freeze_reason).await {
+            Ok(_) => {}
+            // Already frozen or closed
+            Err(AppError::Validation(message)) => {
+                warn!(account_id = %participant_id, "Defaulted participant was not frozen: {}", message)
+            }
+            Err(e) => return Err(e),
+        }
+
+        error!(
+            batch_id = %batch_id,
+            participant_id = %participant_id,
+            currency = %batch.currency,
+            unfunded = %unfunded_amount,
+            resolution = ?resolution,
+            "Participant declared in default"
+        );
+        Ok(report)
+    }
+
+    /// Lists the defaults declared in a batch, oldest first.
+    pub async fn batch_defaults(&self, batch_id: Uuid) -> Result<Vec<ParticipantDefault>> {
+        self.repo.find_by_batch(batch_id).await
+    }
+
+    /// Gets the stored report of a participant's default in a batch.
+    pub async fn default_report(&self, batch_id: Uuid, participant_id: Uuid) -> Result<DefaultReport> {
+        let record = self.repo.find(batch_id, participant_id).await?.ok_or_else(|| {
+            AppError::NotFound(format!(
+                "Participant {} is not in default in batch {}",
+                participant_id, batch_id
+            ))
+        })?;
+
+        serde_json::from_value(record.report)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Stored default report is invalid: {}", e)))
+    }
+}
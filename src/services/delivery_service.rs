@@ -0,0 +1,215 @@
+use crate::delivery::DeliveryChannels;
+use crate::error::{AppError, Result};
+use crate::models::{DeliveryStatus, FileDelivery};
+use crate::repositories::FileDeliveryRepository;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Deliveries claimed per scheduler run.
+const DELIVERY_BATCH_SIZE: i64 = 20;
+
+/// Queues generated files for delivery to configured destinations and uploads them,
+/// retrying failed attempts with exponential backoff.
+pub struct DeliveryService {
+    delivery_repo: FileDeliveryRepository,
+    channels: Option<Arc<DeliveryChannels>>,
+    stale_after: Duration,
+}
+
+impl DeliveryService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            delivery_repo: FileDeliveryRepository::new(pool),
+            channels: None,
+            stale_after: Duration::minutes(10),
+        }
+    }
+
+    /// Enables delivery. Without channels `enqueue` is rejected.
+    pub fn with_channels(mut self, channels: Arc<DeliveryChannels>) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// Sets how long a delivery may stay claimed before it is assumed abandoned.
+    pub fn with_stale_after(mut self, stale_after: Duration) -> Self {
+        self.stale_after = stale_after;
+        self
+    }
+
+    fn channels(&self) -> Result<&Arc<DeliveryChannels>> {
+        self.channels
+            .as_ref()
+            .ok_or_else(|| AppError::Validation("File delivery is not configured".to_string()))
+    }
+
+    /// Queues a file for delivery, optionally not before `scheduled_at`.
+    pub async fn enqueue(
+        &self,
+        destination: &str,
+        file_name: &str,
+        content: Vec<u8>,
+        scheduled_at: Option<DateTime<Utc>>,
+    ) -> Result<FileDelivery> {
+        let channels = self.channels()?;
+        if channels.get(destination).is_none() {
+            return Err(AppError::Validation(format!(
+                "Unknown delivery destination '{}'; configured destinations: {}",
+                destination,
+                channels.names().join(", ")
+            )));
+        }
+        if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+            return Err(AppError::Validation(format!("Invalid file name '{}'", file_name)));
+        }
+
+        let mut delivery = FileDelivery::new(destination, file_name, content, channels.max_attempts);
+        if let Some(at) = scheduled_at {
+            delivery = delivery.scheduled_at(at);
+        }
+        self.delivery_repo.create(&delivery).await
+    }
+
+    /// Gets a delivery by ID.
+    pub async fn get_delivery(&self, id: Uuid) -> Result<FileDelivery> {
+        self.delivery_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Delivery {} not found", id)))
+    }
+
+    /// Lists deliveries, newest first.
+    pub async fn list_deliveries(
+        &self,
+        status: Option<DeliveryStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FileDelivery>> {
+        self.delivery_repo.list(status, limit, offset).await
+    }
+
+    /// Requeues a failed delivery for another round of attempts.
+    pub async fn retry_delivery(&self, id: Uuid) -> Result<FileDelivery> {
+        let delivery = self.get_delivery(id).await?;
+        if delivery.status != DeliveryStatus::Failed {
+            return Err(AppError::Validation(format!(
+                "Delivery {} is {:?}; only failed deliveries can be retried",
+                id, delivery.status
+            )));
+        }
+
+        self.delivery_repo.requeue(id).await?.ok_or_else(|| {
+            AppError::SerializationConflict(format!("Delivery {} changed status concurrently", id))
+        })
+    }
+
+    /// Attempts every due delivery, returning how many were delivered.
+    pub async fn process_due(&self) -> Result<usize> {
+        let channels = self.channels()?;
+        let released = self
+            .delivery_repo
+            .release_stale(Utc::now() - self.stale_after)
+            .await?;
+        if released > 0 {
+            warn!("Requeued {} abandoned deliveries", released);
+        }
+
+        let mut delivered = 0;
+        for mut delivery in self.delivery_repo.claim_due(DELIVERY_BATCH_SIZE).await? {
+            match self.attempt(channels, &delivery).await {
+                Ok(()) => {
+                    delivery.mark_delivered();
+                    delivered += 1;
+                    info!(
+                        delivery_id = %delivery.id,
+                        destination = %delivery.destination,
+                        "Delivered {}",
+                        delivery.file_name
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        delivery_id = %delivery.id,
+                        destination = %delivery.destination,
+                        attempt = delivery.attempts,
+                        "Delivery of {} failed: {}",
+                        delivery.file_name,
+                        e
+                    );
+                    delivery.record_failure(e.to_string(), channels.retry_backoff);
+                }
+            }
+            self.delivery_repo.update(&delivery).await?;
+        }
+
+        Ok(delivered)
+    }
+
+    async fn attempt(&self, channels: &DeliveryChannels, delivery: &FileDelivery) -> Result<()> {
+        let transport = channels.get(&delivery.destination).ok_or_else(|| {
+            AppError::Validation(format!(
+                "Delivery destination '{}' is no longer configured",
+                delivery.destination
+            ))
+        })?;
+        if !delivery.verify_content() {
+            return Err(AppError::Validation(
+                "Stored content does not match its checksum".to_string(),
+            ));
+        }
+
+        transport
+            .deliver(&delivery.file_name, &delivery.content, &delivery.checksum_sha256)
+            .await
+    }
+}
+
+/// Periodically attempts due deliveries in the background.
+pub struct DeliveryScheduler {
+    service: Arc<DeliveryService>,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+}
+
+impl DeliveryScheduler {
+    pub fn new(service: Arc<DeliveryService>, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+        }
+    }
+
+    /// Starts the scheduler in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if let Err(e) = service.process_due().await {
+                    tracing::error!("Delivery scheduler error: {}", e);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the scheduler.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the scheduler is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
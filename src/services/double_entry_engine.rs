@@ -1,5 +1,9 @@
 use crate::error::{AppError, Result};
-use crate::models::{AccountBalance, AccountType, EntryType, LedgerEntry, TransactionRecord, TransactionStatus, TransactionType};
+use crate::events::enqueue_settled_event;
+use crate::models::{
+    AccountBalance, AccountType, EntryType, ExternalIdClaim, LedgerEntry, SettlementRoute,
+    TransactionRecord, TransactionStatus, TransactionType,
+};
 use crate::repositories::{AccountRepository, BalanceRepository, LedgerRepository, TransactionRepository};
 use chrono::{NaiveDate, Utc};
 use rust_decimal::Decimal;
@@ -30,6 +34,17 @@ pub struct TransactionRequest {
     pub idempotency_key: String,
     pub effective_date: Option<NaiveDate>,
     pub metadata: Option<serde_json::Value>,
+    /// Scoped key the transaction holds on its external ID; by default the external
+    /// ID itself, unique across all transactions.
+    pub external_id_claim: Option<ExternalIdClaim>,
+    /// Registered custom type the transaction posts as, if any.
+    pub type_code: Option<String>,
+    /// Settles the transaction gross rather than netting it with its batch.
+    pub exclude_from_netting: bool,
+    /// Description for the transaction and its ledger entries.
+    pub narrative: Option<String>,
+    /// Counterparty's reference, e.g. an invoice number.
+    pub reference: Option<String>,
 }
 
 /// Request to reverse a transaction.
@@ -67,6 +82,17 @@ impl DoubleEntryEngine {
     pub async fn execute_transaction(
         &self,
         request: TransactionRequest,
+    ) -> Result<TransactionResult> {
+        self.execute_transaction_with_route(request, SettlementRoute::Netted)
+            .await
+    }
+
+    /// Executes a double-entry transaction atomically, recording the given settlement route
+    /// on the transaction.
+    pub async fn execute_transaction_with_route(
+        &self,
+        request: TransactionRequest,
+        route: SettlementRoute,
     ) -> Result<TransactionResult> {
         // Validate request
         self.validate_transaction_request(&request)?;
@@ -104,16 +130,20 @@ impl DoubleEntryEngine {
             })?;
 
         if !source_account.status.is_operational() {
-            return Err(AppError::Validation(format!(
-                "Source account '{}' is not operational",
-                request.source_account_id
+            let change = self.account_repo.latest_status_change(source_account.id).await?;
+            return Err(AppError::AccountFrozen(format!(
+                "Source account '{}' is not operational ({})",
+                request.source_account_id,
+                source_account.status_description(change.as_ref())
             )));
         }
 
         if !dest_account.status.is_operational() {
-            return Err(AppError::Validation(format!(
-                "Destination account '{}' is not operational",
-                request.destination_account_id
+            let change = self.account_repo.latest_status_change(dest_account.id).await?;
+            return Err(AppError::AccountFrozen(format!(
+                "Destination account '{}' is not operational ({})",
+                request.destination_account_id,
+                dest_account.status_description(change.as_ref())
             )));
         }
 
@@ -130,7 +160,7 @@ impl DoubleEntryEngine {
 
         // Check sufficient funds for source account
         if !source_balance.has_sufficient_funds(request.amount) {
-            return Err(AppError::Validation(format!(
+            return Err(AppError::InsufficientFunds(format!(
                 "Insufficient funds: requested {}, available {}",
                 request.amount,
                 source_balance.usable_balance()
@@ -158,17 +188,24 @@ impl DoubleEntryEngine {
             currency.clone(),
             request.fee_amount,
             request.idempotency_key,
-        );
+        )
+        .with_route(route)
+        .with_type_code(request.type_code)
+        .excluded_from_netting(request.exclude_from_netting)
+        .with_narrative(request.narrative, request.reference);
 
         if let Some(metadata) = request.metadata {
             transaction = transaction.with_metadata(metadata);
         }
+        if let Some(claim) = request.external_id_claim {
+            transaction = transaction.with_external_id_claim(claim);
+        }
 
         let transaction = sqlx::query_as::<_, TransactionRecord>(
             r#"
-            INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
+            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             "#,
         )
         .bind(transaction.id)
@@ -186,9 +223,19 @@ impl DoubleEntryEngine {
         .bind(&transaction.metadata)
         .bind(transaction.created_at)
         .bind(transaction.settled_at)
+        .bind(transaction.settlement_route)
+        .bind(transaction.priority)
+        .bind(&transaction.source_system)
+        .bind(transaction.resubmission_of)
+        .bind(&transaction.dedupe_key)
+        .bind(&transaction.type_code)
+        .bind(transaction.exclude_from_netting)
+        .bind(&transaction.narrative)
+        .bind(&transaction.reference)
+        .bind(transaction.link_id)
         .fetch_one(&mut *tx)
         .await
-        .map_err(AppError::Database)?;
+        .map_err(TransactionRepository::map_insert_error)?;
 
         // Debit source account
         let updated_source = sqlx::query_as::<_, AccountBalance>(
@@ -208,7 +255,7 @@ impl DoubleEntryEngine {
         .fetch_optional(&mut *tx)
         .await
         .map_err(AppError::Database)?
-        .ok_or_else(|| AppError::Validation("Insufficient funds during transaction".to_string()))?;
+        .ok_or_else(|| AppError::InsufficientFunds("Insufficient funds during transaction".to_string()))?;
 
         // Credit destination account
         let updated_dest = sqlx::query_as::<_, AccountBalance>(
@@ -236,7 +283,8 @@ impl DoubleEntryEngine {
             currency.clone(),
             updated_source.available_balance,
             effective_date,
-        );
+        )
+        .with_narrative(transaction.narrative.clone(), transaction.reference.clone());
 
         let credit_entry = LedgerEntry::credit(
             transaction.id,
@@ -245,14 +293,15 @@ impl DoubleEntryEngine {
             currency.clone(),
             updated_dest.available_balance,
             effective_date,
-        );
+        )
+        .with_narrative(transaction.narrative.clone(), transaction.reference.clone());
 
         // Insert debit entry
         let debit_entry = sqlx::query_as::<_, LedgerEntry>(
             r#"
-            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
             "#,
         )
         .bind(debit_entry.id)
@@ -265,6 +314,8 @@ impl DoubleEntryEngine {
         .bind(debit_entry.effective_date)
         .bind(&debit_entry.metadata)
         .bind(debit_entry.created_at)
+        .bind(&debit_entry.narrative)
+        .bind(&debit_entry.reference)
         .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
@@ -272,9 +323,9 @@ impl DoubleEntryEngine {
         // Insert credit entry
         let credit_entry = sqlx::query_as::<_, LedgerEntry>(
             r#"
-            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
+            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
             "#,
         )
         .bind(credit_entry.id)
@@ -287,6 +338,8 @@ impl DoubleEntryEngine {
         .bind(credit_entry.effective_date)
         .bind(&credit_entry.metadata)
         .bind(credit_entry.created_at)
+        .bind(&credit_entry.narrative)
+        .bind(&credit_entry.reference)
         .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
@@ -297,13 +350,14 @@ impl DoubleEntryEngine {
             UPDATE transactions
             SET status = 'SETTLED', settled_at = NOW()
             WHERE id = $1
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
+            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
             "#,
         )
         .bind(transaction.id)
         .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
+        enqueue_settled_event(&mut tx, &transaction).await?;
 
         // Commit the transaction
         tx.commit().await.map_err(AppError::Database)?;
@@ -383,10 +437,17 @@ impl DoubleEntryEngine {
             idempotency_key: request.idempotency_key,
             effective_date: None,
             metadata: Some(metadata),
+            external_id_claim: None,
+            type_code: None,
+            exclude_from_netting: false,
+            narrative: Some(original.derived_narrative("Reversal of")),
+            reference: original.reference.clone(),
         };
 
-        // Execute the reversal
-        let result = self.execute_transaction(reversal_request).await?;
+        // Execute the reversal on the same route as the original
+        let result = self
+            .execute_transaction_with_route(reversal_request, original.settlement_route)
+            .await?;
 
         // Mark original as reversed
         self.transaction_repo
@@ -521,6 +582,11 @@ mod tests {
             idempotency_key: "IDEM-001".to_string(),
             effective_date: None,
             metadata: None,
+            external_id_claim: None,
+            type_code: None,
+            exclude_from_netting: false,
+            narrative: None,
+            reference: None,
         };
 
         assert_eq!(request.net_amount(), Decimal::from(95));
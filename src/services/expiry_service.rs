@@ -0,0 +1,249 @@
+use crate::core::leader::LeaderElection;
+use crate::error::{AppError, Result};
+use crate::events::{enqueue_event, EventEnvelope, EventType, TransactionEvent};
+use crate::models::{TransactionAuditAction, TransactionAuditEntry};
+use crate::observability::get_metrics;
+use crate::repositories::{
+    BalanceReservationRepository, SubmissionRepository, TransactionAuditRepository, TransactionHoldRepository, TransactionRepository,
+};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::info;
+
+/// How long transactions of each type may stay pending before they expire.
+/// A TTL of zero keeps them pending indefinitely.
+#[derive(Debug, Clone, Default)]
+pub struct ExpiryPolicy {
+    /// TTL in seconds for types without their own.
+    pub default_ttl_secs: i64,
+    /// TTL in seconds per transaction type name, e.g. `PAYMENT`.
+    pub ttl_secs: HashMap<String, i64>,
+}
+
+impl ExpiryPolicy {
+    /// Returns the TTL in seconds for a transaction type name.
+    pub fn ttl_for(&self, transaction_type: &str) -> i64 {
+        self.ttl_secs.get(transaction_type).copied().unwrap_or(self.default_ttl_secs)
+    }
+
+    /// Returns true if no transaction type can expire.
+    pub fn is_disabled(&self) -> bool {
+        self.default_ttl_secs <= 0 && self.ttl_secs.values().all(|ttl| *ttl <= 0)
+    }
+
+    fn columns(&self) -> (Vec<String>, Vec<i64>) {
+        self.ttl_secs.iter().map(|(name, ttl)| (name.clone(), *ttl)).unzip()
+    }
+}
+
+/// Outcome of one expiry sweep, counted per transaction type.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExpirySweep {
+    pub transactions: BTreeMap<String, u64>,
+    pub submissions: BTreeMap<String, u64>,
+    /// Balance reservations released because their expiry passed.
+    pub reservations: u64,
+}
+
+impl ExpirySweep {
+    pub fn total(&self) -> u64 {
+        self.transactions.values().sum::<u64>() + self.submissions.values().sum::<u64>() + self.reservations
+    }
+}
+
+/// Expires transactions that have stayed pending past their type's TTL, queued
+/// submissions no worker picked up in time, and balance reservations past their expiry.
+///
+/// An expired transaction's hold is released back to the source account, an `Expired`
+/// audit entry is recorded and a transaction-expired event is queued in the outbox, all
+/// in the database transaction that expires it.
+pub struct ExpiryService {
+    pool: PgPool,
+    submission_repo: SubmissionRepository,
+    policy: ExpiryPolicy,
+    batch_size: i64,
+}
+
+impl ExpiryService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            submission_repo: SubmissionRepository::new(pool.clone()),
+            pool,
+            policy: ExpiryPolicy::default(),
+            batch_size: 500,
+        }
+    }
+
+    pub fn with_policy(mut self, policy: ExpiryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets how many transactions and how many submissions one sweep expires at most.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Expires one batch of stale pending transactions, one of stale queued submissions
+    /// and one of reservations past their expiry. Reservations carry their own expiry, so
+    /// they expire even when the policy expires no transaction type.
+    pub async fn expire_due(&self) -> Result<ExpirySweep> {
+        let mut sweep = ExpirySweep::default();
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let reservations = BalanceReservationRepository::expire_due_in(&mut tx, self.batch_size).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        sweep.reservations = reservations.len() as u64;
+        if sweep.reservations > 0 {
+            get_metrics().record_expired("reservation", "RESERVATION", sweep.reservations);
+            info!("Expired {} balance reservations", sweep.reservations);
+        }
+
+        if self.policy.is_disabled() {
+            return Ok(sweep);
+        }
+        let (types, ttls) = self.policy.columns();
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let due = TransactionRepository::find_expirable_in(
+            &mut tx,
+            &types,
+            &ttls,
+            self.policy.default_ttl_secs,
+            self.batch_size,
+        )
+        .await?;
+        for transaction in due {
+            let expired = TransactionRepository::mark_expired_in(&mut tx, transaction.id).await?;
+            let hold = TransactionHoldRepository::release_in(&mut tx, expired.id).await?;
+            let type_name = expired.transaction_type.as_str();
+
+            let detail = serde_json::json!({
+                "ttl_secs": self.policy.ttl_for(type_name),
+                "released_amount": hold.as_ref().map(|hold| hold.amount),
+            });
+            TransactionAuditRepository::record_in(
+                &mut tx,
+                &TransactionAuditEntry::new(expired.id, TransactionAuditAction::Expired, detail),
+            )
+            .await?;
+
+            let envelope = EventEnvelope::new(EventType::TransactionExpired, TransactionEvent::from_record(&expired));
+            enqueue_event(&mut tx, TransactionEvent::topic(), Some(expired.id.to_string()), &envelope).await?;
+
+            *sweep.transactions.entry(type_name.to_string()).or_default() += 1;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+
+        let submissions = self
+            .submission_repo
+            .expire_queued(&types, &ttls, self.policy.default_ttl_secs, self.batch_size)
+            .await?;
+        for submission in submissions {
+            let type_name = submission.request["transaction_type"].as_str().unwrap_or("UNKNOWN");
+            *sweep.submissions.entry(type_name.to_string()).or_default() += 1;
+        }
+
+        let metrics = get_metrics();
+        for (transaction_type, count) in &sweep.transactions {
+            metrics.record_expired("transaction", transaction_type, *count);
+        }
+        for (transaction_type, count) in &sweep.submissions {
+            metrics.record_expired("submission", transaction_type, *count);
+        }
+        if sweep.total() > sweep.reservations {
+            info!(
+                "Expired {} pending transactions and {} queued submissions",
+                sweep.transactions.values().sum::<u64>(),
+                sweep.submissions.values().sum::<u64>()
+            );
+        }
+
+        Ok(sweep)
+    }
+}
+
+/// Periodically expires stale pending transactions and queued submissions.
+pub struct ExpiryJob {
+    service: Arc<ExpiryService>,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl ExpiryJob {
+    pub fn new(service: Arc<ExpiryService>, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+            leader: None,
+        }
+    }
+
+    /// Only runs while this instance is the elected leader.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+        let leader = self.leader.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if leader.as_ref().is_none_or(|leader| leader.is_leader()) {
+                    if let Err(e) = service.expire_due().await {
+                        tracing::error!("Expiry job error: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_ttl_lookup() {
+        let policy = ExpiryPolicy {
+            default_ttl_secs: 3600,
+            ttl_secs: HashMap::from([("TRANSFER".to_string(), 0), ("PAYMENT".to_string(), 60)]),
+        };
+        assert_eq!(policy.ttl_for("PAYMENT"), 60);
+        assert_eq!(policy.ttl_for("TRANSFER"), 0);
+        assert_eq!(policy.ttl_for("FEE"), 3600);
+        assert!(!policy.is_disabled());
+
+        let never = ExpiryPolicy {
+            default_ttl_secs: 0,
+            ttl_secs: HashMap::from([("PAYMENT".to_string(), 0)]),
+        };
+        assert!(never.is_disabled());
+        assert!(ExpiryPolicy::default().is_disabled());
+    }
+}
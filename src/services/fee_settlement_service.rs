@@ -0,0 +1,163 @@
+use crate::error::{AppError, Result};
+use crate::events::enqueue_settled_event;
+use crate::models::{BatchStatus, FeeAccrual, FeeSettlement, LedgerEntry, TransactionRecord, TransactionType};
+use crate::repositories::{
+    BalanceRepository, BatchRepository, FeeSettlementRepository, LedgerRepository, TransactionRepository,
+};
+use crate::services::FeeConfig;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Settles the fees accrued in a batch when fees are booked in the accrued mode.
+///
+/// Each payer's fees in the batch are netted against the fees reversed or refunded
+/// since, and the net moves from the fees-receivable account to the revenue account in
+/// one `Fee` transaction per payer, the payer's fee instruction. The settlements are the
+/// per-participant fee report of the batch.
+pub struct FeeSettlementService {
+    pool: PgPool,
+    settlement_repo: FeeSettlementRepository,
+    batch_repo: BatchRepository,
+    fees: Arc<FeeConfig>,
+}
+
+impl FeeSettlementService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            settlement_repo: FeeSettlementRepository::new(pool.clone()),
+            batch_repo: BatchRepository::new(pool.clone()),
+            pool,
+            fees: Arc::new(FeeConfig::default()),
+        }
+    }
+
+    /// Sets the fee accounts settlements move fees between.
+    pub fn with_fees(mut self, fees: Arc<FeeConfig>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Settles a completed batch's unsettled fees, returning one settlement per payer.
+    /// Fees reversed or refunded after an earlier settlement of the batch are picked up
+    /// by running it again. Nothing is settled in currencies whose fees do not accrue.
+    pub async fn settle_batch(&self, batch_id: Uuid) -> Result<Vec<FeeSettlement>> {
+        let batch = self
+            .batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch '{}' not found", batch_id)))?;
+        if batch.status != BatchStatus::Completed {
+            return Err(AppError::Validation(format!(
+                "Fees are settled once a batch completes (current status: {:?})",
+                batch.status
+            )));
+        }
+        let (Some(receivable_id), Some(revenue_id)) = (
+            self.fees.accrual_account(&batch.currency),
+            self.fees.revenue_account(&batch.currency),
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        FeeSettlementRepository::lock_batch_in(&mut tx, batch_id).await?;
+        let accruals = FeeSettlementRepository::find_unsettled_in(&mut tx, batch_id, receivable_id, &batch.currency).await?;
+
+        let mut by_payer: BTreeMap<Uuid, Vec<FeeAccrual>> = BTreeMap::new();
+        for accrual in accruals {
+            by_payer.entry(accrual.account_id).or_default().push(accrual);
+        }
+
+        let effective_date = Utc::now().date_naive();
+        let mut settlements = Vec::with_capacity(by_payer.len());
+        for (payer_id, accruals) in by_payer {
+            let mut settlement = FeeSettlement::from_accruals(batch_id, payer_id, batch.currency.clone(), &accruals);
+
+            if settlement.net_amount != Decimal::ZERO {
+                // A negative net gives revenue already recognized back to the receivable account
+                let (from, to) = if settlement.net_amount > Decimal::ZERO {
+                    (receivable_id, revenue_id)
+                } else {
+                    (revenue_id, receivable_id)
+                };
+                let amount = settlement.net_amount.abs();
+                let detail = serde_json::json!({
+                    "fee_settlement_id": settlement.id,
+                    "batch_id": batch_id,
+                    "payer_account_id": payer_id,
+                });
+
+                let transaction = TransactionRecord::new(
+                    format!("FEE-SET-{}", settlement.id),
+                    TransactionType::Fee,
+                    from,
+                    to,
+                    amount,
+                    batch.currency.clone(),
+                    Decimal::ZERO,
+                    format!("fee-settlement:{}", settlement.id),
+                )
+                .with_narrative(Some(format!("Fee settlement for batch {}", batch_id)), None)
+                .with_metadata(detail.clone());
+                let transaction = TransactionRepository::create_in(&mut tx, &transaction).await?;
+
+                let from_balance = BalanceRepository::adjust_in(&mut tx, from, &batch.currency, -amount).await?;
+                let to_balance = BalanceRepository::adjust_in(&mut tx, to, &batch.currency, amount).await?;
+                let debit = LedgerEntry::debit(
+                    transaction.id,
+                    from,
+                    amount,
+                    batch.currency.clone(),
+                    from_balance.available_balance,
+                    effective_date,
+                )
+                .as_fee_leg(detail.clone())
+                .with_narrative(transaction.narrative.clone(), None);
+                let credit = LedgerEntry::credit(
+                    transaction.id,
+                    to,
+                    amount,
+                    batch.currency.clone(),
+                    to_balance.available_balance,
+                    effective_date,
+                )
+                .as_fee_leg(detail)
+                .with_narrative(transaction.narrative.clone(), None);
+                LedgerRepository::create_in(&mut tx, &debit).await?;
+                LedgerRepository::create_in(&mut tx, &credit).await?;
+
+                let transaction = TransactionRepository::mark_settled_in(&mut tx, transaction.id).await?;
+                enqueue_settled_event(&mut tx, &transaction).await?;
+                settlement.transaction_id = Some(transaction.id);
+            }
+
+            let entry_ids: Vec<Uuid> = accruals.iter().map(|accrual| accrual.ledger_entry_id).collect();
+            settlements.push(FeeSettlementRepository::create_in(&mut tx, &settlement, &entry_ids).await?);
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+
+        if !settlements.is_empty() {
+            tracing::info!(
+                "Settled fees of {} payer(s) in batch {}: {} {}",
+                settlements.len(),
+                batch_id,
+                settlements.iter().map(|s| s.net_amount).sum::<Decimal>(),
+                batch.currency
+            );
+        }
+        Ok(settlements)
+    }
+
+    /// Gets the fee report of a batch: its settlements, optionally for one payer.
+    pub async fn get_fee_report(&self, batch_id: Uuid, account_id: Option<Uuid>) -> Result<Vec<FeeSettlement>> {
+        self.batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch '{}' not found", batch_id)))?;
+        self.settlement_repo.find_by_batch(batch_id, account_id).await
+    }
+}
@@ -0,0 +1,271 @@
+use crate::error::{AppError, Result};
+use crate::models::{BatchStatus, FinalityRecord};
+use crate::repositories::{BatchRepository, FinalityRepository};
+use chrono::{DateTime, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signature algorithm recorded on every attestation.
+pub const ATTESTATION_ALGORITHM: &str = "HMAC-SHA256";
+
+/// Signs finality attestations with a shared secret identified by `key_id`, so a
+/// regulator holding the same key can verify an export has not been altered.
+#[derive(Clone)]
+pub struct AttestationSigner {
+    key_id: String,
+    key: Vec<u8>,
+}
+
+impl AttestationSigner {
+    pub fn new(key_id: impl Into<String>, key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            key: key.as_ref().to_vec(),
+        }
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Returns the hex-encoded HMAC of the payload.
+    pub fn sign(&self, payload: &[u8]) -> String {
+        hex::encode(self.mac(payload).finalize().into_bytes())
+    }
+
+    /// Checks a hex-encoded signature in constant time.
+    pub fn verify(&self, payload: &[u8], signature: &str) -> bool {
+        match hex::decode(signature) {
+            Ok(bytes) => self.mac(payload).verify_slice(&bytes).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    fn mac(&self, payload: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        mac
+    }
+}
+
+impl fmt::Debug for AttestationSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AttestationSigner")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Statement of when each transaction in a completed batch reached settlement finality.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalityAttestation {
+    pub batch_id: Uuid,
+    pub currency: String,
+    pub settlement_date: NaiveDate,
+    pub batch_sequence_number: i32,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+    pub record_count: usize,
+    pub records: Vec<FinalityRecord>,
+}
+
+impl FinalityAttestation {
+    /// Bytes covered by the digest and signature: the attestation serialized as compact
+    /// JSON with fields in declaration order.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| AppError::Internal(e.into()))
+    }
+}
+
+/// A finality attestation together with its digest and signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedAttestation {
+    pub attestation: FinalityAttestation,
+    pub algorithm: String,
+    pub key_id: String,
+    /// Hex-encoded SHA-256 of the canonical attestation bytes.
+    pub digest: String,
+    pub signature: String,
+}
+
+impl SignedAttestation {
+    pub fn sign(attestation: FinalityAttestation, signer: &AttestationSigner) -> Result<Self> {
+        let payload = attestation.canonical_bytes()?;
+
+        Ok(Self {
+            digest: hex::encode(Sha256::digest(&payload)),
+            signature: signer.sign(&payload),
+            algorithm: ATTESTATION_ALGORITHM.to_string(),
+            key_id: signer.key_id().to_string(),
+            attestation,
+        })
+    }
+
+    /// Returns true if the digest and signature both match the attestation.
+    pub fn verify(&self, signer: &AttestationSigner) -> bool {
+        let Ok(payload) = self.attestation.canonical_bytes() else {
+            return false;
+        };
+
+        self.key_id == signer.key_id()
+            && self.digest == hex::encode(Sha256::digest(&payload))
+            && signer.verify(&payload, &self.signature)
+    }
+}
+
+/// Read access to settlement finality records and signed per-batch attestations.
+pub struct FinalityService {
+    finality_repo: FinalityRepository,
+    batch_repo: BatchRepository,
+    signer: Option<Arc<AttestationSigner>>,
+}
+
+impl FinalityService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            finality_repo: FinalityRepository::new(pool.clone()),
+            batch_repo: BatchRepository::new(pool),
+            signer: None,
+        }
+    }
+
+    /// Enables attestation export. Without a signer `attest_batch` is rejected.
+    pub fn with_signer(mut self, signer: Arc<AttestationSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Gets the finality record for a transaction.
+    pub async fn transaction_finality(&self, transaction_id: Uuid) -> Result<FinalityRecord> {
+        self.finality_repo
+            .find_by_transaction(transaction_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Transaction {} has not reached settlement finality",
+                    transaction_id
+                ))
+            })
+    }
+
+    /// Gets the finality records for a batch in sequence order.
+    pub async fn batch_finality(&self, batch_id: Uuid) -> Result<Vec<FinalityRecord>> {
+        self.batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch {} not found", batch_id)))?;
+
+        self.finality_repo.find_by_batch(batch_id).await
+    }
+
+    /// Builds and signs the finality attestation for a completed batch.
+    pub async fn attest_batch(&self, batch_id: Uuid) -> Result<SignedAttestation> {
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            AppError::Validation("Attestation signing key is not configured".to_string())
+        })?;
+
+        let batch = self
+            .batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch {} not found", batch_id)))?;
+
+        if batch.status != BatchStatus::Completed {
+            return Err(AppError::Validation(format!(
+                "Batch {} is {:?}; only completed batches can be attested",
+                batch_id, batch.status
+            )));
+        }
+
+        let records = self.finality_repo.find_by_batch(batch_id).await?;
+        let attestation = FinalityAttestation {
+            batch_id,
+            currency: batch.currency,
+            settlement_date: batch.settlement_date,
+            batch_sequence_number: batch.sequence_number,
+            completed_at: batch.completed_at,
+            generated_at: Utc::now(),
+            record_count: records.len(),
+            records,
+        };
+
+        SignedAttestation::sign(attestation, signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SettlementRoute;
+
+    fn attestation() -> FinalityAttestation {
+        let batch_id = Uuid::new_v4();
+        let records: Vec<FinalityRecord> = (1..=3)
+            .map(|sequence| FinalityRecord {
+                sequence,
+                transaction_id: Uuid::new_v4(),
+                batch_id: Some(batch_id),
+                settlement_route: SettlementRoute::Netted,
+                final_at: Utc::now(),
+            })
+            .collect();
+
+        FinalityAttestation {
+            batch_id,
+            currency: "USD".to_string(),
+            settlement_date: Utc::now().date_naive(),
+            batch_sequence_number: 1,
+            completed_at: Some(Utc::now()),
+            generated_at: Utc::now(),
+            record_count: records.len(),
+            records,
+        }
+    }
+
+    #[test]
+    fn test_signed_attestation_verifies() {
+        let signer = AttestationSigner::new("regulator-2024", b"secret");
+        let signed = SignedAttestation::sign(attestation(), &signer).unwrap();
+
+        assert_eq!(signed.algorithm, ATTESTATION_ALGORITHM);
+        assert_eq!(signed.key_id, "regulator-2024");
+        assert_eq!(signed.digest.len(), 64);
+        assert!(signed.verify(&signer));
+
+        // Survives a JSON round trip, as a regulator would receive it
+        let json = serde_json::to_string(&signed).unwrap();
+        let received: SignedAttestation = serde_json::from_str(&json).unwrap();
+        assert!(received.verify(&signer));
+    }
+
+    #[test]
+    fn test_tampered_attestation_fails_verification() {
+        let signer = AttestationSigner::new("regulator-2024", b"secret");
+        let mut signed = SignedAttestation::sign(attestation(), &signer).unwrap();
+
+        signed.attestation.records.pop();
+        assert!(!signed.verify(&signer));
+    }
+
+    #[test]
+    fn test_wrong_key_fails_verification() {
+        let signer = AttestationSigner::new("regulator-2024", b"secret");
+        let signed = SignedAttestation::sign(attestation(), &signer).unwrap();
+
+        assert!(!signed.verify(&AttestationSigner::new("regulator-2024", b"other")));
+        assert!(!signed.verify(&AttestationSigner::new("regulator-2025", b"secret")));
+    }
+
+    #[test]
+    fn test_signer_debug_hides_key() {
+        let signer = AttestationSigner::new("regulator-2024", b"secret");
+        assert!(!format!("{:?}", signer).contains("secret"));
+    }
+}
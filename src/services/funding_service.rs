@@ -0,0 +1,378 @@
+use crate::core::leader::LeaderElection;
+use crate::core::rounding::RoundingPolicy;
+use crate::error::{AppError, Result};
+use crate::events::{enqueue_event, EventEnvelope, EventType, FundingDeadlineMissedEvent};
+use crate::models::{
+    BatchStatus, FundingObligation, FundingStatus, InternalAccountRole, NettingPosition, StatusChangeReason,
+    StatusReasonCode,
+};
+use crate::repositories::{BatchRepository, FundingRepository, NettingRepository};
+use crate::services::{
+    AccountService, ChartOfAccountsService, DefaultManagementConfig, DefaultManagementService, NettingConfig,
+    NettingService,
+};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// How funding obligations are set and what happens when one is missed.
+#[derive(Debug, Clone, Copy)]
+pub struct FundingConfig {
+    /// How long after a batch's cut-off net payers have to fund their position.
+    pub funding_window: Duration,
+    /// Freeze a participant that misses its deadline.
+    pub freeze_participant: bool,
+    /// Declare a participant that misses its deadline in default. Default management
+    /// freezes the participant itself.
+    pub declare_default: bool,
+}
+
+impl Default for FundingConfig {
+    fn default() -> Self {
+        Self {
+            funding_window: Duration::hours(2),
+            freeze_participant: true,
+            declare_default: false,
+        }
+    }
+}
+
+/// Outcome of one funding sweep.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FundingSweep {
+    /// Obligations found covered by matching credits.
+    pub funded: usize,
+    /// Obligations past their deadline that were escalated.
+    pub escalated: usize,
+    /// Escalated obligations whose participant was declared in default.
+    pub defaulted: usize,
+}
+
+/// Tracks what net payers of completed batches owe the settlement control account.
+///
+/// Each net payer gets an obligation for its net debit, due a funding window after the
+/// batch's cut-off. Sweeps match settled credits from the participant to the currency's
+/// settlement control account that carry the batch ID as reference, and escalate
+/// obligations still unfunded at their deadline: a funding-deadline-missed event is
+/// queued in the outbox with the escalation, and the participant is frozen or declared
+/// in default as configured.
+pub struct FundingService {
+    pool: PgPool,
+    repo: FundingRepository,
+    batch_repo: BatchRepository,
+    netting_repo: NettingRepository,
+    chart: ChartOfAccountsService,
+    account_service: AccountService,
+    config: FundingConfig,
+    default_management: DefaultManagementConfig,
+    rounding: Arc<RoundingPolicy>,
+    netting: NettingConfig,
+    batch_size: i64,
+}
+
+impl FundingService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: FundingRepository::new(pool.clone()),
+            batch_repo: BatchRepository::new(pool.clone()),
+            netting_repo: NettingRepository::new(pool.clone()),
+            chart: ChartOfAccountsService::new(pool.clone()),
+            account_service: AccountService::new(pool.clone()),
+            pool,
+            config: FundingConfig::default(),
+            default_management: DefaultManagementConfig::default(),
+            rounding: Arc::new(RoundingPolicy::default()),
+            netting: NettingConfig::default(),
+            batch_size: 500,
+        }
+    }
+
+    pub fn with_config(mut self, config: FundingConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the rules defaults declared for missed deadlines are resolved with.
+    pub fn with_default_management(mut self, config: DefaultManagementConfig) -> Self {
+        self.default_management = config;
+        self
+    }
+
+    /// Sets how loss shares of defaults declared for missed deadlines are rounded.
+    pub fn with_rounding(mut self, rounding: Arc<RoundingPolicy>) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Sets the netting configuration batches are netted and defaults resolved under.
+    pub fn with_netting(mut self, config: NettingConfig) -> Self {
+        self.netting = config;
+        self
+    }
+
+    /// Sets how many open obligations one sweep checks at most.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Opens an obligation for each net payer of a completed batch and returns the
+    /// batch's obligations. Participants that already have one keep it.
+    pub async fn open_obligations(&self, batch_id: Uuid) -> Result<Vec<FundingObligation>> {
+        let batch = self
+            .batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch with id '{}' not found", batch_id)))?;
+        if batch.status != BatchStatus::Completed {
+            return Err(AppError::Validation(format!(
+                "Batch '{}' is {:?}; funding obligations are opened once it is completed",
+                batch_id, batch.status
+            )));
+        }
+
+        let mut positions = self.netting_repo.find_by_batch(batch_id).await?;
+        if positions.is_empty() {
+            positions = NettingService::new(self.pool.clone())
+                .with_config(self.netting.clone())
+                .process_batch_netting_streaming(batch_id, &batch.currency)
+                .await?
+                .multilateral_result
+                .map(|result| result.positions)
+                .unwrap_or_default();
+        }
+
+        let control_account_id = self
+            .chart
+            .role_account(InternalAccountRole::SettlementControl, &batch.currency)
+            .await?;
+        if control_account_id.is_none() {
+            warn!(
+                "No settlement control account in {}; funding of batch {} cannot be matched",
+                batch.currency, batch_id
+            );
+        }
+
+        let deadline = batch.cut_off_time + self.config.funding_window;
+        for position in positions.iter().filter(|p| p.is_net_payer()) {
+            self.repo
+                .create(&Self::obligation(position, control_account_id, deadline))
+                .await?;
+        }
+
+        self.repo.find_by_batch(batch_id).await
+    }
+
+    fn obligation(
+        position: &NettingPosition,
+        control_account_id: Option<Uuid>,
+        deadline: chrono::DateTime<Utc>,
+    ) -> FundingObligation {
+        FundingObligation::new(
+            position.batch_id,
+            position.participant_id,
+            position.currency.clone(),
+            position.absolute_net(),
+            control_account_id,
+            deadline,
+        )
+    }
+
+    /// Lists a batch's obligations, earliest deadline first.
+    pub async fn list_by_batch(&self, batch_id: Uuid) -> Result<Vec<FundingObligation>> {
+        self.repo.find_by_batch(batch_id).await
+    }
+
+    /// Lists obligations, optionally in one status, earliest deadline first.
+    pub async fn list(&self, status: Option<FundingStatus>, limit: i64, offset: i64) -> Result<Vec<FundingObligation>> {
+        self.repo.list(status, limit, offset).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<FundingObligation> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Funding obligation '{}' not found", id)))
+    }
+
+    /// Matches credits to open obligations and escalates those past their deadline.
+    pub async fn sweep(&self) -> Result<FundingSweep> {
+        let mut sweep = FundingSweep::default();
+        let now = Utc::now();
+
+        for mut obligation in self.repo.find_open(self.batch_size).await? {
+            let matched = self.repo.matched_credits(&obligation).await?;
+            if matched != obligation.funded_amount {
+                obligation.apply_funding(matched);
+                match self.repo.update_funding(&obligation).await? {
+                    Some(updated) => obligation = updated,
+                    None => continue,
+                }
+                if obligation.status == FundingStatus::Funded {
+                    info!(
+                        "Funding obligation {} of {} in batch {} is funded",
+                        obligation.id, obligation.participant_id, obligation.batch_id
+                    );
+                    sweep.funded += 1;
+                    continue;
+                }
+            }
+
+            if obligation.is_past_deadline(now) {
+                if let Some(defaulted) = self.escalate(&obligation).await? {
+                    sweep.escalated += 1;
+                    if defaulted {
+                        sweep.defaulted += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(sweep)
+    }
+
+    /// Marks an obligation overdue, queuing the deadline-missed event with it, then
+    /// freezes the participant or declares it in default. Returns whether it was
+    /// declared in default, or `None` if the obligation was no longer pending. The
+    /// escalation is recorded either way, so failures of the follow-up are logged.
+    async fn escalate(&self, obligation: &FundingObligation) -> Result<Option<bool>> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let Some(overdue) = FundingRepository::mark_overdue_in(&mut tx, obligation.id, now).await? else {
+            tx.rollback().await.map_err(AppError::Database)?;
+            return Ok(None);
+        };
+
+        let event = FundingDeadlineMissedEvent {
+            obligation_id: overdue.id,
+            batch_id: overdue.batch_id,
+            participant_id: overdue.participant_id,
+            currency: overdue.currency.clone(),
+            amount: overdue.amount,
+            funded_amount: overdue.funded_amount,
+            outstanding: overdue.outstanding(),
+            deadline: overdue.deadline,
+            occurred_at: now,
+        };
+        let envelope = EventEnvelope::new(EventType::FundingDeadlineMissed, event);
+        enqueue_event(
+            &mut tx,
+            FundingDeadlineMissedEvent::topic(),
+            Some(overdue.participant_id.to_string()),
+            &envelope,
+        )
+        .await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        error!(
+            batch_id = %overdue.batch_id,
+            participant_id = %overdue.participant_id,
+            currency = %overdue.currency,
+            outstanding = %overdue.outstanding(),
+            "Participant missed its funding deadline"
+        );
+
+        if self.config.declare_default {
+            let service = DefaultManagementService::new(self.pool.clone())
+                .with_config(self.default_management)
+                .with_rounding(self.rounding.clone())
+                .with_netting(self.netting.clone());
+            let reason = format!(
+                "Funding deadline {} missed with {} {} outstanding",
+                overdue.deadline,
+                overdue.outstanding(),
+                overdue.currency
+            );
+            match service.declare_default(overdue.batch_id, overdue.participant_id, None, Some(reason)).await {
+                Ok(_) => {
+                    self.repo.mark_defaulted(overdue.id).await?;
+                    return Ok(Some(true));
+                }
+                Err(e) => warn!(
+                    "Failed to declare {} in default for batch {}: {}",
+                    overdue.participant_id, overdue.batch_id, e
+                ),
+            }
+        }
+
+        if self.config.freeze_participant {
+            let reason = StatusChangeReason::new(StatusReasonCode::FundingOverdue).with_note(format!(
+                "Missed funding deadline {} for batch {} with {} {} outstanding",
+                overdue.deadline,
+                overdue.batch_id,
+                overdue.outstanding(),
+                overdue.currency
+            ));
+            match self.account_service.freeze_account(overdue.participant_id, reason).await {
+                Ok(_) => {}
+                // Already frozen or closed
+                Err(AppError::Validation(message)) => {
+                    warn!(account_id = %overdue.participant_id, "Overdue participant was not frozen: {}", message)
+                }
+                Err(e) => warn!("Failed to freeze overdue participant {}: {}", overdue.participant_id, e),
+            }
+        }
+
+        Ok(Some(false))
+    }
+}
+
+/// Periodically matches funding and escalates missed deadlines.
+pub struct FundingJob {
+    service: Arc<FundingService>,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl FundingJob {
+    pub fn new(service: Arc<FundingService>, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+            leader: None,
+        }
+    }
+
+    /// Only runs while this instance is the elected leader.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+        let leader = self.leader.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if leader.as_ref().is_none_or(|leader| leader.is_leader()) {
+                    if let Err(e) = service.sweep().await {
+                        tracing::error!("Funding job error: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,327 @@
+use crate::core::leader::LeaderElection;
+use crate::core::rounding::RoundingPolicy;
+use crate::error::{AppError, Result};
+use crate::models::{
+    AccountingPeriod, FxRevaluationLine, FxRevaluationReport, FxRevaluationRun, InternalAccountRole,
+};
+use crate::repositories::{BalanceRepository, FxRepository, FxRevaluationRepository, LedgerRepository};
+use crate::services::fx_service::Posting;
+use crate::services::{ChartOfAccountsService, FxService};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Decimal places inverted rates are kept to, as stored rates are.
+const INVERTED_RATE_SCALE: u32 = 10;
+
+/// Where revaluations are measured and booked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxRevaluationConfig {
+    /// Currency the books are kept in; balances in every other currency are revalued.
+    pub functional_currency: String,
+    /// Account unrealized gains are credited to and losses debited from. Revaluation
+    /// is refused until one is configured.
+    pub pnl_account_id: Option<Uuid>,
+}
+
+impl Default for FxRevaluationConfig {
+    fn default() -> Self {
+        Self {
+            functional_currency: "USD".to_string(),
+            pnl_account_id: None,
+        }
+    }
+}
+
+/// Revalues the internal accounts' foreign-currency balances at period end.
+///
+/// Each balance in the chart of accounts held in a currency other than the functional
+/// currency is marked to the latest rate to the functional currency effective by the
+/// end of the period, the quoted rate or the inverse of the opposite pair. The change
+/// in its functional value since it was last revalued is posted as an unrealized gain
+/// or loss between the functional currency's FX position and the configured P&L
+/// account. A period is revalued once per functional currency; currencies without a
+/// rate are reported on the run and left at their previous valuation.
+pub struct FxRevaluationService {
+    pool: PgPool,
+    repo: FxRevaluationRepository,
+    fx_repo: FxRepository,
+    balance_repo: BalanceRepository,
+    ledger_repo: LedgerRepository,
+    chart: ChartOfAccountsService,
+    config: FxRevaluationConfig,
+    rounding: Arc<RoundingPolicy>,
+}
+
+impl FxRevaluationService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: FxRevaluationRepository::new(pool.clone()),
+            fx_repo: FxRepository::new(pool.clone()),
+            balance_repo: BalanceRepository::new(pool.clone()),
+            ledger_repo: LedgerRepository::new(pool.clone()),
+            chart: ChartOfAccountsService::new(pool.clone()),
+            pool,
+            config: FxRevaluationConfig::default(),
+            rounding: Arc::new(RoundingPolicy::default()),
+        }
+    }
+
+    pub fn with_config(mut self, config: FxRevaluationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets how functional amounts and gains or losses are rounded.
+    pub fn with_rounding(mut self, rounding: Arc<RoundingPolicy>) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Revalues balances as of the end of `period_end`. Revaluing a period again returns
+    /// the report of its run.
+    pub async fn revalue(&self, period_end: NaiveDate) -> Result<FxRevaluationReport> {
+        if period_end >= Utc::now().date_naive() {
+            return Err(AppError::Validation(format!("The period ending {} has not ended yet", period_end)));
+        }
+        let functional_currency = self.config.functional_currency.as_str();
+        if let Some(run) = self.repo.find_run_by_period(period_end, functional_currency).await? {
+            return self.report(run).await;
+        }
+
+        let pnl_account_id = self.config.pnl_account_id.ok_or_else(|| {
+            AppError::Validation("No FX revaluation P&L account is configured".to_string())
+        })?;
+        if self
+            .balance_repo
+            .find_by_account_and_currency(pnl_account_id, functional_currency)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::Validation(format!(
+                "The FX revaluation P&L account '{}' has no {} balance",
+                pnl_account_id, functional_currency
+            )));
+        }
+        let position_account_id = self
+            .chart
+            .role_account(InternalAccountRole::FxPosition, functional_currency)
+            .await?
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "No FX position account in {}; add one to the chart of accounts",
+                    functional_currency
+                ))
+            })?;
+
+        let cutoff = (period_end + Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is always valid")
+            .and_utc();
+        let mut run = FxRevaluationRun {
+            id: Uuid::new_v4(),
+            period_end,
+            functional_currency: functional_currency.to_string(),
+            pnl_account_id,
+            position_account_id,
+            total_gain_loss: Decimal::ZERO,
+            missing_rates: Vec::new(),
+            created_at: Utc::now(),
+        };
+
+        let mut rates: HashMap<String, Option<(Uuid, Decimal)>> = HashMap::new();
+        let mut lines = Vec::new();
+        for account in self.chart.list(None).await? {
+            if account.currency == functional_currency || account.account_id == pnl_account_id {
+                continue;
+            }
+            let balance = self.balance_at(account.account_id, &account.currency, cutoff).await?;
+            if balance.is_zero() {
+                continue;
+            }
+            if !rates.contains_key(&account.currency) {
+                let rate = self.rate_at(&account.currency, cutoff).await?;
+                rates.insert(account.currency.clone(), rate);
+            }
+            let Some((rate_id, rate)) = rates[&account.currency] else {
+                continue;
+            };
+            let previous_rate = self
+                .repo
+                .find_last_line(account.account_id, &account.currency)
+                .await?
+                .map(|line| line.rate);
+            lines.push(
+                FxRevaluationLine::new(run.id, account.account_id, account.currency, balance)
+                    .mark(rate_id, rate, previous_rate, functional_currency, &self.rounding),
+            );
+        }
+        run.missing_rates = rates
+            .into_iter()
+            .filter(|(_, rate)| rate.is_none())
+            .map(|(currency, _)| currency)
+            .collect();
+        run.missing_rates.sort();
+        run.total_gain_loss = lines.iter().map(|line| line.gain_loss).sum();
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let Some(run) = FxRevaluationRepository::create_run_in(&mut tx, &run).await? else {
+            // Revalued concurrently
+            tx.rollback().await.map_err(AppError::Database)?;
+            let run = self
+                .repo
+                .find_run_by_period(period_end, functional_currency)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("FX revaluation of {} not found", period_end)))?;
+            return self.report(run).await;
+        };
+        let mut created = Vec::with_capacity(lines.len());
+        for mut line in lines {
+            if !line.gain_loss.is_zero() {
+                let (source_account_id, destination_account_id) = if line.gain_loss > Decimal::ZERO {
+                    (position_account_id, pnl_account_id)
+                } else {
+                    (pnl_account_id, position_account_id)
+                };
+                let posting = Posting {
+                    external_id: format!("FXREVAL-{}-{}-{}", period_end, line.currency, line.account_id),
+                    idempotency_key: format!("fx-revaluation:{}:{}", run.id, line.id),
+                    source_account_id,
+                    destination_account_id,
+                    amount: line.gain_loss.abs(),
+                    currency: functional_currency,
+                    narrative: "Unrealized FX gain/loss",
+                    link_id: Some(run.id),
+                    metadata: serde_json::json!({
+                        "fx_revaluation_run_id": run.id,
+                        "period_end": period_end,
+                        "account_id": line.account_id,
+                        "currency": line.currency,
+                        "rate_id": line.rate_id,
+                    }),
+                };
+                line.transaction_id = Some(FxService::post_in(&mut tx, posting).await?.id);
+            }
+            created.push(FxRevaluationRepository::create_line_in(&mut tx, &line).await?);
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+
+        tracing::info!(
+            "Revalued {} FX balances for the period ending {}: {} {}",
+            created.len(),
+            period_end,
+            run.total_gain_loss,
+            run.functional_currency
+        );
+        Ok(FxRevaluationReport { run, lines: created })
+    }
+
+    /// Revalues the last month that has ended, unless it already has been.
+    pub async fn revalue_due(&self) -> Result<FxRevaluationReport> {
+        let current = AccountingPeriod::containing(Utc::now().date_naive());
+        self.revalue(current.period_start - Duration::days(1)).await
+    }
+
+    pub async fn get_report(&self, id: Uuid) -> Result<FxRevaluationReport> {
+        let run = self
+            .repo
+            .find_run(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("FX revaluation '{}' not found", id)))?;
+        self.report(run).await
+    }
+
+    pub async fn list_runs(&self, limit: i64, offset: i64) -> Result<(Vec<FxRevaluationRun>, i64)> {
+        let runs = self.repo.list_runs(limit, offset).await?;
+        let total = self.repo.count_runs().await?;
+        Ok((runs, total))
+    }
+
+    async fn report(&self, run: FxRevaluationRun) -> Result<FxRevaluationReport> {
+        let lines = self.repo.find_lines(run.id).await?;
+        Ok(FxRevaluationReport { run, lines })
+    }
+
+    /// An account's balance as of `cutoff`: its current balance less everything posted
+    /// since.
+    async fn balance_at(&self, account_id: Uuid, currency: &str, cutoff: DateTime<Utc>) -> Result<Decimal> {
+        let Some(balance) = self.balance_repo.find_by_account_and_currency(account_id, currency).await? else {
+            return Ok(Decimal::ZERO);
+        };
+        let since = self.ledger_repo.net_movement_since(account_id, currency, cutoff).await?;
+        Ok(balance.available_balance - since)
+    }
+
+    /// The rate from a currency to the functional currency in effect before `cutoff`.
+    async fn rate_at(&self, currency: &str, cutoff: DateTime<Utc>) -> Result<Option<(Uuid, Decimal)>> {
+        let functional_currency = self.config.functional_currency.as_str();
+        let at = cutoff - Duration::microseconds(1);
+        if let Some(rate) = self.fx_repo.find_rate_at(currency, functional_currency, at).await? {
+            return Ok(Some((rate.id, rate.rate)));
+        }
+        let inverse = self.fx_repo.find_rate_at(functional_currency, currency, at).await?;
+        Ok(inverse.map(|rate| (rate.id, (Decimal::ONE / rate.rate).round_dp(INVERTED_RATE_SCALE))))
+    }
+}
+
+/// Background job revaluing foreign-currency balances once each month has ended.
+pub struct FxRevaluationJob {
+    service: Arc<FxRevaluationService>,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl FxRevaluationJob {
+    pub fn new(service: Arc<FxRevaluationService>, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+            leader: None,
+        }
+    }
+
+    /// Only runs while this instance is the elected leader.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+        let leader = self.leader.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if leader.as_ref().is_none_or(|leader| leader.is_leader()) {
+                    if let Err(e) = service.revalue_due().await {
+                        tracing::error!("FX revaluation job error: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
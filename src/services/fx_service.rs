@@ -0,0 +1,483 @@
+use crate::core::rounding::RoundingPolicy;
+use crate::error::{AppError, Result};
+use crate::events::enqueue_settled_event;
+use crate::models::{
+    FxConversion, FxRate, FxRateAdjustment, InternalAccountRole, LedgerEntry, TransactionRecord, TransactionType,
+};
+use crate::repositories::{AccountRepository, BalanceRepository, FxRepository, LedgerRepository, TransactionRepository};
+use crate::services::ChartOfAccountsService;
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Most rates a rate listing returns.
+const MAX_RATES_LISTED: i64 = 500;
+
+/// Request to convert funds from an account in one currency to an account in another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxConversionRequest {
+    pub external_id: String,
+    pub idempotency_key: String,
+    pub source_account_id: Uuid,
+    pub destination_account_id: Uuid,
+    /// Amount debited from the source account, in the source currency.
+    pub amount: Decimal,
+    pub source_currency: String,
+    pub destination_currency: String,
+}
+
+/// A rate correction with the revaluation adjustments it posted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxRateCorrection {
+    pub rate: FxRate,
+    /// The rate that was corrected, now superseded.
+    pub corrected: FxRate,
+    pub adjustments: Vec<FxRateAdjustment>,
+}
+
+/// Stores FX rates and converts funds between currencies at them.
+///
+/// A conversion is posted as two transactions linked by the conversion's ID: the source
+/// amount moves into the FX position account of the source currency, and the converted
+/// amount moves out of the FX position account of the destination currency, which may
+/// go negative as the engine's short position. Each conversion keeps the ID of the
+/// stored rate it was made at. A backdated correction of a rate supersedes it and posts
+/// the difference it makes to each conversion valued at it as a revaluation adjustment.
+pub struct FxService {
+    pool: PgPool,
+    repo: FxRepository,
+    account_repo: AccountRepository,
+    balance_repo: BalanceRepository,
+    chart: ChartOfAccountsService,
+    rounding: Arc<RoundingPolicy>,
+}
+
+impl FxService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: FxRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool.clone()),
+            balance_repo: BalanceRepository::new(pool.clone()),
+            chart: ChartOfAccountsService::new(pool.clone()),
+            pool,
+            rounding: Arc::new(RoundingPolicy::default()),
+        }
+    }
+
+    /// Sets how converted amounts are rounded.
+    pub fn with_rounding(mut self, rounding: Arc<RoundingPolicy>) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Stores a rate received from its source.
+    pub async fn record_rate(&self, rate: FxRate) -> Result<FxRate> {
+        rate.validate().map_err(AppError::Validation)?;
+        let rate = self.repo.create_rate(&rate).await?;
+        tracing::info!("Recorded {} rate {} from {}", rate.pair(), rate.rate, rate.source);
+        Ok(rate)
+    }
+
+    pub async fn get_rate(&self, id: Uuid) -> Result<FxRate> {
+        self.repo
+            .find_rate(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("FX rate '{}' not found", id)))
+    }
+
+    /// Lists stored rates for audit, superseded ones included, latest effective first:
+    /// optionally of one pair (`EURUSD` or `EUR/USD`) and effective on one UTC date.
+    pub async fn list_rates(&self, pair: Option<&str>, date: Option<NaiveDate>) -> Result<Vec<FxRate>> {
+        let pair = pair
+            .map(|pair| {
+                FxRate::parse_pair(pair)
+                    .ok_or_else(|| AppError::Validation(format!("pair: '{}' is not a currency pair like EURUSD", pair)))
+            })
+            .transpose()?;
+        let from = date.map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc());
+        let to = from.map(|from| from + Duration::days(1));
+        self.repo
+            .list_rates(
+                pair.as_ref().map(|(base, quote)| (base.as_str(), quote.as_str())),
+                from,
+                to,
+                MAX_RATES_LISTED,
+            )
+            .await
+    }
+
+    /// The rate of a pair conversions are made at now.
+    pub async fn current_rate(&self, base_currency: &str, quote_currency: &str) -> Result<FxRate> {
+        self.repo
+            .find_rate_at(base_currency, quote_currency, Utc::now())
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("No FX rate for {}/{}", base_currency, quote_currency)))
+    }
+
+    /// Converts funds at the pair's current rate less its spread. Resubmitting an
+    /// idempotency key returns the conversion already made.
+    pub async fn convert(&self, request: FxConversionRequest) -> Result<FxConversion> {
+        if let Some(existing) = self
+            .repo
+            .find_conversion_by_idempotency_key(&request.idempotency_key)
+            .await?
+        {
+            return Ok(existing);
+        }
+        if request.amount <= Decimal::ZERO {
+            return Err(AppError::Validation("amount must be positive".to_string()));
+        }
+
+        let rate = self
+            .current_rate(&request.source_currency, &request.destination_currency)
+            .await?;
+        let destination_amount = rate.convert(request.amount, &self.rounding);
+        if destination_amount <= Decimal::ZERO {
+            return Err(AppError::Validation(format!(
+                "{} {} converts to nothing at {}",
+                request.amount,
+                request.source_currency,
+                rate.applied_rate()
+            )));
+        }
+        self.verify_account(request.source_account_id).await?;
+        self.verify_account(request.destination_account_id).await?;
+        let source_position = self.position_account(&request.source_currency).await?;
+        let destination_position = self.position_account(&request.destination_currency).await?;
+        for (account_id, currency) in [
+            (source_position, &request.source_currency),
+            (destination_position, &request.destination_currency),
+            (request.destination_account_id, &request.destination_currency),
+        ] {
+            self.balance_repo.get_or_create(account_id, currency).await?;
+        }
+
+        let conversion_id = Uuid::new_v4();
+        let narrative = format!("FX conversion {} at {}", rate.pair(), rate.applied_rate().normalize());
+        let metadata = serde_json::json!({ "fx_conversion_id": conversion_id, "fx_rate_id": rate.id });
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        Self::ensure_funds_in(&mut tx, request.source_account_id, &request.source_currency, request.amount).await?;
+        let debit = Self::post_in(
+            &mut tx,
+            Posting {
+                external_id: format!("{}:DEBIT", request.external_id),
+                idempotency_key: format!("{}:DEBIT", request.idempotency_key),
+                source_account_id: request.source_account_id,
+                destination_account_id: source_position,
+                amount: request.amount,
+                currency: &request.source_currency,
+                narrative: &narrative,
+                link_id: Some(conversion_id),
+                metadata: metadata.clone(),
+            },
+        )
+        .await?;
+        let credit = Self::post_in(
+            &mut tx,
+            Posting {
+                external_id: format!("{}:CREDIT", request.external_id),
+                idempotency_key: format!("{}:CREDIT", request.idempotency_key),
+                source_account_id: destination_position,
+                destination_account_id: request.destination_account_id,
+                amount: destination_amount,
+                currency: &request.destination_currency,
+                narrative: &narrative,
+                link_id: Some(conversion_id),
+                metadata,
+            },
+        )
+        .await?;
+
+        let now = Utc::now();
+        let conversion = FxConversion {
+            id: conversion_id,
+            external_id: request.external_id,
+            idempotency_key: request.idempotency_key,
+            source_account_id: request.source_account_id,
+            destination_account_id: request.destination_account_id,
+            source_amount: request.amount,
+            source_currency: request.source_currency,
+            destination_amount,
+            destination_currency: request.destination_currency,
+            rate_id: rate.id,
+            applied_rate: rate.applied_rate(),
+            current_rate_id: rate.id,
+            debit_transaction_id: debit.id,
+            credit_transaction_id: credit.id,
+            created_at: now,
+            updated_at: now,
+        };
+        let Some(created) = FxRepository::create_conversion_in(&mut tx, &conversion).await? else {
+            // Lost a race with a concurrent submission of the same key
+            drop(tx);
+            return self
+                .repo
+                .find_conversion_by_idempotency_key(&conversion.idempotency_key)
+                .await?
+                .ok_or_else(|| {
+                    AppError::SerializationConflict(format!(
+                        "FX conversion '{}' was submitted concurrently",
+                        conversion.idempotency_key
+                    ))
+                });
+        };
+        tx.commit().await.map_err(AppError::Database)?;
+
+        tracing::info!(
+            "Converted {} {} to {} {} at {} (rate {})",
+            created.source_amount,
+            created.source_currency,
+            created.destination_amount,
+            created.destination_currency,
+            created.applied_rate,
+            created.rate_id
+        );
+        Ok(created)
+    }
+
+    pub async fn get_conversion(&self, id: Uuid) -> Result<FxConversion> {
+        self.repo
+            .find_conversion(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("FX conversion '{}' not found", id)))
+    }
+
+    /// Revaluation adjustments posted to a conversion, oldest first.
+    pub async fn conversion_adjustments(&self, id: Uuid) -> Result<Vec<FxRateAdjustment>> {
+        self.repo.find_adjustments_by_conversion(id).await
+    }
+
+    /// Corrects a stored rate, effective from the same time. The corrected rate is
+    /// superseded, and every conversion valued at it is revalued at the correction: the
+    /// difference is credited to its destination account from the FX position, or
+    /// debited back into it. The correction is all or nothing, so it fails if a
+    /// destination account cannot cover its adjustment. Only the latest correction of a
+    /// rate can be corrected again.
+    pub async fn correct_rate(
+        &self,
+        id: Uuid,
+        rate: Decimal,
+        spread: Option<Decimal>,
+        source: Option<String>,
+        reason: &str,
+    ) -> Result<FxRateCorrection> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let corrected = FxRepository::find_rate_for_update_in(&mut tx, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("FX rate '{}' not found", id)))?;
+        if let Some(superseded_by) = corrected.superseded_by {
+            return Err(AppError::Validation(format!(
+                "FX rate '{}' was already corrected by '{}'; correct the latest rate instead",
+                id, superseded_by
+            )));
+        }
+        let correction = corrected.correction(
+            rate,
+            spread.unwrap_or(corrected.spread),
+            source.unwrap_or_else(|| corrected.source.clone()),
+            reason,
+        );
+        correction.validate().map_err(AppError::Validation)?;
+
+        let correction = FxRepository::create_rate_in(&mut tx, &correction).await?;
+        FxRepository::supersede_rate_in(&mut tx, corrected.id, correction.id).await?;
+
+        let conversions = FxRepository::find_conversions_valued_at_in(&mut tx, corrected.id).await?;
+        let position = if conversions.is_empty() {
+            None
+        } else {
+            Some(self.position_account(&corrected.quote_currency).await?)
+        };
+        let narrative = format!(
+            "FX revaluation {} from {} to {}",
+            correction.pair(),
+            corrected.applied_rate().normalize(),
+            correction.applied_rate().normalize()
+        );
+        let mut adjustments = Vec::new();
+        for conversion in conversions {
+            let amount = correction.convert(conversion.source_amount, &self.rounding)
+                - corrected.convert(conversion.source_amount, &self.rounding);
+            if let Some(position) = position.filter(|_| !amount.is_zero()) {
+                let (source_account_id, destination_account_id) = if amount > Decimal::ZERO {
+                    (position, conversion.destination_account_id)
+                } else {
+                    Self::ensure_funds_in(
+                        &mut tx,
+                        conversion.destination_account_id,
+                        &conversion.destination_currency,
+                        -amount,
+                    )
+                    .await?;
+                    (conversion.destination_account_id, position)
+                };
+                let transaction = Self::post_in(
+                    &mut tx,
+                    Posting {
+                        external_id: format!("{}:REVALUE:{}", conversion.external_id, correction.id),
+                        idempotency_key: format!("{}:REVALUE:{}", conversion.idempotency_key, correction.id),
+                        source_account_id,
+                        destination_account_id,
+                        amount: amount.abs(),
+                        currency: &conversion.destination_currency,
+                        narrative: &narrative,
+                        link_id: Some(conversion.id),
+                        metadata: serde_json::json!({
+                            "fx_conversion_id": conversion.id,
+                            "fx_rate_id": correction.id,
+                            "corrected_fx_rate_id": corrected.id,
+                        }),
+                    },
+                )
+                .await?;
+                let adjustment = FxRateAdjustment {
+                    id: Uuid::new_v4(),
+                    conversion_id: conversion.id,
+                    rate_id: correction.id,
+                    previous_rate_id: corrected.id,
+                    amount,
+                    currency: conversion.destination_currency.clone(),
+                    transaction_id: transaction.id,
+                    created_at: Utc::now(),
+                };
+                adjustments.push(FxRepository::create_adjustment_in(&mut tx, &adjustment).await?);
+            }
+            FxRepository::set_current_rate_in(&mut tx, conversion.id, correction.id).await?;
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+
+        tracing::warn!(
+            "Corrected {} rate {} from {} to {}: {} revaluation adjustment(s)",
+            correction.pair(),
+            corrected.id,
+            corrected.rate,
+            correction.rate,
+            adjustments.len()
+        );
+        Ok(FxRateCorrection {
+            corrected: FxRate {
+                superseded_by: Some(correction.id),
+                ..corrected
+            },
+            rate: correction,
+            adjustments,
+        })
+    }
+
+    /// The FX position account of a currency in the chart of accounts.
+    async fn position_account(&self, currency: &str) -> Result<Uuid> {
+        self.chart
+            .role_account(InternalAccountRole::FxPosition, currency)
+            .await?
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "No FX position account in {}; add one to the chart of accounts",
+                    currency
+                ))
+            })
+    }
+
+    async fn verify_account(&self, account_id: Uuid) -> Result<()> {
+        let account = self
+            .account_repo
+            .find_by_id(account_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account '{}' not found", account_id)))?;
+        if !account.status.is_operational() {
+            return Err(AppError::AccountFrozen(format!("Account '{}' is not operational", account_id)));
+        }
+        Ok(())
+    }
+
+    /// Locks an account's balance and checks it covers an amount.
+    async fn ensure_funds_in(
+        tx: &mut Transaction<'_, Postgres>,
+        account_id: Uuid,
+        currency: &str,
+        amount: Decimal,
+    ) -> Result<()> {
+        let balance = BalanceRepository::lock_many_in(tx, &[account_id], &[currency.to_string()])
+            .await?
+            .into_iter()
+            .next();
+        match balance {
+            Some(balance) if balance.has_sufficient_funds(amount) => Ok(()),
+            Some(balance) => Err(AppError::InsufficientFunds(format!(
+                "Insufficient funds: requested {}, available {}",
+                amount,
+                balance.usable_balance()
+            ))),
+            None => Err(AppError::NotFound(format!(
+                "Balance not found for account '{}' in currency '{}'",
+                account_id, currency
+            ))),
+        }
+    }
+
+    /// Posts a settled transfer without a funds check; callers check the source first
+    /// where it matters.
+    pub(crate) async fn post_in(tx: &mut Transaction<'_, Postgres>, posting: Posting<'_>) -> Result<TransactionRecord> {
+        let transaction = TransactionRecord::new(
+            posting.external_id,
+            TransactionType::Transfer,
+            posting.source_account_id,
+            posting.destination_account_id,
+            posting.amount,
+            posting.currency.to_string(),
+            Decimal::ZERO,
+            posting.idempotency_key,
+        )
+        .with_narrative(Some(posting.narrative.to_string()), None)
+        .with_link(posting.link_id)
+        .with_metadata(posting.metadata.clone());
+        let transaction = TransactionRepository::create_in(tx, &transaction).await?;
+
+        let effective_date = Utc::now().date_naive();
+        let source = BalanceRepository::adjust_in(tx, posting.source_account_id, posting.currency, -posting.amount).await?;
+        let destination =
+            BalanceRepository::adjust_in(tx, posting.destination_account_id, posting.currency, posting.amount).await?;
+        let debit = LedgerEntry::debit(
+            transaction.id,
+            posting.source_account_id,
+            posting.amount,
+            posting.currency.to_string(),
+            source.available_balance,
+            effective_date,
+        )
+        .with_narrative(transaction.narrative.clone(), None)
+        .with_metadata(posting.metadata.clone());
+        let credit = LedgerEntry::credit(
+            transaction.id,
+            posting.destination_account_id,
+            posting.amount,
+            posting.currency.to_string(),
+            destination.available_balance,
+            effective_date,
+        )
+        .with_narrative(transaction.narrative.clone(), None)
+        .with_metadata(posting.metadata);
+        LedgerRepository::create_in(tx, &debit).await?;
+        LedgerRepository::create_in(tx, &credit).await?;
+
+        let transaction = TransactionRepository::mark_settled_in(tx, transaction.id).await?;
+        enqueue_settled_event(tx, &transaction).await?;
+        Ok(transaction)
+    }
+}
+
+/// A transfer the FX services post themselves.
+pub(crate) struct Posting<'a> {
+    pub(crate) external_id: String,
+    pub(crate) idempotency_key: String,
+    pub(crate) source_account_id: Uuid,
+    pub(crate) destination_account_id: Uuid,
+    pub(crate) amount: Decimal,
+    pub(crate) currency: &'a str,
+    pub(crate) narrative: &'a str,
+    pub(crate) link_id: Option<Uuid>,
+    pub(crate) metadata: serde_json::Value,
+}
@@ -0,0 +1,104 @@
+use crate::error::{AppError, Result};
+use crate::interop::gl::{GlJournal, GlMapping};
+use crate::models::GlPostingRun;
+use crate::repositories::GlRepository;
+use chrono::{NaiveDate, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Posts the sub-ledger to the general ledger, one closed day at a time.
+///
+/// A posting run maps each of the day's ledger entries to a GL account code, sums them
+/// per code and currency and stores the resulting journal. Each date is posted once:
+/// re-running it returns the stored journal, so the ERP never receives a day twice.
+pub struct GlPostingService {
+    pool: PgPool,
+    repo: GlRepository,
+    mapping: Arc<GlMapping>,
+}
+
+impl GlPostingService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: GlRepository::new(pool.clone()),
+            pool,
+            mapping: Arc::new(GlMapping::default()),
+        }
+    }
+
+    /// Sets the GL account codes entries are posted to.
+    pub fn with_mapping(mut self, mapping: Arc<GlMapping>) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Posts a day's ledger entries, or returns the journal already posted for it.
+    /// Only days before today (UTC) can be posted, since later entries could not be
+    /// added to a posted day.
+    pub async fn post_day(&self, posting_date: NaiveDate) -> Result<GlJournal> {
+        if posting_date >= Utc::now().date_naive() {
+            return Err(AppError::Validation(format!(
+                "Cannot post {}: only days before today can be posted",
+                posting_date
+            )));
+        }
+        if let Some(run) = self.repo.find_run_by_date(posting_date).await? {
+            return self.journal_for(run).await;
+        }
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let run = GlPostingRun::new(posting_date);
+        if !GlRepository::create_run_in(&mut tx, &run).await? {
+            // Posted concurrently since the check above
+            tx.rollback().await.map_err(AppError::Database)?;
+            let run = self
+                .repo
+                .find_run_by_date(posting_date)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("GL posting run for {} not found", posting_date)))?;
+            return self.journal_for(run).await;
+        }
+
+        let entries = GlRepository::summarize_entries_in(&mut tx, posting_date).await?;
+        let fees = GlRepository::summarize_fees_in(&mut tx, posting_date).await?;
+        let lines = self.mapping.journal_lines(run.id, &entries, &fees);
+        let run = GlRepository::complete_run_in(&mut tx, run.id, &lines).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        let journal = GlJournal { run, lines };
+        if !journal.is_balanced() {
+            warn!(posting_date = %posting_date, "GL journal does not balance: {:?}", journal.totals());
+        }
+        info!(
+            "Posted {} ledger entries for {} to {} GL journal lines",
+            journal.run.entry_count, posting_date, journal.run.line_count
+        );
+        Ok(journal)
+    }
+
+    /// Gets a posting run's journal.
+    pub async fn get_journal(&self, run_id: Uuid) -> Result<GlJournal> {
+        let run = self
+            .repo
+            .find_run(run_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("GL posting run '{}' not found", run_id)))?;
+        self.journal_for(run).await
+    }
+
+    /// Lists posting runs, latest posting date first.
+    pub async fn list_runs(&self, limit: i64, offset: i64) -> Result<Vec<GlPostingRun>> {
+        self.repo.list_runs(limit, offset).await
+    }
+
+    pub async fn count_runs(&self) -> Result<i64> {
+        self.repo.count_runs().await
+    }
+
+    async fn journal_for(&self, run: GlPostingRun) -> Result<GlJournal> {
+        let lines = self.repo.find_lines(run.id).await?;
+        Ok(GlJournal { run, lines })
+    }
+}
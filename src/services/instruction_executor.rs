@@ -0,0 +1,222 @@
+use crate::core::saga::{Saga, SagaOrchestrator, SagaStep};
+use crate::error::{AppError, Result};
+use crate::models::{SagaState, SagaStatus};
+use crate::repositories::SagaRepository;
+use crate::services::{
+    AcknowledgementStatus, BalanceService, InstructionStatus, SettlementInstruction, SettlementRail,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Saga type under which instruction executions are persisted.
+pub const INSTRUCTION_EXECUTION_SAGA: &str = "instruction_execution";
+
+/// Context carried through an instruction execution saga.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionExecution {
+    pub instruction: SettlementInstruction,
+    pub external_reference: Option<String>,
+}
+
+/// Holds the amount on the paying participant's balance so it cannot be spent while
+/// the rail moves it.
+struct ReserveFunds {
+    balances: BalanceService,
+}
+
+#[async_trait]
+impl SagaStep<InstructionExecution> for ReserveFunds {
+    fn name(&self) -> &str {
+        "reserve_funds"
+    }
+
+    async fn execute(&self, context: &mut InstructionExecution) -> Result<()> {
+        let instruction = &context.instruction;
+        self.balances
+            .reserve(instruction.from_participant, &instruction.money()?)
+            .await?;
+        Ok(())
+    }
+
+    async fn compensate(&self, context: &mut InstructionExecution) -> Result<()> {
+        let instruction = &context.instruction;
+        self.balances
+            .release_reservation(instruction.from_participant, &instruction.money()?)
+            .await?;
+        Ok(())
+    }
+}
+
+struct SendInstruction {
+    rail: Arc<dyn SettlementRail>,
+}
+
+#[async_trait]
+impl SagaStep<InstructionExecution> for SendInstruction {
+    fn name(&self) -> &str {
+        "send_instruction"
+    }
+
+    async fn execute(&self, context: &mut InstructionExecution) -> Result<()> {
+        // Already sent before a restart; sending again would duplicate the payment
+        if context.external_reference.is_none() {
+            context.external_reference = Some(self.rail.submit(&context.instruction).await?);
+        }
+        Ok(())
+    }
+
+    async fn compensate(&self, context: &mut InstructionExecution) -> Result<()> {
+        if let Some(reference) = &context.external_reference {
+            self.rail.cancel(reference).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Polls the rail until the instruction is accepted or rejected. The saga
+/// timeout bounds how long a pending acknowledgement is waited for.
+struct AwaitAcknowledgement {
+    rail: Arc<dyn SettlementRail>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl SagaStep<InstructionExecution> for AwaitAcknowledgement {
+    fn name(&self) -> &str {
+        "await_acknowledgement"
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    async fn execute(&self, context: &mut InstructionExecution) -> Result<()> {
+        let reference = context.external_reference.as_deref().ok_or_else(|| {
+            AppError::Validation("Instruction has not been sent".to_string())
+        })?;
+
+        loop {
+            let status = match self.rail.status(reference).await {
+                Ok(status) => status,
+                // Keep polling through transient rail outages until the step times out
+                Err(e) if e.is_retryable() => {
+                    tracing::warn!("Acknowledgement poll for {} failed: {}", reference, e);
+                    tokio::time::sleep(self.poll_interval).await;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            match status {
+                AcknowledgementStatus::Accepted => return Ok(()),
+                AcknowledgementStatus::Rejected(reason) => {
+                    return Err(AppError::Validation(format!(
+                        "Instruction {} rejected by {}: {}",
+                        reference,
+                        self.rail.name(),
+                        reason
+                    )));
+                }
+                AcknowledgementStatus::Pending => tokio::time::sleep(self.poll_interval).await,
+            }
+        }
+    }
+}
+
+/// Releases the hold once the rail has moved the funds.
+struct Finalize {
+    balances: BalanceService,
+}
+
+#[async_trait]
+impl SagaStep<InstructionExecution> for Finalize {
+    fn name(&self) -> &str {
+        "finalize"
+    }
+
+    async fn execute(&self, context: &mut InstructionExecution) -> Result<()> {
+        let instruction = &mut context.instruction;
+        self.balances
+            .release_reservation(instruction.from_participant, &instruction.money()?)
+            .await?;
+        instruction.status = InstructionStatus::Executed;
+        Ok(())
+    }
+}
+
+/// Executes settlement instructions against a settlement rail as persisted sagas:
+/// reserve funds, send the instruction, await its acknowledgement, then finalize, or
+/// undo the completed steps if any of them fails or times out.
+pub struct InstructionExecutor {
+    pool: PgPool,
+    orchestrator: SagaOrchestrator,
+    rail: Arc<dyn SettlementRail>,
+    poll_interval: Duration,
+    ack_timeout: Duration,
+}
+
+impl InstructionExecutor {
+    pub fn new(pool: PgPool, rail: Arc<dyn SettlementRail>) -> Self {
+        Self {
+            orchestrator: SagaOrchestrator::new(Arc::new(SagaRepository::new(pool.clone()))),
+            pool,
+            rail,
+            poll_interval: Duration::from_millis(500),
+            ack_timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Sets how often and for how long acknowledgements are polled.
+    pub fn with_acknowledgement_polling(mut self, poll_interval: Duration, timeout: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self.ack_timeout = timeout;
+        self
+    }
+
+    /// Executes an instruction, returning the saga state and the instruction with its
+    /// final status.
+    pub async fn execute(&self, instruction: SettlementInstruction) -> Result<(SagaState, SettlementInstruction)> {
+        let context = InstructionExecution {
+            instruction,
+            external_reference: None,
+        };
+        let outcome = self.orchestrator.start(&self.saga(), context).await?;
+        Ok(Self::finish(outcome.state, outcome.context))
+    }
+
+    /// Resumes an instruction execution interrupted by a restart.
+    pub async fn resume(&self, saga_id: Uuid) -> Result<(SagaState, SettlementInstruction)> {
+        let outcome = self.orchestrator.resume(&self.saga(), saga_id).await?;
+        Ok(Self::finish(outcome.state, outcome.context))
+    }
+
+    fn saga(&self) -> Saga<InstructionExecution> {
+        Saga::new(INSTRUCTION_EXECUTION_SAGA)
+            .step(ReserveFunds {
+                balances: BalanceService::new(self.pool.clone()),
+            })
+            .step(SendInstruction {
+                rail: self.rail.clone(),
+            })
+            .step(AwaitAcknowledgement {
+                rail: self.rail.clone(),
+                poll_interval: self.poll_interval,
+                timeout: self.ack_timeout,
+            })
+            .step(Finalize {
+                balances: BalanceService::new(self.pool.clone()),
+            })
+    }
+
+    fn finish(state: SagaState, context: InstructionExecution) -> (SagaState, SettlementInstruction) {
+        let mut instruction = context.instruction;
+        if matches!(state.status, SagaStatus::Compensated | SagaStatus::Failed) {
+            instruction.status = InstructionStatus::Failed;
+        }
+        (state, instruction)
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::{AppError, Result};
+use crate::interop::nacha::{NachaConfig, NachaFile};
+use crate::interop::pacs008::Pacs008Message;
+use crate::models::{BatchStatus, NettingMode, SettlementBatch, SettlementProfile};
+use crate::repositories::{BatchRepository, SettlementProfileRepository};
+use crate::services::{DefaultManagementService, NettingConfig, NettingService, SettlementInstruction};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Exports a completed batch's net settlement instructions as payment files.
+pub struct InstructionExportService {
+    pool: PgPool,
+    batch_repo: BatchRepository,
+    profile_repo: SettlementProfileRepository,
+    netting: NettingConfig,
+    nacha: Option<Arc<NachaConfig>>,
+}
+
+impl InstructionExportService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            batch_repo: BatchRepository::new(pool.clone()),
+            profile_repo: SettlementProfileRepository::new(pool.clone()),
+            pool,
+            netting: NettingConfig::default(),
+            nacha: None,
+        }
+    }
+
+    /// Sets the netting configuration used for batches that were never netted.
+    pub fn with_netting(mut self, config: NettingConfig) -> Self {
+        self.netting = config;
+        self
+    }
+
+    /// Enables NACHA export. Without originator details `export_nacha` is rejected.
+    pub fn with_nacha(mut self, config: Arc<NachaConfig>) -> Self {
+        self.nacha = Some(config);
+        self
+    }
+
+    /// Gets the settlement instructions of a completed batch in its netting mode.
+    ///
+    /// Instructions come from the netting report the batch was released on, so gross
+    /// settled transactions are paid out alongside the net instructions. A batch that
+    /// was never netted is netted now under the same configuration. Once a participant
+    /// of a multilateral batch defaults, the latest replacement instructions are used.
+    pub async fn batch_instructions(
+        &self,
+        batch_id: Uuid,
+    ) -> Result<(SettlementBatch, Vec<SettlementInstruction>)> {
+        let batch = self
+            .batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch {} not found", batch_id)))?;
+
+        if batch.status != BatchStatus::Completed {
+            return Err(AppError::Validation(format!(
+                "Batch {} is {:?}; only completed batches can be exported",
+                batch_id, batch.status
+            )));
+        }
+
+        if batch.netting_mode == NettingMode::Multilateral {
+            let defaults = DefaultManagementService::new(self.pool.clone());
+            if let Some(latest) = defaults.batch_defaults(batch_id).await?.pop() {
+                let report = defaults.default_report(batch_id, latest.participant_id).await?;
+                return Ok((batch, report.replacement_instructions));
+            }
+        }
+
+        let report = NettingService::new(self.pool.clone())
+            .with_config(self.netting.clone())
+            .batch_report(batch_id, &batch.currency)
+            .await?;
+        Ok((batch, report.instructions()))
+    }
+
+    /// Builds a NACHA file paying out a completed batch's settlement instructions.
+    pub async fn export_nacha(&self, batch_id: Uuid) -> Result<NachaFile> {
+        let config = self.nacha.as_ref().ok_or_else(|| {
+            AppError::Validation("NACHA export is not configured".to_string())
+        })?;
+
+        let (batch, instructions) = self.batch_instructions(batch_id).await?;
+        let profiles = self.participant_profiles(&batch, &instructions).await?;
+        NachaFile::build(config, &batch, &instructions, &profiles, Utc::now())
+    }
+
+    /// Builds a pacs.008 credit transfer message paying out a completed batch's
+    /// settlement instructions over SEPA or SWIFT.
+    pub async fn export_pacs008(&self, batch_id: Uuid) -> Result<Pacs008Message> {
+        let (batch, instructions) = self.batch_instructions(batch_id).await?;
+        let profiles = self.participant_profiles(&batch, &instructions).await?;
+        Pacs008Message::build(&batch, &instructions, &profiles, Utc::now())
+    }
+
+    /// Gets the settlement profiles, in the batch currency, of every participant in the
+    /// instructions.
+    async fn participant_profiles(
+        &self,
+        batch: &SettlementBatch,
+        instructions: &[SettlementInstruction],
+    ) -> Result<HashMap<Uuid, SettlementProfile>> {
+        let mut participants: Vec<Uuid> = instructions
+            .iter()
+            .flat_map(|i| [i.from_participant, i.to_participant])
+            .collect();
+        participants.sort();
+        participants.dedup();
+
+        let profiles = self
+            .profile_repo
+            .find_by_accounts(&participants, &batch.currency)
+            .await?
+            .into_iter()
+            .map(|p| (p.account_id, p))
+            .collect();
+        Ok(profiles)
+    }
+}
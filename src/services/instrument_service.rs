@@ -0,0 +1,76 @@
+use crate::error::{AppError, Result};
+use crate::models::{Currency, Instrument, InstrumentKind};
+use crate::repositories::InstrumentRepository;
+use sqlx::PgPool;
+use std::str::FromStr;
+
+/// Registry of the instruments ledger codes denote.
+///
+/// Accounts, balances and ledger entries carry one code each, so an account holds either
+/// cash in a currency or units of one security. Securities are registered here with the
+/// scale of their quantities; any 3-letter code not registered is a cash currency.
+pub struct InstrumentService {
+    repo: InstrumentRepository,
+}
+
+impl InstrumentService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: InstrumentRepository::new(pool),
+        }
+    }
+
+    /// Registers a security. Its code cannot be an ISO 4217 currency or be registered
+    /// already.
+    pub async fn register(&self, instrument: Instrument) -> Result<Instrument> {
+        if !Instrument::is_valid_code(&instrument.code) {
+            return Err(AppError::Validation(format!(
+                "Instrument code '{}' must be 3 to 12 upper-case letters and digits",
+                instrument.code
+            )));
+        }
+        if instrument.kind == InstrumentKind::Security && Currency::from_str(&instrument.code).is_ok() {
+            return Err(AppError::Validation(format!(
+                "'{}' is a currency and cannot be registered as a security",
+                instrument.code
+            )));
+        }
+        if !(0..=4).contains(&instrument.scale) {
+            return Err(AppError::Validation("Instrument scale must be between 0 and 4".to_string()));
+        }
+        if self.repo.find_by_code(&instrument.code).await?.is_some() {
+            return Err(AppError::Validation(format!(
+                "Instrument '{}' is already registered",
+                instrument.code
+            )));
+        }
+        self.repo.create(&instrument).await
+    }
+
+    pub async fn get(&self, code: &str) -> Result<Instrument> {
+        self.repo
+            .find_by_code(code)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Instrument '{}' not found", code)))
+    }
+
+    /// Lists registered instruments, optionally of one kind.
+    pub async fn list(&self, kind: Option<InstrumentKind>) -> Result<Vec<Instrument>> {
+        self.repo.list(kind).await
+    }
+
+    /// What a ledger code denotes: the registered instrument, or cash for an unregistered
+    /// 3-letter code. Other codes are rejected.
+    pub async fn resolve(&self, code: &str) -> Result<Instrument> {
+        if let Some(instrument) = self.repo.find_by_code(code).await? {
+            return Ok(instrument);
+        }
+        if code.len() == 3 {
+            return Ok(Instrument::cash(code));
+        }
+        Err(AppError::Validation(format!(
+            "'{}' is neither a 3-letter currency code nor a registered instrument",
+            code
+        )))
+    }
+}
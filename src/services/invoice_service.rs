@@ -0,0 +1,240 @@
+use crate::error::{AppError, Result};
+use crate::events::enqueue_settled_event;
+use crate::models::{
+    AccountingPeriod, Invoice, InvoiceContract, InvoiceDocument, LedgerEntry, TransactionRecord, TransactionType,
+};
+use crate::repositories::{
+    AccountRepository, BalanceRepository, InvoiceRepository, LedgerRepository, TransactionRepository,
+};
+use crate::services::FeeConfig;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Terms of an invoice contract.
+#[derive(Debug, Clone, Default)]
+pub struct InvoiceContractTerms {
+    pub free_transactions: i32,
+    pub per_transaction_price: Decimal,
+    pub volume_rate: Decimal,
+    pub monthly_cap: Option<Decimal>,
+}
+
+/// Invoices participants monthly for their transaction volume.
+///
+/// An invoicing run sums each payer's payments and transfers over an ended calendar
+/// month, per currency, prices them under the payer's contract and stores the invoice
+/// with its line items. A billed total is posted as a `Fee` transaction debiting the
+/// currency's invoice receivable account and crediting its fee revenue account. Each
+/// participant is invoiced once per month and currency: re-running a month only
+/// invoices participants it has not invoiced yet.
+pub struct InvoiceService {
+    pool: PgPool,
+    repo: InvoiceRepository,
+    account_repo: AccountRepository,
+    fees: Arc<FeeConfig>,
+}
+
+impl InvoiceService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: InvoiceRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool.clone()),
+            pool,
+            fees: Arc::new(FeeConfig::default()),
+        }
+    }
+
+    /// Sets the receivable and revenue accounts invoices are posted to.
+    pub fn with_fees(mut self, fees: Arc<FeeConfig>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Sets how an account's monthly volume in a currency is priced.
+    pub async fn set_contract(&self, account_id: Uuid, currency: &str, terms: InvoiceContractTerms) -> Result<InvoiceContract> {
+        if terms.free_transactions < 0
+            || terms.per_transaction_price < Decimal::ZERO
+            || terms.volume_rate < Decimal::ZERO
+            || terms.monthly_cap.is_some_and(|cap| cap < Decimal::ZERO)
+        {
+            return Err(AppError::Validation("Invoice contract terms cannot be negative".to_string()));
+        }
+        self.account_repo
+            .find_by_id(account_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account with id '{}' not found", account_id)))?;
+
+        self.repo
+            .set_contract(
+                account_id,
+                currency,
+                terms.free_transactions,
+                terms.per_transaction_price,
+                terms.volume_rate,
+                terms.monthly_cap,
+            )
+            .await
+    }
+
+    pub async fn get_contract(&self, account_id: Uuid, currency: &str) -> Result<InvoiceContract> {
+        self.repo.find_contract(account_id, currency).await?.ok_or_else(|| {
+            AppError::NotFound(format!(
+                "No invoice contract for account '{}' in {}",
+                account_id, currency
+            ))
+        })
+    }
+
+    /// Invoices every participant with payments or transfers in the month containing
+    /// `month`, optionally in one currency, and returns the month's invoices. Only ended
+    /// months can be invoiced. Nothing is invoiced unless every billed currency has an
+    /// invoice receivable and a fee revenue account.
+    pub async fn invoice_month(&self, month: NaiveDate, currency: Option<&str>) -> Result<Vec<Invoice>> {
+        let period = AccountingPeriod::containing(month);
+        if period.period_end >= Utc::now().date_naive() {
+            return Err(AppError::Validation(format!(
+                "Cannot invoice {}: only ended months can be invoiced",
+                period.period_start.format("%Y-%m")
+            )));
+        }
+
+        let mut documents = Vec::new();
+        for usage in self.repo.summarize_usage(period.period_start, period.period_end, currency).await? {
+            let contract = self.repo.find_contract(usage.account_id, &usage.currency).await?;
+            let document = InvoiceDocument::price(&usage, contract.as_ref(), period.period_start, period.period_end);
+            if document.invoice.total_amount > Decimal::ZERO && self.posting_accounts(&usage.currency).is_none() {
+                return Err(AppError::Validation(format!(
+                    "Cannot invoice {}: no invoice receivable or fee revenue account for the currency",
+                    usage.currency
+                )));
+            }
+            documents.push(document);
+        }
+
+        let mut created = 0;
+        for document in &documents {
+            if self.create(document).await? {
+                created += 1;
+            }
+        }
+        if created > 0 {
+            info!(
+                "Invoiced {} participant(s) for {}",
+                created,
+                period.period_start.format("%Y-%m")
+            );
+        }
+
+        let mut invoices = self.repo.list(None, Some(period.period_start)).await?;
+        if let Some(currency) = currency {
+            invoices.retain(|invoice| invoice.currency == currency);
+        }
+        Ok(invoices)
+    }
+
+    /// Gets an invoice with its line items.
+    pub async fn get_invoice(&self, id: Uuid) -> Result<InvoiceDocument> {
+        let invoice = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Invoice '{}' not found", id)))?;
+        let lines = self.repo.find_lines(id).await?;
+        Ok(InvoiceDocument { invoice, lines })
+    }
+
+    /// Lists invoices, optionally for one account and/or the month containing a date,
+    /// latest month first.
+    pub async fn list_invoices(&self, account_id: Option<Uuid>, month: Option<NaiveDate>) -> Result<Vec<Invoice>> {
+        let period_start = month.map(|date| AccountingPeriod::containing(date).period_start);
+        self.repo.list(account_id, period_start).await
+    }
+
+    /// The receivable and revenue accounts an invoice in a currency is posted between.
+    fn posting_accounts(&self, currency: &str) -> Option<(Uuid, Uuid)> {
+        Some((
+            self.fees.invoice_receivable_account(currency)?,
+            self.fees.revenue_account(currency)?,
+        ))
+    }
+
+    /// Stores an invoice and posts its receivable. Returns false if the participant was
+    /// already invoiced for the month.
+    async fn create(&self, document: &InvoiceDocument) -> Result<bool> {
+        let invoice = &document.invoice;
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        if !InvoiceRepository::create_in(&mut tx, invoice, &document.lines).await? {
+            tx.rollback().await.map_err(AppError::Database)?;
+            return Ok(false);
+        }
+
+        if invoice.total_amount > Decimal::ZERO {
+            let (receivable_id, revenue_id) = self.posting_accounts(&invoice.currency).ok_or_else(|| {
+                AppError::Validation(format!(
+                    "No invoice receivable or fee revenue account for {}",
+                    invoice.currency
+                ))
+            })?;
+            let amount = invoice.total_amount;
+            let detail = serde_json::json!({
+                "invoice_id": invoice.id,
+                "account_id": invoice.account_id,
+                "period_start": invoice.period_start,
+            });
+
+            let transaction = TransactionRecord::new(
+                format!("INV-{}", invoice.id),
+                TransactionType::Fee,
+                receivable_id,
+                revenue_id,
+                amount,
+                invoice.currency.clone(),
+                Decimal::ZERO,
+                format!("invoice:{}", invoice.id),
+            )
+            .with_narrative(
+                Some(format!("Invoice for {} to {}", invoice.period_start, invoice.period_end)),
+                Some(invoice.id.to_string()),
+            )
+            .with_metadata(detail.clone());
+            let transaction = TransactionRepository::create_in(&mut tx, &transaction).await?;
+
+            let effective_date = Utc::now().date_naive();
+            let receivable = BalanceRepository::adjust_in(&mut tx, receivable_id, &invoice.currency, -amount).await?;
+            let revenue = BalanceRepository::adjust_in(&mut tx, revenue_id, &invoice.currency, amount).await?;
+            let debit = LedgerEntry::debit(
+                transaction.id,
+                receivable_id,
+                amount,
+                invoice.currency.clone(),
+                receivable.available_balance,
+                effective_date,
+            )
+            .with_narrative(transaction.narrative.clone(), transaction.reference.clone())
+            .with_metadata(detail.clone());
+            let credit = LedgerEntry::credit(
+                transaction.id,
+                revenue_id,
+                amount,
+                invoice.currency.clone(),
+                revenue.available_balance,
+                effective_date,
+            )
+            .with_narrative(transaction.narrative.clone(), transaction.reference.clone())
+            .with_metadata(detail);
+            LedgerRepository::create_in(&mut tx, &debit).await?;
+            LedgerRepository::create_in(&mut tx, &credit).await?;
+
+            let transaction = TransactionRepository::mark_settled_in(&mut tx, transaction.id).await?;
+            enqueue_settled_event(&mut tx, &transaction).await?;
+            InvoiceRepository::set_transaction_in(&mut tx, invoice.id, transaction.id).await?;
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(true)
+    }
+}
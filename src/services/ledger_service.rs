@@ -1,14 +1,34 @@
+use crate::core::money::{Money, MoneyError};
 use crate::error::{AppError, Result};
+use crate::events::{enqueue_event, enqueue_settled_event, AmountLimitBreachedEvent, EventEnvelope, EventType};
 use crate::models::{
-    Account, AccountBalance, LedgerEntry, TransactionRecord, TransactionStatus, TransactionType,
+    Account, AccountBalance, AccountHistory, BalanceReservationStatus, DuplicateExternalIdAction, ExternalIdClaim, ExternalIdScope, FeeBookingMode, FeeReversalPolicy, InternalAccountRole,
+    LedgerEntry, RiskHoldStatus, RiskTrigger, SettlementRoute, TransactionAuditAction, TransactionAuditEntry, TransactionPriority,
+    TransactionRecord, TransactionStatus, TransactionType,
+};
+use crate::notifications::{NotificationEngine, SettlementFailure};
+use crate::observability::{get_metrics, timed};
+use crate::repositories::{
+    AccountRepository, BalanceRepository, BalanceReservationRepository, InternalAccountRepository, LedgerRepository,
+    TransactionAuditRepository, TransactionRepository,
+};
+use crate::services::double_entry_engine::TransactionRequest;
+use crate::services::{
+    AccountingPeriodService, BatchAssignment, BatchService, ChartOfAccountsService, ChartRegistry, CounterpartyService,
+    InstrumentService, MetadataSchemaService, RiskService, RtgsService, TransactionTypeService, WriteCombiner,
 };
-use crate::repositories::{AccountRepository, BalanceRepository, LedgerRepository, TransactionRepository};
 use chrono::{NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Code of validation errors raised by the amount guard rails, which fail with
+/// `AmountLimitExceeded` rather than a plain validation error.
+pub const AMOUNT_LIMIT_EXCEEDED: &str = "AMOUNT_LIMIT_EXCEEDED";
+
 /// Validation error details.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationError {
@@ -53,6 +73,23 @@ impl ValidationResult {
         self.is_valid = false;
         self.errors.push(error);
     }
+
+    /// Converts a failed validation into a single error listing every field message.
+    /// Amount guard rail breaches take precedence, so that they surface with their own code.
+    pub fn into_result(self) -> Result<()> {
+        if self.is_valid {
+            return Ok(());
+        }
+        let error_messages: Vec<String> = self
+            .errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+        if self.errors.iter().any(|e| e.code == AMOUNT_LIMIT_EXCEEDED) {
+            return Err(AppError::AmountLimitExceeded(error_messages.join("; ")));
+        }
+        Err(AppError::Validation(error_messages.join("; ")))
+    }
 }
 
 /// Transaction state machine for managing status transitions.
@@ -66,12 +103,14 @@ impl TransactionStateMachine {
             TransactionStatus::Pending => vec![
                 TransactionStatus::Settled,
                 TransactionStatus::Failed,
+                TransactionStatus::Expired,
             ],
             TransactionStatus::Settled => vec![
                 TransactionStatus::Reversed,
             ],
             TransactionStatus::Failed => vec![], // Terminal state
             TransactionStatus::Reversed => vec![], // Terminal state
+            TransactionStatus::Expired => vec![], // Terminal state
         }
     }
 
@@ -107,6 +146,28 @@ pub struct LedgerTransactionRequest {
     pub effective_date: Option<NaiveDate>,
     pub metadata: Option<serde_json::Value>,
     pub original_transaction_id: Option<Uuid>,
+    pub priority: TransactionPriority,
+    /// Upstream system submitting the transaction, for scoping external ID uniqueness.
+    #[serde(default)]
+    pub source_system: Option<String>,
+    /// Registered custom type the transaction posts as; it must have `transaction_type`
+    /// as its base type.
+    #[serde(default)]
+    pub type_code: Option<String>,
+    /// Settles the transaction gross rather than netting it with its batch.
+    #[serde(default)]
+    pub exclude_from_netting: bool,
+    /// Earlier transaction this one intentionally repeats, as reported by the duplicate
+    /// payment rule; that match no longer counts as a duplicate.
+    #[serde(default)]
+    pub duplicate_override: Option<Uuid>,
+    /// Description for the transaction and its ledger entries; by default the narrative
+    /// template of its type.
+    #[serde(default)]
+    pub narrative: Option<String>,
+    /// Counterparty's reference, e.g. an invoice number, copied to the ledger entries.
+    #[serde(default)]
+    pub reference: Option<String>,
 }
 
 impl LedgerTransactionRequest {
@@ -130,6 +191,13 @@ impl LedgerTransactionRequest {
             effective_date: None,
             metadata: None,
             original_transaction_id: None,
+            priority: TransactionPriority::Normal,
+            source_system: None,
+            type_code: None,
+            exclude_from_netting: false,
+            duplicate_override: None,
+            narrative: None,
+            reference: None,
         }
     }
 
@@ -153,6 +221,13 @@ impl LedgerTransactionRequest {
             effective_date: None,
             metadata: None,
             original_transaction_id: None,
+            priority: TransactionPriority::Normal,
+            source_system: None,
+            type_code: None,
+            exclude_from_netting: false,
+            duplicate_override: None,
+            narrative: None,
+            reference: None,
         }
     }
 
@@ -176,6 +251,13 @@ impl LedgerTransactionRequest {
             effective_date: None,
             metadata: None,
             original_transaction_id: None,
+            priority: TransactionPriority::Normal,
+            source_system: None,
+            type_code: None,
+            exclude_from_netting: false,
+            duplicate_override: None,
+            narrative: None,
+            reference: None,
         }
     }
 
@@ -200,6 +282,13 @@ impl LedgerTransactionRequest {
             effective_date: None,
             metadata: None,
             original_transaction_id: Some(original_transaction_id),
+            priority: TransactionPriority::Normal,
+            source_system: None,
+            type_code: None,
+            exclude_from_netting: false,
+            duplicate_override: None,
+            narrative: None,
+            reference: None,
         }
     }
 
@@ -224,6 +313,13 @@ impl LedgerTransactionRequest {
             effective_date: None,
             metadata: None,
             original_transaction_id: Some(original_transaction_id),
+            priority: TransactionPriority::Normal,
+            source_system: None,
+            type_code: None,
+            exclude_from_netting: false,
+            duplicate_override: None,
+            narrative: None,
+            reference: None,
         }
     }
 
@@ -242,9 +338,49 @@ impl LedgerTransactionRequest {
         self
     }
 
+    pub fn with_priority(mut self, priority: TransactionPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_source_system(mut self, source_system: impl Into<String>) -> Self {
+        self.source_system = Some(source_system.into());
+        self
+    }
+
+    pub fn with_type_code(mut self, type_code: impl Into<String>) -> Self {
+        self.type_code = Some(type_code.into());
+        self
+    }
+
+    pub fn excluded_from_netting(mut self) -> Self {
+        self.exclude_from_netting = true;
+        self
+    }
+
+    pub fn with_narrative(mut self, narrative: impl Into<String>) -> Self {
+        self.narrative = Some(narrative.into());
+        self
+    }
+
+    pub fn with_reference(mut self, reference: impl Into<String>) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    pub fn with_duplicate_override(mut self, transaction_id: Uuid) -> Self {
+        self.duplicate_override = Some(transaction_id);
+        self
+    }
+
     pub fn net_amount(&self) -> Decimal {
         self.amount - self.fee_amount
     }
+
+    /// The gross amount of the request, in its currency.
+    pub fn money(&self) -> std::result::Result<Money, MoneyError> {
+        Money::new(self.amount, self.currency.as_str())
+    }
 }
 
 /// Result of a ledger transaction.
@@ -254,6 +390,218 @@ pub struct LedgerTransactionResult {
     pub entries: Vec<LedgerEntry>,
     pub source_balance: AccountBalance,
     pub destination_balance: AccountBalance,
+    /// Batch the transaction was assigned to when it settled, if automatic batch
+    /// assignment is enabled.
+    pub batch_assignment: Option<BatchAssignment>,
+}
+
+/// Request to settle a securities trade delivery-versus-payment: the seller delivers a
+/// quantity of a security to the buyer, who pays for it in cash, and both legs settle or
+/// neither does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DvpRequest {
+    pub external_id: String,
+    /// Security delivered and its quantity.
+    pub instrument: String,
+    pub quantity: Decimal,
+    pub seller_securities_account_id: Uuid,
+    pub buyer_securities_account_id: Uuid,
+    /// Cash paid for the delivery.
+    pub amount: Decimal,
+    pub currency: String,
+    pub buyer_cash_account_id: Uuid,
+    pub seller_cash_account_id: Uuid,
+    pub idempotency_key: String,
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+impl DvpRequest {
+    /// Transfer delivering the securities, keyed `{idempotency_key}:SECURITIES`.
+    pub fn securities_leg(&self) -> LedgerTransactionRequest {
+        let mut leg = LedgerTransactionRequest::transfer(
+            format!("{}:SECURITIES", self.external_id),
+            self.seller_securities_account_id,
+            self.buyer_securities_account_id,
+            self.quantity,
+            self.instrument.clone(),
+            format!("{}:SECURITIES", self.idempotency_key),
+        );
+        leg.reference = self.reference.clone();
+        leg
+    }
+
+    /// Transfer paying for them, keyed `{idempotency_key}:CASH`.
+    pub fn cash_leg(&self) -> LedgerTransactionRequest {
+        let mut leg = LedgerTransactionRequest::transfer(
+            format!("{}:CASH", self.external_id),
+            self.buyer_cash_account_id,
+            self.seller_cash_account_id,
+            self.amount,
+            self.currency.clone(),
+            format!("{}:CASH", self.idempotency_key),
+        );
+        leg.reference = self.reference.clone();
+        leg
+    }
+}
+
+/// A settled delivery-versus-payment trade.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DvpSettlement {
+    /// Link shared by the two legs.
+    pub link_id: Uuid,
+    pub securities: LedgerTransactionResult,
+    pub cash: LedgerTransactionResult,
+}
+
+/// What executing a transaction would do, worked out without writing anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionDryRun {
+    /// True if the transaction would post; false if any check failed.
+    pub feasible: bool,
+    /// Every check the request fails, in pipeline order.
+    pub errors: Vec<ValidationError>,
+    /// Lane the transaction would settle through.
+    pub route: SettlementRoute,
+    pub amount: Decimal,
+    pub fee_amount: Decimal,
+    pub net_amount: Decimal,
+    /// Account the fee would be booked to, if any.
+    pub fee_account_id: Option<Uuid>,
+    /// Risk rules the request trips; with risk review enabled it would be held.
+    pub risk_triggers: Vec<RiskTrigger>,
+    pub held_for_review: bool,
+    /// Ledger entries the transaction would post. Their IDs are not reserved.
+    pub entries: Vec<LedgerEntry>,
+    /// Balances after the transaction, for the accounts that were found.
+    pub source_balance: Option<AccountBalance>,
+    pub destination_balance: Option<AccountBalance>,
+    /// Transaction already posted under the idempotency key, which a retry would return.
+    pub existing_transaction_id: Option<Uuid>,
+}
+
+/// Where fees are booked and what happens to them when a payment is reversed.
+#[derive(Debug, Clone, Default)]
+pub struct FeeConfig {
+    /// Fee revenue account per currency. Fees in a currency without one are not booked
+    /// to any account: the payer is debited gross and the payee credited net.
+    pub revenue_accounts: HashMap<String, Uuid>,
+    /// Policy applied to reversals that do not request one.
+    pub reversal_policy: FeeReversalPolicy,
+    /// Whether fees are revenue as they are charged or once their batch completes.
+    pub booking: FeeBookingMode,
+    /// Fees-receivable account per currency that accrued fees are booked to. Currencies
+    /// without one, or without a revenue account, book fees immediately.
+    pub receivable_accounts: HashMap<String, Uuid>,
+    /// Account per currency that invoiced amounts are owed to until paid.
+    pub invoice_receivable_accounts: HashMap<String, Uuid>,
+    /// Chart of internal accounts consulted for currencies without an account above,
+    /// including currencies provisioned after startup.
+    pub chart: ChartRegistry,
+}
+
+impl FeeConfig {
+    pub fn revenue_account(&self, currency: &str) -> Option<Uuid> {
+        self.revenue_accounts
+            .get(currency)
+            .copied()
+            .or_else(|| self.chart.role_account(InternalAccountRole::FeeRevenue, currency))
+    }
+
+    /// Returns the fees-receivable account fees in a currency accrue to, if they accrue.
+    pub fn accrual_account(&self, currency: &str) -> Option<Uuid> {
+        if self.booking != FeeBookingMode::Accrued || self.revenue_account(currency).is_none() {
+            return None;
+        }
+        self.receivable_accounts
+            .get(currency)
+            .copied()
+            .or_else(|| self.chart.role_account(InternalAccountRole::FeeReceivable, currency))
+    }
+
+    /// Returns the account invoices in a currency are posted to as receivables.
+    pub fn invoice_receivable_account(&self, currency: &str) -> Option<Uuid> {
+        self.invoice_receivable_accounts
+            .get(currency)
+            .copied()
+            .or_else(|| self.chart.role_account(InternalAccountRole::InvoiceReceivable, currency))
+    }
+
+    /// Falls back to the chart of internal accounts for currencies without a fee
+    /// account configured directly.
+    pub fn with_chart(mut self, chart: ChartRegistry) -> Self {
+        self.chart = chart;
+        self
+    }
+}
+
+/// Decimal places of the ledger's amount columns.
+pub const DEFAULT_MAX_AMOUNT_SCALE: u32 = crate::core::money::MAX_MONEY_SCALE;
+
+/// Upper bounds on transaction amounts, guarding settlement against fat-finger entries.
+/// Amounts have no maximum unless one is configured.
+#[derive(Debug, Clone)]
+pub struct AmountLimits {
+    /// Maximum amount in currencies without a maximum of their own.
+    pub default_max: Option<Decimal>,
+    /// Maximum amount per currency, overriding the default.
+    pub currency_max: HashMap<String, Decimal>,
+    /// Most decimal places an amount or fee may have.
+    pub max_scale: u32,
+}
+
+impl Default for AmountLimits {
+    fn default() -> Self {
+        Self {
+            default_max: None,
+            currency_max: HashMap::new(),
+            max_scale: DEFAULT_MAX_AMOUNT_SCALE,
+        }
+    }
+}
+
+/// An amount rejected by the guard rails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmountLimitBreach {
+    pub field: &'static str,
+    /// Maximum the amount exceeded; `None` when its scale was too fine.
+    pub limit: Option<Decimal>,
+    pub reason: String,
+}
+
+impl AmountLimits {
+    pub fn max_for(&self, currency: &str) -> Option<Decimal> {
+        self.currency_max.get(currency).copied().or(self.default_max)
+    }
+
+    /// Returns the first limit the amount or fee breaches, if any.
+    pub fn check(&self, amount: Decimal, fee_amount: Decimal, currency: &str) -> Option<AmountLimitBreach> {
+        if let Some(max) = self.max_for(currency) {
+            if amount > max {
+                return Some(AmountLimitBreach {
+                    field: "amount",
+                    limit: Some(max),
+                    reason: format!("Amount {} {} exceeds the maximum of {} {}", amount, currency, max, currency),
+                });
+            }
+        }
+        [("amount", amount), ("fee_amount", fee_amount)]
+            .into_iter()
+            .find(|(_, value)| value.normalize().scale() > self.max_scale)
+            .map(|(field, value)| AmountLimitBreach {
+                field,
+                limit: None,
+                reason: format!("{} {} has more than {} decimal places", field, value, self.max_scale),
+            })
+    }
+}
+
+/// How external IDs are deduplicated.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExternalIdPolicy {
+    pub scope: ExternalIdScope,
+    pub on_duplicate: DuplicateExternalIdAction,
 }
 
 /// The ledger service handles all ledger operations including transaction processing,
@@ -264,6 +612,41 @@ pub struct LedgerService {
     balance_repo: BalanceRepository,
     ledger_repo: LedgerRepository,
     transaction_repo: TransactionRepository,
+    internal_accounts: InternalAccountRepository,
+    counterparties: CounterpartyService,
+    metadata_schemas: MetadataSchemaService,
+    transaction_types: TransactionTypeService,
+    periods: AccountingPeriodService,
+    notifications: Option<Arc<NotificationEngine>>,
+    rtgs: Option<Arc<RtgsService>>,
+    batching: Option<Arc<BatchService>>,
+    risk: Option<Arc<RiskService>>,
+    fees: Arc<FeeConfig>,
+    external_ids: ExternalIdPolicy,
+    amount_limits: Arc<AmountLimits>,
+    write_combiner: Option<Arc<WriteCombiner>>,
+    chart: Option<Arc<ChartOfAccountsService>>,
+    instruments: InstrumentService,
+}
+
+/// A request admitted to a combined write, with what its preflight checks resolved.
+struct CombinedWrite {
+    index: usize,
+    request: LedgerTransactionRequest,
+    claim: ExternalIdClaim,
+    fee_account_id: Option<Uuid>,
+    narrative: String,
+}
+
+/// Outcome of the checks a request goes through before it is written.
+enum Preflight {
+    /// A transaction with the request's idempotency key already exists.
+    Existing(Box<LedgerTransactionResult>),
+    Ready {
+        claim: ExternalIdClaim,
+        fee_account_id: Option<Uuid>,
+        narrative: String,
+    },
 }
 
 impl LedgerService {
@@ -273,10 +656,84 @@ impl LedgerService {
             balance_repo: BalanceRepository::new(pool.clone()),
             ledger_repo: LedgerRepository::new(pool.clone()),
             transaction_repo: TransactionRepository::new(pool.clone()),
+            internal_accounts: InternalAccountRepository::new(pool.clone()),
+            counterparties: CounterpartyService::new(pool.clone()),
+            metadata_schemas: MetadataSchemaService::new(pool.clone()),
+            transaction_types: TransactionTypeService::new(pool.clone()),
+            periods: AccountingPeriodService::new(pool.clone()),
+            instruments: InstrumentService::new(pool.clone()),
             pool,
+            notifications: None,
+            rtgs: None,
+            batching: None,
+            risk: None,
+            fees: Arc::new(FeeConfig::default()),
+            external_ids: ExternalIdPolicy::default(),
+            amount_limits: Arc::new(AmountLimits::default()),
+            write_combiner: None,
+            chart: None,
         }
     }
 
+    /// Evaluates alert rules for every transaction processed through `process_transaction`.
+    pub fn with_notifications(mut self, engine: Arc<NotificationEngine>) -> Self {
+        self.notifications = Some(engine);
+        self
+    }
+
+    /// Routes transactions above the RTGS threshold through the real-time gross lane.
+    pub fn with_rtgs(mut self, rtgs: Arc<RtgsService>) -> Self {
+        self.rtgs = Some(rtgs);
+        self
+    }
+
+    /// Assigns netted transactions to the current open batch for their currency as they
+    /// settle, instead of waiting for an explicit assignment.
+    pub fn with_batching(mut self, batching: Arc<BatchService>) -> Self {
+        self.batching = Some(batching);
+        self
+    }
+
+    /// Screens payments and transfers against the risk rules before posting, holding
+    /// those that trip one for review.
+    pub fn with_risk(mut self, risk: Arc<RiskService>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Books fees to a revenue account and sets the default fee reversal policy.
+    pub fn with_fees(mut self, fees: Arc<FeeConfig>) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Sets the scope external IDs are unique in and what happens to duplicates.
+    pub fn with_external_ids(mut self, policy: ExternalIdPolicy) -> Self {
+        self.external_ids = policy;
+        self
+    }
+
+    /// Rejects transactions above their currency's maximum amount or with too many
+    /// decimal places.
+    pub fn with_amount_limits(mut self, limits: Arc<AmountLimits>) -> Self {
+        self.amount_limits = limits;
+        self
+    }
+
+    /// Hands netted postings to a write-combining pipeline, which writes transactions
+    /// arriving close together in one database transaction.
+    pub fn with_write_combining(mut self, combiner: Arc<WriteCombiner>) -> Self {
+        self.write_combiner = Some(combiner);
+        self
+    }
+
+    /// Provisions the chart's system accounts in a currency the first time a transaction
+    /// uses it.
+    pub fn with_chart_of_accounts(mut self, chart: Arc<ChartOfAccountsService>) -> Self {
+        self.chart = Some(chart);
+        self
+    }
+
     /// Validates a transaction request through the validation pipeline.
     pub async fn validate_transaction(&self, request: &LedgerTransactionRequest) -> Result<ValidationResult> {
         let mut result = ValidationResult::valid();
@@ -322,12 +779,34 @@ impl LedgerService {
             ));
         }
 
-        if request.currency.len() != 3 {
-            result.add_error(ValidationError::new(
-                "currency",
-                "Currency must be a 3-letter ISO code",
-                "INVALID_CURRENCY",
-            ));
+        // Amount guard rails
+        if let Some(breach) = self.amount_limits.check(request.amount, request.fee_amount, &request.currency) {
+            result.add_error(ValidationError::new(breach.field, breach.reason, AMOUNT_LIMIT_EXCEEDED));
+        }
+
+        // Securities move as whole quantities at their registered scale, without fees
+        match self.instruments.resolve(&request.currency).await {
+            Ok(instrument) if instrument.is_security() => {
+                if let Err(violation) = instrument.validate_quantity(request.amount) {
+                    result.add_error(ValidationError::new("amount", violation, "INVALID_QUANTITY"));
+                }
+                if request.fee_amount != Decimal::ZERO {
+                    result.add_error(ValidationError::new(
+                        "fee_amount",
+                        format!("Transfers of security {} cannot carry a fee", instrument.code),
+                        "FEE_NOT_ALLOWED",
+                    ));
+                }
+            }
+            Ok(_) => {}
+            Err(AppError::Validation(_)) => {
+                result.add_error(ValidationError::new(
+                    "currency",
+                    "Currency must be a 3-letter ISO code or a registered instrument",
+                    "INVALID_CURRENCY",
+                ));
+            }
+            Err(e) => return Err(e),
         }
 
         if request.idempotency_key.is_empty() {
@@ -338,23 +817,183 @@ impl LedgerService {
             ));
         }
 
-        // Transaction type specific validation
-        match request.transaction_type {
-            TransactionType::Refund | TransactionType::Chargeback => {
-                if request.original_transaction_id.is_none() {
+        // Backdated entries must fall in an open accounting period
+        if let Some(effective_date) = request.effective_date {
+            if effective_date > Utc::now().date_naive() {
+                result.add_error(ValidationError::new(
+                    "effective_date",
+                    "Effective date cannot be in the future",
+                    "FUTURE_EFFECTIVE_DATE",
+                ));
+            } else if let Some(violation) = self.periods.check_postable(effective_date).await? {
+                result.add_error(ValidationError::new("effective_date", violation, "PERIOD_CLOSED"));
+            }
+        }
+
+        // Behavior registered for the transaction's type
+        match self
+            .transaction_types
+            .resolve(request.transaction_type, request.type_code.as_deref())
+            .await
+        {
+            Ok(definition) => {
+                if definition.requires_original && request.original_transaction_id.is_none() {
                     result.add_error(ValidationError::new(
                         "original_transaction_id",
-                        "Original transaction ID is required for refunds and chargebacks",
+                        format!("Original transaction ID is required for {} transactions", definition.code),
                         "REQUIRED_FIELD",
                     ));
                 }
+                if !definition.allows_fee() && request.fee_amount > Decimal::ZERO {
+                    result.add_error(ValidationError::new(
+                        "fee_amount",
+                        format!("{} transactions cannot carry a fee", definition.code),
+                        "FEE_NOT_ALLOWED",
+                    ));
+                }
+            }
+            Err(AppError::Validation(message)) => {
+                result.add_error(ValidationError::new("type_code", message, "INVALID_TRANSACTION_TYPE"));
+            }
+            Err(e) => return Err(e),
+        }
+
+        // Metadata schema registered for the transaction type, if any
+        for violation in self
+            .metadata_schemas
+            .validate_metadata(request.transaction_type, request.metadata.as_ref())
+            .await?
+        {
+            result.add_error(ValidationError::new("metadata", violation, "INVALID_METADATA"));
+        }
+
+        // Control accounts of the chart of internal accounts only move through system flows
+        for entry in self
+            .internal_accounts
+            .find_by_accounts(&[request.source_account_id, request.destination_account_id])
+            .await?
+        {
+            if entry.control {
+                let field = if entry.account_id == request.source_account_id {
+                    "source_account_id"
+                } else {
+                    "destination_account_id"
+                };
+                result.add_error(ValidationError::new(
+                    field,
+                    format!("Account is internal control account {} and only moves through system flows", entry.code),
+                    "CONTROL_ACCOUNT",
+                ));
+            }
+        }
+
+        // Counterparty allow/deny lists of either account
+        if request.source_account_id != request.destination_account_id {
+            if let Some(violation) = self
+                .counterparties
+                .check_pair(request.source_account_id, request.destination_account_id)
+                .await?
+            {
+                result.add_error(ValidationError::new(
+                    "destination_account_id",
+                    violation,
+                    "COUNTERPARTY_RESTRICTED",
+                ));
             }
-            _ => {}
         }
 
         Ok(result)
     }
 
+    /// Validates a request that is about to be accepted, failing if it is invalid. An
+    /// amount guard rail breach is also logged, counted and raised as an
+    /// `AMOUNT_LIMIT_BREACHED` event on the alerts topic.
+    pub async fn ensure_valid(&self, request: &LedgerTransactionRequest) -> Result<()> {
+        let result = self.validate_transaction(request).await?;
+        if let Some(breach) = self.amount_limits.check(request.amount, request.fee_amount, &request.currency) {
+            self.raise_amount_limit_breach(request, &breach).await;
+        }
+        result.into_result()
+    }
+
+    /// Alerting never changes the outcome: failures to queue the event are logged.
+    async fn raise_amount_limit_breach(&self, request: &LedgerTransactionRequest, breach: &AmountLimitBreach) {
+        tracing::warn!(
+            "Rejected transaction {} from account {}: {}",
+            request.external_id,
+            request.source_account_id,
+            breach.reason
+        );
+        let kind = if breach.limit.is_some() { "max_amount" } else { "scale" };
+        get_metrics().record_amount_limit_breach(&request.currency, kind);
+
+        let event = AmountLimitBreachedEvent {
+            external_id: request.external_id.clone(),
+            source_account_id: request.source_account_id,
+            destination_account_id: request.destination_account_id,
+            amount: request.amount,
+            currency: request.currency.clone(),
+            limit: breach.limit,
+            max_scale: self.amount_limits.max_scale,
+            reason: breach.reason.clone(),
+            occurred_at: Utc::now(),
+        };
+        let envelope = EventEnvelope::new(EventType::AmountLimitBreached, event);
+        let queued = async {
+            let mut tx = self.pool.begin().await?;
+            enqueue_event(
+                &mut tx,
+                AmountLimitBreachedEvent::topic(),
+                Some(request.source_account_id.to_string()),
+                &envelope,
+            )
+            .await?;
+            tx.commit().await?;
+            Ok::<_, AppError>(())
+        };
+        if let Err(e) = queued.await {
+            tracing::warn!("Failed to queue amount limit alert for {}: {}", request.external_id, e);
+        }
+    }
+
+    /// Checks the request's external ID against earlier transactions in its scope. A
+    /// duplicate is rejected or, if the policy links duplicates, claimed as a resubmission
+    /// of the first transaction with the ID. A retry under the same idempotency key is not
+    /// a duplicate.
+    pub async fn claim_external_id(&self, request: &LedgerTransactionRequest) -> Result<ExternalIdClaim> {
+        let dedupe_key = self.external_ids.scope.dedupe_key(
+            &request.external_id,
+            request.source_system.as_deref(),
+            Utc::now().date_naive(),
+        );
+        let source_system = request.source_system.clone();
+
+        let original = self
+            .transaction_repo
+            .find_by_dedupe_key(&dedupe_key)
+            .await?
+            .filter(|original| original.idempotency_key != request.idempotency_key);
+        let Some(original) = original else {
+            return Ok(ExternalIdClaim {
+                source_system,
+                dedupe_key: Some(dedupe_key),
+                resubmission_of: None,
+            });
+        };
+
+        match self.external_ids.on_duplicate {
+            DuplicateExternalIdAction::Reject => Err(AppError::Validation(format!(
+                "external_id: External ID '{}' was already used by transaction '{}'",
+                request.external_id, original.id
+            ))),
+            DuplicateExternalIdAction::Link => Ok(ExternalIdClaim {
+                source_system,
+                dedupe_key: None,
+                resubmission_of: Some(original.id),
+            }),
+        }
+    }
+
     /// Verifies that an account exists and is operational.
     pub async fn verify_account(&self, account_id: Uuid) -> Result<Account> {
         let account = self
@@ -364,9 +1003,11 @@ impl LedgerService {
             .ok_or_else(|| AppError::NotFound(format!("Account '{}' not found", account_id)))?;
 
         if !account.status.is_operational() {
-            return Err(AppError::Validation(format!(
-                "Account '{}' is not operational (status: {:?})",
-                account_id, account.status
+            let change = self.account_repo.latest_status_change(account_id).await?;
+            return Err(AppError::AccountFrozen(format!(
+                "Account '{}' is not operational ({})",
+                account_id,
+                account.status_description(change.as_ref())
             )));
         }
 
@@ -377,9 +1018,9 @@ impl LedgerService {
     pub async fn check_sufficient_funds(
         &self,
         account_id: Uuid,
-        currency: &str,
-        amount: Decimal,
+        amount: &Money,
     ) -> Result<AccountBalance> {
+        let currency = amount.currency();
         let balance = self
             .balance_repo
             .find_by_account_and_currency(account_id, currency)
@@ -391,8 +1032,8 @@ impl LedgerService {
                 ))
             })?;
 
-        if !balance.has_sufficient_funds(amount) {
-            return Err(AppError::Validation(format!(
+        if !balance.has_sufficient_funds(amount.amount()) {
+            return Err(AppError::InsufficientFunds(format!(
                 "Insufficient funds: requested {}, available {}",
                 amount,
                 balance.usable_balance()
@@ -451,10 +1092,11 @@ impl LedgerService {
             )));
         }
 
-        if !original.transaction_type.is_reversible() {
+        let definition = self.transaction_types.resolve_record(&original).await?;
+        if !definition.reversible {
             return Err(AppError::Validation(format!(
-                "Transaction type {:?} cannot be refunded",
-                original.transaction_type
+                "Transaction type {} cannot be refunded",
+                definition.code
             )));
         }
 
@@ -499,72 +1141,473 @@ impl LedgerService {
 
     /// Executes a transaction with full validation and ACID compliance.
     pub async fn execute_transaction(&self, request: LedgerTransactionRequest) -> Result<LedgerTransactionResult> {
-        // Run validation pipeline
-        let validation = self.validate_transaction(&request).await?;
-        if !validation.is_valid {
-            let error_messages: Vec<String> = validation
-                .errors
-                .iter()
-                .map(|e| format!("{}: {}", e.field, e.message))
-                .collect();
-            return Err(AppError::Validation(error_messages.join("; ")));
+        if let Some(combiner) = &self.write_combiner {
+            return combiner.submit(request).await;
         }
+        self.execute(request, None).await
+    }
 
-        // Check idempotency
+    /// Executes several transactions with one database transaction doing the writes, and
+    /// returns each request's outcome in request order.
+    ///
+    /// Each request is validated on its own first. The balances of every account involved
+    /// are then locked together and funds are checked request by request in order, so a
+    /// request that would overdraw fails alone and later requests see the balances as if
+    /// each had been posted separately. Transactions, ledger entries and audit entries are
+    /// written with multi-row inserts and balances with one set-based update. Requests
+    /// reusing an idempotency key or external ID of an earlier one in the group, and every
+    /// later request touching their accounts, are posted on their own afterwards, which
+    /// keeps each account's postings in request order. If the combined write fails as a
+    /// whole, its requests are posted one by one instead so each gets its own outcome.
+    pub async fn execute_combined(&self, requests: Vec<LedgerTransactionRequest>) -> Vec<Result<LedgerTransactionResult>> {
+        let mut outcomes: Vec<Option<Result<LedgerTransactionResult>>> = requests.iter().map(|_| None).collect();
+        let mut combined = Vec::new();
+        let mut deferred = Vec::new();
+        let mut deferred_accounts = HashSet::new();
+        let mut idempotency_keys = HashSet::new();
+        let mut dedupe_keys = HashSet::new();
+
+        for (index, request) in requests.into_iter().enumerate() {
+            match self.preflight(&request).await {
+                Err(e) => outcomes[index] = Some(Err(e)),
+                Ok(Preflight::Existing(result)) => outcomes[index] = Some(Ok(*result)),
+                Ok(Preflight::Ready { claim, fee_account_id, narrative }) => {
+                    let accounts = [request.source_account_id, request.destination_account_id];
+                    let duplicate = !idempotency_keys.insert(request.idempotency_key.clone())
+                        || claim.dedupe_key.as_ref().is_some_and(|key| !dedupe_keys.insert(key.clone()));
+                    if duplicate || accounts.iter().any(|account| deferred_accounts.contains(account)) {
+                        deferred_accounts.extend(accounts);
+                        deferred.push((index, request));
+                    } else {
+                        combined.push(CombinedWrite {
+                            index,
+                            request,
+                            claim,
+                            fee_account_id,
+                            narrative,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !combined.is_empty() {
+            match self.write_combined(&combined, None).await {
+                Ok(results) => {
+                    for (write, result) in combined.iter().zip(results) {
+                        outcomes[write.index] = Some(result);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Combined write of {} transactions failed, posting them one by one: {}",
+                        combined.len(),
+                        e
+                    );
+                    for write in combined {
+                        outcomes[write.index] = Some(self.execute(write.request, None).await);
+                    }
+                }
+            }
+        }
+        for (index, request) in deferred {
+            outcomes[index] = Some(self.execute(request, None).await);
+        }
+
+        outcomes
+            .into_iter()
+            .map(|outcome| outcome.expect("every request has an outcome"))
+            .collect()
+    }
+
+    /// Executes transactions that settle together, all or nothing, in one database
+    /// transaction, and returns their results in request order.
+    ///
+    /// Every request is validated and funds are checked against the locked balances in
+    /// order; if any request fails, nothing is posted. The transactions share `link_id`,
+    /// settle gross and are never batched. Retrying a group whose transactions were all
+    /// posted under one link returns them; a group only some of which were posted, or
+    /// that were posted on their own, is rejected.
+    pub async fn execute_linked(
+        &self,
+        link_id: Uuid,
+        requests: Vec<LedgerTransactionRequest>,
+    ) -> Result<Vec<LedgerTransactionResult>> {
+        if requests.is_empty() {
+            return Err(AppError::Validation("A linked group needs at least one transaction".to_string()));
+        }
+        let mut idempotency_keys = HashSet::new();
+        if !requests.iter().all(|request| idempotency_keys.insert(request.idempotency_key.clone())) {
+            return Err(AppError::Validation(
+                "Linked transactions must have distinct idempotency keys".to_string(),
+            ));
+        }
+
+        let mut existing = Vec::new();
+        let mut writes = Vec::new();
+        for (index, request) in requests.into_iter().enumerate() {
+            match self.preflight(&request).await? {
+                Preflight::Existing(result) => existing.push(*result),
+                Preflight::Ready { claim, fee_account_id, narrative } => writes.push(CombinedWrite {
+                    index,
+                    request,
+                    claim,
+                    fee_account_id,
+                    narrative,
+                }),
+            }
+        }
+        let links: HashSet<Option<Uuid>> = existing.iter().map(|result| result.transaction.link_id).collect();
+        if writes.is_empty() && links.len() == 1 && !links.contains(&None) {
+            return Ok(existing);
+        }
+        if !existing.is_empty() {
+            return Err(AppError::Validation(format!(
+                "{} of the linked transactions were already posted on their own",
+                existing.len()
+            )));
+        }
+
+        self.write_combined(&writes, Some(link_id))
+            .await?
+            .into_iter()
+            .collect()
+    }
+
+    /// Settles a securities trade delivery-versus-payment: the securities leg and the
+    /// cash leg are linked, so the buyer only receives the securities if the seller is
+    /// paid, and the other way round.
+    pub async fn settle_dvp(&self, request: DvpRequest) -> Result<DvpSettlement> {
+        if !self.instruments.resolve(&request.instrument).await?.is_security() {
+            return Err(AppError::Validation(format!(
+                "instrument: '{}' is not a registered security",
+                request.instrument
+            )));
+        }
+        if self.instruments.resolve(&request.currency).await?.is_security() {
+            return Err(AppError::Validation(format!(
+                "currency: '{}' is a security, not a currency",
+                request.currency
+            )));
+        }
+
+        let link_id = Uuid::new_v4();
+        let mut legs = self
+            .execute_linked(link_id, vec![request.securities_leg(), request.cash_leg()])
+            .await?
+            .into_iter();
+        let (Some(securities), Some(cash)) = (legs.next(), legs.next()) else {
+            return Err(AppError::Internal(anyhow::anyhow!("Linked settlement did not return both legs")));
+        };
+        Ok(DvpSettlement {
+            link_id: securities.transaction.link_id.unwrap_or(link_id),
+            securities,
+            cash,
+        })
+    }
+
+    /// Runs the checks `execute` makes before writing, except the funds check, which a
+    /// combined write makes against the balances it holds locked.
+    async fn preflight(&self, request: &LedgerTransactionRequest) -> Result<Preflight> {
+        self.ensure_valid(request).await?;
         if let Some(existing) = self
             .transaction_repo
             .find_by_idempotency_key(&request.idempotency_key)
             .await?
         {
-            return self.build_result_from_existing(existing).await;
+            return Ok(Preflight::Existing(Box::new(self.build_result_from_existing(existing).await?)));
         }
-
-        // Verify accounts
-        let _source_account = self.verify_account(request.source_account_id).await?;
-        let _dest_account = self.verify_account(request.destination_account_id).await?;
-
-        // Get or create balances
-        let _source_balance = self
-            .balance_repo
+        let claim = self.claim_external_id(request).await?;
+        self.verify_account(request.source_account_id).await?;
+        self.verify_account(request.destination_account_id).await?;
+        self.balance_repo
             .get_or_create(request.source_account_id, &request.currency)
             .await?;
-
-        let _dest_balance = self
-            .balance_repo
+        self.balance_repo
             .get_or_create(request.destination_account_id, &request.currency)
             .await?;
+        let fee_account_id = self.fee_account_for(&request.currency, request.fee_amount).await?;
+        let narrative = self.narrative_for(request).await?;
 
-        // Check sufficient funds (except for refunds/chargebacks where destination pays back)
-        match request.transaction_type {
-            TransactionType::Refund | TransactionType::Chargeback => {
-                // For refunds/chargebacks, the destination (original receiver) pays back
-                self.check_sufficient_funds(
-                    request.source_account_id,
-                    &request.currency,
-                    request.amount,
-                )
-                .await?;
+        Ok(Preflight::Ready { claim, fee_account_id, narrative })
+    }
+
+    /// Writes a group of checked requests in one database transaction. Returns each
+    /// request's outcome, or an error if the group as a whole could not be written.
+    ///
+    /// With a `link_id` the requests are linked: each settles gross under the link, and a
+    /// request that would overdraw fails the whole group instead of failing alone.
+    async fn write_combined(
+        &self,
+        writes: &[CombinedWrite],
+        link_id: Option<Uuid>,
+    ) -> Result<Vec<Result<LedgerTransactionResult>>> {
+        let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = self.pool.begin().await.map_err(AppError::Database)?;
+
+        let mut keys: Vec<(Uuid, String)> = writes
+            .iter()
+            .flat_map(|write| {
+                let currency = &write.request.currency;
+                [Some(write.request.source_account_id), Some(write.request.destination_account_id), write.fee_account_id]
+                    .into_iter()
+                    .flatten()
+                    .map(move |account_id| (account_id, currency.clone()))
+            })
+            .collect();
+        keys.sort();
+        keys.dedup();
+        let (account_ids, currencies): (Vec<Uuid>, Vec<String>) = keys.into_iter().unzip();
+        let mut balances: HashMap<(Uuid, String), AccountBalance> =
+            BalanceRepository::lock_many_in(&mut tx, &account_ids, &currencies)
+                .await?
+                .into_iter()
+                .map(|balance| ((balance.account_id, balance.currency.clone()), balance))
+                .collect();
+
+        // Apply the requests in order to the locked balances
+        let now = Utc::now();
+        let mut deltas: Vec<((Uuid, String), Decimal)> = Vec::new();
+        let mut results: Vec<Option<Result<LedgerTransactionResult>>> = Vec::with_capacity(writes.len());
+        let mut transactions = Vec::new();
+        let mut entries = Vec::new();
+        let mut audit_entries = Vec::new();
+        let mut posted = Vec::new();
+        for write in writes {
+            let request = &write.request;
+            let effective_date = request.effective_date.unwrap_or_else(|| now.date_naive());
+            let source = balances
+                .get(&(request.source_account_id, request.currency.clone()))
+                .ok_or_else(|| AppError::NotFound("Source balance not found".to_string()))?;
+            if source.available_balance - source.reserved_balance < request.amount {
+                if link_id.is_some() {
+                    return Err(AppError::InsufficientFunds(format!(
+                        "Insufficient funds for linked transaction '{}'",
+                        request.external_id
+                    )));
+                }
+                results.push(Some(Err(AppError::InsufficientFunds(
+                    "Insufficient funds during transaction".to_string(),
+                ))));
+                continue;
             }
-            _ => {
-                self.check_sufficient_funds(
+
+            let mut transaction = TransactionRecord::new(
+                request.external_id.clone(),
+                request.transaction_type,
+                request.source_account_id,
+                request.destination_account_id,
+                request.amount,
+                request.currency.clone(),
+                request.fee_amount,
+                request.idempotency_key.clone(),
+            )
+            .with_priority(request.priority)
+            .with_external_id_claim(write.claim.clone())
+            .with_type_code(request.type_code.clone())
+            .excluded_from_netting(request.exclude_from_netting)
+            .with_narrative(Some(write.narrative.clone()), request.reference.clone())
+            .with_link(link_id);
+            if link_id.is_some() {
+                transaction = transaction.with_route(SettlementRoute::Rtgs);
+            }
+            if let Some(metadata) = &request.metadata {
+                transaction = transaction.with_metadata(metadata.clone());
+            }
+            transaction.status = TransactionStatus::Settled;
+            transaction.settled_at = Some(now);
+
+            let mut apply = |account_id: Uuid, delta: Decimal| -> Result<AccountBalance> {
+                let key = (account_id, request.currency.clone());
+                let balance = balances.get_mut(&key).ok_or_else(|| {
+                    AppError::NotFound(format!("Balance for account '{}' in {} not found", account_id, request.currency))
+                })?;
+                balance.available_balance += delta;
+                balance.last_updated = now;
+                deltas.push((key, delta));
+                Ok(balance.clone())
+            };
+
+            let source_balance = apply(request.source_account_id, -request.amount)?;
+            let destination_balance = apply(request.destination_account_id, request.net_amount())?;
+            let mut legs = vec![
+                LedgerEntry::debit(
+                    transaction.id,
                     request.source_account_id,
-                    &request.currency,
                     request.amount,
-                )
-                .await?;
+                    request.currency.clone(),
+                    source_balance.available_balance,
+                    effective_date,
+                ),
+                LedgerEntry::credit(
+                    transaction.id,
+                    request.destination_account_id,
+                    request.net_amount(),
+                    request.currency.clone(),
+                    destination_balance.available_balance,
+                    effective_date,
+                ),
+            ];
+            if let Some(fee_account_id) = write.fee_account_id {
+                let fee_balance = apply(fee_account_id, request.fee_amount)?;
+                legs.push(
+                    LedgerEntry::credit(
+                        transaction.id,
+                        fee_account_id,
+                        request.fee_amount,
+                        request.currency.clone(),
+                        fee_balance.available_balance,
+                        effective_date,
+                    )
+                    .as_fee_leg(serde_json::json!({})),
+                );
             }
-        }
+            let legs: Vec<LedgerEntry> = legs
+                .into_iter()
+                .map(|leg| leg.with_narrative(transaction.narrative.clone(), transaction.reference.clone()))
+                .collect();
 
-        // Extract values before moving
-        let net_amount = request.net_amount();
-        let effective_date = request.effective_date.unwrap_or_else(|| Utc::now().date_naive());
-        let source_account_id = request.source_account_id;
-        let destination_account_id = request.destination_account_id;
-        let amount = request.amount;
-        let currency = request.currency.clone();
+            let detail = match request.original_transaction_id {
+                Some(original_id) => serde_json::json!({ "original_transaction_id": original_id }),
+                None => serde_json::json!({}),
+            };
+            audit_entries.push(TransactionAuditEntry::new(transaction.id, TransactionAuditAction::Validated, detail));
+            entries.extend(legs.iter().cloned());
+            transactions.push(transaction.clone());
+            posted.push((results.len(), transaction, legs, source_balance, destination_balance));
+            results.push(None);
+        }
 
-        // Execute atomically with SERIALIZABLE isolation
-        let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = self.pool.begin().await.map_err(AppError::Database)?;
+        TransactionRepository::create_many_in(&mut tx, &transactions).await?;
+        TransactionAuditRepository::record_many_in(&mut tx, &audit_entries).await?;
+        LedgerRepository::create_many_in(&mut tx, &entries).await?;
+
+        let mut net: HashMap<(Uuid, String), Decimal> = HashMap::new();
+        for (key, delta) in deltas {
+            *net.entry(key).or_default() += delta;
+        }
+        let (keys, net_deltas): (Vec<(Uuid, String)>, Vec<Decimal>) = net.into_iter().unzip();
+        let (account_ids, currencies): (Vec<Uuid>, Vec<String>) = keys.into_iter().unzip();
+        let versions: HashMap<(Uuid, String), i32> =
+            BalanceRepository::adjust_many_in(&mut tx, &account_ids, &currencies, &net_deltas)
+                .await?
+                .into_iter()
+                .map(|balance| ((balance.account_id, balance.currency), balance.version))
+                .collect();
+
+        for (position, transaction, legs, mut source_balance, mut destination_balance) in posted {
+            for balance in [&mut source_balance, &mut destination_balance] {
+                if let Some(version) = versions.get(&(balance.account_id, balance.currency.clone())) {
+                    balance.version = *version;
+                }
+            }
+            let (transaction, batch_assignment) = match &self.batching {
+                Some(batching) if link_id.is_none() => {
+                    let (assigned, assignment) = batching.assign_in(&mut tx, &transaction).await?;
+                    (assigned, Some(assignment))
+                }
+                _ => (transaction, None),
+            };
+            enqueue_settled_event(&mut tx, &transaction).await?;
+            results[position] = Some(Ok(LedgerTransactionResult {
+                transaction,
+                entries: legs,
+                source_balance,
+                destination_balance,
+                batch_assignment,
+            }));
+        }
+
+        tx.commit().await.map_err(AppError::Database)?;
+        get_metrics().record_write_combined(writes.len(), transactions.len());
+
+        Ok(results
+            .into_iter()
+            .map(|result| result.expect("every combined write has an outcome"))
+            .collect())
+    }
+
+    /// Converts an active balance reservation into a settled payment from the reserved
+    /// account. The reservation is closed and its funds are returned to the account in
+    /// the database transaction that debits the payment, so either both happen or
+    /// neither does. Captures settle on the netted lane without risk screening, as the
+    /// funds were already set aside. The payment may be for less than was reserved; the
+    /// rest goes back to the account.
+    pub async fn capture_reservation(
+        &self,
+        reservation_id: Uuid,
+        request: LedgerTransactionRequest,
+    ) -> Result<LedgerTransactionResult> {
+        if request.transaction_type != TransactionType::Payment {
+            return Err(AppError::Validation("Reservations are captured as payments".to_string()));
+        }
+        self.execute(request, Some(reservation_id)).await
+    }
+
+    async fn execute(
+        &self,
+        request: LedgerTransactionRequest,
+        reservation_id: Option<Uuid>,
+    ) -> Result<LedgerTransactionResult> {
+        // Run validation pipeline
+        self.ensure_valid(&request).await?;
+
+        // Check idempotency
+        if let Some(existing) = self
+            .transaction_repo
+            .find_by_idempotency_key(&request.idempotency_key)
+            .await?
+        {
+            return self.build_result_from_existing(existing).await;
+        }
+
+        // Reject or link a duplicate external ID
+        let external_id_claim = self.claim_external_id(&request).await?;
+
+        // Verify accounts
+        let _source_account = self.verify_account(request.source_account_id).await?;
+        let _dest_account = self.verify_account(request.destination_account_id).await?;
+
+        // Get or create balances
+        let _source_balance = self
+            .balance_repo
+            .get_or_create(request.source_account_id, &request.currency)
+            .await?;
+
+        let _dest_balance = self
+            .balance_repo
+            .get_or_create(request.destination_account_id, &request.currency)
+            .await?;
+
+        let fee_account_id = self.fee_account_for(&request.currency, request.fee_amount).await?;
+        let narrative = self.narrative_for(&request).await?;
+
+        // Check sufficient funds (except for refunds/chargebacks where destination pays back).
+        // Reserved funds only become usable once the capture releases them below.
+        match request.transaction_type {
+            _ if reservation_id.is_some() => {}
+            TransactionType::Refund | TransactionType::Chargeback => {
+                // For refunds/chargebacks, the destination (original receiver) pays back
+                self.check_sufficient_funds(request.source_account_id, &request.money()?)
+                    .await?;
+            }
+            _ => {
+                self.check_sufficient_funds(request.source_account_id, &request.money()?)
+                    .await?;
+            }
+        }
+
+        // Extract values before moving
+        let net_amount = request.net_amount();
+        let effective_date = request.effective_date.unwrap_or_else(|| Utc::now().date_naive());
+        let source_account_id = request.source_account_id;
+        let destination_account_id = request.destination_account_id;
+        let amount = request.amount;
+        let fee_amount = request.fee_amount;
+        let currency = request.currency.clone();
+        let original_transaction_id = request.original_transaction_id;
+
+        // Execute atomically with SERIALIZABLE isolation
+        let mut tx: sqlx::Transaction<'_, sqlx::Postgres> = self.pool.begin().await.map_err(AppError::Database)?;
 
         // Set transaction isolation level
         sqlx::query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
@@ -582,72 +1625,109 @@ impl LedgerService {
             currency.clone(),
             request.fee_amount,
             request.idempotency_key,
-        );
+        )
+        .with_priority(request.priority)
+        .with_external_id_claim(external_id_claim)
+        .with_type_code(request.type_code)
+        .excluded_from_netting(request.exclude_from_netting)
+        .with_narrative(Some(narrative), request.reference);
 
         if let Some(metadata) = request.metadata {
             transaction = transaction.with_metadata(metadata);
         }
 
-        let transaction = sqlx::query_as::<_, TransactionRecord>(
-            r#"
-            INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
-            "#,
+        let transaction = timed(
+            "transactions.insert",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
+                RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                "#,
+            )
+            .bind(transaction.id)
+            .bind(&transaction.external_id)
+            .bind(&transaction.transaction_type)
+            .bind(&transaction.status)
+            .bind(transaction.source_account_id)
+            .bind(transaction.destination_account_id)
+            .bind(transaction.amount)
+            .bind(&transaction.currency)
+            .bind(transaction.fee_amount)
+            .bind(transaction.net_amount)
+            .bind(transaction.settlement_batch_id)
+            .bind(&transaction.idempotency_key)
+            .bind(&transaction.metadata)
+            .bind(transaction.created_at)
+            .bind(transaction.settled_at)
+            .bind(transaction.settlement_route)
+            .bind(transaction.priority)
+            .bind(&transaction.source_system)
+            .bind(transaction.resubmission_of)
+            .bind(&transaction.dedupe_key)
+            .bind(&transaction.type_code)
+            .bind(transaction.exclude_from_netting)
+            .bind(&transaction.narrative)
+            .bind(&transaction.reference)
+            .bind(transaction.link_id)
+            .fetch_one(&mut *tx),
         )
-        .bind(transaction.id)
-        .bind(&transaction.external_id)
-        .bind(&transaction.transaction_type)
-        .bind(&transaction.status)
-        .bind(transaction.source_account_id)
-        .bind(transaction.destination_account_id)
-        .bind(transaction.amount)
-        .bind(&transaction.currency)
-        .bind(transaction.fee_amount)
-        .bind(transaction.net_amount)
-        .bind(transaction.settlement_batch_id)
-        .bind(&transaction.idempotency_key)
-        .bind(&transaction.metadata)
-        .bind(transaction.created_at)
-        .bind(transaction.settled_at)
-        .fetch_one(&mut *tx)
         .await
-        .map_err(AppError::Database)?;
+        .map_err(TransactionRepository::map_insert_error)?;
+
+        // Record that the request passed validation; refunds and chargebacks keep the
+        // link to the transaction they return
+        let detail = match original_transaction_id {
+            Some(original_id) => serde_json::json!({ "original_transaction_id": original_id }),
+            None => serde_json::json!({}),
+        };
+        let entry = TransactionAuditEntry::new(transaction.id, TransactionAuditAction::Validated, detail);
+        TransactionAuditRepository::record_in(&mut tx, &entry).await?;
+
+        if let Some(reservation_id) = reservation_id {
+            Self::capture_in(&mut tx, reservation_id, &transaction).await?;
+        }
 
         // Update balances atomically
-        let updated_source = sqlx::query_as::<_, AccountBalance>(
-            r#"
-            UPDATE account_balances
-            SET available_balance = available_balance - $3,
-                version = version + 1,
-                last_updated = NOW()
-            WHERE account_id = $1 AND currency = $2
-              AND available_balance - reserved_balance >= $3
-            RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
-            "#,
+        let updated_source = timed(
+            "account_balances.debit",
+            sqlx::query_as::<_, AccountBalance>(
+                r#"
+                UPDATE account_balances
+                SET available_balance = available_balance - $3,
+                    version = version + 1,
+                    last_updated = NOW()
+                WHERE account_id = $1 AND currency = $2
+                  AND available_balance - reserved_balance >= $3
+                RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
+                "#,
+            )
+            .bind(source_account_id)
+            .bind(&currency)
+            .bind(amount)
+            .fetch_optional(&mut *tx),
         )
-        .bind(source_account_id)
-        .bind(&currency)
-        .bind(amount)
-        .fetch_optional(&mut *tx)
         .await
         .map_err(AppError::Database)?
-        .ok_or_else(|| AppError::Validation("Insufficient funds during transaction".to_string()))?;
-
-        let updated_dest = sqlx::query_as::<_, AccountBalance>(
-            r#"
-            UPDATE account_balances
-            SET available_balance = available_balance + $3,
-                version = version + 1,
-                last_updated = NOW()
-            WHERE account_id = $1 AND currency = $2
-            RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
-            "#,
+        .ok_or_else(|| AppError::InsufficientFunds("Insufficient funds during transaction".to_string()))?;
+
+        let updated_dest = timed(
+            "account_balances.credit",
+            sqlx::query_as::<_, AccountBalance>(
+                r#"
+                UPDATE account_balances
+                SET available_balance = available_balance + $3,
+                    version = version + 1,
+                    last_updated = NOW()
+                WHERE account_id = $1 AND currency = $2
+                RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
+                "#,
+            )
+            .bind(destination_account_id)
+            .bind(&currency)
+            .bind(net_amount)
+            .fetch_one(&mut *tx),
         )
-        .bind(destination_account_id)
-        .bind(&currency)
-        .bind(net_amount)
-        .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
 
@@ -659,7 +1739,8 @@ impl LedgerService {
             currency.clone(),
             updated_source.available_balance,
             effective_date,
-        );
+        )
+        .with_narrative(transaction.narrative.clone(), transaction.reference.clone());
 
         let credit_entry = LedgerEntry::credit(
             transaction.id,
@@ -668,77 +1749,195 @@ impl LedgerService {
             currency.clone(),
             updated_dest.available_balance,
             effective_date,
-        );
+        )
+        .with_narrative(transaction.narrative.clone(), transaction.reference.clone());
 
         // Insert debit entry
-        let debit_entry = sqlx::query_as::<_, LedgerEntry>(
-            r#"
-            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
-            "#,
+        let debit_entry = timed(
+            "ledger_entries.insert",
+            sqlx::query_as::<_, LedgerEntry>(
+                r#"
+                INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
+                "#,
+            )
+            .bind(debit_entry.id)
+            .bind(debit_entry.transaction_id)
+            .bind(debit_entry.account_id)
+            .bind(&debit_entry.entry_type)
+            .bind(debit_entry.amount)
+            .bind(&debit_entry.currency)
+            .bind(debit_entry.balance_after)
+            .bind(debit_entry.effective_date)
+            .bind(&debit_entry.metadata)
+            .bind(debit_entry.created_at)
+            .bind(&debit_entry.narrative)
+            .bind(&debit_entry.reference)
+            .fetch_one(&mut *tx),
         )
-        .bind(debit_entry.id)
-        .bind(debit_entry.transaction_id)
-        .bind(debit_entry.account_id)
-        .bind(&debit_entry.entry_type)
-        .bind(debit_entry.amount)
-        .bind(&debit_entry.currency)
-        .bind(debit_entry.balance_after)
-        .bind(debit_entry.effective_date)
-        .bind(&debit_entry.metadata)
-        .bind(debit_entry.created_at)
-        .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
 
         // Insert credit entry
-        let credit_entry = sqlx::query_as::<_, LedgerEntry>(
-            r#"
-            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
-            "#,
+        let credit_entry = timed(
+            "ledger_entries.insert",
+            sqlx::query_as::<_, LedgerEntry>(
+                r#"
+                INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at, narrative, reference
+                "#,
+            )
+            .bind(credit_entry.id)
+            .bind(credit_entry.transaction_id)
+            .bind(credit_entry.account_id)
+            .bind(&credit_entry.entry_type)
+            .bind(credit_entry.amount)
+            .bind(&credit_entry.currency)
+            .bind(credit_entry.balance_after)
+            .bind(credit_entry.effective_date)
+            .bind(&credit_entry.metadata)
+            .bind(credit_entry.created_at)
+            .bind(&credit_entry.narrative)
+            .bind(&credit_entry.reference)
+            .fetch_one(&mut *tx),
         )
-        .bind(credit_entry.id)
-        .bind(credit_entry.transaction_id)
-        .bind(credit_entry.account_id)
-        .bind(&credit_entry.entry_type)
-        .bind(credit_entry.amount)
-        .bind(&credit_entry.currency)
-        .bind(credit_entry.balance_after)
-        .bind(credit_entry.effective_date)
-        .bind(&credit_entry.metadata)
-        .bind(credit_entry.created_at)
-        .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
 
+        let mut entries = vec![debit_entry, credit_entry];
+
+        // Book the fee, the difference between the gross debit and the net credit
+        if let Some(fee_account_id) = fee_account_id {
+            let fee_balance = BalanceRepository::adjust_in(&mut tx, fee_account_id, &currency, fee_amount).await?;
+            let fee_entry = LedgerEntry::credit(
+                transaction.id,
+                fee_account_id,
+                fee_amount,
+                currency.clone(),
+                fee_balance.available_balance,
+                effective_date,
+            )
+            .as_fee_leg(serde_json::json!({}))
+            .with_narrative(transaction.narrative.clone(), transaction.reference.clone());
+            entries.push(LedgerRepository::create_in(&mut tx, &fee_entry).await?);
+        }
+
         // Update transaction status to settled
-        let transaction = sqlx::query_as::<_, TransactionRecord>(
-            r#"
-            UPDATE transactions
-            SET status = 'SETTLED', settled_at = NOW()
-            WHERE id = $1
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at
-            "#,
+        let transaction = timed(
+            "transactions.mark_settled",
+            sqlx::query_as::<_, TransactionRecord>(
+                r#"
+                UPDATE transactions
+                SET status = 'SETTLED', settled_at = NOW()
+                WHERE id = $1
+                RETURNING id, external_id, type, status, source_account_id, destination_account_id, amount, currency, fee_amount, net_amount, settlement_batch_id, idempotency_key, metadata, created_at, settled_at, settlement_route, priority, source_system, resubmission_of, dedupe_key, type_code, exclude_from_netting, narrative, reference, link_id
+                "#,
+            )
+            .bind(transaction.id)
+            .fetch_one(&mut *tx),
         )
-        .bind(transaction.id)
-        .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
 
+        // Assign to the current batch in the same unit of work
+        let (transaction, batch_assignment) = match &self.batching {
+            Some(batching) => {
+                let (assigned, assignment) = batching.assign_in(&mut tx, &transaction).await?;
+                (assigned, Some(assignment))
+            }
+            None => (transaction, None),
+        };
+        enqueue_settled_event(&mut tx, &transaction).await?;
+
         // Commit transaction
         tx.commit().await.map_err(AppError::Database)?;
 
         Ok(LedgerTransactionResult {
             transaction,
-            entries: vec![debit_entry, credit_entry],
+            entries,
             source_balance: updated_source,
             destination_balance: updated_dest,
+            batch_assignment,
         })
     }
 
+    /// The narrative of a request: its own, or else its type's narrative template filled
+    /// in from the request.
+    async fn narrative_for(&self, request: &LedgerTransactionRequest) -> Result<String> {
+        if let Some(narrative) = &request.narrative {
+            return Ok(narrative.clone());
+        }
+        let definition = self
+            .transaction_types
+            .resolve(request.transaction_type, request.type_code.as_deref())
+            .await?;
+        Ok(definition.narrative(&request.external_id, request.amount, &request.currency, request.reference.as_deref()))
+    }
+
+    /// Closes a reservation as captured into `transaction`, returning its funds to the
+    /// account for the payment's debit. The reservation must be active, unexpired, and
+    /// hold at least the payment's amount in its currency on the payment's source account.
+    async fn capture_in(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        reservation_id: Uuid,
+        transaction: &TransactionRecord,
+    ) -> Result<()> {
+        let reservation = BalanceReservationRepository::lock_in(tx, reservation_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Reservation '{}' not found", reservation_id)))?;
+        if reservation.status != BalanceReservationStatus::Active {
+            return Err(AppError::Validation(format!(
+                "Reservation '{}' is {:?}, not active",
+                reservation_id, reservation.status
+            )));
+        }
+        if reservation.is_expired_at(Utc::now()) {
+            return Err(AppError::Validation(format!("Reservation '{}' has expired", reservation_id)));
+        }
+        if reservation.account_id != transaction.source_account_id || reservation.currency != transaction.currency {
+            return Err(AppError::Validation(format!(
+                "Reservation '{}' is not held on the payment's source account and currency",
+                reservation_id
+            )));
+        }
+        if transaction.amount > reservation.amount {
+            return Err(AppError::Validation(format!(
+                "Payment amount {} exceeds reserved amount {}",
+                transaction.amount, reservation.amount
+            )));
+        }
+
+        BalanceReservationRepository::close_in(
+            tx,
+            reservation_id,
+            BalanceReservationStatus::Captured,
+            Some(transaction.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the account a fee is booked to, making sure it has a balance in the fee's
+    /// currency: the fees-receivable account when fees accrue, otherwise the revenue
+    /// account. `None` if there is no fee or no revenue account for the currency. The
+    /// currency's system accounts are provisioned first if this is its first use.
+    async fn fee_account_for(&self, currency: &str, fee_amount: Decimal) -> Result<Option<Uuid>> {
+        if let Some(chart) = &self.chart {
+            chart.ensure_currency(currency).await?;
+        }
+        if fee_amount <= Decimal::ZERO {
+            return Ok(None);
+        }
+        let Some(account_id) = self.fees.accrual_account(currency).or_else(|| self.fees.revenue_account(currency)) else {
+            return Ok(None);
+        };
+        self.verify_account(account_id).await?;
+        self.balance_repo.get_or_create(account_id, currency).await?;
+        Ok(Some(account_id))
+    }
+
     /// Builds a result from an existing transaction (for idempotency).
     async fn build_result_from_existing(&self, transaction: TransactionRecord) -> Result<LedgerTransactionResult> {
         let entries = self.ledger_repo.find_by_transaction(transaction.id).await?;
@@ -760,16 +1959,34 @@ impl LedgerService {
             entries,
             source_balance,
             destination_balance: dest_balance,
+            batch_assignment: None,
         })
     }
 
-    /// Gets the transaction history for an account.
+    /// Gets a page of an account's ledger history, newest first, optionally within an
+    /// effective date range. Each entry's balance is checked against the entry before
+    /// it, and the page is flagged if the running balance has gaps.
     pub async fn get_account_history(
         &self,
         account_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
         limit: i64,
-    ) -> Result<Vec<LedgerEntry>> {
-        self.ledger_repo.find_by_account(account_id, limit, 0).await
+        offset: i64,
+    ) -> Result<AccountHistory> {
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(AppError::Validation("from must not be after to".to_string()));
+            }
+        }
+
+        let entries = self.ledger_repo.find_history(account_id, from, to, limit, offset).await?;
+        let total = self.ledger_repo.count_history(account_id, from, to).await?;
+        let history = AccountHistory::new(entries, total);
+        if history.gaps_detected {
+            tracing::warn!(account_id = %account_id, "Running balance gap in account ledger history");
+        }
+        Ok(history)
     }
 
     /// Gets a transaction by ID.
@@ -780,6 +1997,28 @@ impl LedgerService {
             .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", id)))
     }
 
+    /// Finds the transactions carrying an upstream reference as their external ID: the
+    /// original and any resubmissions, or one per source system if the ID is scoped by
+    /// source. Case and surrounding whitespace are ignored.
+    pub async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source_system: Option<&str>,
+    ) -> Result<Vec<TransactionRecord>> {
+        self.transaction_repo
+            .find_all_by_external_id(external_id, source_system)
+            .await
+    }
+
+    /// Finds the reversals and fee refunds booked against the given transactions.
+    pub async fn find_returns(&self, transactions: &[TransactionRecord]) -> Result<Vec<TransactionRecord>> {
+        if transactions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<Uuid> = transactions.iter().map(|transaction| transaction.id).collect();
+        self.transaction_repo.find_returns_of(&ids).await
+    }
+
     /// Lists transactions with optional filters.
     pub async fn list_transactions(
         &self,
@@ -806,6 +2045,26 @@ impl LedgerService {
             .await
     }
 
+    /// Changes the release priority of a queued transaction. Only transactions that are
+    /// still pending, or waiting in a batch that has not started processing, can be changed.
+    pub async fn reprioritize_transaction(
+        &self,
+        id: Uuid,
+        priority: TransactionPriority,
+    ) -> Result<TransactionRecord> {
+        let transaction = self.get_transaction(id).await?;
+
+        self.transaction_repo
+            .update_priority(id, priority)
+            .await?
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Transaction '{}' is not queued and cannot be re-prioritized (status: {:?})",
+                    id, transaction.status
+                ))
+            })
+    }
+
     /// Gets ledger entries for an account.
     pub async fn get_account_ledger_entries(
         &self,
@@ -821,23 +2080,334 @@ impl LedgerService {
         self.ledger_repo.count_by_account(account_id).await
     }
 
-    /// Processes any transaction type.
+    /// Settles a transaction gross through the RTGS lane, bypassing batch netting.
+    async fn process_rtgs(&self, request: LedgerTransactionRequest) -> Result<LedgerTransactionResult> {
+        let rtgs = self
+            .rtgs
+            .as_ref()
+            .ok_or_else(|| AppError::Validation("RTGS lane is not configured".to_string()))?;
+
+        self.ensure_valid(&request).await?;
+        let external_id_claim = self.claim_external_id(&request).await?;
+        let narrative = self.narrative_for(&request).await?;
+
+        let result = rtgs
+            .settle(TransactionRequest {
+                external_id: request.external_id,
+                transaction_type: request.transaction_type,
+                source_account_id: request.source_account_id,
+                destination_account_id: request.destination_account_id,
+                amount: request.amount,
+                currency: request.currency,
+                fee_amount: request.fee_amount,
+                idempotency_key: request.idempotency_key,
+                effective_date: request.effective_date,
+                metadata: request.metadata,
+                external_id_claim: Some(external_id_claim),
+                type_code: request.type_code,
+                exclude_from_netting: request.exclude_from_netting,
+                narrative: Some(narrative),
+                reference: request.reference,
+            })
+            .await?;
+
+        Ok(LedgerTransactionResult {
+            transaction: result.transaction,
+            entries: vec![result.debit_entry, result.credit_entry],
+            source_balance: result.source_balance,
+            destination_balance: result.destination_balance,
+            batch_assignment: None,
+        })
+    }
+
+    /// Processes any transaction type. With risk review enabled, payments and transfers
+    /// that trip a rule are held and `HeldForReview` is returned instead.
     pub async fn process_transaction(&self, request: LedgerTransactionRequest) -> Result<LedgerTransactionResult> {
-        match request.transaction_type {
-            TransactionType::Payment => self.process_payment(request).await,
-            TransactionType::Transfer => self.process_transfer(request).await,
-            TransactionType::Fee => self.process_fee(request).await,
-            TransactionType::Refund => self.process_refund(request).await,
-            TransactionType::Chargeback => self.process_chargeback(request).await,
+        // Invalid requests are left to fail on posting rather than queue for review
+        if let Some(risk) = &self.risk {
+            if self.validate_transaction(&request).await?.is_valid {
+                risk.screen(&request).await?;
+            }
         }
+
+        self.post(request).await
     }
 
-    /// Reverses a transaction atomically within a single database transaction.
+    /// Runs a request through the validation pipeline, account and funds checks, risk
+    /// screening and fee resolution, and projects the entries and balances posting it
+    /// would produce. Nothing is written: no balances are created, no currency is
+    /// provisioned, no hold is raised and no alert is sent, so a fee account that first
+    /// use of a currency would provision is reported as missing.
+    pub async fn dry_run(&self, request: &LedgerTransactionRequest) -> Result<TransactionDryRun> {
+        let mut result = self.validate_transaction(request).await?;
+
+        let existing_transaction_id = self
+            .transaction_repo
+            .find_by_idempotency_key(&request.idempotency_key)
+            .await?
+            .map(|existing| existing.id);
+        if existing_transaction_id.is_none() {
+            if let Err(e) = self.claim_external_id(request).await {
+                result.add_error(ValidationError::new("external_id", e.to_string(), "DUPLICATE_EXTERNAL_ID"));
+            }
+        }
+
+        let mut balances = Vec::with_capacity(2);
+        for (field, account_id) in [
+            ("source_account_id", request.source_account_id),
+            ("destination_account_id", request.destination_account_id),
+        ] {
+            let balance = match self.verify_account(account_id).await {
+                Ok(_) => Some(
+                    self.balance_repo
+                        .find_by_account_and_currency(account_id, &request.currency)
+                        .await?
+                        .unwrap_or_else(|| AccountBalance::new(account_id, request.currency.clone())),
+                ),
+                Err(AppError::NotFound(message)) => {
+                    result.add_error(ValidationError::new(field, message, "ACCOUNT_NOT_FOUND"));
+                    None
+                }
+                Err(AppError::AccountFrozen(message)) => {
+                    result.add_error(ValidationError::new(field, message, "ACCOUNT_NOT_OPERATIONAL"));
+                    None
+                }
+                Err(e) => return Err(e),
+            };
+            balances.push(balance);
+        }
+        let destination_balance = balances.pop().flatten();
+        let source_balance = balances.pop().flatten();
+
+        if let Some(balance) = &source_balance {
+            if !balance.has_sufficient_funds(request.amount) {
+                result.add_error(ValidationError::new(
+                    "amount",
+                    format!(
+                        "Insufficient funds: requested {}, available {}",
+                        request.amount,
+                        balance.usable_balance()
+                    ),
+                    "INSUFFICIENT_FUNDS",
+                ));
+            }
+        }
+
+        let route = self
+            .rtgs
+            .as_ref()
+            .map(|rtgs| rtgs.route_for(request.transaction_type, request.amount, &request.currency))
+            .unwrap_or_default();
+
+        // Fees settled gross go with the RTGS posting rather than to a fee account
+        let fee_account_id = match route {
+            SettlementRoute::Netted if request.fee_amount > Decimal::ZERO => {
+                match self
+                    .fees
+                    .accrual_account(&request.currency)
+                    .or_else(|| self.fees.revenue_account(&request.currency))
+                {
+                    Some(account_id) => match self.verify_account(account_id).await {
+                        Ok(_) => Some(account_id),
+                        Err(e) => {
+                            result.add_error(ValidationError::new("fee_amount", e.to_string(), "FEE_ACCOUNT_UNAVAILABLE"));
+                            None
+                        }
+                    },
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+
+        let (risk_triggers, held_for_review) = match &self.risk {
+            Some(risk) if matches!(request.transaction_type, TransactionType::Payment | TransactionType::Transfer) => {
+                let triggers = risk.evaluate(request).await?;
+                let held = !triggers.is_empty();
+                (triggers, held)
+            }
+            _ => (Vec::new(), false),
+        };
+
+        // Project the postings onto the balances as they stand now
+        let transaction_id = Uuid::nil();
+        let effective_date = request.effective_date.unwrap_or_else(|| Utc::now().date_naive());
+        let net_amount = request.net_amount();
+        let narrative = self.narrative_for(request).await.ok();
+        let mut entries = Vec::new();
+        let source_balance = source_balance.map(|mut balance| {
+            // Projected even if it overdraws, so the shortfall shows
+            balance.available_balance -= request.amount;
+            entries.push(
+                LedgerEntry::debit(
+                    transaction_id,
+                    request.source_account_id,
+                    request.amount,
+                    request.currency.clone(),
+                    balance.available_balance,
+                    effective_date,
+                )
+                .with_narrative(narrative.clone(), request.reference.clone()),
+            );
+            balance
+        });
+        let destination_balance = destination_balance.map(|mut balance| {
+            balance.credit(net_amount);
+            entries.push(
+                LedgerEntry::credit(
+                    transaction_id,
+                    request.destination_account_id,
+                    net_amount,
+                    request.currency.clone(),
+                    balance.available_balance,
+                    effective_date,
+                )
+                .with_narrative(narrative.clone(), request.reference.clone()),
+            );
+            balance
+        });
+        if let Some(fee_account_id) = fee_account_id {
+            let fee_balance = self
+                .balance_repo
+                .find_by_account_and_currency(fee_account_id, &request.currency)
+                .await?
+                .map(|balance| balance.available_balance)
+                .unwrap_or(Decimal::ZERO);
+            entries.push(
+                LedgerEntry::credit(
+                    transaction_id,
+                    fee_account_id,
+                    request.fee_amount,
+                    request.currency.clone(),
+                    fee_balance + request.fee_amount,
+                    effective_date,
+                )
+                .as_fee_leg(serde_json::json!({}))
+                .with_narrative(narrative, request.reference.clone()),
+            );
+        }
+
+        Ok(TransactionDryRun {
+            feasible: result.is_valid,
+            errors: result.errors,
+            route,
+            amount: request.amount,
+            fee_amount: request.fee_amount,
+            net_amount,
+            fee_account_id,
+            risk_triggers,
+            held_for_review,
+            entries,
+            source_balance,
+            destination_balance,
+            existing_transaction_id,
+        })
+    }
+
+    /// Releases a transaction held for risk review and posts it. If posting fails the
+    /// hold goes back to the queue, where it can be released again or rejected.
+    pub async fn release_held(&self, hold_id: Uuid, reviewed_by: &str, reason: &str) -> Result<LedgerTransactionResult> {
+        let risk = self
+            .risk
+            .as_ref()
+            .ok_or_else(|| AppError::Validation("Risk review is not enabled".to_string()))?;
+        let hold = risk.decide(hold_id, RiskHoldStatus::Released, reviewed_by, reason).await?;
+        let request: LedgerTransactionRequest = serde_json::from_value(hold.request)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Stored held request is invalid: {}", e)))?;
+
+        match self.post(request).await {
+            Ok(result) => {
+                risk.complete_release(hold_id, result.transaction.id).await?;
+                Ok(result)
+            }
+            Err(e) => {
+                risk.reopen(hold_id, &e).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Routes a transaction to its settlement lane and posts it.
+    async fn post(&self, request: LedgerTransactionRequest) -> Result<LedgerTransactionResult> {
+        let mut failure = SettlementFailure {
+            transaction_id: None,
+            external_id: request.external_id.clone(),
+            source_account_id: request.source_account_id,
+            destination_account_id: request.destination_account_id,
+            amount: request.amount,
+            currency: request.currency.clone(),
+            reason: String::new(),
+        };
+
+        let route = self
+            .rtgs
+            .as_ref()
+            .map(|rtgs| rtgs.route_for(request.transaction_type, request.amount, &request.currency))
+            .unwrap_or_default();
+
+        let result = if route == SettlementRoute::Rtgs {
+            self.process_rtgs(request).await
+        } else {
+            match request.transaction_type {
+                TransactionType::Payment => self.process_payment(request).await,
+                TransactionType::Transfer => self.process_transfer(request).await,
+                TransactionType::Fee => self.process_fee(request).await,
+                TransactionType::Refund => self.process_refund(request).await,
+                TransactionType::Chargeback => self.process_chargeback(request).await,
+            }
+        };
+
+        // Alerts are evaluated and delivered in the background, so a slow webhook never
+        // holds up the response or the next settlement
+        if let Some(engine) = &self.notifications {
+            let engine = engine.clone();
+            match &result {
+                Ok(settled) => {
+                    let transaction = settled.transaction.clone();
+                    let balances = [settled.source_balance.clone(), settled.destination_balance.clone()];
+                    tokio::spawn(async move {
+                        engine.evaluate_settlement(&transaction, &balances).await;
+                    });
+                }
+                Err(e) if is_settlement_failure(e) => {
+                    failure.reason = e.to_string();
+                    tokio::spawn(async move {
+                        engine.evaluate_failure(&failure).await;
+                    });
+                }
+                Err(_) => {}
+            }
+        }
+
+        result
+    }
+
+    /// Reverses a transaction atomically within a single database transaction, applying
+    /// the configured fee reversal policy.
     pub async fn reverse_transaction(
         &self,
         transaction_id: Uuid,
         reason: &str,
         idempotency_key: &str,
+    ) -> Result<LedgerTransactionResult> {
+        self.reverse_transaction_with(transaction_id, reason, idempotency_key, None)
+            .await
+    }
+
+    /// Reverses a transaction, overriding the configured fee reversal policy if one is given.
+    ///
+    /// The payee always returns the net amount it received. Under `Retain` the payer gets
+    /// back just that; under `Reverse` the fee leg is also debited from the revenue account
+    /// and the payer gets back the gross amount less any fee already refunded. A transaction
+    /// whose fee was never booked to a revenue account is always reversed under `Retain`,
+    /// and a type with its own fee policy always reverses under that policy. The reversal's
+    /// metadata records the policy that applied.
+    pub async fn reverse_transaction_with(
+        &self,
+        transaction_id: Uuid,
+        reason: &str,
+        idempotency_key: &str,
+        fee_policy: Option<FeeReversalPolicy>,
     ) -> Result<LedgerTransactionResult> {
         // Check idempotency first - if reversal already exists, return it
         if let Some(existing) = self
@@ -852,21 +2422,9 @@ impl LedgerService {
         let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
 
         // Fetch original transaction with row-level lock to prevent concurrent reversals
-        let original = sqlx::query_as::<_, TransactionRecord>(
-            r#"
-            SELECT id, external_id, type, status, source_account_id, destination_account_id, 
-                   amount, currency, fee_amount, net_amount, settlement_batch_id, 
-                   idempotency_key, metadata, created_at, settled_at
-            FROM transactions
-            WHERE id = $1
-            FOR UPDATE
-            "#,
-        )
-        .bind(transaction_id)
-        .fetch_optional(&mut *tx)
-        .await
-        .map_err(AppError::Database)?
-        .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction_id)))?;
+        let original = TransactionRepository::find_for_update_in(&mut tx, transaction_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction_id)))?;
 
         // Validate transaction can be reversed
         if !original.status.can_be_reversed() {
@@ -876,20 +2434,18 @@ impl LedgerService {
             )));
         }
 
-        if !original.transaction_type.is_reversible() {
+        let definition = self.transaction_types.resolve_record(&original).await?;
+        if !definition.reversible {
             return Err(AppError::Validation(format!(
-                "Transaction type {:?} cannot be reversed",
-                original.transaction_type
+                "Transaction type {} cannot be reversed",
+                definition.code
             )));
         }
-
-        let reversal_type = original.transaction_type.reversal_type().ok_or_else(|| {
-            AppError::Validation("No reversal type defined for this transaction".to_string())
-        })?;
+        let reversal_type = definition.reversal_type();
 
         // Fetch accounts
         let source_account = sqlx::query_as::<_, Account>(
-            "SELECT id, external_id, name, type, currency, status, metadata, created_at, updated_at FROM accounts WHERE id = $1",
+            "SELECT id, external_id, name, type, currency, status, metadata, anonymized_at, created_at, updated_at FROM accounts WHERE id = $1",
         )
         .bind(original.destination_account_id)
         .fetch_one(&mut *tx)
@@ -897,171 +2453,100 @@ impl LedgerService {
         .map_err(AppError::Database)?;
 
         let dest_account = sqlx::query_as::<_, Account>(
-            "SELECT id, external_id, name, type, currency, status, metadata, created_at, updated_at FROM accounts WHERE id = $1",
+            "SELECT id, external_id, name, type, currency, status, metadata, anonymized_at, created_at, updated_at FROM accounts WHERE id = $1",
         )
         .bind(original.source_account_id)
         .fetch_one(&mut *tx)
         .await
         .map_err(AppError::Database)?;
 
+        // Only a fee that was booked to a revenue account can be taken back out of it
+        let fee_leg = match definition.fee_reversal_policy(fee_policy.unwrap_or(self.fees.reversal_policy)) {
+            FeeReversalPolicy::Reverse => LedgerRepository::find_fee_leg_in(&mut tx, original.id).await?,
+            FeeReversalPolicy::Retain => None,
+        };
+        let (applied_policy, reversed_fee) = match &fee_leg {
+            Some(fee_entry) => {
+                let refunded = LedgerRepository::sum_fee_refunds_in(&mut tx, fee_entry.id).await?;
+                (FeeReversalPolicy::Reverse, fee_entry.amount - refunded)
+            }
+            None => (FeeReversalPolicy::Retain, Decimal::ZERO),
+        };
+        let returned_amount = original.net_amount + reversed_fee;
+
         // Create reversal transaction record
         let reversal_tx = TransactionRecord::new(
             format!("REV-{}", original.external_id),
             reversal_type,
             source_account.id,
             dest_account.id,
-            original.amount,
+            returned_amount,
             original.currency.clone(),
             Decimal::ZERO,
             idempotency_key.to_string(),
-        );
-
-        // Insert reversal transaction
-        let reversal_tx = sqlx::query_as::<_, TransactionRecord>(
-            r#"
-            INSERT INTO transactions (id, external_id, type, status, source_account_id, destination_account_id, 
-                                      amount, currency, fee_amount, net_amount, settlement_batch_id, 
-                                      idempotency_key, metadata, created_at, settled_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, 
-                      amount, currency, fee_amount, net_amount, settlement_batch_id, 
-                      idempotency_key, metadata, created_at, settled_at
-            "#,
         )
-        .bind(reversal_tx.id)
-        .bind(&reversal_tx.external_id)
-        .bind(&reversal_tx.transaction_type)
-        .bind(&reversal_tx.status)
-        .bind(reversal_tx.source_account_id)
-        .bind(reversal_tx.destination_account_id)
-        .bind(reversal_tx.amount)
-        .bind(&reversal_tx.currency)
-        .bind(reversal_tx.fee_amount)
-        .bind(reversal_tx.net_amount)
-        .bind(reversal_tx.settlement_batch_id)
-        .bind(&reversal_tx.idempotency_key)
-        .bind(serde_json::json!({
+        .with_route(original.settlement_route)
+        .with_narrative(Some(original.derived_narrative("Reversal of")), original.reference.clone())
+        .with_metadata(serde_json::json!({
             "original_transaction_id": original.id,
-            "reason": reason
-        }))
-        .bind(reversal_tx.created_at)
-        .bind(reversal_tx.settled_at)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(AppError::Database)?;
+            "reason": reason,
+            "fee_policy": applied_policy.as_str(),
+            "reversed_fee": reversed_fee,
+        }));
+        let reversal_tx = TransactionRepository::create_in(&mut tx, &reversal_tx).await?;
 
         // Update balances - debit from source (original destination), credit to dest (original source)
         let effective_date = Utc::now().date_naive();
-
-        // Update source balance (debit)
-        let updated_source = sqlx::query_as::<_, AccountBalance>(
-            r#"
-            UPDATE account_balances
-            SET available_balance = available_balance - $1, last_updated = NOW(), version = version + 1
-            WHERE account_id = $2 AND currency = $3
-            RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
-            "#,
-        )
-        .bind(original.amount)
-        .bind(source_account.id)
-        .bind(&original.currency)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(AppError::Database)?;
-
-        // Update destination balance (credit)
-        let updated_dest = sqlx::query_as::<_, AccountBalance>(
-            r#"
-            UPDATE account_balances
-            SET available_balance = available_balance + $1, last_updated = NOW(), version = version + 1
-            WHERE account_id = $2 AND currency = $3
-            RETURNING account_id, currency, available_balance, pending_balance, reserved_balance, version, last_updated
-            "#,
-        )
-        .bind(original.amount)
-        .bind(dest_account.id)
-        .bind(&original.currency)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(AppError::Database)?;
+        let updated_source =
+            BalanceRepository::adjust_in(&mut tx, source_account.id, &original.currency, -original.net_amount).await?;
+        let updated_dest =
+            BalanceRepository::adjust_in(&mut tx, dest_account.id, &original.currency, returned_amount).await?;
 
         // Create ledger entries
         let debit_entry = LedgerEntry::debit(
             reversal_tx.id,
             source_account.id,
-            original.amount,
+            original.net_amount,
             original.currency.clone(),
             updated_source.available_balance,
             effective_date,
-        );
+        )
+        .with_narrative(reversal_tx.narrative.clone(), reversal_tx.reference.clone());
 
         let credit_entry = LedgerEntry::credit(
             reversal_tx.id,
             dest_account.id,
-            original.amount,
+            returned_amount,
             original.currency.clone(),
             updated_dest.available_balance,
             effective_date,
-        );
-
-        // Insert ledger entries
-        let debit_entry = sqlx::query_as::<_, LedgerEntry>(
-            r#"
-            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
-            "#,
-        )
-        .bind(debit_entry.id)
-        .bind(debit_entry.transaction_id)
-        .bind(debit_entry.account_id)
-        .bind(&debit_entry.entry_type)
-        .bind(debit_entry.amount)
-        .bind(&debit_entry.currency)
-        .bind(debit_entry.balance_after)
-        .bind(debit_entry.effective_date)
-        .bind(&debit_entry.metadata)
-        .bind(debit_entry.created_at)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(AppError::Database)?;
-
-        let credit_entry = sqlx::query_as::<_, LedgerEntry>(
-            r#"
-            INSERT INTO ledger_entries (id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, transaction_id, account_id, entry_type, amount, currency, balance_after, effective_date, metadata, created_at
-            "#,
         )
-        .bind(credit_entry.id)
-        .bind(credit_entry.transaction_id)
-        .bind(credit_entry.account_id)
-        .bind(&credit_entry.entry_type)
-        .bind(credit_entry.amount)
-        .bind(&credit_entry.currency)
-        .bind(credit_entry.balance_after)
-        .bind(credit_entry.effective_date)
-        .bind(&credit_entry.metadata)
-        .bind(credit_entry.created_at)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(AppError::Database)?;
+        .with_narrative(reversal_tx.narrative.clone(), reversal_tx.reference.clone());
+
+        let mut entries = vec![
+            LedgerRepository::create_in(&mut tx, &debit_entry).await?,
+            LedgerRepository::create_in(&mut tx, &credit_entry).await?,
+        ];
+
+        // Take the unrefunded part of the fee back out of the revenue account
+        if let Some(fee_entry) = fee_leg.filter(|_| reversed_fee > Decimal::ZERO) {
+            let fee_balance =
+                BalanceRepository::adjust_in(&mut tx, fee_entry.account_id, &original.currency, -reversed_fee).await?;
+            let fee_debit = LedgerEntry::debit(
+                reversal_tx.id,
+                fee_entry.account_id,
+                reversed_fee,
+                original.currency.clone(),
+                fee_balance.available_balance,
+                effective_date,
+            )
+            .as_fee_leg(serde_json::json!({ "original_fee_entry_id": fee_entry.id }))
+            .with_narrative(reversal_tx.narrative.clone(), reversal_tx.reference.clone());
+            entries.push(LedgerRepository::create_in(&mut tx, &fee_debit).await?);
+        }
 
         // Update reversal transaction status to settled
-        let reversal_tx = sqlx::query_as::<_, TransactionRecord>(
-            r#"
-            UPDATE transactions
-            SET status = 'SETTLED', settled_at = NOW()
-            WHERE id = $1
-            RETURNING id, external_id, type, status, source_account_id, destination_account_id, 
-                      amount, currency, fee_amount, net_amount, settlement_batch_id, 
-                      idempotency_key, metadata, created_at, settled_at
-            "#,
-        )
-        .bind(reversal_tx.id)
-        .fetch_one(&mut *tx)
-        .await
-        .map_err(AppError::Database)?;
+        let reversal_tx = TransactionRepository::mark_settled_in(&mut tx, reversal_tx.id).await?;
 
         // Mark original transaction as reversed
         sqlx::query(
@@ -1071,15 +2556,137 @@ impl LedgerService {
         .execute(&mut *tx)
         .await
         .map_err(AppError::Database)?;
+        enqueue_settled_event(&mut tx, &reversal_tx).await?;
 
         // Commit the entire transaction
         tx.commit().await.map_err(AppError::Database)?;
 
         Ok(LedgerTransactionResult {
             transaction: reversal_tx,
-            entries: vec![debit_entry, credit_entry],
+            entries,
             source_balance: updated_source,
             destination_balance: updated_dest,
+            batch_assignment: None,
+        })
+    }
+
+    /// Refunds all or part of a transaction's booked fee to the payer, without reversing
+    /// the payment itself.
+    ///
+    /// The refund is a `Refund` transaction from the fee revenue account to the original
+    /// source whose debit references the original fee entry. Refunds of one fee, including
+    /// a fee reversed with its payment, never exceed the fee. `amount` defaults to whatever
+    /// is left of it.
+    pub async fn refund_fee(
+        &self,
+        original_transaction_id: Uuid,
+        amount: Option<Decimal>,
+        reason: &str,
+        idempotency_key: &str,
+    ) -> Result<LedgerTransactionResult> {
+        if let Some(existing) = self
+            .transaction_repo
+            .find_by_idempotency_key(idempotency_key)
+            .await?
+        {
+            return self.build_result_from_existing(existing).await;
+        }
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+
+        // Lock the original so concurrent fee refunds see each other's entries
+        let original = TransactionRepository::find_for_update_in(&mut tx, original_transaction_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Original transaction '{}' not found", original_transaction_id))
+            })?;
+
+        if !matches!(original.status, TransactionStatus::Settled | TransactionStatus::Reversed) {
+            return Err(AppError::Validation(format!(
+                "Cannot refund the fee of a transaction with status {:?}",
+                original.status
+            )));
+        }
+
+        let fee_entry = LedgerRepository::find_fee_leg_in(&mut tx, original.id)
+            .await?
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Transaction '{}' has no fee booked to a revenue account",
+                    original.id
+                ))
+            })?;
+        let remaining = fee_entry.amount - LedgerRepository::sum_fee_refunds_in(&mut tx, fee_entry.id).await?;
+        let amount = amount.unwrap_or(remaining);
+        if amount <= Decimal::ZERO {
+            return Err(AppError::Validation("Fee refund amount must be positive".to_string()));
+        }
+        if amount > remaining {
+            return Err(AppError::Validation(format!(
+                "Fee refund amount {} exceeds the {} left of the original fee",
+                amount, remaining
+            )));
+        }
+
+        let refund = TransactionRecord::new(
+            format!("FEE-REF-{}", Uuid::new_v4()),
+            TransactionType::Refund,
+            fee_entry.account_id,
+            original.source_account_id,
+            amount,
+            original.currency.clone(),
+            Decimal::ZERO,
+            idempotency_key.to_string(),
+        )
+        .with_narrative(Some(original.derived_narrative("Fee refund for")), original.reference.clone())
+        .with_metadata(serde_json::json!({
+            "original_transaction_id": original.id,
+            "original_fee_entry_id": fee_entry.id,
+            "reason": reason,
+            "fee_refund": true,
+        }));
+        let refund = TransactionRepository::create_in(&mut tx, &refund).await?;
+
+        let effective_date = Utc::now().date_naive();
+        let updated_source =
+            BalanceRepository::adjust_in(&mut tx, fee_entry.account_id, &original.currency, -amount).await?;
+        let updated_dest =
+            BalanceRepository::adjust_in(&mut tx, original.source_account_id, &original.currency, amount).await?;
+
+        let debit_entry = LedgerEntry::debit(
+            refund.id,
+            fee_entry.account_id,
+            amount,
+            original.currency.clone(),
+            updated_source.available_balance,
+            effective_date,
+        )
+        .as_fee_leg(serde_json::json!({ "original_fee_entry_id": fee_entry.id }))
+        .with_narrative(refund.narrative.clone(), refund.reference.clone());
+        let credit_entry = LedgerEntry::credit(
+            refund.id,
+            original.source_account_id,
+            amount,
+            original.currency.clone(),
+            updated_dest.available_balance,
+            effective_date,
+        )
+        .with_narrative(refund.narrative.clone(), refund.reference.clone());
+        let entries = vec![
+            LedgerRepository::create_in(&mut tx, &debit_entry).await?,
+            LedgerRepository::create_in(&mut tx, &credit_entry).await?,
+        ];
+
+        let refund = TransactionRepository::mark_settled_in(&mut tx, refund.id).await?;
+        enqueue_settled_event(&mut tx, &refund).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        Ok(LedgerTransactionResult {
+            transaction: refund,
+            entries,
+            source_balance: updated_source,
+            destination_balance: updated_dest,
+            batch_assignment: None,
         })
     }
 
@@ -1121,9 +2728,38 @@ impl LedgerService {
     }
 }
 
+/// Whether an error means the transaction could not settle, as opposed to a request
+/// refused before settlement was attempted, such as one that failed validation.
+fn is_settlement_failure(error: &AppError) -> bool {
+    matches!(
+        error,
+        AppError::InsufficientFunds(_)
+            | AppError::AccountFrozen(_)
+            | AppError::LimitExceeded(_)
+            | AppError::SerializationConflict(_)
+            | AppError::Unavailable(_)
+            | AppError::Database(_)
+            | AppError::Redis(_)
+            | AppError::Kafka(_)
+            | AppError::Internal(_)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::InternalAccount;
+
+    #[test]
+    fn test_only_settlement_failures_raise_failure_alerts() {
+        assert!(is_settlement_failure(&AppError::InsufficientFunds("Insufficient funds".to_string())));
+        assert!(is_settlement_failure(&AppError::AccountFrozen("Account is frozen".to_string())));
+        assert!(is_settlement_failure(&AppError::Database(sqlx::Error::PoolTimedOut)));
+        assert!(!is_settlement_failure(&AppError::Validation("Amount must be positive".to_string())));
+        assert!(!is_settlement_failure(&AppError::NotFound("Account not found".to_string())));
+        assert!(!is_settlement_failure(&AppError::IdempotencyConflict("Key reused".to_string())));
+        assert!(!is_settlement_failure(&AppError::HeldForReview("Held".to_string())));
+    }
 
     #[test]
     fn test_state_machine_valid_transitions() {
@@ -1168,6 +2804,78 @@ mod tests {
         assert_eq!(result.errors.len(), 1);
     }
 
+    #[test]
+    fn test_amount_limits() {
+        let limits = AmountLimits {
+            default_max: Some(Decimal::new(1_000_000, 0)),
+            currency_max: HashMap::from([("JPY".to_string(), Decimal::new(100_000_000, 0))]),
+            max_scale: 2,
+        };
+
+        assert_eq!(limits.check(Decimal::new(1_000_000, 0), Decimal::ZERO, "USD"), None);
+        assert_eq!(limits.check(Decimal::new(50_000_000, 0), Decimal::ZERO, "JPY"), None);
+        // Trailing zeros do not count towards the scale
+        assert_eq!(limits.check(Decimal::new(1_2500, 4), Decimal::ZERO, "USD"), None);
+
+        let over = limits.check(Decimal::new(1_000_000_000_000, 0), Decimal::ZERO, "USD").unwrap();
+        assert_eq!(over.field, "amount");
+        assert_eq!(over.limit, Some(Decimal::new(1_000_000, 0)));
+
+        let fee_scale = limits.check(Decimal::ONE, Decimal::new(1, 3), "USD").unwrap();
+        assert_eq!(fee_scale.field, "fee_amount");
+        assert_eq!(fee_scale.limit, None);
+
+        let unbounded = AmountLimits::default();
+        assert_eq!(unbounded.check(Decimal::new(1_000_000_000_000, 0), Decimal::ZERO, "USD"), None);
+    }
+
+    #[test]
+    fn test_amount_limit_breach_has_distinct_error() {
+        let mut result = ValidationResult::valid();
+        result.add_error(ValidationError::new("currency", "Invalid currency", "INVALID_CURRENCY"));
+        assert!(matches!(result.into_result(), Err(AppError::Validation(_))));
+
+        let mut result = ValidationResult::valid();
+        result.add_error(ValidationError::new("currency", "Invalid currency", "INVALID_CURRENCY"));
+        result.add_error(ValidationError::new("amount", "Amount exceeds the maximum", AMOUNT_LIMIT_EXCEEDED));
+        let error = result.into_result().unwrap_err();
+        assert_eq!(error.code(), "AMOUNT_LIMIT_EXCEEDED");
+    }
+
+    #[test]
+    fn test_fee_accounts_fall_back_to_chart() {
+        let configured = Uuid::new_v4();
+        let entry = |code: &str, currency: &str, role| InternalAccount {
+            code: code.to_string(),
+            currency: currency.to_string(),
+            name: code.to_string(),
+            role: Some(role),
+            parent_code: None,
+            account_id: Uuid::new_v4(),
+            control: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let chart = ChartRegistry::default();
+        let fees = FeeConfig {
+            revenue_accounts: HashMap::from([("USD".to_string(), configured)]),
+            ..FeeConfig::default()
+        }
+        .with_chart(chart.clone());
+        assert_eq!(fees.revenue_account("EUR"), None);
+
+        // Entries registered later, e.g. on a currency's first use, are picked up
+        let entries = vec![
+            entry("FEE-REVENUE", "USD", InternalAccountRole::FeeRevenue),
+            entry("FEE-REVENUE", "EUR", InternalAccountRole::FeeRevenue),
+            entry("SUSPENSE", "EUR", InternalAccountRole::Suspense),
+        ];
+        chart.register(&entries);
+        assert_eq!(fees.revenue_account("USD"), Some(configured));
+        assert_eq!(fees.revenue_account("EUR"), Some(entries[1].account_id));
+        assert_eq!(fees.invoice_receivable_account("EUR"), None);
+    }
+
     #[test]
     fn test_payment_request_builder() {
         let request = LedgerTransactionRequest::payment(
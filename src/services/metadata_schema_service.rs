@@ -0,0 +1,75 @@
+use crate::error::{AppError, Result};
+use crate::models::{MetadataSchema, TransactionType};
+use crate::repositories::MetadataSchemaRepository;
+use sqlx::PgPool;
+
+/// Manages the optional JSON Schema for each transaction type and checks transaction
+/// metadata against it. Types without a schema accept any metadata.
+pub struct MetadataSchemaService {
+    schema_repo: MetadataSchemaRepository,
+}
+
+impl MetadataSchemaService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            schema_repo: MetadataSchemaRepository::new(pool),
+        }
+    }
+
+    /// Sets the schema for a transaction type, replacing any existing one.
+    pub async fn set_schema(
+        &self,
+        transaction_type: TransactionType,
+        schema: serde_json::Value,
+    ) -> Result<MetadataSchema> {
+        MetadataSchema::check_schema(&schema)
+            .map_err(|e| AppError::Validation(format!("Invalid JSON schema: {}", e)))?;
+
+        self.schema_repo
+            .upsert(&MetadataSchema::new(transaction_type, schema))
+            .await
+    }
+
+    /// Gets the schema for a transaction type.
+    pub async fn get_schema(&self, transaction_type: TransactionType) -> Result<MetadataSchema> {
+        self.schema_repo
+            .find_by_type(transaction_type)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "No metadata schema for transaction type {:?}",
+                    transaction_type
+                ))
+            })
+    }
+
+    /// Lists all configured schemas.
+    pub async fn list_schemas(&self) -> Result<Vec<MetadataSchema>> {
+        self.schema_repo.list().await
+    }
+
+    /// Removes the schema for a transaction type.
+    pub async fn delete_schema(&self, transaction_type: TransactionType) -> Result<()> {
+        if !self.schema_repo.delete(transaction_type).await? {
+            return Err(AppError::NotFound(format!(
+                "No metadata schema for transaction type {:?}",
+                transaction_type
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validates metadata for a transaction type, returning the violations (empty if the
+    /// metadata is valid or the type has no schema).
+    pub async fn validate_metadata(
+        &self,
+        transaction_type: TransactionType,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<Vec<String>> {
+        let Some(schema) = self.schema_repo.find_by_type(transaction_type).await? else {
+            return Ok(Vec::new());
+        };
+
+        Ok(schema.validate(metadata).err().unwrap_or_default())
+    }
+}
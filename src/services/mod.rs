@@ -1,26 +1,112 @@
 pub mod account_service;
+pub mod accounting_period_service;
+pub mod activity_service;
+pub mod alert_service;
+pub mod amendment_service;
+pub mod balance_guard_service;
+pub mod balance_projection_service;
 pub mod balance_service;
 pub mod batch_service;
+pub mod batch_template_service;
 pub mod cached_balance_service;
+pub mod chart_of_accounts_service;
+pub mod counterparty_service;
+pub mod default_management_service;
+pub mod delivery_service;
 pub mod double_entry_engine;
+pub mod expiry_service;
+pub mod fee_settlement_service;
+pub mod finality_service;
+pub mod funding_service;
+pub mod fx_revaluation_service;
+pub mod fx_service;
+pub mod gl_posting_service;
+pub mod instrument_service;
+pub mod invoice_service;
+pub mod instruction_executor;
+pub mod instruction_export_service;
 pub mod ledger_service;
+pub mod liquidity_report_service;
+pub mod metadata_schema_service;
+pub mod netting_advice_service;
 pub mod netting_service;
+pub mod pvp_service;
+pub mod reconciliation_service;
+pub mod risk_service;
+pub mod rtgs_service;
+pub mod settlement_rail;
+pub mod settlement_window_service;
+pub mod statement_service;
+pub mod submission_service;
+pub mod sync_service;
+pub mod transaction_group_service;
+pub mod transaction_timeline_service;
+pub mod transaction_type_service;
+pub mod write_combiner;
 
-pub use account_service::AccountService;
+pub use account_service::{AccountRetentionJob, AccountRetentionPolicy, AccountService};
+pub use accounting_period_service::AccountingPeriodService;
+pub use activity_service::{ActivityProjectionJob, ActivityService};
+pub use alert_service::{AlertService, CreateAlertRuleRequest, UpdateAlertRuleRequest};
+pub use amendment_service::{AmendmentService, TransactionChanges};
+pub use balance_guard_service::{BalanceGuardJob, BalanceGuardService};
+pub use balance_projection_service::{BalanceProjectionJob, BalanceProjectionService};
 pub use balance_service::BalanceService;
 pub use cached_balance_service::CachedBalanceService;
+pub use chart_of_accounts_service::{ChartOfAccountsService, ChartRegistry};
+pub use counterparty_service::CounterpartyService;
+pub use default_management_service::{DefaultManagementConfig, DefaultManagementService, DefaultReport};
+pub use delivery_service::{DeliveryScheduler, DeliveryService};
 pub use batch_service::{
-    BatchCompletionNotification, BatchProcessingError, BatchProcessingResult, BatchScheduler,
-    BatchService, BatchStateMachine, CreateBatchRequest, SettlementWindowConfig,
-    SettlementWindowType,
+    BatchAssignment, BatchCaps, BatchCompletionNotification, CutOffApproval, BatchProcessingError, BatchProcessingResult, BatchScheduler,
+    BatchService, BatchStateMachine, CreateBatchRequest, NetDebitCapConfig, SettlementWindowConfig,
+    SettlementWindowType, DEFAULT_BATCH_WORKERS, DEFAULT_CHECKPOINT_INTERVAL, DEFAULT_MAX_CUT_OFF_SHIFT_SECS,
+    DEFAULT_STALL_TIMEOUT_SECS,
+};
+pub use batch_template_service::{
+    BatchProvisioningJob, BatchTemplateService, CreateBatchTemplateRequest, ProvisioningReport, SkippedTemplate,
+    UpdateBatchTemplateRequest,
 };
 pub use double_entry_engine::DoubleEntryEngine;
+pub use expiry_service::{ExpiryJob, ExpiryPolicy, ExpiryService, ExpirySweep};
+pub use fee_settlement_service::FeeSettlementService;
+pub use finality_service::{
+    AttestationSigner, FinalityAttestation, FinalityService, SignedAttestation,
+};
+pub use funding_service::{FundingConfig, FundingJob, FundingService, FundingSweep};
+pub use fx_revaluation_service::{FxRevaluationConfig, FxRevaluationJob, FxRevaluationService};
+pub use fx_service::{FxConversionRequest, FxRateCorrection, FxService};
+pub use gl_posting_service::GlPostingService;
+pub use instrument_service::InstrumentService;
+pub use invoice_service::{InvoiceContractTerms, InvoiceService};
+pub use instruction_executor::{InstructionExecution, InstructionExecutor};
+pub use instruction_export_service::InstructionExportService;
 pub use ledger_service::{
-    LedgerService, LedgerTransactionRequest, LedgerTransactionResult,
-    TransactionStateMachine, ValidationError, ValidationResult,
+    AmountLimitBreach, AmountLimits, DvpRequest, DvpSettlement, ExternalIdPolicy, FeeConfig, LedgerService, LedgerTransactionRequest, LedgerTransactionResult,
+    TransactionDryRun, TransactionStateMachine, ValidationError, ValidationResult, AMOUNT_LIMIT_EXCEEDED, DEFAULT_MAX_AMOUNT_SCALE,
 };
+pub use liquidity_report_service::LiquidityReportService;
+pub use metadata_schema_service::MetadataSchemaService;
+pub use netting_advice_service::{NettingAdviceConfig, NettingAdviceService};
 pub use netting_service::{
-    BilateralNettingResult, BilateralPair, InstructionStatus, InstructionType,
-    MultilateralNettingResult, NetDirection, NettingMetrics, NettingReport, NettingService,
-    SettlementInstruction,
+    BilateralNettingResult, BilateralPair, ExcludedTransaction, ExclusionReason, InstructionStatus,
+    InstructionType, MultilateralNettingResult, NetDirection, NettingConfig, NettingMetrics, NettingReport,
+    NettingService, SettlementInstruction, DEFAULT_PARALLEL_THRESHOLD,
+};
+pub use pvp_service::{PvpJob, PvpRequest, PvpService, PvpSweepReport, DEFAULT_PVP_TIMEOUT_SECS};
+pub use reconciliation_service::{ReconciliationJob, ReconciliationRun, ReconciliationService};
+pub use risk_service::{RiskConfig, RiskService};
+pub use rtgs_service::{RoutingReport, RtgsConfig, RtgsService};
+pub use settlement_rail::{AcknowledgementStatus, CircuitBreakingRail, SettlementRail, SimulatedRail};
+pub use settlement_window_service::{
+    CreateSettlementWindowRequest, SettlementWindowService, UpdateSettlementWindowRequest,
+};
+pub use statement_service::StatementService;
+pub use submission_service::{SubmissionService, SubmissionWorker};
+pub use sync_service::{SyncPage, SyncService};
+pub use transaction_group_service::{TransactionGroupDetail, TransactionGroupService, MAX_GROUP_SIZE};
+pub use transaction_timeline_service::{
+    TimelineEvent, TimelineEventKind, TransactionTimeline, TransactionTimelineService,
 };
+pub use transaction_type_service::TransactionTypeService;
+pub use write_combiner::{WriteCombiner, DEFAULT_COMBINE_WINDOW, DEFAULT_MAX_COMBINED};
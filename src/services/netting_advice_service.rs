@@ -0,0 +1,239 @@
+use crate::delivery::DeliveryChannels;
+use crate::error::{AppError, Result};
+use crate::models::{AdviceInstruction, BatchStatus, NettingAdvice, PositionSide, SettlementBatch};
+use crate::repositories::{BatchRepository, NettingAdviceRepository};
+use crate::services::{DeliveryService, NettingConfig, NettingReport, NettingService, SettlementInstruction};
+use anyhow::anyhow;
+use chrono::Duration;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Where and how netting advices are issued.
+#[derive(Debug, Clone)]
+pub struct NettingAdviceConfig {
+    /// How long after a batch's cut-off net payers have to fund their position.
+    pub funding_window: Duration,
+    /// Delivery destination each advice is uploaded to as CSV and PDF.
+    pub destination: Option<String>,
+    /// URL each advice is posted to as JSON.
+    pub webhook_url: Option<String>,
+    pub webhook_timeout: StdDuration,
+}
+
+impl Default for NettingAdviceConfig {
+    fn default() -> Self {
+        Self {
+            funding_window: Duration::hours(2),
+            destination: None,
+            webhook_url: None,
+            webhook_timeout: StdDuration::from_secs(10),
+        }
+    }
+}
+
+/// Issues participant-facing netting advices for completed batches.
+///
+/// Each participant with a position in a batch's netting gets an advice of its gross
+/// receivable and payable, net position, the instructions it pays or receives and, if it
+/// is a net payer, the deadline for funding. Unlike the netting report, an advice only
+/// shows one participant's side. Advices are stored, so they can be retrieved later, and
+/// delivered to the configured destination and webhook; a failed delivery is logged and
+/// does not stop the others.
+pub struct NettingAdviceService {
+    pool: PgPool,
+    repo: NettingAdviceRepository,
+    batch_repo: BatchRepository,
+    config: NettingAdviceConfig,
+    netting: NettingConfig,
+    delivery: Option<Arc<DeliveryChannels>>,
+}
+
+impl NettingAdviceService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: NettingAdviceRepository::new(pool.clone()),
+            batch_repo: BatchRepository::new(pool.clone()),
+            pool,
+            config: NettingAdviceConfig::default(),
+            netting: NettingConfig::default(),
+            delivery: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: NettingAdviceConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the netting configuration batches that were never netted are netted under.
+    pub fn with_netting(mut self, config: NettingConfig) -> Self {
+        self.netting = config;
+        self
+    }
+
+    /// Sets the delivery channels the configured destination is looked up in.
+    pub fn with_delivery(mut self, channels: Arc<DeliveryChannels>) -> Self {
+        self.delivery = Some(channels);
+        self
+    }
+
+    /// Issues the advices of a completed batch, replacing any issued before, and delivers
+    /// them. A batch that has not been netted yet is netted first.
+    pub async fn issue(&self, batch_id: Uuid) -> Result<Vec<NettingAdvice>> {
+        let batch = self.find_batch(batch_id).await?;
+        if batch.status != BatchStatus::Completed {
+            return Err(AppError::Validation(format!(
+                "Batch '{}' is {:?}; netting advices are issued once it is completed",
+                batch_id, batch.status
+            )));
+        }
+
+        let netting = NettingService::new(self.pool.clone()).with_config(self.netting.clone());
+        let report = match netting.get_stored_report(batch_id).await {
+            Ok(report) => report,
+            Err(AppError::NotFound(_)) => {
+                netting
+                    .process_batch_netting_streaming(batch_id, &batch.currency)
+                    .await?
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut advices = Vec::new();
+        for advice in self.build(&batch, &report) {
+            let advice = self.repo.upsert(&advice).await?;
+            self.deliver(&advice).await;
+            advices.push(advice);
+        }
+
+        info!("Issued {} netting advice(s) for batch {}", advices.len(), batch_id);
+        Ok(advices)
+    }
+
+    /// Lists the advices issued for a batch, net payers first.
+    pub async fn list(&self, batch_id: Uuid) -> Result<Vec<NettingAdvice>> {
+        self.find_batch(batch_id).await?;
+        self.repo.find_by_batch(batch_id).await
+    }
+
+    /// Gets the advice a participant was issued for a batch.
+    pub async fn get(&self, batch_id: Uuid, participant_id: Uuid) -> Result<NettingAdvice> {
+        self.repo
+            .find_by_batch_and_participant(batch_id, participant_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "No netting advice for participant '{}' in batch '{}'",
+                    participant_id, batch_id
+                ))
+            })
+    }
+
+    async fn find_batch(&self, batch_id: Uuid) -> Result<SettlementBatch> {
+        self.batch_repo
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch with id '{}' not found", batch_id)))
+    }
+
+    /// Builds an advice per participant position of a batch's netting report.
+    fn build(&self, batch: &SettlementBatch, report: &NettingReport) -> Vec<NettingAdvice> {
+        let Some(multilateral) = &report.multilateral_result else {
+            return Vec::new();
+        };
+        let instructions = report.instructions();
+        let funding_deadline = batch.cut_off_time + self.config.funding_window;
+
+        multilateral
+            .positions
+            .iter()
+            .map(|position| {
+                let participant_instructions = instructions
+                    .iter()
+                    .filter_map(|instruction| Self::advice_instruction(position.participant_id, instruction))
+                    .collect();
+                NettingAdvice::new(
+                    position,
+                    batch.settlement_date,
+                    participant_instructions,
+                    Some(funding_deadline),
+                )
+            })
+            .collect()
+    }
+
+    /// An instruction as seen by a participant, if it pays or receives it.
+    fn advice_instruction(participant_id: Uuid, instruction: &SettlementInstruction) -> Option<AdviceInstruction> {
+        let (side, counterparty_id) = if instruction.from_participant == participant_id {
+            (PositionSide::Payable, instruction.to_participant)
+        } else if instruction.to_participant == participant_id {
+            (PositionSide::Receivable, instruction.from_participant)
+        } else {
+            return None;
+        };
+
+        Some(AdviceInstruction {
+            instruction_id: instruction.id,
+            side,
+            counterparty_id,
+            amount: instruction.amount,
+            instruction_type: format!("{:?}", instruction.instruction_type),
+            status: format!("{:?}", instruction.status),
+        })
+    }
+
+    /// Delivers an advice to the configured destination and webhook. The advice is stored
+    /// either way, so failures are logged rather than returned.
+    async fn deliver(&self, advice: &NettingAdvice) {
+        if let (Some(destination), Some(channels)) = (&self.config.destination, &self.delivery) {
+            let delivery = DeliveryService::new(self.pool.clone()).with_channels(channels.clone());
+            let files = [
+                (advice.file_name("csv"), advice.to_csv().into_bytes()),
+                (advice.file_name("pdf"), advice.to_pdf()),
+            ];
+            for (file_name, content) in files {
+                if let Err(e) = delivery.enqueue(destination, &file_name, content, None).await {
+                    warn!("Failed to queue netting advice {} for delivery: {}", file_name, e);
+                }
+            }
+        }
+
+        if let Some(url) = &self.config.webhook_url {
+            if let Err(e) = self.deliver_webhook(advice, url).await {
+                warn!(
+                    "Failed to post netting advice for participant {} in batch {}: {}",
+                    advice.participant_id, advice.batch_id, e
+                );
+            }
+        }
+    }
+
+    /// Posts an advice as JSON to a webhook.
+    async fn deliver_webhook(&self, advice: &NettingAdvice, url: &str) -> Result<()> {
+        let client = reqwest::Client::builder()
+            .timeout(self.config.webhook_timeout)
+            .build()
+            .map_err(|e| AppError::Internal(anyhow!("Failed to build webhook client: {}", e)))?;
+
+        let body = serde_json::to_vec(advice)
+            .map_err(|e| AppError::Internal(anyhow!("Failed to serialize netting advice: {}", e)))?;
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("Netting advice webhook request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Internal(anyhow!(
+                "Netting advice webhook returned status {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
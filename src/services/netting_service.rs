@@ -1,11 +1,27 @@
+use crate::core::money::{Money, MoneyError};
 use crate::error::{AppError, Result};
-use crate::models::{NettingPosition, NettingSummary, TransactionRecord};
-use crate::repositories::{BatchNettingSummary, NettingRepository};
-use chrono::{DateTime, Utc};
+use crate::models::{
+    BilateralPairRecord, DailyNettingMetrics, InstructionStrategy, InternalAccountRole, NettingMode, NettingPosition, NettingReportRecord, NettingSummary,
+    PositionContribution, SagaState, SettlementBatch, TransactionRecord, TransactionType,
+};
+use crate::observability::get_metrics;
+use crate::netting::optimizer;
+use crate::repositories::{
+    BatchNettingSummary, BatchRepository, BilateralPairRepository, NettingMetricsRepository, NettingReportRepository,
+    NettingRepository, TransactionRepository,
+};
+use crate::services::{ChartOfAccountsService, InstructionExecutor, SettlementRail, TransactionTypeService};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::{Stream, TryStreamExt};
+use rayon::prelude::*;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::warn;
 use uuid::Uuid;
 
 /// Represents a bilateral netting pair between two participants.
@@ -83,6 +99,32 @@ impl BilateralPair {
         }
         (self.netting_benefit() / self.gross_volume()) * Decimal::from(100)
     }
+
+    /// Converts the pair into its stored form for a batch.
+    pub fn to_record(&self, batch_id: Uuid) -> BilateralPairRecord {
+        BilateralPairRecord {
+            batch_id,
+            participant_a: self.participant_a,
+            participant_b: self.participant_b,
+            currency: self.currency.clone(),
+            a_to_b_gross: self.a_to_b_gross,
+            b_to_a_gross: self.b_to_a_gross,
+            net_amount: self.a_to_b_gross - self.b_to_a_gross,
+            transaction_count: self.transaction_count,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl From<&BilateralPairRecord> for BilateralPair {
+    fn from(record: &BilateralPairRecord) -> Self {
+        let mut pair = BilateralPair::new(record.participant_a, record.participant_b, record.currency.clone());
+        pair.a_to_b_gross = record.a_to_b_gross;
+        pair.b_to_a_gross = record.b_to_a_gross;
+        pair.transaction_count = record.transaction_count;
+        pair.recalculate();
+        pair
+    }
 }
 
 /// Settlement instruction generated from netting.
@@ -103,6 +145,12 @@ pub struct SettlementInstruction {
 pub enum InstructionType {
     BilateralNet,
     MultilateralNet,
+    /// A single transaction of a type that does not net, settled for its full amount.
+    Gross,
+    /// A net payer's payment into the settlement control account.
+    Funding,
+    /// A payment from the settlement control account to a net receiver.
+    Payout,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -133,6 +181,33 @@ impl SettlementInstruction {
             created_at: Utc::now(),
         }
     }
+
+    /// The amount the instruction moves, in its currency.
+    pub fn money(&self) -> std::result::Result<Money, MoneyError> {
+        Money::new(self.amount, self.currency.as_str())
+    }
+}
+
+/// Why a transaction was kept out of netting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExclusionReason {
+    /// Its type, custom or base, does not net.
+    TransactionType,
+    /// The transaction itself was flagged to settle gross.
+    Flagged,
+}
+
+/// A batch transaction settled gross, with an instruction of its own, instead of netted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExcludedTransaction {
+    pub transaction_id: Uuid,
+    pub transaction_type: TransactionType,
+    pub type_code: Option<String>,
+    pub from_participant: Uuid,
+    pub to_participant: Uuid,
+    pub amount: Decimal,
+    pub reason: ExclusionReason,
 }
 
 /// Result of bilateral netting calculation.
@@ -168,6 +243,9 @@ pub struct NettingReport {
     pub batch_id: Uuid,
     pub currency: String,
     pub generated_at: DateTime<Utc>,
+    /// How the batch settles; the report's volumes and instructions follow it.
+    #[serde(default)]
+    pub netting_mode: NettingMode,
     pub bilateral_result: Option<BilateralNettingResult>,
     pub multilateral_result: Option<MultilateralNettingResult>,
     pub total_transactions: i32,
@@ -175,6 +253,30 @@ pub struct NettingReport {
     pub net_volume: Decimal,
     pub reduction_amount: Decimal,
     pub reduction_percentage: Decimal,
+    /// Transactions settled gross rather than netted. Their instructions are among the
+    /// results' and their amounts count in both the gross and net volumes.
+    #[serde(default)]
+    pub excluded_transactions: Vec<ExcludedTransaction>,
+    #[serde(default)]
+    pub excluded_volume: Decimal,
+}
+
+impl NettingReport {
+    /// The settlement instructions of the batch's netting mode.
+    pub fn instructions(&self) -> Vec<SettlementInstruction> {
+        match self.netting_mode {
+            NettingMode::Multilateral => self.multilateral_result.as_ref().map(|r| r.instructions.clone()),
+            NettingMode::Bilateral => self.bilateral_result.as_ref().map(|r| r.instructions.clone()),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Records the transactions kept out of netting and their total amount.
+    pub fn with_excluded_transactions(mut self, excluded: Vec<ExcludedTransaction>) -> Self {
+        self.excluded_volume = excluded.iter().map(|tx| tx.amount).sum();
+        self.excluded_transactions = excluded;
+        self
+    }
 }
 
 /// Netting metrics for monitoring.
@@ -187,48 +289,234 @@ pub struct NettingMetrics {
     pub average_efficiency: Decimal,
 }
 
+/// Transaction count from which batches aggregate positions in parallel, unless configured.
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 100_000;
+
+/// Configuration for netting calculations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NettingConfig {
+    /// Batches with at least this many transactions aggregate positions in parallel.
+    pub parallel_threshold: usize,
+    /// Instruction strategy for batches that do not set their own `instruction_strategy`.
+    pub instruction_strategy: InstructionStrategy,
+}
+
+impl Default for NettingConfig {
+    fn default() -> Self {
+        Self {
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+            instruction_strategy: InstructionStrategy::Greedy,
+        }
+    }
+}
+
 /// The netting engine service handles all netting calculations.
 pub struct NettingService {
     pool: PgPool,
     netting_repo: NettingRepository,
+    pair_repo: BilateralPairRepository,
+    report_repo: NettingReportRepository,
+    metrics_repo: NettingMetricsRepository,
+    config: NettingConfig,
     metrics: std::sync::RwLock<NettingMetrics>,
+    /// Codes of the custom transaction types that settle gross, kept out of netting.
+    gross_types: std::sync::RwLock<HashSet<String>>,
+    /// Settlement control account of each currency, for the control account strategy.
+    control_accounts: std::sync::RwLock<HashMap<String, Uuid>>,
+    rail: Option<Arc<dyn SettlementRail>>,
 }
 
 impl NettingService {
     pub fn new(pool: PgPool) -> Self {
         Self {
             netting_repo: NettingRepository::new(pool.clone()),
+            pair_repo: BilateralPairRepository::new(pool.clone()),
+            report_repo: NettingReportRepository::new(pool.clone()),
+            metrics_repo: NettingMetricsRepository::new(pool.clone()),
             pool,
+            config: NettingConfig::default(),
             metrics: std::sync::RwLock::new(NettingMetrics::default()),
+            gross_types: std::sync::RwLock::new(HashSet::new()),
+            control_accounts: std::sync::RwLock::new(HashMap::new()),
+            rail: None,
         }
     }
 
-    /// Calculates bilateral netting for a set of transactions.
+    pub fn with_config(mut self, config: NettingConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Routes released instructions to an external settlement rail.
+    pub fn with_rail(mut self, rail: Arc<dyn SettlementRail>) -> Self {
+        self.rail = Some(rail);
+        self
+    }
+
+    /// Settles transactions of these types gross, by custom type code or base type name.
+    /// Batch netting reloads the set from the transaction type registry before it runs.
+    pub fn with_gross_types(self, codes: impl IntoIterator<Item = String>) -> Self {
+        if let Ok(mut gross_types) = self.gross_types.write() {
+            *gross_types = codes.into_iter().collect();
+        }
+        self
+    }
+
+    /// Reloads the types that settle gross from the transaction type registry.
+    pub async fn refresh_gross_types(&self) -> Result<()> {
+        let codes = TransactionTypeService::new(self.pool.clone()).gross_type_codes().await?;
+        if let Ok(mut gross_types) = self.gross_types.write() {
+            *gross_types = codes.into_iter().collect();
+        }
+        Ok(())
+    }
+
+    fn gross_types(&self) -> HashSet<String> {
+        self.gross_types.read().map(|types| types.clone()).unwrap_or_default()
+    }
+
+    /// Sets the settlement control account the control account strategy routes a
+    /// currency's funds through. Batch netting reloads it from the chart of accounts.
+    pub fn with_control_account(self, currency: &str, account_id: Uuid) -> Self {
+        if let Ok(mut accounts) = self.control_accounts.write() {
+            accounts.insert(currency.to_string(), account_id);
+        }
+        self
+    }
+
+    /// Reloads a currency's settlement control account from the chart of accounts when
+    /// instructions are routed through it.
+    async fn refresh_control_account(&self, currency: &str, strategy: InstructionStrategy) -> Result<()> {
+        if strategy != InstructionStrategy::ControlAccount {
+            return Ok(());
+        }
+        let account_id = ChartOfAccountsService::new(self.pool.clone())
+            .role_account(InternalAccountRole::SettlementControl, currency)
+            .await?;
+        if let (Some(account_id), Ok(mut accounts)) = (account_id, self.control_accounts.write()) {
+            accounts.insert(currency.to_string(), account_id);
+        }
+        Ok(())
+    }
+
+    /// The settlement control account of a currency, if known.
+    pub fn control_account(&self, currency: &str) -> Option<Uuid> {
+        self.control_accounts
+            .read()
+            .ok()
+            .and_then(|accounts| accounts.get(currency).copied())
+    }
+
+    /// Why a transaction settles gross, if it does: its type, by custom code or else base
+    /// type, does not net, or it was flagged itself.
+    fn exclusion_reason(gross_types: &HashSet<String>, tx: &TransactionRecord) -> Option<ExclusionReason> {
+        let code = tx.type_code.as_deref().unwrap_or(tx.transaction_type.as_str());
+        if gross_types.contains(code) {
+            Some(ExclusionReason::TransactionType)
+        } else if tx.exclude_from_netting {
+            Some(ExclusionReason::Flagged)
+        } else {
+            None
+        }
+    }
+
+    fn settles_gross(gross_types: &HashSet<String>, tx: &TransactionRecord) -> bool {
+        Self::exclusion_reason(gross_types, tx).is_some()
+    }
+
+    fn excluded_transaction(tx: &TransactionRecord, reason: ExclusionReason) -> ExcludedTransaction {
+        ExcludedTransaction {
+            transaction_id: tx.id,
+            transaction_type: tx.transaction_type,
+            type_code: tx.type_code.clone(),
+            from_participant: tx.source_account_id,
+            to_participant: tx.destination_account_id,
+            amount: tx.amount,
+            reason,
+        }
+    }
+
+    /// The transactions that settle gross instead of netting.
+    pub fn excluded_transactions(&self, transactions: &[TransactionRecord]) -> Vec<ExcludedTransaction> {
+        let gross_types = self.gross_types();
+        transactions
+            .iter()
+            .filter_map(|tx| Self::exclusion_reason(&gross_types, tx).map(|reason| Self::excluded_transaction(tx, reason)))
+            .collect()
+    }
+
+    /// Splits transactions into those that net and those of types that settle gross.
+    fn split_gross<'a>(&self, transactions: &'a [TransactionRecord]) -> (Vec<&'a TransactionRecord>, Vec<&'a TransactionRecord>) {
+        let gross_types = self.gross_types();
+        transactions.iter().partition(|tx| !Self::settles_gross(&gross_types, tx))
+    }
+
+    /// One gross instruction per transaction, from payer to payee for its full amount.
+    fn gross_instructions(batch_id: Uuid, currency: &str, transactions: &[&TransactionRecord]) -> Vec<SettlementInstruction> {
+        transactions
+            .iter()
+            .map(|tx| {
+                SettlementInstruction::new(
+                    batch_id,
+                    tx.source_account_id,
+                    tx.destination_account_id,
+                    tx.amount,
+                    currency.to_string(),
+                    InstructionType::Gross,
+                )
+            })
+            .collect()
+    }
+
+    /// Calculates bilateral netting for a set of transactions. Transactions that settle
+    /// gross, by type or flag, are left out of the pairs and get an instruction each.
     pub fn calculate_bilateral_netting(
         &self,
         batch_id: Uuid,
         currency: &str,
         transactions: &[TransactionRecord],
     ) -> BilateralNettingResult {
+        let (netted, gross) = self.split_gross(transactions);
         let mut pairs: HashMap<(Uuid, Uuid), BilateralPair> = HashMap::new();
 
-        for tx in transactions {
-            let (key, is_a_to_b) = self.normalize_pair_key(tx.source_account_id, tx.destination_account_id);
+        for tx in netted {
+            self.apply_to_pairs(&mut pairs, currency, tx);
+        }
 
-            let pair = pairs.entry(key).or_insert_with(|| {
-                BilateralPair::new(key.0, key.1, currency.to_string())
-            });
+        let gross = Self::gross_instructions(batch_id, currency, &gross);
+        self.build_bilateral_result(batch_id, currency, pairs, gross)
+    }
 
-            if is_a_to_b {
-                pair.add_a_to_b(tx.amount);
-            } else {
-                pair.add_b_to_a(tx.amount);
-            }
+    fn apply_to_pairs(
+        &self,
+        pairs: &mut HashMap<(Uuid, Uuid), BilateralPair>,
+        currency: &str,
+        tx: &TransactionRecord,
+    ) {
+        let (key, is_a_to_b) = self.normalize_pair_key(tx.source_account_id, tx.destination_account_id);
+
+        let pair = pairs.entry(key).or_insert_with(|| {
+            BilateralPair::new(key.0, key.1, currency.to_string())
+        });
+
+        if is_a_to_b {
+            pair.add_a_to_b(tx.amount);
+        } else {
+            pair.add_b_to_a(tx.amount);
         }
+    }
 
+    fn build_bilateral_result(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        pairs: HashMap<(Uuid, Uuid), BilateralPair>,
+        gross: Vec<SettlementInstruction>,
+    ) -> BilateralNettingResult {
         let pairs_vec: Vec<BilateralPair> = pairs.into_values().collect();
-        let total_gross: Decimal = pairs_vec.iter().map(|p| p.gross_volume()).sum();
-        let total_net: Decimal = pairs_vec.iter().map(|p| p.net_amount).sum();
+        let gross_amount: Decimal = gross.iter().map(|i| i.amount).sum();
+        let total_gross: Decimal = pairs_vec.iter().map(|p| p.gross_volume()).sum::<Decimal>() + gross_amount;
+        let total_net: Decimal = pairs_vec.iter().map(|p| p.net_amount).sum::<Decimal>() + gross_amount;
 
         let efficiency = if total_gross.is_zero() {
             Decimal::ZERO
@@ -236,7 +524,8 @@ impl NettingService {
             ((total_gross - total_net) / total_gross) * Decimal::from(100)
         };
 
-        let instructions = self.generate_bilateral_instructions(batch_id, &pairs_vec);
+        let mut instructions = self.generate_bilateral_instructions(batch_id, &pairs_vec);
+        instructions.extend(gross);
 
         BilateralNettingResult {
             batch_id,
@@ -257,7 +546,9 @@ impl NettingService {
         }
     }
 
-    fn generate_bilateral_instructions(
+    /// Produces one settlement instruction per pair that doesn't balance, from the net
+    /// payer to the net receiver.
+    pub fn generate_bilateral_instructions(
         &self,
         batch_id: Uuid,
         pairs: &[BilateralPair],
@@ -283,33 +574,92 @@ impl NettingService {
             .collect()
     }
 
-    /// Calculates multilateral netting for a set of transactions.
+    /// Calculates multilateral netting for a set of transactions. Transactions that settle
+    /// gross, by type or flag, are left out of the positions and get an instruction each.
+    ///
+    /// Batches at or above the configured parallel threshold are aggregated across the
+    /// rayon thread pool; the result is the same either way.
     pub fn calculate_multilateral_netting(
         &self,
         batch_id: Uuid,
         currency: &str,
         transactions: &[TransactionRecord],
     ) -> MultilateralNettingResult {
-        let mut positions: HashMap<Uuid, NettingPosition> = HashMap::new();
+        self.calculate_multilateral_netting_with_strategy(
+            batch_id,
+            currency,
+            transactions,
+            self.config.instruction_strategy,
+        )
+    }
 
-        for tx in transactions {
-            // Source pays
-            let source_pos = positions
-                .entry(tx.source_account_id)
-                .or_insert_with(|| NettingPosition::new(batch_id, tx.source_account_id, currency.to_string()));
-            source_pos.add_payable(tx.amount);
+    /// Calculates multilateral netting using the given instruction strategy for this batch.
+    pub fn calculate_multilateral_netting_with_strategy(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        transactions: &[TransactionRecord],
+        strategy: InstructionStrategy,
+    ) -> MultilateralNettingResult {
+        let (netted, gross) = self.split_gross(transactions);
+        let positions = if netted.len() >= self.config.parallel_threshold {
+            Self::aggregate_positions_parallel(batch_id, currency, &netted)
+        } else {
+            let mut positions = HashMap::new();
+            for tx in netted {
+                Self::apply_to_positions(&mut positions, batch_id, currency, tx);
+            }
+            positions
+        };
 
-            // Destination receives
-            let dest_pos = positions
-                .entry(tx.destination_account_id)
-                .or_insert_with(|| NettingPosition::new(batch_id, tx.destination_account_id, currency.to_string()));
-            dest_pos.add_receivable(tx.amount);
+        let gross = Self::gross_instructions(batch_id, currency, &gross);
+        self.build_multilateral_result(batch_id, currency, positions, gross, strategy)
+    }
+
+    /// Calculates multilateral netting from a stream of transactions.
+    ///
+    /// Positions are accumulated as rows arrive, so memory is bounded by the number of
+    /// participants rather than the number of transactions.
+    pub async fn calculate_multilateral_netting_streaming<S>(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        mut transactions: S,
+    ) -> Result<MultilateralNettingResult>
+    where
+        S: Stream<Item = Result<TransactionRecord>> + Unpin,
+    {
+        let gross_types = self.gross_types();
+        let mut positions = HashMap::new();
+        let mut gross = Vec::new();
+        while let Some(tx) = transactions.try_next().await? {
+            if Self::settles_gross(&gross_types, &tx) {
+                gross.extend(Self::gross_instructions(batch_id, currency, &[&tx]));
+            } else {
+                Self::apply_to_positions(&mut positions, batch_id, currency, &tx);
+            }
         }
 
+        Ok(self.build_multilateral_result(batch_id, currency, positions, gross, self.config.instruction_strategy))
+    }
+
+    fn build_multilateral_result(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        positions: HashMap<Uuid, NettingPosition>,
+        gross: Vec<SettlementInstruction>,
+        strategy: InstructionStrategy,
+    ) -> MultilateralNettingResult {
         let positions_vec: Vec<NettingPosition> = positions.into_values().collect();
-        let summary = NettingSummary::from_positions(batch_id, currency.to_string(), &positions_vec);
+        let mut summary = NettingSummary::from_positions(batch_id, currency.to_string(), &positions_vec);
+        // Positions count each transaction on both sides, payer and payee
+        let gross_amount: Decimal = gross.iter().map(|i| i.amount).sum::<Decimal>() * Decimal::TWO;
+        summary.total_gross_volume += gross_amount;
+        summary.total_net_volume += gross_amount;
 
-        let instructions = self.generate_multilateral_instructions(batch_id, currency, &positions_vec);
+        let mut instructions = self.generate_instructions(batch_id, currency, &positions_vec, strategy);
+        instructions.extend(gross);
 
         MultilateralNettingResult {
             batch_id,
@@ -325,7 +675,144 @@ impl NettingService {
         }
     }
 
-    fn generate_multilateral_instructions(
+    /// Folds transactions into per-thread position maps and merges them pairwise.
+    fn aggregate_positions_parallel(
+        batch_id: Uuid,
+        currency: &str,
+        transactions: &[&TransactionRecord],
+    ) -> HashMap<Uuid, NettingPosition> {
+        transactions
+            .par_iter()
+            .fold(HashMap::new, |mut positions, tx| {
+                Self::apply_to_positions(&mut positions, batch_id, currency, tx);
+                positions
+            })
+            .reduce(HashMap::new, |mut merged, partial| {
+                for (participant_id, position) in partial {
+                    match merged.entry(participant_id) {
+                        Entry::Occupied(mut existing) => existing.get_mut().merge(&position),
+                        Entry::Vacant(slot) => {
+                            slot.insert(position);
+                        }
+                    }
+                }
+                merged
+            })
+    }
+
+    fn apply_to_positions(
+        positions: &mut HashMap<Uuid, NettingPosition>,
+        batch_id: Uuid,
+        currency: &str,
+        tx: &TransactionRecord,
+    ) {
+        // Source pays
+        positions
+            .entry(tx.source_account_id)
+            .or_insert_with(|| NettingPosition::new(batch_id, tx.source_account_id, currency.to_string()))
+            .add_payable(tx.amount);
+
+        // Destination receives
+        positions
+            .entry(tx.destination_account_id)
+            .or_insert_with(|| NettingPosition::new(batch_id, tx.destination_account_id, currency.to_string()))
+            .add_receivable(tx.amount);
+    }
+
+    /// Produces the fewest settlement instructions that clear a set of multilateral positions.
+    /// The total amount moved is the same as the greedy matcher's.
+    pub fn generate_minimal_instructions(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        positions: &[NettingPosition],
+    ) -> Vec<SettlementInstruction> {
+        let nets: Vec<(Uuid, Decimal)> = positions
+            .iter()
+            .map(|p| (p.participant_id, p.net_position))
+            .collect();
+
+        optimizer::minimize_transfers(&nets)
+            .into_iter()
+            .map(|t| {
+                SettlementInstruction::new(
+                    batch_id,
+                    t.from,
+                    t.to,
+                    t.amount,
+                    currency.to_string(),
+                    InstructionType::MultilateralNet,
+                )
+            })
+            .collect()
+    }
+
+    /// Routes a set of multilateral positions through a settlement control account: each
+    /// net payer funds the account with its net debit and each net receiver is paid its
+    /// net credit from it. As positions sum to zero, so do the account's movements.
+    pub fn generate_control_account_instructions(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        control_account_id: Uuid,
+        positions: &[NettingPosition],
+    ) -> Vec<SettlementInstruction> {
+        let mut sorted: Vec<&NettingPosition> = positions.iter().filter(|p| !p.is_balanced()).collect();
+        // Funding legs first, then payouts, each in participant order
+        sorted.sort_by_key(|p| (p.is_net_receiver(), p.participant_id));
+
+        sorted
+            .into_iter()
+            .map(|position| {
+                let (from, to, instruction_type) = if position.is_net_payer() {
+                    (position.participant_id, control_account_id, InstructionType::Funding)
+                } else {
+                    (control_account_id, position.participant_id, InstructionType::Payout)
+                };
+                SettlementInstruction::new(
+                    batch_id,
+                    from,
+                    to,
+                    position.absolute_net(),
+                    currency.to_string(),
+                    instruction_type,
+                )
+            })
+            .collect()
+    }
+
+    /// Produces the settlement instructions for a set of multilateral positions under
+    /// `strategy`. Control-account routing falls back to direct settlement when the
+    /// currency has no control account.
+    pub fn generate_instructions(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        positions: &[NettingPosition],
+        strategy: InstructionStrategy,
+    ) -> Vec<SettlementInstruction> {
+        match strategy {
+            InstructionStrategy::Greedy => self.generate_multilateral_instructions(batch_id, currency, positions),
+            InstructionStrategy::MinimumCount => self.generate_minimal_instructions(batch_id, currency, positions),
+            InstructionStrategy::ControlAccount => match self.control_account(currency) {
+                Some(control_account_id) => {
+                    self.generate_control_account_instructions(batch_id, currency, control_account_id, positions)
+                }
+                None => {
+                    warn!(
+                        "No settlement control account in {}; batch {} settles directly between participants",
+                        currency, batch_id
+                    );
+                    self.generate_multilateral_instructions(batch_id, currency, positions)
+                }
+            },
+        }
+    }
+
+    /// Matches net payers to net receivers greedily, producing the settlement instructions
+    /// for a set of multilateral positions. Use [`Self::generate_instructions`] to honour
+    /// a strategy.
+    pub fn generate_multilateral_instructions(
         &self,
         batch_id: Uuid,
         currency: &str,
@@ -408,6 +895,47 @@ impl NettingService {
         self.netting_repo.find_by_batch(batch_id).await
     }
 
+    /// Lists the transactions that made up a participant's gross receivable and payable
+    /// in a batch, oldest first, with how many there are in all. Transactions that settled
+    /// gross are left out, as they were of the position.
+    pub async fn get_position_transactions(
+        &self,
+        batch_id: Uuid,
+        participant_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<PositionContribution>, i64)> {
+        let batch = BatchRepository::new(self.pool.clone())
+            .find_by_id(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Batch '{}' not found", batch_id)))?;
+        self.netting_repo
+            .find_by_batch_and_participant(batch_id, participant_id, &batch.currency)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "No netting position for participant '{}' in batch '{}'",
+                    participant_id, batch_id
+                ))
+            })?;
+
+        self.refresh_gross_types().await?;
+        let gross_types: Vec<String> = self.gross_types().into_iter().collect();
+        let transaction_repo = TransactionRepository::new(self.pool.clone());
+        let total = transaction_repo
+            .count_netted_by_participant(batch_id, participant_id, &gross_types)
+            .await?;
+        let transactions = transaction_repo
+            .find_netted_by_participant(batch_id, participant_id, &gross_types, limit, offset)
+            .await?;
+
+        let contributions = transactions
+            .iter()
+            .map(|transaction| PositionContribution::new(participant_id, transaction))
+            .collect();
+        Ok((contributions, total))
+    }
+
     /// Gets batch netting summary.
     pub async fn get_batch_summary(&self, batch_id: Uuid) -> Result<BatchNettingSummary> {
         self.netting_repo.get_batch_summary(batch_id).await
@@ -419,12 +947,54 @@ impl NettingService {
         batch_id: Uuid,
         currency: &str,
         transactions: &[TransactionRecord],
+    ) -> NettingReport {
+        self.generate_report_with_mode(batch_id, currency, transactions, NettingMode::Multilateral)
+    }
+
+    /// Generates a complete netting report for a batch settled in `mode`.
+    pub fn generate_report_with_mode(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        transactions: &[TransactionRecord],
+        mode: NettingMode,
+    ) -> NettingReport {
+        self.generate_report_with_strategy(batch_id, currency, transactions, mode, self.config.instruction_strategy)
+    }
+
+    /// Generates a complete netting report for a batch settled in `mode`, with its
+    /// instructions produced under `strategy`.
+    pub fn generate_report_with_strategy(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        transactions: &[TransactionRecord],
+        mode: NettingMode,
+        strategy: InstructionStrategy,
     ) -> NettingReport {
         let bilateral = self.calculate_bilateral_netting(batch_id, currency, transactions);
-        let multilateral = self.calculate_multilateral_netting(batch_id, currency, transactions);
+        let multilateral =
+            self.calculate_multilateral_netting_with_strategy(batch_id, currency, transactions, strategy);
+
+        let excluded = self.excluded_transactions(transactions);
+
+        self.build_report(batch_id, currency, mode, bilateral, multilateral, transactions.len())
+            .with_excluded_transactions(excluded)
+    }
 
-        let gross_volume = multilateral.total_gross_volume;
-        let net_volume = multilateral.total_net_volume;
+    fn build_report(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+        mode: NettingMode,
+        bilateral: BilateralNettingResult,
+        multilateral: MultilateralNettingResult,
+        transaction_count: usize,
+    ) -> NettingReport {
+        let (gross_volume, net_volume) = match mode {
+            NettingMode::Multilateral => (multilateral.total_gross_volume, multilateral.total_net_volume),
+            NettingMode::Bilateral => (bilateral.total_gross_volume, bilateral.total_net_volume),
+        };
         let reduction_amount = gross_volume - net_volume;
         let reduction_percentage = if gross_volume.is_zero() {
             Decimal::ZERO
@@ -433,19 +1003,22 @@ impl NettingService {
         };
 
         // Update metrics
-        self.update_metrics(transactions.len() as u64, gross_volume, net_volume);
+        self.update_metrics(transaction_count as u64, gross_volume, net_volume);
 
         NettingReport {
             batch_id,
             currency: currency.to_string(),
             generated_at: Utc::now(),
+            netting_mode: mode,
             bilateral_result: Some(bilateral),
             multilateral_result: Some(multilateral),
-            total_transactions: transactions.len() as i32,
+            total_transactions: transaction_count as i32,
             gross_volume,
             net_volume,
             reduction_amount,
             reduction_percentage,
+            excluded_transactions: Vec::new(),
+            excluded_volume: Decimal::ZERO,
         }
     }
 
@@ -473,6 +1046,32 @@ impl NettingService {
         self.netting_repo.delete_by_batch(batch_id).await
     }
 
+    /// Releases pending instructions to the settlement rail, each as an instruction
+    /// execution saga, and updates their status from the rail's acknowledgement.
+    /// Instructions that are no longer pending are left untouched.
+    pub async fn release_instructions(
+        &self,
+        instructions: &mut [SettlementInstruction],
+    ) -> Result<Vec<SagaState>> {
+        let rail = self
+            .rail
+            .clone()
+            .ok_or_else(|| AppError::Validation("No settlement rail configured".to_string()))?;
+        let executor = InstructionExecutor::new(self.pool.clone(), rail);
+
+        let mut sagas = Vec::new();
+        for instruction in instructions
+            .iter_mut()
+            .filter(|i| i.status == InstructionStatus::Pending)
+        {
+            let (state, executed) = executor.execute(instruction.clone()).await?;
+            *instruction = executed;
+            sagas.push(state);
+        }
+
+        Ok(sagas)
+    }
+
     /// Performs full netting for a batch and persists results.
     pub async fn process_batch_netting(
         &self,
@@ -480,22 +1079,257 @@ impl NettingService {
         currency: &str,
         transactions: &[TransactionRecord],
     ) -> Result<NettingReport> {
-        // Calculate multilateral netting
-        let result = self.calculate_multilateral_netting(batch_id, currency, transactions);
+        self.refresh_gross_types().await?;
+        let (mode, strategy) = self.batch_netting(batch_id).await?;
+        self.refresh_control_account(currency, strategy).await?;
+        let report = self.generate_report_with_strategy(batch_id, currency, transactions, mode, strategy);
+
+        self.persist_netting(&report).await?;
+        Ok(report)
+    }
+
+    /// Performs full netting for a batch by streaming its transactions from the database
+    /// and persists the resulting positions.
+    ///
+    /// Bilateral pairs and multilateral positions are built in a single pass over the
+    /// cursor, so batches far larger than memory can be netted.
+    pub async fn process_batch_netting_streaming(
+        &self,
+        batch_id: Uuid,
+        currency: &str,
+    ) -> Result<NettingReport> {
+        let report = self.calculate_batch_netting(batch_id, currency).await?;
+        self.persist_netting(&report).await?;
+        Ok(report)
+    }
 
-        // Persist positions
-        self.persist_positions(&result.positions).await?;
+    /// The netting report a batch settles on: its stored report once it has been netted,
+    /// otherwise one calculated now, without persisting it.
+    pub async fn batch_report(&self, batch_id: Uuid, currency: &str) -> Result<NettingReport> {
+        match self.get_stored_report(batch_id).await {
+            Err(AppError::NotFound(_)) => self.calculate_batch_netting(batch_id, currency).await,
+            result => result,
+        }
+    }
+
+    /// Produces the settlement instructions for a batch's multilateral positions under its
+    /// own instruction strategy, or the configured one.
+    pub async fn batch_instructions(
+        &self,
+        batch: &SettlementBatch,
+        positions: &[NettingPosition],
+    ) -> Result<Vec<SettlementInstruction>> {
+        let strategy = batch.instruction_strategy.unwrap_or(self.config.instruction_strategy);
+        self.refresh_control_account(&batch.currency, strategy).await?;
+        Ok(self.generate_instructions(batch.id, &batch.currency, positions, strategy))
+    }
+
+    /// Nets a batch by streaming its transactions from the database, without persisting
+    /// the result.
+    async fn calculate_batch_netting(&self, batch_id: Uuid, currency: &str) -> Result<NettingReport> {
+        self.refresh_gross_types().await?;
+        let (mode, strategy) = self.batch_netting(batch_id).await?;
+        self.refresh_control_account(currency, strategy).await?;
+        let transaction_repo = TransactionRepository::new(self.pool.clone());
+        let mut transactions = transaction_repo.stream_by_batch(batch_id);
+
+        let gross_types = self.gross_types();
+        let mut pairs = HashMap::new();
+        let mut positions = HashMap::new();
+        let mut gross = Vec::new();
+        let mut excluded = Vec::new();
+        let mut transaction_count = 0usize;
+        while let Some(tx) = transactions.try_next().await? {
+            if let Some(reason) = Self::exclusion_reason(&gross_types, &tx) {
+                gross.extend(Self::gross_instructions(batch_id, currency, &[&tx]));
+                excluded.push(Self::excluded_transaction(&tx, reason));
+            } else {
+                self.apply_to_pairs(&mut pairs, currency, &tx);
+                Self::apply_to_positions(&mut positions, batch_id, currency, &tx);
+            }
+            transaction_count += 1;
+        }
 
-        // Generate full report
-        Ok(self.generate_report(batch_id, currency, transactions))
+        let bilateral = self.build_bilateral_result(batch_id, currency, pairs, gross.clone());
+        let multilateral = self.build_multilateral_result(batch_id, currency, positions, gross, strategy);
+
+        Ok(self
+            .build_report(batch_id, currency, mode, bilateral, multilateral, transaction_count)
+            .with_excluded_transactions(excluded))
+    }
+
+    /// Netting mode and instruction strategy of a batch. Batches not on record net
+    /// multilaterally, and batches without a strategy of their own use the configured one.
+    async fn batch_netting(&self, batch_id: Uuid) -> Result<(NettingMode, InstructionStrategy)> {
+        let batch = BatchRepository::new(self.pool.clone()).find_by_id(batch_id).await?;
+        let mode = batch.as_ref().map(|batch| batch.netting_mode).unwrap_or_default();
+        let strategy = batch
+            .and_then(|batch| batch.instruction_strategy)
+            .unwrap_or(self.config.instruction_strategy);
+        Ok((mode, strategy))
+    }
+
+    /// Persists a batch's positions, its bilateral pairs when it nets bilaterally, the
+    /// report and the day's metrics.
+    async fn persist_netting(&self, report: &NettingReport) -> Result<()> {
+        if let Some(multilateral) = &report.multilateral_result {
+            self.persist_positions(&multilateral.positions).await?;
+        }
+        if report.netting_mode == NettingMode::Bilateral {
+            if let Some(bilateral) = &report.bilateral_result {
+                self.persist_bilateral_pairs(report.batch_id, &bilateral.pairs).await?;
+            }
+        }
+
+        self.persist_report(report).await?;
+        self.record_daily_metrics(report).await?;
+        Ok(())
+    }
+
+    /// Persists a batch's bilateral pairs, replacing any stored earlier.
+    pub async fn persist_bilateral_pairs(
+        &self,
+        batch_id: Uuid,
+        pairs: &[BilateralPair],
+    ) -> Result<Vec<BilateralPairRecord>> {
+        let records: Vec<BilateralPairRecord> = pairs.iter().map(|pair| pair.to_record(batch_id)).collect();
+        self.pair_repo.upsert_batch(&records).await
+    }
+
+    /// Gets the stored bilateral pairs of a batch.
+    pub async fn get_bilateral_pairs(&self, batch_id: Uuid) -> Result<Vec<BilateralPairRecord>> {
+        self.pair_repo.find_by_batch(batch_id).await
+    }
+
+    /// Adds a netted batch to the persisted daily metrics, replacing its contribution if it
+    /// was netted before, and exports the day's totals.
+    async fn record_daily_metrics(&self, report: &NettingReport) -> Result<DailyNettingMetrics> {
+        let daily = self
+            .metrics_repo
+            .record_batch(
+                report.batch_id,
+                report.generated_at.date_naive(),
+                &report.currency,
+                report.total_transactions as i64,
+                report.gross_volume,
+                report.net_volume,
+            )
+            .await?;
+
+        get_metrics().record_netting_batch(&report.currency, report.total_transactions as u64);
+        export_daily_metrics(&daily);
+        Ok(daily)
+    }
+
+    /// Gets persisted daily netting metrics in `[from, to]`.
+    pub async fn get_daily_metrics(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        currency: Option<&str>,
+    ) -> Result<Vec<DailyNettingMetrics>> {
+        self.metrics_repo.find_by_period(from, to, currency).await
+    }
+
+    /// Exports a day's persisted netting metrics as Prometheus gauges, e.g. after a
+    /// restart, and returns them.
+    pub async fn export_daily_metrics(&self, date: NaiveDate) -> Result<Vec<DailyNettingMetrics>> {
+        let metrics = self.metrics_repo.find_by_period(date, date, None).await?;
+        metrics.iter().for_each(export_daily_metrics);
+        Ok(metrics)
+    }
+
+    /// Replaces the in-process metrics with the totals persisted across all instances
+    /// and restarts.
+    pub async fn restore_metrics(&self) -> Result<NettingMetrics> {
+        let (batches, transactions, gross, net) = self.metrics_repo.totals().await?;
+        let restored = NettingMetrics {
+            batches_processed: batches as u64,
+            total_transactions_netted: transactions as u64,
+            total_gross_volume: gross,
+            total_net_volume: net,
+            average_efficiency: if gross.is_zero() {
+                Decimal::ZERO
+            } else {
+                ((gross - net) / gross) * Decimal::from(100)
+            },
+        };
+
+        if let Ok(mut metrics) = self.metrics.write() {
+            *metrics = restored.clone();
+        }
+        Ok(restored)
+    }
+
+    /// Stores a batch's netting report for historical analysis.
+    pub async fn persist_report(&self, report: &NettingReport) -> Result<NettingReportRecord> {
+        let record = NettingReportRecord {
+            id: Uuid::new_v4(),
+            batch_id: report.batch_id,
+            currency: report.currency.clone(),
+            total_transactions: report.total_transactions,
+            participant_count: report
+                .multilateral_result
+                .as_ref()
+                .map(|result| result.participant_count)
+                .unwrap_or(0),
+            gross_volume: report.gross_volume,
+            net_volume: report.net_volume,
+            reduction_amount: report.reduction_amount,
+            reduction_percentage: report.reduction_percentage.round_dp(4),
+            report: serde_json::to_value(report)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize netting report: {}", e)))?,
+            generated_at: report.generated_at,
+            window_id: None,
+        };
+
+        self.report_repo.upsert(&record).await
+    }
+
+    /// Gets the stored netting report of a batch.
+    pub async fn get_stored_report(&self, batch_id: Uuid) -> Result<NettingReport> {
+        let record = self
+            .report_repo
+            .find_by_batch(batch_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("No netting report for batch '{}'", batch_id)))?;
+
+        serde_json::from_value(record.report)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Stored netting report is invalid: {}", e)))
+    }
+
+    /// Lists stored netting reports generated in `[from, to)`, optionally of one currency
+    /// or named settlement window.
+    pub async fn list_stored_reports(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        currency: Option<&str>,
+        window_id: Option<Uuid>,
+    ) -> Result<Vec<NettingReportRecord>> {
+        if from >= to {
+            return Err(AppError::Validation("'from' must be before 'to'".to_string()));
+        }
+
+        self.report_repo.find_by_period(from, to, currency, window_id).await
     }
 }
 
+fn export_daily_metrics(daily: &DailyNettingMetrics) {
+    get_metrics().set_netting_daily(
+        &daily.currency,
+        &daily.metric_date.to_string(),
+        daily.gross_volume.to_f64().unwrap_or(0.0),
+        daily.net_volume.to_f64().unwrap_or(0.0),
+        daily.efficiency().to_f64().unwrap_or(0.0),
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rust_decimal_macros::dec;
-    use crate::models::{TransactionType, TransactionStatus};
+    use crate::models::{SettlementRoute, TransactionPriority, TransactionType, TransactionStatus};
 
     fn create_test_transaction(
         source: Uuid,
@@ -519,6 +1353,16 @@ mod tests {
             settlement_batch_id: None,
             created_at: Utc::now(),
             settled_at: Some(Utc::now()),
+            settlement_route: SettlementRoute::Netted,
+            priority: TransactionPriority::Normal,
+            source_system: None,
+            resubmission_of: None,
+            dedupe_key: None,
+            type_code: None,
+            exclude_from_netting: false,
+            narrative: None,
+            reference: None,
+            link_id: None,
         }
     }
 
@@ -618,6 +1462,23 @@ mod tests {
         assert_eq!(result.instructions.len(), 1);
     }
 
+    #[test]
+    fn test_bilateral_pair_record_round_trip() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut pair = BilateralPair::new(a, b, "USD".to_string());
+        pair.add_a_to_b(dec!(40));
+        pair.add_b_to_a(dec!(100));
+
+        let record = pair.to_record(Uuid::new_v4());
+        assert_eq!(record.net_amount, dec!(-60));
+        assert_eq!(record.payer_and_payee(), Some((b, a)));
+
+        let restored = BilateralPair::from(&record);
+        assert_eq!(restored.net_amount, dec!(60));
+        assert_eq!(restored.net_direction, NetDirection::BToA);
+        assert_eq!(restored.transaction_count, 2);
+    }
+
     #[test]
     fn test_multilateral_netting_calculation() {
         let batch_id = Uuid::new_v4();
@@ -690,6 +1551,51 @@ mod tests {
         assert_eq!(result.netting_efficiency, dec!(100));
     }
 
+    #[test]
+    fn test_parallel_aggregation_matches_sequential() {
+        let batch_id = Uuid::new_v4();
+        let banks: Vec<Uuid> = (0..7).map(|_| Uuid::new_v4()).collect();
+        let transactions: Vec<TransactionRecord> = (0..5_000)
+            .map(|i| {
+                let from = banks[i % banks.len()];
+                let to = banks[(i * 3 + 1) % banks.len()];
+                create_test_transaction(from, to, Decimal::new(i as i64 + 1, 2), "USD")
+            })
+            .filter(|tx| tx.source_account_id != tx.destination_account_id)
+            .collect();
+
+        let sequential = calculate_multilateral_netting_standalone(batch_id, "USD", &transactions);
+        let parallel = NettingService::aggregate_positions_parallel(batch_id, "USD", &transactions.iter().collect::<Vec<_>>());
+
+        assert_eq!(parallel.len(), sequential.positions.len());
+        for expected in &sequential.positions {
+            let actual = &parallel[&expected.participant_id];
+            assert_eq!(actual.gross_receivable, expected.gross_receivable);
+            assert_eq!(actual.gross_payable, expected.gross_payable);
+            assert_eq!(actual.net_position, expected.net_position);
+            assert_eq!(actual.transaction_count, expected.transaction_count);
+        }
+    }
+
+    #[test]
+    fn test_exclusion_reason_prefers_transaction_type() {
+        let bank_a = Uuid::new_v4();
+        let bank_b = Uuid::new_v4();
+        let gross_types: HashSet<String> = ["REFUND".to_string()].into_iter().collect();
+
+        let payment = create_test_transaction(bank_a, bank_b, dec!(100), "USD");
+        let flagged = create_test_transaction(bank_a, bank_b, dec!(100), "USD").excluded_from_netting(true);
+        let mut refund = create_test_transaction(bank_b, bank_a, dec!(100), "USD").excluded_from_netting(true);
+        refund.transaction_type = TransactionType::Refund;
+
+        assert_eq!(NettingService::exclusion_reason(&gross_types, &payment), None);
+        assert_eq!(NettingService::exclusion_reason(&gross_types, &flagged), Some(ExclusionReason::Flagged));
+        assert_eq!(
+            NettingService::exclusion_reason(&gross_types, &refund),
+            Some(ExclusionReason::TransactionType)
+        );
+    }
+
     fn calculate_bilateral_netting_standalone(
         batch_id: Uuid,
         currency: &str,
@@ -0,0 +1,366 @@
+use crate::core::leader::LeaderElection;
+use crate::error::{AppError, Result};
+use crate::events::{enqueue_event, EventEnvelope, EventType, PvpEvent};
+use crate::models::{PvpLeg, PvpSettlement, PvpStatus};
+use crate::repositories::{BalanceRepository, PvpRepository, TransactionRepository};
+use crate::services::{LedgerService, LedgerTransactionRequest};
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// How long a PvP settlement waits for funding unless the request sets a deadline.
+pub const DEFAULT_PVP_TIMEOUT_SECS: u64 = 3600;
+
+/// Reason recorded on settlements cancelled at their deadline.
+const EXPIRED_REASON: &str = "Not funded by the deadline";
+
+/// Request to settle two opposite payments in different currencies payment-versus-payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PvpRequest {
+    pub external_id: String,
+    pub idempotency_key: String,
+    /// First payment, from one counterparty to the other.
+    pub first_source_account_id: Uuid,
+    pub first_destination_account_id: Uuid,
+    pub first_amount: Decimal,
+    pub first_currency: String,
+    /// Second payment, back the other way in another currency.
+    pub second_source_account_id: Uuid,
+    pub second_destination_account_id: Uuid,
+    pub second_amount: Decimal,
+    pub second_currency: String,
+    /// How long to wait for both payers to be funded; the service's timeout by default.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Outcome of a sweep of settlements awaiting funding.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PvpSweepReport {
+    pub checked: usize,
+    pub settled: usize,
+    pub cancelled: usize,
+}
+
+/// Settles opposite payments in two currencies payment-versus-payment.
+///
+/// A settlement holds both payments until each payer has the funds for its own, then
+/// posts them together through `LedgerService::execute_linked`, linked by the
+/// settlement's ID, so neither counterparty pays without being paid. Funding is checked
+/// when the settlement is created, on request and by `PvpJob`; settlements still not
+/// funded at their deadline are cancelled without either payment being made. Each step
+/// queues a PvP event on the `settlement.pvp` topic.
+pub struct PvpService {
+    pool: PgPool,
+    repo: PvpRepository,
+    balance_repo: BalanceRepository,
+    transaction_repo: TransactionRepository,
+    ledger: Arc<LedgerService>,
+    timeout: Duration,
+    batch_size: i64,
+}
+
+impl PvpService {
+    pub fn new(pool: PgPool, ledger: Arc<LedgerService>) -> Self {
+        Self {
+            repo: PvpRepository::new(pool.clone()),
+            balance_repo: BalanceRepository::new(pool.clone()),
+            transaction_repo: TransactionRepository::new(pool.clone()),
+            pool,
+            ledger,
+            timeout: Duration::seconds(DEFAULT_PVP_TIMEOUT_SECS as i64),
+            batch_size: 100,
+        }
+    }
+
+    /// Sets how long settlements wait for funding by default.
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Duration::from_std(timeout).unwrap_or(self.timeout);
+        self
+    }
+
+    /// Sets how many settlements a sweep checks.
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Accepts a PvP settlement and settles it at once if both payers are funded.
+    /// Resubmitting an idempotency key returns the settlement already accepted.
+    pub async fn create(&self, request: PvpRequest) -> Result<PvpSettlement> {
+        if let Some(existing) = self.repo.find_by_idempotency_key(&request.idempotency_key).await? {
+            return Ok(existing);
+        }
+
+        let timeout = request
+            .timeout_secs
+            .map(|secs| Duration::seconds(secs as i64))
+            .unwrap_or(self.timeout);
+        let pvp = PvpSettlement::new(request.external_id, request.idempotency_key, timeout)
+            .with_first_payment(
+                request.first_source_account_id,
+                request.first_destination_account_id,
+                request.first_amount,
+                request.first_currency,
+            )
+            .with_second_payment(
+                request.second_source_account_id,
+                request.second_destination_account_id,
+                request.second_amount,
+                request.second_currency,
+            );
+        pvp.validate().map_err(AppError::Validation)?;
+        for payment in Self::payments(&pvp) {
+            self.ledger.ensure_valid(&payment).await?;
+            self.ledger.verify_account(payment.source_account_id).await?;
+            self.ledger.verify_account(payment.destination_account_id).await?;
+        }
+
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let created = match PvpRepository::create_in(&mut tx, &pvp).await? {
+            Some(created) => created,
+            None => {
+                // Lost a race with a concurrent submission of the same key
+                drop(tx);
+                return self
+                    .repo
+                    .find_by_idempotency_key(&pvp.idempotency_key)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::SerializationConflict(format!(
+                            "PvP settlement '{}' was submitted concurrently",
+                            pvp.idempotency_key
+                        ))
+                    });
+            }
+        };
+        Self::publish(&mut tx, &created, EventType::PvpCreated, None).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+
+        self.attempt(created.id).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<PvpSettlement> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("PvP settlement '{}' not found", id)))
+    }
+
+    /// Lists settlements newest first, optionally in one status, with the total count.
+    pub async fn list(&self, status: Option<PvpStatus>, limit: i64, offset: i64) -> Result<(Vec<PvpSettlement>, i64)> {
+        let settlements = self.repo.list(status, limit, offset).await?;
+        let total = self.repo.count(status).await?;
+        Ok((settlements, total))
+    }
+
+    /// Checks a settlement awaiting funding: settles it if both payers are funded,
+    /// cancels it if its deadline has passed, and otherwise records which payers are
+    /// funded. Settled and cancelled settlements are returned as they are.
+    pub async fn attempt(&self, id: Uuid) -> Result<PvpSettlement> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let pvp = PvpRepository::find_for_update_in(&mut tx, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("PvP settlement '{}' not found", id)))?;
+        if pvp.status != PvpStatus::AwaitingFunding {
+            return Ok(pvp);
+        }
+        if self.payments_posted(id).await? {
+            return self.settle_in(tx, id).await;
+        }
+        if pvp.is_expired(Utc::now()) {
+            return self.cancel_in(tx, id, EXPIRED_REASON).await;
+        }
+
+        let first_funded = self
+            .is_funded(pvp.first_source_account_id, &pvp.first_currency, pvp.first_amount)
+            .await?;
+        let second_funded = self
+            .is_funded(pvp.second_source_account_id, &pvp.second_currency, pvp.second_amount)
+            .await?;
+        if first_funded && second_funded {
+            match self.ledger.execute_linked(pvp.id, Self::payments(&pvp).to_vec()).await {
+                Ok(_) => return self.settle_in(tx, id).await,
+                // Funds moved between the check and the settlement; try again later
+                Err(AppError::InsufficientFunds(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let updated = PvpRepository::update_funding_in(&mut tx, id, first_funded, second_funded).await?;
+        for (leg, was_funded, funded) in [
+            (PvpLeg::First, pvp.first_funded, first_funded),
+            (PvpLeg::Second, pvp.second_funded, second_funded),
+        ] {
+            if funded && !was_funded {
+                Self::publish(&mut tx, &updated, EventType::PvpLegFunded, Some(leg)).await?;
+            }
+        }
+        tx.commit().await.map_err(AppError::Database)?;
+        Ok(updated)
+    }
+
+    /// Cancels a settlement still awaiting funding. Neither payment is made.
+    pub async fn cancel(&self, id: Uuid, reason: &str) -> Result<PvpSettlement> {
+        let mut tx = self.pool.begin().await.map_err(AppError::Database)?;
+        let pvp = PvpRepository::find_for_update_in(&mut tx, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("PvP settlement '{}' not found", id)))?;
+        match pvp.status {
+            PvpStatus::Cancelled => Ok(pvp),
+            PvpStatus::Settled => Err(AppError::Validation(format!("PvP settlement '{}' has already settled", id))),
+            PvpStatus::AwaitingFunding if self.payments_posted(id).await? => {
+                self.settle_in(tx, id).await?;
+                Err(AppError::Validation(format!("PvP settlement '{}' has already settled", id)))
+            }
+            PvpStatus::AwaitingFunding => self.cancel_in(tx, id, reason).await,
+        }
+    }
+
+    /// Checks every settlement awaiting funding, nearest deadline first.
+    pub async fn sweep(&self) -> Result<PvpSweepReport> {
+        let mut report = PvpSweepReport::default();
+        for id in self.repo.find_awaiting(self.batch_size).await? {
+            report.checked += 1;
+            match self.attempt(id).await {
+                Ok(pvp) if pvp.status == PvpStatus::Settled => report.settled += 1,
+                Ok(pvp) if pvp.status == PvpStatus::Cancelled => report.cancelled += 1,
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to check PvP settlement {}: {}", id, e),
+            }
+        }
+        if report.settled > 0 || report.cancelled > 0 {
+            tracing::info!(
+                "PvP sweep checked {} settlements: {} settled, {} cancelled",
+                report.checked,
+                report.settled,
+                report.cancelled
+            );
+        }
+        Ok(report)
+    }
+
+    /// The two payments of a settlement, keyed `{idempotency_key}:FIRST` and `:SECOND`.
+    pub fn payments(pvp: &PvpSettlement) -> [LedgerTransactionRequest; 2] {
+        [
+            LedgerTransactionRequest::payment(
+                format!("{}:FIRST", pvp.external_id),
+                pvp.first_source_account_id,
+                pvp.first_destination_account_id,
+                pvp.first_amount,
+                pvp.first_currency.clone(),
+                format!("{}:FIRST", pvp.idempotency_key),
+            ),
+            LedgerTransactionRequest::payment(
+                format!("{}:SECOND", pvp.external_id),
+                pvp.second_source_account_id,
+                pvp.second_destination_account_id,
+                pvp.second_amount,
+                pvp.second_currency.clone(),
+                format!("{}:SECOND", pvp.idempotency_key),
+            ),
+        ]
+    }
+
+    async fn is_funded(&self, account_id: Uuid, currency: &str, amount: Decimal) -> Result<bool> {
+        Ok(self
+            .balance_repo
+            .find_by_account_and_currency(account_id, currency)
+            .await?
+            .is_some_and(|balance| balance.has_sufficient_funds(amount)))
+    }
+
+    /// Whether the settlement's payments were posted, e.g. by an attempt that stopped
+    /// before recording the outcome.
+    async fn payments_posted(&self, id: Uuid) -> Result<bool> {
+        Ok(!self.transaction_repo.find_by_link(id).await?.is_empty())
+    }
+
+    async fn settle_in(&self, mut tx: Transaction<'_, Postgres>, id: Uuid) -> Result<PvpSettlement> {
+        let settled = PvpRepository::mark_settled_in(&mut tx, id).await?;
+        Self::publish(&mut tx, &settled, EventType::PvpSettled, None).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        tracing::info!("PvP settlement {} settled", id);
+        Ok(settled)
+    }
+
+    async fn cancel_in(&self, mut tx: Transaction<'_, Postgres>, id: Uuid, reason: &str) -> Result<PvpSettlement> {
+        let cancelled = PvpRepository::cancel_in(&mut tx, id, reason).await?;
+        Self::publish(&mut tx, &cancelled, EventType::PvpCancelled, None).await?;
+        tx.commit().await.map_err(AppError::Database)?;
+        tracing::warn!("PvP settlement {} cancelled: {}", id, reason);
+        Ok(cancelled)
+    }
+
+    async fn publish(
+        tx: &mut Transaction<'_, Postgres>,
+        pvp: &PvpSettlement,
+        event_type: EventType,
+        leg: Option<PvpLeg>,
+    ) -> Result<()> {
+        let event = PvpEvent::from_settlement(pvp, leg);
+        enqueue_event(tx, PvpEvent::topic(), Some(pvp.id.to_string()), &EventEnvelope::new(event_type, event)).await
+    }
+}
+
+/// Background job checking PvP settlements awaiting funding: settling those whose
+/// payers are both funded and cancelling those past their deadline.
+pub struct PvpJob {
+    service: Arc<PvpService>,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl PvpJob {
+    pub fn new(service: Arc<PvpService>, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+            leader: None,
+        }
+    }
+
+    /// Only runs while this instance is the elected leader.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+        let leader = self.leader.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if leader.as_ref().is_none_or(|leader| leader.is_leader()) {
+                    if let Err(e) = service.sweep().await {
+                        tracing::error!("PvP job error: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::core::leader::LeaderElection;
+use crate::error::Result;
+use crate::models::BalanceBreak;
+use crate::notifications::NotificationEngine;
+use crate::observability::get_metrics;
+use crate::repositories::ReconciliationRepository;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Outcome of one reconciliation run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconciliationRun {
+    pub accounts_checked: usize,
+    /// Breaks found by this run, including ones already open before it.
+    pub breaks: Vec<BalanceBreak>,
+    pub new_breaks: usize,
+    /// Open breaks whose accounts reconciled again.
+    pub resolved: u64,
+}
+
+/// Reconciles stored account balances against the ledger.
+///
+/// Each run derives every balance from its ledger entries and compares it with
+/// `account_balances`. A mismatch opens a balance break and raises balance-break
+/// alerts; the break is refreshed on later runs and resolved once the account
+/// reconciles again.
+pub struct ReconciliationService {
+    repo: ReconciliationRepository,
+    notifications: Option<Arc<NotificationEngine>>,
+}
+
+impl ReconciliationService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: ReconciliationRepository::new(pool),
+            notifications: None,
+        }
+    }
+
+    /// Raises balance-break alerts through the notification engine.
+    pub fn with_notifications(mut self, engine: Arc<NotificationEngine>) -> Self {
+        self.notifications = Some(engine);
+        self
+    }
+
+    /// Checks every account balance against the ledger.
+    pub async fn run(&self) -> Result<ReconciliationRun> {
+        let checks = self.repo.check_balances().await?;
+        let mut run = ReconciliationRun {
+            accounts_checked: checks.len(),
+            ..Default::default()
+        };
+
+        let (drifted, reconciled): (Vec<_>, Vec<_>) = checks.iter().partition(|check| check.is_break());
+        let account_ids: Vec<Uuid> = reconciled.iter().map(|check| check.account_id).collect();
+        let currencies: Vec<String> = reconciled.iter().map(|check| check.currency.clone()).collect();
+        run.resolved = self.repo.resolve_reconciled(&account_ids, &currencies).await?;
+
+        for check in drifted {
+            let existing = self.repo.find_open(check.account_id, &check.currency).await?;
+            let balance_break = self.repo.upsert_open(&BalanceBreak::new(check)).await?;
+
+            if existing.is_none() {
+                warn!(
+                    account_id = %balance_break.account_id,
+                    currency = %balance_break.currency,
+                    stored = %balance_break.stored_balance,
+                    derived = %balance_break.derived_balance,
+                    "Balance break detected"
+                );
+                run.new_breaks += 1;
+                if let Some(engine) = &self.notifications {
+                    engine.evaluate_break(&balance_break).await;
+                }
+            }
+            run.breaks.push(balance_break);
+        }
+
+        let open = self.repo.count(true, None, None).await?;
+        get_metrics().record_reconciliation_run(run.accounts_checked as u64, run.new_breaks as u64, open);
+        info!(
+            "Reconciled {} account balances: {} breaks ({} new), {} resolved",
+            run.accounts_checked,
+            run.breaks.len(),
+            run.new_breaks,
+            run.resolved
+        );
+
+        Ok(run)
+    }
+
+    /// Lists balance breaks, newest first.
+    pub async fn list_breaks(
+        &self,
+        open_only: bool,
+        account_id: Option<Uuid>,
+        currency: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BalanceBreak>> {
+        self.repo.list(open_only, account_id, currency, limit, offset).await
+    }
+
+    /// Counts balance breaks.
+    pub async fn count_breaks(&self, open_only: bool, account_id: Option<Uuid>, currency: Option<&str>) -> Result<i64> {
+        self.repo.count(open_only, account_id, currency).await
+    }
+}
+
+/// Periodically reconciles account balances against the ledger.
+pub struct ReconciliationJob {
+    service: Arc<ReconciliationService>,
+    running: Arc<AtomicBool>,
+    interval_seconds: u64,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl ReconciliationJob {
+    pub fn new(service: Arc<ReconciliationService>, interval_seconds: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_seconds,
+            leader: None,
+        }
+    }
+
+    /// Only runs while this instance is the elected leader.
+    pub fn with_leader(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Starts the job in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_seconds;
+        let leader = self.leader.clone();
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if leader.as_ref().is_none_or(|leader| leader.is_leader()) {
+                    if let Err(e) = service.run().await {
+                        tracing::error!("Reconciliation job error: {}", e);
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the job.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the job is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,398 @@
+use crate::error::{AppError, Result};
+use crate::events::{enqueue_event, EventEnvelope, EventType, PossibleDuplicateEvent};
+use crate::models::{DuplicatePaymentAction, RiskHold, RiskHoldAudit, RiskHoldStatus, RiskTrigger, TransactionType};
+use crate::observability::get_metrics;
+use crate::repositories::{RiskHoldRepository, TransactionRepository};
+use crate::services::LedgerTransactionRequest;
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Default window the velocity rule counts a source account's transactions over.
+pub const DEFAULT_VELOCITY_WINDOW_SECS: u64 = 3600;
+
+/// Rules that hold payments and transfers for review before posting. Every rule is off
+/// unless configured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    /// Review threshold for currencies without their own entry.
+    pub default_amount_threshold: Option<Decimal>,
+    /// Per-currency review thresholds keyed by ISO code; these take precedence over the default.
+    #[serde(default)]
+    pub currency_amount_thresholds: HashMap<String, Decimal>,
+    /// Holds the first transaction from an account to a counterparty.
+    #[serde(default)]
+    pub new_counterparty: bool,
+    pub velocity_window_secs: u64,
+    /// Most transactions a source account may send in the window.
+    pub velocity_max_count: Option<i64>,
+    /// Most a source account may send in the window, per currency.
+    pub velocity_max_amount: Option<Decimal>,
+    /// Window in which a payment with the same source, destination, amount and currency
+    /// as an earlier one, but another idempotency key, is a likely duplicate.
+    #[serde(default)]
+    pub duplicate_window_secs: Option<u64>,
+    #[serde(default)]
+    pub duplicate_action: DuplicatePaymentAction,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            default_amount_threshold: None,
+            currency_amount_thresholds: HashMap::new(),
+            new_counterparty: false,
+            velocity_window_secs: DEFAULT_VELOCITY_WINDOW_SECS,
+            velocity_max_count: None,
+            velocity_max_amount: None,
+            duplicate_window_secs: None,
+            duplicate_action: DuplicatePaymentAction::default(),
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Returns the review threshold for a currency, if the rule applies to it.
+    pub fn amount_threshold_for(&self, currency: &str) -> Option<Decimal> {
+        self.currency_amount_thresholds
+            .get(currency)
+            .copied()
+            .or(self.default_amount_threshold)
+    }
+}
+
+/// Holds payments and transfers that trip a risk rule for review before they are
+/// posted, and keeps the review queue. Released holds are posted by
+/// `LedgerService::release_held`.
+pub struct RiskService {
+    pool: PgPool,
+    repo: RiskHoldRepository,
+    transaction_repo: TransactionRepository,
+    config: RiskConfig,
+}
+
+impl RiskService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: RiskHoldRepository::new(pool.clone()),
+            transaction_repo: TransactionRepository::new(pool.clone()),
+            pool,
+            config: RiskConfig::default(),
+        }
+    }
+
+    /// Sets the risk rules.
+    pub fn with_config(mut self, config: RiskConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Evaluates the risk rules against a request, returning every rule it trips.
+    pub async fn evaluate(&self, request: &LedgerTransactionRequest) -> Result<Vec<RiskTrigger>> {
+        let mut triggers = Vec::new();
+
+        if let Some(threshold) = self.config.amount_threshold_for(&request.currency) {
+            if request.amount > threshold {
+                triggers.push(RiskTrigger::LargeAmount { threshold });
+            }
+        }
+
+        if self.config.new_counterparty
+            && !self
+                .transaction_repo
+                .has_settled_between(request.source_account_id, request.destination_account_id)
+                .await?
+        {
+            triggers.push(RiskTrigger::NewCounterparty);
+        }
+
+        if self.config.velocity_max_count.is_some() || self.config.velocity_max_amount.is_some() {
+            let window_secs = self.config.velocity_window_secs;
+            let since = Utc::now() - Duration::seconds(window_secs as i64);
+            let (count, amount) = self
+                .transaction_repo
+                .outgoing_since(request.source_account_id, &request.currency, since)
+                .await?;
+            let (count, amount) = (count + 1, amount + request.amount);
+            let over_count = self.config.velocity_max_count.is_some_and(|max| count > max);
+            let over_amount = self.config.velocity_max_amount.is_some_and(|max| amount > max);
+            if over_count || over_amount {
+                triggers.push(RiskTrigger::Velocity {
+                    window_secs,
+                    count,
+                    amount,
+                });
+            }
+        }
+
+        if let Some(window_secs) = self.config.duplicate_window_secs {
+            let since = Utc::now() - Duration::seconds(window_secs as i64);
+            let duplicate = self
+                .transaction_repo
+                .find_recent_duplicate(
+                    request.source_account_id,
+                    request.destination_account_id,
+                    request.amount,
+                    &request.currency,
+                    &request.idempotency_key,
+                    since,
+                )
+                .await?
+                .filter(|id| request.duplicate_override != Some(*id));
+            if let Some(transaction_id) = duplicate {
+                triggers.push(RiskTrigger::PossibleDuplicate {
+                    transaction_id,
+                    window_secs,
+                });
+            }
+        }
+
+        Ok(triggers)
+    }
+
+    /// Screens a payment or transfer before it is posted. A request that trips a rule is
+    /// held and `HeldForReview` returned; so is a retry of a request still held. Retries
+    /// of rejected requests are refused, and requests already posted pass unchecked.
+    pub async fn screen(&self, request: &LedgerTransactionRequest) -> Result<()> {
+        if !matches!(request.transaction_type, TransactionType::Payment | TransactionType::Transfer) {
+            return Ok(());
+        }
+
+        if let Some(hold) = self.repo.find_by_idempotency_key(&request.idempotency_key).await? {
+            return match hold.status {
+                RiskHoldStatus::Held => Err(held_error(&hold)),
+                RiskHoldStatus::Rejected => Err(AppError::Validation(format!(
+                    "Transaction was rejected in risk review of hold {}",
+                    hold.id
+                ))),
+                RiskHoldStatus::Released => Ok(()),
+            };
+        }
+        if self
+            .transaction_repo
+            .find_by_idempotency_key(&request.idempotency_key)
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let mut triggers = self.evaluate(request).await?;
+        if let Some(RiskTrigger::PossibleDuplicate { transaction_id, window_secs }) = triggers
+            .iter()
+            .find(|t| matches!(t, RiskTrigger::PossibleDuplicate { .. }))
+            .cloned()
+        {
+            let action = self.config.duplicate_action;
+            self.raise_possible_duplicate(request, transaction_id, window_secs, action).await;
+            match action {
+                DuplicatePaymentAction::Reject => {
+                    return Err(AppError::PossibleDuplicate(format!(
+                        "Transaction looks like a duplicate of {} submitted in the last {} seconds; \
+                         resubmit with duplicate_override set to {} if it is intended",
+                        transaction_id, window_secs, transaction_id
+                    )));
+                }
+                DuplicatePaymentAction::Warn => {
+                    triggers.retain(|t| !matches!(t, RiskTrigger::PossibleDuplicate { .. }));
+                }
+                DuplicatePaymentAction::Hold => {}
+            }
+        }
+        if triggers.is_empty() {
+            return Ok(());
+        }
+
+        let hold = RiskHold {
+            id: Uuid::new_v4(),
+            idempotency_key: request.idempotency_key.clone(),
+            external_id: request.external_id.clone(),
+            transaction_type: request.transaction_type,
+            source_account_id: request.source_account_id,
+            destination_account_id: request.destination_account_id,
+            amount: request.amount,
+            currency: request.currency.clone(),
+            request: to_json(request)?,
+            triggers: to_json(&triggers)?,
+            status: RiskHoldStatus::Held,
+            transaction_id: None,
+            reviewed_by: None,
+            review_reason: None,
+            created_at: Utc::now(),
+            reviewed_at: None,
+        };
+        let hold = self.repo.create(&hold).await?;
+        warn!(
+            hold_id = %hold.id,
+            external_id = %hold.external_id,
+            source_account_id = %hold.source_account_id,
+            amount = %hold.amount,
+            currency = %hold.currency,
+            "Transaction held for risk review"
+        );
+        Err(held_error(&hold))
+    }
+
+    /// Logs, counts and queues a `POSSIBLE_DUPLICATE` event on the alerts topic. Failing
+    /// to queue the event does not change the outcome of screening.
+    async fn raise_possible_duplicate(
+        &self,
+        request: &LedgerTransactionRequest,
+        duplicate_of: Uuid,
+        window_secs: u64,
+        action: DuplicatePaymentAction,
+    ) {
+        warn!(
+            external_id = %request.external_id,
+            source_account_id = %request.source_account_id,
+            duplicate_of = %duplicate_of,
+            action = ?action,
+            "Possible duplicate payment"
+        );
+        get_metrics().record_possible_duplicate(&request.currency, &format!("{:?}", action).to_lowercase());
+
+        let event = PossibleDuplicateEvent {
+            external_id: request.external_id.clone(),
+            idempotency_key: request.idempotency_key.clone(),
+            source_account_id: request.source_account_id,
+            destination_account_id: request.destination_account_id,
+            amount: request.amount,
+            currency: request.currency.clone(),
+            duplicate_of,
+            window_secs,
+            action,
+            occurred_at: Utc::now(),
+        };
+        let envelope = EventEnvelope::new(EventType::PossibleDuplicate, event);
+        let queued = async {
+            let mut tx = self.pool.begin().await?;
+            enqueue_event(
+                &mut tx,
+                PossibleDuplicateEvent::topic(),
+                Some(request.source_account_id.to_string()),
+                &envelope,
+            )
+            .await?;
+            tx.commit().await?;
+            Ok::<_, AppError>(())
+        };
+        if let Err(e) = queued.await {
+            warn!("Failed to queue possible duplicate alert for {}: {}", request.external_id, e);
+        }
+    }
+
+    /// Gets a hold.
+    pub async fn get_hold(&self, id: Uuid) -> Result<RiskHold> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Risk hold {} not found", id)))
+    }
+
+    /// Lists holds, oldest first.
+    pub async fn list_holds(&self, status: Option<RiskHoldStatus>, limit: i64, offset: i64) -> Result<Vec<RiskHold>> {
+        self.repo.list(status, limit, offset).await
+    }
+
+    /// Counts holds matching the `list_holds` filter.
+    pub async fn count_holds(&self, status: Option<RiskHoldStatus>) -> Result<i64> {
+        self.repo.count(status).await
+    }
+
+    /// Gets a hold's audit trail in order.
+    pub async fn hold_audit(&self, id: Uuid) -> Result<Vec<RiskHoldAudit>> {
+        self.repo.find_audit(id).await
+    }
+
+    /// Rejects a held transaction; it will never be posted.
+    pub async fn reject(&self, id: Uuid, reviewed_by: &str, reason: &str) -> Result<RiskHold> {
+        self.decide(id, RiskHoldStatus::Rejected, reviewed_by, reason).await
+    }
+
+    /// Records a reviewer's decision on a held transaction. Reviewer and reason are required.
+    pub(crate) async fn decide(
+        &self,
+        id: Uuid,
+        status: RiskHoldStatus,
+        reviewed_by: &str,
+        reason: &str,
+    ) -> Result<RiskHold> {
+        if reviewed_by.trim().is_empty() || reason.trim().is_empty() {
+            return Err(AppError::Validation("A reviewer and a reason are required".to_string()));
+        }
+
+        match self.repo.decide(id, status, reviewed_by, reason).await? {
+            Some(hold) => Ok(hold),
+            None => {
+                let hold = self.get_hold(id).await?;
+                Err(AppError::Validation(format!(
+                    "Risk hold {} was already {:?}",
+                    id, hold.status
+                )))
+            }
+        }
+    }
+
+    /// Links a released hold to the transaction it posted.
+    pub(crate) async fn complete_release(&self, id: Uuid, transaction_id: Uuid) -> Result<RiskHold> {
+        self.repo.set_transaction(id, transaction_id).await
+    }
+
+    /// Returns a released hold to the queue after it failed to post.
+    pub(crate) async fn reopen(&self, id: Uuid, failure: &AppError) -> Result<()> {
+        self.repo.reopen(id, &failure.public_message()).await
+    }
+}
+
+fn held_error(hold: &RiskHold) -> AppError {
+    AppError::HeldForReview(format!("Transaction held for risk review as hold {}", hold.id))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value> {
+    serde_json::to_value(value)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize risk hold: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_amount_threshold_for() {
+        let config = RiskConfig {
+            default_amount_threshold: Some(dec!(10000)),
+            currency_amount_thresholds: [("JPY".to_string(), dec!(1000000))].into_iter().collect(),
+            ..RiskConfig::default()
+        };
+
+        assert_eq!(config.amount_threshold_for("USD"), Some(dec!(10000)));
+        assert_eq!(config.amount_threshold_for("JPY"), Some(dec!(1000000)));
+        assert_eq!(RiskConfig::default().amount_threshold_for("USD"), None);
+    }
+
+    #[test]
+    fn test_trigger_serialization() {
+        let trigger = RiskTrigger::Velocity {
+            window_secs: 60,
+            count: 4,
+            amount: dec!(250),
+        };
+        let json = serde_json::to_value(&trigger).unwrap();
+        assert_eq!(json["rule"], "VELOCITY");
+        assert_eq!(json["count"], 4);
+        assert_eq!(serde_json::from_value::<RiskTrigger>(json).unwrap(), trigger);
+
+        let duplicate = RiskTrigger::PossibleDuplicate {
+            transaction_id: Uuid::nil(),
+            window_secs: 300,
+        };
+        let json = serde_json::to_value(&duplicate).unwrap();
+        assert_eq!(json["rule"], "POSSIBLE_DUPLICATE");
+        assert_eq!(serde_json::from_value::<RiskTrigger>(json).unwrap(), duplicate);
+    }
+}
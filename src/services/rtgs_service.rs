@@ -0,0 +1,355 @@
+use crate::error::{AppError, Result};
+use crate::events::{EventEnvelope, EventProducer, EventType, RtgsSettlementEvent};
+use crate::models::{
+    EngineMode, FinalityRecord, SettlementRoute, TransactionAuditAction, TransactionAuditEntry, TransactionType,
+};
+use crate::observability::get_metrics;
+use crate::repositories::{
+    FinalityRepository, RouteVolume, TransactionAuditRepository, TransactionRepository,
+};
+use crate::services::double_entry_engine::{DoubleEntryEngine, TransactionRequest, TransactionResult};
+use chrono::{Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Thresholds above which transactions bypass batch netting and settle gross.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RtgsConfig {
+    /// Threshold for currencies without their own entry. `None` disables the lane for them.
+    pub default_threshold: Option<Decimal>,
+    /// Per-currency thresholds keyed by ISO code; these take precedence over the default.
+    #[serde(default)]
+    pub currency_thresholds: HashMap<String, Decimal>,
+}
+
+impl RtgsConfig {
+    /// Returns the RTGS threshold for a currency, if the lane is enabled for it.
+    pub fn threshold_for(&self, currency: &str) -> Option<Decimal> {
+        self.currency_thresholds
+            .get(currency)
+            .copied()
+            .or(self.default_threshold)
+    }
+
+    /// Decides the settlement route for a transaction. Only payments and transfers are
+    /// eligible; refunds, chargebacks and fees follow the transaction they relate to.
+    pub fn route_for(
+        &self,
+        transaction_type: TransactionType,
+        amount: Decimal,
+        currency: &str,
+    ) -> SettlementRoute {
+        self.route_in(EngineMode::Hybrid, transaction_type, amount, currency)
+    }
+
+    /// Decides the settlement route for a transaction under an engine mode: every
+    /// eligible transaction goes gross in `Gross` mode and none in `Net` mode, while
+    /// `Hybrid` routes by threshold.
+    pub fn route_in(
+        &self,
+        mode: EngineMode,
+        transaction_type: TransactionType,
+        amount: Decimal,
+        currency: &str,
+    ) -> SettlementRoute {
+        let eligible = matches!(transaction_type, TransactionType::Payment | TransactionType::Transfer);
+        match mode {
+            EngineMode::Gross if eligible => SettlementRoute::Rtgs,
+            EngineMode::Gross | EngineMode::Net => SettlementRoute::Netted,
+            EngineMode::Hybrid => match self.threshold_for(currency) {
+                Some(threshold) if eligible && amount > threshold => SettlementRoute::Rtgs,
+                _ => SettlementRoute::Netted,
+            },
+        }
+    }
+}
+
+/// Settled volume per route for a single day.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingReport {
+    pub date: NaiveDate,
+    pub routes: Vec<RouteVolume>,
+}
+
+impl RoutingReport {
+    /// Returns the number of transactions settled on the given route across all currencies.
+    pub fn transaction_count(&self, route: SettlementRoute) -> i64 {
+        self.routes
+            .iter()
+            .filter(|v| v.settlement_route == route)
+            .map(|v| v.transaction_count)
+            .sum()
+    }
+}
+
+/// Real-time gross settlement lane. Transactions routed here are posted individually
+/// through the double-entry engine and never join a settlement batch.
+pub struct RtgsService {
+    engine: DoubleEntryEngine,
+    transaction_repo: TransactionRepository,
+    finality_repo: FinalityRepository,
+    audit_repo: TransactionAuditRepository,
+    config: RtgsConfig,
+    mode: EngineMode,
+    producer: Option<Arc<EventProducer>>,
+}
+
+impl RtgsService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            engine: DoubleEntryEngine::new(pool.clone()),
+            transaction_repo: TransactionRepository::new(pool.clone()),
+            finality_repo: FinalityRepository::new(pool.clone()),
+            audit_repo: TransactionAuditRepository::new(pool),
+            config: RtgsConfig::default(),
+            mode: EngineMode::default(),
+            producer: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: RtgsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the engine mode routing decisions follow.
+    pub fn with_mode(mut self, mode: EngineMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Publishes an RTGS settlement event for every transaction settled through the lane.
+    pub fn with_producer(mut self, producer: Arc<EventProducer>) -> Self {
+        self.producer = Some(producer);
+        self
+    }
+
+    pub fn config(&self) -> &RtgsConfig {
+        &self.config
+    }
+
+    pub fn mode(&self) -> EngineMode {
+        self.mode
+    }
+
+    /// Decides the settlement route for a transaction under the engine mode.
+    pub fn route_for(
+        &self,
+        transaction_type: TransactionType,
+        amount: Decimal,
+        currency: &str,
+    ) -> SettlementRoute {
+        self.config.route_in(self.mode, transaction_type, amount, currency)
+    }
+
+    /// Settles a transaction gross and in real time, recording the RTGS route on it and the
+    /// moment it became final. Replays of an idempotency key return the original result
+    /// without a second event.
+    pub async fn settle(&self, request: TransactionRequest) -> Result<TransactionResult> {
+        // In gross mode the lane takes every amount; the threshold reported is zero
+        // unless one is configured
+        let threshold = match (self.mode, self.config.threshold_for(&request.currency)) {
+            (EngineMode::Net, _) => {
+                return Err(AppError::Validation(
+                    "RTGS lane is closed in NET engine mode".to_string(),
+                ))
+            }
+            (EngineMode::Gross, threshold) => threshold.unwrap_or(Decimal::ZERO),
+            (EngineMode::Hybrid, Some(threshold)) => threshold,
+            (EngineMode::Hybrid, None) => {
+                return Err(AppError::Validation(format!(
+                    "RTGS lane is not enabled for currency '{}'",
+                    request.currency
+                )))
+            }
+        };
+
+        let replay = self
+            .transaction_repo
+            .exists_by_idempotency_key(&request.idempotency_key)
+            .await?;
+
+        let result = self
+            .engine
+            .execute_transaction_with_route(request, SettlementRoute::Rtgs)
+            .await?;
+        let finality = self
+            .finality_repo
+            .record(result.transaction.id, None, SettlementRoute::Rtgs)
+            .await?;
+
+        if !replay {
+            get_metrics().record_rtgs_settlement(&result.transaction.currency);
+            self.publish(&result, threshold, &finality).await;
+        }
+
+        Ok(result)
+    }
+
+    /// Summarises settled volume by route and currency for a UTC calendar day.
+    pub async fn daily_report(&self, date: NaiveDate) -> Result<RoutingReport> {
+        let start = Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN));
+        let routes = self
+            .transaction_repo
+            .route_volumes(start, start + Duration::days(1))
+            .await?;
+
+        Ok(RoutingReport { date, routes })
+    }
+
+    /// Publishes the settlement event. The transaction is already final, so a publish
+    /// failure is logged rather than returned.
+    async fn publish(&self, result: &TransactionResult, threshold: Decimal, finality: &FinalityRecord) {
+        let Some(producer) = &self.producer else {
+            return;
+        };
+
+        let transaction = &result.transaction;
+        let event = RtgsSettlementEvent {
+            transaction_id: transaction.id,
+            external_id: transaction.external_id.clone(),
+            transaction_type: transaction.transaction_type,
+            source_account_id: transaction.source_account_id,
+            destination_account_id: transaction.destination_account_id,
+            amount: transaction.amount,
+            currency: transaction.currency.clone(),
+            fee_amount: transaction.fee_amount,
+            net_amount: transaction.net_amount,
+            threshold,
+            settled_at: transaction.settled_at.unwrap_or_else(Utc::now),
+            finality_sequence: finality.sequence,
+            final_at: finality.final_at,
+        };
+        let envelope = EventEnvelope::new(EventType::RtgsSettled, event);
+        let key = transaction.id.to_string();
+
+        let sent = producer
+            .send(RtgsSettlementEvent::topic(), Some(&key), &envelope)
+            .await;
+        get_metrics().record_kafka_message(RtgsSettlementEvent::topic(), sent.is_ok());
+        match sent {
+            Ok(offset) => {
+                let entry = TransactionAuditEntry::new(
+                    transaction.id,
+                    TransactionAuditAction::EventEmitted,
+                    serde_json::json!({
+                        "topic": RtgsSettlementEvent::topic(),
+                        "event_type": envelope.event_type,
+                        "event_id": envelope.event_id,
+                        "offset": offset,
+                    }),
+                );
+                if let Err(e) = self.audit_repo.record(&entry).await {
+                    tracing::warn!("Failed to audit RTGS event for transaction {}: {}", transaction.id, e);
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to publish RTGS settlement event for transaction {}: {}",
+                    transaction.id,
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn config() -> RtgsConfig {
+        RtgsConfig {
+            default_threshold: Some(dec!(1000000)),
+            currency_thresholds: HashMap::from([("JPY".to_string(), dec!(100000000))]),
+        }
+    }
+
+    #[test]
+    fn test_route_above_threshold() {
+        let config = config();
+
+        assert_eq!(
+            config.route_for(TransactionType::Payment, dec!(1000000.01), "USD"),
+            SettlementRoute::Rtgs
+        );
+        assert_eq!(
+            config.route_for(TransactionType::Transfer, dec!(2000000), "EUR"),
+            SettlementRoute::Rtgs
+        );
+        // At the threshold stays in the netted lane
+        assert_eq!(
+            config.route_for(TransactionType::Payment, dec!(1000000), "USD"),
+            SettlementRoute::Netted
+        );
+    }
+
+    #[test]
+    fn test_currency_threshold_overrides_default() {
+        let config = config();
+
+        assert_eq!(
+            config.route_for(TransactionType::Payment, dec!(5000000), "JPY"),
+            SettlementRoute::Netted
+        );
+        assert_eq!(
+            config.route_for(TransactionType::Payment, dec!(150000000), "JPY"),
+            SettlementRoute::Rtgs
+        );
+    }
+
+    #[test]
+    fn test_only_payments_and_transfers_are_eligible() {
+        let config = config();
+
+        for transaction_type in [TransactionType::Refund, TransactionType::Chargeback, TransactionType::Fee] {
+            assert_eq!(
+                config.route_for(transaction_type, dec!(5000000), "USD"),
+                SettlementRoute::Netted
+            );
+        }
+    }
+
+    #[test]
+    fn test_engine_mode_overrides_thresholds() {
+        let config = config();
+
+        assert_eq!(
+            config.route_in(EngineMode::Gross, TransactionType::Payment, dec!(1), "USD"),
+            SettlementRoute::Rtgs
+        );
+        assert_eq!(
+            RtgsConfig::default().route_in(EngineMode::Gross, TransactionType::Transfer, dec!(1), "CHF"),
+            SettlementRoute::Rtgs
+        );
+        assert_eq!(
+            config.route_in(EngineMode::Gross, TransactionType::Refund, dec!(1), "USD"),
+            SettlementRoute::Netted
+        );
+        assert_eq!(
+            config.route_in(EngineMode::Net, TransactionType::Payment, dec!(5000000), "USD"),
+            SettlementRoute::Netted
+        );
+        assert_eq!(
+            config.route_in(EngineMode::Hybrid, TransactionType::Payment, dec!(5000000), "USD"),
+            config.route_for(TransactionType::Payment, dec!(5000000), "USD")
+        );
+        assert!(!EngineMode::Gross.batching_enabled());
+        assert!(EngineMode::Net.batching_enabled());
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let config = RtgsConfig::default();
+
+        assert!(config.threshold_for("USD").is_none());
+        assert_eq!(
+            config.route_for(TransactionType::Payment, dec!(999999999), "USD"),
+            SettlementRoute::Netted
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::core::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use crate::error::{AppError, Result};
+use crate::services::SettlementInstruction;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Acknowledgement state of an instruction submitted to a rail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AcknowledgementStatus {
+    Pending,
+    Accepted,
+    Rejected(String),
+}
+
+/// An external settlement rail (e.g. an RTGS or ACH gateway) that released settlement
+/// instructions are submitted to.
+#[async_trait]
+pub trait SettlementRail: Send + Sync {
+    /// Name of the rail, used in errors and logs.
+    fn name(&self) -> &str;
+
+    /// Submits an instruction, returning the rail's reference for it.
+    async fn submit(&self, instruction: &SettlementInstruction) -> Result<String>;
+
+    /// Gets the acknowledgement state of a submitted instruction.
+    async fn status(&self, reference: &str) -> Result<AcknowledgementStatus>;
+
+    /// Cancels a submitted instruction that has not been accepted.
+    async fn cancel(&self, reference: &str) -> Result<()>;
+}
+
+struct Submission {
+    status: AcknowledgementStatus,
+    polls: u32,
+}
+
+/// In-memory rail for development and tests. Instructions are accepted after a
+/// configurable number of status queries, and rejected outright above an optional
+/// amount limit.
+pub struct SimulatedRail {
+    ack_after_polls: u32,
+    rejection_limit: Option<Decimal>,
+    submissions: Mutex<HashMap<String, Submission>>,
+}
+
+impl SimulatedRail {
+    pub fn new() -> Self {
+        Self {
+            ack_after_polls: 1,
+            rejection_limit: None,
+            submissions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how many status queries an instruction stays pending for.
+    pub fn with_ack_after_polls(mut self, polls: u32) -> Self {
+        self.ack_after_polls = polls;
+        self
+    }
+
+    /// Rejects instructions above this amount.
+    pub fn with_rejection_limit(mut self, limit: Decimal) -> Self {
+        self.rejection_limit = Some(limit);
+        self
+    }
+
+    /// Number of instructions submitted so far.
+    pub fn submission_count(&self) -> usize {
+        self.submissions.lock().unwrap().len()
+    }
+}
+
+impl Default for SimulatedRail {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SettlementRail for SimulatedRail {
+    fn name(&self) -> &str {
+        "simulator"
+    }
+
+    async fn submit(&self, instruction: &SettlementInstruction) -> Result<String> {
+        let status = match self.rejection_limit {
+            Some(limit) if instruction.amount > limit => AcknowledgementStatus::Rejected(format!(
+                "Amount {} exceeds rail limit of {}",
+                instruction.amount, limit
+            )),
+            _ => AcknowledgementStatus::Pending,
+        };
+
+        let reference = format!("SIM-{}", instruction.id.simple());
+        self.submissions
+            .lock()
+            .unwrap()
+            .insert(reference.clone(), Submission { status, polls: 0 });
+        Ok(reference)
+    }
+
+    async fn status(&self, reference: &str) -> Result<AcknowledgementStatus> {
+        let mut submissions = self.submissions.lock().unwrap();
+        let submission = submissions
+            .get_mut(reference)
+            .ok_or_else(|| AppError::NotFound(format!("Unknown rail reference '{}'", reference)))?;
+
+        if submission.status == AcknowledgementStatus::Pending {
+            submission.polls += 1;
+            if submission.polls >= self.ack_after_polls {
+                submission.status = AcknowledgementStatus::Accepted;
+            }
+        }
+        Ok(submission.status.clone())
+    }
+
+    async fn cancel(&self, reference: &str) -> Result<()> {
+        let mut submissions = self.submissions.lock().unwrap();
+        let submission = submissions
+            .get_mut(reference)
+            .ok_or_else(|| AppError::NotFound(format!("Unknown rail reference '{}'", reference)))?;
+
+        if submission.status == AcknowledgementStatus::Accepted {
+            return Err(AppError::Validation(format!(
+                "Instruction '{}' has already been accepted",
+                reference
+            )));
+        }
+        submission.status = AcknowledgementStatus::Rejected("Cancelled".to_string());
+        Ok(())
+    }
+}
+
+/// Wraps a rail in a circuit breaker so that, while the rail is failing, submissions
+/// and status polls error out immediately instead of waiting on its timeouts.
+pub struct CircuitBreakingRail {
+    inner: Arc<dyn SettlementRail>,
+    breaker: CircuitBreaker,
+}
+
+impl CircuitBreakingRail {
+    pub fn new(inner: Arc<dyn SettlementRail>, config: CircuitBreakerConfig) -> Self {
+        let breaker = CircuitBreaker::new(format!("rail:{}", inner.name()), config);
+        Self { inner, breaker }
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+}
+
+#[async_trait]
+impl SettlementRail for CircuitBreakingRail {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn submit(&self, instruction: &SettlementInstruction) -> Result<String> {
+        self.breaker.call(|| self.inner.submit(instruction)).await
+    }
+
+    async fn status(&self, reference: &str) -> Result<AcknowledgementStatus> {
+        self.breaker.call(|| self.inner.status(reference)).await
+    }
+
+    async fn cancel(&self, reference: &str) -> Result<()> {
+        self.breaker.call(|| self.inner.cancel(reference)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::InstructionType;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn instruction(amount: Decimal) -> SettlementInstruction {
+        SettlementInstruction::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            amount,
+            "USD".to_string(),
+            InstructionType::MultilateralNet,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_simulator_accepts_after_polls() {
+        let rail = SimulatedRail::new().with_ack_after_polls(3);
+        let reference = rail.submit(&instruction(dec!(100))).await.unwrap();
+
+        assert_eq!(rail.status(&reference).await.unwrap(), AcknowledgementStatus::Pending);
+        assert_eq!(rail.status(&reference).await.unwrap(), AcknowledgementStatus::Pending);
+        assert_eq!(rail.status(&reference).await.unwrap(), AcknowledgementStatus::Accepted);
+        assert!(rail.cancel(&reference).await.is_err());
+        assert_eq!(rail.submission_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_simulator_rejects_above_limit() {
+        let rail = SimulatedRail::new().with_rejection_limit(dec!(1000));
+        let reference = rail.submit(&instruction(dec!(1000.01))).await.unwrap();
+
+        assert!(matches!(
+            rail.status(&reference).await.unwrap(),
+            AcknowledgementStatus::Rejected(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_simulator_cancel_and_unknown_reference() {
+        let rail = SimulatedRail::new().with_ack_after_polls(5);
+        let reference = rail.submit(&instruction(dec!(10))).await.unwrap();
+
+        rail.cancel(&reference).await.unwrap();
+        assert_eq!(
+            rail.status(&reference).await.unwrap(),
+            AcknowledgementStatus::Rejected("Cancelled".to_string())
+        );
+        assert!(matches!(rail.status("SIM-missing").await, Err(AppError::NotFound(_))));
+    }
+
+    struct DownRail;
+
+    #[async_trait]
+    impl SettlementRail for DownRail {
+        fn name(&self) -> &str {
+            "down"
+        }
+
+        async fn submit(&self, _instruction: &SettlementInstruction) -> Result<String> {
+            Err(AppError::Internal(anyhow::anyhow!("gateway timeout")))
+        }
+
+        async fn status(&self, reference: &str) -> Result<AcknowledgementStatus> {
+            Err(AppError::NotFound(format!("Unknown rail reference '{}'", reference)))
+        }
+
+        async fn cancel(&self, _reference: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaking_rail_fails_fast() {
+        let rail = CircuitBreakingRail::new(
+            Arc::new(DownRail),
+            CircuitBreakerConfig {
+                minimum_calls: 3,
+                ..CircuitBreakerConfig::default()
+            },
+        );
+
+        // Unknown references are not the rail's fault
+        for _ in 0..3 {
+            assert!(matches!(rail.status("missing").await, Err(AppError::NotFound(_))));
+        }
+        assert!(rail.breaker().is_available());
+
+        for _ in 0..3 {
+            let error = rail.submit(&instruction(dec!(10))).await.unwrap_err();
+            assert!(error.to_string().contains("gateway timeout"));
+        }
+        let error = rail.submit(&instruction(dec!(10))).await.unwrap_err();
+        assert!(error.to_string().contains("rail:down unavailable: circuit open"));
+        assert_eq!(rail.name(), "down");
+    }
+}
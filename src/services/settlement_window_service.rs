@@ -0,0 +1,135 @@
+use crate::error::{AppError, Result};
+use crate::models::SettlementWindow;
+use crate::repositories::SettlementWindowRepository;
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Request to create a named settlement window.
+#[derive(Debug, Clone)]
+pub struct CreateSettlementWindowRequest {
+    pub name: String,
+    pub currency: String,
+    pub cut_off_time: NaiveTime,
+    pub timezone: String,
+}
+
+/// Partial update of a settlement window. Fields left as None are unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateSettlementWindowRequest {
+    pub name: Option<String>,
+    pub cut_off_time: Option<NaiveTime>,
+    pub timezone: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// Service for managing the settlement window calendar.
+pub struct SettlementWindowService {
+    window_repo: SettlementWindowRepository,
+}
+
+impl SettlementWindowService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            window_repo: SettlementWindowRepository::new(pool),
+        }
+    }
+
+    /// Creates a settlement window. Names are unique per currency.
+    pub async fn create_window(&self, request: CreateSettlementWindowRequest) -> Result<SettlementWindow> {
+        if request.currency.len() != 3 {
+            return Err(AppError::Validation(
+                "Currency must be a 3-letter ISO 4217 code".to_string(),
+            ));
+        }
+        Self::validate_name(&request.name)?;
+        Self::validate_timezone(&request.timezone)?;
+
+        if let Some(existing) = self.window_repo.find_by_name(&request.currency, &request.name).await? {
+            return Err(AppError::Validation(format!(
+                "Settlement window '{}' already exists for {}: {}",
+                request.name, request.currency, existing.id
+            )));
+        }
+
+        let window = SettlementWindow::new(
+            request.name,
+            request.currency,
+            request.cut_off_time,
+            request.timezone,
+        );
+        self.window_repo.create(&window).await
+    }
+
+    /// Gets a settlement window by ID.
+    pub async fn get_window(&self, id: Uuid) -> Result<SettlementWindow> {
+        self.window_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Settlement window with id '{}' not found", id)))
+    }
+
+    /// Lists settlement windows, optionally for one currency.
+    pub async fn list_windows(&self, currency: Option<&str>, active_only: bool) -> Result<Vec<SettlementWindow>> {
+        self.window_repo.list(currency, active_only).await
+    }
+
+    /// Applies a partial update to a settlement window.
+    pub async fn update_window(&self, id: Uuid, request: UpdateSettlementWindowRequest) -> Result<SettlementWindow> {
+        let mut window = self.get_window(id).await?;
+
+        if let Some(name) = request.name {
+            Self::validate_name(&name)?;
+            if name != window.name {
+                if let Some(existing) = self.window_repo.find_by_name(&window.currency, &name).await? {
+                    return Err(AppError::Validation(format!(
+                        "Settlement window '{}' already exists for {}: {}",
+                        name, window.currency, existing.id
+                    )));
+                }
+            }
+            window.name = name;
+        }
+        if let Some(timezone) = request.timezone {
+            Self::validate_timezone(&timezone)?;
+            window.timezone = timezone;
+        }
+        if let Some(cut_off_time) = request.cut_off_time {
+            window.cut_off_time = cut_off_time;
+        }
+        if let Some(active) = request.active {
+            window.active = active;
+        }
+
+        self.window_repo
+            .update(&window)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Settlement window with id '{}' not found", id)))
+    }
+
+    /// Deactivates a settlement window so no new batches are opened for it. Its existing
+    /// batches are left to run to completion.
+    pub async fn deactivate_window(&self, id: Uuid) -> Result<SettlementWindow> {
+        self.window_repo
+            .deactivate(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Settlement window with id '{}' not found", id)))
+    }
+
+    fn validate_name(name: &str) -> Result<()> {
+        if name.trim().is_empty() || name.len() > 100 {
+            return Err(AppError::Validation(
+                "Settlement window name must be 1-100 characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_timezone(timezone: &str) -> Result<()> {
+        timezone
+            .parse::<Tz>()
+            .map(|_| ())
+            .map_err(|_| AppError::Validation(format!("Unknown timezone '{}'", timezone)))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::{AppError, Result};
+use crate::interop::camt::{Statement, StatementType};
+use crate::repositories::{AccountRepository, LedgerRepository};
+use crate::services::BalanceService;
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use sqlx::PgPool;
+use std::time::Duration as StdDuration;
+use tracing::info;
+use uuid::Uuid;
+
+/// Builds camt.053 and camt.052 statements for participant accounts and pushes them to
+/// webhooks.
+pub struct StatementService {
+    account_repo: AccountRepository,
+    ledger_repo: LedgerRepository,
+    balance_service: BalanceService,
+    webhook_timeout: StdDuration,
+}
+
+impl StatementService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            account_repo: AccountRepository::new(pool.clone()),
+            ledger_repo: LedgerRepository::new(pool.clone()),
+            balance_service: BalanceService::new(pool),
+            webhook_timeout: StdDuration::from_secs(10),
+        }
+    }
+
+    /// Sets the timeout for webhook deliveries.
+    pub fn with_webhook_timeout(mut self, timeout: StdDuration) -> Self {
+        self.webhook_timeout = timeout;
+        self
+    }
+
+    /// Generates a statement for an account's balance in `currency` (the account's own
+    /// currency if none is given) on `date`.
+    ///
+    /// End-of-day statements cover the whole UTC day, or up to now for today;
+    /// intraday reports are only available for today. Period balances are derived
+    /// from the current balance snapshot less the ledger movement since each boundary.
+    pub async fn generate(
+        &self,
+        account_id: Uuid,
+        statement_type: StatementType,
+        date: NaiveDate,
+        currency: Option<&str>,
+    ) -> Result<Statement> {
+        let account = self
+            .account_repo
+            .find_by_id(account_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account with id '{}' not found", account_id)))?;
+        let currency = currency.unwrap_or(&account.currency).to_string();
+
+        let now = Utc::now();
+        let from = DateTime::from_naive_utc_and_offset(date.and_time(NaiveTime::MIN), Utc);
+        if from > now {
+            return Err(AppError::Validation(format!(
+                "Cannot generate a statement for future date {}",
+                date
+            )));
+        }
+        if statement_type == StatementType::Camt052 && date != now.date_naive() {
+            return Err(AppError::Validation(
+                "Intraday reports are only available for the current day".to_string(),
+            ));
+        }
+        let to = (from + Duration::days(1)).min(now);
+
+        let snapshot = self.balance_service.create_snapshot(account_id, &currency).await?;
+        let since_from = self.ledger_repo.net_movement_since(account_id, &currency, from).await?;
+        let since_to = self.ledger_repo.net_movement_since(account_id, &currency, to).await?;
+        let entries = self
+            .ledger_repo
+            .find_by_account_and_time_range(account_id, &currency, from, to)
+            .await?;
+
+        // Reserved funds are held but still booked to the account
+        let booked = snapshot.available_balance + snapshot.reserved_balance;
+        let mut statement = Statement::new(statement_type, account, currency, from, to)
+            .with_balances(booked - since_from, booked - since_to)
+            .with_entries(entries);
+        if statement_type == StatementType::Camt052 {
+            statement = statement.with_available_balance(snapshot.available_balance);
+        }
+
+        Ok(statement)
+    }
+
+    /// Posts a statement's XML to a webhook.
+    pub async fn deliver_webhook(&self, statement: &Statement, url: &str) -> Result<()> {
+        let client = reqwest::Client::builder()
+            .timeout(self.webhook_timeout)
+            .build()
+            .map_err(|e| AppError::Internal(anyhow!("Failed to build webhook client: {}", e)))?;
+
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/xml")
+            .body(statement.to_xml())
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow!("Statement webhook request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Internal(anyhow!(
+                "Statement webhook returned status {}",
+                response.status()
+            )));
+        }
+
+        info!(
+            message_id = %statement.message_id,
+            account_id = %statement.account.id,
+            "Delivered statement to webhook"
+        );
+        Ok(())
+    }
+}
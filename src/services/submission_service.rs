@@ -0,0 +1,217 @@
+use crate::core::executor::AccountExecutor;
+use crate::error::{AppError, Result};
+use crate::models::{SubmissionStatus, TransactionSubmission};
+use crate::repositories::SubmissionRepository;
+use crate::services::{LedgerService, LedgerTransactionRequest};
+use chrono::{Duration, Utc};
+use futures::future::join_all;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Accepts transactions for asynchronous settlement and settles them from a durable queue.
+///
+/// Submissions are validated up front and stored; workers then claim due submissions and
+/// settle up to `concurrency` of them at a time through the ledger service. Submissions
+/// touching the same account settle one at a time, oldest first.
+pub struct SubmissionService {
+    submission_repo: SubmissionRepository,
+    ledger: Arc<LedgerService>,
+    max_attempts: i32,
+    retry_backoff: Duration,
+    stale_after: Duration,
+    batch_size: i64,
+    executor: AccountExecutor,
+}
+
+impl SubmissionService {
+    pub fn new(pool: PgPool, ledger: Arc<LedgerService>) -> Self {
+        Self {
+            submission_repo: SubmissionRepository::new(pool),
+            ledger,
+            max_attempts: 5,
+            retry_backoff: Duration::seconds(1),
+            stale_after: Duration::minutes(5),
+            batch_size: 100,
+            executor: AccountExecutor::new(8),
+        }
+    }
+
+    /// Sets how many attempts a transiently failing submission gets, and the base
+    /// backoff between them.
+    pub fn with_retry_policy(mut self, max_attempts: i32, retry_backoff: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// Sets how many submissions are claimed per pass and how many settle at once.
+    pub fn with_concurrency(mut self, batch_size: i64, concurrency: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self.executor = AccountExecutor::new(concurrency);
+        self
+    }
+
+    /// Sets how long a submission may stay claimed before it is assumed abandoned.
+    pub fn with_stale_after(mut self, stale_after: Duration) -> Self {
+        self.stale_after = stale_after;
+        self
+    }
+
+    /// Validates a transaction request and queues it. Resubmitting an idempotency key
+    /// returns the submission already queued under it.
+    pub async fn submit(&self, request: LedgerTransactionRequest) -> Result<TransactionSubmission> {
+        if let Some(existing) = self
+            .submission_repo
+            .find_by_idempotency_key(&request.idempotency_key)
+            .await?
+        {
+            return Ok(existing);
+        }
+
+        self.ledger.ensure_valid(&request).await?;
+        self.ledger.verify_account(request.source_account_id).await?;
+        self.ledger.verify_account(request.destination_account_id).await?;
+
+        let payload = serde_json::to_value(&request)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize submission: {}", e)))?;
+        let submission = TransactionSubmission::new(&request.idempotency_key, payload, self.max_attempts);
+
+        match self.submission_repo.create(&submission).await? {
+            Some(created) => Ok(created),
+            // Lost a race with a concurrent submission of the same key
+            None => self
+                .submission_repo
+                .find_by_idempotency_key(&request.idempotency_key)
+                .await?
+                .ok_or_else(|| {
+                    AppError::SerializationConflict(format!(
+                        "Submission '{}' changed concurrently",
+                        request.idempotency_key
+                    ))
+                }),
+        }
+    }
+
+    /// Gets a submission by ID.
+    pub async fn get_submission(&self, id: Uuid) -> Result<TransactionSubmission> {
+        self.submission_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Submission {} not found", id)))
+    }
+
+    /// Settles every due submission, returning how many settled.
+    pub async fn process_due(&self) -> Result<usize> {
+        let released = self
+            .submission_repo
+            .release_stale(Utc::now() - self.stale_after)
+            .await?;
+        if released > 0 {
+            warn!("Requeued {} abandoned submissions", released);
+        }
+
+        let mut claimed = self.submission_repo.claim_due(self.batch_size).await?;
+        claimed.sort_by_key(|submission| submission.created_at);
+        let outcomes: Vec<Result<bool>> = join_all(claimed.into_iter().map(|submission| {
+            let request = serde_json::from_value::<LedgerTransactionRequest>(submission.request.clone());
+            let accounts = match &request {
+                Ok(request) => vec![request.source_account_id, request.destination_account_id],
+                Err(_) => Vec::new(),
+            };
+            self.executor.run(&accounts, self.process(submission, request))
+        }))
+        .await;
+
+        let mut settled = 0;
+        for outcome in outcomes {
+            if outcome? {
+                settled += 1;
+            }
+        }
+        Ok(settled)
+    }
+
+    /// Makes one settlement attempt and saves its outcome. Returns true if it settled.
+    async fn process(
+        &self,
+        mut submission: TransactionSubmission,
+        request: serde_json::Result<LedgerTransactionRequest>,
+    ) -> Result<bool> {
+        let result = match request {
+            Ok(request) => self.ledger.process_transaction(request).await,
+            Err(e) => Err(AppError::Validation(format!("Stored request is unreadable: {}", e))),
+        };
+
+        match result {
+            Ok(settled) => {
+                submission.mark_settled(settled.transaction.id);
+                info!(
+                    submission_id = %submission.id,
+                    transaction_id = %settled.transaction.id,
+                    "Settled submitted transaction"
+                );
+            }
+            Err(e) => {
+                warn!(
+                    submission_id = %submission.id,
+                    attempt = submission.attempts,
+                    "Submitted transaction failed: {}",
+                    e
+                );
+                submission.record_failure(e.code(), e.public_message(), e.is_retryable(), self.retry_backoff);
+            }
+        }
+
+        self.submission_repo.update(&submission).await?;
+        Ok(submission.status == SubmissionStatus::Settled)
+    }
+}
+
+/// Background worker pool draining the submission queue.
+pub struct SubmissionWorker {
+    service: Arc<SubmissionService>,
+    running: Arc<AtomicBool>,
+    interval_millis: u64,
+}
+
+impl SubmissionWorker {
+    pub fn new(service: Arc<SubmissionService>, interval_millis: u64) -> Self {
+        Self {
+            service,
+            running: Arc::new(AtomicBool::new(false)),
+            interval_millis,
+        }
+    }
+
+    /// Starts the worker in a background task.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.service.clone();
+        let running = self.running.clone();
+        let interval = self.interval_millis;
+
+        running.store(true, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                if let Err(e) = service.process_due().await {
+                    tracing::error!("Submission worker error: {}", e);
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_millis(interval)).await;
+            }
+        })
+    }
+
+    /// Stops the worker.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Checks if the worker is running.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
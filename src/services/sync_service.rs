@@ -0,0 +1,90 @@
+use crate::error::{AppError, Result};
+use crate::models::{Account, SyncToken, TransactionRecord};
+use crate::repositories::SyncRepository;
+use sqlx::PgPool;
+
+/// One page of the change feed.
+#[derive(Debug, Clone)]
+pub struct SyncPage<T> {
+    pub items: Vec<T>,
+    /// Token to pass to the next call.
+    pub next_token: SyncToken,
+    /// True if the window of the token passed in has more rows to read.
+    pub has_more: bool,
+    /// True if the page is part of the initial snapshot rather than later changes.
+    pub snapshot: bool,
+}
+
+/// Serves snapshots and incremental changes of accounts and transactions to downstream
+/// readers.
+///
+/// Without a token a reader gets a consistent snapshot of everything written so far,
+/// page by page; the token on the last page then returns every row written or updated
+/// since, each at its latest state. Deletes are not part of the feed.
+pub struct SyncService {
+    repo: SyncRepository,
+}
+
+impl SyncService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: SyncRepository::new(pool),
+        }
+    }
+
+    /// Reads the next page of account changes.
+    pub async fn accounts(&self, token: Option<&str>, limit: i64) -> Result<SyncPage<Account>> {
+        let token = Self::parse(token)?;
+        let high = self.high(&token).await?;
+        let rows = self.repo.account_changes(token.low, high, token.after_seq, limit).await?;
+        let last_seq = rows.last().map(|row| row.change_seq);
+        Ok(Self::page(
+            token,
+            high,
+            last_seq,
+            limit,
+            rows.into_iter().map(|row| row.account).collect(),
+        ))
+    }
+
+    /// Reads the next page of transaction changes.
+    pub async fn transactions(&self, token: Option<&str>, limit: i64) -> Result<SyncPage<TransactionRecord>> {
+        let token = Self::parse(token)?;
+        let high = self.high(&token).await?;
+        let rows = self.repo.transaction_changes(token.low, high, token.after_seq, limit).await?;
+        let last_seq = rows.last().map(|row| row.change_seq);
+        Ok(Self::page(
+            token,
+            high,
+            last_seq,
+            limit,
+            rows.into_iter().map(|row| row.transaction).collect(),
+        ))
+    }
+
+    fn parse(token: Option<&str>) -> Result<SyncToken> {
+        match token {
+            Some(token) => SyncToken::decode(token)
+                .ok_or_else(|| AppError::Validation("Invalid sync token".to_string())),
+            None => Ok(SyncToken::snapshot()),
+        }
+    }
+
+    /// Upper bound of the token's window, closing an open window at the watermark.
+    async fn high(&self, token: &SyncToken) -> Result<i64> {
+        match token.high {
+            Some(high) => Ok(high),
+            None => Ok(self.repo.watermark().await?.max(token.low)),
+        }
+    }
+
+    fn page<T>(token: SyncToken, high: i64, last_seq: Option<i64>, limit: i64, items: Vec<T>) -> SyncPage<T> {
+        let has_more = items.len() as i64 >= limit;
+        SyncPage {
+            items,
+            next_token: token.next(high, last_seq, has_more),
+            has_more,
+            snapshot: token.is_snapshot(),
+        }
+    }
+}
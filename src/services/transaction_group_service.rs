@@ -0,0 +1,170 @@
+use crate::error::{AppError, Result};
+use crate::models::{TransactionGroup, TransactionGroupStatus, TransactionRecord};
+use crate::repositories::{TransactionGroupRepository, TransactionRepository};
+use crate::services::{LedgerService, LedgerTransactionRequest};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Most transactions a group can hold.
+pub const MAX_GROUP_SIZE: usize = 100;
+
+/// A transaction group with the transactions it settled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionGroupDetail {
+    pub group: TransactionGroup,
+    /// The group's transactions in submission order; empty unless it settled.
+    pub transactions: Vec<TransactionRecord>,
+}
+
+/// Settles groups of transactions all or nothing.
+///
+/// A group's transactions are validated and posted in one database transaction through
+/// `LedgerService::execute_linked`, linked by the group's ID: if any of them fails,
+/// none is posted and the group is marked `FAILED` with the error. Groups are idempotent
+/// on their own key: resubmitting a settled group returns it, and resubmitting a failed
+/// one tries again. A resubmission must carry the same transactions.
+pub struct TransactionGroupService {
+    group_repo: TransactionGroupRepository,
+    transaction_repo: TransactionRepository,
+    ledger: Arc<LedgerService>,
+}
+
+impl TransactionGroupService {
+    pub fn new(pool: PgPool, ledger: Arc<LedgerService>) -> Self {
+        Self {
+            group_repo: TransactionGroupRepository::new(pool.clone()),
+            transaction_repo: TransactionRepository::new(pool),
+            ledger,
+        }
+    }
+
+    /// Settles a group of transactions, all or nothing.
+    pub async fn submit(
+        &self,
+        idempotency_key: &str,
+        requests: Vec<LedgerTransactionRequest>,
+    ) -> Result<TransactionGroupDetail> {
+        if idempotency_key.trim().is_empty() {
+            return Err(AppError::Validation("Idempotency key cannot be empty".to_string()));
+        }
+        if requests.is_empty() || requests.len() > MAX_GROUP_SIZE {
+            return Err(AppError::Validation(format!(
+                "A transaction group must have between 1 and {} transactions",
+                MAX_GROUP_SIZE
+            )));
+        }
+        let transaction_keys: Vec<String> = requests.iter().map(|r| r.idempotency_key.clone()).collect();
+
+        let group = match self.group_repo.find_by_idempotency_key(idempotency_key).await? {
+            Some(existing) => existing,
+            None => match self
+                .group_repo
+                .create(&TransactionGroup::new(idempotency_key, transaction_keys.clone()))
+                .await?
+            {
+                Some(created) => created,
+                // Lost a race with a concurrent submission of the same key
+                None => self
+                    .group_repo
+                    .find_by_idempotency_key(idempotency_key)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::SerializationConflict(format!(
+                            "Transaction group '{}' was submitted concurrently",
+                            idempotency_key
+                        ))
+                    })?,
+            },
+        };
+        if !group.matches(&transaction_keys) {
+            return Err(AppError::IdempotencyConflict(format!(
+                "Transaction group '{}' was submitted with different transactions",
+                idempotency_key
+            )));
+        }
+        if group.status == TransactionGroupStatus::Settled {
+            return self.detail(group).await;
+        }
+
+        match self.ledger.execute_linked(group.id, requests).await {
+            Ok(results) => {
+                if results.iter().any(|result| result.transaction.link_id != Some(group.id)) {
+                    let error = AppError::IdempotencyConflict(format!(
+                        "Transactions of group '{}' were already posted outside it",
+                        idempotency_key
+                    ));
+                    self.record_failure(group.id, &error).await?;
+                    return Err(error);
+                }
+                let group = self.mark_settled(group).await?;
+                Ok(TransactionGroupDetail {
+                    group,
+                    transactions: results.into_iter().map(|result| result.transaction).collect(),
+                })
+            }
+            Err(e) => {
+                self.record_failure(group.id, &e).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Gets a group and the transactions it settled.
+    ///
+    /// A group left `PENDING` whose transactions were all posted, e.g. because the
+    /// instance settling it stopped before recording the outcome, is marked settled.
+    pub async fn get(&self, id: Uuid) -> Result<TransactionGroupDetail> {
+        let group = self
+            .group_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction group '{}' not found", id)))?;
+        self.detail(group).await
+    }
+
+    /// Gets a group by its idempotency key.
+    pub async fn get_by_idempotency_key(&self, idempotency_key: &str) -> Result<TransactionGroupDetail> {
+        let group = self
+            .group_repo
+            .find_by_idempotency_key(idempotency_key)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction group '{}' not found", idempotency_key)))?;
+        self.detail(group).await
+    }
+
+    async fn detail(&self, mut group: TransactionGroup) -> Result<TransactionGroupDetail> {
+        let mut transactions = self.transaction_repo.find_by_link(group.id).await?;
+        transactions.sort_by_key(|transaction| {
+            group
+                .transaction_keys
+                .iter()
+                .position(|key| *key == transaction.idempotency_key)
+        });
+        if group.status == TransactionGroupStatus::Pending && transactions.len() == group.transaction_keys.len() {
+            group = self.mark_settled(group).await?;
+        }
+        Ok(TransactionGroupDetail { group, transactions })
+    }
+
+    async fn mark_settled(&self, group: TransactionGroup) -> Result<TransactionGroup> {
+        match self
+            .group_repo
+            .update_status(group.id, TransactionGroupStatus::Settled, None, None)
+            .await?
+        {
+            Some(updated) => Ok(updated),
+            // Settled concurrently
+            None => Ok(self.group_repo.find_by_id(group.id).await?.unwrap_or(group)),
+        }
+    }
+
+    /// Marks a group failed, unless a concurrent attempt settled it.
+    async fn record_failure(&self, id: Uuid, error: &AppError) -> Result<()> {
+        self.group_repo
+            .update_status(id, TransactionGroupStatus::Failed, Some(error.code()), Some(&error.to_string()))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use crate::error::{AppError, Result};
+use crate::models::{SagaState, TransactionAuditAction, TransactionRecord, TransactionStatus};
+use crate::repositories::{
+    FinalityRepository, LedgerRepository, NettingRepository, SagaRepository, TransactionAuditRepository,
+    TransactionRepository,
+};
+use crate::services::instruction_executor::{InstructionExecution, INSTRUCTION_EXECUTION_SAGA};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Kind of step shown on a transaction timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimelineEventKind {
+    Created,
+    Validated,
+    LedgerEntry,
+    Settled,
+    BatchAssigned,
+    BatchReassigned,
+    NettingIncluded,
+    InstructionExecution,
+    Finalized,
+    EventEmitted,
+    /// A reversal, refund or chargeback of this transaction.
+    Reversal,
+    Failed,
+    Expired,
+}
+
+/// A single step in a transaction's history.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    pub occurred_at: DateTime<Utc>,
+    pub kind: TimelineEventKind,
+    pub summary: String,
+    pub details: serde_json::Value,
+}
+
+/// Everything that happened to a transaction, oldest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionTimeline {
+    pub transaction_id: Uuid,
+    pub status: TransactionStatus,
+    pub events: Vec<TimelineEvent>,
+}
+
+/// Assembles transaction timelines from the transaction, its ledger entries, batch
+/// netting and instruction execution, finality, related transactions and the
+/// transaction audit log.
+pub struct TransactionTimelineService {
+    transaction_repo: TransactionRepository,
+    audit_repo: TransactionAuditRepository,
+    ledger_repo: LedgerRepository,
+    netting_repo: NettingRepository,
+    saga_repo: SagaRepository,
+    finality_repo: FinalityRepository,
+}
+
+impl TransactionTimelineService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            transaction_repo: TransactionRepository::new(pool.clone()),
+            audit_repo: TransactionAuditRepository::new(pool.clone()),
+            ledger_repo: LedgerRepository::new(pool.clone()),
+            netting_repo: NettingRepository::new(pool.clone()),
+            saga_repo: SagaRepository::new(pool.clone()),
+            finality_repo: FinalityRepository::new(pool),
+        }
+    }
+
+    /// Builds the timeline of a transaction.
+    pub async fn get_timeline(&self, transaction_id: Uuid) -> Result<TransactionTimeline> {
+        let transaction = self
+            .transaction_repo
+            .find_by_id(transaction_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Transaction '{}' not found", transaction_id)))?;
+
+        let mut events = vec![TimelineEvent {
+            occurred_at: transaction.created_at,
+            kind: TimelineEventKind::Created,
+            summary: format!(
+                "{:?} of {} {} from {} to {}",
+                transaction.transaction_type,
+                transaction.amount,
+                transaction.currency,
+                transaction.source_account_id,
+                transaction.destination_account_id
+            ),
+            details: serde_json::json!({
+                "external_id": transaction.external_id,
+                "fee_amount": transaction.fee_amount,
+                "net_amount": transaction.net_amount,
+                "settlement_route": transaction.settlement_route,
+                "priority": transaction.priority,
+            }),
+        }];
+
+        for entry in self.audit_repo.find_by_transaction(transaction_id).await? {
+            let (kind, summary) = match entry.action {
+                TransactionAuditAction::Validated => {
+                    (TimelineEventKind::Validated, "Passed validation".to_string())
+                }
+                TransactionAuditAction::BatchAssigned => (
+                    TimelineEventKind::BatchAssigned,
+                    format!("Assigned to batch {}", json_str(&entry.detail, "batch_id")),
+                ),
+                TransactionAuditAction::BatchReassigned => (
+                    TimelineEventKind::BatchReassigned,
+                    format!(
+                        "Moved from batch {} to batch {}",
+                        json_str(&entry.detail, "from_batch_id"),
+                        json_str(&entry.detail, "batch_id")
+                    ),
+                ),
+                TransactionAuditAction::EventEmitted => (
+                    TimelineEventKind::EventEmitted,
+                    format!("Published to {}", json_str(&entry.detail, "topic")),
+                ),
+                TransactionAuditAction::Expired => (
+                    TimelineEventKind::Expired,
+                    format!("Expired after {}s pending", entry.detail["ttl_secs"]),
+                ),
+            };
+            events.push(TimelineEvent {
+                occurred_at: entry.recorded_at,
+                kind,
+                summary,
+                details: entry.detail,
+            });
+        }
+
+        let mut posted_at = transaction.created_at;
+        for entry in self.ledger_repo.find_by_transaction(transaction_id).await? {
+            posted_at = posted_at.max(entry.created_at);
+            events.push(TimelineEvent {
+                occurred_at: entry.created_at,
+                kind: TimelineEventKind::LedgerEntry,
+                summary: format!(
+                    "{:?} {} {} on account {}",
+                    entry.entry_type, entry.amount, entry.currency, entry.account_id
+                ),
+                details: serde_json::json!({
+                    "entry_id": entry.id,
+                    "balance_after": entry.balance_after,
+                    "effective_date": entry.effective_date,
+                }),
+            });
+        }
+
+        if let Some(settled_at) = transaction.settled_at {
+            // settled_at is the database transaction's start time, which can precede the
+            // creation and postings it settled
+            events.push(TimelineEvent {
+                occurred_at: settled_at.max(posted_at),
+                kind: TimelineEventKind::Settled,
+                summary: format!("Settled via {:?}", transaction.settlement_route),
+                details: serde_json::json!({}),
+            });
+        }
+
+        if let Some(batch_id) = transaction.settlement_batch_id {
+            self.push_batch_events(&transaction, batch_id, &mut events).await?;
+        }
+
+        if let Some(finality) = self.finality_repo.find_by_transaction(transaction_id).await? {
+            events.push(TimelineEvent {
+                occurred_at: finality.final_at,
+                kind: TimelineEventKind::Finalized,
+                summary: "Settlement became final".to_string(),
+                details: serde_json::json!({
+                    "sequence": finality.sequence,
+                    "batch_id": finality.batch_id,
+                }),
+            });
+        }
+
+        for related in self.transaction_repo.find_related(transaction_id).await? {
+            events.push(TimelineEvent {
+                occurred_at: related.created_at,
+                kind: TimelineEventKind::Reversal,
+                summary: format!(
+                    "{:?} {} of {} {}",
+                    related.transaction_type, related.id, related.amount, related.currency
+                ),
+                details: serde_json::json!({
+                    "transaction_id": related.id,
+                    "status": related.status,
+                }),
+            });
+        }
+
+        if transaction.status == TransactionStatus::Failed {
+            events.push(TimelineEvent {
+                occurred_at: transaction.settled_at.unwrap_or(transaction.created_at),
+                kind: TimelineEventKind::Failed,
+                summary: "Transaction failed".to_string(),
+                details: serde_json::json!({}),
+            });
+        }
+
+        // Stable sort keeps causal order for steps recorded at the same instant
+        events.sort_by_key(|event| event.occurred_at);
+
+        Ok(TransactionTimeline {
+            transaction_id,
+            status: transaction.status,
+            events,
+        })
+    }
+
+    /// Adds the netting positions and instruction executions of the transaction's
+    /// batch that involve its parties.
+    async fn push_batch_events(
+        &self,
+        transaction: &TransactionRecord,
+        batch_id: Uuid,
+        events: &mut Vec<TimelineEvent>,
+    ) -> Result<()> {
+        for participant in [transaction.source_account_id, transaction.destination_account_id] {
+            let position = self
+                .netting_repo
+                .find_by_batch_and_participant(batch_id, participant, &transaction.currency)
+                .await?;
+            if let Some(position) = position {
+                events.push(TimelineEvent {
+                    occurred_at: position.created_at,
+                    kind: TimelineEventKind::NettingIncluded,
+                    summary: format!(
+                        "Netted in batch {}: participant {} net position {}",
+                        batch_id, participant, position.net_position
+                    ),
+                    details: serde_json::json!({
+                        "participant_id": participant,
+                        "gross_receivable": position.gross_receivable,
+                        "gross_payable": position.gross_payable,
+                        "transaction_count": position.transaction_count,
+                    }),
+                });
+            }
+        }
+
+        let sagas = self
+            .saga_repo
+            .find_by_instruction_parties(
+                INSTRUCTION_EXECUTION_SAGA,
+                batch_id,
+                transaction.source_account_id,
+                transaction.destination_account_id,
+            )
+            .await?;
+        for saga in sagas {
+            events.push(instruction_event(saga));
+        }
+
+        Ok(())
+    }
+}
+
+fn instruction_event(saga: SagaState) -> TimelineEvent {
+    let execution = serde_json::from_value::<InstructionExecution>(saga.context).ok();
+    let summary = match &execution {
+        Some(execution) => format!(
+            "Instruction {} for {} {} {:?}",
+            execution.instruction.id, execution.instruction.amount, execution.instruction.currency, saga.status
+        ),
+        None => format!("Instruction execution {:?}", saga.status),
+    };
+
+    TimelineEvent {
+        occurred_at: saga.updated_at,
+        kind: TimelineEventKind::InstructionExecution,
+        summary,
+        details: serde_json::json!({
+            "saga_id": saga.id,
+            "status": saga.status,
+            "started_at": saga.created_at,
+            "external_reference": execution.as_ref().and_then(|e| e.external_reference.clone()),
+            "error": saga.error,
+        }),
+    }
+}
+
+fn json_str(value: &serde_json::Value, field: &str) -> String {
+    value.get(field).and_then(|v| v.as_str()).unwrap_or("unknown").to_string()
+}
@@ -0,0 +1,96 @@
+use crate::error::{AppError, Result};
+use crate::models::{TransactionRecord, TransactionType, TransactionTypeDefinition};
+use crate::repositories::TransactionTypeRepository;
+use sqlx::PgPool;
+
+/// Registry of transaction types: the built-in definition of each base type plus the
+/// custom types registered in the database. A base type can be redefined too, e.g. to
+/// settle refunds gross, by registering a definition under its own name. Posting and
+/// netting look up a transaction's definition here for its behavior instead of matching
+/// on its base type.
+pub struct TransactionTypeService {
+    repo: TransactionTypeRepository,
+}
+
+impl TransactionTypeService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            repo: TransactionTypeRepository::new(pool),
+        }
+    }
+
+    /// Registers a custom type, or redefines a base type, replacing any definition it
+    /// had. Existing transactions of the type take on the new behavior.
+    pub async fn register(&self, definition: TransactionTypeDefinition) -> Result<TransactionTypeDefinition> {
+        if TransactionTypeDefinition::is_builtin_code(&definition.code) {
+            if definition.code != definition.base_type.as_str() {
+                return Err(AppError::Validation(format!(
+                    "Base type {} can only be redefined with itself as its base type",
+                    definition.code
+                )));
+            }
+        } else {
+            TransactionTypeDefinition::check_code(&definition.code).map_err(AppError::Validation)?;
+        }
+        self.repo.upsert(&definition).await
+    }
+
+    /// Gets a type's definition by code: the registered one, or a base type's built-in
+    /// definition if it was not redefined.
+    pub async fn get(&self, code: &str) -> Result<TransactionTypeDefinition> {
+        if let Some(definition) = self.repo.find_by_code(code).await? {
+            return Ok(definition);
+        }
+        TransactionTypeDefinition::builtins()
+            .into_iter()
+            .find(|d| d.code == code)
+            .ok_or_else(|| AppError::NotFound(format!("Transaction type '{}' not found", code)))
+    }
+
+    /// Lists the base types, as redefined, followed by the custom ones.
+    pub async fn list(&self) -> Result<Vec<TransactionTypeDefinition>> {
+        let registered = self.repo.list().await?;
+        let (redefined, custom): (Vec<_>, Vec<_>) = registered
+            .into_iter()
+            .partition(|d| TransactionTypeDefinition::is_builtin_code(&d.code));
+        let mut definitions: Vec<TransactionTypeDefinition> = TransactionTypeDefinition::builtins()
+            .into_iter()
+            .map(|builtin| redefined.iter().find(|d| d.code == builtin.code).cloned().unwrap_or(builtin))
+            .collect();
+        definitions.extend(custom);
+        Ok(definitions)
+    }
+
+    /// Resolves the definition a transaction posting as `base_type` follows: the custom
+    /// type named by `code`, or the base type's own definition if there is none. The
+    /// code must name a registered type posting as the same base type.
+    pub async fn resolve(&self, base_type: TransactionType, code: Option<&str>) -> Result<TransactionTypeDefinition> {
+        let code = code.unwrap_or(base_type.as_str());
+        let definition = match self.get(code).await {
+            Err(AppError::NotFound(_)) => {
+                return Err(AppError::Validation(format!("Unknown transaction type '{}'", code)));
+            }
+            result => result?,
+        };
+        if definition.base_type != base_type {
+            return Err(AppError::Validation(format!(
+                "Transaction type '{}' posts as {}, not {}",
+                code,
+                definition.base_type.as_str(),
+                base_type.as_str()
+            )));
+        }
+        Ok(definition)
+    }
+
+    /// Definition of a posted transaction's type.
+    pub async fn resolve_record(&self, transaction: &TransactionRecord) -> Result<TransactionTypeDefinition> {
+        self.resolve(transaction.transaction_type, transaction.type_code.as_deref()).await
+    }
+
+    /// Codes of the types, custom or redefined base types, that settle gross rather than
+    /// netting.
+    pub async fn gross_type_codes(&self) -> Result<Vec<String>> {
+        self.repo.list_gross_codes().await
+    }
+}
@@ -0,0 +1,78 @@
+//! Write combining for ledger postings.
+//!
+//! Posting a transaction on its own takes several round trips to Postgres. Under load,
+//! the combiner queues postings, collects those arriving within a short window, and
+//! hands them to `LedgerService::execute_combined`, which writes the whole group in one
+//! database transaction. Callers still wait for, and get, their own posting's outcome.
+//! Groups are written one after another, so postings keep the order they arrived in.
+
+use crate::error::{AppError, Result};
+use crate::services::{LedgerService, LedgerTransactionRequest, LedgerTransactionResult};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// How long the first posting of a group waits for others to join it, unless configured.
+pub const DEFAULT_COMBINE_WINDOW: Duration = Duration::from_millis(2);
+
+/// Most postings written in one group, unless configured.
+pub const DEFAULT_MAX_COMBINED: usize = 100;
+
+struct QueuedWrite {
+    request: LedgerTransactionRequest,
+    reply: oneshot::Sender<Result<LedgerTransactionResult>>,
+}
+
+/// Queues postings and writes them in groups through a ledger service.
+pub struct WriteCombiner {
+    sender: mpsc::Sender<QueuedWrite>,
+}
+
+impl WriteCombiner {
+    /// Starts writing groups through `ledger`, which must not itself combine writes.
+    /// Each group closes `window` after its first posting arrives, or once it holds
+    /// `max_combined` postings.
+    pub fn start(ledger: Arc<LedgerService>, window: Duration, max_combined: usize) -> Self {
+        let max_combined = max_combined.max(1);
+        let (sender, receiver) = mpsc::channel(max_combined * 4);
+        tokio::spawn(run(ledger, receiver, window, max_combined));
+        Self { sender }
+    }
+
+    /// Queues a posting and waits for the outcome of the group it was written in.
+    pub async fn submit(&self, request: LedgerTransactionRequest) -> Result<LedgerTransactionResult> {
+        let (reply, outcome) = oneshot::channel();
+        self.sender
+            .send(QueuedWrite { request, reply })
+            .await
+            .map_err(|_| AppError::Unavailable("Write combining has stopped".to_string()))?;
+        outcome
+            .await
+            .map_err(|_| AppError::Unavailable("Write combining stopped before the posting was written".to_string()))?
+    }
+}
+
+async fn run(
+    ledger: Arc<LedgerService>,
+    mut receiver: mpsc::Receiver<QueuedWrite>,
+    window: Duration,
+    max_combined: usize,
+) {
+    while let Some(first) = receiver.recv().await {
+        let mut group = vec![first];
+        let closes_at = tokio::time::Instant::now() + window;
+        while group.len() < max_combined {
+            match tokio::time::timeout_at(closes_at, receiver.recv()).await {
+                Ok(Some(write)) => group.push(write),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        let (requests, replies): (Vec<_>, Vec<_>) = group.into_iter().map(|write| (write.request, write.reply)).unzip();
+        let outcomes = ledger.execute_combined(requests).await;
+        for (reply, outcome) in replies.into_iter().zip(outcomes) {
+            // The caller may have given up waiting
+            let _ = reply.send(outcome);
+        }
+    }
+}
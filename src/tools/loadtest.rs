@@ -0,0 +1,592 @@
+//! HTTP load-test harness with latency SLO reporting.
+//!
+//! Seeds funded accounts through `POST /accounts`, then keeps `concurrency` clients
+//! sending a weighted mix of requests back to back for a set duration:
+//!
+//! - **payment**: `POST /transactions` with a `PAYMENT`
+//! - **transfer**: `POST /transactions` with a `TRANSFER`
+//! - **async**: `POST /transactions/async`
+//! - **balance**: `GET /accounts/:id/balance`
+//!
+//! Unlike the scenario generator this goes through the whole server (routing, JSON,
+//! middleware), so its numbers are what a client sees. The report records throughput,
+//! latency percentiles overall and per operation, and errors by status, and checks them
+//! against p99 latency, throughput and error rate thresholds.
+
+use super::percentile;
+use crate::api::requests::{CreateAccountRequest, CreateTransactionRequest};
+use crate::api::server::API_KEY_HEADER;
+use crate::error::{AppError, Result};
+use crate::models::{AccountType, TransactionType};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// A kind of request the load test sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Operation {
+    Payment,
+    Transfer,
+    AsyncPayment,
+    BalanceRead,
+}
+
+impl Operation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Payment => "payment",
+            Operation::Transfer => "transfer",
+            Operation::AsyncPayment => "async",
+            Operation::BalanceRead => "balance",
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "payment" => Ok(Operation::Payment),
+            "transfer" => Ok(Operation::Transfer),
+            "async" => Ok(Operation::AsyncPayment),
+            "balance" => Ok(Operation::BalanceRead),
+            other => Err(AppError::Validation(format!("Unknown load test operation: {}", other))),
+        }
+    }
+}
+
+/// Parses an operation mix such as `payment=8,transfer=1,balance=1`. An operation
+/// without a weight counts once.
+pub fn parse_operation_mix(value: &str) -> Result<Vec<(Operation, u32)>> {
+    let mix = value
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let (operation, weight) = part.split_once('=').unwrap_or((part, "1"));
+            let weight = weight
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| AppError::Validation(format!("Invalid weight in operation mix '{}'", part)))?;
+            Ok((operation.parse()?, weight))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if mix.iter().all(|(_, weight)| *weight == 0) {
+        return Err(AppError::Validation("Operation mix needs at least one operation with a weight".to_string()));
+    }
+    Ok(mix)
+}
+
+/// Levels a run has to meet to pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SloThresholds {
+    /// Highest acceptable p99 latency over all requests, in milliseconds.
+    pub p99_ms: f64,
+    /// Lowest acceptable throughput in requests per second; 0 disables the check.
+    pub min_throughput: f64,
+    /// Highest acceptable share of failed requests, from 0.0 to 1.0.
+    pub max_error_rate: f64,
+}
+
+impl Default for SloThresholds {
+    fn default() -> Self {
+        Self {
+            p99_ms: 100.0,
+            min_throughput: 0.0,
+            max_error_rate: 0.01,
+        }
+    }
+}
+
+/// What to run against which server.
+#[derive(Debug, Clone)]
+pub struct LoadTestConfig {
+    pub base_url: String,
+    pub api_key: Option<String>,
+    /// Accounts seeded before the run; payments go round them in a ring.
+    pub accounts: usize,
+    /// Currency of the seeded accounts; a fresh one is made up if unset.
+    pub currency: Option<String>,
+    pub opening_balance: Decimal,
+    pub amount: Decimal,
+    pub mix: Vec<(Operation, u32)>,
+    /// Clients sending requests at once.
+    pub concurrency: usize,
+    pub duration: Duration,
+    pub request_timeout: Duration,
+    pub slo: SloThresholds,
+}
+
+impl Default for LoadTestConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:3000".to_string(),
+            api_key: None,
+            accounts: 20,
+            currency: None,
+            opening_balance: Decimal::from(1_000_000_000),
+            amount: Decimal::new(1000, 2),
+            mix: vec![
+                (Operation::Payment, 6),
+                (Operation::Transfer, 1),
+                (Operation::AsyncPayment, 1),
+                (Operation::BalanceRead, 2),
+            ],
+            concurrency: 32,
+            duration: Duration::from_secs(30),
+            request_timeout: Duration::from_secs(10),
+            slo: SloThresholds::default(),
+        }
+    }
+}
+
+impl LoadTestConfig {
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn with_accounts(mut self, accounts: usize) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    pub fn with_currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = Some(currency.into());
+        self
+    }
+
+    pub fn with_amount(mut self, amount: Decimal) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    pub fn with_mix(mut self, mix: Vec<(Operation, u32)>) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    pub fn with_load(mut self, concurrency: usize, duration: Duration) -> Self {
+        self.concurrency = concurrency;
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_slo(mut self, slo: SloThresholds) -> Self {
+        self.slo = slo;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.accounts < 2 {
+            return Err(AppError::Validation("A load test needs at least two accounts".to_string()));
+        }
+        if self.concurrency == 0 || self.duration.is_zero() {
+            return Err(AppError::Validation("Load test concurrency and duration must be positive".to_string()));
+        }
+        if self.amount <= Decimal::ZERO {
+            return Err(AppError::Validation("Load test amount must be positive".to_string()));
+        }
+        if self.mix.iter().all(|(_, weight)| *weight == 0) {
+            return Err(AppError::Validation("Operation mix needs at least one operation with a weight".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Operation of the `sequence`th request, cycling through the mix by weight.
+    pub fn operation_for(&self, sequence: usize) -> Operation {
+        let total: usize = self.mix.iter().map(|(_, weight)| *weight as usize).sum();
+        let mut slot = sequence % total;
+        for (operation, weight) in &self.mix {
+            if slot < *weight as usize {
+                return *operation;
+            }
+            slot -= *weight as usize;
+        }
+        unreachable!("slot is below the total weight")
+    }
+}
+
+/// Latency percentiles of a set of requests, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn from_latencies(mut latencies: Vec<Duration>) -> Self {
+        latencies.sort();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        Self {
+            p50_ms: ms(percentile(&latencies, 0.50)),
+            p95_ms: ms(percentile(&latencies, 0.95)),
+            p99_ms: ms(percentile(&latencies, 0.99)),
+            max_ms: ms(latencies.last().copied().unwrap_or_default()),
+        }
+    }
+}
+
+/// Requests of one operation.
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationReport {
+    pub requests: usize,
+    pub errors: usize,
+    pub latency: LatencySummary,
+}
+
+/// One SLO and whether the run met it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SloCheck {
+    pub name: &'static str,
+    pub threshold: f64,
+    pub actual: f64,
+    pub passed: bool,
+}
+
+/// Outcome of a load test, serialized as the machine-readable report.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadTestReport {
+    pub base_url: String,
+    pub currency: String,
+    pub concurrency: usize,
+    pub elapsed_secs: f64,
+    pub requests: usize,
+    pub errors: usize,
+    pub throughput_rps: f64,
+    pub error_rate: f64,
+    pub latency: LatencySummary,
+    pub operations: BTreeMap<&'static str, OperationReport>,
+    /// Failed requests by HTTP status, or `transport` when no response came back.
+    pub errors_by_status: BTreeMap<String, usize>,
+    pub slo: Vec<SloCheck>,
+    pub passed: bool,
+}
+
+/// Checks a run's p99 latency, throughput and error rate against `slo`.
+pub fn evaluate_slo(slo: &SloThresholds, latency: &LatencySummary, throughput: f64, error_rate: f64) -> Vec<SloCheck> {
+    let mut checks = vec![SloCheck {
+        name: "p99_latency_ms",
+        threshold: slo.p99_ms,
+        actual: latency.p99_ms,
+        passed: latency.p99_ms <= slo.p99_ms,
+    }];
+    if slo.min_throughput > 0.0 {
+        checks.push(SloCheck {
+            name: "min_throughput_rps",
+            threshold: slo.min_throughput,
+            actual: throughput,
+            passed: throughput >= slo.min_throughput,
+        });
+    }
+    checks.push(SloCheck {
+        name: "max_error_rate",
+        threshold: slo.max_error_rate,
+        actual: error_rate,
+        passed: error_rate <= slo.max_error_rate,
+    });
+    checks
+}
+
+struct Sample {
+    operation: Operation,
+    latency: Duration,
+    /// `None` for a success, otherwise the status or `transport`.
+    error: Option<String>,
+}
+
+/// Drives the HTTP API and reports against the configured SLOs.
+pub struct LoadTester {
+    client: reqwest::Client,
+    config: LoadTestConfig,
+}
+
+impl LoadTester {
+    pub fn new(config: LoadTestConfig) -> Result<Self> {
+        config.validate()?;
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .pool_max_idle_per_host(config.concurrency)
+            .build()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to build HTTP client: {}", e)))?;
+        Ok(Self { client, config })
+    }
+
+    pub fn config(&self) -> &LoadTestConfig {
+        &self.config
+    }
+
+    /// Seeds the accounts, then sends the mix until the duration is up.
+    pub async fn run(&self) -> Result<LoadTestReport> {
+        let currency = self.config.currency.clone().unwrap_or_else(|| {
+            let id = Uuid::new_v4().simple().to_string();
+            format!("L{}", &id[..2]).to_uppercase()
+        });
+        let mut accounts = Vec::with_capacity(self.config.accounts);
+        for i in 0..self.config.accounts {
+            accounts.push(self.create_account(&currency, i).await?);
+        }
+        let accounts = Arc::new(accounts);
+
+        let sequence = Arc::new(AtomicUsize::new(0));
+        let started = Instant::now();
+        let deadline = started + self.config.duration;
+        let workers = (0..self.config.concurrency).map(|_| {
+            let client = self.client.clone();
+            let config = self.config.clone();
+            let accounts = accounts.clone();
+            let currency = currency.clone();
+            let sequence = sequence.clone();
+            tokio::spawn(async move {
+                let mut samples = Vec::new();
+                while Instant::now() < deadline {
+                    let seq = sequence.fetch_add(1, Ordering::Relaxed);
+                    let operation = config.operation_for(seq);
+                    let start = Instant::now();
+                    let error = send(&client, &config, &accounts, &currency, operation, seq).await.err();
+                    samples.push(Sample {
+                        operation,
+                        latency: start.elapsed(),
+                        error,
+                    });
+                }
+                samples
+            })
+        });
+        let mut samples = Vec::new();
+        for worker in futures::future::join_all(workers).await {
+            samples.extend(worker.map_err(|e| AppError::Internal(anyhow::anyhow!("Load test client panicked: {}", e)))?);
+        }
+        let elapsed = started.elapsed();
+
+        Ok(self.report(currency, samples, elapsed))
+    }
+
+    async fn create_account(&self, currency: &str, index: usize) -> Result<Uuid> {
+        let request = CreateAccountRequest {
+            external_id: format!("LOADTEST-{}", Uuid::new_v4()),
+            name: format!("Load test account {}", index + 1),
+            account_type: AccountType::Asset,
+            currency: currency.to_string(),
+            initial_balance: Some(self.config.opening_balance),
+            metadata: None,
+        };
+        let response = request_builder(&self.client, &self.config, reqwest::Method::POST, "/accounts")
+            .body(serde_json::to_vec(&request).map_err(|e| AppError::Internal(e.into()))?)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to create load test account: {}", e)))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to read account response: {}", e)))?;
+        if !status.is_success() {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Creating load test account returned {}: {}",
+                status,
+                body
+            )));
+        }
+        let body: serde_json::Value = serde_json::from_str(&body).map_err(|e| AppError::Internal(e.into()))?;
+        body["data"]["id"]
+            .as_str()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Account response has no id: {}", body)))
+    }
+
+    fn report(&self, currency: String, samples: Vec<Sample>, elapsed: Duration) -> LoadTestReport {
+        let requests = samples.len();
+        let mut errors_by_status = BTreeMap::new();
+        let mut by_operation: BTreeMap<Operation, (Vec<Duration>, usize)> = BTreeMap::new();
+        let mut latencies = Vec::with_capacity(requests);
+        for sample in samples {
+            let entry = by_operation.entry(sample.operation).or_default();
+            entry.0.push(sample.latency);
+            if let Some(error) = sample.error {
+                entry.1 += 1;
+                *errors_by_status.entry(error).or_insert(0) += 1;
+            }
+            latencies.push(sample.latency);
+        }
+        let errors: usize = errors_by_status.values().sum();
+        let operations = by_operation
+            .into_iter()
+            .map(|(operation, (latencies, errors))| {
+                let report = OperationReport {
+                    requests: latencies.len(),
+                    errors,
+                    latency: LatencySummary::from_latencies(latencies),
+                };
+                (operation.as_str(), report)
+            })
+            .collect();
+
+        let throughput_rps = if elapsed.is_zero() { 0.0 } else { requests as f64 / elapsed.as_secs_f64() };
+        let error_rate = if requests == 0 { 0.0 } else { errors as f64 / requests as f64 };
+        let latency = LatencySummary::from_latencies(latencies);
+        let slo = evaluate_slo(&self.config.slo, &latency, throughput_rps, error_rate);
+        let passed = requests > 0 && slo.iter().all(|check| check.passed);
+
+        LoadTestReport {
+            base_url: self.config.base_url.clone(),
+            currency,
+            concurrency: self.config.concurrency,
+            elapsed_secs: elapsed.as_secs_f64(),
+            requests,
+            errors,
+            throughput_rps,
+            error_rate,
+            latency,
+            operations,
+            errors_by_status,
+            slo,
+            passed,
+        }
+    }
+}
+
+fn request_builder(
+    client: &reqwest::Client,
+    config: &LoadTestConfig,
+    method: reqwest::Method,
+    path: &str,
+) -> reqwest::RequestBuilder {
+    let builder = client
+        .request(method, format!("{}{}", config.base_url, path))
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    match &config.api_key {
+        Some(key) => builder.header(API_KEY_HEADER, key),
+        None => builder,
+    }
+}
+
+/// Sends the `seq`th request, returning the status or `transport` if it failed.
+async fn send(
+    client: &reqwest::Client,
+    config: &LoadTestConfig,
+    accounts: &[Uuid],
+    currency: &str,
+    operation: Operation,
+    seq: usize,
+) -> std::result::Result<(), String> {
+    let source = accounts[seq % accounts.len()];
+    let destination = accounts[(seq + 1) % accounts.len()];
+    let request = match operation {
+        Operation::BalanceRead => {
+            request_builder(client, config, reqwest::Method::GET, &format!("/accounts/{}/balance", source))
+        }
+        Operation::Payment | Operation::Transfer | Operation::AsyncPayment => {
+            let (path, transaction_type) = match operation {
+                Operation::Transfer => ("/transactions", TransactionType::Transfer),
+                Operation::AsyncPayment => ("/transactions/async", TransactionType::Payment),
+                _ => ("/transactions", TransactionType::Payment),
+            };
+            let body = CreateTransactionRequest {
+                external_id: format!("LOADTEST-{}", Uuid::new_v4()),
+                transaction_type,
+                source_account_id: source,
+                destination_account_id: destination,
+                amount: config.amount,
+                currency: currency.to_string(),
+                fee_amount: None,
+                idempotency_key: Uuid::new_v4().to_string(),
+                metadata: None,
+                priority: None,
+                effective_date: None,
+                source_system: None,
+                type_code: None,
+                exclude_from_netting: false,
+                duplicate_override: None,
+                narrative: None,
+                reference: None,
+            };
+            let body = serde_json::to_vec(&body).map_err(|_| "serialization".to_string())?;
+            request_builder(client, config, reqwest::Method::POST, path).body(body)
+        }
+    };
+
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            // Read the body so the connection goes back to the pool
+            let _ = response.bytes().await;
+            if status.is_success() {
+                Ok(())
+            } else {
+                Err(status.as_u16().to_string())
+            }
+        }
+        Err(_) => Err("transport".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_mix_cycles_by_weight() {
+        let mix = parse_operation_mix("payment=2, balance").unwrap();
+        assert_eq!(mix, vec![(Operation::Payment, 2), (Operation::BalanceRead, 1)]);
+
+        let config = LoadTestConfig::default().with_mix(mix);
+        let operations: Vec<_> = (0..4).map(|i| config.operation_for(i)).collect();
+        assert_eq!(
+            operations,
+            vec![Operation::Payment, Operation::Payment, Operation::BalanceRead, Operation::Payment]
+        );
+
+        assert!(parse_operation_mix("refund=1").is_err());
+        assert!(parse_operation_mix("payment=x").is_err());
+        assert!(parse_operation_mix("payment=0").is_err());
+    }
+
+    #[test]
+    fn test_latency_summary_percentiles() {
+        let latencies = (1..=100).rev().map(Duration::from_millis).collect();
+        let summary = LatencySummary::from_latencies(latencies);
+        assert_eq!(summary.p50_ms.round(), 51.0);
+        assert_eq!(summary.p99_ms.round(), 99.0);
+        assert_eq!(summary.max_ms.round(), 100.0);
+
+        assert_eq!(LatencySummary::from_latencies(Vec::new()), LatencySummary::default());
+    }
+
+    #[test]
+    fn test_slo_evaluation() {
+        let slo = SloThresholds {
+            p99_ms: 50.0,
+            min_throughput: 1000.0,
+            max_error_rate: 0.01,
+        };
+        let latency = LatencySummary {
+            p99_ms: 42.0,
+            ..Default::default()
+        };
+
+        let checks = evaluate_slo(&slo, &latency, 1500.0, 0.0);
+        assert_eq!(checks.len(), 3);
+        assert!(checks.iter().all(|check| check.passed));
+
+        let checks = evaluate_slo(&slo, &latency, 800.0, 0.05);
+        let failed: Vec<_> = checks.iter().filter(|check| !check.passed).map(|check| check.name).collect();
+        assert_eq!(failed, vec!["min_throughput_rps", "max_error_rate"]);
+
+        // No throughput floor unless one is set
+        let checks = evaluate_slo(&SloThresholds::default(), &latency, 0.0, 0.0);
+        assert!(checks.iter().all(|check| check.name != "min_throughput_rps"));
+    }
+}
@@ -0,0 +1,17 @@
+pub mod loadtest;
+pub mod scenario;
+
+pub use loadtest::{
+    evaluate_slo, parse_operation_mix, LoadTestConfig, LoadTestReport, LoadTester, Operation, SloCheck, SloThresholds,
+};
+pub use scenario::{parse_mix, PaymentPattern, ScenarioConfig, ScenarioGenerator, ScenarioReport};
+
+use std::time::Duration;
+
+/// The `p`th percentile (0.0 to 1.0) of latencies sorted in ascending order.
+pub(crate) fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    sorted[((sorted.len() as f64 - 1.0) * p).round() as usize]
+}
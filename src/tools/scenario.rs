@@ -0,0 +1,443 @@
+//! Scenario generator for demos and load tests.
+//!
+//! Opens a set of funded participant accounts in a currency of its own, then posts
+//! payments through `LedgerService` at a target rate for a set duration. Each payment
+//! follows one of the configured patterns, picked in proportion to its weight:
+//!
+//! - **Bilateral**: participants are paired off and each pair pays back and forth
+//! - **Circular**: every participant pays the next one round a ring
+//! - **Hub-and-spoke**: the first participant is a hub that the others pay and are paid by
+//!
+//! The settled payments are netted at the end, so runs show how each mix nets as well
+//! as how fast it posts. Payer, payee and amount are derived from the payment's sequence
+//! number, so a scenario posts the same payments every time it is run.
+
+use super::percentile;
+use crate::error::{AppError, Result};
+use crate::models::{AccountType, TransactionRecord};
+use crate::services::{
+    account_service::CreateAccountRequest, AccountService, LedgerService, LedgerTransactionRequest, NettingService,
+};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+/// How a payment picks its payer and payee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaymentPattern {
+    Bilateral,
+    Circular,
+    HubAndSpoke,
+}
+
+impl PaymentPattern {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentPattern::Bilateral => "bilateral",
+            PaymentPattern::Circular => "circular",
+            PaymentPattern::HubAndSpoke => "hub",
+        }
+    }
+
+    /// Payer and payee, as participant indices, of the `sequence`th payment following
+    /// this pattern among `participants` participants (at least two).
+    pub fn parties(&self, participants: usize, sequence: usize) -> (usize, usize) {
+        match self {
+            PaymentPattern::Bilateral => {
+                // An odd participant out trades with the first pair
+                let pairs = (participants / 2).max(1);
+                let pair = sequence / 2 % pairs;
+                let (a, b) = if pair == 0 && participants % 2 == 1 && sequence / 2 / pairs % 2 == 1 {
+                    (0, participants - 1)
+                } else {
+                    (pair * 2, pair * 2 + 1)
+                };
+                if sequence.is_multiple_of(2) { (a, b) } else { (b, a) }
+            }
+            PaymentPattern::Circular => {
+                let from = sequence % participants;
+                (from, (from + 1) % participants)
+            }
+            PaymentPattern::HubAndSpoke => {
+                let spoke = 1 + sequence / 2 % (participants - 1);
+                if sequence.is_multiple_of(2) { (spoke, 0) } else { (0, spoke) }
+            }
+        }
+    }
+}
+
+impl FromStr for PaymentPattern {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "bilateral" => Ok(PaymentPattern::Bilateral),
+            "circular" => Ok(PaymentPattern::Circular),
+            "hub" | "hub_and_spoke" | "hub-and-spoke" => Ok(PaymentPattern::HubAndSpoke),
+            other => Err(AppError::Validation(format!(
+                "Unknown payment pattern '{}'; expected bilateral, circular or hub",
+                other
+            ))),
+        }
+    }
+}
+
+/// Parses a pattern mix such as `bilateral=2,circular=1,hub=1`. A pattern without a
+/// weight counts once.
+pub fn parse_mix(value: &str) -> Result<Vec<(PaymentPattern, u32)>> {
+    let mix = value
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let (pattern, weight) = part.split_once('=').unwrap_or((part, "1"));
+            let weight = weight
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| AppError::Validation(format!("Invalid weight in pattern mix '{}'", part)))?;
+            Ok((pattern.parse()?, weight))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if mix.iter().all(|(_, weight)| *weight == 0) {
+        return Err(AppError::Validation("Pattern mix needs at least one pattern with a weight".to_string()));
+    }
+    Ok(mix)
+}
+
+/// What to generate.
+#[derive(Debug, Clone)]
+pub struct ScenarioConfig {
+    pub participants: usize,
+    /// Currency of the scenario's accounts; a fresh one is made up if unset.
+    pub currency: Option<String>,
+    pub opening_balance: Decimal,
+    pub mix: Vec<(PaymentPattern, u32)>,
+    /// Payments started per second.
+    pub tps: f64,
+    pub duration: Duration,
+    pub min_amount: Decimal,
+    pub max_amount: Decimal,
+    /// Most payments in flight at once.
+    pub concurrency: usize,
+}
+
+impl Default for ScenarioConfig {
+    fn default() -> Self {
+        Self {
+            participants: 10,
+            currency: None,
+            opening_balance: Decimal::from(1_000_000),
+            mix: vec![
+                (PaymentPattern::Bilateral, 1),
+                (PaymentPattern::Circular, 1),
+                (PaymentPattern::HubAndSpoke, 1),
+            ],
+            tps: 50.0,
+            duration: Duration::from_secs(10),
+            min_amount: Decimal::new(100, 2),
+            max_amount: Decimal::new(100_000, 2),
+            concurrency: 16,
+        }
+    }
+}
+
+impl ScenarioConfig {
+    pub fn with_participants(mut self, participants: usize) -> Self {
+        self.participants = participants;
+        self
+    }
+
+    pub fn with_currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = Some(currency.into());
+        self
+    }
+
+    pub fn with_opening_balance(mut self, opening_balance: Decimal) -> Self {
+        self.opening_balance = opening_balance;
+        self
+    }
+
+    pub fn with_mix(mut self, mix: Vec<(PaymentPattern, u32)>) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    pub fn with_rate(mut self, tps: f64, duration: Duration) -> Self {
+        self.tps = tps;
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_amounts(mut self, min_amount: Decimal, max_amount: Decimal) -> Self {
+        self.min_amount = min_amount;
+        self.max_amount = max_amount;
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.participants < 2 {
+            return Err(AppError::Validation("A scenario needs at least two participants".to_string()));
+        }
+        if !self.tps.is_finite() || self.tps <= 0.0 {
+            return Err(AppError::Validation("Target TPS must be positive".to_string()));
+        }
+        if self.min_amount <= Decimal::ZERO || self.max_amount < self.min_amount {
+            return Err(AppError::Validation(
+                "Payment amounts must be positive with the minimum no larger than the maximum".to_string(),
+            ));
+        }
+        if self.mix.iter().all(|(_, weight)| *weight == 0) {
+            return Err(AppError::Validation("Pattern mix needs at least one pattern with a weight".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Pattern of the `sequence`th payment, cycling through the mix by weight.
+    pub fn pattern_for(&self, sequence: usize) -> PaymentPattern {
+        let total: usize = self.mix.iter().map(|(_, weight)| *weight as usize).sum();
+        let mut slot = sequence % total;
+        for (pattern, weight) in &self.mix {
+            if slot < *weight as usize {
+                return *pattern;
+            }
+            slot -= *weight as usize;
+        }
+        unreachable!("slot is below the total weight")
+    }
+
+    /// Amount of the `sequence`th payment, spread over the configured range in cents.
+    pub fn amount_for(&self, sequence: usize) -> Decimal {
+        let span = ((self.max_amount - self.min_amount) * Decimal::from(100)).trunc();
+        let span = u64::try_from(span).unwrap_or(u64::MAX).saturating_add(1);
+        let step = (sequence as u64).wrapping_mul(7919) % span;
+        self.min_amount + Decimal::new(step as i64, 2)
+    }
+}
+
+/// Outcome of a scenario run.
+#[derive(Debug, Clone)]
+pub struct ScenarioReport {
+    pub currency: String,
+    pub participants: Vec<Uuid>,
+    pub submitted: usize,
+    pub settled: usize,
+    /// Failed payments by error message.
+    pub failures: HashMap<String, usize>,
+    /// Payments started, by pattern.
+    pub by_pattern: HashMap<PaymentPattern, usize>,
+    pub elapsed: Duration,
+    pub latency_p50: Duration,
+    pub latency_p95: Duration,
+    pub latency_p99: Duration,
+    pub gross_volume: Decimal,
+    pub net_volume: Decimal,
+    /// Share of the gross volume netted away, in percent.
+    pub netting_efficiency: Decimal,
+    pub settlement_instructions: usize,
+}
+
+impl ScenarioReport {
+    /// Settled payments per second over the run.
+    pub fn achieved_tps(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.settled as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Generates scenarios through the service layer.
+pub struct ScenarioGenerator {
+    pool: PgPool,
+    config: ScenarioConfig,
+}
+
+impl ScenarioGenerator {
+    pub fn new(pool: PgPool, config: ScenarioConfig) -> Self {
+        Self { pool, config }
+    }
+
+    /// Opens the participants' accounts, returning their IDs in participant order.
+    pub async fn seed(&self, currency: &str) -> Result<Vec<Uuid>> {
+        let accounts = AccountService::new(self.pool.clone());
+        let run = Uuid::new_v4().simple().to_string();
+        let mut participants = Vec::with_capacity(self.config.participants);
+        for i in 0..self.config.participants {
+            let account = accounts
+                .create_account(CreateAccountRequest {
+                    external_id: format!("SCN-{}-{}", &run[..8], i),
+                    name: format!("Scenario Participant {}", i),
+                    account_type: AccountType::Asset,
+                    currency: currency.to_string(),
+                    initial_balance: Some(self.config.opening_balance),
+                    metadata: Some(serde_json::json!({ "scenario": &run[..8], "participant": i })),
+                })
+                .await?;
+            participants.push(account.id);
+        }
+        Ok(participants)
+    }
+
+    /// Seeds the participants, posts payments at the target rate until the duration is
+    /// up, waits for those in flight, and nets what settled.
+    pub async fn run(&self) -> Result<ScenarioReport> {
+        self.config.validate()?;
+        let currency = self
+            .config
+            .currency
+            .clone()
+            .unwrap_or_else(|| format!("S{}", &Uuid::new_v4().simple().to_string()[..2]).to_uppercase());
+        let participants = self.seed(&currency).await?;
+        tracing::info!(
+            "Scenario seeded {} participants in {}, posting at {} tx/s for {:?}",
+            participants.len(),
+            currency,
+            self.config.tps,
+            self.config.duration
+        );
+
+        let ledger = Arc::new(LedgerService::new(self.pool.clone()));
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency.max(1)));
+        let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / self.config.tps));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Burst);
+        let mut by_pattern: HashMap<PaymentPattern, usize> = HashMap::new();
+        let mut sequences: HashMap<PaymentPattern, usize> = HashMap::new();
+        let mut handles = Vec::new();
+        let started = Instant::now();
+
+        for sequence in 0.. {
+            ticker.tick().await;
+            if started.elapsed() >= self.config.duration {
+                break;
+            }
+            let pattern = self.config.pattern_for(sequence);
+            let pattern_sequence = sequences.entry(pattern).or_default();
+            let (from, to) = pattern.parties(participants.len(), *pattern_sequence);
+            *pattern_sequence += 1;
+            *by_pattern.entry(pattern).or_default() += 1;
+
+            let request = LedgerTransactionRequest::payment(
+                format!("SCN-PAY-{}", Uuid::new_v4()),
+                participants[from],
+                participants[to],
+                self.config.amount_for(sequence),
+                &currency,
+                Uuid::new_v4().to_string(),
+            );
+            let permit = semaphore.clone().acquire_owned()
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Scenario stopped: {}", e)))?;
+            let ledger = ledger.clone();
+            handles.push(tokio::spawn(async move {
+                let timer = Instant::now();
+                let result = ledger.process_payment(request).await;
+                drop(permit);
+                (timer.elapsed(), result.map(|posted| posted.transaction))
+            }));
+        }
+
+        let submitted = handles.len();
+        let mut latencies = Vec::with_capacity(submitted);
+        let mut settled: Vec<TransactionRecord> = Vec::with_capacity(submitted);
+        let mut failures: HashMap<String, usize> = HashMap::new();
+        for handle in handles {
+            let (latency, result) = handle
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Scenario payment task failed: {}", e)))?;
+            latencies.push(latency);
+            match result {
+                Ok(transaction) => settled.push(transaction),
+                Err(e) => *failures.entry(e.to_string()).or_default() += 1,
+            }
+        }
+        let elapsed = started.elapsed();
+        latencies.sort();
+
+        let netting = NettingService::new(self.pool.clone()).calculate_multilateral_netting(
+            Uuid::new_v4(),
+            &currency,
+            &settled,
+        );
+
+        Ok(ScenarioReport {
+            currency,
+            participants,
+            submitted,
+            settled: settled.len(),
+            failures,
+            by_pattern,
+            elapsed,
+            latency_p50: percentile(&latencies, 0.50),
+            latency_p95: percentile(&latencies, 0.95),
+            latency_p99: percentile(&latencies, 0.99),
+            gross_volume: netting.total_gross_volume,
+            net_volume: netting.total_net_volume,
+            netting_efficiency: netting.netting_efficiency,
+            settlement_instructions: netting.instructions.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bilateral_pairs_pay_back_and_forth() {
+        let parties: Vec<_> = (0..6).map(|i| PaymentPattern::Bilateral.parties(4, i)).collect();
+        assert_eq!(parties, vec![(0, 1), (1, 0), (2, 3), (3, 2), (0, 1), (1, 0)]);
+
+        // The odd participant out still trades
+        let parties: Vec<_> = (0..4).map(|i| PaymentPattern::Bilateral.parties(3, i)).collect();
+        assert_eq!(parties, vec![(0, 1), (1, 0), (0, 2), (2, 0)]);
+    }
+
+    #[test]
+    fn test_circular_and_hub_patterns() {
+        let ring: Vec<_> = (0..4).map(|i| PaymentPattern::Circular.parties(3, i)).collect();
+        assert_eq!(ring, vec![(0, 1), (1, 2), (2, 0), (0, 1)]);
+
+        let hub: Vec<_> = (0..6).map(|i| PaymentPattern::HubAndSpoke.parties(3, i)).collect();
+        assert_eq!(hub, vec![(1, 0), (0, 1), (2, 0), (0, 2), (1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_mix_cycles_by_weight() {
+        let mix = parse_mix("bilateral=2, hub").unwrap();
+        assert_eq!(mix, vec![(PaymentPattern::Bilateral, 2), (PaymentPattern::HubAndSpoke, 1)]);
+
+        let config = ScenarioConfig::default().with_mix(mix);
+        let patterns: Vec<_> = (0..4).map(|i| config.pattern_for(i)).collect();
+        assert_eq!(
+            patterns,
+            vec![
+                PaymentPattern::Bilateral,
+                PaymentPattern::Bilateral,
+                PaymentPattern::HubAndSpoke,
+                PaymentPattern::Bilateral
+            ]
+        );
+
+        assert!(parse_mix("star=1").is_err());
+        assert!(parse_mix("circular=0").is_err());
+    }
+
+    #[test]
+    fn test_amounts_stay_in_range() {
+        let config = ScenarioConfig::default().with_amounts(Decimal::new(100, 2), Decimal::new(250, 2));
+        for sequence in 0..1000 {
+            let amount = config.amount_for(sequence);
+            assert!(amount >= Decimal::new(100, 2) && amount <= Decimal::new(250, 2), "{}", amount);
+        }
+        assert_eq!(config.amount_for(0), Decimal::new(100, 2));
+    }
+}
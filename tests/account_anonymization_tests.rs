@@ -0,0 +1,116 @@
+mod common;
+
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{
+    AccountStatus, AccountType, BankAccountType, SettlementProfile, StatusChangeReason, StatusReasonCode,
+};
+use settlement_engine::services::{account_service::CreateAccountRequest, AccountService};
+use uuid::Uuid;
+
+fn account_request(name: &str) -> CreateAccountRequest {
+    CreateAccountRequest {
+        external_id: format!("EXT-{}", Uuid::new_v4()),
+        name: name.to_string(),
+        account_type: AccountType::Liability,
+        currency: "USD".to_string(),
+        initial_balance: Some(dec!(0)),
+        metadata: Some(serde_json::json!({"email": "jane@example.com"})),
+    }
+}
+
+#[tokio::test]
+async fn test_anonymize_closed_account() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let service = AccountService::new(pool.clone());
+    let account = service.create_account(account_request("Jane Doe")).await.unwrap();
+    service
+        .set_settlement_profile(SettlementProfile::new(
+            account.id,
+            "Jane Doe",
+            "021000021",
+            "123456789",
+            BankAccountType::Checking,
+        ))
+        .await
+        .unwrap();
+
+    // Open accounts cannot be anonymized, and the audit fields are required
+    let result = service.anonymize(account.id, "ops@example.com", "Customer erasure request").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    service
+        .close_account(
+            account.id,
+            StatusChangeReason::new(StatusReasonCode::CustomerRequest).with_note("Jane asked to leave"),
+        )
+        .await
+        .unwrap();
+    let result = service.anonymize(account.id, " ", "Customer erasure request").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let anonymized = service
+        .anonymize(account.id, "ops@example.com", "Customer erasure request")
+        .await
+        .unwrap();
+    assert!(anonymized.is_anonymized());
+    assert_eq!(anonymized.status, AccountStatus::Closed);
+    assert_ne!(anonymized.name, "Jane Doe");
+    assert_ne!(anonymized.external_id, account.external_id);
+    assert!(anonymized.metadata.is_none());
+
+    // Personal data is gone everywhere, but balances stay addressable by ID
+    assert!(service.find_by_external_id(&account.external_id).await.is_err());
+    assert!(matches!(
+        service.get_settlement_profile(account.id, "USD").await,
+        Err(AppError::NotFound(_))
+    ));
+    let history = service.get_status_history(account.id).await.unwrap();
+    assert!(history.iter().all(|change| change.note.is_none()));
+    assert_eq!(service.get_balance(account.id, "USD").await.unwrap().available_balance, dec!(0));
+
+    let audit = service.get_anonymization(account.id).await.unwrap();
+    assert_eq!(audit.requested_by, "ops@example.com");
+    assert!(audit.scrubbed_fields.contains(&"name".to_string()));
+
+    // Anonymization is one-way
+    let result = service.anonymize(account.id, "ops@example.com", "again").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let result = service
+        .update_metadata(account.id, serde_json::json!({"email": "jane@example.com"}))
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_retention_policy_anonymizes_old_closed_accounts() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let service = AccountService::new(pool.clone());
+    let closed = service.create_account(account_request("Closed Customer")).await.unwrap();
+    let open = service.create_account(account_request("Open Customer")).await.unwrap();
+    service
+        .close_account(closed.id, StatusChangeReason::new(StatusReasonCode::CustomerRequest))
+        .await
+        .unwrap();
+
+    // Not yet past retention
+    let anonymized = service
+        .anonymize_closed_before(chrono::Duration::days(30), 100)
+        .await
+        .unwrap();
+    assert!(anonymized.is_empty());
+
+    // A zero retention period makes every closed account eligible
+    let anonymized = service
+        .anonymize_closed_before(chrono::Duration::zero(), 100)
+        .await
+        .unwrap();
+    assert!(anonymized.contains(&closed.id));
+    assert!(!anonymized.contains(&open.id));
+    assert_eq!(service.get_anonymization(closed.id).await.unwrap().requested_by, "retention-policy");
+    assert!(!service.find_by_id(open.id).await.unwrap().is_anonymized());
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::fixtures;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{AccountIdentifierType, AccountType, StatusChangeReason, StatusReasonCode};
+use settlement_engine::services::AccountService;
+
+#[tokio::test]
+async fn test_lookup_account_by_secondary_identifier() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let service = AccountService::new(pool.clone());
+    let account = fixtures::account("EUR")
+        .with_name("Acme GmbH")
+        .with_type(AccountType::Liability)
+        .with_balance(dec!(0))
+        .create(&pool)
+        .await;
+    let other = fixtures::account("EUR")
+        .with_name("Globex")
+        .with_type(AccountType::Liability)
+        .with_balance(dec!(0))
+        .create(&pool)
+        .await;
+
+    let iban = service
+        .add_identifier(account.id, AccountIdentifierType::Iban, "gb82 west 1234 5698 7654 32")
+        .await
+        .unwrap();
+    assert_eq!(iban.identifier, "GB82WEST12345698765432");
+    service
+        .add_identifier(account.id, AccountIdentifierType::Alias, "acme-ops")
+        .await
+        .unwrap();
+
+    // Spacing and case do not matter on lookup
+    assert_eq!(service.lookup("GB82 WEST 1234 5698 7654 32").await.unwrap().id, account.id);
+    assert_eq!(service.lookup("Acme-Ops").await.unwrap().id, account.id);
+    assert!(matches!(service.lookup("UNKNOWN").await, Err(AppError::NotFound(_))));
+
+    // An identifier names at most one account, and IBANs must pass their check digits
+    let taken = service.add_identifier(other.id, AccountIdentifierType::Alias, "ACME-OPS").await;
+    assert!(matches!(taken, Err(AppError::Validation(_))));
+    let invalid = service
+        .add_identifier(other.id, AccountIdentifierType::Iban, "GB00WEST12345698765432")
+        .await;
+    assert!(matches!(invalid, Err(AppError::Validation(_))));
+
+    assert_eq!(service.list_identifiers(account.id).await.unwrap().len(), 2);
+    service.remove_identifier(account.id, iban.id).await.unwrap();
+    assert!(matches!(
+        service.lookup("GB82WEST12345698765432").await,
+        Err(AppError::NotFound(_))
+    ));
+    assert!(matches!(
+        service.remove_identifier(other.id, iban.id).await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_anonymize_removes_identifiers() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let service = AccountService::new(pool.clone());
+    let account = fixtures::account("EUR")
+        .with_name("Jane Doe")
+        .with_type(AccountType::Liability)
+        .with_balance(dec!(0))
+        .create(&pool)
+        .await;
+    service
+        .add_identifier(account.id, AccountIdentifierType::InternalCode, "CC-104")
+        .await
+        .unwrap();
+
+    service
+        .close_account(account.id, StatusChangeReason::new(StatusReasonCode::CustomerRequest))
+        .await
+        .unwrap();
+    let anonymized = service
+        .anonymize(account.id, "ops@example.com", "Customer erasure request")
+        .await
+        .unwrap();
+    assert!(anonymized.is_anonymized());
+
+    assert!(service.list_identifiers(account.id).await.unwrap().is_empty());
+    assert!(matches!(service.lookup("CC-104").await, Err(AppError::NotFound(_))));
+    let anonymization = service.get_anonymization(account.id).await.unwrap();
+    assert!(anonymization.scrubbed_fields.contains(&"identifiers".to_string()));
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use chrono::{Duration, Months, NaiveDate, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{BalanceBasis, PeriodStatus};
+use settlement_engine::services::{
+    AccountingPeriodService, BalanceService, LedgerService, LedgerTransactionRequest,
+};
+use uuid::Uuid;
+
+/// The 15th of a month in the 1990s no other test closes.
+fn unique_past_day() -> NaiveDate {
+    let offset = (Uuid::new_v4().as_u128() % 120) as u32;
+    NaiveDate::from_ymd_opt(1990, 1, 15).unwrap() + Months::new(offset)
+}
+
+#[tokio::test]
+async fn test_backdating_respects_closed_periods() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let period_service = AccountingPeriodService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+
+    let (payer, payee) = fixtures::payer_and_payee(&pool, &currency, dec!(1000)).await;
+
+    let day = unique_past_day();
+    period_service.reopen(day).await.expect("Failed to reopen period");
+    let payment = |idempotency_key: String, date: NaiveDate| {
+        LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            payer,
+            payee,
+            dec!(10),
+            &currency,
+            idempotency_key,
+        )
+        .with_effective_date(date)
+    };
+
+    let settled = ledger_service
+        .process_payment(payment(Uuid::new_v4().to_string(), day))
+        .await
+        .expect("Backdating into an open period should succeed");
+    assert!(settled.entries.iter().all(|entry| entry.effective_date == day));
+
+    let period = period_service.close(day).await.expect("Failed to close period");
+    assert_eq!(period.status, PeriodStatus::Closed);
+    assert!(period.contains(day));
+    assert!(period.closed_at.is_some());
+
+    let rejected = ledger_service
+        .process_payment(payment(Uuid::new_v4().to_string(), day + Duration::days(1)))
+        .await;
+    assert!(matches!(rejected, Err(AppError::Validation(ref message)) if message.contains("closed")));
+
+    let future = ledger_service
+        .process_payment(payment(Uuid::new_v4().to_string(), Utc::now().date_naive() + Duration::days(1)))
+        .await;
+    assert!(matches!(future, Err(AppError::Validation(_))));
+
+    // The current period cannot be closed
+    let current = period_service.close(Utc::now().date_naive()).await;
+    assert!(matches!(current, Err(AppError::Validation(_))));
+
+    let reopened = period_service.reopen(day).await.expect("Failed to reopen period");
+    assert_eq!(reopened.status, PeriodStatus::Open);
+    assert!(reopened.closed_at.is_none());
+    ledger_service
+        .process_payment(payment(Uuid::new_v4().to_string(), day + Duration::days(1)))
+        .await
+        .expect("Backdating into a reopened period should succeed");
+}
+
+#[tokio::test]
+async fn test_balances_as_of_value_and_booking_date() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let ledger_service = LedgerService::new(pool.clone());
+    let balance_service = BalanceService::new(pool.clone());
+
+    let (payer, payee) = fixtures::payer_and_payee(&pool, &currency, dec!(500)).await;
+
+    // Posted today, valued three days ago
+    let today = Utc::now().date_naive();
+    let value_date = today - Duration::days(3);
+    ledger_service
+        .process_payment(
+            LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                payer,
+                payee,
+                dec!(120),
+                &currency,
+                Uuid::new_v4().to_string(),
+            )
+            .with_effective_date(value_date),
+        )
+        .await
+        .expect("Failed to process backdated payment");
+
+    let by_value = balance_service
+        .balance_as_of(payee, &currency, today - Duration::days(2), BalanceBasis::Value)
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(by_value.balance, dec!(620));
+
+    let by_booking = balance_service
+        .balance_as_of(payee, &currency, today - Duration::days(2), BalanceBasis::Booking)
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(by_booking.balance, dec!(500));
+
+    let history = balance_service
+        .balance_history(payer, &currency, today - Duration::days(4), today, BalanceBasis::Value)
+        .await
+        .expect("Failed to get balance history");
+    let balances: Vec<_> = history.iter().map(|balance| balance.balance).collect();
+    assert_eq!(balances, vec![dec!(500), dec!(380), dec!(380), dec!(380), dec!(380)]);
+
+    let future = balance_service
+        .balance_as_of(payer, &currency, today + Duration::days(1), BalanceBasis::Value)
+        .await;
+    assert!(matches!(future, Err(AppError::Validation(_))));
+}
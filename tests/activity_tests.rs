@@ -0,0 +1,101 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{ActivityGranularity, TransactionType};
+use settlement_engine::services::{ActivityService, LedgerService, LedgerTransactionRequest};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_activity_projected_from_settled_transactions() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let ledger_service = LedgerService::new(pool.clone());
+    let activity_service = ActivityService::new(pool.clone()).with_batch_size(50);
+
+    let mut accounts = Vec::new();
+    for prefix in ["PAYER", "MERCHANT", "SUPPLIER"] {
+        let account = fixtures::account(&currency)
+            .with_name(prefix)
+            .with_balance(dec!(1000))
+            .create(&pool)
+            .await;
+        accounts.push(account);
+    }
+    let (payer, merchant, supplier) = (&accounts[0], &accounts[1], &accounts[2]);
+
+    let requests = vec![
+        LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            payer.id,
+            merchant.id,
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+        .with_fee(dec!(2)),
+        LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            payer.id,
+            merchant.id,
+            dec!(50),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ),
+        LedgerTransactionRequest::transfer(
+            format!("TRF-{}", Uuid::new_v4()),
+            merchant.id,
+            supplier.id,
+            dec!(30),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ),
+    ];
+    for request in requests {
+        ledger_service.process_transaction(request).await.expect("Failed to settle transaction");
+    }
+
+    while activity_service.project_pending().await.expect("Failed to project activity") > 0 {}
+
+    let payer_activity = activity_service
+        .activity(payer.id, ActivityGranularity::Day, None, None, None)
+        .await
+        .expect("Failed to get activity");
+    assert_eq!(payer_activity.len(), 1);
+    assert_eq!(payer_activity[0].transaction_count, 2);
+    assert_eq!(payer_activity[0].total_out, dec!(150));
+    assert_eq!(payer_activity[0].total_fees, dec!(2));
+    assert_eq!(payer_activity[0].total_in, dec!(0));
+
+    let merchant_activity = activity_service
+        .activity(merchant.id, ActivityGranularity::Month, Some(&currency), None, None)
+        .await
+        .expect("Failed to get activity");
+    assert_eq!(merchant_activity.len(), 1);
+    let period = &merchant_activity[0];
+    assert_eq!(period.transaction_count, 3);
+    assert_eq!(period.total_in, dec!(148));
+    assert_eq!(period.total_out, dec!(30));
+    let transfers = period
+        .by_type
+        .iter()
+        .find(|summary| summary.transaction_type == TransactionType::Transfer)
+        .expect("Transfers should be summarised");
+    assert_eq!(transfers.transaction_count, 1);
+    assert_eq!(transfers.total_out, dec!(30));
+
+    // Events are applied once, however often the projector runs
+    activity_service.project_pending().await.expect("Failed to project activity");
+    let again = activity_service
+        .activity(merchant.id, ActivityGranularity::Day, None, None, None)
+        .await
+        .expect("Failed to get activity");
+    assert_eq!(again[0].transaction_count, 3);
+
+    let missing = activity_service
+        .activity(Uuid::new_v4(), ActivityGranularity::Day, None, None, None)
+        .await;
+    assert!(matches!(missing, Err(AppError::NotFound(_))));
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{AmendmentTarget, RiskHoldStatus};
+use settlement_engine::services::{
+    AmendmentService, LedgerService, LedgerTransactionRequest, RiskConfig, RiskService, SubmissionService,
+    TransactionChanges,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn payment(from: Uuid, to: Uuid, amount: rust_decimal::Decimal, currency: &str) -> LedgerTransactionRequest {
+    LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        from,
+        to,
+        amount,
+        currency,
+        format!("IDEM-{}", Uuid::new_v4()),
+    )
+}
+
+fn amount(value: rust_decimal::Decimal) -> TransactionChanges {
+    TransactionChanges {
+        amount: Some(value),
+        ..TransactionChanges::default()
+    }
+}
+
+#[tokio::test]
+async fn test_queued_submission_amended_with_history() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let ledger = Arc::new(LedgerService::new(pool.clone()));
+    let submissions = SubmissionService::new(pool.clone(), ledger.clone());
+    let amendments = AmendmentService::new(pool.clone(), ledger.clone());
+
+    let submission = submissions
+        .submit(payment(a, b, dec!(100), &currency))
+        .await
+        .expect("Failed to submit");
+
+    let amendment = amendments
+        .amend(submission.id, &amount(dec!(150)), "ops-user", Some("Customer corrected amount".to_string()))
+        .await
+        .expect("Failed to amend submission");
+    assert_eq!(amendment.target, AmendmentTarget::Submission);
+    assert_eq!(amendment.amended_by, "ops-user");
+
+    let changes = TransactionChanges {
+        metadata: Some(serde_json::json!({"invoice": "INV-1"})),
+        ..TransactionChanges::default()
+    };
+    amendments
+        .amend(submission.id, &changes, "ops-user", None)
+        .await
+        .expect("Failed to amend metadata");
+
+    // The amended request still has to pass validation
+    let result = amendments.amend(submission.id, &amount(dec!(-5)), "ops-user", None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    // Unchanged values are not an amendment
+    let result = amendments.amend(submission.id, &amount(dec!(150)), "ops-user", None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let stored = submissions.get_submission(submission.id).await.expect("Failed to get submission");
+    let request: LedgerTransactionRequest = serde_json::from_value(stored.request).unwrap();
+    assert_eq!(request.amount, dec!(150));
+    assert_eq!(request.metadata, Some(serde_json::json!({"invoice": "INV-1"})));
+
+    let history = amendments.history(submission.id).await.expect("Failed to get history");
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].id, amendment.id);
+    assert_eq!(history[0].reason.as_deref(), Some("Customer corrected amount"));
+    assert_eq!(history[0].changes[0]["field"], "amount");
+    assert_eq!(history[0].changes[0]["from"], serde_json::to_value(dec!(100)).unwrap());
+    assert_eq!(history[1].changes[0]["field"], "metadata");
+}
+
+#[tokio::test]
+async fn test_held_transaction_amended_before_release() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let risk = Arc::new(RiskService::new(pool.clone()).with_config(RiskConfig {
+        default_amount_threshold: Some(dec!(100)),
+        ..RiskConfig::default()
+    }));
+    let ledger = Arc::new(LedgerService::new(pool.clone()).with_risk(risk.clone()));
+    let amendments = AmendmentService::new(pool.clone(), ledger.clone());
+
+    let request = payment(a, b, dec!(500), &currency);
+    let result = ledger.process_transaction(request.clone()).await;
+    assert!(matches!(result, Err(AppError::HeldForReview(_))));
+    let hold = risk
+        .list_holds(Some(RiskHoldStatus::Held), 100, 0)
+        .await
+        .expect("Failed to list holds")
+        .into_iter()
+        .find(|h| h.idempotency_key == request.idempotency_key)
+        .expect("Hold not listed");
+
+    let amendment = amendments
+        .amend(hold.id, &amount(dec!(400)), "ops-user", None)
+        .await
+        .expect("Failed to amend hold");
+    assert_eq!(amendment.target, AmendmentTarget::RiskHold);
+    assert_eq!(risk.get_hold(hold.id).await.expect("Failed to get hold").amount, dec!(400));
+
+    let released = ledger
+        .release_held(hold.id, "reviewer", "Amended and approved")
+        .await
+        .expect("Failed to release hold");
+    assert_eq!(released.transaction.amount, dec!(400));
+
+    // Once released the hold is closed to amendments, and so is the posted transaction
+    let result = amendments.amend(hold.id, &amount(dec!(300)), "ops-user", None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let result = amendments.amend(released.transaction.id, &amount(dec!(300)), "ops-user", None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let result = amendments.amend(Uuid::new_v4(), &amount(dec!(300)), "ops-user", None).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
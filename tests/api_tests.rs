@@ -1,17 +1,14 @@
 mod common;
 
+use common::fixtures::unique_currency;
 use settlement_engine::api::requests::{CreateAccountRequest, CreateTransactionRequest};
 use settlement_engine::api::responses::{ApiResponse, AccountResponse, TransactionResponse, BatchResponse, PaginatedResponse};
+use settlement_engine::api::validation::RequestBody;
 use settlement_engine::models::{AccountType, TransactionType};
 use settlement_engine::services::{AccountService, LedgerService, BatchService, LedgerTransactionRequest};
 use rust_decimal_macros::dec;
 use uuid::Uuid;
 
-fn unique_currency() -> String {
-    let id = Uuid::new_v4().to_string();
-    format!("T{}", &id[0..2].to_uppercase())
-}
-
 #[tokio::test]
 async fn test_api_response_success_serialization() {
     let response: ApiResponse<String> = ApiResponse::success("test data".to_string());
@@ -182,6 +179,14 @@ async fn test_create_transaction_request_validation_success() {
         fee_amount: None,
         idempotency_key: "IDEM001".to_string(),
         metadata: None,
+        priority: None,
+        effective_date: None,
+        source_system: None,
+        type_code: None,
+        exclude_from_netting: false,
+        duplicate_override: None,
+        narrative: None,
+        reference: None,
     };
     assert!(request.validate().is_ok());
 }
@@ -198,6 +203,14 @@ async fn test_create_transaction_request_validation_zero_amount() {
         fee_amount: None,
         idempotency_key: "IDEM001".to_string(),
         metadata: None,
+        priority: None,
+        effective_date: None,
+        source_system: None,
+        type_code: None,
+        exclude_from_netting: false,
+        duplicate_override: None,
+        narrative: None,
+        reference: None,
     };
     let result = request.validate();
     assert!(result.is_err());
@@ -325,7 +338,7 @@ async fn test_batch_service_list_batches() {
     batch_service.get_or_create_current_batch(&currency).await.unwrap();
 
     let batches = batch_service
-        .list_batches(None, Some(&currency), 10, 0)
+        .list_batches(None, Some(&currency), None, 10, 0)
         .await
         .unwrap();
 
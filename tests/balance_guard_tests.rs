@@ -0,0 +1,99 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{AccountStatus, AlertRuleType, BalanceIncidentStatus, StatusReasonCode};
+use settlement_engine::notifications::NotificationEngine;
+use settlement_engine::services::{
+    AccountService, AlertService, BalanceGuardService, CreateAlertRuleRequest,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Writes a balance directly, the way a bug or manual fix would.
+async fn set_available(pool: &PgPool, account_id: Uuid, available: Decimal) {
+    sqlx::query("UPDATE account_balances SET available_balance = $2 WHERE account_id = $1")
+        .bind(account_id)
+        .bind(available)
+        .execute(pool)
+        .await
+        .expect("Failed to write balance");
+}
+
+#[tokio::test]
+async fn test_negative_balance_freezes_account_and_alerts() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let account_id = fixtures::account(&currency).with_balance(dec!(100)).create(&pool).await.id;
+    let alert_service = AlertService::new(pool.clone());
+    let rule = alert_service
+        .create_rule(CreateAlertRuleRequest {
+            account_id: Some(account_id),
+            rule_type: AlertRuleType::NegativeBalance,
+            currency: currency.clone(),
+            threshold: None,
+            webhook_url: None,
+            suppression_window_seconds: None,
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create alert rule");
+    let guard = BalanceGuardService::new(pool.clone())
+        .with_notifications(Arc::new(NotificationEngine::new(pool.clone())));
+
+    set_available(&pool, account_id, dec!(-30)).await;
+    set_available(&pool, account_id, dec!(-45)).await;
+
+    let open = guard
+        .list(Some(BalanceIncidentStatus::Open), Some(account_id), 50, 0)
+        .await
+        .expect("Failed to list incidents");
+    assert_eq!(open.len(), 1, "Repeated breaches refresh one incident");
+    assert_eq!(open[0].available_balance, dec!(-45));
+    assert_eq!(open[0].shortfall(), dec!(45));
+
+    let frozen = guard.enforce().await.expect("Failed to enforce");
+    let incident = frozen.iter().find(|incident| incident.account_id == account_id).expect("Incident should be acted on");
+    assert_eq!(incident.status, BalanceIncidentStatus::Frozen);
+    assert!(incident.frozen_at.is_some());
+
+    let account_service = AccountService::new(pool.clone());
+    assert_eq!(account_service.find_by_id(account_id).await.unwrap().status, AccountStatus::Frozen);
+    let history = account_service.get_status_history(account_id).await.unwrap();
+    assert_eq!(history.last().unwrap().reason_code, StatusReasonCode::BalanceFloorBreach);
+    assert!(alert_service.get_rule(rule.id).await.unwrap().last_triggered_at.is_some());
+
+    let premature = guard.resolve(incident.id, "fixed").await;
+    assert!(matches!(premature, Err(AppError::Validation(_))));
+
+    set_available(&pool, account_id, dec!(100)).await;
+    let resolved = guard.resolve(incident.id, "Restored from ledger").await.expect("Failed to resolve");
+    assert_eq!(resolved.status, BalanceIncidentStatus::Resolved);
+    assert_eq!(resolved.resolution_note.as_deref(), Some("Restored from ledger"));
+    // Resolving does not unfreeze the account
+    assert_eq!(account_service.find_by_id(account_id).await.unwrap().status, AccountStatus::Frozen);
+}
+
+#[tokio::test]
+async fn test_balance_floor_allows_overdraft_down_to_it() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let account_id = fixtures::account(&currency).with_balance(dec!(100)).create(&pool).await.id;
+    let guard = BalanceGuardService::new(pool.clone());
+
+    guard.set_floor(account_id, &currency, dec!(-50)).await.expect("Failed to set floor");
+    set_available(&pool, account_id, dec!(-40)).await;
+    assert_eq!(guard.count(None, Some(account_id)).await.unwrap(), 0);
+
+    set_available(&pool, account_id, dec!(-60)).await;
+    let incidents = guard.list(None, Some(account_id), 50, 0).await.unwrap();
+    assert_eq!(incidents.len(), 1);
+    assert_eq!(incidents[0].floor, dec!(-50));
+    assert_eq!(incidents[0].shortfall(), dec!(10));
+
+    let missing = guard.set_floor(Uuid::new_v4(), &currency, dec!(0)).await;
+    assert!(matches!(missing, Err(AppError::NotFound(_))));
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::repositories::{BalanceRepository, LedgerRepository};
+use settlement_engine::services::{BalanceProjectionService, LedgerService, LedgerTransactionRequest};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn payment(from: Uuid, to: Uuid, amount: rust_decimal::Decimal, currency: &str) -> LedgerTransactionRequest {
+    LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        from,
+        to,
+        amount,
+        currency,
+        format!("IDEM-{}", Uuid::new_v4()),
+    )
+}
+
+async fn catch_up(service: &BalanceProjectionService, account_id: Uuid, currency: &str) {
+    while service
+        .project_account(account_id, currency)
+        .await
+        .expect("Failed to project account")
+        > 0
+    {}
+}
+
+#[tokio::test]
+async fn test_projection_folds_ledger_entries() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(0)).create(&pool).await.id;
+    let ledger = LedgerService::new(pool.clone());
+    let service = BalanceProjectionService::new(pool.clone()).with_batch_size(2);
+
+    for request in [
+        payment(a, b, dec!(100), &currency),
+        payment(a, b, dec!(50), &currency),
+        payment(b, a, dec!(30), &currency),
+    ] {
+        ledger.process_payment(request).await.expect("Failed to process payment");
+    }
+
+    let result = service.get(a, &currency).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+    let lag = service.lag(Some(a), Some(&currency)).await.expect("Failed to get lag");
+    assert_eq!(lag.accounts_behind, 1);
+    assert_eq!(lag.entries_behind, 3);
+    assert!(lag.oldest_unprojected_at.is_some());
+
+    // Batches of two: the first pass stops part way
+    assert_eq!(service.project_account(a, &currency).await.unwrap(), 2);
+    assert_eq!(service.get(a, &currency).await.unwrap().last_sequence, 2);
+    catch_up(&service, a, &currency).await;
+    catch_up(&service, b, &currency).await;
+
+    let projected = service.get(a, &currency).await.expect("Failed to get projection");
+    assert_eq!(projected.balance, dec!(880));
+    assert_eq!(projected.last_sequence, 3);
+    assert_eq!(service.get(b, &currency).await.unwrap().balance, dec!(120));
+    let lag = service.lag(Some(a), None).await.expect("Failed to get lag");
+    assert_eq!(lag.entries_behind, 0);
+    assert_eq!(lag.oldest_unprojected_at, None);
+
+    // Rebuilding from scratch gives the same balance
+    ledger
+        .process_payment(payment(a, b, dec!(5), &currency))
+        .await
+        .expect("Failed to process payment");
+    assert_eq!(service.lag(Some(a), None).await.unwrap().entries_behind, 1);
+    let folded = service.rebuild(Some(a)).await.expect("Failed to rebuild");
+    assert_eq!(folded, 4);
+    let rebuilt = service.get(a, &currency).await.expect("Failed to get projection");
+    assert_eq!(rebuilt.balance, dec!(875));
+    assert_eq!(rebuilt.last_sequence, 4);
+
+    let stored = BalanceRepository::new(pool.clone())
+        .find_by_account_and_currency(a, &currency)
+        .await
+        .expect("Failed to get balance")
+        .expect("Balance not found");
+    assert_eq!(rebuilt.balance, stored.available_balance + stored.pending_balance + stored.reserved_balance);
+}
+
+#[tokio::test]
+async fn test_hot_account_sequence_has_no_gaps() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let hot = fixtures::account(&currency).with_balance(dec!(0)).create(&pool).await.id;
+    let mut payers = Vec::new();
+    for _ in 0..4 {
+        payers.push(fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id);
+    }
+    let ledger = Arc::new(LedgerService::new(pool.clone()));
+
+    let mut handles = Vec::new();
+    for i in 0..20 {
+        let ledger = ledger.clone();
+        let request = payment(payers[i % payers.len()], hot, dec!(10), &currency);
+        handles.push(tokio::spawn(async move { ledger.process_payment(request).await }));
+    }
+    let mut settled = 0;
+    for handle in handles {
+        if handle.await.expect("Task panicked").is_ok() {
+            settled += 1;
+        }
+    }
+    assert!(settled > 0);
+
+    // Every entry folds in sequence: a gap would stop the projection short
+    let service = BalanceProjectionService::new(pool.clone());
+    catch_up(&service, hot, &currency).await;
+    let projected = service.get(hot, &currency).await.expect("Failed to get projection");
+    let entries = LedgerRepository::new(pool.clone())
+        .count_by_account(hot)
+        .await
+        .expect("Failed to count entries");
+    assert_eq!(projected.last_sequence, entries);
+    assert_eq!(projected.balance, dec!(10) * rust_decimal::Decimal::from(settled));
+}
@@ -0,0 +1,197 @@
+mod common;
+
+use chrono::{Duration, NaiveTime, Utc};
+use common::fixtures::unique_currency;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{CutOffApprovalPolicy, NettingMode};
+use settlement_engine::services::{
+    BatchService, BatchTemplateService, CreateBatchRequest, CreateBatchTemplateRequest, CreateSettlementWindowRequest,
+    CutOffApproval, SettlementWindowService, UpdateBatchTemplateRequest,
+};
+
+fn template_request(name: &str, currency: &str) -> CreateBatchTemplateRequest {
+    CreateBatchTemplateRequest {
+        name: name.to_string(),
+        currency: currency.to_string(),
+        window_id: None,
+        cut_off_time: NaiveTime::from_hms_opt(23, 59, 59),
+        metadata: None,
+        netting_mode: NettingMode::Multilateral,
+        cut_off_approval: CutOffApprovalPolicy::FourEyes,
+    }
+}
+
+fn approval(requested_by: &str, approved_by: &str) -> CutOffApproval {
+    CutOffApproval {
+        requested_by: requested_by.to_string(),
+        approved_by: approved_by.to_string(),
+        reason: "early close for holiday".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_first_transaction_of_the_day_finds_provisioned_batch() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let templates = BatchTemplateService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut request = template_request("end of day", &currency);
+    request.metadata = Some(serde_json::json!({ "desk": "treasury" }));
+    request.netting_mode = NettingMode::Bilateral;
+    let template = templates.create_template(request).await.expect("Failed to create template");
+
+    let today = Utc::now().date_naive();
+    let report = templates.provision(today).await.expect("Failed to provision");
+    let opened = report
+        .opened
+        .iter()
+        .find(|batch| batch.currency == currency)
+        .expect("Template's batch not opened");
+    assert_eq!(opened.settlement_date, today);
+    assert_eq!(opened.cut_off_time, today.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap()).and_utc());
+    assert_eq!(opened.netting_mode, NettingMode::Bilateral);
+    let metadata = opened.metadata.clone().unwrap();
+    assert_eq!(metadata["desk"], "treasury");
+    assert_eq!(metadata["template_id"], serde_json::json!(template.id));
+
+    // The day's first transaction goes to the provisioned batch instead of opening one
+    let current = batch_service.get_or_create_current_batch(&currency).await.expect("Failed to get batch");
+    assert_eq!(current.id, opened.id);
+
+    // Provisioning again leaves the open batch alone
+    let again = templates.provision(today).await.expect("Failed to provision again");
+    assert!(again.opened.iter().all(|batch| batch.currency != currency));
+    assert!(again.existing.contains(&opened.id));
+
+    templates
+        .update_template(template.id, UpdateBatchTemplateRequest { active: Some(false), ..Default::default() })
+        .await
+        .expect("Failed to deactivate template");
+}
+
+#[tokio::test]
+async fn test_provisioning_reuses_batches_and_skips_inactive_windows() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let templates = BatchTemplateService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let window_service = SettlementWindowService::new(pool.clone());
+    let window = window_service
+        .create_window(CreateSettlementWindowRequest {
+            name: "morning".to_string(),
+            currency: currency.clone(),
+            cut_off_time: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            timezone: "Europe/Berlin".to_string(),
+        })
+        .await
+        .expect("Failed to create window");
+
+    let plain = templates
+        .create_template(template_request("plain", &currency))
+        .await
+        .expect("Failed to create template");
+    let mut windowed = template_request("morning", &currency);
+    windowed.window_id = Some(window.id);
+    windowed.cut_off_time = None;
+    let windowed = templates.create_template(windowed).await.expect("Failed to create window template");
+
+    // A batch someone opened by hand for the date is kept
+    let tomorrow = Utc::now().date_naive() + Duration::days(1);
+    let by_hand = batch_service
+        .create_batch(CreateBatchRequest::new(tomorrow, Utc::now() + Duration::hours(30), &currency))
+        .await
+        .expect("Failed to create batch");
+    window_service.deactivate_window(window.id).await.expect("Failed to deactivate window");
+
+    let report = templates.provision(tomorrow).await.expect("Failed to provision");
+    assert!(report.existing.contains(&by_hand.id));
+    assert!(report.opened.iter().all(|batch| batch.currency != currency));
+    assert!(report.skipped.iter().any(|skipped| skipped.template_id == windowed.id));
+
+    for id in [plain.id, windowed.id] {
+        templates
+            .update_template(id, UpdateBatchTemplateRequest { active: Some(false), ..Default::default() })
+            .await
+            .expect("Failed to deactivate template");
+    }
+}
+
+#[tokio::test]
+async fn test_template_approval_policy_applies_to_cut_off_changes() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let templates = BatchTemplateService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut request = template_request("single operator", &currency);
+    request.cut_off_approval = CutOffApprovalPolicy::SingleOperator;
+    let template = templates.create_template(request).await.expect("Failed to create template");
+    let tomorrow = Utc::now().date_naive() + Duration::days(1);
+    let report = templates.provision(tomorrow).await.expect("Failed to provision");
+    let batch = report.opened.iter().find(|batch| batch.currency == currency).expect("Batch not opened");
+    assert_eq!(batch.cut_off_approval, CutOffApprovalPolicy::SingleOperator);
+
+    let closed = batch_service
+        .close_early(batch.id, &approval("alice", "alice"))
+        .await
+        .expect("Single-operator batches can be closed by the requester");
+    assert!(closed.cut_off_time <= Utc::now());
+
+    // Batches opened without a template keep the four-eyes rule
+    let other = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 2))
+        .await
+        .expect("Failed to create batch");
+    assert_eq!(other.cut_off_approval, CutOffApprovalPolicy::FourEyes);
+    let refused = batch_service.close_early(other.id, &approval("alice", "alice")).await;
+    assert!(matches!(refused, Err(AppError::Validation(_))), "got {:?}", refused);
+
+    templates
+        .update_template(template.id, UpdateBatchTemplateRequest { active: Some(false), ..Default::default() })
+        .await
+        .expect("Failed to deactivate template");
+}
+
+#[tokio::test]
+async fn test_batch_template_validation() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let templates = BatchTemplateService::new(pool.clone());
+
+    let mut no_cut_off = template_request("none", &currency);
+    no_cut_off.cut_off_time = None;
+    assert!(matches!(templates.create_template(no_cut_off).await, Err(AppError::Validation(_))));
+    let mut bad_metadata = template_request("bad", &currency);
+    bad_metadata.metadata = Some(serde_json::json!(["not", "an", "object"]));
+    assert!(matches!(templates.create_template(bad_metadata).await, Err(AppError::Validation(_))));
+
+    let first = templates
+        .create_template(template_request("first", &currency))
+        .await
+        .expect("Failed to create template");
+    // Batches outside any window share one open batch, so only one template may be active
+    let second = templates.create_template(template_request("second", &currency)).await;
+    assert!(matches!(second, Err(AppError::Validation(_))), "got {:?}", second);
+
+    let deactivated = templates
+        .update_template(first.id, UpdateBatchTemplateRequest { active: Some(false), ..Default::default() })
+        .await
+        .expect("Failed to deactivate template");
+    assert!(!deactivated.active);
+    let second = templates
+        .create_template(template_request("second", &currency))
+        .await
+        .expect("Slot is free once the first template is inactive");
+    let reactivate = templates
+        .update_template(first.id, UpdateBatchTemplateRequest { active: Some(true), ..Default::default() })
+        .await;
+    assert!(matches!(reactivate, Err(AppError::Validation(_))));
+
+    let listed = templates.list_templates(Some(&currency), true).await.expect("Failed to list templates");
+    assert_eq!(listed.iter().map(|t| t.id).collect::<Vec<_>>(), vec![second.id]);
+    templates
+        .update_template(second.id, UpdateBatchTemplateRequest { active: Some(false), ..Default::default() })
+        .await
+        .expect("Failed to deactivate template");
+}
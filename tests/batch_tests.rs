@@ -1,21 +1,28 @@
 mod common;
 
-use chrono::{Duration, Utc};
+use common::fixtures::unique_currency;
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::{Duration, NaiveTime, Utc};
 use rust_decimal_macros::dec;
-use settlement_engine::models::{AccountType, BatchStatus};
+use settlement_engine::api::handlers;
+use settlement_engine::api::requests::AssignTransactionWindowRequest;
+use settlement_engine::api::validation::ValidJson;
+use settlement_engine::core::locks::DistributedLocks;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{
+    AccountType, BatchCheck, BatchStatus, SettlementRoute, TransactionPriority, TransactionStatus,
+};
+use settlement_engine::repositories::BatchRepository;
 use settlement_engine::services::{
-    AccountService, BatchService, BatchStateMachine, CreateBatchRequest, LedgerService,
-    LedgerTransactionRequest, SettlementWindowConfig, SettlementWindowType,
+    AccountService, AttestationSigner, BalanceService, BatchCaps, BatchService, BatchStateMachine,
+    CreateBatchRequest, CreateSettlementWindowRequest, FinalityService, InstructionStatus, LedgerService, LedgerTransactionRequest, RtgsConfig,
+    RtgsService, SettlementWindowConfig, SettlementWindowService, SettlementWindowType, SimulatedRail,
     account_service::CreateAccountRequest,
 };
+use std::sync::Arc;
 use uuid::Uuid;
 
-fn unique_currency() -> String {
-    // Use more entropy - take 2 chars from different parts of UUID
-    let uuid = Uuid::new_v4().to_string().replace("-", "");
-    format!("{}{}", &uuid[0..1], &uuid[16..18]).to_uppercase()
-}
-
 #[tokio::test]
 async fn test_batch_service_create_batch() {
     let pool = common::setup_test_db().await;
@@ -160,6 +167,188 @@ async fn test_batch_service_assign_transaction() {
     assert_eq!(updated_batch.gross_amount, dec!(100));
 }
 
+#[tokio::test]
+async fn test_batch_assignment_is_idempotent() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(1000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
+    let payment = ledger_service
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source.id,
+            dest.id,
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    // Retries, sequential and concurrent, count the transaction once
+    batch_service
+        .assign_transaction_to_batch(payment.transaction.id, batch.id)
+        .await
+        .expect("Failed to assign transaction");
+    let (first, second) = tokio::join!(
+        batch_service.assign_transaction_to_batch(payment.transaction.id, batch.id),
+        batch_service.assign_transaction_to_batch(payment.transaction.id, batch.id),
+    );
+    assert_eq!(first.expect("Retry failed").settlement_batch_id, Some(batch.id));
+    assert_eq!(second.expect("Retry failed").settlement_batch_id, Some(batch.id));
+
+    let updated_batch = batch_service.get_batch(batch.id).await.expect("Failed to get batch");
+    assert_eq!(updated_batch.total_transactions, 1);
+    assert_eq!(updated_batch.gross_amount, dec!(100));
+
+    // A transaction in one batch cannot be assigned to another
+    let tomorrow = batch_service
+        .create_batch(CreateBatchRequest::new(
+            Utc::now().date_naive() + Duration::days(1),
+            Utc::now() + Duration::hours(48),
+            &currency,
+        ))
+        .await
+        .expect("Failed to create batch");
+    let result = batch_service
+        .assign_transaction_to_batch(payment.transaction.id, tomorrow.id)
+        .await;
+    assert!(matches!(result, Err(AppError::AlreadyAssigned(_))));
+
+    let tomorrow = batch_service.get_batch(tomorrow.id).await.expect("Failed to get batch");
+    assert_eq!(tomorrow.total_transactions, 0);
+}
+
+#[tokio::test]
+async fn test_transaction_reassigned_between_batches() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(1000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
+    let payment = ledger_service
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source.id,
+            dest.id,
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+
+    let today = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+    let tomorrow = batch_service
+        .create_batch(CreateBatchRequest::new(
+            Utc::now().date_naive() + Duration::days(1),
+            Utc::now() + Duration::hours(48),
+            &currency,
+        ))
+        .await
+        .expect("Failed to create batch");
+
+    // Only batched transactions can be moved
+    let result = batch_service
+        .reassign_transaction(payment.transaction.id, tomorrow.id, "Wrong window")
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    batch_service
+        .assign_transaction_to_batch(payment.transaction.id, today.id)
+        .await
+        .expect("Failed to assign transaction");
+    let moved = batch_service
+        .reassign_transaction(payment.transaction.id, tomorrow.id, "Wrong window")
+        .await
+        .expect("Failed to reassign transaction");
+    assert_eq!(moved.settlement_batch_id, Some(tomorrow.id));
+
+    let today = batch_service.get_batch(today.id).await.expect("Failed to get batch");
+    assert_eq!(today.total_transactions, 0);
+    assert_eq!(today.gross_amount, dec!(0));
+    let tomorrow = batch_service.get_batch(tomorrow.id).await.expect("Failed to get batch");
+    assert_eq!(tomorrow.total_transactions, 1);
+    assert_eq!(tomorrow.gross_amount, dec!(100));
+
+    // Moving it again to where it is changes nothing
+    batch_service
+        .reassign_transaction(payment.transaction.id, tomorrow.id, "Wrong window")
+        .await
+        .expect("Failed to repeat reassignment");
+    let tomorrow = batch_service.get_batch(tomorrow.id).await.expect("Failed to get batch");
+    assert_eq!(tomorrow.total_transactions, 1);
+
+    // A closed batch no longer takes transactions
+    batch_service
+        .close_batch(today.id)
+        .await
+        .expect("Failed to close batch");
+    let result = batch_service
+        .reassign_transaction(payment.transaction.id, today.id, "Back again")
+        .await;
+    assert!(matches!(result, Err(AppError::BatchClosed(_))));
+}
+
 #[tokio::test]
 async fn test_batch_service_recalculate_totals() {
     let pool = common::setup_test_db().await;
@@ -236,22 +425,24 @@ async fn test_batch_service_recalculate_totals() {
 }
 
 #[tokio::test]
-async fn test_batch_service_close_and_process() {
+async fn test_batch_service_rolls_over_when_caps_exceeded() {
     let pool = common::setup_test_db().await;
     let currency = unique_currency();
 
     let account_service = AccountService::new(pool.clone());
     let ledger_service = LedgerService::new(pool.clone());
-    let batch_service = BatchService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone()).with_caps(BatchCaps {
+        max_transactions: Some(2),
+        max_gross_amount: None,
+    });
 
-    // Create accounts
     let source = account_service
         .create_account(CreateAccountRequest {
             external_id: format!("SRC-{}", Uuid::new_v4()),
             name: "Source".to_string(),
             account_type: AccountType::Asset,
             currency: currency.clone(),
-            initial_balance: Some(dec!(1000)),
+            initial_balance: Some(dec!(5000)),
             metadata: None,
         })
         .await
@@ -269,70 +460,122 @@ async fn test_batch_service_close_and_process() {
         .await
         .expect("Failed to create destination");
 
-    // Create batch and add transaction
     let batch = batch_service
         .get_or_create_current_batch(&currency)
         .await
         .expect("Failed to create batch");
+    assert_eq!(batch.sequence_number, 1);
 
-    let tx_request = LedgerTransactionRequest::payment(
-        format!("PAY-{}", Uuid::new_v4()),
-        source.id,
-        dest.id,
-        dec!(100),
-        &currency,
-        format!("IDEM-{}", Uuid::new_v4()),
-    );
+    // Always target the first batch; the third and fourth transactions must spill over
+    let mut landed = Vec::new();
+    for i in 0..4 {
+        let tx_result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}-{}", i, Uuid::new_v4()),
+                source.id,
+                dest.id,
+                dec!(100),
+                &currency,
+                format!("IDEM-{}-{}", i, Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
 
-    let tx_result = ledger_service
-        .process_payment(tx_request)
-        .await
-        .expect("Failed to process payment");
+        let assigned = batch_service
+            .assign_transaction_to_batch(tx_result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+        landed.push(assigned.settlement_batch_id.expect("Transaction not assigned"));
+    }
 
-    batch_service
-        .assign_transaction_to_batch(tx_result.transaction.id, batch.id)
-        .await
-        .expect("Failed to assign transaction");
+    assert_eq!(landed[0], batch.id);
+    assert_eq!(landed[1], batch.id);
+    assert_ne!(landed[2], batch.id);
+    assert_eq!(landed[3], landed[2]);
+
+    let first = batch_service.get_batch(batch.id).await.expect("Failed to get batch");
+    let second = batch_service.get_batch(landed[2]).await.expect("Failed to get batch");
+    assert_eq!(first.total_transactions, 2);
+    assert_eq!(second.total_transactions, 2);
+    assert_eq!(second.sequence_number, 2);
+    assert_eq!(second.settlement_date, first.settlement_date);
+}
 
-    // Trigger batch processing
-    let result = batch_service
-        .trigger_batch_processing(batch.id)
+#[tokio::test]
+async fn test_window_assignment_rolls_over_under_configured_caps() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let state = common::app_state(&pool).with_batch_caps(BatchCaps {
+        max_transactions: Some(1),
+        max_gross_amount: None,
+    });
+
+    let window = SettlementWindowService::new(pool.clone())
+        .create_window(CreateSettlementWindowRequest {
+            name: "capped".to_string(),
+            currency: currency.clone(),
+            cut_off_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            timezone: "UTC".to_string(),
+        })
         .await
-        .expect("Failed to process batch");
+        .expect("Failed to create window");
+    let source = common::fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let dest = common::fixtures::account(&currency).with_balance(dec!(0)).create(&pool).await;
+    let ledger_service = LedgerService::new(pool.clone());
 
-    assert_eq!(result.status, BatchStatus::Completed);
-    assert_eq!(result.total_transactions, 1);
-    assert_eq!(result.successful_transactions, 1);
-    assert_eq!(result.failed_transactions, 0);
-    assert!(result.errors.is_empty());
+    let mut landed = Vec::new();
+    for i in 0..2 {
+        let payment = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}-{}", i, Uuid::new_v4()),
+                source.id,
+                dest.id,
+                dec!(100),
+                &currency,
+                format!("IDEM-{}-{}", i, Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
 
-    // Verify batch status updated
-    let final_batch = batch_service
-        .get_batch(batch.id)
+        let Json(response) = handlers::assign_transaction_window(
+            State(state.clone()),
+            Path(payment.transaction.id),
+            ValidJson(AssignTransactionWindowRequest { window_id: window.id }),
+        )
         .await
-        .expect("Failed to get batch");
+        .expect("Failed to assign transaction to window");
+        landed.push(response.data.unwrap().settlement_batch_id.expect("Transaction not assigned"));
+    }
 
-    assert_eq!(final_batch.status, BatchStatus::Completed);
-    assert!(final_batch.completed_at.is_some());
+    assert_ne!(landed[0], landed[1]);
+    let batch_service = BatchService::new(pool.clone());
+    let first = batch_service.get_batch(landed[0]).await.expect("Failed to get batch");
+    let second = batch_service.get_batch(landed[1]).await.expect("Failed to get batch");
+    assert_eq!((first.total_transactions, second.total_transactions), (1, 1));
+    assert_eq!(second.window_id, Some(window.id));
+    assert_eq!(second.sequence_number, first.sequence_number + 1);
 }
 
 #[tokio::test]
-async fn test_batch_service_notifications() {
+async fn test_large_value_transactions_bypass_batching() {
     let pool = common::setup_test_db().await;
     let currency = unique_currency();
 
     let account_service = AccountService::new(pool.clone());
-    let ledger_service = LedgerService::new(pool.clone());
     let batch_service = BatchService::new(pool.clone());
+    let rtgs_service = Arc::new(RtgsService::new(pool.clone()).with_config(RtgsConfig {
+        default_threshold: None,
+        currency_thresholds: [(currency.clone(), dec!(1000))].into_iter().collect(),
+    }));
+    let ledger_service = LedgerService::new(pool.clone()).with_rtgs(rtgs_service.clone());
 
-    // Create accounts and transaction
     let source = account_service
         .create_account(CreateAccountRequest {
             external_id: format!("SRC-{}", Uuid::new_v4()),
             name: "Source".to_string(),
             account_type: AccountType::Asset,
             currency: currency.clone(),
-            initial_balance: Some(dec!(1000)),
+            initial_balance: Some(dec!(10000)),
             metadata: None,
         })
         .await
@@ -350,57 +593,525 @@ async fn test_batch_service_notifications() {
         .await
         .expect("Failed to create destination");
 
-    let batch = batch_service
-        .get_or_create_current_batch(&currency)
+    let small = ledger_service
+        .process_transaction(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source.id,
+            dest.id,
+            dec!(1000),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
         .await
-        .expect("Failed to create batch");
+        .expect("Failed to process small payment");
+    let large = ledger_service
+        .process_transaction(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source.id,
+            dest.id,
+            dec!(5000),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process large payment");
 
-    let tx_request = LedgerTransactionRequest::payment(
-        format!("PAY-{}", Uuid::new_v4()),
-        source.id,
-        dest.id,
-        dec!(100),
-        &currency,
-        format!("IDEM-{}", Uuid::new_v4()),
-    );
+    assert_eq!(small.transaction.settlement_route, SettlementRoute::Netted);
+    assert_eq!(large.transaction.settlement_route, SettlementRoute::Rtgs);
+    assert_eq!(large.transaction.status, TransactionStatus::Settled);
+    assert_eq!(large.source_balance.available_balance, dec!(4000));
+    assert_eq!(large.destination_balance.available_balance, dec!(6000));
 
-    let tx_result = ledger_service
-        .process_payment(tx_request)
+    // Only the netted payment may join a batch
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
         .await
-        .expect("Failed to process payment");
-
+        .expect("Failed to create batch");
     batch_service
-        .assign_transaction_to_batch(tx_result.transaction.id, batch.id)
+        .assign_transaction_to_batch(small.transaction.id, batch.id)
         .await
-        .expect("Failed to assign transaction");
-
-    // Clear any existing notifications
-    batch_service.clear_notifications().await;
-
-    // Process batch
-    batch_service
-        .trigger_batch_processing(batch.id)
+        .expect("Failed to assign netted transaction");
+    let rejected = batch_service
+        .assign_transaction_to_batch(large.transaction.id, batch.id)
+        .await;
+    assert!(matches!(rejected, Err(AppError::Validation(_))));
+
+    // RTGS settlement is final immediately and outside any batch
+    let finality = FinalityService::new(pool.clone())
+        .transaction_finality(large.transaction.id)
         .await
-        .expect("Failed to process batch");
+        .expect("RTGS transaction should be final");
+    assert_eq!(finality.batch_id, None);
+    assert_eq!(finality.settlement_route, SettlementRoute::Rtgs);
 
-    // Check notifications
-    let notifications = batch_service.get_notifications().await;
-    assert_eq!(notifications.len(), 1);
-    assert_eq!(notifications[0].batch_id, batch.id);
-    assert_eq!(notifications[0].status, BatchStatus::Completed);
+    let report = rtgs_service
+        .daily_report(Utc::now().date_naive())
+        .await
+        .expect("Failed to build routing report");
+    let volume = |route| {
+        report
+            .routes
+            .iter()
+            .find(|v| v.currency == currency && v.settlement_route == route)
+            .map(|v| (v.transaction_count, v.gross_amount))
+    };
+    assert_eq!(volume(SettlementRoute::Netted), Some((1, dec!(1000))));
+    assert_eq!(volume(SettlementRoute::Rtgs), Some((1, dec!(5000))));
 }
 
 #[tokio::test]
-async fn test_batch_service_fail_and_retry() {
+async fn test_batch_releases_by_priority_then_fifo() {
     let pool = common::setup_test_db().await;
     let currency = unique_currency();
 
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
     let batch_service = BatchService::new(pool.clone());
 
-    // Create batch directly with explicit request
-    let request = CreateBatchRequest::for_today(&currency, 24);
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(5000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
     let batch = batch_service
-        .create_batch(request)
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    let mut ids = Vec::new();
+    for priority in [
+        TransactionPriority::Normal,
+        TransactionPriority::High,
+        TransactionPriority::Normal,
+        TransactionPriority::Urgent,
+    ] {
+        let result = ledger_service
+            .process_payment(
+                LedgerTransactionRequest::payment(
+                    format!("PAY-{}", Uuid::new_v4()),
+                    source.id,
+                    dest.id,
+                    dec!(100),
+                    &currency,
+                    format!("IDEM-{}", Uuid::new_v4()),
+                )
+                .with_priority(priority),
+            )
+            .await
+            .expect("Failed to process payment");
+        assert_eq!(result.transaction.priority, priority);
+
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+        ids.push(result.transaction.id);
+    }
+
+    let release_order = |transactions: Vec<settlement_engine::models::TransactionRecord>| {
+        transactions.into_iter().map(|tx| tx.id).collect::<Vec<_>>()
+    };
+    let queued = batch_service
+        .get_batch_transactions(batch.id)
+        .await
+        .expect("Failed to get batch transactions");
+    assert_eq!(release_order(queued), vec![ids[3], ids[1], ids[0], ids[2]]);
+
+    // Bumping the later normal payment ahead of the high one
+    let bumped = ledger_service
+        .reprioritize_transaction(ids[2], TransactionPriority::Urgent)
+        .await
+        .expect("Failed to re-prioritize");
+    assert_eq!(bumped.priority, TransactionPriority::Urgent);
+
+    let queued = batch_service
+        .get_batch_transactions(batch.id)
+        .await
+        .expect("Failed to get batch transactions");
+    assert_eq!(release_order(queued), vec![ids[2], ids[3], ids[1], ids[0]]);
+
+    // Once the batch has been processed nothing is queued any more
+    batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+    let result = ledger_service
+        .reprioritize_transaction(ids[0], TransactionPriority::Urgent)
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_batch_finality_records_and_attestation() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let signer = Arc::new(AttestationSigner::new("test-key", b"attestation-secret"));
+    let finality_service = FinalityService::new(pool.clone()).with_signer(signer.clone());
+
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(5000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    let mut ids = Vec::new();
+    for priority in [TransactionPriority::Normal, TransactionPriority::Urgent, TransactionPriority::High] {
+        let result = ledger_service
+            .process_payment(
+                LedgerTransactionRequest::payment(
+                    format!("PAY-{}", Uuid::new_v4()),
+                    source.id,
+                    dest.id,
+                    dec!(100),
+                    &currency,
+                    format!("IDEM-{}", Uuid::new_v4()),
+                )
+                .with_priority(priority),
+            )
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+        ids.push(result.transaction.id);
+    }
+
+    // Nothing is final, and nothing can be attested, before the batch settles
+    let pending = finality_service.transaction_finality(ids[0]).await;
+    assert!(matches!(pending, Err(AppError::NotFound(_))));
+    let early = finality_service.attest_batch(batch.id).await;
+    assert!(matches!(early, Err(AppError::Validation(_))));
+
+    batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    let records = finality_service
+        .batch_finality(batch.id)
+        .await
+        .expect("Failed to get batch finality");
+    let order: Vec<Uuid> = records.iter().map(|r| r.transaction_id).collect();
+    assert_eq!(order, vec![ids[1], ids[2], ids[0]]);
+    assert!(records.windows(2).all(|w| w[0].sequence < w[1].sequence));
+    assert!(records.iter().all(|r| r.batch_id == Some(batch.id)));
+
+    let record = finality_service
+        .transaction_finality(ids[0])
+        .await
+        .expect("Transaction should be final");
+    assert_eq!(record, records[2]);
+
+    let signed = finality_service
+        .attest_batch(batch.id)
+        .await
+        .expect("Failed to attest batch");
+    assert_eq!(signed.attestation.record_count, 3);
+    assert_eq!(signed.attestation.records, records);
+    assert!(signed.verify(&signer));
+
+    // Attestation export requires a signing key
+    let unsigned = FinalityService::new(pool.clone()).attest_batch(batch.id).await;
+    assert!(matches!(unsigned, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_batch_releases_instructions_to_rail() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let balance_service = BalanceService::new(pool.clone());
+    let rail = Arc::new(SimulatedRail::new().with_ack_after_polls(2).with_rejection_limit(dec!(150)));
+    let batch_service = BatchService::new(pool.clone()).with_rail(rail.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Participant A", "Participant B", "Participant C"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("RAIL-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let (a, b, c) = (accounts[0], accounts[1], accounts[2]);
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    // A pays B 300 and B pays C 100: A owes 200 to B and 100 to C net
+    for (from, to, amount) in [(a, b, dec!(300)), (b, c, dec!(100))] {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                from,
+                to,
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    let result = batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    assert_eq!(result.status, BatchStatus::Completed);
+    assert_eq!(result.instructions.len(), 2);
+    assert_eq!(rail.submission_count(), 2);
+    for instruction in &result.instructions {
+        assert_eq!(instruction.from_participant, a);
+        // The 200 instruction exceeds the rail limit and is rejected
+        let expected = if instruction.amount > dec!(150) {
+            InstructionStatus::Failed
+        } else {
+            InstructionStatus::Executed
+        };
+        assert_eq!(instruction.status, expected);
+    }
+
+    let positions = batch_service
+        .get_batch_positions(batch.id)
+        .await
+        .expect("Failed to get positions");
+    assert_eq!(positions.len(), 3);
+
+    let balance = balance_service
+        .get_balance(a, &currency)
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.reserved_balance, dec!(0));
+}
+
+#[tokio::test]
+async fn test_batch_service_close_and_process() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    // Create accounts
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(1000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
+    // Create batch and add transaction
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    let tx_request = LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        source.id,
+        dest.id,
+        dec!(100),
+        &currency,
+        format!("IDEM-{}", Uuid::new_v4()),
+    );
+
+    let tx_result = ledger_service
+        .process_payment(tx_request)
+        .await
+        .expect("Failed to process payment");
+
+    batch_service
+        .assign_transaction_to_batch(tx_result.transaction.id, batch.id)
+        .await
+        .expect("Failed to assign transaction");
+
+    // Trigger batch processing
+    let result = batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    assert_eq!(result.status, BatchStatus::Completed);
+    assert_eq!(result.total_transactions, 1);
+    assert_eq!(result.successful_transactions, 1);
+    assert_eq!(result.failed_transactions, 0);
+    assert!(result.errors.is_empty());
+
+    // Verify batch status updated
+    let final_batch = batch_service
+        .get_batch(batch.id)
+        .await
+        .expect("Failed to get batch");
+
+    assert_eq!(final_batch.status, BatchStatus::Completed);
+    assert!(final_batch.completed_at.is_some());
+}
+
+#[tokio::test]
+async fn test_batch_service_notifications() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    // Create accounts and transaction
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(1000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    let tx_request = LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        source.id,
+        dest.id,
+        dec!(100),
+        &currency,
+        format!("IDEM-{}", Uuid::new_v4()),
+    );
+
+    let tx_result = ledger_service
+        .process_payment(tx_request)
+        .await
+        .expect("Failed to process payment");
+
+    batch_service
+        .assign_transaction_to_batch(tx_result.transaction.id, batch.id)
+        .await
+        .expect("Failed to assign transaction");
+
+    // Clear any existing notifications
+    batch_service.clear_notifications().await;
+
+    // Process batch
+    batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    // Check notifications
+    let notifications = batch_service.get_notifications().await;
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].batch_id, batch.id);
+    assert_eq!(notifications[0].status, BatchStatus::Completed);
+}
+
+#[tokio::test]
+async fn test_batch_service_fail_and_retry() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let batch_service = BatchService::new(pool.clone());
+
+    // Create batch directly with explicit request
+    let request = CreateBatchRequest::for_today(&currency, 24);
+    let batch = batch_service
+        .create_batch(request)
         .await
         .expect("Failed to create batch");
 
@@ -408,147 +1119,825 @@ async fn test_batch_service_fail_and_retry() {
     let closed = batch_service
         .close_batch(batch.id)
         .await
-        .expect("Failed to close batch");
-    assert_eq!(closed.status, BatchStatus::Processing);
+        .expect("Failed to close batch");
+    assert_eq!(closed.status, BatchStatus::Processing);
+
+    // Fail the batch
+    let failed = batch_service
+        .fail_batch(batch.id, "Test failure reason")
+        .await
+        .expect("Failed to fail batch");
+    assert_eq!(failed.status, BatchStatus::Failed);
+
+    // Retry the batch
+    let retried = batch_service
+        .retry_batch(batch.id)
+        .await
+        .expect("Failed to retry batch");
+    assert_eq!(retried.status, BatchStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_batch_service_list_batches() {
+    let pool = common::setup_test_db().await;
+    let currency1 = unique_currency();
+    let currency2 = unique_currency();
+    let currency3 = unique_currency();
+
+    let batch_service = BatchService::new(pool.clone());
+
+    // Create batches in different currencies
+    batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency1, 24))
+        .await
+        .expect("Failed to create first batch");
+
+    batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency2, 24))
+        .await
+        .expect("Failed to create second batch");
+
+    batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency3, 24))
+        .await
+        .expect("Failed to create third batch");
+
+    // List all batches
+    let all_batches = batch_service
+        .list_batches(None, None, None, 100, 0)
+        .await
+        .expect("Failed to list batches");
+    assert!(all_batches.len() >= 3);
+
+    // List first currency batches only
+    let filtered_batches = batch_service
+        .list_batches(None, Some(&currency1), None, 10, 0)
+        .await
+        .expect("Failed to list filtered batches");
+    assert!(filtered_batches.iter().all(|b| b.currency == currency1));
+
+    // List pending batches
+    let pending_batches = batch_service
+        .list_batches(Some(BatchStatus::Pending), None, None, 100, 0)
+        .await
+        .expect("Failed to list pending batches");
+    assert!(pending_batches.iter().all(|b| b.status == BatchStatus::Pending));
+}
+
+#[tokio::test]
+async fn test_batch_state_machine() {
+    // Valid transitions
+    assert!(BatchStateMachine::can_transition(
+        BatchStatus::Pending,
+        BatchStatus::Processing
+    ));
+    assert!(BatchStateMachine::can_transition(
+        BatchStatus::Processing,
+        BatchStatus::Completed
+    ));
+    assert!(BatchStateMachine::can_transition(
+        BatchStatus::Processing,
+        BatchStatus::Failed
+    ));
+    assert!(BatchStateMachine::can_transition(
+        BatchStatus::Failed,
+        BatchStatus::Pending
+    ));
+
+    // Invalid transitions
+    assert!(!BatchStateMachine::can_transition(
+        BatchStatus::Completed,
+        BatchStatus::Pending
+    ));
+    assert!(!BatchStateMachine::can_transition(
+        BatchStatus::Pending,
+        BatchStatus::Completed
+    ));
+
+    // Transition function
+    let result = BatchStateMachine::transition(BatchStatus::Pending, BatchStatus::Processing);
+    assert!(result.is_ok());
+
+    let result = BatchStateMachine::transition(BatchStatus::Completed, BatchStatus::Pending);
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_settlement_window_config() {
+    let config = SettlementWindowConfig::default();
+    assert_eq!(config.window_type, SettlementWindowType::Daily);
+    assert!(config.auto_close);
+
+    // Test window durations
+    assert_eq!(SettlementWindowType::RealTime.duration(), Duration::seconds(0));
+    assert_eq!(SettlementWindowType::MicroBatch.duration(), Duration::minutes(5));
+    assert_eq!(SettlementWindowType::Hourly.duration(), Duration::hours(1));
+    assert_eq!(SettlementWindowType::Daily.duration(), Duration::hours(24));
+}
+
+#[tokio::test]
+async fn test_batch_service_with_custom_config() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let config = SettlementWindowConfig {
+        window_type: SettlementWindowType::Hourly,
+        cut_off_time: None,
+        timezone: "UTC".to_string(),
+        auto_close: true,
+    };
+
+    let batch_service = BatchService::new(pool.clone()).with_config(config);
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    assert_eq!(batch.status, BatchStatus::Pending);
+    assert_eq!(batch.currency, currency);
+}
+
+#[tokio::test]
+async fn test_batch_service_get_batch_transactions() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    // Create accounts
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(5000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
+    // Create batch
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    // Create and assign transactions
+    for i in 0..5 {
+        let tx_request = LedgerTransactionRequest::payment(
+            format!("PAY-{}-{}", i, Uuid::new_v4()),
+            source.id,
+            dest.id,
+            dec!(50),
+            &currency,
+            format!("IDEM-{}-{}", i, Uuid::new_v4()),
+        );
+
+        let tx_result = ledger_service
+            .process_payment(tx_request)
+            .await
+            .expect("Failed to process payment");
+
+        batch_service
+            .assign_transaction_to_batch(tx_result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    // Get batch transactions
+    let transactions = batch_service
+        .get_batch_transactions(batch.id)
+        .await
+        .expect("Failed to get transactions");
+
+    assert_eq!(transactions.len(), 5);
+    assert!(transactions.iter().all(|t| t.settlement_batch_id == Some(batch.id)));
+}
+
+#[tokio::test]
+async fn test_batch_lifecycle_events_queued_in_outbox() {
+    use settlement_engine::events::{BatchEvent, EventEnvelope, EventType};
+    use settlement_engine::repositories::OutboxRepository;
+
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let outbox = OutboxRepository::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Source", "Destination"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("{}-{}", name, Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account);
+    }
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+    let payment = ledger_service
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            accounts[0].id,
+            accounts[1].id,
+            dec!(250),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+    batch_service
+        .assign_transaction_to_batch(payment.transaction.id, batch.id)
+        .await
+        .expect("Failed to assign transaction");
+    batch_service
+        .fail_batch(batch.id, "rail unavailable")
+        .await
+        .expect("Failed to fail batch");
+    batch_service.retry_batch(batch.id).await.expect("Failed to retry batch");
+    batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    let events: Vec<EventEnvelope<BatchEvent>> = outbox
+        .find_by_partition_key(BatchEvent::topic(), &batch.id.to_string())
+        .await
+        .expect("Failed to read outbox")
+        .into_iter()
+        .map(|message| serde_json::from_value(message.payload).expect("Invalid batch event"))
+        .collect();
+
+    let types: Vec<_> = events.iter().map(|e| e.event_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            EventType::BatchCreated,
+            EventType::BatchFailed,
+            EventType::BatchRetried,
+            EventType::BatchProcessing,
+            EventType::BatchCompleted,
+        ]
+    );
+
+    let completed = &events.last().unwrap().payload;
+    assert_eq!(completed.status, BatchStatus::Completed);
+    assert_eq!(completed.total_transactions, 1);
+    assert_eq!(completed.gross_amount, dec!(250));
+    assert!(completed.completed_at.is_some());
+}
+
+#[tokio::test]
+async fn test_transactions_auto_assigned_at_settlement() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let batch_service = Arc::new(BatchService::new(pool.clone()));
+    let ledger_service = LedgerService::new(pool.clone()).with_batching(batch_service.clone());
+
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(1000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
 
-    // Fail the batch
-    let failed = batch_service
-        .fail_batch(batch.id, "Test failure reason")
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
         .await
-        .expect("Failed to fail batch");
-    assert_eq!(failed.status, BatchStatus::Failed);
+        .expect("Failed to create destination");
 
-    // Retry the batch
-    let retried = batch_service
-        .retry_batch(batch.id)
+    let payment = |amount| {
+        LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source.id,
+            dest.id,
+            amount,
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+        .with_fee(dec!(1))
+    };
+
+    let first = ledger_service
+        .process_payment(payment(dec!(100)))
         .await
-        .expect("Failed to retry batch");
-    assert_eq!(retried.status, BatchStatus::Pending);
+        .expect("Failed to process first payment");
+    let second = ledger_service
+        .process_payment(payment(dec!(50)))
+        .await
+        .expect("Failed to process second payment");
+
+    let first_assignment = first.batch_assignment.expect("First payment not assigned");
+    let second_assignment = second.batch_assignment.expect("Second payment not assigned");
+    assert!(first_assignment.opened_batch);
+    assert!(!second_assignment.opened_batch);
+    assert_eq!(first_assignment.batch_id, second_assignment.batch_id);
+    assert_eq!(first.transaction.settlement_batch_id, Some(first_assignment.batch_id));
+    assert_eq!(second.transaction.settlement_batch_id, Some(first_assignment.batch_id));
+
+    let batch = batch_service
+        .get_batch(first_assignment.batch_id)
+        .await
+        .expect("Failed to get batch");
+    assert_eq!(batch.currency, currency);
+    assert_eq!(batch.total_transactions, 2);
+    assert_eq!(batch.gross_amount, dec!(150));
+    assert_eq!(batch.fee_amount, dec!(2));
+
+    // A failed settlement leaves no trace in the batch
+    let failed = ledger_service.process_payment(payment(dec!(5000))).await;
+    assert!(matches!(failed, Err(AppError::InsufficientFunds(_))));
+    let batch = batch_service
+        .get_batch(first_assignment.batch_id)
+        .await
+        .expect("Failed to get batch");
+    assert_eq!(batch.total_transactions, 2);
 }
 
 #[tokio::test]
-async fn test_batch_service_list_batches() {
+async fn test_batch_processed_by_worker_pool() {
     let pool = common::setup_test_db().await;
-    let currency1 = unique_currency();
-    let currency2 = unique_currency();
-    let currency3 = unique_currency();
+    let currency = unique_currency();
 
-    let batch_service = BatchService::new(pool.clone());
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone()).with_workers(4);
+    let finality_service = FinalityService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for i in 0..6 {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("WRK{}-{}", i, Uuid::new_v4()),
+                name: format!("Worker Account {}", i),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(10000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account);
+    }
 
-    // Create batches in different currencies
-    batch_service
-        .create_batch(CreateBatchRequest::for_today(&currency1, 24))
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
         .await
-        .expect("Failed to create first batch");
+        .expect("Failed to create batch");
 
-    batch_service
-        .create_batch(CreateBatchRequest::for_today(&currency2, 24))
-        .await
-        .expect("Failed to create second batch");
+    // Three disjoint pairs of accounts, each paying back and forth
+    let mut ids = Vec::new();
+    for round in 0..4 {
+        for pair in accounts.chunks(2) {
+            let (from, to) = if round % 2 == 0 { (&pair[0], &pair[1]) } else { (&pair[1], &pair[0]) };
+            let result = ledger_service
+                .process_payment(LedgerTransactionRequest::payment(
+                    format!("PAY-{}", Uuid::new_v4()),
+                    from.id,
+                    to.id,
+                    dec!(10),
+                    &currency,
+                    format!("IDEM-{}", Uuid::new_v4()),
+                ))
+                .await
+                .expect("Failed to process payment");
+            batch_service
+                .assign_transaction_to_batch(result.transaction.id, batch.id)
+                .await
+                .expect("Failed to assign transaction");
+            ids.push(result.transaction.id);
+        }
+    }
 
-    batch_service
-        .create_batch(CreateBatchRequest::for_today(&currency3, 24))
+    let result = batch_service
+        .trigger_batch_processing(batch.id)
         .await
-        .expect("Failed to create third batch");
+        .expect("Failed to process batch");
+    assert_eq!(result.status, BatchStatus::Completed);
+    assert_eq!(result.successful_transactions, 12);
+    assert_eq!(result.failed_transactions, 0);
 
-    // List all batches
-    let all_batches = batch_service
-        .list_batches(None, None, 100, 0)
+    // Finality follows batch order regardless of how the work was spread
+    let records = finality_service
+        .batch_finality(batch.id)
         .await
-        .expect("Failed to list batches");
-    assert!(all_batches.len() >= 3);
+        .expect("Failed to get batch finality");
+    let order: Vec<Uuid> = records.iter().map(|r| r.transaction_id).collect();
+    assert_eq!(order, ids);
+}
 
-    // List first currency batches only
-    let filtered_batches = batch_service
-        .list_batches(None, Some(&currency1), 10, 0)
+#[tokio::test]
+async fn test_background_processing_reports_progress() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = Arc::new(BatchService::new(pool.clone()).with_checkpoint_interval(2));
+
+    let mut accounts = Vec::new();
+    for name in ["Source", "Destination"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("PRG-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account);
+    }
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
         .await
-        .expect("Failed to list filtered batches");
-    assert!(filtered_batches.iter().all(|b| b.currency == currency1));
+        .expect("Failed to create batch");
+    for _ in 0..5 {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                accounts[0].id,
+                accounts[1].id,
+                dec!(10),
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
 
-    // List pending batches
-    let pending_batches = batch_service
-        .list_batches(Some(BatchStatus::Pending), None, 100, 0)
+    let not_started = batch_service.get_processing_progress(batch.id).await;
+    assert!(matches!(not_started, Err(AppError::NotFound(_))));
+
+    let closed = batch_service
+        .start_batch_processing(batch.id)
         .await
-        .expect("Failed to list pending batches");
-    assert!(pending_batches.iter().all(|b| b.status == BatchStatus::Pending));
+        .expect("Failed to start processing");
+    assert_eq!(closed.status, BatchStatus::Processing);
+
+    let mut progress = None;
+    for _ in 0..100 {
+        if let Ok(current) = batch_service.get_processing_progress(batch.id).await {
+            if current.is_finished() {
+                progress = Some(current);
+                break;
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let progress = progress.expect("Batch processing did not finish");
+    assert_eq!(progress.total_transactions, 5);
+    assert_eq!(progress.processed_transactions, 5);
+    assert_eq!(progress.failed_transactions, 0);
+    assert_eq!(progress.remaining(), 0);
+    assert_eq!(progress.estimated_completion_at(), progress.finished_at);
+
+    let processed = batch_service.get_batch(batch.id).await.expect("Failed to get batch");
+    assert_eq!(processed.status, BatchStatus::Completed);
+
+    // A batch can only be closed once
+    assert!(batch_service.start_batch_processing(batch.id).await.is_err());
 }
 
 #[tokio::test]
-async fn test_batch_state_machine() {
-    // Valid transitions
-    assert!(BatchStateMachine::can_transition(
-        BatchStatus::Pending,
-        BatchStatus::Processing
-    ));
-    assert!(BatchStateMachine::can_transition(
-        BatchStatus::Processing,
-        BatchStatus::Completed
-    ));
-    assert!(BatchStateMachine::can_transition(
-        BatchStatus::Processing,
-        BatchStatus::Failed
-    ));
-    assert!(BatchStateMachine::can_transition(
-        BatchStatus::Failed,
-        BatchStatus::Pending
-    ));
+async fn test_stalled_processing_resumes_where_it_stopped() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
 
-    // Invalid transitions
-    assert!(!BatchStateMachine::can_transition(
-        BatchStatus::Completed,
-        BatchStatus::Pending
-    ));
-    assert!(!BatchStateMachine::can_transition(
-        BatchStatus::Pending,
-        BatchStatus::Completed
-    ));
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone()).with_stall_timeout(std::time::Duration::from_secs(3600));
+
+    let mut accounts = Vec::new();
+    for name in ["Source", "Destination"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("RSM-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account);
+    }
 
-    // Transition function
-    let result = BatchStateMachine::transition(BatchStatus::Pending, BatchStatus::Processing);
-    assert!(result.is_ok());
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+    let mut transaction_ids = Vec::new();
+    for _ in 0..4 {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                accounts[0].id,
+                accounts[1].id,
+                dec!(10),
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+        transaction_ids.push(result.transaction.id);
+    }
 
-    let result = BatchStateMachine::transition(BatchStatus::Completed, BatchStatus::Pending);
-    assert!(result.is_err());
+    // A run that died ten minutes ago, after processing the first transaction
+    batch_service.close_batch(batch.id).await.expect("Failed to close batch");
+    sqlx::query(
+        r#"
+        INSERT INTO batch_processing_progress (batch_id, total_transactions, processed_transactions, run_id, started_at, updated_at)
+        VALUES ($1, 4, 1, $2, NOW() - INTERVAL '15 minutes', NOW() - INTERVAL '10 minutes')
+        "#,
+    )
+    .bind(batch.id)
+    .bind(Uuid::new_v4())
+    .execute(&pool)
+    .await
+    .expect("Failed to record progress");
+    sqlx::query(
+        r#"
+        INSERT INTO batch_transaction_checkpoints (batch_id, transaction_id, outcome, processed_at)
+        VALUES ($1, $2, 'PROCESSED', NOW() - INTERVAL '10 minutes')
+        "#,
+    )
+    .bind(batch.id)
+    .bind(transaction_ids[0])
+    .execute(&pool)
+    .await
+    .expect("Failed to record checkpoint");
+
+    // Within the stall timeout the run is presumed alive
+    let held = batch_service.resume_batch_processing(batch.id).await;
+    assert!(matches!(held, Err(AppError::BatchClosed(_))));
+    assert!(batch_service.resume_stalled_batches().await.unwrap().iter().all(|r| r.batch_id != batch.id));
+
+    let batch_service = batch_service.with_stall_timeout(std::time::Duration::from_secs(60));
+    let results = batch_service.resume_stalled_batches().await.expect("Failed to resume batches");
+    let result = results
+        .iter()
+        .find(|r| r.batch_id == batch.id)
+        .expect("Stalled batch was not resumed");
+    assert_eq!(result.status, BatchStatus::Completed);
+    assert_eq!(result.total_transactions, 4);
+    assert_eq!(result.successful_transactions, 4);
+
+    let progress = batch_service.get_processing_progress(batch.id).await.unwrap();
+    assert!(progress.is_finished());
+    assert_eq!(progress.processed_transactions, 4);
+    assert_eq!(progress.resumes, 1);
+
+    // The first transaction was not processed again
+    let first_processed: bool = sqlx::query_scalar(
+        r#"
+        SELECT processed_at < NOW() - INTERVAL '5 minutes'
+        FROM batch_transaction_checkpoints
+        WHERE batch_id = $1 AND transaction_id = $2
+        "#,
+    )
+    .bind(batch.id)
+    .bind(transaction_ids[0])
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(first_processed);
+    let checkpoints: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM batch_transaction_checkpoints WHERE batch_id = $1")
+            .bind(batch.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(checkpoints, 4);
+
+    // Finished batches are no longer resumable
+    assert!(batch_service.resume_batch_processing(batch.id).await.is_err());
 }
 
 #[tokio::test]
-async fn test_settlement_window_config() {
-    let config = SettlementWindowConfig::default();
-    assert_eq!(config.window_type, SettlementWindowType::Daily);
-    assert!(config.auto_close);
+async fn test_batch_lock_serializes_close_and_processing() {
+    use settlement_engine::events::{BatchEvent, EventEnvelope, EventType};
+    use settlement_engine::repositories::OutboxRepository;
 
-    // Test window durations
-    assert_eq!(SettlementWindowType::RealTime.duration(), Duration::seconds(0));
-    assert_eq!(SettlementWindowType::MicroBatch.duration(), Duration::minutes(5));
-    assert_eq!(SettlementWindowType::Hourly.duration(), Duration::hours(1));
-    assert_eq!(SettlementWindowType::Daily.duration(), Duration::hours(24));
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let locks = Arc::new(DistributedLocks::postgres(pool.clone()));
+    let batch_service = Arc::new(BatchService::new(pool.clone()).with_locks(locks.clone()));
+    // Another instance, with locks of its own on the same database
+    let other_instance = BatchService::new(pool.clone());
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+
+    // While anyone holds the batch's lock, nobody closes or processes it
+    let lock = locks
+        .try_acquire(&format!("batch:{}", batch.id))
+        .await
+        .unwrap()
+        .expect("Lock should be free");
+    assert!(locks.try_acquire(lock.name()).await.unwrap().is_none());
+    for attempt in [
+        other_instance.close_batch(batch.id).await.map(|_| ()),
+        other_instance.trigger_batch_processing(batch.id).await.map(|_| ()),
+        batch_service.start_batch_processing(batch.id).await.map(|_| ()),
+    ] {
+        assert!(matches!(attempt, Err(AppError::BatchClosed(_))), "{:?}", attempt);
+    }
+    assert_eq!(batch_service.get_batch(batch.id).await.unwrap().status, BatchStatus::Pending);
+
+    // Dropping a guard without releasing it frees the lock too
+    lock.release().await.expect("Failed to release lock");
+    drop(locks.try_acquire(&format!("batch:{}", batch.id)).await.unwrap().expect("Lock should be free"));
+
+    // Concurrent requests: one processes the batch, the others are turned away
+    let attempts = futures::future::join_all((0..4).map(|_| other_instance.trigger_batch_processing(batch.id))).await;
+    let processed = attempts.iter().filter(|attempt| attempt.is_ok()).count();
+    assert_eq!(processed, 1, "{:?}", attempts);
+    assert!(attempts
+        .iter()
+        .filter_map(|attempt| attempt.as_ref().err())
+        .all(|e| matches!(e, AppError::BatchClosed(_) | AppError::Validation(_))));
+    assert_eq!(batch_service.get_batch(batch.id).await.unwrap().status, BatchStatus::Completed);
+
+    let closes = OutboxRepository::new(pool.clone())
+        .find_by_partition_key(BatchEvent::topic(), &batch.id.to_string())
+        .await
+        .expect("Failed to read outbox")
+        .into_iter()
+        .map(|message| serde_json::from_value::<EventEnvelope<BatchEvent>>(message.payload).unwrap())
+        .filter(|event| event.event_type == EventType::BatchProcessing)
+        .count();
+    assert_eq!(closes, 1, "The batch was closed more than once");
 }
 
 #[tokio::test]
-async fn test_batch_service_with_custom_config() {
+async fn test_batch_cut_off_moves_with_approval() {
+    use settlement_engine::events::{BatchEvent, EventEnvelope, EventType};
+    use settlement_engine::models::CutOffChangeKind;
+    use settlement_engine::repositories::OutboxRepository;
+    use settlement_engine::services::CutOffApproval;
+
     let pool = common::setup_test_db().await;
     let currency = unique_currency();
-
-    let config = SettlementWindowConfig {
-        window_type: SettlementWindowType::Hourly,
-        cut_off_time: None,
-        timezone: "UTC".to_string(),
-        auto_close: true,
+    let batch_service = BatchService::new(pool.clone());
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 1))
+        .await
+        .expect("Failed to create batch");
+    let approval = CutOffApproval {
+        requested_by: "ops-alice".to_string(),
+        approved_by: "ops-bob".to_string(),
+        reason: "Participant funding delayed".to_string(),
     };
 
-    let batch_service = BatchService::new(pool.clone()).with_config(config);
+    let self_approved = CutOffApproval { approved_by: "ops-alice".to_string(), ..approval.clone() };
+    let extended = batch.cut_off_time + Duration::minutes(30);
+    assert!(matches!(
+        batch_service.move_cut_off(batch.id, extended, &self_approved).await,
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        batch_service.move_cut_off(batch.id, batch.cut_off_time + Duration::hours(10), &approval).await,
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        batch_service.move_cut_off(batch.id, Utc::now() - Duration::minutes(1), &approval).await,
+        Err(AppError::Validation(_))
+    ));
+
+    let moved = batch_service
+        .move_cut_off(batch.id, extended, &approval)
+        .await
+        .expect("Failed to move cut-off");
+    assert_eq!(moved.cut_off_time, extended);
+    assert_eq!(moved.status, BatchStatus::Pending);
+
+    let changes = batch_service.get_cut_off_changes(batch.id).await.expect("Failed to list changes");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, CutOffChangeKind::Moved);
+    assert_eq!(changes[0].previous_cut_off, batch.cut_off_time);
+    assert_eq!(changes[0].new_cut_off, extended);
+    assert_eq!(changes[0].approved_by, "ops-bob");
+
+    let events: Vec<EventEnvelope<BatchEvent>> = OutboxRepository::new(pool.clone())
+        .find_by_partition_key(BatchEvent::topic(), &batch.id.to_string())
+        .await
+        .expect("Failed to read outbox")
+        .into_iter()
+        .map(|message| serde_json::from_value(message.payload).expect("Invalid batch event"))
+        .collect();
+    let changed = events.last().expect("No batch events");
+    assert_eq!(changed.event_type, EventType::BatchCutOffChanged);
+    assert_eq!(changed.payload.cut_off_time, Some(extended));
+
+    // Completed batches keep their cut-off
+    batch_service.trigger_batch_processing(batch.id).await.expect("Failed to process batch");
+    assert!(matches!(
+        batch_service.move_cut_off(batch.id, extended + Duration::minutes(5), &approval).await,
+        Err(AppError::BatchClosed(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_batch_closed_early_is_left_to_scheduler() {
+    use settlement_engine::models::CutOffChangeKind;
+    use settlement_engine::services::CutOffApproval;
 
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let batch_service = BatchService::new(pool.clone());
     let batch = batch_service
-        .get_or_create_current_batch(&currency)
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
         .await
         .expect("Failed to create batch");
+    let approval = CutOffApproval {
+        requested_by: "ops-alice".to_string(),
+        approved_by: "ops-bob".to_string(),
+        reason: "Holiday schedule".to_string(),
+    };
 
-    assert_eq!(batch.status, BatchStatus::Pending);
-    assert_eq!(batch.currency, currency);
+    let closed = batch_service.close_early(batch.id, &approval).await.expect("Failed to close early");
+    assert!(closed.cut_off_time <= Utc::now());
+    assert_eq!(closed.status, BatchStatus::Pending);
+    assert!(matches!(
+        batch_service.close_early(batch.id, &approval).await,
+        Err(AppError::Validation(_))
+    ));
+    let changes = batch_service.get_cut_off_changes(batch.id).await.unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, CutOffChangeKind::ClosedEarly);
+
+    // New transactions go to a new batch while this one waits for the scheduler
+    let next = batch_service.get_or_create_current_batch(&currency).await.expect("Failed to open next batch");
+    assert_ne!(next.id, batch.id);
+    let ready = batch_service.find_batches_ready_for_processing().await.unwrap();
+    assert!(ready.iter().any(|ready| ready.id == batch.id));
+    assert!(!ready.iter().any(|ready| ready.id == next.id));
+
+    let processed = batch_service.auto_close_expired_batches().await.expect("Scheduler run failed");
+    assert!(processed.iter().any(|result| result.batch_id == batch.id));
+    assert_eq!(batch_service.get_batch(batch.id).await.unwrap().status, BatchStatus::Completed);
 }
 
 #[tokio::test]
-async fn test_batch_service_get_batch_transactions() {
+async fn test_batch_totals_reconciled_before_completion() {
     let pool = common::setup_test_db().await;
     let currency = unique_currency();
 
@@ -556,7 +1945,6 @@ async fn test_batch_service_get_batch_transactions() {
     let ledger_service = LedgerService::new(pool.clone());
     let batch_service = BatchService::new(pool.clone());
 
-    // Create accounts
     let source = account_service
         .create_account(CreateAccountRequest {
             external_id: format!("SRC-{}", Uuid::new_v4()),
@@ -568,7 +1956,6 @@ async fn test_batch_service_get_batch_transactions() {
         })
         .await
         .expect("Failed to create source");
-
     let dest = account_service
         .create_account(CreateAccountRequest {
             external_id: format!("DST-{}", Uuid::new_v4()),
@@ -581,40 +1968,66 @@ async fn test_batch_service_get_batch_transactions() {
         .await
         .expect("Failed to create destination");
 
-    // Create batch
-    let batch = batch_service
-        .get_or_create_current_batch(&currency)
-        .await
-        .expect("Failed to create batch");
-
-    // Create and assign transactions
-    for i in 0..5 {
-        let tx_request = LedgerTransactionRequest::payment(
-            format!("PAY-{}-{}", i, Uuid::new_v4()),
-            source.id,
-            dest.id,
-            dec!(50),
-            &currency,
-            format!("IDEM-{}-{}", i, Uuid::new_v4()),
-        );
-
-        let tx_result = ledger_service
-            .process_payment(tx_request)
+    // One open batch per settlement date and currency, so the second settles tomorrow
+    let today = Utc::now().date_naive();
+    let mut batches = Vec::new();
+    for days in 0..2 {
+        let batch = common::fixtures::batch(&currency)
+            .with_settlement_date(today + Duration::days(days))
+            .create(&pool)
+            .await;
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                source.id,
+                dest.id,
+                dec!(100),
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
             .await
             .expect("Failed to process payment");
-
         batch_service
-            .assign_transaction_to_batch(tx_result.transaction.id, batch.id)
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
             .await
             .expect("Failed to assign transaction");
+        batches.push(batch);
     }
 
-    // Get batch transactions
-    let transactions = batch_service
-        .get_batch_transactions(batch.id)
+    // Totals that agree complete the batch
+    let result = batch_service
+        .trigger_batch_processing(batches[0].id)
         .await
-        .expect("Failed to get transactions");
+        .expect("Failed to process batch");
+    assert_eq!(result.status, BatchStatus::Completed);
+    assert!(result.reconciliation.expect("Reconciliation not run").passed);
+    let recorded = batch_service
+        .get_reconciliation(batches[0].id)
+        .await
+        .expect("Reconciliation not recorded");
+    assert!(recorded.passed);
+    assert!(recorded.breaks().is_empty());
 
-    assert_eq!(transactions.len(), 5);
-    assert!(transactions.iter().all(|t| t.settlement_batch_id == Some(batch.id)));
+    // A gross amount that drifted from the transactions fails the batch with a break report
+    BatchRepository::new(pool.clone())
+        .update_totals(batches[1].id, 1, dec!(150), dec!(150), dec!(0))
+        .await
+        .expect("Failed to update totals");
+    let result = batch_service
+        .trigger_batch_processing(batches[1].id)
+        .await
+        .expect("Failed to process batch");
+    assert_eq!(result.status, BatchStatus::Failed);
+    let breaks = result.reconciliation.expect("Reconciliation not run").breaks();
+    assert_eq!(breaks.len(), 1);
+    assert_eq!(breaks[0].check, BatchCheck::GrossAmount);
+    assert_eq!(breaks[0].expected, dec!(150));
+    assert_eq!(breaks[0].actual, dec!(100));
+
+    let batch = batch_service.get_batch(batches[1].id).await.expect("Failed to get batch");
+    assert_eq!(batch.status, BatchStatus::Failed);
+    assert!(matches!(
+        batch_service.get_reconciliation(Uuid::new_v4()).await,
+        Err(AppError::NotFound(_))
+    ));
 }
@@ -0,0 +1,75 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::events::{CdcConfig, CdcPublisher, ChangeOperation, EventProducer, ProducerConfig};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Logical decoding needs `wal_level = logical`; the compose file sets it.
+async fn logical_decoding_enabled(pool: &PgPool) -> bool {
+    let wal_level: String = sqlx::query_scalar("SHOW wal_level")
+        .fetch_one(pool)
+        .await
+        .expect("Failed to read wal_level");
+    wal_level == "logical"
+}
+
+#[tokio::test]
+async fn test_captures_and_acknowledges_changes() {
+    let pool = common::setup_test_db().await;
+    if !logical_decoding_enabled(&pool).await {
+        eprintln!("Skipping CDC test: wal_level is not logical");
+        return;
+    }
+
+    let name = format!("test_cdc_{}", &Uuid::new_v4().simple().to_string()[..12]);
+    let publisher = CdcPublisher::new(pool.clone(), Arc::new(EventProducer::new(ProducerConfig::default())))
+        .with_config(CdcConfig {
+            slot_name: name.clone(),
+            publication: name.clone(),
+            tables: vec!["accounts".to_string()],
+            ..CdcConfig::default()
+        });
+    assert!(publisher.ensure_replication().await.expect("Failed to set up replication"));
+    assert!(!publisher.ensure_replication().await.expect("Set up should be repeatable"));
+
+    let account = fixtures::account(&unique_currency())
+        .with_name("CDC Test Account")
+        .with_balance(dec!(0))
+        .with_metadata(serde_json::json!({ "tier": "gold" }))
+        .create(&pool)
+        .await;
+
+    let transactions = publisher.read_pending().await.expect("Failed to read changes");
+    let (transaction, change) = transactions
+        .iter()
+        .flat_map(|t| t.changes.iter().map(move |c| (t, c)))
+        .find(|(_, c)| c.key.get("id").and_then(|id| id.as_str()) == Some(account.id.to_string().as_str()))
+        .expect("Account insert was not captured");
+    assert_eq!(change.table, "accounts");
+    assert_eq!(change.operation, ChangeOperation::Insert);
+    assert_eq!(change.partition_key(), Some(account.id.to_string()));
+    let after = change.after.as_ref().expect("Insert should carry the new row");
+    assert_eq!(after["external_id"], account.external_id.as_str());
+    assert_eq!(after["metadata"]["tier"], "gold");
+
+    // Peeking does not consume; acknowledging does
+    assert!(publisher.read_pending().await.unwrap().contains(transaction));
+    publisher.acknowledge(transaction.end_lsn).await.expect("Failed to acknowledge");
+    let remaining = publisher.read_pending().await.expect("Failed to read changes");
+    assert!(remaining.iter().all(|t| t.end_lsn > transaction.end_lsn));
+
+    let status = publisher.status().await.expect("Failed to get slot status");
+    assert_eq!(status.slot_name, name);
+    assert_eq!(status.plugin.as_deref(), Some("pgoutput"));
+    assert!(!status.active);
+
+    assert!(publisher.drop_slot().await.expect("Failed to drop slot"));
+    assert!(publisher.status().await.is_err());
+    sqlx::query(&format!("DROP PUBLICATION {}", name))
+        .execute(&pool)
+        .await
+        .expect("Failed to drop publication");
+}
@@ -0,0 +1,92 @@
+//! Postgres, Redis and Kafka in Docker for the integration tests.
+//!
+//! With `TESTCONTAINERS=1` and no explicit `DATABASE_URL`, `REDIS_URL` or
+//! `KAFKA_BROKERS`, a dependency is started in Docker the first time a test asks for it.
+//! Containers are marked for reuse, so later test binaries and later runs attach to the
+//! running ones instead of starting their own; remove them with `docker rm -f` when done.
+//! Tests can share one database because each gets a schema of its own. Postgres runs
+//! with `wal_level=logical` so the change data capture tests work.
+#![allow(dead_code)]
+
+use std::sync::OnceLock;
+use testcontainers::runners::SyncRunner;
+use testcontainers::{Container, ImageExt, ReuseDirective};
+use testcontainers_modules::kafka::{Kafka, KAFKA_PORT};
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::redis::{Redis, REDIS_PORT};
+
+/// Whether missing dependencies should be started in containers.
+pub fn enabled() -> bool {
+    std::env::var("TESTCONTAINERS").is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+}
+
+// Containers are started from a runtime of their own, as each test has a separate one
+fn start<T>(start: impl FnOnce() -> T + Send + 'static) -> T
+where
+    T: Send + 'static,
+{
+    std::thread::spawn(start).join().expect("Failed to start test container")
+}
+
+pub fn postgres_url() -> &'static str {
+    static POSTGRES: OnceLock<(Container<Postgres>, String)> = OnceLock::new();
+    &POSTGRES
+        .get_or_init(|| {
+            start(|| {
+                let container = Postgres::default()
+                    .with_tag("15-alpine")
+                    .with_cmd(["postgres", "-c", "wal_level=logical"])
+                    .with_reuse(ReuseDirective::Always)
+                    .start()
+                    .expect("Failed to start Postgres container");
+                let url = format!(
+                    "postgres://postgres:postgres@{}:{}/postgres",
+                    container.get_host().expect("Postgres container has no host"),
+                    container.get_host_port_ipv4(5432).expect("Postgres port is not mapped")
+                );
+                (container, url)
+            })
+        })
+        .1
+}
+
+pub fn redis_url() -> &'static str {
+    static REDIS: OnceLock<(Container<Redis>, String)> = OnceLock::new();
+    &REDIS
+        .get_or_init(|| {
+            start(|| {
+                let container = Redis::default()
+                    .with_tag("7-alpine")
+                    .with_reuse(ReuseDirective::Always)
+                    .start()
+                    .expect("Failed to start Redis container");
+                let url = format!(
+                    "redis://{}:{}",
+                    container.get_host().expect("Redis container has no host"),
+                    container.get_host_port_ipv4(REDIS_PORT).expect("Redis port is not mapped")
+                );
+                (container, url)
+            })
+        })
+        .1
+}
+
+pub fn kafka_brokers() -> &'static str {
+    static KAFKA: OnceLock<(Container<Kafka>, String)> = OnceLock::new();
+    &KAFKA
+        .get_or_init(|| {
+            start(|| {
+                let container = Kafka::default()
+                    .with_reuse(ReuseDirective::Always)
+                    .start()
+                    .expect("Failed to start Kafka container");
+                let brokers = format!(
+                    "{}:{}",
+                    container.get_host().expect("Kafka container has no host"),
+                    container.get_host_port_ipv4(KAFKA_PORT).expect("Kafka port is not mapped")
+                );
+                (container, brokers)
+            })
+        })
+        .1
+}
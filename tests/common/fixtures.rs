@@ -0,0 +1,130 @@
+//! Builders for the accounts and batches tests set up before exercising the engine.
+//!
+//! Each builder starts from defaults that suit most tests (an asset account holding 100,
+//! a batch for today cutting off in a day) and creates its record through the services.
+#![allow(dead_code)]
+
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use settlement_engine::models::{Account, AccountType, InstructionStrategy, NettingMode, SettlementBatch};
+use settlement_engine::services::{
+    account_service::CreateAccountRequest, AccountService, BatchService, CreateBatchRequest,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A three-letter currency code unlikely to be used by another test.
+pub fn unique_currency() -> String {
+    let uuid = Uuid::new_v4().to_string().replace("-", "");
+    format!("{}{}", &uuid[0..1], &uuid[16..18]).to_uppercase()
+}
+
+pub struct AccountFixture {
+    request: CreateAccountRequest,
+}
+
+/// An asset account in `currency` with an opening balance of 100.
+pub fn account(currency: &str) -> AccountFixture {
+    AccountFixture {
+        request: CreateAccountRequest {
+            external_id: format!("FIX-{}", Uuid::new_v4()),
+            name: "Test Account".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.to_string(),
+            initial_balance: Some(Decimal::new(100, 0)),
+            metadata: None,
+        },
+    }
+}
+
+impl AccountFixture {
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.request.name = name.to_string();
+        self
+    }
+
+    pub fn with_type(mut self, account_type: AccountType) -> Self {
+        self.request.account_type = account_type;
+        self
+    }
+
+    pub fn with_balance(mut self, balance: Decimal) -> Self {
+        self.request.initial_balance = Some(balance);
+        self
+    }
+
+    /// Opens the account without a balance.
+    pub fn without_balance(mut self) -> Self {
+        self.request.initial_balance = None;
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.request.metadata = Some(metadata);
+        self
+    }
+
+    pub async fn create(self, pool: &PgPool) -> Account {
+        AccountService::new(pool.clone())
+            .create_account(self.request)
+            .await
+            .expect("Failed to create account fixture")
+    }
+}
+
+/// A payer and a payee asset account in `currency`, both opened with `balance`.
+pub async fn payer_and_payee(pool: &PgPool, currency: &str, balance: Decimal) -> (Uuid, Uuid) {
+    let payer = account(currency).with_name("PAYER").with_balance(balance).create(pool).await;
+    let payee = account(currency).with_name("PAYEE").with_balance(balance).create(pool).await;
+    (payer.id, payee.id)
+}
+
+pub struct BatchFixture {
+    request: CreateBatchRequest,
+}
+
+/// An open batch in `currency` for today, cutting off in 24 hours.
+pub fn batch(currency: &str) -> BatchFixture {
+    BatchFixture {
+        request: CreateBatchRequest::for_today(currency, 24),
+    }
+}
+
+impl BatchFixture {
+    pub fn with_settlement_date(mut self, settlement_date: NaiveDate) -> Self {
+        self.request.settlement_date = settlement_date;
+        self
+    }
+
+    pub fn with_cut_off_in_hours(mut self, hours: i64) -> Self {
+        self.request.cut_off_time = Utc::now() + chrono::Duration::hours(hours);
+        self
+    }
+
+    pub fn with_window(mut self, window_id: Uuid) -> Self {
+        self.request = self.request.with_window(window_id);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.request = self.request.with_metadata(metadata);
+        self
+    }
+
+    pub fn with_netting_mode(mut self, netting_mode: NettingMode) -> Self {
+        self.request = self.request.with_netting_mode(netting_mode);
+        self
+    }
+
+    pub fn with_instruction_strategy(mut self, strategy: InstructionStrategy) -> Self {
+        self.request = self.request.with_instruction_strategy(strategy);
+        self
+    }
+
+    pub async fn create(self, pool: &PgPool) -> SettlementBatch {
+        BatchService::new(pool.clone())
+            .create_batch(self.request)
+            .await
+            .expect("Failed to create batch fixture")
+    }
+}
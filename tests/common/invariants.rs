@@ -0,0 +1,231 @@
+//! Property-based invariant harness for the ledger.
+//!
+//! Generates arbitrary sequences of payments, refunds and reversals, applies them through
+//! `LedgerService` against the test database, and checks the invariants every ledger state
+//! must satisfy regardless of which operations were accepted or rejected.
+//!
+//! Fees are always zero in generated operations: fee legs are only booked when a fee
+//! revenue account is configured, and without one a fee-bearing transaction intentionally
+//! leaves debits and credits unequal.
+//! Reversals are compensating entries and skip the funds check, so the harness only issues
+//! one when the original destination can still cover it.
+#![allow(dead_code)]
+
+use proptest::prelude::*;
+use rust_decimal::Decimal;
+use settlement_engine::models::{AccountType, TransactionRecord, TransactionStatus};
+use settlement_engine::repositories::{BalanceRepository, LedgerRepository};
+use settlement_engine::services::{
+    account_service::CreateAccountRequest, AccountService, LedgerService,
+    LedgerTransactionRequest, MultilateralNettingResult,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A single generated ledger operation. Indices are resolved modulo the number of
+/// accounts or previously settled transactions when the operation is applied.
+#[derive(Debug, Clone)]
+pub enum LedgerOp {
+    Payment { from: usize, to: usize, amount_cents: i64 },
+    Refund { of: usize, percent: u8 },
+    Reversal { of: usize },
+}
+
+/// Strategy for a single operation with amounts up to `max_amount_cents`.
+pub fn ledger_op(max_amount_cents: i64) -> impl Strategy<Value = LedgerOp> {
+    prop_oneof![
+        6 => (any::<usize>(), any::<usize>(), 1..=max_amount_cents)
+            .prop_map(|(from, to, amount_cents)| LedgerOp::Payment { from, to, amount_cents }),
+        2 => (any::<usize>(), 1u8..=100).prop_map(|(of, percent)| LedgerOp::Refund { of, percent }),
+        2 => any::<usize>().prop_map(|of| LedgerOp::Reversal { of }),
+    ]
+}
+
+/// Strategy for a sequence of up to `max_len` operations.
+pub fn ledger_ops(max_len: usize, max_amount_cents: i64) -> impl Strategy<Value = Vec<LedgerOp>> {
+    prop::collection::vec(ledger_op(max_amount_cents), 1..=max_len)
+}
+
+/// A set of freshly created accounts in an isolated currency.
+pub struct LedgerFixture {
+    pub currency: String,
+    pub accounts: Vec<Uuid>,
+    pub initial_total: Decimal,
+}
+
+impl LedgerFixture {
+    /// Creates `count` accounts each funded with `initial_balance` in a currency no other
+    /// test uses, so that conservation can be checked by summing just these accounts.
+    pub async fn create(pool: &PgPool, count: usize, initial_balance: Decimal) -> Self {
+        let account_service = AccountService::new(pool.clone());
+        let currency = format!("P{}", &Uuid::new_v4().simple().to_string()[..2]).to_uppercase();
+
+        let mut accounts = Vec::with_capacity(count);
+        for i in 0..count {
+            let account = account_service
+                .create_account(CreateAccountRequest {
+                    external_id: format!("PROP-{}-{}", i, Uuid::new_v4()),
+                    name: format!("Property Account {}", i),
+                    account_type: AccountType::Asset,
+                    currency: currency.clone(),
+                    initial_balance: Some(initial_balance),
+                    metadata: None,
+                })
+                .await
+                .expect("Failed to create fixture account");
+            accounts.push(account.id);
+        }
+
+        Self {
+            currency,
+            accounts,
+            initial_total: initial_balance * Decimal::from(count as i64),
+        }
+    }
+}
+
+/// Applies operations in order and returns every transaction that settled.
+/// Rejected operations (insufficient funds, already reversed, ...) are skipped.
+pub async fn apply_ops(
+    pool: &PgPool,
+    ledger_service: &LedgerService,
+    fixture: &LedgerFixture,
+    ops: &[LedgerOp],
+) -> Vec<TransactionRecord> {
+    let balance_repo = BalanceRepository::new(pool.clone());
+    let mut settled: Vec<TransactionRecord> = Vec::new();
+    let n = fixture.accounts.len();
+
+    for op in ops {
+        let result = match op {
+            LedgerOp::Payment { from, to, amount_cents } => {
+                let source = fixture.accounts[from % n];
+                let mut destination = fixture.accounts[to % n];
+                if source == destination {
+                    destination = fixture.accounts[(from % n + 1) % n];
+                }
+                ledger_service
+                    .process_payment(LedgerTransactionRequest::payment(
+                        format!("PROP-PAY-{}", Uuid::new_v4()),
+                        source,
+                        destination,
+                        Decimal::new(*amount_cents, 2),
+                        fixture.currency.clone(),
+                        Uuid::new_v4().to_string(),
+                    ))
+                    .await
+                    .map(|r| r.transaction)
+            }
+            LedgerOp::Refund { of, percent } => {
+                let Some(original) = pick_settled(&settled, *of) else { continue };
+                let amount = (original.amount * Decimal::from(*percent) / Decimal::from(100)).round_dp(2);
+                if amount <= Decimal::ZERO {
+                    continue;
+                }
+                ledger_service
+                    .process_refund(LedgerTransactionRequest::refund(
+                        format!("PROP-REF-{}", Uuid::new_v4()),
+                        original.id,
+                        original.destination_account_id,
+                        original.source_account_id,
+                        amount,
+                        fixture.currency.clone(),
+                        Uuid::new_v4().to_string(),
+                    ))
+                    .await
+                    .map(|r| r.transaction)
+            }
+            LedgerOp::Reversal { of } => {
+                let Some(original) = pick_settled(&settled, *of) else { continue };
+                if !can_cover(&balance_repo, &original).await {
+                    continue;
+                }
+                ledger_service
+                    .reverse_transaction(original.id, "property test", &Uuid::new_v4().to_string())
+                    .await
+                    .map(|r| r.transaction)
+            }
+        };
+
+        if let Ok(transaction) = result {
+            if transaction.status == TransactionStatus::Settled {
+                settled.push(transaction);
+            }
+        }
+    }
+
+    settled
+}
+
+async fn can_cover(balance_repo: &BalanceRepository, original: &TransactionRecord) -> bool {
+    match balance_repo
+        .find_by_account_and_currency(original.destination_account_id, &original.currency)
+        .await
+    {
+        Ok(Some(balance)) => balance.available_balance - balance.reserved_balance >= original.amount,
+        _ => false,
+    }
+}
+
+fn pick_settled(settled: &[TransactionRecord], index: usize) -> Option<TransactionRecord> {
+    if settled.is_empty() {
+        None
+    } else {
+        Some(settled[index % settled.len()].clone())
+    }
+}
+
+/// Every transaction's debit entries sum to its credit entries.
+pub async fn assert_entries_balanced(pool: &PgPool, transactions: &[TransactionRecord]) {
+    let ledger_repo = LedgerRepository::new(pool.clone());
+    for transaction in transactions {
+        let balanced = ledger_repo
+            .verify_transaction_balance(transaction.id)
+            .await
+            .expect("Failed to verify transaction balance");
+        assert!(balanced, "Transaction {} has unequal debits and credits", transaction.id);
+    }
+}
+
+/// No account's usable balance (available minus reserved) is negative.
+pub async fn assert_non_negative_balances(pool: &PgPool, fixture: &LedgerFixture) {
+    let balance_repo = BalanceRepository::new(pool.clone());
+    for account_id in &fixture.accounts {
+        let balance = balance_repo
+            .find_by_account_and_currency(*account_id, &fixture.currency)
+            .await
+            .expect("Failed to load balance")
+            .expect("Balance not found");
+        assert!(
+            balance.available_balance - balance.reserved_balance >= Decimal::ZERO,
+            "Account {} has negative usable balance {}",
+            account_id,
+            balance.available_balance - balance.reserved_balance
+        );
+    }
+}
+
+/// Money is neither created nor destroyed: the fixture's total balance is unchanged.
+pub async fn assert_money_conserved(pool: &PgPool, fixture: &LedgerFixture) {
+    let balance_repo = BalanceRepository::new(pool.clone());
+    let mut total = Decimal::ZERO;
+    for account_id in &fixture.accounts {
+        let balance = balance_repo
+            .find_by_account_and_currency(*account_id, &fixture.currency)
+            .await
+            .expect("Failed to load balance")
+            .expect("Balance not found");
+        total += balance.available_balance + balance.pending_balance;
+    }
+    assert_eq!(total, fixture.initial_total, "Total balance drifted from the funded amount");
+}
+
+/// Net positions sum to zero and gross receivables equal gross payables.
+pub fn assert_netting_balanced(result: &MultilateralNettingResult) {
+    let net: Decimal = result.positions.iter().map(|p| p.net_position).sum();
+    let receivable: Decimal = result.positions.iter().map(|p| p.gross_receivable).sum();
+    let payable: Decimal = result.positions.iter().map(|p| p.gross_payable).sum();
+
+    assert_eq!(net, Decimal::ZERO, "Net positions do not sum to zero");
+    assert_eq!(receivable, payable, "Gross receivables do not equal gross payables");
+}
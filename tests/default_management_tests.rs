@@ -0,0 +1,164 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{
+    AccountStatus, DefaultResolution, LossAllocationBasis, NettingPosition, StatusReasonCode,
+};
+use settlement_engine::services::{
+    AccountService, BatchService, DefaultManagementService, InstructionExportService, LedgerService,
+    LedgerTransactionRequest,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn net(positions: &[NettingPosition], participant: Uuid) -> Decimal {
+    positions.iter().find(|p| p.participant_id == participant).unwrap().net_position
+}
+
+/// Processes a batch where D owes A 600 and B 200, C owes B 300 and A owes C 100:
+/// A +500, B +500, C -200, D -800. Returns the batch and participants A to D.
+async fn completed_batch(pool: &PgPool, currency: &str) -> (Uuid, [Uuid; 4]) {
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Participant A", "Participant B", "Participant C", "Participant D"] {
+        let account = fixtures::account(currency).with_name(name).with_balance(dec!(1000)).create(pool).await;
+        accounts.push(account.id);
+    }
+    let (a, b, c, d) = (accounts[0], accounts[1], accounts[2], accounts[3]);
+
+    let batch = batch_service
+        .get_or_create_current_batch(currency)
+        .await
+        .expect("Failed to create batch");
+    for (from, to, amount) in [(d, a, dec!(600)), (d, b, dec!(200)), (c, b, dec!(300)), (a, c, dec!(100))] {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                from,
+                to,
+                amount,
+                currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+    batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    (batch.id, [a, b, c, d])
+}
+
+#[tokio::test]
+async fn test_default_excludes_participant_from_batch() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let (batch_id, [a, b, c, d]) = completed_batch(&pool, &currency).await;
+    let default_service = DefaultManagementService::new(pool.clone());
+
+    // Only net payers can default
+    let result = default_service.declare_default(batch_id, b, None, None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let report = default_service
+        .declare_default(batch_id, d, None, Some("Funding deadline missed".to_string()))
+        .await
+        .expect("Failed to declare default");
+    assert_eq!(report.resolution, DefaultResolution::Exclude);
+    assert_eq!(report.unfunded_amount, dec!(800));
+    assert_eq!(report.excluded_transactions.len(), 2);
+    assert!(report.allocations.is_empty());
+    assert_eq!(net(&report.original_positions, a), dec!(500));
+
+    // C -> B 300 and A -> C 100 are left
+    assert_eq!(net(&report.adjusted_positions, a), dec!(-100));
+    assert_eq!(net(&report.adjusted_positions, b), dec!(300));
+    assert_eq!(net(&report.adjusted_positions, c), dec!(-200));
+    assert_eq!(net(&report.adjusted_positions, d), dec!(0));
+    assert!(report
+        .replacement_instructions
+        .iter()
+        .all(|i| i.from_participant != d && i.to_participant != d));
+    let total: Decimal = report.replacement_instructions.iter().map(|i| i.amount).sum();
+    assert_eq!(total, dec!(300));
+
+    // Exports pay out the replacement instructions
+    let (_, instructions) = InstructionExportService::new(pool.clone())
+        .batch_instructions(batch_id)
+        .await
+        .expect("Failed to get instructions");
+    assert_eq!(instructions.len(), report.replacement_instructions.len());
+    assert!(instructions.iter().all(|i| i.to_participant == b));
+
+    let account_service = AccountService::new(pool.clone());
+    let account = account_service.find_by_id(d).await.expect("Failed to find account");
+    assert_eq!(account.status, AccountStatus::Frozen);
+    let history = account_service.get_status_history(d).await.expect("Failed to get history");
+    assert_eq!(history.last().unwrap().reason_code, StatusReasonCode::ParticipantDefault);
+
+    let result = default_service.declare_default(batch_id, d, None, None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let defaults = default_service.batch_defaults(batch_id).await.expect("Failed to list defaults");
+    assert_eq!(defaults.len(), 1);
+    assert_eq!(defaults[0].unfunded_amount, dec!(800));
+    assert_eq!(defaults[0].reason.as_deref(), Some("Funding deadline missed"));
+    let stored = default_service.default_report(batch_id, d).await.expect("Failed to get report");
+    assert_eq!(stored.adjusted_positions.len(), report.adjusted_positions.len());
+
+    let result = default_service.default_report(batch_id, a).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_default_shares_loss_pro_rata() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let (batch_id, [a, b, c, d]) = completed_batch(&pool, &currency).await;
+    let default_service = DefaultManagementService::new(pool.clone());
+
+    let report = default_service
+        .declare_default(batch_id, d, Some(DefaultResolution::LossSharing), None)
+        .await
+        .expect("Failed to declare default");
+
+    // A and B are each owed 500 net, so each bears 400
+    assert_eq!(report.loss_allocation, Some(LossAllocationBasis::NetCredit));
+    assert!(report.excluded_transactions.is_empty());
+    assert_eq!(report.allocations.len(), 2);
+    assert!(report.allocations.iter().all(|a| a.amount == dec!(400)));
+    assert_eq!(net(&report.adjusted_positions, a), dec!(100));
+    assert_eq!(net(&report.adjusted_positions, b), dec!(100));
+    assert_eq!(net(&report.adjusted_positions, c), dec!(-200));
+    assert_eq!(net(&report.adjusted_positions, d), dec!(0));
+
+    let positions = BatchService::new(pool.clone())
+        .get_batch_positions(batch_id)
+        .await
+        .expect("Failed to get positions");
+    assert_eq!(net(&positions, a), dec!(100));
+    assert_eq!(positions.iter().map(|p| p.net_position).sum::<Decimal>(), dec!(0));
+
+    // C is now the only payer; it cannot leave the others short
+    let report = default_service
+        .declare_default(batch_id, c, None, None)
+        .await
+        .expect("Failed to declare default");
+    assert_eq!(report.unfunded_amount, dec!(200));
+    assert!(report.replacement_instructions.iter().all(|i| i.from_participant != c));
+    assert_eq!(
+        report.adjusted_positions.iter().map(|p| p.net_position).sum::<Decimal>(),
+        dec!(0)
+    );
+}
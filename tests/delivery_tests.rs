@@ -0,0 +1,127 @@
+mod common;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use settlement_engine::delivery::{DeliveryChannels, DeliveryTransport, DirectoryTransport};
+use settlement_engine::error::{AppError, Result};
+use settlement_engine::models::file_delivery::sha256_hex;
+use settlement_engine::models::DeliveryStatus;
+use settlement_engine::services::DeliveryService;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Transport that rejects every upload.
+struct UnreachableTransport {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl DeliveryTransport for UnreachableTransport {
+    fn name(&self) -> &str {
+        "unreachable"
+    }
+
+    async fn deliver(&self, _file_name: &str, _content: &[u8], _checksum: &str) -> Result<()> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Err(AppError::Internal(anyhow::anyhow!("connection refused")))
+    }
+}
+
+#[tokio::test]
+async fn test_file_delivery_lifecycle() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let drop_dir = std::env::temp_dir().join(format!("delivery-test-{}", Uuid::new_v4()));
+    let unreachable = Arc::new(UnreachableTransport { calls: AtomicUsize::new(0) });
+    let channels = Arc::new(
+        DeliveryChannels::new()
+            .with_destination("bank", Arc::new(DirectoryTransport::new(&drop_dir)))
+            .with_destination("offline", unreachable.clone())
+            .with_retry_policy(2, Duration::zero()),
+    );
+    let service = DeliveryService::new(pool.clone()).with_channels(channels);
+
+    // Unknown destinations and unsafe file names are rejected up front
+    let result = service.enqueue("nowhere", "a.txt", b"x".to_vec(), None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let result = service.enqueue("bank", "../a.txt", b"x".to_vec(), None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let unconfigured = DeliveryService::new(pool.clone());
+    let result = unconfigured.enqueue("bank", "a.txt", b"x".to_vec(), None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let content = b"101 091000019 1234567890".to_vec();
+    let delivered = service
+        .enqueue("bank", "batch.ach", content.clone(), None)
+        .await
+        .expect("Failed to enqueue");
+    assert_eq!(delivered.status, DeliveryStatus::Pending);
+    assert_eq!(delivered.checksum_sha256, sha256_hex(&content));
+
+    let failing = service
+        .enqueue("offline", "statement.xml", b"<Document/>".to_vec(), None)
+        .await
+        .expect("Failed to enqueue");
+    let scheduled = service
+        .enqueue("bank", "later.ach", b"later".to_vec(), Some(Utc::now() + Duration::hours(1)))
+        .await
+        .expect("Failed to enqueue");
+
+    // First run: the directory upload succeeds, the offline one is retried later
+    assert_eq!(service.process_due().await.expect("Failed to process"), 1);
+
+    let delivered = service.get_delivery(delivered.id).await.unwrap();
+    assert_eq!(delivered.status, DeliveryStatus::Delivered);
+    assert_eq!(delivered.attempts, 1);
+    assert!(delivered.delivered_at.is_some());
+    let written = std::fs::read(drop_dir.join("batch.ach")).expect("File not delivered");
+    assert_eq!(written, content);
+    assert!(!drop_dir.join("batch.ach.part").exists());
+
+    let failing_after_first = service.get_delivery(failing.id).await.unwrap();
+    assert_eq!(failing_after_first.status, DeliveryStatus::Pending);
+    assert_eq!(failing_after_first.attempts, 1);
+    assert!(failing_after_first.last_error.as_deref().unwrap().contains("connection refused"));
+
+    // Retrying a delivery that has not failed is rejected
+    let result = service.retry_delivery(failing.id).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    // Second run exhausts the attempts
+    assert_eq!(service.process_due().await.unwrap(), 0);
+    let exhausted = service.get_delivery(failing.id).await.unwrap();
+    assert_eq!(exhausted.status, DeliveryStatus::Failed);
+    assert_eq!(exhausted.attempts, 2);
+    assert_eq!(unreachable.calls.load(Ordering::SeqCst), 2);
+
+    // Failed deliveries are not attempted again until requeued
+    service.process_due().await.unwrap();
+    assert_eq!(unreachable.calls.load(Ordering::SeqCst), 2);
+
+    let requeued = service.retry_delivery(failing.id).await.expect("Failed to retry");
+    assert_eq!(requeued.status, DeliveryStatus::Pending);
+    assert_eq!(requeued.attempts, 0);
+    service.process_due().await.unwrap();
+    assert_eq!(unreachable.calls.load(Ordering::SeqCst), 3);
+
+    // The scheduled delivery is not due yet
+    let scheduled = service.get_delivery(scheduled.id).await.unwrap();
+    assert_eq!(scheduled.status, DeliveryStatus::Pending);
+    assert_eq!(scheduled.attempts, 0);
+    assert!(!drop_dir.join("later.ach").exists());
+
+    let listed = service
+        .list_deliveries(Some(DeliveryStatus::Delivered), 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, delivered.id);
+
+    let result = service.get_delivery(Uuid::new_v4()).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+
+    let _ = std::fs::remove_dir_all(&drop_dir);
+    common::cleanup_test_data(&pool).await;
+}
@@ -16,14 +16,6 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use uuid::Uuid;
 
-fn get_kafka_brokers() -> Vec<String> {
-    std::env::var("KAFKA_BROKERS")
-        .unwrap_or_else(|_| "localhost:9092".to_string())
-        .split(',')
-        .map(|s| s.to_string())
-        .collect()
-}
-
 fn unique_topic() -> String {
     format!("test.{}", Uuid::new_v4().to_string().replace("-", "")[..8].to_string())
 }
@@ -98,6 +90,9 @@ async fn test_batch_event_creation() {
         fee_amount: dec!(100),
         created_at: Utc::now(),
         completed_at: Some(Utc::now()),
+        sequence_number: 1,
+        cut_off_time: None,
+        netting: None,
     };
 
     let envelope = EventEnvelope::new(EventType::BatchCompleted, event)
@@ -251,7 +246,7 @@ async fn test_message_handler_trait() {
 #[tokio::test]
 #[ignore = "Requires running Kafka"]
 async fn test_producer_connect_and_send() {
-    let brokers = get_kafka_brokers();
+    let brokers = common::kafka_brokers();
     let topic = unique_topic();
 
     let mut producer = EventProducer::new(ProducerConfig {
@@ -290,7 +285,7 @@ async fn test_producer_connect_and_send() {
 #[tokio::test]
 #[ignore = "Requires running Kafka"]
 async fn test_producer_send_batch() {
-    let brokers = get_kafka_brokers();
+    let brokers = common::kafka_brokers();
     let topic = unique_topic();
 
     let mut producer = EventProducer::new(ProducerConfig {
@@ -332,7 +327,7 @@ async fn test_producer_send_batch() {
 #[tokio::test]
 #[ignore = "Requires running Kafka"]
 async fn test_consumer_connect_and_poll() {
-    let brokers = get_kafka_brokers();
+    let brokers = common::kafka_brokers();
     let topic = unique_topic();
 
     // First, produce a message
@@ -386,7 +381,7 @@ async fn test_consumer_connect_and_poll() {
 #[tokio::test]
 #[ignore = "Requires running Kafka"]
 async fn test_end_to_end_event_flow() {
-    let brokers = get_kafka_brokers();
+    let brokers = common::kafka_brokers();
     let topic = unique_topic();
 
     // Producer
@@ -412,6 +407,9 @@ async fn test_end_to_end_event_flow() {
         fee_amount: dec!(0),
         created_at: Utc::now(),
         completed_at: None,
+        sequence_number: 1,
+        cut_off_time: None,
+        netting: None,
     };
     producer.send(&topic, Some(&batch_id.to_string()), 
         &EventEnvelope::new(EventType::BatchCreated, batch_event)).await.unwrap();
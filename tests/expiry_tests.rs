@@ -0,0 +1,134 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::models::{
+    SubmissionStatus, TransactionAuditAction, TransactionHold, TransactionRecord, TransactionStatus,
+    TransactionSubmission, TransactionType,
+};
+use settlement_engine::repositories::{
+    BalanceRepository, SubmissionRepository, TransactionAuditRepository, TransactionHoldRepository,
+    TransactionRepository,
+};
+use settlement_engine::services::{ExpiryPolicy, ExpiryService};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn policy() -> ExpiryPolicy {
+    ExpiryPolicy {
+        default_ttl_secs: 0,
+        ttl_secs: HashMap::from([("PAYMENT".to_string(), 3600), ("TRANSFER".to_string(), 0)]),
+    }
+}
+
+/// Sweeps until nothing is left to expire, so rows left over by other runs cannot
+/// crowd this test's rows out of a batch.
+async fn sweep(service: &ExpiryService) {
+    while service.expire_due().await.expect("Expiry sweep failed").total() > 0 {}
+}
+
+async fn pending(
+    pool: &PgPool,
+    transaction_type: TransactionType,
+    age: Duration,
+    accounts: (Uuid, Uuid),
+    currency: &str,
+) -> TransactionRecord {
+    let mut record = TransactionRecord::new(
+        format!("EXP-{}", Uuid::new_v4()),
+        transaction_type,
+        accounts.0,
+        accounts.1,
+        dec!(40),
+        currency.to_string(),
+        dec!(0),
+        Uuid::new_v4().to_string(),
+    );
+    record.created_at = Utc::now() - age;
+    TransactionRepository::new(pool.clone())
+        .create(&record)
+        .await
+        .expect("Failed to create transaction")
+}
+
+#[tokio::test]
+async fn test_stale_pending_transactions_expire_and_release_holds() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let accounts = fixtures::payer_and_payee(&pool, &currency, dec!(100)).await;
+
+    let stale = pending(&pool, TransactionType::Payment, Duration::hours(2), accounts, &currency).await;
+    let fresh = pending(&pool, TransactionType::Payment, Duration::minutes(5), accounts, &currency).await;
+    let never = pending(&pool, TransactionType::Transfer, Duration::days(3), accounts, &currency).await;
+
+    let holds = TransactionHoldRepository::new(pool.clone());
+    holds
+        .place(&TransactionHold::new(stale.id, accounts.0, &currency, dec!(40)))
+        .await
+        .expect("Failed to place hold");
+    let balances = BalanceRepository::new(pool.clone());
+    let held = balances.find_by_account_and_currency(accounts.0, &currency).await.unwrap().unwrap();
+    assert_eq!(held.available_balance, dec!(60));
+    assert_eq!(held.reserved_balance, dec!(40));
+
+    sweep(&ExpiryService::new(pool.clone()).with_policy(policy())).await;
+
+    let transactions = TransactionRepository::new(pool.clone());
+    let expired = transactions.find_by_id(stale.id).await.unwrap().unwrap();
+    assert_eq!(expired.status, TransactionStatus::Expired);
+    assert_eq!(transactions.find_by_id(fresh.id).await.unwrap().unwrap().status, TransactionStatus::Pending);
+    assert_eq!(transactions.find_by_id(never.id).await.unwrap().unwrap().status, TransactionStatus::Pending);
+
+    let released = balances.find_by_account_and_currency(accounts.0, &currency).await.unwrap().unwrap();
+    assert_eq!(released.available_balance, dec!(100));
+    assert_eq!(released.reserved_balance, dec!(0));
+    assert!(holds.find_by_transaction(stale.id).await.unwrap().unwrap().released_at.is_some());
+
+    let audit = TransactionAuditRepository::new(pool.clone())
+        .find_by_transaction(stale.id)
+        .await
+        .expect("Failed to load audit log");
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].action, TransactionAuditAction::Expired);
+    assert_eq!(audit[0].detail["ttl_secs"], 3600);
+
+    let (event_type,): (String,) =
+        sqlx::query_as("SELECT payload->>'event_type' FROM event_outbox WHERE partition_key = $1")
+    .bind(stale.id.to_string())
+    .fetch_one(&pool)
+    .await
+    .expect("Expiry event should be queued");
+    assert_eq!(event_type, "TRANSACTION_EXPIRED");
+}
+
+#[tokio::test]
+async fn test_stale_queued_submissions_expire() {
+    let pool = common::setup_test_db().await;
+    let repo = SubmissionRepository::new(pool.clone());
+
+    let mut ids = Vec::new();
+    for transaction_type in ["PAYMENT", "TRANSFER"] {
+        let submission = TransactionSubmission::new(
+            Uuid::new_v4().to_string(),
+            serde_json::json!({ "transaction_type": transaction_type }),
+            3,
+        );
+        let created = repo.create(&submission).await.unwrap().expect("Submission should be queued");
+        sqlx::query("UPDATE transaction_submissions SET created_at = NOW() - INTERVAL '2 hours' WHERE id = $1")
+            .bind(created.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        ids.push(created.id);
+    }
+
+    sweep(&ExpiryService::new(pool.clone()).with_policy(policy())).await;
+
+    let payment = repo.find_by_id(ids[0]).await.unwrap().unwrap();
+    assert_eq!(payment.status, SubmissionStatus::Expired);
+    assert_eq!(payment.error_code.as_deref(), Some("EXPIRED"));
+    assert!(payment.completed_at.is_some());
+    assert_eq!(repo.find_by_id(ids[1]).await.unwrap().unwrap().status, SubmissionStatus::Queued);
+}
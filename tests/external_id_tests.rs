@@ -0,0 +1,127 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{DuplicateExternalIdAction, ExternalIdScope};
+use settlement_engine::services::{ExternalIdPolicy, LedgerService, LedgerTransactionRequest};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_duplicate_external_id_rejected() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let (payer, payee) = fixtures::payer_and_payee(&pool, &currency, dec!(1000)).await;
+    let ledger = LedgerService::new(pool.clone());
+    let external_id = format!("PAY-{}", Uuid::new_v4());
+    let idempotency_key = Uuid::new_v4().to_string();
+    let payment = |idempotency_key: &str| {
+        LedgerTransactionRequest::payment(&external_id, payer, payee, dec!(25), &currency, idempotency_key)
+    };
+
+    let first = ledger
+        .process_payment(payment(&idempotency_key))
+        .await
+        .expect("Failed to process payment");
+
+    // A retry under the same idempotency key is not a duplicate
+    let retried = ledger
+        .process_payment(payment(&idempotency_key))
+        .await
+        .expect("Retry should return the original");
+    assert_eq!(retried.transaction.id, first.transaction.id);
+
+    let duplicate = ledger.process_payment(payment(&Uuid::new_v4().to_string())).await;
+    assert!(matches!(duplicate, Err(AppError::Validation(ref message)) if message.contains("already used")));
+
+    let found = ledger
+        .find_by_external_id(&external_id, None)
+        .await
+        .expect("Failed to look up external ID");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, first.transaction.id);
+}
+
+#[tokio::test]
+async fn test_duplicates_linked_per_source_system() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let (payer, payee) = fixtures::payer_and_payee(&pool, &currency, dec!(1000)).await;
+    let ledger = LedgerService::new(pool.clone()).with_external_ids(ExternalIdPolicy {
+        scope: ExternalIdScope::SourceSystem,
+        on_duplicate: DuplicateExternalIdAction::Link,
+    });
+    let external_id = format!("PAY-{}", Uuid::new_v4());
+    let payment = |source_system: &str| {
+        LedgerTransactionRequest::payment(&external_id, payer, payee, dec!(10), &currency, Uuid::new_v4().to_string())
+            .with_source_system(source_system)
+    };
+
+    let cards = ledger.process_payment(payment("CARDS")).await.expect("Failed to process payment");
+    let wires = ledger.process_payment(payment("WIRES")).await.expect("Failed to process payment");
+    assert!(cards.transaction.resubmission_of.is_none());
+    assert!(wires.transaction.resubmission_of.is_none());
+
+    let resubmitted = ledger
+        .process_payment(payment("CARDS"))
+        .await
+        .expect("A duplicate should be accepted as a resubmission");
+    assert_eq!(resubmitted.transaction.resubmission_of, Some(cards.transaction.id));
+    assert!(resubmitted.transaction.dedupe_key.is_none());
+
+    let all = ledger
+        .find_by_external_id(&external_id, None)
+        .await
+        .expect("Failed to look up external ID");
+    assert_eq!(all.len(), 3);
+    let from_cards = ledger
+        .find_by_external_id(&external_id, Some("CARDS"))
+        .await
+        .expect("Failed to look up external ID");
+    let ids: Vec<Uuid> = from_cards.iter().map(|transaction| transaction.id).collect();
+    assert_eq!(ids, vec![cards.transaction.id, resubmitted.transaction.id]);
+}
+
+#[tokio::test]
+async fn test_lookup_by_upstream_reference() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let (payer, payee) = fixtures::payer_and_payee(&pool, &currency, dec!(1000)).await;
+    let ledger = LedgerService::new(pool.clone());
+    let reference = format!("Ref-{}", Uuid::new_v4().simple());
+
+    let payment = ledger
+        .process_payment(LedgerTransactionRequest::payment(
+            &reference,
+            payer,
+            payee,
+            dec!(40),
+            &currency,
+            Uuid::new_v4().to_string(),
+        ))
+        .await
+        .expect("Failed to process payment");
+
+    // Partners echo references back in their own casing
+    let found = ledger
+        .find_by_external_id(&format!(" {} ", reference.to_uppercase()), None)
+        .await
+        .expect("Failed to look up reference");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, payment.transaction.id);
+    assert!(ledger.find_returns(&found).await.unwrap().is_empty());
+
+    let reversal = ledger
+        .reverse_transaction(payment.transaction.id, "customer cancelled", &Uuid::new_v4().to_string())
+        .await
+        .expect("Failed to reverse");
+    let returns = ledger.find_returns(&found).await.expect("Failed to look up returns");
+    assert_eq!(returns.len(), 1);
+    assert_eq!(returns[0].id, reversal.transaction.id);
+
+    let missing = ledger
+        .find_by_external_id(&format!("Ref-{}", Uuid::new_v4()), None)
+        .await
+        .expect("Failed to look up reference");
+    assert!(missing.is_empty());
+}
@@ -0,0 +1,283 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{AccountType, BatchStatus, FeeBookingMode, FeeReversalPolicy, TransactionType};
+use settlement_engine::repositories::BalanceRepository;
+use settlement_engine::services::{
+    BatchService, CreateBatchRequest, FeeConfig, FeeSettlementService, LedgerService,
+    LedgerTransactionRequest,
+};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+struct FeeFixture {
+    currency: String,
+    payer: Uuid,
+    merchant: Uuid,
+    revenue: Uuid,
+    ledger: LedgerService,
+}
+
+async fn fixture(pool: &PgPool, policy: FeeReversalPolicy) -> FeeFixture {
+    let currency = unique_currency();
+    let mut ids = Vec::new();
+    for (prefix, account_type, balance) in [
+        ("PAYER", AccountType::Asset, dec!(1000)),
+        ("MERCHANT", AccountType::Liability, dec!(0)),
+        ("FEES", AccountType::Revenue, dec!(0)),
+    ] {
+        let account = fixtures::account(&currency)
+            .with_name(prefix)
+            .with_type(account_type)
+            .with_balance(balance)
+            .create(pool)
+            .await;
+        ids.push(account.id);
+    }
+
+    let fees = FeeConfig {
+        revenue_accounts: HashMap::from([(currency.clone(), ids[2])]),
+        reversal_policy: policy,
+        ..FeeConfig::default()
+    };
+    FeeFixture {
+        ledger: LedgerService::new(pool.clone()).with_fees(Arc::new(fees)),
+        currency,
+        payer: ids[0],
+        merchant: ids[1],
+        revenue: ids[2],
+    }
+}
+
+async fn pay(fixture: &FeeFixture, amount: Decimal, fee: Decimal) -> Uuid {
+    let result = fixture
+        .ledger
+        .process_payment(
+            LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                fixture.payer,
+                fixture.merchant,
+                amount,
+                &fixture.currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            )
+            .with_fee(fee),
+        )
+        .await
+        .expect("Failed to process payment");
+    result.transaction.id
+}
+
+async fn balance(pool: &PgPool, account_id: Uuid, currency: &str) -> Decimal {
+    BalanceRepository::new(pool.clone())
+        .find_by_account_and_currency(account_id, currency)
+        .await
+        .expect("Failed to load balance")
+        .expect("Balance not found")
+        .available_balance
+}
+
+#[tokio::test]
+async fn test_fee_leg_booked_and_retained_on_reversal() {
+    let pool = common::setup_test_db().await;
+    let fixture = fixture(&pool, FeeReversalPolicy::Retain).await;
+
+    let payment = fixture
+        .ledger
+        .process_payment(
+            LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                fixture.payer,
+                fixture.merchant,
+                dec!(100),
+                &fixture.currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            )
+            .with_fee(dec!(3)),
+        )
+        .await
+        .expect("Failed to process payment");
+    assert_eq!(payment.entries.len(), 3);
+    let fee_entry = payment.entries.iter().find(|entry| entry.is_fee_leg()).expect("Fee leg should be booked");
+    assert_eq!(fee_entry.account_id, fixture.revenue);
+    assert_eq!(fee_entry.amount, dec!(3));
+    assert!(fixture.ledger.verify_transaction_balance(payment.transaction.id).await.unwrap());
+    assert_eq!(balance(&pool, fixture.revenue, &fixture.currency).await, dec!(3));
+
+    let reversal = fixture
+        .ledger
+        .reverse_transaction(payment.transaction.id, "customer cancelled", &Uuid::new_v4().to_string())
+        .await
+        .expect("Failed to reverse");
+    assert_eq!(reversal.transaction.amount, dec!(97));
+    assert_eq!(reversal.transaction.metadata.as_ref().unwrap()["fee_policy"], "RETAIN");
+    assert!(fixture.ledger.verify_transaction_balance(reversal.transaction.id).await.unwrap());
+
+    assert_eq!(balance(&pool, fixture.payer, &fixture.currency).await, dec!(997));
+    assert_eq!(balance(&pool, fixture.merchant, &fixture.currency).await, dec!(0));
+    assert_eq!(balance(&pool, fixture.revenue, &fixture.currency).await, dec!(3));
+}
+
+#[tokio::test]
+async fn test_fee_reversed_with_payment() {
+    let pool = common::setup_test_db().await;
+    let fixture = fixture(&pool, FeeReversalPolicy::Retain).await;
+    let payment_id = pay(&fixture, dec!(200), dec!(4)).await;
+
+    // The request overrides the configured policy
+    let reversal = fixture
+        .ledger
+        .reverse_transaction_with(
+            payment_id,
+            "duplicate charge",
+            &Uuid::new_v4().to_string(),
+            Some(FeeReversalPolicy::Reverse),
+        )
+        .await
+        .expect("Failed to reverse");
+    assert_eq!(reversal.transaction.amount, dec!(200));
+    assert_eq!(reversal.transaction.metadata.as_ref().unwrap()["fee_policy"], "REVERSE");
+    let fee_debit = reversal.entries.iter().find(|entry| entry.is_fee_leg()).expect("Fee leg should be reversed");
+    assert_eq!(fee_debit.account_id, fixture.revenue);
+    assert_eq!(fee_debit.amount, dec!(4));
+    assert!(fixture.ledger.verify_transaction_balance(reversal.transaction.id).await.unwrap());
+
+    assert_eq!(balance(&pool, fixture.payer, &fixture.currency).await, dec!(1000));
+    assert_eq!(balance(&pool, fixture.merchant, &fixture.currency).await, dec!(0));
+    assert_eq!(balance(&pool, fixture.revenue, &fixture.currency).await, dec!(0));
+
+    // Nothing of the fee is left to refund
+    let refund = fixture
+        .ledger
+        .refund_fee(payment_id, None, "goodwill", &Uuid::new_v4().to_string())
+        .await;
+    assert!(matches!(refund, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_partial_fee_refunds() {
+    let pool = common::setup_test_db().await;
+    let fixture = fixture(&pool, FeeReversalPolicy::Reverse).await;
+    let payment_id = pay(&fixture, dec!(100), dec!(5)).await;
+
+    let refund = fixture
+        .ledger
+        .refund_fee(payment_id, Some(dec!(2)), "goodwill", &Uuid::new_v4().to_string())
+        .await
+        .expect("Failed to refund fee");
+    assert_eq!(refund.transaction.transaction_type, TransactionType::Refund);
+    assert_eq!(refund.transaction.source_account_id, fixture.revenue);
+    assert_eq!(refund.transaction.destination_account_id, fixture.payer);
+    assert_eq!(refund.transaction.metadata.as_ref().unwrap()["original_transaction_id"], payment_id.to_string());
+    assert!(fixture.ledger.verify_transaction_balance(refund.transaction.id).await.unwrap());
+
+    let over_refund = fixture
+        .ledger
+        .refund_fee(payment_id, Some(dec!(4)), "goodwill", &Uuid::new_v4().to_string())
+        .await;
+    assert!(matches!(over_refund, Err(AppError::Validation(_))));
+
+    // Reversing under the configured policy only takes back what is left of the fee
+    let reversal = fixture
+        .ledger
+        .reverse_transaction(payment_id, "cancelled", &Uuid::new_v4().to_string())
+        .await
+        .expect("Failed to reverse");
+    assert_eq!(reversal.transaction.amount, dec!(98));
+    assert_eq!(balance(&pool, fixture.payer, &fixture.currency).await, dec!(1000));
+    assert_eq!(balance(&pool, fixture.revenue, &fixture.currency).await, dec!(0));
+
+    let no_fee_id = pay(&fixture, dec!(10), dec!(0)).await;
+    let no_fee = fixture
+        .ledger
+        .refund_fee(no_fee_id, None, "goodwill", &Uuid::new_v4().to_string())
+        .await;
+    assert!(matches!(no_fee, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_accrued_fees_settled_per_payer_at_batch_completion() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let first_payer = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let second_payer = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let merchant = fixtures::account(&currency).with_type(AccountType::Liability).with_balance(dec!(0)).create(&pool).await;
+    let revenue = fixtures::account(&currency).with_type(AccountType::Revenue).with_balance(dec!(0)).create(&pool).await;
+    let receivable = fixtures::account(&currency).with_balance(dec!(0)).create(&pool).await;
+    let fees = Arc::new(FeeConfig {
+        revenue_accounts: HashMap::from([(currency.clone(), revenue.id)]),
+        reversal_policy: FeeReversalPolicy::Reverse,
+        booking: FeeBookingMode::Accrued,
+        receivable_accounts: HashMap::from([(currency.clone(), receivable.id)]),
+        ..FeeConfig::default()
+    });
+    let ledger = LedgerService::new(pool.clone()).with_fees(fees.clone());
+    let batch_service = BatchService::new(pool.clone()).with_fees(fees.clone());
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+
+    let mut payments = Vec::new();
+    let orders = [(&first_payer, dec!(100), dec!(3)), (&first_payer, dec!(50), dec!(1)), (&second_payer, dec!(80), dec!(2))];
+    for (payer, amount, fee) in orders {
+        let payment = ledger
+            .process_payment(
+                LedgerTransactionRequest::payment(
+                    format!("PAY-{}", Uuid::new_v4()),
+                    payer.id,
+                    merchant.id,
+                    amount,
+                    &currency,
+                    format!("IDEM-{}", Uuid::new_v4()),
+                )
+                .with_fee(fee),
+            )
+            .await
+            .expect("Failed to process payment");
+        let fee_entry = payment.entries.iter().find(|entry| entry.is_fee_leg()).expect("Fee leg should be booked");
+        assert_eq!(fee_entry.account_id, receivable.id, "fees accrue until the batch settles");
+        batch_service
+            .assign_transaction_to_batch(payment.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+        payments.push(payment.transaction.id);
+    }
+    assert_eq!(balance(&pool, receivable.id, &currency).await, dec!(6));
+    assert_eq!(balance(&pool, revenue.id, &currency).await, dec!(0));
+
+    batch_service.process_batch(batch.id).await.expect("Failed to process batch");
+    assert_eq!(batch_service.get_batch(batch.id).await.unwrap().status, BatchStatus::Completed);
+    assert_eq!(balance(&pool, receivable.id, &currency).await, dec!(0));
+    assert_eq!(balance(&pool, revenue.id, &currency).await, dec!(6));
+
+    let fee_service = FeeSettlementService::new(pool.clone()).with_fees(fees.clone());
+    let report = fee_service.get_fee_report(batch.id, None).await.expect("Failed to get fee report");
+    assert_eq!(report.len(), 2);
+    let first = report.iter().find(|s| s.account_id == first_payer.id).expect("First payer not settled");
+    assert_eq!((first.accrued_amount, first.net_amount, first.fee_count), (dec!(4), dec!(4), 2));
+    let instruction = first.transaction_id.expect("Fee instruction not posted");
+    assert!(ledger.verify_transaction_balance(instruction).await.unwrap());
+
+    // Settling again picks up only fees reversed since, netted back out of revenue
+    assert!(fee_service.settle_batch(batch.id).await.expect("Failed to settle").is_empty());
+    ledger
+        .reverse_transaction(payments[2], "cancelled", &Uuid::new_v4().to_string())
+        .await
+        .expect("Failed to reverse");
+    assert_eq!(balance(&pool, receivable.id, &currency).await, dec!(-2));
+    let resettled = fee_service.settle_batch(batch.id).await.expect("Failed to settle");
+    assert_eq!(resettled.len(), 1);
+    assert_eq!(resettled[0].account_id, second_payer.id);
+    assert_eq!((resettled[0].reversed_amount, resettled[0].net_amount), (dec!(2), dec!(-2)));
+    assert_eq!(balance(&pool, receivable.id, &currency).await, dec!(0));
+    assert_eq!(balance(&pool, revenue.id, &currency).await, dec!(4));
+
+    let second_report = fee_service.get_fee_report(batch.id, Some(second_payer.id)).await.unwrap();
+    assert_eq!(second_report.iter().map(|s| s.net_amount).sum::<Decimal>(), dec!(0));
+}
@@ -0,0 +1,219 @@
+mod common;
+
+use common::fixtures;
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{AccountType, FxRate, InternalAccountDefinition, InternalAccountRole, ProvisioningTrigger};
+use settlement_engine::services::{
+    AccountService, ChartOfAccountsService, FxConversionRequest, FxRevaluationConfig,
+    FxRevaluationService, FxService,
+};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_conversions_keep_their_rate_and_are_revalued_on_correction() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let chart = ChartOfAccountsService::new(pool.clone());
+    chart
+        .provision(
+            &[
+                InternalAccountDefinition::new("FX-POSITION", "FX position", "EUR", Some(InternalAccountRole::FxPosition)),
+                InternalAccountDefinition::new("FX-POSITION", "FX position", "USD", Some(InternalAccountRole::FxPosition)),
+            ],
+            ProvisioningTrigger::Startup,
+        )
+        .await
+        .expect("Failed to provision FX positions");
+    let account_service = AccountService::new(pool.clone());
+    let mut accounts = Vec::new();
+    for (currency, balance) in [("EUR", dec!(5000)), ("USD", dec!(0))] {
+        let account = fixtures::account(currency)
+            .with_name(&format!("Customer {}", currency))
+            .with_balance(balance)
+            .create(&pool)
+            .await;
+        accounts.push(account.id);
+    }
+
+    let fx = FxService::new(pool.clone());
+    let stale = fx
+        .record_rate(FxRate::new("EUR", "USD", dec!(1.05), dec!(0), "FEED", Utc::now() - Duration::hours(2)))
+        .await
+        .expect("Failed to record rate");
+    let rate = fx
+        .record_rate(FxRate::new("EUR", "USD", dec!(1.08), dec!(0.001), "FEED", Utc::now() - Duration::hours(1)))
+        .await
+        .expect("Failed to record rate");
+
+    // The conversion is made at the latest rate less its spread and keeps its ID
+    let request = FxConversionRequest {
+        external_id: format!("FX-{}", Uuid::new_v4()),
+        idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
+        source_account_id: accounts[0],
+        destination_account_id: accounts[1],
+        amount: dec!(1000),
+        source_currency: "EUR".to_string(),
+        destination_currency: "USD".to_string(),
+    };
+    let conversion = fx.convert(request.clone()).await.expect("Failed to convert");
+    assert_eq!(conversion.rate_id, rate.id);
+    assert_eq!(conversion.destination_amount, dec!(1078.92));
+    let retry = fx.convert(request).await.expect("Failed to retry conversion");
+    assert_eq!(retry.id, conversion.id);
+    let usd = account_service.get_balance(accounts[1], "USD").await.expect("Failed to get balance");
+    assert_eq!(usd.available_balance, dec!(1078.92));
+
+    // Rates are listed for audit by pair and date
+    let rates = fx
+        .list_rates(Some("EUR/USD"), Some(rate.effective_at.date_naive()))
+        .await
+        .expect("Failed to list rates");
+    assert!(rates.iter().any(|listed| listed.id == rate.id));
+    assert!(matches!(fx.list_rates(Some("EURO"), None).await, Err(AppError::Validation(_))));
+
+    // A backdated correction supersedes the rate and credits the difference
+    let correction = fx
+        .correct_rate(rate.id, dec!(1.09), None, None, "Feed published a stale fixing")
+        .await
+        .expect("Failed to correct rate");
+    assert_eq!(correction.rate.corrects_rate_id, Some(rate.id));
+    assert_eq!(correction.rate.effective_at, rate.effective_at);
+    assert_eq!(correction.adjustments.len(), 1);
+    assert_eq!(correction.adjustments[0].amount, dec!(9.99));
+    let usd = account_service.get_balance(accounts[1], "USD").await.expect("Failed to get balance");
+    assert_eq!(usd.available_balance, dec!(1088.91));
+    let usd_position = chart
+        .role_account(InternalAccountRole::FxPosition, "USD")
+        .await
+        .unwrap()
+        .expect("No USD FX position");
+    let position = account_service
+        .get_balance(usd_position, "USD")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(position.available_balance, dec!(-1088.91));
+
+    // The conversion keeps the rate it was made at and is now valued at the correction
+    let revalued = fx.get_conversion(conversion.id).await.expect("Failed to get conversion");
+    assert_eq!(revalued.rate_id, rate.id);
+    assert_eq!(revalued.current_rate_id, correction.rate.id);
+    assert_eq!(fx.current_rate("EUR", "USD").await.unwrap().id, correction.rate.id);
+
+    // Only the latest correction of a rate can be corrected
+    let result = fx.correct_rate(rate.id, dec!(1.07), None, None, "Again").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let unused = fx
+        .correct_rate(stale.id, dec!(1.06), None, None, "Typo")
+        .await
+        .expect("Failed to correct unused rate");
+    assert!(unused.adjustments.is_empty());
+
+    common::cleanup_test_data(&pool).await;
+}
+
+#[tokio::test]
+async fn test_revaluation_books_unrealized_gain_once_per_period() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let chart = ChartOfAccountsService::new(pool.clone());
+    chart
+        .provision(
+            &[
+                InternalAccountDefinition::new("FX-POSITION", "FX position", "EUR", Some(InternalAccountRole::FxPosition)),
+                InternalAccountDefinition::new("FX-POSITION", "FX position", "USD", Some(InternalAccountRole::FxPosition)),
+            ],
+            ProvisioningTrigger::Startup,
+        )
+        .await
+        .expect("Failed to provision FX positions");
+    let account_service = AccountService::new(pool.clone());
+    let mut accounts = Vec::new();
+    for (currency, account_type, balance) in [
+        ("EUR", AccountType::Asset, dec!(5000)),
+        ("USD", AccountType::Asset, dec!(0)),
+        ("USD", AccountType::Revenue, dec!(0)),
+    ] {
+        let account = fixtures::account(currency)
+            .with_name(&format!("Account {}", currency))
+            .with_type(account_type)
+            .with_balance(balance)
+            .create(&pool)
+            .await;
+        accounts.push(account.id);
+    }
+    let pnl = accounts[2];
+
+    // The engine goes long 1000 EUR three days ago
+    let fx = FxService::new(pool.clone());
+    fx.record_rate(FxRate::new("EUR", "USD", dec!(1.08), dec!(0), "FEED", Utc::now() - Duration::days(3)))
+        .await
+        .expect("Failed to record rate");
+    fx.convert(FxConversionRequest {
+        external_id: format!("FX-{}", Uuid::new_v4()),
+        idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
+        source_account_id: accounts[0],
+        destination_account_id: accounts[1],
+        amount: dec!(1000),
+        source_currency: "EUR".to_string(),
+        destination_currency: "USD".to_string(),
+    })
+    .await
+    .expect("Failed to convert");
+    sqlx::query("UPDATE ledger_entries SET created_at = created_at - INTERVAL '3 days'")
+        .execute(&pool)
+        .await
+        .expect("Failed to backdate entries");
+    let eur_position = chart
+        .role_account(InternalAccountRole::FxPosition, "EUR")
+        .await
+        .unwrap()
+        .expect("No EUR FX position");
+
+    let unconfigured = FxRevaluationService::new(pool.clone());
+    let today = Utc::now().date_naive();
+    let result = unconfigured.revalue(today - Duration::days(2)).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let service = FxRevaluationService::new(pool.clone()).with_config(FxRevaluationConfig {
+        functional_currency: "USD".to_string(),
+        pnl_account_id: Some(pnl),
+    });
+    assert!(matches!(service.revalue(today).await, Err(AppError::Validation(_))));
+
+    // The first revaluation sets the baseline
+    let first = service.revalue(today - Duration::days(2)).await.expect("Failed to revalue");
+    let line = first.lines.iter().find(|line| line.account_id == eur_position).expect("No EUR position line");
+    assert_eq!(line.balance, dec!(1000));
+    assert_eq!(line.functional_amount, dec!(1080.00));
+    assert_eq!(line.gain_loss, dec!(0));
+    assert!(line.transaction_id.is_none());
+
+    // EUR strengthens: the position gains 20 USD, credited to the P&L account
+    let yesterday = (today - Duration::days(1)).and_hms_opt(12, 0, 0).unwrap().and_utc();
+    fx.record_rate(FxRate::new("EUR", "USD", dec!(1.10), dec!(0), "FEED", yesterday))
+        .await
+        .expect("Failed to record rate");
+    let second = service.revalue(today - Duration::days(1)).await.expect("Failed to revalue");
+    let line = second.lines.iter().find(|line| line.account_id == eur_position).expect("No EUR position line");
+    assert_eq!(line.previous_rate, Some(dec!(1.08)));
+    assert_eq!(line.gain_loss, dec!(20.00));
+    assert!(line.transaction_id.is_some());
+    assert_eq!(second.run.total_gain_loss, dec!(20.00));
+    assert!(second.run.missing_rates.is_empty());
+    let pnl_balance = account_service.get_balance(pnl, "USD").await.expect("Failed to get balance");
+    assert_eq!(pnl_balance.available_balance, dec!(20.00));
+
+    // A period is revalued once
+    let again = service.revalue(today - Duration::days(1)).await.expect("Failed to revalue again");
+    assert_eq!(again.run.id, second.run.id);
+    let pnl_balance = account_service.get_balance(pnl, "USD").await.expect("Failed to get balance");
+    assert_eq!(pnl_balance.available_balance, dec!(20.00));
+    let report = service.get_report(second.run.id).await.expect("Failed to get report");
+    assert_eq!(report.lines.len(), second.lines.len());
+
+    common::cleanup_test_data(&pool).await;
+}
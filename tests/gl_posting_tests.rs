@@ -0,0 +1,91 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::AccountType;
+use settlement_engine::services::{GlPostingService, LedgerService, LedgerTransactionRequest};
+use uuid::Uuid;
+
+/// A past day no other test posts entries on.
+fn unique_posting_date() -> NaiveDate {
+    let offset = (Uuid::new_v4().as_u128() % 3650) as i64;
+    NaiveDate::from_ymd_opt(2000, 1, 1).unwrap() + Duration::days(offset)
+}
+
+#[tokio::test]
+async fn test_gl_posting_run_is_idempotent() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let posting_date = unique_posting_date();
+
+    for table in ["gl_journal_lines", "gl_posting_runs"] {
+        let filter = if table == "gl_journal_lines" {
+            "run_id IN (SELECT id FROM gl_posting_runs WHERE posting_date = $1)"
+        } else {
+            "posting_date = $1"
+        };
+        sqlx::query(&format!("DELETE FROM {} WHERE {}", table, filter))
+            .bind(posting_date)
+            .execute(&pool)
+            .await
+            .expect("Failed to clear posting run");
+    }
+
+    let ledger_service = LedgerService::new(pool.clone());
+    let gl_service = GlPostingService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for (prefix, account_type) in [("CASH", AccountType::Asset), ("CUSTOMER", AccountType::Liability)] {
+        let account = fixtures::account(&currency)
+            .with_name(prefix)
+            .with_type(account_type)
+            .with_balance(dec!(1000))
+            .create(&pool)
+            .await;
+        accounts.push(account);
+    }
+    let (cash, customer) = (&accounts[0], &accounts[1]);
+
+    for (amount, fee) in [(dec!(100), dec!(2)), (dec!(40), dec!(0))] {
+        ledger_service
+            .process_transaction(
+                LedgerTransactionRequest::payment(
+                    format!("PAY-{}", Uuid::new_v4()),
+                    cash.id,
+                    customer.id,
+                    amount,
+                    &currency,
+                    format!("IDEM-{}", Uuid::new_v4()),
+                )
+                .with_fee(fee)
+                .with_effective_date(posting_date),
+            )
+            .await
+            .expect("Failed to settle payment");
+    }
+
+    let journal = gl_service.post_day(posting_date).await.expect("Failed to post day");
+    let lines: Vec<_> = journal.lines.iter().filter(|line| line.currency == currency).collect();
+    let codes: Vec<&str> = lines.iter().map(|line| line.gl_code.as_str()).collect();
+    assert_eq!(codes, vec!["1000", "2000", "4100"]);
+    assert_eq!(lines[0].debit_amount, dec!(140));
+    assert_eq!(lines[0].entry_count, 2);
+    assert_eq!(lines[1].credit_amount, dec!(138));
+    assert_eq!(lines[2].credit_amount, dec!(2));
+    assert!(journal.is_balanced());
+    assert!(journal
+        .to_csv()
+        .contains(&format!("{},{},4100,{},0.0000,2.0000,1,", posting_date, lines[2].line_number, currency)));
+
+    // Re-running the day returns the posted journal
+    let again = gl_service.post_day(posting_date).await.expect("Failed to re-run day");
+    assert_eq!(again.run.id, journal.run.id);
+    assert_eq!(again.lines, journal.lines);
+    let stored = gl_service.get_journal(journal.run.id).await.expect("Failed to get journal");
+    assert_eq!(stored.run.entry_count, journal.run.entry_count);
+
+    let today = gl_service.post_day(Utc::now().date_naive()).await;
+    assert!(matches!(today, Err(AppError::Validation(_))));
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use rust_decimal_macros::dec;
+use settlement_engine::api::graphql::build_schema;
+use settlement_engine::services::{BatchService, LedgerService, LedgerTransactionRequest};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_graphql_resolves_nested_selection() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let bank_a = common::fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let bank_b = common::fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let batch = common::fixtures::batch(&currency).create(&pool).await;
+    let ledger = LedgerService::new(pool.clone());
+    let batches = BatchService::new(pool.clone());
+
+    let mut ids = Vec::new();
+    for amount in [dec!(100), dec!(40), dec!(25)] {
+        let posted = ledger
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                bank_a.id,
+                bank_b.id,
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to post payment");
+        batches
+            .assign_transaction_to_batch(posted.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+        ids.push(posted.transaction.id);
+    }
+
+    let schema = build_schema(pool.clone());
+    let query = format!(
+        r#"{{
+            account(id: "{account}") {{
+                name
+                balances {{ currency availableBalance }}
+                transactions(limit: 2) {{ hasMore items {{ id }} }}
+            }}
+            transaction(id: "{transaction}") {{
+                amount
+                status
+                ledgerEntries {{ entryType amount }}
+                batch {{ id }}
+            }}
+            batch(id: "{batch}") {{
+                totalTransactions
+                transactions {{ id }}
+            }}
+        }}"#,
+        account = bank_a.id,
+        transaction = ids[0],
+        batch = batch.id,
+    );
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+
+    let account = &data["account"];
+    assert_eq!(account["balances"][0]["currency"], currency.as_str());
+    assert_eq!(account["transactions"]["items"].as_array().unwrap().len(), 2);
+    assert_eq!(account["transactions"]["hasMore"], true);
+    assert!(account.get("externalId").is_none());
+
+    let transaction = &data["transaction"];
+    assert_eq!(transaction["status"], "SETTLED");
+    let entry_types: Vec<_> = transaction["ledgerEntries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["entryType"].as_str().unwrap().to_string())
+        .collect();
+    assert!(entry_types.contains(&"DEBIT".to_string()) && entry_types.contains(&"CREDIT".to_string()));
+    assert_eq!(transaction["batch"]["id"], batch.id.to_string());
+
+    assert_eq!(data["batch"]["transactions"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_graphql_rejects_invalid_page() {
+    let pool = common::setup_test_db().await;
+    let schema = build_schema(pool);
+
+    let response = schema.execute("{ transactions(limit: 0) { hasMore } }").await;
+    assert_eq!(response.errors.len(), 1);
+    let extensions = response.errors[0].extensions.as_ref().unwrap();
+    assert_eq!(extensions.get("code").unwrap().to_string(), "\"VALIDATION_ERROR\"");
+}
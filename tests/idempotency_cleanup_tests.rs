@@ -0,0 +1,166 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use settlement_engine::idempotency::{
+    IdempotencyCleanupConfig, IdempotencyCleanupJob, IdempotencyRecord, PostgresIdempotencyStore,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn insert_record(pool: &PgPool, operation_type: &str, age: Duration, expires_in: Duration) -> String {
+    let key = format!("idem_cleanup_{}", Uuid::new_v4());
+    let record = IdempotencyRecord::new(
+        key.clone(),
+        "client-cleanup".to_string(),
+        operation_type.to_string(),
+        "hash".to_string(),
+        0,
+    );
+    let created_at = Utc::now() - age;
+
+    sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (id, idempotency_key, client_id, operation_type, status, request_hash, created_at, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(record.id)
+    .bind(&record.idempotency_key)
+    .bind(&record.client_id)
+    .bind(&record.operation_type)
+    .bind(record.status)
+    .bind(&record.request_hash)
+    .bind(created_at)
+    .bind(Utc::now() + expires_in)
+    .execute(pool)
+    .await
+    .expect("Failed to insert idempotency record");
+
+    key
+}
+
+async fn archived(pool: &PgPool, key: &str) -> bool {
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM idempotency_keys_archive WHERE idempotency_key = $1")
+        .bind(key)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to query archive");
+    row.0 > 0
+}
+
+async fn cleanup(pool: &PgPool) {
+    sqlx::query("DELETE FROM idempotency_keys").execute(pool).await.ok();
+    sqlx::query("DELETE FROM idempotency_keys_archive").execute(pool).await.ok();
+}
+
+#[tokio::test]
+async fn test_cleanup_job_retention_batching_and_archival() {
+    let pool = common::setup_test_db().await;
+    cleanup(&pool).await;
+    let store = PostgresIdempotencyStore::new(pool.clone());
+
+    let mut expired_payments = Vec::new();
+    for _ in 0..5 {
+        expired_payments.push(insert_record(&pool, "payment", Duration::days(2), -Duration::days(1)).await);
+    }
+    // Expired, but refunds are retained for a week
+    let retained_refund = insert_record(&pool, "refund", Duration::days(2), -Duration::days(1)).await;
+    let old_refund = insert_record(&pool, "refund", Duration::days(10), -Duration::days(9)).await;
+    // Not expired yet, so kept whatever its age
+    let live_payment = insert_record(&pool, "payment", Duration::days(2), Duration::hours(1)).await;
+
+    let mut config = IdempotencyCleanupConfig {
+        batch_size: 2,
+        batch_pause_ms: 0,
+        max_batches_per_run: 2,
+        archive: true,
+        ..Default::default()
+    };
+    config.class_retention_seconds.insert("refund".to_string(), 7 * 86400);
+
+    // Two batches of two leave a backlog for the next run
+    let job = IdempotencyCleanupJob::new(pool.clone(), config.clone());
+    let first = job.run_once().await.expect("Cleanup failed");
+    assert_eq!(first.removed, 4);
+    assert_eq!(first.batches, 2);
+    assert!(first.archived);
+    assert_eq!(first.remaining, 4);
+
+    let second = job.run_once().await.expect("Cleanup failed");
+    assert_eq!(second.removed, 2);
+    // A full batch is followed by one more to confirm the backlog is gone
+    assert_eq!(second.batches, 2);
+    assert_eq!(second.remaining, 2);
+
+    for key in expired_payments.iter().chain([&old_refund]) {
+        assert!(store.find_by_key(key).await.unwrap().is_none());
+        assert!(archived(&pool, key).await, "{} was not archived", key);
+    }
+    for key in [&retained_refund, &live_payment] {
+        assert!(store.find_by_key(key).await.unwrap().is_some());
+        assert!(!archived(&pool, key).await);
+    }
+
+    // Without archival records are only deleted
+    let unarchived = insert_record(&pool, "payment", Duration::days(2), -Duration::days(1)).await;
+    config.archive = false;
+    let job = IdempotencyCleanupJob::new(pool.clone(), config);
+    let summary = job.run_once().await.expect("Cleanup failed");
+    assert_eq!(summary.removed, 1);
+    assert!(!summary.archived);
+    assert!(store.find_by_key(&unarchived).await.unwrap().is_none());
+    assert!(!archived(&pool, &unarchived).await);
+
+    cleanup(&pool).await;
+}
+
+async fn wait_for_runs(control: &settlement_engine::core::job_control::JobControl, runs: u64) {
+    for _ in 0..100 {
+        if control.status().runs >= runs {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    panic!("Job did not complete run {}", runs);
+}
+
+#[tokio::test]
+async fn test_cleanup_job_runtime_control() {
+    let pool = common::setup_test_db().await;
+    // Retention no record reaches, so runs leave other tests' records alone
+    let config = IdempotencyCleanupConfig {
+        interval_seconds: 3600,
+        default_retention_seconds: 100 * 365 * 86400,
+        ..Default::default()
+    };
+    let job = IdempotencyCleanupJob::new(pool.clone(), config);
+    let control = job.control();
+    assert!(control.trigger().is_err(), "a stopped job cannot be triggered");
+
+    job.start();
+    wait_for_runs(&control, 1).await;
+    let status = control.status();
+    assert!(status.running);
+    assert_eq!(status.name, IdempotencyCleanupJob::JOB_NAME);
+    assert_eq!(status.errors, 0);
+    assert!(status.next_run_at.unwrap() > chrono::Utc::now() + Duration::minutes(59));
+
+    // Triggered runs happen while paused; scheduled ones do not
+    control.pause();
+    assert_eq!(control.status().next_run_at, None);
+    control.trigger().expect("Failed to trigger job");
+    wait_for_runs(&control, 2).await;
+    assert!(control.status().paused);
+    assert_eq!(control.status().next_run_at, None);
+
+    control.resume();
+    control
+        .set_interval(std::time::Duration::from_secs(1))
+        .expect("Failed to set interval");
+    assert_eq!(control.status().interval_secs, 1);
+    wait_for_runs(&control, 3).await;
+
+    job.stop();
+    assert!(!job.is_running());
+    assert_eq!(control.status().next_run_at, None);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use serde_json::json;
+use settlement_engine::cache::{RedisPool, RedisPoolConfig};
+use settlement_engine::idempotency::{
+    HybridIdempotencyStore, IdempotencyRecord, IdempotencyStatus, PostgresIdempotencyStore,
+    RedisIdempotencyCache,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_hybrid_store_falls_back_to_postgres_when_redis_is_down() {
+    let pool = common::setup_test_db().await;
+
+    let mut config = RedisPoolConfig::standalone("redis://127.0.0.1:1");
+    config.reconnect_attempts = 0;
+    config.circuit.minimum_calls = 1;
+    config.circuit.open_duration = Duration::from_secs(60);
+    let redis = Arc::new(RedisPool::new(config));
+
+    let store = HybridIdempotencyStore::new(
+        PostgresIdempotencyStore::new(pool.clone()),
+        RedisIdempotencyCache::new(redis.clone(), "idem-test"),
+        3600,
+    );
+
+    let key = format!("idem_degraded_{}", Uuid::new_v4());
+    let record = IdempotencyRecord::new(
+        key.clone(),
+        "client-degraded".to_string(),
+        "payment".to_string(),
+        "hash".to_string(),
+        3600,
+    );
+
+    // New key is acquired through PostgreSQL alone
+    let existing = store.try_acquire(&record).await.expect("Acquire should not fail");
+    assert!(existing.is_none());
+    assert!(!redis.is_available(), "Circuit should be open after the failed connect");
+
+    // Duplicates are still detected, and completion does not fail on the cache
+    let retry = IdempotencyRecord::new(
+        key.clone(),
+        "client-degraded".to_string(),
+        "payment".to_string(),
+        "hash".to_string(),
+        3600,
+    );
+    let duplicate = store.try_acquire(&retry).await.expect("Acquire should not fail");
+    assert_eq!(duplicate.map(|r| r.id), Some(record.id));
+
+    store
+        .mark_completed(&key, json!({"status": "ok"}))
+        .await
+        .expect("Completion should not fail");
+    let found = store.check_duplicate(&key).await.expect("Lookup should not fail");
+    assert_eq!(found.map(|r| r.status), Some(IdempotencyStatus::Completed));
+
+    sqlx::query("DELETE FROM idempotency_keys WHERE idempotency_key = $1")
+        .bind(&key)
+        .execute(&pool)
+        .await
+        .ok();
+}
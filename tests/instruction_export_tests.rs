@@ -0,0 +1,336 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::interop::nacha::NachaConfig;
+use settlement_engine::models::{AccountType, BankAccountType, BatchStatus, PaymentRail, SettlementProfile};
+use settlement_engine::services::{
+    AccountService, BatchService, InstructionExportService, InstructionType, LedgerService, LedgerTransactionRequest,
+    account_service::CreateAccountRequest,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn nacha_config() -> NachaConfig {
+    NachaConfig {
+        immediate_destination: "021000021".to_string(),
+        immediate_destination_name: "Federal Reserve Bank".to_string(),
+        immediate_origin: "1234567890".to_string(),
+        immediate_origin_name: "Settlement Engine".to_string(),
+        company_name: "Settlement Engine".to_string(),
+        company_id: "1234567890".to_string(),
+        originating_dfi: "01100001".to_string(),
+        entry_description: "SETTLEMENT".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_export_batch_instructions_as_nacha() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let export_service =
+        InstructionExportService::new(pool.clone()).with_nacha(Arc::new(nacha_config()));
+
+    let mut accounts = Vec::new();
+    for name in ["Participant A", "Participant B", "Participant C"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("NACHA-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: "USD".to_string(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let (a, b, c) = (accounts[0], accounts[1], accounts[2]);
+
+    let batch = batch_service
+        .get_or_create_current_batch("USD")
+        .await
+        .expect("Failed to create batch");
+
+    // A pays B 300.25 and B pays C 100: A owes 200.25 to B and 100 to C net
+    for (from, to, amount) in [(a, b, dec!(300.25)), (b, c, dec!(100))] {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                from,
+                to,
+                amount,
+                "USD",
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    // Only completed batches can be exported
+    let result = export_service.export_nacha(batch.id).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let processed = batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+    assert_eq!(processed.status, BatchStatus::Completed);
+
+    // Every participant needs bank details
+    account_service
+        .set_settlement_profile(SettlementProfile::new(a, "Participant A", "021000021", "111111111", BankAccountType::Checking))
+        .await
+        .expect("Failed to set profile");
+    let result = export_service.export_nacha(batch.id).await;
+    assert!(matches!(result, Err(AppError::Validation(msg)) if msg.contains(&b.to_string())));
+
+    for (account_id, routing_number) in [(b, "011000015"), (c, "021000021")] {
+        account_service
+            .set_settlement_profile(SettlementProfile::new(
+                account_id,
+                "Participant",
+                routing_number,
+                "222222222",
+                BankAccountType::Savings,
+            ))
+            .await
+            .expect("Failed to set profile");
+    }
+
+    let file = export_service.export_nacha(batch.id).await.expect("Failed to export");
+    let records = file.records();
+
+    assert!(records.iter().all(|r| r.len() == 94));
+    assert_eq!(records.len() % 10, 0);
+    assert_eq!(file.entry_count, 4);
+    assert_eq!(file.total_debit, dec!(300.25));
+    assert_eq!(file.total_credit, dec!(300.25));
+
+    let file_control = records.iter().find(|r| r.starts_with('9') && !r.starts_with("999")).unwrap();
+    assert_eq!(&file_control[31..43], "000000030025");
+    assert_eq!(&file_control[43..55], "000000030025");
+
+    let invalid = SettlementProfile::new(a, "Participant A", "021000022", "111111111", BankAccountType::Checking);
+    let result = account_service.set_settlement_profile(invalid).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let unknown = SettlementProfile::new(Uuid::new_v4(), "Nobody", "021000021", "1", BankAccountType::Checking);
+    let result = account_service.set_settlement_profile(unknown).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_export_batch_instructions_as_pacs008() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let export_service = InstructionExportService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Participant A", "Participant B"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("PACS-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let (a, b) = (accounts[0], accounts[1]);
+
+    let batch = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to create batch");
+    let result = ledger_service
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            a,
+            b,
+            dec!(420.75),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+    batch_service
+        .assign_transaction_to_batch(result.transaction.id, batch.id)
+        .await
+        .expect("Failed to assign transaction");
+    batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    account_service
+        .set_settlement_profile(SettlementProfile::international(
+            a,
+            &currency,
+            "Participant A",
+            "DE89 3704 0044 0532 0130 00",
+            Some("deutdeff".to_string()),
+            PaymentRail::Swift,
+        ))
+        .await
+        .expect("Failed to set profile");
+    let result = export_service.export_pacs008(batch.id).await;
+    assert!(matches!(result, Err(AppError::Validation(msg)) if msg.contains(&b.to_string())));
+
+    // SWIFT needs a BIC, and IBANs must pass their checksum
+    let no_bic =
+        SettlementProfile::international(b, &currency, "Participant B", "GB82WEST12345698765432", None, PaymentRail::Swift);
+    let result = account_service.set_settlement_profile(no_bic).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let bad_iban = SettlementProfile::international(
+        b,
+        &currency,
+        "Participant B",
+        "GB82WEST12345698765431",
+        Some("NWBKGB2L".to_string()),
+        PaymentRail::Swift,
+    );
+    let result = account_service.set_settlement_profile(bad_iban).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    account_service
+        .set_settlement_profile(SettlementProfile::international(
+            b,
+            &currency,
+            "Participant B",
+            "GB82WEST12345698765432",
+            Some("NWBKGB2L".to_string()),
+            PaymentRail::Swift,
+        ))
+        .await
+        .expect("Failed to set profile");
+
+    let message = export_service.export_pacs008(batch.id).await.expect("Failed to export");
+    assert_eq!(message.transfers.len(), 1);
+    assert_eq!(message.total(), dec!(420.75));
+    let xml = message.to_xml();
+    assert!(xml.contains(&format!("<TtlIntrBkSttlmAmt Ccy=\"{}\">420.75</TtlIntrBkSttlmAmt>", currency)));
+    assert!(xml.contains("<DbtrAcct><Id><IBAN>DE89370400440532013000</IBAN></Id></DbtrAcct>"));
+    assert!(xml.contains("<DbtrAgt><FinInstnId><BICFI>DEUTDEFF</BICFI></FinInstnId></DbtrAgt>"));
+    assert!(xml.contains("<CdtrAcct><Id><IBAN>GB82WEST12345698765432</IBAN></Id></CdtrAcct>"));
+
+    // Profiles are per currency
+    account_service
+        .set_settlement_profile(SettlementProfile::new(a, "Participant A", "021000021", "111111111", BankAccountType::Checking))
+        .await
+        .expect("Failed to set profile");
+    let profiles = account_service.list_settlement_profiles(a).await.expect("Failed to list profiles");
+    assert_eq!(profiles.len(), 2);
+
+    account_service
+        .delete_settlement_profile(a, &currency)
+        .await
+        .expect("Failed to delete profile");
+    let result = account_service.delete_settlement_profile(a, &currency).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+    let result = export_service.export_pacs008(batch.id).await;
+    assert!(matches!(result, Err(AppError::Validation(msg)) if msg.contains(&a.to_string())));
+}
+
+#[tokio::test]
+async fn test_export_includes_gross_settled_refund() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let export_service = InstructionExportService::new(pool.clone());
+
+    let a = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let batch = fixtures::batch(&currency).create(&pool).await;
+
+    // A pays B 500 and B refunds 200 of it outside netting
+    let payment = ledger_service
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            a,
+            b,
+            dec!(500),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+    let refund = ledger_service
+        .process_refund(
+            LedgerTransactionRequest::refund(
+                format!("REF-{}", Uuid::new_v4()),
+                payment.transaction.id,
+                b,
+                a,
+                dec!(200),
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            )
+            .excluded_from_netting(),
+        )
+        .await
+        .expect("Failed to process refund");
+    for transaction_id in [payment.transaction.id, refund.transaction.id] {
+        batch_service
+            .assign_transaction_to_batch(transaction_id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+    batch_service
+        .trigger_batch_processing(batch.id)
+        .await
+        .expect("Failed to process batch");
+
+    for (account_id, iban, bic) in [
+        (a, "DE89 3704 0044 0532 0130 00", "DEUTDEFF"),
+        (b, "GB82WEST12345698765432", "NWBKGB2L"),
+    ] {
+        account_service
+            .set_settlement_profile(SettlementProfile::international(
+                account_id,
+                &currency,
+                "Participant",
+                iban,
+                Some(bic.to_string()),
+                PaymentRail::Swift,
+            ))
+            .await
+            .expect("Failed to set profile");
+    }
+
+    let (_, instructions) = export_service
+        .batch_instructions(batch.id)
+        .await
+        .expect("Failed to get instructions");
+    let gross: Vec<_> = instructions
+        .iter()
+        .filter(|i| i.instruction_type == InstructionType::Gross)
+        .collect();
+    assert_eq!(gross.len(), 1);
+    assert_eq!((gross[0].from_participant, gross[0].to_participant, gross[0].amount), (b, a, dec!(200)));
+
+    let message = export_service.export_pacs008(batch.id).await.expect("Failed to export");
+    assert_eq!(message.transfers.len(), 2);
+    assert_eq!(message.total(), dec!(700));
+}
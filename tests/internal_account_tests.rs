@@ -0,0 +1,164 @@
+mod common;
+
+use common::fixtures;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{
+    AccountType, InternalAccountDefinition, InternalAccountRole, ProvisioningTrigger, StatusChangeReason,
+    StatusReasonCode,
+};
+use settlement_engine::services::{
+    AccountService, ChartOfAccountsService, FeeConfig, LedgerService,
+    LedgerTransactionRequest,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn chart() -> Vec<InternalAccountDefinition> {
+    vec![
+        InternalAccountDefinition::new("FEE-REVENUE", "Fee revenue", "USD", Some(InternalAccountRole::FeeRevenue))
+            .with_parent("INCOME"),
+        InternalAccountDefinition::new("INCOME", "Income", "USD", None).with_account_type(AccountType::Revenue),
+        InternalAccountDefinition::new("SUSPENSE", "Suspense", "USD", Some(InternalAccountRole::Suspense)),
+        InternalAccountDefinition::new("COLLATERAL", "Collateral", "USD", Some(InternalAccountRole::Collateral)),
+    ]
+}
+
+#[tokio::test]
+async fn test_chart_is_provisioned_idempotently() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let service = ChartOfAccountsService::new(pool.clone());
+    let provisioned = service.provision(&chart(), ProvisioningTrigger::Startup).await.unwrap();
+    assert_eq!(provisioned.len(), 4);
+    assert_eq!(provisioned[0].code, "INCOME");
+
+    let revenue = service.get("fee-revenue", "usd").await.unwrap();
+    assert_eq!(revenue.parent_code.as_deref(), Some("INCOME"));
+    assert!(revenue.control);
+    let account = AccountService::new(pool.clone())
+        .find_by_external_id("INTERNAL-FEE-REVENUE-USD")
+        .await
+        .unwrap();
+    assert_eq!(account.id, revenue.account_id);
+    assert_eq!(account.account_type, AccountType::Revenue);
+
+    // Provisioning again keeps the backing accounts and applies changes
+    let mut renamed = chart();
+    renamed[2].name = "Unapplied funds".to_string();
+    let again = service.provision(&renamed, ProvisioningTrigger::Startup).await.unwrap();
+    let suspense = again.iter().find(|entry| entry.code == "SUSPENSE").unwrap();
+    assert_eq!(suspense.name, "Unapplied funds");
+    assert_eq!(service.get("FEE-REVENUE", "USD").await.unwrap().account_id, revenue.account_id);
+    assert_eq!(
+        service.role_account(InternalAccountRole::Suspense, "USD").await.unwrap(),
+        Some(suspense.account_id)
+    );
+    assert_eq!(service.list(Some("USD")).await.unwrap().len(), 4);
+    // Only the first provisioning of each entry is audited
+    let history = service.provisioning_history(Some("USD"), 100).await.unwrap();
+    assert_eq!(history.len(), 4);
+    assert!(history.iter().all(|record| record.created_account && record.trigger == ProvisioningTrigger::Startup));
+    assert!(matches!(service.get("SUSPENSE", "EUR").await, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_control_accounts_only_move_through_system_flows() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let chart_service = ChartOfAccountsService::new(pool.clone());
+    chart_service.provision(&chart(), ProvisioningTrigger::Startup).await.unwrap();
+    let suspense = chart_service.get("SUSPENSE", "USD").await.unwrap();
+    let collateral = chart_service.get("COLLATERAL", "USD").await.unwrap();
+
+    let account_service = AccountService::new(pool.clone());
+    let participant = fixtures::account("USD")
+        .with_name("Participant")
+        .with_balance(dec!(1000))
+        .create(&pool)
+        .await;
+
+    let ledger_service = LedgerService::new(pool.clone());
+    let transfer = |destination| {
+        LedgerTransactionRequest::transfer(
+            format!("TRF-{}", Uuid::new_v4()),
+            participant.id,
+            destination,
+            dec!(100),
+            "USD",
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+    };
+
+    let validation = ledger_service.validate_transaction(&transfer(suspense.account_id)).await.unwrap();
+    assert!(!validation.is_valid);
+    assert!(validation.errors.iter().any(|e| e.code == "CONTROL_ACCOUNT"));
+
+    // Collateral is not a control account
+    let validation = ledger_service.validate_transaction(&transfer(collateral.account_id)).await.unwrap();
+    assert!(validation.is_valid);
+
+    let close = account_service
+        .close_account(suspense.account_id, StatusChangeReason::new(StatusReasonCode::CustomerRequest))
+        .await;
+    assert!(matches!(close, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_system_accounts_provisioned_on_first_use_of_currency() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let templates: Vec<InternalAccountDefinition> = chart().iter().map(|entry| entry.in_currency("")).collect();
+    let chart_service = Arc::new(ChartOfAccountsService::new(pool.clone()).with_templates(templates));
+    chart_service
+        .provision(&[chart()[1].clone()], ProvisioningTrigger::Startup)
+        .await
+        .unwrap();
+    let fees = Arc::new(FeeConfig::default().with_chart(chart_service.registry()));
+    let ledger_service = LedgerService::new(pool.clone())
+        .with_fees(fees.clone())
+        .with_chart_of_accounts(chart_service.clone());
+    assert_eq!(fees.revenue_account("CHF"), None);
+
+    let mut participants = Vec::new();
+    for name in ["Payer", "Payee"] {
+        let account = fixtures::account("CHF").with_name(name).with_balance(dec!(1000)).create(&pool).await;
+        participants.push(account);
+    }
+
+    // The first CHF transaction provisions the chart in CHF and books its fee to it
+    let mut request = LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        participants[0].id,
+        participants[1].id,
+        dec!(100),
+        "CHF",
+        format!("IDEM-{}", Uuid::new_v4()),
+    );
+    request.fee_amount = dec!(2);
+    let result = ledger_service.process_payment(request).await.unwrap();
+
+    let revenue = chart_service.get("FEE-REVENUE", "CHF").await.unwrap();
+    assert_eq!(fees.revenue_account("CHF"), Some(revenue.account_id));
+    assert!(result.entries.iter().any(|entry| entry.account_id == revenue.account_id));
+    assert_eq!(chart_service.list(Some("CHF")).await.unwrap().len(), 4);
+
+    let history = chart_service.provisioning_history(Some("CHF"), 100).await.unwrap();
+    assert_eq!(history.len(), 4);
+    assert!(history.iter().all(|record| record.trigger == ProvisioningTrigger::FirstUse));
+
+    // Later transactions in the currency provision nothing more
+    let request = LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        participants[0].id,
+        participants[1].id,
+        dec!(10),
+        "CHF",
+        format!("IDEM-{}", Uuid::new_v4()),
+    );
+    ledger_service.process_payment(request).await.unwrap();
+    assert_eq!(chart_service.provisioning_history(Some("CHF"), 100).await.unwrap().len(), 4);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use chrono::{Datelike, Months, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{AccountType, InvoiceLineKind};
+use settlement_engine::repositories::BalanceRepository;
+use settlement_engine::services::{
+    FeeConfig, InvoiceContractTerms, InvoiceService, LedgerService, LedgerTransactionRequest,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_monthly_invoice_applies_contract_and_posts_receivable() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let payer = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let unpriced = common::fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let merchant = common::fixtures::account(&currency).with_type(AccountType::Liability).with_balance(dec!(0)).create(&pool).await;
+    let revenue = common::fixtures::account(&currency).with_type(AccountType::Revenue).with_balance(dec!(0)).create(&pool).await;
+    let receivable = common::fixtures::account(&currency).with_balance(dec!(0)).create(&pool).await;
+    let fees = Arc::new(FeeConfig {
+        revenue_accounts: HashMap::from([(currency.clone(), revenue.id)]),
+        invoice_receivable_accounts: HashMap::from([(currency.clone(), receivable.id)]),
+        ..FeeConfig::default()
+    });
+    let ledger = LedgerService::new(pool.clone()).with_fees(fees.clone());
+    let invoices = InvoiceService::new(pool.clone()).with_fees(fees.clone());
+
+    invoices
+        .set_contract(
+            payer.id,
+            &currency,
+            InvoiceContractTerms {
+                free_transactions: 2,
+                per_transaction_price: dec!(1.5),
+                volume_rate: dec!(0.01),
+                monthly_cap: Some(dec!(20)),
+            },
+        )
+        .await
+        .expect("Failed to set contract");
+
+    // Backdated into last month, which is still open
+    let last_month = (Utc::now().date_naive() - Months::new(1)).with_day(10).unwrap();
+    for (payer_id, amount) in [(payer.id, dec!(100)), (payer.id, dec!(200)), (payer.id, dec!(300)), (payer.id, dec!(400)), (unpriced.id, dec!(50))] {
+        ledger
+            .process_payment(
+                LedgerTransactionRequest::payment(
+                    format!("PAY-{}", Uuid::new_v4()),
+                    payer_id,
+                    merchant.id,
+                    amount,
+                    &currency,
+                    format!("IDEM-{}", Uuid::new_v4()),
+                )
+                .with_fee(dec!(1))
+                .with_effective_date(last_month),
+            )
+            .await
+            .expect("Failed to process payment");
+    }
+
+    let month = invoices.invoice_month(last_month, Some(&currency)).await.expect("Failed to invoice month");
+    assert_eq!(month.len(), 2);
+    let invoice = month.iter().find(|invoice| invoice.account_id == payer.id).unwrap();
+    assert_eq!((invoice.transaction_count, invoice.transaction_volume, invoice.fees_charged), (4, dec!(1000), dec!(4)));
+
+    // 4 x 1.5 - 2 free x 1.5 + 1% of 1000 = 13, under the cap of 20
+    let document = invoices.get_invoice(invoice.id).await.expect("Failed to get invoice");
+    let kinds: Vec<InvoiceLineKind> = document.lines.iter().map(|line| line.kind).collect();
+    assert_eq!(kinds, vec![InvoiceLineKind::Transactions, InvoiceLineKind::FreeTransactions, InvoiceLineKind::Volume]);
+    assert_eq!(invoice.total_amount, dec!(13));
+    let posting = invoice.transaction_id.expect("Receivable not posted");
+    assert!(ledger.verify_transaction_balance(posting).await.unwrap());
+
+    let balances = BalanceRepository::new(pool.clone());
+    let revenue_balance = balances.find_by_account_and_currency(revenue.id, &currency).await.unwrap().unwrap();
+    let receivable_balance = balances.find_by_account_and_currency(receivable.id, &currency).await.unwrap().unwrap();
+    assert_eq!(revenue_balance.available_balance, dec!(5) + dec!(13), "fees plus the invoiced total");
+    assert_eq!(receivable_balance.available_balance, dec!(-13));
+
+    let unpriced_invoice = month.iter().find(|invoice| invoice.account_id == unpriced.id).unwrap();
+    assert_eq!((unpriced_invoice.total_amount, unpriced_invoice.transaction_id), (dec!(0), None));
+
+    // Re-running the month keeps the invoices already issued
+    let rerun = invoices.invoice_month(last_month, Some(&currency)).await.unwrap();
+    assert_eq!(rerun.iter().map(|invoice| invoice.id).collect::<Vec<_>>(), month.iter().map(|invoice| invoice.id).collect::<Vec<_>>());
+    assert_eq!(invoices.list_invoices(Some(payer.id), Some(last_month)).await.unwrap().len(), 1);
+
+    let csv = document.to_csv();
+    assert_eq!(csv.lines().count(), 4);
+
+    let current = invoices.invoice_month(Utc::now().date_naive(), None).await;
+    assert!(matches!(current, Err(AppError::Validation(_))));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use settlement_engine::core::job_control::JobControl;
+use settlement_engine::core::leader::LeaderElection;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn unique_lock_key() -> i64 {
+    Uuid::new_v4().as_u64_pair().0 as i64
+}
+
+async fn wait_until(condition: impl Fn() -> bool) {
+    for _ in 0..200 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    panic!("Condition was not met in time");
+}
+
+#[tokio::test]
+async fn test_single_leader_with_failover() {
+    let pool = common::setup_test_db().await;
+    let lock_key = unique_lock_key();
+    let first = Arc::new(LeaderElection::new(pool.clone(), lock_key, Duration::from_millis(50)));
+    let second = Arc::new(LeaderElection::new(pool.clone(), lock_key, Duration::from_millis(50)));
+
+    first.start();
+    wait_until(|| first.is_leader()).await;
+    second.start();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!second.is_leader(), "only one instance leads");
+
+    let status = first.status();
+    assert!(status.leader);
+    assert_eq!(status.lock_key, lock_key);
+    assert_eq!(status.transitions, 1);
+    assert!(status.leader_since.is_some());
+
+    // The leader stepping down hands over to the follower
+    first.stop();
+    wait_until(|| second.is_leader()).await;
+    assert!(!first.is_leader());
+    assert_eq!(first.status().transitions, 2);
+    assert_eq!(first.status().leader_since, None);
+
+    second.stop();
+    wait_until(|| !second.is_leader()).await;
+}
+
+#[tokio::test]
+async fn test_jobs_stand_by_until_leader() {
+    let pool = common::setup_test_db().await;
+    let election = Arc::new(LeaderElection::new(pool.clone(), unique_lock_key(), Duration::from_millis(50)));
+    let control = Arc::new(JobControl::new("test_job", Duration::from_secs(3600)));
+    control.set_leader(election.clone());
+    control.set_running(true);
+
+    assert!(control.is_standby());
+    assert!(control.status().standby);
+    assert!(!control.begin_run(), "a follower does not run the job");
+    assert!(control.trigger().is_err());
+
+    // Winning the election wakes the job for its overdue run
+    let waiter = tokio::spawn({
+        let control = control.clone();
+        async move { control.wait().await }
+    });
+    election.start();
+    tokio::time::timeout(Duration::from_secs(5), waiter)
+        .await
+        .expect("Taking over should wake the job")
+        .unwrap();
+    assert!(!control.is_standby());
+    assert!(control.begin_run());
+
+    election.stop();
+    control.set_running(false);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::invariants::{self, LedgerFixture, LedgerOp};
+use proptest::prelude::*;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use settlement_engine::models::{TransactionRecord, TransactionType};
+use settlement_engine::services::{LedgerService, NettingService};
+use uuid::Uuid;
+
+const ACCOUNTS: usize = 4;
+const MAX_OPS: usize = 24;
+const MAX_AMOUNT_CENTS: i64 = 50_000;
+
+fn run_ledger_case(ops: Vec<LedgerOp>) {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to build runtime");
+    runtime.block_on(async {
+        let pool = common::setup_test_db().await;
+        let fixture = LedgerFixture::create(&pool, ACCOUNTS, dec!(1000)).await;
+        let ledger_service = LedgerService::new(pool.clone());
+
+        let settled = invariants::apply_ops(&pool, &ledger_service, &fixture, &ops).await;
+
+        invariants::assert_entries_balanced(&pool, &settled).await;
+        invariants::assert_non_negative_balances(&pool, &fixture).await;
+        invariants::assert_money_conserved(&pool, &fixture).await;
+
+        let netting = NettingService::new(pool.clone()).calculate_multilateral_netting(
+            Uuid::new_v4(),
+            &fixture.currency,
+            &settled,
+        );
+        invariants::assert_netting_balanced(&netting);
+    });
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn prop_ledger_invariants_hold(ops in invariants::ledger_ops(MAX_OPS, MAX_AMOUNT_CENTS)) {
+        run_ledger_case(ops);
+    }
+
+    #[test]
+    fn prop_netting_positions_sum_to_zero(
+        legs in prop::collection::vec((0usize..8, 0usize..8, 1i64..=1_000_000), 1..64)
+    ) {
+        let participants: Vec<Uuid> = (0..8).map(|_| Uuid::new_v4()).collect();
+        let transactions: Vec<TransactionRecord> = legs
+            .into_iter()
+            .filter(|(from, to, _)| from != to)
+            .map(|(from, to, cents)| {
+                TransactionRecord::new(
+                    format!("PROP-{}", Uuid::new_v4()),
+                    TransactionType::Payment,
+                    participants[from],
+                    participants[to],
+                    Decimal::new(cents, 2),
+                    "USD".to_string(),
+                    Decimal::ZERO,
+                    Uuid::new_v4().to_string(),
+                )
+            })
+            .collect();
+
+        let runtime = tokio::runtime::Runtime::new().expect("Failed to build runtime");
+        let pool = runtime.block_on(common::setup_test_db());
+        let result = NettingService::new(pool).calculate_multilateral_netting(
+            Uuid::new_v4(),
+            "USD",
+            &transactions,
+        );
+
+        invariants::assert_netting_balanced(&result);
+        prop_assert!(result.total_net_volume <= result.total_gross_volume);
+    }
+}
@@ -1,11 +1,18 @@
 mod common;
 
 use rust_decimal_macros::dec;
-use settlement_engine::models::{AccountType, TransactionStatus, TransactionType};
+use settlement_engine::error::AppError;
+use settlement_engine::events::{AmountLimitBreachedEvent, EventEnvelope, EventType, PvpEvent};
+use settlement_engine::models::{
+    AccountType, CounterpartyAuditAction, CounterpartyListMode, Instrument, PvpStatus, TransactionGroupStatus, TransactionStatus, TransactionType,
+};
+use settlement_engine::repositories::OutboxRepository;
 use settlement_engine::services::{
-    AccountService, LedgerService, LedgerTransactionRequest, TransactionStateMachine,
-    ValidationResult, account_service::CreateAccountRequest,
+    AccountService, AmountLimits, CounterpartyService, DvpRequest, InstrumentService, LedgerService, LedgerTransactionRequest, PvpRequest, PvpService, TransactionGroupService,
+    TransactionStateMachine, ValidationResult, account_service::CreateAccountRequest,
 };
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -642,11 +649,90 @@ async fn test_ledger_service_account_history() {
 
     // Get account history
     let history = ledger_service
-        .get_account_history(account.id, 10)
+        .get_account_history(account.id, None, None, 10, 0)
+        .await
+        .expect("Failed to get history");
+
+    assert_eq!(history.entries.len(), 3);
+
+    common::cleanup_test_data(&pool).await;
+}
+
+#[tokio::test]
+async fn test_account_history_date_range_and_running_balance() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["History Account", "Other Account"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("ACC-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: "USD".to_string(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account);
+    }
+    let pay = |amount| {
+        LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            accounts[0].id,
+            accounts[1].id,
+            amount,
+            "USD",
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+    };
+
+    for amount in [dec!(50), dec!(25)] {
+        ledger_service.process_payment(pay(amount)).await.expect("Failed to process payment");
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    let history = ledger_service
+        .get_account_history(accounts[0].id, Some(today), Some(today), 10, 0)
         .await
         .expect("Failed to get history");
+    assert_eq!(history.total, 2);
+    assert!(!history.gaps_detected);
+    assert!(history.entries.iter().all(|e| e.is_continuous()));
 
-    assert_eq!(history.len(), 3);
+    let later = ledger_service
+        .get_account_history(accounts[0].id, Some(today + chrono::Duration::days(1)), None, 10, 0)
+        .await
+        .expect("Failed to get history");
+    assert_eq!(later.total, 0);
+    assert!(later.entries.is_empty());
+
+    let reversed = ledger_service
+        .get_account_history(accounts[0].id, Some(today), Some(today - chrono::Duration::days(1)), 10, 0)
+        .await;
+    assert!(matches!(reversed, Err(AppError::Validation(_))));
+
+    // A balance changed without a ledger entry breaks the running balance
+    sqlx::query("UPDATE account_balances SET available_balance = available_balance + 10 WHERE account_id = $1")
+        .bind(accounts[0].id)
+        .execute(&pool)
+        .await
+        .expect("Failed to adjust balance");
+    ledger_service.process_payment(pay(dec!(5))).await.expect("Failed to process payment");
+
+    let history = ledger_service
+        .get_account_history(accounts[0].id, None, None, 1, 0)
+        .await
+        .expect("Failed to get history");
+    assert_eq!(history.total, 3);
+    assert!(history.gaps_detected);
+    let latest = &history.entries[0];
+    assert_eq!(latest.expected_balance_after(), Some(latest.entry.balance_after - dec!(10)));
 
     common::cleanup_test_data(&pool).await;
 }
@@ -772,3 +858,475 @@ async fn test_ledger_service_refund_validation() {
 
     common::cleanup_test_data(&pool).await;
 }
+
+#[tokio::test]
+async fn test_counterparty_restrictions_block_transactions() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let counterparty_service = CounterpartyService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Participant A", "Participant B", "Participant C"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("CP-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: "USD".to_string(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let (a, b, c) = (accounts[0], accounts[1], accounts[2]);
+
+    let pay = |from, to| {
+        LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            from,
+            to,
+            dec!(10),
+            "USD",
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+    };
+
+    // A denies B: blocked in both directions, C unaffected
+    counterparty_service
+        .add_restriction(a, b, CounterpartyListMode::Deny, Some("Sanctioned entity".to_string()))
+        .await
+        .expect("Failed to deny counterparty");
+
+    let validation = ledger_service
+        .validate_transaction(&pay(a, b))
+        .await
+        .expect("Validation failed to run");
+    assert!(!validation.is_valid);
+    assert_eq!(validation.errors[0].code, "COUNTERPARTY_RESTRICTED");
+
+    let result = ledger_service.process_transaction(pay(b, a)).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    ledger_service
+        .process_transaction(pay(a, c))
+        .await
+        .expect("Unrestricted payment should settle");
+
+    // Switching B to the allowlist makes A refuse everyone else
+    counterparty_service
+        .add_restriction(a, b, CounterpartyListMode::Allow, None)
+        .await
+        .expect("Failed to allow counterparty");
+    ledger_service
+        .process_transaction(pay(a, b))
+        .await
+        .expect("Allowed payment should settle");
+    let result = ledger_service.process_transaction(pay(c, a)).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    counterparty_service
+        .remove_restriction(a, b)
+        .await
+        .expect("Failed to remove restriction");
+    assert!(counterparty_service.list_restrictions(a).await.unwrap().is_empty());
+    ledger_service
+        .process_transaction(pay(c, a))
+        .await
+        .expect("Payment should settle once restrictions are lifted");
+
+    let audit = counterparty_service
+        .get_audit_log(a)
+        .await
+        .expect("Failed to get audit log");
+    let changes: Vec<_> = audit.iter().map(|e| (e.mode, e.action)).collect();
+    assert_eq!(
+        changes,
+        vec![
+            (CounterpartyListMode::Deny, CounterpartyAuditAction::Added),
+            (CounterpartyListMode::Deny, CounterpartyAuditAction::Removed),
+            (CounterpartyListMode::Allow, CounterpartyAuditAction::Added),
+            (CounterpartyListMode::Allow, CounterpartyAuditAction::Removed),
+        ]
+    );
+    assert_eq!(audit[0].reason.as_deref(), Some("Sanctioned entity"));
+
+    common::cleanup_test_data(&pool).await;
+}
+
+#[tokio::test]
+async fn test_amount_limits_reject_and_alert() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let payer = common::fixtures::account(&currency).with_balance(dec!(1000000)).create(&pool).await;
+    let payee = common::fixtures::account(&currency).create(&pool).await;
+    let ledger_service = LedgerService::new(pool.clone()).with_amount_limits(Arc::new(AmountLimits {
+        default_max: Some(dec!(1000000)),
+        currency_max: HashMap::from([(currency.clone(), dec!(50000))]),
+        max_scale: 2,
+    }));
+    let payment = |amount| {
+        LedgerTransactionRequest::payment(
+            format!("LIM-{}", Uuid::new_v4()),
+            payer.id,
+            payee.id,
+            amount,
+            &currency,
+            Uuid::new_v4().to_string(),
+        )
+    };
+
+    ledger_service.process_payment(payment(dec!(50000))).await.expect("Payment at the maximum failed");
+
+    let over = ledger_service.process_payment(payment(dec!(1000000000000))).await;
+    assert!(matches!(over, Err(AppError::AmountLimitExceeded(_))), "got {:?}", over);
+    let too_fine = ledger_service.process_payment(payment(dec!(10.125))).await;
+    assert!(matches!(too_fine, Err(AppError::AmountLimitExceeded(_))), "got {:?}", too_fine);
+
+    let alerts: Vec<AmountLimitBreachedEvent> = OutboxRepository::new(pool.clone())
+        .find_by_partition_key(AmountLimitBreachedEvent::topic(), &payer.id.to_string())
+        .await
+        .expect("Failed to read outbox")
+        .into_iter()
+        .map(|message| {
+            let envelope: EventEnvelope<AmountLimitBreachedEvent> =
+                serde_json::from_value(message.payload).expect("Invalid alert event");
+            assert_eq!(envelope.event_type, EventType::AmountLimitBreached);
+            envelope.payload
+        })
+        .collect();
+    assert_eq!(alerts.len(), 2);
+    assert_eq!(alerts[0].limit, Some(dec!(50000)));
+    assert_eq!(alerts[1].limit, None);
+    assert_eq!(alerts[1].max_scale, 2);
+}
+
+#[tokio::test]
+async fn test_dry_run_projects_without_posting() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+
+    let source = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("SRC-{}", Uuid::new_v4()),
+            name: "Source".to_string(),
+            account_type: AccountType::Asset,
+            currency: "USD".to_string(),
+            initial_balance: Some(dec!(1000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create source");
+
+    let dest = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("DST-{}", Uuid::new_v4()),
+            name: "Destination".to_string(),
+            account_type: AccountType::Asset,
+            currency: "USD".to_string(),
+            initial_balance: Some(dec!(0)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create destination");
+
+    let request = LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        source.id,
+        dest.id,
+        dec!(100),
+        "USD",
+        format!("IDEM-{}", Uuid::new_v4()),
+    )
+    .with_fee(dec!(5));
+
+    let preview = ledger_service.dry_run(&request).await.expect("Failed to dry-run");
+    assert!(preview.feasible);
+    assert!(preview.errors.is_empty());
+    assert_eq!(preview.net_amount, dec!(95));
+    assert_eq!(preview.entries.len(), 2);
+    assert_eq!(preview.source_balance.as_ref().unwrap().available_balance, dec!(900));
+    assert_eq!(preview.destination_balance.as_ref().unwrap().available_balance, dec!(95));
+    assert_eq!(preview.existing_transaction_id, None);
+
+    // Nothing was posted
+    let balance = account_service
+        .get_balance(source.id, "USD")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.available_balance, dec!(1000));
+
+    // Failed checks are reported rather than raised
+    let request = LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        source.id,
+        Uuid::new_v4(),
+        dec!(2000),
+        "USD",
+        format!("IDEM-{}", Uuid::new_v4()),
+    );
+    let preview = ledger_service.dry_run(&request).await.expect("Failed to dry-run");
+    assert!(!preview.feasible);
+    let codes: Vec<&str> = preview.errors.iter().map(|error| error.code.as_str()).collect();
+    assert!(codes.contains(&"INSUFFICIENT_FUNDS"));
+    assert!(codes.contains(&"ACCOUNT_NOT_FOUND"));
+    assert!(preview.destination_balance.is_none());
+    assert_eq!(preview.entries.len(), 1);
+
+    common::cleanup_test_data(&pool).await;
+}
+
+#[tokio::test]
+async fn test_dvp_settles_both_legs_or_neither() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    InstrumentService::new(pool.clone())
+        .register(Instrument::security("US0378331005", Some("ISIN".to_string()), 0))
+        .await
+        .expect("Failed to register security");
+
+    let mut accounts = Vec::new();
+    for (name, code, balance) in [
+        ("Seller securities", "US0378331005", dec!(500)),
+        ("Buyer securities", "US0378331005", dec!(0)),
+        ("Buyer cash", "USD", dec!(10000)),
+        ("Seller cash", "USD", dec!(0)),
+    ] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("ACC-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: code.to_string(),
+                initial_balance: Some(balance),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let trade = |quantity, amount| DvpRequest {
+        external_id: format!("TRADE-{}", Uuid::new_v4()),
+        instrument: "US0378331005".to_string(),
+        quantity,
+        seller_securities_account_id: accounts[0],
+        buyer_securities_account_id: accounts[1],
+        amount,
+        currency: "USD".to_string(),
+        buyer_cash_account_id: accounts[2],
+        seller_cash_account_id: accounts[3],
+        idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
+        reference: None,
+    };
+
+    let request = trade(dec!(100), dec!(9000));
+    let settlement = ledger_service.settle_dvp(request.clone()).await.expect("Failed to settle DvP");
+    assert_eq!(settlement.securities.transaction.link_id, Some(settlement.link_id));
+    assert_eq!(settlement.cash.transaction.link_id, Some(settlement.link_id));
+    assert_eq!(settlement.securities.destination_balance.available_balance, dec!(100));
+    assert_eq!(settlement.cash.destination_balance.available_balance, dec!(9000));
+
+    // A retry returns the settled legs
+    let retry = ledger_service.settle_dvp(request).await.expect("Failed to retry DvP");
+    assert_eq!(retry.securities.transaction.id, settlement.securities.transaction.id);
+    assert_eq!(retry.cash.transaction.id, settlement.cash.transaction.id);
+
+    // The buyer cannot pay for a second trade, so the securities are not delivered either
+    let result = ledger_service.settle_dvp(trade(dec!(100), dec!(9000))).await;
+    assert!(matches!(result, Err(AppError::InsufficientFunds(_))));
+    let seller_securities = account_service
+        .get_balance(accounts[0], "US0378331005")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(seller_securities.available_balance, dec!(400));
+
+    // Quantities follow the security's scale
+    let result = ledger_service.settle_dvp(trade(dec!(1.5), dec!(10))).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    common::cleanup_test_data(&pool).await;
+}
+
+#[tokio::test]
+async fn test_transaction_group_settles_all_or_nothing() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let group_service = TransactionGroupService::new(pool.clone(), Arc::new(LedgerService::new(pool.clone())));
+
+    let mut accounts = Vec::new();
+    for (name, balance) in [("Payer", dec!(1000)), ("Payee A", dec!(0)), ("Payee B", dec!(0))] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("ACC-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: "USD".to_string(),
+                initial_balance: Some(balance),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let split = |amounts: [rust_decimal::Decimal; 2]| -> Vec<LedgerTransactionRequest> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(index, amount)| {
+                LedgerTransactionRequest::payment(
+                    format!("SPLIT-{}", Uuid::new_v4()),
+                    accounts[0],
+                    accounts[index + 1],
+                    *amount,
+                    "USD",
+                    format!("IDEM-{}", Uuid::new_v4()),
+                )
+            })
+            .collect()
+    };
+
+    // A split disbursement settles both payments under the group's link
+    let requests = split([dec!(300), dec!(200)]);
+    let group_key = format!("GROUP-{}", Uuid::new_v4());
+    let detail = group_service
+        .submit(&group_key, requests.clone())
+        .await
+        .expect("Failed to settle group");
+    assert_eq!(detail.group.status, TransactionGroupStatus::Settled);
+    assert_eq!(detail.transactions.len(), 2);
+    assert!(detail.transactions.iter().all(|tx| tx.link_id == Some(detail.group.id)));
+
+    // Resubmitting returns the group; different transactions under its key are rejected
+    let retry = group_service.submit(&group_key, requests).await.expect("Failed to resubmit group");
+    assert_eq!(retry.group.id, detail.group.id);
+    let result = group_service.submit(&group_key, split([dec!(1), dec!(1)])).await;
+    assert!(matches!(result, Err(AppError::IdempotencyConflict(_))));
+
+    // The second payment overdraws the payer, so neither posts
+    let failed_key = format!("GROUP-{}", Uuid::new_v4());
+    let result = group_service.submit(&failed_key, split([dec!(300), dec!(400)])).await;
+    assert!(matches!(result, Err(AppError::InsufficientFunds(_))));
+    let failed = group_service
+        .get_by_idempotency_key(&failed_key)
+        .await
+        .expect("Failed to get group");
+    assert_eq!(failed.group.status, TransactionGroupStatus::Failed);
+    assert_eq!(failed.group.error_code.as_deref(), Some("INSUFFICIENT_FUNDS"));
+    assert!(failed.transactions.is_empty());
+    let balance = account_service
+        .get_balance(accounts[0], "USD")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.available_balance, dec!(500));
+
+    common::cleanup_test_data(&pool).await;
+}
+
+#[tokio::test]
+async fn test_pvp_holds_both_payments_until_funded() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = Arc::new(LedgerService::new(pool.clone()));
+    let pvp_service = PvpService::new(pool.clone(), ledger_service.clone());
+
+    let mut accounts = Vec::new();
+    for (name, currency, balance) in [
+        ("Bank A USD", "USD", dec!(1000)),
+        ("Bank B USD", "USD", dec!(0)),
+        ("Bank B EUR", "EUR", dec!(0)),
+        ("Bank A EUR", "EUR", dec!(0)),
+        ("EUR funding", "EUR", dec!(5000)),
+    ] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("ACC-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.to_string(),
+                initial_balance: Some(balance),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let trade = || PvpRequest {
+        external_id: format!("FX-{}", Uuid::new_v4()),
+        idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
+        first_source_account_id: accounts[0],
+        first_destination_account_id: accounts[1],
+        first_amount: dec!(1000),
+        first_currency: "USD".to_string(),
+        second_source_account_id: accounts[2],
+        second_destination_account_id: accounts[3],
+        second_amount: dec!(920),
+        second_currency: "EUR".to_string(),
+        timeout_secs: None,
+    };
+
+    // Bank B has no euros yet, so neither payment is made
+    let pvp = pvp_service.create(trade()).await.expect("Failed to create PvP settlement");
+    assert_eq!(pvp.status, PvpStatus::AwaitingFunding);
+    assert!(pvp.first_funded);
+    assert!(!pvp.second_funded);
+    let bank_a_usd = account_service.get_balance(accounts[0], "USD").await.expect("Failed to get balance");
+    assert_eq!(bank_a_usd.available_balance, dec!(1000));
+
+    // Once Bank B is funded, both payments settle together under the settlement's link
+    ledger_service
+        .execute_transaction(LedgerTransactionRequest::payment(
+            format!("FUND-{}", Uuid::new_v4()),
+            accounts[4],
+            accounts[2],
+            dec!(920),
+            "EUR",
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to fund Bank B");
+    let settled = pvp_service.attempt(pvp.id).await.expect("Failed to attempt PvP settlement");
+    assert_eq!(settled.status, PvpStatus::Settled);
+    let bank_a_eur = account_service.get_balance(accounts[3], "EUR").await.expect("Failed to get balance");
+    assert_eq!(bank_a_eur.available_balance, dec!(920));
+    let bank_b_usd = account_service.get_balance(accounts[1], "USD").await.expect("Failed to get balance");
+    assert_eq!(bank_b_usd.available_balance, dec!(1000));
+
+    let events: Vec<EventType> = OutboxRepository::new(pool.clone())
+        .find_by_partition_key(PvpEvent::topic(), &pvp.id.to_string())
+        .await
+        .expect("Failed to read outbox")
+        .into_iter()
+        .map(|message| {
+            let envelope: EventEnvelope<PvpEvent> = serde_json::from_value(message.payload).expect("Invalid PvP event");
+            envelope.event_type
+        })
+        .collect();
+    assert!(events.contains(&EventType::PvpCreated));
+    assert!(events.contains(&EventType::PvpLegFunded));
+    assert!(events.contains(&EventType::PvpSettled));
+
+    // A settlement not funded by its deadline is cancelled with neither payment made
+    let expiring = PvpService::new(pool.clone(), ledger_service.clone()).with_timeout(std::time::Duration::ZERO);
+    let pvp = expiring.create(trade()).await.expect("Failed to create PvP settlement");
+    assert_eq!(pvp.status, PvpStatus::Cancelled);
+    assert!(pvp.cancel_reason.is_some());
+    let bank_a_usd = account_service.get_balance(accounts[0], "USD").await.expect("Failed to get balance");
+    assert_eq!(bank_a_usd.available_balance, dec!(0));
+    let result = expiring.cancel(settled.id, "Too late").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    common::cleanup_test_data(&pool).await;
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{LiquidityFlowSource, SagaStatus};
+use settlement_engine::services::{
+    BalanceGuardService, InstructionExecutor, InstructionType, LedgerService,
+    LedgerTransactionRequest, LiquidityReportService, RtgsConfig, RtgsService, SettlementInstruction,
+    SimulatedRail,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_intraday_liquidity_report() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let rtgs_service = Arc::new(RtgsService::new(pool.clone()).with_config(RtgsConfig {
+        default_threshold: None,
+        currency_thresholds: [(currency.clone(), dec!(1000))].into_iter().collect(),
+    }));
+    let ledger_service = LedgerService::new(pool.clone()).with_rtgs(rtgs_service);
+    let executor = InstructionExecutor::new(pool.clone(), Arc::new(SimulatedRail::new()));
+    let report_service = LiquidityReportService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for (name, balance) in [("Participant A", dec!(10000)), ("Participant B", dec!(0))] {
+        let account = fixtures::account(&currency).with_name(name).with_balance(balance).create(&pool).await;
+        accounts.push(account.id);
+    }
+    let (a, b) = (accounts[0], accounts[1]);
+    BalanceGuardService::new(pool.clone())
+        .set_floor(a, &currency, dec!(-3000))
+        .await
+        .expect("Failed to set floor");
+
+    // A pays gross over RTGS, then settles one net instruction in each direction
+    let payment = ledger_service
+        .process_transaction(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            a,
+            b,
+            dec!(5000),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+    for (from, to, amount) in [(b, a, dec!(2000)), (a, b, dec!(4000))] {
+        let instruction =
+            SettlementInstruction::new(Uuid::new_v4(), from, to, amount, currency.clone(), InstructionType::MultilateralNet);
+        let (state, _) = executor.execute(instruction).await.expect("Failed to execute instruction");
+        assert_eq!(state.status, SagaStatus::Completed);
+    }
+
+    let today = Utc::now().date_naive();
+    let report = report_service
+        .intraday_report(today, &currency, None)
+        .await
+        .expect("Failed to build report");
+    assert_eq!(report.participants.len(), 2);
+
+    // A: -5000, then -3000, then -7000
+    let participant_a = report.participants.iter().find(|p| p.participant_id == a).unwrap();
+    assert_eq!(participant_a.opening_balance, dec!(10000));
+    assert_eq!(participant_a.credit_line(), dec!(3000));
+    assert_eq!(participant_a.available_liquidity, dec!(13000));
+    assert_eq!(participant_a.total_outflows, dec!(9000));
+    assert_eq!(participant_a.total_inflows, dec!(2000));
+    assert_eq!(participant_a.peak_net_outflow, dec!(7000));
+    assert_eq!(participant_a.headroom(), dec!(6000));
+    assert_eq!(participant_a.flow_count, 3);
+    let largest = &participant_a.largest_obligations[0];
+    assert_eq!(largest.source, LiquidityFlowSource::Rtgs);
+    assert_eq!(largest.reference_id, payment.transaction.id);
+    assert_eq!(largest.amount, dec!(-5000));
+    assert_eq!(participant_a.largest_obligations[1].source, LiquidityFlowSource::Instruction);
+
+    // B receives before it pays, so never draws on its own liquidity
+    let participant_b = report.participants.iter().find(|p| p.participant_id == b).unwrap();
+    assert_eq!(participant_b.total_inflows, payment.transaction.net_amount + dec!(4000));
+    assert_eq!(participant_b.peak_net_outflow, dec!(0));
+
+    let report = report_service
+        .intraday_report(today, &currency, Some(a))
+        .await
+        .expect("Failed to build report");
+    assert_eq!(report.participants.len(), 1);
+    let csv = report.to_csv();
+    assert_eq!(csv.lines().count(), 2);
+    assert!(csv.lines().nth(1).unwrap().starts_with(&format!("{},{},{},10000.0000,3000.0000,13000.0000,2000.0000,9000.0000,7000.0000,", today, currency, a)));
+
+    // Nothing settled yesterday; future days cannot be reported
+    let report = report_service
+        .intraday_report(today - Duration::days(1), &currency, None)
+        .await
+        .expect("Failed to build report");
+    assert!(report.participants.is_empty());
+    let result = report_service.intraday_report(today + Duration::days(1), &currency, None).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
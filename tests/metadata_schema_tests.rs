@@ -0,0 +1,109 @@
+mod common;
+
+use rust_decimal_macros::dec;
+use serde_json::json;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{AccountType, TransactionType};
+use settlement_engine::services::{
+    AccountService, LedgerService, LedgerTransactionRequest, MetadataSchemaService,
+    account_service::CreateAccountRequest,
+};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_metadata_schema_enforced_at_ingestion() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let schema_service = MetadataSchemaService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Customer", "Fee Income"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("MS-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: "USD".to_string(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let (customer, fee_income) = (accounts[0], accounts[1]);
+
+    let fee = || {
+        LedgerTransactionRequest::fee(
+            format!("FEE-{}", Uuid::new_v4()),
+            customer,
+            fee_income,
+            dec!(5),
+            "USD",
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+    };
+
+    // Invalid schemas are rejected up front
+    let result = schema_service
+        .set_schema(TransactionType::Fee, json!({"type": "not-a-type"}))
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    schema_service
+        .set_schema(
+            TransactionType::Fee,
+            json!({
+                "type": "object",
+                "required": ["invoice_number"],
+                "properties": {"invoice_number": {"type": "string", "minLength": 1}}
+            }),
+        )
+        .await
+        .expect("Failed to set schema");
+
+    // Missing and malformed references are refused
+    let validation = ledger_service
+        .validate_transaction(&fee())
+        .await
+        .expect("Validation failed to run");
+    assert!(!validation.is_valid);
+    assert_eq!(validation.errors[0].field, "metadata");
+    assert_eq!(validation.errors[0].code, "INVALID_METADATA");
+
+    let result = ledger_service
+        .process_transaction(fee().with_metadata(json!({"invoice_number": 42})))
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    // A valid reference settles; other types are unaffected
+    ledger_service
+        .process_transaction(fee().with_metadata(json!({"invoice_number": "INV-2024-001"})))
+        .await
+        .expect("Fee with invoice number should settle");
+    ledger_service
+        .process_transaction(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            customer,
+            fee_income,
+            dec!(10),
+            "USD",
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Payment without a schema should settle");
+
+    // Removing the schema lifts the requirement
+    schema_service
+        .delete_schema(TransactionType::Fee)
+        .await
+        .expect("Failed to delete schema");
+    assert!(schema_service.list_schemas().await.unwrap().is_empty());
+    ledger_service
+        .process_transaction(fee())
+        .await
+        .expect("Fee should settle once the schema is removed");
+}
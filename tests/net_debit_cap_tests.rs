@@ -0,0 +1,185 @@
+mod common;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::api::handlers;
+use settlement_engine::api::requests::ReassignTransactionBatchRequest;
+use settlement_engine::api::validation::ValidJson;
+use settlement_engine::error::AppError;
+use settlement_engine::events::{EventEnvelope, EventType, NetDebitCapWarningEvent};
+use settlement_engine::models::NetDebitCapAction;
+use settlement_engine::repositories::OutboxRepository;
+use settlement_engine::services::{
+    BalanceService, BatchService, CreateBatchRequest, LedgerService, LedgerTransactionRequest, NetDebitCapConfig,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn payment(from: Uuid, to: Uuid, amount: rust_decimal::Decimal, currency: &str) -> LedgerTransactionRequest {
+    LedgerTransactionRequest::payment(
+        format!("NDC-{}", Uuid::new_v4()),
+        from,
+        to,
+        amount,
+        currency,
+        Uuid::new_v4().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_auto_assignment_rejects_payments_over_net_debit_cap() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let payer = fixtures::account(&currency).with_balance(dec!(5000)).create(&pool).await;
+    let payee = fixtures::account(&currency).with_balance(dec!(5000)).create(&pool).await;
+    let batch_service = Arc::new(BatchService::new(pool.clone()));
+    let ledger = LedgerService::new(pool.clone()).with_batching(batch_service.clone());
+
+    batch_service
+        .set_net_debit_cap(payer.id, &currency, dec!(1000))
+        .await
+        .expect("Failed to set cap");
+
+    ledger.process_payment(payment(payer.id, payee.id, dec!(800), &currency)).await.expect("80% should post");
+    ledger.process_payment(payment(payer.id, payee.id, dec!(160), &currency)).await.expect("96% should post");
+
+    let over = ledger.process_payment(payment(payer.id, payee.id, dec!(100), &currency)).await;
+    assert!(matches!(over, Err(AppError::LimitExceeded(_))), "got {:?}", over);
+    let balances = BalanceService::new(pool.clone());
+    assert_eq!(
+        balances.get_balance(payer.id, &currency).await.unwrap().available_balance,
+        dec!(4040),
+        "a rejected payment does not settle"
+    );
+
+    // Money coming back reduces the net debit and makes room again
+    ledger.process_payment(payment(payee.id, payer.id, dec!(500), &currency)).await.expect("Inbound payment failed");
+    ledger.process_payment(payment(payer.id, payee.id, dec!(100), &currency)).await.expect("Payment within cap failed");
+
+    let warnings: Vec<NetDebitCapWarningEvent> = OutboxRepository::new(pool.clone())
+        .find_by_partition_key(NetDebitCapWarningEvent::topic(), &payer.id.to_string())
+        .await
+        .expect("Failed to read outbox")
+        .into_iter()
+        .map(|message| {
+            let envelope: EventEnvelope<NetDebitCapWarningEvent> =
+                serde_json::from_value(message.payload).expect("Invalid warning event");
+            assert_eq!(envelope.event_type, EventType::NetDebitCapWarning);
+            envelope.payload
+        })
+        .collect();
+    assert_eq!(warnings.iter().map(|w| w.threshold_percent).collect::<Vec<_>>(), vec![80, 95]);
+    assert_eq!(warnings[1].net_debit, dec!(960));
+    assert_eq!(warnings[1].utilization_percent, dec!(96));
+    assert_eq!(warnings[1].cap, dec!(1000));
+}
+
+#[tokio::test]
+async fn test_explicit_assignment_queues_over_default_cap() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let payer = fixtures::account(&currency).with_balance(dec!(5000)).create(&pool).await;
+    let payee = fixtures::account(&currency).create(&pool).await;
+    let batch_service = BatchService::new(pool.clone()).with_net_debit_caps(NetDebitCapConfig {
+        default_cap: Some(dec!(300)),
+        on_breach: NetDebitCapAction::Queue,
+        ..NetDebitCapConfig::default()
+    });
+    let ledger = LedgerService::new(pool.clone());
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+
+    let mut assigned = Vec::new();
+    for _ in 0..3 {
+        let settled = ledger
+            .process_payment(payment(payer.id, payee.id, dec!(200), &currency))
+            .await
+            .expect("Failed to process payment");
+        assigned.push(batch_service.assign_transaction_to_batch(settled.transaction.id, batch.id).await);
+    }
+
+    let first = assigned[0].as_ref().expect("First payment fits under the cap");
+    assert_eq!(first.settlement_batch_id, Some(batch.id));
+    let queued = assigned[1].as_ref().expect("Second payment should be queued");
+    let next_batch_id = queued.settlement_batch_id.expect("Queued payment not assigned");
+    assert_ne!(next_batch_id, batch.id);
+    let next_batch = batch_service.get_batch(next_batch_id).await.expect("Failed to get next batch");
+    assert_eq!(next_batch.total_transactions, 1);
+    assert_eq!(next_batch.metadata.unwrap()["rolled_over_from"], serde_json::json!(batch.id));
+    // No headroom left in either batch
+    assert!(matches!(assigned[2], Err(AppError::LimitExceeded(_))), "got {:?}", assigned[2]);
+    assert_eq!(batch_service.get_batch(batch.id).await.unwrap().total_transactions, 1);
+}
+
+#[tokio::test]
+async fn test_reassignment_rejected_over_net_debit_cap() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let payer = fixtures::account(&currency).with_balance(dec!(5000)).create(&pool).await;
+    let payee = fixtures::account(&currency).create(&pool).await;
+    let state = common::app_state(&pool).with_net_debit_caps(NetDebitCapConfig {
+        default_cap: Some(dec!(300)),
+        ..NetDebitCapConfig::default()
+    });
+    let batch_service = state.batch_service();
+    let ledger = LedgerService::new(pool.clone());
+    let today = fixtures::batch(&currency).create(&pool).await;
+    let tomorrow = fixtures::batch(&currency)
+        .with_settlement_date(Utc::now().date_naive() + Duration::days(1))
+        .create(&pool)
+        .await;
+
+    let mut settled = Vec::new();
+    for batch in [&tomorrow, &today] {
+        let payment = ledger
+            .process_payment(payment(payer.id, payee.id, dec!(200), &currency))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(payment.transaction.id, batch.id)
+            .await
+            .expect("Payment fits under the cap in its own batch");
+        settled.push(payment.transaction.id);
+    }
+
+    // Moving the second payment would take the payer to a net debit of 400 tomorrow
+    let moved = handlers::reassign_transaction_batch(
+        State(state.clone()),
+        Path(settled[1]),
+        ValidJson(ReassignTransactionBatchRequest {
+            batch_id: tomorrow.id,
+            reason: "Wrong window".to_string(),
+        }),
+    )
+    .await;
+    let (status, _) = moved.expect_err("Reassignment over the cap should be rejected");
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let today = batch_service.get_batch(today.id).await.unwrap();
+    let tomorrow = batch_service.get_batch(tomorrow.id).await.unwrap();
+    assert_eq!((today.total_transactions, tomorrow.total_transactions), (1, 1));
+    assert_eq!(tomorrow.gross_amount, dec!(200));
+}
+
+#[tokio::test]
+async fn test_net_debit_cap_rejects_negative_and_unknown_accounts() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let account = fixtures::account(&currency).create(&pool).await;
+    let batch_service = BatchService::new(pool.clone());
+
+    let negative = batch_service.set_net_debit_cap(account.id, &currency, dec!(-1)).await;
+    assert!(matches!(negative, Err(AppError::Validation(_))));
+    let unknown = batch_service.set_net_debit_cap(Uuid::new_v4(), &currency, dec!(10)).await;
+    assert!(matches!(unknown, Err(AppError::NotFound(_))));
+
+    let cap = batch_service.set_net_debit_cap(account.id, &currency, dec!(10)).await.expect("Failed to set cap");
+    let updated = batch_service.set_net_debit_cap(account.id, &currency, dec!(25)).await.expect("Failed to update cap");
+    assert_eq!(updated.cap, dec!(25));
+    assert_eq!(updated.created_at, cap.created_at);
+}
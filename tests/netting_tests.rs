@@ -2,17 +2,19 @@ mod common;
 
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use settlement_engine::models::{AccountType, NettingPosition, NettingSummary};
+use common::fixtures::{self, unique_currency};
+use settlement_engine::error::AppError;
+use settlement_engine::models::{
+    AccountStatus, AccountType, BatchStatus, FundingStatus, InstructionStrategy, NettingMode, NettingPosition, NettingSummary, PositionSide, TransactionRecord, TransactionType,
+};
 use settlement_engine::services::{
-    AccountService, BatchService, CreateBatchRequest, LedgerService, LedgerTransactionRequest,
-    NettingService, account_service::CreateAccountRequest,
+    AccountService, BatchService, CreateBatchRequest, FundingService, InstructionType, LedgerService,
+    LedgerTransactionRequest, NettingAdviceService, NettingService, account_service::CreateAccountRequest,
 };
+use std::sync::Arc;
+use std::collections::HashMap;
 use uuid::Uuid;
 
-fn unique_currency() -> String {
-    format!("N{}", &Uuid::new_v4().to_string().replace("-", "")[..2]).to_uppercase()
-}
-
 #[tokio::test]
 async fn test_netting_service_bilateral_calculation() {
     let pool = common::setup_test_db().await;
@@ -428,6 +430,90 @@ async fn test_netting_service_persist_positions() {
     assert_eq!(summary.net_payers, 1);
 }
 
+#[tokio::test]
+async fn test_netting_position_transactions() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let netting_service = NettingService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let bank_a = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("BANK-A-{}", Uuid::new_v4()),
+            name: "Bank A".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(100000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create Bank A");
+    let bank_b = account_service
+        .create_account(CreateAccountRequest {
+            external_id: format!("BANK-B-{}", Uuid::new_v4()),
+            name: "Bank B".to_string(),
+            account_type: AccountType::Asset,
+            currency: currency.clone(),
+            initial_balance: Some(dec!(100000)),
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create Bank B");
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+
+    // Bank A pays Bank B 500, Bank B pays Bank A 200
+    for (source, destination, amount) in [(bank_a.id, bank_b.id, dec!(500)), (bank_b.id, bank_a.id, dec!(200))] {
+        let tx = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                source,
+                destination,
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service.assign_transaction_to_batch(tx.transaction.id, batch.id).await.unwrap();
+    }
+
+    let transactions = batch_service.get_batch_transactions(batch.id).await.unwrap();
+    let result = netting_service.calculate_multilateral_netting(batch.id, &currency, &transactions);
+    netting_service.persist_positions(&result.positions).await.unwrap();
+
+    let (contributions, total) = netting_service
+        .get_position_transactions(batch.id, bank_a.id, 50, 0)
+        .await
+        .expect("Failed to get position transactions");
+    assert_eq!(total, 2);
+    assert_eq!(contributions[0].side, PositionSide::Payable);
+    assert_eq!(contributions[0].counterparty_id, bank_b.id);
+    assert_eq!(contributions[0].amount, dec!(500));
+    assert_eq!(contributions[1].side, PositionSide::Receivable);
+    assert_eq!(contributions[1].amount, dec!(200));
+
+    // Pages hold at most `limit` transactions; the total counts them all
+    let (page, total) = netting_service
+        .get_position_transactions(batch.id, bank_a.id, 1, 1)
+        .await
+        .unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].transaction_id, contributions[1].transaction_id);
+
+    let result = netting_service
+        .get_position_transactions(batch.id, Uuid::new_v4(), 50, 0)
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
 #[tokio::test]
 async fn test_netting_service_generate_report() {
     let pool = common::setup_test_db().await;
@@ -636,3 +722,681 @@ async fn test_netting_summary_from_positions() {
     // Efficiency should be ~85.7%
     assert!(summary.netting_efficiency() > dec!(85));
 }
+
+#[tokio::test]
+async fn test_streaming_batch_netting_matches_in_memory() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let netting_service = NettingService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut banks = Vec::new();
+    for name in ["A", "B", "C"] {
+        let bank = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("BANK-{}-{}", name, Uuid::new_v4()),
+                name: format!("Bank {}", name),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(100000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create bank");
+        banks.push(bank.id);
+    }
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+
+    // A -> B 500, B -> C 300, C -> A 200, A -> C 100
+    for (from, to, amount) in [(0, 1, dec!(500)), (1, 2, dec!(300)), (2, 0, dec!(200)), (0, 2, dec!(100))] {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                banks[from],
+                banks[to],
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    let transactions = batch_service
+        .get_batch_transactions(batch.id)
+        .await
+        .expect("Failed to get transactions");
+    let expected = netting_service.generate_report(batch.id, &currency, &transactions);
+
+    let report = netting_service
+        .process_batch_netting_streaming(batch.id, &currency)
+        .await
+        .expect("Failed to stream batch netting");
+
+    assert_eq!(report.total_transactions, 4);
+    assert_eq!(report.gross_volume, expected.gross_volume);
+    assert_eq!(report.net_volume, expected.net_volume);
+    assert_eq!(
+        report.bilateral_result.as_ref().unwrap().total_net_volume,
+        expected.bilateral_result.as_ref().unwrap().total_net_volume
+    );
+
+    let persisted = netting_service
+        .get_batch_positions(batch.id)
+        .await
+        .expect("Failed to get positions");
+    assert_eq!(persisted.len(), 3);
+    let net_sum: Decimal = persisted.iter().map(|p| p.net_position).sum();
+    assert_eq!(net_sum, Decimal::ZERO);
+}
+
+#[tokio::test]
+async fn test_minimum_count_strategy_beats_greedy() {
+    let pool = common::setup_test_db().await;
+    let netting_service = NettingService::new(pool.clone());
+    let batch_id = Uuid::new_v4();
+    let banks: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+
+    // Net positions: A -6, B -4, C +5, D +4, E +1
+    let transactions: Vec<TransactionRecord> = [(0, 2, dec!(5)), (0, 4, dec!(1)), (1, 3, dec!(4))]
+        .into_iter()
+        .map(|(from, to, amount)| {
+            TransactionRecord::new(
+                format!("TX-{}", Uuid::new_v4()),
+                TransactionType::Payment,
+                banks[from],
+                banks[to],
+                amount,
+                "USD".to_string(),
+                Decimal::ZERO,
+                format!("IDEM-{}", Uuid::new_v4()),
+            )
+        })
+        .collect();
+
+    let greedy = netting_service.calculate_multilateral_netting_with_strategy(
+        batch_id,
+        "USD",
+        &transactions,
+        InstructionStrategy::Greedy,
+    );
+    let optimized = netting_service.calculate_multilateral_netting_with_strategy(
+        batch_id,
+        "USD",
+        &transactions,
+        InstructionStrategy::MinimumCount,
+    );
+
+    assert_eq!(greedy.instructions.len(), 4);
+    assert_eq!(optimized.instructions.len(), 3);
+
+    let moved = |r: &[settlement_engine::services::SettlementInstruction]| -> Decimal {
+        r.iter().map(|i| i.amount).sum()
+    };
+    assert_eq!(moved(&optimized.instructions), moved(&greedy.instructions));
+
+    let mut flows: HashMap<Uuid, Decimal> = HashMap::new();
+    for instruction in &optimized.instructions {
+        *flows.entry(instruction.from_participant).or_insert(Decimal::ZERO) -= instruction.amount;
+        *flows.entry(instruction.to_participant).or_insert(Decimal::ZERO) += instruction.amount;
+    }
+    for position in &optimized.positions {
+        assert_eq!(flows[&position.participant_id], position.net_position);
+    }
+}
+
+#[tokio::test]
+async fn test_batch_instruction_strategy_overrides_configured_strategy() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let ledger_service = LedgerService::new(pool.clone());
+    let netting_service = NettingService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut banks = Vec::new();
+    for _ in 0..5 {
+        banks.push(fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id);
+    }
+    let optimized = fixtures::batch(&currency)
+        .with_instruction_strategy(InstructionStrategy::MinimumCount)
+        .create(&pool)
+        .await;
+    let greedy = fixtures::batch(&currency)
+        .with_settlement_date(chrono::Utc::now().date_naive() + chrono::Duration::days(1))
+        .create(&pool)
+        .await;
+
+    // Net positions: A -6, B -4, C +5, D +4, E +1
+    for batch in [&optimized, &greedy] {
+        for (from, to, amount) in [(0, 2, dec!(5)), (0, 4, dec!(1)), (1, 3, dec!(4))] {
+            let result = ledger_service
+                .process_payment(LedgerTransactionRequest::payment(
+                    format!("PAY-{}", Uuid::new_v4()),
+                    banks[from],
+                    banks[to],
+                    amount,
+                    &currency,
+                    format!("IDEM-{}", Uuid::new_v4()),
+                ))
+                .await
+                .expect("Failed to process payment");
+            batch_service
+                .assign_transaction_to_batch(result.transaction.id, batch.id)
+                .await
+                .expect("Failed to assign transaction");
+        }
+    }
+
+    let report = netting_service
+        .process_batch_netting_streaming(optimized.id, &currency)
+        .await
+        .expect("Failed to net batch");
+    assert_eq!(report.instructions().len(), 3);
+
+    let report = netting_service
+        .process_batch_netting_streaming(greedy.id, &currency)
+        .await
+        .expect("Failed to net batch");
+    assert_eq!(report.instructions().len(), 4);
+
+    let reverted = batch_service
+        .set_instruction_strategy(optimized.id, None)
+        .await
+        .expect("Failed to clear instruction strategy");
+    assert_eq!(reverted.instruction_strategy, None);
+}
+
+#[tokio::test]
+async fn test_control_account_strategy_routes_funds_through_control_account() {
+    let pool = common::setup_test_db().await;
+    let batch_id = Uuid::new_v4();
+    let control_account = Uuid::new_v4();
+    let banks: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+
+    // Net positions: A -60, B +10, C +50
+    let transactions: Vec<TransactionRecord> = [(0, 1, dec!(40)), (0, 2, dec!(50)), (1, 0, dec!(30))]
+        .into_iter()
+        .map(|(from, to, amount)| {
+            TransactionRecord::new(
+                format!("TX-{}", Uuid::new_v4()),
+                TransactionType::Payment,
+                banks[from],
+                banks[to],
+                amount,
+                "USD".to_string(),
+                Decimal::ZERO,
+                format!("IDEM-{}", Uuid::new_v4()),
+            )
+        })
+        .collect();
+
+    let netting_service = NettingService::new(pool.clone()).with_control_account("USD", control_account);
+    let result = netting_service.calculate_multilateral_netting_with_strategy(
+        batch_id,
+        "USD",
+        &transactions,
+        InstructionStrategy::ControlAccount,
+    );
+
+    // One funding leg per net payer, one payout per net receiver
+    assert_eq!(result.instructions.len(), 3);
+    let funding: Vec<_> = result.instructions.iter().filter(|i| i.instruction_type == InstructionType::Funding).collect();
+    assert_eq!(funding.len(), 1);
+    assert_eq!(funding[0].from_participant, banks[0]);
+    assert_eq!(funding[0].to_participant, control_account);
+    assert_eq!(funding[0].amount, dec!(60));
+
+    let payouts: HashMap<Uuid, Decimal> = result
+        .instructions
+        .iter()
+        .filter(|i| i.instruction_type == InstructionType::Payout)
+        .inspect(|i| assert_eq!(i.from_participant, control_account))
+        .map(|i| (i.to_participant, i.amount))
+        .collect();
+    assert_eq!(payouts[&banks[1]], dec!(10));
+    assert_eq!(payouts[&banks[2]], dec!(50));
+
+    // The control account ends where it started
+    let paid_out: Decimal = payouts.values().copied().sum();
+    assert_eq!(paid_out, funding[0].amount);
+
+    // Without a control account in the currency, participants settle directly
+    let direct = NettingService::new(pool.clone()).calculate_multilateral_netting_with_strategy(
+        batch_id,
+        "USD",
+        &transactions,
+        InstructionStrategy::ControlAccount,
+    );
+    assert!(direct.instructions.iter().all(|i| i.instruction_type == InstructionType::MultilateralNet));
+    assert!(direct.instructions.iter().all(|i| i.from_participant != control_account && i.to_participant != control_account));
+}
+
+#[tokio::test]
+async fn test_netting_reports_are_stored_for_history() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let netting_service = NettingService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut banks = Vec::new();
+    for name in ["A", "B"] {
+        let bank = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("BANK-{}-{}", name, Uuid::new_v4()),
+                name: format!("Bank {}", name),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(10000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create bank");
+        banks.push(bank.id);
+    }
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+
+    // A -> B 400, B -> A 100: 500 gross nets down to 300
+    for (from, to, amount) in [(0, 1, dec!(400)), (1, 0, dec!(100))] {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                banks[from],
+                banks[to],
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+    let report = netting_service
+        .process_batch_netting_streaming(batch.id, &currency)
+        .await
+        .expect("Failed to net batch");
+
+    let stored = netting_service
+        .get_stored_report(batch.id)
+        .await
+        .expect("Report should be stored");
+    assert_eq!(stored.batch_id, batch.id);
+    assert_eq!(stored.gross_volume, report.gross_volume);
+    assert_eq!(stored.net_volume, report.net_volume);
+    assert_eq!(stored.total_transactions, 2);
+
+    let history = netting_service
+        .list_stored_reports(before, chrono::Utc::now() + chrono::Duration::seconds(1), Some(&currency), None)
+        .await
+        .expect("Failed to list reports");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].batch_id, batch.id);
+    assert_eq!(history[0].participant_count, 2);
+    assert_eq!(history[0].reduction_amount, report.reduction_amount);
+
+    assert!(matches!(
+        netting_service.get_stored_report(Uuid::new_v4()).await,
+        Err(settlement_engine::error::AppError::NotFound(_))
+    ));
+    assert!(netting_service
+        .list_stored_reports(chrono::Utc::now(), before, None, None)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_netting_metrics_persisted_per_currency_and_day() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut banks = Vec::new();
+    for name in ["A", "B"] {
+        let bank = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("BANK-{}-{}", name, Uuid::new_v4()),
+                name: format!("Bank {}", name),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(10000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create bank");
+        banks.push(bank.id);
+    }
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+    for (from, to, amount) in [(0, 1, dec!(600)), (1, 0, dec!(200))] {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                banks[from],
+                banks[to],
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    let report = NettingService::new(pool.clone())
+        .process_batch_netting_streaming(batch.id, &currency)
+        .await
+        .expect("Failed to net batch");
+
+    // A fresh service, as after a restart, sees the persisted totals
+    let restarted = NettingService::new(pool.clone());
+    assert_eq!(restarted.get_metrics().batches_processed, 0);
+
+    let today = report.generated_at.date_naive();
+    let daily = restarted
+        .get_daily_metrics(today, today, Some(&currency))
+        .await
+        .expect("Failed to get daily metrics");
+    assert_eq!(daily.len(), 1);
+    assert_eq!(daily[0].batches_processed, 1);
+    assert_eq!(daily[0].transactions_netted, 2);
+    assert_eq!(daily[0].gross_volume, report.gross_volume);
+    assert_eq!(daily[0].net_volume, report.net_volume);
+
+    let restored = restarted.restore_metrics().await.expect("Failed to restore metrics");
+    assert!(restored.batches_processed >= 1);
+    assert!(restored.total_gross_volume >= report.gross_volume);
+    assert_eq!(restarted.get_metrics().batches_processed, restored.batches_processed);
+}
+
+#[tokio::test]
+async fn test_bilateral_batch_netting_persists_pairs() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let mut banks = Vec::new();
+    for _ in 0..3 {
+        banks.push(fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await.id);
+    }
+    let batch = fixtures::batch(&currency)
+        .with_netting_mode(NettingMode::Bilateral)
+        .create(&pool)
+        .await;
+    assert_eq!(batch.netting_mode, NettingMode::Bilateral);
+
+    // A <-> B nets to 300, B -> C 300 and C -> A 100 don't net at all
+    for (from, to, amount) in [(0, 1, dec!(500)), (1, 0, dec!(200)), (1, 2, dec!(300)), (2, 0, dec!(100))] {
+        let result = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                banks[from],
+                banks[to],
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_batch(result.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    let report = NettingService::new(pool.clone())
+        .process_batch_netting_streaming(batch.id, &currency)
+        .await
+        .expect("Failed to net batch");
+
+    assert_eq!(report.netting_mode, NettingMode::Bilateral);
+    assert_eq!(report.gross_volume, dec!(1100));
+    assert_eq!(report.net_volume, dec!(700));
+    let instructions = report.instructions();
+    assert_eq!(instructions.len(), 3);
+    assert!(instructions.iter().all(|i| i.instruction_type == InstructionType::BilateralNet));
+    assert!(instructions
+        .iter()
+        .any(|i| i.from_participant == banks[0] && i.to_participant == banks[1] && i.amount == dec!(300)));
+
+    let pairs = batch_service
+        .get_bilateral_pairs(batch.id)
+        .await
+        .expect("Failed to get bilateral pairs");
+    assert_eq!(pairs.len(), 3);
+    let a_b = pairs
+        .iter()
+        .find(|p| p.participant_a.min(p.participant_b) == banks[0].min(banks[1]) && p.gross_volume() == dec!(700))
+        .expect("A-B pair should be stored");
+    assert_eq!(a_b.transaction_count, 2);
+    assert_eq!(a_b.payer_and_payee(), Some((banks[0], banks[1])));
+
+    // Participants' net positions are stored in either mode
+    assert_eq!(batch_service.get_batch_positions(batch.id).await.unwrap().len(), 3);
+    let stored = NettingService::new(pool.clone()).get_stored_report(batch.id).await.unwrap();
+    assert_eq!(stored.netting_mode, NettingMode::Bilateral);
+}
+
+#[tokio::test]
+async fn test_netting_mode_changes_only_while_pending() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let batch_service = BatchService::new(pool.clone());
+    let batch = fixtures::batch(&currency).create(&pool).await;
+    assert_eq!(batch.netting_mode, NettingMode::Multilateral);
+
+    let updated = batch_service
+        .set_netting_mode(batch.id, NettingMode::Bilateral)
+        .await
+        .expect("Pending batch should change mode");
+    assert_eq!(updated.netting_mode, NettingMode::Bilateral);
+
+    batch_service.close_batch(batch.id).await.expect("Failed to close batch");
+    let result = batch_service.set_netting_mode(batch.id, NettingMode::Multilateral).await;
+    assert!(matches!(result, Err(AppError::BatchClosed(_))));
+
+    let result = batch_service.set_netting_mode(Uuid::new_v4(), NettingMode::Bilateral).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_netting_advices_issued_on_completion() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let advice_service = NettingAdviceService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone()).with_advices(Arc::new(NettingAdviceService::new(pool.clone())));
+
+    let mut banks = Vec::new();
+    for name in ["Bank A", "Bank B"] {
+        let bank = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("{}-{}", name, Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(100000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create bank");
+        banks.push(bank);
+    }
+    let (bank_a, bank_b) = (&banks[0], &banks[1]);
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+
+    // Advices are only issued for completed batches
+    let early = advice_service.issue(batch.id).await;
+    assert!(matches!(early, Err(AppError::Validation(_))));
+
+    // Bank A pays Bank B 500, Bank B pays Bank A 200
+    for (source, destination, amount) in [(bank_a.id, bank_b.id, dec!(500)), (bank_b.id, bank_a.id, dec!(200))] {
+        let tx = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                source,
+                destination,
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service.assign_transaction_to_batch(tx.transaction.id, batch.id).await.unwrap();
+    }
+
+    let result = batch_service.process_batch(batch.id).await.expect("Failed to process batch");
+    assert_eq!(result.status, BatchStatus::Completed);
+
+    let advices = advice_service.list(batch.id).await.expect("Failed to list advices");
+    assert_eq!(advices.len(), 2);
+
+    // Net payers come first and are told when to fund
+    let payer = &advices[0];
+    assert_eq!(payer.participant_id, bank_a.id);
+    assert_eq!(payer.gross_payable, dec!(500));
+    assert_eq!(payer.gross_receivable, dec!(200));
+    assert_eq!(payer.net_position, dec!(-300));
+    assert!(payer.funding_deadline.is_some());
+    assert_eq!(payer.instructions.len(), 1);
+    assert_eq!(payer.instructions[0].side, PositionSide::Payable);
+    assert_eq!(payer.instructions[0].counterparty_id, bank_b.id);
+    assert_eq!(payer.instructions[0].amount, dec!(300));
+
+    let receiver = advice_service.get(batch.id, bank_b.id).await.expect("Failed to get advice");
+    assert_eq!(receiver.net_position, dec!(300));
+    assert!(receiver.funding_deadline.is_none());
+    assert_eq!(receiver.instructions[0].side, PositionSide::Receivable);
+
+    // Issuing again replaces the advices
+    let reissued = advice_service.issue(batch.id).await.expect("Failed to reissue advices");
+    assert_eq!(reissued.len(), 2);
+    assert_eq!(advice_service.list(batch.id).await.unwrap().len(), 2);
+
+    let missing = advice_service.get(batch.id, Uuid::new_v4()).await;
+    assert!(matches!(missing, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_missed_funding_deadline_escalates_participant() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let funding_service = Arc::new(FundingService::new(pool.clone()));
+    let batch_service = BatchService::new(pool.clone()).with_funding(funding_service.clone());
+
+    let mut banks = Vec::new();
+    for name in ["Bank A", "Bank B"] {
+        let bank = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("{}-{}", name, Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: currency.clone(),
+                initial_balance: Some(dec!(100000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create bank");
+        banks.push(bank);
+    }
+    let (bank_a, bank_b) = (&banks[0], &banks[1]);
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .expect("Failed to create batch");
+
+    // Bank A pays Bank B 500, Bank B pays Bank A 200
+    for (source, destination, amount) in [(bank_a.id, bank_b.id, dec!(500)), (bank_b.id, bank_a.id, dec!(200))] {
+        let tx = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                source,
+                destination,
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service.assign_transaction_to_batch(tx.transaction.id, batch.id).await.unwrap();
+    }
+
+    let result = batch_service.process_batch(batch.id).await.expect("Failed to process batch");
+    assert_eq!(result.status, BatchStatus::Completed);
+
+    // Only the net payer owes funding
+    let obligations = funding_service.list_by_batch(batch.id).await.expect("Failed to list obligations");
+    assert_eq!(obligations.len(), 1);
+    let obligation = &obligations[0];
+    assert_eq!(obligation.participant_id, bank_a.id);
+    assert_eq!(obligation.amount, dec!(300));
+    assert_eq!(obligation.status, FundingStatus::Pending);
+
+    // Opening again keeps the existing obligation
+    let reopened = funding_service.open_obligations(batch.id).await.unwrap();
+    assert_eq!(reopened.len(), 1);
+    assert_eq!(reopened[0].id, obligation.id);
+
+    // Nothing is escalated before the deadline
+    funding_service.sweep().await.expect("Failed to sweep");
+    assert_eq!(funding_service.get(obligation.id).await.unwrap().status, FundingStatus::Pending);
+
+    sqlx::query("UPDATE funding_obligations SET deadline = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(obligation.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let sweep = funding_service.sweep().await.expect("Failed to sweep");
+    assert!(sweep.escalated >= 1);
+
+    let overdue = funding_service.get(obligation.id).await.unwrap();
+    assert_eq!(overdue.status, FundingStatus::Overdue);
+    assert!(overdue.escalated_at.is_some());
+    assert_eq!(account_service.find_by_id(bank_a.id).await.unwrap().status, AccountStatus::Frozen);
+    assert_eq!(account_service.find_by_id(bank_b.id).await.unwrap().status, AccountStatus::Active);
+
+    let missing = funding_service.get(Uuid::new_v4()).await;
+    assert!(matches!(missing, Err(AppError::NotFound(_))));
+}
@@ -133,6 +133,14 @@ fn test_metrics_db_query() {
     metrics.record_db_query("update", 15.0, false);
 }
 
+#[test]
+fn test_metrics_named_query() {
+    let metrics = Metrics::new();
+    metrics.record_named_query("transactions.insert", 3.0, true, false);
+    metrics.record_named_query("account_balances.adjust", 400.0, true, true);
+    metrics.record_named_query("ledger_entries.insert", 2.0, false, false);
+}
+
 #[test]
 fn test_metrics_redis_operation() {
     let metrics = Metrics::new();
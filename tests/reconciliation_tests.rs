@@ -0,0 +1,119 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::models::AlertRuleType;
+use settlement_engine::notifications::NotificationEngine;
+use settlement_engine::services::{
+    AlertService, CreateAlertRuleRequest, LedgerService, LedgerTransactionRequest,
+    ReconciliationService,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_reconciliation_records_and_resolves_breaks() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let ledger_service = LedgerService::new(pool.clone());
+    let alert_service = AlertService::new(pool.clone());
+    let reconciliation = ReconciliationService::new(pool.clone())
+        .with_notifications(Arc::new(NotificationEngine::new(pool.clone())));
+
+    let mut accounts = Vec::new();
+    for (prefix, balance) in [("SRC", dec!(1000)), ("DST", dec!(50))] {
+        let account = fixtures::account(&currency).with_name(prefix).with_balance(balance).create(&pool).await;
+        accounts.push(account);
+    }
+    let (source, dest) = (&accounts[0], &accounts[1]);
+
+    for amount in [dec!(100), dec!(40)] {
+        ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                source.id,
+                dest.id,
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+    }
+
+    let rule = alert_service
+        .create_rule(CreateAlertRuleRequest {
+            account_id: Some(dest.id),
+            rule_type: AlertRuleType::BalanceBreak,
+            currency: currency.clone(),
+            threshold: None,
+            webhook_url: None,
+            suppression_window_seconds: None,
+            metadata: None,
+        })
+        .await
+        .expect("Failed to create alert rule");
+
+    // Balances posted through the ledger reconcile, including the opening balances
+    let run = reconciliation.run().await.expect("Failed to reconcile");
+    assert!(run.accounts_checked >= 2);
+    assert!(run.breaks.iter().all(|b| b.account_id != source.id && b.account_id != dest.id));
+
+    // A balance changed outside the ledger is a break
+    sqlx::query("UPDATE account_balances SET available_balance = available_balance + 25 WHERE account_id = $1")
+        .bind(dest.id)
+        .execute(&pool)
+        .await
+        .expect("Failed to tamper with balance");
+
+    let run = reconciliation.run().await.expect("Failed to reconcile");
+    let found = run
+        .breaks
+        .iter()
+        .find(|b| b.account_id == dest.id)
+        .expect("Break should be found")
+        .clone();
+    assert_eq!(found.stored_balance, dec!(215));
+    assert_eq!(found.derived_balance, dec!(190));
+    assert_eq!(found.difference, dec!(25));
+    assert_eq!(found.entry_count, 2);
+    assert!(found.is_open());
+
+    let alerted = alert_service.get_rule(rule.id).await.expect("Failed to get rule");
+    assert!(alerted.last_triggered_at.is_some());
+
+    // Later runs refresh the open break rather than opening another
+    let run = reconciliation.run().await.expect("Failed to reconcile");
+    let again = run.breaks.iter().find(|b| b.account_id == dest.id).expect("Break should remain");
+    assert_eq!(again.id, found.id);
+    assert_eq!(again.detected_at, found.detected_at);
+    assert!(again.last_seen_at > found.last_seen_at);
+    let open = reconciliation
+        .list_breaks(true, Some(dest.id), None, 50, 0)
+        .await
+        .expect("Failed to list breaks");
+    assert_eq!(open.len(), 1);
+
+    // Once the balance is corrected the break resolves
+    sqlx::query("UPDATE account_balances SET available_balance = available_balance - 25 WHERE account_id = $1")
+        .bind(dest.id)
+        .execute(&pool)
+        .await
+        .expect("Failed to correct balance");
+    reconciliation.run().await.expect("Failed to reconcile");
+
+    assert_eq!(
+        reconciliation
+            .count_breaks(true, Some(dest.id), Some(&currency))
+            .await
+            .expect("Failed to count breaks"),
+        0
+    );
+    let history = reconciliation
+        .list_breaks(false, Some(dest.id), Some(&currency), 50, 0)
+        .await
+        .expect("Failed to list breaks");
+    assert_eq!(history.len(), 1);
+    assert!(history[0].resolved_at.is_some());
+}
@@ -3,13 +3,15 @@ mod common;
 use chrono::{Duration, NaiveDate, Utc};
 use rust_decimal_macros::dec;
 use settlement_engine::models::{
-    Account, AccountBalance, AccountStatus, AccountType, BatchStatus, EntryType, LedgerEntry,
-    NettingPosition, SettlementBatch, TransactionRecord, TransactionStatus, TransactionType,
+    Account, AccountBalance, AccountStatus, AccountType, AlertRule, AlertRuleType, BatchStatus,
+    EntryType, LedgerEntry, NettingPosition, SettlementBatch, TransactionRecord,
+    TransactionStatus, TransactionType,
 };
 use settlement_engine::repositories::{
-    AccountRepository, BalanceRepository, BatchRepository, LedgerRepository, NettingRepository,
-    TransactionRepository,
+    AccountRepository, AlertRuleRepository, BalanceRepository, BatchRepository, LedgerRepository,
+    NettingMetricsRepository, NettingRepository, TransactionRepository,
 };
+use settlement_engine::notifications::NotificationEngine;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -430,6 +432,30 @@ async fn test_batch_repository_lifecycle() {
     common::cleanup_test_data(&pool).await;
 }
 
+#[tokio::test]
+async fn test_concurrent_batch_creation_numbers_window_consecutively() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let settlement_date = NaiveDate::from_ymd_opt(2026, 1, 16).unwrap();
+    let cut_off = Utc::now() + Duration::hours(2);
+    let creations = (0..8).map(|_| {
+        let batch_repo = BatchRepository::new(pool.clone());
+        let batch = SettlementBatch::new(settlement_date, cut_off, "USD".to_string());
+        tokio::spawn(async move { batch_repo.create(&batch).await })
+    });
+
+    let mut sequence_numbers = Vec::new();
+    for creation in creations.collect::<Vec<_>>() {
+        let created = creation.await.unwrap().expect("Failed to create batch");
+        sequence_numbers.push(created.sequence_number);
+    }
+    sequence_numbers.sort();
+    assert_eq!(sequence_numbers, (1..=8).collect::<Vec<_>>());
+
+    common::cleanup_test_data(&pool).await;
+}
+
 #[tokio::test]
 async fn test_netting_repository_operations() {
     let pool = common::setup_test_db().await;
@@ -590,3 +616,133 @@ async fn test_transaction_idempotency() {
 
     common::cleanup_test_data(&pool).await;
 }
+
+#[tokio::test]
+async fn test_alert_rule_repository_suppression() {
+    let pool = common::setup_test_db().await;
+
+    let account_repo = AccountRepository::new(pool.clone());
+    let rule_repo = AlertRuleRepository::new(pool.clone());
+
+    let account = account_repo
+        .create(&Account::new(
+            format!("EXT-{}", Uuid::new_v4()),
+            "Alert Account".to_string(),
+            AccountType::Asset,
+            "USD".to_string(),
+        ))
+        .await
+        .expect("Failed to create account");
+
+    let rule = AlertRule::new(AlertRuleType::LowBalance, "USD".to_string(), Some(dec!(100)))
+        .for_account(account.id)
+        .with_suppression_window(3600);
+    let created = rule_repo.create(&rule).await.expect("Failed to create rule");
+    assert_eq!(created.rule_type, AlertRuleType::LowBalance);
+
+    let applicable = rule_repo
+        .find_enabled_for_accounts(&[account.id], "USD")
+        .await
+        .expect("Failed to find rules");
+    assert!(applicable.iter().any(|r| r.id == created.id));
+
+    // First trigger claims the window, second is suppressed
+    let now = Utc::now();
+    let first = rule_repo.mark_triggered(created.id, account.id, now).await.expect("Failed to mark");
+    assert!(first.is_some());
+    let second = rule_repo
+        .mark_triggered(created.id, account.id, now + Duration::seconds(60))
+        .await
+        .expect("Failed to mark");
+    assert!(second.is_none());
+    let after_window = rule_repo
+        .mark_triggered(created.id, account.id, now + Duration::seconds(3601))
+        .await
+        .expect("Failed to mark");
+    assert!(after_window.is_some());
+
+    assert!(rule_repo.delete(created.id).await.expect("Failed to delete"));
+    assert!(rule_repo.find_by_id(created.id).await.expect("Failed to find").is_none());
+}
+
+#[tokio::test]
+async fn test_alert_rule_for_every_account_is_suppressed_per_account() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let a = common::fixtures::account(&currency).create(&pool).await.id;
+    let b = common::fixtures::account(&currency).create(&pool).await.id;
+
+    let rule = AlertRule::new(AlertRuleType::LowBalance, currency.clone(), Some(dec!(100))).with_suppression_window(3600);
+    AlertRuleRepository::new(pool.clone()).create(&rule).await.expect("Failed to create rule");
+
+    let engine = NotificationEngine::new(pool.clone());
+    let tx = TransactionRecord::new(
+        format!("TX-{}", Uuid::new_v4()),
+        TransactionType::Payment,
+        a,
+        b,
+        dec!(10),
+        currency.clone(),
+        dec!(0),
+        format!("IDEM-{}", Uuid::new_v4()),
+    );
+    let balances: Vec<AccountBalance> = [a, b]
+        .into_iter()
+        .map(|account_id| AccountBalance::with_available_balance(account_id, currency.clone(), dec!(50)))
+        .collect();
+
+    // Both accounts breach the rule; neither suppresses the other
+    let alerts = engine.evaluate_settlement(&tx, &balances).await;
+    let mut alerted: Vec<Uuid> = alerts.iter().map(|alert| alert.account_id).collect();
+    alerted.sort();
+    let mut expected = vec![a, b];
+    expected.sort();
+    assert_eq!(alerted, expected);
+
+    // Each is suppressed on its own within the window
+    assert!(engine.evaluate_settlement(&tx, &balances).await.is_empty());
+    let c = common::fixtures::account(&currency).create(&pool).await.id;
+    let alerts = engine
+        .evaluate_settlement(&tx, &[AccountBalance::with_available_balance(c, currency.clone(), dec!(50))])
+        .await;
+    assert_eq!(alerts.iter().map(|alert| alert.account_id).collect::<Vec<_>>(), vec![c]);
+}
+
+#[tokio::test]
+async fn test_netting_metrics_count_a_batch_recorded_twice_once() {
+    let pool = common::setup_test_db().await;
+    let repo = NettingMetricsRepository::new(pool.clone());
+    let currency = common::fixtures::unique_currency();
+    let today = Utc::now().date_naive();
+    let batch_id = Uuid::new_v4();
+
+    repo.record_batch(Uuid::new_v4(), today, &currency, 2, dec!(300), dec!(100))
+        .await
+        .expect("Failed to record batch");
+    repo.record_batch(batch_id, today, &currency, 4, dec!(1000), dec!(200))
+        .await
+        .expect("Failed to record batch");
+
+    // Netting the batch again replaces its contribution
+    let daily = repo
+        .record_batch(batch_id, today, &currency, 5, dec!(1200), dec!(250))
+        .await
+        .expect("Failed to record batch again");
+    assert_eq!(daily.batches_processed, 2);
+    assert_eq!(daily.transactions_netted, 7);
+    assert_eq!(daily.gross_volume, dec!(1500));
+    assert_eq!(daily.net_volume, dec!(350));
+
+    // A batch netted again on a later day moves to that day
+    let tomorrow = today + Duration::days(1);
+    repo.record_batch(batch_id, tomorrow, &currency, 5, dec!(1200), dec!(250))
+        .await
+        .expect("Failed to record batch on the next day");
+    let days = repo
+        .find_by_period(today, tomorrow, Some(&currency))
+        .await
+        .expect("Failed to get daily metrics");
+    assert_eq!(days.len(), 2);
+    assert_eq!((days[0].batches_processed, days[0].gross_volume), (1, dec!(300)));
+    assert_eq!((days[1].batches_processed, days[1].gross_volume), (1, dec!(1200)));
+}
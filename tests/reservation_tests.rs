@@ -0,0 +1,121 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{BalanceReservation, BalanceReservationStatus, TransactionStatus};
+use settlement_engine::services::{BalanceService, ExpiryService, LedgerService, LedgerTransactionRequest};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_reservation_captured_into_payment() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let payer = fixtures::account(&currency).create(&pool).await.id;
+    let payee = fixtures::account(&currency).create(&pool).await.id;
+    let balances = BalanceService::new(pool.clone());
+    let ledger = LedgerService::new(pool.clone());
+
+    let reservation = balances
+        .create_reservation(BalanceReservation::new(payer, &currency, dec!(100), "checkout", "ORDER-1"))
+        .await
+        .expect("Failed to reserve");
+    assert_eq!(reservation.status, BalanceReservationStatus::Active);
+
+    let duplicate = balances
+        .create_reservation(BalanceReservation::new(payer, &currency, dec!(1), "checkout", "ORDER-1"))
+        .await;
+    assert!(matches!(duplicate, Err(AppError::Validation(_))), "references are unique per account");
+
+    // Everything is reserved, so an ordinary payment cannot spend it
+    let payment = |amount, key: &str| {
+        LedgerTransactionRequest::payment(format!("PAY-{}", Uuid::new_v4()), payer, payee, amount, &currency, key)
+    };
+    let result = ledger.execute_transaction(payment(dec!(10), &Uuid::new_v4().to_string())).await;
+    assert!(matches!(result, Err(AppError::InsufficientFunds(_))));
+
+    let key = Uuid::new_v4().to_string();
+    let captured = ledger
+        .capture_reservation(reservation.id, payment(dec!(60), &key))
+        .await
+        .expect("Failed to capture reservation");
+    assert_eq!(captured.transaction.status, TransactionStatus::Settled);
+
+    let reservation = balances.get_reservation(payer, reservation.id).await.unwrap();
+    assert_eq!(reservation.status, BalanceReservationStatus::Captured);
+    assert_eq!(reservation.transaction_id, Some(captured.transaction.id));
+
+    // The uncaptured rest goes back to the payer
+    let payer_balance = balances.get_balance(payer, &currency).await.unwrap();
+    assert_eq!(payer_balance.available_balance, dec!(40));
+    assert_eq!(payer_balance.reserved_balance, dec!(0));
+    assert_eq!(balances.get_balance(payee, &currency).await.unwrap().available_balance, dec!(160));
+
+    // Retries return the captured payment; a new capture or a release is refused
+    let retried = ledger.capture_reservation(reservation.id, payment(dec!(60), &key)).await.unwrap();
+    assert_eq!(retried.transaction.id, captured.transaction.id);
+    let again = ledger
+        .capture_reservation(reservation.id, payment(dec!(10), &Uuid::new_v4().to_string()))
+        .await;
+    assert!(matches!(again, Err(AppError::Validation(_))));
+    assert!(balances.release_reservation_by_id(payer, reservation.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_reservations_release_and_expire() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let payer = fixtures::account(&currency).create(&pool).await.id;
+    let payee = fixtures::account(&currency).create(&pool).await.id;
+    let balances = BalanceService::new(pool.clone());
+    let ledger = LedgerService::new(pool.clone());
+
+    let released = balances
+        .create_reservation(BalanceReservation::new(payer, &currency, dec!(30), "hold", "REL-1"))
+        .await
+        .unwrap();
+    let expiring = balances
+        .create_reservation(
+            BalanceReservation::new(payer, &currency, dec!(50), "hold", "EXP-1")
+                .with_expiry(Utc::now() + Duration::milliseconds(500)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(balances.get_balance(payer, &currency).await.unwrap().available_balance, dec!(20));
+
+    let over = balances
+        .create_reservation(BalanceReservation::new(payer, &currency, dec!(50), "hold", "OVER-1"))
+        .await;
+    assert!(matches!(over, Err(AppError::InsufficientFunds(_))));
+
+    let released = balances.release_reservation_by_id(payer, released.id).await.unwrap();
+    assert_eq!(released.status, BalanceReservationStatus::Released);
+    assert!(balances.get_reservation(payee, released.id).await.is_err(), "reservations are scoped to their account");
+
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+    let capture = ledger
+        .capture_reservation(
+            expiring.id,
+            LedgerTransactionRequest::payment("PAY-EXP", payer, payee, dec!(50), &currency, Uuid::new_v4().to_string()),
+        )
+        .await;
+    assert!(matches!(capture, Err(AppError::Validation(_))), "expired reservations cannot be captured");
+
+    // Sweepers of concurrent tests may hold the reservation locked, so poll for it
+    let expiry = ExpiryService::new(pool.clone());
+    let mut expired = expiring;
+    for _ in 0..50 {
+        expiry.expire_due().await.expect("Expiry sweep failed");
+        expired = balances.get_reservation(payer, expired.id).await.unwrap();
+        if expired.status != BalanceReservationStatus::Active {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(expired.status, BalanceReservationStatus::Expired);
+
+    let payer_balance = balances.get_balance(payer, &currency).await.unwrap();
+    assert_eq!(payer_balance.available_balance, dec!(100));
+    assert_eq!(payer_balance.reserved_balance, dec!(0));
+}
@@ -0,0 +1,286 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{DuplicatePaymentAction, RiskHoldAction, RiskHoldStatus, RiskTrigger};
+use settlement_engine::services::{LedgerService, LedgerTransactionRequest, RiskConfig, RiskService};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn payment(from: Uuid, to: Uuid, amount: Decimal, currency: &str) -> LedgerTransactionRequest {
+    LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        from,
+        to,
+        amount,
+        currency,
+        format!("IDEM-{}", Uuid::new_v4()),
+    )
+}
+
+fn services(pool: &PgPool, config: RiskConfig) -> (Arc<RiskService>, LedgerService) {
+    let risk = Arc::new(RiskService::new(pool.clone()).with_config(config));
+    let ledger = LedgerService::new(pool.clone()).with_risk(risk.clone());
+    (risk, ledger)
+}
+
+#[tokio::test]
+async fn test_large_amount_held_until_released() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let (risk, ledger) = services(
+        &pool,
+        RiskConfig {
+            currency_amount_thresholds: [(currency.clone(), dec!(1000))].into_iter().collect(),
+            ..RiskConfig::default()
+        },
+    );
+
+    ledger
+        .process_transaction(payment(a, b, dec!(1000), &currency))
+        .await
+        .expect("Payment at the threshold should post");
+
+    let request = payment(a, b, dec!(5000), &currency);
+    let result = ledger.process_transaction(request.clone()).await;
+    assert!(matches!(result, Err(AppError::HeldForReview(_))));
+
+    // Retries return the same hold
+    let result = ledger.process_transaction(request.clone()).await;
+    assert!(matches!(result, Err(AppError::HeldForReview(_))));
+    let holds = risk
+        .list_holds(Some(RiskHoldStatus::Held), 100, 0)
+        .await
+        .expect("Failed to list holds");
+    let hold = holds
+        .into_iter()
+        .find(|h| h.idempotency_key == request.idempotency_key)
+        .expect("Hold not listed");
+    assert_eq!(hold.amount, dec!(5000));
+    let triggers: Vec<RiskTrigger> = serde_json::from_value(hold.triggers.clone()).unwrap();
+    assert_eq!(triggers, vec![RiskTrigger::LargeAmount { threshold: dec!(1000) }]);
+
+    let result = ledger.release_held(hold.id, "reviewer", "").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let released = ledger
+        .release_held(hold.id, "reviewer", "Known customer")
+        .await
+        .expect("Failed to release hold");
+    assert_eq!(released.transaction.amount, dec!(5000));
+
+    let hold = risk.get_hold(hold.id).await.expect("Failed to get hold");
+    assert_eq!(hold.status, RiskHoldStatus::Released);
+    assert_eq!(hold.transaction_id, Some(released.transaction.id));
+    assert_eq!(hold.reviewed_by.as_deref(), Some("reviewer"));
+
+    // Retrying the request returns the posted transaction
+    let retried = ledger.process_transaction(request).await.expect("Retry should succeed");
+    assert_eq!(retried.transaction.id, released.transaction.id);
+
+    let result = risk.reject(hold.id, "reviewer", "Too late").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let audit = risk.hold_audit(hold.id).await.expect("Failed to get audit");
+    let actions: Vec<RiskHoldAction> = audit.iter().map(|a| a.action).collect();
+    assert_eq!(actions, vec![RiskHoldAction::Held, RiskHoldAction::Released]);
+    assert_eq!(audit[1].reason.as_deref(), Some("Known customer"));
+}
+
+#[tokio::test]
+async fn test_rejected_hold_is_never_posted() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let (risk, ledger) = services(
+        &pool,
+        RiskConfig {
+            default_amount_threshold: Some(dec!(100)),
+            ..RiskConfig::default()
+        },
+    );
+
+    let request = payment(a, b, dec!(500), &currency);
+    let result = ledger.process_transaction(request.clone()).await;
+    let Err(AppError::HeldForReview(_)) = result else {
+        panic!("Payment should be held");
+    };
+    let hold = risk
+        .list_holds(Some(RiskHoldStatus::Held), 100, 0)
+        .await
+        .expect("Failed to list holds")
+        .into_iter()
+        .find(|h| h.idempotency_key == request.idempotency_key)
+        .expect("Hold not listed");
+
+    let rejected = risk
+        .reject(hold.id, "reviewer", "Suspected fraud")
+        .await
+        .expect("Failed to reject hold");
+    assert_eq!(rejected.status, RiskHoldStatus::Rejected);
+    assert_eq!(rejected.transaction_id, None);
+
+    let result = ledger.process_transaction(request).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let result = ledger.release_held(hold.id, "reviewer", "Changed my mind").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let audit = risk.hold_audit(hold.id).await.expect("Failed to get audit");
+    assert_eq!(audit.last().unwrap().action, RiskHoldAction::Rejected);
+    assert_eq!(audit.last().unwrap().actor.as_deref(), Some("reviewer"));
+}
+
+#[tokio::test]
+async fn test_new_counterparty_and_velocity_triggers() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let (a, b, c) = (
+        fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id,
+        fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id,
+        fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id,
+    );
+    let (risk, ledger) = services(
+        &pool,
+        RiskConfig {
+            new_counterparty: true,
+            ..RiskConfig::default()
+        },
+    );
+
+    let request = payment(a, b, dec!(10), &currency);
+    let triggers = risk.evaluate(&request).await.expect("Failed to evaluate");
+    assert_eq!(triggers, vec![RiskTrigger::NewCounterparty]);
+    assert!(ledger.process_transaction(request.clone()).await.is_err());
+    let hold = risk
+        .list_holds(Some(RiskHoldStatus::Held), 100, 0)
+        .await
+        .expect("Failed to list holds")
+        .into_iter()
+        .find(|h| h.idempotency_key == request.idempotency_key)
+        .expect("Hold not listed");
+    ledger
+        .release_held(hold.id, "reviewer", "Verified beneficiary")
+        .await
+        .expect("Failed to release hold");
+
+    // A has now settled with B, but not with C
+    assert!(risk.evaluate(&payment(a, b, dec!(10), &currency)).await.unwrap().is_empty());
+    assert_eq!(
+        risk.evaluate(&payment(a, c, dec!(10), &currency)).await.unwrap(),
+        vec![RiskTrigger::NewCounterparty]
+    );
+
+    let (risk, ledger) = services(
+        &pool,
+        RiskConfig {
+            velocity_max_count: Some(3),
+            velocity_max_amount: Some(dec!(1000)),
+            ..RiskConfig::default()
+        },
+    );
+    ledger
+        .process_transaction(payment(a, b, dec!(20), &currency))
+        .await
+        .expect("Second payment should post");
+
+    // A has sent 2 (30); a 4th payment exceeds the count and a large one the amount
+    let triggers = risk.evaluate(&payment(a, c, dec!(975), &currency)).await.unwrap();
+    assert!(matches!(
+        triggers.as_slice(),
+        [RiskTrigger::Velocity { count: 3, amount, .. }] if *amount == dec!(1005)
+    ));
+    ledger
+        .process_transaction(payment(a, c, dec!(10), &currency))
+        .await
+        .expect("Third payment should post");
+    let result = ledger.process_transaction(payment(a, c, dec!(10), &currency)).await;
+    assert!(matches!(result, Err(AppError::HeldForReview(_))));
+
+    // Other accounts are unaffected
+    assert!(risk.evaluate(&payment(b, a, dec!(10), &currency)).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_duplicate_payment_rejected_unless_overridden() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let (_, ledger) = services(
+        &pool,
+        RiskConfig {
+            duplicate_window_secs: Some(600),
+            duplicate_action: DuplicatePaymentAction::Reject,
+            ..RiskConfig::default()
+        },
+    );
+
+    let first = ledger
+        .process_transaction(payment(a, b, dec!(250), &currency))
+        .await
+        .expect("First payment should post");
+
+    // Retrying under the same idempotency key is not a duplicate
+    let mut retry = payment(a, b, dec!(250), &currency);
+    retry.idempotency_key = first.transaction.idempotency_key.clone();
+    let retried = ledger.process_transaction(retry).await.expect("Retry should succeed");
+    assert_eq!(retried.transaction.id, first.transaction.id);
+
+    let result = ledger.process_transaction(payment(a, b, dec!(250), &currency)).await;
+    let Err(AppError::PossibleDuplicate(message)) = result else {
+        panic!("Repeat should be rejected, got {:?}", result);
+    };
+    assert!(message.contains(&first.transaction.id.to_string()));
+
+    // Another amount or direction is not a duplicate
+    ledger.process_transaction(payment(a, b, dec!(251), &currency)).await.expect("Other amount should post");
+    ledger.process_transaction(payment(b, a, dec!(250), &currency)).await.expect("Other direction should post");
+
+    let repeat = payment(a, b, dec!(250), &currency).with_duplicate_override(first.transaction.id);
+    ledger.process_transaction(repeat).await.expect("Overridden repeat should post");
+}
+
+#[tokio::test]
+async fn test_duplicate_payment_held_for_review() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let b = fixtures::account(&currency).with_balance(dec!(100000)).create(&pool).await.id;
+    let (risk, ledger) = services(
+        &pool,
+        RiskConfig {
+            duplicate_window_secs: Some(600),
+            ..RiskConfig::default()
+        },
+    );
+
+    let first = ledger
+        .process_transaction(payment(a, b, dec!(75), &currency))
+        .await
+        .expect("First payment should post");
+    let request = payment(a, b, dec!(75), &currency);
+    let result = ledger.process_transaction(request.clone()).await;
+    assert!(matches!(result, Err(AppError::HeldForReview(_))), "got {:?}", result);
+
+    let hold = risk
+        .list_holds(Some(RiskHoldStatus::Held), 100, 0)
+        .await
+        .expect("Failed to list holds")
+        .into_iter()
+        .find(|h| h.idempotency_key == request.idempotency_key)
+        .expect("Hold not listed");
+    let triggers: Vec<RiskTrigger> = serde_json::from_value(hold.triggers).unwrap();
+    assert_eq!(
+        triggers,
+        vec![RiskTrigger::PossibleDuplicate {
+            transaction_id: first.transaction.id,
+            window_secs: 600,
+        }]
+    );
+}
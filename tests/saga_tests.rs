@@ -0,0 +1,141 @@
+mod common;
+
+use async_trait::async_trait;
+use rust_decimal_macros::dec;
+use settlement_engine::error::{AppError, Result};
+use settlement_engine::models::{AccountType, SagaStatus};
+use settlement_engine::repositories::SagaRepository;
+use settlement_engine::services::{
+    AccountService, AcknowledgementStatus, BalanceService, InstructionExecutor, InstructionStatus,
+    InstructionType, SettlementInstruction, SettlementRail, account_service::CreateAccountRequest,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Rail that answers every status query with a fixed acknowledgement.
+struct ScriptedRail {
+    acknowledgement: AcknowledgementStatus,
+    cancelled: Mutex<Vec<String>>,
+}
+
+impl ScriptedRail {
+    fn new(acknowledgement: AcknowledgementStatus) -> Arc<Self> {
+        Arc::new(Self {
+            acknowledgement,
+            cancelled: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait]
+impl SettlementRail for ScriptedRail {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    async fn submit(&self, instruction: &SettlementInstruction) -> Result<String> {
+        Ok(format!("EXT-{}", instruction.id))
+    }
+
+    async fn status(&self, _reference: &str) -> Result<AcknowledgementStatus> {
+        Ok(self.acknowledgement.clone())
+    }
+
+    async fn cancel(&self, reference: &str) -> Result<()> {
+        self.cancelled.lock().unwrap().push(reference.to_string());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_instruction_execution_saga() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let balance_service = BalanceService::new(pool.clone());
+    let saga_repo = SagaRepository::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Payer", "Payee"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("SAGA-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: "USD".to_string(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let (payer, payee) = (accounts[0], accounts[1]);
+
+    let instruction = || {
+        SettlementInstruction::new(
+            Uuid::new_v4(),
+            payer,
+            payee,
+            dec!(250),
+            "USD".to_string(),
+            InstructionType::MultilateralNet,
+        )
+    };
+
+    // Accepted: every step runs and the hold is released on finalize
+    let rail = ScriptedRail::new(AcknowledgementStatus::Accepted);
+    let executor = InstructionExecutor::new(pool.clone(), rail.clone());
+    let (state, executed) = executor.execute(instruction()).await.expect("Saga failed to run");
+
+    assert_eq!(state.status, SagaStatus::Completed);
+    assert_eq!(state.current_step, 4);
+    assert_eq!(executed.status, InstructionStatus::Executed);
+    assert_eq!(
+        state.context["external_reference"],
+        format!("EXT-{}", executed.id).as_str()
+    );
+    let persisted = saga_repo.find_by_id(state.id).await.unwrap().expect("Saga not persisted");
+    assert_eq!(persisted.status, SagaStatus::Completed);
+
+    // Rejected: the instruction is cancelled and the reservation released
+    let rail = ScriptedRail::new(AcknowledgementStatus::Rejected("Beneficiary closed".to_string()));
+    let executor = InstructionExecutor::new(pool.clone(), rail.clone());
+    let (state, failed) = executor.execute(instruction()).await.expect("Saga failed to run");
+
+    assert_eq!(state.status, SagaStatus::Compensated);
+    assert_eq!(failed.status, InstructionStatus::Failed);
+    assert!(state.error.unwrap().contains("Beneficiary closed"));
+    assert_eq!(*rail.cancelled.lock().unwrap(), vec![format!("EXT-{}", failed.id)]);
+
+    // Pending past the acknowledgement timeout is compensated the same way
+    let rail = ScriptedRail::new(AcknowledgementStatus::Pending);
+    let executor = InstructionExecutor::new(pool.clone(), rail.clone())
+        .with_acknowledgement_polling(Duration::from_millis(10), Duration::from_millis(100));
+    let (state, _) = executor.execute(instruction()).await.expect("Saga failed to run");
+
+    assert_eq!(state.status, SagaStatus::Compensated);
+    assert!(state.error.unwrap().contains("await_acknowledgement"));
+    assert_eq!(rail.cancelled.lock().unwrap().len(), 1);
+
+    let balance = balance_service
+        .get_balance(payer, "USD")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.reserved_balance, dec!(0));
+    assert_eq!(balance.available_balance, dec!(1000));
+
+    // Insufficient funds fails the first step with nothing to undo
+    let rail = ScriptedRail::new(AcknowledgementStatus::Accepted);
+    let executor = InstructionExecutor::new(pool.clone(), rail.clone());
+    let mut oversized = instruction();
+    oversized.amount = dec!(5000);
+    let (state, _) = executor.execute(oversized).await.expect("Saga failed to run");
+    assert_eq!(state.status, SagaStatus::Compensated);
+    assert_eq!(state.current_step, 0);
+
+    let result = executor.resume(Uuid::new_v4()).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
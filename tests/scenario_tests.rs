@@ -0,0 +1,41 @@
+mod common;
+
+use common::fixtures::unique_currency;
+use rust_decimal::Decimal;
+use settlement_engine::services::BalanceService;
+use settlement_engine::tools::{PaymentPattern, ScenarioConfig, ScenarioGenerator};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_circular_scenario_nets_down_to_one_round() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    // Equal amounts round a ring of four cancel out, except for an unfinished last round
+    let config = ScenarioConfig::default()
+        .with_participants(4)
+        .with_currency(&currency)
+        .with_mix(vec![(PaymentPattern::Circular, 1)])
+        .with_rate(200.0, Duration::from_millis(300))
+        .with_amounts(Decimal::new(500, 2), Decimal::new(500, 2))
+        .with_concurrency(1);
+
+    let report = ScenarioGenerator::new(pool.clone(), config)
+        .run()
+        .await
+        .expect("Scenario failed");
+
+    assert_eq!(report.currency, currency);
+    assert_eq!(report.participants.len(), 4);
+    assert!(report.submitted > 0);
+    assert_eq!(report.settled, report.submitted, "failures: {:?}", report.failures);
+    assert_eq!(report.by_pattern.get(&PaymentPattern::Circular), Some(&report.submitted));
+    assert!(report.gross_volume > Decimal::ZERO);
+    assert!(report.net_volume <= Decimal::new(2000, 2), "net volume {}", report.net_volume);
+
+    let balances = BalanceService::new(pool.clone());
+    let mut total = Decimal::ZERO;
+    for participant in &report.participants {
+        total += balances.get_balance(*participant, &currency).await.unwrap().available_balance;
+    }
+    assert_eq!(total, Decimal::from(4_000_000), "payments move money between participants only");
+}
@@ -1,7 +1,12 @@
 mod common;
 
+use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use settlement_engine::models::{AccountStatus, AccountType, TransactionType};
+use settlement_engine::core::money::Money;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{
+    AccountStatus, AccountType, StatusChangeReason, StatusReasonCode, TransactionType,
+};
 use settlement_engine::services::{
     AccountService, BalanceService, DoubleEntryEngine,
     account_service::CreateAccountRequest,
@@ -9,6 +14,10 @@ use settlement_engine::services::{
 };
 use uuid::Uuid;
 
+fn usd(amount: Decimal) -> Money {
+    Money::new(amount, "USD").unwrap()
+}
+
 #[tokio::test]
 async fn test_account_service_create_and_find() {
     let pool = common::setup_test_db().await;
@@ -68,23 +77,58 @@ async fn test_account_service_status_management() {
     let account = service.create_account(request).await.expect("Failed to create account");
 
     // Freeze account
-    let frozen = service.freeze_account(account.id).await.expect("Failed to freeze");
+    let frozen = service
+        .freeze_account(
+            account.id,
+            StatusChangeReason::new(StatusReasonCode::SuspectedFraud).with_note("chargeback spike"),
+        )
+        .await
+        .expect("Failed to freeze");
     assert_eq!(frozen.status, AccountStatus::Frozen);
 
-    // Validate for transaction should fail
+    // Validate for transaction should fail and explain why
     let validation = service.validate_for_transaction(account.id).await;
-    assert!(validation.is_err());
+    match validation {
+        Err(AppError::AccountFrozen(msg)) => assert!(msg.contains("SUSPECTED_FRAUD: chargeback spike")),
+        other => panic!("Expected account frozen error, got {:?}", other),
+    }
 
     // Activate account
-    let activated = service.activate_account(account.id).await.expect("Failed to activate");
+    let activated = service
+        .activate_account(account.id, StatusChangeReason::new(StatusReasonCode::ReviewCleared))
+        .await
+        .expect("Failed to activate");
     assert_eq!(activated.status, AccountStatus::Active);
 
     // Close account (balance is zero)
-    let closed = service.close_account(account.id).await.expect("Failed to close");
+    let closed = service
+        .close_account(account.id, StatusChangeReason::new(StatusReasonCode::CustomerRequest))
+        .await
+        .expect("Failed to close");
     assert_eq!(closed.status, AccountStatus::Closed);
 
+    // Every transition is recorded in order
+    let history = service
+        .get_status_history(account.id)
+        .await
+        .expect("Failed to get status history");
+    let transitions: Vec<_> = history
+        .iter()
+        .map(|c| (c.from_status, c.to_status, c.reason_code))
+        .collect();
+    assert_eq!(
+        transitions,
+        vec![
+            (AccountStatus::Active, AccountStatus::Frozen, StatusReasonCode::SuspectedFraud),
+            (AccountStatus::Frozen, AccountStatus::Active, StatusReasonCode::ReviewCleared),
+            (AccountStatus::Active, AccountStatus::Closed, StatusReasonCode::CustomerRequest),
+        ]
+    );
+
     // Cannot freeze closed account
-    let freeze_closed = service.freeze_account(account.id).await;
+    let freeze_closed = service
+        .freeze_account(account.id, StatusChangeReason::new(StatusReasonCode::Other))
+        .await;
     assert!(freeze_closed.is_err());
 
     common::cleanup_test_data(&pool).await;
@@ -155,21 +199,21 @@ async fn test_balance_service_operations() {
 
     // Credit
     let credited = balance_service
-        .credit(account.id, "USD", dec!(500))
+        .credit(account.id, &usd(dec!(500)))
         .await
         .expect("Failed to credit");
     assert_eq!(credited.available_balance, dec!(1500));
 
     // Debit
     let debited = balance_service
-        .debit(account.id, "USD", dec!(200))
+        .debit(account.id, &usd(dec!(200)))
         .await
         .expect("Failed to debit");
     assert_eq!(debited.available_balance, dec!(1300));
 
     // Reserve
     let reserved = balance_service
-        .reserve(account.id, "USD", dec!(100))
+        .reserve(account.id, &usd(dec!(100)))
         .await
         .expect("Failed to reserve");
     assert_eq!(reserved.available_balance, dec!(1200));
@@ -184,7 +228,7 @@ async fn test_balance_service_operations() {
 
     // Release reservation
     let released = balance_service
-        .release_reservation(account.id, "USD", dec!(50))
+        .release_reservation(account.id, &usd(dec!(50)))
         .await
         .expect("Failed to release");
     assert_eq!(released.available_balance, dec!(1250));
@@ -221,12 +265,12 @@ async fn test_balance_service_insufficient_funds() {
     let account = account_service.create_account(request).await.expect("Failed to create account");
 
     // Debit more than available should fail
-    let result = balance_service.debit(account.id, "USD", dec!(200)).await;
+    let result = balance_service.debit(account.id, &usd(dec!(200))).await;
     assert!(result.is_err());
 
     // Validate sufficient funds
     let validation = balance_service
-        .validate_sufficient_funds(account.id, "USD", dec!(200))
+        .validate_sufficient_funds(account.id, &usd(dec!(200)))
         .await;
     assert!(validation.is_err());
 
@@ -278,6 +322,11 @@ async fn test_double_entry_engine_basic_transaction() {
         idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
 
     let result = engine.execute_transaction(request).await.expect("Failed to execute transaction");
@@ -354,6 +403,11 @@ async fn test_double_entry_engine_idempotency() {
         idempotency_key: idempotency_key.clone(),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
 
     let result1 = engine.execute_transaction(request1).await.expect("Failed first transaction");
@@ -370,6 +424,11 @@ async fn test_double_entry_engine_idempotency() {
         idempotency_key: idempotency_key.clone(),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
 
     let result2 = engine.execute_transaction(request2).await.expect("Failed second transaction");
@@ -431,6 +490,11 @@ async fn test_double_entry_engine_insufficient_funds() {
         idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
 
     let result = engine.execute_transaction(request).await;
@@ -490,6 +554,11 @@ async fn test_double_entry_engine_reversal() {
         idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
 
     let original = engine
@@ -569,6 +638,11 @@ async fn test_double_entry_engine_validation() {
         idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
     assert!(engine.execute_transaction(request).await.is_err());
 
@@ -596,6 +670,11 @@ async fn test_double_entry_engine_validation() {
         idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
     assert!(engine.execute_transaction(request).await.is_err());
 
@@ -611,6 +690,11 @@ async fn test_double_entry_engine_validation() {
         idempotency_key: format!("IDEM-{}", Uuid::new_v4()),
         effective_date: None,
         metadata: None,
+        external_id_claim: None,
+        type_code: None,
+        exclude_from_netting: false,
+        narrative: None,
+        reference: None,
     };
     assert!(engine.execute_transaction(request).await.is_err());
 
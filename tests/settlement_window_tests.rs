@@ -0,0 +1,286 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use chrono::{NaiveTime, Utc};
+use futures::future::join_all;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::services::{
+    BatchService, CreateSettlementWindowRequest, LedgerService, LedgerTransactionRequest,
+    NettingService, SettlementWindowService, UpdateSettlementWindowRequest,
+};
+use uuid::Uuid;
+
+fn window_request(name: &str, currency: &str, hour: u32) -> CreateSettlementWindowRequest {
+    CreateSettlementWindowRequest {
+        name: name.to_string(),
+        currency: currency.to_string(),
+        cut_off_time: NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+        timezone: "Europe/Berlin".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_settlement_window_crud() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let window_service = SettlementWindowService::new(pool.clone());
+
+    let morning = window_service
+        .create_window(window_request("morning", &currency, 10))
+        .await
+        .expect("Failed to create window");
+    window_service
+        .create_window(window_request("afternoon", &currency, 16))
+        .await
+        .expect("Failed to create window");
+
+    let duplicate = window_service.create_window(window_request("morning", &currency, 11)).await;
+    assert!(matches!(duplicate, Err(AppError::Validation(_))));
+
+    let mut bad_timezone = window_request("evening", &currency, 20);
+    bad_timezone.timezone = "Mars/Olympus".to_string();
+    assert!(matches!(
+        window_service.create_window(bad_timezone).await,
+        Err(AppError::Validation(_))
+    ));
+
+    let windows = window_service
+        .list_windows(Some(&currency), false)
+        .await
+        .expect("Failed to list windows");
+    let names: Vec<_> = windows.iter().map(|w| w.name.as_str()).collect();
+    assert_eq!(names, vec!["morning", "afternoon"]);
+
+    let updated = window_service
+        .update_window(
+            morning.id,
+            UpdateSettlementWindowRequest {
+                cut_off_time: Some(NaiveTime::from_hms_opt(9, 30, 0).unwrap()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to update window");
+    assert_eq!(updated.cut_off_time, NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+
+    let deactivated = window_service
+        .deactivate_window(morning.id)
+        .await
+        .expect("Failed to deactivate window");
+    assert!(!deactivated.active);
+
+    let active = window_service
+        .list_windows(Some(&currency), true)
+        .await
+        .expect("Failed to list windows");
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].name, "afternoon");
+}
+
+#[tokio::test]
+async fn test_batches_created_per_window() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let window_service = SettlementWindowService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let morning = window_service
+        .create_window(window_request("morning", &currency, 10))
+        .await
+        .expect("Failed to create window");
+    let afternoon = window_service
+        .create_window(window_request("afternoon", &currency, 16))
+        .await
+        .expect("Failed to create window");
+
+    let morning_batch = batch_service
+        .get_or_create_window_batch(morning.id)
+        .await
+        .expect("Failed to open morning batch");
+    let afternoon_batch = batch_service
+        .get_or_create_window_batch(afternoon.id)
+        .await
+        .expect("Failed to open afternoon batch");
+
+    assert_ne!(morning_batch.id, afternoon_batch.id);
+    assert_eq!(morning_batch.window_id, Some(morning.id));
+    assert_eq!(afternoon_batch.window_id, Some(afternoon.id));
+    assert!(morning_batch.cut_off_time > Utc::now());
+    assert_eq!(
+        Some(morning_batch.cut_off_time),
+        morning.next_cut_off(Utc::now()).map(|(_, cut_off)| cut_off)
+    );
+
+    // The same window reuses its open batch
+    let again = batch_service
+        .get_or_create_window_batch(morning.id)
+        .await
+        .expect("Failed to get morning batch");
+    assert_eq!(again.id, morning_batch.id);
+
+    // The current batch for the currency is the window closing soonest
+    let current = batch_service
+        .get_or_create_current_batch(&currency)
+        .await
+        .expect("Failed to get current batch");
+    let soonest = if morning_batch.cut_off_time < afternoon_batch.cut_off_time {
+        morning_batch.id
+    } else {
+        afternoon_batch.id
+    };
+    assert_eq!(current.id, soonest);
+}
+
+#[tokio::test]
+async fn test_transaction_routed_to_window() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let ledger_service = LedgerService::new(pool.clone());
+    let window_service = SettlementWindowService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+
+    let source = fixtures::account(&currency).with_name("Source").with_balance(dec!(1000)).create(&pool).await;
+    let dest = fixtures::account(&currency).with_name("Destination").with_balance(dec!(0)).create(&pool).await;
+
+    let afternoon = window_service
+        .create_window(window_request("afternoon", &currency, 16))
+        .await
+        .expect("Failed to create window");
+    let other_currency = window_service
+        .create_window(window_request("afternoon", &unique_currency(), 16))
+        .await
+        .expect("Failed to create window");
+
+    let payment = ledger_service
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source.id,
+            dest.id,
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+
+    let mismatch = batch_service
+        .assign_transaction_to_window(payment.transaction.id, other_currency.id)
+        .await;
+    assert!(matches!(mismatch, Err(AppError::Validation(_))));
+
+    let assigned = batch_service
+        .assign_transaction_to_window(payment.transaction.id, afternoon.id)
+        .await
+        .expect("Failed to assign transaction to window");
+    let batch_id = assigned.settlement_batch_id.expect("Transaction not batched");
+
+    let batch = batch_service.get_batch(batch_id).await.expect("Failed to get batch");
+    assert_eq!(batch.window_id, Some(afternoon.id));
+    assert_eq!(batch.total_transactions, 1);
+    assert_eq!(batch.gross_amount, dec!(100));
+}
+
+#[tokio::test]
+async fn test_concurrent_windows_in_one_currency() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let ledger_service = LedgerService::new(pool.clone());
+    let window_service = SettlementWindowService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let netting_service = NettingService::new(pool.clone());
+
+    // Two schemes settling the same currency with the same cut-off
+    let scheme_a = window_service
+        .create_window(window_request("scheme-a", &currency, 16))
+        .await
+        .expect("Failed to create window");
+    let scheme_b = window_service
+        .create_window(window_request("scheme-b", &currency, 16))
+        .await
+        .expect("Failed to create window");
+
+    // Requests racing to open a window's batch all get the same one
+    let opened = join_all((0..4).map(|_| batch_service.get_or_create_window_batch(scheme_a.id))).await;
+    let batch_a = opened[0].as_ref().expect("Failed to open scheme A batch").clone();
+    assert!(opened.iter().all(|batch| batch.as_ref().map(|b| b.id).ok() == Some(batch_a.id)));
+
+    let batch_b = batch_service
+        .get_or_create_window_batch(scheme_b.id)
+        .await
+        .expect("Failed to open scheme B batch");
+    assert_ne!(batch_a.id, batch_b.id);
+    assert_eq!(batch_a.settlement_date, batch_b.settlement_date);
+    assert_eq!(batch_a.sequence_number, 1);
+    assert_eq!(batch_b.sequence_number, 1);
+
+    // Uniqueness is scoped to the date, currency and window
+    let duplicate = sqlx::query(
+        r#"
+        INSERT INTO settlement_batches (id, status, settlement_date, cut_off_time, currency, window_id, sequence_number)
+        VALUES ($1, 'PENDING', $2, $3, $4, $5, 1)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(batch_a.settlement_date)
+    .bind(batch_a.cut_off_time)
+    .bind(&currency)
+    .bind(scheme_a.id)
+    .execute(&pool)
+    .await;
+    assert!(duplicate.is_err());
+
+    let scheme_a_batches = batch_service
+        .list_batches(None, None, Some(scheme_a.id), 10, 0)
+        .await
+        .expect("Failed to list batches");
+    assert_eq!(scheme_a_batches.iter().map(|b| b.id).collect::<Vec<_>>(), vec![batch_a.id]);
+
+    // Each window nets its own batch, and its reports can be listed on their own
+    let mut accounts = Vec::new();
+    for name in ["Source", "Destination"] {
+        let account = fixtures::account(&currency)
+            .with_name(name)
+            .with_balance(dec!(1000))
+            .create(&pool)
+            .await;
+        accounts.push(account);
+    }
+    for (window, amount) in [(&scheme_a, dec!(100)), (&scheme_b, dec!(40))] {
+        let payment = ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                accounts[0].id,
+                accounts[1].id,
+                amount,
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+        batch_service
+            .assign_transaction_to_window(payment.transaction.id, window.id)
+            .await
+            .expect("Failed to assign transaction to window");
+    }
+
+    let before = Utc::now() - chrono::Duration::seconds(1);
+    let mut netted = Vec::new();
+    for batch in [&batch_a, &batch_b] {
+        let report = netting_service
+            .process_batch_netting_streaming(batch.id, &currency)
+            .await
+            .expect("Failed to net batch");
+        netted.push(report);
+    }
+    let reports = netting_service
+        .list_stored_reports(before, Utc::now() + chrono::Duration::seconds(1), None, Some(scheme_a.id))
+        .await
+        .expect("Failed to list reports");
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].batch_id, batch_a.id);
+    assert_eq!(reports[0].window_id, Some(scheme_a.id));
+    assert_eq!(reports[0].total_transactions, 1);
+    assert_eq!(reports[0].gross_volume, netted[0].gross_volume);
+    assert_ne!(netted[0].gross_volume, netted[1].gross_volume);
+}
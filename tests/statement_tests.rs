@@ -0,0 +1,128 @@
+mod common;
+
+use axum::{routing::post, Router};
+use chrono::{Duration, Utc};
+use rust_decimal_macros::dec;
+use settlement_engine::core::money::Money;
+use settlement_engine::error::AppError;
+use settlement_engine::interop::camt::StatementType;
+use settlement_engine::models::AccountType;
+use settlement_engine::services::{
+    AccountService, BalanceService, LedgerService, LedgerTransactionRequest, StatementService,
+    account_service::CreateAccountRequest,
+};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_account_statements() {
+    let pool = common::setup_test_db().await;
+    common::cleanup_test_data(&pool).await;
+
+    let account_service = AccountService::new(pool.clone());
+    let ledger_service = LedgerService::new(pool.clone());
+    let balance_service = BalanceService::new(pool.clone());
+    let statement_service = StatementService::new(pool.clone());
+
+    let mut accounts = Vec::new();
+    for name in ["Participant A", "Participant B"] {
+        let account = account_service
+            .create_account(CreateAccountRequest {
+                external_id: format!("STMT-{}", Uuid::new_v4()),
+                name: name.to_string(),
+                account_type: AccountType::Asset,
+                currency: "USD".to_string(),
+                initial_balance: Some(dec!(1000)),
+                metadata: None,
+            })
+            .await
+            .expect("Failed to create account");
+        accounts.push(account.id);
+    }
+    let (a, b) = (accounts[0], accounts[1]);
+
+    for (from, to, amount) in [(a, b, dec!(300)), (b, a, dec!(50.25))] {
+        ledger_service
+            .process_payment(LedgerTransactionRequest::payment(
+                format!("PAY-{}", Uuid::new_v4()),
+                from,
+                to,
+                amount,
+                "USD",
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to process payment");
+    }
+    balance_service
+        .reserve(a, &Money::new(dec!(100), "USD").unwrap())
+        .await
+        .expect("Failed to reserve");
+
+    let today = Utc::now().date_naive();
+
+    // End of day: opening balance predates today's entries
+    let statement = statement_service
+        .generate(a, StatementType::Camt053, today, None)
+        .await
+        .expect("Failed to generate statement");
+    assert_eq!(statement.opening_balance, dec!(1000));
+    assert_eq!(statement.closing_balance, dec!(750.25));
+    assert_eq!(statement.entries.len(), 2);
+    assert_eq!(statement.debit_totals(), (1, dec!(300)));
+    assert_eq!(statement.credit_totals(), (1, dec!(50.25)));
+    assert!(statement.available_balance.is_none());
+
+    let xml = statement.to_xml();
+    assert!(xml.contains("camt.053.001.08"));
+    assert!(xml.contains("<Cd>CLBD</Cd></CdOrPrtry></Tp><Amt Ccy=\"USD\">750.25</Amt>"));
+
+    // Intraday: interim available balance excludes the reservation
+    let report = statement_service
+        .generate(a, StatementType::Camt052, today, Some("USD"))
+        .await
+        .expect("Failed to generate report");
+    assert_eq!(report.closing_balance, dec!(750.25));
+    assert_eq!(report.available_balance, Some(dec!(650.25)));
+
+    // Yesterday's statement has no entries and carries the opening balance through
+    let yesterday = statement_service
+        .generate(a, StatementType::Camt053, today - Duration::days(1), None)
+        .await
+        .expect("Failed to generate statement");
+    assert!(yesterday.entries.is_empty());
+    assert_eq!(yesterday.closing_balance, dec!(1000));
+
+    let result = statement_service
+        .generate(a, StatementType::Camt052, today - Duration::days(1), None)
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let result = statement_service
+        .generate(a, StatementType::Camt053, today + Duration::days(1), None)
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+    let result = statement_service
+        .generate(Uuid::new_v4(), StatementType::Camt053, today, None)
+        .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+
+    // Webhook delivery posts the XML document
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let app = Router::new().route(
+        "/statements",
+        post(move |body: String| async move {
+            sink.lock().unwrap().push(body);
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/statements", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    statement_service
+        .deliver_webhook(&statement, &url)
+        .await
+        .expect("Failed to deliver statement");
+    assert_eq!(*received.lock().unwrap(), vec![xml]);
+}
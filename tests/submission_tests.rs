@@ -0,0 +1,122 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use chrono::Duration;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{SubmissionStatus, TransactionStatus};
+use settlement_engine::services::{BalanceService, LedgerService, LedgerTransactionRequest, SubmissionService};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Runs the workers until every listed submission has an outcome. Submissions of
+/// concurrently running tests may be settled along the way.
+async fn drain(service: &SubmissionService, ids: &[Uuid]) {
+    for _ in 0..100 {
+        service.process_due().await.expect("Failed to process submissions");
+        let mut done = true;
+        for id in ids {
+            let submission = service.get_submission(*id).await.expect("Failed to get submission");
+            done &= submission.status.is_final();
+        }
+        if done {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    panic!("Submissions did not complete");
+}
+
+#[tokio::test]
+async fn test_submitted_transactions_settle_asynchronously() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let source = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let dest = fixtures::account(&currency).with_balance(dec!(0)).create(&pool).await.id;
+    // Payments from one account conflict when settled concurrently; they are retried
+    let service = SubmissionService::new(pool.clone(), Arc::new(LedgerService::new(pool.clone())))
+        .with_concurrency(10, 4)
+        .with_retry_policy(20, Duration::milliseconds(10));
+
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        let submission = service
+            .submit(LedgerTransactionRequest::payment(
+                format!("PAY-{}-{}", i, Uuid::new_v4()),
+                source,
+                dest,
+                dec!(100),
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            ))
+            .await
+            .expect("Failed to submit");
+        assert_eq!(submission.status, SubmissionStatus::Queued);
+        assert!(submission.transaction_id.is_none());
+        ids.push(submission.id);
+    }
+
+    drain(&service, &ids).await;
+
+    let ledger_service = LedgerService::new(pool.clone());
+    for id in &ids {
+        let submission = service.get_submission(*id).await.expect("Failed to get submission");
+        assert_eq!(submission.status, SubmissionStatus::Settled, "{:?}", submission.last_error);
+        let transaction = ledger_service
+            .get_transaction(submission.transaction_id.expect("No transaction recorded"))
+            .await
+            .expect("Failed to get transaction");
+        assert_eq!(transaction.status, TransactionStatus::Settled);
+        assert_eq!(transaction.idempotency_key, submission.idempotency_key);
+    }
+
+    let balance = BalanceService::new(pool.clone())
+        .get_balance(source, &currency)
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.available_balance, dec!(500));
+}
+
+#[tokio::test]
+async fn test_submission_validation_and_failures() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let source = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await.id;
+    let dest = fixtures::account(&currency).with_balance(dec!(0)).create(&pool).await.id;
+    let service = SubmissionService::new(pool.clone(), Arc::new(LedgerService::new(pool.clone())));
+
+    // Invalid requests are rejected before they are queued
+    let invalid = service
+        .submit(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source,
+            Uuid::new_v4(),
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await;
+    assert!(matches!(invalid, Err(AppError::NotFound(_))));
+
+    // Resubmitting an idempotency key returns the queued submission
+    let idempotency_key = format!("IDEM-{}", Uuid::new_v4());
+    let overdraft = LedgerTransactionRequest::payment(
+        format!("PAY-{}", Uuid::new_v4()),
+        source,
+        dest,
+        dec!(5000),
+        &currency,
+        idempotency_key.clone(),
+    );
+    let first = service.submit(overdraft.clone()).await.expect("Failed to submit");
+    let again = service.submit(overdraft).await.expect("Failed to resubmit");
+    assert_eq!(first.id, again.id);
+
+    // Business rule rejections fail without retries
+    drain(&service, &[first.id]).await;
+    let failed = service.get_submission(first.id).await.expect("Failed to get submission");
+    assert_eq!(failed.status, SubmissionStatus::Failed);
+    assert_eq!(failed.attempts, 1);
+    assert_eq!(failed.error_code.as_deref(), Some("INSUFFICIENT_FUNDS"));
+    assert!(failed.transaction_id.is_none());
+}
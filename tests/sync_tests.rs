@@ -0,0 +1,122 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{
+    Account, AccountStatus, StatusChangeReason, StatusReasonCode, TransactionRecord,
+};
+use settlement_engine::services::{
+    AccountService, LedgerService, LedgerTransactionRequest, SyncPage, SyncService,
+};
+use std::future::Future;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Reads the feed from `token` until the token's window is exhausted, returning every
+/// item read and the token to poll with next.
+async fn drain<T, F, Fut>(token: Option<String>, read: F) -> (Vec<T>, String, bool)
+where
+    F: Fn(Option<String>) -> Fut,
+    Fut: Future<Output = settlement_engine::error::Result<SyncPage<T>>>,
+{
+    let mut items = Vec::new();
+    let mut token = token;
+    let mut snapshot = false;
+    loop {
+        let page = read(token.clone()).await.expect("Failed to read sync page");
+        snapshot |= page.snapshot;
+        items.extend(page.items);
+        token = Some(page.next_token.encode());
+        if !page.has_more {
+            return (items, token.unwrap(), snapshot);
+        }
+    }
+}
+
+/// Polls the feed until `found` matches an item, returning the token after it. Writes
+/// show up once every older database transaction has finished.
+async fn poll_until<T, F, Fut>(token: Option<String>, read: F, found: impl Fn(&T) -> bool) -> String
+where
+    F: Fn(Option<String>) -> Fut,
+    Fut: Future<Output = settlement_engine::error::Result<SyncPage<T>>>,
+{
+    let mut token = token;
+    for attempt in 0..100 {
+        let from_start = token.is_none();
+        let (items, next, snapshot) = drain(token, &read).await;
+        assert_eq!(snapshot, attempt == 0 && from_start);
+        token = Some(next);
+        if items.iter().any(&found) {
+            return token.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Change never appeared in the sync feed");
+}
+
+#[tokio::test]
+async fn test_account_snapshot_then_changes() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let account = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let sync = SyncService::new(pool.clone());
+    let read = |token: Option<String>| {
+        let sync = &sync;
+        async move { sync.accounts(token.as_deref(), 200).await }
+    };
+
+    let token = poll_until(None, read, |a: &Account| a.id == account.id).await;
+
+    // Caught up: nothing changed since the token
+    let (items, token, snapshot) = drain(Some(token), read).await;
+    assert!(!snapshot);
+    assert!(items.iter().all(|a| a.id != account.id));
+
+    // An update shows up again, at its latest state
+    AccountService::new(pool.clone())
+        .freeze_account(account.id, StatusChangeReason::new(StatusReasonCode::CustomerRequest))
+        .await
+        .expect("Failed to freeze account");
+    poll_until(Some(token), read, |a: &Account| {
+        a.id == account.id && a.status == AccountStatus::Frozen
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_transaction_changes() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let a = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let b = fixtures::account(&currency).with_balance(dec!(1000)).create(&pool).await;
+    let sync = SyncService::new(pool.clone());
+    let read = |token: Option<String>| {
+        let sync = &sync;
+        async move { sync.transactions(token.as_deref(), 200).await }
+    };
+    let (_, token, _) = drain(None, read).await;
+
+    let result = LedgerService::new(pool.clone())
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            a.id,
+            b.id,
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .expect("Failed to process payment");
+
+    poll_until(Some(token), read, |t: &TransactionRecord| t.id == result.transaction.id).await;
+}
+
+#[tokio::test]
+async fn test_invalid_token_rejected() {
+    let pool = common::setup_test_db().await;
+    let sync = SyncService::new(pool);
+
+    let result = sync.accounts(Some("garbage"), 10).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::TransactionStatus;
+use settlement_engine::services::{
+    BatchService, CreateBatchRequest, LedgerService, LedgerTransactionRequest, NettingService,
+    TimelineEventKind, TransactionTimelineService,
+};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_timeline_covers_transaction_lifecycle() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+
+    let ledger_service = LedgerService::new(pool.clone());
+    let batch_service = BatchService::new(pool.clone());
+    let timeline_service = TransactionTimelineService::new(pool.clone());
+
+    let source = fixtures::account(&currency).with_name("Source").with_balance(dec!(1000)).create(&pool).await;
+    let dest = fixtures::account(&currency).with_name("Destination").with_balance(dec!(1000)).create(&pool).await;
+
+    let payment = ledger_service
+        .process_payment(LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            source.id,
+            dest.id,
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .unwrap()
+        .transaction;
+
+    let batch = batch_service
+        .create_batch(CreateBatchRequest::for_today(&currency, 24))
+        .await
+        .unwrap();
+    batch_service.assign_transaction_to_batch(payment.id, batch.id).await.unwrap();
+    NettingService::new(pool.clone())
+        .process_batch_netting_streaming(batch.id, &currency)
+        .await
+        .unwrap();
+    batch_service.trigger_batch_processing(batch.id).await.unwrap();
+
+    let refund = ledger_service
+        .process_refund(LedgerTransactionRequest::refund(
+            format!("REF-{}", Uuid::new_v4()),
+            payment.id,
+            dest.id,
+            source.id,
+            dec!(40),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        ))
+        .await
+        .unwrap()
+        .transaction;
+
+    let timeline = timeline_service.get_timeline(payment.id).await.unwrap();
+    assert_eq!(timeline.transaction_id, payment.id);
+    assert_eq!(timeline.status, TransactionStatus::Settled);
+
+    let kinds: Vec<_> = timeline.events.iter().map(|event| event.kind).collect();
+    assert_eq!(kinds[0], TimelineEventKind::Created);
+    assert_eq!(kinds.iter().filter(|kind| **kind == TimelineEventKind::LedgerEntry).count(), 2);
+    assert_eq!(kinds.iter().filter(|kind| **kind == TimelineEventKind::NettingIncluded).count(), 2);
+    for expected in [
+        TimelineEventKind::Validated,
+        TimelineEventKind::Settled,
+        TimelineEventKind::BatchAssigned,
+        TimelineEventKind::Finalized,
+    ] {
+        assert!(kinds.contains(&expected), "missing {:?} in {:?}", expected, kinds);
+    }
+
+    let reversal = timeline
+        .events
+        .iter()
+        .find(|event| event.kind == TimelineEventKind::Reversal)
+        .expect("refund should appear on the original's timeline");
+    assert_eq!(reversal.details["transaction_id"], serde_json::json!(refund.id));
+
+    assert!(timeline
+        .events
+        .windows(2)
+        .all(|pair| pair[0].occurred_at <= pair[1].occurred_at));
+}
+
+#[tokio::test]
+async fn test_timeline_for_unknown_transaction() {
+    let pool = common::setup_test_db().await;
+    let timeline_service = TransactionTimelineService::new(pool);
+
+    let result = timeline_service.get_timeline(Uuid::new_v4()).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
@@ -0,0 +1,303 @@
+mod common;
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::{TransactionType, TransactionTypeDefinition, TypeFeePolicy};
+use settlement_engine::services::{
+    BatchService, ExclusionReason, InstructionType, LedgerService, LedgerTransactionRequest, NettingService,
+    TransactionTypeService,
+};
+use uuid::Uuid;
+
+fn unique_code(prefix: &str) -> String {
+    format!("{}_{}", prefix, &Uuid::new_v4().simple().to_string()[..8]).to_uppercase()
+}
+
+#[tokio::test]
+async fn test_custom_types_drive_validation_and_reversal() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let bank_a = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let bank_b = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let ledger = LedgerService::new(pool.clone());
+    let types = TransactionTypeService::new(pool.clone());
+
+    let interchange = unique_code("INTERCHANGE_FEE");
+    let mut definition = TransactionTypeDefinition::new(&interchange, TransactionType::Payment);
+    definition.fee_policy = TypeFeePolicy::Exempt;
+    types.register(definition).await.expect("Failed to register type");
+
+    let adjustment = unique_code("ADJUSTMENT");
+    let mut definition = TransactionTypeDefinition::new(&adjustment, TransactionType::Transfer);
+    definition.reversible = false;
+    types.register(definition).await.expect("Failed to register type");
+
+    let invalid = types.register(TransactionTypeDefinition::new("PAYMENT", TransactionType::Transfer)).await;
+    assert!(matches!(invalid, Err(AppError::Validation(_))));
+    assert!(types.list().await.unwrap().iter().any(|d| d.code == adjustment));
+
+    let payment = |code: &str| {
+        LedgerTransactionRequest::payment(
+            format!("PAY-{}", Uuid::new_v4()),
+            bank_a.id,
+            bank_b.id,
+            dec!(100),
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+        .with_type_code(code)
+    };
+
+    // Unknown codes, codes of another base type and fees on exempt types are rejected
+    let unknown = ledger.process_payment(payment("NOT_REGISTERED")).await;
+    assert!(matches!(unknown, Err(AppError::Validation(_))));
+    let mismatched = ledger.process_payment(payment(&adjustment)).await;
+    assert!(matches!(mismatched, Err(AppError::Validation(_))));
+    let with_fee = ledger.process_payment(payment(&interchange).with_fee(dec!(1))).await;
+    assert!(matches!(with_fee, Err(AppError::Validation(_))));
+
+    let posted = ledger.process_payment(payment(&interchange)).await.expect("Failed to post custom type");
+    assert_eq!(posted.transaction.type_code.as_deref(), Some(interchange.as_str()));
+
+    let reversal = ledger
+        .reverse_transaction(posted.transaction.id, "test", &format!("REV-{}", Uuid::new_v4()))
+        .await
+        .expect("Failed to reverse");
+    assert_eq!(reversal.transaction.transaction_type, TransactionType::Refund);
+
+    // A type that is not reversible cannot be reversed even though its base type can
+    let transfer = ledger
+        .process_transfer(
+            LedgerTransactionRequest::transfer(
+                format!("ADJ-{}", Uuid::new_v4()),
+                bank_a.id,
+                bank_b.id,
+                dec!(50),
+                &currency,
+                format!("IDEM-{}", Uuid::new_v4()),
+            )
+            .with_type_code(&adjustment),
+        )
+        .await
+        .expect("Failed to post adjustment");
+    let blocked = ledger
+        .reverse_transaction(transfer.transaction.id, "test", &format!("REV-{}", Uuid::new_v4()))
+        .await;
+    assert!(matches!(blocked, Err(AppError::Validation(_))));
+}
+
+#[tokio::test]
+async fn test_non_nettable_types_settle_gross() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let bank_a = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let bank_b = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let batch = common::fixtures::batch(&currency).create(&pool).await;
+    let ledger = LedgerService::new(pool.clone());
+    let batches = BatchService::new(pool.clone());
+    let netting = NettingService::new(pool.clone());
+
+    let sweep = unique_code("SWEEP");
+    let mut definition = TransactionTypeDefinition::new(&sweep, TransactionType::Transfer);
+    definition.nettable = false;
+    TransactionTypeService::new(pool.clone())
+        .register(definition)
+        .await
+        .expect("Failed to register type");
+
+    for (from, to, amount, code) in [
+        (bank_a.id, bank_b.id, dec!(100), None),
+        (bank_b.id, bank_a.id, dec!(60), None),
+        (bank_b.id, bank_a.id, dec!(30), Some(sweep.clone())),
+    ] {
+        let mut request = LedgerTransactionRequest::transfer(
+            format!("TR-{}", Uuid::new_v4()),
+            from,
+            to,
+            amount,
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        );
+        if let Some(code) = code {
+            request = request.with_type_code(code);
+        }
+        let posted = ledger.process_transfer(request).await.expect("Failed to post transfer");
+        batches
+            .assign_transaction_to_batch(posted.transaction.id, batch.id)
+            .await
+            .expect("Failed to assign transaction");
+    }
+
+    let transactions = batches.get_batch_transactions(batch.id).await.unwrap();
+    let report = netting
+        .process_batch_netting(batch.id, &currency, &transactions)
+        .await
+        .expect("Failed to net batch");
+
+    let bilateral = report.bilateral_result.as_ref().unwrap();
+    assert_eq!((bilateral.total_gross_volume, bilateral.total_net_volume), (dec!(190), dec!(70)));
+
+    // The sweep is settled on its own, outside the netted positions
+    let instructions = report.instructions();
+    let gross: Vec<_> = instructions.iter().filter(|i| i.instruction_type == InstructionType::Gross).collect();
+    assert_eq!(gross.len(), 1);
+    assert_eq!((gross[0].from_participant, gross[0].to_participant, gross[0].amount), (bank_b.id, bank_a.id, dec!(30)));
+    let netted: Vec<_> = instructions.iter().filter(|i| i.instruction_type == InstructionType::MultilateralNet).collect();
+    assert_eq!(netted.len(), 1);
+    assert_eq!(netted[0].amount, dec!(40));
+    assert_eq!(report.gross_volume, dec!(380));
+    assert_eq!(report.net_volume, dec!(140));
+}
+
+#[tokio::test]
+async fn test_excluded_transactions_reported_separately() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let bank_a = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let bank_b = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let batch = common::fixtures::batch(&currency).create(&pool).await;
+    let ledger = LedgerService::new(pool.clone());
+    let batches = BatchService::new(pool.clone());
+    let netting = NettingService::new(pool.clone());
+
+    let sweep = unique_code("SWEEP");
+    let mut definition = TransactionTypeDefinition::new(&sweep, TransactionType::Transfer);
+    definition.nettable = false;
+    TransactionTypeService::new(pool.clone())
+        .register(definition)
+        .await
+        .expect("Failed to register type");
+
+    let transfer = |from, to, amount| {
+        LedgerTransactionRequest::transfer(
+            format!("TR-{}", Uuid::new_v4()),
+            from,
+            to,
+            amount,
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+    };
+    let requests = [
+        transfer(bank_a.id, bank_b.id, dec!(100)),
+        transfer(bank_a.id, bank_b.id, dec!(25)).excluded_from_netting(),
+        transfer(bank_b.id, bank_a.id, dec!(30)).with_type_code(&sweep),
+    ];
+    let mut assigned = Vec::new();
+    for request in requests {
+        let posted = ledger.process_transfer(request).await.expect("Failed to post transfer");
+        assigned.push(
+            batches
+                .assign_transaction_to_batch(posted.transaction.id, batch.id)
+                .await
+                .expect("Failed to assign transaction"),
+        );
+    }
+
+    // Assignment flags transactions of non-nettable types too
+    assert_eq!(
+        assigned.iter().map(|t| t.exclude_from_netting).collect::<Vec<_>>(),
+        vec![false, true, true]
+    );
+
+    let transactions = batches.get_batch_transactions(batch.id).await.unwrap();
+    let report = netting
+        .process_batch_netting(batch.id, &currency, &transactions)
+        .await
+        .expect("Failed to net batch");
+
+    let mut excluded: Vec<_> = report
+        .excluded_transactions
+        .iter()
+        .map(|e| (e.transaction_id, e.reason, e.amount))
+        .collect();
+    excluded.sort_by_key(|(_, _, amount)| *amount);
+    assert_eq!(
+        excluded,
+        vec![
+            (assigned[1].id, ExclusionReason::Flagged, dec!(25)),
+            (assigned[2].id, ExclusionReason::TransactionType, dec!(30)),
+        ]
+    );
+    assert_eq!(report.excluded_volume, dec!(55));
+
+    let bilateral = report.bilateral_result.as_ref().unwrap();
+    // Only the netted transfer forms a pair; the gross-settled ones still count toward
+    // the batch's gross volume
+    let pair_volume: Decimal = bilateral.pairs.iter().map(|p| p.a_to_b_gross + p.b_to_a_gross).sum();
+    assert_eq!(pair_volume, dec!(100));
+    assert_eq!(bilateral.total_gross_volume, dec!(155));
+    let gross = report
+        .instructions()
+        .into_iter()
+        .filter(|i| i.instruction_type == InstructionType::Gross)
+        .count();
+    assert_eq!(gross, 2);
+}
+
+#[tokio::test]
+async fn test_narratives_from_request_or_type_template() {
+    let pool = common::setup_test_db().await;
+    let currency = common::fixtures::unique_currency();
+    let bank_a = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let bank_b = common::fixtures::account(&currency).with_balance(dec!(10000)).create(&pool).await;
+    let ledger = LedgerService::new(pool.clone());
+
+    let interchange = unique_code("INTERCHANGE");
+    let mut definition = TransactionTypeDefinition::new(&interchange, TransactionType::Transfer);
+    definition.narrative_template = Some("Interchange {amount} {currency} ref {reference}".to_string());
+    TransactionTypeService::new(pool.clone())
+        .register(definition)
+        .await
+        .expect("Failed to register type");
+
+    let transfer = |amount| {
+        LedgerTransactionRequest::transfer(
+            format!("TR-{}", Uuid::new_v4()),
+            bank_a.id,
+            bank_b.id,
+            amount,
+            &currency,
+            format!("IDEM-{}", Uuid::new_v4()),
+        )
+    };
+
+    // The type's template, filled from the request
+    let posted = ledger
+        .process_transfer(transfer(dec!(25.00)).with_type_code(&interchange).with_reference("INV-7"))
+        .await
+        .expect("Failed to post transfer");
+    let expected = format!("Interchange 25.00 {} ref INV-7", currency);
+    assert_eq!(posted.transaction.narrative.as_deref(), Some(expected.as_str()));
+    assert_eq!(posted.transaction.reference.as_deref(), Some("INV-7"));
+    for entry in &posted.entries {
+        assert_eq!(entry.narrative.as_deref(), Some(expected.as_str()));
+        assert_eq!(entry.reference.as_deref(), Some("INV-7"));
+    }
+
+    // The request's own narrative wins; base types fall back to the default template
+    let posted = ledger
+        .process_transfer(transfer(dec!(10)).with_type_code(&interchange).with_narrative("Manual adjustment"))
+        .await
+        .expect("Failed to post transfer");
+    assert_eq!(posted.transaction.narrative.as_deref(), Some("Manual adjustment"));
+    let request = transfer(dec!(10));
+    let external_id = request.external_id.clone();
+    let posted = ledger.process_transfer(request).await.expect("Failed to post transfer");
+    assert_eq!(posted.transaction.narrative, Some(format!("TRANSFER {}", external_id)));
+
+    // Narratives are stored with the entries and carried to reversals
+    let stored = ledger
+        .get_transaction(posted.transaction.id)
+        .await
+        .expect("Failed to get transaction");
+    assert_eq!(stored.narrative, posted.transaction.narrative);
+    let reversal = ledger
+        .reverse_transaction(posted.transaction.id, "Sent in error", &format!("REV-{}", Uuid::new_v4()))
+        .await
+        .expect("Failed to reverse transfer");
+    let expected = format!("Reversal of TRANSFER {}", external_id);
+    assert_eq!(reversal.transaction.narrative.as_deref(), Some(expected.as_str()));
+    assert!(reversal.entries.iter().all(|entry| entry.narrative.as_deref() == Some(expected.as_str())));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::fixtures::{self, unique_currency};
+use rust_decimal_macros::dec;
+use settlement_engine::error::AppError;
+use settlement_engine::models::TransactionStatus;
+use settlement_engine::services::{BalanceService, LedgerService, LedgerTransactionRequest, WriteCombiner};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_combined_write_isolates_failures() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let (a, b, c) = (
+        fixtures::account(&currency).create(&pool).await.id,
+        fixtures::account(&currency).create(&pool).await.id,
+        fixtures::account(&currency).create(&pool).await.id,
+    );
+    let ledger = LedgerService::new(pool.clone());
+    let payment = |from, to, amount, key: &str| {
+        LedgerTransactionRequest::payment(format!("WC-{}", Uuid::new_v4()), from, to, amount, &currency, key)
+    };
+
+    let repeated = Uuid::new_v4().to_string();
+    let outcomes = ledger
+        .execute_combined(vec![
+            payment(a, b, dec!(70), &Uuid::new_v4().to_string()),
+            // Only 30 is left on `a` once the first payment is applied
+            payment(a, c, dec!(50), &Uuid::new_v4().to_string()),
+            payment(b, c, dec!(150), &repeated),
+            payment(b, c, dec!(150), &repeated),
+            payment(c, a, dec!(0), &Uuid::new_v4().to_string()),
+        ])
+        .await;
+
+    assert_eq!(outcomes.len(), 5);
+    let first = outcomes[0].as_ref().expect("First payment should post");
+    assert_eq!(first.transaction.status, TransactionStatus::Settled);
+    assert_eq!(first.source_balance.available_balance, dec!(30));
+    assert!(matches!(outcomes[1], Err(AppError::InsufficientFunds(_))));
+    let spent = outcomes[2].as_ref().expect("Payment funded by the first should post");
+    assert_eq!(spent.destination_balance.available_balance, dec!(250));
+    let retried = outcomes[3].as_ref().expect("Repeated idempotency key should return the payment");
+    assert_eq!(retried.transaction.id, spent.transaction.id);
+    assert!(outcomes[4].is_err(), "invalid requests fail on their own");
+
+    let balances = BalanceService::new(pool.clone());
+    for (account, expected) in [(a, dec!(30)), (b, dec!(20)), (c, dec!(250))] {
+        assert_eq!(balances.get_balance(account, &currency).await.unwrap().available_balance, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_write_combiner_posts_concurrent_submissions() {
+    let pool = common::setup_test_db().await;
+    let currency = unique_currency();
+    let payer = fixtures::account(&currency).create(&pool).await.id;
+    let payee = fixtures::account(&currency).create(&pool).await.id;
+    let combiner = Arc::new(WriteCombiner::start(
+        Arc::new(LedgerService::new(pool.clone())),
+        Duration::from_millis(20),
+        10,
+    ));
+    let ledger = Arc::new(LedgerService::new(pool.clone()).with_write_combining(combiner));
+
+    let submissions = (0..12).map(|_| {
+        let ledger = ledger.clone();
+        let currency = currency.clone();
+        tokio::spawn(async move {
+            ledger
+                .execute_transaction(LedgerTransactionRequest::payment(
+                    format!("WC-{}", Uuid::new_v4()),
+                    payer,
+                    payee,
+                    dec!(10),
+                    &currency,
+                    Uuid::new_v4().to_string(),
+                ))
+                .await
+        })
+    });
+    let outcomes: Vec<_> = futures::future::join_all(submissions)
+        .await
+        .into_iter()
+        .map(|joined| joined.expect("Submission task panicked"))
+        .collect();
+
+    assert_eq!(outcomes.iter().filter(|outcome| outcome.is_ok()).count(), 10);
+    assert!(outcomes
+        .iter()
+        .filter_map(|outcome| outcome.as_ref().err())
+        .all(|e| matches!(e, AppError::InsufficientFunds(_))));
+
+    let balances = BalanceService::new(pool.clone());
+    assert_eq!(balances.get_balance(payer, &currency).await.unwrap().available_balance, dec!(0));
+    assert_eq!(balances.get_balance(payee, &currency).await.unwrap().available_balance, dec!(200));
+}